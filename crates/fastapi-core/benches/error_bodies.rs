@@ -0,0 +1,100 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use fastapi_core::error::{LocItem, ValidationError, ValidationErrors, error_types, loc};
+use fastapi_core::{HttpError, IntoResponse};
+use serde::Serialize;
+
+// ============================================================================
+// Test data: typical 422 payloads
+// ============================================================================
+
+fn missing_query_params(count: usize) -> ValidationErrors {
+    ValidationErrors::from_errors(
+        (0..count)
+            .map(|i| ValidationError::missing(loc::query(&format!("param{i}"))))
+            .collect(),
+    )
+}
+
+fn body_errors(count: usize) -> ValidationErrors {
+    ValidationErrors::from_errors(
+        (0..count)
+            .map(|i| {
+                ValidationError::new(
+                    error_types::STRING_TOO_SHORT,
+                    vec![
+                        LocItem::field("body"),
+                        LocItem::field("items"),
+                        LocItem::index(i),
+                        LocItem::field("name"),
+                    ],
+                )
+                .with_msg("String should have at least 3 characters")
+                .with_input(serde_json::json!("ab"))
+                .with_ctx_value("min_length", serde_json::json!(3))
+            })
+            .collect(),
+    )
+}
+
+fn serialize_uncached(errors: &ValidationErrors) -> Vec<u8> {
+    #[derive(Serialize)]
+    struct Body<'a> {
+        detail: &'a [ValidationError],
+    }
+
+    serde_json::to_vec(&Body {
+        detail: &errors.errors,
+    })
+    .unwrap()
+}
+
+// ============================================================================
+// Benchmarks: 422 bodies
+// ============================================================================
+
+fn bench_validation_bodies(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validation_body");
+
+    let cases = [
+        ("missing_1", missing_query_params(1)),
+        ("missing_5", missing_query_params(5)),
+        ("body_5", body_errors(5)),
+    ];
+
+    for (name, errors) in &cases {
+        group.bench_with_input(BenchmarkId::new("serde", name), errors, |b, errors| {
+            b.iter(|| serialize_uncached(black_box(errors)));
+        });
+        group.bench_with_input(BenchmarkId::new("cached", name), errors, |b, errors| {
+            b.iter(|| black_box(errors).to_json_bytes());
+        });
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// Benchmarks: router error responses
+// ============================================================================
+
+fn bench_status_bodies(c: &mut Criterion) {
+    let mut group = c.benchmark_group("status_body");
+
+    group.bench_function("not_found", |b| {
+        b.iter(|| HttpError::not_found().into_response());
+    });
+    group.bench_function("not_found_with_detail", |b| {
+        b.iter(|| {
+            HttpError::not_found()
+                .with_detail(black_box("Not Found"))
+                .into_response()
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_validation_bodies, bench_status_bodies);
+criterion_main!(benches);
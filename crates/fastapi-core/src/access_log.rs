@@ -0,0 +1,574 @@
+//! Access logs from format templates.
+//!
+//! [`AccessLog`] writes one line per request, laid out by an
+//! Apache-`mod_log_config`-style template:
+//!
+//! ```ignore
+//! let app = App::builder()
+//!     .middleware(AccessLog::combined())
+//!     .middleware(AccessLog::new(
+//!         AccessLogFormat::parse("%h %r %s %b %Dms %{X-Request-Id}i")?,
+//!     ))
+//!     .build();
+//! ```
+//!
+//! | Token | Value |
+//! |-------|-------|
+//! | `%h`, `%a` | Client IP |
+//! | `%l` | Always `-` (identd) |
+//! | `%u` | User id recorded on the request's [`LogScope`](crate::logging::LogScope) |
+//! | `%t` | Time the request started, `[10/Oct/2000:13:55:36 +0000]` |
+//! | `%r` | Request line, `GET /path?query HTTP/1.1` |
+//! | `%m`, `%U`, `%q`, `%H` | Method, path, `?query` (or empty), protocol |
+//! | `%s` | Response status |
+//! | `%b`, `%B` | Response body bytes; `%b` prints `-` for none |
+//! | `%D` | Duration in milliseconds |
+//! | `%T`, `%{ms}T`, `%{us}T` | Duration in seconds, milliseconds, microseconds |
+//! | `%{Name}i`, `%{Name}o` | Request or response header |
+//! | `%%` | A literal `%` |
+//!
+//! Unlike Apache, `%D` is in milliseconds; use `%{us}T` for microseconds.
+//! The `<` and `>` modifiers (as in `%>s`) are accepted and ignored. Missing
+//! values print as `-`, and quotes and control characters in request data are
+//! escaped so a client cannot forge log lines.
+//!
+//! Lines are logged at INFO level through the request's logger, or handed to
+//! a [`writer`](AccessLog::writer) as plain text.
+
+use std::fmt;
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::context::RequestContext;
+use crate::logging::{LogConfig, RequestLogger};
+use crate::middleware::{
+    BoxFuture, ControlFlow, KeyExtractor, Middleware, RemoteAddr, TrustedProxyIpKeyExtractor,
+};
+use crate::request::{HttpVersion, Request};
+use crate::response::{Response, ResponseBody};
+
+/// Apache's Common Log Format.
+pub const COMMON_FORMAT: &str = "%h %l %u %t \"%r\" %>s %b";
+
+/// Apache's Combined Log Format.
+pub const COMBINED_FORMAT: &str = "%h %l %u %t \"%r\" %>s %b \"%{Referer}i\" \"%{User-Agent}i\"";
+
+/// Error parsing an access log template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessLogFormatError {
+    /// Byte offset of the offending `%`.
+    pub position: usize,
+    /// What is wrong.
+    pub message: String,
+}
+
+impl fmt::Display for AccessLogFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid access log format at byte {}: {}",
+            self.position, self.message
+        )
+    }
+}
+
+impl std::error::Error for AccessLogFormatError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Seconds,
+    Millis,
+    Micros,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Literal(String),
+    ClientIp,
+    Ident,
+    User,
+    Time,
+    RequestLine,
+    Method,
+    Path,
+    Query,
+    Protocol,
+    Status,
+    Bytes { dash_if_zero: bool },
+    Duration(Unit),
+    RequestHeader(String),
+    ResponseHeader(String),
+}
+
+/// A parsed access log template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessLogFormat {
+    tokens: Vec<Token>,
+}
+
+impl AccessLogFormat {
+    /// Parse a template; see the [module docs](self) for the tokens.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for unknown tokens, a `%{` without a closing `}`,
+    /// or an argument on a token that takes none.
+    pub fn parse(template: &str) -> Result<Self, AccessLogFormatError> {
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.char_indices().peekable();
+
+        while let Some((position, c)) = chars.next() {
+            if c != '%' {
+                literal.push(c);
+                continue;
+            }
+            let error = |message: &str| AccessLogFormatError {
+                position,
+                message: message.to_string(),
+            };
+
+            let mut arg = None;
+            if chars.next_if(|(_, c)| *c == '{').is_some() {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '}')) => break,
+                        Some((_, c)) => name.push(c),
+                        None => return Err(error("unterminated %{")),
+                    }
+                }
+                arg = Some(name);
+            }
+            while chars.next_if(|(_, c)| *c == '<' || *c == '>').is_some() {}
+
+            let Some((_, directive)) = chars.next() else {
+                return Err(error("template ends with %"));
+            };
+            let token = match (directive, arg) {
+                ('%', None) => {
+                    literal.push('%');
+                    continue;
+                }
+                ('h' | 'a', None) => Token::ClientIp,
+                ('l', None) => Token::Ident,
+                ('u', None) => Token::User,
+                ('t', None) => Token::Time,
+                ('r', None) => Token::RequestLine,
+                ('m', None) => Token::Method,
+                ('U', None) => Token::Path,
+                ('q', None) => Token::Query,
+                ('H', None) => Token::Protocol,
+                ('s', None) => Token::Status,
+                ('b', None) => Token::Bytes { dash_if_zero: true },
+                ('B', None) => Token::Bytes {
+                    dash_if_zero: false,
+                },
+                ('D', None) => Token::Duration(Unit::Millis),
+                ('T', None) => Token::Duration(Unit::Seconds),
+                ('T', Some(unit)) => match unit.as_str() {
+                    "s" => Token::Duration(Unit::Seconds),
+                    "ms" => Token::Duration(Unit::Millis),
+                    "us" => Token::Duration(Unit::Micros),
+                    _ => return Err(error("%{..}T takes s, ms or us")),
+                },
+                ('i', Some(name)) => Token::RequestHeader(name.to_ascii_lowercase()),
+                ('o', Some(name)) => Token::ResponseHeader(name.to_ascii_lowercase()),
+                ('i' | 'o', None) => return Err(error("header tokens need a name: %{Name}i")),
+                (_, Some(_)) => return Err(error("token takes no argument")),
+                (other, None) => return Err(error(&format!("unknown token %{other}"))),
+            };
+            if !literal.is_empty() {
+                tokens.push(Token::Literal(std::mem::take(&mut literal)));
+            }
+            tokens.push(token);
+        }
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(literal));
+        }
+        Ok(Self { tokens })
+    }
+
+    /// [`COMMON_FORMAT`].
+    #[must_use]
+    pub fn common() -> Self {
+        Self::parse(COMMON_FORMAT).expect("common format is valid")
+    }
+
+    /// [`COMBINED_FORMAT`].
+    #[must_use]
+    pub fn combined() -> Self {
+        Self::parse(COMBINED_FORMAT).expect("combined format is valid")
+    }
+
+    fn render(&self, entry: &Entry<'_>) -> String {
+        let mut out = String::new();
+        for token in &self.tokens {
+            match token {
+                Token::Literal(text) => out.push_str(text),
+                Token::ClientIp => push_or_dash(&mut out, entry.client_ip.as_deref()),
+                Token::Ident => out.push('-'),
+                Token::User => push_escaped_or_dash(&mut out, entry.user.as_deref()),
+                Token::Time => out.push_str(&clf_time(entry.started)),
+                Token::RequestLine => {
+                    let req = entry.req;
+                    let mut line = format!("{} {}", req.method(), req.path());
+                    if let Some(query) = req.query() {
+                        line.push('?');
+                        line.push_str(query);
+                    }
+                    line.push(' ');
+                    line.push_str(protocol(req.version()));
+                    push_escaped(&mut out, &line);
+                }
+                Token::Method => out.push_str(entry.req.method().as_str()),
+                Token::Path => push_escaped(&mut out, entry.req.path()),
+                Token::Query => {
+                    if let Some(query) = entry.req.query() {
+                        out.push('?');
+                        push_escaped(&mut out, query);
+                    }
+                }
+                Token::Protocol => out.push_str(protocol(entry.req.version())),
+                Token::Status => {
+                    let _ = write!(out, "{}", entry.response.status().as_u16());
+                }
+                Token::Bytes { dash_if_zero } => match entry.response_bytes {
+                    Some(0) | None if *dash_if_zero => out.push('-'),
+                    Some(n) => {
+                        let _ = write!(out, "{n}");
+                    }
+                    None => out.push('0'),
+                },
+                Token::Duration(unit) => {
+                    let value = match unit {
+                        Unit::Seconds => entry.elapsed.as_secs(),
+                        Unit::Millis => {
+                            u64::try_from(entry.elapsed.as_millis()).unwrap_or(u64::MAX)
+                        }
+                        Unit::Micros => {
+                            u64::try_from(entry.elapsed.as_micros()).unwrap_or(u64::MAX)
+                        }
+                    };
+                    let _ = write!(out, "{value}");
+                }
+                Token::RequestHeader(name) => {
+                    let value = entry.req.headers().get(name).map(String::from_utf8_lossy);
+                    push_escaped_or_dash(&mut out, value.as_deref());
+                }
+                Token::ResponseHeader(name) => {
+                    let value = entry
+                        .response
+                        .headers()
+                        .iter()
+                        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                        .map(|(_, v)| String::from_utf8_lossy(v));
+                    push_escaped_or_dash(&mut out, value.as_deref());
+                }
+            }
+        }
+        out
+    }
+}
+
+impl std::str::FromStr for AccessLogFormat {
+    type Err = AccessLogFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Everything a template can refer to.
+struct Entry<'a> {
+    req: &'a Request,
+    response: &'a Response,
+    client_ip: Option<String>,
+    user: Option<String>,
+    started: SystemTime,
+    elapsed: std::time::Duration,
+    response_bytes: Option<usize>,
+}
+
+fn protocol(version: HttpVersion) -> &'static str {
+    match version {
+        HttpVersion::Http10 => "HTTP/1.0",
+        HttpVersion::Http11 => "HTTP/1.1",
+        HttpVersion::Http2 => "HTTP/2.0",
+    }
+}
+
+fn push_or_dash(out: &mut String, value: Option<&str>) {
+    out.push_str(value.filter(|v| !v.is_empty()).unwrap_or("-"));
+}
+
+fn push_escaped_or_dash(out: &mut String, value: Option<&str>) {
+    match value.filter(|v| !v.is_empty()) {
+        Some(value) => push_escaped(out, value),
+        None => out.push('-'),
+    }
+}
+
+/// Escape `"`, `\` and control characters, as Apache does.
+fn push_escaped(out: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\x{:02x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+}
+
+/// `[10/Oct/2000:13:55:36 +0000]`, in UTC.
+fn clf_time(time: SystemTime) -> String {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let (year, month, day) = civil_from_days(secs / 86_400);
+    let rem = secs % 86_400;
+    format!(
+        "[{day:02}/{}/{year}:{:02}:{:02}:{:02} +0000]",
+        MONTHS[usize::from(month - 1)],
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+/// Days since the Unix epoch to (year, month, day), after Howard Hinnant's
+/// `civil_from_days`.
+fn civil_from_days(days: u64) -> (u64, u8, u8) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    // Both fit: day is 1..=31 and month 1..=12.
+    (
+        year,
+        u8::try_from(month).unwrap_or(1),
+        u8::try_from(day).unwrap_or(1),
+    )
+}
+
+// ============================================================================
+// Middleware
+// ============================================================================
+
+type Writer = Arc<dyn Fn(&str) + Send + Sync>;
+
+#[derive(Debug, Clone, Copy)]
+struct AccessStart {
+    at: SystemTime,
+    instant: Instant,
+}
+
+/// Middleware writing an access log line per request.
+#[derive(Clone)]
+pub struct AccessLog {
+    format: AccessLogFormat,
+    proxies: Option<TrustedProxyIpKeyExtractor>,
+    writer: Option<Writer>,
+    log_config: LogConfig,
+}
+
+impl AccessLog {
+    /// Log with `format`.
+    #[must_use]
+    pub fn new(format: AccessLogFormat) -> Self {
+        Self {
+            format,
+            proxies: None,
+            writer: None,
+            log_config: LogConfig::default(),
+        }
+    }
+
+    /// Log in the Common Log Format.
+    #[must_use]
+    pub fn common() -> Self {
+        Self::new(AccessLogFormat::common())
+    }
+
+    /// Log in the Combined Log Format.
+    #[must_use]
+    pub fn combined() -> Self {
+        Self::new(AccessLogFormat::combined())
+    }
+
+    /// Resolve `%h` through trusted proxies' forwarding headers.
+    #[must_use]
+    pub fn trusted_proxies(mut self, proxies: TrustedProxyIpKeyExtractor) -> Self {
+        self.proxies = Some(proxies);
+        self
+    }
+
+    /// Hand each line to `writer` instead of the request logger.
+    #[must_use]
+    pub fn writer(mut self, writer: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.writer = Some(Arc::new(writer));
+        self
+    }
+
+    /// Logging configuration used when no writer is set.
+    #[must_use]
+    pub fn log_config(mut self, config: LogConfig) -> Self {
+        self.log_config = config;
+        self
+    }
+
+    /// Render the line for a finished request.
+    fn line(&self, ctx: &RequestContext, req: &Request, response: &Response) -> String {
+        let start = req.get_extension::<AccessStart>().copied();
+        let client_ip = match &self.proxies {
+            Some(proxies) => proxies.extract_key(req),
+            None => req.get_extension::<RemoteAddr>().map(ToString::to_string),
+        };
+        let response_bytes = match response.body_ref() {
+            ResponseBody::Empty => Some(0),
+            ResponseBody::Bytes(bytes) => Some(bytes.len()),
+            ResponseBody::Stream(_) => response
+                .headers()
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case("content-length"))
+                .and_then(|(_, v)| std::str::from_utf8(v).ok()?.trim().parse().ok()),
+        };
+        self.format.render(&Entry {
+            req,
+            response,
+            client_ip,
+            user: ctx.log_scope().user_id(),
+            started: start.map_or_else(SystemTime::now, |start| start.at),
+            elapsed: start
+                .map(|start| start.instant.elapsed())
+                .unwrap_or_default(),
+            response_bytes,
+        })
+    }
+}
+
+impl fmt::Debug for AccessLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessLog")
+            .field("format", &self.format)
+            .field("proxies", &self.proxies)
+            .field("writer", &self.writer.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Middleware for AccessLog {
+    fn before<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        req.insert_extension(AccessStart {
+            at: SystemTime::now(),
+            instant: Instant::now(),
+        });
+        Box::pin(async { ControlFlow::Continue })
+    }
+
+    fn after<'a>(
+        &'a self,
+        ctx: &'a RequestContext,
+        req: &'a Request,
+        response: Response,
+    ) -> BoxFuture<'a, Response> {
+        let line = self.line(ctx, req, &response);
+        match &self.writer {
+            Some(writer) => writer(&line),
+            None => RequestLogger::new(ctx, self.log_config.clone()).info(line),
+        }
+        Box::pin(async move { response })
+    }
+
+    fn name(&self) -> &'static str {
+        "AccessLog"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Method;
+    use crate::response::StatusCode;
+
+    fn render(format: &AccessLogFormat, req: &Request, response: &Response) -> String {
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+        AccessLog::new(format.clone()).line(&ctx, req, response)
+    }
+
+    fn request() -> Request {
+        let mut req = Request::new(Method::Get, "/items");
+        req.set_query(Some("page=2".into()));
+        req.insert_extension(RemoteAddr("203.0.113.9".parse().unwrap()));
+        req.insert_extension(AccessStart {
+            at: UNIX_EPOCH + std::time::Duration::from_secs(971_186_136),
+            instant: Instant::now(),
+        });
+        req.headers_mut()
+            .insert("user-agent", b"curl/8.0 \"quoted\"".to_vec());
+        req.headers_mut().insert("x-request-id", b"abc123".to_vec());
+        req
+    }
+
+    #[test]
+    fn renders_combined_format() {
+        let response =
+            Response::with_status(StatusCode::OK).body(ResponseBody::Bytes(b"hello".to_vec()));
+        assert_eq!(
+            render(&AccessLogFormat::combined(), &request(), &response),
+            "203.0.113.9 - - [10/Oct/2000:13:55:36 +0000] \"GET /items?page=2 HTTP/1.1\" 200 5 \
+             \"-\" \"curl/8.0 \\\"quoted\\\"\""
+        );
+    }
+
+    #[test]
+    fn renders_custom_tokens() {
+        let format =
+            AccessLogFormat::parse("%h %r %s %b %Dms %{X-Request-Id}i %{x-cache}o 100%%").unwrap();
+        let response =
+            Response::with_status(StatusCode::NO_CONTENT).header("X-Cache", b"HIT".to_vec());
+        let line = render(&format, &request(), &response);
+        assert!(line.starts_with("203.0.113.9 GET /items?page=2 HTTP/1.1 204 - "));
+        assert!(line.ends_with("ms abc123 HIT 100%"), "{line}");
+    }
+
+    #[test]
+    fn rejects_invalid_templates() {
+        for (template, position) in [
+            ("%Z", 0),
+            ("a %{Referer", 2),
+            ("%i", 0),
+            ("%{x}s", 0),
+            ("50%", 2),
+        ] {
+            let err = AccessLogFormat::parse(template).unwrap_err();
+            assert_eq!(err.position, position, "{template}");
+        }
+    }
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(19_723), (2024, 1, 1));
+    }
+}
@@ -35,8 +35,10 @@ use std::sync::Arc;
 
 use crate::app::{BoxHandler, RouteEntry};
 use crate::context::RequestContext;
+use crate::dependency::{Depends, FromDependency};
+use crate::extract::FromRequest;
 use crate::request::{Method, Request};
-use crate::response::Response;
+use crate::response::{IntoResponse, Response};
 
 /// Response definition for OpenAPI documentation.
 #[derive(Debug, Clone)]
@@ -116,6 +118,26 @@ impl RouterDependency {
         }
     }
 
+    /// Create a dependency that resolves `Depends<T>` before the handler.
+    ///
+    /// A failure short-circuits with `T`'s error response (e.g. a 404 from a
+    /// loader). On success the value stays in the request's dependency cache,
+    /// so a handler taking `Depends<T>` receives it without resolving it again.
+    #[must_use]
+    pub fn depends<T: FromDependency>() -> Self {
+        Self {
+            handler: Arc::new(|ctx, req| {
+                Box::pin(async move {
+                    Depends::<T>::from_request(ctx, req)
+                        .await
+                        .map(drop)
+                        .map_err(IntoResponse::into_response)
+                })
+            }),
+            name: std::any::type_name::<T>().to_string(),
+        }
+    }
+
     /// Execute the dependency.
     pub async fn execute(&self, ctx: &RequestContext, req: &mut Request) -> Result<(), Response> {
         (self.handler)(ctx, req).await
@@ -565,6 +587,36 @@ impl APIRouter {
         self.route(path, Method::Head, handler)
     }
 
+    /// Adds routes nested under a resource path.
+    ///
+    /// `build` receives a router prefixed with `path`; its routes inherit the
+    /// resource's path parameters, and its dependencies run after this
+    /// router's and before each nested route's own. Resources can be nested
+    /// further.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let router = APIRouter::new().prefix("/api").resource("/items/{item_id}", |item| {
+    ///     item.dependency(RouterDependency::depends::<ItemLoader>())
+    ///         .get("", get_item)
+    ///         .get("/reviews", list_reviews)
+    ///         .resource("/reviews/{review_id}", |review| review.delete("", delete_review))
+    /// });
+    ///
+    /// // Routes: /api/items/{item_id}, /api/items/{item_id}/reviews and
+    /// // /api/items/{item_id}/reviews/{review_id}, all answering 404 when the
+    /// // item does not exist.
+    /// ```
+    #[must_use]
+    pub fn resource<F>(self, path: impl Into<String>, build: F) -> Self
+    where
+        F: FnOnce(APIRouter) -> APIRouter,
+    {
+        let nested = build(APIRouter::new().prefix(path));
+        self.include_router(nested)
+    }
+
     /// Includes another router's routes with an optional additional prefix.
     ///
     /// This allows nesting routers for hierarchical organization.
@@ -1149,4 +1201,78 @@ mod tests {
         let combined_1234 = combine_paths(&combined_123, level4);
         assert_eq!(combined_1234, "/api/v1/users/{id}");
     }
+
+    // =========================================================================
+    // Resource Tests
+    // =========================================================================
+
+    fn route_summary(router: &APIRouter) -> Vec<(Method, String, Vec<String>)> {
+        router
+            .get_routes()
+            .iter()
+            .map(|route| {
+                let deps = route.dependencies.iter().map(|d| d.name.clone()).collect();
+                (route.method, route.path.clone(), deps)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_resource_nests_paths_and_dependencies() {
+        let router = APIRouter::new()
+            .prefix("/api")
+            .get("/items", |_ctx, _req| async { Response::ok() })
+            .resource("/items/{item_id}", |item| {
+                item.dependency(RouterDependency::new("item_loader", |_ctx, _req| async {
+                    Ok(())
+                }))
+                .get("", |_ctx, _req| async { Response::ok() })
+                .resource("reviews/{review_id}", |review| {
+                    review
+                        .dependency(RouterDependency::new("review_loader", |_ctx, _req| async {
+                            Ok(())
+                        }))
+                        .delete("/", |_ctx, _req| async { Response::ok() })
+                })
+            });
+
+        assert_eq!(
+            route_summary(&router),
+            vec![
+                (Method::Get, "/items".to_string(), vec![]),
+                (
+                    Method::Get,
+                    "/items/{item_id}".to_string(),
+                    vec!["item_loader".to_string()]
+                ),
+                (
+                    Method::Delete,
+                    "/items/{item_id}/reviews/{review_id}".to_string(),
+                    vec!["item_loader".to_string(), "review_loader".to_string()]
+                ),
+            ]
+        );
+        // The router's own prefix is applied when it is turned into routes.
+        assert_eq!(router.get_prefix(), "/api");
+    }
+
+    #[test]
+    fn test_depends_dependency_is_named_after_type() {
+        #[derive(Clone)]
+        struct ItemLoader;
+
+        impl FromDependency for ItemLoader {
+            type Error = crate::HttpError;
+
+            async fn from_dependency(
+                _ctx: &RequestContext,
+                _req: &mut Request,
+            ) -> Result<Self, Self::Error> {
+                Err(crate::HttpError::not_found())
+            }
+        }
+
+        let dep = RouterDependency::depends::<ItemLoader>();
+        assert!(dep.name.ends_with("ItemLoader"));
+    }
 }
@@ -39,9 +39,13 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use crate::context::RequestContext;
+use crate::dependency::RequestOutcome;
+use crate::dependency_graph::{DependencyGraph, DependencyRequirement};
+use crate::error_reporting::{ErrorEvent, ErrorReporter};
+use crate::interop::ForeignHandler;
 use crate::middleware::{BoxFuture, Handler, Middleware, MiddlewareStack};
 use crate::request::{Method, Request};
-use crate::response::{Response, StatusCode};
+use crate::response::{IntoResponse, Response, StatusCode};
 use crate::shutdown::ShutdownController;
 use fastapi_router::{Route, RouteLookup, Router};
 
@@ -62,6 +66,9 @@ pub enum StartupHook {
     ),
 }
 
+/// Teardown recorded by a [`AppBuilder::lifespan`] whose setup succeeded.
+type LifespanTeardown = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>;
+
 impl StartupHook {
     /// Create a synchronous startup hook.
     pub fn sync<F>(f: F) -> Self
@@ -153,6 +160,26 @@ impl std::fmt::Display for StartupHookError {
 
 impl std::error::Error for StartupHookError {}
 
+/// Error returned by [`App::reload`] when the builder configures parts of the
+/// app that live outside its [`AppSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReloadError {
+    /// What the builder configured, e.g. `"state"` or `"startup hooks"`.
+    pub rejected: Vec<&'static str>,
+}
+
+impl std::fmt::Display for ReloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "reload cannot replace app-level {}; configure them on the running app",
+            self.rejected.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for ReloadError {}
+
 /// Outcome of running all startup hooks.
 #[derive(Debug)]
 pub enum StartupOutcome {
@@ -219,10 +246,22 @@ pub struct RouteEntry {
     /// When routes are created by proc-macros, we preserve a full `fastapi_router::Route`
     /// so OpenAPI generation can use stable operation IDs, tags, parameters, etc.
     meta: Option<fastapi_router::Route>,
+    /// Security schemes this route's extractors contribute to the OpenAPI components.
+    security_schemes: Vec<(String, fastapi_openapi::SecurityScheme)>,
+    /// Shared state types the handler extracts, checked by [`App::check`].
+    required_state: Vec<(TypeId, &'static str)>,
+    /// Dependencies the handler extracts, validated by [`AppBuilder::build`].
+    dependencies: Vec<DependencyRequirement>,
+    /// Registers the request/response body types as OpenAPI components.
+    openapi_schemas: Option<RouteSchemasFn>,
     /// The handler function.
     handler: Arc<BoxHandler>,
 }
 
+/// Resolves a route's body schemas against the OpenAPI component registry.
+pub type RouteSchemasFn =
+    fn(&mut fastapi_openapi::SchemaRegistryMut<'_>) -> fastapi_openapi::RouteSchemas;
+
 impl RouteEntry {
     /// Creates a new route entry.
     ///
@@ -242,6 +281,10 @@ impl RouteEntry {
             method,
             path: path.into(),
             meta: None,
+            security_schemes: Vec::new(),
+            required_state: Vec::new(),
+            dependencies: Vec::new(),
+            openapi_schemas: None,
             handler: Arc::new(handler),
         }
     }
@@ -267,6 +310,78 @@ impl RouteEntry {
         self.meta.as_ref()
     }
 
+    /// Registers a security scheme to include in the OpenAPI components.
+    ///
+    /// Proc-macro generated routes call this for every
+    /// [`SecurityExtractor`](crate::SecurityExtractor) parameter.
+    #[must_use]
+    pub fn security_scheme(
+        mut self,
+        name: impl Into<String>,
+        scheme: fastapi_openapi::SecurityScheme,
+    ) -> Self {
+        self.security_schemes.push((name.into(), scheme));
+        self
+    }
+
+    /// Returns the security schemes registered for this route.
+    pub fn security_schemes(&self) -> &[(String, fastapi_openapi::SecurityScheme)] {
+        &self.security_schemes
+    }
+
+    /// Sets how this route's body types are registered as OpenAPI components.
+    ///
+    /// Proc-macro generated routes call this so request and response bodies
+    /// (and the types nested in them) appear in `components/schemas`.
+    #[must_use]
+    pub fn openapi_schemas(mut self, schemas: RouteSchemasFn) -> Self {
+        self.openapi_schemas = Some(schemas);
+        self
+    }
+
+    /// Declares that the handler needs shared state of type `T`.
+    ///
+    /// Proc-macro generated routes call this for every `State<T>` parameter;
+    /// [`App::check`] reports routes whose state was never registered.
+    #[must_use]
+    pub fn requires_state<T: 'static>(mut self) -> Self {
+        let id = TypeId::of::<T>();
+        if !self.required_state.iter().any(|(t, _)| *t == id) {
+            self.required_state.push((id, std::any::type_name::<T>()));
+        }
+        self
+    }
+
+    /// Returns the names of the shared state types this route requires.
+    pub fn required_state(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.required_state.iter().map(|(_, name)| *name)
+    }
+
+    /// Declares what one of the handler's extractors requires.
+    ///
+    /// Proc-macro generated routes call this for every parameter. `State<T>`
+    /// requirements are handled like [`Self::requires_state`]; `Depends<T>`
+    /// trees are validated when the app is built.
+    #[must_use]
+    pub fn requires(mut self, requirements: Vec<DependencyRequirement>) -> Self {
+        for requirement in requirements {
+            match requirement {
+                DependencyRequirement::State { type_id, name } => {
+                    if !self.required_state.iter().any(|(t, _)| *t == type_id) {
+                        self.required_state.push((type_id, name));
+                    }
+                }
+                DependencyRequirement::Dependency(_) => self.dependencies.push(requirement),
+            }
+        }
+        self
+    }
+
+    /// Returns the dependencies this route's handler extracts.
+    pub fn dependencies(&self) -> &[DependencyRequirement] {
+        &self.dependencies
+    }
+
     /// Calls the handler with the given context and request.
     pub async fn call(&self, ctx: &RequestContext, req: &mut Request) -> Response {
         (self.handler)(ctx, req).await
@@ -337,52 +452,70 @@ impl std::fmt::Debug for WebSocketRouteEntry {
 /// the `State<T>` extractor.
 #[derive(Default)]
 pub struct StateContainer {
-    state: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    state: parking_lot::RwLock<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
 }
 
 impl StateContainer {
     /// Creates a new empty state container.
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            state: HashMap::new(),
-        }
+        Self::default()
     }
 
     /// Inserts a value into the state container.
     ///
     /// If a value of the same type already exists, it is replaced.
     pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) {
-        self.state.insert(TypeId::of::<T>(), Arc::new(value));
+        self.state
+            .get_mut()
+            .insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    /// Inserts a value through a shared reference; used for lifespan state.
+    pub(crate) fn insert_arc<T: Send + Sync + 'static>(&self, value: Arc<T>) {
+        self.state.write().insert(TypeId::of::<T>(), value);
+    }
+
+    /// Removes a value, returning it if it was present.
+    pub(crate) fn remove<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.state
+            .write()
+            .remove(&TypeId::of::<T>())
+            .and_then(|v| v.downcast::<T>().ok())
     }
 
     /// Gets a reference to a value in the state container.
     pub fn get<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
         self.state
+            .read()
             .get(&TypeId::of::<T>())
             .and_then(|v| Arc::clone(v).downcast::<T>().ok())
     }
 
     /// Returns true if the state container contains a value of type T.
     pub fn contains<T: 'static>(&self) -> bool {
-        self.state.contains_key(&TypeId::of::<T>())
+        self.state.read().contains_key(&TypeId::of::<T>())
+    }
+
+    fn contains_type_id(&self, id: TypeId) -> bool {
+        self.state.read().contains_key(&id)
     }
 
     /// Returns the number of values in the state container.
     pub fn len(&self) -> usize {
-        self.state.len()
+        self.state.read().len()
     }
 
     /// Returns true if the state container is empty.
     pub fn is_empty(&self) -> bool {
-        self.state.is_empty()
+        self.state.read().is_empty()
     }
 }
 
 impl std::fmt::Debug for StateContainer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("StateContainer")
-            .field("count", &self.state.len())
+            .field("count", &self.len())
             .finish()
     }
 }
@@ -391,6 +524,18 @@ impl std::fmt::Debug for StateContainer {
 // Exception Handler Registry
 // ============================================================================
 
+/// Builds the response for a request that matched no route.
+///
+/// See [`AppBuilder::not_found`].
+pub type NotFoundHandler = Arc<dyn Fn(&RequestContext, &Request) -> Response + Send + Sync>;
+
+/// Builds the response for a request whose path matched but method did not.
+///
+/// Receives the methods the path allows. See
+/// [`AppBuilder::method_not_allowed`].
+pub type MethodNotAllowedHandler =
+    Arc<dyn Fn(&RequestContext, &Request, &[Method]) -> Response + Send + Sync>;
+
 /// A boxed exception handler function.
 ///
 /// The handler receives the RequestContext and a boxed error, and returns a Response.
@@ -409,6 +554,10 @@ pub type BoxExceptionHandler = Box<
 /// - [`HttpError`](crate::HttpError) → JSON response with status/detail
 /// - [`ValidationErrors`](crate::ValidationErrors) → 422 with error list
 ///
+/// Errors returned from handlers as [`HandlerError`](crate::HandlerError)
+/// (or any response built with [`Response::with_error`]) are matched against
+/// the registered types by downcasting once the handler returns.
+///
 /// # Example
 ///
 /// ```ignore
@@ -434,7 +583,19 @@ pub type BoxExceptionHandler = Box<
 /// ```
 #[derive(Default)]
 pub struct ExceptionHandlers {
-    handlers: HashMap<TypeId, BoxExceptionHandler>,
+    handlers: HashMap<TypeId, RegisteredHandler>,
+}
+
+struct RegisteredHandler {
+    /// Whether a type-erased error is of the handler's type.
+    matches: fn(&(dyn std::error::Error + Send + Sync + 'static)) -> bool,
+    handler: BoxExceptionHandler,
+}
+
+fn error_is<E: std::error::Error + 'static>(
+    err: &(dyn std::error::Error + Send + Sync + 'static),
+) -> bool {
+    err.is::<E>()
 }
 
 impl ExceptionHandlers {
@@ -452,16 +613,10 @@ impl ExceptionHandlers {
         let mut handlers = Self::new();
 
         // Default handler for HttpError
-        handlers.register::<crate::HttpError>(|_ctx, err| {
-            use crate::IntoResponse;
-            err.into_response()
-        });
+        handlers.register::<crate::HttpError>(|_ctx, err| err.into_response());
 
         // Default handler for ValidationErrors
-        handlers.register::<crate::ValidationErrors>(|_ctx, err| {
-            use crate::IntoResponse;
-            err.into_response()
-        });
+        handlers.register::<crate::ValidationErrors>(|_ctx, err| err.into_response());
 
         handlers
     }
@@ -486,7 +641,13 @@ impl ExceptionHandlers {
                 }
             }
         });
-        self.handlers.insert(TypeId::of::<E>(), boxed_handler);
+        self.handlers.insert(
+            TypeId::of::<E>(),
+            RegisteredHandler {
+                matches: error_is::<E>,
+                handler: boxed_handler,
+            },
+        );
     }
 
     /// Registers a handler for a specific error type (builder pattern).
@@ -513,7 +674,25 @@ impl ExceptionHandlers {
         let type_id = TypeId::of::<E>();
         self.handlers
             .get(&type_id)
-            .map(|handler| handler(ctx, Box::new(err)))
+            .map(|registered| (registered.handler)(ctx, Box::new(err)))
+    }
+
+    /// Handles a type-erased error by downcasting it to the registered types.
+    ///
+    /// Returns the error back if no handler is registered for its type.
+    pub fn handle_boxed(
+        &self,
+        ctx: &RequestContext,
+        err: Box<dyn std::error::Error + Send + Sync>,
+    ) -> Result<Response, Box<dyn std::error::Error + Send + Sync>> {
+        match self
+            .handlers
+            .values()
+            .find(|registered| (registered.matches)(err.as_ref()))
+        {
+            Some(registered) => Ok((registered.handler)(ctx, err)),
+            None => Err(err),
+        }
     }
 
     /// Handles an error, falling back to a default 500 response if no handler is found.
@@ -579,6 +758,31 @@ pub struct AppConfig {
     pub max_body_size: usize,
     /// Default request timeout in milliseconds.
     pub request_timeout_ms: u64,
+    /// Pretty-print JSON responses for humans.
+    ///
+    /// When enabled, JSON bodies are re-indented if the request carries
+    /// `?pretty=1` (or `true`), and always when [`debug`](Self::debug) is set.
+    /// Disabled by default, in which case responses are not inspected at all.
+    pub pretty_json: bool,
+    /// Casing applied to JSON object keys.
+    ///
+    /// When set, response keys are rewritten, JSON request bodies are
+    /// accepted in the same casing, and the OpenAPI schemas show the
+    /// transformed property names. See [`crate::json_case`]. `None` (the
+    /// default) sends keys as serialized.
+    pub json_key_case: Option<crate::json_case::JsonKeyCase>,
+    /// Render built-in error bodies as RFC 9457 `application/problem+json`.
+    ///
+    /// `HttpError` and `ValidationErrors` responses (including the router's
+    /// 404 and 405) are rewritten with `type`, `title`, `status`, `detail`
+    /// and `instance` members. See [`crate::problem`]. Disabled by default.
+    pub problem_details: bool,
+    /// Log a backtrace when a handler panics.
+    ///
+    /// Panics are always answered with a 500 and logged with the request id;
+    /// this also captures the backtrace at the panic site, through a process
+    /// panic hook installed when the app is built. Disabled by default.
+    pub panic_backtraces: bool,
 }
 
 impl Default for AppConfig {
@@ -593,6 +797,10 @@ impl Default for AppConfig {
             debug_config: crate::error::DebugConfig::default(),
             max_body_size: 1024 * 1024, // 1MB
             request_timeout_ms: 30_000, // 30 seconds
+            pretty_json: false,
+            json_key_case: None,
+            problem_details: false,
+            panic_backtraces: false,
         }
     }
 }
@@ -675,6 +883,34 @@ impl AppConfig {
         self.request_timeout_ms = timeout;
         self
     }
+
+    /// Enables pretty-printed JSON responses (see [`AppConfig::pretty_json`]).
+    #[must_use]
+    pub fn pretty_json(mut self, enabled: bool) -> Self {
+        self.pretty_json = enabled;
+        self
+    }
+
+    /// Sets the casing of JSON object keys (see [`AppConfig::json_key_case`]).
+    #[must_use]
+    pub fn json_key_case(mut self, case: crate::json_case::JsonKeyCase) -> Self {
+        self.json_key_case = Some(case);
+        self
+    }
+
+    /// Enables problem+json error bodies (see [`AppConfig::problem_details`]).
+    #[must_use]
+    pub fn problem_details(mut self, enabled: bool) -> Self {
+        self.problem_details = enabled;
+        self
+    }
+
+    /// Enables panic backtrace logging (see [`AppConfig::panic_backtraces`]).
+    #[must_use]
+    pub fn panic_backtraces(mut self, enabled: bool) -> Self {
+        self.panic_backtraces = enabled;
+        self
+    }
 }
 
 // ============================================================================
@@ -712,8 +948,14 @@ pub struct OpenApiConfig {
     pub openapi_path: String,
     /// Servers to include in the spec.
     pub servers: Vec<(String, Option<String>)>,
-    /// Tags for organizing operations.
-    pub tags: Vec<(String, Option<String>)>,
+    /// Tags for organizing operations, with descriptions, links and display order.
+    pub tags: Vec<fastapi_openapi::Tag>,
+    /// Security schemes added to the spec.
+    ///
+    /// These override schemes of the same name contributed by route extractors.
+    pub security_schemes: Vec<(String, fastapi_openapi::SecurityScheme)>,
+    /// Partial OpenAPI JSON document deep-merged over the generated spec.
+    pub overrides_file: Option<std::path::PathBuf>,
 }
 
 impl Default for OpenApiConfig {
@@ -726,6 +968,8 @@ impl Default for OpenApiConfig {
             openapi_path: "/openapi.json".to_string(),
             servers: Vec::new(),
             tags: Vec::new(),
+            security_schemes: Vec::new(),
+            overrides_file: None,
         }
     }
 }
@@ -775,7 +1019,39 @@ impl OpenApiConfig {
     /// Add a tag to the spec.
     #[must_use]
     pub fn tag(mut self, name: impl Into<String>, description: Option<String>) -> Self {
-        self.tags.push((name.into(), description));
+        let mut tag = fastapi_openapi::Tag::new(name);
+        tag.description = description;
+        self.tags.push(tag);
+        self
+    }
+
+    /// Add a tag with full metadata (external docs, display order) to the spec.
+    #[must_use]
+    pub fn tag_def(mut self, tag: fastapi_openapi::Tag) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Add a security scheme to the spec.
+    #[must_use]
+    pub fn security_scheme(
+        mut self,
+        name: impl Into<String>,
+        scheme: fastapi_openapi::SecurityScheme,
+    ) -> Self {
+        self.security_schemes.push((name.into(), scheme));
+        self
+    }
+
+    /// Merge a partial OpenAPI JSON document over the generated spec.
+    ///
+    /// The file is read when the app is built. Objects merge key by key and
+    /// other values from the file win. Replaced generated values, operations
+    /// the app does not serve, and load failures are reported by
+    /// [`App::check`].
+    #[must_use]
+    pub fn overrides_file(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.overrides_file = Some(path.into());
         self
     }
 
@@ -817,13 +1093,30 @@ pub struct AppBuilder {
     routes: Vec<RouteEntry>,
     ws_routes: Vec<WebSocketRouteEntry>,
     middleware: Vec<Arc<dyn Middleware>>,
-    state: StateContainer,
+    state: Arc<StateContainer>,
+    /// State types provided by lifespans, inserted at startup.
+    lifespan_state: Vec<TypeId>,
+    lifespan_teardowns: Arc<parking_lot::Mutex<Vec<LifespanTeardown>>>,
     exception_handlers: ExceptionHandlers,
+    error_reporter: Option<Arc<dyn ErrorReporter>>,
+    profiling: Option<(
+        Arc<dyn crate::profiling::Profiler>,
+        crate::profiling::ProfileConfig,
+    )>,
+    metrics: Option<(String, crate::metrics::MetricsRegistry)>,
+    /// Counters of registered variant routers, exported to `metrics` at build.
+    variant_metrics: Vec<crate::variant::VariantMetrics>,
+    health: Option<Arc<crate::health::HealthCheckRegistry>>,
+    scheduler: crate::scheduler::Scheduler,
     startup_hooks: Vec<StartupHook>,
     shutdown_hooks: Vec<Box<dyn FnOnce() + Send>>,
     async_shutdown_hooks: Vec<Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>>,
     openapi_config: Option<OpenApiConfig>,
     docs_config: Option<crate::docs::DocsConfig>,
+    embedded: Vec<(String, Arc<crate::embed::EmbeddedAssets>)>,
+    batch: Option<crate::batch::Batch>,
+    not_found: Option<NotFoundHandler>,
+    method_not_allowed: Option<MethodNotAllowedHandler>,
 }
 
 impl Default for AppBuilder {
@@ -833,13 +1126,25 @@ impl Default for AppBuilder {
             routes: Vec::new(),
             ws_routes: Vec::new(),
             middleware: Vec::new(),
-            state: StateContainer::default(),
+            state: Arc::new(StateContainer::default()),
+            lifespan_state: Vec::new(),
+            lifespan_teardowns: Arc::new(parking_lot::Mutex::new(Vec::new())),
             exception_handlers: ExceptionHandlers::default(),
+            error_reporter: None,
+            profiling: None,
+            metrics: None,
+            variant_metrics: Vec::new(),
+            health: None,
+            scheduler: crate::scheduler::Scheduler::new(),
             startup_hooks: Vec::new(),
             shutdown_hooks: Vec::new(),
             async_shutdown_hooks: Vec::new(),
             openapi_config: None,
             docs_config: None,
+            embedded: Vec::new(),
+            batch: None,
+            not_found: None,
+            method_not_allowed: None,
         }
     }
 }
@@ -879,6 +1184,23 @@ impl AppBuilder {
         self
     }
 
+    /// Serves the generated OpenAPI document at `path`.
+    ///
+    /// Enables OpenAPI with the app's name and version as title and version,
+    /// or moves the endpoint of an existing [`openapi`](Self::openapi) config.
+    /// The document is generated once when the app is built (and again on
+    /// [`App::reload`]), never per request.
+    #[must_use]
+    pub fn openapi_route(mut self, path: impl Into<String>) -> Self {
+        let config = self.openapi_config.take().unwrap_or_else(|| {
+            OpenApiConfig::new()
+                .title(self.config.name.clone())
+                .version(self.config.version.clone())
+        });
+        self.openapi_config = Some(config.path(path));
+        self
+    }
+
     /// Enables and configures interactive API documentation endpoints.
     ///
     /// This wires [`crate::docs::DocsConfig`] into the application build and ensures an
@@ -914,6 +1236,78 @@ impl AppBuilder {
         self
     }
 
+    /// Serves Swagger UI at `path`.
+    ///
+    /// Shorthand for [`enable_docs`](Self::enable_docs) that enables only
+    /// Swagger UI (plus its OAuth2 redirect page); combine with
+    /// [`redoc`](Self::redoc) for both. Settings from an earlier
+    /// `enable_docs` call are kept.
+    #[must_use]
+    pub fn docs(mut self, path: impl Into<String>) -> Self {
+        let config = self
+            .docs_config
+            .take()
+            .unwrap_or_else(Self::empty_docs_config);
+        self.enable_docs(config.docs_path(Some(path.into())))
+    }
+
+    /// Serves ReDoc at `path`.
+    ///
+    /// See [`docs`](Self::docs).
+    #[must_use]
+    pub fn redoc(mut self, path: impl Into<String>) -> Self {
+        let config = self
+            .docs_config
+            .take()
+            .unwrap_or_else(Self::empty_docs_config);
+        self.enable_docs(config.redoc_path(Some(path.into())))
+    }
+
+    fn empty_docs_config() -> crate::docs::DocsConfig {
+        crate::docs::DocsConfig::new()
+            .docs_path(None::<String>)
+            .redoc_path(None::<String>)
+    }
+
+    /// Serves assets embedded with `embed_dir!` under `prefix`.
+    ///
+    /// `GET {prefix}/{*path}` serves the file at `path`, and `GET {prefix}`
+    /// its index file. Accepts an [`EmbeddedDir`](crate::embed::EmbeddedDir)
+    /// or a configured [`EmbeddedAssets`](crate::embed::EmbeddedAssets);
+    /// the routes are kept out of the OpenAPI spec.
+    #[must_use]
+    pub fn serve_embedded(
+        mut self,
+        prefix: impl Into<String>,
+        assets: impl Into<crate::embed::EmbeddedAssets>,
+    ) -> Self {
+        let prefix = prefix.into().trim_end_matches('/').to_string();
+        self.embedded.push((prefix, Arc::new(assets.into())));
+        self
+    }
+
+    /// Adds a route served by a handler from another ecosystem.
+    ///
+    /// Accepts the adapters from [`interop`](crate::interop), e.g.
+    /// `interop::from_fn(legacy_handler)`. The handler receives the request
+    /// by value.
+    #[must_use]
+    pub fn route_foreign(
+        self,
+        path: impl Into<String>,
+        method: Method,
+        handler: impl ForeignHandler,
+    ) -> Self {
+        let handler = Arc::new(handler);
+        self.route(
+            path,
+            method,
+            move |_ctx: &RequestContext, req: &mut Request| {
+                handler.call_owned(crate::interop::take_request(req))
+            },
+        )
+    }
+
     /// Adds a route to the application.
     ///
     /// Routes are matched in the order they are added.
@@ -937,6 +1331,34 @@ impl AppBuilder {
         self
     }
 
+    /// Adds a route whose traffic is split across the variants of a
+    /// [`VariantRouter`](crate::variant::VariantRouter).
+    ///
+    /// With a registry served by [`metrics`](Self::metrics), assignments are
+    /// also counted there as `variant_assignments_total`.
+    #[must_use]
+    pub fn variants(
+        mut self,
+        path: impl Into<String>,
+        method: Method,
+        router: crate::variant::VariantRouter,
+    ) -> Self {
+        self.variant_metrics.push(router.metrics());
+        self.routes.push(router.into_route_entry(method, path));
+        self
+    }
+
+    /// Serves a [`Batch`](crate::batch::Batch) endpoint that dispatches an
+    /// array of sub-requests through this app's routes and middleware.
+    ///
+    /// The endpoint is kept out of the OpenAPI document and takes precedence
+    /// over a route registered at the same path.
+    #[must_use]
+    pub fn batch(mut self, batch: crate::batch::Batch) -> Self {
+        self.batch = Some(batch);
+        self
+    }
+
     /// Adds a websocket route to the application.
     ///
     /// WebSocket routes are matched only when the server receives a valid
@@ -1019,8 +1441,8 @@ impl AppBuilder {
     ///
     /// State can be accessed by handlers through the `State<T>` extractor.
     #[must_use]
-    pub fn state<T: Send + Sync + 'static>(mut self, state: T) -> Self {
-        self.state.insert(state);
+    pub fn state<T: Send + Sync + 'static>(self, state: T) -> Self {
+        self.state.insert_arc(Arc::new(state));
         self
     }
 
@@ -1061,6 +1483,44 @@ impl AppBuilder {
         self
     }
 
+    /// Sets the response for requests that match no route.
+    ///
+    /// Replaces the built-in `{"detail": "Not Found"}` 404, for example to
+    /// return the same problem-JSON body as the rest of the API:
+    ///
+    /// ```ignore
+    /// let app = App::builder()
+    ///     .not_found(|_ctx, req| {
+    ///         Response::with_status(StatusCode::NOT_FOUND)
+    ///             .header("content-type", b"application/problem+json".to_vec())
+    ///             .body_json(&json!({"title": "Not Found", "instance": req.path()}))
+    ///     })
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn not_found<H>(mut self, handler: H) -> Self
+    where
+        H: Fn(&RequestContext, &Request) -> Response + Send + Sync + 'static,
+    {
+        self.not_found = Some(Arc::new(handler));
+        self
+    }
+
+    /// Sets the response for requests whose path exists but not for their
+    /// method.
+    ///
+    /// The handler receives the allowed methods. The `Allow` header is set on
+    /// its response either way, and `OPTIONS` requests keep their automatic
+    /// 204 answer.
+    #[must_use]
+    pub fn method_not_allowed<H>(mut self, handler: H) -> Self
+    where
+        H: Fn(&RequestContext, &Request, &[Method]) -> Response + Send + Sync + 'static,
+    {
+        self.method_not_allowed = Some(Arc::new(handler));
+        self
+    }
+
     /// Sets the exception handlers registry.
     ///
     /// This replaces any previously registered handlers.
@@ -1081,6 +1541,94 @@ impl AppBuilder {
         self
     }
 
+    /// Sets the reporter notified of unhandled errors and panics.
+    ///
+    /// Panics in handlers or middleware are always answered with a 500
+    /// response; with a reporter they are also reported as fatal events. See
+    /// [`error_reporting`](crate::error_reporting) for the event contents.
+    ///
+    /// The reporter is flushed at shutdown and, when it has a
+    /// [`flush_interval`](ErrorReporter::flush_interval), by an
+    /// `error-reporter-flush` scheduled job.
+    #[must_use]
+    pub fn error_reporter(mut self, reporter: impl ErrorReporter + 'static) -> Self {
+        self.error_reporter = Some(Arc::new(reporter));
+        self
+    }
+
+    /// Mounts the on-demand profiling endpoint backed by `profiler`.
+    ///
+    /// The endpoint is only reachable by requests authorized by the app's
+    /// [`DebugConfig`](crate::error::DebugConfig), so set
+    /// [`AppConfig::debug_config`] before calling [`build`](Self::build). See
+    /// [`profiling`](crate::profiling) for the query parameters and output format.
+    #[must_use]
+    pub fn profiler(
+        mut self,
+        profiler: impl crate::profiling::Profiler + 'static,
+        config: crate::profiling::ProfileConfig,
+    ) -> Self {
+        self.profiling = Some((Arc::new(profiler), config));
+        self
+    }
+
+    /// Serves `registry` at `path` in the Prometheus text format.
+    ///
+    /// Pair with [`MetricsMiddleware`](crate::metrics::MetricsMiddleware) for
+    /// the HTTP server metrics; see [`metrics`](crate::metrics).
+    #[must_use]
+    pub fn metrics(
+        mut self,
+        path: impl Into<String>,
+        registry: crate::metrics::MetricsRegistry,
+    ) -> Self {
+        self.metrics = Some((path.into(), registry));
+        self
+    }
+
+    /// Serves `registry` at `GET /healthz` (liveness) and `GET /readyz`
+    /// (readiness).
+    ///
+    /// Readiness reports `draining` once a shutdown hook marks the registry;
+    /// see [`health`](crate::health) for the JSON format and status codes.
+    #[must_use]
+    pub fn health(mut self, registry: impl Into<Arc<crate::health::HealthCheckRegistry>>) -> Self {
+        self.health = Some(registry.into());
+        self
+    }
+
+    /// Registers a job that runs on `schedule` while the server is up.
+    ///
+    /// Jobs start after the startup hooks and stop when shutdown begins; see
+    /// [`scheduler`](crate::scheduler) for error handling and status output.
+    #[must_use]
+    pub fn schedule<F, Fut, E>(
+        self,
+        name: impl Into<String>,
+        schedule: crate::scheduler::Schedule,
+        job: F,
+    ) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: std::fmt::Display + 'static,
+    {
+        self.scheduler.add(name, schedule, job);
+        self
+    }
+
+    /// Uses `scheduler` for this app's jobs.
+    ///
+    /// Keep a clone to serve its status, e.g. with
+    /// [`status_handler`](crate::scheduler::status_handler). Jobs already
+    /// registered with [`schedule`](Self::schedule) are moved onto it.
+    #[must_use]
+    pub fn scheduler(mut self, scheduler: crate::scheduler::Scheduler) -> Self {
+        let previous = std::mem::replace(&mut self.scheduler, scheduler);
+        previous.move_jobs_to(&self.scheduler);
+        self
+    }
+
     // =========================================================================
     // Lifecycle Hooks
     // =========================================================================
@@ -1189,6 +1737,59 @@ impl AppBuilder {
         self
     }
 
+    /// Registers a resource with async setup and guaranteed teardown.
+    ///
+    /// `setup` runs with the startup hooks, in registration order, and
+    /// receives the app state so it can use resources set up before it. The
+    /// value it returns is stored as state of type `T`. At shutdown the value
+    /// is removed from the state and passed to `teardown`; teardowns run after
+    /// the shutdown hooks, in reverse registration order. If a later startup
+    /// hook aborts, the teardowns of resources already set up still run.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = App::builder()
+    ///     .lifespan(
+    ///         |_state| async {
+    ///             DbPool::connect(DATABASE_URL)
+    ///                 .await
+    ///                 .map_err(|e| StartupHookError::new(e.to_string()))
+    ///         },
+    ///         |pool: Arc<DbPool>| async move { pool.close().await },
+    ///     )
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn lifespan<T, S, SFut, D, DFut>(mut self, setup: S, teardown: D) -> Self
+    where
+        T: Send + Sync + 'static,
+        S: FnOnce(Arc<StateContainer>) -> SFut + Send + 'static,
+        SFut: Future<Output = Result<T, StartupHookError>> + Send + 'static,
+        D: FnOnce(Arc<T>) -> DFut + Send + 'static,
+        DFut: Future<Output = ()> + Send + 'static,
+    {
+        self.lifespan_state.push(TypeId::of::<T>());
+        let state = Arc::clone(&self.state);
+        let teardowns = Arc::clone(&self.lifespan_teardowns);
+        self.startup_hooks
+            .push(StartupHook::AsyncFactory(Box::new(move || {
+                Box::pin(async move {
+                    let value = setup(Arc::clone(&state)).await?;
+                    state.insert_arc(Arc::new(value));
+                    teardowns.lock().push(Box::new(move || {
+                        Box::pin(async move {
+                            if let Some(value) = state.remove::<T>() {
+                                teardown(value).await;
+                            }
+                        })
+                    }));
+                    Ok(())
+                })
+            })));
+        self
+    }
+
     /// Returns the number of registered startup hooks.
     #[must_use]
     pub fn startup_hook_count(&self) -> usize {
@@ -1201,6 +1802,39 @@ impl AppBuilder {
         self.shutdown_hooks.len() + self.async_shutdown_hooks.len()
     }
 
+    /// Names the app-level parts this builder configures, which
+    /// [`App::reload`] cannot swap in.
+    fn app_level_parts(&self) -> Vec<&'static str> {
+        let mut parts = Vec::new();
+        if !self.state.is_empty() {
+            parts.push("state");
+        }
+        if !self.lifespan_state.is_empty() {
+            parts.push("lifespans");
+        }
+        // Each lifespan registers one startup hook.
+        if self.startup_hooks.len() > self.lifespan_state.len() {
+            parts.push("startup hooks");
+        }
+        if self.shutdown_hook_count() > 0 {
+            parts.push("shutdown hooks");
+        }
+        if !self.exception_handlers.is_empty() {
+            parts.push("exception handlers");
+        }
+        if self.error_reporter.is_some() {
+            parts.push("error reporter");
+        }
+        if !self.scheduler.is_empty() {
+            parts.push("scheduled jobs");
+        }
+        // Health probes register a shutdown hook that marks readiness draining.
+        if self.health.is_some() {
+            parts.push("health checks");
+        }
+        parts
+    }
+
     /// Builds the application.
     ///
     /// This consumes the builder and returns the configured [`App`].
@@ -1211,12 +1845,23 @@ impl AppBuilder {
     #[must_use]
     #[allow(clippy::too_many_lines)]
     pub fn build(mut self) -> App {
+        if self.config.panic_backtraces {
+            install_panic_backtrace_hook();
+        }
+        let openapi_config = self.openapi_config.clone();
+        let docs_config = self.docs_config.clone();
+
         // Generate OpenAPI spec if configured
+        let mut openapi_issues = Vec::new();
         let (openapi_spec, openapi_path) = if let Some(ref openapi_config) = self.openapi_config {
             if openapi_config.enabled {
                 let spec = self.generate_openapi_spec(openapi_config);
-                let spec_json =
-                    serde_json::to_string_pretty(&spec).unwrap_or_else(|_| "{}".to_string());
+                let spec_json = render_openapi_spec(
+                    &spec,
+                    openapi_config,
+                    self.config.json_key_case,
+                    &mut openapi_issues,
+                );
                 (
                     Some(Arc::new(spec_json)),
                     Some(openapi_config.openapi_path.clone()),
@@ -1250,7 +1895,10 @@ impl AppBuilder {
         // Add interactive docs endpoints (Swagger UI / ReDoc) if configured and OpenAPI is enabled.
         //
         // These are appended after OpenAPI generation so they do not appear in the OpenAPI spec.
-        if let (Some(openapi_url), Some(docs_config)) = (openapi_path.clone(), self.docs_config) {
+        if let (Some(openapi_url), Some(docs_config)) = (
+            openapi_path.clone(),
+            self.docs_config.filter(|config| config.enabled),
+        ) {
             let docs_config = Arc::new(docs_config);
             let openapi_url = Arc::new(openapi_url);
 
@@ -1268,12 +1916,9 @@ impl AppBuilder {
                 ));
 
                 // FastAPI uses `/docs/oauth2-redirect` by default, relative to docs_path.
-                let docs_prefix = docs_path.trim_end_matches('/');
-                let oauth2_redirect_path = if docs_prefix.is_empty() {
-                    "/oauth2-redirect".to_string()
-                } else {
-                    format!("{docs_prefix}/oauth2-redirect")
-                };
+                let oauth2_redirect_path = docs_config
+                    .oauth2_redirect_path()
+                    .unwrap_or_else(|| "/oauth2-redirect".to_string());
                 self.routes.push(RouteEntry::new(
                     Method::Get,
                     oauth2_redirect_path,
@@ -1298,24 +1943,161 @@ impl AppBuilder {
             }
         }
 
-        let mut middleware_stack = MiddlewareStack::with_capacity(self.middleware.len());
-        for mw in self.middleware {
-            middleware_stack.push_arc(mw);
-        }
-
-        // Build the trie-based router from registered routes
-        let mut router = Router::new();
-        for entry in &self.routes {
-            let route = entry
-                .route_meta()
-                .cloned()
-                .unwrap_or_else(|| Route::new(entry.method, &entry.path));
-            router
-                .add(route)
-                .expect("route conflict during App::build()");
+        // Embedded assets are likewise kept out of the OpenAPI spec.
+        for (prefix, assets) in std::mem::take(&mut self.embedded) {
+            let index_path = if prefix.is_empty() {
+                "/".to_string()
+            } else {
+                prefix.clone()
+            };
+            let paths = [index_path, format!("{prefix}/{{*path}}")];
+            for path in paths {
+                let assets = Arc::clone(&assets);
+                self.routes.push(RouteEntry::new(
+                    Method::Get,
+                    path,
+                    move |_ctx: &RequestContext, req: &mut Request| {
+                        let response = assets.serve_request(req);
+                        async move { response }
+                    },
+                ));
+            }
         }
 
-        // Build the websocket router separately (so websocket + HTTP can share the same path).
+        // The profiling endpoint is likewise kept out of the OpenAPI spec.
+        if let Some((profiler, config)) = self.profiling {
+            let endpoint = Arc::new(crate::profiling::ProfileEndpoint::new(
+                profiler,
+                config,
+                self.config.debug_config.clone(),
+            ));
+            let path = endpoint.path().to_string();
+            self.routes.push(RouteEntry::new(
+                Method::Get,
+                path,
+                move |_ctx: &RequestContext, req: &mut Request| {
+                    let endpoint = Arc::clone(&endpoint);
+                    let prepared = endpoint.prepare(req);
+                    async move {
+                        match prepared {
+                            Ok((kind, duration)) => endpoint.capture(kind, duration).await,
+                            Err(response) => response,
+                        }
+                    }
+                },
+            ));
+        }
+
+        // So is the metrics endpoint.
+        if let Some((path, registry)) = self.metrics.take() {
+            for metrics in &self.variant_metrics {
+                metrics.export(&registry);
+            }
+            self.routes.push(RouteEntry::new(
+                Method::Get,
+                path,
+                move |_ctx: &RequestContext, _req: &mut Request| {
+                    let response = registry.response();
+                    async move { response }
+                },
+            ));
+        }
+
+        // Buffered error reports go out on the reporter's interval and once
+        // more after every other async shutdown hook, so events from the
+        // shutdown itself are not lost.
+        if let Some(reporter) = &self.error_reporter {
+            if let Some(interval) = reporter.flush_interval() {
+                let reporter = Arc::clone(reporter);
+                self.scheduler.add(
+                    "error-reporter-flush",
+                    crate::scheduler::Schedule::every(interval),
+                    move || {
+                        let reporter = Arc::clone(&reporter);
+                        async move { reporter.flush().await.map(drop) }
+                    },
+                );
+            }
+            let reporter = Arc::clone(reporter);
+            self.async_shutdown_hooks.insert(
+                0,
+                Box::new(move || {
+                    Box::pin(async move {
+                        // Nothing is left to retry with at shutdown.
+                        let _ = reporter.flush().await;
+                    })
+                }),
+            );
+        }
+
+        // Scheduled jobs run between the startup and shutdown hooks.
+        if !self.scheduler.is_empty() {
+            let scheduler = self.scheduler.clone();
+            self.startup_hooks.push(StartupHook::Sync(Box::new(move || {
+                scheduler
+                    .start()
+                    .map_err(|err| StartupHookError::new(err.message).with_hook_name("scheduler"))
+            })));
+            let scheduler = self.scheduler.clone();
+            self.async_shutdown_hooks.push(Box::new(move || {
+                Box::pin(async move { scheduler.shutdown().await })
+            }));
+        }
+
+        // The health probes stay out of the spec too. Shutdown hooks run in
+        // reverse order, so readiness reports draining before anything else
+        // is torn down.
+        if let Some(registry) = self.health.take() {
+            self.routes.push(RouteEntry::new(
+                Method::Get,
+                "/healthz",
+                crate::health::detailed_health_handler(Arc::clone(&registry)),
+            ));
+            self.routes.push(RouteEntry::new(
+                Method::Get,
+                "/readyz",
+                crate::health::readiness_handler(Arc::clone(&registry)),
+            ));
+            self.async_shutdown_hooks.push(Box::new(move || {
+                registry.mark_draining();
+                Box::pin(async {})
+            }));
+        }
+
+        // Dependency cycles, scope conflicts and missing state would otherwise
+        // only show up when a request resolves the dependency.
+        let graph = dependency_graph(&self.routes, &self.state, &self.lifespan_state);
+        if !graph.is_ok() {
+            let issues: Vec<String> = graph.issues.iter().map(ToString::to_string).collect();
+            panic!(
+                "invalid dependency graph during App::build():\n  {}",
+                issues.join("\n  ")
+            );
+        }
+
+        let mut middleware_stack = MiddlewareStack::with_capacity(self.middleware.len());
+        for mw in self.middleware {
+            middleware_stack.push_arc(mw);
+        }
+
+        // Build the trie-based router from registered routes, warming the
+        // error bodies its lookups and parameter extraction can produce.
+        let error_bodies = crate::error_bodies::ErrorBodyCache::global();
+        error_bodies.status_body(StatusCode::NOT_FOUND);
+        error_bodies.status_body(StatusCode::METHOD_NOT_ALLOWED);
+        let mut router = Router::new();
+        for entry in &self.routes {
+            let route = entry
+                .route_meta()
+                .cloned()
+                .unwrap_or_else(|| Route::new(entry.method, &entry.path));
+            error_bodies.prewarm_route(&route);
+            router
+                .add(route)
+                .expect("route conflict during App::build()");
+        }
+
+        // Build the websocket router separately (so websocket + HTTP can share the same path).
         let mut ws_router = Router::new();
         for entry in &self.ws_routes {
             ws_router
@@ -1323,20 +2105,35 @@ impl AppBuilder {
                 .expect("websocket route conflict during App::build()");
         }
 
+        let exception_handlers = Arc::new(self.exception_handlers);
         App {
             config: self.config,
-            routes: self.routes,
-            ws_routes: self.ws_routes,
-            router,
-            ws_router,
-            middleware: middleware_stack,
-            state: Arc::new(self.state),
-            exception_handlers: Arc::new(self.exception_handlers),
+            snapshot: SnapshotCell::new(AppSnapshot {
+                routes: self.routes,
+                ws_routes: self.ws_routes,
+                router,
+                ws_router,
+                middleware: middleware_stack,
+                openapi_spec,
+                openapi_issues,
+                dependency_graph: graph,
+                batch: self.batch,
+                exception_handlers: Arc::clone(&exception_handlers),
+                not_found: self.not_found,
+                method_not_allowed: self.method_not_allowed,
+            }),
+            openapi_config,
+            docs_config,
+            state: self.state,
+            lifespan_state: self.lifespan_state,
+            lifespan_teardowns: self.lifespan_teardowns,
+            exception_handlers,
+            error_reporter: self.error_reporter,
             dependency_overrides: Arc::new(crate::dependency::DependencyOverrides::new()),
+            scheduler: self.scheduler,
             startup_hooks: parking_lot::Mutex::new(self.startup_hooks),
             shutdown_hooks: parking_lot::Mutex::new(self.shutdown_hooks),
             async_shutdown_hooks: parking_lot::Mutex::new(self.async_shutdown_hooks),
-            openapi_spec,
         }
     }
 
@@ -1358,14 +2155,24 @@ impl AppBuilder {
         }
 
         // Add tags
-        for (name, desc) in &config.tags {
-            builder = builder.tag(name, desc.clone());
+        for tag in &config.tags {
+            builder = builder.tag_def(tag.clone());
         }
 
         // Add operations for each registered route
         for entry in &self.routes {
+            for (name, scheme) in entry.security_schemes() {
+                builder = builder.security_scheme(name, scheme.clone());
+            }
+
             if let Some(route) = entry.route_meta() {
-                builder.add_route(route);
+                match entry.openapi_schemas {
+                    Some(schemas) => {
+                        let schemas = schemas(&mut builder.registry());
+                        builder.add_route_with_schemas(route, &schemas);
+                    }
+                    None => builder.add_route(route),
+                }
                 continue;
             }
 
@@ -1375,6 +2182,7 @@ impl AppBuilder {
                 "200".to_string(),
                 OAResponse {
                     description: "Successful response".to_string(),
+                    headers: HashMap::new(),
                     content: HashMap::new(),
                 },
             );
@@ -1396,15 +2204,71 @@ impl AppBuilder {
                 request_body: None,
                 responses,
                 deprecated: false,
+                idempotent: false,
+                security: Vec::new(),
             };
 
             builder = builder.operation(entry.method.as_str(), &entry.path, operation);
         }
 
+        for (name, scheme) in &config.security_schemes {
+            builder = builder.security_scheme(name, scheme.clone());
+        }
+
         builder.build()
     }
 }
 
+/// Serialize the generated spec, merging the configured overrides file.
+///
+/// Override conflicts and load failures are appended to `issues`; a file that
+/// cannot be loaded leaves the generated spec untouched. With a JSON key case
+/// configured, schema property names are transformed to match the responses.
+fn render_openapi_spec(
+    spec: &fastapi_openapi::OpenApi,
+    config: &OpenApiConfig,
+    key_case: Option<crate::json_case::JsonKeyCase>,
+    issues: &mut Vec<crate::check::CheckIssue>,
+) -> String {
+    use crate::check::CheckIssue;
+
+    let render = |mut doc: serde_json::Value| {
+        if let Some(case) = key_case {
+            case.convert_schemas(&mut doc);
+        }
+        serde_json::to_string_pretty(&doc).unwrap_or_else(|_| "{}".to_string())
+    };
+    let generated = || match key_case {
+        Some(_) => render(serde_json::to_value(spec).unwrap_or(serde_json::Value::Null)),
+        None => serde_json::to_string_pretty(spec).unwrap_or_else(|_| "{}".to_string()),
+    };
+    let Some(path) = &config.overrides_file else {
+        return generated();
+    };
+
+    let overrides = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| {
+            serde_json::from_str::<serde_json::Value>(&text).map_err(|e| e.to_string())
+        });
+    match overrides {
+        Ok(overrides) => {
+            let (doc, conflicts) = spec.merge_overrides(&overrides);
+            issues.extend(conflicts.iter().map(|conflict| {
+                CheckIssue::warning("openapi", format!("{}: {conflict}", path.display()))
+            }));
+            render(doc)
+        }
+        Err(err) => {
+            issues.push(CheckIssue::error(
+                "openapi",
+                format!("cannot load overrides file {}: {err}", path.display()),
+            ));
+            generated()
+        }
+    }
+}
+
 impl std::fmt::Debug for AppBuilder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AppBuilder")
@@ -1419,62 +2283,28 @@ impl std::fmt::Debug for AppBuilder {
     }
 }
 
-/// A configured web application.
+/// Immutable routing state of an [`App`]: route table, middleware stack,
+/// dependency graph and generated OpenAPI document.
 ///
-/// The `App` holds all routes, middleware, state, and lifecycle hooks,
-/// and provides methods to handle incoming requests.
-pub struct App {
-    config: AppConfig,
+/// Built once by [`AppBuilder::build`] and shared as `Arc<AppSnapshot>`, so
+/// workers route requests without taking locks. [`App::reload`] installs a
+/// new snapshot rather than mutating this one.
+pub struct AppSnapshot {
     routes: Vec<RouteEntry>,
     ws_routes: Vec<WebSocketRouteEntry>,
     router: Router,
     ws_router: Router,
     middleware: MiddlewareStack,
-    state: Arc<StateContainer>,
-    exception_handlers: Arc<ExceptionHandlers>,
-    dependency_overrides: Arc<crate::dependency::DependencyOverrides>,
-    startup_hooks: parking_lot::Mutex<Vec<StartupHook>>,
-    shutdown_hooks: parking_lot::Mutex<Vec<Box<dyn FnOnce() + Send>>>,
-    async_shutdown_hooks: parking_lot::Mutex<
-        Vec<Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>>,
-    >,
-    /// The generated OpenAPI specification (if enabled).
     openapi_spec: Option<Arc<String>>,
+    openapi_issues: Vec<crate::check::CheckIssue>,
+    dependency_graph: DependencyGraph,
+    batch: Option<crate::batch::Batch>,
+    exception_handlers: Arc<ExceptionHandlers>,
+    not_found: Option<NotFoundHandler>,
+    method_not_allowed: Option<MethodNotAllowedHandler>,
 }
 
-impl App {
-    /// Creates a new application builder.
-    #[must_use]
-    pub fn builder() -> AppBuilder {
-        AppBuilder::new()
-    }
-
-    /// Create an in-process test client for this app.
-    ///
-    /// This takes `Arc<Self>` so tests can keep using shared `Arc<App>` values
-    /// without extra boilerplate.
-    #[cfg(feature = "testing")]
-    #[must_use]
-    pub fn test_client(self: Arc<Self>) -> crate::testing::TestClient<Arc<Self>> {
-        crate::testing::TestClient::new(self)
-    }
-
-    /// Create an in-process test client with a deterministic seed.
-    #[cfg(feature = "testing")]
-    #[must_use]
-    pub fn test_client_with_seed(
-        self: Arc<Self>,
-        seed: u64,
-    ) -> crate::testing::TestClient<Arc<Self>> {
-        crate::testing::TestClient::with_seed(self, seed)
-    }
-
-    /// Returns the application configuration.
-    #[must_use]
-    pub fn config(&self) -> &AppConfig {
-        &self.config
-    }
-
+impl AppSnapshot {
     /// Returns the number of registered routes.
     #[must_use]
     pub fn route_count(&self) -> usize {
@@ -1497,101 +2327,38 @@ impl App {
     }
 
     /// Returns an iterator over route metadata (method, path).
-    ///
-    /// This is useful for generating OpenAPI specifications or debugging.
     pub fn routes(&self) -> impl Iterator<Item = (Method, &str)> {
         self.routes.iter().map(|r| (r.method, r.path.as_str()))
     }
 
     /// Returns the generated OpenAPI specification JSON, if OpenAPI is enabled.
-    ///
-    /// # Example
-    ///
-    /// ```ignore
-    /// let app = App::builder()
-    ///     .openapi(OpenApiConfig::new().title("My API"))
-    ///     .build();
-    ///
-    /// if let Some(spec) = app.openapi_spec() {
-    ///     println!("OpenAPI spec: {}", spec);
-    /// }
-    /// ```
     #[must_use]
     pub fn openapi_spec(&self) -> Option<&str> {
         self.openapi_spec.as_ref().map(|s| s.as_str())
     }
 
-    /// Returns the shared state container.
-    #[must_use]
-    pub fn state(&self) -> &Arc<StateContainer> {
-        &self.state
-    }
-
-    /// Gets a reference to shared state of type T.
-    pub fn get_state<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
-        self.state.get::<T>()
-    }
-
-    /// Returns the exception handlers registry.
-    #[must_use]
-    pub fn exception_handlers(&self) -> &Arc<ExceptionHandlers> {
-        &self.exception_handlers
-    }
-
-    /// Register a fixed override value for a dependency type.
-    ///
-    /// This is a test-focused convenience API; production code typically wires
-    /// dependencies via [`crate::dependency::FromDependency`] implementations.
-    pub fn override_dependency_value<T>(&self, value: T)
-    where
-        T: crate::dependency::FromDependency,
-    {
-        self.dependency_overrides.insert_value(value);
-    }
-
-    /// Clear all registered dependency overrides.
-    pub fn clear_dependency_overrides(&self) {
-        self.dependency_overrides.clear();
-    }
-
-    /// Returns the shared dependency overrides registry.
+    /// Returns the dependency trees of the routes, as validated at build time.
     #[must_use]
-    pub fn dependency_overrides(&self) -> Arc<crate::dependency::DependencyOverrides> {
-        Arc::clone(&self.dependency_overrides)
-    }
-
-    /// Take request-scoped background tasks (if any) from a request.
-    ///
-    /// The HTTP server calls this after writing the response so any deferred work
-    /// runs outside the main request handler path.
-    pub fn take_background_tasks(req: &mut Request) -> Option<crate::request::BackgroundTasks> {
-        req.take_extension::<crate::request::BackgroundTasks>()
-    }
-
-    /// Handles an error using registered exception handlers.
-    ///
-    /// If a handler is registered for the error type, it will be invoked.
-    /// Otherwise, returns `None`.
-    pub fn handle_error<E>(&self, ctx: &RequestContext, err: E) -> Option<Response>
-    where
-        E: std::error::Error + Send + Sync + 'static,
-    {
-        self.exception_handlers.handle(ctx, err)
+    pub fn dependency_graph(&self) -> &DependencyGraph {
+        &self.dependency_graph
     }
 
-    /// Handles an error, returning a 500 response if no handler is registered.
-    pub fn handle_error_or_default<E>(&self, ctx: &RequestContext, err: E) -> Response
-    where
-        E: std::error::Error + Send + Sync + 'static,
-    {
-        self.exception_handlers.handle_or_default(ctx, err)
-    }
-
-    /// Handles an incoming request.
+    /// Handles an incoming request against this snapshot.
     ///
     /// This matches the request against registered routes, runs middleware,
     /// and returns the response.
     pub async fn handle(&self, ctx: &RequestContext, req: &mut Request) -> Response {
+        if let Some(batch) = &self.batch {
+            if req.method() == Method::Post && req.path() == batch.path() {
+                ctx.log_scope().set_route(batch.path().to_string());
+                let handler = crate::batch::BatchHandler {
+                    batch,
+                    snapshot: self,
+                };
+                return self.middleware.execute(&handler, ctx, req).await;
+            }
+        }
+
         // Use the trie-based router for efficient matching with path parameter extraction
         match self.router.lookup(req.path(), req.method()) {
             RouteLookup::Match(route_match) => {
@@ -1605,6 +2372,12 @@ impl App {
                     return Response::with_status(StatusCode::INTERNAL_SERVER_ERROR);
                 };
 
+                ctx.log_scope().set_route(entry.path.clone());
+
+                if entry.route_meta().is_some_and(|route| route.idempotent) {
+                    req.insert_extension(crate::middleware::RetrySafe);
+                }
+
                 // Store extracted path parameters in the request
                 if !route_match.params.is_empty() {
                     let path_params = crate::extract::PathParams::from_pairs(
@@ -1618,7 +2391,10 @@ impl App {
                 }
 
                 // Create a handler that wraps the route
-                let handler = RouteHandler { entry };
+                let handler = RouteHandler {
+                    entry,
+                    exception_handlers: &self.exception_handlers,
+                };
                 self.middleware.execute(&handler, ctx, req).await
             }
             RouteLookup::MethodNotAllowed { allowed } => {
@@ -1632,12 +2408,20 @@ impl App {
                     let allow = fastapi_router::AllowedMethods::new(methods);
                     Response::with_status(StatusCode::NO_CONTENT)
                         .header("allow", allow.header_value().as_bytes().to_vec())
-                } else {
-                    Response::with_status(StatusCode::METHOD_NOT_ALLOWED)
+                } else if let Some(handler) = &self.method_not_allowed {
+                    handler(ctx, req, allowed.methods())
+                        .remove_header("allow")
                         .header("allow", allowed.header_value().as_bytes().to_vec())
+                } else {
+                    crate::HttpError::new(StatusCode::METHOD_NOT_ALLOWED)
+                        .with_header("allow", allowed.header_value().as_bytes().to_vec())
+                        .into_response()
                 }
             }
-            RouteLookup::NotFound => Response::with_status(StatusCode::NOT_FOUND),
+            RouteLookup::NotFound => match &self.not_found {
+                Some(handler) => handler(ctx, req),
+                None => crate::HttpError::not_found().into_response(),
+            },
         }
     }
 
@@ -1681,1170 +2465,2740 @@ impl App {
             )),
         }
     }
+}
 
-    // =========================================================================
-    // Lifecycle Hook Execution
-    // =========================================================================
+impl std::fmt::Debug for AppSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppSnapshot")
+            .field("routes", &self.routes.len())
+            .field("ws_routes", &self.ws_routes.len())
+            .field("middleware", &self.middleware.len())
+            .field("openapi", &self.openapi_spec.is_some())
+            .field("dependency_trees", &self.dependency_graph.trees.len())
+            .finish()
+    }
+}
 
-    /// Runs all startup hooks.
-    ///
-    /// Hooks run in registration order (FIFO). If a hook returns an error
-    /// with `abort: true`, execution stops and returns `StartupOutcome::Aborted`.
-    ///
-    /// This consumes the startup hooks - they can only be run once.
-    ///
-    /// # Returns
-    ///
-    /// - `StartupOutcome::Success` if all hooks succeeded
-    /// - `StartupOutcome::PartialSuccess` if some hooks had non-fatal errors
-    /// - `StartupOutcome::Aborted` if a fatal hook error occurred
-    pub async fn run_startup_hooks(&self) -> StartupOutcome {
-        let hooks: Vec<StartupHook> = std::mem::take(&mut *self.startup_hooks.lock());
-        let mut warnings = 0;
+/// Holder for the current [`AppSnapshot`] that can be replaced atomically.
+///
+/// Readers hold the read lock only long enough to clone the `Arc`; the
+/// request itself runs against that clone with no lock held. An uncontended
+/// `parking_lot` read lock is a single atomic update, and the write lock is
+/// only taken by [`App::reload`], so readers practically never wait. A
+/// replaced snapshot is dropped once the last in-flight request holding it
+/// finishes.
+struct SnapshotCell {
+    current: parking_lot::RwLock<Arc<AppSnapshot>>,
+    generation: std::sync::atomic::AtomicU64,
+}
 
-        for hook in hooks {
-            match hook.run() {
-                Ok(None) => {
-                    // Sync hook succeeded
-                }
-                Ok(Some(fut)) => {
-                    // Async hook - await it
-                    match fut.await {
-                        Ok(()) => {}
-                        Err(e) if e.abort => {
-                            return StartupOutcome::Aborted(e);
-                        }
-                        Err(_) => {
-                            warnings += 1;
-                        }
-                    }
-                }
-                Err(e) if e.abort => {
-                    return StartupOutcome::Aborted(e);
-                }
-                Err(_) => {
-                    warnings += 1;
-                }
-            }
+impl SnapshotCell {
+    fn new(snapshot: AppSnapshot) -> Self {
+        Self {
+            current: parking_lot::RwLock::new(Arc::new(snapshot)),
+            generation: std::sync::atomic::AtomicU64::new(0),
         }
+    }
 
-        if warnings > 0 {
-            StartupOutcome::PartialSuccess { warnings }
-        } else {
-            StartupOutcome::Success
-        }
+    fn load(&self) -> Arc<AppSnapshot> {
+        Arc::clone(&self.current.read())
     }
 
-    /// Runs all shutdown hooks.
+    fn swap(&self, next: Arc<AppSnapshot>) -> Arc<AppSnapshot> {
+        let mut current = self.current.write();
+        let previous = std::mem::replace(&mut *current, next);
+        self.generation
+            .fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+        previous
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.load(std::sync::atomic::Ordering::Acquire)
+    }
+
+    /// Takes the current snapshot out of a cell that is being discarded.
+    fn into_current(self) -> Arc<AppSnapshot> {
+        self.current.into_inner()
+    }
+}
+
+/// Runs and clears the recorded lifespan teardowns, most recent first.
+async fn run_lifespan_teardowns(teardowns: &parking_lot::Mutex<Vec<LifespanTeardown>>) {
+    let teardowns = std::mem::take(&mut *teardowns.lock());
+    for teardown in teardowns.into_iter().rev() {
+        teardown().await;
+    }
+}
+
+/// A configured web application.
+///
+/// The `App` holds all routes, middleware, state, and lifecycle hooks,
+/// and provides methods to handle incoming requests.
+pub struct App {
+    config: AppConfig,
+    /// Routes, middleware and OpenAPI output, replaceable as a unit.
+    snapshot: SnapshotCell,
+    /// Kept so [`App::reload`] can regenerate the spec and docs endpoints.
+    openapi_config: Option<OpenApiConfig>,
+    docs_config: Option<crate::docs::DocsConfig>,
+    state: Arc<StateContainer>,
+    lifespan_state: Vec<TypeId>,
+    lifespan_teardowns: Arc<parking_lot::Mutex<Vec<LifespanTeardown>>>,
+    exception_handlers: Arc<ExceptionHandlers>,
+    error_reporter: Option<Arc<dyn ErrorReporter>>,
+    dependency_overrides: Arc<crate::dependency::DependencyOverrides>,
+    scheduler: crate::scheduler::Scheduler,
+    startup_hooks: parking_lot::Mutex<Vec<StartupHook>>,
+    shutdown_hooks: parking_lot::Mutex<Vec<Box<dyn FnOnce() + Send>>>,
+    async_shutdown_hooks: parking_lot::Mutex<
+        Vec<Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>>,
+    >,
+}
+
+impl App {
+    /// Creates a new application builder.
+    #[must_use]
+    pub fn builder() -> AppBuilder {
+        AppBuilder::new()
+    }
+
+    /// Create an in-process test client for this app.
     ///
-    /// Hooks run in reverse registration order (LIFO). Errors are logged
-    /// but do not stop other hooks from running.
+    /// This takes `Arc<Self>` so tests can keep using shared `Arc<App>` values
+    /// without extra boilerplate.
+    #[cfg(feature = "testing")]
+    #[must_use]
+    pub fn test_client(self: Arc<Self>) -> crate::testing::TestClient<Arc<Self>> {
+        crate::testing::TestClient::new(self)
+    }
+
+    /// Create an in-process test client with a deterministic seed.
+    #[cfg(feature = "testing")]
+    #[must_use]
+    pub fn test_client_with_seed(
+        self: Arc<Self>,
+        seed: u64,
+    ) -> crate::testing::TestClient<Arc<Self>> {
+        crate::testing::TestClient::with_seed(self, seed)
+    }
+
+    /// Returns the application configuration.
+    #[must_use]
+    pub fn config(&self) -> &AppConfig {
+        &self.config
+    }
+
+    /// Returns the number of registered routes.
+    #[must_use]
+    pub fn route_count(&self) -> usize {
+        self.snapshot().route_count()
+    }
+
+    /// Returns the number of registered websocket routes.
+    #[must_use]
+    pub fn websocket_route_count(&self) -> usize {
+        self.snapshot().websocket_route_count()
+    }
+
+    /// Returns true if a websocket route matches the given path.
+    #[must_use]
+    pub fn has_websocket_route(&self, path: &str) -> bool {
+        self.snapshot().has_websocket_route(path)
+    }
+
+    /// Returns an iterator over route metadata (method, path).
     ///
-    /// This consumes the shutdown hooks - they can only be run once.
-    pub async fn run_shutdown_hooks(&self) {
-        // Run async hooks first (LIFO)
-        let async_hooks: Vec<_> = std::mem::take(&mut *self.async_shutdown_hooks.lock());
-        for hook in async_hooks.into_iter().rev() {
-            let fut = hook();
-            fut.await;
+    /// This is useful for generating OpenAPI specifications or debugging.
+    /// The routes are those of the snapshot current at the time of the call.
+    pub fn routes(&self) -> impl Iterator<Item = (Method, String)> {
+        self.snapshot()
+            .routes()
+            .map(|(method, path)| (method, path.to_string()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Returns the generated OpenAPI specification JSON, if OpenAPI is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = App::builder()
+    ///     .openapi(OpenApiConfig::new().title("My API"))
+    ///     .build();
+    ///
+    /// if let Some(spec) = app.openapi_spec() {
+    ///     println!("OpenAPI spec: {}", spec);
+    /// }
+    /// ```
+    #[must_use]
+    ///
+    /// The document is shared with the snapshot it was generated for, so it
+    /// stays readable across a [`reload`](Self::reload).
+    pub fn openapi_spec(&self) -> Option<Arc<String>> {
+        self.snapshot().openapi_spec.clone()
+    }
+
+    /// Validates the built application without serving any traffic.
+    ///
+    /// Reports the registered routes, middleware, state and OpenAPI output,
+    /// and flags configuration that will misbehave at runtime: handlers whose
+    /// `State<T>` was never registered, zero body-size or timeout limits,
+    /// unauthenticated debug access, OpenAPI overrides that conflict with the
+    /// generated spec, and findings from each middleware's
+    /// [`Middleware::check`]. See [`check`](crate::check).
+    #[must_use]
+    pub fn check(&self) -> crate::check::CheckReport {
+        use crate::check::{CheckIssue, CheckReport};
+
+        let snapshot = self.snapshot();
+        let mut issues = Vec::new();
+
+        if snapshot.routes.is_empty() && snapshot.ws_routes.is_empty() {
+            issues.push(CheckIssue::warning("routes", "no routes registered"));
+        }
+        for entry in &snapshot.routes {
+            for (id, name) in &entry.required_state {
+                if !self.state.contains_type_id(*id) && !self.lifespan_state.contains(id) {
+                    issues.push(CheckIssue::error(
+                        "state",
+                        format!(
+                            "{} {} requires state `{name}`, which is not registered",
+                            entry.method.as_str(),
+                            entry.path
+                        ),
+                    ));
+                }
+            }
         }
 
-        // Run sync hooks (LIFO)
-        let sync_hooks: Vec<_> = std::mem::take(&mut *self.shutdown_hooks.lock());
-        for hook in sync_hooks.into_iter().rev() {
-            hook();
+        if self.config.max_body_size == 0 {
+            issues.push(CheckIssue::error(
+                "limits",
+                "max_body_size is 0; every request with a body is rejected",
+            ));
+        }
+        if self.config.request_timeout_ms == 0 {
+            issues.push(CheckIssue::error(
+                "limits",
+                "request_timeout_ms is 0; every request times out",
+            ));
+        }
+        let debug = &self.config.debug_config;
+        if debug.enabled && debug.allow_unauthenticated {
+            issues.push(CheckIssue::warning(
+                "debug",
+                "debug output is enabled without authentication",
+            ));
+        }
+
+        let graph = snapshot.dependency_graph.clone();
+        issues.extend(graph.issues);
+        issues.extend(snapshot.openapi_issues.iter().cloned());
+        for mw in snapshot.middleware.iter() {
+            issues.extend(mw.check());
+        }
+        issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+
+        CheckReport {
+            app_name: self.config.name.clone(),
+            version: self.config.version.clone(),
+            routes: snapshot
+                .routes
+                .iter()
+                .map(|r| (r.method, r.path.clone()))
+                .collect(),
+            websocket_routes: snapshot.ws_routes.len(),
+            middleware: snapshot
+                .middleware
+                .iter()
+                .map(|mw| mw.name().to_string())
+                .collect(),
+            state_count: self.state.len(),
+            openapi_bytes: snapshot.openapi_spec.as_ref().map(|spec| spec.len()),
+            dependency_trees: graph.trees,
+            dependency_cycles: graph.cycles,
+            issues,
         }
     }
 
-    /// Transfers shutdown hooks to a [`ShutdownController`].
+    /// Returns the current routing snapshot.
     ///
-    /// This moves all registered shutdown hooks to the controller, which
-    /// will run them during the appropriate shutdown phase.
+    /// The snapshot is immutable: it keeps serving the routes it was built with
+    /// even if [`App::reload`] installs a newer one while it is held.
+    #[must_use]
+    pub fn snapshot(&self) -> Arc<AppSnapshot> {
+        self.snapshot.load()
+    }
+
+    /// Returns how many times the routing snapshot has been replaced.
     ///
-    /// Call this when integrating with the server's shutdown mechanism.
-    pub fn transfer_shutdown_hooks(&self, controller: &ShutdownController) {
-        // Transfer sync hooks (they'll run in LIFO due to how pop_hook works)
-        let sync_hooks: Vec<_> = std::mem::take(&mut *self.shutdown_hooks.lock());
-        for hook in sync_hooks {
-            controller.register_hook(hook);
-        }
+    /// Starts at `0` for a freshly built app.
+    #[must_use]
+    pub fn snapshot_generation(&self) -> u64 {
+        self.snapshot.generation()
+    }
 
-        // Transfer async hooks
-        let async_hooks: Vec<_> = std::mem::take(&mut *self.async_shutdown_hooks.lock());
-        for hook in async_hooks {
-            controller.register_async_hook(move || hook());
+    /// Replaces the routes, middleware, dependency graph and OpenAPI output
+    /// with those of `builder`.
+    ///
+    /// Intended for development reload. Requests already in flight finish on the
+    /// snapshot they started with; new requests see the replacement. The new
+    /// snapshot is built against the running app's config and state, so its
+    /// routes may use state registered at startup. Unless `builder` configures
+    /// its own, the running app's OpenAPI and docs endpoints are carried over
+    /// and the spec is regenerated from the new routes.
+    ///
+    /// Dependency overrides are not part of the snapshot: they are a runtime
+    /// registry on the app and apply to the new routes as well.
+    ///
+    /// Returns the previous snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReloadError`] without touching the running app if `builder`
+    /// registers state, lifespans, lifecycle hooks, exception handlers, an
+    /// error reporter, scheduled jobs or health checks. Those belong to the
+    /// running app and are not replaced by a reload.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`AppBuilder::build`].
+    pub fn reload(&self, mut builder: AppBuilder) -> Result<Arc<AppSnapshot>, ReloadError> {
+        let rejected = builder.app_level_parts();
+        if !rejected.is_empty() {
+            return Err(ReloadError { rejected });
+        }
+        builder.config = self.config.clone();
+        builder.state = Arc::clone(&self.state);
+        builder.lifespan_state.clone_from(&self.lifespan_state);
+        if builder.openapi_config.is_none() {
+            builder.openapi_config.clone_from(&self.openapi_config);
+        }
+        if builder.docs_config.is_none() {
+            builder.docs_config.clone_from(&self.docs_config);
+        }
+        let App { snapshot, .. } = builder.build();
+        let mut next = snapshot.into_current();
+        // Freshly built, so this is the only reference.
+        if let Some(next) = Arc::get_mut(&mut next) {
+            next.exception_handlers = Arc::clone(&self.exception_handlers);
         }
+        Ok(self.snapshot.swap(next))
     }
 
-    /// Returns the number of pending startup hooks.
+    /// Returns the shared state container.
     #[must_use]
-    pub fn pending_startup_hooks(&self) -> usize {
-        self.startup_hooks.lock().len()
+    pub fn state(&self) -> &Arc<StateContainer> {
+        &self.state
     }
 
-    /// Returns the number of pending shutdown hooks.
+    /// Gets a reference to shared state of type T.
+    pub fn get_state<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.state.get::<T>()
+    }
+
+    /// Returns the exception handlers registry.
     #[must_use]
-    pub fn pending_shutdown_hooks(&self) -> usize {
-        self.shutdown_hooks.lock().len() + self.async_shutdown_hooks.lock().len()
+    pub fn exception_handlers(&self) -> &Arc<ExceptionHandlers> {
+        &self.exception_handlers
     }
-}
 
-impl std::fmt::Debug for App {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("App")
-            .field("config", &self.config)
-            .field("routes", &self.routes.len())
-            .field("middleware", &self.middleware.len())
-            .field("state", &self.state)
-            .field("exception_handlers", &self.exception_handlers)
-            .field("startup_hooks", &self.startup_hooks.lock().len())
-            .field("shutdown_hooks", &self.pending_shutdown_hooks())
-            .finish()
+    /// Register a fixed override value for a dependency type.
+    ///
+    /// This is a test-focused convenience API; production code typically wires
+    /// dependencies via [`crate::dependency::FromDependency`] implementations.
+    pub fn override_dependency_value<T>(&self, value: T)
+    where
+        T: crate::dependency::FromDependency,
+    {
+        self.dependency_overrides.insert_value(value);
     }
-}
 
-// Allow `App` to be used anywhere a middleware `Handler` is expected (e.g. TestClient).
-impl Handler for App {
-    fn call<'a>(
-        &'a self,
-        ctx: &'a RequestContext,
-        req: &'a mut Request,
-    ) -> BoxFuture<'a, Response> {
-        Box::pin(async move { self.handle(ctx, req).await })
+    /// Clear all registered dependency overrides.
+    pub fn clear_dependency_overrides(&self) {
+        self.dependency_overrides.clear();
     }
 
-    fn dependency_overrides(&self) -> Option<Arc<crate::dependency::DependencyOverrides>> {
-        Some(Arc::clone(&self.dependency_overrides))
+    /// Returns the shared dependency overrides registry.
+    #[must_use]
+    pub fn dependency_overrides(&self) -> Arc<crate::dependency::DependencyOverrides> {
+        Arc::clone(&self.dependency_overrides)
     }
-}
 
-/// Handler wrapper for a route entry.
-struct RouteHandler<'a> {
-    entry: &'a RouteEntry,
-}
+    /// Take request-scoped background tasks (if any) from a request.
+    ///
+    /// The HTTP server calls this after writing the response so any deferred work
+    /// runs outside the main request handler path.
+    pub fn take_background_tasks(req: &mut Request) -> Option<crate::request::BackgroundTasks> {
+        req.take_extension::<crate::request::BackgroundTasks>()
+    }
 
-impl<'a> Handler for RouteHandler<'a> {
-    fn call<'b>(
-        &'b self,
-        ctx: &'b RequestContext,
-        req: &'b mut Request,
-    ) -> BoxFuture<'b, Response> {
-        let handler = self.entry.handler.clone();
-        Box::pin(async move { handler(ctx, req).await })
+    /// Returns the scheduler running this app's jobs.
+    #[must_use]
+    pub fn scheduler(&self) -> &crate::scheduler::Scheduler {
+        &self.scheduler
+    }
+
+    /// Returns the configured error reporter, if any.
+    #[must_use]
+    pub fn error_reporter(&self) -> Option<&Arc<dyn ErrorReporter>> {
+        self.error_reporter.as_ref()
+    }
+
+    /// Handles an error using registered exception handlers.
+    ///
+    /// If a handler is registered for the error type, it will be invoked.
+    /// Otherwise, returns `None`.
+    ///
+    /// The error is sent to the [`ErrorReporter`] when no handler is
+    /// registered or the handler produced a 5xx response.
+    pub fn handle_error<E>(&self, ctx: &RequestContext, err: E) -> Option<Response>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let Some(reporter) = &self.error_reporter else {
+            return self.exception_handlers.handle(ctx, err);
+        };
+        let event = ErrorEvent::from_error(ctx, &err);
+        let response = self.exception_handlers.handle(ctx, err);
+        if response.as_ref().is_none_or(|r| r.status().as_u16() >= 500) {
+            reporter.report(event);
+        }
+        response
+    }
+
+    /// Handles an error, returning a 500 response if no handler is registered.
+    pub fn handle_error_or_default<E>(&self, ctx: &RequestContext, err: E) -> Response
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        self.handle_error(ctx, err)
+            .unwrap_or_else(|| Response::with_status(StatusCode::INTERNAL_SERVER_ERROR))
+    }
+
+    /// Handles an incoming request.
+    ///
+    /// This matches the request against registered routes, runs middleware,
+    /// and returns the response. Child tasks the handler spawned with
+    /// [`RequestContext::spawn_scoped`] and did not await or detach are
+    /// cancelled before this returns, and teardowns registered by
+    /// dependencies (see [`RequestContext::defer`]) run once the response is
+    /// produced. If this future is dropped early the teardowns still run,
+    /// with [`RequestOutcome::Aborted`].
+    pub async fn handle(&self, ctx: &RequestContext, req: &mut Request) -> Response {
+        let pretty = self.config.pretty_json && (self.config.debug || wants_pretty_json(req));
+        let key_case = self.config.json_key_case;
+        if let Some(case) = key_case {
+            req.insert_extension(case);
+        }
+        let mut guard = CleanupGuard { ctx, armed: true };
+        let mut response = self.dispatch(ctx, req).await;
+        ctx.scoped_tasks().cancel_remaining();
+        if !ctx.cleanup_stack().is_empty() {
+            ctx.cleanup_stack()
+                .set_outcome(RequestOutcome::Response(response.status()));
+            ctx.cleanup_stack().run_cleanups().await;
+        }
+        guard.armed = false;
+        if self.config.problem_details {
+            response = crate::problem::ProblemDetails::rewrite_response(response, req.path());
+        }
+        if let Some(case) = key_case {
+            response = response.json_key_case(case);
+        }
+        if pretty {
+            response.pretty_json()
+        } else {
+            response
+        }
+    }
+
+    async fn dispatch(&self, ctx: &RequestContext, req: &mut Request) -> Response {
+        use std::panic::{AssertUnwindSafe, catch_unwind};
+        use std::task::Poll;
+
+        let snapshot = self.snapshot();
+        if self.error_reporter.is_some() {
+            ctx.log_scope()
+                .set_request(crate::error_reporting::RequestSummary::from_request(req));
+        }
+        // A panicking handler must not take the connection task down with it.
+        let mut future = std::pin::pin!(snapshot.handle(ctx, req));
+        let result = std::future::poll_fn(move |cx| {
+            match catch_unwind(AssertUnwindSafe(|| future.as_mut().poll(cx))) {
+                Ok(Poll::Ready(response)) => Poll::Ready(Ok(response)),
+                Ok(Poll::Pending) => Poll::Pending,
+                Err(payload) => Poll::Ready(Err(payload)),
+            }
+        })
+        .await;
+
+        match result {
+            Ok(response) => response,
+            Err(payload) => self.panic_response(ctx, payload.as_ref()),
+        }
+    }
+
+    /// Logs and reports a caught panic, and builds the 500 sent in its place.
+    ///
+    /// The response carries the request id in `x-request-id` so a client
+    /// report can be matched with the log entry.
+    fn panic_response(&self, ctx: &RequestContext, payload: &(dyn Any + Send)) -> Response {
+        let event = ErrorEvent::from_panic(ctx, payload);
+        let backtrace = take_panic_backtrace();
+        crate::logging::RequestLogger::new(ctx, crate::logging::LogConfig::default())
+            .error_with_fields("handler panicked", |entry| {
+                let entry = entry.field("panic", &event.message);
+                match &backtrace {
+                    Some(backtrace) => entry.field("backtrace", backtrace),
+                    None => entry,
+                }
+            });
+        if let Some(reporter) = &self.error_reporter {
+            reporter.report(event);
+        }
+        crate::HttpError::internal()
+            .with_header("x-request-id", ctx.request_id().to_string().into_bytes())
+            .into_response()
+    }
+
+    /// Handles an incoming websocket upgrade request after the handshake has been accepted.
+    ///
+    /// The HTTP server is responsible for validating the upgrade headers and writing the 101
+    /// response. This function only performs path matching and calls the websocket handler.
+    pub async fn handle_websocket(
+        &self,
+        ctx: &RequestContext,
+        req: &mut Request,
+        ws: crate::websocket::WebSocket,
+    ) -> Result<(), crate::websocket::WebSocketError> {
+        let snapshot = self.snapshot();
+        snapshot.handle_websocket(ctx, req, ws).await
+    }
+
+    // =========================================================================
+    // Lifecycle Hook Execution
+    // =========================================================================
+
+    /// Runs all startup hooks.
+    ///
+    /// Hooks run in registration order (FIFO). If a hook returns an error
+    /// with `abort: true`, execution stops and returns `StartupOutcome::Aborted`.
+    ///
+    /// This consumes the startup hooks - they can only be run once.
+    ///
+    /// # Returns
+    ///
+    /// - `StartupOutcome::Success` if all hooks succeeded
+    /// - `StartupOutcome::PartialSuccess` if some hooks had non-fatal errors
+    /// - `StartupOutcome::Aborted` if a fatal hook error occurred
+    ///
+    /// On abort, the teardowns of lifespans already set up are run.
+    pub async fn run_startup_hooks(&self) -> StartupOutcome {
+        let hooks: Vec<StartupHook> = std::mem::take(&mut *self.startup_hooks.lock());
+        let mut warnings = 0;
+
+        for hook in hooks {
+            match hook.run() {
+                Ok(None) => {
+                    // Sync hook succeeded
+                }
+                Ok(Some(fut)) => {
+                    // Async hook - await it
+                    match fut.await {
+                        Ok(()) => {}
+                        Err(e) if e.abort => {
+                            run_lifespan_teardowns(&self.lifespan_teardowns).await;
+                            return StartupOutcome::Aborted(e);
+                        }
+                        Err(_) => {
+                            warnings += 1;
+                        }
+                    }
+                }
+                Err(e) if e.abort => {
+                    run_lifespan_teardowns(&self.lifespan_teardowns).await;
+                    return StartupOutcome::Aborted(e);
+                }
+                Err(_) => {
+                    warnings += 1;
+                }
+            }
+        }
+
+        if warnings > 0 {
+            StartupOutcome::PartialSuccess { warnings }
+        } else {
+            StartupOutcome::Success
+        }
+    }
+
+    /// Runs all shutdown hooks.
+    ///
+    /// Hooks run in reverse registration order (LIFO). Errors are logged
+    /// but do not stop other hooks from running.
+    ///
+    /// Lifespan teardowns run last, also in reverse order.
+    ///
+    /// This consumes the shutdown hooks - they can only be run once.
+    pub async fn run_shutdown_hooks(&self) {
+        // Run async hooks first (LIFO)
+        let async_hooks: Vec<_> = std::mem::take(&mut *self.async_shutdown_hooks.lock());
+        for hook in async_hooks.into_iter().rev() {
+            let fut = hook();
+            fut.await;
+        }
+
+        // Run sync hooks (LIFO)
+        let sync_hooks: Vec<_> = std::mem::take(&mut *self.shutdown_hooks.lock());
+        for hook in sync_hooks.into_iter().rev() {
+            hook();
+        }
+
+        run_lifespan_teardowns(&self.lifespan_teardowns).await;
+    }
+
+    /// Transfers shutdown hooks to a [`ShutdownController`].
+    ///
+    /// This moves all registered shutdown hooks to the controller, which
+    /// will run them during the appropriate shutdown phase.
+    ///
+    /// Call this when integrating with the server's shutdown mechanism.
+    pub fn transfer_shutdown_hooks(&self, controller: &ShutdownController) {
+        // Registered first so lifespan teardowns run after the other hooks.
+        let teardowns = Arc::clone(&self.lifespan_teardowns);
+        controller.register_async_hook(move || async move {
+            run_lifespan_teardowns(&teardowns).await;
+        });
+
+        // Transfer sync hooks (they'll run in LIFO due to how pop_hook works)
+        let sync_hooks: Vec<_> = std::mem::take(&mut *self.shutdown_hooks.lock());
+        for hook in sync_hooks {
+            controller.register_hook(hook);
+        }
+
+        // Transfer async hooks
+        let async_hooks: Vec<_> = std::mem::take(&mut *self.async_shutdown_hooks.lock());
+        for hook in async_hooks {
+            controller.register_async_hook(move || hook());
+        }
+    }
+
+    /// Returns the number of pending startup hooks.
+    #[must_use]
+    pub fn pending_startup_hooks(&self) -> usize {
+        self.startup_hooks.lock().len()
+    }
+
+    /// Returns the number of pending shutdown hooks.
+    #[must_use]
+    pub fn pending_shutdown_hooks(&self) -> usize {
+        self.shutdown_hooks.lock().len() + self.async_shutdown_hooks.lock().len()
+    }
+}
+
+/// Walks the dependency trees of `routes` against the registered state.
+fn dependency_graph(
+    routes: &[RouteEntry],
+    state: &StateContainer,
+    lifespan_state: &[TypeId],
+) -> DependencyGraph {
+    DependencyGraph::build(
+        routes.iter().map(|entry| {
+            (
+                format!("{} {}", entry.method.as_str(), entry.path),
+                entry.dependencies(),
+            )
+        }),
+        &|id| state.contains_type_id(id) || lifespan_state.contains(&id),
+    )
+}
+
+/// Runs a request's cleanup stack if [`App::handle`] is dropped before the
+/// response is produced (client disconnect, server timeout, panic).
+struct CleanupGuard<'a> {
+    ctx: &'a RequestContext,
+    armed: bool,
+}
+
+impl Drop for CleanupGuard<'_> {
+    fn drop(&mut self) {
+        if !self.armed || self.ctx.cleanup_stack().is_empty() {
+            return;
+        }
+        self.ctx
+            .cleanup_stack()
+            .set_outcome(RequestOutcome::Aborted);
+        let teardown = |ctx: RequestContext| async move {
+            ctx.cleanup_stack().run_cleanups().await;
+        };
+        if let Some(runtime) = asupersync::runtime::Runtime::current_handle() {
+            // The cleanups are only taken once the task runs, so a failed
+            // spawn leaves them for the fallback below.
+            if runtime.try_spawn(teardown(self.ctx.clone())).is_ok() {
+                return;
+            }
+        }
+        futures_executor::block_on(teardown(self.ctx.clone()));
+    }
+}
+
+thread_local! {
+    static PANIC_BACKTRACE: std::cell::RefCell<Option<std::backtrace::Backtrace>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Chains a panic hook that keeps the panicking thread's backtrace for
+/// [`App::panic_response`]. Installed at most once per process.
+fn install_panic_backtrace_hook() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let backtrace = std::backtrace::Backtrace::force_capture();
+            PANIC_BACKTRACE.with(|slot| *slot.borrow_mut() = Some(backtrace));
+            previous(info);
+        }));
+    });
+}
+
+fn take_panic_backtrace() -> Option<std::backtrace::Backtrace> {
+    PANIC_BACKTRACE.with(|slot| slot.borrow_mut().take())
+}
+
+/// Returns true if the query string asks for pretty JSON (`?pretty=1`).
+fn wants_pretty_json(req: &Request) -> bool {
+    req.query().is_some_and(|q| {
+        crate::extract::QueryParams::parse(q)
+            .get("pretty")
+            .is_some_and(|v| matches!(v, "1" | "true" | ""))
+    })
+}
+
+impl std::fmt::Debug for App {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("App")
+            .field("config", &self.config)
+            .field("snapshot", &self.snapshot())
+            .field("snapshot_generation", &self.snapshot_generation())
+            .field("state", &self.state)
+            .field("exception_handlers", &self.exception_handlers)
+            .field("startup_hooks", &self.startup_hooks.lock().len())
+            .field("shutdown_hooks", &self.pending_shutdown_hooks())
+            .finish()
+    }
+}
+
+// Allow `App` to be used anywhere a middleware `Handler` is expected (e.g. TestClient).
+impl Handler for App {
+    fn call<'a>(
+        &'a self,
+        ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, Response> {
+        Box::pin(async move { self.handle(ctx, req).await })
+    }
+
+    fn dependency_overrides(&self) -> Option<Arc<crate::dependency::DependencyOverrides>> {
+        Some(Arc::clone(&self.dependency_overrides))
+    }
+}
+
+/// Handler wrapper for a route entry.
+struct RouteHandler<'a> {
+    entry: &'a RouteEntry,
+    exception_handlers: &'a ExceptionHandlers,
+}
+
+impl<'a> Handler for RouteHandler<'a> {
+    fn call<'b>(
+        &'b self,
+        ctx: &'b RequestContext,
+        req: &'b mut Request,
+    ) -> BoxFuture<'b, Response> {
+        let handler = self.entry.handler.clone();
+        let exception_handlers = self.exception_handlers;
+        Box::pin(async move {
+            let mut response = handler(ctx, req).await;
+            match response.take_error() {
+                Some(err) => exception_handlers
+                    .handle_boxed(ctx, err)
+                    .unwrap_or(response),
+                None => response,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::response::ResponseBody;
+
+    // Test handlers that return 'static futures (no borrowing from parameters)
+    fn test_handler(_ctx: &RequestContext, _req: &mut Request) -> std::future::Ready<Response> {
+        std::future::ready(Response::ok().body(ResponseBody::Bytes(b"Hello, World!".to_vec())))
+    }
+
+    fn health_handler(_ctx: &RequestContext, _req: &mut Request) -> std::future::Ready<Response> {
+        std::future::ready(Response::ok().body(ResponseBody::Bytes(b"OK".to_vec())))
+    }
+
+    fn test_context() -> RequestContext {
+        let cx = asupersync::Cx::for_testing();
+        RequestContext::new(cx, 1)
+    }
+
+    #[test]
+    fn app_builder_creates_app() {
+        let app = App::builder()
+            .config(AppConfig::new().name("Test App"))
+            .get("/", test_handler)
+            .get("/health", health_handler)
+            .build();
+
+        assert_eq!(app.route_count(), 2);
+        assert_eq!(app.config().name, "Test App");
+    }
+
+    #[test]
+    fn app_config_builder() {
+        let config = AppConfig::new()
+            .name("My API")
+            .version("1.0.0")
+            .debug(true)
+            .max_body_size(2 * 1024 * 1024)
+            .request_timeout_ms(60_000);
+
+        assert_eq!(config.name, "My API");
+        assert_eq!(config.version, "1.0.0");
+        assert!(config.debug);
+        assert_eq!(config.max_body_size, 2 * 1024 * 1024);
+        assert_eq!(config.request_timeout_ms, 60_000);
+    }
+
+    #[test]
+    fn state_container_insert_and_get() {
+        #[derive(Debug, PartialEq)]
+        struct MyState {
+            value: i32,
+        }
+
+        let mut container = StateContainer::new();
+        container.insert(MyState { value: 42 });
+
+        let state = container.get::<MyState>();
+        assert!(state.is_some());
+        assert_eq!(state.unwrap().value, 42);
+    }
+
+    #[test]
+    fn state_container_multiple_types() {
+        struct TypeA(i32);
+        struct TypeB(String);
+
+        let mut container = StateContainer::new();
+        container.insert(TypeA(1));
+        container.insert(TypeB("hello".to_string()));
+
+        assert!(container.contains::<TypeA>());
+        assert!(container.contains::<TypeB>());
+        assert!(!container.contains::<i64>());
+
+        assert_eq!(container.get::<TypeA>().unwrap().0, 1);
+        assert_eq!(container.get::<TypeB>().unwrap().0, "hello");
+    }
+
+    #[test]
+    fn app_builder_with_state() {
+        struct DbPool {
+            connection_count: usize,
+        }
+
+        let app = App::builder()
+            .state(DbPool {
+                connection_count: 10,
+            })
+            .get("/", test_handler)
+            .build();
+
+        let pool = app.get_state::<DbPool>();
+        assert!(pool.is_some());
+        assert_eq!(pool.unwrap().connection_count, 10);
+    }
+
+    #[test]
+    fn app_handles_get_request() {
+        let app = App::builder().get("/", test_handler).build();
+
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/");
+
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 200);
+    }
+
+    #[test]
+    fn app_returns_404_for_unknown_path() {
+        let app = App::builder().get("/", test_handler).build();
+
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/unknown");
+
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 404);
+    }
+
+    #[test]
+    fn app_returns_405_for_wrong_method() {
+        let app = App::builder().get("/", test_handler).build();
+
+        let ctx = test_context();
+        let mut req = Request::new(Method::Post, "/");
+
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 405);
+    }
+
+    #[test]
+    fn deferred_teardowns_see_the_response_outcome() {
+        let outcomes = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let seen = Arc::clone(&outcomes);
+        let app = App::builder()
+            .get("/fail", move |ctx: &RequestContext, _req: &mut Request| {
+                for label in ["first", "second"] {
+                    let seen = Arc::clone(&seen);
+                    ctx.defer(move |outcome| async move { seen.lock().push((label, outcome)) });
+                }
+                std::future::ready(Response::with_status(StatusCode::INTERNAL_SERVER_ERROR))
+            })
+            .build();
+
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/fail");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 500);
+
+        let failed = RequestOutcome::Response(StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(*outcomes.lock(), [("second", failed), ("first", failed)]);
+        assert!(!failed.is_success());
+    }
+
+    #[test]
+    fn deferred_teardowns_run_when_the_request_is_dropped() {
+        let outcome = Arc::new(parking_lot::Mutex::new(None));
+        let seen = Arc::clone(&outcome);
+        let app = App::builder()
+            .get("/slow", move |ctx: &RequestContext, _req: &mut Request| {
+                let seen = Arc::clone(&seen);
+                ctx.defer(move |outcome| async move { *seen.lock() = Some(outcome) });
+                std::future::pending::<Response>()
+            })
+            .build();
+
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/slow");
+        {
+            let mut future = std::pin::pin!(app.handle(&ctx, &mut req));
+            let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+            assert!(future.as_mut().poll(&mut cx).is_pending());
+        }
+        assert_eq!(*outcome.lock(), Some(RequestOutcome::Aborted));
+    }
+
+    #[test]
+    fn app_builder_all_methods() {
+        let app = App::builder()
+            .get("/get", test_handler)
+            .post("/post", test_handler)
+            .put("/put", test_handler)
+            .delete("/delete", test_handler)
+            .patch("/patch", test_handler)
+            .build();
+
+        assert_eq!(app.route_count(), 5);
+    }
+
+    #[test]
+    fn route_entry_debug() {
+        let entry = RouteEntry::new(Method::Get, "/test", test_handler);
+        let debug = format!("{:?}", entry);
+        assert!(debug.contains("RouteEntry"));
+        assert!(debug.contains("Get"));
+        assert!(debug.contains("/test"));
+    }
+
+    #[test]
+    fn app_with_middleware() {
+        use crate::middleware::NoopMiddleware;
+
+        let app = App::builder()
+            .middleware(NoopMiddleware)
+            .middleware(NoopMiddleware)
+            .get("/", test_handler)
+            .build();
+
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/");
+
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 200);
+    }
+
+    // =========================================================================
+    // Exception Handlers Tests
+    // =========================================================================
+
+    // Custom error type for testing
+    #[derive(Debug)]
+    struct TestError {
+        message: String,
+        code: u32,
+    }
+
+    impl std::fmt::Display for TestError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "TestError({}): {}", self.code, self.message)
+        }
+    }
+
+    impl std::error::Error for TestError {}
+
+    // Another custom error type
+    #[derive(Debug)]
+    struct AnotherError(String);
+
+    impl std::fmt::Display for AnotherError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "AnotherError: {}", self.0)
+        }
+    }
+
+    impl std::error::Error for AnotherError {}
+
+    // --- Unit Tests: Handler Registration ---
+
+    #[test]
+    fn exception_handlers_new_is_empty() {
+        let handlers = ExceptionHandlers::new();
+        assert!(handlers.is_empty());
+        assert_eq!(handlers.len(), 0);
+    }
+
+    #[test]
+    fn exception_handlers_register_single() {
+        let mut handlers = ExceptionHandlers::new();
+        handlers.register::<TestError>(|_ctx, err| {
+            Response::with_status(StatusCode::BAD_REQUEST)
+                .body(ResponseBody::Bytes(err.message.as_bytes().to_vec()))
+        });
+
+        assert!(handlers.has_handler::<TestError>());
+        assert!(!handlers.has_handler::<AnotherError>());
+        assert_eq!(handlers.len(), 1);
+    }
+
+    #[test]
+    fn exception_handlers_register_multiple() {
+        let mut handlers = ExceptionHandlers::new();
+        handlers.register::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST));
+        handlers.register::<AnotherError>(|_ctx, _err| {
+            Response::with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        });
+
+        assert!(handlers.has_handler::<TestError>());
+        assert!(handlers.has_handler::<AnotherError>());
+        assert_eq!(handlers.len(), 2);
+    }
+
+    #[test]
+    fn exception_handlers_builder_pattern() {
+        let handlers = ExceptionHandlers::new()
+            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST))
+            .handler::<AnotherError>(|_ctx, _err| {
+                Response::with_status(StatusCode::INTERNAL_SERVER_ERROR)
+            });
+
+        assert!(handlers.has_handler::<TestError>());
+        assert!(handlers.has_handler::<AnotherError>());
+        assert_eq!(handlers.len(), 2);
+    }
+
+    #[test]
+    fn exception_handlers_with_defaults() {
+        let handlers = ExceptionHandlers::with_defaults();
+
+        assert!(handlers.has_handler::<crate::HttpError>());
+        assert!(handlers.has_handler::<crate::ValidationErrors>());
+        assert_eq!(handlers.len(), 2);
+    }
+
+    #[test]
+    fn exception_handlers_merge() {
+        let mut handlers1 = ExceptionHandlers::new()
+            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST));
+
+        let handlers2 = ExceptionHandlers::new().handler::<AnotherError>(|_ctx, _err| {
+            Response::with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        });
+
+        handlers1.merge(handlers2);
+
+        assert!(handlers1.has_handler::<TestError>());
+        assert!(handlers1.has_handler::<AnotherError>());
+        assert_eq!(handlers1.len(), 2);
+    }
+
+    // --- Unit Tests: Handler Invocation ---
+
+    #[test]
+    fn exception_handlers_handle_registered_error() {
+        let handlers = ExceptionHandlers::new().handler::<TestError>(|_ctx, err| {
+            Response::with_status(StatusCode::BAD_REQUEST)
+                .body(ResponseBody::Bytes(err.message.as_bytes().to_vec()))
+        });
+
+        let ctx = test_context();
+        let err = TestError {
+            message: "test error".into(),
+            code: 42,
+        };
+
+        let response = handlers.handle(&ctx, err);
+        assert!(response.is_some());
+
+        let response = response.unwrap();
+        assert_eq!(response.status().as_u16(), 400);
+    }
+
+    #[test]
+    fn exception_handlers_handle_unregistered_error() {
+        let handlers = ExceptionHandlers::new()
+            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST));
+
+        let ctx = test_context();
+        let err = AnotherError("unhandled".into());
+
+        let response = handlers.handle(&ctx, err);
+        assert!(response.is_none());
+    }
+
+    #[test]
+    fn exception_handlers_handle_or_default_registered() {
+        let handlers = ExceptionHandlers::new()
+            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST));
+
+        let ctx = test_context();
+        let err = TestError {
+            message: "test".into(),
+            code: 1,
+        };
+
+        let response = handlers.handle_or_default(&ctx, err);
+        assert_eq!(response.status().as_u16(), 400);
+    }
+
+    #[test]
+    fn exception_handlers_handle_or_default_unregistered() {
+        let handlers = ExceptionHandlers::new();
+
+        let ctx = test_context();
+        let err = TestError {
+            message: "test".into(),
+            code: 1,
+        };
+
+        let response = handlers.handle_or_default(&ctx, err);
+        assert_eq!(response.status().as_u16(), 500);
+    }
+
+    #[test]
+    fn exception_handlers_error_values_passed_to_handler() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let captured_code = Arc::new(AtomicU32::new(0));
+        let captured_code_clone = captured_code.clone();
+
+        let handlers = ExceptionHandlers::new().handler::<TestError>(move |_ctx, err| {
+            captured_code_clone.store(err.code, Ordering::SeqCst);
+            Response::with_status(StatusCode::BAD_REQUEST)
+        });
+
+        let ctx = test_context();
+        let err = TestError {
+            message: "test".into(),
+            code: 12345,
+        };
+
+        let _ = handlers.handle(&ctx, err);
+        assert_eq!(captured_code.load(Ordering::SeqCst), 12345);
+    }
+
+    // --- Integration Tests: Custom Error Type Handling ---
+
+    #[test]
+    fn app_builder_exception_handler_single() {
+        let app = App::builder()
+            .exception_handler::<TestError, _>(|_ctx, err| {
+                Response::with_status(StatusCode::BAD_REQUEST)
+                    .body(ResponseBody::Bytes(err.message.as_bytes().to_vec()))
+            })
+            .get("/", test_handler)
+            .build();
+
+        assert!(app.exception_handlers().has_handler::<TestError>());
+    }
+
+    #[test]
+    fn app_builder_exception_handler_multiple() {
+        let app = App::builder()
+            .exception_handler::<TestError, _>(|_ctx, _err| {
+                Response::with_status(StatusCode::BAD_REQUEST)
+            })
+            .exception_handler::<AnotherError, _>(|_ctx, _err| {
+                Response::with_status(StatusCode::INTERNAL_SERVER_ERROR)
+            })
+            .get("/", test_handler)
+            .build();
+
+        assert!(app.exception_handlers().has_handler::<TestError>());
+        assert!(app.exception_handlers().has_handler::<AnotherError>());
+    }
+
+    #[test]
+    fn app_builder_with_default_exception_handlers() {
+        let app = App::builder()
+            .with_default_exception_handlers()
+            .get("/", test_handler)
+            .build();
+
+        assert!(app.exception_handlers().has_handler::<crate::HttpError>());
+        assert!(
+            app.exception_handlers()
+                .has_handler::<crate::ValidationErrors>()
+        );
+    }
+
+    #[test]
+    fn app_builder_exception_handlers_registry() {
+        let handlers = ExceptionHandlers::new()
+            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST))
+            .handler::<AnotherError>(|_ctx, _err| {
+                Response::with_status(StatusCode::INTERNAL_SERVER_ERROR)
+            });
+
+        let app = App::builder()
+            .exception_handlers(handlers)
+            .get("/", test_handler)
+            .build();
+
+        assert!(app.exception_handlers().has_handler::<TestError>());
+        assert!(app.exception_handlers().has_handler::<AnotherError>());
+    }
+
+    #[test]
+    fn app_handle_error_registered() {
+        let app = App::builder()
+            .exception_handler::<TestError, _>(|_ctx, _err| {
+                Response::with_status(StatusCode::BAD_REQUEST)
+            })
+            .get("/", test_handler)
+            .build();
+
+        let ctx = test_context();
+        let err = TestError {
+            message: "test".into(),
+            code: 1,
+        };
+
+        let response = app.handle_error(&ctx, err);
+        assert!(response.is_some());
+        assert_eq!(response.unwrap().status().as_u16(), 400);
+    }
+
+    #[test]
+    fn app_handle_error_unregistered() {
+        let app = App::builder().get("/", test_handler).build();
+
+        let ctx = test_context();
+        let err = TestError {
+            message: "test".into(),
+            code: 1,
+        };
+
+        let response = app.handle_error(&ctx, err);
+        assert!(response.is_none());
+    }
+
+    #[test]
+    fn app_handle_error_or_default() {
+        let app = App::builder().get("/", test_handler).build();
+
+        let ctx = test_context();
+        let err = TestError {
+            message: "test".into(),
+            code: 1,
+        };
+
+        let response = app.handle_error_or_default(&ctx, err);
+        assert_eq!(response.status().as_u16(), 500);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn exception_handlers_handle_boxed_downcasts() {
+        let handlers = ExceptionHandlers::new()
+            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::from_u16(409)));
+        let ctx = test_context();
 
-    use crate::response::ResponseBody;
+        let err: Box<dyn std::error::Error + Send + Sync> = Box::new(TestError {
+            message: "boxed".into(),
+            code: 9,
+        });
+        let response = handlers.handle_boxed(&ctx, err).unwrap();
+        assert_eq!(response.status().as_u16(), 409);
 
-    // Test handlers that return 'static futures (no borrowing from parameters)
-    fn test_handler(_ctx: &RequestContext, _req: &mut Request) -> std::future::Ready<Response> {
-        std::future::ready(Response::ok().body(ResponseBody::Bytes(b"Hello, World!".to_vec())))
+        let err: Box<dyn std::error::Error + Send + Sync> = Box::new(AnotherError("other".into()));
+        let err = handlers.handle_boxed(&ctx, err).unwrap_err();
+        assert_eq!(err.to_string(), AnotherError("other".into()).to_string());
     }
 
-    fn health_handler(_ctx: &RequestContext, _req: &mut Request) -> std::future::Ready<Response> {
-        std::future::ready(Response::ok().body(ResponseBody::Bytes(b"OK".to_vec())))
+    fn raising_handler(_ctx: &RequestContext, req: &mut Request) -> std::future::Ready<Response> {
+        let result: Result<Response, crate::HandlerError> = match req.path() {
+            "/domain" => Err(TestError {
+                message: "domain".into(),
+                code: 7,
+            }
+            .into()),
+            "/http" => Err(crate::HttpError::not_found().into()),
+            _ => Err(AnotherError("unhandled".into()).into()),
+        };
+        std::future::ready(result.unwrap_or_else(IntoResponse::into_response))
     }
 
-    fn test_context() -> RequestContext {
-        let cx = asupersync::Cx::for_testing();
-        RequestContext::new(cx, 1)
+    #[test]
+    fn handler_errors_reach_exception_handlers() {
+        let app = App::builder()
+            .get("/domain", raising_handler)
+            .get("/http", raising_handler)
+            .get("/other", raising_handler)
+            .exception_handler::<TestError, _>(|_ctx, err| {
+                Response::with_status(StatusCode::from_u16(409))
+                    .body(ResponseBody::Bytes(err.message.into_bytes()))
+            })
+            .build();
+        let ctx = test_context();
+        let status = |path: &str| {
+            let mut req = Request::new(Method::Get, path);
+            futures_executor::block_on(app.handle(&ctx, &mut req))
+                .status()
+                .as_u16()
+        };
+
+        assert_eq!(status("/domain"), 409);
+        assert_eq!(status("/http"), 404);
+        assert_eq!(status("/other"), 500);
     }
 
     #[test]
-    fn app_builder_creates_app() {
+    fn custom_not_found_and_method_not_allowed_responses() {
         let app = App::builder()
-            .config(AppConfig::new().name("Test App"))
-            .get("/", test_handler)
-            .get("/health", health_handler)
+            .get("/items", test_handler)
+            .post("/items", test_handler)
+            .not_found(|_ctx, req| {
+                Response::with_status(StatusCode::NOT_FOUND)
+                    .header("content-type", b"application/problem+json".to_vec())
+                    .body(ResponseBody::Bytes(req.path().as_bytes().to_vec()))
+            })
+            .method_not_allowed(|_ctx, _req, allowed| {
+                Response::with_status(StatusCode::METHOD_NOT_ALLOWED)
+                    .header("allow", b"bogus".to_vec())
+                    .body(ResponseBody::Bytes(allowed.len().to_string().into_bytes()))
+            })
             .build();
+        let ctx = test_context();
+        let send = |method: Method, path: &str| {
+            let mut req = Request::new(method, path);
+            futures_executor::block_on(app.handle(&ctx, &mut req))
+        };
+        let header = |response: &Response, name: &str| {
+            response
+                .headers()
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.clone())
+        };
 
-        assert_eq!(app.route_count(), 2);
-        assert_eq!(app.config().name, "Test App");
+        let missing = send(Method::Get, "/nope");
+        assert_eq!(missing.status().as_u16(), 404);
+        assert_eq!(
+            header(&missing, "content-type").as_deref(),
+            Some(&b"application/problem+json"[..])
+        );
+        assert!(matches!(missing.body_ref(), ResponseBody::Bytes(b) if b == b"/nope"));
+
+        let wrong_method = send(Method::Delete, "/items");
+        assert_eq!(wrong_method.status().as_u16(), 405);
+        assert_eq!(
+            header(&wrong_method, "allow").as_deref(),
+            Some(&b"GET, HEAD, POST"[..])
+        );
+        assert!(matches!(wrong_method.body_ref(), ResponseBody::Bytes(b) if b == b"3"));
+
+        let options = send(Method::Options, "/items");
+        assert_eq!(options.status().as_u16(), 204);
     }
 
+    // --- Integration Tests: Override Default Handler ---
+
     #[test]
-    fn app_config_builder() {
-        let config = AppConfig::new()
-            .name("My API")
-            .version("1.0.0")
-            .debug(true)
-            .max_body_size(2 * 1024 * 1024)
-            .request_timeout_ms(60_000);
+    fn exception_handlers_override_on_register() {
+        let handlers = ExceptionHandlers::new()
+            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST))
+            .handler::<TestError>(|_ctx, _err| {
+                Response::with_status(StatusCode::UNPROCESSABLE_ENTITY)
+            });
 
-        assert_eq!(config.name, "My API");
-        assert_eq!(config.version, "1.0.0");
-        assert!(config.debug);
-        assert_eq!(config.max_body_size, 2 * 1024 * 1024);
-        assert_eq!(config.request_timeout_ms, 60_000);
+        // Only one handler for TestError
+        assert_eq!(handlers.len(), 1);
+
+        let ctx = test_context();
+        let err = TestError {
+            message: "test".into(),
+            code: 1,
+        };
+
+        // Should use the second (overriding) handler
+        let response = handlers.handle(&ctx, err);
+        assert!(response.is_some());
+        assert_eq!(response.unwrap().status().as_u16(), 422);
     }
 
     #[test]
-    fn state_container_insert_and_get() {
-        #[derive(Debug, PartialEq)]
-        struct MyState {
-            value: i32,
-        }
+    fn exception_handlers_merge_overrides() {
+        let mut handlers1 = ExceptionHandlers::new()
+            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST));
 
-        let mut container = StateContainer::new();
-        container.insert(MyState { value: 42 });
+        let handlers2 = ExceptionHandlers::new().handler::<TestError>(|_ctx, _err| {
+            Response::with_status(StatusCode::UNPROCESSABLE_ENTITY)
+        });
 
-        let state = container.get::<MyState>();
-        assert!(state.is_some());
-        assert_eq!(state.unwrap().value, 42);
+        handlers1.merge(handlers2);
+
+        // Only one handler for TestError after merge
+        assert_eq!(handlers1.len(), 1);
+
+        let ctx = test_context();
+        let err = TestError {
+            message: "test".into(),
+            code: 1,
+        };
+
+        // Merged handlers should override
+        let response = handlers1.handle(&ctx, err);
+        assert!(response.is_some());
+        assert_eq!(response.unwrap().status().as_u16(), 422);
     }
 
     #[test]
-    fn state_container_multiple_types() {
-        struct TypeA(i32);
-        struct TypeB(String);
+    fn exception_handlers_override_default_http_error() {
+        // Start with default handlers
+        let mut handlers = ExceptionHandlers::with_defaults();
 
-        let mut container = StateContainer::new();
-        container.insert(TypeA(1));
-        container.insert(TypeB("hello".to_string()));
+        // Override HttpError handler
+        handlers.register::<crate::HttpError>(|_ctx, err| {
+            // Custom handler that adds extra header
+            let detail = err.detail.as_deref().unwrap_or("Unknown error");
+            Response::with_status(err.status)
+                .header("x-custom-error", b"true".to_vec())
+                .body(ResponseBody::Bytes(detail.as_bytes().to_vec()))
+        });
 
-        assert!(container.contains::<TypeA>());
-        assert!(container.contains::<TypeB>());
-        assert!(!container.contains::<i64>());
+        // Still has 2 handlers (HttpError and ValidationErrors)
+        assert_eq!(handlers.len(), 2);
 
-        assert_eq!(container.get::<TypeA>().unwrap().0, 1);
-        assert_eq!(container.get::<TypeB>().unwrap().0, "hello");
+        let ctx = test_context();
+        let err = crate::HttpError::bad_request().with_detail("test error");
+
+        let response = handlers.handle(&ctx, err);
+        assert!(response.is_some());
+
+        let response = response.unwrap();
+        assert_eq!(response.status().as_u16(), 400);
+
+        // Check custom header was added
+        let custom_header = response
+            .headers()
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("x-custom-error"))
+            .map(|(_, v)| v.as_slice());
+        assert_eq!(custom_header, Some(b"true".as_slice()));
     }
 
     #[test]
-    fn app_builder_with_state() {
-        struct DbPool {
-            connection_count: usize,
-        }
+    fn exception_handlers_override_default_validation_errors() {
+        // Start with default handlers
+        let mut handlers = ExceptionHandlers::with_defaults();
+
+        // Override ValidationErrors handler
+        handlers.register::<crate::ValidationErrors>(|_ctx, errs| {
+            // Custom handler that returns 400 instead of 422
+            Response::with_status(StatusCode::BAD_REQUEST)
+                .header("x-error-count", errs.len().to_string().as_bytes().to_vec())
+        });
+
+        let ctx = test_context();
+        let mut errs = crate::ValidationErrors::new();
+        errs.push(crate::ValidationError::missing(
+            crate::error::loc::body_field("name"),
+        ));
+        errs.push(crate::ValidationError::missing(
+            crate::error::loc::body_field("email"),
+        ));
+
+        let response = handlers.handle(&ctx, errs);
+        assert!(response.is_some());
+
+        let response = response.unwrap();
+        // Custom handler returns 400 instead of 422
+        assert_eq!(response.status().as_u16(), 400);
+
+        // Check custom header
+        let count_header = response
+            .headers()
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("x-error-count"))
+            .map(|(_, v)| v.as_slice());
+        assert_eq!(count_header, Some(b"2".as_slice()));
+    }
+
+    #[test]
+    fn exception_handlers_debug_format() {
+        let handlers = ExceptionHandlers::new()
+            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST));
+
+        let debug = format!("{:?}", handlers);
+        assert!(debug.contains("ExceptionHandlers"));
+        assert!(debug.contains("count"));
+        assert!(debug.contains("1"));
+    }
 
+    #[test]
+    fn app_debug_includes_exception_handlers() {
         let app = App::builder()
-            .state(DbPool {
-                connection_count: 10,
+            .exception_handler::<TestError, _>(|_ctx, _err| {
+                Response::with_status(StatusCode::BAD_REQUEST)
             })
             .get("/", test_handler)
             .build();
 
-        let pool = app.get_state::<DbPool>();
-        assert!(pool.is_some());
-        assert_eq!(pool.unwrap().connection_count, 10);
+        let debug = format!("{:?}", app);
+        assert!(debug.contains("exception_handlers"));
     }
 
     #[test]
-    fn app_handles_get_request() {
-        let app = App::builder().get("/", test_handler).build();
-
-        let ctx = test_context();
-        let mut req = Request::new(Method::Get, "/");
+    fn app_builder_debug_includes_exception_handlers() {
+        let builder = App::builder().exception_handler::<TestError, _>(|_ctx, _err| {
+            Response::with_status(StatusCode::BAD_REQUEST)
+        });
 
-        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
-        assert_eq!(response.status().as_u16(), 200);
+        let debug = format!("{:?}", builder);
+        assert!(debug.contains("exception_handlers"));
     }
 
-    #[test]
-    fn app_returns_404_for_unknown_path() {
-        let app = App::builder().get("/", test_handler).build();
+    // =========================================================================
+    // Lifecycle Hooks Tests
+    // =========================================================================
 
-        let ctx = test_context();
-        let mut req = Request::new(Method::Get, "/unknown");
+    // --- Startup Hooks: Registration ---
 
-        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
-        assert_eq!(response.status().as_u16(), 404);
+    #[test]
+    fn app_builder_startup_hook_registration() {
+        let builder = App::builder().on_startup(|| Ok(())).on_startup(|| Ok(()));
+
+        assert_eq!(builder.startup_hook_count(), 2);
     }
 
     #[test]
-    fn app_returns_405_for_wrong_method() {
-        let app = App::builder().get("/", test_handler).build();
+    fn app_builder_shutdown_hook_registration() {
+        let builder = App::builder().on_shutdown(|| {}).on_shutdown(|| {});
 
-        let ctx = test_context();
-        let mut req = Request::new(Method::Post, "/");
+        assert_eq!(builder.shutdown_hook_count(), 2);
+    }
+
+    #[test]
+    fn app_builder_mixed_hooks() {
+        let builder = App::builder()
+            .on_startup(|| Ok(()))
+            .on_shutdown(|| {})
+            .on_startup(|| Ok(()))
+            .on_shutdown(|| {});
 
-        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
-        assert_eq!(response.status().as_u16(), 405);
+        assert_eq!(builder.startup_hook_count(), 2);
+        assert_eq!(builder.shutdown_hook_count(), 2);
     }
 
     #[test]
-    fn app_builder_all_methods() {
+    fn app_pending_hooks_count() {
         let app = App::builder()
-            .get("/get", test_handler)
-            .post("/post", test_handler)
-            .put("/put", test_handler)
-            .delete("/delete", test_handler)
-            .patch("/patch", test_handler)
+            .on_startup(|| Ok(()))
+            .on_startup(|| Ok(()))
+            .on_shutdown(|| {})
+            .get("/", test_handler)
             .build();
 
-        assert_eq!(app.route_count(), 5);
+        assert_eq!(app.pending_startup_hooks(), 2);
+        assert_eq!(app.pending_shutdown_hooks(), 1);
     }
 
     #[test]
-    fn route_entry_debug() {
-        let entry = RouteEntry::new(Method::Get, "/test", test_handler);
-        let debug = format!("{:?}", entry);
-        assert!(debug.contains("RouteEntry"));
-        assert!(debug.contains("Get"));
-        assert!(debug.contains("/test"));
+    fn lifespan_state_and_reverse_teardown() {
+        struct Pool(u32);
+        struct Cache(u32);
+
+        let log = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let (log1, log2, log3) = (Arc::clone(&log), Arc::clone(&log), Arc::clone(&log));
+        let app = App::builder()
+            .lifespan(
+                |_state| async { Ok(Pool(5)) },
+                move |pool: Arc<Pool>| async move { log1.lock().push(format!("pool {}", pool.0)) },
+            )
+            .lifespan(
+                |state: Arc<StateContainer>| async move {
+                    let pool = state.get::<Pool>().expect("pool is set up first");
+                    Ok(Cache(pool.0 * 2))
+                },
+                move |cache: Arc<Cache>| async move {
+                    log2.lock().push(format!("cache {}", cache.0));
+                },
+            )
+            .on_shutdown(move || log3.lock().push("hook".to_string()))
+            .get("/", test_handler)
+            .build();
+
+        assert!(app.get_state::<Pool>().is_none());
+        let outcome = futures_executor::block_on(app.run_startup_hooks());
+        assert!(matches!(outcome, StartupOutcome::Success));
+        assert_eq!(app.get_state::<Cache>().unwrap().0, 10);
+
+        futures_executor::block_on(app.run_shutdown_hooks());
+        assert_eq!(*log.lock(), ["hook", "cache 10", "pool 5"]);
+        assert!(app.get_state::<Pool>().is_none());
     }
 
     #[test]
-    fn app_with_middleware() {
-        use crate::middleware::NoopMiddleware;
+    fn lifespan_teardown_runs_when_startup_aborts() {
+        use std::sync::atomic::{AtomicBool, Ordering};
 
+        let torn_down = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&torn_down);
         let app = App::builder()
-            .middleware(NoopMiddleware)
-            .middleware(NoopMiddleware)
+            .lifespan(
+                |_state| async { Ok(1u8) },
+                move |_value: Arc<u8>| async move { flag.store(true, Ordering::SeqCst) },
+            )
+            .on_startup(|| Err(StartupHookError::new("migrations failed")))
             .get("/", test_handler)
             .build();
 
-        let ctx = test_context();
-        let mut req = Request::new(Method::Get, "/");
-
-        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
-        assert_eq!(response.status().as_u16(), 200);
+        let outcome = futures_executor::block_on(app.run_startup_hooks());
+        assert!(matches!(outcome, StartupOutcome::Aborted(_)));
+        assert!(torn_down.load(Ordering::SeqCst));
     }
 
-    // =========================================================================
-    // Exception Handlers Tests
-    // =========================================================================
+    #[test]
+    fn scheduled_jobs_register_lifecycle_hooks() {
+        use crate::scheduler::{Schedule, Scheduler};
 
-    // Custom error type for testing
-    #[derive(Debug)]
-    struct TestError {
-        message: String,
-        code: u32,
-    }
+        let shared = Scheduler::new();
+        let app = App::builder()
+            .schedule(
+                "early",
+                Schedule::every(std::time::Duration::from_secs(60)),
+                || async { Ok::<(), String>(()) },
+            )
+            .scheduler(shared.clone())
+            .schedule("nightly", Schedule::cron("@daily").unwrap(), || async {
+                Ok::<(), String>(())
+            })
+            .get("/", test_handler)
+            .build();
 
-    impl std::fmt::Display for TestError {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            write!(f, "TestError({}): {}", self.code, self.message)
-        }
+        assert_eq!(app.pending_startup_hooks(), 1);
+        assert_eq!(app.pending_shutdown_hooks(), 1);
+        let names: Vec<String> = shared.statuses().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["early", "nightly"]);
+        assert_eq!(app.scheduler().len(), 2);
+
+        // Without a runtime to spawn onto, the jobs cannot start.
+        let outcome = futures_executor::block_on(app.run_startup_hooks());
+        assert!(matches!(outcome, StartupOutcome::Aborted(_)));
     }
 
-    impl std::error::Error for TestError {}
+    // --- Startup Hooks: Execution Order (FIFO) ---
 
-    // Another custom error type
-    #[derive(Debug)]
-    struct AnotherError(String);
+    #[test]
+    fn startup_hooks_run_in_fifo_order() {
+        let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
 
-    impl std::fmt::Display for AnotherError {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            write!(f, "AnotherError: {}", self.0)
-        }
-    }
+        let order1 = Arc::clone(&order);
+        let order2 = Arc::clone(&order);
+        let order3 = Arc::clone(&order);
 
-    impl std::error::Error for AnotherError {}
+        let app = App::builder()
+            .on_startup(move || {
+                order1.lock().push(1);
+                Ok(())
+            })
+            .on_startup(move || {
+                order2.lock().push(2);
+                Ok(())
+            })
+            .on_startup(move || {
+                order3.lock().push(3);
+                Ok(())
+            })
+            .get("/", test_handler)
+            .build();
 
-    // --- Unit Tests: Handler Registration ---
+        let outcome = futures_executor::block_on(app.run_startup_hooks());
+        assert!(outcome.can_proceed());
 
-    #[test]
-    fn exception_handlers_new_is_empty() {
-        let handlers = ExceptionHandlers::new();
-        assert!(handlers.is_empty());
-        assert_eq!(handlers.len(), 0);
+        // FIFO: 1, 2, 3
+        assert_eq!(*order.lock(), vec![1, 2, 3]);
+
+        // Hooks consumed
+        assert_eq!(app.pending_startup_hooks(), 0);
     }
 
+    // --- Shutdown Hooks: Execution Order (LIFO) ---
+
     #[test]
-    fn exception_handlers_register_single() {
-        let mut handlers = ExceptionHandlers::new();
-        handlers.register::<TestError>(|_ctx, err| {
-            Response::with_status(StatusCode::BAD_REQUEST)
-                .body(ResponseBody::Bytes(err.message.as_bytes().to_vec()))
-        });
+    fn shutdown_hooks_run_in_lifo_order() {
+        let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
 
-        assert!(handlers.has_handler::<TestError>());
-        assert!(!handlers.has_handler::<AnotherError>());
-        assert_eq!(handlers.len(), 1);
-    }
+        let order1 = Arc::clone(&order);
+        let order2 = Arc::clone(&order);
+        let order3 = Arc::clone(&order);
 
-    #[test]
-    fn exception_handlers_register_multiple() {
-        let mut handlers = ExceptionHandlers::new();
-        handlers.register::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST));
-        handlers.register::<AnotherError>(|_ctx, _err| {
-            Response::with_status(StatusCode::INTERNAL_SERVER_ERROR)
-        });
+        let app = App::builder()
+            .on_shutdown(move || {
+                order1.lock().push(1);
+            })
+            .on_shutdown(move || {
+                order2.lock().push(2);
+            })
+            .on_shutdown(move || {
+                order3.lock().push(3);
+            })
+            .get("/", test_handler)
+            .build();
 
-        assert!(handlers.has_handler::<TestError>());
-        assert!(handlers.has_handler::<AnotherError>());
-        assert_eq!(handlers.len(), 2);
-    }
+        futures_executor::block_on(app.run_shutdown_hooks());
 
-    #[test]
-    fn exception_handlers_builder_pattern() {
-        let handlers = ExceptionHandlers::new()
-            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST))
-            .handler::<AnotherError>(|_ctx, _err| {
-                Response::with_status(StatusCode::INTERNAL_SERVER_ERROR)
-            });
+        // LIFO: 3, 2, 1
+        assert_eq!(*order.lock(), vec![3, 2, 1]);
 
-        assert!(handlers.has_handler::<TestError>());
-        assert!(handlers.has_handler::<AnotherError>());
-        assert_eq!(handlers.len(), 2);
+        // Hooks consumed
+        assert_eq!(app.pending_shutdown_hooks(), 0);
     }
 
+    // --- Startup Hooks: Success Outcome ---
+
     #[test]
-    fn exception_handlers_with_defaults() {
-        let handlers = ExceptionHandlers::with_defaults();
+    fn startup_hooks_success_outcome() {
+        let app = App::builder()
+            .on_startup(|| Ok(()))
+            .on_startup(|| Ok(()))
+            .get("/", test_handler)
+            .build();
 
-        assert!(handlers.has_handler::<crate::HttpError>());
-        assert!(handlers.has_handler::<crate::ValidationErrors>());
-        assert_eq!(handlers.len(), 2);
+        let outcome = futures_executor::block_on(app.run_startup_hooks());
+        assert!(matches!(outcome, StartupOutcome::Success));
+        assert!(outcome.can_proceed());
     }
 
-    #[test]
-    fn exception_handlers_merge() {
-        let mut handlers1 = ExceptionHandlers::new()
-            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST));
+    // --- Startup Hooks: Fatal Error Aborts ---
 
-        let handlers2 = ExceptionHandlers::new().handler::<AnotherError>(|_ctx, _err| {
-            Response::with_status(StatusCode::INTERNAL_SERVER_ERROR)
-        });
+    #[test]
+    fn startup_hooks_fatal_error_aborts() {
+        let app = App::builder()
+            .on_startup(|| Ok(()))
+            .on_startup(|| Err(StartupHookError::new("database connection failed")))
+            .on_startup(|| Ok(())) // Should not run
+            .get("/", test_handler)
+            .build();
 
-        handlers1.merge(handlers2);
+        let outcome = futures_executor::block_on(app.run_startup_hooks());
+        assert!(!outcome.can_proceed());
 
-        assert!(handlers1.has_handler::<TestError>());
-        assert!(handlers1.has_handler::<AnotherError>());
-        assert_eq!(handlers1.len(), 2);
+        if let StartupOutcome::Aborted(err) = outcome {
+            assert!(err.message.contains("database connection failed"));
+            assert!(err.abort);
+        } else {
+            panic!("Expected Aborted outcome");
+        }
     }
 
-    // --- Unit Tests: Handler Invocation ---
+    // --- Startup Hooks: Non-Fatal Error Continues ---
 
     #[test]
-    fn exception_handlers_handle_registered_error() {
-        let handlers = ExceptionHandlers::new().handler::<TestError>(|_ctx, err| {
-            Response::with_status(StatusCode::BAD_REQUEST)
-                .body(ResponseBody::Bytes(err.message.as_bytes().to_vec()))
-        });
-
-        let ctx = test_context();
-        let err = TestError {
-            message: "test error".into(),
-            code: 42,
-        };
+    fn startup_hooks_non_fatal_error_continues() {
+        let app = App::builder()
+            .on_startup(|| Ok(()))
+            .on_startup(|| Err(StartupHookError::non_fatal("optional feature unavailable")))
+            .on_startup(|| Ok(())) // Should still run
+            .get("/", test_handler)
+            .build();
 
-        let response = handlers.handle(&ctx, err);
-        assert!(response.is_some());
+        let outcome = futures_executor::block_on(app.run_startup_hooks());
+        assert!(outcome.can_proceed());
 
-        let response = response.unwrap();
-        assert_eq!(response.status().as_u16(), 400);
+        if let StartupOutcome::PartialSuccess { warnings } = outcome {
+            assert_eq!(warnings, 1);
+        } else {
+            panic!("Expected PartialSuccess outcome");
+        }
     }
 
-    #[test]
-    fn exception_handlers_handle_unregistered_error() {
-        let handlers = ExceptionHandlers::new()
-            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST));
+    // --- Startup Hook Error Types ---
 
-        let ctx = test_context();
-        let err = AnotherError("unhandled".into());
+    #[test]
+    fn startup_hook_error_builder() {
+        let err = StartupHookError::new("test error")
+            .with_hook_name("database_init")
+            .with_abort(false);
 
-        let response = handlers.handle(&ctx, err);
-        assert!(response.is_none());
+        assert_eq!(err.hook_name.as_deref(), Some("database_init"));
+        assert_eq!(err.message, "test error");
+        assert!(!err.abort);
     }
 
     #[test]
-    fn exception_handlers_handle_or_default_registered() {
-        let handlers = ExceptionHandlers::new()
-            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST));
+    fn startup_hook_error_display() {
+        let err = StartupHookError::new("connection failed").with_hook_name("redis_init");
 
-        let ctx = test_context();
-        let err = TestError {
-            message: "test".into(),
-            code: 1,
-        };
+        let display = format!("{}", err);
+        assert!(display.contains("redis_init"));
+        assert!(display.contains("connection failed"));
+    }
 
-        let response = handlers.handle_or_default(&ctx, err);
-        assert_eq!(response.status().as_u16(), 400);
+    #[test]
+    fn startup_hook_error_non_fatal() {
+        let err = StartupHookError::non_fatal("optional feature");
+        assert!(!err.abort);
     }
 
+    // --- Transfer Shutdown Hooks to Controller ---
+
     #[test]
-    fn exception_handlers_handle_or_default_unregistered() {
-        let handlers = ExceptionHandlers::new();
+    fn transfer_shutdown_hooks_to_controller() {
+        let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
 
-        let ctx = test_context();
-        let err = TestError {
-            message: "test".into(),
-            code: 1,
-        };
+        let order1 = Arc::clone(&order);
+        let order2 = Arc::clone(&order);
 
-        let response = handlers.handle_or_default(&ctx, err);
-        assert_eq!(response.status().as_u16(), 500);
-    }
+        let app = App::builder()
+            .on_shutdown(move || {
+                order1.lock().push(1);
+            })
+            .on_shutdown(move || {
+                order2.lock().push(2);
+            })
+            .get("/", test_handler)
+            .build();
 
-    #[test]
-    fn exception_handlers_error_values_passed_to_handler() {
-        use std::sync::atomic::{AtomicU32, Ordering};
+        let controller = ShutdownController::new();
+        app.transfer_shutdown_hooks(&controller);
 
-        let captured_code = Arc::new(AtomicU32::new(0));
-        let captured_code_clone = captured_code.clone();
+        // App hooks consumed
+        assert_eq!(app.pending_shutdown_hooks(), 0);
 
-        let handlers = ExceptionHandlers::new().handler::<TestError>(move |_ctx, err| {
-            captured_code_clone.store(err.code, Ordering::SeqCst);
-            Response::with_status(StatusCode::BAD_REQUEST)
-        });
+        // Controller has the hooks
+        assert_eq!(controller.hook_count(), 2);
 
-        let ctx = test_context();
-        let err = TestError {
-            message: "test".into(),
-            code: 12345,
-        };
+        // Run via controller (LIFO order)
+        while let Some(hook) = controller.pop_hook() {
+            hook.run();
+        }
 
-        let _ = handlers.handle(&ctx, err);
-        assert_eq!(captured_code.load(Ordering::SeqCst), 12345);
+        // LIFO order via controller
+        assert_eq!(*order.lock(), vec![2, 1]);
     }
 
-    // --- Integration Tests: Custom Error Type Handling ---
+    // --- Debug Format Includes Hooks ---
 
     #[test]
-    fn app_builder_exception_handler_single() {
+    fn app_debug_includes_hooks() {
         let app = App::builder()
-            .exception_handler::<TestError, _>(|_ctx, err| {
-                Response::with_status(StatusCode::BAD_REQUEST)
-                    .body(ResponseBody::Bytes(err.message.as_bytes().to_vec()))
-            })
+            .on_startup(|| Ok(()))
+            .on_shutdown(|| {})
             .get("/", test_handler)
             .build();
 
-        assert!(app.exception_handlers().has_handler::<TestError>());
+        let debug = format!("{:?}", app);
+        assert!(debug.contains("startup_hooks"));
+        assert!(debug.contains("shutdown_hooks"));
     }
 
     #[test]
-    fn app_builder_exception_handler_multiple() {
-        let app = App::builder()
-            .exception_handler::<TestError, _>(|_ctx, _err| {
-                Response::with_status(StatusCode::BAD_REQUEST)
-            })
-            .exception_handler::<AnotherError, _>(|_ctx, _err| {
-                Response::with_status(StatusCode::INTERNAL_SERVER_ERROR)
-            })
-            .get("/", test_handler)
-            .build();
+    fn app_builder_debug_includes_hooks() {
+        let builder = App::builder().on_startup(|| Ok(())).on_shutdown(|| {});
 
-        assert!(app.exception_handlers().has_handler::<TestError>());
-        assert!(app.exception_handlers().has_handler::<AnotherError>());
+        let debug = format!("{:?}", builder);
+        assert!(debug.contains("startup_hooks"));
+        assert!(debug.contains("shutdown_hooks"));
     }
 
-    #[test]
-    fn app_builder_with_default_exception_handlers() {
-        let app = App::builder()
-            .with_default_exception_handlers()
-            .get("/", test_handler)
-            .build();
+    // --- Startup Outcome Accessors ---
 
-        assert!(app.exception_handlers().has_handler::<crate::HttpError>());
-        assert!(
-            app.exception_handlers()
-                .has_handler::<crate::ValidationErrors>()
-        );
+    #[test]
+    fn startup_outcome_success() {
+        let outcome = StartupOutcome::Success;
+        assert!(outcome.can_proceed());
+        assert!(outcome.into_error().is_none());
     }
 
     #[test]
-    fn app_builder_exception_handlers_registry() {
-        let handlers = ExceptionHandlers::new()
-            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST))
-            .handler::<AnotherError>(|_ctx, _err| {
-                Response::with_status(StatusCode::INTERNAL_SERVER_ERROR)
-            });
+    fn startup_outcome_partial_success() {
+        let outcome = StartupOutcome::PartialSuccess { warnings: 2 };
+        assert!(outcome.can_proceed());
+        assert!(outcome.into_error().is_none());
+    }
 
-        let app = App::builder()
-            .exception_handlers(handlers)
-            .get("/", test_handler)
-            .build();
+    #[test]
+    fn startup_outcome_aborted() {
+        let err = StartupHookError::new("fatal");
+        let outcome = StartupOutcome::Aborted(err);
+        assert!(!outcome.can_proceed());
 
-        assert!(app.exception_handlers().has_handler::<TestError>());
-        assert!(app.exception_handlers().has_handler::<AnotherError>());
+        let err = outcome.into_error();
+        assert!(err.is_some());
+        assert_eq!(err.unwrap().message, "fatal");
     }
 
+    // --- Multiple Non-Fatal Errors ---
+
     #[test]
-    fn app_handle_error_registered() {
+    fn startup_hooks_multiple_non_fatal_errors() {
         let app = App::builder()
-            .exception_handler::<TestError, _>(|_ctx, _err| {
-                Response::with_status(StatusCode::BAD_REQUEST)
-            })
+            .on_startup(|| Err(StartupHookError::non_fatal("warning 1")))
+            .on_startup(|| Ok(()))
+            .on_startup(|| Err(StartupHookError::non_fatal("warning 2")))
+            .on_startup(|| Err(StartupHookError::non_fatal("warning 3")))
             .get("/", test_handler)
             .build();
 
-        let ctx = test_context();
-        let err = TestError {
-            message: "test".into(),
-            code: 1,
-        };
+        let outcome = futures_executor::block_on(app.run_startup_hooks());
+        assert!(outcome.can_proceed());
 
-        let response = app.handle_error(&ctx, err);
-        assert!(response.is_some());
-        assert_eq!(response.unwrap().status().as_u16(), 400);
+        if let StartupOutcome::PartialSuccess { warnings } = outcome {
+            assert_eq!(warnings, 3);
+        } else {
+            panic!("Expected PartialSuccess");
+        }
     }
 
+    // --- Empty Hooks ---
+
     #[test]
-    fn app_handle_error_unregistered() {
+    fn empty_startup_hooks() {
         let app = App::builder().get("/", test_handler).build();
 
-        let ctx = test_context();
-        let err = TestError {
-            message: "test".into(),
-            code: 1,
-        };
-
-        let response = app.handle_error(&ctx, err);
-        assert!(response.is_none());
+        let outcome = futures_executor::block_on(app.run_startup_hooks());
+        assert!(matches!(outcome, StartupOutcome::Success));
     }
 
     #[test]
-    fn app_handle_error_or_default() {
+    fn empty_shutdown_hooks() {
         let app = App::builder().get("/", test_handler).build();
 
-        let ctx = test_context();
-        let err = TestError {
-            message: "test".into(),
-            code: 1,
-        };
-
-        let response = app.handle_error_or_default(&ctx, err);
-        assert_eq!(response.status().as_u16(), 500);
+        // Should not panic with empty hooks
+        futures_executor::block_on(app.run_shutdown_hooks());
     }
 
-    // --- Integration Tests: Override Default Handler ---
+    // --- Hooks Can Only Run Once ---
 
     #[test]
-    fn exception_handlers_override_on_register() {
-        let handlers = ExceptionHandlers::new()
-            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST))
-            .handler::<TestError>(|_ctx, _err| {
-                Response::with_status(StatusCode::UNPROCESSABLE_ENTITY)
-            });
+    fn startup_hooks_consumed_after_run() {
+        let counter = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter_clone = Arc::clone(&counter);
 
-        // Only one handler for TestError
-        assert_eq!(handlers.len(), 1);
+        let app = App::builder()
+            .on_startup(move || {
+                counter_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(())
+            })
+            .get("/", test_handler)
+            .build();
 
-        let ctx = test_context();
-        let err = TestError {
-            message: "test".into(),
-            code: 1,
-        };
+        // First run
+        futures_executor::block_on(app.run_startup_hooks());
+        assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
 
-        // Should use the second (overriding) handler
-        let response = handlers.handle(&ctx, err);
-        assert!(response.is_some());
-        assert_eq!(response.unwrap().status().as_u16(), 422);
+        // Second run - no hooks left
+        futures_executor::block_on(app.run_startup_hooks());
+        assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn shutdown_hooks_consumed_after_run() {
+        let counter = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter_clone = Arc::clone(&counter);
+
+        let app = App::builder()
+            .on_shutdown(move || {
+                counter_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            })
+            .get("/", test_handler)
+            .build();
+
+        // First run
+        futures_executor::block_on(app.run_shutdown_hooks());
+        assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // Second run - no hooks left
+        futures_executor::block_on(app.run_shutdown_hooks());
+        assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
     }
 
     #[test]
-    fn exception_handlers_merge_overrides() {
-        let mut handlers1 = ExceptionHandlers::new()
-            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST));
-
-        let handlers2 = ExceptionHandlers::new().handler::<TestError>(|_ctx, _err| {
-            Response::with_status(StatusCode::UNPROCESSABLE_ENTITY)
-        });
-
-        handlers1.merge(handlers2);
+    fn root_path_strips_trailing_slashes() {
+        let config = AppConfig::new().root_path("/api/");
+        assert_eq!(config.root_path, "/api");
 
-        // Only one handler for TestError after merge
-        assert_eq!(handlers1.len(), 1);
+        let config = AppConfig::new().root_path("/api///");
+        assert_eq!(config.root_path, "/api");
 
-        let ctx = test_context();
-        let err = TestError {
-            message: "test".into(),
-            code: 1,
-        };
+        let config = AppConfig::new().root_path("/api");
+        assert_eq!(config.root_path, "/api");
 
-        // Merged handlers should override
-        let response = handlers1.handle(&ctx, err);
-        assert!(response.is_some());
-        assert_eq!(response.unwrap().status().as_u16(), 422);
+        // Empty root_path stays empty
+        let config = AppConfig::new().root_path("");
+        assert_eq!(config.root_path, "");
     }
 
     #[test]
-    fn exception_handlers_override_default_http_error() {
-        // Start with default handlers
-        let mut handlers = ExceptionHandlers::with_defaults();
-
-        // Override HttpError handler
-        handlers.register::<crate::HttpError>(|_ctx, err| {
-            // Custom handler that adds extra header
-            let detail = err.detail.as_deref().unwrap_or("Unknown error");
-            Response::with_status(err.status)
-                .header("x-custom-error", b"true".to_vec())
-                .body(ResponseBody::Bytes(detail.as_bytes().to_vec()))
-        });
+    fn reload_swaps_snapshot_for_new_requests() {
+        let app = App::builder().get("/", test_handler).state(42u32).build();
+        assert_eq!(app.snapshot_generation(), 0);
 
-        // Still has 2 handlers (HttpError and ValidationErrors)
-        assert_eq!(handlers.len(), 2);
+        let before = app.snapshot();
+        let previous = app
+            .reload(App::builder().get("/health", health_handler))
+            .unwrap();
+        assert!(Arc::ptr_eq(&before, &previous));
+        assert_eq!(app.snapshot_generation(), 1);
 
         let ctx = test_context();
-        let err = crate::HttpError::bad_request().with_detail("test error");
-
-        let response = handlers.handle(&ctx, err);
-        assert!(response.is_some());
+        let mut req = Request::new(Method::Get, "/health");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 200);
 
-        let response = response.unwrap();
-        assert_eq!(response.status().as_u16(), 400);
+        let mut req = Request::new(Method::Get, "/");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 404);
 
-        // Check custom header was added
-        let custom_header = response
-            .headers()
-            .iter()
-            .find(|(name, _)| name.eq_ignore_ascii_case("x-custom-error"))
-            .map(|(_, v)| v.as_slice());
-        assert_eq!(custom_header, Some(b"true".as_slice()));
+        // State from the running app survives the reload.
+        assert_eq!(app.get_state::<u32>().as_deref(), Some(&42));
     }
 
     #[test]
-    fn exception_handlers_override_default_validation_errors() {
-        // Start with default handlers
-        let mut handlers = ExceptionHandlers::with_defaults();
+    fn reload_builds_against_running_app_state() {
+        let app = App::builder().state(42u32).build();
+        app.reload(App::builder().route_entry(
+            RouteEntry::new(Method::Get, "/count", test_handler).requires_state::<u32>(),
+        ))
+        .unwrap();
+        assert!(app.snapshot().dependency_graph().is_ok());
+        let report = app.check();
+        assert!(report.issues.iter().all(|issue| issue.category != "state"));
+    }
 
-        // Override ValidationErrors handler
-        handlers.register::<crate::ValidationErrors>(|_ctx, errs| {
-            // Custom handler that returns 400 instead of 422
-            Response::with_status(StatusCode::BAD_REQUEST)
-                .header("x-error-count", errs.len().to_string().as_bytes().to_vec())
-        });
+    #[test]
+    fn reload_rejects_app_level_parts() {
+        let app = App::builder().get("/", test_handler).build();
+        let err = app
+            .reload(
+                App::builder()
+                    .get("/health", health_handler)
+                    .state(1u8)
+                    .on_shutdown(|| {})
+                    .exception_handler::<TestError, _>(|_ctx, _err| {
+                        Response::with_status(StatusCode::BAD_REQUEST)
+                    }),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.rejected,
+            ["state", "shutdown hooks", "exception handlers"]
+        );
+        assert_eq!(app.snapshot_generation(), 0);
+        assert_eq!(app.routes().next(), Some((Method::Get, "/".to_string())));
+    }
 
+    fn served_spec(app: &App, path: &str) -> serde_json::Value {
         let ctx = test_context();
-        let mut errs = crate::ValidationErrors::new();
-        errs.push(crate::ValidationError::missing(
-            crate::error::loc::body_field("name"),
-        ));
-        errs.push(crate::ValidationError::missing(
-            crate::error::loc::body_field("email"),
-        ));
-
-        let response = handlers.handle(&ctx, errs);
-        assert!(response.is_some());
+        let mut req = Request::new(Method::Get, path);
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 200);
+        serde_json::from_str(&body_text(&response)).unwrap()
+    }
 
-        let response = response.unwrap();
-        // Custom handler returns 400 instead of 422
-        assert_eq!(response.status().as_u16(), 400);
+    #[test]
+    fn openapi_route_serves_spec_with_app_metadata() {
+        let app = App::builder()
+            .config(AppConfig::new().name("Pets").version("2.0.0"))
+            .get("/pets", test_handler)
+            .openapi_route("/spec.json")
+            .build();
 
-        // Check custom header
-        let count_header = response
-            .headers()
-            .iter()
-            .find(|(name, _)| name.eq_ignore_ascii_case("x-error-count"))
-            .map(|(_, v)| v.as_slice());
-        assert_eq!(count_header, Some(b"2".as_slice()));
+        let spec = served_spec(&app, "/spec.json");
+        assert_eq!(spec["info"]["title"], "Pets");
+        assert_eq!(spec["info"]["version"], "2.0.0");
+        assert!(spec["paths"]["/pets"]["get"].is_object());
+        assert!(spec["paths"].get("/spec.json").is_none());
     }
 
     #[test]
-    fn exception_handlers_debug_format() {
-        let handlers = ExceptionHandlers::new()
-            .handler::<TestError>(|_ctx, _err| Response::with_status(StatusCode::BAD_REQUEST));
+    fn openapi_route_moves_existing_config() {
+        let app = App::builder()
+            .openapi(OpenApiConfig::new().title("Custom"))
+            .openapi_route("/v1/openapi.json")
+            .build();
 
-        let debug = format!("{:?}", handlers);
-        assert!(debug.contains("ExceptionHandlers"));
-        assert!(debug.contains("count"));
-        assert!(debug.contains("1"));
+        let spec = served_spec(&app, "/v1/openapi.json");
+        assert_eq!(spec["info"]["title"], "Custom");
+
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/openapi.json");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 404);
     }
 
     #[test]
-    fn app_debug_includes_exception_handlers() {
+    fn reload_regenerates_served_spec() {
         let app = App::builder()
-            .exception_handler::<TestError, _>(|_ctx, _err| {
-                Response::with_status(StatusCode::BAD_REQUEST)
-            })
             .get("/", test_handler)
+            .openapi_route("/openapi.json")
             .build();
+        let before = app.openapi_spec().unwrap();
 
-        let debug = format!("{:?}", app);
-        assert!(debug.contains("exception_handlers"));
+        app.reload(App::builder().get("/health", health_handler))
+            .unwrap();
+
+        let spec = served_spec(&app, "/openapi.json");
+        assert!(spec["paths"]["/health"]["get"].is_object());
+        assert!(spec["paths"].get("/").is_none());
+        // A spec taken before the reload still reads the old document.
+        assert!(before.contains("\"/\""));
+        assert!(!Arc::ptr_eq(&before, &app.openapi_spec().unwrap()));
     }
 
     #[test]
-    fn app_builder_debug_includes_exception_handlers() {
-        let builder = App::builder().exception_handler::<TestError, _>(|_ctx, _err| {
-            Response::with_status(StatusCode::BAD_REQUEST)
-        });
-
-        let debug = format!("{:?}", builder);
-        assert!(debug.contains("exception_handlers"));
-    }
+    fn serve_embedded_mounts_assets_outside_spec() {
+        static FILES: &[crate::embed::EmbeddedFile] = &[
+            crate::embed::EmbeddedFile::new("index.html", b"<h1>home</h1>"),
+            crate::embed::EmbeddedFile::new("css/app.css", b"body {}"),
+        ];
+        let app = App::builder()
+            .serve_embedded("/static/", crate::embed::EmbeddedDir::new(FILES))
+            .openapi_route("/openapi.json")
+            .build();
 
-    // =========================================================================
-    // Lifecycle Hooks Tests
-    // =========================================================================
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/static/css/app.css");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(body_text(&response), "body {}");
 
-    // --- Startup Hooks: Registration ---
+        let mut req = Request::new(Method::Get, "/static");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(body_text(&response), "<h1>home</h1>");
 
-    #[test]
-    fn app_builder_startup_hook_registration() {
-        let builder = App::builder().on_startup(|| Ok(())).on_startup(|| Ok(()));
+        let mut req = Request::new(Method::Get, "/static/missing.js");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 404);
 
-        assert_eq!(builder.startup_hook_count(), 2);
+        let spec = served_spec(&app, "/openapi.json");
+        assert!(
+            spec.get("paths")
+                .is_none_or(|paths| paths.as_object().unwrap().is_empty())
+        );
     }
 
     #[test]
-    fn app_builder_shutdown_hook_registration() {
-        let builder = App::builder().on_shutdown(|| {}).on_shutdown(|| {});
+    fn metrics_endpoint_serves_registry_outside_spec() {
+        let registry = crate::metrics::MetricsRegistry::new();
+        let app = App::builder()
+            .middleware(crate::metrics::MetricsMiddleware::new(&registry))
+            .get("/ping", |_ctx: &RequestContext, _req: &mut Request| async {
+                Response::ok()
+            })
+            .metrics("/metrics", registry)
+            .openapi_route("/openapi.json")
+            .build();
 
-        assert_eq!(builder.shutdown_hook_count(), 2);
-    }
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/ping");
+        let _ = futures_executor::block_on(app.handle(&ctx, &mut req));
 
-    #[test]
-    fn app_builder_mixed_hooks() {
-        let builder = App::builder()
-            .on_startup(|| Ok(()))
-            .on_shutdown(|| {})
-            .on_startup(|| Ok(()))
-            .on_shutdown(|| {});
+        let mut req = Request::new(Method::Get, "/metrics");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert!(
+            body_text(&response)
+                .contains("http_requests_total{method=\"GET\",route=\"/ping\",status=\"200\"} 1")
+        );
 
-        assert_eq!(builder.startup_hook_count(), 2);
-        assert_eq!(builder.shutdown_hook_count(), 2);
+        let spec = served_spec(&app, "/openapi.json");
+        assert!(spec["paths"].get("/metrics").is_none());
     }
 
     #[test]
-    fn app_pending_hooks_count() {
+    fn health_probes_flip_readiness_on_shutdown() {
+        let mut registry = crate::health::HealthCheckRegistry::new();
+        registry.add("db", true, || async { Ok(()) });
         let app = App::builder()
-            .on_startup(|| Ok(()))
-            .on_startup(|| Ok(()))
-            .on_shutdown(|| {})
-            .get("/", test_handler)
+            .health(registry)
+            .openapi_route("/openapi.json")
             .build();
+        let ctx = test_context();
 
-        assert_eq!(app.pending_startup_hooks(), 2);
-        assert_eq!(app.pending_shutdown_hooks(), 1);
-    }
+        let mut req = Request::new(Method::Get, "/readyz");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 200);
+        assert!(body_text(&response).contains("\"name\":\"db\""));
 
-    // --- Startup Hooks: Execution Order (FIFO) ---
+        futures_executor::block_on(app.run_shutdown_hooks());
+        let mut req = Request::new(Method::Get, "/readyz");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 503);
+        assert!(body_text(&response).contains("\"status\":\"draining\""));
+        let mut req = Request::new(Method::Get, "/healthz");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 200);
 
-    #[test]
-    fn startup_hooks_run_in_fifo_order() {
-        let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let spec = served_spec(&app, "/openapi.json");
+        assert!(spec["paths"].get("/healthz").is_none());
+    }
 
-        let order1 = Arc::clone(&order);
-        let order2 = Arc::clone(&order);
-        let order3 = Arc::clone(&order);
+    #[test]
+    fn route_foreign_mounts_owned_request_handlers() {
+        async fn legacy(req: Request) -> Result<String, crate::HttpError> {
+            match req.query() {
+                Some(query) => Ok(format!("{} ?{query}", req.path())),
+                None => Err(crate::HttpError::bad_request()),
+            }
+        }
 
         let app = App::builder()
-            .on_startup(move || {
-                order1.lock().push(1);
-                Ok(())
-            })
-            .on_startup(move || {
-                order2.lock().push(2);
-                Ok(())
-            })
-            .on_startup(move || {
-                order3.lock().push(3);
-                Ok(())
-            })
-            .get("/", test_handler)
+            .route_foreign("/legacy", Method::Get, crate::interop::from_fn(legacy))
             .build();
 
-        let outcome = futures_executor::block_on(app.run_startup_hooks());
-        assert!(outcome.can_proceed());
-
-        // FIFO: 1, 2, 3
-        assert_eq!(*order.lock(), vec![1, 2, 3]);
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/legacy");
+        req.set_query(Some("v=1".to_string()));
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(body_text(&response), "/legacy ?v=1");
 
-        // Hooks consumed
-        assert_eq!(app.pending_startup_hooks(), 0);
+        let mut req = Request::new(Method::Get, "/legacy");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 400);
     }
 
-    // --- Shutdown Hooks: Execution Order (LIFO) ---
-
     #[test]
-    fn shutdown_hooks_run_in_lifo_order() {
-        let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
+    fn held_snapshot_keeps_serving_old_routes() {
+        let app = App::builder().get("/", test_handler).build();
+        let held = app.snapshot();
 
-        let order1 = Arc::clone(&order);
-        let order2 = Arc::clone(&order);
-        let order3 = Arc::clone(&order);
+        app.reload(App::builder().get("/health", health_handler))
+            .unwrap();
 
-        let app = App::builder()
-            .on_shutdown(move || {
-                order1.lock().push(1);
-            })
-            .on_shutdown(move || {
-                order2.lock().push(2);
-            })
-            .on_shutdown(move || {
-                order3.lock().push(3);
-            })
-            .get("/", test_handler)
-            .build();
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/");
+        let response = futures_executor::block_on(held.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(held.route_count(), 1);
+        assert_eq!(
+            app.routes().next(),
+            Some((Method::Get, "/health".to_string()))
+        );
 
-        futures_executor::block_on(app.run_shutdown_hooks());
+        // Once released, the replaced snapshot is dropped.
+        let replaced = Arc::downgrade(&held);
+        drop(held);
+        assert!(replaced.upgrade().is_none());
+    }
 
-        // LIFO: 3, 2, 1
-        assert_eq!(*order.lock(), vec![3, 2, 1]);
+    #[test]
+    fn repeated_reloads_serve_latest_snapshot() {
+        let app = App::builder().get("/", test_handler).build();
+        let first = app.snapshot();
+        for n in 1..=20 {
+            let mut builder = App::builder();
+            for i in 0..n {
+                builder = builder.get(format!("/r{i}"), test_handler);
+            }
+            app.reload(builder).unwrap();
+            assert_eq!(app.route_count(), n);
+        }
+        assert_eq!(app.snapshot_generation(), 20);
+        assert_eq!(first.route_count(), 1);
+    }
 
-        // Hooks consumed
-        assert_eq!(app.pending_shutdown_hooks(), 0);
+    fn panicking_handler(
+        _ctx: &RequestContext,
+        _req: &mut Request,
+    ) -> std::future::Ready<Response> {
+        panic!("handler exploded")
     }
 
-    // --- Startup Hooks: Success Outcome ---
+    fn recording_reporter() -> (
+        Arc<parking_lot::Mutex<Vec<ErrorEvent>>>,
+        impl ErrorReporter + 'static,
+    ) {
+        let events = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        (events, move |event: ErrorEvent| sink.lock().push(event))
+    }
 
     #[test]
-    fn startup_hooks_success_outcome() {
+    fn error_reporter_catches_handler_panics() {
+        let (events, reporter) = recording_reporter();
         let app = App::builder()
-            .on_startup(|| Ok(()))
-            .on_startup(|| Ok(()))
-            .get("/", test_handler)
+            .error_reporter(reporter)
+            .get("/boom", panicking_handler)
             .build();
 
-        let outcome = futures_executor::block_on(app.run_startup_hooks());
-        assert!(matches!(outcome, StartupOutcome::Success));
-        assert!(outcome.can_proceed());
-    }
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/boom");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 500);
 
-    // --- Startup Hooks: Fatal Error Aborts ---
+        let events = events.lock();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].level, crate::error_reporting::ErrorLevel::Fatal);
+        assert_eq!(events[0].message, "handler exploded");
+        assert_eq!(events[0].route.as_deref(), Some("/boom"));
+        assert_eq!(events[0].request.as_ref().unwrap().method, "GET");
+    }
 
     #[test]
-    fn startup_hooks_fatal_error_aborts() {
+    fn handler_panics_become_500_without_a_reporter() {
         let app = App::builder()
-            .on_startup(|| Ok(()))
-            .on_startup(|| Err(StartupHookError::new("database connection failed")))
-            .on_startup(|| Ok(())) // Should not run
-            .get("/", test_handler)
+            .config(AppConfig::new().panic_backtraces(true))
+            .get("/boom", panicking_handler)
+            .get("/ok", test_handler)
             .build();
+        let ctx = test_context();
 
-        let outcome = futures_executor::block_on(app.run_startup_hooks());
-        assert!(!outcome.can_proceed());
+        let mut req = Request::new(Method::Get, "/boom");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 500);
+        assert!(
+            response
+                .headers()
+                .iter()
+                .any(|(n, v)| n == "x-request-id" && v == ctx.request_id().to_string().as_bytes())
+        );
 
-        if let StartupOutcome::Aborted(err) = outcome {
-            assert!(err.message.contains("database connection failed"));
-            assert!(err.abort);
-        } else {
-            panic!("Expected Aborted outcome");
-        }
+        // The app keeps serving after the panic.
+        let mut req = Request::new(Method::Get, "/ok");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 200);
     }
 
-    // --- Startup Hooks: Non-Fatal Error Continues ---
-
     #[test]
-    fn startup_hooks_non_fatal_error_continues() {
+    fn error_reporter_skips_handled_client_errors() {
+        let (events, reporter) = recording_reporter();
         let app = App::builder()
-            .on_startup(|| Ok(()))
-            .on_startup(|| Err(StartupHookError::non_fatal("optional feature unavailable")))
-            .on_startup(|| Ok(())) // Should still run
-            .get("/", test_handler)
+            .error_reporter(reporter)
+            .exception_handler(|_ctx, _err: TestError| {
+                Response::with_status(StatusCode::BAD_REQUEST)
+            })
             .build();
+        let ctx = test_context();
 
-        let outcome = futures_executor::block_on(app.run_startup_hooks());
-        assert!(outcome.can_proceed());
+        let response = app.handle_error(
+            &ctx,
+            TestError {
+                message: "bad input".into(),
+                code: 1,
+            },
+        );
+        assert_eq!(response.unwrap().status().as_u16(), 400);
+        assert!(events.lock().is_empty());
 
-        if let StartupOutcome::PartialSuccess { warnings } = outcome {
-            assert_eq!(warnings, 1);
-        } else {
-            panic!("Expected PartialSuccess outcome");
-        }
+        let response = app.handle_error_or_default(&ctx, AnotherError("unhandled".into()));
+        assert_eq!(response.status().as_u16(), 500);
+        let events = events.lock();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].error_type.ends_with("AnotherError"));
     }
 
-    // --- Startup Hook Error Types ---
-
     #[test]
-    fn startup_hook_error_builder() {
-        let err = StartupHookError::new("test error")
-            .with_hook_name("database_init")
-            .with_abort(false);
+    fn buffered_reporter_flushes_on_interval_and_shutdown() {
+        use crate::error_reporting::{BufferedReporter, ReportTransport};
+
+        #[derive(Default)]
+        struct Counting(Arc<std::sync::atomic::AtomicUsize>);
+        impl ReportTransport for Counting {
+            fn send(
+                &self,
+                _payload: Vec<u8>,
+            ) -> Pin<Box<dyn Future<Output = std::io::Result<()>> + Send + '_>> {
+                self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                Box::pin(async { Ok(()) })
+            }
+        }
 
-        assert_eq!(err.hook_name.as_deref(), Some("database_init"));
-        assert_eq!(err.message, "test error");
-        assert!(!err.abort);
-    }
+        let transport = Counting::default();
+        let sent = Arc::clone(&transport.0);
+        let app = App::builder()
+            .error_reporter(BufferedReporter::new(transport))
+            .build();
+        assert!(app.scheduler().status("error-reporter-flush").is_some());
 
-    #[test]
-    fn startup_hook_error_display() {
-        let err = StartupHookError::new("connection failed").with_hook_name("redis_init");
+        let ctx = test_context();
+        let _ = app.handle_error_or_default(&ctx, AnotherError("unhandled".into()));
+        assert_eq!(sent.load(std::sync::atomic::Ordering::Relaxed), 0);
+        futures_executor::block_on(app.run_shutdown_hooks());
+        assert_eq!(sent.load(std::sync::atomic::Ordering::Relaxed), 1);
 
-        let display = format!("{}", err);
-        assert!(display.contains("redis_init"));
-        assert!(display.contains("connection failed"));
+        // Reporters without an interval are still flushed at shutdown.
+        let (_events, reporter) = recording_reporter();
+        let app = App::builder().error_reporter(reporter).build();
+        assert!(app.scheduler().is_empty());
+        assert_eq!(app.pending_shutdown_hooks(), 1);
     }
 
     #[test]
-    fn startup_hook_error_non_fatal() {
-        let err = StartupHookError::non_fatal("optional feature");
-        assert!(!err.abort);
-    }
+    fn openapi_includes_route_security_schemes() {
+        let route = Route::new(Method::Get, "/me")
+            .operation_id("me")
+            .security("OAuth2PasswordBearer", Vec::<String>::new());
+        let entry = RouteEntry::from_route(route, test_handler).security_scheme(
+            "OAuth2PasswordBearer",
+            fastapi_openapi::SecurityScheme::oauth2_password("/token"),
+        );
+        let app = App::builder()
+            .openapi(OpenApiConfig::new().security_scheme(
+                "HTTPBearer",
+                fastapi_openapi::SecurityScheme::bearer_with_format("JWT"),
+            ))
+            .route_entry(entry)
+            .build();
 
-    // --- Transfer Shutdown Hooks to Controller ---
+        let spec = app.openapi_spec().unwrap();
+        let json: serde_json::Value = serde_json::from_str(&spec).unwrap();
+        let schemes = &json["components"]["securitySchemes"];
+        assert_eq!(schemes["OAuth2PasswordBearer"]["type"], "oauth2");
+        assert_eq!(
+            schemes["OAuth2PasswordBearer"]["flows"]["password"]["tokenUrl"],
+            "/token"
+        );
+        assert_eq!(schemes["HTTPBearer"]["bearerFormat"], "JWT");
+        assert_eq!(
+            json["paths"]["/me"]["get"]["security"][0]["OAuth2PasswordBearer"],
+            serde_json::json!([])
+        );
+    }
 
     #[test]
-    fn transfer_shutdown_hooks_to_controller() {
-        let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
-
-        let order1 = Arc::clone(&order);
-        let order2 = Arc::clone(&order);
-
+    fn openapi_tags_follow_declared_order() {
+        let route = Route::new(Method::Get, "/items")
+            .operation_id("items")
+            .tag("items")
+            .tag("misc");
         let app = App::builder()
-            .on_shutdown(move || {
-                order1.lock().push(1);
-            })
-            .on_shutdown(move || {
-                order2.lock().push(2);
-            })
-            .get("/", test_handler)
+            .openapi(
+                OpenApiConfig::new()
+                    .tag("users", Some("User management".to_string()))
+                    .tag_def(
+                        fastapi_openapi::Tag::new("items")
+                            .description("Inventory")
+                            .external_docs(fastapi_openapi::ExternalDocs::new(
+                                "https://docs.example.com/items",
+                            ))
+                            .order(0),
+                    ),
+            )
+            .route_entry(RouteEntry::from_route(route, test_handler))
             .build();
 
-        let controller = ShutdownController::new();
-        app.transfer_shutdown_hooks(&controller);
+        let spec = app.openapi_spec().unwrap();
+        let json: serde_json::Value = serde_json::from_str(&spec).unwrap();
+        let tags = json["tags"].as_array().unwrap();
+        let names: Vec<_> = tags.iter().map(|t| t["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["items", "users", "misc"]);
+        assert_eq!(
+            tags[0]["externalDocs"]["url"],
+            "https://docs.example.com/items"
+        );
+        assert!(tags[0].get("order").is_none());
+    }
 
-        // App hooks consumed
-        assert_eq!(app.pending_shutdown_hooks(), 0);
+    #[test]
+    fn openapi_overrides_file_is_merged_and_conflicts_reported() {
+        let path = std::env::temp_dir().join(format!(
+            "fastapi_openapi_overrides_{}.json",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            r#"{
+                "info": {"title": "Inventory"},
+                "paths": {
+                    "/items": {"get": {"description": "Paginated.", "x-internal": true}},
+                    "/gone": {"get": {"summary": "Removed"}}
+                }
+            }"#,
+        )
+        .unwrap();
 
-        // Controller has the hooks
-        assert_eq!(controller.hook_count(), 2);
+        let app = App::builder()
+            .openapi(OpenApiConfig::new().title("Items").overrides_file(&path))
+            .get("/items", test_handler)
+            .build();
+        std::fs::remove_file(&path).ok();
 
-        // Run via controller (LIFO order)
-        while let Some(hook) = controller.pop_hook() {
-            hook.run();
-        }
+        let spec = served_spec(&app, "/openapi.json");
+        assert_eq!(spec["info"]["title"], "Inventory");
+        assert_eq!(spec["paths"]["/items"]["get"]["description"], "Paginated.");
+        assert_eq!(spec["paths"]["/items"]["get"]["x-internal"], true);
 
-        // LIFO order via controller
-        assert_eq!(*order.lock(), vec![2, 1]);
+        let report = app.check();
+        assert!(report.is_ok(), "{report}");
+        let warnings: Vec<_> = report.warnings().map(|i| i.message.as_str()).collect();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("/info/title"));
+        assert!(warnings[1].contains("/paths/~1gone"));
     }
 
-    // --- Debug Format Includes Hooks ---
-
     #[test]
-    fn app_debug_includes_hooks() {
+    fn openapi_missing_overrides_file_is_a_check_error() {
         let app = App::builder()
-            .on_startup(|| Ok(()))
-            .on_shutdown(|| {})
-            .get("/", test_handler)
+            .openapi(OpenApiConfig::new().overrides_file("/nonexistent/overrides.json"))
+            .get("/items", test_handler)
             .build();
 
-        let debug = format!("{:?}", app);
-        assert!(debug.contains("startup_hooks"));
-        assert!(debug.contains("shutdown_hooks"));
+        assert!(app.openapi_spec().is_some());
+        let report = app.check();
+        let errors: Vec<_> = report.errors().map(|i| i.category.as_str()).collect();
+        assert_eq!(errors, ["openapi"]);
     }
 
     #[test]
-    fn app_builder_debug_includes_hooks() {
-        let builder = App::builder().on_startup(|| Ok(())).on_shutdown(|| {});
+    fn check_reports_missing_state_and_bad_limits() {
+        struct Db;
 
-        let debug = format!("{:?}", builder);
-        assert!(debug.contains("startup_hooks"));
-        assert!(debug.contains("shutdown_hooks"));
-    }
+        let app = App::builder()
+            .config(AppConfig::new().max_body_size(0))
+            .middleware(
+                crate::middleware::Cors::new()
+                    .allow_any_origin()
+                    .allow_credentials(true),
+            )
+            .route_entry(
+                RouteEntry::new(Method::Get, "/users", test_handler).requires_state::<Db>(),
+            )
+            .route_entry(
+                RouteEntry::new(Method::Get, "/count", test_handler).requires_state::<u32>(),
+            )
+            .state(7u32)
+            .build();
 
-    // --- Startup Outcome Accessors ---
+        let report = app.check();
+        assert!(!report.is_ok());
+        assert_eq!(report.routes.len(), 2);
+        assert_eq!(report.middleware, ["Cors"]);
+        assert_eq!(report.state_count, 1);
 
-    #[test]
-    fn startup_outcome_success() {
-        let outcome = StartupOutcome::Success;
-        assert!(outcome.can_proceed());
-        assert!(outcome.into_error().is_none());
+        let errors: Vec<_> = report.errors().map(|i| i.category.as_str()).collect();
+        assert_eq!(errors, ["state", "limits"]);
+        assert!(report.issues[0].message.contains("GET /users"));
+        assert!(report.issues[0].message.contains("Db"));
+        assert_eq!(report.warnings().next().unwrap().category, "cors");
     }
 
     #[test]
-    fn startup_outcome_partial_success() {
-        let outcome = StartupOutcome::PartialSuccess { warnings: 2 };
-        assert!(outcome.can_proceed());
-        assert!(outcome.into_error().is_none());
+    fn check_passes_for_default_app() {
+        let report = App::builder().get("/", test_handler).build().check();
+        assert!(report.is_ok(), "{report}");
+        assert_eq!(report.issues.len(), 0);
     }
 
     #[test]
-    fn startup_outcome_aborted() {
-        let err = StartupHookError::new("fatal");
-        let outcome = StartupOutcome::Aborted(err);
-        assert!(!outcome.can_proceed());
+    fn snapshot_is_shared_across_threads() {
+        let app = Arc::new(App::builder().get("/", test_handler).build());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let app = Arc::clone(&app);
+                std::thread::spawn(move || {
+                    let ctx = test_context();
+                    let mut req = Request::new(Method::Get, "/");
+                    futures_executor::block_on(app.handle(&ctx, &mut req))
+                        .status()
+                        .as_u16()
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 200);
+        }
+    }
 
-        let err = outcome.into_error();
-        assert!(err.is_some());
-        assert_eq!(err.unwrap().message, "fatal");
+    fn json_handler(_ctx: &RequestContext, _req: &mut Request) -> std::future::Ready<Response> {
+        std::future::ready(Response::json(&serde_json::json!({"id": 1})).unwrap())
     }
 
-    // --- Multiple Non-Fatal Errors ---
+    fn body_text(response: &Response) -> String {
+        match response.body_ref() {
+            ResponseBody::Bytes(b) => String::from_utf8(b.clone()).unwrap(),
+            _ => String::new(),
+        }
+    }
 
     #[test]
-    fn startup_hooks_multiple_non_fatal_errors() {
+    fn pretty_json_on_query_flag() {
         let app = App::builder()
-            .on_startup(|| Err(StartupHookError::non_fatal("warning 1")))
-            .on_startup(|| Ok(()))
-            .on_startup(|| Err(StartupHookError::non_fatal("warning 2")))
-            .on_startup(|| Err(StartupHookError::non_fatal("warning 3")))
-            .get("/", test_handler)
+            .config(AppConfig::new().pretty_json(true))
+            .get("/item", json_handler)
             .build();
+        let ctx = test_context();
 
-        let outcome = futures_executor::block_on(app.run_startup_hooks());
-        assert!(outcome.can_proceed());
+        let mut req = Request::new(Method::Get, "/item");
+        let compact = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(body_text(&compact), r#"{"id":1}"#);
 
-        if let StartupOutcome::PartialSuccess { warnings } = outcome {
-            assert_eq!(warnings, 3);
-        } else {
-            panic!("Expected PartialSuccess");
-        }
+        let mut req = Request::new(Method::Get, "/item");
+        req.set_query(Some("pretty=1".to_string()));
+        let pretty = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(body_text(&pretty), "{\n  \"id\": 1\n}");
     }
 
-    // --- Empty Hooks ---
-
     #[test]
-    fn empty_startup_hooks() {
-        let app = App::builder().get("/", test_handler).build();
+    fn pretty_json_always_in_debug_and_never_when_disabled() {
+        let debug = App::builder()
+            .config(AppConfig::new().debug(true).pretty_json(true))
+            .get("/item", json_handler)
+            .build();
+        let disabled = App::builder()
+            .config(AppConfig::new().debug(true))
+            .get("/item", json_handler)
+            .build();
+        let ctx = test_context();
 
-        let outcome = futures_executor::block_on(app.run_startup_hooks());
-        assert!(matches!(outcome, StartupOutcome::Success));
+        let mut req = Request::new(Method::Get, "/item");
+        let response = futures_executor::block_on(debug.handle(&ctx, &mut req));
+        assert_eq!(body_text(&response), "{\n  \"id\": 1\n}");
+
+        let mut req = Request::new(Method::Get, "/item");
+        req.set_query(Some("pretty=1".to_string()));
+        let response = futures_executor::block_on(disabled.handle(&ctx, &mut req));
+        assert_eq!(body_text(&response), r#"{"id":1}"#);
     }
 
     #[test]
-    fn empty_shutdown_hooks() {
-        let app = App::builder().get("/", test_handler).build();
+    fn problem_details_rewrites_error_bodies() {
+        let app = App::builder()
+            .config(AppConfig::new().problem_details(true))
+            .get("/item", json_handler)
+            .build();
+        let ctx = test_context();
 
-        // Should not panic with empty hooks
-        futures_executor::block_on(app.run_shutdown_hooks());
-    }
+        let mut req = Request::new(Method::Get, "/item");
+        let ok = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(body_text(&ok), r#"{"id":1}"#);
 
-    // --- Hooks Can Only Run Once ---
+        let mut req = Request::new(Method::Post, "/item");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert!(response.headers().iter().any(|(n, v)| {
+            n.eq_ignore_ascii_case("content-type") && v == b"application/problem+json"
+        }));
+        assert!(response.headers().iter().any(|(n, _)| n == "allow"));
+        let doc: serde_json::Value = serde_json::from_str(&body_text(&response)).unwrap();
+        assert_eq!(doc["status"], 405);
+        assert_eq!(doc["title"], "Method Not Allowed");
+        assert_eq!(doc["instance"], "/item");
+    }
 
     #[test]
-    fn startup_hooks_consumed_after_run() {
-        let counter = Arc::new(std::sync::atomic::AtomicU32::new(0));
-        let counter_clone = Arc::clone(&counter);
+    fn json_key_case_rewrites_response_keys() {
+        fn user_handler(_ctx: &RequestContext, req: &mut Request) -> std::future::Ready<Response> {
+            let case = req
+                .get_extension::<crate::json_case::JsonKeyCase>()
+                .copied();
+            let body = serde_json::json!({"user_id": 1, "display_name": "Ada", "case_set": case.is_some()});
+            std::future::ready(Response::json(&body).unwrap())
+        }
 
         let app = App::builder()
-            .on_startup(move || {
-                counter_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-                Ok(())
-            })
-            .get("/", test_handler)
+            .config(AppConfig::new().json_key_case(crate::json_case::JsonKeyCase::Camel))
+            .get("/user", user_handler)
             .build();
+        let ctx = test_context();
 
-        // First run
-        futures_executor::block_on(app.run_startup_hooks());
-        assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let mut req = Request::new(Method::Get, "/user");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(
+            body_text(&response),
+            r#"{"caseSet":true,"displayName":"Ada","userId":1}"#
+        );
 
-        // Second run - no hooks left
-        futures_executor::block_on(app.run_startup_hooks());
-        assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let mut req = Request::new(Method::Get, "/missing");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(body_text(&response), r#"{"detail":"Not Found"}"#);
     }
 
     #[test]
-    fn shutdown_hooks_consumed_after_run() {
-        let counter = Arc::new(std::sync::atomic::AtomicU32::new(0));
-        let counter_clone = Arc::clone(&counter);
-
+    fn docs_and_redoc_shortcuts_register_only_requested_uis() {
         let app = App::builder()
-            .on_shutdown(move || {
-                counter_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-            })
+            .config(AppConfig::new().name("Shop"))
             .get("/", test_handler)
+            .docs("/swagger")
             .build();
+        let ctx = test_context();
 
-        // First run
-        futures_executor::block_on(app.run_shutdown_hooks());
-        assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let mut req = Request::new(Method::Get, "/swagger");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 200);
+        assert!(body_text(&response).contains("<title>Shop</title>"));
 
-        // Second run - no hooks left
-        futures_executor::block_on(app.run_shutdown_hooks());
-        assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
-    }
+        for path in ["/swagger/oauth2-redirect", "/openapi.json"] {
+            let mut req = Request::new(Method::Get, path);
+            let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+            assert_eq!(response.status().as_u16(), 200, "{path}");
+        }
 
-    #[test]
-    fn root_path_strips_trailing_slashes() {
-        let config = AppConfig::new().root_path("/api/");
-        assert_eq!(config.root_path, "/api");
+        let mut req = Request::new(Method::Get, "/redoc");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 404);
 
-        let config = AppConfig::new().root_path("/api///");
-        assert_eq!(config.root_path, "/api");
+        let both = App::builder()
+            .get("/", test_handler)
+            .docs("/docs")
+            .redoc("/reference")
+            .build();
+        let mut req = Request::new(Method::Get, "/reference");
+        let response = futures_executor::block_on(both.handle(&ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 200);
+    }
 
-        let config = AppConfig::new().root_path("/api");
-        assert_eq!(config.root_path, "/api");
+    #[test]
+    fn disabled_docs_keep_openapi_json_only() {
+        let app = App::builder()
+            .get("/", test_handler)
+            .enable_docs(crate::docs::DocsConfig::new().enabled(false))
+            .build();
+        let ctx = test_context();
 
-        // Empty root_path stays empty
-        let config = AppConfig::new().root_path("");
-        assert_eq!(config.root_path, "");
+        for (path, status) in [("/docs", 404), ("/redoc", 404), ("/openapi.json", 200)] {
+            let mut req = Request::new(Method::Get, path);
+            let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+            assert_eq!(response.status().as_u16(), status, "{path}");
+        }
     }
 }
@@ -0,0 +1,522 @@
+//! Audit logging with redaction.
+//!
+//! [`AuditLog`] records one [`AuditRecord`] per request: method, path,
+//! status, duration, the user recorded on the request's
+//! [`LogScope`](crate::logging::LogScope), the headers you select and,
+//! optionally, the bodies. Secrets are replaced by `[REDACTED]` before the
+//! record leaves the middleware:
+//!
+//! ```ignore
+//! let audit = AuditLog::new()
+//!     .header("x-request-id")
+//!     .header("authorization") // recorded as [REDACTED]
+//!     .request_bodies(true)
+//!     .redact_pointer("/password")
+//!     .redact_pointer("/cards/*/number");
+//!
+//! let app = App::builder().middleware(audit).build();
+//! ```
+//!
+//! `Authorization`, `Proxy-Authorization`, `Cookie` and `Set-Cookie` are
+//! redacted by default; [`redact_header`](AuditLog::redact_header) adds more.
+//! Body rules are JSON pointers (RFC 6901) in which a `*` segment matches
+//! every member of an object or array. When body rules are configured, a
+//! body that is not valid JSON is omitted rather than recorded unredacted.
+//!
+//! Records are logged at INFO level through the request's logger, or handed
+//! to a [`sink`](AuditLog::sink).
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Instant;
+
+use serde_json::Value;
+
+use crate::context::RequestContext;
+use crate::logging::{LogConfig, RequestLogger};
+use crate::middleware::{BoxFuture, ControlFlow, Middleware, default_redacted_headers};
+use crate::request::{Body, Request};
+use crate::response::{Response, ResponseBody};
+
+/// Replacement for redacted values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// What [`AuditLog`] recorded about one request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditRecord {
+    /// Request method.
+    pub method: String,
+    /// Request path.
+    pub path: String,
+    /// Query string, without the `?`.
+    pub query: Option<String>,
+    /// Response status code.
+    pub status: u16,
+    /// Time from the audit middleware seeing the request to the response.
+    pub duration_ms: u64,
+    /// User id recorded on the request's log scope.
+    pub user: Option<String>,
+    /// Selected request headers, redacted.
+    pub request_headers: Vec<(String, String)>,
+    /// Selected response headers, redacted.
+    pub response_headers: Vec<(String, String)>,
+    /// Request body, redacted and truncated, when body recording is on.
+    pub request_body: Option<String>,
+    /// Response body, redacted and truncated, when body recording is on.
+    pub response_body: Option<String>,
+}
+
+impl AuditRecord {
+    /// The record as a JSON object.
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        let headers = |headers: &[(String, String)]| -> serde_json::Map<String, Value> {
+            headers
+                .iter()
+                .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+                .collect()
+        };
+        serde_json::json!({
+            "method": self.method,
+            "path": self.path,
+            "query": self.query,
+            "status": self.status,
+            "duration_ms": self.duration_ms,
+            "user": self.user,
+            "request_headers": headers(&self.request_headers),
+            "response_headers": headers(&self.response_headers),
+            "request_body": self.request_body,
+            "response_body": self.response_body,
+        })
+    }
+}
+
+type Sink = Arc<dyn Fn(&AuditRecord) + Send + Sync>;
+
+/// Per-request state captured before the handler can consume the body.
+#[derive(Debug, Clone)]
+struct AuditStart {
+    instant: Instant,
+    request_body: Option<String>,
+}
+
+/// Middleware recording an audit trail of requests and responses.
+#[derive(Clone)]
+pub struct AuditLog {
+    headers: Vec<String>,
+    redact_headers: HashSet<String>,
+    pointers: Vec<Vec<String>>,
+    request_bodies: bool,
+    response_bodies: bool,
+    max_body_bytes: usize,
+    sink: Option<Sink>,
+    log_config: LogConfig,
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self {
+            headers: Vec::new(),
+            redact_headers: default_redacted_headers(),
+            pointers: Vec::new(),
+            request_bodies: false,
+            response_bodies: false,
+            max_body_bytes: 4096,
+            sink: None,
+            log_config: LogConfig::production(),
+        }
+    }
+}
+
+impl AuditLog {
+    /// Record method, path, status and duration only.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the request and response header `name` (case-insensitive).
+    #[must_use]
+    pub fn header(mut self, name: impl Into<String>) -> Self {
+        self.headers.push(name.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Redact the header `name` (case-insensitive) when it is recorded.
+    #[must_use]
+    pub fn redact_header(mut self, name: impl Into<String>) -> Self {
+        self.redact_headers.insert(name.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Record request bodies.
+    #[must_use]
+    pub fn request_bodies(mut self, enabled: bool) -> Self {
+        self.request_bodies = enabled;
+        self
+    }
+
+    /// Record response bodies.
+    #[must_use]
+    pub fn response_bodies(mut self, enabled: bool) -> Self {
+        self.response_bodies = enabled;
+        self
+    }
+
+    /// Truncate recorded bodies to `max` bytes (default 4096).
+    #[must_use]
+    pub fn max_body_bytes(mut self, max: usize) -> Self {
+        self.max_body_bytes = max;
+        self
+    }
+
+    /// Redact the JSON body value at `pointer`, such as `"/user/password"`.
+    /// A `*` segment matches every member of an object or array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pointer` does not start with `/`.
+    #[must_use]
+    pub fn redact_pointer(mut self, pointer: &str) -> Self {
+        let rest = pointer.strip_prefix('/').expect("invalid JSON pointer");
+        self.pointers.push(
+            rest.split('/')
+                .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+                .collect(),
+        );
+        self
+    }
+
+    /// Hand each record to `sink` instead of the request logger.
+    #[must_use]
+    pub fn sink(mut self, sink: impl Fn(&AuditRecord) + Send + Sync + 'static) -> Self {
+        self.sink = Some(Arc::new(sink));
+        self
+    }
+
+    /// Logging configuration used when no sink is set.
+    #[must_use]
+    pub fn log_config(mut self, config: LogConfig) -> Self {
+        self.log_config = config;
+        self
+    }
+
+    fn selected_headers<'h>(
+        &self,
+        headers: impl Iterator<Item = (&'h str, &'h [u8])>,
+    ) -> Vec<(String, String)> {
+        if self.headers.is_empty() {
+            return Vec::new();
+        }
+        headers
+            .filter_map(|(name, value)| {
+                let lowered = name.to_ascii_lowercase();
+                if !self.headers.contains(&lowered) {
+                    return None;
+                }
+                let value = if self.redact_headers.contains(&lowered) {
+                    REDACTED.to_string()
+                } else {
+                    String::from_utf8_lossy(value).into_owned()
+                };
+                Some((lowered, value))
+            })
+            .collect()
+    }
+
+    /// Redact and truncate a body for the record.
+    fn body(&self, bytes: &[u8]) -> Option<String> {
+        if bytes.is_empty() {
+            return None;
+        }
+        let text = match serde_json::from_slice::<Value>(bytes) {
+            Ok(mut json) => {
+                for pointer in &self.pointers {
+                    redact_at(&mut json, pointer);
+                }
+                json.to_string()
+            }
+            Err(_) if !self.pointers.is_empty() => {
+                return Some(format!("<{} bytes omitted>", bytes.len()));
+            }
+            Err(_) => match std::str::from_utf8(bytes) {
+                Ok(text) => text.to_string(),
+                Err(_) => return Some(format!("<{} bytes binary>", bytes.len())),
+            },
+        };
+        Some(truncate(text, self.max_body_bytes))
+    }
+
+    fn record(&self, ctx: &RequestContext, req: &Request, response: &Response) -> AuditRecord {
+        let start = req.get_extension::<AuditStart>();
+        let response_body = if self.response_bodies {
+            match response.body_ref() {
+                ResponseBody::Empty => None,
+                ResponseBody::Bytes(bytes) => self.body(bytes),
+                ResponseBody::Stream(_) => Some("<streaming body>".to_string()),
+            }
+        } else {
+            None
+        };
+        AuditRecord {
+            method: req.method().to_string(),
+            path: req.path().to_string(),
+            query: req.query().map(ToString::to_string),
+            status: response.status().as_u16(),
+            duration_ms: start.map_or(0, |start| {
+                u64::try_from(start.instant.elapsed().as_millis()).unwrap_or(u64::MAX)
+            }),
+            user: ctx.log_scope().user_id(),
+            request_headers: self.selected_headers(req.headers().iter()),
+            response_headers: self.selected_headers(
+                response
+                    .headers()
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_slice())),
+            ),
+            request_body: start.and_then(|start| start.request_body.clone()),
+            response_body,
+        }
+    }
+}
+
+/// Replace the values matched by `segments` with [`REDACTED`].
+fn redact_at(value: &mut Value, segments: &[String]) {
+    let Some((first, rest)) = segments.split_first() else {
+        *value = Value::String(REDACTED.to_string());
+        return;
+    };
+    match value {
+        Value::Object(map) if first == "*" => {
+            for member in map.values_mut() {
+                redact_at(member, rest);
+            }
+        }
+        Value::Object(map) => {
+            if let Some(member) = map.get_mut(first) {
+                redact_at(member, rest);
+            }
+        }
+        Value::Array(items) if first == "*" => {
+            for item in items {
+                redact_at(item, rest);
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item) = first.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+                redact_at(item, rest);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn truncate(mut text: String, max: usize) -> String {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    text.push_str("...");
+    text
+}
+
+fn format_headers(headers: &[(String, String)]) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("headers", &self.headers)
+            .field("redact_headers", &self.redact_headers)
+            .field("pointers", &self.pointers)
+            .field("request_bodies", &self.request_bodies)
+            .field("response_bodies", &self.response_bodies)
+            .field("sink", &self.sink.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Middleware for AuditLog {
+    fn before<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        let request_body = match req.body() {
+            Body::Bytes(bytes) if self.request_bodies => self.body(bytes),
+            Body::Stream { .. } if self.request_bodies => Some("<streaming body>".to_string()),
+            _ => None,
+        };
+        req.insert_extension(AuditStart {
+            instant: Instant::now(),
+            request_body,
+        });
+        Box::pin(async { ControlFlow::Continue })
+    }
+
+    fn after<'a>(
+        &'a self,
+        ctx: &'a RequestContext,
+        req: &'a Request,
+        response: Response,
+    ) -> BoxFuture<'a, Response> {
+        let record = self.record(ctx, req, &response);
+        match &self.sink {
+            Some(sink) => sink(&record),
+            None => RequestLogger::new(ctx, self.log_config.clone()).info_with_fields(
+                "audit",
+                |entry| {
+                    let mut entry = entry
+                        .field("method", &record.method)
+                        .field("path", &record.path)
+                        .field("status", record.status)
+                        .field("duration_ms", record.duration_ms);
+                    if let Some(query) = &record.query {
+                        entry = entry.field("query", query);
+                    }
+                    if let Some(user) = &record.user {
+                        entry = entry.field("user", user);
+                    }
+                    if !record.request_headers.is_empty() {
+                        entry =
+                            entry.field("request_headers", format_headers(&record.request_headers));
+                    }
+                    if !record.response_headers.is_empty() {
+                        entry = entry
+                            .field("response_headers", format_headers(&record.response_headers));
+                    }
+                    if let Some(body) = &record.request_body {
+                        entry = entry.field("request_body", body);
+                    }
+                    if let Some(body) = &record.response_body {
+                        entry = entry.field("response_body", body);
+                    }
+                    entry
+                },
+            ),
+        }
+        Box::pin(async move { response })
+    }
+
+    fn name(&self) -> &'static str {
+        "AuditLog"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Method;
+    use crate::response::StatusCode;
+
+    fn audit(mw: &AuditLog, req: &mut Request, response: Response) -> AuditRecord {
+        let records = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&records);
+        let mw = mw
+            .clone()
+            .sink(move |record: &AuditRecord| sink.lock().push(record.clone()));
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+        futures_executor::block_on(async {
+            assert!(mw.before(&ctx, req).await.is_continue());
+            mw.after(&ctx, req, response).await
+        });
+        records.lock().pop().expect("one record per request")
+    }
+
+    #[test]
+    fn records_selected_headers_with_redaction() {
+        let mw = AuditLog::new()
+            .header("X-Request-Id")
+            .header("authorization")
+            .header("x-api-key")
+            .redact_header("X-Api-Key");
+        let mut req = Request::new(Method::Delete, "/users/7");
+        req.set_query(Some("hard=true".into()));
+        req.headers_mut().insert("x-request-id", b"req-1".to_vec());
+        req.headers_mut()
+            .insert("authorization", b"Bearer secret".to_vec());
+        req.headers_mut().insert("x-api-key", b"key".to_vec());
+        req.headers_mut().insert("user-agent", b"curl".to_vec());
+        let response =
+            Response::with_status(StatusCode::NO_CONTENT).header("X-Request-Id", b"req-1".to_vec());
+
+        let record = audit(&mw, &mut req, response);
+        assert_eq!(record.method, "DELETE");
+        assert_eq!(record.path, "/users/7");
+        assert_eq!(record.query.as_deref(), Some("hard=true"));
+        assert_eq!(record.status, 204);
+        assert_eq!(record.request_headers.len(), 3);
+        assert!(
+            record
+                .request_headers
+                .contains(&("authorization".to_string(), REDACTED.to_string()))
+        );
+        assert!(
+            record
+                .request_headers
+                .contains(&("x-api-key".to_string(), REDACTED.to_string()))
+        );
+        assert_eq!(
+            record.response_headers,
+            vec![("x-request-id".to_string(), "req-1".to_string())]
+        );
+        assert!(record.request_body.is_none());
+    }
+
+    #[test]
+    fn redacts_json_pointers_in_bodies() {
+        let mw = AuditLog::new()
+            .request_bodies(true)
+            .response_bodies(true)
+            .redact_pointer("/password")
+            .redact_pointer("/cards/*/number")
+            .redact_pointer("/token");
+        let mut req = Request::new(Method::Post, "/signup");
+        req.set_body(Body::Bytes(
+            br#"{"user":"ann","password":"hunter2","cards":[{"number":"4111","exp":"12/30"}]}"#
+                .to_vec(),
+        ));
+        let response = Response::with_status(StatusCode::CREATED)
+            .body(ResponseBody::Bytes(br#"{"id":1,"token":"abc"}"#.to_vec()));
+
+        let record = audit(&mw, &mut req, response);
+        let body: Value = serde_json::from_str(record.request_body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["user"], "ann");
+        assert_eq!(body["password"], REDACTED);
+        assert_eq!(body["cards"][0]["number"], REDACTED);
+        assert_eq!(body["cards"][0]["exp"], "12/30");
+        let body: Value = serde_json::from_str(record.response_body.as_deref().unwrap()).unwrap();
+        assert_eq!(body, serde_json::json!({"id": 1, "token": REDACTED}));
+    }
+
+    #[test]
+    fn omits_unparseable_bodies_when_rules_are_set() {
+        let mut req = Request::new(Method::Post, "/login");
+        req.set_body(Body::Bytes(b"user=ann&password=hunter2".to_vec()));
+        let plain = AuditLog::new().request_bodies(true).max_body_bytes(8);
+        let record = audit(&plain, &mut req, Response::ok());
+        assert_eq!(record.request_body.as_deref(), Some("user=ann..."));
+
+        let mut req = Request::new(Method::Post, "/login");
+        req.set_body(Body::Bytes(b"user=ann&password=hunter2".to_vec()));
+        let redacting = plain.redact_pointer("/password");
+        let record = audit(&redacting, &mut req, Response::ok());
+        assert_eq!(record.request_body.as_deref(), Some("<25 bytes omitted>"));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid JSON pointer")]
+    fn pointer_without_leading_slash_panics() {
+        let _ = AuditLog::new().redact_pointer("password");
+    }
+}
@@ -0,0 +1,407 @@
+//! Batch endpoint: many sub-requests in one round trip.
+//!
+//! A [`Batch`] endpoint accepts a JSON array of sub-requests in one `POST`,
+//! dispatches each through the app's router and middleware in-process, and
+//! returns a JSON array of results in the same order:
+//!
+//! ```text
+//! POST /batch
+//! [
+//!   {"method": "GET", "path": "/items/1"},
+//!   {"method": "POST", "path": "/items", "body": {"name": "widget"}}
+//! ]
+//!
+//! 200 OK
+//! [
+//!   {"status": 200, "headers": {"content-type": "application/json"}, "body": {"id": 1}},
+//!   {"status": 201, "headers": {"content-type": "application/json"}, "body": {"id": 2}}
+//! ]
+//! ```
+//!
+//! Sub-requests inherit the batch request's headers (so `Authorization` and
+//! cookies apply to every item) and may add their own. At most
+//! [`Batch::max_concurrency`] of them run at once. A malformed item gets a
+//! 400 result of its own rather than failing the whole batch.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use fastapi_core::{App, Batch};
+//!
+//! let app = App::builder()
+//!     .batch(Batch::new("/batch").max_requests(50).max_concurrency(8))
+//!     .route_entry(get_item_route())
+//!     .build();
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::task::Poll;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppSnapshot;
+use crate::context::RequestContext;
+use crate::error::HttpError;
+use crate::extract::{FromRequest, Json};
+use crate::middleware::{BoxFuture, Handler};
+use crate::request::{Body, Method, Request};
+use crate::response::{IntoResponse, Response, ResponseBody, StatusCode};
+
+/// Default maximum number of sub-requests in one batch.
+pub const DEFAULT_MAX_BATCH_REQUESTS: usize = 20;
+/// Default number of sub-requests dispatched concurrently.
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+/// Request headers that describe the batch body rather than the caller, and
+/// so are not passed on to sub-requests.
+const BODY_HEADERS: &[&str] = &[
+    "content-length",
+    "content-type",
+    "content-encoding",
+    "transfer-encoding",
+    "expect",
+];
+
+/// Configuration of a batch endpoint, registered with
+/// [`AppBuilder::batch`](crate::app::AppBuilder::batch).
+#[derive(Debug, Clone)]
+pub struct Batch {
+    path: String,
+    max_requests: usize,
+    max_concurrency: usize,
+}
+
+impl Batch {
+    /// Serves batches at `POST path`.
+    #[must_use]
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            max_requests: DEFAULT_MAX_BATCH_REQUESTS,
+            max_concurrency: DEFAULT_BATCH_CONCURRENCY,
+        }
+    }
+
+    /// Sets the largest accepted batch; bigger ones are rejected with 413.
+    #[must_use]
+    pub fn max_requests(mut self, max: usize) -> Self {
+        self.max_requests = max;
+        self
+    }
+
+    /// Sets how many sub-requests run at once (at least one).
+    #[must_use]
+    pub fn max_concurrency(mut self, max: usize) -> Self {
+        self.max_concurrency = max.max(1);
+        self
+    }
+
+    /// The endpoint path.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Builds the sub-request for `item`, or the error result to report for it.
+    fn sub_request(&self, outer: &Request, item: BatchRequest) -> Result<Request, Response> {
+        let bad_request =
+            |detail: String| HttpError::bad_request().with_detail(detail).into_response();
+
+        let Some(method) = Method::from_bytes(item.method.to_ascii_uppercase().as_bytes()) else {
+            return Err(bad_request(format!("unknown method `{}`", item.method)));
+        };
+        if !item.path.starts_with('/') {
+            return Err(bad_request(format!(
+                "path `{}` must start with `/`",
+                item.path
+            )));
+        }
+        let (path, query) = match item.path.split_once('?') {
+            Some((path, query)) => (path, Some(query.to_string())),
+            None => (item.path.as_str(), None),
+        };
+        if path == self.path {
+            return Err(bad_request("batch requests cannot be nested".to_string()));
+        }
+
+        let mut req = Request::new(method, path);
+        req.set_query(query);
+        for (name, value) in outer.headers().iter() {
+            if !BODY_HEADERS.contains(&name) {
+                req.headers_mut().insert_from_slice(name, value);
+            }
+        }
+        for (name, value) in item.headers {
+            req.headers_mut().insert(name, value.into_bytes());
+        }
+        if let Some(body) = item.body {
+            let bytes = serde_json::to_vec(&body).unwrap_or_default();
+            req.headers_mut()
+                .insert("content-type", b"application/json".to_vec());
+            req.set_body(Body::Bytes(bytes));
+        }
+        Ok(req)
+    }
+}
+
+/// One sub-request of a batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchRequest {
+    /// HTTP method, e.g. `"GET"`.
+    pub method: String,
+    /// Path, optionally with a query string.
+    pub path: String,
+    /// Extra headers, added to those of the batch request.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// JSON body, sent with `content-type: application/json`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+}
+
+/// The result of one sub-request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchResponse {
+    /// Response status code.
+    pub status: u16,
+    /// Response headers; for repeated headers the last value is kept.
+    pub headers: HashMap<String, String>,
+    /// Response body: parsed JSON for JSON responses, text otherwise, and
+    /// `null` for empty or streaming bodies.
+    pub body: serde_json::Value,
+}
+
+impl BatchResponse {
+    fn from_response(response: &Response) -> Self {
+        let headers: HashMap<String, String> = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_ascii_lowercase(),
+                    String::from_utf8_lossy(value).into_owned(),
+                )
+            })
+            .collect();
+        let is_json = headers
+            .get("content-type")
+            .is_some_and(|ct| ct.contains("json"));
+        let body = match response.body_ref() {
+            ResponseBody::Bytes(bytes) if bytes.is_empty() => serde_json::Value::Null,
+            ResponseBody::Bytes(bytes) => is_json
+                .then(|| serde_json::from_slice(bytes).ok())
+                .flatten()
+                .unwrap_or_else(|| {
+                    serde_json::Value::String(String::from_utf8_lossy(bytes).into_owned())
+                }),
+            ResponseBody::Empty | ResponseBody::Stream(_) => serde_json::Value::Null,
+        };
+        Self {
+            status: response.status().as_u16(),
+            headers,
+            body,
+        }
+    }
+}
+
+/// Handles the batch request itself, as the innermost layer of the app's
+/// middleware stack.
+pub(crate) struct BatchHandler<'a> {
+    pub(crate) batch: &'a Batch,
+    pub(crate) snapshot: &'a AppSnapshot,
+}
+
+impl Handler for BatchHandler<'_> {
+    fn call<'b>(
+        &'b self,
+        ctx: &'b RequestContext,
+        req: &'b mut Request,
+    ) -> BoxFuture<'b, Response> {
+        Box::pin(async move {
+            let items = match Json::<Vec<BatchRequest>>::from_request(ctx, req).await {
+                Ok(Json(items)) => items,
+                Err(err) => return err.into_response(),
+            };
+            if items.len() > self.batch.max_requests {
+                return HttpError::new(StatusCode::PAYLOAD_TOO_LARGE)
+                    .with_detail(format!(
+                        "batch has {} requests; the limit is {}",
+                        items.len(),
+                        self.batch.max_requests
+                    ))
+                    .into_response();
+            }
+
+            let outer: &Request = req;
+            let futures: Vec<BoxFuture<'b, Response>> = items
+                .into_iter()
+                .map(|item| match self.batch.sub_request(outer, item) {
+                    Ok(mut sub) => {
+                        Box::pin(async move { self.snapshot.handle(ctx, &mut sub).await })
+                            as BoxFuture<'b, Response>
+                    }
+                    Err(response) => Box::pin(std::future::ready(response)),
+                })
+                .collect();
+            let results: Vec<BatchResponse> = join_bounded(futures, self.batch.max_concurrency)
+                .await
+                .iter()
+                .map(BatchResponse::from_response)
+                .collect();
+
+            Response::json(&results)
+                .unwrap_or_else(|_| Response::with_status(StatusCode::INTERNAL_SERVER_ERROR))
+        })
+    }
+}
+
+/// Runs `futures` with at most `limit` in flight, returning their outputs in
+/// the original order.
+async fn join_bounded<'a, T>(futures: Vec<BoxFuture<'a, T>>, limit: usize) -> Vec<T> {
+    let mut results: Vec<Option<T>> = futures.iter().map(|_| None).collect();
+    let mut queue = futures.into_iter().enumerate();
+    let mut running: Vec<(usize, BoxFuture<'a, T>)> = Vec::with_capacity(limit);
+
+    std::future::poll_fn(move |cx| {
+        loop {
+            while running.len() < limit.max(1) {
+                let Some(next) = queue.next() else {
+                    break;
+                };
+                running.push(next);
+            }
+            if running.is_empty() {
+                return Poll::Ready(results.iter_mut().filter_map(Option::take).collect());
+            }
+
+            let before = running.len();
+            running.retain_mut(|(index, future)| match future.as_mut().poll(cx) {
+                Poll::Ready(value) => {
+                    results[*index] = Some(value);
+                    false
+                }
+                Poll::Pending => true,
+            });
+            if running.len() == before {
+                return Poll::Pending;
+            }
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::App;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn test_context() -> RequestContext {
+        RequestContext::new(asupersync::Cx::for_testing(), 1)
+    }
+
+    fn batch_request(body: &str) -> Request {
+        let mut req = Request::new(Method::Post, "/batch");
+        req.headers_mut()
+            .insert("content-type", b"application/json".to_vec());
+        req.headers_mut().insert("x-user", b"alice".to_vec());
+        req.set_body(Body::Bytes(body.as_bytes().to_vec()));
+        req
+    }
+
+    fn results(response: &Response) -> Vec<BatchResponse> {
+        let ResponseBody::Bytes(bytes) = response.body_ref() else {
+            panic!("expected a buffered body");
+        };
+        serde_json::from_slice(bytes).unwrap()
+    }
+
+    fn echo_app(batch: Batch) -> App {
+        App::builder()
+            .batch(batch)
+            .get("/whoami", |_ctx: &RequestContext, req: &mut Request| {
+                let user = req.headers().get("x-user").unwrap_or_default().to_vec();
+                async move { Response::ok().body(ResponseBody::Bytes(user)) }
+            })
+            .post("/echo", |_ctx: &RequestContext, req: &mut Request| {
+                let body = match req.body() {
+                    Body::Bytes(bytes) => bytes.clone(),
+                    _ => Vec::new(),
+                };
+                async move {
+                    Response::with_status(StatusCode::CREATED)
+                        .header("content-type", b"application/json".to_vec())
+                        .body(ResponseBody::Bytes(body))
+                }
+            })
+            .build()
+    }
+
+    #[test]
+    fn sub_requests_are_dispatched_in_order() {
+        let app = echo_app(Batch::new("/batch"));
+        let mut req = batch_request(
+            r#"[
+                {"method": "get", "path": "/whoami"},
+                {"method": "POST", "path": "/echo", "body": {"n": 1}},
+                {"method": "GET", "path": "/missing?x=1"},
+                {"method": "BREW", "path": "/coffee"},
+                {"method": "POST", "path": "/batch", "body": []}
+            ]"#,
+        );
+        let response = futures_executor::block_on(app.handle(&test_context(), &mut req));
+        assert_eq!(response.status().as_u16(), 200);
+
+        let results = results(&response);
+        let statuses: Vec<u16> = results.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, vec![200, 201, 404, 400, 400]);
+        assert_eq!(results[0].body, serde_json::json!("alice"));
+        assert_eq!(results[1].body, serde_json::json!({"n": 1}));
+    }
+
+    #[test]
+    fn oversized_batches_are_rejected() {
+        let app = echo_app(Batch::new("/batch").max_requests(1));
+        let mut req = batch_request(
+            r#"[{"method": "GET", "path": "/whoami"}, {"method": "GET", "path": "/whoami"}]"#,
+        );
+        let response = futures_executor::block_on(app.handle(&test_context(), &mut req));
+        assert_eq!(response.status().as_u16(), 413);
+    }
+
+    #[test]
+    fn join_bounded_limits_concurrency() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let futures: Vec<BoxFuture<'static, usize>> = (0..6)
+            .map(|i| {
+                let in_flight = Arc::clone(&in_flight);
+                let peak = Arc::clone(&peak);
+                Box::pin(async move {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    // Yield once so other futures get polled while this one is in flight.
+                    let mut yielded = false;
+                    std::future::poll_fn(|cx| {
+                        if yielded {
+                            Poll::Ready(())
+                        } else {
+                            yielded = true;
+                            cx.waker().wake_by_ref();
+                            Poll::Pending
+                        }
+                    })
+                    .await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    i
+                }) as BoxFuture<'static, usize>
+            })
+            .collect();
+
+        let outputs = futures_executor::block_on(join_bounded(futures, 2));
+        assert_eq!(outputs, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,180 @@
+//! Offloading blocking calls from async code.
+//!
+//! [`run_blocking`] runs a closure on a small pool of worker threads and
+//! resolves with its result, so file system calls made from handlers don't
+//! stall the thread driving other connections. Workers are started on demand
+//! (up to [`MAX_WORKERS`]) and exit after idling for [`IDLE_TIMEOUT`].
+
+use std::collections::VecDeque;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, OnceLock};
+use std::task::{Poll, Waker};
+use std::time::Duration;
+
+use parking_lot::{Condvar, Mutex};
+
+/// Upper bound on concurrently running blocking jobs.
+const MAX_WORKERS: usize = 16;
+
+/// How long an idle worker waits for a job before exiting.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+type Job = Box<dyn FnOnce() + Send>;
+
+struct PoolState {
+    jobs: VecDeque<Job>,
+    workers: usize,
+    idle: usize,
+}
+
+struct Pool {
+    state: Mutex<PoolState>,
+    available: Condvar,
+}
+
+fn pool() -> &'static Pool {
+    static POOL: OnceLock<Pool> = OnceLock::new();
+    POOL.get_or_init(|| Pool {
+        state: Mutex::new(PoolState {
+            jobs: VecDeque::new(),
+            workers: 0,
+            idle: 0,
+        }),
+        available: Condvar::new(),
+    })
+}
+
+impl Pool {
+    fn submit(&'static self, job: Job) {
+        let spawn = {
+            let mut state = self.state.lock();
+            state.jobs.push_back(job);
+            if state.idle == 0 && state.workers < MAX_WORKERS {
+                state.workers += 1;
+                true
+            } else {
+                self.available.notify_one();
+                false
+            }
+        };
+        if !spawn {
+            return;
+        }
+
+        let spawned = std::thread::Builder::new()
+            .name("fastapi-blocking".to_string())
+            .spawn(move || self.work());
+        if spawned.is_err() {
+            let mut state = self.state.lock();
+            state.workers -= 1;
+            if state.workers == 0 {
+                // No worker will ever pick these up: run them here.
+                let jobs: Vec<Job> = state.jobs.drain(..).collect();
+                drop(state);
+                for job in jobs {
+                    job();
+                }
+            }
+        }
+    }
+
+    fn work(&self) {
+        let mut state = self.state.lock();
+        loop {
+            if let Some(job) = state.jobs.pop_front() {
+                drop(state);
+                job();
+                state = self.state.lock();
+                continue;
+            }
+            state.idle += 1;
+            let timed_out = self
+                .available
+                .wait_for(&mut state, IDLE_TIMEOUT)
+                .timed_out();
+            state.idle -= 1;
+            if timed_out && state.jobs.is_empty() {
+                state.workers -= 1;
+                return;
+            }
+        }
+    }
+}
+
+struct Slot<T> {
+    result: Option<std::thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// Run `f` on the blocking pool and wait for its result.
+///
+/// A panic in `f` is resumed in the awaiting task.
+pub(crate) async fn run_blocking<F, T>(f: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let slot = Arc::new(Mutex::new(Slot {
+        result: None,
+        waker: None,
+    }));
+    let job_slot = Arc::clone(&slot);
+    pool().submit(Box::new(move || {
+        let result = std::panic::catch_unwind(AssertUnwindSafe(f));
+        let waker = {
+            let mut slot = job_slot.lock();
+            slot.result = Some(result);
+            slot.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }));
+
+    let result = std::future::poll_fn(|cx| {
+        let mut slot = slot.lock();
+        match slot.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    })
+    .await;
+    match result {
+        Ok(value) => value,
+        Err(payload) => std::panic::resume_unwind(payload),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_jobs_off_the_calling_thread() {
+        let caller = std::thread::current().id();
+        let results = futures_executor::block_on(async {
+            let mut results = Vec::new();
+            for i in 0..4 {
+                results.push(run_blocking(move || (i * 2, std::thread::current().id())).await);
+            }
+            results
+        });
+        for (i, (value, thread)) in results.into_iter().enumerate() {
+            assert_eq!(value, i * 2);
+            assert_ne!(thread, caller);
+        }
+    }
+
+    #[test]
+    fn panics_are_resumed_in_the_caller() {
+        let outcome = std::panic::catch_unwind(|| {
+            futures_executor::block_on(run_blocking(|| -> u8 { panic!("boom") }))
+        });
+        assert!(outcome.is_err());
+        // The pool keeps working afterwards.
+        assert_eq!(futures_executor::block_on(run_blocking(|| 7)), 7);
+    }
+}
@@ -0,0 +1,383 @@
+//! Streaming response bodies fed through a channel.
+//!
+//! [`body_channel`] returns a [`BodySender`] and a [`ChannelBody`]. The body
+//! is a response stream; whatever is sent into the sender is written to the
+//! client as it arrives, and the response ends once every sender is dropped.
+//!
+//! The easiest way to produce output progressively is
+//! [`ChannelBody::with_producer`], which runs the producer future as part of
+//! the response stream itself:
+//!
+//! ```ignore
+//! #[get("/report")]
+//! async fn report() -> Response {
+//!     let body = ChannelBody::with_producer(8, |tx| async move {
+//!         for section in sections() {
+//!             let text = render(section).await;
+//!             if tx.send(text).await.is_err() {
+//!                 return; // client went away
+//!             }
+//!         }
+//!     });
+//!     Response::from_channel(body).header("content-type", b"text/plain".to_vec())
+//! }
+//! ```
+//!
+//! # Backpressure and cancellation
+//!
+//! The channel holds at most `capacity` chunks; [`BodySender::send`] waits
+//! while it is full, so a slow client slows the producer down. When the
+//! client disconnects the server drops the response stream, which closes the
+//! channel: pending and later sends fail with [`BodyClosed`],
+//! [`BodySender::closed`] resolves, and a producer passed to
+//! [`with_producer`](ChannelBody::with_producer) is dropped at its next
+//! await point.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+
+use asupersync::stream::Stream;
+use parking_lot::Mutex;
+
+use crate::response::{IntoResponse, Response};
+
+/// Error returned by [`BodySender`] once the response body has been dropped,
+/// usually because the client disconnected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyClosed;
+
+impl fmt::Display for BodyClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("response body closed")
+    }
+}
+
+impl std::error::Error for BodyClosed {}
+
+struct State {
+    queue: VecDeque<Vec<u8>>,
+    capacity: usize,
+    senders: usize,
+    closed: bool,
+    receiver_waker: Option<Waker>,
+    sender_wakers: Vec<Waker>,
+}
+
+impl State {
+    fn wake_senders(&mut self) {
+        for waker in self.sender_wakers.drain(..) {
+            waker.wake();
+        }
+    }
+
+    fn wake_receiver(&mut self) {
+        if let Some(waker) = self.receiver_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Create a channel whose receiving half is a response body.
+///
+/// `capacity` is the number of chunks buffered before [`BodySender::send`]
+/// waits; it is raised to 1 if zero.
+#[must_use]
+pub fn body_channel(capacity: usize) -> (BodySender, ChannelBody) {
+    let state = Arc::new(Mutex::new(State {
+        queue: VecDeque::new(),
+        capacity: capacity.max(1),
+        senders: 1,
+        closed: false,
+        receiver_waker: None,
+        sender_wakers: Vec::new(),
+    }));
+    (
+        BodySender {
+            state: Arc::clone(&state),
+        },
+        ChannelBody {
+            state,
+            producer: None,
+        },
+    )
+}
+
+/// Sending half of a [`body_channel`]. Clone it to send from several tasks.
+pub struct BodySender {
+    state: Arc<Mutex<State>>,
+}
+
+impl BodySender {
+    /// Send a chunk, waiting while the channel is full.
+    ///
+    /// Empty chunks are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BodyClosed`] if the response body has been dropped.
+    pub async fn send(&self, chunk: impl Into<Vec<u8>>) -> Result<(), BodyClosed> {
+        let mut chunk = Some(chunk.into());
+        std::future::poll_fn(|cx| {
+            let mut state = self.state.lock();
+            if state.closed {
+                return Poll::Ready(Err(BodyClosed));
+            }
+            if state.queue.len() < state.capacity {
+                if let Some(chunk) = chunk.take().filter(|c| !c.is_empty()) {
+                    state.queue.push_back(chunk);
+                    state.wake_receiver();
+                }
+                return Poll::Ready(Ok(()));
+            }
+            state.sender_wakers.push(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Send a chunk without waiting.
+    ///
+    /// # Errors
+    ///
+    /// Returns the chunk in [`TrySendError::Full`] if the channel is at
+    /// capacity, or in [`TrySendError::Closed`] if the body has been dropped.
+    pub fn try_send(&self, chunk: impl Into<Vec<u8>>) -> Result<(), TrySendError> {
+        let chunk = chunk.into();
+        let mut state = self.state.lock();
+        if state.closed {
+            return Err(TrySendError::Closed(chunk));
+        }
+        if state.queue.len() >= state.capacity {
+            return Err(TrySendError::Full(chunk));
+        }
+        if !chunk.is_empty() {
+            state.queue.push_back(chunk);
+            state.wake_receiver();
+        }
+        Ok(())
+    }
+
+    /// Whether the response body has been dropped.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.state.lock().closed
+    }
+
+    /// Wait until the response body is dropped, e.g. to stop background work
+    /// when the client disconnects.
+    pub async fn closed(&self) {
+        std::future::poll_fn(|cx| {
+            let mut state = self.state.lock();
+            if state.closed {
+                Poll::Ready(())
+            } else {
+                state.sender_wakers.push(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+        .await;
+    }
+}
+
+impl Clone for BodySender {
+    fn clone(&self) -> Self {
+        self.state.lock().senders += 1;
+        Self {
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+impl Drop for BodySender {
+    fn drop(&mut self) {
+        let mut state = self.state.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            state.wake_receiver();
+        }
+    }
+}
+
+impl fmt::Debug for BodySender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BodySender")
+            .field("closed", &self.is_closed())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Error returned by [`BodySender::try_send`], carrying the rejected chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrySendError {
+    /// The channel is at capacity.
+    Full(Vec<u8>),
+    /// The response body has been dropped.
+    Closed(Vec<u8>),
+}
+
+impl fmt::Display for TrySendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => f.write_str("response body channel is full"),
+            Self::Closed(_) => f.write_str("response body closed"),
+        }
+    }
+}
+
+impl std::error::Error for TrySendError {}
+
+type Producer = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Receiving half of a [`body_channel`], streamed as a response body.
+///
+/// Dropping it closes the channel.
+pub struct ChannelBody {
+    state: Arc<Mutex<State>>,
+    producer: Option<Producer>,
+}
+
+impl ChannelBody {
+    /// A body fed by `producer`, which is polled by the response stream and
+    /// so runs only while the client is reading.
+    ///
+    /// The body ends when the producer returns and every clone of its sender
+    /// has been dropped.
+    #[must_use]
+    pub fn with_producer<F, Fut>(capacity: usize, producer: F) -> Self
+    where
+        F: FnOnce(BodySender) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let (tx, mut body) = body_channel(capacity);
+        body.producer = Some(Box::pin(producer(tx)));
+        body
+    }
+}
+
+impl Stream for ChannelBody {
+    type Item = Vec<u8>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(producer) = &mut this.producer {
+            if producer.as_mut().poll(cx).is_ready() {
+                this.producer = None;
+            }
+        }
+
+        let mut state = this.state.lock();
+        if let Some(chunk) = state.queue.pop_front() {
+            state.wake_senders();
+            return Poll::Ready(Some(chunk));
+        }
+        if state.senders == 0 {
+            return Poll::Ready(None);
+        }
+        state.receiver_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for ChannelBody {
+    fn drop(&mut self) {
+        // Drop the producer first so its sender is released before senders
+        // waiting on `closed()` resume.
+        self.producer = None;
+        let mut state = self.state.lock();
+        state.closed = true;
+        state.queue.clear();
+        state.wake_senders();
+    }
+}
+
+impl IntoResponse for ChannelBody {
+    fn into_response(self) -> Response {
+        Response::from_channel(self)
+    }
+}
+
+impl fmt::Debug for ChannelBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("ChannelBody")
+            .field("buffered", &state.queue.len())
+            .field("capacity", &state.capacity)
+            .field("producer", &self.producer.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::ResponseBody;
+
+    fn next(body: &mut ChannelBody) -> Option<Vec<u8>> {
+        futures_executor::block_on(std::future::poll_fn(|cx| {
+            Pin::new(&mut *body).poll_next(cx)
+        }))
+    }
+
+    #[test]
+    fn streams_chunks_until_senders_drop() {
+        let (tx, mut body) = body_channel(4);
+        let tx2 = tx.clone();
+        futures_executor::block_on(async {
+            tx.send("a").await.unwrap();
+            tx2.send(b"".to_vec()).await.unwrap();
+            tx2.send("b").await.unwrap();
+        });
+        drop((tx, tx2));
+        assert_eq!(next(&mut body), Some(b"a".to_vec()));
+        assert_eq!(next(&mut body), Some(b"b".to_vec()));
+        assert_eq!(next(&mut body), None);
+    }
+
+    #[test]
+    fn full_channel_applies_backpressure() {
+        let (tx, mut body) = body_channel(1);
+        tx.try_send("a").unwrap();
+        assert_eq!(tx.try_send("b"), Err(TrySendError::Full(b"b".to_vec())));
+        assert_eq!(next(&mut body), Some(b"a".to_vec()));
+        tx.try_send("b").unwrap();
+    }
+
+    #[test]
+    fn dropping_body_closes_channel() {
+        let (tx, body) = body_channel(1);
+        assert!(!tx.is_closed());
+        drop(body);
+        assert!(tx.is_closed());
+        futures_executor::block_on(async {
+            tx.closed().await;
+            assert_eq!(tx.send("late").await, Err(BodyClosed));
+        });
+    }
+
+    #[test]
+    fn producer_runs_as_body_is_read() {
+        let body = ChannelBody::with_producer(1, |tx| async move {
+            for i in 0..3 {
+                if tx.send(format!("{i}\n")).await.is_err() {
+                    return;
+                }
+            }
+        });
+        let response = Response::from_channel(body);
+        let ResponseBody::Stream(mut stream) = response.into_parts().2 else {
+            panic!("expected a streaming body");
+        };
+        let chunks = futures_executor::block_on(async {
+            let mut chunks = Vec::new();
+            while let Some(chunk) = std::future::poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+                chunks.push(String::from_utf8(chunk).unwrap());
+            }
+            chunks
+        });
+        assert_eq!(chunks, ["0\n", "1\n", "2\n"]);
+    }
+}
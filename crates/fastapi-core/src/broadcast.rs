@@ -0,0 +1,632 @@
+//! Fan-out to many connections: broadcast channels and named rooms.
+//!
+//! [`Rooms`] keeps one bounded queue per connected [`Member`]. Members join
+//! and leave rooms by name, and a message sent to a room is cloned into the
+//! queue of every member in it. [`Broadcast`] is the single-topic case.
+//!
+//! ```ignore
+//! let rooms: Rooms<String> = Rooms::new(64);
+//!
+//! let chat = rooms.clone();
+//! let app = App::builder()
+//!     .websocket("/chat", move |_ctx, _req, mut ws| {
+//!         let mut me = chat.connect();
+//!         async move {
+//!             me.join("lobby");
+//!             loop {
+//!                 match me.next_event(&mut ws).await? {
+//!                     RoomEvent::Frame(frame) if frame.opcode == OpCode::Text => {
+//!                         let text = String::from_utf8_lossy(&frame.payload).into_owned();
+//!                         me.send_others("lobby", text);
+//!                     }
+//!                     RoomEvent::Frame(frame) if frame.opcode == OpCode::Close => return Ok(()),
+//!                     RoomEvent::Frame(_) => {}
+//!                     RoomEvent::Message(Ok(text)) => ws.send_text(&text).await?,
+//!                     RoomEvent::Message(Err(RecvError::Lagged(_))) => {}
+//!                     RoomEvent::Message(Err(_)) => return Ok(()),
+//!                 }
+//!             }
+//!         }
+//!     })
+//!     .build();
+//!
+//! // Elsewhere, e.g. from an HTTP handler:
+//! rooms.send("lobby", "server restarting in 5 minutes".to_string());
+//! ```
+//!
+//! # Backpressure
+//!
+//! Sending never waits: each member's queue holds at most `capacity`
+//! messages, so one slow connection cannot stall the others. What happens
+//! when a queue is full is set by [`Overflow`]: by default the oldest
+//! message is dropped and the member's next receive reports how many it
+//! missed ([`RecvError::Lagged`]); [`Overflow::Disconnect`] evicts the member
+//! instead.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+
+use parking_lot::Mutex;
+
+use crate::websocket::{Frame, WebSocket, WebSocketError};
+
+/// What to do when a member's queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// Drop the member's oldest queued message; it sees
+    /// [`RecvError::Lagged`] on its next receive.
+    #[default]
+    DropOldest,
+    /// Remove the member from all rooms; it sees [`RecvError::Evicted`].
+    Disconnect,
+}
+
+/// Error returned when receiving from a [`Member`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvError {
+    /// The member fell behind and this many messages were dropped. Later
+    /// receives continue with the oldest message still queued.
+    Lagged(u64),
+    /// The member fell behind under [`Overflow::Disconnect`] and was removed.
+    Evicted,
+    /// Every [`Rooms`] / [`Broadcast`] handle was dropped and the queue is
+    /// empty.
+    Closed,
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lagged(n) => write!(f, "receiver lagged behind by {n} messages"),
+            Self::Evicted => f.write_str("receiver evicted for falling behind"),
+            Self::Closed => f.write_str("broadcast closed"),
+        }
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+/// Identifies a [`Member`], e.g. to skip the sender in [`Rooms::send_except`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MemberId(u64);
+
+struct Queue<T> {
+    items: VecDeque<T>,
+    lagged: u64,
+    evicted: bool,
+    rooms: BTreeSet<String>,
+    waker: Option<Waker>,
+}
+
+struct Hub<T> {
+    next_id: u64,
+    members: HashMap<MemberId, Queue<T>>,
+    rooms: BTreeMap<String, BTreeSet<MemberId>>,
+    handles: usize,
+}
+
+struct Shared<T> {
+    hub: Mutex<Hub<T>>,
+    capacity: usize,
+    overflow: Overflow,
+}
+
+impl<T: Clone> Shared<T> {
+    /// Queue `message` for each of `ids`; returns how many got it.
+    fn deliver(&self, hub: &mut Hub<T>, ids: &[MemberId], message: &T) -> usize {
+        let mut delivered = 0;
+        let mut evicted = Vec::new();
+        for id in ids {
+            let Some(queue) = hub.members.get_mut(id) else {
+                continue;
+            };
+            if queue.evicted {
+                continue;
+            }
+            if queue.items.len() >= self.capacity {
+                match self.overflow {
+                    Overflow::DropOldest => {
+                        queue.items.pop_front();
+                        queue.lagged += 1;
+                    }
+                    Overflow::Disconnect => {
+                        queue.evicted = true;
+                        queue.items.clear();
+                        evicted.push(*id);
+                        if let Some(waker) = queue.waker.take() {
+                            waker.wake();
+                        }
+                        continue;
+                    }
+                }
+            }
+            queue.items.push_back(message.clone());
+            delivered += 1;
+            if let Some(waker) = queue.waker.take() {
+                waker.wake();
+            }
+        }
+        for id in evicted {
+            hub.leave_all(id);
+        }
+        delivered
+    }
+}
+
+impl<T> Hub<T> {
+    fn room_members(&self, room: &str) -> Vec<MemberId> {
+        self.rooms
+            .get(room)
+            .map(|ids| ids.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    fn leave(&mut self, id: MemberId, room: &str) -> bool {
+        let Some(ids) = self.rooms.get_mut(room) else {
+            return false;
+        };
+        let removed = ids.remove(&id);
+        if ids.is_empty() {
+            self.rooms.remove(room);
+        }
+        if let Some(queue) = self.members.get_mut(&id) {
+            queue.rooms.remove(room);
+        }
+        removed
+    }
+
+    fn leave_all(&mut self, id: MemberId) {
+        let rooms = self
+            .members
+            .get_mut(&id)
+            .map(|queue| std::mem::take(&mut queue.rooms))
+            .unwrap_or_default();
+        for room in rooms {
+            self.leave(id, &room);
+        }
+    }
+}
+
+/// Named rooms of connections with per-connection queues.
+///
+/// Cloning is cheap and clones share the rooms. Once every clone is dropped,
+/// members see [`RecvError::Closed`] after draining their queues.
+pub struct Rooms<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Clone> Rooms<T> {
+    /// Rooms whose members each queue up to `capacity` messages (at least
+    /// 1), dropping the oldest when full.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self::with_overflow(capacity, Overflow::default())
+    }
+
+    /// Like [`new`](Self::new), with an explicit [`Overflow`] policy.
+    #[must_use]
+    pub fn with_overflow(capacity: usize, overflow: Overflow) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                hub: Mutex::new(Hub {
+                    next_id: 0,
+                    members: HashMap::new(),
+                    rooms: BTreeMap::new(),
+                    handles: 1,
+                }),
+                capacity: capacity.max(1),
+                overflow,
+            }),
+        }
+    }
+
+    /// Register a connection. It receives nothing until it joins a room (or
+    /// a message is sent with [`send_all`](Self::send_all)).
+    #[must_use]
+    pub fn connect(&self) -> Member<T> {
+        let mut hub = self.shared.hub.lock();
+        let id = MemberId(hub.next_id);
+        hub.next_id += 1;
+        hub.members.insert(
+            id,
+            Queue {
+                items: VecDeque::new(),
+                lagged: 0,
+                evicted: false,
+                rooms: BTreeSet::new(),
+                waker: None,
+            },
+        );
+        Member {
+            id,
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    /// Send `message` to every member of `room`; returns how many members
+    /// it was queued for.
+    pub fn send(&self, room: &str, message: T) -> usize {
+        let mut hub = self.shared.hub.lock();
+        let ids = hub.room_members(room);
+        self.shared.deliver(&mut hub, &ids, &message)
+    }
+
+    /// Send `message` to every member of `room` except `except`.
+    pub fn send_except(&self, room: &str, message: T, except: MemberId) -> usize {
+        let mut hub = self.shared.hub.lock();
+        let mut ids = hub.room_members(room);
+        ids.retain(|id| *id != except);
+        self.shared.deliver(&mut hub, &ids, &message)
+    }
+
+    /// Send `message` to every connected member, in a room or not.
+    pub fn send_all(&self, message: T) -> usize {
+        let mut hub = self.shared.hub.lock();
+        let ids: Vec<MemberId> = hub.members.keys().copied().collect();
+        self.shared.deliver(&mut hub, &ids, &message)
+    }
+}
+
+impl<T> Rooms<T> {
+    /// Number of members in `room`.
+    #[must_use]
+    pub fn member_count(&self, room: &str) -> usize {
+        self.shared
+            .hub
+            .lock()
+            .rooms
+            .get(room)
+            .map_or(0, BTreeSet::len)
+    }
+
+    /// Names of the rooms that have members, sorted.
+    #[must_use]
+    pub fn room_names(&self) -> Vec<String> {
+        self.shared.hub.lock().rooms.keys().cloned().collect()
+    }
+
+    /// Number of connected members.
+    #[must_use]
+    pub fn connections(&self) -> usize {
+        self.shared.hub.lock().members.len()
+    }
+}
+
+impl<T> Clone for Rooms<T> {
+    fn clone(&self) -> Self {
+        self.shared.hub.lock().handles += 1;
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Drop for Rooms<T> {
+    fn drop(&mut self) {
+        let mut hub = self.shared.hub.lock();
+        hub.handles -= 1;
+        if hub.handles == 0 {
+            for queue in hub.members.values_mut() {
+                if let Some(waker) = queue.waker.take() {
+                    waker.wake();
+                }
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Rooms<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hub = self.shared.hub.lock();
+        f.debug_struct("Rooms")
+            .field("connections", &hub.members.len())
+            .field("rooms", &hub.rooms.len())
+            .field("capacity", &self.shared.capacity)
+            .field("overflow", &self.shared.overflow)
+            .finish()
+    }
+}
+
+/// A single topic: every subscriber receives every message.
+pub struct Broadcast<T> {
+    rooms: Rooms<T>,
+}
+
+impl<T: Clone> Broadcast<T> {
+    /// A broadcast whose subscribers each queue up to `capacity` messages,
+    /// dropping the oldest when full.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            rooms: Rooms::new(capacity),
+        }
+    }
+
+    /// Like [`new`](Self::new), with an explicit [`Overflow`] policy.
+    #[must_use]
+    pub fn with_overflow(capacity: usize, overflow: Overflow) -> Self {
+        Self {
+            rooms: Rooms::with_overflow(capacity, overflow),
+        }
+    }
+
+    /// Add a subscriber.
+    #[must_use]
+    pub fn subscribe(&self) -> Member<T> {
+        self.rooms.connect()
+    }
+
+    /// Send `message` to every subscriber; returns how many got it.
+    pub fn send(&self, message: T) -> usize {
+        self.rooms.send_all(message)
+    }
+}
+
+impl<T> Broadcast<T> {
+    /// Number of subscribers.
+    #[must_use]
+    pub fn subscribers(&self) -> usize {
+        self.rooms.connections()
+    }
+}
+
+impl<T> Clone for Broadcast<T> {
+    fn clone(&self) -> Self {
+        Self {
+            rooms: self.rooms.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Broadcast<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Broadcast")
+            .field("subscribers", &self.subscribers())
+            .finish()
+    }
+}
+
+/// Event returned by [`Member::next_event`].
+#[derive(Debug)]
+pub enum RoomEvent<T> {
+    /// A frame from the WebSocket client.
+    Frame(Frame),
+    /// A message (or receive error) from the member's rooms.
+    Message(Result<T, RecvError>),
+}
+
+/// One connection's membership and message queue.
+///
+/// Dropping it leaves every room.
+pub struct Member<T> {
+    id: MemberId,
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Member<T> {
+    /// This member's ID.
+    #[must_use]
+    pub fn id(&self) -> MemberId {
+        self.id
+    }
+
+    /// Join `room`, creating it if needed. Returns `false` if already a
+    /// member, or if the member has been evicted.
+    pub fn join(&self, room: &str) -> bool {
+        let mut hub = self.shared.hub.lock();
+        let Some(queue) = hub.members.get_mut(&self.id) else {
+            return false;
+        };
+        if queue.evicted || !queue.rooms.insert(room.to_string()) {
+            return false;
+        }
+        hub.rooms
+            .entry(room.to_string())
+            .or_default()
+            .insert(self.id);
+        true
+    }
+
+    /// Leave `room`. Returns `false` if not a member.
+    pub fn leave(&self, room: &str) -> bool {
+        self.shared.hub.lock().leave(self.id, room)
+    }
+
+    /// The rooms this member is in, sorted.
+    #[must_use]
+    pub fn rooms(&self) -> Vec<String> {
+        self.shared
+            .hub
+            .lock()
+            .members
+            .get(&self.id)
+            .map(|queue| queue.rooms.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Wait for the next message.
+    ///
+    /// # Errors
+    ///
+    /// See [`RecvError`].
+    pub async fn recv(&mut self) -> Result<T, RecvError> {
+        std::future::poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Take the next message if one is queued.
+    ///
+    /// # Errors
+    ///
+    /// See [`RecvError`].
+    pub fn try_recv(&mut self) -> Result<Option<T>, RecvError> {
+        let mut hub = self.shared.hub.lock();
+        let handles = hub.handles;
+        let Some(queue) = hub.members.get_mut(&self.id) else {
+            return Err(RecvError::Closed);
+        };
+        take_message(queue, handles)
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError>> {
+        let mut hub = self.shared.hub.lock();
+        let handles = hub.handles;
+        let Some(queue) = hub.members.get_mut(&self.id) else {
+            return Poll::Ready(Err(RecvError::Closed));
+        };
+        match take_message(queue, handles) {
+            Ok(Some(message)) => Poll::Ready(Ok(message)),
+            Err(err) => Poll::Ready(Err(err)),
+            Ok(None) => {
+                queue.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Wait for whichever comes first: a frame from `ws` or a message for
+    /// this member. Neither is lost when the other wins.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`WebSocketError`] from reading `ws`.
+    pub async fn next_event(&mut self, ws: &mut WebSocket) -> Result<RoomEvent<T>, WebSocketError> {
+        let mut read = pin!(ws.read_frame());
+        std::future::poll_fn(|cx| {
+            if let Poll::Ready(message) = self.poll_recv(cx) {
+                return Poll::Ready(Ok(RoomEvent::Message(message)));
+            }
+            read.as_mut()
+                .poll(cx)
+                .map(|frame| frame.map(RoomEvent::Frame))
+        })
+        .await
+    }
+}
+
+impl<T: Clone> Member<T> {
+    /// Send `message` to the other members of `room`.
+    pub fn send_others(&self, room: &str, message: T) -> usize {
+        let mut hub = self.shared.hub.lock();
+        let mut ids = hub.room_members(room);
+        ids.retain(|id| *id != self.id);
+        self.shared.deliver(&mut hub, &ids, &message)
+    }
+}
+
+fn take_message<T>(queue: &mut Queue<T>, handles: usize) -> Result<Option<T>, RecvError> {
+    if queue.evicted {
+        return Err(RecvError::Evicted);
+    }
+    if queue.lagged > 0 {
+        return Err(RecvError::Lagged(std::mem::take(&mut queue.lagged)));
+    }
+    match queue.items.pop_front() {
+        Some(message) => Ok(Some(message)),
+        None if handles == 0 => Err(RecvError::Closed),
+        None => Ok(None),
+    }
+}
+
+impl<T> Drop for Member<T> {
+    fn drop(&mut self) {
+        let mut hub = self.shared.hub.lock();
+        hub.leave_all(self.id);
+        hub.members.remove(&self.id);
+    }
+}
+
+impl<T> fmt::Debug for Member<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Member")
+            .field("id", &self.id)
+            .field("rooms", &self.rooms())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rooms_route_messages_to_members() {
+        let rooms: Rooms<&str> = Rooms::new(8);
+        let mut alice = rooms.connect();
+        let mut bob = rooms.connect();
+        assert!(alice.join("rust"));
+        assert!(!alice.join("rust"));
+        assert!(bob.join("rust"));
+        assert!(bob.join("go"));
+        assert_eq!(rooms.room_names(), ["go", "rust"]);
+        assert_eq!(rooms.member_count("rust"), 2);
+
+        assert_eq!(rooms.send("rust", "hello"), 2);
+        assert_eq!(rooms.send("go", "gopher"), 1);
+        assert_eq!(alice.send_others("rust", "from alice"), 1);
+        assert_eq!(rooms.send_except("rust", "not bob", bob.id()), 1);
+        assert_eq!(rooms.send("empty", "nobody"), 0);
+
+        assert_eq!(alice.try_recv(), Ok(Some("hello")));
+        assert_eq!(alice.try_recv(), Ok(Some("not bob")));
+        assert_eq!(alice.try_recv(), Ok(None));
+        let got: Vec<_> = std::iter::from_fn(|| bob.try_recv().unwrap()).collect();
+        assert_eq!(got, ["hello", "gopher", "from alice"]);
+
+        assert!(bob.leave("go"));
+        assert!(!bob.leave("go"));
+        assert_eq!(rooms.room_names(), ["rust"]);
+        drop(bob);
+        assert_eq!(rooms.member_count("rust"), 1);
+        assert_eq!(rooms.connections(), 1);
+    }
+
+    #[test]
+    fn slow_members_lag_or_get_evicted() {
+        let broadcast = Broadcast::new(2);
+        let mut slow = broadcast.subscribe();
+        for i in 0..5 {
+            assert_eq!(broadcast.send(i), 1);
+        }
+        assert_eq!(slow.try_recv(), Err(RecvError::Lagged(3)));
+        assert_eq!(slow.try_recv(), Ok(Some(3)));
+        assert_eq!(slow.try_recv(), Ok(Some(4)));
+
+        let rooms = Rooms::with_overflow(1, Overflow::Disconnect);
+        let mut slow = rooms.connect();
+        let mut fast = rooms.connect();
+        slow.join("feed");
+        fast.join("feed");
+        assert_eq!(rooms.send("feed", 1), 2);
+        assert_eq!(fast.try_recv(), Ok(Some(1)));
+        assert_eq!(rooms.send("feed", 2), 1);
+        assert_eq!(slow.try_recv(), Err(RecvError::Evicted));
+        assert_eq!(rooms.member_count("feed"), 1);
+        assert!(!slow.join("feed"));
+        assert_eq!(fast.try_recv(), Ok(Some(2)));
+    }
+
+    #[test]
+    fn recv_wakes_on_send_and_ends_when_closed() {
+        let broadcast = Broadcast::new(4);
+        let mut member = broadcast.subscribe();
+        let sender = broadcast.clone();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            sender.send("late".to_string());
+        });
+        assert_eq!(futures_executor::block_on(member.recv()).unwrap(), "late");
+        handle.join().unwrap();
+
+        broadcast.send("queued".to_string());
+        drop(broadcast);
+        assert_eq!(futures_executor::block_on(member.recv()).unwrap(), "queued");
+        assert_eq!(
+            futures_executor::block_on(member.recv()),
+            Err(RecvError::Closed)
+        );
+    }
+}
@@ -0,0 +1,386 @@
+//! Concurrency limits (bulkheads).
+//!
+//! A [`Bulkhead`] caps how many requests run at once. Requests over the cap
+//! wait in a FIFO queue of bounded depth; once the queue is full, further
+//! requests are shed with a 503 instead of piling up. Giving slow routes
+//! their own bulkhead keeps one stalled dependency from tying up every
+//! worker:
+//!
+//! ```ignore
+//! let bulkheads = BulkheadMiddleware::new()
+//!     .global(Bulkhead::new(512).max_queued(1024))
+//!     .route("/reports/*", Bulkhead::new(4).max_queued(8));
+//!
+//! let app = App::builder().middleware(bulkheads).build();
+//! ```
+//!
+//! A request holds its slot until the handler returns, or until it is
+//! dropped if it never completes. Queued requests that are cancelled leave
+//! the queue.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll, Waker};
+
+use parking_lot::Mutex;
+
+use crate::context::RequestContext;
+use crate::error::HttpError;
+use crate::middleware::{BoxFuture, ControlFlow, Middleware, path_matches_pattern};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response, StatusCode};
+
+/// An async semaphore with a bounded wait queue.
+///
+/// Clones share the same slots.
+#[derive(Clone)]
+pub struct Bulkhead {
+    max_in_flight: usize,
+    max_queued: usize,
+    state: Arc<Mutex<BulkheadState>>,
+    rejected: Arc<AtomicU64>,
+}
+
+struct BulkheadState {
+    in_flight: usize,
+    next_ticket: u64,
+    waiters: VecDeque<Waiter>,
+}
+
+struct Waiter {
+    ticket: u64,
+    waker: Option<Waker>,
+    granted: bool,
+}
+
+impl Bulkhead {
+    /// Allow at most `max_in_flight` concurrent requests, with no queue.
+    #[must_use]
+    pub fn new(max_in_flight: usize) -> Self {
+        Self {
+            max_in_flight,
+            max_queued: 0,
+            state: Arc::new(Mutex::new(BulkheadState {
+                in_flight: 0,
+                next_ticket: 0,
+                waiters: VecDeque::new(),
+            })),
+            rejected: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Let up to `max_queued` requests wait for a slot before shedding.
+    #[must_use]
+    pub fn max_queued(mut self, max_queued: usize) -> Self {
+        self.max_queued = max_queued;
+        self
+    }
+
+    /// Requests currently holding a slot.
+    #[must_use]
+    pub fn in_flight(&self) -> usize {
+        self.state.lock().in_flight
+    }
+
+    /// Requests currently waiting for a slot.
+    #[must_use]
+    pub fn queued(&self) -> usize {
+        self.state.lock().waiters.len()
+    }
+
+    /// Requests shed because the queue was full.
+    #[must_use]
+    pub fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    /// Wait for a slot, or return `None` at once if the queue is full.
+    pub async fn acquire(&self) -> Option<BulkheadPermit> {
+        let ticket = {
+            let mut state = self.state.lock();
+            if state.waiters.is_empty() && state.in_flight < self.max_in_flight {
+                state.in_flight += 1;
+                return Some(self.permit());
+            }
+            if state.waiters.len() >= self.max_queued {
+                drop(state);
+                self.rejected.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+            let ticket = state.next_ticket;
+            state.next_ticket += 1;
+            state.waiters.push_back(Waiter {
+                ticket,
+                waker: None,
+                granted: false,
+            });
+            ticket
+        };
+        WaitForSlot {
+            state: &self.state,
+            ticket,
+            done: false,
+        }
+        .await;
+        Some(self.permit())
+    }
+
+    fn permit(&self) -> BulkheadPermit {
+        BulkheadPermit {
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+impl std::fmt::Debug for Bulkhead {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Bulkhead")
+            .field("max_in_flight", &self.max_in_flight)
+            .field("max_queued", &self.max_queued)
+            .field("in_flight", &self.in_flight())
+            .field("queued", &self.queued())
+            .finish()
+    }
+}
+
+/// Hands a freed slot to the first waiter that has not been granted one,
+/// or returns it to the pool.
+fn release(state: &Mutex<BulkheadState>) {
+    let mut state = state.lock();
+    if let Some(waiter) = state.waiters.iter_mut().find(|w| !w.granted) {
+        waiter.granted = true;
+        if let Some(waker) = waiter.waker.take() {
+            waker.wake();
+        }
+    } else {
+        state.in_flight -= 1;
+    }
+}
+
+/// Resolves once the queued ticket has been granted a slot.
+struct WaitForSlot<'a> {
+    state: &'a Mutex<BulkheadState>,
+    ticket: u64,
+    done: bool,
+}
+
+impl Future for WaitForSlot<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock();
+        let Some(index) = state.waiters.iter().position(|w| w.ticket == self.ticket) else {
+            return Poll::Pending;
+        };
+        if state.waiters[index].granted {
+            state.waiters.remove(index);
+            drop(state);
+            self.done = true;
+            return Poll::Ready(());
+        }
+        state.waiters[index].waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for WaitForSlot<'_> {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let granted = {
+            let mut state = self.state.lock();
+            let index = state.waiters.iter().position(|w| w.ticket == self.ticket);
+            index
+                .and_then(|index| state.waiters.remove(index))
+                .is_some_and(|waiter| waiter.granted)
+        };
+        // A slot handed to a cancelled waiter goes to the next one.
+        if granted {
+            release(self.state);
+        }
+    }
+}
+
+/// A held bulkhead slot, released on drop.
+pub struct BulkheadPermit {
+    state: Arc<Mutex<BulkheadState>>,
+}
+
+impl Drop for BulkheadPermit {
+    fn drop(&mut self) {
+        release(&self.state);
+    }
+}
+
+impl std::fmt::Debug for BulkheadPermit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BulkheadPermit").finish_non_exhaustive()
+    }
+}
+
+/// Request extension holding the slot until the `after` hook runs.
+struct HeldPermit(Mutex<Option<BulkheadPermit>>);
+
+/// Middleware that limits in-flight requests with [`Bulkhead`]s.
+///
+/// A request uses the bulkhead of the first route pattern matching its path,
+/// otherwise the global one; requests with neither are not limited. Shed
+/// requests get a 503.
+#[derive(Debug, Default)]
+pub struct BulkheadMiddleware {
+    global: Option<Bulkhead>,
+    routes: Vec<(String, Bulkhead)>,
+}
+
+impl BulkheadMiddleware {
+    /// Create a middleware that limits nothing until bulkheads are added.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit every request not covered by a route bulkhead.
+    #[must_use]
+    pub fn global(mut self, bulkhead: Bulkhead) -> Self {
+        self.global = Some(bulkhead);
+        self
+    }
+
+    /// Limit paths matching `pattern` (`*` wildcards) with their own bulkhead.
+    ///
+    /// The first matching pattern wins.
+    #[must_use]
+    pub fn route(mut self, pattern: impl Into<String>, bulkhead: Bulkhead) -> Self {
+        self.routes.push((pattern.into(), bulkhead));
+        self
+    }
+
+    fn bulkhead_for(&self, path: &str) -> Option<&Bulkhead> {
+        self.routes
+            .iter()
+            .find(|(pattern, _)| path_matches_pattern(path, pattern))
+            .map(|(_, bulkhead)| bulkhead)
+            .or(self.global.as_ref())
+    }
+}
+
+impl Middleware for BulkheadMiddleware {
+    fn before<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        Box::pin(async move {
+            let Some(bulkhead) = self.bulkhead_for(req.path()) else {
+                return ControlFlow::Continue;
+            };
+            match bulkhead.acquire().await {
+                Some(permit) => {
+                    req.insert_extension(HeldPermit(Mutex::new(Some(permit))));
+                    ControlFlow::Continue
+                }
+                None => ControlFlow::Break(
+                    HttpError::new(StatusCode::SERVICE_UNAVAILABLE)
+                        .with_detail("Server is at capacity")
+                        .into_response(),
+                ),
+            }
+        })
+    }
+
+    fn after<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a Request,
+        response: Response,
+    ) -> BoxFuture<'a, Response> {
+        if let Some(held) = req.get_extension::<HeldPermit>() {
+            held.0.lock().take();
+        }
+        Box::pin(async move { response })
+    }
+
+    fn name(&self) -> &'static str {
+        "Bulkhead"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Method;
+
+    fn poll_once<F: Future>(future: Pin<&mut F>) -> Poll<F::Output> {
+        let waker = Waker::noop();
+        future.poll(&mut Context::from_waker(waker))
+    }
+
+    #[test]
+    fn queued_requests_get_freed_slots_in_order() {
+        let bulkhead = Bulkhead::new(1).max_queued(2);
+        let first = futures_executor::block_on(bulkhead.acquire()).unwrap();
+
+        let mut second = std::pin::pin!(bulkhead.acquire());
+        let mut third = std::pin::pin!(bulkhead.acquire());
+        assert!(poll_once(second.as_mut()).is_pending());
+        assert!(poll_once(third.as_mut()).is_pending());
+        assert_eq!(bulkhead.queued(), 2);
+
+        // The queue is full, so the next request is shed.
+        assert!(futures_executor::block_on(bulkhead.acquire()).is_none());
+        assert_eq!(bulkhead.rejected(), 1);
+
+        drop(first);
+        assert!(poll_once(third.as_mut()).is_pending());
+        let Poll::Ready(Some(second)) = poll_once(second.as_mut()) else {
+            panic!("the first waiter should get the freed slot");
+        };
+        assert_eq!(bulkhead.in_flight(), 1);
+        drop(second);
+        assert!(matches!(poll_once(third.as_mut()), Poll::Ready(Some(_))));
+    }
+
+    #[test]
+    fn cancelled_waiters_pass_their_slot_on() {
+        let bulkhead = Bulkhead::new(1).max_queued(2);
+        let first = futures_executor::block_on(bulkhead.acquire()).unwrap();
+        let mut second = Box::pin(bulkhead.acquire());
+        let mut third = std::pin::pin!(bulkhead.acquire());
+        assert!(poll_once(second.as_mut()).is_pending());
+        assert!(poll_once(third.as_mut()).is_pending());
+
+        drop(first);
+        drop(second);
+        assert!(matches!(poll_once(third.as_mut()), Poll::Ready(Some(_))));
+        assert_eq!(bulkhead.queued(), 0);
+    }
+
+    #[test]
+    fn middleware_sheds_with_503_and_releases_after_the_handler() {
+        let reports = Bulkhead::new(1);
+        let mw = BulkheadMiddleware::new().route("/reports/*", reports.clone());
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+
+        let mut running = Request::new(Method::Get, "/reports/daily");
+        let flow = futures_executor::block_on(mw.before(&ctx, &mut running));
+        assert!(flow.is_continue());
+        assert_eq!(reports.in_flight(), 1);
+
+        let mut shed = Request::new(Method::Get, "/reports/weekly");
+        let ControlFlow::Break(response) = futures_executor::block_on(mw.before(&ctx, &mut shed))
+        else {
+            panic!("a full bulkhead should shed the request");
+        };
+        assert_eq!(response.status().as_u16(), 503);
+
+        let mut other = Request::new(Method::Get, "/health");
+        assert!(futures_executor::block_on(mw.before(&ctx, &mut other)).is_continue());
+
+        let _ = futures_executor::block_on(mw.after(&ctx, &running, Response::ok()));
+        assert_eq!(reports.in_flight(), 0);
+    }
+}
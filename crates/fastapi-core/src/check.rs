@@ -0,0 +1,200 @@
+//! Startup configuration checks.
+//!
+//! [`App::check`](crate::App::check) inspects a fully built app — routes,
+//! middleware, shared state and the generated OpenAPI document — without
+//! binding a socket, and returns a [`CheckReport`]. A `--check` CI gate
+//! typically builds the app, prints the report and exits non-zero when
+//! [`CheckReport::is_ok`] is false:
+//!
+//! ```ignore
+//! let app = build_app();
+//! if std::env::args().any(|a| a == "--check") {
+//!     let report = app.check();
+//!     println!("{report}");
+//!     std::process::exit(i32::from(!report.is_ok()));
+//! }
+//! ```
+//!
+//! Middleware can contribute findings by overriding
+//! [`Middleware::check`](crate::middleware::Middleware::check).
+
+use std::fmt;
+
+use crate::dependency_graph::DependencyTree;
+use crate::request::Method;
+
+/// How serious a [`CheckIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CheckSeverity {
+    /// Suspicious but workable configuration.
+    Warning,
+    /// Configuration that will fail at runtime.
+    Error,
+}
+
+impl CheckSeverity {
+    /// Returns a lowercase label (`warning` or `error`).
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// A single configuration finding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckIssue {
+    /// Severity.
+    pub severity: CheckSeverity,
+    /// Area the finding belongs to, e.g. `state`, `cors` or `limits`.
+    pub category: String,
+    /// Human-readable description.
+    pub message: String,
+}
+
+impl CheckIssue {
+    /// Creates an error finding.
+    #[must_use]
+    pub fn error(category: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: CheckSeverity::Error,
+            category: category.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Creates a warning finding.
+    #[must_use]
+    pub fn warning(category: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: CheckSeverity::Warning,
+            category: category.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for CheckIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} [{}] {}",
+            self.severity.as_str(),
+            self.category,
+            self.message
+        )
+    }
+}
+
+/// Result of [`App::check`](crate::App::check).
+#[derive(Debug, Clone)]
+pub struct CheckReport {
+    /// Application name.
+    pub app_name: String,
+    /// Application version.
+    pub version: String,
+    /// Registered HTTP routes.
+    pub routes: Vec<(Method, String)>,
+    /// Number of websocket routes.
+    pub websocket_routes: usize,
+    /// Middleware names in execution order.
+    pub middleware: Vec<String>,
+    /// Number of shared state values.
+    pub state_count: usize,
+    /// Size in bytes of the generated OpenAPI document, if enabled.
+    pub openapi_bytes: Option<usize>,
+    /// Dependency tree of every route that extracts `Depends<T>`.
+    pub dependency_trees: Vec<DependencyTree>,
+    /// Dependency cycles, as type names ending with the repeated one.
+    pub dependency_cycles: Vec<Vec<String>>,
+    /// Findings, errors first.
+    pub issues: Vec<CheckIssue>,
+}
+
+impl CheckReport {
+    /// Returns true if there are no error findings.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Returns the error findings.
+    pub fn errors(&self) -> impl Iterator<Item = &CheckIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == CheckSeverity::Error)
+    }
+
+    /// Returns the warning findings.
+    pub fn warnings(&self) -> impl Iterator<Item = &CheckIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == CheckSeverity::Warning)
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}", self.app_name, self.version)?;
+        writeln!(
+            f,
+            "routes: {} (+{} websocket)",
+            self.routes.len(),
+            self.websocket_routes
+        )?;
+        writeln!(f, "middleware: {}", self.middleware.len())?;
+        writeln!(f, "state values: {}", self.state_count)?;
+        match self.openapi_bytes {
+            Some(bytes) => writeln!(f, "openapi: {bytes} bytes")?,
+            None => writeln!(f, "openapi: disabled")?,
+        }
+        for issue in &self.issues {
+            writeln!(f, "{issue}")?;
+        }
+        let errors = self.errors().count();
+        let warnings = self.warnings().count();
+        write!(
+            f,
+            "{}: {errors} error(s), {warnings} warning(s)",
+            if errors == 0 { "ok" } else { "failed" }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(issues: Vec<CheckIssue>) -> CheckReport {
+        CheckReport {
+            app_name: "api".into(),
+            version: "1.0.0".into(),
+            routes: vec![(Method::Get, "/".into())],
+            websocket_routes: 0,
+            middleware: Vec::new(),
+            state_count: 0,
+            openapi_bytes: None,
+            dependency_trees: Vec::new(),
+            dependency_cycles: Vec::new(),
+            issues,
+        }
+    }
+
+    #[test]
+    fn warnings_do_not_fail_the_check() {
+        let report = report(vec![CheckIssue::warning("cors", "wide open")]);
+        assert!(report.is_ok());
+        assert_eq!(report.warnings().count(), 1);
+    }
+
+    #[test]
+    fn display_summarizes_findings() {
+        let report = report(vec![CheckIssue::error("state", "missing Db")]);
+        assert!(!report.is_ok());
+        let text = report.to_string();
+        assert!(text.contains("error [state] missing Db"));
+        assert!(text.ends_with("failed: 1 error(s), 0 warning(s)"));
+    }
+}
@@ -0,0 +1,554 @@
+//! Circuit breakers for routes that call failing upstreams.
+//!
+//! A [`CircuitBreaker`] watches the outcomes of the last requests through it.
+//! When the share of failures (5xx responses by default) reaches the
+//! threshold, it *opens* and answers further requests with a 503 straight
+//! away, without running the handler, giving the upstream time to recover.
+//! After the cool-down it turns *half-open* and lets a few probe requests
+//! through: if they all succeed it closes again, and if one fails it reopens.
+//!
+//! ```ignore
+//! let payments = CircuitBreaker::new()
+//!     .failure_rate(0.5)
+//!     .min_requests(20)
+//!     .open_for(Duration::from_secs(30))
+//!     .on_transition(|from, to| metrics.breaker_changed("payments", from, to));
+//!
+//! let app = App::builder()
+//!     .middleware(CircuitBreakerMiddleware::new().route("/payments/*", payments))
+//!     .build();
+//! ```
+//!
+//! A request that never reaches the `after` hook (a panic or a dropped
+//! request) counts as a failure.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::context::RequestContext;
+use crate::error::HttpError;
+use crate::middleware::{BoxFuture, ControlFlow, Middleware, path_matches_pattern};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response, StatusCode};
+
+/// State of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Requests flow normally and their outcomes are recorded.
+    Closed,
+    /// Requests are rejected until the cool-down ends.
+    Open,
+    /// A limited number of probe requests decide whether to close again.
+    HalfOpen,
+}
+
+impl BreakerState {
+    /// Lowercase name, for metrics labels.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::Open => "open",
+            Self::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// Snapshot of a breaker's counters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerStats {
+    /// Current state.
+    pub state: BreakerState,
+    /// Requests in the current window.
+    pub window_requests: usize,
+    /// Failures in the current window.
+    pub window_failures: usize,
+    /// Requests rejected while open or half-open.
+    pub rejected: u64,
+}
+
+type TransitionHook = Arc<dyn Fn(BreakerState, BreakerState) + Send + Sync>;
+type FailurePredicate = Arc<dyn Fn(&Response) -> bool + Send + Sync>;
+
+/// A failure-rate circuit breaker.
+///
+/// Clones share the same state.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    failure_rate: f64,
+    min_requests: usize,
+    window_size: usize,
+    open_for: Duration,
+    half_open_probes: u32,
+    is_failure: FailurePredicate,
+    on_transition: Option<TransitionHook>,
+    state: Arc<Mutex<BreakerInner>>,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: BreakerState,
+    /// Recent outcomes while closed; `true` marks a failure.
+    outcomes: VecDeque<bool>,
+    opened_at: Option<Instant>,
+    probes_in_flight: u32,
+    probe_successes: u32,
+    rejected: u64,
+}
+
+/// Whether a request may proceed, and as what.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Admission {
+    Normal,
+    Probe,
+    Rejected { retry_after: Duration },
+}
+
+impl CircuitBreaker {
+    /// A breaker that opens at a 50% failure rate over the last 20 requests
+    /// (once 10 have been seen), stays open for 30 seconds and closes after
+    /// one successful probe.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            failure_rate: 0.5,
+            min_requests: 10,
+            window_size: 20,
+            open_for: Duration::from_secs(30),
+            half_open_probes: 1,
+            is_failure: Arc::new(|response: &Response| response.status().as_u16() >= 500),
+            on_transition: None,
+            state: Arc::new(Mutex::new(BreakerInner {
+                state: BreakerState::Closed,
+                outcomes: VecDeque::new(),
+                opened_at: None,
+                probes_in_flight: 0,
+                probe_successes: 0,
+                rejected: 0,
+            })),
+        }
+    }
+
+    /// Open when at least this share of windowed requests failed (0.0-1.0).
+    #[must_use]
+    pub fn failure_rate(mut self, rate: f64) -> Self {
+        self.failure_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Requests the window must hold before the rate is judged.
+    #[must_use]
+    pub fn min_requests(mut self, min_requests: usize) -> Self {
+        self.min_requests = min_requests.max(1);
+        self
+    }
+
+    /// Number of recent requests the failure rate is computed over.
+    #[must_use]
+    pub fn window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size.max(1);
+        self
+    }
+
+    /// How long to reject requests before probing.
+    #[must_use]
+    pub fn open_for(mut self, open_for: Duration) -> Self {
+        self.open_for = open_for;
+        self
+    }
+
+    /// Probe requests that must all succeed to close again.
+    #[must_use]
+    pub fn half_open_probes(mut self, probes: u32) -> Self {
+        self.half_open_probes = probes.max(1);
+        self
+    }
+
+    /// Decide which responses count as failures (default: any 5xx).
+    #[must_use]
+    pub fn is_failure(
+        mut self,
+        predicate: impl Fn(&Response) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.is_failure = Arc::new(predicate);
+        self
+    }
+
+    /// Called with the old and new state on every transition, e.g. to update
+    /// a metrics gauge.
+    #[must_use]
+    pub fn on_transition(
+        mut self,
+        hook: impl Fn(BreakerState, BreakerState) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_transition = Some(Arc::new(hook));
+        self
+    }
+
+    /// The current state.
+    #[must_use]
+    pub fn state(&self) -> BreakerState {
+        self.state.lock().state
+    }
+
+    /// The current counters.
+    #[must_use]
+    pub fn stats(&self) -> BreakerStats {
+        let inner = self.state.lock();
+        BreakerStats {
+            state: inner.state,
+            window_requests: inner.outcomes.len(),
+            window_failures: inner.outcomes.iter().filter(|failed| **failed).count(),
+            rejected: inner.rejected,
+        }
+    }
+
+    fn admit(&self, now: Instant) -> Admission {
+        let mut transition = None;
+        let admission = {
+            let mut inner = self.state.lock();
+            if inner.state == BreakerState::Open {
+                let reopens_at = inner.opened_at.map_or(now, |at| at + self.open_for);
+                if now < reopens_at {
+                    inner.rejected += 1;
+                    return Admission::Rejected {
+                        retry_after: reopens_at - now,
+                    };
+                }
+                transition = Some(inner.set_state(BreakerState::HalfOpen));
+            }
+            match inner.state {
+                BreakerState::Closed => Admission::Normal,
+                _ if inner.probes_in_flight + inner.probe_successes < self.half_open_probes => {
+                    inner.probes_in_flight += 1;
+                    Admission::Probe
+                }
+                _ => {
+                    inner.rejected += 1;
+                    Admission::Rejected {
+                        retry_after: Duration::ZERO,
+                    }
+                }
+            }
+        };
+        self.notify(transition);
+        admission
+    }
+
+    fn record(&self, admission: Admission, failed: bool, now: Instant) {
+        let transition = {
+            let mut inner = self.state.lock();
+            match admission {
+                Admission::Probe => {
+                    inner.probes_in_flight -= 1;
+                    if inner.state != BreakerState::HalfOpen {
+                        None
+                    } else if failed {
+                        inner.opened_at = Some(now);
+                        Some(inner.set_state(BreakerState::Open))
+                    } else {
+                        inner.probe_successes += 1;
+                        (inner.probe_successes >= self.half_open_probes)
+                            .then(|| inner.set_state(BreakerState::Closed))
+                    }
+                }
+                Admission::Normal if inner.state == BreakerState::Closed => {
+                    inner.outcomes.push_back(failed);
+                    while inner.outcomes.len() > self.window_size {
+                        inner.outcomes.pop_front();
+                    }
+                    if self.should_open(&inner.outcomes) {
+                        inner.opened_at = Some(now);
+                        Some(inner.set_state(BreakerState::Open))
+                    } else {
+                        None
+                    }
+                }
+                // Outcomes of requests admitted before the breaker opened.
+                Admission::Normal | Admission::Rejected { .. } => None,
+            }
+        };
+        self.notify(transition);
+    }
+
+    #[allow(clippy::cast_precision_loss)] // window sizes are small
+    fn should_open(&self, outcomes: &VecDeque<bool>) -> bool {
+        if outcomes.len() < self.min_requests {
+            return false;
+        }
+        let failures = outcomes.iter().filter(|failed| **failed).count();
+        failures as f64 / outcomes.len() as f64 >= self.failure_rate
+    }
+
+    fn notify(&self, transition: Option<(BreakerState, BreakerState)>) {
+        if let (Some((from, to)), Some(hook)) = (transition, &self.on_transition) {
+            hook(from, to);
+        }
+    }
+}
+
+impl BreakerInner {
+    /// Moves to `state`, resetting the counters that belong to the old one.
+    fn set_state(&mut self, state: BreakerState) -> (BreakerState, BreakerState) {
+        let from = std::mem::replace(&mut self.state, state);
+        self.outcomes.clear();
+        self.probe_successes = 0;
+        (from, state)
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("failure_rate", &self.failure_rate)
+            .field("min_requests", &self.min_requests)
+            .field("window_size", &self.window_size)
+            .field("open_for", &self.open_for)
+            .field("half_open_probes", &self.half_open_probes)
+            .field("stats", &self.stats())
+            .finish_non_exhaustive()
+    }
+}
+
+/// An admitted request whose outcome has not been recorded yet.
+///
+/// Dropped without a response, it records a failure.
+struct BreakerCall {
+    breaker: CircuitBreaker,
+    admission: Admission,
+    recorded: bool,
+}
+
+impl BreakerCall {
+    fn finish(mut self, failed: bool) {
+        self.breaker.record(self.admission, failed, Instant::now());
+        self.recorded = true;
+    }
+}
+
+impl Drop for BreakerCall {
+    fn drop(&mut self) {
+        if !self.recorded {
+            self.breaker.record(self.admission, true, Instant::now());
+        }
+    }
+}
+
+/// Request extension carrying the call from `before` to `after`.
+struct PendingCall(Mutex<Option<BreakerCall>>);
+
+/// Middleware that guards routes with [`CircuitBreaker`]s.
+///
+/// A request uses the breaker of the first route pattern matching its path,
+/// otherwise the global one; requests with neither pass through. Rejected
+/// requests get a 503 with `Retry-After`.
+#[derive(Debug, Default)]
+pub struct CircuitBreakerMiddleware {
+    global: Option<CircuitBreaker>,
+    routes: Vec<(String, CircuitBreaker)>,
+}
+
+impl CircuitBreakerMiddleware {
+    /// Create a middleware that guards nothing until breakers are added.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Guard every request not covered by a route breaker.
+    #[must_use]
+    pub fn global(mut self, breaker: CircuitBreaker) -> Self {
+        self.global = Some(breaker);
+        self
+    }
+
+    /// Guard paths matching `pattern` (`*` wildcards) with their own breaker.
+    ///
+    /// The first matching pattern wins.
+    #[must_use]
+    pub fn route(mut self, pattern: impl Into<String>, breaker: CircuitBreaker) -> Self {
+        self.routes.push((pattern.into(), breaker));
+        self
+    }
+
+    fn breaker_for(&self, path: &str) -> Option<&CircuitBreaker> {
+        self.routes
+            .iter()
+            .find(|(pattern, _)| path_matches_pattern(path, pattern))
+            .map(|(_, breaker)| breaker)
+            .or(self.global.as_ref())
+    }
+}
+
+impl Middleware for CircuitBreakerMiddleware {
+    fn before<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        let Some(breaker) = self.breaker_for(req.path()) else {
+            return Box::pin(async { ControlFlow::Continue });
+        };
+        let flow = match breaker.admit(Instant::now()) {
+            Admission::Rejected { retry_after } => {
+                let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+                ControlFlow::Break(
+                    HttpError::new(StatusCode::SERVICE_UNAVAILABLE)
+                        .with_detail("Circuit breaker is open")
+                        .with_header("retry-after", secs.max(1).to_string().into_bytes())
+                        .into_response(),
+                )
+            }
+            admission => {
+                let call = BreakerCall {
+                    breaker: breaker.clone(),
+                    admission,
+                    recorded: false,
+                };
+                req.insert_extension(PendingCall(Mutex::new(Some(call))));
+                ControlFlow::Continue
+            }
+        };
+        Box::pin(async move { flow })
+    }
+
+    fn after<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a Request,
+        response: Response,
+    ) -> BoxFuture<'a, Response> {
+        let call = req
+            .get_extension::<PendingCall>()
+            .and_then(|pending| pending.0.lock().take());
+        if let Some(call) = call {
+            let failed = (call.breaker.is_failure)(&response);
+            call.finish(failed);
+        }
+        Box::pin(async move { response })
+    }
+
+    fn name(&self) -> &'static str {
+        "CircuitBreaker"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Method;
+
+    fn failing_breaker() -> CircuitBreaker {
+        CircuitBreaker::new()
+            .failure_rate(0.5)
+            .min_requests(4)
+            .window_size(4)
+            .open_for(Duration::from_secs(10))
+    }
+
+    #[test]
+    fn opens_at_the_failure_rate_and_recovers_through_a_probe() {
+        let transitions = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&transitions);
+        let breaker = failing_breaker()
+            .on_transition(move |from, to| seen.lock().push((from.as_str(), to.as_str())));
+        let start = Instant::now();
+
+        for failed in [false, true, false, true] {
+            let admission = breaker.admit(start);
+            assert_eq!(admission, Admission::Normal);
+            breaker.record(admission, failed, start);
+        }
+        assert_eq!(breaker.state(), BreakerState::Open);
+
+        let later = start + Duration::from_secs(4);
+        assert_eq!(
+            breaker.admit(later),
+            Admission::Rejected {
+                retry_after: Duration::from_secs(6)
+            }
+        );
+        assert_eq!(breaker.stats().rejected, 1);
+
+        let cooled = start + Duration::from_secs(10);
+        let probe = breaker.admit(cooled);
+        assert_eq!(probe, Admission::Probe);
+        // Only one probe at a time.
+        assert!(matches!(breaker.admit(cooled), Admission::Rejected { .. }));
+        breaker.record(probe, false, cooled);
+        assert_eq!(breaker.state(), BreakerState::Closed);
+
+        assert_eq!(
+            *transitions.lock(),
+            vec![
+                ("closed", "open"),
+                ("open", "half_open"),
+                ("half_open", "closed")
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_probe_reopens() {
+        let breaker = failing_breaker().open_for(Duration::ZERO);
+        let now = Instant::now();
+        for _ in 0..4 {
+            let admission = breaker.admit(now);
+            breaker.record(admission, true, now);
+        }
+        let probe = breaker.admit(now);
+        assert_eq!(probe, Admission::Probe);
+        breaker.record(probe, true, now);
+        assert_eq!(breaker.state(), BreakerState::Open);
+    }
+
+    #[test]
+    fn middleware_short_circuits_open_routes_with_503() {
+        let breaker = failing_breaker().min_requests(1).window_size(1);
+        let mw = CircuitBreakerMiddleware::new().route("/upstream/*", breaker.clone());
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+
+        let mut req = Request::new(Method::Get, "/upstream/quote");
+        assert!(futures_executor::block_on(mw.before(&ctx, &mut req)).is_continue());
+        let failure = Response::with_status(StatusCode::INTERNAL_SERVER_ERROR);
+        let _ = futures_executor::block_on(mw.after(&ctx, &req, failure));
+        assert_eq!(breaker.state(), BreakerState::Open);
+
+        let mut req = Request::new(Method::Get, "/upstream/quote");
+        let ControlFlow::Break(response) = futures_executor::block_on(mw.before(&ctx, &mut req))
+        else {
+            panic!("an open breaker should short-circuit");
+        };
+        assert_eq!(response.status().as_u16(), 503);
+        assert!(
+            response
+                .headers()
+                .iter()
+                .any(|(n, v)| n == "retry-after" && v == b"10")
+        );
+
+        let mut other = Request::new(Method::Get, "/health");
+        assert!(futures_executor::block_on(mw.before(&ctx, &mut other)).is_continue());
+    }
+
+    #[test]
+    fn dropped_calls_count_as_failures() {
+        let breaker = failing_breaker().min_requests(1).window_size(1);
+        let mw = CircuitBreakerMiddleware::new().global(breaker.clone());
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+
+        let mut req = Request::new(Method::Get, "/");
+        assert!(futures_executor::block_on(mw.before(&ctx, &mut req)).is_continue());
+        drop(req);
+        assert_eq!(breaker.state(), BreakerState::Open);
+    }
+}
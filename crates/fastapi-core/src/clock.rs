@@ -0,0 +1,327 @@
+//! Time source for request deadlines, timeouts and rate limits.
+//!
+//! Requests normally run against the system clock. A test can give its
+//! `TestClient` a [`VirtualClock`] instead:
+//! request deadlines, [`TimeoutLayer`](crate::TimeoutLayer) and the
+//! in-memory rate limiter then read virtual time, which only moves when the
+//! test advances it, or when a request has nothing left to do but wait for
+//! a virtual timer.
+//!
+//! ```ignore
+//! let time = VirtualClock::new();
+//! let client = TestClient::new(app).with_virtual_time(time.clone());
+//!
+//! client.get("/limited").send().assert_status(200);
+//! client.get("/limited").send().assert_status(429);
+//! time.advance(Duration::from_secs(60)); // next window, no real waiting
+//! client.get("/limited").send().assert_status(200);
+//! ```
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::{Duration, Instant};
+
+use asupersync::{Cx, Time};
+use parking_lot::Mutex;
+
+/// The clock a request runs against: the system clock or a [`VirtualClock`].
+#[derive(Debug, Clone, Default)]
+pub struct Clock {
+    virtual_time: Option<VirtualClock>,
+}
+
+impl Clock {
+    /// The system clock.
+    #[must_use]
+    pub fn system() -> Self {
+        Self::default()
+    }
+
+    /// The virtual clock behind this clock, if any.
+    #[must_use]
+    pub fn virtual_time(&self) -> Option<&VirtualClock> {
+        self.virtual_time.as_ref()
+    }
+
+    /// The current instant.
+    #[must_use]
+    pub fn now(&self) -> Instant {
+        match &self.virtual_time {
+            Some(clock) => clock.now(),
+            None => Instant::now(),
+        }
+    }
+
+    /// Completes once `duration` has passed on this clock.
+    #[must_use]
+    pub fn sleep(&self, duration: Duration) -> Sleep {
+        match &self.virtual_time {
+            Some(clock) => Sleep::Virtual(clock.sleep(duration)),
+            None => Sleep::System(Box::pin(asupersync::time::sleep(
+                asupersync::time::wall_now(),
+                duration,
+            ))),
+        }
+    }
+
+    /// The current time on the runtime clock of `cx`, the clock request
+    /// deadlines are set on. Virtual time starts at [`Time::ZERO`].
+    pub(crate) fn runtime_now(&self, cx: &Cx) -> Time {
+        match &self.virtual_time {
+            Some(clock) => {
+                Time::from_nanos(u64::try_from(clock.elapsed().as_nanos()).unwrap_or(u64::MAX))
+            }
+            None => cx.now(),
+        }
+    }
+}
+
+impl From<VirtualClock> for Clock {
+    fn from(clock: VirtualClock) -> Self {
+        Self {
+            virtual_time: Some(clock),
+        }
+    }
+}
+
+/// Future returned by [`Clock::sleep`].
+pub enum Sleep {
+    /// A timer on the system clock.
+    System(Pin<Box<dyn Future<Output = ()> + Send>>),
+    /// A timer on a virtual clock.
+    Virtual(VirtualSleep),
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        match self.get_mut() {
+            Self::System(sleep) => sleep.as_mut().poll(cx),
+            Self::Virtual(sleep) => Pin::new(sleep).poll(cx),
+        }
+    }
+}
+
+impl std::fmt::Debug for Sleep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::System(_) => f.write_str("Sleep::System"),
+            Self::Virtual(sleep) => f.debug_tuple("Sleep::Virtual").field(sleep).finish(),
+        }
+    }
+}
+
+/// A clock that only moves when told to.
+///
+/// Clones share the same time. [`advance`](Self::advance) moves it forward
+/// and wakes the timers that became due; [`block_on`](Self::block_on) runs
+/// a future and, whenever it is only waiting on virtual timers, jumps to the
+/// next one.
+#[derive(Clone)]
+pub struct VirtualClock {
+    inner: Arc<VirtualInner>,
+}
+
+struct VirtualInner {
+    start: Instant,
+    state: Mutex<VirtualState>,
+}
+
+#[derive(Default)]
+struct VirtualState {
+    elapsed: Duration,
+    next_id: u64,
+    /// Pending timers by (deadline, id).
+    timers: BTreeMap<(Duration, u64), Waker>,
+}
+
+impl VirtualClock {
+    /// A virtual clock at zero elapsed time.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(VirtualInner {
+                start: Instant::now(),
+                state: Mutex::new(VirtualState::default()),
+            }),
+        }
+    }
+
+    /// The current virtual instant.
+    #[must_use]
+    pub fn now(&self) -> Instant {
+        self.inner.start + self.elapsed()
+    }
+
+    /// Virtual time passed since the clock was created.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.inner.state.lock().elapsed
+    }
+
+    /// Move time forward by `duration`, waking timers that are now due.
+    pub fn advance(&self, duration: Duration) {
+        let due = {
+            let mut state = self.inner.state.lock();
+            state.elapsed += duration;
+            let first_later = (state.elapsed + Duration::from_nanos(1), 0);
+            let later = state.timers.split_off(&first_later);
+            std::mem::replace(&mut state.timers, later)
+        };
+        for waker in due.into_values() {
+            waker.wake();
+        }
+    }
+
+    /// When the earliest pending timer is due, as elapsed time.
+    #[must_use]
+    pub fn next_timer(&self) -> Option<Duration> {
+        let state = self.inner.state.lock();
+        state.timers.keys().next().map(|(deadline, _)| *deadline)
+    }
+
+    /// Completes once the clock has advanced by `duration`.
+    #[must_use]
+    pub fn sleep(&self, duration: Duration) -> VirtualSleep {
+        let mut state = self.inner.state.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+        VirtualSleep {
+            clock: self.clone(),
+            key: (state.elapsed + duration, id),
+        }
+    }
+
+    /// Run `future` to completion on the current thread.
+    ///
+    /// Whenever the future is pending and nothing has woken it, time jumps
+    /// to the next pending virtual timer, so timeouts fire without real
+    /// waiting. With no virtual timer pending, it waits for a real wakeup.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let signal = Arc::new(ThreadSignal {
+            woken: AtomicBool::new(false),
+            thread: std::thread::current(),
+        });
+        let waker = Waker::from(Arc::clone(&signal));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            if signal.woken.swap(false, Ordering::SeqCst) {
+                continue;
+            }
+            match self.next_timer() {
+                Some(deadline) => self.advance(deadline.saturating_sub(self.elapsed())),
+                None => {
+                    while !signal.woken.swap(false, Ordering::SeqCst) {
+                        std::thread::park();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for VirtualClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.inner.state.lock();
+        f.debug_struct("VirtualClock")
+            .field("elapsed", &state.elapsed)
+            .field("pending_timers", &state.timers.len())
+            .finish()
+    }
+}
+
+struct ThreadSignal {
+    woken: AtomicBool,
+    thread: std::thread::Thread,
+}
+
+impl Wake for ThreadSignal {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+        self.thread.unpark();
+    }
+}
+
+/// Future returned by [`VirtualClock::sleep`].
+#[derive(Debug)]
+pub struct VirtualSleep {
+    clock: VirtualClock,
+    key: (Duration, u64),
+}
+
+impl Future for VirtualSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.clock.inner.state.lock();
+        if state.elapsed >= self.key.0 {
+            return Poll::Ready(());
+        }
+        state.timers.insert(self.key, cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for VirtualSleep {
+    fn drop(&mut self) {
+        self.clock.inner.state.lock().timers.remove(&self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance_wakes_due_timers_only() {
+        let clock = VirtualClock::new();
+        let start = clock.now();
+        let mut short = clock.sleep(Duration::from_secs(1));
+        let mut long = clock.sleep(Duration::from_secs(5));
+        let mut cx = Context::from_waker(Waker::noop());
+
+        assert!(Pin::new(&mut short).poll(&mut cx).is_pending());
+        assert!(Pin::new(&mut long).poll(&mut cx).is_pending());
+        assert_eq!(clock.next_timer(), Some(Duration::from_secs(1)));
+
+        clock.advance(Duration::from_secs(1));
+        assert!(Pin::new(&mut short).poll(&mut cx).is_ready());
+        assert!(Pin::new(&mut long).poll(&mut cx).is_pending());
+        assert_eq!(clock.now() - start, Duration::from_secs(1));
+
+        drop(long);
+        assert_eq!(clock.next_timer(), None);
+    }
+
+    #[test]
+    fn block_on_skips_to_the_next_timer() {
+        let clock = VirtualClock::new();
+        let real_start = Instant::now();
+        let clock2 = clock.clone();
+        clock.block_on(async move {
+            clock2.sleep(Duration::from_secs(3600)).await;
+            clock2.sleep(Duration::from_secs(60)).await;
+        });
+        assert_eq!(clock.elapsed(), Duration::from_secs(3660));
+        assert!(real_start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -3,11 +3,21 @@
 //! [`RequestContext`] wraps asupersync's [`Cx`] to provide request-scoped
 //! capabilities for HTTP request handling.
 
+use asupersync::runtime::Runtime;
 use asupersync::types::CancelReason;
 use asupersync::{Budget, Cx, Outcome, RegionId, TaskId, Time};
+use parking_lot::Mutex;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
 
-use crate::dependency::{CleanupStack, DependencyCache, DependencyOverrides, ResolutionStack};
+use crate::clock::Clock;
+use crate::dependency::{
+    CleanupStack, DependencyCache, DependencyOverrides, RequestOutcome, ResolutionStack,
+};
+use crate::logging::LogScope;
+use crate::request::BackgroundTasks;
 
 /// Default maximum body size: 1MB.
 pub const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
@@ -87,6 +97,10 @@ pub struct RequestContext {
     cleanup_stack: Arc<CleanupStack>,
     /// Body size limit configuration for this request.
     body_limit: BodyLimitConfig,
+    /// Fields attached to every log entry for this request.
+    log_scope: Arc<LogScope>,
+    /// Child tasks spawned with [`Self::spawn_scoped`].
+    scoped_tasks: Arc<ScopedTasks>,
     /// Absolute server deadline for this request, on the runtime clock.
     ///
     /// Set by the server from its configured request timeout. `None` means no
@@ -96,6 +110,8 @@ pub struct RequestContext {
     /// publishing, because the server abandons the response once the deadline
     /// passes.
     deadline: Option<Time>,
+    /// Clock that deadlines, timeouts and rate limits read.
+    clock: Clock,
 }
 
 impl RequestContext {
@@ -114,7 +130,10 @@ impl RequestContext {
             resolution_stack: Arc::new(ResolutionStack::new()),
             cleanup_stack: Arc::new(CleanupStack::new()),
             body_limit: BodyLimitConfig::default(),
+            log_scope: Arc::new(LogScope::new()),
+            scoped_tasks: Arc::new(ScopedTasks::new()),
             deadline: None,
+            clock: Clock::system(),
         }
     }
 
@@ -132,7 +151,10 @@ impl RequestContext {
             resolution_stack: Arc::new(ResolutionStack::new()),
             cleanup_stack: Arc::new(CleanupStack::new()),
             body_limit: BodyLimitConfig::new(max_body_size),
+            log_scope: Arc::new(LogScope::new()),
+            scoped_tasks: Arc::new(ScopedTasks::new()),
             deadline: None,
+            clock: Clock::system(),
         }
     }
 
@@ -147,7 +169,10 @@ impl RequestContext {
             resolution_stack: Arc::new(ResolutionStack::new()),
             cleanup_stack: Arc::new(CleanupStack::new()),
             body_limit: BodyLimitConfig::default(),
+            log_scope: Arc::new(LogScope::new()),
+            scoped_tasks: Arc::new(ScopedTasks::new()),
             deadline: None,
+            clock: Clock::system(),
         }
     }
 
@@ -167,7 +192,10 @@ impl RequestContext {
             resolution_stack: Arc::new(ResolutionStack::new()),
             cleanup_stack: Arc::new(CleanupStack::new()),
             body_limit: BodyLimitConfig::new(max_body_size),
+            log_scope: Arc::new(LogScope::new()),
+            scoped_tasks: Arc::new(ScopedTasks::new()),
             deadline: None,
+            clock: Clock::system(),
         }
     }
 
@@ -183,6 +211,28 @@ impl RequestContext {
         self
     }
 
+    /// Runs this request against `clock` instead of the system clock.
+    ///
+    /// Used by `TestClient::with_virtual_time`; with a virtual clock the
+    /// deadline is measured from the clock's start.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns the clock this request runs against.
+    #[must_use]
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// Returns the current time on the clock [`Self::deadline`] is set on.
+    #[must_use]
+    pub fn now(&self) -> Time {
+        self.clock.runtime_now(&self.cx)
+    }
+
     /// Returns the absolute server deadline for this request, if one applies.
     #[must_use]
     pub fn deadline(&self) -> Option<Time> {
@@ -200,8 +250,7 @@ impl RequestContext {
     /// Returns false when no server deadline was set.
     #[must_use]
     pub fn deadline_exceeded(&self) -> bool {
-        self.deadline
-            .is_some_and(|deadline| self.cx.now() >= deadline)
+        self.deadline.is_some_and(|deadline| self.now() >= deadline)
     }
 
     /// Returns the unique request identifier.
@@ -212,6 +261,15 @@ impl RequestContext {
         self.request_id
     }
 
+    /// Returns the request-scoped logging fields.
+    ///
+    /// Every [`LogEntry`](crate::logging::LogEntry) created from this context
+    /// includes the scope's route and fields.
+    #[must_use]
+    pub fn log_scope(&self) -> &LogScope {
+        &self.log_scope
+    }
+
     /// Returns the dependency cache for this request.
     #[must_use]
     pub fn dependency_cache(&self) -> &DependencyCache {
@@ -238,6 +296,107 @@ impl RequestContext {
         &self.cleanup_stack
     }
 
+    /// Registers async teardown to run after the response is produced.
+    ///
+    /// This is how a dependency hands a value to the handler and cleans up
+    /// afterwards, like a FastAPI `yield` dependency. `teardown` receives the
+    /// [`RequestOutcome`] and runs even when the request is cancelled, times
+    /// out, or panics. Teardowns run in reverse registration order.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// #[dependency]
+    /// async fn transaction(ctx: &RequestContext, db: Depends<Db>) -> Result<Tx, HttpError> {
+    ///     let tx = db.begin().await?;
+    ///     let guard = tx.clone();
+    ///     ctx.defer(move |outcome| async move {
+    ///         if outcome.is_success() {
+    ///             guard.commit().await;
+    ///         } else {
+    ///             guard.rollback().await;
+    ///         }
+    ///     });
+    ///     Ok(tx)
+    /// }
+    /// ```
+    pub fn defer<F, Fut>(&self, teardown: F)
+    where
+        F: FnOnce(RequestOutcome) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let stack = Arc::downgrade(&self.cleanup_stack);
+        self.cleanup_stack.push(Box::new(move || {
+            let outcome = stack
+                .upgrade()
+                .and_then(|stack| stack.outcome())
+                .unwrap_or(RequestOutcome::Aborted);
+            Box::pin(teardown(outcome))
+        }));
+    }
+
+    /// Returns the child tasks spawned for this request.
+    #[must_use]
+    pub fn scoped_tasks(&self) -> &ScopedTasks {
+        &self.scoped_tasks
+    }
+
+    /// Spawns child work tied to this request.
+    ///
+    /// The child shares this request's [`Cx`], so it sees the same budget,
+    /// cancellation, and trace context; clone the context into `fut` to use
+    /// the request id or log scope from inside it. The child is cancelled if
+    /// the request is cancelled or passes its deadline, and any child still
+    /// running when the request completes is cancelled then, so none
+    /// outlives the response unless handed to [`ScopedTask::detach`].
+    ///
+    /// Inside an asupersync runtime the child runs concurrently on it;
+    /// otherwise (e.g. in tests) it runs when the handle is awaited.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// async fn dashboard(ctx: &RequestContext) -> Result<Json<Dashboard>, HttpError> {
+    ///     let user = ctx.spawn_scoped(load_user(ctx.clone()));
+    ///     let stats = ctx.spawn_scoped(load_stats(ctx.clone()));
+    ///     Ok(Json(Dashboard { user: user.await?, stats: stats.await? }))
+    /// }
+    /// ```
+    pub fn spawn_scoped<F>(&self, fut: F) -> ScopedTask<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let shared = Arc::new(ScopedShared {
+            state: Mutex::new(ScopedState {
+                future: Some(Box::pin(fut)),
+                output: None,
+                finished: false,
+                detached: false,
+                spawned: false,
+                waiter: None,
+                driver: None,
+            }),
+            cx: self.cx.clone(),
+            deadline: self.deadline,
+            clock: self.clock.clone(),
+        });
+        self.scoped_tasks.push(shared.clone());
+
+        if let Some(runtime) = Runtime::current_handle() {
+            shared.state.lock().spawned = true;
+            let driver = Arc::clone(&shared);
+            let spawned = runtime.try_spawn(async move {
+                std::future::poll_fn(|task_cx| driver.poll_drive(task_cx)).await;
+            });
+            if spawned.is_err() {
+                // Fall back to running the child when it is awaited.
+                shared.state.lock().spawned = false;
+            }
+        }
+        ScopedTask { shared }
+    }
+
     /// Returns the body limit configuration for this request.
     ///
     /// This can be used by body extractors (e.g., `Json<T>`) to enforce
@@ -371,6 +530,209 @@ impl std::fmt::Display for CancelledError {
 
 impl std::error::Error for CancelledError {}
 
+type ScopedFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// Child tasks spawned with [`RequestContext::spawn_scoped`].
+///
+/// Shared by every clone of a request's context. When the request finishes,
+/// [`App::handle`](crate::app::App::handle) calls [`Self::cancel_remaining`]
+/// so no child outlives its request unless it was detached.
+#[derive(Default)]
+pub struct ScopedTasks {
+    tasks: Mutex<Vec<Arc<dyn ScopedEntry>>>,
+}
+
+impl std::fmt::Debug for ScopedTasks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScopedTasks")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl ScopedTasks {
+    /// Create an empty task set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of tracked children, finished or not.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tasks.lock().len()
+    }
+
+    /// Returns true if no children are tracked.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cancel every child that has neither finished nor been detached.
+    ///
+    /// Cancelled futures are dropped before this returns, so their code no
+    /// longer runs afterwards. Returns the number of children cancelled.
+    pub fn cancel_remaining(&self) -> usize {
+        let tasks = std::mem::take(&mut *self.tasks.lock());
+        tasks.iter().filter(|task| task.cancel()).count()
+    }
+
+    fn push(&self, task: Arc<dyn ScopedEntry>) {
+        self.tasks.lock().push(task);
+    }
+}
+
+trait ScopedEntry: Send + Sync {
+    /// Cancel the child if it is still running and attached; returns true if
+    /// it was cancelled.
+    fn cancel(&self) -> bool;
+}
+
+struct ScopedState<T> {
+    future: Option<ScopedFuture<T>>,
+    output: Option<Result<T, CancelledError>>,
+    finished: bool,
+    detached: bool,
+    /// Whether a runtime task polls `future` (otherwise the handle does).
+    spawned: bool,
+    /// Waker of whoever awaits the result.
+    waiter: Option<Waker>,
+    /// Waker of the runtime task driving `future`.
+    driver: Option<Waker>,
+}
+
+struct ScopedShared<T> {
+    state: Mutex<ScopedState<T>>,
+    cx: Cx,
+    deadline: Option<Time>,
+    clock: Clock,
+}
+
+impl<T> ScopedShared<T> {
+    /// Poll the child future, cancelling it first if the request was
+    /// cancelled or ran past its deadline.
+    fn poll_drive(&self, task_cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock();
+        if state.finished {
+            return Poll::Ready(());
+        }
+        let expired = self.cx.is_cancel_requested()
+            || self
+                .deadline
+                .is_some_and(|deadline| self.clock.runtime_now(&self.cx) >= deadline);
+        if expired && !state.detached {
+            Self::finish(&mut state, Err(CancelledError));
+            return Poll::Ready(());
+        }
+        let Some(future) = state.future.as_mut() else {
+            return Poll::Ready(());
+        };
+        match future.as_mut().poll(task_cx) {
+            Poll::Ready(value) => {
+                Self::finish(&mut state, Ok(value));
+                Poll::Ready(())
+            }
+            Poll::Pending => {
+                if state.spawned {
+                    state.driver = Some(task_cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Drive the child if nothing else does, then take its result.
+    fn poll_join(&self, task_cx: &mut Context<'_>) -> Poll<Result<T, CancelledError>> {
+        if !self.state.lock().spawned {
+            let _ = self.poll_drive(task_cx);
+        }
+        let mut state = self.state.lock();
+        if let Some(output) = state.output.take() {
+            return Poll::Ready(output);
+        }
+        if state.finished {
+            // The result was already taken by an earlier poll.
+            return Poll::Ready(Err(CancelledError));
+        }
+        state.waiter = Some(task_cx.waker().clone());
+        Poll::Pending
+    }
+
+    fn finish(state: &mut ScopedState<T>, output: Result<T, CancelledError>) {
+        state.future = None;
+        state.output = Some(output);
+        state.finished = true;
+        if let Some(waker) = state.waiter.take() {
+            waker.wake();
+        }
+        if let Some(waker) = state.driver.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T: Send> ScopedEntry for ScopedShared<T> {
+    fn cancel(&self) -> bool {
+        let mut state = self.state.lock();
+        if state.finished || state.detached {
+            return false;
+        }
+        Self::finish(&mut state, Err(CancelledError));
+        true
+    }
+}
+
+/// Handle to a child task spawned with [`RequestContext::spawn_scoped`].
+///
+/// Awaiting the handle yields the child's output, or [`CancelledError`] if
+/// the request was cancelled, hit its deadline, or finished before the child
+/// did. Dropping the handle does not stop the child; it keeps running until
+/// the request completes and is then cancelled.
+#[must_use = "a scoped task is cancelled when the request completes unless awaited or detached"]
+pub struct ScopedTask<T> {
+    shared: Arc<ScopedShared<T>>,
+}
+
+impl<T> std::fmt::Debug for ScopedTask<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.shared.state.lock();
+        f.debug_struct("ScopedTask")
+            .field("finished", &state.finished)
+            .field("detached", &state.detached)
+            .finish()
+    }
+}
+
+impl<T: Send + 'static> ScopedTask<T> {
+    /// Returns true once the child has completed or been cancelled.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.shared.state.lock().finished
+    }
+
+    /// Let the child outlive the response.
+    ///
+    /// The child is no longer cancelled with the request; instead `tasks`
+    /// waits for it after the response has been sent, like any other
+    /// background task. Its output is discarded.
+    pub fn detach(self, tasks: &BackgroundTasks) {
+        self.shared.state.lock().detached = true;
+        let shared = self.shared;
+        tasks.add_async(async move {
+            let _ = std::future::poll_fn(|task_cx| shared.poll_join(task_cx)).await;
+        });
+    }
+}
+
+impl<T> Future for ScopedTask<T> {
+    type Output = Result<T, CancelledError>;
+
+    fn poll(self: Pin<&mut Self>, task_cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.shared.poll_join(task_cx)
+    }
+}
+
 /// Extension trait for converting HTTP results to asupersync Outcome.
 ///
 /// This bridges the HTTP error model with asupersync's 4-valued outcome
@@ -749,4 +1111,54 @@ mod tests {
         assert!(ctx1.resolution_stack().is_empty());
         assert!(ctx2.resolution_stack().is_empty());
     }
+
+    #[test]
+    fn scoped_task_yields_child_output() {
+        let ctx = RequestContext::new(Cx::for_testing(), 7);
+        let child_ctx = ctx.clone();
+        let task = ctx.spawn_scoped(async move { child_ctx.request_id() * 6 });
+
+        assert_eq!(futures_executor::block_on(task).unwrap(), 42);
+    }
+
+    #[test]
+    fn unawaited_scoped_tasks_are_cancelled_with_the_request() {
+        let ran = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let ctx = RequestContext::new(Cx::for_testing(), 1);
+        let flag = Arc::clone(&ran);
+        let task = ctx.spawn_scoped(async move {
+            flag.store(true, std::sync::atomic::Ordering::SeqCst);
+        });
+
+        assert_eq!(ctx.scoped_tasks().cancel_remaining(), 1);
+        assert!(task.is_finished());
+        assert!(futures_executor::block_on(task).is_err());
+        assert!(!ran.load(std::sync::atomic::Ordering::SeqCst));
+        assert!(ctx.scoped_tasks().is_empty());
+    }
+
+    #[test]
+    fn scoped_tasks_observe_request_cancellation() {
+        let ctx = RequestContext::new(Cx::for_testing(), 1);
+        let task = ctx.spawn_scoped(async { "done" });
+        ctx.cx().set_cancel_requested(true);
+
+        assert!(futures_executor::block_on(task).is_err());
+    }
+
+    #[test]
+    fn detached_scoped_tasks_run_as_background_tasks() {
+        let ran = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let ctx = RequestContext::new(Cx::for_testing(), 1);
+        let flag = Arc::clone(&ran);
+        let tasks = BackgroundTasks::new();
+        ctx.spawn_scoped(async move {
+            flag.store(true, std::sync::atomic::Ordering::SeqCst);
+        })
+        .detach(&tasks);
+
+        assert_eq!(ctx.scoped_tasks().cancel_remaining(), 0);
+        futures_executor::block_on(tasks.execute_all());
+        assert!(ran.load(std::sync::atomic::Ordering::SeqCst));
+    }
 }
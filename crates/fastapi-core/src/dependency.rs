@@ -34,6 +34,7 @@
 //! - Consider using scoped dependencies to break cycles
 
 use crate::context::RequestContext;
+use crate::dependency_graph::DependencyRequirement;
 use crate::extract::FromRequest;
 use crate::request::Request;
 use crate::response::{IntoResponse, Response, ResponseBody, StatusCode};
@@ -45,7 +46,9 @@ use std::future::Future;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
 
 /// Dependency resolution scope.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -54,6 +57,10 @@ pub enum DependencyScope {
     Function,
     /// Cache for the lifetime of the request.
     Request,
+    /// Construct once, on first use, and share with every later request.
+    ///
+    /// See [`Singleton`].
+    App,
 }
 
 // ============================================================================
@@ -67,6 +74,28 @@ pub enum DependencyScope {
 /// first-out) order, similar to Python's `contextlib.ExitStack`.
 pub type CleanupFn = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>;
 
+/// How a request ended, as seen by its cleanup functions.
+///
+/// Teardowns registered with [`RequestContext::defer`] receive this so they
+/// can, for example, commit a transaction on success and roll it back
+/// otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestOutcome {
+    /// The app produced a response with this status.
+    Response(StatusCode),
+    /// No response was produced: the request was cancelled, timed out, or
+    /// the handler panicked.
+    Aborted,
+}
+
+impl RequestOutcome {
+    /// Whether the request produced a non-error (below 400) response.
+    #[must_use]
+    pub fn is_success(self) -> bool {
+        matches!(self, Self::Response(status) if status.as_u16() < 400)
+    }
+}
+
 /// Stack of cleanup functions to run after handler completion.
 ///
 /// `CleanupStack` provides generator-style dependency lifecycle management
@@ -110,9 +139,15 @@ pub type CleanupFn = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>
 ///
 /// This ensures that dependencies are cleaned up in the reverse order
 /// of their setup, maintaining proper resource lifecycle semantics.
+///
+/// `App::handle` runs the stack once the response has been produced. If the
+/// request is dropped first (client disconnect, server timeout, panic), the
+/// stack is run on the runtime with [`RequestOutcome::Aborted`].
 pub struct CleanupStack {
     /// Cleanup functions in registration order (will be reversed when running).
     cleanups: Mutex<Vec<CleanupFn>>,
+    /// How the request ended, recorded before the cleanups run.
+    outcome: Mutex<Option<RequestOutcome>>,
 }
 
 impl CleanupStack {
@@ -121,9 +156,21 @@ impl CleanupStack {
     pub fn new() -> Self {
         Self {
             cleanups: Mutex::new(Vec::new()),
+            outcome: Mutex::new(None),
         }
     }
 
+    /// Record how the request ended, for cleanups that inspect it.
+    pub fn set_outcome(&self, outcome: RequestOutcome) {
+        *self.outcome.lock() = Some(outcome);
+    }
+
+    /// How the request ended, once recorded.
+    #[must_use]
+    pub fn outcome(&self) -> Option<RequestOutcome> {
+        *self.outcome.lock()
+    }
+
     /// Register a cleanup function to run after handler completion.
     ///
     /// Cleanup functions are run in LIFO order (last registered runs first).
@@ -371,6 +418,8 @@ pub trait DependsConfig {
     const USE_CACHE: bool;
     /// Optional scope override.
     const SCOPE: Option<DependencyScope>;
+    /// How construction of an application-scoped dependency is retried.
+    const RETRY: SingletonRetry = SingletonRetry::DEFAULT;
 }
 
 /// Default dependency configuration (cache per request).
@@ -394,6 +443,182 @@ impl DependsConfig for NoCache {
     const SCOPE: Option<DependencyScope> = Some(DependencyScope::Function);
 }
 
+/// Construct the dependency once and share it across requests.
+///
+/// The first request that needs `Depends<T, Singleton>` runs
+/// `T::from_dependency`; concurrent requests wait for it, and later requests
+/// get a clone of the value. Failed construction is retried per
+/// [`DependsConfig::RETRY`]; if every attempt fails the error is returned and
+/// the next request starts over. Useful for connection pools configured from
+/// the environment at first use:
+///
+/// ```ignore
+/// async fn list_users(pool: Depends<DbPool, Singleton>) -> Json<Vec<User>> {
+///     // ...
+/// }
+/// ```
+///
+/// Values are kept per type for the lifetime of the process, shared by every
+/// app in it. Define your own [`DependsConfig`] with
+/// `SCOPE = Some(DependencyScope::App)` to change the retry policy.
+#[derive(Debug, Clone, Copy)]
+pub struct Singleton;
+
+impl DependsConfig for Singleton {
+    const USE_CACHE: bool = true;
+    const SCOPE: Option<DependencyScope> = Some(DependencyScope::App);
+}
+
+/// Retry policy for constructing application-scoped dependencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SingletonRetry {
+    /// Attempts per resolution before the error is returned.
+    pub attempts: u32,
+    /// Delay before the second attempt, doubled after each further failure.
+    pub initial_delay: Duration,
+    /// Upper bound on the delay between attempts.
+    pub max_delay: Duration,
+}
+
+impl SingletonRetry {
+    /// Three attempts, 100ms then 200ms apart.
+    pub const DEFAULT: Self = Self::new(3, Duration::from_millis(100), Duration::from_secs(5));
+
+    /// A single attempt.
+    pub const NONE: Self = Self::new(1, Duration::ZERO, Duration::ZERO);
+
+    /// Create a retry policy.
+    #[must_use]
+    pub const fn new(attempts: u32, initial_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            attempts,
+            initial_delay,
+            max_delay,
+        }
+    }
+
+    /// Delay after the `failures`-th failed attempt.
+    #[must_use]
+    pub fn delay(&self, failures: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(failures.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+}
+
+impl Default for SingletonRetry {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+enum SingletonState {
+    Empty,
+    Building(Vec<Waker>),
+    Ready(Box<dyn Any + Send + Sync>),
+}
+
+type SingletonSlot = Arc<Mutex<SingletonState>>;
+
+static SINGLETONS: LazyLock<Mutex<HashMap<TypeId, SingletonSlot>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Resolves when the slot holds a value (`Some`) or when the caller has
+/// claimed it to build the value (`None`).
+struct ClaimSingleton<'a, T> {
+    slot: &'a Mutex<SingletonState>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Clone + 'static> Future for ClaimSingleton<'_, T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.slot.lock();
+        match &mut *state {
+            SingletonState::Ready(value) => Poll::Ready(value.downcast_ref::<T>().cloned()),
+            SingletonState::Building(wakers) => {
+                if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                    wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+            SingletonState::Empty => {
+                *state = SingletonState::Building(Vec::new());
+                Poll::Ready(None)
+            }
+        }
+    }
+}
+
+/// Publishes the built value, or releases the claim if building failed or
+/// the request was cancelled, and wakes the waiting requests.
+struct SingletonBuild<'a> {
+    slot: &'a Mutex<SingletonState>,
+    value: Option<Box<dyn Any + Send + Sync>>,
+}
+
+impl Drop for SingletonBuild<'_> {
+    fn drop(&mut self) {
+        let next = match self.value.take() {
+            Some(value) => SingletonState::Ready(value),
+            None => SingletonState::Empty,
+        };
+        let previous = std::mem::replace(&mut *self.slot.lock(), next);
+        if let SingletonState::Building(wakers) = previous {
+            wakers.into_iter().for_each(Waker::wake);
+        }
+    }
+}
+
+async fn resolve_singleton<T: FromDependency>(
+    ctx: &RequestContext,
+    req: &mut Request,
+    retry: SingletonRetry,
+) -> Result<T, T::Error> {
+    let slot = Arc::clone(
+        SINGLETONS
+            .lock()
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Arc::new(Mutex::new(SingletonState::Empty))),
+    );
+    let claim = ClaimSingleton::<T> {
+        slot: &slot,
+        _marker: PhantomData,
+    };
+    if let Some(value) = claim.await {
+        return Ok(value);
+    }
+
+    let mut build = SingletonBuild {
+        slot: &slot,
+        value: None,
+    };
+    let mut failures = 0;
+    loop {
+        let _ = ctx.checkpoint();
+        match T::from_dependency(ctx, req).await {
+            Ok(value) => {
+                build.value = Some(Box::new(value.clone()));
+                return Ok(value);
+            }
+            Err(err) => {
+                failures += 1;
+                if failures >= retry.attempts {
+                    return Err(err);
+                }
+                let delay = retry.delay(failures);
+                if !delay.is_zero() {
+                    asupersync::time::sleep(asupersync::time::wall_now(), delay).await;
+                }
+            }
+        }
+    }
+}
+
 // ============================================================================
 // Circular Dependency Detection
 // ============================================================================
@@ -638,6 +863,7 @@ impl ResolutionStack {
     /// - Function-scoped can depend on function-scoped (both fresh, OK)
     /// - Function-scoped can depend on request-scoped (inner cached, outer fresh, OK)
     /// - Request-scoped CANNOT depend on function-scoped (outer cached with stale inner, BAD)
+    /// - App-scoped is cached like request-scoped, so the same rule applies
     pub fn check_scope_violation(
         &self,
         type_name: &str,
@@ -650,10 +876,10 @@ impl ResolutionStack {
 
         let guard = self.stack.read();
 
-        // Find any request-scoped dependency on the stack
-        // (i.e., an outer request-scoped dependency trying to use this function-scoped one)
+        // Find any cached (request- or app-scoped) dependency on the stack
+        // (i.e., an outer cached dependency trying to use this function-scoped one)
         for (_, name, dep_scope) in guard.iter().rev() {
-            if *dep_scope == DependencyScope::Request {
+            if *dep_scope != DependencyScope::Function {
                 return Some(DependencyScopeError::new(
                     name.clone(),
                     type_name.to_owned(),
@@ -779,6 +1005,61 @@ pub trait FromDependency: Clone + Send + Sync + 'static {
         ctx: &RequestContext,
         req: &mut Request,
     ) -> impl Future<Output = Result<Self, Self::Error>> + Send;
+
+    /// The dependencies and state this dependency extracts, used to validate
+    /// the dependency graph when the app is built.
+    ///
+    /// `#[dependency]` generates this from the function's parameters.
+    fn requirements() -> Vec<DependencyRequirement> {
+        Vec::new()
+    }
+}
+
+/// Error returned by dependencies generated with `#[dependency]`.
+///
+/// Holds the response of whichever step failed: extracting one of the
+/// function's parameters or the function itself. `E` is the function's own
+/// error type (`Infallible` when it returns a plain value); it is only used
+/// to document the error's responses in OpenAPI when it implements
+/// [`ErrorResponses`](fastapi_openapi::ErrorResponses).
+pub struct DependencyError<E = std::convert::Infallible>(Mutex<Response>, PhantomData<fn() -> E>);
+
+impl<E> DependencyError<E> {
+    /// Wrap a failure as its response.
+    #[must_use]
+    pub fn new(error: impl IntoResponse) -> Self {
+        Self(Mutex::new(error.into_response()), PhantomData)
+    }
+}
+
+impl<E> std::fmt::Debug for DependencyError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("DependencyError")
+            .field(&self.0.lock().status().as_u16())
+            .finish()
+    }
+}
+
+impl<E> std::fmt::Display for DependencyError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Dependency rejected with {}",
+            self.0.lock().status().as_u16()
+        )
+    }
+}
+
+impl<E> IntoResponse for DependencyError<E> {
+    fn into_response(self) -> Response {
+        self.0.into_inner()
+    }
+}
+
+impl<E: fastapi_openapi::ErrorResponses> fastapi_openapi::ErrorResponses for DependencyError<E> {
+    fn error_responses() -> Vec<(u16, &'static str)> {
+        E::error_responses()
+    }
 }
 
 impl<T, C> FromRequest for Depends<T, C>
@@ -828,7 +1109,11 @@ where
 
         // Resolve the dependency
         let _ = ctx.checkpoint();
-        let value = T::from_dependency(ctx, req).await?;
+        let value = if scope == DependencyScope::App {
+            resolve_singleton::<T>(ctx, req, C::RETRY).await?
+        } else {
+            T::from_dependency(ctx, req).await?
+        };
 
         // Cache if needed (guard will pop stack when dropped)
         if use_cache {
@@ -908,8 +1193,15 @@ type OverrideFuture = Pin<Box<dyn Future<Output = Result<OverrideBox, OverrideBo
 type OverrideFn = Arc<dyn Fn(&RequestContext, &mut Request) -> OverrideFuture + Send + Sync>;
 
 /// Dependency override registry (primarily for testing).
+///
+/// A registry can be [`layered`](Self::layered) over another one, as each
+/// `TestClient` does over its app's registry, so overrides set through one
+/// client do not leak into others. [`scoped`](Self::scoped) and
+/// [`with_override`](Self::with_override) put back the previous override
+/// when they end.
 pub struct DependencyOverrides {
     inner: RwLock<HashMap<TypeId, OverrideFn>>,
+    parent: Option<Arc<DependencyOverrides>>,
 }
 
 impl DependencyOverrides {
@@ -918,6 +1210,19 @@ impl DependencyOverrides {
     pub fn new() -> Self {
         Self {
             inner: RwLock::new(HashMap::new()),
+            parent: None,
+        }
+    }
+
+    /// Create an empty registry on top of `parent`.
+    ///
+    /// Types without an override here resolve through `parent`. Inserting
+    /// and clearing only affect this registry.
+    #[must_use]
+    pub fn layered(parent: Arc<DependencyOverrides>) -> Self {
+        Self {
+            inner: RwLock::new(HashMap::new()),
+            parent: Some(parent),
         }
     }
 
@@ -928,18 +1233,7 @@ impl DependencyOverrides {
         F: Fn(&RequestContext, &mut Request) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Result<T, T::Error>> + Send + 'static,
     {
-        let wrapper: OverrideFn = Arc::new(move |ctx, req| {
-            let fut = f(ctx, req);
-            Box::pin(async move {
-                match fut.await {
-                    Ok(value) => Ok(Box::new(value) as OverrideBox),
-                    Err(err) => Err(Box::new(err) as OverrideBox),
-                }
-            })
-        });
-
-        let mut guard = self.inner.write();
-        guard.insert(TypeId::of::<T>(), wrapper);
+        self.replace(TypeId::of::<T>(), Some(override_fn(f)));
     }
 
     /// Register a fixed override value for a dependency type.
@@ -947,10 +1241,79 @@ impl DependencyOverrides {
     where
         T: FromDependency,
     {
-        self.insert::<T, _, _>(move |_ctx, _req| {
-            let value = value.clone();
-            async move { Ok(value) }
-        });
+        self.insert::<T, _, _>(value_resolver(value));
+    }
+
+    /// Override `T` until the returned guard is dropped, then restore the
+    /// override it replaced (or none).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// {
+    ///     let _db = overrides.scoped::<Db, _, _>(|_ctx, _req| async { Ok(Db::in_memory()) });
+    ///     // requests here see the in-memory database
+    /// }
+    /// // the previous override of `Db`, if any, is back
+    /// ```
+    pub fn scoped<T, F, Fut>(&self, f: F) -> OverrideGuard<'_>
+    where
+        T: FromDependency,
+        F: Fn(&RequestContext, &mut Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, T::Error>> + Send + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+        OverrideGuard {
+            overrides: self,
+            type_id,
+            previous: self.replace(type_id, Some(override_fn(f))),
+        }
+    }
+
+    /// Override `T` with a fixed value until the returned guard is dropped.
+    pub fn scoped_value<T>(&self, value: T) -> OverrideGuard<'_>
+    where
+        T: FromDependency,
+    {
+        self.scoped::<T, _, _>(value_resolver(value))
+    }
+
+    /// Run `body` with `T` overridden by `value`, restoring the previous
+    /// override afterwards, also when `body` panics or is cancelled.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// overrides
+    ///     .with_override(Clock::fixed(NOON), || async {
+    ///         assert_eq!(call_greeting().await, "Good afternoon");
+    ///     })
+    ///     .await;
+    /// ```
+    pub async fn with_override<T, F, Fut>(&self, value: T, body: F) -> Fut::Output
+    where
+        T: FromDependency,
+        F: FnOnce() -> Fut,
+        Fut: Future,
+    {
+        let _guard = self.scoped_value(value);
+        body().await
+    }
+
+    /// Set (or, with `None`, remove) the override for `type_id`, returning
+    /// the one it replaces.
+    fn replace(&self, type_id: TypeId, f: Option<OverrideFn>) -> Option<OverrideFn> {
+        let mut guard = self.inner.write();
+        match f {
+            Some(f) => guard.insert(type_id, f),
+            None => guard.remove(&type_id),
+        }
+    }
+
+    /// The override for `type_id` here or in a parent registry.
+    fn lookup(&self, type_id: TypeId) -> Option<OverrideFn> {
+        let found = self.inner.read().get(&type_id).cloned();
+        found.or_else(|| self.parent.as_ref()?.lookup(type_id))
     }
 
     /// Clear all overrides.
@@ -976,12 +1339,7 @@ impl DependencyOverrides {
     where
         T: FromDependency,
     {
-        let override_fn = {
-            let guard = self.inner.read();
-            guard.get(&TypeId::of::<T>()).cloned()
-        };
-
-        let override_fn = override_fn?;
+        let override_fn = self.lookup(TypeId::of::<T>())?;
         match override_fn(ctx, req).await {
             Ok(value) => match value.downcast::<T>() {
                 Ok(value) => Some(Ok(*value)),
@@ -1038,10 +1396,62 @@ impl std::fmt::Debug for DependencyOverrides {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("DependencyOverrides")
             .field("size", &self.len())
+            .field("layered", &self.parent.is_some())
             .finish()
     }
 }
 
+fn override_fn<T, F, Fut>(f: F) -> OverrideFn
+where
+    T: FromDependency,
+    F: Fn(&RequestContext, &mut Request) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, T::Error>> + Send + 'static,
+{
+    Arc::new(move |ctx, req| {
+        let fut = f(ctx, req);
+        Box::pin(async move {
+            match fut.await {
+                Ok(value) => Ok(Box::new(value) as OverrideBox),
+                Err(err) => Err(Box::new(err) as OverrideBox),
+            }
+        })
+    })
+}
+
+fn value_resolver<T: FromDependency>(
+    value: T,
+) -> impl Fn(&RequestContext, &mut Request) -> std::future::Ready<Result<T, T::Error>>
++ Send
++ Sync
++ 'static {
+    move |_ctx, _req| std::future::ready(Ok(value.clone()))
+}
+
+/// Restores a dependency's previous override when dropped.
+///
+/// Returned by [`DependencyOverrides::scoped`] and
+/// [`DependencyOverrides::scoped_value`].
+#[must_use = "the override is reverted as soon as the guard is dropped"]
+pub struct OverrideGuard<'a> {
+    overrides: &'a DependencyOverrides,
+    type_id: TypeId,
+    previous: Option<OverrideFn>,
+}
+
+impl Drop for OverrideGuard<'_> {
+    fn drop(&mut self) {
+        self.overrides.replace(self.type_id, self.previous.take());
+    }
+}
+
+impl std::fmt::Debug for OverrideGuard<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OverrideGuard")
+            .field("restores_previous", &self.previous.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1127,6 +1537,91 @@ mod tests {
         assert_eq!(counter.load(Ordering::SeqCst), 1);
     }
 
+    static POOL_BUILDS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone)]
+    struct PoolDep(usize);
+
+    impl FromDependency for PoolDep {
+        type Error = HttpError;
+
+        async fn from_dependency(
+            _ctx: &RequestContext,
+            _req: &mut Request,
+        ) -> Result<Self, Self::Error> {
+            Ok(PoolDep(POOL_BUILDS.fetch_add(1, Ordering::SeqCst)))
+        }
+    }
+
+    #[test]
+    fn singleton_is_built_once_and_shared() {
+        for _ in 0..3 {
+            let ctx = test_context(None);
+            let mut req = empty_request();
+            let pool = futures_executor::block_on(Depends::<PoolDep, Singleton>::from_request(
+                &ctx, &mut req,
+            ))
+            .expect("singleton resolution failed");
+            assert_eq!(pool.0, 0);
+        }
+        assert_eq!(POOL_BUILDS.load(Ordering::SeqCst), 1);
+    }
+
+    static FLAKY_ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+    /// Fails its first four construction attempts.
+    #[derive(Clone)]
+    struct FlakyPool;
+
+    impl FromDependency for FlakyPool {
+        type Error = HttpError;
+
+        async fn from_dependency(
+            _ctx: &RequestContext,
+            _req: &mut Request,
+        ) -> Result<Self, Self::Error> {
+            if FLAKY_ATTEMPTS.fetch_add(1, Ordering::SeqCst) < 4 {
+                Err(HttpError::internal())
+            } else {
+                Ok(FlakyPool)
+            }
+        }
+    }
+
+    struct ImmediateRetry;
+
+    impl DependsConfig for ImmediateRetry {
+        const USE_CACHE: bool = true;
+        const SCOPE: Option<DependencyScope> = Some(DependencyScope::App);
+        const RETRY: SingletonRetry = SingletonRetry::new(3, Duration::ZERO, Duration::ZERO);
+    }
+
+    #[test]
+    fn singleton_construction_is_retried() {
+        let resolve = || {
+            let ctx = test_context(None);
+            let mut req = empty_request();
+            futures_executor::block_on(Depends::<FlakyPool, ImmediateRetry>::from_request(
+                &ctx, &mut req,
+            ))
+        };
+
+        assert!(resolve().is_err());
+        assert_eq!(FLAKY_ATTEMPTS.load(Ordering::SeqCst), 3);
+        assert!(resolve().is_ok());
+        assert!(resolve().is_ok());
+        assert_eq!(FLAKY_ATTEMPTS.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn singleton_retry_delay_doubles_up_to_the_cap() {
+        let retry = SingletonRetry::new(5, Duration::from_millis(100), Duration::from_millis(300));
+        assert_eq!(retry.delay(1), Duration::from_millis(100));
+        assert_eq!(retry.delay(2), Duration::from_millis(200));
+        assert_eq!(retry.delay(3), Duration::from_millis(300));
+        assert_eq!(retry.delay(40), Duration::from_millis(300));
+    }
+
     #[test]
     fn depends_no_cache_config() {
         let ctx = test_context(None);
@@ -1929,6 +2424,65 @@ mod tests {
         assert!(result.is_none(), "Unregistered type should resolve to None");
     }
 
+    #[test]
+    fn overrides_layered_fall_back_to_parent() {
+        let parent = Arc::new(DependencyOverrides::new());
+        parent.insert_value(OverrideDep { value: 1 });
+        let child = Arc::new(DependencyOverrides::layered(Arc::clone(&parent)));
+        let resolve = |overrides: &Arc<DependencyOverrides>| {
+            let ctx = test_context(Some(Arc::clone(overrides)));
+            let mut req = empty_request();
+            futures_executor::block_on(overrides.resolve::<OverrideDep>(&ctx, &mut req))
+                .map(|dep| dep.expect("override").value)
+        };
+
+        assert_eq!(resolve(&child), Some(1));
+        child.insert_value(OverrideDep { value: 2 });
+        assert_eq!(resolve(&child), Some(2));
+        assert_eq!(resolve(&parent), Some(1));
+
+        child.clear();
+        assert_eq!(resolve(&child), Some(1));
+        assert_eq!(parent.len(), 1);
+    }
+
+    #[test]
+    fn overrides_scoped_restore_previous() {
+        let overrides = Arc::new(DependencyOverrides::new());
+        let resolve = || {
+            let ctx = test_context(Some(Arc::clone(&overrides)));
+            let mut req = empty_request();
+            futures_executor::block_on(overrides.resolve::<OverrideDep>(&ctx, &mut req))
+                .map(|dep| dep.expect("override").value)
+        };
+
+        overrides.insert_value(OverrideDep { value: 1 });
+        {
+            let _outer = overrides.scoped_value(OverrideDep { value: 2 });
+            {
+                let _inner = overrides.scoped_value(OverrideDep { value: 3 });
+                assert_eq!(resolve(), Some(3));
+            }
+            assert_eq!(resolve(), Some(2));
+        }
+        assert_eq!(resolve(), Some(1));
+
+        overrides.clear();
+        let seen = futures_executor::block_on(overrides.with_override(
+            OverrideDep { value: 4 },
+            || async {
+                let ctx = test_context(Some(Arc::clone(&overrides)));
+                let mut req = empty_request();
+                overrides
+                    .resolve::<OverrideDep>(&ctx, &mut req)
+                    .await
+                    .map(|dep| dep.expect("override").value)
+            },
+        ));
+        assert_eq!(seen, Some(4));
+        assert_eq!(resolve(), None);
+    }
+
     #[test]
     fn overrides_resolve_some_for_registered_type() {
         let overrides = Arc::new(DependencyOverrides::new());
@@ -0,0 +1,426 @@
+//! Startup validation of dependency graphs.
+//!
+//! Route macros record what each handler's `Depends<T>` parameters resolve,
+//! and `#[dependency]` records the parameters of every dependency function,
+//! so the whole tree is known when the app is built. `AppBuilder::build`
+//! walks each route's tree and panics with a readable report on problems
+//! that would otherwise only surface at request time:
+//!
+//! - dependency cycles (`A -> B -> A`);
+//! - `State<T>` extracted by a dependency but never registered;
+//! - cached (request-scoped or `Singleton`) dependencies that depend on
+//!   function-scoped (`NoCache`) ones.
+//!
+//! [`App::check`](crate::App::check) reports the same findings and carries
+//! the trees, ready for `fastapi-output`'s `DependencyTreeDisplay`.
+//!
+//! Hand-written [`FromDependency`] impls declare nothing by default; they
+//! can override [`FromDependency::requirements`] to take part.
+
+use std::any::TypeId;
+
+use crate::check::CheckIssue;
+use crate::dependency::{DependencyScope, Depends, DependsConfig, FromDependency};
+use crate::extract::State;
+
+/// Something a dependency or handler needs in order to resolve.
+#[derive(Debug, Clone, Copy)]
+pub enum DependencyRequirement {
+    /// Another dependency, extracted as `Depends<T, C>`.
+    Dependency(DependencyDecl),
+    /// Shared state extracted as `State<T>`.
+    State {
+        /// Type id of `T`.
+        type_id: TypeId,
+        /// Type name of `T`.
+        name: &'static str,
+    },
+}
+
+impl DependencyRequirement {
+    /// The requirement for a `Depends<T, C>` parameter.
+    #[must_use]
+    pub fn dependency<T: FromDependency, C: DependsConfig>() -> Self {
+        Self::Dependency(DependencyDecl::of::<T, C>())
+    }
+
+    /// The requirement for a `State<T>` parameter.
+    #[must_use]
+    pub fn state<T: 'static>() -> Self {
+        Self::State {
+            type_id: TypeId::of::<T>(),
+            name: std::any::type_name::<T>(),
+        }
+    }
+}
+
+/// A dependency as used at one `Depends<T, C>` site.
+#[derive(Debug, Clone, Copy)]
+pub struct DependencyDecl {
+    /// Type id of `T`.
+    pub type_id: TypeId,
+    /// Type name of `T`.
+    pub name: &'static str,
+    /// Scope the dependency resolves in at this site.
+    pub scope: DependencyScope,
+    /// Whether the value is cached for the request.
+    pub cached: bool,
+    /// What resolving `T` needs. A function pointer so cyclic graphs can be
+    /// described without recursing forever.
+    pub requirements: fn() -> Vec<DependencyRequirement>,
+}
+
+impl DependencyDecl {
+    /// Describes `Depends<T, C>`.
+    #[must_use]
+    pub fn of<T: FromDependency, C: DependsConfig>() -> Self {
+        let scope = C::SCOPE.unwrap_or(DependencyScope::Request);
+        Self {
+            type_id: TypeId::of::<T>(),
+            name: std::any::type_name::<T>(),
+            scope,
+            cached: match scope {
+                DependencyScope::Function => false,
+                DependencyScope::Request => C::USE_CACHE,
+                DependencyScope::App => true,
+            },
+            requirements: T::requirements,
+        }
+    }
+}
+
+/// Extractors that declare dependency graph requirements.
+pub trait DeclaresRequirements {
+    /// What extracting `Self` needs.
+    fn requirements() -> Vec<DependencyRequirement>;
+}
+
+impl<T: FromDependency, C: DependsConfig> DeclaresRequirements for Depends<T, C> {
+    fn requirements() -> Vec<DependencyRequirement> {
+        vec![DependencyRequirement::dependency::<T, C>()]
+    }
+}
+
+impl<T: 'static> DeclaresRequirements for State<T> {
+    fn requirements() -> Vec<DependencyRequirement> {
+        vec![DependencyRequirement::state::<T>()]
+    }
+}
+
+/// Macro support: collects requirements from any parameter type.
+///
+/// `(&&DependencyProbe::<T>::default()).requirements()` picks
+/// [`DependencyProbeMatch`] when `T` implements [`DeclaresRequirements`] and
+/// falls back to [`DependencyProbeFallback`] otherwise.
+#[doc(hidden)]
+pub struct DependencyProbe<T>(std::marker::PhantomData<T>);
+
+impl<T> Default for DependencyProbe<T> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait DependencyProbeMatch {
+    fn requirements(&self) -> Vec<DependencyRequirement>;
+}
+
+impl<T: DeclaresRequirements> DependencyProbeMatch for &DependencyProbe<T> {
+    fn requirements(&self) -> Vec<DependencyRequirement> {
+        T::requirements()
+    }
+}
+
+#[doc(hidden)]
+pub trait DependencyProbeFallback {
+    fn requirements(&self) -> Vec<DependencyRequirement>;
+}
+
+impl<T> DependencyProbeFallback for DependencyProbe<T> {
+    fn requirements(&self) -> Vec<DependencyRequirement> {
+        Vec::new()
+    }
+}
+
+/// One node of a resolved dependency tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyTree {
+    /// Route (`GET /users`), dependency or `State<T>` name.
+    pub name: String,
+    /// Scope for dependency nodes.
+    pub scope: Option<DependencyScope>,
+    /// Whether the dependency is cached for the request.
+    pub cached: bool,
+    /// Whether this node closes a cycle (its children are not expanded).
+    pub cycle: bool,
+    /// Problem found at this node, if any.
+    pub note: Option<String>,
+    /// Child nodes.
+    pub children: Vec<DependencyTree>,
+}
+
+impl DependencyTree {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            scope: None,
+            cached: false,
+            cycle: false,
+            note: None,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Dependency trees of an app's routes with the problems found in them.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    /// One tree per route that has dependencies, rooted at the route.
+    pub trees: Vec<DependencyTree>,
+    /// Every cycle, as dependency names from the first repeated one.
+    pub cycles: Vec<Vec<String>>,
+    /// Error findings, one per distinct problem.
+    pub issues: Vec<CheckIssue>,
+}
+
+impl DependencyGraph {
+    /// Walks the dependency trees of `routes`, given as
+    /// `(route label, requirements)`; `has_state` tells whether a state type
+    /// is registered.
+    pub fn build<'a, I>(routes: I, has_state: &dyn Fn(TypeId) -> bool) -> Self
+    where
+        I: IntoIterator<Item = (String, &'a [DependencyRequirement])>,
+    {
+        let mut graph = Self::default();
+        for (route, requirements) in routes {
+            let decls: Vec<&DependencyDecl> = requirements
+                .iter()
+                .filter_map(|req| match req {
+                    DependencyRequirement::Dependency(decl) => Some(decl),
+                    DependencyRequirement::State { .. } => None,
+                })
+                .collect();
+            if decls.is_empty() {
+                continue;
+            }
+            let mut walk = Walk {
+                route: &route,
+                has_state,
+                path: Vec::new(),
+                graph: &mut graph,
+            };
+            let children = decls.into_iter().map(|decl| walk.visit(decl)).collect();
+            let mut root = DependencyTree::new(route);
+            root.children = children;
+            graph.trees.push(root);
+        }
+        graph
+    }
+
+    /// Returns true if no problems were found.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn issue(&mut self, message: String) {
+        let issue = CheckIssue::error("dependencies", message);
+        if !self.issues.contains(&issue) {
+            self.issues.push(issue);
+        }
+    }
+}
+
+struct Walk<'a> {
+    route: &'a str,
+    has_state: &'a dyn Fn(TypeId) -> bool,
+    /// Dependencies being resolved, outermost first.
+    path: Vec<DependencyDecl>,
+    graph: &'a mut DependencyGraph,
+}
+
+impl Walk<'_> {
+    fn visit(&mut self, decl: &DependencyDecl) -> DependencyTree {
+        let name = short_type_name(decl.name);
+        let mut node = DependencyTree::new(name.clone());
+        node.scope = Some(decl.scope);
+        node.cached = decl.cached;
+
+        if let Some(start) = self.path.iter().position(|d| d.type_id == decl.type_id) {
+            let cycle: Vec<String> = self.path[start..]
+                .iter()
+                .map(|d| short_type_name(d.name))
+                .chain(std::iter::once(name))
+                .collect();
+            node.cycle = true;
+            self.graph
+                .issue(format!("dependency cycle: {}", cycle.join(" -> ")));
+            if !self.graph.cycles.contains(&cycle) {
+                self.graph.cycles.push(cycle);
+            }
+            return node;
+        }
+
+        if decl.scope == DependencyScope::Function {
+            if let Some(outer) = self
+                .path
+                .iter()
+                .rev()
+                .find(|d| d.scope != DependencyScope::Function)
+            {
+                let scope = scope_name(outer.scope);
+                let outer = short_type_name(outer.name);
+                node.note = Some(format!("function-scoped inside {scope}-scoped `{outer}`"));
+                self.graph.issue(format!(
+                    "{}: {scope}-scoped dependency `{outer}` depends on function-scoped `{}`",
+                    self.route, node.name
+                ));
+            }
+        }
+
+        self.path.push(*decl);
+        for requirement in (decl.requirements)() {
+            let child = match requirement {
+                DependencyRequirement::Dependency(child) => self.visit(&child),
+                DependencyRequirement::State { type_id, name } => {
+                    let state = short_type_name(name);
+                    let mut child = DependencyTree::new(format!("State<{state}>"));
+                    if !(self.has_state)(type_id) {
+                        child.note = Some("not registered".to_string());
+                        self.graph.issue(format!(
+                            "{}: dependency `{}` requires state `{state}`, which is not registered",
+                            self.route, node.name
+                        ));
+                    }
+                    child
+                }
+            };
+            node.children.push(child);
+        }
+        self.path.pop();
+        node
+    }
+}
+
+fn scope_name(scope: DependencyScope) -> &'static str {
+    match scope {
+        DependencyScope::Function => "function",
+        DependencyScope::Request => "request",
+        DependencyScope::App => "app",
+    }
+}
+
+/// `app::deps::Pool<app::db::Pg>` → `Pool<Pg>`.
+fn short_type_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut segment_start = 0;
+    for (i, c) in name.char_indices() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            continue;
+        }
+        out.push_str(last_segment(&name[segment_start..i]));
+        out.push(c);
+        segment_start = i + c.len_utf8();
+    }
+    out.push_str(last_segment(&name[segment_start..]));
+    out
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::RequestContext;
+    use crate::dependency::{DefaultDependencyConfig, NoCache, Singleton};
+    use crate::error::HttpError;
+    use crate::request::Request;
+
+    macro_rules! node {
+        ($name:ident => [$($req:expr),*]) => {
+            #[derive(Clone)]
+            struct $name;
+
+            impl FromDependency for $name {
+                type Error = HttpError;
+
+                async fn from_dependency(
+                    _ctx: &RequestContext,
+                    _req: &mut Request,
+                ) -> Result<Self, HttpError> {
+                    Ok(Self)
+                }
+
+                fn requirements() -> Vec<DependencyRequirement> {
+                    vec![$($req),*]
+                }
+            }
+        };
+    }
+
+    struct Db;
+
+    node!(Leaf => []);
+    node!(Uncached => []);
+    node!(Service => [
+        DependencyRequirement::dependency::<Leaf, DefaultDependencyConfig>(),
+        DependencyRequirement::state::<Db>()
+    ]);
+    node!(Stale => [DependencyRequirement::dependency::<Uncached, NoCache>()]);
+    node!(Pool => [DependencyRequirement::dependency::<Uncached, NoCache>()]);
+    node!(CycleA => [DependencyRequirement::dependency::<CycleB, DefaultDependencyConfig>()]);
+    node!(CycleB => [DependencyRequirement::dependency::<CycleA, DefaultDependencyConfig>()]);
+
+    fn graph(requirements: &[DependencyRequirement], db: bool) -> DependencyGraph {
+        let db_id = TypeId::of::<Db>();
+        DependencyGraph::build([("GET /".to_string(), requirements)], &|id| {
+            db && id == db_id
+        })
+    }
+
+    #[test]
+    fn valid_tree_has_no_issues() {
+        let graph = graph(
+            &<Depends<Service> as DeclaresRequirements>::requirements(),
+            true,
+        );
+        assert!(graph.is_ok());
+        let service = &graph.trees[0].children[0];
+        assert_eq!(service.name, "Service");
+        assert!(service.cached);
+        let names: Vec<&str> = service.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Leaf", "State<Db>"]);
+    }
+
+    #[test]
+    fn reports_missing_state_scope_conflicts_and_cycles() {
+        let requirements = [
+            DependencyRequirement::dependency::<Service, DefaultDependencyConfig>(),
+            DependencyRequirement::dependency::<Stale, DefaultDependencyConfig>(),
+            DependencyRequirement::dependency::<CycleA, DefaultDependencyConfig>(),
+            DependencyRequirement::dependency::<Pool, Singleton>(),
+        ];
+        let graph = graph(&requirements, false);
+        let messages: Vec<&str> = graph.issues.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "GET /: dependency `Service` requires state `Db`, which is not registered",
+                "GET /: request-scoped dependency `Stale` depends on function-scoped `Uncached`",
+                "dependency cycle: CycleA -> CycleB -> CycleA",
+                "GET /: app-scoped dependency `Pool` depends on function-scoped `Uncached`",
+            ]
+        );
+        assert_eq!(graph.cycles, [["CycleA", "CycleB", "CycleA"]]);
+        assert!(graph.trees[0].children[2].children[0].children[0].cycle);
+    }
+
+    #[test]
+    fn shortens_type_names() {
+        assert_eq!(short_type_name("app::deps::Pool<app::db::Pg>"), "Pool<Pg>");
+        assert_eq!(short_type_name("(a::B, c::D)"), "(B, D)");
+    }
+}
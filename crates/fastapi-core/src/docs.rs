@@ -29,14 +29,25 @@
 //!     .redoc_path("/api-redoc")  // Default: /redoc
 //!     .openapi_path("/api-spec.json")  // Default: /openapi.json
 //!     .title("My API Documentation")
-//!     .swagger_ui_parameters(r#"{"docExpansion": "none"}"#);
+//!     .swagger_ui_parameters(r#"{"docExpansion": "none"}"#)
+//!     .swagger_ui_oauth(SwaggerOAuthConfig::new("my-client-id").scope("read").pkce())
+//!     .enabled(cfg!(debug_assertions));  // Hide the UIs in release builds
 //! ```
+//!
+//! Single UIs can be enabled directly on the builder with
+//! [`AppBuilder::docs`](crate::AppBuilder::docs) and
+//! [`AppBuilder::redoc`](crate::AppBuilder::redoc).
 
 use crate::response::{Response, ResponseBody};
 
 /// Configuration for the API documentation endpoints.
 #[derive(Debug, Clone)]
 pub struct DocsConfig {
+    /// Whether the documentation UIs are served at all.
+    ///
+    /// Disabling keeps the OpenAPI JSON endpoint but drops Swagger UI,
+    /// ReDoc and the OAuth2 redirect page.
+    pub enabled: bool,
     /// Path for Swagger UI. Set to None to disable.
     pub docs_path: Option<String>,
     /// Path for ReDoc. Set to None to disable.
@@ -45,6 +56,8 @@ pub struct DocsConfig {
     pub openapi_path: String,
     /// Title shown in the documentation.
     pub title: String,
+    /// Title for the ReDoc page; falls back to `title`.
+    pub redoc_title: Option<String>,
     /// Swagger UI configuration parameters (JSON).
     pub swagger_ui_parameters: Option<String>,
     /// Swagger UI OAuth initialization config (JSON).
@@ -60,10 +73,12 @@ pub struct DocsConfig {
 impl Default for DocsConfig {
     fn default() -> Self {
         Self {
+            enabled: true,
             docs_path: Some("/docs".to_string()),
             redoc_path: Some("/redoc".to_string()),
             openapi_path: "/openapi.json".to_string(),
             title: "API Documentation".to_string(),
+            redoc_title: None,
             swagger_ui_parameters: None,
             swagger_ui_init_oauth: None,
             favicon_url: None,
@@ -80,6 +95,13 @@ impl DocsConfig {
         Self::default()
     }
 
+    /// Enable or disable the documentation UIs.
+    #[must_use]
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
     /// Set the path for Swagger UI. Use None to disable.
     #[must_use]
     pub fn docs_path(mut self, path: impl Into<Option<String>>) -> Self {
@@ -108,6 +130,13 @@ impl DocsConfig {
         self
     }
 
+    /// Set a separate title for the ReDoc page.
+    #[must_use]
+    pub fn redoc_title(mut self, title: impl Into<String>) -> Self {
+        self.redoc_title = Some(title.into());
+        self
+    }
+
     /// Set Swagger UI configuration parameters (JSON object).
     ///
     /// # Example
@@ -134,6 +163,21 @@ impl DocsConfig {
         self
     }
 
+    /// Configure the OAuth2 client Swagger UI uses for "Authorize".
+    #[must_use]
+    pub fn swagger_ui_oauth(self, oauth: SwaggerOAuthConfig) -> Self {
+        self.swagger_ui_init_oauth(oauth.to_json())
+    }
+
+    /// Path of the OAuth2 redirect page, served next to Swagger UI.
+    ///
+    /// Returns `None` when Swagger UI is disabled.
+    #[must_use]
+    pub fn oauth2_redirect_path(&self) -> Option<String> {
+        let prefix = self.docs_path.as_deref()?.trim_end_matches('/');
+        Some(format!("{prefix}/oauth2-redirect"))
+    }
+
     /// Set a custom favicon URL.
     #[must_use]
     pub fn favicon_url(mut self, url: impl Into<String>) -> Self {
@@ -156,6 +200,83 @@ impl DocsConfig {
     }
 }
 
+/// OAuth2 client settings passed to Swagger UI's `initOAuth`.
+#[derive(Debug, Clone, Default)]
+pub struct SwaggerOAuthConfig {
+    /// OAuth2 client id.
+    pub client_id: String,
+    /// Realm query parameter (OAuth1-style providers).
+    pub realm: Option<String>,
+    /// Application name shown in the authorization popup.
+    pub app_name: Option<String>,
+    /// Scopes pre-selected in the authorization dialog.
+    pub scopes: Vec<String>,
+    /// Use PKCE with the authorization code flow.
+    pub use_pkce: bool,
+}
+
+impl SwaggerOAuthConfig {
+    /// Create a config for the given client id.
+    #[must_use]
+    pub fn new(client_id: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Set the realm.
+    #[must_use]
+    pub fn realm(mut self, realm: impl Into<String>) -> Self {
+        self.realm = Some(realm.into());
+        self
+    }
+
+    /// Set the application name.
+    #[must_use]
+    pub fn app_name(mut self, name: impl Into<String>) -> Self {
+        self.app_name = Some(name.into());
+        self
+    }
+
+    /// Pre-select a scope.
+    #[must_use]
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scopes.push(scope.into());
+        self
+    }
+
+    /// Enable PKCE for the authorization code flow.
+    #[must_use]
+    pub fn pkce(mut self) -> Self {
+        self.use_pkce = true;
+        self
+    }
+
+    /// Render as the JSON object Swagger UI expects.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut obj = serde_json::Map::new();
+        obj.insert("clientId".into(), self.client_id.clone().into());
+        if let Some(realm) = &self.realm {
+            obj.insert("realm".into(), realm.clone().into());
+        }
+        if let Some(app_name) = &self.app_name {
+            obj.insert("appName".into(), app_name.clone().into());
+        }
+        if !self.scopes.is_empty() {
+            obj.insert("scopes".into(), self.scopes.clone().into());
+        }
+        if self.use_pkce {
+            obj.insert("usePkceWithAuthorizationCodeGrant".into(), true.into());
+        }
+        // Escape `<` so the JSON cannot close the surrounding <script> tag.
+        serde_json::Value::Object(obj)
+            .to_string()
+            .replace('<', "\\u003c")
+    }
+}
+
 /// Generate the Swagger UI HTML page.
 ///
 /// # Arguments
@@ -182,6 +303,17 @@ pub fn swagger_ui_html(config: &DocsConfig, openapi_url: &str) -> String {
         .as_ref()
         .map_or_else(String::new, |o| format!("ui.initOAuth({});", o));
 
+    let oauth2_redirect = config
+        .oauth2_redirect_path()
+        .map_or_else(String::new, |path| {
+            format!(
+                "oauth2RedirectUrl: window.location.origin + {},\n                ",
+                serde_json::Value::String(path)
+                    .to_string()
+                    .replace('<', "\\u003c")
+            )
+        });
+
     format!(
         r#"<!DOCTYPE html>
 <html lang="en">
@@ -201,7 +333,7 @@ pub fn swagger_ui_html(config: &DocsConfig, openapi_url: &str) -> String {
             const ui = SwaggerUIBundle(Object.assign({{
                 url: "{openapi_url}",
                 dom_id: '#swagger-ui',
-                deepLinking: true,
+                {oauth2_redirect}deepLinking: true,
                 presets: [
                     SwaggerUIBundle.presets.apis,
                     SwaggerUIStandalonePreset
@@ -221,6 +353,7 @@ pub fn swagger_ui_html(config: &DocsConfig, openapi_url: &str) -> String {
         favicon = favicon,
         swagger_cdn = swagger_cdn,
         openapi_url = html_escape(openapi_url),
+        oauth2_redirect = oauth2_redirect,
         ui_parameters = ui_parameters,
         init_oauth = init_oauth,
     )
@@ -234,7 +367,7 @@ pub fn swagger_ui_html(config: &DocsConfig, openapi_url: &str) -> String {
 /// * `openapi_url` - URL to the OpenAPI JSON specification
 #[must_use]
 pub fn redoc_html(config: &DocsConfig, openapi_url: &str) -> String {
-    let title = html_escape(&config.title);
+    let title = html_escape(config.redoc_title.as_deref().unwrap_or(&config.title));
     let redoc_cdn = &config.redoc_cdn_url;
 
     let favicon = config.favicon_url.as_ref().map_or_else(String::new, |url| {
@@ -485,4 +618,57 @@ mod tests {
         assert!(swagger_html.contains("https://custom.cdn/swagger"));
         assert!(redoc_html.contains("https://custom.cdn/redoc"));
     }
+
+    #[test]
+    fn test_disable_and_redoc_title() {
+        let config = DocsConfig::new()
+            .enabled(false)
+            .title("Swagger")
+            .redoc_title("Reference");
+        assert!(!config.enabled);
+        assert!(swagger_ui_html(&config, "/openapi.json").contains("<title>Swagger</title>"));
+        assert!(redoc_html(&config, "/openapi.json").contains("<title>Reference</title>"));
+    }
+
+    #[test]
+    fn test_swagger_oauth_config() {
+        let oauth = SwaggerOAuthConfig::new("client-1")
+            .app_name("Console")
+            .scope("read")
+            .scope("write")
+            .pkce();
+        let json: serde_json::Value = serde_json::from_str(&oauth.to_json()).unwrap();
+        assert_eq!(json["clientId"], "client-1");
+        assert_eq!(json["appName"], "Console");
+        assert_eq!(json["scopes"], serde_json::json!(["read", "write"]));
+        assert_eq!(json["usePkceWithAuthorizationCodeGrant"], true);
+        assert!(json.get("realm").is_none());
+
+        let html = swagger_ui_html(&DocsConfig::new().swagger_ui_oauth(oauth), "/openapi.json");
+        assert!(html.contains("ui.initOAuth({"));
+        assert!(
+            html.contains("oauth2RedirectUrl: window.location.origin + \"/docs/oauth2-redirect\"")
+        );
+    }
+
+    #[test]
+    fn test_oauth2_redirect_path() {
+        assert_eq!(
+            DocsConfig::new().oauth2_redirect_path().as_deref(),
+            Some("/docs/oauth2-redirect")
+        );
+        assert_eq!(
+            DocsConfig::new()
+                .docs_path(Some("/".to_string()))
+                .oauth2_redirect_path()
+                .as_deref(),
+            Some("/oauth2-redirect")
+        );
+        assert!(
+            DocsConfig::new()
+                .docs_path(None::<String>)
+                .oauth2_redirect_path()
+                .is_none()
+        );
+    }
 }
@@ -0,0 +1,603 @@
+//! Assets embedded in the binary at compile time.
+//!
+//! The `embed_dir!` macro (from `fastapi-macros`) walks a directory at build
+//! time and produces an [`EmbeddedDir`]; [`EmbeddedAssets`] serves it without
+//! touching the filesystem, so a single binary carries its own frontend.
+//!
+//! Everything that can be computed ahead of a request is: content types,
+//! strong ETags and encoded variants. Precompressed `.br` / `.gz` siblings
+//! (e.g. `app.js.br` next to `app.js`) are used as variants of their source
+//! file; with the `compression` feature, compressible files without a `.gz`
+//! sibling get a gzip variant at startup. Responses carry a long-lived
+//! `immutable` cache policy by default, which suits fingerprinted asset names.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use fastapi::prelude::*;
+//! use fastapi::embed_dir;
+//!
+//! let app = App::builder()
+//!     .serve_embedded("/static", embed_dir!("static"))
+//!     .build();
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::extract::PathParams;
+use crate::request::Request;
+use crate::response::{
+    Response, ResponseBody, StatusCode, check_if_none_match, mime_type_for_extension,
+};
+
+/// Default `Cache-Control` for embedded assets.
+pub const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// A single file embedded at compile time.
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddedFile {
+    path: &'static str,
+    bytes: &'static [u8],
+}
+
+impl EmbeddedFile {
+    /// Creates an entry for `bytes` at `path`, relative to the embedded root
+    /// and separated by `/` (e.g. `css/app.css`).
+    #[must_use]
+    pub const fn new(path: &'static str, bytes: &'static [u8]) -> Self {
+        Self { path, bytes }
+    }
+
+    /// Path relative to the embedded root.
+    #[must_use]
+    pub const fn path(&self) -> &'static str {
+        self.path
+    }
+
+    /// File contents.
+    #[must_use]
+    pub const fn bytes(&self) -> &'static [u8] {
+        self.bytes
+    }
+}
+
+/// A directory tree embedded at compile time, usually built by `embed_dir!`.
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddedDir {
+    files: &'static [EmbeddedFile],
+}
+
+impl EmbeddedDir {
+    /// Wraps a static list of files.
+    #[must_use]
+    pub const fn new(files: &'static [EmbeddedFile]) -> Self {
+        Self { files }
+    }
+
+    /// Looks up a file by its path relative to the root.
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&'static EmbeddedFile> {
+        let files: &'static [EmbeddedFile] = self.files;
+        files.iter().find(|file| file.path == path)
+    }
+
+    /// All embedded files.
+    #[must_use]
+    pub const fn files(&self) -> &'static [EmbeddedFile] {
+        self.files
+    }
+
+    /// Number of embedded files.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether the directory is empty.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// An embedded file with everything a response needs precomputed.
+struct PreparedAsset {
+    bytes: &'static [u8],
+    content_type: &'static str,
+    etag: String,
+    gzip: Option<Cow<'static, [u8]>>,
+    br: Option<&'static [u8]>,
+}
+
+impl PreparedAsset {
+    /// Picks the smallest representation the client accepts: `(coding, bytes)`,
+    /// with `None` for the identity encoding.
+    fn negotiate(&self, req: &Request) -> (Option<&'static str>, &[u8]) {
+        let variants = [("br", self.br), ("gzip", self.gzip.as_deref())];
+        variants
+            .into_iter()
+            .filter_map(|(coding, bytes)| Some((coding, bytes?)))
+            .filter(|(coding, _)| accepts_encoding(req, coding))
+            .map(|(coding, bytes)| (Some(coding), bytes))
+            .chain(std::iter::once((None, self.bytes)))
+            .min_by_key(|(_, bytes)| bytes.len())
+            .unwrap_or((None, self.bytes))
+    }
+
+    fn has_variants(&self) -> bool {
+        self.gzip.is_some() || self.br.is_some()
+    }
+}
+
+/// Serves an [`EmbeddedDir`] with ETags, gzip variants and cache headers.
+///
+/// Build it once at startup; [`serve`](Self::serve) does no hashing or
+/// compression per request.
+pub struct EmbeddedAssets {
+    assets: HashMap<&'static str, PreparedAsset>,
+    cache_control: String,
+    index_file: Option<String>,
+}
+
+impl EmbeddedAssets {
+    /// Prepares every file in `dir`.
+    ///
+    /// `name.br` and `name.gz` files become the brotli and gzip variants of
+    /// `name` (and stay servable under their own names). Without a `.gz`
+    /// sibling, a gzip variant is computed only when the `compression`
+    /// feature is enabled, the content type is not already compressed, and
+    /// the result is smaller.
+    #[must_use]
+    pub fn new(dir: EmbeddedDir) -> Self {
+        let sibling = |path: &str, ext: &str| dir.get(&format!("{path}.{ext}")).map(|f| f.bytes);
+        let assets = dir
+            .files()
+            .iter()
+            .map(|file| {
+                let content_type = content_type_for(file.path);
+                let gzip = match sibling(file.path, "gz") {
+                    Some(bytes) => Some(Cow::Borrowed(bytes)),
+                    None => gzip_variant(file.bytes, content_type).map(Cow::Owned),
+                };
+                let asset = PreparedAsset {
+                    bytes: file.bytes,
+                    content_type,
+                    etag: strong_etag(file.bytes),
+                    gzip,
+                    br: sibling(file.path, "br"),
+                };
+                (file.path, asset)
+            })
+            .collect();
+
+        Self {
+            assets,
+            cache_control: IMMUTABLE_CACHE_CONTROL.to_string(),
+            index_file: Some("index.html".to_string()),
+        }
+    }
+
+    /// Sets the `Cache-Control` value sent with every asset.
+    ///
+    /// Defaults to [`IMMUTABLE_CACHE_CONTROL`]; use something like
+    /// `"no-cache"` if asset names are not content-hashed.
+    #[must_use]
+    pub fn cache_control(mut self, value: impl Into<String>) -> Self {
+        self.cache_control = value.into();
+        self
+    }
+
+    /// Sets the file served for directory paths (default `index.html`).
+    #[must_use]
+    pub fn index_file(mut self, file: Option<impl Into<String>>) -> Self {
+        self.index_file = file.map(Into::into);
+        self
+    }
+
+    /// Number of embedded files.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    /// Whether no files are embedded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+
+    /// Whether a gzip variant was precomputed for `path`.
+    #[must_use]
+    pub fn has_gzip_variant(&self, path: &str) -> bool {
+        self.assets
+            .get(path)
+            .is_some_and(|asset| asset.gzip.is_some())
+    }
+
+    /// Whether a `.br` sibling was embedded for `path`.
+    #[must_use]
+    pub fn has_brotli_variant(&self, path: &str) -> bool {
+        self.assets
+            .get(path)
+            .is_some_and(|asset| asset.br.is_some())
+    }
+
+    /// Builds the response for `path`, relative to the embedded root.
+    ///
+    /// Empty paths and paths ending in `/` resolve to the index file. Sends
+    /// the smallest variant the client's `Accept-Encoding` allows, with an
+    /// ETag specific to that encoding, and answers `304 Not Modified` when
+    /// `If-None-Match` matches it.
+    #[must_use]
+    pub fn serve(&self, req: &Request, path: &str) -> Response {
+        let path = path.trim_start_matches('/');
+        let resolved;
+        let path = match &self.index_file {
+            Some(index) if path.is_empty() || path.ends_with('/') => {
+                resolved = format!("{path}{index}");
+                resolved.as_str()
+            }
+            _ => path,
+        };
+
+        let Some(asset) = self.assets.get(path) else {
+            return Response::with_status(StatusCode::NOT_FOUND);
+        };
+
+        let (coding, body) = asset.negotiate(req);
+        let etag = match coding {
+            Some(coding) => variant_etag(&asset.etag, coding),
+            None => asset.etag.clone(),
+        };
+
+        let if_none_match = req
+            .headers()
+            .get("if-none-match")
+            .and_then(|value| std::str::from_utf8(value).ok());
+        let mut response = match if_none_match {
+            Some(if_none_match) if !check_if_none_match(if_none_match, &etag) => {
+                Response::not_modified()
+            }
+            _ => Response::ok()
+                .header("content-type", asset.content_type.as_bytes().to_vec())
+                .body(ResponseBody::Bytes(body.to_vec())),
+        };
+        response = response
+            .header("etag", etag.into_bytes())
+            .header("cache-control", self.cache_control.clone().into_bytes());
+        if asset.has_variants() {
+            response = response.header("vary", b"accept-encoding".to_vec());
+        }
+        if let Some(coding) = coding {
+            if response.status().as_u16() == 200 {
+                response = response.header("content-encoding", coding.as_bytes().to_vec());
+            }
+        }
+        response
+    }
+
+    /// Serves the file named by the `path` route parameter.
+    ///
+    /// Used by `AppBuilder::serve_embedded`, which mounts routes with a
+    /// `{*path}` wildcard; a missing parameter serves the index file.
+    #[must_use]
+    pub fn serve_request(&self, req: &Request) -> Response {
+        let path = req
+            .get_extension::<PathParams>()
+            .and_then(|params| params.get("path"))
+            .unwrap_or("");
+        self.serve(req, path)
+    }
+}
+
+impl From<EmbeddedDir> for EmbeddedAssets {
+    fn from(dir: EmbeddedDir) -> Self {
+        Self::new(dir)
+    }
+}
+
+impl std::fmt::Debug for EmbeddedAssets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmbeddedAssets")
+            .field("files", &self.assets.len())
+            .field("cache_control", &self.cache_control)
+            .field("index_file", &self.index_file)
+            .finish()
+    }
+}
+
+fn content_type_for(path: &str) -> &'static str {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    match file_name.rsplit_once('.') {
+        Some((_, ext)) => mime_type_for_extension(ext),
+        None => "application/octet-stream",
+    }
+}
+
+/// Strong ETag from an FNV-1a hash of the contents and their length.
+fn strong_etag(bytes: &[u8]) -> String {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    format!("\"{:016x}-{:x}\"", hash, bytes.len())
+}
+
+/// ETag of an encoded variant: the identity ETag with the coding appended,
+/// so caches never serve one encoding in place of another.
+pub(crate) fn variant_etag(etag: &str, coding: &str) -> String {
+    format!("{}-{coding}\"", etag.trim_end_matches('"'))
+}
+
+/// Whether a content type is worth compressing.
+#[cfg_attr(not(feature = "compression"), allow(dead_code))]
+pub(crate) fn is_compressible(content_type: &str) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    essence.starts_with("text/")
+        || essence.ends_with("+json")
+        || essence.ends_with("+xml")
+        || matches!(
+            essence.as_str(),
+            "application/json"
+                | "application/javascript"
+                | "application/xml"
+                | "application/wasm"
+                | "image/svg+xml"
+        )
+}
+
+#[cfg(feature = "compression")]
+pub(crate) fn gzip_variant(bytes: &[u8], content_type: &str) -> Option<Vec<u8>> {
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    if bytes.is_empty() || !is_compressible(content_type) {
+        return None;
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(bytes).ok()?;
+    let compressed = encoder.finish().ok()?;
+    (compressed.len() < bytes.len()).then_some(compressed)
+}
+
+#[cfg(not(feature = "compression"))]
+pub(crate) fn gzip_variant(_bytes: &[u8], _content_type: &str) -> Option<Vec<u8>> {
+    None
+}
+
+/// Whether `Accept-Encoding` allows `coding` (`q=0` opts out).
+pub(crate) fn accepts_encoding(req: &Request, coding: &str) -> bool {
+    let Some(value) = req
+        .headers()
+        .get("accept-encoding")
+        .and_then(|value| std::str::from_utf8(value).ok())
+    else {
+        return false;
+    };
+    value.split(',').any(|part| {
+        let mut pieces = part.split(';');
+        let name = pieces.next().unwrap_or("").trim();
+        let rejected = pieces.any(|param| {
+            param
+                .trim()
+                .strip_prefix("q=")
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .is_some_and(|q| q <= 0.0)
+        });
+        !rejected && (name.eq_ignore_ascii_case(coding) || name == "*")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+
+    static FILES: &[EmbeddedFile] = &[
+        EmbeddedFile::new("index.html", b"<h1>home</h1>"),
+        EmbeddedFile::new("css/app.css", b"body { color: red; }"),
+        EmbeddedFile::new("docs/index.html", b"<h1>docs</h1>"),
+        EmbeddedFile::new("LICENSE", b"MIT"),
+    ];
+
+    fn assets() -> EmbeddedAssets {
+        EmbeddedAssets::new(EmbeddedDir::new(FILES))
+    }
+
+    fn header<'a>(response: &'a Response, name: &str) -> Option<&'a str> {
+        response
+            .headers()
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .and_then(|(_, v)| std::str::from_utf8(v).ok())
+    }
+
+    #[test]
+    fn dir_lookup() {
+        let dir = EmbeddedDir::new(FILES);
+        assert_eq!(dir.len(), 4);
+        assert_eq!(dir.get("LICENSE").unwrap().bytes(), b"MIT");
+        assert!(dir.get("missing.txt").is_none());
+    }
+
+    #[test]
+    fn serves_file_with_cache_headers() {
+        let req = Request::new(Method::Get, "/static/css/app.css");
+        let response = assets().serve(&req, "css/app.css");
+
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(
+            header(&response, "content-type"),
+            Some("text/css; charset=utf-8")
+        );
+        assert_eq!(
+            header(&response, "cache-control"),
+            Some(IMMUTABLE_CACHE_CONTROL)
+        );
+        assert!(header(&response, "etag").unwrap().starts_with('"'));
+        assert_eq!(response.body_ref().len(), 20);
+    }
+
+    #[test]
+    fn directories_resolve_to_index() {
+        let assets = assets();
+        let req = Request::new(Method::Get, "/");
+        assert_eq!(assets.serve(&req, "").body_ref().len(), 13);
+        assert_eq!(assets.serve(&req, "docs/").status().as_u16(), 200);
+
+        let assets = assets.index_file(None::<String>);
+        assert_eq!(assets.serve(&req, "").status().as_u16(), 404);
+    }
+
+    #[test]
+    fn unknown_path_is_not_found() {
+        let req = Request::new(Method::Get, "/nope");
+        assert_eq!(assets().serve(&req, "nope.js").status().as_u16(), 404);
+    }
+
+    #[test]
+    fn matching_etag_returns_not_modified() {
+        let assets = assets();
+        let req = Request::new(Method::Get, "/LICENSE");
+        let etag = header(&assets.serve(&req, "LICENSE"), "etag")
+            .unwrap()
+            .to_string();
+
+        let mut req = Request::new(Method::Get, "/LICENSE");
+        req.headers_mut()
+            .insert("if-none-match", etag.clone().into_bytes());
+        let response = assets.serve(&req, "LICENSE");
+        assert_eq!(response.status().as_u16(), 304);
+        assert_eq!(header(&response, "etag"), Some(etag.as_str()));
+    }
+
+    #[test]
+    fn etag_depends_on_contents() {
+        assert_eq!(strong_etag(b"a"), strong_etag(b"a"));
+        assert_ne!(strong_etag(b"a"), strong_etag(b"b"));
+    }
+
+    #[test]
+    fn custom_cache_control() {
+        let assets = assets().cache_control("no-cache");
+        let req = Request::new(Method::Get, "/LICENSE");
+        let response = assets.serve(&req, "LICENSE");
+        assert_eq!(header(&response, "cache-control"), Some("no-cache"));
+    }
+
+    #[test]
+    fn accept_encoding_parsing() {
+        let mut req = Request::new(Method::Get, "/");
+        assert!(!accepts_encoding(&req, "gzip"));
+        req.headers_mut()
+            .insert("accept-encoding", b"br, gzip;q=0.8".to_vec());
+        assert!(accepts_encoding(&req, "gzip"));
+        assert!(accepts_encoding(&req, "br"));
+
+        let mut req = Request::new(Method::Get, "/");
+        req.headers_mut()
+            .insert("accept-encoding", b"gzip;q=0, br".to_vec());
+        assert!(!accepts_encoding(&req, "gzip"));
+        assert!(accepts_encoding(&req, "br"));
+    }
+
+    #[test]
+    fn precompressed_siblings_pick_smallest_accepted() {
+        static PRE: &[EmbeddedFile] = &[
+            EmbeddedFile::new("app.js", b"console.log('precompressed asset');"),
+            EmbeddedFile::new("app.js.br", b"brbytes"),
+            EmbeddedFile::new("app.js.gz", b"gzip-bytes"),
+        ];
+        let assets = EmbeddedAssets::new(EmbeddedDir::new(PRE));
+        assert!(assets.has_brotli_variant("app.js"));
+        assert!(assets.has_gzip_variant("app.js"));
+
+        let mut req = Request::new(Method::Get, "/app.js");
+        req.headers_mut()
+            .insert("accept-encoding", b"gzip, br".to_vec());
+        let br = assets.serve(&req, "app.js");
+        assert_eq!(header(&br, "content-encoding"), Some("br"));
+        assert_eq!(br.body_ref().len(), 7);
+        assert!(header(&br, "etag").unwrap().ends_with("-br\""));
+
+        let mut req = Request::new(Method::Get, "/app.js");
+        req.headers_mut()
+            .insert("accept-encoding", b"gzip".to_vec());
+        let gz = assets.serve(&req, "app.js");
+        assert_eq!(header(&gz, "content-encoding"), Some("gzip"));
+        assert_eq!(header(&gz, "vary"), Some("accept-encoding"));
+        assert_ne!(header(&gz, "etag"), header(&br, "etag"));
+
+        let plain = assets.serve(&Request::new(Method::Get, "/app.js"), "app.js");
+        assert!(header(&plain, "content-encoding").is_none());
+        assert_eq!(header(&plain, "vary"), Some("accept-encoding"));
+    }
+
+    #[test]
+    fn not_modified_uses_variant_etag() {
+        static PRE: &[EmbeddedFile] = &[
+            EmbeddedFile::new("app.css", b"body { margin: 0; padding: 0; }"),
+            EmbeddedFile::new("app.css.br", b"tiny"),
+        ];
+        let assets = EmbeddedAssets::new(EmbeddedDir::new(PRE));
+        let mut req = Request::new(Method::Get, "/app.css");
+        req.headers_mut().insert("accept-encoding", b"br".to_vec());
+        let etag = header(&assets.serve(&req, "app.css"), "etag")
+            .unwrap()
+            .to_string();
+
+        req.headers_mut()
+            .insert("if-none-match", etag.clone().into_bytes());
+        let response = assets.serve(&req, "app.css");
+        assert_eq!(response.status().as_u16(), 304);
+        assert!(header(&response, "content-encoding").is_none());
+
+        // The identity representation has a different ETag.
+        let mut req = Request::new(Method::Get, "/app.css");
+        req.headers_mut().insert("if-none-match", etag.into_bytes());
+        assert_eq!(assets.serve(&req, "app.css").status().as_u16(), 200);
+    }
+
+    #[test]
+    fn compressible_types() {
+        assert!(is_compressible("text/css"));
+        assert!(is_compressible("application/json; charset=utf-8"));
+        assert!(is_compressible("image/svg+xml"));
+        assert!(!is_compressible("image/png"));
+        assert!(!is_compressible("font/woff2"));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn gzip_variant_served_when_accepted() {
+        static BIG: &[EmbeddedFile] = &[EmbeddedFile::new(
+            "app.js",
+            b"console.log('hello'); console.log('hello'); console.log('hello'); console.log('hello');",
+        )];
+        let assets = EmbeddedAssets::new(EmbeddedDir::new(BIG));
+        assert!(assets.has_gzip_variant("app.js"));
+
+        let mut req = Request::new(Method::Get, "/app.js");
+        req.headers_mut()
+            .insert("accept-encoding", b"gzip".to_vec());
+        let response = assets.serve(&req, "app.js");
+        assert_eq!(header(&response, "content-encoding"), Some("gzip"));
+        assert_eq!(header(&response, "vary"), Some("accept-encoding"));
+
+        let req = Request::new(Method::Get, "/app.js");
+        let response = assets.serve(&req, "app.js");
+        assert!(header(&response, "content-encoding").is_none());
+        assert_eq!(header(&response, "vary"), Some("accept-encoding"));
+    }
+}
@@ -341,7 +341,7 @@ macro_rules! debug_location {
 /// let idx = LocItem::index(0);
 /// assert_eq!(idx.as_index(), Some(0));
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum LocItem {
     /// Field name (string).
     Field(String),
@@ -542,12 +542,30 @@ pub mod error_types {
     pub const ENUM: &str = "enum";
     /// Extra field not allowed.
     pub const EXTRA_FORBIDDEN: &str = "extra_forbidden";
+    /// Value is not a valid list.
+    pub const LIST_TYPE: &str = "list_type";
+    /// Value is not a valid object.
+    pub const DICT_TYPE: &str = "dict_type";
 
     // Response validation error types
     /// Response failed to serialize (e.g., JSON serialization error).
     pub const SERIALIZATION_ERROR: &str = "serialization_error";
     /// Response doesn't match the declared response model.
     pub const MODEL_VALIDATION_ERROR: &str = "model_validation_error";
+
+    // Header policy error types
+    /// A header that may appear once was sent more than once.
+    pub const HEADER_REPEATED: &str = "header_repeated";
+    /// A header value exceeds the configured length.
+    pub const HEADER_TOO_LONG: &str = "header_too_long";
+    /// A header value contains control characters.
+    pub const HEADER_INVALID_CHARS: &str = "header_invalid_chars";
+
+    // Upload policy error types
+    /// An uploaded file's content type is not allowed.
+    pub const UPLOAD_CONTENT_TYPE: &str = "upload_content_type";
+    /// An uploaded file's name or extension is not allowed.
+    pub const UPLOAD_FILENAME: &str = "upload_filename";
 }
 
 // ============================================================================
@@ -699,36 +717,32 @@ impl HttpError {
 
 impl IntoResponse for HttpError {
     fn into_response(self) -> Response {
+        #[derive(Serialize)]
+        struct ErrorBody<'a> {
+            detail: &'a str,
+        }
+
         let detail = self
             .detail
             .as_deref()
             .unwrap_or_else(|| self.status.canonical_reason());
 
         // Conditionally include debug info based on global debug mode flag
-        let body = if is_debug_mode_enabled() {
-            if let Some(ref debug_info) = self.debug_info {
-                #[derive(Serialize)]
-                struct ErrorBodyWithDebug<'a> {
-                    detail: &'a str,
-                    debug: &'a DebugInfo,
-                }
-                serde_json::to_vec(&ErrorBodyWithDebug {
-                    detail,
-                    debug: debug_info,
-                })
-                .unwrap_or_default()
-            } else {
-                #[derive(Serialize)]
-                struct ErrorBody<'a> {
-                    detail: &'a str,
-                }
-                serde_json::to_vec(&ErrorBody { detail }).unwrap_or_default()
-            }
-        } else {
+        let debug_info = self.debug_info.as_ref().filter(|_| is_debug_mode_enabled());
+        let body = if let Some(debug_info) = debug_info {
             #[derive(Serialize)]
-            struct ErrorBody<'a> {
+            struct ErrorBodyWithDebug<'a> {
                 detail: &'a str,
+                debug: &'a DebugInfo,
             }
+            serde_json::to_vec(&ErrorBodyWithDebug {
+                detail,
+                debug: debug_info,
+            })
+            .unwrap_or_default()
+        } else if self.detail.is_none() {
+            crate::error_bodies::ErrorBodyCache::global().status_body(self.status)
+        } else {
             serde_json::to_vec(&ErrorBody { detail }).unwrap_or_default()
         };
 
@@ -756,6 +770,74 @@ impl std::fmt::Display for HttpError {
 
 impl std::error::Error for HttpError {}
 
+// ============================================================================
+// Handler Error
+// ============================================================================
+
+/// Any error returned from a handler, for the app's exception handlers.
+///
+/// Every `std::error::Error` converts into `HandlerError`, so handlers can
+/// use `?` on their own error types and leave the response to an
+/// [`ExceptionHandlers`](crate::app::ExceptionHandlers) entry for that type:
+///
+/// ```ignore
+/// async fn get_order(id: Path<u64>, repo: State<Repo>) -> Result<Json<Order>, HandlerError> {
+///     Ok(Json(repo.find(*id)?)) // `RepoError` goes to its registered handler
+/// }
+///
+/// let app = App::builder()
+///     .exception_handler(|_ctx, err: RepoError| {
+///         HttpError::not_found().with_detail(err.to_string()).into_response()
+///     })
+///     .get("/orders/{id}", get_order)
+///     .build();
+/// ```
+///
+/// An [`HttpError`] or [`ValidationErrors`] becomes its own response. Other
+/// errors without a registered handler become a plain 500.
+#[derive(Debug)]
+pub struct HandlerError(Box<dyn std::error::Error + Send + Sync>);
+
+impl HandlerError {
+    /// Returns a reference to the error if it is of type `E`.
+    #[must_use]
+    pub fn downcast_ref<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        self.0.downcast_ref::<E>()
+    }
+
+    /// Returns the boxed error.
+    #[must_use]
+    pub fn into_inner(self) -> Box<dyn std::error::Error + Send + Sync> {
+        self.0
+    }
+}
+
+impl<E: std::error::Error + Send + Sync + 'static> From<E> for HandlerError {
+    fn from(error: E) -> Self {
+        Self(Box::new(error))
+    }
+}
+
+impl std::fmt::Display for HandlerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl IntoResponse for HandlerError {
+    fn into_response(self) -> Response {
+        let error = match self.0.downcast::<HttpError>() {
+            Ok(error) => return error.into_response(),
+            Err(error) => error,
+        };
+        let error = match error.downcast::<ValidationErrors>() {
+            Ok(errors) => return errors.into_response(),
+            Err(error) => error,
+        };
+        HttpError::internal().into_response().with_error(error)
+    }
+}
+
 // ============================================================================
 // Validation Error (FastAPI-compatible)
 // ============================================================================
@@ -1117,17 +1199,16 @@ impl ValidationErrors {
     }
 
     /// Convert to JSON bytes.
+    ///
+    /// Produces the same bytes as [`to_json`](Self::to_json), reusing the
+    /// serialized `type`/`loc`/`msg` of errors seen before (see
+    /// [`ErrorBodyCache`](crate::error_bodies::ErrorBodyCache)).
     #[must_use]
     pub fn to_json_bytes(&self) -> Vec<u8> {
-        #[derive(Serialize)]
-        struct Body<'a> {
-            detail: &'a [ValidationError],
-        }
-
-        serde_json::to_vec(&Body {
-            detail: &self.errors,
-        })
-        .unwrap_or_else(|_| b"{\"detail\":[]}".to_vec())
+        let mut out = Vec::with_capacity(16 + self.errors.len() * 96);
+        crate::error_bodies::ErrorBodyCache::global()
+            .write_validation_errors(&self.errors, &mut out);
+        out
     }
 
     /// Merge another ValidationErrors into this one.
@@ -0,0 +1,300 @@
+//! Pre-serialized error response bodies.
+//!
+//! Validation failures and router errors are answered often and with nearly
+//! identical bodies. [`ErrorBodyCache`] keeps the serialized JSON that does
+//! not change between requests, so building an error response mostly copies
+//! bytes instead of running the serializer:
+//!
+//! - the `{"detail": "<reason>"}` body of every status answered without a
+//!   custom detail (404, 405, ...);
+//! - the fixed part of each 422 error item (`type`, `loc` and `msg`), with
+//!   only `input` and `ctx` serialized per response.
+//!
+//! `AppBuilder::build` warms the cache with the 404/405 bodies and, per
+//! route, with the "missing parameter" errors its path and required query
+//! parameters can produce. Other error items are cached the first time they
+//! are sent, up to the cache's capacity.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, OnceLock};
+
+use parking_lot::RwLock;
+use serde::Serialize;
+
+use crate::error::{LocItem, ValidationError};
+use crate::extract::{IntoValidationErrors, PathExtractError, QueryExtractError};
+use crate::response::StatusCode;
+
+/// Default number of error item skeletons kept by [`ErrorBodyCache::global`].
+pub const DEFAULT_ERROR_BODY_CAPACITY: usize = 1024;
+
+/// The serialized fixed part of a validation error item.
+struct Skeleton {
+    error_type: &'static str,
+    loc: Vec<LocItem>,
+    msg: String,
+    /// `{"type":...,"loc":[...],"msg":...` without the closing brace.
+    bytes: Arc<[u8]>,
+}
+
+impl Skeleton {
+    fn matches(&self, error: &ValidationError) -> bool {
+        self.error_type == error.error_type && self.msg == error.msg && self.loc == error.loc
+    }
+}
+
+/// Cache of serialized error envelopes.
+pub struct ErrorBodyCache {
+    items: RwLock<HashMap<u64, Skeleton>>,
+    statuses: RwLock<HashMap<u16, Arc<[u8]>>>,
+    capacity: usize,
+}
+
+impl Default for ErrorBodyCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_ERROR_BODY_CAPACITY)
+    }
+}
+
+impl ErrorBodyCache {
+    /// Create a cache holding at most `capacity` error item skeletons.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            items: RwLock::new(HashMap::new()),
+            statuses: RwLock::new(HashMap::new()),
+            capacity,
+        }
+    }
+
+    /// The process-wide cache used by `HttpError` and `ValidationErrors`.
+    pub fn global() -> &'static Self {
+        static CACHE: OnceLock<ErrorBodyCache> = OnceLock::new();
+        CACHE.get_or_init(Self::default)
+    }
+
+    /// Number of cached error item skeletons.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.read().len()
+    }
+
+    /// Whether no error item skeletons are cached.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.read().is_empty()
+    }
+
+    /// The `{"detail": "<reason>"}` body for `status`.
+    #[must_use]
+    pub fn status_body(&self, status: StatusCode) -> Vec<u8> {
+        if let Some(body) = self.statuses.read().get(&status.as_u16()) {
+            return body.to_vec();
+        }
+        let body: Arc<[u8]> = render_status_body(status).into();
+        self.statuses
+            .write()
+            .insert(status.as_u16(), Arc::clone(&body));
+        body.to_vec()
+    }
+
+    /// Append the `{"detail": [...]}` body for `errors` to `out`.
+    ///
+    /// The output is byte-for-byte what serializing the errors with
+    /// `serde_json` produces.
+    pub fn write_validation_errors(&self, errors: &[ValidationError], out: &mut Vec<u8>) {
+        out.extend_from_slice(b"{\"detail\":[");
+        for (i, error) in errors.iter().enumerate() {
+            if i > 0 {
+                out.push(b',');
+            }
+            out.extend_from_slice(&self.skeleton(error));
+            if let Some(input) = &error.input {
+                out.extend_from_slice(b",\"input\":");
+                let _ = serde_json::to_writer(&mut *out, input);
+            }
+            if let Some(ctx) = &error.ctx {
+                out.extend_from_slice(b",\"ctx\":");
+                let _ = serde_json::to_writer(&mut *out, ctx);
+            }
+            out.push(b'}');
+        }
+        out.extend_from_slice(b"]}");
+    }
+
+    /// Cache the bodies a route's parameters can fail with.
+    pub fn prewarm_route(&self, route: &fastapi_router::Route) {
+        let path = route.path_params.iter().map(|param| {
+            PathExtractError::MissingParam {
+                name: param.name.clone(),
+            }
+            .into_validation_errors()
+            .ok()
+        });
+        let query = route
+            .query_params
+            .iter()
+            .filter(|param| param.required)
+            .map(|param| {
+                QueryExtractError::MissingParam {
+                    name: param.name.clone(),
+                }
+                .into_validation_errors()
+                .ok()
+            });
+        for errors in path.chain(query).flatten() {
+            for error in &errors.errors {
+                let _ = self.skeleton(error);
+            }
+        }
+    }
+
+    fn skeleton(&self, error: &ValidationError) -> Arc<[u8]> {
+        let key = skeleton_key(error);
+        if let Some(skeleton) = self.items.read().get(&key) {
+            if skeleton.matches(error) {
+                return Arc::clone(&skeleton.bytes);
+            }
+            // Hash collision with a different item: serve it uncached.
+            return render_skeleton(error).into();
+        }
+
+        let bytes: Arc<[u8]> = render_skeleton(error).into();
+        let mut items = self.items.write();
+        if items.len() < self.capacity {
+            items.entry(key).or_insert_with(|| Skeleton {
+                error_type: error.error_type,
+                loc: error.loc.clone(),
+                msg: error.msg.clone(),
+                bytes: Arc::clone(&bytes),
+            });
+        }
+        bytes
+    }
+}
+
+fn skeleton_key(error: &ValidationError) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    error.error_type.hash(&mut hasher);
+    error.loc.hash(&mut hasher);
+    error.msg.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn render_skeleton(error: &ValidationError) -> Vec<u8> {
+    #[derive(Serialize)]
+    struct Fixed<'a> {
+        #[serde(rename = "type")]
+        error_type: &'a str,
+        loc: &'a [LocItem],
+        msg: &'a str,
+    }
+
+    let mut bytes = serde_json::to_vec(&Fixed {
+        error_type: error.error_type,
+        loc: &error.loc,
+        msg: &error.msg,
+    })
+    .unwrap_or_default();
+    // Drop the closing brace so `input`/`ctx` can follow.
+    bytes.pop();
+    bytes
+}
+
+fn render_status_body(status: StatusCode) -> Vec<u8> {
+    #[derive(Serialize)]
+    struct ErrorBody<'a> {
+        detail: &'a str,
+    }
+
+    serde_json::to_vec(&ErrorBody {
+        detail: status.canonical_reason(),
+    })
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ValidationErrors, error_types, loc};
+    use fastapi_router::{Converter, ParamInfo};
+
+    fn sample_errors() -> Vec<ValidationError> {
+        vec![
+            ValidationError::missing(loc::query("q")),
+            ValidationError::new(error_types::STRING_TOO_SHORT, loc::body_field("name"))
+                .with_msg("String should have at least 3 characters")
+                .with_input(serde_json::json!("ab"))
+                .with_ctx_value("min_length", serde_json::json!(3)),
+            ValidationError::new(
+                error_types::INT_TYPE,
+                vec![
+                    LocItem::field("body"),
+                    LocItem::field("items"),
+                    LocItem::index(2),
+                ],
+            )
+            .with_msg("Input should be a valid \"integer\""),
+        ]
+    }
+
+    #[test]
+    fn cached_bodies_match_serde_output() {
+        #[derive(Serialize)]
+        struct Body<'a> {
+            detail: &'a [ValidationError],
+        }
+
+        let cache = ErrorBodyCache::new(8);
+        let errors = sample_errors();
+        let expected = serde_json::to_vec(&Body { detail: &errors }).unwrap();
+        for _ in 0..2 {
+            let mut out = Vec::new();
+            cache.write_validation_errors(&errors, &mut out);
+            assert_eq!(
+                String::from_utf8(out).unwrap(),
+                String::from_utf8(expected.clone()).unwrap()
+            );
+        }
+        assert_eq!(cache.len(), 3);
+
+        let mut out = Vec::new();
+        cache.write_validation_errors(&[], &mut out);
+        assert_eq!(out, b"{\"detail\":[]}");
+    }
+
+    #[test]
+    fn capacity_bounds_the_cache() {
+        let cache = ErrorBodyCache::new(1);
+        let mut out = Vec::new();
+        cache.write_validation_errors(&sample_errors(), &mut out);
+        assert_eq!(cache.len(), 1);
+        assert!(
+            ValidationErrors::from_errors(sample_errors())
+                .to_json()
+                .starts_with("{\"detail\":[")
+        );
+    }
+
+    #[test]
+    fn status_bodies_and_route_prewarm() {
+        let cache = ErrorBodyCache::new(8);
+        assert_eq!(
+            cache.status_body(StatusCode::NOT_FOUND),
+            b"{\"detail\":\"Not Found\"}"
+        );
+        assert_eq!(
+            cache.status_body(StatusCode::METHOD_NOT_ALLOWED),
+            b"{\"detail\":\"Method Not Allowed\"}"
+        );
+
+        let route = fastapi_router::Route::new(crate::request::Method::Get, "/items/{id}")
+            .query_params([
+                ParamInfo::new("q", Converter::Str).required(),
+                ParamInfo::new("page", Converter::Int),
+            ]);
+        cache.prewarm_route(&route);
+        assert_eq!(cache.len(), 2);
+    }
+}
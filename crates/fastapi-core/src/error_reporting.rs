@@ -0,0 +1,954 @@
+//! Error reporting to external sinks (Sentry-style).
+//!
+//! An [`ErrorReporter`] receives an [`ErrorEvent`] whenever a request fails in
+//! a way the application could not handle:
+//!
+//! - an error reaches [`App::handle_error`](crate::App::handle_error) without a
+//!   registered exception handler, or its handler produced a 5xx response;
+//! - a handler or middleware panics (the request is answered with a 500).
+//!
+//! Each event carries a [`RequestSummary`], the user id recorded on the
+//! request's [`LogScope`](crate::LogScope) (if any), and breadcrumbs collected
+//! from spans opened while the request was being served.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use fastapi_core::error_reporting::{BufferedReporter, Dsn, HttpTransport};
+//!
+//! let dsn = Dsn::parse("http://public@relay.internal:9000/42")?;
+//! let reporter = Arc::new(BufferedReporter::new(HttpTransport::new(dsn)));
+//!
+//! let app = App::builder()
+//!     .error_reporter(Arc::clone(&reporter))
+//!     .build();
+//! ```
+//!
+//! [`HttpTransport`] speaks plain HTTP only. Sentry's hosted DSNs are
+//! `https://`, so run a local relay (such as `sentry-relay`) that forwards
+//! over TLS, or implement [`ReportTransport`] on top of an HTTPS client.
+//!
+//! The app flushes the reporter every [`ErrorReporter::flush_interval`] and
+//! once more at shutdown; a [`BufferedReporter`] also starts a flush as soon
+//! as [`flush_threshold`](BufferedReporter::flush_threshold) events are queued.
+//!
+//! Authentication dependencies attach the user with
+//! `ctx.log_scope().set_user_id(user.id)`.
+
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::future::{Future, poll_fn};
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::task::Poll;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use asupersync::io::{AsyncRead, AsyncWrite, ReadBuf};
+use asupersync::net::TcpStream;
+
+use crate::context::RequestContext;
+use crate::request::Request;
+
+/// Default number of events a [`BufferedReporter`] holds before dropping the oldest.
+pub const DEFAULT_BUFFER_CAPACITY: usize = 256;
+
+/// Default number of queued events at which a [`BufferedReporter`] starts a flush.
+pub const DEFAULT_FLUSH_THRESHOLD: usize = 32;
+
+/// Default interval between periodic flushes of a [`BufferedReporter`].
+pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Default limit on each step (connect, write, status read) of an
+/// [`HttpTransport`] send.
+pub const DEFAULT_TRANSPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest status line [`HttpTransport`] reads before giving up on a response.
+const MAX_STATUS_LINE: usize = 1024;
+
+/// Maximum number of breadcrumbs retained per request.
+pub const MAX_BREADCRUMBS: usize = 50;
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+// ============================================================================
+// Event data
+// ============================================================================
+
+/// Severity of a reported event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorLevel {
+    /// An error that produced a server error response.
+    Error,
+    /// A panic caught while serving the request.
+    Fatal,
+}
+
+impl ErrorLevel {
+    /// Returns the level as a lowercase string.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Fatal => "fatal",
+        }
+    }
+}
+
+/// The request a reported error occurred in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestSummary {
+    /// HTTP method.
+    pub method: String,
+    /// Request path (without query string).
+    pub path: String,
+    /// Raw query string, if any.
+    pub query: Option<String>,
+}
+
+impl RequestSummary {
+    /// Summarizes `req`.
+    ///
+    /// Headers and body are deliberately left out so credentials and payloads
+    /// never leave the process.
+    #[must_use]
+    pub fn from_request(req: &Request) -> Self {
+        Self {
+            method: req.method().as_str().to_string(),
+            path: req.path().to_string(),
+            query: req.query().map(str::to_string),
+        }
+    }
+}
+
+/// A trail entry describing something that happened before an error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breadcrumb {
+    /// Milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    /// Category (e.g. `span`).
+    pub category: String,
+    /// Human-readable message.
+    pub message: String,
+}
+
+impl Breadcrumb {
+    /// Creates a breadcrumb timestamped now.
+    #[must_use]
+    pub fn new(category: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            timestamp_ms: now_millis(),
+            category: category.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// A single error occurrence with its request context.
+#[derive(Debug, Clone)]
+pub struct ErrorEvent {
+    /// Unique event id (32 lowercase hex characters).
+    pub event_id: String,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    /// Severity.
+    pub level: ErrorLevel,
+    /// Rust type name of the error, or `panic`.
+    pub error_type: String,
+    /// Error message (`Display` output or panic payload).
+    pub message: String,
+    /// Request id assigned by the server.
+    pub request_id: u64,
+    /// Matched route pattern, if routing got that far.
+    pub route: Option<String>,
+    /// The request being served.
+    pub request: Option<RequestSummary>,
+    /// Authenticated user, if one was recorded.
+    pub user_id: Option<String>,
+    /// Breadcrumbs recorded for the request, oldest first.
+    pub breadcrumbs: Vec<Breadcrumb>,
+}
+
+impl ErrorEvent {
+    fn from_context(
+        ctx: &RequestContext,
+        level: ErrorLevel,
+        error_type: String,
+        message: String,
+    ) -> Self {
+        let scope = ctx.log_scope();
+        Self {
+            event_id: new_event_id(),
+            timestamp_ms: now_millis(),
+            level,
+            error_type,
+            message,
+            request_id: ctx.request_id(),
+            route: scope.route(),
+            request: scope.request(),
+            user_id: scope.user_id(),
+            breadcrumbs: scope.breadcrumbs(),
+        }
+    }
+
+    /// Builds an event for an unhandled error.
+    #[must_use]
+    pub fn from_error<E>(ctx: &RequestContext, err: &E) -> Self
+    where
+        E: std::error::Error + 'static,
+    {
+        Self::from_context(
+            ctx,
+            ErrorLevel::Error,
+            std::any::type_name::<E>().to_string(),
+            err.to_string(),
+        )
+    }
+
+    /// Builds an event for a caught panic.
+    #[must_use]
+    pub fn from_panic(ctx: &RequestContext, payload: &(dyn std::any::Any + Send)) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&'static str>() {
+            (*message).to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "non-string panic payload".to_string()
+        };
+        Self::from_context(ctx, ErrorLevel::Fatal, "panic".to_string(), message)
+    }
+
+    /// Serializes the event in the Sentry store payload shape.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut event = serde_json::json!({
+            "event_id": self.event_id,
+            "timestamp": self.timestamp_ms as f64 / 1000.0,
+            "level": self.level.as_str(),
+            "platform": "rust",
+            "exception": {
+                "values": [{ "type": self.error_type, "value": self.message }]
+            },
+            "tags": { "request_id": self.request_id.to_string() },
+            "breadcrumbs": {
+                "values": self.breadcrumbs.iter().map(|b| serde_json::json!({
+                    "timestamp": b.timestamp_ms as f64 / 1000.0,
+                    "category": b.category,
+                    "message": b.message,
+                })).collect::<Vec<_>>()
+            },
+        });
+        if let Some(route) = &self.route {
+            event["transaction"] = serde_json::json!(route);
+        }
+        if let Some(request) = &self.request {
+            event["request"] = serde_json::json!({
+                "method": request.method,
+                "url": request.path,
+                "query_string": request.query,
+            });
+        }
+        if let Some(user_id) = &self.user_id {
+            event["user"] = serde_json::json!({ "id": user_id });
+        }
+        event
+    }
+}
+
+fn new_event_id() -> String {
+    static FALLBACK: AtomicU64 = AtomicU64::new(1);
+
+    let mut bytes = [0u8; 16];
+    if getrandom::fill(&mut bytes).is_err() {
+        bytes[..8].copy_from_slice(&now_millis().to_be_bytes());
+        bytes[8..].copy_from_slice(&FALLBACK.fetch_add(1, Ordering::Relaxed).to_be_bytes());
+    }
+    let mut id = String::with_capacity(32);
+    for b in bytes {
+        let _ = write!(id, "{b:02x}");
+    }
+    id
+}
+
+// ============================================================================
+// Reporter
+// ============================================================================
+
+/// Receives error events from the exception path and the panic catcher.
+///
+/// `report` runs on the request path, so implementations should hand the
+/// event off (e.g. to a buffer) rather than perform I/O.
+pub trait ErrorReporter: Send + Sync {
+    /// Records an event.
+    fn report(&self, event: ErrorEvent);
+
+    /// Delivers buffered events, returning how many were sent.
+    ///
+    /// The app calls this every [`flush_interval`](Self::flush_interval) and
+    /// at shutdown. Reporters that send from `report` keep the default, which
+    /// does nothing.
+    fn flush(&self) -> Pin<Box<dyn Future<Output = io::Result<usize>> + Send + '_>> {
+        Box::pin(std::future::ready(Ok(0)))
+    }
+
+    /// How often the app should call [`flush`](Self::flush); `None` (the
+    /// default) means only at shutdown.
+    fn flush_interval(&self) -> Option<Duration> {
+        None
+    }
+}
+
+impl<F> ErrorReporter for F
+where
+    F: Fn(ErrorEvent) + Send + Sync,
+{
+    fn report(&self, event: ErrorEvent) {
+        self(event);
+    }
+}
+
+impl<R: ErrorReporter + ?Sized> ErrorReporter for Arc<R> {
+    fn report(&self, event: ErrorEvent) {
+        (**self).report(event);
+    }
+
+    fn flush(&self) -> Pin<Box<dyn Future<Output = io::Result<usize>> + Send + '_>> {
+        (**self).flush()
+    }
+
+    fn flush_interval(&self) -> Option<Duration> {
+        (**self).flush_interval()
+    }
+}
+
+// ============================================================================
+// Buffered transport
+// ============================================================================
+
+/// Delivers serialized events to a collector.
+pub trait ReportTransport: Send + Sync {
+    /// Sends one JSON-encoded event.
+    fn send(&self, payload: Vec<u8>) -> Pin<Box<dyn Future<Output = io::Result<()>> + Send + '_>>;
+}
+
+/// An [`ErrorReporter`] that queues events in memory and ships them through a
+/// [`ReportTransport`] when flushed.
+///
+/// Registered with [`AppBuilder::error_reporter`](crate::AppBuilder::error_reporter),
+/// it is flushed every [`flush_interval`](Self::flush_interval) and at
+/// shutdown. Reaching [`flush_threshold`](Self::flush_threshold) queued
+/// events also spawns a flush on the current runtime, so bursts go out
+/// without waiting for the next interval. [`flush`](Self::flush) can still be
+/// awaited directly.
+///
+/// When the queue is full the oldest event is dropped; [`dropped`](Self::dropped)
+/// counts how many were lost.
+pub struct BufferedReporter<T> {
+    shared: Arc<BufferedQueue<T>>,
+    capacity: usize,
+    flush_threshold: usize,
+    flush_interval: Option<Duration>,
+}
+
+/// The part of a [`BufferedReporter`] a spawned flush needs.
+struct BufferedQueue<T> {
+    transport: T,
+    queue: parking_lot::Mutex<VecDeque<ErrorEvent>>,
+    dropped: AtomicU64,
+    /// Set while a threshold-triggered flush is spawned, so a burst spawns one.
+    flushing: AtomicBool,
+}
+
+impl<T: ReportTransport> BufferedQueue<T> {
+    async fn flush(&self) -> io::Result<usize> {
+        let mut sent = 0;
+        loop {
+            let Some(event) = self.queue.lock().pop_front() else {
+                return Ok(sent);
+            };
+            let payload = event.to_json().to_string().into_bytes();
+            if let Err(err) = self.transport.send(payload).await {
+                self.queue.lock().push_front(event);
+                return Err(err);
+            }
+            sent += 1;
+        }
+    }
+}
+
+impl<T: ReportTransport + 'static> BufferedReporter<T> {
+    /// Creates a reporter with [`DEFAULT_BUFFER_CAPACITY`],
+    /// [`DEFAULT_FLUSH_THRESHOLD`] and [`DEFAULT_FLUSH_INTERVAL`].
+    #[must_use]
+    pub fn new(transport: T) -> Self {
+        Self {
+            shared: Arc::new(BufferedQueue {
+                transport,
+                queue: parking_lot::Mutex::new(VecDeque::new()),
+                dropped: AtomicU64::new(0),
+                flushing: AtomicBool::new(false),
+            }),
+            capacity: DEFAULT_BUFFER_CAPACITY,
+            flush_threshold: DEFAULT_FLUSH_THRESHOLD,
+            flush_interval: Some(DEFAULT_FLUSH_INTERVAL),
+        }
+    }
+
+    /// Sets the maximum number of queued events (minimum 1).
+    #[must_use]
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Sets how many queued events start a flush (minimum 1). A threshold
+    /// above the capacity is reached at the capacity.
+    #[must_use]
+    pub fn flush_threshold(mut self, threshold: usize) -> Self {
+        self.flush_threshold = threshold.max(1);
+        self
+    }
+
+    /// Sets the interval between periodic flushes; `None` flushes only on
+    /// the threshold and at shutdown.
+    #[must_use]
+    pub fn flush_interval(mut self, interval: Option<Duration>) -> Self {
+        self.flush_interval = interval;
+        self
+    }
+
+    /// Returns the number of queued events.
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.shared.queue.lock().len()
+    }
+
+    /// Returns the number of events dropped because the queue was full.
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Sends all queued events, returning how many were delivered.
+    ///
+    /// Stops at the first transport error; the failed event and everything
+    /// after it stay queued for the next flush.
+    pub async fn flush(&self) -> io::Result<usize> {
+        self.shared.flush().await
+    }
+
+    /// Spawns a flush on the current runtime unless one is already running.
+    /// Outside a runtime the events wait for the next interval or shutdown.
+    fn spawn_flush(&self) {
+        if self.shared.flushing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let shared = Arc::clone(&self.shared);
+        let spawned = asupersync::runtime::Runtime::current_handle().is_some_and(|runtime| {
+            runtime
+                .try_spawn(async move {
+                    // A failed flush keeps its events queued for the next one.
+                    let _ = shared.flush().await;
+                    shared.flushing.store(false, Ordering::Release);
+                })
+                .is_ok()
+        });
+        if !spawned {
+            self.shared.flushing.store(false, Ordering::Release);
+        }
+    }
+}
+
+impl<T: ReportTransport + 'static> ErrorReporter for BufferedReporter<T> {
+    fn report(&self, event: ErrorEvent) {
+        let pending = {
+            let mut queue = self.shared.queue.lock();
+            while queue.len() >= self.capacity {
+                queue.pop_front();
+                self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            queue.push_back(event);
+            queue.len()
+        };
+        if pending >= self.flush_threshold.min(self.capacity) {
+            self.spawn_flush();
+        }
+    }
+
+    fn flush(&self) -> Pin<Box<dyn Future<Output = io::Result<usize>> + Send + '_>> {
+        Box::pin(self.shared.flush())
+    }
+
+    fn flush_interval(&self) -> Option<Duration> {
+        self.flush_interval
+    }
+}
+
+impl<T> fmt::Debug for BufferedReporter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferedReporter")
+            .field("pending", &self.shared.queue.lock().len())
+            .field("capacity", &self.capacity)
+            .field("flush_threshold", &self.flush_threshold)
+            .field("flush_interval", &self.flush_interval)
+            .field("dropped", &self.shared.dropped.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+// ============================================================================
+// DSN + HTTP transport
+// ============================================================================
+
+/// Error returned when a DSN cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DsnError(String);
+
+impl fmt::Display for DsnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid DSN: {}", self.0)
+    }
+}
+
+impl std::error::Error for DsnError {}
+
+/// A Sentry-style DSN: `scheme://public_key@host[:port]/project_id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dsn {
+    /// `http` or `https`.
+    pub scheme: String,
+    /// Public key sent in the auth header.
+    pub public_key: String,
+    /// Collector host.
+    pub host: String,
+    /// Collector port.
+    pub port: u16,
+    /// Project id.
+    pub project_id: String,
+}
+
+impl Dsn {
+    /// Parses a DSN string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DsnError`] if the scheme, key, host or project id is missing.
+    pub fn parse(dsn: &str) -> Result<Self, DsnError> {
+        let (scheme, rest) = dsn
+            .split_once("://")
+            .ok_or_else(|| DsnError("missing scheme".into()))?;
+        let default_port = match scheme {
+            "http" => 80,
+            "https" => 443,
+            other => return Err(DsnError(format!("unsupported scheme `{other}`"))),
+        };
+        let (public_key, rest) = rest
+            .split_once('@')
+            .ok_or_else(|| DsnError("missing public key".into()))?;
+        let (authority, project_id) = rest
+            .split_once('/')
+            .ok_or_else(|| DsnError("missing project id".into()))?;
+        let project_id = project_id.trim_end_matches('/');
+        if public_key.is_empty() || authority.is_empty() || project_id.is_empty() {
+            return Err(DsnError("empty component".into()));
+        }
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .map_err(|_| DsnError(format!("invalid port `{port}`")))?,
+            ),
+            None => (authority, default_port),
+        };
+        Ok(Self {
+            scheme: scheme.to_string(),
+            public_key: public_key.to_string(),
+            host: host.to_string(),
+            port,
+            project_id: project_id.to_string(),
+        })
+    }
+
+    /// Path events are posted to.
+    #[must_use]
+    pub fn store_path(&self) -> String {
+        format!("/api/{}/store/", self.project_id)
+    }
+
+    /// Value of the `X-Sentry-Auth` header.
+    #[must_use]
+    pub fn auth_header(&self) -> String {
+        format!(
+            "Sentry sentry_version=7, sentry_client=fastapi_rust/{}, sentry_key={}",
+            env!("CARGO_PKG_VERSION"),
+            self.public_key
+        )
+    }
+}
+
+/// Posts events to a DSN endpoint over plain HTTP/1.1.
+///
+/// TLS is not available here: `https` DSNs fail at send time. Point this at a
+/// local relay, or implement [`ReportTransport`] on top of an HTTPS client.
+///
+/// Connecting, writing the event and reading the status line are each
+/// limited by [`timeout`](Self::timeout), so a stalled collector fails the
+/// send instead of holding up the flush behind it.
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    dsn: Dsn,
+    timeout: Duration,
+}
+
+impl HttpTransport {
+    /// Creates a transport for `dsn` with [`DEFAULT_TRANSPORT_TIMEOUT`].
+    #[must_use]
+    pub fn new(dsn: Dsn) -> Self {
+        Self {
+            dsn,
+            timeout: DEFAULT_TRANSPORT_TIMEOUT,
+        }
+    }
+
+    /// Sets the limit on each step of a send.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the configured DSN.
+    #[must_use]
+    pub fn dsn(&self) -> &Dsn {
+        &self.dsn
+    }
+
+    /// `Host` header value: the port is included unless it is the default.
+    fn host_header(&self) -> String {
+        if self.dsn.port == 80 {
+            self.dsn.host.clone()
+        } else {
+            format!("{}:{}", self.dsn.host, self.dsn.port)
+        }
+    }
+
+    fn request_bytes(&self, payload: &[u8]) -> Vec<u8> {
+        let mut request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nX-Sentry-Auth: {}\r\nConnection: close\r\n\r\n",
+            self.dsn.store_path(),
+            self.host_header(),
+            payload.len(),
+            self.dsn.auth_header()
+        )
+        .into_bytes();
+        request.extend_from_slice(payload);
+        request
+    }
+
+    /// Runs one step of a send, failing with `TimedOut` after the timeout.
+    async fn within<T>(
+        &self,
+        step: &str,
+        fut: impl Future<Output = io::Result<T>>,
+    ) -> io::Result<T> {
+        match asupersync::time::timeout(asupersync::time::wall_now(), self.timeout, Box::pin(fut))
+            .await
+        {
+            Ok(result) => result,
+            Err(_elapsed) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("{step} timed out after {:?}", self.timeout),
+            )),
+        }
+    }
+
+    async fn post(&self, payload: Vec<u8>) -> io::Result<()> {
+        if self.dsn.scheme != "http" {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "HttpTransport only supports http:// DSNs",
+            ));
+        }
+
+        let address = (self.dsn.host.clone(), self.dsn.port);
+        let mut stream = self.within("connect", TcpStream::connect(address)).await?;
+        let request = self.request_bytes(&payload);
+        self.within("write", async {
+            let mut written = &request[..];
+            while !written.is_empty() {
+                let n = poll_fn(|cx| Pin::new(&mut stream).poll_write(cx, written)).await?;
+                if n == 0 {
+                    return Err(io::Error::new(io::ErrorKind::WriteZero, "write zero"));
+                }
+                written = &written[n..];
+            }
+            poll_fn(|cx| Pin::new(&mut stream).poll_flush(cx)).await
+        })
+        .await?;
+
+        // Only the status line matters; it may arrive over several reads.
+        let response = self
+            .within("status read", async {
+                let mut response = Vec::new();
+                let mut buf = [0u8; 256];
+                while !response.windows(2).any(|w| w == b"\r\n") {
+                    if response.len() >= MAX_STATUS_LINE {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "status line too long",
+                        ));
+                    }
+                    let read = poll_fn(|cx| {
+                        let mut read_buf = ReadBuf::new(&mut buf);
+                        match Pin::new(&mut stream).poll_read(cx, &mut read_buf) {
+                            Poll::Ready(Ok(())) => Poll::Ready(Ok(read_buf.filled().len())),
+                            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+                            Poll::Pending => Poll::Pending,
+                        }
+                    })
+                    .await?;
+                    if read == 0 {
+                        break;
+                    }
+                    response.extend_from_slice(&buf[..read]);
+                }
+                Ok(response)
+            })
+            .await?;
+        let status = parse_status(&response)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed response"))?;
+        if (200..300).contains(&status) {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "collector responded with {status}"
+            )))
+        }
+    }
+}
+
+impl ReportTransport for HttpTransport {
+    fn send(&self, payload: Vec<u8>) -> Pin<Box<dyn Future<Output = io::Result<()>> + Send + '_>> {
+        Box::pin(self.post(payload))
+    }
+}
+
+/// Status code from the first line of `response`, which must be complete.
+fn parse_status(response: &[u8]) -> Option<u16> {
+    let end = response.windows(2).position(|w| w == b"\r\n")?;
+    let line = std::str::from_utf8(&response[..end]).ok()?;
+    let mut parts = line.split_whitespace();
+    if !parts.next()?.starts_with("HTTP/") {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Method;
+
+    fn test_context() -> RequestContext {
+        RequestContext::new(asupersync::Cx::for_testing(), 7)
+    }
+
+    #[derive(Debug)]
+    struct BoomError;
+
+    impl fmt::Display for BoomError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("boom")
+        }
+    }
+
+    impl std::error::Error for BoomError {}
+
+    #[derive(Default)]
+    struct RecordingTransport {
+        sent: parking_lot::Mutex<Vec<serde_json::Value>>,
+        fail: std::sync::atomic::AtomicBool,
+    }
+
+    impl ReportTransport for RecordingTransport {
+        fn send(
+            &self,
+            payload: Vec<u8>,
+        ) -> Pin<Box<dyn Future<Output = io::Result<()>> + Send + '_>> {
+            let result = if self.fail.load(Ordering::Relaxed) {
+                Err(io::Error::other("down"))
+            } else {
+                self.sent
+                    .lock()
+                    .push(serde_json::from_slice(&payload).unwrap());
+                Ok(())
+            };
+            Box::pin(std::future::ready(result))
+        }
+    }
+
+    #[test]
+    fn event_captures_request_scope() {
+        let ctx = test_context();
+        let mut req = Request::new(Method::Post, "/orders");
+        req.set_query(Some("dry_run=1".to_string()));
+        ctx.log_scope()
+            .set_request(RequestSummary::from_request(&req));
+        ctx.log_scope().set_route("/orders");
+        ctx.log_scope().set_user_id("user-42");
+        let _span = crate::logging::Span::new(&ctx, "load_cart");
+
+        let event = ErrorEvent::from_error(&ctx, &BoomError);
+        assert_eq!(event.level, ErrorLevel::Error);
+        assert!(event.error_type.ends_with("BoomError"));
+        assert_eq!(event.message, "boom");
+        assert_eq!(event.request_id, 7);
+        assert_eq!(event.user_id.as_deref(), Some("user-42"));
+        assert_eq!(event.event_id.len(), 32);
+
+        let json = event.to_json();
+        assert_eq!(json["request"]["method"], "POST");
+        assert_eq!(json["request"]["url"], "/orders");
+        assert_eq!(json["request"]["query_string"], "dry_run=1");
+        assert_eq!(json["transaction"], "/orders");
+        assert_eq!(json["user"]["id"], "user-42");
+        assert_eq!(json["breadcrumbs"]["values"][0]["category"], "span");
+        assert_eq!(json["breadcrumbs"]["values"][0]["message"], "load_cart");
+    }
+
+    #[test]
+    fn panic_event_uses_payload_message() {
+        let ctx = test_context();
+        let payload: Box<dyn std::any::Any + Send> = Box::new(String::from("index out of bounds"));
+        let event = ErrorEvent::from_panic(&ctx, payload.as_ref());
+        assert_eq!(event.level, ErrorLevel::Fatal);
+        assert_eq!(event.error_type, "panic");
+        assert_eq!(event.message, "index out of bounds");
+    }
+
+    #[test]
+    fn buffered_reporter_drops_oldest_when_full() {
+        let ctx = test_context();
+        let reporter = BufferedReporter::new(RecordingTransport::default()).capacity(2);
+        for _ in 0..3 {
+            reporter.report(ErrorEvent::from_error(&ctx, &BoomError));
+        }
+        assert_eq!(reporter.pending(), 2);
+        assert_eq!(reporter.dropped(), 1);
+    }
+
+    #[test]
+    fn buffered_reporter_flush_sends_and_requeues_on_failure() {
+        let ctx = test_context();
+        let reporter = BufferedReporter::new(RecordingTransport::default());
+        reporter.report(ErrorEvent::from_error(&ctx, &BoomError));
+        reporter.report(ErrorEvent::from_error(&ctx, &BoomError));
+
+        reporter
+            .shared
+            .transport
+            .fail
+            .store(true, Ordering::Relaxed);
+        assert!(futures_executor::block_on(reporter.flush()).is_err());
+        assert_eq!(reporter.pending(), 2);
+
+        reporter
+            .shared
+            .transport
+            .fail
+            .store(false, Ordering::Relaxed);
+        assert_eq!(futures_executor::block_on(reporter.flush()).unwrap(), 2);
+        assert_eq!(reporter.pending(), 0);
+        let sent = reporter.shared.transport.sent.lock();
+        assert_eq!(sent[0]["exception"]["values"][0]["value"], "boom");
+    }
+
+    #[test]
+    fn dsn_parses_components() {
+        let dsn = Dsn::parse("http://abc123@relay.local:9000/42").unwrap();
+        assert_eq!(dsn.scheme, "http");
+        assert_eq!(dsn.public_key, "abc123");
+        assert_eq!(dsn.host, "relay.local");
+        assert_eq!(dsn.port, 9000);
+        assert_eq!(dsn.store_path(), "/api/42/store/");
+        assert!(dsn.auth_header().contains("sentry_key=abc123"));
+
+        let dsn = Dsn::parse("https://key@sentry.example.com/7").unwrap();
+        assert_eq!(dsn.port, 443);
+    }
+
+    #[test]
+    fn dsn_rejects_malformed_input() {
+        assert!(Dsn::parse("relay.local/42").is_err());
+        assert!(Dsn::parse("ftp://key@host/1").is_err());
+        assert!(Dsn::parse("http://host/1").is_err());
+        assert!(Dsn::parse("http://key@host").is_err());
+        assert!(Dsn::parse("http://key@host:notaport/1").is_err());
+    }
+
+    #[test]
+    fn http_transport_builds_store_request() {
+        let transport = HttpTransport::new(Dsn::parse("http://k@relay:8080/3").unwrap());
+        let request = String::from_utf8(transport.request_bytes(b"{}")).unwrap();
+        assert!(request.starts_with("POST /api/3/store/ HTTP/1.1\r\n"));
+        assert!(request.contains("Host: relay:8080\r\n"));
+
+        let transport = HttpTransport::new(Dsn::parse("http://k@relay/3").unwrap());
+        let request = String::from_utf8(transport.request_bytes(b"{}")).unwrap();
+        assert!(request.contains("Host: relay\r\n"));
+        assert!(request.contains("Content-Length: 2\r\n"));
+        assert!(request.ends_with("\r\n\r\n{}"));
+    }
+
+    #[test]
+    fn parse_status_reads_status_line() {
+        assert_eq!(parse_status(b"HTTP/1.1 200 OK\r\n"), Some(200));
+        assert_eq!(parse_status(b"HTTP/1.0 503 Busy\r\n"), Some(503));
+        assert_eq!(parse_status(b"garbage"), None);
+        // A status line cut short is not parsed.
+        assert_eq!(parse_status(b"HTTP/1.1 2"), None);
+    }
+
+    #[test]
+    fn stalled_transport_step_times_out() {
+        let transport = HttpTransport::new(Dsn::parse("http://k@relay/3").unwrap())
+            .timeout(Duration::from_millis(20));
+        let err = futures_executor::block_on(
+            transport.within("status read", std::future::pending::<io::Result<()>>()),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(err.to_string().starts_with("status read timed out"));
+    }
+
+    #[test]
+    fn reporter_flush_is_reachable_through_the_trait() {
+        let ctx = test_context();
+        let reporter: Arc<dyn ErrorReporter> = Arc::new(
+            BufferedReporter::new(RecordingTransport::default())
+                .flush_interval(Some(Duration::from_secs(1))),
+        );
+        reporter.report(ErrorEvent::from_error(&ctx, &BoomError));
+        assert_eq!(reporter.flush_interval(), Some(Duration::from_secs(1)));
+        assert_eq!(futures_executor::block_on(reporter.flush()).unwrap(), 1);
+
+        let closure = |_event: ErrorEvent| {};
+        assert_eq!(futures_executor::block_on(closure.flush()).unwrap(), 0);
+        assert_eq!(closure.flush_interval(), None);
+    }
+
+    #[test]
+    fn threshold_outside_a_runtime_leaves_events_queued() {
+        let ctx = test_context();
+        let reporter = BufferedReporter::new(RecordingTransport::default()).flush_threshold(1);
+        reporter.report(ErrorEvent::from_error(&ctx, &BoomError));
+        assert_eq!(reporter.pending(), 1);
+        assert!(!reporter.shared.flushing.load(Ordering::Acquire));
+    }
+}
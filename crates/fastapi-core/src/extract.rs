@@ -6,8 +6,8 @@
 use crate::context::RequestContext;
 use crate::error::{HttpError, ValidationError, ValidationErrors};
 use crate::multipart;
-use crate::request::{Body, Request, RequestBodyStreamError};
-use crate::response::IntoResponse;
+use crate::request::{BackgroundTasks, Body, Request, RequestBodyStreamError};
+use crate::response::{IntoResponse, LinkHeader, LinkRel, Response};
 use serde::de::{
     self, DeserializeOwned, Deserializer, IntoDeserializer, MapAccess, SeqAccess, Visitor,
 };
@@ -73,44 +73,44 @@ async fn collect_body_limited(
     }
 }
 
-async fn parse_multipart_limited(
-    ctx: &RequestContext,
-    body: Body,
-    limit: usize,
-    parser: &multipart::MultipartParser,
-) -> Result<Vec<multipart::Part>, MultipartExtractError> {
-    fn map_parser_error(err: multipart::MultipartError) -> MultipartExtractError {
-        match err {
-            multipart::MultipartError::FileTooLarge { size, max }
-            | multipart::MultipartError::TotalTooLarge { size, max } => {
-                MultipartExtractError::PayloadTooLarge { size, limit: max }
-            }
-            multipart::MultipartError::Io { detail } => {
-                MultipartExtractError::ReadError { message: detail }
-            }
-            other => MultipartExtractError::BadRequest {
-                message: other.to_string(),
-            },
+pub(crate) fn multipart_parser_error(err: multipart::MultipartError) -> MultipartExtractError {
+    match err {
+        multipart::MultipartError::FileTooLarge { size, max }
+        | multipart::MultipartError::TotalTooLarge { size, max } => {
+            MultipartExtractError::PayloadTooLarge { size, limit: max }
         }
+        multipart::MultipartError::Io { detail } => {
+            MultipartExtractError::ReadError { message: detail }
+        }
+        other => MultipartExtractError::BadRequest {
+            message: other.to_string(),
+        },
     }
+}
 
-    fn map_stream_error(err: RequestBodyStreamError) -> MultipartExtractError {
-        match err {
-            RequestBodyStreamError::TooLarge { received, max } => {
-                MultipartExtractError::PayloadTooLarge {
-                    size: received,
-                    limit: max,
-                }
+pub(crate) fn multipart_stream_error(err: RequestBodyStreamError) -> MultipartExtractError {
+    match err {
+        RequestBodyStreamError::TooLarge { received, max } => {
+            MultipartExtractError::PayloadTooLarge {
+                size: received,
+                limit: max,
             }
-            RequestBodyStreamError::ConnectionClosed => MultipartExtractError::BadRequest {
-                message: RequestBodyStreamError::ConnectionClosed.to_string(),
-            },
-            RequestBodyStreamError::Io(message) => MultipartExtractError::ReadError { message },
         }
+        RequestBodyStreamError::ConnectionClosed => MultipartExtractError::BadRequest {
+            message: RequestBodyStreamError::ConnectionClosed.to_string(),
+        },
+        RequestBodyStreamError::Io(message) => MultipartExtractError::ReadError { message },
     }
+}
 
+async fn parse_multipart_limited(
+    ctx: &RequestContext,
+    body: Body,
+    limit: usize,
+    parser: &multipart::MultipartParser,
+) -> Result<Vec<multipart::Part>, MultipartExtractError> {
     match body {
-        Body::Empty => parser.parse(&[]).map_err(map_parser_error),
+        Body::Empty => parser.parse(&[]).map_err(multipart_parser_error),
         Body::Bytes(bytes) => {
             if bytes.len() > limit {
                 return Err(MultipartExtractError::PayloadTooLarge {
@@ -118,7 +118,8 @@ async fn parse_multipart_limited(
                     limit,
                 });
             }
-            parser.parse(&bytes).map_err(map_parser_error)
+            let parts = parser.parse(&bytes).map_err(multipart_parser_error)?;
+            Ok(parser.spool_large_files(parts).await)
         }
         Body::Stream {
             stream,
@@ -144,7 +145,7 @@ async fn parse_multipart_limited(
                 let Some(chunk) = next else {
                     break;
                 };
-                let chunk = chunk.map_err(map_stream_error)?;
+                let chunk = chunk.map_err(multipart_stream_error)?;
 
                 seen = seen.saturating_add(chunk.len());
                 if seen > limit {
@@ -153,15 +154,17 @@ async fn parse_multipart_limited(
 
                 buffer.extend_from_slice(&chunk);
                 let mut newly_parsed = parser
-                    .parse_incremental(&mut buffer, &mut state, false)
-                    .map_err(map_parser_error)?;
+                    .parse_incremental_async(&mut buffer, &mut state, false)
+                    .await
+                    .map_err(multipart_parser_error)?;
                 parts.append(&mut newly_parsed);
                 let _ = ctx.checkpoint();
             }
 
             let mut tail = parser
-                .parse_incremental(&mut buffer, &mut state, true)
-                .map_err(map_parser_error)?;
+                .parse_incremental_async(&mut buffer, &mut state, true)
+                .await
+                .map_err(multipart_parser_error)?;
             parts.append(&mut tail);
 
             if !state.is_done() {
@@ -235,6 +238,8 @@ pub enum MultipartExtractError {
     PayloadTooLarge { size: usize, limit: usize },
     /// Body stream read error.
     ReadError { message: String },
+    /// Files rejected by the route's [`UploadPolicy`](crate::upload_policy::UploadPolicy).
+    Policy(crate::upload_policy::UploadPolicyError),
 }
 
 impl fmt::Display for MultipartExtractError {
@@ -256,6 +261,7 @@ impl fmt::Display for MultipartExtractError {
                 "Request body too large: {size} bytes exceeds {limit} byte limit"
             ),
             Self::ReadError { message } => write!(f, "Failed to read request body: {message}"),
+            Self::Policy(err) => write!(f, "{err}"),
         }
     }
 }
@@ -273,6 +279,7 @@ impl IntoResponse for MultipartExtractError {
             Self::BadRequest { message } => (StatusCode::BAD_REQUEST, message),
             Self::PayloadTooLarge { .. } => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
             Self::ReadError { .. } => (StatusCode::BAD_REQUEST, self.to_string()),
+            Self::Policy(err) => return err.into_response(),
         };
 
         let body = serde_json::json!({ "detail": detail });
@@ -287,41 +294,76 @@ impl FromRequest for multipart::MultipartForm {
 
     async fn from_request(ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
         let _ = ctx.checkpoint();
+        let boundary = multipart_boundary(req)?;
 
-        let content_type = req
-            .headers()
-            .get("content-type")
-            .and_then(|v| std::str::from_utf8(v).ok());
-        let Some(ct) = content_type else {
-            return Err(MultipartExtractError::UnsupportedMediaType { actual: None });
-        };
-
-        let ct = ct.trim();
-        let main = ct.split(';').next().unwrap_or("").trim();
-        if !main.eq_ignore_ascii_case("multipart/form-data") {
-            return Err(MultipartExtractError::UnsupportedMediaType {
-                actual: Some(ct.to_string()),
-            });
+        let policy = req
+            .get_extension::<crate::upload_policy::UploadPolicy>()
+            .cloned();
+        let mut multipart_config = multipart::MultipartConfig::default();
+        if let Some(policy) = &policy {
+            multipart_config = policy.apply_to_config(multipart_config);
         }
-
-        let boundary =
-            multipart::parse_boundary(ct).map_err(|e| MultipartExtractError::BadRequest {
-                message: e.to_string(),
-            })?;
-
-        let multipart_config = multipart::MultipartConfig::default();
         let limit = multipart_config.get_max_total_size();
         let spool_threshold = multipart_config.get_spool_threshold();
         let parser = multipart::MultipartParser::new(&boundary, multipart_config);
         let parts = parse_multipart_limited(ctx, req.take_body(), limit, &parser).await?;
 
-        Ok(multipart::MultipartForm::from_parts_with_spool_threshold(
-            parts,
-            spool_threshold,
-        ))
+        let mut form =
+            multipart::MultipartForm::from_parts_with_spool_threshold(parts, spool_threshold);
+        if let Some(policy) = &policy {
+            policy
+                .check_form(&mut form)
+                .map_err(MultipartExtractError::Policy)?;
+        }
+        Ok(form)
     }
 }
 
+impl FromRequest for multipart::MultipartStream {
+    type Error = MultipartExtractError;
+
+    async fn from_request(ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
+        let _ = ctx.checkpoint();
+        let boundary = multipart_boundary(req)?;
+
+        let policy = req
+            .get_extension::<crate::upload_policy::UploadPolicy>()
+            .cloned();
+        let mut multipart_config = req
+            .get_extension::<multipart::MultipartConfig>()
+            .cloned()
+            .unwrap_or_default();
+        if let Some(policy) = &policy {
+            multipart_config = policy.apply_to_config(multipart_config);
+        }
+        let parser = multipart::MultipartParser::new(&boundary, multipart_config);
+        Ok(multipart::MultipartStream::new(parser, req.take_body())?.with_policy(policy))
+    }
+}
+
+/// Check that the request is `multipart/form-data` and return its boundary.
+fn multipart_boundary(req: &Request) -> Result<String, MultipartExtractError> {
+    let content_type = req
+        .headers()
+        .get("content-type")
+        .and_then(|v| std::str::from_utf8(v).ok());
+    let Some(ct) = content_type else {
+        return Err(MultipartExtractError::UnsupportedMediaType { actual: None });
+    };
+
+    let ct = ct.trim();
+    let main = ct.split(';').next().unwrap_or("").trim();
+    if !main.eq_ignore_ascii_case("multipart/form-data") {
+        return Err(MultipartExtractError::UnsupportedMediaType {
+            actual: Some(ct.to_string()),
+        });
+    }
+
+    multipart::parse_boundary(ct).map_err(|e| MultipartExtractError::BadRequest {
+        message: e.to_string(),
+    })
+}
+
 #[cfg(test)]
 mod multipart_extractor_tests {
     use super::*;
@@ -440,6 +482,46 @@ mod multipart_extractor_tests {
         );
     }
 
+    #[test]
+    fn multipart_extract_spools_large_files() {
+        use asupersync::stream;
+
+        let ctx = test_context();
+        let content = vec![b'z'; multipart::DEFAULT_SPOOL_THRESHOLD + 10];
+        let mut body = Vec::new();
+        body.extend_from_slice(b"--b\r\n");
+        body.extend_from_slice(
+            b"Content-Disposition: form-data; name=\"file\"; filename=\"big.bin\"\r\n\r\n",
+        );
+        body.extend_from_slice(&content);
+        body.extend_from_slice(b"\r\n--b--\r\n");
+
+        let chunks: Vec<Result<Vec<u8>, RequestBodyStreamError>> = body
+            .chunks(64 * 1024)
+            .map(|chunk| Ok(chunk.to_vec()))
+            .collect();
+        for streamed in [false, true] {
+            let mut req = Request::new(Method::Post, "/upload");
+            req.headers_mut()
+                .insert("content-type", b"multipart/form-data; boundary=b".to_vec());
+            if streamed {
+                req.set_body(Body::streaming(stream::iter(chunks.clone())));
+            } else {
+                req.set_body(Body::Bytes(body.clone()));
+            }
+
+            let mut form =
+                futures_executor::block_on(multipart::MultipartForm::from_request(&ctx, &mut req))
+                    .expect("multipart parse");
+            let mut file = form.take_file("file").expect("file");
+            assert!(file.is_spooled(), "streamed: {streamed}");
+            assert_eq!(file.size(), content.len());
+            let read = futures_executor::block_on(file.read(None)).expect("read upload");
+            assert_eq!(read, content);
+            futures_executor::block_on(file.close()).expect("close upload");
+        }
+    }
+
     #[test]
     fn multipart_extract_file_too_large_maps_to_payload_too_large() {
         let ctx = test_context();
@@ -492,6 +574,18 @@ impl FromRequest for RequestContext {
     }
 }
 
+// Background tasks share the request's task list, which the server runs after
+// the response is written.
+impl FromRequest for BackgroundTasks {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
+        let tasks = req.background_tasks().clone();
+        tasks.set_context(ctx);
+        Ok(tasks)
+    }
+}
+
 // ============================================================================
 // JSON Body Extractor
 // ============================================================================
@@ -686,79 +780,196 @@ impl IntoResponse for JsonExtractError {
                 message,
                 line,
                 column,
-            } => {
-                // Return a 422 with validation error format
-                let msg = if let (Some(l), Some(c)) = (line, column) {
-                    format!("JSON parse error at line {l}, column {c}: {message}")
-                } else {
-                    format!("JSON parse error: {message}")
-                };
-                ValidationErrors::single(ValidationError::json_invalid(
-                    crate::error::loc::body(),
-                    msg,
-                ))
-                .into_response()
-            }
+            } => json_invalid(&message, line, column).into_response(),
         }
     }
 }
 
+impl IntoValidationErrors for JsonExtractError {
+    fn into_validation_errors(self) -> Result<ValidationErrors, Self> {
+        match self {
+            Self::DeserializeError {
+                message,
+                line,
+                column,
+            } => Ok(json_invalid(&message, line, column)),
+            other => Err(other),
+        }
+    }
+}
+
+/// A 422 in validation error format for a JSON body that failed to parse.
+fn json_invalid(message: &str, line: Option<usize>, column: Option<usize>) -> ValidationErrors {
+    let msg = if let (Some(l), Some(c)) = (line, column) {
+        format!("JSON parse error at line {l}, column {c}: {message}")
+    } else {
+        format!("JSON parse error: {message}")
+    };
+    ValidationErrors::single(ValidationError::json_invalid(
+        crate::error::loc::body(),
+        msg,
+    ))
+}
+
 impl<T: DeserializeOwned> FromRequest for Json<T> {
     type Error = JsonExtractError;
 
     async fn from_request(ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
-        // Check cancellation at start
-        let _ = ctx.checkpoint();
+        let bytes = read_json_body(ctx, req).await?;
 
-        // Validate Content-Type
-        let content_type = req
-            .headers()
-            .get("content-type")
-            .and_then(|v| std::str::from_utf8(v).ok());
+        // Deserialize JSON
+        serde_json::from_slice(&bytes)
+            .map(Json)
+            .map_err(|e| JsonExtractError::DeserializeError {
+                message: e.to_string(),
+                line: Some(e.line()),
+                column: Some(e.column()),
+            })
+    }
+}
 
-        let is_json = content_type.is_some_and(|ct| {
-            let ct_lower = ct.to_ascii_lowercase();
-            // Check for exact "application/json" possibly followed by parameters (";")
-            // Reject near-miss types like "application/jsonl" or "application/json-seq"
-            let base_type = ct_lower.split(';').next().unwrap_or("").trim();
-            base_type == "application/json"
-                || (base_type.starts_with("application/") && base_type.ends_with("+json"))
+/// Check the Content-Type and read a JSON request body within the size limit.
+pub(crate) async fn read_json_body(
+    ctx: &RequestContext,
+    req: &mut Request,
+) -> Result<Vec<u8>, JsonExtractError> {
+    // Check cancellation at start
+    let _ = ctx.checkpoint();
+
+    // Validate Content-Type
+    let content_type = req
+        .headers()
+        .get("content-type")
+        .and_then(|v| std::str::from_utf8(v).ok());
+
+    let is_json = content_type.is_some_and(|ct| {
+        let ct_lower = ct.to_ascii_lowercase();
+        // Check for exact "application/json" possibly followed by parameters (";")
+        // Reject near-miss types like "application/jsonl" or "application/json-seq"
+        let base_type = ct_lower.split(';').next().unwrap_or("").trim();
+        base_type == "application/json"
+            || (base_type.starts_with("application/") && base_type.ends_with("+json"))
+    });
+
+    if !is_json {
+        return Err(JsonExtractError::UnsupportedMediaType {
+            actual: content_type.map(String::from),
         });
+    }
 
-        if !is_json {
-            return Err(JsonExtractError::UnsupportedMediaType {
-                actual: content_type.map(String::from),
-            });
-        }
-
-        // Get body bytes
-        let body = req.take_body();
-        let limit = DEFAULT_JSON_LIMIT;
-        let bytes = collect_body_limited(ctx, body, limit)
-            .await
-            .map_err(|e| match e {
-                RequestBodyStreamError::TooLarge { received, .. } => {
-                    JsonExtractError::PayloadTooLarge {
-                        size: received,
-                        limit,
-                    }
+    // Get body bytes
+    let body = req.take_body();
+    let limit = DEFAULT_JSON_LIMIT;
+    let bytes = collect_body_limited(ctx, body, limit)
+        .await
+        .map_err(|e| match e {
+            RequestBodyStreamError::TooLarge { received, .. } => {
+                JsonExtractError::PayloadTooLarge {
+                    size: received,
+                    limit,
                 }
-                other => JsonExtractError::ReadError {
-                    message: other.to_string(),
-                },
-            })?;
+            }
+            other => JsonExtractError::ReadError {
+                message: other.to_string(),
+            },
+        })?;
 
-        // Check cancellation before deserialization
-        let _ = ctx.checkpoint();
+    // Check cancellation before deserialization
+    let _ = ctx.checkpoint();
 
-        // Deserialize JSON
-        serde_json::from_slice(&bytes)
-            .map(Json)
-            .map_err(|e| JsonExtractError::DeserializeError {
+    // Map keys sent in the app's JSON key case back to the field names.
+    match req.get_extension::<crate::json_case::JsonKeyCase>() {
+        Some(case) => Ok(case.inverse().convert_json(&bytes)),
+        None => Ok(bytes),
+    }
+}
+
+/// JSON body extractor that checks the body against `T`'s JSON Schema
+/// before deserializing it.
+///
+/// An opt-in, stricter [`Json`]: where serde stops at the first problem and
+/// silently ignores unknown keys, the body is first checked against the
+/// schema derived with `#[derive(JsonSchema)]`, reporting every violation
+/// with its `loc` path, e.g. `["body", "items", 0, "qty"]`. Unknown fields
+/// are rejected (`extra_forbidden`), as are mistyped values, values outside
+/// an `enum`, `email`/`uri` format violations and broken `#[validate(...)]`
+/// constraints. See [`validate_json_schema`](crate::validation::validate_json_schema).
+///
+/// # Error Responses
+///
+/// - **415 Unsupported Media Type**: Content-Type is not `application/json`
+/// - **413 Payload Too Large**: Body exceeds configured size limit
+/// - **422 Unprocessable Entity**: malformed JSON, or the body does not
+///   match the schema
+///
+/// # Example
+///
+/// ```ignore
+/// use fastapi_core::extract::StrictJson;
+///
+/// #[derive(Deserialize, JsonSchema)]
+/// struct CreateUser {
+///     #[validate(email)]
+///     email: String,
+///     role: Role,
+/// }
+///
+/// async fn create_user(StrictJson(user): StrictJson<CreateUser>) -> impl IntoResponse {
+///     format!("Created user: {}", user.email)
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StrictJson<T>(pub T);
+
+impl<T> StrictJson<T> {
+    /// Unwrap the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for StrictJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for StrictJson<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: DeserializeOwned + fastapi_openapi::JsonSchema> FromRequest for StrictJson<T> {
+    type Error = ValidExtractError<JsonExtractError>;
+
+    async fn from_request(ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
+        let bytes = read_json_body(ctx, req)
+            .await
+            .map_err(ValidExtractError::Extract)?;
+
+        let deserialize_error = |e: serde_json::Error| {
+            ValidExtractError::Extract(JsonExtractError::DeserializeError {
                 message: e.to_string(),
                 line: Some(e.line()),
                 column: Some(e.column()),
             })
+        };
+        let value: serde_json::Value = serde_json::from_slice(&bytes).map_err(deserialize_error)?;
+
+        if let Err(errors) =
+            crate::validation::validate_json_schema(&value, &T::schema(), crate::error::loc::body())
+        {
+            return Err(ValidExtractError::Validation(Box::new(
+                (*errors).with_body(value),
+            )));
+        }
+
+        serde_json::from_value(value)
+            .map(StrictJson)
+            .map_err(deserialize_error)
     }
 }
 
@@ -825,6 +1036,26 @@ mod tests {
         assert_eq!(payload.value, 42);
     }
 
+    #[test]
+    fn json_extract_maps_keys_from_app_case() {
+        use serde::Deserialize;
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct TestPayload {
+            display_name: String,
+            page_size: i32,
+        }
+
+        let ctx = test_context();
+        let mut req = json_request(r#"{"displayName": "test", "pageSize": 42}"#);
+        req.insert_extension(crate::json_case::JsonKeyCase::Camel);
+
+        let result = futures_executor::block_on(Json::<TestPayload>::from_request(&ctx, &mut req));
+        let Json(payload) = result.unwrap();
+        assert_eq!(payload.display_name, "test");
+        assert_eq!(payload.page_size, 42);
+    }
+
     #[test]
     fn json_extract_wrong_content_type() {
         use serde::Deserialize;
@@ -955,6 +1186,132 @@ mod tests {
     }
 }
 
+#[cfg(test)]
+mod strict_json_tests {
+    use super::*;
+    use crate::error::{LocItem, error_types};
+    use crate::request::Method;
+    use serde::Deserialize;
+
+    fn test_context() -> RequestContext {
+        let cx = asupersync::Cx::for_testing();
+        RequestContext::new(cx, 12345)
+    }
+
+    fn json_request(body: &str) -> Request {
+        let mut req = Request::new(Method::Post, "/test");
+        req.headers_mut()
+            .insert("content-type", b"application/json".to_vec());
+        req.set_body(Body::Bytes(body.as_bytes().to_vec()));
+        req
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deserialize, fastapi_macros::JsonSchema)]
+    #[serde(rename_all = "lowercase")]
+    enum Role {
+        Admin,
+        Member,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deserialize, fastapi_macros::JsonSchema)]
+    struct LineItem {
+        sku: String,
+        qty: u32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deserialize, fastapi_macros::JsonSchema)]
+    struct Order {
+        #[validate(email)]
+        email: String,
+        role: Role,
+        items: Vec<LineItem>,
+        note: Option<String>,
+    }
+
+    fn extract(body: &str) -> Result<StrictJson<Order>, ValidExtractError<JsonExtractError>> {
+        let ctx = test_context();
+        let mut req = json_request(body);
+        futures_executor::block_on(StrictJson::<Order>::from_request(&ctx, &mut req))
+    }
+
+    fn loc(path: &[LocItem]) -> Vec<LocItem> {
+        let mut loc = crate::error::loc::body();
+        loc.extend_from_slice(path);
+        loc
+    }
+
+    #[test]
+    fn accepts_conforming_body() {
+        let StrictJson(order) = extract(
+                r#"{"email": "a@b.io", "role": "admin", "items": [{"sku": "x", "qty": 2}], "note": null}"#,
+            )
+            .unwrap();
+        assert_eq!(order.items[0].qty, 2);
+        assert!(order.note.is_none());
+    }
+
+    #[test]
+    fn reports_every_violation_with_its_loc() {
+        let Err(ValidExtractError::Validation(errors)) = extract(
+            r#"{"email": "nope", "role": "owner", "items": [{"sku": "x", "qty": "2"}], "extra": 1}"#,
+        ) else {
+            panic!("expected validation errors");
+        };
+
+        let found: Vec<(&str, Vec<LocItem>)> = errors
+            .iter()
+            .map(|e| (e.error_type, e.loc.clone()))
+            .collect();
+        for expected in [
+            (error_types::VALUE_ERROR, loc(&[LocItem::field("email")])),
+            (error_types::ENUM, loc(&[LocItem::field("role")])),
+            (
+                error_types::INT_TYPE,
+                loc(&[
+                    LocItem::field("items"),
+                    LocItem::index(0),
+                    LocItem::field("qty"),
+                ]),
+            ),
+            (
+                error_types::EXTRA_FORBIDDEN,
+                loc(&[LocItem::field("extra")]),
+            ),
+        ] {
+            assert!(
+                found.contains(&expected),
+                "missing {expected:?} in {found:?}"
+            );
+        }
+        assert_eq!(found.len(), 4);
+        assert!(errors.body.is_some());
+    }
+
+    #[test]
+    fn missing_fields_and_bad_json() {
+        let Err(ValidExtractError::Validation(errors)) = extract(r#"{"email": "a@b.io"}"#) else {
+            panic!("expected validation errors");
+        };
+        let missing: Vec<Vec<LocItem>> = errors
+            .iter()
+            .filter(|e| e.error_type == error_types::MISSING)
+            .map(|e| e.loc.clone())
+            .collect();
+        assert_eq!(missing.len(), 2);
+        assert!(missing.contains(&loc(&[LocItem::field("role")])));
+
+        assert!(matches!(
+            extract("{not json"),
+            Err(ValidExtractError::Extract(
+                JsonExtractError::DeserializeError { .. }
+            ))
+        ));
+    }
+}
+
 // ============================================================================
 // Path Parameter Extractor
 // ============================================================================
@@ -1146,38 +1503,37 @@ impl std::error::Error for PathExtractError {}
 
 impl IntoResponse for PathExtractError {
     fn into_response(self) -> crate::response::Response {
-        match self {
-            Self::MissingPathParams => {
-                // Server bug - path params should always be set by router
-                HttpError::internal()
-                    .with_detail("Path parameters not available")
-                    .into_response()
-            }
-            Self::MissingParam { name } => ValidationErrors::single(
-                ValidationError::missing(crate::error::loc::path(&name))
-                    .with_msg("Path parameter is required"),
-            )
-            .into_response(),
+        match self.into_validation_errors() {
+            Ok(errors) => errors.into_response(),
+            // Server bug - path params should always be set by router
+            Err(_) => HttpError::internal()
+                .with_detail("Path parameters not available")
+                .into_response(),
+        }
+    }
+}
+
+impl IntoValidationErrors for PathExtractError {
+    fn into_validation_errors(self) -> Result<ValidationErrors, Self> {
+        let error = match self {
+            Self::MissingPathParams => return Err(self),
+            Self::MissingParam { name } => ValidationError::missing(crate::error::loc::path(&name))
+                .with_msg("Path parameter is required"),
             Self::InvalidValue {
                 name,
                 value,
                 expected,
                 message,
-            } => ValidationErrors::single(
-                ValidationError::type_error(crate::error::loc::path(&name), &expected)
-                    .with_msg(format!("Expected {expected}: {message}"))
-                    .with_input(serde_json::Value::String(value)),
-            )
-            .into_response(),
-            Self::DeserializeError { message } => ValidationErrors::single(
-                ValidationError::new(
-                    crate::error::error_types::VALUE_ERROR,
-                    vec![crate::error::LocItem::field("path")],
-                )
-                .with_msg(message),
+            } => ValidationError::type_error(crate::error::loc::path(&name), &expected)
+                .with_msg(format!("Expected {expected}: {message}"))
+                .with_input(serde_json::Value::String(value)),
+            Self::DeserializeError { message } => ValidationError::new(
+                crate::error::error_types::VALUE_ERROR,
+                vec![crate::error::LocItem::field("path")],
             )
-            .into_response(),
-        }
+            .with_msg(message),
+        };
+        Ok(ValidationErrors::single(error))
     }
 }
 
@@ -1198,6 +1554,91 @@ impl<T: DeserializeOwned> FromRequest for Path<T> {
     }
 }
 
+// ============================================================================
+// Typed Paths
+// ============================================================================
+
+/// A struct bound to a route template, usable both as an extractor and to
+/// build URLs.
+///
+/// Implement it with `#[derive(TypedPath)]`, which checks at compile time
+/// that the template's parameters and the struct's fields match, and use
+/// the type in place of the path in a route macro (`#[get(ItemPath)]`).
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(TypedPath)]
+/// #[typed_path("/items/{id}")]
+/// struct ItemPath {
+///     id: i64,
+/// }
+///
+/// #[get(ItemPath)]
+/// async fn get_item(path: ItemPath) -> Json<Item> { /* ... */ }
+///
+/// assert_eq!(ItemPath { id: 7 }.url(), "/items/7");
+/// ```
+pub trait TypedPath: Sized {
+    /// The route template, e.g. `/items/{id}`.
+    const PATH: &'static str;
+
+    /// Build the struct from matched path parameters.
+    fn from_params(params: &PathParams) -> Result<Self, PathExtractError>;
+
+    /// Build the URL for these parameter values, percent-encoding each one.
+    fn url(&self) -> String;
+}
+
+/// Parse one named path parameter with [`FromStr`](std::str::FromStr).
+///
+/// Used by `#[derive(TypedPath)]`.
+#[doc(hidden)]
+pub fn parse_path_param<T>(params: &PathParams, name: &str) -> Result<T, PathExtractError>
+where
+    T: std::str::FromStr,
+    T::Err: fmt::Display,
+{
+    let value = params
+        .get(name)
+        .ok_or_else(|| PathExtractError::MissingParam {
+            name: name.to_string(),
+        })?;
+    value
+        .parse()
+        .map_err(|e: T::Err| PathExtractError::InvalidValue {
+            name: name.to_string(),
+            value: value.to_string(),
+            expected: std::any::type_name::<T>(),
+            message: e.to_string(),
+        })
+}
+
+/// Percent-encode a path parameter value for use in a URL.
+///
+/// Everything but RFC 3986 unreserved characters is escaped; `/` is kept
+/// when `keep_slash` is set, for `{name:path}` parameters. Used by
+/// `#[derive(TypedPath)]`.
+#[doc(hidden)]
+#[must_use]
+pub fn encode_path_param(value: &str, keep_slash: bool) -> String {
+    use std::fmt::Write as _;
+
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(char::from(byte));
+            }
+            b'/' if keep_slash => out.push('/'),
+            _ => {
+                let _ = write!(out, "%{byte:02X}");
+            }
+        }
+    }
+    out
+}
+
 // ============================================================================
 // Path Parameter Deserializer
 // ============================================================================
@@ -2231,37 +2672,42 @@ impl de::Error for QueryExtractError {
     }
 }
 
-impl IntoResponse for QueryExtractError {
-    fn into_response(self) -> crate::response::Response {
+impl QueryExtractError {
+    fn validation_error(self) -> ValidationError {
         match self {
-            Self::MissingParam { name } => ValidationErrors::single(
+            Self::MissingParam { name } => {
                 ValidationError::missing(crate::error::loc::query(&name))
-                    .with_msg("Query parameter is required"),
-            )
-            .into_response(),
+                    .with_msg("Query parameter is required")
+            }
             Self::InvalidValue {
                 name,
                 value,
                 expected,
                 message,
-            } => ValidationErrors::single(
-                ValidationError::type_error(crate::error::loc::query(&name), &expected)
-                    .with_msg(format!("Expected {expected}: {message}"))
-                    .with_input(serde_json::Value::String(value)),
-            )
-            .into_response(),
-            Self::DeserializeError { message } => ValidationErrors::single(
-                ValidationError::new(
-                    crate::error::error_types::VALUE_ERROR,
-                    vec![crate::error::LocItem::field("query")],
-                )
-                .with_msg(message),
+            } => ValidationError::type_error(crate::error::loc::query(&name), &expected)
+                .with_msg(format!("Expected {expected}: {message}"))
+                .with_input(serde_json::Value::String(value)),
+            Self::DeserializeError { message } => ValidationError::new(
+                crate::error::error_types::VALUE_ERROR,
+                vec![crate::error::LocItem::field("query")],
             )
-            .into_response(),
+            .with_msg(message),
         }
     }
 }
 
+impl IntoResponse for QueryExtractError {
+    fn into_response(self) -> crate::response::Response {
+        ValidationErrors::single(self.validation_error()).into_response()
+    }
+}
+
+impl IntoValidationErrors for QueryExtractError {
+    fn into_validation_errors(self) -> Result<ValidationErrors, Self> {
+        Ok(ValidationErrors::single(self.validation_error()))
+    }
+}
+
 /// Stored query parameters for extraction.
 ///
 /// Similar to `PathParams` but handles multi-value parameters.
@@ -2492,10 +2938,36 @@ impl PaginationConfig {
 struct PaginationParams {
     page: Option<u64>,
     per_page: Option<u64>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+fn invalid_pagination(name: &str, value: impl fmt::Display, message: String) -> QueryExtractError {
+    QueryExtractError::InvalidValue {
+        name: name.to_string(),
+        value: value.to_string(),
+        expected: "u64",
+        message,
+    }
+}
+
+/// Checks a page size against `1..=max`.
+fn check_page_size(name: &str, size: u64, max: u64) -> Result<u64, QueryExtractError> {
+    if size == 0 {
+        return Err(invalid_pagination(name, size, "must be >= 1".to_string()));
+    }
+    if size > max {
+        return Err(invalid_pagination(name, size, format!("must be <= {max}")));
+    }
+    Ok(size)
 }
 
 /// Pagination extractor: reads `?page=` and `?per_page=` from the query string.
 ///
+/// Clients may send `?limit=` and `?offset=` instead; `limit` is an alias
+/// for `per_page`, and `offset` replaces `page` for offsets that are not a
+/// multiple of the page size. Mixing the two styles is rejected.
+///
 /// Defaults:
 /// - `page`: 1
 /// - `per_page`: 20
@@ -2504,6 +2976,7 @@ struct PaginationParams {
 pub struct Pagination {
     page: u64,
     per_page: u64,
+    offset: u64,
 }
 
 impl Pagination {
@@ -2523,10 +2996,10 @@ impl Pagination {
         self.per_page
     }
 
-    /// Zero-based offset (`(page - 1) * per_page`).
+    /// Zero-based offset: `?offset=` if given, else `(page - 1) * per_page`.
     #[must_use]
     pub fn offset(&self) -> u64 {
-        self.page.saturating_sub(1).saturating_mul(self.per_page)
+        self.offset
     }
 }
 
@@ -2542,39 +3015,212 @@ impl FromRequest for Pagination {
 
         let Query(params) = Query::<PaginationParams>::from_request(ctx, req).await?;
 
-        let page = params.page.unwrap_or(config.default_page);
-        if page == 0 {
-            return Err(QueryExtractError::InvalidValue {
-                name: "page".to_string(),
-                value: "0".to_string(),
-                expected: "u64",
-                message: "must be >= 1".to_string(),
-            });
+        if let (Some(_), Some(offset)) = (params.page, params.offset) {
+            return Err(invalid_pagination(
+                "offset",
+                offset,
+                "cannot be combined with `page`".to_string(),
+            ));
+        }
+        if let (Some(_), Some(limit)) = (params.per_page, params.limit) {
+            return Err(invalid_pagination(
+                "limit",
+                limit,
+                "cannot be combined with `per_page`".to_string(),
+            ));
         }
 
-        let per_page = params.per_page.unwrap_or(config.default_per_page);
-        if per_page == 0 {
-            return Err(QueryExtractError::InvalidValue {
-                name: "per_page".to_string(),
-                value: "0".to_string(),
-                expected: "u64",
-                message: "must be >= 1".to_string(),
+        let (size_name, per_page) = match params.limit {
+            Some(limit) => ("limit", limit),
+            None => (
+                "per_page",
+                params.per_page.unwrap_or(config.default_per_page),
+            ),
+        };
+        let per_page = check_page_size(size_name, per_page, config.max_per_page)?;
+
+        if let Some(offset) = params.offset {
+            return Ok(Self {
+                page: offset / per_page + 1,
+                per_page,
+                offset,
             });
         }
-        if per_page > config.max_per_page {
-            return Err(QueryExtractError::InvalidValue {
-                name: "per_page".to_string(),
-                value: per_page.to_string(),
-                expected: "u64",
-                message: format!("must be <= {}", config.max_per_page),
-            });
+
+        let page = params.page.unwrap_or(config.default_page);
+        if page == 0 {
+            return Err(invalid_pagination("page", 0, "must be >= 1".to_string()));
         }
 
-        Ok(Self { page, per_page })
+        Ok(Self {
+            page,
+            per_page,
+            offset: (page - 1).saturating_mul(per_page),
+        })
+    }
+}
+
+impl QueryParamDocs for Pagination {
+    fn query_param_docs() -> Vec<fastapi_router::ParamInfo> {
+        let size_schema =
+            serde_json::json!({"type": "integer", "minimum": 1, "maximum": MAX_PER_PAGE});
+        vec![
+            fastapi_router::ParamInfo::new("page", fastapi_router::Converter::Int)
+                .with_description("Page number, starting at 1.")
+                .with_schema(serde_json::json!({"type": "integer", "minimum": 1}))
+                .with_default(DEFAULT_PAGE.into()),
+            fastapi_router::ParamInfo::new("per_page", fastapi_router::Converter::Int)
+                .with_description("Items per page.")
+                .with_schema(size_schema.clone())
+                .with_default(DEFAULT_PER_PAGE.into()),
+            fastapi_router::ParamInfo::new("limit", fastapi_router::Converter::Int)
+                .with_description("Alias for `per_page`.")
+                .with_schema(size_schema),
+            fastapi_router::ParamInfo::new("offset", fastapi_router::Converter::Int)
+                .with_description("Items to skip; use instead of `page`.")
+                .with_schema(serde_json::json!({"type": "integer", "minimum": 0})),
+        ]
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct CursorPaginationParams {
+    cursor: Option<String>,
+    limit: Option<u64>,
+}
+
+/// Cursor pagination extractor: reads `?cursor=` and `?limit=` from the
+/// query string.
+///
+/// The cursor is opaque to the framework; an empty `?cursor=` is treated as
+/// absent (the first page). `limit` uses the `per_page` default and maximum
+/// of [`PaginationConfig`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CursorPagination {
+    cursor: Option<String>,
+    limit: u64,
+}
+
+impl CursorPagination {
+    /// The cursor sent by the client, or `None` for the first page.
+    #[must_use]
+    pub fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+
+    /// Maximum number of items to return.
+    #[must_use]
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+}
+
+impl FromRequest for CursorPagination {
+    type Error = QueryExtractError;
+
+    async fn from_request(ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
+        let config = req
+            .get_extension::<PaginationConfig>()
+            .copied()
+            .unwrap_or_default();
+
+        let Query(params) = Query::<CursorPaginationParams>::from_request(ctx, req).await?;
+        let limit = check_page_size(
+            "limit",
+            params.limit.unwrap_or(config.default_per_page),
+            config.max_per_page,
+        )?;
+
+        Ok(Self {
+            cursor: params.cursor.filter(|c| !c.is_empty()),
+            limit,
+        })
+    }
+}
+
+impl QueryParamDocs for CursorPagination {
+    fn query_param_docs() -> Vec<fastapi_router::ParamInfo> {
+        vec![
+            fastapi_router::ParamInfo::new("cursor", fastapi_router::Converter::Str)
+                .with_description("Opaque cursor from a previous page; omit for the first page."),
+            fastapi_router::ParamInfo::new("limit", fastapi_router::Converter::Int)
+                .with_description("Maximum number of items to return.")
+                .with_schema(
+                    serde_json::json!({"type": "integer", "minimum": 1, "maximum": MAX_PER_PAGE}),
+                )
+                .with_default(DEFAULT_PER_PAGE.into()),
+        ]
+    }
+}
+
+/// Extractors that read documented query parameters.
+///
+/// Route macros add these parameters to the operation's OpenAPI entry.
+pub trait QueryParamDocs {
+    /// The query parameters the extractor reads.
+    fn query_param_docs() -> Vec<fastapi_router::ParamInfo>;
+}
+
+/// Macro support: documents the query parameters of any parameter type.
+///
+/// `(&&QueryParamsProbe::<T>::default()).query_param_docs()` picks
+/// [`QueryParamsProbeMatch`] when `T` implements [`QueryParamDocs`] and falls
+/// back to [`QueryParamsProbeFallback`] otherwise.
+#[doc(hidden)]
+pub struct QueryParamsProbe<T>(std::marker::PhantomData<T>);
+
+impl<T> Default for QueryParamsProbe<T> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait QueryParamsProbeMatch {
+    fn query_param_docs(&self) -> Vec<fastapi_router::ParamInfo>;
+}
+
+impl<T: QueryParamDocs> QueryParamsProbeMatch for &QueryParamsProbe<T> {
+    fn query_param_docs(&self) -> Vec<fastapi_router::ParamInfo> {
+        T::query_param_docs()
+    }
+}
+
+#[doc(hidden)]
+pub trait QueryParamsProbeFallback {
+    fn query_param_docs(&self) -> Vec<fastapi_router::ParamInfo>;
+}
+
+impl<T> QueryParamsProbeFallback for QueryParamsProbe<T> {
+    fn query_param_docs(&self) -> Vec<fastapi_router::ParamInfo> {
+        Vec::new()
+    }
+}
+
+/// Removes `names` from the query string of `url`.
+fn strip_query_params(url: &str, names: &[&str]) -> String {
+    let Some((path, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| {
+            let name = pair.split('=').next().unwrap_or_default();
+            !pair.is_empty() && !names.contains(&name)
+        })
+        .collect();
+    if kept.is_empty() {
+        path.to_string()
+    } else {
+        format!("{path}?{}", kept.join("&"))
     }
 }
 
 /// Generic paginated response payload.
+///
+/// Serializes as `{"items": [...], "total", "page", "per_page", "total_pages"}`.
+/// Returned directly from a handler it becomes a JSON response, with an
+/// RFC 8288 `Link` header once [`with_links`](Self::with_links) is set.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct Page<T> {
     pub items: Vec<T>,
@@ -2582,6 +3228,8 @@ pub struct Page<T> {
     pub page: u64,
     pub per_page: u64,
     pub total_pages: u64,
+    #[serde(skip)]
+    link_base: Option<String>,
 }
 
 impl<T> Page<T> {
@@ -2598,7 +3246,134 @@ impl<T> Page<T> {
             page,
             per_page,
             total_pages,
+            link_base: None,
+        }
+    }
+
+    /// Build a page from the request's [`Pagination`].
+    #[must_use]
+    pub fn from_pagination(items: Vec<T>, total: u64, pagination: &Pagination) -> Self {
+        Self::new(items, total, pagination.page(), pagination.per_page())
+    }
+
+    /// Add `self`, `first`, `last`, `prev` and `next` links relative to `url`,
+    /// usually the request path and query. Existing pagination parameters in
+    /// `url` are replaced; others are kept.
+    #[must_use]
+    pub fn with_links(mut self, url: impl Into<String>) -> Self {
+        self.link_base = Some(url.into());
+        self
+    }
+
+    /// The `Link` header for this page, if [`with_links`](Self::with_links) was set.
+    #[must_use]
+    pub fn link_header(&self) -> Option<LinkHeader> {
+        let base = strip_query_params(
+            self.link_base.as_deref()?,
+            &["page", "per_page", "limit", "offset"],
+        );
+        Some(LinkHeader::new().paginate(&base, self.page, self.per_page.max(1), self.total))
+    }
+}
+
+impl<T: serde::Serialize> IntoResponse for Page<T> {
+    fn into_response(self) -> Response {
+        paginated_response(&self, self.link_header())
+    }
+}
+
+/// Cursor-paginated response payload.
+///
+/// Serializes as `{"items": [...], "limit", "next_cursor", "prev_cursor"}`,
+/// with `null` cursors at either end. Returned directly from a handler it
+/// becomes a JSON response, with a `Link` header once
+/// [`with_links`](Self::with_links) is set.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    pub limit: u64,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+    #[serde(skip)]
+    link_base: Option<String>,
+}
+
+impl<T> CursorPage<T> {
+    /// A page of `items`, followed by `next_cursor` if there are more.
+    #[must_use]
+    pub fn new(items: Vec<T>, limit: u64, next_cursor: Option<String>) -> Self {
+        Self {
+            items,
+            limit,
+            next_cursor,
+            prev_cursor: None,
+            link_base: None,
+        }
+    }
+
+    /// Set the cursor of the previous page.
+    #[must_use]
+    pub fn prev_cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.prev_cursor = Some(cursor.into());
+        self
+    }
+
+    /// Whether another page follows.
+    #[must_use]
+    pub fn has_more(&self) -> bool {
+        self.next_cursor.is_some()
+    }
+
+    /// Add `first`, `prev` and `next` links relative to `url`, usually the
+    /// request path and query. Existing `cursor`/`limit` parameters in `url`
+    /// are replaced; others are kept.
+    #[must_use]
+    pub fn with_links(mut self, url: impl Into<String>) -> Self {
+        self.link_base = Some(url.into());
+        self
+    }
+
+    /// The `Link` header for this page, if [`with_links`](Self::with_links) was set.
+    #[must_use]
+    pub fn link_header(&self) -> Option<LinkHeader> {
+        let base = strip_query_params(self.link_base.as_deref()?, &["cursor", "limit"]);
+        let sep = if base.contains('?') { '&' } else { '?' };
+        let limit = self.limit;
+        let mut header =
+            LinkHeader::new().link(format!("{base}{sep}limit={limit}"), LinkRel::First);
+        if let Some(prev) = &self.prev_cursor {
+            let cursor = encode_path_param(prev, false);
+            header = header.link(
+                format!("{base}{sep}cursor={cursor}&limit={limit}"),
+                LinkRel::Prev,
+            );
+        }
+        if let Some(next) = &self.next_cursor {
+            let cursor = encode_path_param(next, false);
+            header = header.link(
+                format!("{base}{sep}cursor={cursor}&limit={limit}"),
+                LinkRel::Next,
+            );
         }
+        Some(header)
+    }
+}
+
+impl<T: serde::Serialize> IntoResponse for CursorPage<T> {
+    fn into_response(self) -> Response {
+        paginated_response(&self, self.link_header())
+    }
+}
+
+fn paginated_response<P: serde::Serialize>(page: &P, links: Option<LinkHeader>) -> Response {
+    match Response::json(page) {
+        Ok(response) => match links {
+            Some(links) => links.apply(response),
+            None => response,
+        },
+        Err(_) => HttpError::new(crate::response::StatusCode::INTERNAL_SERVER_ERROR)
+            .with_detail("Failed to serialize page")
+            .into_response(),
     }
 }
 
@@ -4465,10 +5240,9 @@ impl std::fmt::Display for HeaderExtractError {
 
 impl std::error::Error for HeaderExtractError {}
 
-impl IntoResponse for HeaderExtractError {
-    fn into_response(self) -> crate::response::Response {
-        // Missing or invalid headers are client errors (400)
-        let error = match &self {
+impl HeaderExtractError {
+    fn validation_error(&self) -> ValidationError {
+        match self {
             HeaderExtractError::MissingHeader { name } => {
                 ValidationError::missing(crate::error::loc::header(name))
                     .with_msg(format!("Missing required header: {name}"))
@@ -4485,8 +5259,20 @@ impl IntoResponse for HeaderExtractError {
             } => ValidationError::type_error(crate::error::loc::header(name), expected)
                 .with_msg(format!("Failed to parse as {expected}: {message}"))
                 .with_input(serde_json::Value::String(value.clone())),
-        };
-        ValidationErrors::single(error).into_response()
+        }
+    }
+}
+
+impl IntoResponse for HeaderExtractError {
+    fn into_response(self) -> crate::response::Response {
+        // Missing or invalid headers are client errors (400)
+        ValidationErrors::single(self.validation_error()).into_response()
+    }
+}
+
+impl IntoValidationErrors for HeaderExtractError {
+    fn into_validation_errors(self) -> Result<ValidationErrors, Self> {
+        Ok(ValidationErrors::single(self.validation_error()))
     }
 }
 
@@ -4699,6 +5485,100 @@ impl HeaderName for Host {
     const NAME: &'static str = "host";
 }
 
+// ============================================================================
+// OpenAPI Security Metadata
+// ============================================================================
+
+/// An extractor that authenticates requests under an OpenAPI security scheme.
+///
+/// Handlers generated by the route macros inspect their parameters: each one
+/// implementing this trait adds its scheme to `components.securitySchemes`
+/// and a matching security requirement to the operation.
+pub trait SecurityExtractor {
+    /// Key under `components.securitySchemes`.
+    const SCHEME_NAME: &'static str;
+
+    /// The scheme definition.
+    fn security_scheme() -> fastapi_openapi::SecurityScheme;
+
+    /// Scopes the operation requires (none by default).
+    fn required_scopes() -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Optional authentication is still documented under the inner scheme.
+impl<T: SecurityExtractor> SecurityExtractor for Option<T> {
+    const SCHEME_NAME: &'static str = T::SCHEME_NAME;
+
+    fn security_scheme() -> fastapi_openapi::SecurityScheme {
+        T::security_scheme()
+    }
+
+    fn required_scopes() -> Vec<String> {
+        T::required_scopes()
+    }
+}
+
+/// A security scheme together with the scopes a route requires from it.
+#[derive(Debug, Clone)]
+pub struct SecurityDefinition {
+    /// Key under `components.securitySchemes`.
+    pub name: &'static str,
+    /// The scheme definition.
+    pub scheme: fastapi_openapi::SecurityScheme,
+    /// Required scopes.
+    pub scopes: Vec<String>,
+}
+
+impl SecurityDefinition {
+    /// Collects the definition for extractor `T`.
+    #[must_use]
+    pub fn of<T: SecurityExtractor>() -> Self {
+        Self {
+            name: T::SCHEME_NAME,
+            scheme: T::security_scheme(),
+            scopes: T::required_scopes(),
+        }
+    }
+}
+
+/// Macro support: resolves whether a parameter type is a [`SecurityExtractor`].
+///
+/// `(&&SecurityProbe::<T>::default()).security_definition()` picks
+/// [`SecurityProbeMatch`] when `T` implements the trait and falls back to
+/// [`SecurityProbeFallback`] otherwise.
+#[doc(hidden)]
+pub struct SecurityProbe<T>(std::marker::PhantomData<T>);
+
+impl<T> Default for SecurityProbe<T> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait SecurityProbeMatch {
+    fn security_definition(&self) -> Option<SecurityDefinition>;
+}
+
+impl<T: SecurityExtractor> SecurityProbeMatch for &SecurityProbe<T> {
+    fn security_definition(&self) -> Option<SecurityDefinition> {
+        Some(SecurityDefinition::of::<T>())
+    }
+}
+
+#[doc(hidden)]
+pub trait SecurityProbeFallback {
+    fn security_definition(&self) -> Option<SecurityDefinition>;
+}
+
+impl<T> SecurityProbeFallback for SecurityProbe<T> {
+    fn security_definition(&self) -> Option<SecurityDefinition> {
+        None
+    }
+}
+
 // ============================================================================
 // OAuth2 Security Extractors
 // ============================================================================
@@ -4854,6 +5734,26 @@ impl OAuth2PasswordBearerConfig {
         self.auto_error = auto_error;
         self
     }
+
+    /// Builds the OpenAPI password-flow scheme described by this config.
+    ///
+    /// Register it with [`OpenApiConfig::security_scheme`](crate::OpenApiConfig::security_scheme)
+    /// under [`scheme_name`](Self::scheme_name) (or `OAuth2PasswordBearer`) to
+    /// replace the default scheme generated for the extractor.
+    #[must_use]
+    pub fn security_scheme(&self) -> fastapi_openapi::SecurityScheme {
+        let mut flow = fastapi_openapi::OAuthFlow::password(&self.token_url);
+        flow.refresh_url.clone_from(&self.refresh_url);
+        flow.scopes.clone_from(&self.scopes);
+        let scheme = fastapi_openapi::SecurityScheme::oauth2(fastapi_openapi::OAuthFlows {
+            password: Some(flow),
+            ..Default::default()
+        });
+        match &self.description {
+            Some(description) => scheme.with_description(description),
+            None => scheme,
+        }
+    }
 }
 
 /// Error when OAuth2 bearer token extraction fails.
@@ -4971,6 +5871,14 @@ impl FromRequest for OAuth2PasswordBearer {
     }
 }
 
+impl SecurityExtractor for OAuth2PasswordBearer {
+    const SCHEME_NAME: &'static str = "OAuth2PasswordBearer";
+
+    fn security_scheme() -> fastapi_openapi::SecurityScheme {
+        OAuth2PasswordBearerConfig::default().security_scheme()
+    }
+}
+
 // ============================================================================
 // HTTP Basic Auth Extractor
 // ============================================================================
@@ -5058,7 +5966,7 @@ impl BasicAuth {
 
 /// Simple base64 decoder (standard alphabet).
 #[allow(clippy::cast_sign_loss)] // value is validated >= 0 before cast
-fn base64_decode(input: &str) -> Option<Vec<u8>> {
+pub(crate) fn base64_decode(input: &str) -> Option<Vec<u8>> {
     const DECODE_TABLE: [i8; 256] = {
         let mut table = [-1i8; 256];
         let alphabet = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
@@ -5229,6 +6137,14 @@ impl FromRequest for BasicAuth {
     }
 }
 
+impl SecurityExtractor for BasicAuth {
+    const SCHEME_NAME: &'static str = "HTTPBasic";
+
+    fn security_scheme() -> fastapi_openapi::SecurityScheme {
+        fastapi_openapi::SecurityScheme::basic()
+    }
+}
+
 // ============================================================================
 // Bearer Token Extractor
 // ============================================================================
@@ -5374,6 +6290,14 @@ impl FromRequest for BearerToken {
     }
 }
 
+impl SecurityExtractor for BearerToken {
+    const SCHEME_NAME: &'static str = "HTTPBearer";
+
+    fn security_scheme() -> fastapi_openapi::SecurityScheme {
+        fastapi_openapi::SecurityScheme::bearer()
+    }
+}
+
 // ============================================================================
 // API Key Extractor
 // ============================================================================
@@ -5510,6 +6434,21 @@ impl ApiKeyConfig {
         self.description = Some(desc.into());
         self
     }
+
+    /// Builds the OpenAPI `apiKey` scheme described by this config.
+    #[must_use]
+    pub fn security_scheme(&self) -> fastapi_openapi::SecurityScheme {
+        let location = match self.location {
+            ApiKeyLocation::Header => fastapi_openapi::ApiKeyLocation::Header,
+            ApiKeyLocation::Query => fastapi_openapi::ApiKeyLocation::Query,
+            ApiKeyLocation::Cookie => fastapi_openapi::ApiKeyLocation::Cookie,
+        };
+        let scheme = fastapi_openapi::SecurityScheme::api_key(&self.name, location);
+        match &self.description {
+            Some(description) => scheme.with_description(description),
+            None => scheme,
+        }
+    }
 }
 
 /// Error when API key extraction fails.
@@ -5530,6 +6469,10 @@ pub enum ApiKeyErrorKind {
     Missing,
     /// API key is empty.
     Empty,
+    /// API key was rejected by the [`ApiKeyVerifier`].
+    Invalid,
+    /// No [`ApiKeyVerifier`] is registered in the application state.
+    NotConfigured,
 }
 
 impl ApiKeyError {
@@ -5552,6 +6495,27 @@ impl ApiKeyError {
             name: name.into(),
         }
     }
+
+    /// Create a rejected key error.
+    #[must_use]
+    pub fn invalid(location: ApiKeyLocation, name: impl Into<String>) -> Self {
+        Self {
+            kind: ApiKeyErrorKind::Invalid,
+            location,
+            name: name.into(),
+        }
+    }
+
+    /// Create an error for a key that cannot be checked because no
+    /// [`ApiKeyVerifier`] is registered.
+    #[must_use]
+    pub fn not_configured(location: ApiKeyLocation, name: impl Into<String>) -> Self {
+        Self {
+            kind: ApiKeyErrorKind::NotConfigured,
+            location,
+            name: name.into(),
+        }
+    }
 }
 
 impl fmt::Display for ApiKeyError {
@@ -5568,6 +6532,14 @@ impl fmt::Display for ApiKeyError {
             ApiKeyErrorKind::Empty => {
                 write!(f, "Empty API key in {} '{}'", location_name, self.name)
             }
+            ApiKeyErrorKind::Invalid => {
+                write!(f, "Invalid API key in {} '{}'", location_name, self.name)
+            }
+            ApiKeyErrorKind::NotConfigured => write!(
+                f,
+                "ApiKeyVerifier not registered in app state; cannot check {} '{}'",
+                location_name, self.name
+            ),
         }
     }
 }
@@ -5576,11 +6548,20 @@ impl IntoResponse for ApiKeyError {
     fn into_response(self) -> crate::response::Response {
         use crate::response::{Response, ResponseBody, StatusCode};
 
-        let body = serde_json::json!({
-            "detail": "Not authenticated"
-        });
+        // A missing key is unauthenticated; a wrong one is forbidden. A
+        // missing verifier is the server's fault and must not let keys through.
+        let (status, detail) = match self.kind {
+            ApiKeyErrorKind::Missing | ApiKeyErrorKind::Empty => {
+                (StatusCode::UNAUTHORIZED, "Not authenticated")
+            }
+            ApiKeyErrorKind::Invalid => (StatusCode::FORBIDDEN, "Invalid API key"),
+            ApiKeyErrorKind::NotConfigured => {
+                return crate::HttpError::internal().into_response();
+            }
+        };
+        let body = serde_json::json!({ "detail": detail });
 
-        Response::with_status(StatusCode::UNAUTHORIZED)
+        Response::with_status(status)
             .header("content-type", b"application/json".to_vec())
             .body(ResponseBody::Bytes(body.to_string().into_bytes()))
     }
@@ -5609,6 +6590,266 @@ impl FromRequest for ApiKey {
     }
 }
 
+impl SecurityExtractor for ApiKey {
+    const SCHEME_NAME: &'static str = "APIKeyHeader";
+
+    fn security_scheme() -> fastapi_openapi::SecurityScheme {
+        ApiKeyConfig::default().security_scheme()
+    }
+}
+
+/// Name marker for the [`ApiKeyHeader`], [`ApiKeyQuery`] and [`ApiKeyCookie`]
+/// extractors.
+///
+/// # Example
+///
+/// ```ignore
+/// use fastapi_core::{ApiKeyHeader, ApiKeyName};
+///
+/// struct ServiceToken;
+/// impl ApiKeyName for ServiceToken {
+///     const NAME: &'static str = "X-Service-Token";
+///     const SCHEME_NAME: Option<&'static str> = Some("ServiceToken");
+/// }
+///
+/// async fn internal(key: ApiKeyHeader<ServiceToken>) -> impl IntoResponse {
+///     "ok"
+/// }
+/// ```
+pub trait ApiKeyName {
+    /// Header, query parameter or cookie name.
+    const NAME: &'static str;
+    /// Key under `components.securitySchemes`; defaults to `APIKeyHeader`,
+    /// `APIKeyQuery` or `APIKeyCookie` by location.
+    const SCHEME_NAME: Option<&'static str> = None;
+}
+
+/// The `X-API-Key` header (default for [`ApiKeyHeader`]).
+pub struct XApiKey;
+impl ApiKeyName for XApiKey {
+    const NAME: &'static str = "X-API-Key";
+}
+
+/// The `api_key` query parameter or cookie (default for [`ApiKeyQuery`] and
+/// [`ApiKeyCookie`]).
+pub struct ApiKeyParam;
+impl ApiKeyName for ApiKeyParam {
+    const NAME: &'static str = "api_key";
+}
+
+/// Decides which API keys are accepted by the `ApiKey*` extractors.
+///
+/// Register one in the application state. Without a verifier the `ApiKey*`
+/// extractors reject every key with a 500, so a missing registration fails
+/// closed. The plain [`ApiKey`] extractor does not consult a verifier.
+///
+/// ```ignore
+/// let state = AppState::new().with(ApiKeyVerifier::keys(["k-live-1", "k-live-2"]));
+/// // or
+/// let state = AppState::new().with(ApiKeyVerifier::new(|key| keys_db.contains(key)));
+/// ```
+#[derive(Clone)]
+pub struct ApiKeyVerifier {
+    verify: std::sync::Arc<dyn Fn(&str) -> bool + Send + Sync>,
+}
+
+impl ApiKeyVerifier {
+    /// Accept keys for which `verify` returns true.
+    #[must_use]
+    pub fn new(verify: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            verify: std::sync::Arc::new(verify),
+        }
+    }
+
+    /// Accept exactly the given keys, compared in constant time.
+    #[must_use]
+    pub fn keys<I, K>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        let keys: Vec<String> = keys.into_iter().map(Into::into).collect();
+        Self::new(move |key| {
+            keys.iter().fold(false, |found, expected| {
+                crate::password::constant_time_eq(key.as_bytes(), expected.as_bytes()) | found
+            })
+        })
+    }
+
+    /// Whether `key` is accepted.
+    #[must_use]
+    pub fn verify(&self, key: &str) -> bool {
+        (self.verify)(key)
+    }
+}
+
+impl fmt::Debug for ApiKeyVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKeyVerifier").finish_non_exhaustive()
+    }
+}
+
+/// Reads the key at `location`, then checks it with the registered verifier.
+fn extract_api_key(
+    req: &Request,
+    location: ApiKeyLocation,
+    name: &str,
+) -> Result<String, ApiKeyError> {
+    let key = match location {
+        ApiKeyLocation::Header => req
+            .headers()
+            .get(name)
+            .and_then(|v| std::str::from_utf8(v).ok())
+            .map(|s| s.trim().to_string()),
+        ApiKeyLocation::Query => req
+            .query()
+            .and_then(|query| QueryParams::parse(query).get(name).map(str::to_string)),
+        ApiKeyLocation::Cookie => req
+            .headers()
+            .get("cookie")
+            .and_then(|v| std::str::from_utf8(v).ok())
+            .and_then(|cookies| {
+                parse_cookies(cookies)
+                    .find(|(cookie, _)| *cookie == name)
+                    .map(|(_, value)| value.to_string())
+            }),
+    }
+    .ok_or_else(|| ApiKeyError::missing(location, name))?;
+
+    if key.is_empty() {
+        return Err(ApiKeyError::empty(location, name));
+    }
+    let verifier = req
+        .get_extension::<AppState>()
+        .and_then(AppState::get::<ApiKeyVerifier>)
+        .ok_or_else(|| ApiKeyError::not_configured(location, name))?;
+    if !verifier.verify(&key) {
+        return Err(ApiKeyError::invalid(location, name));
+    }
+    Ok(key)
+}
+
+macro_rules! api_key_extractor {
+    ($(#[$doc:meta])* $name:ident, $default:ty, $location:ident, $scheme:literal) => {
+        $(#[$doc])*
+        pub struct $name<N = $default> {
+            /// The verified API key.
+            pub key: String,
+            _marker: std::marker::PhantomData<N>,
+        }
+
+        impl<N> $name<N> {
+            /// Wrap an already verified key.
+            #[must_use]
+            pub fn new(key: impl Into<String>) -> Self {
+                Self {
+                    key: key.into(),
+                    _marker: std::marker::PhantomData,
+                }
+            }
+
+            /// Get the key value.
+            #[must_use]
+            pub fn key(&self) -> &str {
+                &self.key
+            }
+
+            /// Consume self and return the key.
+            #[must_use]
+            pub fn into_key(self) -> String {
+                self.key
+            }
+        }
+
+        impl<N> Clone for $name<N> {
+            fn clone(&self) -> Self {
+                Self::new(self.key.clone())
+            }
+        }
+
+        impl<N> fmt::Debug for $name<N> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($name)).finish_non_exhaustive()
+            }
+        }
+
+        impl<N> Deref for $name<N> {
+            type Target = str;
+
+            fn deref(&self) -> &Self::Target {
+                &self.key
+            }
+        }
+
+        impl<N: ApiKeyName + Send + Sync + 'static> FromRequest for $name<N> {
+            type Error = ApiKeyError;
+
+            async fn from_request(
+                _ctx: &RequestContext,
+                req: &mut Request,
+            ) -> Result<Self, Self::Error> {
+                extract_api_key(req, ApiKeyLocation::$location, N::NAME).map(Self::new)
+            }
+        }
+
+        impl<N: ApiKeyName> SecurityExtractor for $name<N> {
+            const SCHEME_NAME: &'static str = match N::SCHEME_NAME {
+                Some(name) => name,
+                None => $scheme,
+            };
+
+            fn security_scheme() -> fastapi_openapi::SecurityScheme {
+                ApiKeyConfig {
+                    name: N::NAME.to_string(),
+                    location: ApiKeyLocation::$location,
+                    description: None,
+                }
+                .security_scheme()
+            }
+        }
+    };
+}
+
+api_key_extractor!(
+    /// API key read from a request header (`X-API-Key` by default).
+    ///
+    /// Missing or empty keys are answered with 401, keys rejected by the
+    /// [`ApiKeyVerifier`] in the application state with 403, and any key with
+    /// 500 when no verifier is registered. Documented as an
+    /// `apiKey` scheme `in: header`.
+    ///
+    /// ```ignore
+    /// async fn protected(key: ApiKeyHeader) -> impl IntoResponse {
+    ///     format!("caller: {}", key.key())
+    /// }
+    /// ```
+    ApiKeyHeader,
+    XApiKey,
+    Header,
+    "APIKeyHeader"
+);
+
+api_key_extractor!(
+    /// API key read from a query parameter (`api_key` by default).
+    ///
+    /// Same error mapping as [`ApiKeyHeader`]; documented `in: query`.
+    ApiKeyQuery,
+    ApiKeyParam,
+    Query,
+    "APIKeyQuery"
+);
+
+api_key_extractor!(
+    /// API key read from a cookie (`api_key` by default).
+    ///
+    /// Same error mapping as [`ApiKeyHeader`]; documented `in: cookie`.
+    ApiKeyCookie,
+    ApiKeyParam,
+    Cookie,
+    "APIKeyCookie"
+);
+
 // ============================================================================
 // Cookie Extractor
 // ============================================================================
@@ -5790,8 +7031,17 @@ impl IntoResponse for CookieExtractError {
     }
 }
 
+impl IntoValidationErrors for CookieExtractError {
+    fn into_validation_errors(self) -> Result<ValidationErrors, Self> {
+        Ok(ValidationErrors::single(
+            ValidationError::missing(crate::error::loc::cookie(&self.name))
+                .with_msg(format!("Cookie '{}' is required", self.name)),
+        ))
+    }
+}
+
 /// Parse cookies from the Cookie header.
-fn parse_cookies(header: &str) -> impl Iterator<Item = (&str, &str)> {
+pub(crate) fn parse_cookies(header: &str) -> impl Iterator<Item = (&str, &str)> {
     header.split(';').filter_map(|cookie| {
         let cookie = cookie.trim();
         let eq_pos = cookie.find('=')?;
@@ -6331,6 +7581,100 @@ where
     }
 }
 
+// ============================================================================
+// Composite Extractors
+// ============================================================================
+
+/// Extraction errors that can be reported as 422 validation errors.
+///
+/// `#[derive(FromRequest)]` uses this to collect the failures of every field
+/// into one response instead of stopping at the first.
+pub trait IntoValidationErrors: Sized {
+    /// Returns the validation errors, or `Err(self)` if the failure is of
+    /// another kind (unsupported media type, payload too large, ...).
+    fn into_validation_errors(self) -> Result<ValidationErrors, Self>;
+}
+
+impl IntoValidationErrors for ValidationErrors {
+    fn into_validation_errors(self) -> Result<ValidationErrors, Self> {
+        Ok(self)
+    }
+}
+
+impl<E: IntoValidationErrors> IntoValidationErrors for ValidExtractError<E> {
+    fn into_validation_errors(self) -> Result<ValidationErrors, Self> {
+        match self {
+            Self::Extract(e) => e.into_validation_errors().map_err(Self::Extract),
+            Self::Validation(errors) => Ok(*errors),
+        }
+    }
+}
+
+/// Error returned by extractors generated with `#[derive(FromRequest)]`.
+#[derive(Debug)]
+pub enum CompositeExtractError {
+    /// One or more fields failed validation; all of their errors are kept.
+    Validation(ValidationErrors),
+    /// A field failed for another reason; its response is returned as-is.
+    Rejected(crate::response::Response),
+}
+
+impl std::fmt::Display for CompositeExtractError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Validation(errors) => write!(f, "{errors}"),
+            Self::Rejected(response) => {
+                write!(f, "Extraction rejected with {}", response.status().as_u16())
+            }
+        }
+    }
+}
+
+impl IntoResponse for CompositeExtractError {
+    fn into_response(self) -> crate::response::Response {
+        match self {
+            Self::Validation(errors) => errors.into_response(),
+            Self::Rejected(response) => response,
+        }
+    }
+}
+
+/// Macro support: splits a field's extraction error into validation errors.
+///
+/// `(&&ValidationProbe::<E>::default()).split(error)` picks
+/// [`ValidationProbeMatch`] when `E` implements [`IntoValidationErrors`] and
+/// falls back to [`ValidationProbeFallback`] (always `Err`) otherwise.
+#[doc(hidden)]
+pub struct ValidationProbe<E>(std::marker::PhantomData<E>);
+
+impl<E> Default for ValidationProbe<E> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait ValidationProbeMatch<E> {
+    fn split(&self, error: E) -> Result<ValidationErrors, E>;
+}
+
+impl<E: IntoValidationErrors> ValidationProbeMatch<E> for &ValidationProbe<E> {
+    fn split(&self, error: E) -> Result<ValidationErrors, E> {
+        error.into_validation_errors()
+    }
+}
+
+#[doc(hidden)]
+pub trait ValidationProbeFallback<E> {
+    fn split(&self, error: E) -> Result<ValidationErrors, E>;
+}
+
+impl<E> ValidationProbeFallback<E> for ValidationProbe<E> {
+    fn split(&self, error: E) -> Result<ValidationErrors, E> {
+        Err(error)
+    }
+}
+
 #[cfg(test)]
 mod header_tests {
     use super::*;
@@ -6680,6 +8024,58 @@ mod oauth2_tests {
         assert_eq!(bearer.token(), "test_token");
         assert_eq!(bearer.into_token(), "test_token");
     }
+
+    #[test]
+    fn oauth2_config_builds_password_flow_scheme() {
+        let scheme = OAuth2PasswordBearerConfig::new("/auth/token")
+            .with_scope("read", "Read access")
+            .with_description("Login")
+            .security_scheme();
+
+        let json = serde_json::to_value(&scheme).unwrap();
+        assert_eq!(json["type"], "oauth2");
+        assert_eq!(json["description"], "Login");
+        assert_eq!(json["flows"]["password"]["tokenUrl"], "/auth/token");
+        assert_eq!(json["flows"]["password"]["scopes"]["read"], "Read access");
+    }
+
+    #[test]
+    #[allow(clippy::needless_borrow)]
+    fn security_probe_resolves_auth_extractors() {
+        use super::{SecurityProbeFallback as _, SecurityProbeMatch as _};
+
+        let def = (&&SecurityProbe::<OAuth2PasswordBearer>::default())
+            .security_definition()
+            .unwrap();
+        assert_eq!(def.name, "OAuth2PasswordBearer");
+        assert!(matches!(
+            def.scheme,
+            fastapi_openapi::SecurityScheme::OAuth2 { .. }
+        ));
+
+        let def = (&&SecurityProbe::<Option<BearerToken>>::default())
+            .security_definition()
+            .unwrap();
+        assert_eq!(def.name, "HTTPBearer");
+        assert_eq!(def.scheme, fastapi_openapi::SecurityScheme::bearer());
+
+        let def = (&&SecurityProbe::<ApiKey>::default())
+            .security_definition()
+            .unwrap();
+        assert_eq!(
+            def.scheme,
+            fastapi_openapi::SecurityScheme::api_key(
+                "X-API-Key",
+                fastapi_openapi::ApiKeyLocation::Header
+            )
+        );
+
+        assert!(
+            (&&SecurityProbe::<Json<String>>::default())
+                .security_definition()
+                .is_none()
+        );
+    }
 }
 
 #[cfg(test)]
@@ -7025,6 +8421,22 @@ mod query_tests {
         assert_eq!(params.limit, 0); // Default for i32
     }
 
+    #[test]
+    fn query_extract_serde_alias() {
+        #[derive(Deserialize, Debug)]
+        struct Params {
+            #[serde(alias = "q")]
+            query: String,
+        }
+
+        let ctx = test_context();
+        for query in ["query=rust", "q=rust"] {
+            let mut req = request_with_query(query);
+            let result = futures_executor::block_on(Query::<Params>::from_request(&ctx, &mut req));
+            assert_eq!(result.unwrap().query, "rust");
+        }
+    }
+
     #[test]
     fn query_extract_bool() {
         #[derive(Deserialize, Debug)]
@@ -7986,6 +9398,75 @@ mod edge_case_tests {
 // Security Tests
 // ============================================================================
 
+#[cfg(test)]
+mod api_key_tests {
+    use super::*;
+    use crate::request::Method;
+    use crate::response::IntoResponse;
+
+    fn request_with_verifier(path: &str) -> Request {
+        let mut req = Request::new(Method::Get, path);
+        req.insert_extension(AppState::new().with(ApiKeyVerifier::keys(["good"])));
+        req
+    }
+
+    fn extract<T: FromRequest>(req: &mut Request) -> Result<T, T::Error> {
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+        futures_executor::block_on(T::from_request(&ctx, req))
+    }
+
+    #[test]
+    fn reads_each_location_and_checks_the_verifier() {
+        let mut req = request_with_verifier("/");
+        req.headers_mut().insert("x-api-key", b"good".to_vec());
+        assert_eq!(extract::<ApiKeyHeader>(&mut req).unwrap().key(), "good");
+
+        let mut req = request_with_verifier("/");
+        req.set_query(Some("api_key=good".to_string()));
+        assert_eq!(extract::<ApiKeyQuery>(&mut req).unwrap().key(), "good");
+
+        let mut req = request_with_verifier("/");
+        req.headers_mut()
+            .insert("cookie", b"theme=dark; api_key=bad".to_vec());
+        let err = extract::<ApiKeyCookie>(&mut req).unwrap_err();
+        assert_eq!(err.kind, ApiKeyErrorKind::Invalid);
+        assert_eq!(err.into_response().status().as_u16(), 403);
+    }
+
+    #[test]
+    fn missing_key_is_401_and_no_verifier_rejects_every_key() {
+        let mut req = request_with_verifier("/");
+        let err = extract::<ApiKeyHeader>(&mut req).unwrap_err();
+        assert_eq!(err.kind, ApiKeyErrorKind::Missing);
+        assert_eq!(err.into_response().status().as_u16(), 401);
+
+        let mut req = Request::new(Method::Get, "/");
+        req.headers_mut().insert("x-api-key", b"anything".to_vec());
+        let err = extract::<ApiKeyHeader>(&mut req).unwrap_err();
+        assert_eq!(err.kind, ApiKeyErrorKind::NotConfigured);
+        assert_eq!(err.into_response().status().as_u16(), 500);
+
+        req.insert_extension(AppState::new());
+        let err = extract::<ApiKeyHeader>(&mut req).unwrap_err();
+        assert_eq!(err.kind, ApiKeyErrorKind::NotConfigured);
+    }
+
+    #[test]
+    fn custom_names_set_the_security_scheme() {
+        struct ServiceToken;
+        impl ApiKeyName for ServiceToken {
+            const NAME: &'static str = "X-Service-Token";
+            const SCHEME_NAME: Option<&'static str> = Some("ServiceToken");
+        }
+
+        assert_eq!(<ApiKeyHeader<ServiceToken>>::SCHEME_NAME, "ServiceToken");
+        assert_eq!(<ApiKeyQuery>::SCHEME_NAME, "APIKeyQuery");
+        let scheme = serde_json::to_value(<ApiKeyCookie>::security_scheme()).unwrap();
+        assert_eq!(scheme["in"], "cookie");
+        assert_eq!(scheme["name"], "api_key");
+    }
+}
+
 #[cfg(test)]
 mod security_tests {
     use super::*;
@@ -8439,3 +9920,116 @@ mod valid_tests {
         assert!(display.contains("validation error"));
     }
 }
+
+#[cfg(test)]
+mod pagination_tests {
+    use super::*;
+    use crate::request::Method;
+    use crate::response::ResponseBody;
+
+    fn extract<T: FromRequest>(query: &str) -> Result<T, T::Error> {
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+        let mut req = Request::new(Method::Get, "/items");
+        req.set_query(Some(query.to_string()));
+        futures_executor::block_on(T::from_request(&ctx, &mut req))
+    }
+
+    fn header<'a>(response: &'a Response, name: &str) -> Option<&'a str> {
+        response
+            .headers()
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .and_then(|(_, v)| std::str::from_utf8(v).ok())
+    }
+
+    #[test]
+    fn pagination_accepts_page_or_offset() {
+        let page = extract::<Pagination>("page=3&per_page=10").unwrap();
+        assert_eq!((page.page(), page.per_page(), page.offset()), (3, 10, 20));
+
+        let page = extract::<Pagination>("limit=10&offset=25").unwrap();
+        assert_eq!((page.page(), page.limit(), page.offset()), (3, 10, 25));
+
+        let page = extract::<Pagination>("").unwrap();
+        assert_eq!((page.page(), page.per_page(), page.offset()), (1, 20, 0));
+    }
+
+    #[test]
+    fn pagination_rejects_bad_bounds_and_mixed_styles() {
+        for query in [
+            "page=0",
+            "per_page=0",
+            "limit=101",
+            "page=2&offset=10",
+            "per_page=5&limit=5",
+        ] {
+            let err = extract::<Pagination>(query).unwrap_err();
+            assert!(
+                matches!(err, QueryExtractError::InvalidValue { .. }),
+                "{query}: {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn cursor_pagination_reads_cursor_and_limit() {
+        let page = extract::<CursorPagination>("cursor=abc&limit=5").unwrap();
+        assert_eq!((page.cursor(), page.limit()), (Some("abc"), 5));
+
+        let page = extract::<CursorPagination>("cursor=").unwrap();
+        assert_eq!((page.cursor(), page.limit()), (None, DEFAULT_PER_PAGE));
+
+        assert!(extract::<CursorPagination>("limit=0").is_err());
+    }
+
+    #[test]
+    fn page_response_has_envelope_and_links() {
+        let response = Page::new(vec![1, 2], 45, 2, 20)
+            .with_links("/items?sort=name&page=2&per_page=20")
+            .into_response();
+        let ResponseBody::Bytes(body) = response.body_ref() else {
+            panic!("expected a buffered body");
+        };
+        let json: serde_json::Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"items": [1, 2], "total": 45, "page": 2, "per_page": 20, "total_pages": 3})
+        );
+        let link = header(&response, "link").unwrap();
+        assert!(link.contains("</items?sort=name&page=3&per_page=20>; rel=\"next\""));
+        assert!(link.contains("</items?sort=name&page=1&per_page=20>; rel=\"prev\""));
+    }
+
+    #[test]
+    fn cursor_page_links_encode_cursors() {
+        let page = CursorPage::new(vec!["a"], 10, Some("b+c=".to_string())).with_links("/items");
+        assert!(page.has_more());
+        let link = page.link_header().unwrap().to_string();
+        assert!(link.contains("</items?limit=10>; rel=\"first\""));
+        assert!(link.contains("</items?cursor=b%2Bc%3D&limit=10>; rel=\"next\""));
+        assert!(!link.contains("rel=\"prev\""));
+
+        let response = page.into_response();
+        let ResponseBody::Bytes(body) = response.body_ref() else {
+            panic!("expected a buffered body");
+        };
+        let json: serde_json::Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(json["next_cursor"], "b+c=");
+        assert!(json["prev_cursor"].is_null());
+    }
+
+    #[test]
+    fn pagination_documents_query_params() {
+        let names: Vec<String> = (&&QueryParamsProbe::<Pagination>::default())
+            .query_param_docs()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, ["page", "per_page", "limit", "offset"]);
+        assert!(
+            (&&QueryParamsProbe::<Query<String>>::default())
+                .query_param_docs()
+                .is_empty()
+        );
+    }
+}
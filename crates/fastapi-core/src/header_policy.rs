@@ -0,0 +1,435 @@
+//! Request header hygiene.
+//!
+//! [`HeaderPolicyMiddleware`] applies a [`HeaderPolicy`] to each request
+//! before handlers see its headers:
+//!
+//! - headers that may appear once (`Host`, `Content-Length` by default) are
+//!   rejected when repeated;
+//! - each header value is capped in length;
+//! - leading and trailing whitespace is trimmed and inner runs of spaces and
+//!   tabs become a single space;
+//! - control characters are rejected, or stripped if configured.
+//!
+//! Violations are answered with a 400 listing one error item per problem,
+//! in the same shape as validation errors:
+//!
+//! ```json
+//! {"detail": [{"type": "header_repeated", "loc": ["header", "host"], "msg": "Header must not be repeated"}]}
+//! ```
+//!
+//! Routes can use a different policy through
+//! [`HeaderPolicyMiddleware::route`], and [`HeaderPolicyStats`] counts every
+//! outcome. The HTTP parser keeps enforcing message framing (header line
+//! limits, conflicting `Content-Length`); it records repeated header names as
+//! [`RepeatedHeaders`] for this layer to judge.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::context::RequestContext;
+use crate::error::{ValidationError, ValidationErrors, error_types, loc};
+use crate::middleware::{BoxFuture, ControlFlow, Middleware, path_matches_pattern};
+use crate::request::Request;
+use crate::response::{Response, ResponseBody, StatusCode};
+
+/// Default cap on a single header value, in bytes.
+pub const DEFAULT_MAX_HEADER_VALUE_LEN: usize = 8 * 1024;
+
+/// Lowercased names of headers that occurred more than once in a request.
+///
+/// Set as a request extension by the HTTP parser. The header map itself keeps
+/// only the last value of a repeated header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepeatedHeaders(pub Vec<String>);
+
+/// Rules applied to request headers.
+#[derive(Debug, Clone)]
+pub struct HeaderPolicy {
+    singletons: Vec<String>,
+    max_value_len: Option<usize>,
+    normalize_whitespace: bool,
+    strip_control_chars: bool,
+}
+
+impl Default for HeaderPolicy {
+    fn default() -> Self {
+        Self {
+            singletons: vec!["host".to_string(), "content-length".to_string()],
+            max_value_len: Some(DEFAULT_MAX_HEADER_VALUE_LEN),
+            normalize_whitespace: true,
+            strip_control_chars: false,
+        }
+    }
+}
+
+impl HeaderPolicy {
+    /// Create the default policy: `Host` and `Content-Length` are singletons,
+    /// values are capped at [`DEFAULT_MAX_HEADER_VALUE_LEN`] bytes, whitespace
+    /// is normalized and control characters are rejected.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject requests that repeat header `name`.
+    #[must_use]
+    pub fn singleton(mut self, name: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        if !self.singletons.contains(&name) {
+            self.singletons.push(name);
+        }
+        self
+    }
+
+    /// Reject header values longer than `len` bytes.
+    #[must_use]
+    pub fn max_value_len(mut self, len: usize) -> Self {
+        self.max_value_len = Some(len);
+        self
+    }
+
+    /// Accept header values of any length.
+    #[must_use]
+    pub fn unlimited_value_len(mut self) -> Self {
+        self.max_value_len = None;
+        self
+    }
+
+    /// Whether to trim and collapse whitespace in header values.
+    #[must_use]
+    pub fn normalize_whitespace(mut self, enable: bool) -> Self {
+        self.normalize_whitespace = enable;
+        self
+    }
+
+    /// Whether to strip control characters instead of rejecting the request.
+    #[must_use]
+    pub fn strip_control_chars(mut self, enable: bool) -> Self {
+        self.strip_control_chars = enable;
+        self
+    }
+
+    /// Check and clean the headers of `req`.
+    ///
+    /// Returns how many header values were rewritten. On error the request is
+    /// left unchanged.
+    pub fn apply(&self, req: &mut Request) -> Result<usize, ValidationErrors> {
+        let mut errors = Vec::new();
+        if let Some(RepeatedHeaders(names)) = req.get_extension::<RepeatedHeaders>() {
+            for name in names.iter().filter(|name| self.singletons.contains(name)) {
+                errors.push(
+                    ValidationError::new(error_types::HEADER_REPEATED, loc::header(name))
+                        .with_msg("Header must not be repeated"),
+                );
+            }
+        }
+
+        let mut headers: Vec<(&str, &[u8])> = req.headers().iter().collect();
+        headers.sort_unstable_by_key(|(name, _)| *name);
+        let mut rewrites = Vec::new();
+        for (name, value) in headers {
+            let mut cleaned: Option<Vec<u8>> = None;
+            if value.iter().copied().any(is_control) {
+                if !self.strip_control_chars {
+                    errors.push(
+                        ValidationError::new(error_types::HEADER_INVALID_CHARS, loc::header(name))
+                            .with_msg("Header value must not contain control characters"),
+                    );
+                    continue;
+                }
+                cleaned = Some(value.iter().copied().filter(|&b| !is_control(b)).collect());
+            }
+            if self.normalize_whitespace {
+                if let Some(normalized) = normalize_whitespace(cleaned.as_deref().unwrap_or(value))
+                {
+                    cleaned = Some(normalized);
+                }
+            }
+            let len = cleaned.as_ref().map_or(value.len(), Vec::len);
+            if let Some(max) = self.max_value_len.filter(|max| len > *max) {
+                errors.push(
+                    ValidationError::new(error_types::HEADER_TOO_LONG, loc::header(name))
+                        .with_msg(format!("Header value should have at most {max} bytes"))
+                        .with_ctx_value("max_length", serde_json::json!(max)),
+                );
+                continue;
+            }
+            if let Some(cleaned) = cleaned {
+                rewrites.push((name.to_string(), cleaned));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(ValidationErrors::from_errors(errors));
+        }
+        let rewritten = rewrites.len();
+        for (name, value) in rewrites {
+            req.headers_mut().insert(name, value);
+        }
+        Ok(rewritten)
+    }
+}
+
+/// Control characters other than horizontal tab.
+fn is_control(b: u8) -> bool {
+    (b < 0x20 && b != b'\t') || b == 0x7f
+}
+
+/// Trimmed value with inner whitespace runs collapsed, or `None` if the
+/// value is already normalized.
+fn normalize_whitespace(value: &[u8]) -> Option<Vec<u8>> {
+    let is_ws = |b: u8| b == b' ' || b == b'\t';
+    let start = value.iter().position(|&b| !is_ws(b)).unwrap_or(value.len());
+    let end = value
+        .iter()
+        .rposition(|&b| !is_ws(b))
+        .map_or(start, |i| i + 1);
+    let trimmed = &value[start..end];
+    let collapsible = trimmed
+        .windows(2)
+        .any(|pair| is_ws(pair[0]) && is_ws(pair[1]))
+        || trimmed.contains(&b'\t');
+    if trimmed.len() == value.len() && !collapsible {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(trimmed.len());
+    let mut in_ws = false;
+    for &b in trimmed {
+        if is_ws(b) {
+            if !in_ws {
+                out.push(b' ');
+            }
+            in_ws = true;
+        } else {
+            out.push(b);
+            in_ws = false;
+        }
+    }
+    Some(out)
+}
+
+/// Counters for [`HeaderPolicyMiddleware`].
+#[derive(Debug, Default)]
+pub struct HeaderPolicyStats {
+    checked: AtomicU64,
+    rejected: AtomicU64,
+    repeated: AtomicU64,
+    too_long: AtomicU64,
+    invalid_chars: AtomicU64,
+    rewritten: AtomicU64,
+}
+
+impl HeaderPolicyStats {
+    /// Requests checked.
+    #[must_use]
+    pub fn checked(&self) -> u64 {
+        self.checked.load(Ordering::Relaxed)
+    }
+
+    /// Requests rejected with 400.
+    #[must_use]
+    pub fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    /// Repeated singleton headers found.
+    #[must_use]
+    pub fn repeated(&self) -> u64 {
+        self.repeated.load(Ordering::Relaxed)
+    }
+
+    /// Header values over the length cap.
+    #[must_use]
+    pub fn too_long(&self) -> u64 {
+        self.too_long.load(Ordering::Relaxed)
+    }
+
+    /// Header values rejected for control characters.
+    #[must_use]
+    pub fn invalid_chars(&self) -> u64 {
+        self.invalid_chars.load(Ordering::Relaxed)
+    }
+
+    /// Header values rewritten by normalization or stripping.
+    #[must_use]
+    pub fn rewritten(&self) -> u64 {
+        self.rewritten.load(Ordering::Relaxed)
+    }
+
+    fn record(&self, outcome: &Result<usize, ValidationErrors>) {
+        self.checked.fetch_add(1, Ordering::Relaxed);
+        match outcome {
+            Ok(rewritten) => {
+                self.rewritten
+                    .fetch_add(*rewritten as u64, Ordering::Relaxed);
+            }
+            Err(errors) => {
+                self.rejected.fetch_add(1, Ordering::Relaxed);
+                for error in &errors.errors {
+                    let counter = match error.error_type {
+                        error_types::HEADER_REPEATED => &self.repeated,
+                        error_types::HEADER_TOO_LONG => &self.too_long,
+                        _ => &self.invalid_chars,
+                    };
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+}
+
+/// Middleware enforcing a [`HeaderPolicy`], with per-route overrides.
+///
+/// ```ignore
+/// let headers = HeaderPolicyMiddleware::new(HeaderPolicy::new())
+///     .route("/uploads/*", HeaderPolicy::new().max_value_len(32 * 1024));
+/// let stats = headers.stats();
+///
+/// let app = App::builder().middleware(headers).build();
+/// ```
+pub struct HeaderPolicyMiddleware {
+    policy: HeaderPolicy,
+    routes: Vec<(String, HeaderPolicy)>,
+    stats: Arc<HeaderPolicyStats>,
+}
+
+impl HeaderPolicyMiddleware {
+    /// Apply `policy` to every request.
+    #[must_use]
+    pub fn new(policy: HeaderPolicy) -> Self {
+        Self {
+            policy,
+            routes: Vec::new(),
+            stats: Arc::new(HeaderPolicyStats::default()),
+        }
+    }
+
+    /// Apply `policy` instead to paths matching `pattern` (`*` wildcards).
+    ///
+    /// The first matching pattern wins.
+    #[must_use]
+    pub fn route(mut self, pattern: impl Into<String>, policy: HeaderPolicy) -> Self {
+        self.routes.push((pattern.into(), policy));
+        self
+    }
+
+    /// Shared counters, updated as requests are checked.
+    #[must_use]
+    pub fn stats(&self) -> Arc<HeaderPolicyStats> {
+        Arc::clone(&self.stats)
+    }
+
+    fn policy_for(&self, path: &str) -> &HeaderPolicy {
+        self.routes
+            .iter()
+            .find(|(pattern, _)| path_matches_pattern(path, pattern))
+            .map_or(&self.policy, |(_, policy)| policy)
+    }
+}
+
+impl Middleware for HeaderPolicyMiddleware {
+    fn before<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        let outcome = self.policy_for(req.path()).apply(req);
+        self.stats.record(&outcome);
+        Box::pin(async move {
+            match outcome {
+                Ok(_) => ControlFlow::Continue,
+                Err(errors) => ControlFlow::Break(
+                    Response::with_status(StatusCode::BAD_REQUEST)
+                        .header("content-type", b"application/json".to_vec())
+                        .body(ResponseBody::Bytes(errors.to_json_bytes())),
+                ),
+            }
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "HeaderPolicy"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Method;
+
+    fn request(headers: &[(&str, &[u8])]) -> Request {
+        request_to("/", headers)
+    }
+
+    fn request_to(path: &str, headers: &[(&str, &[u8])]) -> Request {
+        let mut req = Request::new(Method::Get, path);
+        for (name, value) in headers {
+            req.headers_mut().insert(*name, value.to_vec());
+        }
+        req
+    }
+
+    #[test]
+    fn normalizes_and_strips_values() {
+        let mut req = request(&[
+            ("x-spaced", b"  a \t  b  "),
+            ("x-control", b"ab\x01c"),
+            ("accept", b"text/html"),
+        ]);
+        let policy = HeaderPolicy::new().strip_control_chars(true);
+        assert_eq!(policy.apply(&mut req).unwrap(), 2);
+        assert_eq!(req.headers().get("x-spaced"), Some(&b"a b"[..]));
+        assert_eq!(req.headers().get("x-control"), Some(&b"abc"[..]));
+        assert_eq!(req.headers().get("accept"), Some(&b"text/html"[..]));
+    }
+
+    #[test]
+    fn rejections_list_every_problem() {
+        let mut req = request(&[
+            ("host", b"a.io"),
+            ("x-control", b"a\x7f"),
+            ("x-long", b"0123456789"),
+        ]);
+        req.insert_extension(RepeatedHeaders(vec![
+            "host".to_string(),
+            "accept".to_string(),
+        ]));
+        let errors = HeaderPolicy::new()
+            .max_value_len(8)
+            .apply(&mut req)
+            .unwrap_err();
+        let types: Vec<&str> = errors.errors.iter().map(|e| e.error_type).collect();
+        assert_eq!(
+            types,
+            [
+                error_types::HEADER_REPEATED,
+                error_types::HEADER_INVALID_CHARS,
+                error_types::HEADER_TOO_LONG,
+            ]
+        );
+    }
+
+    #[test]
+    fn middleware_answers_400_and_counts() {
+        let middleware = HeaderPolicyMiddleware::new(HeaderPolicy::new().max_value_len(4))
+            .route("/uploads/*", HeaderPolicy::new().unlimited_value_len());
+        let stats = middleware.stats();
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+
+        let mut req = request(&[("x-token", b"0123456789")]);
+        let flow = futures_executor::block_on(middleware.before(&ctx, &mut req));
+        let ControlFlow::Break(response) = flow else {
+            panic!("expected a rejection");
+        };
+        assert_eq!(response.status().as_u16(), 400);
+
+        let mut req = request_to("/uploads/file", &[("x-token", b"0123456789")]);
+        let flow = futures_executor::block_on(middleware.before(&ctx, &mut req));
+        assert!(matches!(flow, ControlFlow::Continue));
+
+        assert_eq!(stats.checked(), 2);
+        assert_eq!(stats.rejected(), 1);
+        assert_eq!(stats.too_long(), 1);
+    }
+}
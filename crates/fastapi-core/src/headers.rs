@@ -0,0 +1,845 @@
+//! Typed HTTP headers.
+//!
+//! Header name constants plus typed values for the headers handlers touch
+//! most often. Each value type implements [`TypedHeader`], so the same type
+//! parses request headers and renders response headers:
+//!
+//! ```ignore
+//! use fastapi_core::headers::{CacheControl, ContentDisposition, ContentType};
+//!
+//! let ct: Option<ContentType> = req.headers().typed().transpose()?;
+//! if ct.is_some_and(|ct| ct.is_json()) { /* ... */ }
+//!
+//! Response::ok()
+//!     .typed_header(&ContentType::octet_stream())
+//!     .typed_header(&ContentDisposition::attachment("report.csv"))
+//!     .typed_header(&CacheControl::new().private().max_age(60));
+//! ```
+//!
+//! Typed values also work with the [`NamedHeader`](crate::NamedHeader)
+//! extractor, e.g. `NamedHeader<headers::ContentType, ContentType>`.
+
+use std::fmt;
+
+use crate::extract::FromHeaderValue;
+
+/// `accept`
+pub const ACCEPT: &str = "accept";
+/// `authorization`
+pub const AUTHORIZATION: &str = "authorization";
+/// `cache-control`
+pub const CACHE_CONTROL: &str = "cache-control";
+/// `content-disposition`
+pub const CONTENT_DISPOSITION: &str = "content-disposition";
+/// `content-length`
+pub const CONTENT_LENGTH: &str = "content-length";
+/// `content-type`
+pub const CONTENT_TYPE: &str = "content-type";
+/// `etag`
+pub const ETAG: &str = "etag";
+/// `location`
+pub const LOCATION: &str = "location";
+/// `user-agent`
+pub const USER_AGENT: &str = "user-agent";
+/// `www-authenticate`
+pub const WWW_AUTHENTICATE: &str = "www-authenticate";
+
+/// A header with a fixed name and a typed value.
+pub trait TypedHeader: Sized {
+    /// Lowercase header name.
+    const NAME: &'static str;
+
+    /// Parse a header value.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem if `value` is malformed.
+    fn decode(value: &str) -> Result<Self, String>;
+
+    /// Render the header value.
+    fn encode(&self) -> String;
+}
+
+macro_rules! from_header_value {
+    ($ty:ident) => {
+        impl FromHeaderValue for $ty {
+            fn from_header_value(value: &str) -> Result<Self, String> {
+                <$ty as TypedHeader>::decode(value)
+            }
+
+            fn type_name() -> &'static str {
+                stringify!($ty)
+            }
+        }
+    };
+}
+
+// ============================================================================
+// Content-Type
+// ============================================================================
+
+/// `Content-Type` value: a media type plus parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentType {
+    essence: String,
+    params: Vec<(String, String)>,
+}
+
+impl ContentType {
+    /// Create a content type from a `type/subtype` essence.
+    #[must_use]
+    pub fn new(essence: impl Into<String>) -> Self {
+        Self {
+            essence: essence.into().trim().to_ascii_lowercase(),
+            params: Vec::new(),
+        }
+    }
+
+    /// `application/json`
+    #[must_use]
+    pub fn json() -> Self {
+        Self::new("application/json")
+    }
+
+    /// `text/plain; charset=utf-8`
+    #[must_use]
+    pub fn text() -> Self {
+        Self::new("text/plain").param("charset", "utf-8")
+    }
+
+    /// `text/html; charset=utf-8`
+    #[must_use]
+    pub fn html() -> Self {
+        Self::new("text/html").param("charset", "utf-8")
+    }
+
+    /// `application/x-www-form-urlencoded`
+    #[must_use]
+    pub fn form() -> Self {
+        Self::new("application/x-www-form-urlencoded")
+    }
+
+    /// `application/octet-stream`
+    #[must_use]
+    pub fn octet_stream() -> Self {
+        Self::new("application/octet-stream")
+    }
+
+    /// Add a parameter such as `charset` or `boundary`.
+    #[must_use]
+    pub fn param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params
+            .push((name.into().to_ascii_lowercase(), value.into()));
+        self
+    }
+
+    /// The `type/subtype` part, lowercased.
+    #[must_use]
+    pub fn essence(&self) -> &str {
+        &self.essence
+    }
+
+    /// Look up a parameter by name (case-insensitive).
+    #[must_use]
+    pub fn get_param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The `charset` parameter, if present.
+    #[must_use]
+    pub fn charset(&self) -> Option<&str> {
+        self.get_param("charset")
+    }
+
+    /// Returns true for `application/json` and `+json` suffixed types.
+    #[must_use]
+    pub fn is_json(&self) -> bool {
+        self.essence == "application/json" || self.essence.ends_with("+json")
+    }
+}
+
+impl TypedHeader for ContentType {
+    const NAME: &'static str = CONTENT_TYPE;
+
+    fn decode(value: &str) -> Result<Self, String> {
+        let mut parts = value.split(';');
+        let essence = parts.next().unwrap_or("").trim();
+        match essence.split_once('/') {
+            Some((ty, sub)) if !ty.is_empty() && !sub.is_empty() => {}
+            _ => return Err(format!("invalid media type: {value}")),
+        }
+        let mut content_type = Self::new(essence);
+        for part in parts {
+            let Some((name, val)) = part.split_once('=') else {
+                continue;
+            };
+            content_type = content_type.param(name.trim(), unquote(val.trim()));
+        }
+        Ok(content_type)
+    }
+
+    fn encode(&self) -> String {
+        let mut out = self.essence.clone();
+        for (name, value) in &self.params {
+            out.push_str("; ");
+            out.push_str(name);
+            out.push('=');
+            out.push_str(&quote_if_needed(value));
+        }
+        out
+    }
+}
+
+from_header_value!(ContentType);
+
+// ============================================================================
+// Authorization
+// ============================================================================
+
+/// `Authorization` value.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Authorization {
+    /// `Bearer <token>`
+    Bearer(String),
+    /// `Basic <base64(username:password)>`
+    Basic {
+        /// User name.
+        username: String,
+        /// Password.
+        password: String,
+    },
+    /// Any other scheme, kept verbatim.
+    Other {
+        /// Scheme name as sent.
+        scheme: String,
+        /// Credentials after the scheme.
+        credentials: String,
+    },
+}
+
+impl Authorization {
+    /// Bearer credentials.
+    #[must_use]
+    pub fn bearer(token: impl Into<String>) -> Self {
+        Self::Bearer(token.into())
+    }
+
+    /// Basic credentials.
+    #[must_use]
+    pub fn basic(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self::Basic {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+
+    /// The scheme name (`Bearer`, `Basic`, or the custom scheme).
+    #[must_use]
+    pub fn scheme(&self) -> &str {
+        match self {
+            Self::Bearer(_) => "Bearer",
+            Self::Basic { .. } => "Basic",
+            Self::Other { scheme, .. } => scheme,
+        }
+    }
+}
+
+// Credentials must not end up in logs.
+impl fmt::Debug for Authorization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"[REDACTED]")
+                .finish(),
+            _ => write!(f, "{}([REDACTED])", self.scheme()),
+        }
+    }
+}
+
+impl TypedHeader for Authorization {
+    const NAME: &'static str = AUTHORIZATION;
+
+    fn decode(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let (scheme, credentials) = value
+            .split_once(' ')
+            .ok_or_else(|| "missing authorization scheme".to_string())?;
+        let credentials = credentials.trim();
+        if credentials.is_empty() {
+            return Err("empty credentials".to_string());
+        }
+        if scheme.eq_ignore_ascii_case("bearer") {
+            Ok(Self::Bearer(credentials.to_string()))
+        } else if scheme.eq_ignore_ascii_case("basic") {
+            let decoded = crate::extract::base64_decode(credentials)
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .ok_or_else(|| "invalid base64 in basic credentials".to_string())?;
+            let (username, password) = decoded
+                .split_once(':')
+                .ok_or_else(|| "basic credentials missing ':'".to_string())?;
+            Ok(Self::basic(username, password))
+        } else {
+            Ok(Self::Other {
+                scheme: scheme.to_string(),
+                credentials: credentials.to_string(),
+            })
+        }
+    }
+
+    fn encode(&self) -> String {
+        match self {
+            Self::Bearer(token) => format!("Bearer {token}"),
+            Self::Basic { username, password } => {
+                format!(
+                    "Basic {}",
+                    base64_encode(format!("{username}:{password}").as_bytes())
+                )
+            }
+            Self::Other {
+                scheme,
+                credentials,
+            } => format!("{scheme} {credentials}"),
+        }
+    }
+}
+
+from_header_value!(Authorization);
+
+// ============================================================================
+// Cache-Control
+// ============================================================================
+
+/// `Cache-Control` value.
+///
+/// Directives render in a fixed order regardless of how they were set.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheControl {
+    /// `no-store`
+    pub no_store: bool,
+    /// `no-cache`
+    pub no_cache: bool,
+    /// `public`
+    pub public: bool,
+    /// `private`
+    pub private: bool,
+    /// `must-revalidate`
+    pub must_revalidate: bool,
+    /// `immutable`
+    pub immutable: bool,
+    /// `max-age` in seconds.
+    pub max_age: Option<u64>,
+    /// `s-maxage` in seconds.
+    pub s_maxage: Option<u64>,
+}
+
+impl CacheControl {
+    /// An empty directive set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `no-store`.
+    #[must_use]
+    pub fn no_store(mut self) -> Self {
+        self.no_store = true;
+        self
+    }
+
+    /// Add `no-cache`.
+    #[must_use]
+    pub fn no_cache(mut self) -> Self {
+        self.no_cache = true;
+        self
+    }
+
+    /// Add `public`.
+    #[must_use]
+    pub fn public(mut self) -> Self {
+        self.public = true;
+        self
+    }
+
+    /// Add `private`.
+    #[must_use]
+    pub fn private(mut self) -> Self {
+        self.private = true;
+        self
+    }
+
+    /// Add `must-revalidate`.
+    #[must_use]
+    pub fn must_revalidate(mut self) -> Self {
+        self.must_revalidate = true;
+        self
+    }
+
+    /// Add `immutable`.
+    #[must_use]
+    pub fn immutable(mut self) -> Self {
+        self.immutable = true;
+        self
+    }
+
+    /// Set `max-age`.
+    #[must_use]
+    pub fn max_age(mut self, seconds: u64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Set `s-maxage`.
+    #[must_use]
+    pub fn s_maxage(mut self, seconds: u64) -> Self {
+        self.s_maxage = Some(seconds);
+        self
+    }
+}
+
+impl TypedHeader for CacheControl {
+    const NAME: &'static str = CACHE_CONTROL;
+
+    fn decode(value: &str) -> Result<Self, String> {
+        let mut cc = Self::new();
+        for directive in value.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let (name, arg) = match directive.split_once('=') {
+                Some((n, a)) => (n.trim(), Some(unquote(a.trim()))),
+                None => (directive, None),
+            };
+            let seconds = || {
+                arg.as_deref()
+                    .and_then(|a| a.parse::<u64>().ok())
+                    .ok_or_else(|| format!("invalid {name} value"))
+            };
+            match name.to_ascii_lowercase().as_str() {
+                "no-store" => cc.no_store = true,
+                "no-cache" => cc.no_cache = true,
+                "public" => cc.public = true,
+                "private" => cc.private = true,
+                "must-revalidate" => cc.must_revalidate = true,
+                "immutable" => cc.immutable = true,
+                "max-age" => cc.max_age = Some(seconds()?),
+                "s-maxage" => cc.s_maxage = Some(seconds()?),
+                // Unknown directives are ignored, as caches do.
+                _ => {}
+            }
+        }
+        Ok(cc)
+    }
+
+    fn encode(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        let flags = [
+            (self.no_store, "no-store"),
+            (self.no_cache, "no-cache"),
+            (self.public, "public"),
+            (self.private, "private"),
+            (self.must_revalidate, "must-revalidate"),
+            (self.immutable, "immutable"),
+        ];
+        parts.extend(
+            flags
+                .iter()
+                .filter(|(set, _)| *set)
+                .map(|(_, name)| (*name).to_string()),
+        );
+        if let Some(secs) = self.max_age {
+            parts.push(format!("max-age={secs}"));
+        }
+        if let Some(secs) = self.s_maxage {
+            parts.push(format!("s-maxage={secs}"));
+        }
+        parts.join(", ")
+    }
+}
+
+from_header_value!(CacheControl);
+
+// ============================================================================
+// Content-Disposition
+// ============================================================================
+
+/// Disposition type of a `Content-Disposition` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispositionType {
+    /// Display in the browser.
+    Inline,
+    /// Download as a file.
+    Attachment,
+    /// A `multipart/form-data` part.
+    FormData,
+}
+
+impl DispositionType {
+    /// Header token for this disposition.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Inline => "inline",
+            Self::Attachment => "attachment",
+            Self::FormData => "form-data",
+        }
+    }
+}
+
+/// `Content-Disposition` value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentDisposition {
+    /// Disposition type.
+    pub disposition: DispositionType,
+    /// Form field name (`form-data` only).
+    pub name: Option<String>,
+    /// Suggested file name.
+    pub filename: Option<String>,
+}
+
+impl ContentDisposition {
+    /// `inline`
+    #[must_use]
+    pub fn inline() -> Self {
+        Self {
+            disposition: DispositionType::Inline,
+            name: None,
+            filename: None,
+        }
+    }
+
+    /// `attachment; filename="..."`
+    #[must_use]
+    pub fn attachment(filename: impl Into<String>) -> Self {
+        Self {
+            disposition: DispositionType::Attachment,
+            name: None,
+            filename: Some(filename.into()),
+        }
+    }
+
+    /// `form-data; name="..."`
+    #[must_use]
+    pub fn form_data(name: impl Into<String>) -> Self {
+        Self {
+            disposition: DispositionType::FormData,
+            name: Some(name.into()),
+            filename: None,
+        }
+    }
+
+    /// Set the suggested file name.
+    #[must_use]
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+}
+
+impl TypedHeader for ContentDisposition {
+    const NAME: &'static str = CONTENT_DISPOSITION;
+
+    fn decode(value: &str) -> Result<Self, String> {
+        let mut parts = value.split(';');
+        let disposition = match parts
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "inline" => DispositionType::Inline,
+            "attachment" => DispositionType::Attachment,
+            "form-data" => DispositionType::FormData,
+            other => return Err(format!("unknown disposition type: {other}")),
+        };
+        let mut cd = Self {
+            disposition,
+            name: None,
+            filename: None,
+        };
+        let mut extended_filename = None;
+        for part in parts {
+            let Some((key, val)) = part.split_once('=') else {
+                continue;
+            };
+            match key.trim().to_ascii_lowercase().as_str() {
+                "name" => cd.name = Some(unquote(val.trim())),
+                "filename" => cd.filename = Some(unquote(val.trim())),
+                "filename*" => {
+                    extended_filename = val
+                        .trim()
+                        .split_once("''")
+                        .and_then(|(_, encoded)| crate::routing::url_decode(encoded));
+                }
+                _ => {}
+            }
+        }
+        // RFC 6266: filename* takes precedence over filename.
+        if extended_filename.is_some() {
+            cd.filename = extended_filename;
+        }
+        Ok(cd)
+    }
+
+    fn encode(&self) -> String {
+        let mut out = self.disposition.as_str().to_string();
+        if let Some(name) = &self.name {
+            out.push_str(&format!("; name=\"{}\"", escape_quoted(name)));
+        }
+        if let Some(filename) = &self.filename {
+            if filename.is_ascii() {
+                out.push_str(&format!("; filename=\"{}\"", escape_quoted(filename)));
+            } else {
+                // ASCII fallback for old clients plus the RFC 5987 form.
+                let fallback: String = filename
+                    .chars()
+                    .map(|c| if c.is_ascii() { c } else { '_' })
+                    .collect();
+                out.push_str(&format!(
+                    "; filename=\"{}\"; filename*=UTF-8''{}",
+                    escape_quoted(&fallback),
+                    crate::routing::url_encode(filename)
+                ));
+            }
+        }
+        out
+    }
+}
+
+from_header_value!(ContentDisposition);
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn unquote(value: &str) -> String {
+    match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(inner) => inner.replace("\\\"", "\"").replace("\\\\", "\\"),
+        None => value.to_string(),
+    }
+}
+
+fn escape_quoted(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(['\r', '\n'], "")
+}
+
+fn quote_if_needed(value: &str) -> String {
+    let is_token = !value.is_empty()
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b));
+    if is_token {
+        value.to_string()
+    } else {
+        format!("\"{}\"", escape_quoted(value))
+    }
+}
+
+/// Parse an IMF-fixdate such as `Wed, 21 Oct 2015 07:28:00 GMT`.
+pub(crate) fn parse_http_date(input: &str) -> Option<std::time::SystemTime> {
+    // Intentionally minimal: other date formats yield `None`.
+    let s = input.trim().trim_matches('"').trim();
+    let (_dow, rest) = s.split_once(',')?;
+    let rest = rest.trim();
+    let mut it = rest.split_whitespace();
+    let day = it.next()?.parse::<u32>().ok()?;
+    let month = match it.next()? {
+        "Jan" => 1,
+        "Feb" => 2,
+        "Mar" => 3,
+        "Apr" => 4,
+        "May" => 5,
+        "Jun" => 6,
+        "Jul" => 7,
+        "Aug" => 8,
+        "Sep" => 9,
+        "Oct" => 10,
+        "Nov" => 11,
+        "Dec" => 12,
+        _ => return None,
+    };
+    let year = it.next()?.parse::<i32>().ok()?;
+    let time = it.next()?;
+    let tz = it.next()?;
+    if tz != "GMT" {
+        return None;
+    }
+    let (hh, mm, ss) = {
+        let mut t = time.split(':');
+        let hh = t.next()?.parse::<u32>().ok()?;
+        let mm = t.next()?.parse::<u32>().ok()?;
+        let ss = t.next()?.parse::<u32>().ok()?;
+        (hh, mm, ss)
+    };
+
+    // Convert to unix timestamp using a small civil->days function.
+    fn days_from_civil(y: i32, m: u32, d: u32) -> i64 {
+        // Howard Hinnant's algorithm.
+        let y = i64::from(y) - i64::from(m <= 2);
+        let era = (if y >= 0 { y } else { y - 399 }) / 400;
+        let yoe = y - era * 400;
+        let m = i64::from(m);
+        let doy = (153 * (m + if m > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(d) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    let days = days_from_civil(year, month, day);
+    let secs = days
+        .checked_mul(86_400)?
+        .checked_add(i64::from(hh) * 3600 + i64::from(mm) * 60 + i64::from(ss))?;
+    if secs < 0 {
+        return None;
+    }
+    let secs_u64 = u64::try_from(secs).ok()?;
+    Some(std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs_u64))
+}
+
+/// Base64 encode (standard alphabet, padded).
+fn base64_encode(data: &[u8]) -> String {
+    const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b1 = chunk.get(1).copied().map_or(0, u32::from);
+        let b2 = chunk.get(2).copied().map_or(0, u32::from);
+        let n = (u32::from(chunk[0]) << 16) | (b1 << 8) | b2;
+        result.push(CHARS[((n >> 18) & 63) as usize] as char);
+        result.push(CHARS[((n >> 12) & 63) as usize] as char);
+        result.push(if chunk.len() > 1 {
+            CHARS[((n >> 6) & 63) as usize] as char
+        } else {
+            '='
+        });
+        result.push(if chunk.len() > 2 {
+            CHARS[(n & 63) as usize] as char
+        } else {
+            '='
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Method, Request, Response};
+
+    #[test]
+    fn content_type_round_trip() {
+        let ct = ContentType::decode("Application/JSON; charset=\"UTF-8\"").unwrap();
+        assert_eq!(ct.essence(), "application/json");
+        assert_eq!(ct.charset(), Some("UTF-8"));
+        assert!(ct.is_json());
+        assert_eq!(ct.encode(), "application/json; charset=UTF-8");
+        assert!(ContentType::new("application/problem+json").is_json());
+        assert!(ContentType::decode("json").is_err());
+        assert_eq!(
+            ContentType::new("multipart/form-data")
+                .param("boundary", "a b")
+                .encode(),
+            "multipart/form-data; boundary=\"a b\""
+        );
+    }
+
+    #[test]
+    fn authorization_schemes() {
+        assert_eq!(
+            Authorization::decode("bearer abc.def").unwrap(),
+            Authorization::bearer("abc.def")
+        );
+        let basic = Authorization::basic("alice", "s3cret:x");
+        assert_eq!(basic.encode(), "Basic YWxpY2U6czNjcmV0Ong=");
+        assert_eq!(Authorization::decode(&basic.encode()).unwrap(), basic);
+        assert_eq!(
+            Authorization::decode("Digest username=x").unwrap().scheme(),
+            "Digest"
+        );
+        assert!(Authorization::decode("Bearer").is_err());
+        assert!(Authorization::decode("Basic !!!").is_err());
+    }
+
+    #[test]
+    fn authorization_debug_redacts_credentials() {
+        let debug = format!("{:?}", Authorization::basic("alice", "s3cret"));
+        assert!(debug.contains("alice"));
+        assert!(!debug.contains("s3cret"));
+        assert!(!format!("{:?}", Authorization::bearer("tok")).contains("tok"));
+    }
+
+    #[test]
+    fn cache_control_round_trip() {
+        let cc = CacheControl::new().max_age(60).private().must_revalidate();
+        assert_eq!(cc.encode(), "private, must-revalidate, max-age=60");
+        assert_eq!(CacheControl::decode(&cc.encode()).unwrap(), cc);
+
+        let parsed = CacheControl::decode("public, s-maxage=\"300\", stale-if-error=10").unwrap();
+        assert!(parsed.public);
+        assert_eq!(parsed.s_maxage, Some(300));
+        assert!(CacheControl::decode("max-age=soon").is_err());
+    }
+
+    #[test]
+    fn content_disposition_filenames() {
+        assert_eq!(
+            ContentDisposition::attachment("report \"q1\".csv").encode(),
+            "attachment; filename=\"report \\\"q1\\\".csv\""
+        );
+        let unicode = ContentDisposition::attachment("résumé.pdf");
+        assert_eq!(
+            unicode.encode(),
+            "attachment; filename=\"r_sum_.pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf"
+        );
+        assert_eq!(
+            ContentDisposition::decode(&unicode.encode()).unwrap(),
+            unicode
+        );
+
+        let part =
+            ContentDisposition::decode("form-data; name=\"file\"; filename=\"a.txt\"").unwrap();
+        assert_eq!(part.disposition, DispositionType::FormData);
+        assert_eq!(part.name.as_deref(), Some("file"));
+        assert_eq!(part.filename.as_deref(), Some("a.txt"));
+        assert!(ContentDisposition::decode("download").is_err());
+    }
+
+    #[test]
+    fn request_and_response_integration() {
+        let mut req = Request::new(Method::Post, "/upload");
+        req.headers_mut()
+            .insert("Content-Type", b"text/csv; charset=utf-8".to_vec());
+        let ct: ContentType = req.headers().typed().unwrap().unwrap();
+        assert_eq!(ct.essence(), "text/csv");
+        assert!(req.headers().typed::<Authorization>().is_none());
+
+        let resp = Response::ok()
+            .typed_header(&ContentType::text())
+            .typed_header(&CacheControl::new().no_store());
+        let headers = resp.headers();
+        assert!(
+            headers
+                .iter()
+                .any(|(n, v)| n == CONTENT_TYPE && v.as_slice() == b"text/plain; charset=utf-8")
+        );
+        assert!(
+            headers
+                .iter()
+                .any(|(n, v)| n == CACHE_CONTROL && v.as_slice() == b"no-store")
+        );
+    }
+
+    #[test]
+    fn from_header_value_uses_decode() {
+        let cc = <CacheControl as FromHeaderValue>::from_header_value("no-cache").unwrap();
+        assert!(cc.no_cache);
+        assert_eq!(
+            <CacheControl as FromHeaderValue>::type_name(),
+            "CacheControl"
+        );
+    }
+}
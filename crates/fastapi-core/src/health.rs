@@ -3,12 +3,18 @@
 //! Provides standard health check patterns:
 //! - Basic health: returns `{"status":"healthy"}` on `GET /health`
 //! - Detailed health with named checks, critical flags, and per-check latency
-//! - Kubernetes-style liveness (`/healthz`) and readiness (`/ready`) probes
+//! - Kubernetes-style liveness (`/healthz`) and readiness (`/readyz`) probes
+//!
+//! The checks of a report run concurrently, each bounded by a timeout
+//! ([`DEFAULT_CHECK_TIMEOUT`] unless configured), so one hung dependency
+//! cannot stall the probe. Once the registry is draining, readiness
+//! reports `draining` with 503 while liveness keeps passing; load balancers
+//! stop routing to the instance before it exits.
 //!
 //! # Example
 //!
 //! ```ignore
-//! use fastapi_core::health::{HealthCheckRegistry, HealthStatus};
+//! use fastapi_core::health::{DiskCheck, HealthCheckRegistry, HealthStatus};
 //!
 //! let mut registry = HealthCheckRegistry::new();
 //! registry.add("database", true, || async {
@@ -19,19 +25,32 @@
 //!     // Check cache connection (non-critical)
 //!     Ok(())
 //! });
+//! registry.register("uploads", true, DiskCheck::new("/var/lib/app/uploads"));
 //!
 //! let result = futures_executor::block_on(registry.check_all());
 //! assert_eq!(result.status, HealthStatus::Healthy);
+//!
+//! // Mounts GET /healthz and GET /readyz.
+//! let app = App::builder().health(registry).build();
 //! ```
 
+use std::fmt::Write as _;
 use std::future::Future;
-use std::pin::Pin;
+use std::path::{Path, PathBuf};
+use std::pin::{Pin, pin};
 use std::sync::Arc;
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::task::Poll;
+use std::time::{Duration, Instant};
 
 use crate::context::RequestContext;
+use crate::middleware::BoxFuture;
 use crate::request::Request;
 use crate::response::{Response, ResponseBody, StatusCode};
+use crate::shutdown::ShutdownReceiver;
+
+/// Timeout for checks that do not set their own.
+pub const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Overall health status of the application.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -42,6 +61,8 @@ pub enum HealthStatus {
     Degraded,
     /// At least one critical check failed.
     Unhealthy,
+    /// The application is shutting down and should receive no new traffic.
+    Draining,
 }
 
 impl HealthStatus {
@@ -52,6 +73,7 @@ impl HealthStatus {
             Self::Healthy => "healthy",
             Self::Degraded => "degraded",
             Self::Unhealthy => "unhealthy",
+            Self::Draining => "draining",
         }
     }
 
@@ -60,7 +82,7 @@ impl HealthStatus {
     pub fn status_code(self) -> StatusCode {
         match self {
             Self::Healthy | Self::Degraded => StatusCode::OK,
-            Self::Unhealthy => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Unhealthy | Self::Draining => StatusCode::SERVICE_UNAVAILABLE,
         }
     }
 }
@@ -112,28 +134,17 @@ impl HealthReport {
             if i > 0 {
                 json.push(',');
             }
-            json.push_str("{\"name\":\"");
-            json.push_str(&check.name);
-            json.push_str("\",\"passed\":");
+            json.push_str("{\"name\":");
+            push_json_string(&mut json, &check.name);
+            json.push_str(",\"passed\":");
             json.push_str(if check.passed { "true" } else { "false" });
             json.push_str(",\"critical\":");
             json.push_str(if check.critical { "true" } else { "false" });
             json.push_str(",\"latency_ms\":");
             json.push_str(&check.latency_ms.to_string());
             if let Some(ref err) = check.error {
-                json.push_str(",\"error\":\"");
-                // Escape JSON special characters
-                for ch in err.chars() {
-                    match ch {
-                        '"' => json.push_str("\\\""),
-                        '\\' => json.push_str("\\\\"),
-                        '\n' => json.push_str("\\n"),
-                        '\r' => json.push_str("\\r"),
-                        '\t' => json.push_str("\\t"),
-                        c => json.push(c),
-                    }
-                }
-                json.push('"');
+                json.push_str(",\"error\":");
+                push_json_string(&mut json, err);
             }
             json.push('}');
         }
@@ -143,15 +154,123 @@ impl HealthReport {
     }
 }
 
-/// A boxed future for health check functions.
-type CheckFn =
-    Arc<dyn Fn() -> Pin<Box<dyn Future<Output = Result<(), String>> + Send>> + Send + Sync>;
+fn push_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    // Escape JSON special characters
+    for ch in value.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", u32::from(c));
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// An asynchronous probe of one dependency.
+///
+/// Closures are registered with [`HealthCheckRegistry::add`]; implement
+/// this trait for clients that know how to ping their backend:
+///
+/// ```ignore
+/// struct DbPing(Pool);
+///
+/// impl HealthCheck for DbPing {
+///     fn check(&self) -> BoxFuture<'_, Result<(), String>> {
+///         Box::pin(async move { self.0.execute("SELECT 1").await.map(drop).map_err(|e| e.to_string()) })
+///     }
+///
+///     fn timeout(&self) -> Option<Duration> {
+///         Some(Duration::from_secs(1))
+///     }
+/// }
+///
+/// registry.register("database", true, DbPing(pool.clone()));
+/// ```
+pub trait HealthCheck: Send + Sync + 'static {
+    /// Run the probe, returning a description of the failure if it fails.
+    fn check(&self) -> BoxFuture<'_, Result<(), String>>;
+
+    /// Timeout for this check, overriding the registry's default.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Adapts an async closure to [`HealthCheck`].
+struct FnCheck<F>(F);
+
+impl<F, Fut> HealthCheck for FnCheck<F>
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), String>> + Send + 'static,
+{
+    fn check(&self) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin((self.0)())
+    }
+}
+
+/// Checks that a directory exists and is writable.
+///
+/// The probe creates and removes a small file in the directory, which
+/// catches missing mounts, read-only remounts and full disks.
+#[derive(Debug, Clone)]
+pub struct DiskCheck {
+    dir: PathBuf,
+    timeout: Option<Duration>,
+}
+
+impl DiskCheck {
+    /// Probe `dir`.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            timeout: None,
+        }
+    }
+
+    /// Override the registry's timeout for this check.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl HealthCheck for DiskCheck {
+    fn check(&self) -> BoxFuture<'_, Result<(), String>> {
+        let outcome = probe_dir(&self.dir);
+        Box::pin(async move { outcome })
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+}
+
+fn probe_dir(dir: &Path) -> Result<(), String> {
+    static PROBES: AtomicU64 = AtomicU64::new(0);
+    let probe = dir.join(format!(
+        ".health-probe-{}-{}",
+        std::process::id(),
+        PROBES.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&probe, b"ok").map_err(|err| format!("{}: {err}", dir.display()))?;
+    std::fs::remove_file(&probe).map_err(|err| format!("{}: {err}", probe.display()))
+}
 
 /// A single health check entry.
 struct HealthCheckEntry {
     name: String,
     critical: bool,
-    check_fn: CheckFn,
+    check: Box<dyn HealthCheck>,
 }
 
 /// Registry of health checks.
@@ -178,13 +297,21 @@ struct HealthCheckEntry {
 /// ```
 pub struct HealthCheckRegistry {
     checks: Vec<HealthCheckEntry>,
+    timeout: Duration,
+    draining: AtomicBool,
+    shutdown: Option<ShutdownReceiver>,
 }
 
 impl HealthCheckRegistry {
     /// Create a new empty registry.
     #[must_use]
     pub fn new() -> Self {
-        Self { checks: Vec::new() }
+        Self {
+            checks: Vec::new(),
+            timeout: DEFAULT_CHECK_TIMEOUT,
+            draining: AtomicBool::new(false),
+            shutdown: None,
+        }
     }
 
     /// Add a named health check.
@@ -200,54 +327,77 @@ impl HealthCheckRegistry {
         F: Fn() -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Result<(), String>> + Send + 'static,
     {
-        let check_fn = Arc::new(move || {
-            let fut = check_fn();
-            Box::pin(fut) as Pin<Box<dyn Future<Output = Result<(), String>> + Send>>
-        }) as CheckFn;
+        self.register(name, critical, FnCheck(check_fn));
+    }
 
+    /// Add a named [`HealthCheck`], with the same `critical` semantics as
+    /// [`add`](Self::add).
+    pub fn register(&mut self, name: impl Into<String>, critical: bool, check: impl HealthCheck) {
         self.checks.push(HealthCheckEntry {
             name: name.into(),
             critical,
-            check_fn,
+            check: Box::new(check),
         });
     }
 
-    /// Run all health checks and produce a report.
+    /// Set the timeout for checks that do not define their own.
+    ///
+    /// A check still running when its timeout expires is cancelled and
+    /// reported as failed.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Report `draining` from readiness once `shutdown` begins.
+    pub fn watch_shutdown(&mut self, shutdown: ShutdownReceiver) {
+        self.shutdown = Some(shutdown);
+    }
+
+    /// Start reporting `draining` from readiness.
+    ///
+    /// [`AppBuilder::health`](crate::app::AppBuilder::health) calls this
+    /// from a shutdown hook.
+    pub fn mark_draining(&self) {
+        self.draining.store(true, Ordering::Release);
+    }
+
+    /// Whether the application is shutting down.
+    #[must_use]
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Acquire)
+            || self
+                .shutdown
+                .as_ref()
+                .is_some_and(ShutdownReceiver::is_shutting_down)
+    }
+
+    /// Run all health checks concurrently and produce a report.
     pub async fn check_all(&self) -> HealthReport {
         let total_start = Instant::now();
-        let mut results = Vec::with_capacity(self.checks.len());
-        let mut has_critical_failure = false;
-        let mut has_non_critical_failure = false;
-
-        for entry in &self.checks {
-            let start = Instant::now();
-            let outcome = (entry.check_fn)().await;
-            let latency_ms = start.elapsed().as_millis() as u64;
-
-            let (passed, error) = match outcome {
-                Ok(()) => (true, None),
-                Err(msg) => {
-                    if entry.critical {
-                        has_critical_failure = true;
-                    } else {
-                        has_non_critical_failure = true;
+        let mut pending: Vec<_> = self
+            .checks
+            .iter()
+            .map(|entry| Box::pin(self.run_check(entry)))
+            .collect();
+        let mut results: Vec<Option<HealthCheckResult>> = vec![None; pending.len()];
+        std::future::poll_fn(|cx| {
+            let mut done = true;
+            for (slot, check) in results.iter_mut().zip(&mut pending) {
+                if slot.is_none() {
+                    match check.as_mut().poll(cx) {
+                        Poll::Ready(result) => *slot = Some(result),
+                        Poll::Pending => done = false,
                     }
-                    (false, Some(msg))
                 }
-            };
-
-            results.push(HealthCheckResult {
-                name: entry.name.clone(),
-                passed,
-                critical: entry.critical,
-                latency_ms,
-                error,
-            });
-        }
+            }
+            if done { Poll::Ready(()) } else { Poll::Pending }
+        })
+        .await;
+        let results: Vec<HealthCheckResult> = results.into_iter().flatten().collect();
 
-        let status = if has_critical_failure {
+        let status = if results.iter().any(|r| !r.passed && r.critical) {
             HealthStatus::Unhealthy
-        } else if has_non_critical_failure {
+        } else if results.iter().any(|r| !r.passed) {
             HealthStatus::Degraded
         } else {
             HealthStatus::Healthy
@@ -256,7 +406,51 @@ impl HealthCheckRegistry {
         HealthReport {
             status,
             checks: results,
-            total_latency_ms: total_start.elapsed().as_millis() as u64,
+            total_latency_ms: millis(total_start.elapsed()),
+        }
+    }
+
+    /// Like [`check_all`](Self::check_all), but reports
+    /// [`HealthStatus::Draining`] without running the checks once the
+    /// application is shutting down.
+    pub async fn check_readiness(&self) -> HealthReport {
+        if self.is_draining() {
+            return HealthReport {
+                status: HealthStatus::Draining,
+                checks: Vec::new(),
+                total_latency_ms: 0,
+            };
+        }
+        self.check_all().await
+    }
+
+    async fn run_check(&self, entry: &HealthCheckEntry) -> HealthCheckResult {
+        let timeout = entry.check.timeout().unwrap_or(self.timeout);
+        let start = Instant::now();
+        let mut probe = entry.check.check();
+        let mut expiry = pin!(asupersync::time::sleep(
+            asupersync::time::wall_now(),
+            timeout
+        ));
+        let outcome = std::future::poll_fn(|cx| {
+            if let Poll::Ready(outcome) = probe.as_mut().poll(cx) {
+                return Poll::Ready(outcome);
+            }
+            expiry
+                .as_mut()
+                .poll(cx)
+                .map(|_| Err(format!("timed out after {}ms", millis(timeout))))
+        })
+        .await;
+        // Dropping the probe cancels whatever it was awaiting.
+        drop(probe);
+
+        HealthCheckResult {
+            name: entry.name.clone(),
+            passed: outcome.is_ok(),
+            critical: entry.critical,
+            latency_ms: millis(start.elapsed()),
+            error: outcome.err(),
         }
     }
 
@@ -279,6 +473,28 @@ impl Default for HealthCheckRegistry {
     }
 }
 
+impl std::fmt::Debug for HealthCheckRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<&str> = self.checks.iter().map(|c| c.name.as_str()).collect();
+        f.debug_struct("HealthCheckRegistry")
+            .field("checks", &names)
+            .field("timeout", &self.timeout)
+            .field("draining", &self.is_draining())
+            .finish()
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+fn report_response(report: &HealthReport) -> Response {
+    Response::with_status(report.status.status_code())
+        .header("Content-Type", b"application/json".to_vec())
+        .header("Cache-Control", b"no-cache, no-store".to_vec())
+        .body(ResponseBody::Bytes(report.to_json()))
+}
+
 /// Create a basic health check handler that returns `{"status":"healthy"}`.
 ///
 /// This is the simplest health check — always returns 200 OK. Useful as a
@@ -309,16 +525,7 @@ pub fn detailed_health_handler(
 + 'static {
     move |_ctx: &RequestContext, _req: &mut Request| {
         let registry = Arc::clone(&registry);
-        Box::pin(async move {
-            let report = registry.check_all().await;
-            let status_code = report.status.status_code();
-            let body = report.to_json();
-
-            Response::with_status(status_code)
-                .header("Content-Type", b"application/json".to_vec())
-                .header("Cache-Control", b"no-cache, no-store".to_vec())
-                .body(ResponseBody::Bytes(body))
-        })
+        Box::pin(async move { report_response(&registry.check_all().await) })
     }
 }
 
@@ -336,6 +543,8 @@ pub fn liveness_handler()
 /// Runs all checks and returns:
 /// - 200 OK when the app is ready to serve traffic
 /// - 503 Service Unavailable when critical checks fail
+/// - 503 Service Unavailable with `{"status":"draining", ...}` once the
+///   registry is draining
 ///
 /// Kubernetes will stop routing traffic to pods that fail readiness checks.
 pub fn readiness_handler(
@@ -344,7 +553,10 @@ pub fn readiness_handler(
 + Send
 + Sync
 + 'static {
-    detailed_health_handler(registry)
+    move |_ctx: &RequestContext, _req: &mut Request| {
+        let registry = Arc::clone(&registry);
+        Box::pin(async move { report_response(&registry.check_readiness().await) })
+    }
 }
 
 #[cfg(test)]
@@ -367,6 +579,7 @@ mod tests {
         assert_eq!(HealthStatus::Healthy.as_str(), "healthy");
         assert_eq!(HealthStatus::Degraded.as_str(), "degraded");
         assert_eq!(HealthStatus::Unhealthy.as_str(), "unhealthy");
+        assert_eq!(HealthStatus::Draining.as_str(), "draining");
     }
 
     #[test]
@@ -383,6 +596,10 @@ mod tests {
             HealthStatus::Unhealthy.status_code(),
             StatusCode::SERVICE_UNAVAILABLE
         );
+        assert_eq!(
+            HealthStatus::Draining.status_code(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
     }
 
     #[test]
@@ -651,4 +868,73 @@ mod tests {
         // Search passed
         assert!(report.checks[2].passed);
     }
+
+    #[test]
+    fn hung_check_times_out() {
+        let mut registry = HealthCheckRegistry::new();
+        registry.set_timeout(Duration::from_millis(20));
+        registry.add("hung", true, std::future::pending::<Result<(), String>>);
+        registry.add("fast", false, || async { Ok(()) });
+
+        let report = futures_executor::block_on(registry.check_all());
+        assert_eq!(report.status, HealthStatus::Unhealthy);
+        assert!(!report.checks[0].passed);
+        assert_eq!(
+            report.checks[0].error.as_deref(),
+            Some("timed out after 20ms")
+        );
+        assert!(report.checks[1].passed);
+    }
+
+    #[test]
+    fn readiness_reports_draining_after_shutdown() {
+        let controller = crate::shutdown::ShutdownController::new();
+        let mut registry = HealthCheckRegistry::new();
+        registry.add("db", true, || async { Ok(()) });
+        registry.watch_shutdown(controller.subscribe());
+        let registry = Arc::new(registry);
+        let readiness = readiness_handler(Arc::clone(&registry));
+        let liveness = detailed_health_handler(Arc::clone(&registry));
+
+        assert_eq!(run_handler(&readiness).status(), StatusCode::OK);
+        controller.shutdown();
+        let resp = run_handler(&readiness);
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let ResponseBody::Bytes(body) = resp.body_ref() else {
+            panic!("expected Bytes body");
+        };
+        assert!(
+            std::str::from_utf8(body)
+                .unwrap()
+                .contains("\"status\":\"draining\"")
+        );
+        assert_eq!(run_handler(&liveness).status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn mark_draining_flips_readiness() {
+        let registry = HealthCheckRegistry::new();
+        assert!(!registry.is_draining());
+        registry.mark_draining();
+        let report = futures_executor::block_on(registry.check_readiness());
+        assert_eq!(report.status, HealthStatus::Draining);
+    }
+
+    #[test]
+    fn disk_check_probes_directory() {
+        let dir = std::env::temp_dir();
+        let mut registry = HealthCheckRegistry::new();
+        registry.register("tmp", true, DiskCheck::new(&dir));
+        registry.register(
+            "missing",
+            false,
+            DiskCheck::new(dir.join("fastapi-health-missing-dir"))
+                .with_timeout(Duration::from_secs(1)),
+        );
+
+        let report = futures_executor::block_on(registry.check_all());
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert!(report.checks[0].passed);
+        assert!(!report.checks[1].passed);
+    }
 }
@@ -0,0 +1,290 @@
+//! Adapters for handlers written against other ecosystems.
+//!
+//! Migrating an existing service rarely happens in one step. These adapters
+//! let code shaped like other frameworks' handlers run inside an [`App`]
+//! unchanged while the rest of the service moves over:
+//!
+//! - [`from_fn`] wraps a plain `async fn(Request) -> impl IntoResponse`, the
+//!   shape of an axum/actix handler that owns its request.
+//! - [`from_service`] wraps a [`Service`], a trait with the same shape as
+//!   `tower::Service`: readiness is polled, then the service is called with
+//!   an owned request.
+//!
+//! Both produce a [`ForeignHandler`], which implements [`Handler`] and can be
+//! mounted with [`AppBuilder::route_foreign`].
+//!
+//! This crate does not depend on tower. A tower service is bridged with a
+//! newtype implementing [`Service`] that forwards `poll_ready` and `call`,
+//! converting request and response types at the boundary.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use fastapi_core::interop;
+//!
+//! async fn legacy_status(req: Request) -> Result<String, HttpError> {
+//!     Ok(format!("legacy handler saw {}", req.path()))
+//! }
+//!
+//! let app = App::builder()
+//!     .route_foreign("/legacy/status", Method::Get, interop::from_fn(legacy_status))
+//!     .build();
+//! ```
+//!
+//! [`App`]: crate::app::App
+//! [`AppBuilder::route_foreign`]: crate::app::AppBuilder::route_foreign
+
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::context::RequestContext;
+use crate::middleware::{BoxFuture, Handler};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response};
+
+/// An asynchronous request-to-response function, shaped like `tower::Service`.
+///
+/// The caller awaits [`poll_ready`](Service::poll_ready) before each
+/// [`call`](Service::call). [`ServiceHandler`] clones the service for every
+/// request, so state shared across requests belongs behind an `Arc`.
+pub trait Service<Req> {
+    /// Response produced by the service.
+    type Response;
+    /// Error produced by the service.
+    type Error;
+    /// Future returned by [`call`](Service::call).
+    type Future: Future<Output = Result<Self::Response, Self::Error>>;
+
+    /// Whether the service can accept a request.
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>>;
+
+    /// Process a request.
+    fn call(&mut self, req: Req) -> Self::Future;
+}
+
+/// A handler that takes ownership of the request.
+///
+/// Foreign handlers never borrow the request context, so their futures are
+/// `'static` and can be mounted like any route closure.
+pub trait ForeignHandler: Send + Sync + 'static {
+    /// Process an owned request.
+    fn call_owned(&self, req: Request) -> BoxFuture<'static, Response>;
+}
+
+/// Wrap an `async fn(Request) -> impl IntoResponse` as a handler.
+pub fn from_fn<F, Fut>(f: F) -> FnHandler<F>
+where
+    F: Fn(Request) -> Fut + Send + Sync + 'static,
+    Fut: Future + Send + 'static,
+    Fut::Output: IntoResponse,
+{
+    FnHandler { f }
+}
+
+/// Wrap a [`Service`] as a handler.
+pub fn from_service<S>(service: S) -> ServiceHandler<S>
+where
+    S: Service<Request> + Clone + Send + Sync + 'static,
+    S::Future: Send + 'static,
+    S::Response: IntoResponse,
+    S::Error: IntoResponse,
+{
+    ServiceHandler { service }
+}
+
+/// Handler created by [`from_fn`].
+#[derive(Clone)]
+pub struct FnHandler<F> {
+    f: F,
+}
+
+impl<F, Fut> ForeignHandler for FnHandler<F>
+where
+    F: Fn(Request) -> Fut + Send + Sync + 'static,
+    Fut: Future + Send + 'static,
+    Fut::Output: IntoResponse,
+{
+    fn call_owned(&self, req: Request) -> BoxFuture<'static, Response> {
+        let fut = (self.f)(req);
+        Box::pin(async move { fut.await.into_response() })
+    }
+}
+
+impl<F, Fut> Handler for FnHandler<F>
+where
+    F: Fn(Request) -> Fut + Send + Sync + 'static,
+    Fut: Future + Send + 'static,
+    Fut::Output: IntoResponse,
+{
+    fn call<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, Response> {
+        self.call_owned(take_request(req))
+    }
+}
+
+/// Handler created by [`from_service`].
+#[derive(Clone)]
+pub struct ServiceHandler<S> {
+    service: S,
+}
+
+impl<S> ForeignHandler for ServiceHandler<S>
+where
+    S: Service<Request> + Clone + Send + Sync + 'static,
+    S::Future: Send + 'static,
+    S::Response: IntoResponse,
+    S::Error: IntoResponse,
+{
+    fn call_owned(&self, req: Request) -> BoxFuture<'static, Response> {
+        let mut service = self.service.clone();
+        Box::pin(async move {
+            if let Err(err) = std::future::poll_fn(|cx| service.poll_ready(cx)).await {
+                return err.into_response();
+            }
+            service.call(req).await.into_response()
+        })
+    }
+}
+
+impl<S> Handler for ServiceHandler<S>
+where
+    S: Service<Request> + Clone + Send + Sync + 'static,
+    S::Future: Send + 'static,
+    S::Response: IntoResponse,
+    S::Error: IntoResponse,
+{
+    fn call<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, Response> {
+        self.call_owned(take_request(req))
+    }
+}
+
+impl<H: ForeignHandler + ?Sized> ForeignHandler for Arc<H> {
+    fn call_owned(&self, req: Request) -> BoxFuture<'static, Response> {
+        (**self).call_owned(req)
+    }
+}
+
+/// Move the request out, leaving a copy of its request line behind so
+/// middleware running after the handler still sees method, path and query.
+pub(crate) fn take_request(req: &mut Request) -> Request {
+    let mut remaining = Request::with_version(req.method(), req.path(), req.version());
+    remaining.set_query(req.query().map(str::to_owned));
+    std::mem::replace(req, remaining)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::{Body, Method};
+    use crate::response::{ResponseBody, StatusCode};
+    use std::convert::Infallible;
+    use std::future::Ready;
+
+    fn test_context() -> RequestContext {
+        RequestContext::new(asupersync::Cx::for_testing(), 1)
+    }
+
+    fn body_text(response: &Response) -> String {
+        match response.body_ref() {
+            ResponseBody::Bytes(b) => String::from_utf8(b.clone()).unwrap(),
+            _ => String::new(),
+        }
+    }
+
+    async fn echo(mut req: Request) -> String {
+        let body = match req.take_body() {
+            Body::Bytes(bytes) => String::from_utf8(bytes).unwrap(),
+            _ => String::new(),
+        };
+        format!("{} {}", req.path(), body)
+    }
+
+    #[test]
+    fn fn_handler_receives_owned_request() {
+        let handler = from_fn(echo);
+        let ctx = test_context();
+        let mut req = Request::new(Method::Post, "/legacy");
+        req.set_query(Some("a=1".to_string()));
+        req.set_body(Body::Bytes(b"payload".to_vec()));
+
+        let response = futures_executor::block_on(handler.call(&ctx, &mut req));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_text(&response), "/legacy payload");
+
+        // The request line stays visible to code running after the handler.
+        assert_eq!(req.path(), "/legacy");
+        assert_eq!(req.query(), Some("a=1"));
+        assert!(matches!(req.body(), Body::Empty));
+    }
+
+    #[derive(Clone)]
+    struct Counter {
+        ready: bool,
+    }
+
+    impl Service<Request> for Counter {
+        type Response = String;
+        type Error = Response;
+        type Future = Ready<Result<String, Response>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Response>> {
+            if self.ready {
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Ready(Err(Response::with_status(StatusCode::SERVICE_UNAVAILABLE)))
+            }
+        }
+
+        fn call(&mut self, req: Request) -> Self::Future {
+            std::future::ready(Ok(format!("served {}", req.path())))
+        }
+    }
+
+    #[test]
+    fn service_handler_polls_readiness_then_calls() {
+        let ctx = test_context();
+
+        let handler = from_service(Counter { ready: true });
+        let mut req = Request::new(Method::Get, "/svc");
+        let response = futures_executor::block_on(handler.call(&ctx, &mut req));
+        assert_eq!(body_text(&response), "served /svc");
+
+        let handler = from_service(Counter { ready: false });
+        let mut req = Request::new(Method::Get, "/svc");
+        let response = futures_executor::block_on(handler.call(&ctx, &mut req));
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn infallible_services_are_supported() {
+        #[derive(Clone)]
+        struct Hello;
+
+        impl Service<Request> for Hello {
+            type Response = &'static str;
+            type Error = Infallible;
+            type Future = Ready<Result<&'static str, Infallible>>;
+
+            fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, _req: Request) -> Self::Future {
+                std::future::ready(Ok("hello"))
+            }
+        }
+
+        let response = futures_executor::block_on(
+            from_service(Hello).call_owned(Request::new(Method::Get, "/")),
+        );
+        assert_eq!(body_text(&response), "hello");
+    }
+}
@@ -0,0 +1,290 @@
+//! Client IP allow and deny lists.
+//!
+//! [`IpFilterMiddleware`] rejects requests by the client's address before
+//! they reach a handler. An *allowlist* only admits addresses inside its
+//! ranges; a *denylist* admits everything except them.
+//!
+//! The client address is the TCP peer from the [`RemoteAddr`] extension.
+//! Behind a load balancer, pass a [`TrustedProxyIpKeyExtractor`] so that
+//! `X-Forwarded-For` is honoured only when the peer is a trusted proxy:
+//!
+//! ```ignore
+//! let filter = IpFilterMiddleware::allowlist()
+//!     .cidr("10.0.0.0/8")
+//!     .cidr("2001:db8::/32")
+//!     .trusted_proxies(TrustedProxyIpKeyExtractor::new().trust_cidr("10.1.0.0/16"));
+//!
+//! let app = App::builder().middleware(filter).build();
+//! ```
+//!
+//! Each rejection is logged at WARN level with the client IP, path and
+//! filter mode.
+
+use std::net::IpAddr;
+
+use crate::context::RequestContext;
+use crate::error::HttpError;
+use crate::logging::{LogConfig, RequestLogger};
+use crate::middleware::{
+    BoxFuture, ControlFlow, KeyExtractor, Middleware, RemoteAddr, TrustedProxyIpKeyExtractor,
+    ip_in_cidr, parse_cidr,
+};
+use crate::request::Request;
+use crate::response::{IntoResponse, StatusCode};
+
+/// Whether the ranges of an [`IpFilterMiddleware`] admit or reject.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpFilterMode {
+    /// Only addresses inside the ranges are admitted.
+    Allow,
+    /// Addresses inside the ranges are rejected.
+    Deny,
+}
+
+impl IpFilterMode {
+    /// Lowercase name, for log fields.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allowlist",
+            Self::Deny => "denylist",
+        }
+    }
+}
+
+/// Middleware that filters requests by client IP address.
+#[derive(Debug, Clone)]
+pub struct IpFilterMiddleware {
+    mode: IpFilterMode,
+    ranges: Vec<(IpAddr, u8)>,
+    proxies: Option<TrustedProxyIpKeyExtractor>,
+    status: StatusCode,
+    audit: bool,
+}
+
+impl IpFilterMiddleware {
+    fn new(mode: IpFilterMode) -> Self {
+        Self {
+            mode,
+            ranges: Vec::new(),
+            proxies: None,
+            status: StatusCode::FORBIDDEN,
+            audit: true,
+        }
+    }
+
+    /// A filter that admits only the listed ranges.
+    ///
+    /// Requests whose client IP cannot be determined are rejected.
+    #[must_use]
+    pub fn allowlist() -> Self {
+        Self::new(IpFilterMode::Allow)
+    }
+
+    /// A filter that rejects the listed ranges.
+    ///
+    /// Requests whose client IP cannot be determined are admitted.
+    #[must_use]
+    pub fn denylist() -> Self {
+        Self::new(IpFilterMode::Deny)
+    }
+
+    /// Add a CIDR range such as `"192.168.0.0/16"`. A bare address matches
+    /// only itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is not valid CIDR notation.
+    #[must_use]
+    pub fn cidr(mut self, cidr: &str) -> Self {
+        let range = if cidr.contains('/') {
+            parse_cidr(cidr)
+        } else {
+            cidr.parse::<IpAddr>().ok().map(host_range)
+        };
+        self.ranges.push(range.expect("invalid CIDR notation"));
+        self
+    }
+
+    /// Add a single address.
+    #[must_use]
+    pub fn ip(mut self, ip: IpAddr) -> Self {
+        self.ranges.push(host_range(ip));
+        self
+    }
+
+    /// Resolve the client IP through trusted proxies' forwarding headers.
+    #[must_use]
+    pub fn trusted_proxies(mut self, proxies: TrustedProxyIpKeyExtractor) -> Self {
+        self.proxies = Some(proxies);
+        self
+    }
+
+    /// Status of rejection responses (default 403).
+    #[must_use]
+    pub fn rejection_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Whether to log rejections (default on).
+    #[must_use]
+    pub fn audit_log(mut self, enabled: bool) -> Self {
+        self.audit = enabled;
+        self
+    }
+
+    /// The filter mode.
+    #[must_use]
+    pub fn mode(&self) -> IpFilterMode {
+        self.mode
+    }
+
+    fn client_ip(&self, req: &Request) -> Option<IpAddr> {
+        match &self.proxies {
+            Some(proxies) => proxies.extract_key(req)?.parse().ok(),
+            None => req.get_extension::<RemoteAddr>().map(|remote| remote.0),
+        }
+    }
+
+    /// Whether a request from `ip` is admitted.
+    ///
+    /// IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`, as reported for IPv4
+    /// peers on a dual-stack listener) are matched as the IPv4 address.
+    #[must_use]
+    pub fn admits(&self, ip: Option<IpAddr>) -> bool {
+        let Some(ip) = ip.map(|ip| ip.to_canonical()) else {
+            return self.mode == IpFilterMode::Deny;
+        };
+        let listed = self
+            .ranges
+            .iter()
+            .any(|(range, prefix)| ip_in_cidr(ip, *range, *prefix));
+        match self.mode {
+            IpFilterMode::Allow => listed,
+            IpFilterMode::Deny => !listed,
+        }
+    }
+}
+
+fn host_range(ip: IpAddr) -> (IpAddr, u8) {
+    match ip {
+        IpAddr::V4(_) => (ip, 32),
+        IpAddr::V6(_) => (ip, 128),
+    }
+}
+
+impl Middleware for IpFilterMiddleware {
+    fn before<'a>(
+        &'a self,
+        ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        let ip = self.client_ip(req);
+        if self.admits(ip) {
+            return Box::pin(async { ControlFlow::Continue });
+        }
+        if self.audit {
+            RequestLogger::new(ctx, LogConfig::default()).warn_with_fields(
+                "request rejected by IP filter",
+                |entry| {
+                    let entry = match ip {
+                        Some(ip) => entry.field("client_ip", ip),
+                        None => entry.field("client_ip", "unknown"),
+                    };
+                    entry
+                        .field("path", req.path())
+                        .field("mode", self.mode.as_str())
+                },
+            );
+        }
+        let response = HttpError::new(self.status)
+            .with_detail("Access denied")
+            .into_response();
+        Box::pin(async move { ControlFlow::Break(response) })
+    }
+
+    fn name(&self) -> &'static str {
+        "IpFilter"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Method;
+
+    fn request_from(peer: &str) -> Request {
+        let mut req = Request::new(Method::Get, "/admin");
+        req.insert_extension(RemoteAddr(peer.parse().unwrap()));
+        req
+    }
+
+    fn run(mw: &IpFilterMiddleware, req: &mut Request) -> ControlFlow {
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+        futures_executor::block_on(mw.before(&ctx, req))
+    }
+
+    #[test]
+    fn allowlist_admits_only_listed_ranges() {
+        let mw = IpFilterMiddleware::allowlist()
+            .cidr("10.0.0.0/8")
+            .cidr("2001:db8::1");
+        assert!(run(&mw, &mut request_from("10.20.30.40")).is_continue());
+        assert!(run(&mw, &mut request_from("2001:db8::1")).is_continue());
+        assert!(!run(&mw, &mut request_from("192.168.1.1")).is_continue());
+        assert!(!run(&mw, &mut Request::new(Method::Get, "/admin")).is_continue());
+    }
+
+    #[test]
+    fn denylist_rejects_with_configured_status() {
+        let mw = IpFilterMiddleware::denylist()
+            .ip("203.0.113.7".parse().unwrap())
+            .rejection_status(StatusCode::NOT_FOUND)
+            .audit_log(false);
+        assert!(run(&mw, &mut request_from("203.0.113.8")).is_continue());
+        let ControlFlow::Break(response) = run(&mw, &mut request_from("203.0.113.7")) else {
+            panic!("denied address should be rejected");
+        };
+        assert_eq!(response.status().as_u16(), 404);
+    }
+
+    #[test]
+    fn forwarded_address_is_used_only_behind_trusted_proxies() {
+        let mw = IpFilterMiddleware::denylist()
+            .cidr("198.51.100.0/24")
+            .trusted_proxies(TrustedProxyIpKeyExtractor::new().trust_cidr("10.0.0.0/8"));
+
+        let mut proxied = request_from("10.0.0.2");
+        proxied
+            .headers_mut()
+            .insert("x-forwarded-for", b"198.51.100.9, 10.0.0.2".to_vec());
+        assert!(!run(&mw, &mut proxied).is_continue());
+
+        let mut spoofed = request_from("192.0.2.1");
+        spoofed
+            .headers_mut()
+            .insert("x-forwarded-for", b"198.51.100.9".to_vec());
+        assert!(run(&mw, &mut spoofed).is_continue());
+    }
+
+    #[test]
+    fn ipv4_mapped_peers_match_ipv4_ranges() {
+        let deny = IpFilterMiddleware::denylist()
+            .cidr("203.0.113.0/24")
+            .audit_log(false);
+        assert!(!run(&deny, &mut request_from("::ffff:203.0.113.7")).is_continue());
+        assert!(run(&deny, &mut request_from("::ffff:198.51.100.1")).is_continue());
+
+        let allow = IpFilterMiddleware::allowlist()
+            .cidr("10.0.0.0/8")
+            .audit_log(false);
+        assert!(run(&allow, &mut request_from("::ffff:10.1.2.3")).is_continue());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid CIDR notation")]
+    fn invalid_cidr_panics() {
+        let _ = IpFilterMiddleware::allowlist().cidr("10.0.0.0/33");
+    }
+}
@@ -0,0 +1,255 @@
+//! Application-wide JSON key casing.
+//!
+//! With [`AppConfig::json_key_case`](crate::app::AppConfig::json_key_case)
+//! set, every JSON response has its object keys rewritten (for example
+//! `user_id` becomes `userId`), JSON request bodies have their keys mapped
+//! back before deserialization, and the OpenAPI document describes the
+//! transformed names. Structs keep their Rust field names and need no
+//! `#[serde(rename_all = "...")]`.
+//!
+//! Keys are rewritten on the serialized bytes, so map keys (`HashMap`
+//! entries) are transformed as well as struct fields; string values are
+//! never touched.
+
+use serde_json::Value;
+
+/// Casing applied to JSON object keys in responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonKeyCase {
+    /// `snake_case` keys are sent as `camelCase`; request keys are accepted
+    /// in `camelCase`.
+    Camel,
+    /// `camelCase` keys are sent as `snake_case`; request keys are accepted
+    /// in `snake_case`.
+    Snake,
+}
+
+impl JsonKeyCase {
+    /// The casing that undoes this one, used for request bodies.
+    #[must_use]
+    pub fn inverse(self) -> Self {
+        match self {
+            Self::Camel => Self::Snake,
+            Self::Snake => Self::Camel,
+        }
+    }
+
+    /// Convert a single key.
+    #[must_use]
+    pub fn convert(self, key: &str) -> String {
+        match self {
+            Self::Camel => to_camel_case(key),
+            Self::Snake => to_snake_case(key),
+        }
+    }
+
+    /// Rewrite the object keys of a JSON document.
+    ///
+    /// Works on the token stream, so key order, numbers and string values are
+    /// kept byte for byte. Keys containing escape sequences are left as-is.
+    #[must_use]
+    pub fn convert_json(self, input: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(input.len() + input.len() / 8);
+        // One entry per open container: true for objects.
+        let mut stack: Vec<bool> = Vec::new();
+        let mut expect_key = false;
+        let mut i = 0;
+
+        while i < input.len() {
+            let b = input[i];
+            match b {
+                b'"' => {
+                    let Some(end) = string_end(input, i) else {
+                        out.extend_from_slice(&input[i..]);
+                        break;
+                    };
+                    let raw = &input[i + 1..end - 1];
+                    let key = std::str::from_utf8(raw)
+                        .ok()
+                        .filter(|key| expect_key && !key.contains('\\'));
+                    match key {
+                        Some(key) => {
+                            out.push(b'"');
+                            out.extend_from_slice(self.convert(key).as_bytes());
+                            out.push(b'"');
+                        }
+                        None => out.extend_from_slice(&input[i..end]),
+                    }
+                    expect_key = false;
+                    i = end;
+                    continue;
+                }
+                b'{' => {
+                    stack.push(true);
+                    expect_key = true;
+                }
+                b'[' => {
+                    stack.push(false);
+                    expect_key = false;
+                }
+                b'}' | b']' => {
+                    stack.pop();
+                    expect_key = false;
+                }
+                b',' => expect_key = stack.last().copied().unwrap_or(false),
+                _ => {}
+            }
+            out.push(b);
+            i += 1;
+        }
+        out
+    }
+
+    /// Rewrite the property names of every schema in an OpenAPI document.
+    ///
+    /// Renames the keys of each `properties` object and the entries of the
+    /// sibling `required` list, recursing into nested schemas.
+    pub fn convert_schemas(self, doc: &mut Value) {
+        match doc {
+            Value::Object(map) => {
+                let has_properties = matches!(map.get("properties"), Some(Value::Object(_)));
+                if let Some(Value::Object(properties)) = map.get_mut("properties") {
+                    let renamed = std::mem::take(properties)
+                        .into_iter()
+                        .map(|(name, schema)| (self.convert(&name), schema))
+                        .collect();
+                    *properties = renamed;
+                }
+                if has_properties {
+                    if let Some(Value::Array(required)) = map.get_mut("required") {
+                        for name in required.iter_mut() {
+                            if let Value::String(name) = name {
+                                *name = self.convert(name);
+                            }
+                        }
+                    }
+                }
+                for value in map.values_mut() {
+                    self.convert_schemas(value);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.convert_schemas(item);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Index just past the closing quote of the string starting at `start`.
+fn string_end(input: &[u8], start: usize) -> Option<usize> {
+    let mut escaped = false;
+    for (offset, &b) in input[start + 1..].iter().enumerate() {
+        if escaped {
+            escaped = false;
+        } else if b == b'\\' {
+            escaped = true;
+        } else if b == b'"' {
+            return Some(start + offset + 2);
+        }
+    }
+    None
+}
+
+/// `user_id` → `userId`. Leading underscores are kept.
+fn to_camel_case(key: &str) -> String {
+    let trimmed = key.trim_start_matches('_');
+    let mut out = String::with_capacity(key.len());
+    out.push_str(&key[..key.len() - trimmed.len()]);
+    let mut upper_next = false;
+    for c in trimmed.chars() {
+        if c == '_' {
+            upper_next = true;
+        } else if upper_next {
+            out.extend(c.to_uppercase());
+            upper_next = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// `userId` → `user_id`, `HTTPStatus` → `http_status`.
+fn to_snake_case(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    let mut out = String::with_capacity(key.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let prev = i.checked_sub(1).map(|p| chars[p]);
+            let next = chars.get(i + 1).copied();
+            let boundary = prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit())
+                || (prev.is_some_and(char::is_uppercase) && next.is_some_and(char::is_lowercase));
+            if boundary && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_keys() {
+        assert_eq!(JsonKeyCase::Camel.convert("user_id"), "userId");
+        assert_eq!(JsonKeyCase::Camel.convert("_private_key"), "_privateKey");
+        assert_eq!(JsonKeyCase::Camel.convert("id"), "id");
+        assert_eq!(JsonKeyCase::Snake.convert("userId"), "user_id");
+        assert_eq!(JsonKeyCase::Snake.convert("HTTPStatus"), "http_status");
+        assert_eq!(JsonKeyCase::Snake.convert("page2Size"), "page2_size");
+        assert_eq!(JsonKeyCase::Snake.convert("already_snake"), "already_snake");
+    }
+
+    #[test]
+    fn rewrites_only_object_keys() {
+        let input = br#"{"user_id":1,"tags":["first_tag",{"tag_name":"x_y"}],"note":"a \"quoted_word\": no","nested":{"created_at":null}}"#;
+        let output = JsonKeyCase::Camel.convert_json(input);
+        assert_eq!(
+            String::from_utf8(output.clone()).unwrap(),
+            r#"{"userId":1,"tags":["first_tag",{"tagName":"x_y"}],"note":"a \"quoted_word\": no","nested":{"createdAt":null}}"#
+        );
+        assert_eq!(JsonKeyCase::Snake.convert_json(&output), input.to_vec());
+    }
+
+    #[test]
+    fn rewrites_schema_properties() {
+        let mut doc = serde_json::json!({
+            "components": {"schemas": {"User": {
+                "type": "object",
+                "properties": {
+                    "user_id": {"type": "integer"},
+                    "home_address": {
+                        "type": "object",
+                        "properties": {"zip_code": {"type": "string"}},
+                        "required": ["zip_code"]
+                    }
+                },
+                "required": ["user_id"]
+            }}},
+            "paths": {"/users": {"get": {"parameters": [
+                {"name": "page_size", "in": "query", "required": true}
+            ]}}}
+        });
+        JsonKeyCase::Camel.convert_schemas(&mut doc);
+
+        let user = &doc["components"]["schemas"]["User"];
+        assert!(user["properties"].get("userId").is_some());
+        assert_eq!(user["required"], serde_json::json!(["userId"]));
+        assert_eq!(
+            user["properties"]["homeAddress"]["required"],
+            serde_json::json!(["zipCode"])
+        );
+        assert_eq!(
+            doc["paths"]["/users"]["get"]["parameters"][0]["name"],
+            "page_size"
+        );
+    }
+}
@@ -0,0 +1,488 @@
+//! JSON Patch ([RFC 6902](https://www.rfc-editor.org/rfc/rfc6902)).
+//!
+//! A JSON Patch is an array of operations addressed by JSON Pointers:
+//!
+//! ```json
+//! [
+//!   {"op": "test", "path": "/version", "value": 3},
+//!   {"op": "replace", "path": "/title", "value": "New title"},
+//!   {"op": "add", "path": "/tags/-", "value": "urgent"},
+//!   {"op": "move", "from": "/draft", "path": "/archive/draft"}
+//! ]
+//! ```
+//!
+//! [`JsonPatch`] extracts one from a request body and applies it atomically:
+//! if any operation fails the document is left as it was. A failed `test`
+//! answers 409 Conflict; a path that does not exist answers 422.
+//!
+//! ```ignore
+//! #[patch("/docs/{id}")]
+//! async fn patch_doc(cx: &Cx, id: Path<u64>, patch: JsonPatch) -> Result<Json<Doc>, JsonPatchError> {
+//!     let doc = load_doc(id.0);
+//!     let doc: Doc = patch.apply_validated(&doc)?; // runs `Validate` on the result
+//!     save_doc(&doc);
+//!     Ok(Json(doc))
+//! }
+//! ```
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::context::RequestContext;
+use crate::error::{HttpError, LocItem, ValidationError, ValidationErrors, loc};
+use crate::extract::{FromRequest, JsonExtractError, read_json_body};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response, StatusCode};
+use crate::validation::Validate;
+
+/// Media type of JSON Patch documents.
+pub const JSON_PATCH_CONTENT_TYPE: &str = "application/json-patch+json";
+
+/// One JSON Patch operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    /// Insert `value` at `path`, replacing an existing object member.
+    Add { path: String, value: Value },
+    /// Remove the value at `path`.
+    Remove { path: String },
+    /// Replace the existing value at `path`.
+    Replace { path: String, value: Value },
+    /// Remove the value at `from` and add it at `path`.
+    Move { from: String, path: String },
+    /// Add a copy of the value at `from` at `path`.
+    Copy { from: String, path: String },
+    /// Check that the value at `path` equals `value`.
+    Test { path: String, value: Value },
+}
+
+impl PatchOperation {
+    /// The operation's `path`.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Add { path, .. }
+            | Self::Remove { path }
+            | Self::Replace { path, .. }
+            | Self::Move { path, .. }
+            | Self::Copy { path, .. }
+            | Self::Test { path, .. } => path,
+        }
+    }
+}
+
+/// Why a patch could not be applied.
+#[derive(Debug)]
+pub enum JsonPatchError {
+    /// A `test` operation did not match. Responds with 409.
+    TestFailed {
+        /// Index of the operation in the patch.
+        index: usize,
+        /// The tested path.
+        path: String,
+    },
+    /// An operation could not be applied, e.g. its path does not exist.
+    /// Responds with 422.
+    Operation {
+        /// Index of the operation in the patch.
+        index: usize,
+        /// What went wrong.
+        message: String,
+    },
+    /// The patched document is not a valid target type. Responds with 422.
+    Invalid(Box<ValidationErrors>),
+}
+
+impl fmt::Display for JsonPatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TestFailed { index, path } => {
+                write!(f, "patch operation {index}: test failed at `{path}`")
+            }
+            Self::Operation { index, message } => write!(f, "patch operation {index}: {message}"),
+            Self::Invalid(errors) => write!(f, "patched document is invalid: {errors}"),
+        }
+    }
+}
+
+impl std::error::Error for JsonPatchError {}
+
+impl IntoResponse for JsonPatchError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::TestFailed { .. } => HttpError::new(StatusCode::CONFLICT)
+                .with_detail(self.to_string())
+                .into_response(),
+            Self::Operation { index, message } => {
+                let mut location = loc::body();
+                location.push(LocItem::index(index));
+                ValidationErrors::single(ValidationError::value_error(location, message))
+                    .into_response()
+            }
+            Self::Invalid(errors) => errors.into_response(),
+        }
+    }
+}
+
+/// A JSON Patch document; also the extractor for JSON Patch request bodies.
+///
+/// Accepts `application/json-patch+json` as well as `application/json`
+/// bodies, with the same size limit and error responses as
+/// [`Json`](crate::Json). Unknown operations and missing members are
+/// rejected with 422 at extraction.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct JsonPatch(pub Vec<PatchOperation>);
+
+impl JsonPatch {
+    /// The operations, in order.
+    #[must_use]
+    pub fn operations(&self) -> &[PatchOperation] {
+        &self.0
+    }
+
+    /// Apply the patch to `document`. Either every operation is applied or,
+    /// on error, `document` is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JsonPatchError::TestFailed`] for a failed `test` and
+    /// [`JsonPatchError::Operation`] for any other failing operation.
+    pub fn apply(&self, document: &mut Value) -> Result<(), JsonPatchError> {
+        let mut patched = document.clone();
+        for (index, op) in self.0.iter().enumerate() {
+            apply_operation(&mut patched, op).map_err(|err| match err {
+                OpError::TestFailed => JsonPatchError::TestFailed {
+                    index,
+                    path: op.path().to_string(),
+                },
+                OpError::Invalid(message) => JsonPatchError::Operation { index, message },
+            })?;
+        }
+        *document = patched;
+        Ok(())
+    }
+
+    /// Return a patched copy of a typed `target`.
+    ///
+    /// # Errors
+    ///
+    /// As [`apply`](Self::apply), plus [`JsonPatchError::Invalid`] if the
+    /// patched document does not deserialize as `T`.
+    pub fn apply_to<T: Serialize + DeserializeOwned>(
+        &self,
+        target: &T,
+    ) -> Result<T, JsonPatchError> {
+        let mut document = serde_json::to_value(target).map_err(|err| invalid(&err))?;
+        self.apply(&mut document)?;
+        serde_json::from_value(document).map_err(|err| invalid(&err))
+    }
+
+    /// Like [`apply_to`](Self::apply_to), then run `T`'s [`Validate`] rules
+    /// on the result.
+    ///
+    /// # Errors
+    ///
+    /// As [`apply_to`](Self::apply_to), with validation failures reported as
+    /// [`JsonPatchError::Invalid`].
+    pub fn apply_validated<T>(&self, target: &T) -> Result<T, JsonPatchError>
+    where
+        T: Serialize + DeserializeOwned + Validate,
+    {
+        let patched = self.apply_to(target)?;
+        patched.validate().map_err(JsonPatchError::Invalid)?;
+        Ok(patched)
+    }
+}
+
+fn invalid(err: &serde_json::Error) -> JsonPatchError {
+    JsonPatchError::Invalid(Box::new(ValidationErrors::single(
+        ValidationError::value_error(loc::body(), format!("Patched value is invalid: {err}")),
+    )))
+}
+
+impl FromRequest for JsonPatch {
+    type Error = JsonExtractError;
+
+    async fn from_request(ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
+        let bytes = read_json_body(ctx, req).await?;
+        serde_json::from_slice(&bytes).map_err(|e| JsonExtractError::DeserializeError {
+            message: e.to_string(),
+            line: Some(e.line()),
+            column: Some(e.column()),
+        })
+    }
+}
+
+enum OpError {
+    TestFailed,
+    Invalid(String),
+}
+
+fn apply_operation(doc: &mut Value, op: &PatchOperation) -> Result<(), OpError> {
+    match op {
+        PatchOperation::Add { path, value } => add(doc, path, value.clone()),
+        PatchOperation::Remove { path } => remove(doc, path).map(drop),
+        PatchOperation::Replace { path, value } => {
+            *lookup_mut(doc, path)? = value.clone();
+            Ok(())
+        }
+        PatchOperation::Move { from, path } => {
+            if path.starts_with(from.as_str()) && path[from.len()..].starts_with('/') {
+                return Err(OpError::Invalid(format!(
+                    "cannot move `{from}` into its own child `{path}`"
+                )));
+            }
+            if from == path {
+                return lookup_mut(doc, from).map(drop);
+            }
+            let value = remove(doc, from)?;
+            add(doc, path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = lookup_mut(doc, from)?.clone();
+            add(doc, path, value)
+        }
+        PatchOperation::Test { path, value } => {
+            if *lookup_mut(doc, path)? == *value {
+                Ok(())
+            } else {
+                Err(OpError::TestFailed)
+            }
+        }
+    }
+}
+
+/// Split a JSON Pointer into unescaped reference tokens.
+fn tokens(pointer: &str) -> Result<Vec<String>, OpError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err(OpError::Invalid(format!(
+            "invalid JSON pointer `{pointer}`"
+        )));
+    };
+    Ok(rest
+        .split('/')
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+fn array_index(token: &str, len: usize, pointer: &str) -> Result<usize, OpError> {
+    let valid = !token.is_empty()
+        && token.bytes().all(|b| b.is_ascii_digit())
+        && (token == "0" || !token.starts_with('0'));
+    match token.parse::<usize>() {
+        Ok(index) if valid && index < len => Ok(index),
+        _ => Err(OpError::Invalid(format!("path `{pointer}` does not exist"))),
+    }
+}
+
+fn lookup_mut<'v>(doc: &'v mut Value, pointer: &str) -> Result<&'v mut Value, OpError> {
+    let mut current = doc;
+    for token in tokens(pointer)? {
+        current = match current {
+            Value::Object(map) => map.get_mut(&token),
+            Value::Array(items) => {
+                let index = array_index(&token, items.len(), pointer)?;
+                items.get_mut(index)
+            }
+            _ => None,
+        }
+        .ok_or_else(|| OpError::Invalid(format!("path `{pointer}` does not exist")))?;
+    }
+    Ok(current)
+}
+
+/// The parent container of `pointer` and its unescaped last token.
+fn parent_mut<'v>(doc: &'v mut Value, pointer: &str) -> Result<(&'v mut Value, String), OpError> {
+    let Some(pos) = pointer.rfind('/') else {
+        return Err(OpError::Invalid(format!(
+            "invalid JSON pointer `{pointer}`"
+        )));
+    };
+    let last = pointer[pos + 1..].replace("~1", "/").replace("~0", "~");
+    Ok((lookup_mut(doc, &pointer[..pos])?, last))
+}
+
+fn add(doc: &mut Value, pointer: &str, value: Value) -> Result<(), OpError> {
+    if pointer.is_empty() {
+        *doc = value;
+        return Ok(());
+    }
+    let (parent, last) = parent_mut(doc, pointer)?;
+    match parent {
+        Value::Object(map) => {
+            map.insert(last, value);
+            Ok(())
+        }
+        Value::Array(items) => {
+            let index = if last == "-" {
+                items.len()
+            } else {
+                array_index(&last, items.len() + 1, pointer)?
+            };
+            items.insert(index, value);
+            Ok(())
+        }
+        _ => Err(OpError::Invalid(format!(
+            "parent of `{pointer}` is not an object or array"
+        ))),
+    }
+}
+
+fn remove(doc: &mut Value, pointer: &str) -> Result<Value, OpError> {
+    if pointer.is_empty() {
+        return Err(OpError::Invalid(
+            "cannot remove the whole document".to_string(),
+        ));
+    }
+    let (parent, last) = parent_mut(doc, pointer)?;
+    match parent {
+        Value::Object(map) => map.remove(&last),
+        Value::Array(items) => {
+            let index = array_index(&last, items.len(), pointer)?;
+            Some(items.remove(index))
+        }
+        _ => None,
+    }
+    .ok_or_else(|| OpError::Invalid(format!("path `{pointer}` does not exist")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::{Body, Method};
+    use serde_json::json;
+
+    fn patch(ops: Value) -> JsonPatch {
+        serde_json::from_value(ops).unwrap()
+    }
+
+    #[test]
+    fn applies_all_operations() {
+        let mut doc = json!({"a": {"b": 1}, "list": [1, 2], "x~y": "tilde"});
+        patch(json!([
+            {"op": "test", "path": "/a/b", "value": 1},
+            {"op": "add", "path": "/list/1", "value": 9},
+            {"op": "add", "path": "/list/-", "value": 3},
+            {"op": "replace", "path": "/x~0y", "value": "ok"},
+            {"op": "copy", "from": "/a", "path": "/c"},
+            {"op": "move", "from": "/a/b", "path": "/moved"},
+            {"op": "remove", "path": "/list/0"},
+        ]))
+        .apply(&mut doc)
+        .unwrap();
+        assert_eq!(
+            doc,
+            json!({"a": {}, "c": {"b": 1}, "moved": 1, "list": [9, 2, 3], "x~y": "ok"})
+        );
+    }
+
+    #[test]
+    fn failures_leave_document_unchanged() {
+        let original = json!({"a": 1, "list": [1]});
+
+        let mut doc = original.clone();
+        let err = patch(json!([
+            {"op": "replace", "path": "/a", "value": 2},
+            {"op": "test", "path": "/a", "value": 1},
+        ]))
+        .apply(&mut doc)
+        .unwrap_err();
+        assert!(matches!(err, JsonPatchError::TestFailed { index: 1, .. }));
+        assert_eq!(err.into_response().status().as_u16(), 409);
+        assert_eq!(doc, original);
+
+        for ops in [
+            json!([{"op": "remove", "path": "/missing"}]),
+            json!([{"op": "add", "path": "/list/5", "value": 0}]),
+            json!([{"op": "replace", "path": "/list/01", "value": 0}]),
+            json!([{"op": "move", "from": "/list", "path": "/list/0"}]),
+            json!([{"op": "add", "path": "a", "value": 0}]),
+        ] {
+            let err = patch(ops.clone()).apply(&mut doc).unwrap_err();
+            assert!(
+                matches!(err, JsonPatchError::Operation { index: 0, .. }),
+                "{ops}"
+            );
+            assert_eq!(err.into_response().status().as_u16(), 422);
+            assert_eq!(doc, original);
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Item {
+        name: String,
+        qty: u32,
+    }
+
+    impl Validate for Item {
+        fn validate(&self) -> Result<(), Box<ValidationErrors>> {
+            if self.qty > 10 {
+                return Err(Box::new(ValidationErrors::single(
+                    ValidationError::value_error(loc::body_field("qty"), "too many"),
+                )));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn typed_targets_are_checked() {
+        let item = Item {
+            name: "pen".into(),
+            qty: 1,
+        };
+        let bumped = patch(json!([{"op": "replace", "path": "/qty", "value": 5}]))
+            .apply_validated(&item)
+            .unwrap();
+        assert_eq!(bumped.qty, 5);
+
+        let too_many = patch(json!([{"op": "replace", "path": "/qty", "value": 50}]));
+        assert!(too_many.apply_to(&item).is_ok());
+        let err = too_many.apply_validated(&item).unwrap_err();
+        assert!(matches!(err, JsonPatchError::Invalid(_)));
+        assert_eq!(err.into_response().status().as_u16(), 422);
+
+        let wrong_type = patch(json!([{"op": "replace", "path": "/qty", "value": "x"}]));
+        assert!(matches!(
+            wrong_type.apply_to(&item),
+            Err(JsonPatchError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn extracts_patch_bodies() {
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+        let mut req = Request::new(Method::Patch, "/items/1");
+        req.headers_mut()
+            .insert("content-type", JSON_PATCH_CONTENT_TYPE.as_bytes().to_vec());
+        req.set_body(Body::Bytes(
+            br#"[{"op": "remove", "path": "/name"}]"#.to_vec(),
+        ));
+        let extracted =
+            futures_executor::block_on(JsonPatch::from_request(&ctx, &mut req)).unwrap();
+        assert_eq!(
+            extracted.operations(),
+            [PatchOperation::Remove {
+                path: "/name".into()
+            }]
+        );
+
+        let mut req = Request::new(Method::Patch, "/items/1");
+        req.headers_mut()
+            .insert("content-type", JSON_PATCH_CONTENT_TYPE.as_bytes().to_vec());
+        req.set_body(Body::Bytes(
+            br#"[{"op": "frobnicate", "path": "/"}]"#.to_vec(),
+        ));
+        assert!(matches!(
+            futures_executor::block_on(JsonPatch::from_request(&ctx, &mut req)),
+            Err(JsonExtractError::DeserializeError { .. })
+        ));
+    }
+}
@@ -0,0 +1,982 @@
+//! JSON Web Token validation for bearer tokens.
+//!
+//! [`JwtBearer<C>`] reads the bearer token like
+//! [`OAuth2PasswordBearer`](crate::OAuth2PasswordBearer), verifies its
+//! signature (HS256 or RS256), checks the `exp`, `nbf`, `aud` and `iss`
+//! claims, and deserializes the claims into `C`. The [`JwtValidator`] doing
+//! the work is taken from the application state:
+//!
+//! ```ignore
+//! #[derive(Deserialize)]
+//! struct User { sub: String, scope: String }
+//!
+//! let validator = JwtValidator::new()
+//!     .key(DecodingKey::rsa_pem(PUBLIC_KEY_PEM)?)
+//!     .issuer("https://auth.example.com")
+//!     .audience("api");
+//! let state = AppState::new().with(validator);
+//!
+//! #[get("/me")]
+//! async fn me(cx: &Cx, user: JwtBearer<User>) -> String {
+//!     user.claims.sub.clone()
+//! }
+//! ```
+//!
+//! Keys are parsed once and cached. Keys from a JWKS document are indexed by
+//! `kid`; a token naming an unknown `kid` triggers the optional
+//! [`jwks_refresh`](JwtValidator::jwks_refresh) hook, at most once per
+//! interval, so rotated signing keys are picked up without a restart.
+//!
+//! Signatures are checked with the key the validator selected, and each key
+//! only verifies its own algorithm: an HMAC secret never verifies an RS256
+//! token and vice versa, and `alg: none` is always rejected.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use parking_lot::{Mutex, RwLock};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::context::RequestContext;
+use crate::extract::{
+    AppState, FromRequest, OAuth2PasswordBearer, OAuth2PasswordBearerConfig, SecurityExtractor,
+};
+use crate::password::{base64_decode, constant_time_eq, hmac_sha256, sha256};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response, ResponseBody, StatusCode};
+
+/// A signature algorithm supported by [`JwtValidator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JwtAlgorithm {
+    /// HMAC with SHA-256.
+    Hs256,
+    /// RSASSA-PKCS1-v1_5 with SHA-256.
+    Rs256,
+}
+
+impl JwtAlgorithm {
+    /// The `alg` header value.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hs256 => "HS256",
+            Self::Rs256 => "RS256",
+        }
+    }
+}
+
+/// A key that verifies token signatures.
+#[derive(Clone)]
+pub struct DecodingKey {
+    kind: KeyKind,
+}
+
+#[derive(Clone)]
+enum KeyKind {
+    Hmac(Arc<[u8]>),
+    Rsa(Arc<RsaPublicKey>),
+}
+
+impl DecodingKey {
+    /// An HS256 shared secret.
+    #[must_use]
+    pub fn hmac(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            kind: KeyKind::Hmac(Arc::from(secret.as_ref())),
+        }
+    }
+
+    /// An RS256 public key from its big-endian modulus and exponent.
+    pub fn rsa_components(modulus: &[u8], exponent: &[u8]) -> Result<Self, JwtError> {
+        Ok(Self {
+            kind: KeyKind::Rsa(Arc::new(RsaPublicKey::new(modulus, exponent)?)),
+        })
+    }
+
+    /// An RS256 public key in PEM form, either `PUBLIC KEY`
+    /// (SubjectPublicKeyInfo) or `RSA PUBLIC KEY` (PKCS#1).
+    pub fn rsa_pem(pem: &str) -> Result<Self, JwtError> {
+        let invalid = || JwtError::invalid_key("not an RSA public key PEM");
+        let begin = pem.find("-----BEGIN ").ok_or_else(invalid)?;
+        let rest = &pem[begin + "-----BEGIN ".len()..];
+        let label_end = rest.find("-----").ok_or_else(invalid)?;
+        let label = &rest[..label_end];
+        let body = &rest[label_end + 5..];
+        let body = &body[..body.find("-----END").ok_or_else(invalid)?];
+        let encoded: String = body.chars().filter(|c| !c.is_whitespace()).collect();
+        let der = base64_decode(&encoded).ok_or_else(invalid)?;
+
+        let pkcs1 = match label {
+            "PUBLIC KEY" => spki_rsa_key(&der).ok_or_else(invalid)?,
+            "RSA PUBLIC KEY" => der.as_slice(),
+            _ => return Err(invalid()),
+        };
+        let (modulus, exponent) = pkcs1_components(pkcs1).ok_or_else(invalid)?;
+        Self::rsa_components(modulus, exponent)
+    }
+
+    /// A key from a JWK object: `kty: RSA` (`n`, `e`) or `kty: oct` (`k`).
+    pub fn from_jwk(jwk: &Value) -> Result<Self, JwtError> {
+        let field = |name: &str| {
+            jwk.get(name)
+                .and_then(Value::as_str)
+                .and_then(base64url_decode)
+                .ok_or_else(|| JwtError::invalid_key(format!("JWK is missing `{name}`")))
+        };
+        match jwk.get("kty").and_then(Value::as_str) {
+            Some("RSA") => Self::rsa_components(&field("n")?, &field("e")?),
+            Some("oct") => Ok(Self::hmac(field("k")?)),
+            _ => Err(JwtError::invalid_key("unsupported JWK key type")),
+        }
+    }
+
+    /// The algorithm this key verifies.
+    #[must_use]
+    pub fn algorithm(&self) -> JwtAlgorithm {
+        match self.kind {
+            KeyKind::Hmac(_) => JwtAlgorithm::Hs256,
+            KeyKind::Rsa(_) => JwtAlgorithm::Rs256,
+        }
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match &self.kind {
+            KeyKind::Hmac(secret) => constant_time_eq(&hmac_sha256(secret, message), signature),
+            KeyKind::Rsa(key) => key.verify_sha256(message, signature),
+        }
+    }
+}
+
+impl fmt::Debug for DecodingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecodingKey")
+            .field("algorithm", &self.algorithm())
+            .finish_non_exhaustive()
+    }
+}
+
+type JwksFetch = Arc<dyn Fn() -> Option<Value> + Send + Sync>;
+
+/// Verifies tokens and their registered claims.
+///
+/// Clones share the key cache. Defaults: `exp` is required, 60 seconds of
+/// clock leeway, and `iss`/`aud` are not checked until configured.
+#[derive(Clone)]
+pub struct JwtValidator {
+    default_key: Option<DecodingKey>,
+    keys: Arc<RwLock<HashMap<String, DecodingKey>>>,
+    issuer: Option<String>,
+    audiences: Vec<String>,
+    leeway: Duration,
+    require_exp: bool,
+    refresh: Option<JwksFetch>,
+    refresh_interval: Duration,
+    last_refresh: Arc<Mutex<Option<Instant>>>,
+}
+
+impl JwtValidator {
+    /// A validator with no keys; add them with [`key`](Self::key),
+    /// [`key_id`](Self::key_id) or [`load_jwks`](Self::load_jwks).
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            default_key: None,
+            keys: Arc::new(RwLock::new(HashMap::new())),
+            issuer: None,
+            audiences: Vec::new(),
+            leeway: Duration::from_secs(60),
+            require_exp: true,
+            refresh: None,
+            refresh_interval: Duration::from_secs(60),
+            last_refresh: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Key for tokens without a `kid`. Tokens naming a `kid` are only
+    /// checked against the key registered under it.
+    #[must_use]
+    pub fn key(mut self, key: DecodingKey) -> Self {
+        self.default_key = Some(key);
+        self
+    }
+
+    /// Key for tokens whose header names `kid`.
+    #[must_use]
+    pub fn key_id(self, kid: impl Into<String>, key: DecodingKey) -> Self {
+        self.keys.write().insert(kid.into(), key);
+        self
+    }
+
+    /// Require this `iss` claim.
+    #[must_use]
+    pub fn issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Accept tokens whose `aud` includes `audience`. May be called more
+    /// than once; any configured audience is then required.
+    #[must_use]
+    pub fn audience(mut self, audience: impl Into<String>) -> Self {
+        self.audiences.push(audience.into());
+        self
+    }
+
+    /// Clock skew tolerated when checking `exp` and `nbf`.
+    #[must_use]
+    pub fn leeway(mut self, leeway: Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Whether tokens without `exp` are rejected (default: true).
+    #[must_use]
+    pub fn require_exp(mut self, require: bool) -> Self {
+        self.require_exp = require;
+        self
+    }
+
+    /// Called for tokens naming an unknown `kid`, at most once per
+    /// `interval`; a returned JWKS document replaces the cached keys.
+    ///
+    /// The hook runs on the request path, so it should return quickly, for
+    /// example from a document a background task keeps fresh.
+    #[must_use]
+    pub fn jwks_refresh(
+        mut self,
+        fetch: impl Fn() -> Option<Value> + Send + Sync + 'static,
+        interval: Duration,
+    ) -> Self {
+        self.refresh = Some(Arc::new(fetch));
+        self.refresh_interval = interval;
+        self
+    }
+
+    /// Replace the `kid`-indexed keys with those of a JWKS document
+    /// (`{"keys": [...]}`), returning how many were loaded.
+    ///
+    /// Keys without a `kid` or of an unsupported type are skipped.
+    pub fn load_jwks(&self, jwks: &Value) -> Result<usize, JwtError> {
+        let entries = jwks
+            .get("keys")
+            .and_then(Value::as_array)
+            .ok_or_else(|| JwtError::invalid_key("JWKS document has no `keys` array"))?;
+        let keys: HashMap<String, DecodingKey> = entries
+            .iter()
+            .filter_map(|jwk| {
+                let kid = jwk.get("kid").and_then(Value::as_str)?;
+                Some((kid.to_string(), DecodingKey::from_jwk(jwk).ok()?))
+            })
+            .collect();
+        let loaded = keys.len();
+        *self.keys.write() = keys;
+        Ok(loaded)
+    }
+
+    /// Verify `token` and deserialize its claims.
+    pub fn decode<C: DeserializeOwned>(&self, token: &str) -> Result<C, JwtError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.decode_at(token, now)
+    }
+
+    fn decode_at<C: DeserializeOwned>(&self, token: &str, now: u64) -> Result<C, JwtError> {
+        let mut parts = token.split('.');
+        let (Some(header), Some(payload), Some(signature_part), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(JwtError::new(JwtErrorKind::Malformed));
+        };
+        let header: Value = decode_segment(header)?;
+        let alg = header.get("alg").and_then(Value::as_str).unwrap_or("none");
+        let kid = header.get("kid").and_then(Value::as_str);
+
+        let key = self
+            .select_key(kid)
+            .ok_or_else(|| JwtError::new(JwtErrorKind::UnknownKey))?;
+        if alg != key.algorithm().as_str() {
+            return Err(JwtError::new(JwtErrorKind::UnsupportedAlgorithm));
+        }
+        let signature = base64url_decode(signature_part)
+            .ok_or_else(|| JwtError::new(JwtErrorKind::Malformed))?;
+        let signed = &token[..token.len() - signature_part.len() - 1];
+        if !key.verify(signed.as_bytes(), &signature) {
+            return Err(JwtError::new(JwtErrorKind::InvalidSignature));
+        }
+
+        let claims: Value = decode_segment(payload)?;
+        self.check_claims(&claims, now)?;
+        serde_json::from_value(claims)
+            .map_err(|err| JwtError::new(JwtErrorKind::InvalidClaims).with_message(err.to_string()))
+    }
+
+    fn select_key(&self, kid: Option<&str>) -> Option<DecodingKey> {
+        let Some(kid) = kid else {
+            return self.default_key.clone();
+        };
+        let cached = self.keys.read().get(kid).cloned();
+        cached.or_else(|| {
+            self.try_refresh()
+                .then(|| self.keys.read().get(kid).cloned())
+                .flatten()
+        })
+    }
+
+    /// Runs the refresh hook unless it ran within the interval.
+    fn try_refresh(&self) -> bool {
+        let Some(fetch) = &self.refresh else {
+            return false;
+        };
+        {
+            let mut last = self.last_refresh.lock();
+            if last.is_some_and(|at| at.elapsed() < self.refresh_interval) {
+                return false;
+            }
+            *last = Some(Instant::now());
+        }
+        fetch().is_some_and(|jwks| self.load_jwks(&jwks).is_ok())
+    }
+
+    // NumericDates may be fractional (RFC 7519 section 2), so compare as
+    // floats; second-resolution timestamps are exact well past year 10000.
+    #[allow(clippy::cast_precision_loss)]
+    fn check_claims(&self, claims: &Value, now: u64) -> Result<(), JwtError> {
+        let leeway = self.leeway.as_secs_f64();
+        let now = now as f64;
+        match claims.get("exp").map(Value::as_f64) {
+            Some(Some(exp)) if exp + leeway <= now => {
+                return Err(JwtError::new(JwtErrorKind::Expired));
+            }
+            Some(None) => return Err(JwtError::new(JwtErrorKind::InvalidClaims)),
+            None if self.require_exp => {
+                return Err(JwtError::new(JwtErrorKind::MissingExpiration));
+            }
+            _ => {}
+        }
+        match claims.get("nbf").map(Value::as_f64) {
+            Some(Some(nbf)) if nbf > now + leeway => {
+                return Err(JwtError::new(JwtErrorKind::NotYetValid));
+            }
+            Some(None) => return Err(JwtError::new(JwtErrorKind::InvalidClaims)),
+            _ => {}
+        }
+        let iss = claims.get("iss").and_then(Value::as_str);
+        if self
+            .issuer
+            .as_deref()
+            .is_some_and(|issuer| iss != Some(issuer))
+        {
+            return Err(JwtError::new(JwtErrorKind::InvalidIssuer));
+        }
+        if !self.audiences.is_empty() {
+            let accepted = |aud: &Value| {
+                aud.as_str()
+                    .is_some_and(|aud| self.audiences.iter().any(|a| a == aud))
+            };
+            let ok = match claims.get("aud") {
+                Some(Value::Array(auds)) => auds.iter().any(accepted),
+                Some(aud) => accepted(aud),
+                None => false,
+            };
+            if !ok {
+                return Err(JwtError::new(JwtErrorKind::InvalidAudience));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for JwtValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for JwtValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtValidator")
+            .field("default_key", &self.default_key)
+            .field("key_ids", &self.keys.read().keys().collect::<Vec<_>>())
+            .field("issuer", &self.issuer)
+            .field("audiences", &self.audiences)
+            .field("leeway", &self.leeway)
+            .field("require_exp", &self.require_exp)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Decodes one base64url-encoded JSON segment.
+fn decode_segment<T: DeserializeOwned>(segment: &str) -> Result<T, JwtError> {
+    base64url_decode(segment)
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or_else(|| JwtError::new(JwtErrorKind::Malformed))
+}
+
+/// Base64url without padding, as used throughout JOSE.
+fn base64url_decode(input: &str) -> Option<Vec<u8>> {
+    if input.contains(['+', '/', '=']) {
+        return None;
+    }
+    base64_decode(&input.replace('-', "+").replace('_', "/"))
+}
+
+// ============================================================================
+// RSA signature verification (no external crypto crates)
+// ============================================================================
+
+/// DER `AlgorithmIdentifier` + `OCTET STRING` prefix of a SHA-256 DigestInfo.
+const SHA256_DIGEST_INFO: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+/// `rsaEncryption` object identifier (1.2.840.113549.1.1.1).
+const RSA_ENCRYPTION_OID: [u8; 9] = [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+
+/// An RSA public key with its Montgomery constants precomputed.
+struct RsaPublicKey {
+    /// Modulus, little-endian 32-bit limbs.
+    n: Vec<u32>,
+    /// Public exponent, big-endian bytes.
+    e: Vec<u8>,
+    /// Modulus length in bytes.
+    size: usize,
+    /// `-n⁻¹ mod 2³²`.
+    n0_inv: u32,
+    /// `R² mod n` with `R = 2^(32 * limbs)`.
+    r2: Vec<u32>,
+}
+
+impl RsaPublicKey {
+    fn new(modulus: &[u8], exponent: &[u8]) -> Result<Self, JwtError> {
+        let modulus = strip_leading_zeros(modulus);
+        let exponent = strip_leading_zeros(exponent);
+        if modulus.len() < 256 {
+            return Err(JwtError::invalid_key(
+                "RSA modulus must be at least 2048 bits",
+            ));
+        }
+        if modulus[modulus.len() - 1] & 1 == 0 || exponent.is_empty() {
+            return Err(JwtError::invalid_key("invalid RSA public key"));
+        }
+        let n = limbs_from_be(modulus, modulus.len().div_ceil(4));
+
+        // Newton's iteration doubles the correct low bits each round.
+        let mut inv = 1u32;
+        for _ in 0..5 {
+            inv = inv.wrapping_mul(2u32.wrapping_sub(n[0].wrapping_mul(inv)));
+        }
+
+        // R² mod n by doubling 1 modulo n.
+        let mut r2 = vec![0u32; n.len()];
+        r2[0] = 1;
+        for _ in 0..64 * n.len() {
+            let carry = shl1(&mut r2);
+            if carry || !less_than(&r2, &n) {
+                sub_assign(&mut r2, &n);
+            }
+        }
+
+        Ok(Self {
+            size: modulus.len(),
+            e: exponent.to_vec(),
+            n0_inv: inv.wrapping_neg(),
+            r2,
+            n,
+        })
+    }
+
+    /// RSASSA-PKCS1-v1_5 verification with SHA-256.
+    fn verify_sha256(&self, message: &[u8], signature: &[u8]) -> bool {
+        if signature.len() != self.size {
+            return false;
+        }
+        let s = limbs_from_be(signature, self.n.len());
+        if !less_than(&s, &self.n) {
+            return false;
+        }
+        let decoded = limbs_to_be(&self.pow_e(&s), self.size);
+
+        let mut expected = Vec::with_capacity(self.size);
+        expected.extend_from_slice(&[0x00, 0x01]);
+        expected.resize(self.size - SHA256_DIGEST_INFO.len() - 32 - 1, 0xff);
+        expected.push(0x00);
+        expected.extend_from_slice(&SHA256_DIGEST_INFO);
+        expected.extend_from_slice(&sha256(message));
+        constant_time_eq(&decoded, &expected)
+    }
+
+    /// `base^e mod n` by square-and-multiply in Montgomery form.
+    fn pow_e(&self, base: &[u32]) -> Vec<u32> {
+        let mut one = vec![0u32; self.n.len()];
+        one[0] = 1;
+        let base = self.mont_mul(base, &self.r2);
+        let mut acc = self.mont_mul(&one, &self.r2);
+        for byte in &self.e {
+            for bit in (0..8).rev() {
+                acc = self.mont_mul(&acc, &acc);
+                if (byte >> bit) & 1 == 1 {
+                    acc = self.mont_mul(&acc, &base);
+                }
+            }
+        }
+        self.mont_mul(&acc, &one)
+    }
+
+    /// `a * b * R⁻¹ mod n` (CIOS Montgomery multiplication).
+    #[allow(clippy::many_single_char_names)]
+    fn mont_mul(&self, a: &[u32], b: &[u32]) -> Vec<u32> {
+        let n = &self.n;
+        let len = n.len();
+        let mut t = vec![0u32; len + 2];
+        for &b_i in b {
+            let mut carry = 0u64;
+            for j in 0..len {
+                let v = u64::from(t[j]) + u64::from(a[j]) * u64::from(b_i) + carry;
+                t[j] = low(v);
+                carry = v >> 32;
+            }
+            let v = u64::from(t[len]) + carry;
+            t[len] = low(v);
+            t[len + 1] = low(v >> 32);
+
+            let m = t[0].wrapping_mul(self.n0_inv);
+            let mut carry = (u64::from(t[0]) + u64::from(m) * u64::from(n[0])) >> 32;
+            for j in 1..len {
+                let v = u64::from(t[j]) + u64::from(m) * u64::from(n[j]) + carry;
+                t[j - 1] = low(v);
+                carry = v >> 32;
+            }
+            let v = u64::from(t[len]) + carry;
+            t[len - 1] = low(v);
+            t[len] = t[len + 1] + low(v >> 32);
+            t[len + 1] = 0;
+        }
+        let overflow = t[len] != 0;
+        t.truncate(len);
+        if overflow || !less_than(&t, n) {
+            sub_assign(&mut t, n);
+        }
+        t
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)] // keeping the low half is the point
+fn low(value: u64) -> u32 {
+    value as u32
+}
+
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+/// Big-endian bytes to `len` little-endian limbs (extra high bytes dropped).
+fn limbs_from_be(bytes: &[u8], len: usize) -> Vec<u32> {
+    let mut limbs = vec![0u32; len];
+    for (i, chunk) in bytes.rchunks(4).take(len).enumerate() {
+        limbs[i] = chunk.iter().fold(0, |acc, &b| (acc << 8) | u32::from(b));
+    }
+    limbs
+}
+
+/// Little-endian limbs to exactly `size` big-endian bytes.
+fn limbs_to_be(limbs: &[u32], size: usize) -> Vec<u8> {
+    let bytes: Vec<u8> = limbs.iter().rev().flat_map(|l| l.to_be_bytes()).collect();
+    bytes[bytes.len() - size..].to_vec()
+}
+
+fn less_than(a: &[u32], b: &[u32]) -> bool {
+    for (x, y) in a.iter().rev().zip(b.iter().rev()) {
+        if x != y {
+            return x < y;
+        }
+    }
+    false
+}
+
+/// `a -= b`, wrapping.
+fn sub_assign(a: &mut [u32], b: &[u32]) {
+    let mut borrow = false;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let (d, b1) = x.overflowing_sub(y);
+        let (d, b2) = d.overflowing_sub(u32::from(borrow));
+        *x = d;
+        borrow = b1 || b2;
+    }
+}
+
+/// `a <<= 1`, returning the bit shifted out.
+fn shl1(a: &mut [u32]) -> bool {
+    let mut carry = 0;
+    for limb in a.iter_mut() {
+        let next = *limb >> 31;
+        *limb = (*limb << 1) | carry;
+        carry = next;
+    }
+    carry == 1
+}
+
+/// Reads one DER element with the given tag, returning its contents and the
+/// remaining input.
+fn der_element(input: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    let (&first, rest) = input.split_first()?;
+    if first != tag {
+        return None;
+    }
+    let (&len_byte, rest) = rest.split_first()?;
+    let (len, rest) = if len_byte < 0x80 {
+        (usize::from(len_byte), rest)
+    } else {
+        let count = usize::from(len_byte & 0x7f);
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let len = rest[..count]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 8) | usize::from(b));
+        (len, &rest[count..])
+    };
+    (rest.len() >= len).then(|| rest.split_at(len))
+}
+
+/// The PKCS#1 `RSAPublicKey` inside a SubjectPublicKeyInfo.
+fn spki_rsa_key(der: &[u8]) -> Option<&[u8]> {
+    let (spki, _) = der_element(der, 0x30)?;
+    let (algorithm, rest) = der_element(spki, 0x30)?;
+    let (oid, _) = der_element(algorithm, 0x06)?;
+    if oid != RSA_ENCRYPTION_OID {
+        return None;
+    }
+    let (bits, _) = der_element(rest, 0x03)?;
+    // The first byte counts unused bits, which must be zero for a key.
+    bits.split_first()
+        .filter(|(unused, _)| **unused == 0)
+        .map(|(_, key)| key)
+}
+
+/// Modulus and exponent of a PKCS#1 `RSAPublicKey`.
+fn pkcs1_components(der: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (key, _) = der_element(der, 0x30)?;
+    let (modulus, rest) = der_element(key, 0x02)?;
+    let (exponent, _) = der_element(rest, 0x02)?;
+    Some((modulus, exponent))
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+/// The specific kind of JWT error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JwtErrorKind {
+    /// No bearer token in the request.
+    Missing,
+    /// The token is not three base64url JSON segments.
+    Malformed,
+    /// `alg` is not the algorithm of the selected key.
+    UnsupportedAlgorithm,
+    /// No key matches the token.
+    UnknownKey,
+    /// The signature does not verify.
+    InvalidSignature,
+    /// `exp` has passed.
+    Expired,
+    /// `exp` is missing while required.
+    MissingExpiration,
+    /// `nbf` is in the future.
+    NotYetValid,
+    /// `aud` does not name an accepted audience.
+    InvalidAudience,
+    /// `iss` is not the expected issuer.
+    InvalidIssuer,
+    /// The claims do not have the expected shape.
+    InvalidClaims,
+    /// A configured key could not be parsed.
+    InvalidKey,
+    /// No [`JwtValidator`] is registered in the application state.
+    NotConfigured,
+}
+
+/// Error when a JWT cannot be accepted.
+#[derive(Debug, Clone)]
+pub struct JwtError {
+    /// The kind of error.
+    pub kind: JwtErrorKind,
+    /// Extra detail, for logs; never sent to the client.
+    pub message: Option<String>,
+}
+
+impl JwtError {
+    /// Create an error of the given kind.
+    #[must_use]
+    pub fn new(kind: JwtErrorKind) -> Self {
+        Self {
+            kind,
+            message: None,
+        }
+    }
+
+    /// Attach extra detail.
+    #[must_use]
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    fn invalid_key(message: impl Into<String>) -> Self {
+        Self::new(JwtErrorKind::InvalidKey).with_message(message)
+    }
+}
+
+impl fmt::Display for JwtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let summary = match self.kind {
+            JwtErrorKind::Missing => "Missing bearer token",
+            JwtErrorKind::Malformed => "Malformed token",
+            JwtErrorKind::UnsupportedAlgorithm => "Unsupported token algorithm",
+            JwtErrorKind::UnknownKey => "No key for token",
+            JwtErrorKind::InvalidSignature => "Invalid token signature",
+            JwtErrorKind::Expired => "Token expired",
+            JwtErrorKind::MissingExpiration => "Token has no expiration",
+            JwtErrorKind::NotYetValid => "Token not yet valid",
+            JwtErrorKind::InvalidAudience => "Invalid token audience",
+            JwtErrorKind::InvalidIssuer => "Invalid token issuer",
+            JwtErrorKind::InvalidClaims => "Invalid token claims",
+            JwtErrorKind::InvalidKey => "Invalid decoding key",
+            JwtErrorKind::NotConfigured => "JwtValidator not registered in app state",
+        };
+        match &self.message {
+            Some(message) => write!(f, "{summary}: {message}"),
+            None => f.write_str(summary),
+        }
+    }
+}
+
+impl std::error::Error for JwtError {}
+
+impl IntoResponse for JwtError {
+    fn into_response(self) -> Response {
+        let (status, detail, challenge): (_, _, &[u8]) = match self.kind {
+            JwtErrorKind::InvalidKey | JwtErrorKind::NotConfigured => {
+                return crate::HttpError::internal().into_response();
+            }
+            JwtErrorKind::Missing => (StatusCode::UNAUTHORIZED, "Not authenticated", b"Bearer"),
+            _ => (
+                StatusCode::UNAUTHORIZED,
+                "Could not validate credentials",
+                b"Bearer error=\"invalid_token\"",
+            ),
+        };
+        let body = serde_json::json!({ "detail": detail });
+        Response::with_status(status)
+            .header("www-authenticate", challenge.to_vec())
+            .header("content-type", b"application/json".to_vec())
+            .body(ResponseBody::Bytes(body.to_string().into_bytes()))
+    }
+}
+
+// ============================================================================
+// Extractor
+// ============================================================================
+
+/// A verified JWT bearer token and its claims.
+///
+/// Requires a [`JwtValidator`] in the application state. Missing tokens get
+/// a 401 `Not authenticated`; tokens that fail validation get a 401 with
+/// `WWW-Authenticate: Bearer error="invalid_token"`. Documented in OpenAPI
+/// as the `OAuth2PasswordBearer` scheme.
+#[derive(Debug, Clone)]
+pub struct JwtBearer<C> {
+    /// The deserialized claims.
+    pub claims: C,
+    /// The raw token.
+    pub token: String,
+}
+
+impl<C> JwtBearer<C> {
+    /// Consume self and return the claims.
+    pub fn into_claims(self) -> C {
+        self.claims
+    }
+}
+
+impl<C> Deref for JwtBearer<C> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        &self.claims
+    }
+}
+
+impl<C: DeserializeOwned + Send + 'static> FromRequest for JwtBearer<C> {
+    type Error = JwtError;
+
+    async fn from_request(ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
+        let token = OAuth2PasswordBearer::from_request(ctx, req)
+            .await
+            .map_err(|_| JwtError::new(JwtErrorKind::Missing))?
+            .into_token();
+        let validator = req
+            .get_extension::<AppState>()
+            .and_then(AppState::get::<JwtValidator>)
+            .ok_or_else(|| JwtError::new(JwtErrorKind::NotConfigured))?;
+        let claims = validator.decode(&token)?;
+        Ok(Self { claims, token })
+    }
+}
+
+impl<C> SecurityExtractor for JwtBearer<C> {
+    const SCHEME_NAME: &'static str = "OAuth2PasswordBearer";
+
+    fn security_scheme() -> fastapi_openapi::SecurityScheme {
+        OAuth2PasswordBearerConfig::default().security_scheme()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::password::base64_encode;
+
+    const RSA_PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEAr6FmGp35ELGxhwjQmn+r
+Lx5u6a6CmLjn5dp9BgMC6IUlwxlEH43Kja2ud3zC/rp5K7yq80e43Vx2er7iY/ty
+wGFuHWzLdWgOElgRF89npo/uPnwPtEqjwsfNj7OUvwwoYEL7ZNCm+5SsvbKWyV33
+pa1YeID6tdoLv0UIE5gvYthOXRb9d4wdCh4FlUd9Pt6fI45WUSlwC+MIBw6kkwb/
+hfyaHFMxSrAuHuSi6Op+2fVPRQfhge9fOWUwyj80Gc7umweDZpz+kJA0vsrTo+HZ
+YJzxOV/AudNbV9Xfrl05FswE/EPKuyCtO6YPIQjSz+5k7gyKZ0l54DXNnD/8tBB1
+jwIDAQAB
+-----END PUBLIC KEY-----";
+
+    /// RS256, `kid: k1`, claims `sub: alice`, `aud: api`,
+    /// `iss: https://issuer.example`, `exp: 4102444800`.
+    const RS256_TOKEN: &str = "eyJhbGciOiJSUzI1NiIsInR5cCI6IkpXVCIsImtpZCI6ImsxIn0.\
+eyJzdWIiOiJhbGljZSIsImF1ZCI6ImFwaSIsImlzcyI6Imh0dHBzOi8vaXNzdWVyLmV4YW1wbGUiLCJleHAiOjQxMDI0NDQ4MDB9.\
+XoS4-fDDI6sXYMh7b3DlPyG-XagGtAJeXlYVz-m316_fitw09Xj16ea-6XbMiM9ouPXoa_a4mV_eFqWdp-ftLHFscngHb7Tf9\
+lBSHSggj_iFnQcaJbgHeXEk43ZbqHx11NG3HxpvdLji6sDCnI_zWyu2qfMXh_Ev-we-HKcK-Jw2Wv7pkof8d02ZivypKQmMX9S\
+VKeSVnI5i3AO3xueW5lZKA0An7XpJcL1N10mHoBWuJfbrPQ6I3M3Yimx6838DsGNMngwoawY87vHWNAPvJOUpHwoK_2YJGQTog\
+WRZgZe4mzTwU2np3NOgg4kJIau2qm9KJ3VFfUnOUixeAblYwg";
+
+    fn base64url(data: &[u8]) -> String {
+        base64_encode(data).replace('+', "-").replace('/', "_")
+    }
+
+    fn hs256_token(secret: &[u8], claims: &Value) -> String {
+        let signed = format!(
+            "{}.{}",
+            base64url(br#"{"alg":"HS256","typ":"JWT"}"#),
+            base64url(claims.to_string().as_bytes())
+        );
+        let signature = hmac_sha256(secret, signed.as_bytes());
+        format!("{signed}.{}", base64url(&signature))
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Claims {
+        sub: String,
+    }
+
+    #[test]
+    fn verifies_rs256_against_a_pem_key() {
+        let validator = JwtValidator::new()
+            .key_id("k1", DecodingKey::rsa_pem(RSA_PUBLIC_KEY).unwrap())
+            .issuer("https://issuer.example")
+            .audience("api");
+        let claims: Claims = validator.decode(RS256_TOKEN).unwrap();
+        assert_eq!(claims.sub, "alice");
+
+        let tampered = RS256_TOKEN.replace("eyJzdWIiOiJhbGljZSIs", "eyJzdWIiOiJhbGljZUIs");
+        let err = validator.decode::<Claims>(&tampered).unwrap_err();
+        assert_eq!(err.kind, JwtErrorKind::InvalidSignature);
+
+        let wrong_audience = JwtValidator::new()
+            .key_id("k1", DecodingKey::rsa_pem(RSA_PUBLIC_KEY).unwrap())
+            .audience("other");
+        let err = wrong_audience.decode::<Claims>(RS256_TOKEN).unwrap_err();
+        assert_eq!(err.kind, JwtErrorKind::InvalidAudience);
+    }
+
+    #[test]
+    fn checks_hs256_signature_and_time_claims() {
+        let validator = JwtValidator::new()
+            .key(DecodingKey::hmac("secret"))
+            .leeway(Duration::ZERO);
+        let token = hs256_token(b"secret", &serde_json::json!({"sub": "bob", "exp": 2000}));
+        let claims: Claims = validator.decode_at(&token, 1000).unwrap();
+        assert_eq!(claims.sub, "bob");
+
+        let err = validator.decode_at::<Claims>(&token, 2000).unwrap_err();
+        assert_eq!(err.kind, JwtErrorKind::Expired);
+
+        let early = hs256_token(b"secret", &serde_json::json!({"exp": 2000, "nbf": 1500}));
+        let err = validator.decode_at::<Value>(&early, 1000).unwrap_err();
+        assert_eq!(err.kind, JwtErrorKind::NotYetValid);
+
+        let forged = hs256_token(b"guess", &serde_json::json!({"sub": "bob", "exp": 2000}));
+        let err = validator.decode_at::<Claims>(&forged, 1000).unwrap_err();
+        assert_eq!(err.kind, JwtErrorKind::InvalidSignature);
+        assert_eq!(err.into_response().status().as_u16(), 401);
+    }
+
+    #[test]
+    fn hmac_keys_refuse_rs256_tokens() {
+        let validator = JwtValidator::new().key_id("k1", DecodingKey::hmac(RSA_PUBLIC_KEY));
+        let err = validator.decode::<Claims>(RS256_TOKEN).unwrap_err();
+        assert_eq!(err.kind, JwtErrorKind::UnsupportedAlgorithm);
+    }
+
+    #[test]
+    fn unknown_kid_does_not_fall_back_to_the_default_key() {
+        let validator = JwtValidator::new().key(DecodingKey::rsa_pem(RSA_PUBLIC_KEY).unwrap());
+        let err = validator.decode::<Claims>(RS256_TOKEN).unwrap_err();
+        assert_eq!(err.kind, JwtErrorKind::UnknownKey);
+    }
+
+    #[test]
+    fn fractional_dates_and_missing_exp() {
+        let validator = JwtValidator::new()
+            .key(DecodingKey::hmac("secret"))
+            .leeway(Duration::ZERO);
+        let token = hs256_token(b"secret", &serde_json::json!({"exp": 1000.5, "nbf": 999.5}));
+        assert!(validator.decode_at::<Value>(&token, 1000).is_ok());
+        let err = validator.decode_at::<Value>(&token, 1001).unwrap_err();
+        assert_eq!(err.kind, JwtErrorKind::Expired);
+
+        let no_exp = hs256_token(b"secret", &serde_json::json!({"sub": "bob"}));
+        let err = validator.decode_at::<Value>(&no_exp, 1000).unwrap_err();
+        assert_eq!(err.kind, JwtErrorKind::MissingExpiration);
+        let optional = validator.require_exp(false);
+        assert!(optional.decode_at::<Value>(&no_exp, 1000).is_ok());
+    }
+
+    #[test]
+    fn unknown_kids_trigger_a_jwks_refresh() {
+        let jwks = serde_json::json!({"keys": [{
+            "kty": "RSA",
+            "kid": "k1",
+            "e": "AQAB",
+            "n": "r6FmGp35ELGxhwjQmn-rLx5u6a6CmLjn5dp9BgMC6IUlwxlEH43Kja2ud3zC_rp5K7yq80e43Vx2er7iY_tywGFuHWzLdWgOElgRF89npo_uPnwPtEqjwsfNj7OUvwwoYEL7ZNCm-5SsvbKWyV33pa1YeID6tdoLv0UIE5gvYthOXRb9d4wdCh4FlUd9Pt6fI45WUSlwC-MIBw6kkwb_hfyaHFMxSrAuHuSi6Op-2fVPRQfhge9fOWUwyj80Gc7umweDZpz-kJA0vsrTo-HZYJzxOV_AudNbV9Xfrl05FswE_EPKuyCtO6YPIQjSz-5k7gyKZ0l54DXNnD_8tBB1jw",
+        }]});
+        let fetches = Arc::new(Mutex::new(0));
+        let counter = Arc::clone(&fetches);
+        let validator = JwtValidator::new().jwks_refresh(
+            move || {
+                *counter.lock() += 1;
+                Some(jwks.clone())
+            },
+            Duration::from_secs(60),
+        );
+
+        let claims: Claims = validator.decode(RS256_TOKEN).unwrap();
+        assert_eq!(claims.sub, "alice");
+        assert!(validator.decode::<Claims>(RS256_TOKEN).is_ok());
+        assert_eq!(*fetches.lock(), 1);
+    }
+}
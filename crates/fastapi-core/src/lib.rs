@@ -51,66 +51,140 @@
 #![allow(clippy::elidable_lifetime_names)]
 #![allow(clippy::map_unwrap_or)]
 
+pub mod access_log;
 pub mod app;
+pub mod audit;
+pub mod batch;
+mod blocking;
+pub mod body_channel;
+pub mod broadcast;
+pub mod bulkhead;
+pub mod check;
+pub mod circuit_breaker;
+pub mod clock;
 mod context;
 pub mod coverage;
 mod dependency;
+mod dependency_graph;
 pub mod digest;
 pub mod docs;
+pub mod embed;
 pub mod error;
+pub mod error_bodies;
+pub mod error_reporting;
 mod extract;
+pub mod header_policy;
+pub mod headers;
+pub mod health;
+pub mod interop;
+pub mod ip_filter;
+pub mod json_case;
+pub mod json_patch;
+pub mod jwt;
 pub mod logging;
+pub mod merge_patch;
+pub mod metrics;
 pub mod middleware;
 pub mod multipart;
 mod password;
+pub mod problem;
+pub mod profiling;
 mod request;
 mod response;
+pub mod response_cache;
 pub mod routing;
+pub mod scheduler;
+pub mod security;
+pub mod session;
 pub mod shutdown;
+pub mod slow_request;
+pub mod sse;
+pub mod template;
 #[cfg(feature = "testing")]
 pub mod testing;
+pub mod timeout;
+pub mod trace_context;
+pub mod upload_policy;
 pub mod validation;
+pub mod variant;
+pub mod wasi;
 pub mod websocket;
 
-pub use context::{CancelledError, IntoOutcome, RequestContext};
+pub use access_log::{AccessLog, AccessLogFormat};
+pub use audit::{AuditLog, AuditRecord};
+pub use batch::{Batch, BatchRequest, BatchResponse};
+pub use body_channel::{BodyClosed, BodySender, ChannelBody, body_channel};
+pub use broadcast::{Broadcast, Member, MemberId, Overflow, RecvError, RoomEvent, Rooms};
+pub use bulkhead::{Bulkhead, BulkheadMiddleware, BulkheadPermit};
+pub use circuit_breaker::{BreakerState, BreakerStats, CircuitBreaker, CircuitBreakerMiddleware};
+pub use context::{CancelledError, IntoOutcome, RequestContext, ScopedTask, ScopedTasks};
 pub use dependency::{
-    DefaultConfig, DefaultDependencyConfig, DependencyCache, DependencyOverrides, DependencyScope,
-    Depends, DependsCleanup, DependsConfig, FromDependency, FromDependencyWithCleanup, NoCache,
+    DefaultConfig, DefaultDependencyConfig, DependencyCache, DependencyError, DependencyOverrides,
+    DependencyScope, Depends, DependsCleanup, DependsConfig, FromDependency,
+    FromDependencyWithCleanup, NoCache, OverrideGuard, RequestOutcome, Singleton, SingletonRetry,
+};
+pub use dependency_graph::{
+    DeclaresRequirements, DependencyDecl, DependencyGraph, DependencyProbe,
+    DependencyProbeFallback, DependencyProbeMatch, DependencyRequirement, DependencyTree,
 };
 pub use digest::{DigestAlgorithm, DigestAuth, DigestAuthError, DigestAuthErrorKind, DigestQop};
-pub use error::{HttpError, LocItem, ValidationError, ValidationErrors};
+pub use error::{HandlerError, HttpError, LocItem, ValidationError, ValidationErrors};
 pub use extract::{
-    Accept, ApiKey, ApiKeyConfig, ApiKeyError, ApiKeyErrorKind, ApiKeyLocation, AppState,
-    Authorization, BasicAuth, BasicAuthError, BasicAuthErrorKind, BearerToken, BearerTokenError,
-    BearerTokenErrorKind, ContentType, Cookie, CookieExtractError, CookieExtractErrorKind,
-    CookieName, CsrfToken, CsrfTokenCookie, DEFAULT_JSON_LIMIT, DEFAULT_PAGE, DEFAULT_PER_PAGE,
-    Form, FormExtractError, FormExtractErrorKind, FromHeaderValue, FromRequest, Header,
-    HeaderExtractError, HeaderName, HeaderValues, Host, Json, JsonConfig, JsonExtractError,
-    MAX_PER_PAGE, MultipartExtractError, NamedHeader, OAuth2BearerError, OAuth2BearerErrorKind,
+    Accept, ApiKey, ApiKeyConfig, ApiKeyCookie, ApiKeyError, ApiKeyErrorKind, ApiKeyHeader,
+    ApiKeyLocation, ApiKeyName, ApiKeyParam, ApiKeyQuery, ApiKeyVerifier, AppState, Authorization,
+    BasicAuth, BasicAuthError, BasicAuthErrorKind, BearerToken, BearerTokenError,
+    BearerTokenErrorKind, CompositeExtractError, ContentType, Cookie, CookieExtractError,
+    CookieExtractErrorKind, CookieName, CsrfToken, CsrfTokenCookie, CursorPage, CursorPagination,
+    DEFAULT_JSON_LIMIT, DEFAULT_PAGE, DEFAULT_PER_PAGE, Form, FormExtractError,
+    FormExtractErrorKind, FromHeaderValue, FromRequest, Header, HeaderExtractError, HeaderName,
+    HeaderValues, Host, IntoValidationErrors, Json, JsonConfig, JsonExtractError, MAX_PER_PAGE,
+    MultipartExtractError, NamedHeader, OAuth2BearerError, OAuth2BearerErrorKind,
     OAuth2PasswordBearer, OAuth2PasswordBearerConfig, Page, Pagination, PaginationConfig, Path,
-    PathExtractError, PathParams, Query, QueryExtractError, QueryParams, SessionId, State,
-    StateExtractError, UserAgent, Valid, ValidExtractError, Validate, XRequestId,
-    snake_to_header_case,
+    PathExtractError, PathParams, Query, QueryExtractError, QueryParamDocs, QueryParams,
+    QueryParamsProbe, QueryParamsProbeFallback, QueryParamsProbeMatch, SecurityDefinition,
+    SecurityExtractor, SecurityProbe, SecurityProbeFallback, SecurityProbeMatch, SessionId, State,
+    StateExtractError, StrictJson, TypedPath, UserAgent, Valid, ValidExtractError, Validate,
+    ValidationProbe, ValidationProbeFallback, ValidationProbeMatch, XApiKey, XRequestId,
+    encode_path_param, parse_path_param, snake_to_header_case,
 };
+pub use header_policy::{HeaderPolicy, HeaderPolicyMiddleware, HeaderPolicyStats, RepeatedHeaders};
+pub use health::{DiskCheck, HealthCheck, HealthCheckRegistry, HealthStatus};
+pub use ip_filter::{IpFilterMiddleware, IpFilterMode};
+pub use json_case::JsonKeyCase;
+pub use json_patch::{JSON_PATCH_CONTENT_TYPE, JsonPatch, JsonPatchError, PatchOperation};
+pub use jwt::{DecodingKey, JwtAlgorithm, JwtBearer, JwtError, JwtErrorKind, JwtValidator};
+pub use merge_patch::{MERGE_PATCH_CONTENT_TYPE, MergePatch, MergePatchError};
+pub use metrics::{MetricsMiddleware, MetricsRegistry};
 pub use middleware::{
-    AddResponseHeader, BoxFuture, ControlFlow, Cors, CorsConfig, Handler, Layer, Layered,
-    Middleware, MiddlewareStack, NoopMiddleware, OriginPattern, PathPrefixFilter, ReferrerPolicy,
-    RequestId, RequestIdConfig, RequestIdMiddleware, RequestResponseLogger, RequireHeader,
-    SecurityHeaders, SecurityHeadersConfig, XFrameOptions,
+    AddResponseHeader, BoxFuture, ControlFlow, Cors, CorsConfig, DeprecatedParam, DeprecatedParams,
+    DeprecationHeaders, DeprecationNotice, Handler, Layer, Layered, Middleware, MiddlewareStack,
+    NoopMiddleware, OriginPattern, PathPrefixFilter, ReferrerPolicy, RequestId, RequestIdConfig,
+    RequestIdMiddleware, RequestResponseLogger, RequireHeader, SecurityHeaders,
+    SecurityHeadersConfig, XFrameOptions,
 };
 pub use multipart::{
     DEFAULT_MAX_FIELDS, DEFAULT_MAX_FILE_SIZE, DEFAULT_MAX_TOTAL_SIZE, MultipartConfig,
-    MultipartError, MultipartForm, MultipartParser, Part, UploadFile, parse_boundary,
+    MultipartError, MultipartForm, MultipartParser, MultipartStream, Part, StreamingPart,
+    UploadFile, parse_boundary,
 };
 pub use request::{
-    BackgroundTasks, BackgroundTasksInner, Body, Headers, HttpVersion, Method, Request,
-    RequestBodyStream, RequestBodyStreamError,
+    BackgroundTasks, BackgroundTasksInner, Body, ExtensionMethod, Headers, HttpVersion,
+    InvalidMethodName, Method, Request, RequestBodyStream, RequestBodyStreamError,
 };
 pub use response::{
-    Binary, BodyStream, FileResponse, Html, IntoResponse, Link, LinkHeader, LinkRel, NoContent,
-    Redirect, Response, ResponseBody, ResponseModelAliases, ResponseModelConfig, ResponseProduces,
-    SameSite, SetCookie, StatusCode, Text, ValidatedResponse, apply_conditional, check_if_match,
-    check_if_none_match, exclude_fields, include_fields, mime_type_for_extension,
+    Attachment, AttachmentSource, Binary, BodyStream, FileResponse, Html, IntoResponse, Link,
+    LinkHeader, LinkRel, NoContent, Redirect, Response, ResponseBody, ResponseModelAliases,
+    ResponseModelConfig, ResponseProduces, SameSite, SetCookie, StatusCode, Text,
+    ValidatedResponse, apply_conditional, check_if_match, check_if_none_match, exclude_fields,
+    include_fields, mime_type_for_extension,
+};
+pub use response_cache::{
+    CacheStats, CacheStatsHandle, CacheStore, CachedResponse, MemoryCacheStore,
+    ResponseCacheMiddleware,
+};
+pub use variant::{
+    AssignmentSource, VariantAssignment, VariantExtractError, VariantMetrics, VariantRouter,
+    VariantStrategy,
 };
 pub use websocket::{
     Frame as WebSocketFrame, OpCode as WebSocketOpCode, WS_GUID, WebSocket, WebSocketError,
@@ -119,41 +193,64 @@ pub use websocket::{
 
 // Re-export interactive docs helpers.
 pub use docs::{
-    DocsConfig, oauth2_redirect_html, oauth2_redirect_response, redoc_html, redoc_response,
-    swagger_ui_html, swagger_ui_response,
+    DocsConfig, SwaggerOAuthConfig, oauth2_redirect_html, oauth2_redirect_response, redoc_html,
+    redoc_response, swagger_ui_html, swagger_ui_response,
 };
 
+// Re-export request clocks
+pub use clock::{Clock, VirtualClock};
+
 // Re-export key asupersync types for convenience
 pub use asupersync::{Budget, Cx, Outcome, RegionId, TaskId};
 
 // Re-export security helpers
 pub use password::{Algorithm, HashConfig, PasswordHasher, SecureCompare, constant_time_eq};
+pub use problem::ProblemDetails;
+pub use timeout::TimeoutLayer;
+pub use trace_context::{SpanExporter, SpanRecord, TraceContext, TracingMiddleware};
 
 // Re-export testing utilities
 #[cfg(feature = "testing")]
 pub use testing::{
-    CookieJar, FixtureGuard, IntegrationTest, RequestBuilder, TestClient, TestFixture,
-    TestResponse, json_contains,
+    ContractChecker, ContractViolation, CookieJar, FixtureGuard, IntegrationTest, RequestBuilder,
+    ResponseSnapshot, SnapshotConfig, TestBodyChunks, TestChunk, TestClient, TestFixture,
+    TestResponse, TestSseEvent, TestSseEvents, TestWebSocket, json_contains,
 };
 
 // Re-export assertion macros (defined via #[macro_export] in testing module)
-// Note: The macros assert_status!, assert_header!, assert_body_contains!,
+// Note: The macros assert_status!, assert_header!, assert_body_contains!, assert_snapshot!,
 // assert_json!, and assert_body_matches! are automatically exported at the crate root
 // due to #[macro_export]. Users can import them with `use fastapi_core::assert_status;`
 // They are available when the `testing` feature is enabled.
 
+// Re-export error reporting
+pub use error_reporting::{ErrorEvent, ErrorReporter};
+
 // Re-export logging utilities
-pub use logging::{AutoSpan, LogConfig, LogEntry, LogLevel, Span};
+pub use logging::{AutoSpan, LogConfig, LogEntry, LogLevel, LogScope, Span};
 
 // Re-export app utilities
 pub use app::{
-    App, AppBuilder, AppConfig, ExceptionHandlers, OpenApiConfig, RouteEntry, StartupHook,
+    App, AppBuilder, AppConfig, AppSnapshot, ExceptionHandlers, MethodNotAllowedHandler,
+    NotFoundHandler, OpenApiConfig, ReloadError, RouteEntry, RouteSchemasFn, StartupHook,
     StartupHookError, StartupOutcome, StateContainer,
 };
 
+pub use security::{InsufficientScopes, Scopes, Security, SecurityScopes};
+
+// Re-export session utilities
+pub use session::{
+    CurrentUser, CurrentUserError, MemorySessionStore, Session, SessionConfig, SessionMiddleware,
+    SessionStore, UserLoader,
+};
+
 // Re-export shutdown utilities
 pub use shutdown::{
-    GracefulConfig, GracefulShutdown, InFlightGuard, ShutdownAware, ShutdownController,
-    ShutdownHook, ShutdownOutcome, ShutdownPhase, ShutdownReceiver, grace_expired_cancel_reason,
-    shutdown_cancel_reason, subdivide_grace_budget,
+    GracefulConfig, GracefulShutdown, InFlightGuard, InFlightRequest, ShutdownAware,
+    ShutdownController, ShutdownHook, ShutdownOutcome, ShutdownPhase, ShutdownReceiver,
+    grace_expired_cancel_reason, shutdown_cancel_reason, subdivide_grace_budget,
 };
+pub use slow_request::{HandlerPhases, SlowRequest, SlowRequestMiddleware};
+pub use sse::{SseConfig, SseEvent, SseResponse, SseStream, last_event_id, sse_response};
+pub use template::{Template, TemplateEngine, TemplateError, Templates};
+pub use upload_policy::{FilenameRule, UploadPolicy, UploadPolicyError, UploadPolicyMiddleware};
@@ -28,6 +28,9 @@
 //!         role => user.role
 //!     );
 //!
+//!     // Emit immediately; request id and route are attached automatically
+//!     log_info!(ctx, "Order placed", order_id = order.id, total = order.total);
+//!
 //!     "ok"
 //! }
 //! ```
@@ -85,11 +88,14 @@
 //!     .with_logging(config);
 //! ```
 
+use std::collections::VecDeque;
 use std::fmt;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
 use std::time::Instant;
 
 use crate::context::RequestContext;
+use crate::error_reporting::{Breadcrumb, MAX_BREADCRUMBS, RequestSummary};
 
 // These types mirror an intended asupersync observability surface. The current
 // implementation provides a minimal built-in sink (stderr) and can be wired to
@@ -160,6 +166,8 @@ pub struct LogEntry {
     pub task_id: String,
     /// Module/target path (optional).
     pub target: Option<String>,
+    /// Matched route pattern, taken from the request's [`LogScope`].
+    pub route: Option<String>,
     /// Structured key-value fields (max 16).
     pub fields: Vec<(String, String)>,
     /// Nanosecond timestamp from asupersync's virtual time.
@@ -168,8 +176,13 @@ pub struct LogEntry {
 
 impl LogEntry {
     /// Creates a new log entry with context from RequestContext.
+    ///
+    /// The route and any fields recorded on the request's [`LogScope`] are
+    /// attached automatically.
     #[must_use]
     pub fn new(ctx: &RequestContext, level: LogLevel, message: impl Into<String>) -> Self {
+        let (route, mut fields) = ctx.log_scope().snapshot();
+        fields.truncate(16);
         Self {
             level,
             message: message.into(),
@@ -177,7 +190,8 @@ impl LogEntry {
             region_id: format!("{:?}", ctx.region_id()),
             task_id: format!("{:?}", ctx.task_id()),
             target: None,
-            fields: Vec::new(),
+            route,
+            fields,
             timestamp_ns: 0, // Will be set by asupersync's virtual time
         }
     }
@@ -217,6 +231,10 @@ impl LogEntry {
             json.push_str(&format!(r#","target":"{}""#, escape_json(target)));
         }
 
+        if let Some(ref route) = self.route {
+            json.push_str(&format!(r#","route":"{}""#, escape_json(route)));
+        }
+
         if !self.fields.is_empty() {
             json.push_str(r#","fields":{"#);
             for (i, (k, v)) in self.fields.iter().enumerate() {
@@ -235,12 +253,12 @@ impl LogEntry {
     /// Formats the log entry in compact format.
     #[must_use]
     pub fn to_compact(&self) -> String {
-        let mut output = format!(
-            "[{}] req={} {}",
-            self.level.as_char(),
-            self.request_id,
-            self.message
-        );
+        let mut output = format!("[{}] req={}", self.level.as_char(), self.request_id);
+        if let Some(ref route) = self.route {
+            output.push_str(&format!(" route={route}"));
+        }
+        output.push(' ');
+        output.push_str(&self.message);
 
         if !self.fields.is_empty() {
             output.push_str(" {");
@@ -269,6 +287,7 @@ fn escape_json(s: &str) -> String {
 /// A timing span for instrumentation.
 ///
 /// Spans track the duration of operations and can be nested hierarchically.
+/// Opening a span leaves a breadcrumb on the request's [`LogScope`].
 /// They integrate with asupersync's DiagnosticContext for distributed tracing.
 pub struct Span {
     name: String,
@@ -285,8 +304,11 @@ impl Span {
     pub fn new(ctx: &RequestContext, name: impl Into<String>) -> Self {
         static SPAN_COUNTER: AtomicU64 = AtomicU64::new(1);
 
+        let name = name.into();
+        ctx.log_scope()
+            .add_breadcrumb(Breadcrumb::new("span", name.clone()));
         Self {
-            name: name.into(),
+            name,
             request_id: ctx.request_id(),
             start: Instant::now(),
             span_id: SPAN_COUNTER.fetch_add(1, Ordering::SeqCst),
@@ -300,8 +322,11 @@ impl Span {
     pub fn child(&self, ctx: &RequestContext, name: impl Into<String>) -> Self {
         static SPAN_COUNTER: AtomicU64 = AtomicU64::new(1);
 
+        let name = name.into();
+        ctx.log_scope()
+            .add_breadcrumb(Breadcrumb::new("span", name.clone()));
         Self {
-            name: name.into(),
+            name,
             request_id: ctx.request_id(),
             start: Instant::now(),
             span_id: SPAN_COUNTER.fetch_add(1, Ordering::SeqCst),
@@ -522,6 +547,152 @@ impl LogConfig {
     }
 }
 
+// ============================================================================
+// Request Log Scope
+// ============================================================================
+
+/// Request-scoped logging fields shared by every [`LogEntry`] created from
+/// the same [`RequestContext`].
+///
+/// The app records the matched route here before running the handler;
+/// handlers and middleware can add their own fields (e.g. `user_id`) so later
+/// log lines for the request carry them without repeating them.
+///
+/// The scope also holds the context attached to
+/// [`ErrorEvent`](crate::error_reporting::ErrorEvent)s: a request summary, the
+/// authenticated user and a bounded trail of breadcrumbs.
+#[derive(Debug, Default)]
+pub struct LogScope {
+    inner: parking_lot::Mutex<LogScopeInner>,
+}
+
+#[derive(Debug, Default)]
+struct LogScopeInner {
+    route: Option<String>,
+    fields: Vec<(String, String)>,
+    request: Option<RequestSummary>,
+    user_id: Option<String>,
+    breadcrumbs: VecDeque<Breadcrumb>,
+}
+
+impl LogScope {
+    /// Creates an empty scope.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the matched route pattern (e.g. `/users/{id}`).
+    pub fn set_route(&self, route: impl Into<String>) {
+        self.inner.lock().route = Some(route.into());
+    }
+
+    /// Returns the matched route pattern, if one was recorded.
+    #[must_use]
+    pub fn route(&self) -> Option<String> {
+        self.inner.lock().route.clone()
+    }
+
+    /// Adds a field to every subsequent log entry for this request.
+    ///
+    /// Setting an existing key replaces its value.
+    pub fn insert(&self, key: impl Into<String>, value: impl fmt::Display) {
+        let key = key.into();
+        let value = value.to_string();
+        let mut inner = self.inner.lock();
+        if let Some(slot) = inner.fields.iter_mut().find(|(k, _)| *k == key) {
+            slot.1 = value;
+        } else {
+            inner.fields.push((key, value));
+        }
+    }
+
+    /// Returns the scope's fields in insertion order.
+    #[must_use]
+    pub fn fields(&self) -> Vec<(String, String)> {
+        self.inner.lock().fields.clone()
+    }
+
+    /// Records the request being served, for error reports.
+    pub fn set_request(&self, request: RequestSummary) {
+        self.inner.lock().request = Some(request);
+    }
+
+    /// Returns the recorded request summary.
+    #[must_use]
+    pub fn request(&self) -> Option<RequestSummary> {
+        self.inner.lock().request.clone()
+    }
+
+    /// Records the authenticated user for error reports.
+    pub fn set_user_id(&self, user_id: impl fmt::Display) {
+        self.inner.lock().user_id = Some(user_id.to_string());
+    }
+
+    /// Returns the recorded user id.
+    #[must_use]
+    pub fn user_id(&self) -> Option<String> {
+        self.inner.lock().user_id.clone()
+    }
+
+    /// Appends a breadcrumb, dropping the oldest beyond
+    /// [`MAX_BREADCRUMBS`](crate::error_reporting::MAX_BREADCRUMBS).
+    pub fn add_breadcrumb(&self, breadcrumb: Breadcrumb) {
+        let mut inner = self.inner.lock();
+        if inner.breadcrumbs.len() >= MAX_BREADCRUMBS {
+            inner.breadcrumbs.pop_front();
+        }
+        inner.breadcrumbs.push_back(breadcrumb);
+    }
+
+    /// Returns the breadcrumbs, oldest first.
+    #[must_use]
+    pub fn breadcrumbs(&self) -> Vec<Breadcrumb> {
+        self.inner.lock().breadcrumbs.iter().cloned().collect()
+    }
+
+    fn snapshot(&self) -> (Option<String>, Vec<(String, String)>) {
+        let inner = self.inner.lock();
+        (inner.route.clone(), inner.fields.clone())
+    }
+}
+
+// ============================================================================
+// Global Sink
+// ============================================================================
+
+/// Destination for entries emitted by the `key = value` form of the log macros.
+pub type LogSink = Arc<dyn Fn(&LogEntry) + Send + Sync>;
+
+static LOG_SINK: RwLock<Option<LogSink>> = RwLock::new(None);
+
+/// Routes emitted log entries to `sink` instead of stderr.
+pub fn set_log_sink(sink: impl Fn(&LogEntry) + Send + Sync + 'static) {
+    *LOG_SINK.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(sink));
+}
+
+/// Restores the default stderr sink.
+pub fn reset_log_sink() {
+    *LOG_SINK.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Emits `entry` through the global sink if its level is enabled.
+///
+/// The default sink writes the entry as JSON to stderr.
+pub fn emit(entry: &LogEntry) {
+    if !level_enabled(entry.level) {
+        return;
+    }
+    let sink = LOG_SINK
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    match sink {
+        Some(sink) => sink(entry),
+        None => eprintln!("{}", entry.to_json()),
+    }
+}
+
 // ============================================================================
 // Request Logger
 // ============================================================================
@@ -725,8 +896,14 @@ impl<'a> RequestLogger<'a> {
 
 /// Logs a message at the TRACE level with request context.
 ///
-/// Returns a [`LogEntry`] that can be emitted or inspected.
-/// For zero-overhead logging, use [`RequestLogger`].
+/// The `key => value` and format-argument forms return a [`LogEntry`] that
+/// can be emitted or inspected.
+///
+/// The `key = value` form emits immediately through [`emit`], attaching the
+/// request id, route and [`LogScope`] fields. The message must be a literal;
+/// it is checked at compile time and may capture variables inline
+/// (`"user {name}"`). When the level is disabled, nothing is evaluated
+/// beyond the level check.
 ///
 /// # Example
 ///
@@ -734,6 +911,7 @@ impl<'a> RequestLogger<'a> {
 /// log_trace!(ctx, "Entering function");
 /// log_trace!(ctx, "Processing item {}", item_id);
 /// log_trace!(ctx, "With fields", key => value, another => thing);
+/// log_trace!(ctx, "Cache lookup", key = cache_key, hit = false);
 /// ```
 #[macro_export]
 macro_rules! log_trace {
@@ -746,6 +924,15 @@ macro_rules! log_trace {
             .target(module_path!())
             $(.field(stringify!($key), $value))+
     };
+    ($ctx:expr, $msg:literal, $($key:ident = $value:expr),+ $(,)?) => {
+        if $crate::logging::level_enabled($crate::logging::LogLevel::Trace) {
+            $crate::logging::emit(
+                &$crate::logging::LogEntry::new($ctx, $crate::logging::LogLevel::Trace, ::std::fmt::format(::std::format_args!($msg)))
+                    .target(module_path!())
+                    $(.field(stringify!($key), $value))+,
+            );
+        }
+    };
     ($ctx:expr, $fmt:expr, $($arg:tt)*) => {
         $crate::logging::LogEntry::new($ctx, $crate::logging::LogLevel::Trace, format!($fmt, $($arg)*))
             .target(module_path!())
@@ -764,6 +951,15 @@ macro_rules! log_debug {
             .target(module_path!())
             $(.field(stringify!($key), $value))+
     };
+    ($ctx:expr, $msg:literal, $($key:ident = $value:expr),+ $(,)?) => {
+        if $crate::logging::level_enabled($crate::logging::LogLevel::Debug) {
+            $crate::logging::emit(
+                &$crate::logging::LogEntry::new($ctx, $crate::logging::LogLevel::Debug, ::std::fmt::format(::std::format_args!($msg)))
+                    .target(module_path!())
+                    $(.field(stringify!($key), $value))+,
+            );
+        }
+    };
     ($ctx:expr, $fmt:expr, $($arg:tt)*) => {
         $crate::logging::LogEntry::new($ctx, $crate::logging::LogLevel::Debug, format!($fmt, $($arg)*))
             .target(module_path!())
@@ -782,6 +978,15 @@ macro_rules! log_info {
             .target(module_path!())
             $(.field(stringify!($key), $value))+
     };
+    ($ctx:expr, $msg:literal, $($key:ident = $value:expr),+ $(,)?) => {
+        if $crate::logging::level_enabled($crate::logging::LogLevel::Info) {
+            $crate::logging::emit(
+                &$crate::logging::LogEntry::new($ctx, $crate::logging::LogLevel::Info, ::std::fmt::format(::std::format_args!($msg)))
+                    .target(module_path!())
+                    $(.field(stringify!($key), $value))+,
+            );
+        }
+    };
     ($ctx:expr, $fmt:expr, $($arg:tt)*) => {
         $crate::logging::LogEntry::new($ctx, $crate::logging::LogLevel::Info, format!($fmt, $($arg)*))
             .target(module_path!())
@@ -800,6 +1005,15 @@ macro_rules! log_warn {
             .target(module_path!())
             $(.field(stringify!($key), $value))+
     };
+    ($ctx:expr, $msg:literal, $($key:ident = $value:expr),+ $(,)?) => {
+        if $crate::logging::level_enabled($crate::logging::LogLevel::Warn) {
+            $crate::logging::emit(
+                &$crate::logging::LogEntry::new($ctx, $crate::logging::LogLevel::Warn, ::std::fmt::format(::std::format_args!($msg)))
+                    .target(module_path!())
+                    $(.field(stringify!($key), $value))+,
+            );
+        }
+    };
     ($ctx:expr, $fmt:expr, $($arg:tt)*) => {
         $crate::logging::LogEntry::new($ctx, $crate::logging::LogLevel::Warn, format!($fmt, $($arg)*))
             .target(module_path!())
@@ -818,6 +1032,15 @@ macro_rules! log_error {
             .target(module_path!())
             $(.field(stringify!($key), $value))+
     };
+    ($ctx:expr, $msg:literal, $($key:ident = $value:expr),+ $(,)?) => {
+        if $crate::logging::level_enabled($crate::logging::LogLevel::Error) {
+            $crate::logging::emit(
+                &$crate::logging::LogEntry::new($ctx, $crate::logging::LogLevel::Error, ::std::fmt::format(::std::format_args!($msg)))
+                    .target(module_path!())
+                    $(.field(stringify!($key), $value))+,
+            );
+        }
+    };
     ($ctx:expr, $fmt:expr, $($arg:tt)*) => {
         $crate::logging::LogEntry::new($ctx, $crate::logging::LogLevel::Error, format!($fmt, $($arg)*))
             .target(module_path!())
@@ -956,6 +1179,107 @@ mod tests {
         assert_eq!(entry.message, "Processing item 99");
     }
 
+    // =========================================================================
+    // Request scope and emitting macros
+    // =========================================================================
+
+    fn capture_sink() -> Arc<parking_lot::Mutex<Vec<String>>> {
+        let captured = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&captured);
+        set_log_sink(move |entry| sink.lock().push(entry.to_json()));
+        captured
+    }
+
+    #[test]
+    fn log_entry_includes_scope_route_and_fields() {
+        let ctx = test_context();
+        ctx.log_scope().set_route("/users/{id}");
+        ctx.log_scope().insert("user_id", 7);
+        ctx.log_scope().insert("user_id", 8);
+
+        let entry = log_info!(&ctx, "Scoped", action => "read");
+        assert_eq!(entry.route.as_deref(), Some("/users/{id}"));
+        assert_eq!(entry.fields[0], ("user_id".to_string(), "8".to_string()));
+        assert_eq!(entry.fields[1], ("action".to_string(), "read".to_string()));
+        assert!(entry.to_json().contains(r#""route":"/users/{id}""#));
+        assert!(entry.to_compact().contains("route=/users/{id}"));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn log_macro_key_value_form_emits() {
+        let captured = capture_sink();
+        let ctx = test_context();
+        let order = 41;
+
+        log_info!(&ctx, "Order {order} placed", total = 99, currency = "EUR");
+
+        reset_log_sink();
+        let lines = captured.lock();
+        assert_eq!(lines.len(), 1);
+        assert!(
+            lines[0].contains(r#""message":"Order 41 placed""#),
+            "{}",
+            lines[0]
+        );
+        assert!(lines[0].contains(r#""total":"99""#), "{}", lines[0]);
+        assert!(lines[0].contains(r#""currency":"EUR""#), "{}", lines[0]);
+        assert!(lines[0].contains(r#""request_id":12345"#), "{}", lines[0]);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn log_macro_key_value_form_skips_disabled_levels() {
+        let captured = capture_sink();
+        let ctx = test_context();
+        let mut evaluated = false;
+
+        log_debug!(
+            &ctx,
+            "Not shown",
+            value = {
+                evaluated = true;
+                1
+            }
+        );
+
+        reset_log_sink();
+        assert!(
+            !evaluated,
+            "field values must not be evaluated when disabled"
+        );
+        assert!(captured.lock().is_empty());
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn app_records_matched_route_in_scope() {
+        use crate::app::App;
+        use crate::request::{Method, Request};
+        use crate::response::Response;
+
+        let captured = capture_sink();
+        let app = App::builder()
+            .get("/items/{id}", |ctx: &RequestContext, _req: &mut Request| {
+                log_warn!(ctx, "Item handler", hit = true);
+                std::future::ready(Response::ok())
+            })
+            .build();
+
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/items/5");
+        let _ = futures_executor::block_on(app.handle(&ctx, &mut req));
+
+        reset_log_sink();
+        let lines = captured.lock();
+        assert_eq!(lines.len(), 1);
+        assert!(
+            lines[0].contains(r#""route":"/items/{id}""#),
+            "{}",
+            lines[0]
+        );
+    }
+
     // =========================================================================
     // AutoSpan and Span nesting tests (bd-sdrz)
     // =========================================================================
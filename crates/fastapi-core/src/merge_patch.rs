@@ -0,0 +1,382 @@
+//! JSON Merge Patch ([RFC 7386](https://www.rfc-editor.org/rfc/rfc7386)).
+//!
+//! A merge patch is a JSON document shaped like the resource: members present
+//! in the patch replace those of the target, members set to `null` are
+//! removed, and nested objects are merged recursively. Anything that is not
+//! an object (including arrays) replaces the target value outright.
+//!
+//! [`MergePatch<T>`] extracts a patch from a `PATCH` body and applies it to
+//! an existing `T`:
+//!
+//! ```ignore
+//! #[patch("/users/{id}")]
+//! async fn update_user(
+//!     cx: &Cx,
+//!     id: Path<u64>,
+//!     patch: MergePatch<User>,
+//!     db: State<Db>,
+//! ) -> Result<Json<User>, MergePatchError> {
+//!     let mut user = db.user(id.0);
+//!     // A patch producing an invalid `User` is answered with 422.
+//!     let changed = patch.apply_to(&mut user)?; // e.g. ["/address/city", "/email"]
+//!     if !changed.is_empty() {
+//!         db.save(&user);
+//!     }
+//!     Ok(Json(user))
+//! }
+//! ```
+//!
+//! [`merge_patch`], [`diff`] and [`changed_paths`] work on plain
+//! [`serde_json::Value`]s.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::context::RequestContext;
+use crate::error::{ValidationError, ValidationErrors, loc};
+use crate::extract::{FromRequest, JsonExtractError, read_json_body};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response};
+
+/// Media type of JSON Merge Patch documents.
+pub const MERGE_PATCH_CONTENT_TYPE: &str = "application/merge-patch+json";
+
+/// Apply `patch` to `target` in place, as defined by RFC 7386.
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// A merge patch that turns `before` into `after`.
+///
+/// Returns `{}` when nothing changed. Merge patches cannot set a member to
+/// `null`, so `null` members of `after` come out as removals.
+#[must_use]
+pub fn diff(before: &Value, after: &Value) -> Value {
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            let mut patch = Map::new();
+            for key in before.keys() {
+                if !after.contains_key(key) {
+                    patch.insert(key.clone(), Value::Null);
+                }
+            }
+            for (key, new) in after {
+                match before.get(key) {
+                    Some(old) if old == new => {}
+                    Some(old @ Value::Object(_)) if new.is_object() => {
+                        patch.insert(key.clone(), diff(old, new));
+                    }
+                    _ => {
+                        patch.insert(key.clone(), new.clone());
+                    }
+                }
+            }
+            Value::Object(patch)
+        }
+        _ if before == after => Value::Object(Map::new()),
+        _ => after.clone(),
+    }
+}
+
+/// JSON Pointers of the members that differ between `before` and `after`,
+/// sorted. Objects are compared member by member; any other differing value,
+/// including an array, is reported at its own path (`""` for the root).
+#[must_use]
+pub fn changed_paths(before: &Value, after: &Value) -> Vec<String> {
+    fn walk(
+        before: Option<&Value>,
+        after: Option<&Value>,
+        path: &mut String,
+        out: &mut Vec<String>,
+    ) {
+        match (before, after) {
+            (Some(Value::Object(before)), Some(Value::Object(after))) => {
+                let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+                keys.sort();
+                keys.dedup();
+                for key in keys {
+                    let len = path.len();
+                    path.push('/');
+                    path.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                    walk(before.get(key), after.get(key), path, out);
+                    path.truncate(len);
+                }
+            }
+            (before, after) if before != after => out.push(path.clone()),
+            _ => {}
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(Some(before), Some(after), &mut String::new(), &mut out);
+    out
+}
+
+/// A patch could not be applied because the result is not a valid `T`.
+///
+/// Responds with 422 and a `value_error` at `["body"]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergePatchError {
+    /// Why the patched value was rejected.
+    pub message: String,
+}
+
+impl fmt::Display for MergePatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid merge patch: {}", self.message)
+    }
+}
+
+impl std::error::Error for MergePatchError {}
+
+impl IntoResponse for MergePatchError {
+    fn into_response(self) -> Response {
+        ValidationErrors::single(ValidationError::value_error(
+            loc::body(),
+            format!("Patched value is invalid: {}", self.message),
+        ))
+        .into_response()
+    }
+}
+
+/// Extractor for a JSON Merge Patch body, to be applied to a `T`.
+///
+/// Accepts `application/merge-patch+json` as well as `application/json`
+/// bodies, with the same size limit and error responses as
+/// [`Json`](crate::Json).
+pub struct MergePatch<T> {
+    patch: Value,
+    _target: PhantomData<fn() -> T>,
+}
+
+impl<T> MergePatch<T> {
+    /// Wrap an already parsed patch document.
+    #[must_use]
+    pub fn new(patch: Value) -> Self {
+        Self {
+            patch,
+            _target: PhantomData,
+        }
+    }
+
+    /// The patch document.
+    #[must_use]
+    pub fn patch(&self) -> &Value {
+        &self.patch
+    }
+
+    /// Consume the extractor, returning the patch document.
+    #[must_use]
+    pub fn into_inner(self) -> Value {
+        self.patch
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> MergePatch<T> {
+    /// Return a patched copy of `target`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MergePatchError`] if `target` cannot be serialized or the
+    /// patched document does not deserialize as `T`.
+    pub fn apply(&self, target: &T) -> Result<T, MergePatchError> {
+        let mut value = to_value(target)?;
+        merge_patch(&mut value, &self.patch);
+        from_value(value)
+    }
+
+    /// Patch `target` in place, returning the JSON Pointers of the members
+    /// that changed (see [`changed_paths`]). `target` is left untouched on
+    /// error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MergePatchError`] if `target` cannot be serialized or the
+    /// patched document does not deserialize as `T`.
+    pub fn apply_to(&self, target: &mut T) -> Result<Vec<String>, MergePatchError> {
+        let before = to_value(target)?;
+        let mut after = before.clone();
+        merge_patch(&mut after, &self.patch);
+        let changed = changed_paths(&before, &after);
+        if !changed.is_empty() {
+            *target = from_value(after)?;
+        }
+        Ok(changed)
+    }
+}
+
+fn to_value<T: Serialize>(target: &T) -> Result<Value, MergePatchError> {
+    serde_json::to_value(target).map_err(|err| MergePatchError {
+        message: err.to_string(),
+    })
+}
+
+fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, MergePatchError> {
+    serde_json::from_value(value).map_err(|err| MergePatchError {
+        message: err.to_string(),
+    })
+}
+
+impl<T> fmt::Debug for MergePatch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MergePatch").field(&self.patch).finish()
+    }
+}
+
+impl<T> Clone for MergePatch<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.patch.clone())
+    }
+}
+
+impl<T> FromRequest for MergePatch<T> {
+    type Error = JsonExtractError;
+
+    async fn from_request(ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
+        let bytes = read_json_body(ctx, req).await?;
+        serde_json::from_slice(&bytes).map(Self::new).map_err(|e| {
+            JsonExtractError::DeserializeError {
+                message: e.to_string(),
+                line: Some(e.line()),
+                column: Some(e.column()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::{Body, Method};
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[test]
+    fn applies_rfc_7386_examples() {
+        let cases = [
+            (json!({"a": "b"}), json!({"a": "c"}), json!({"a": "c"})),
+            (
+                json!({"a": "b"}),
+                json!({"b": "c"}),
+                json!({"a": "b", "b": "c"}),
+            ),
+            (json!({"a": "b"}), json!({"a": null}), json!({})),
+            (
+                json!({"a": {"b": "c"}}),
+                json!({"a": {"b": "d", "c": null}}),
+                json!({"a": {"b": "d"}}),
+            ),
+            (
+                json!({"a": [{"b": "c"}]}),
+                json!({"a": [1]}),
+                json!({"a": [1]}),
+            ),
+            (json!(["a", "b"]), json!(["c", "d"]), json!(["c", "d"])),
+            (json!({"a": "foo"}), json!("bar"), json!("bar")),
+            (json!("bar"), json!({"a": "foo"}), json!({"a": "foo"})),
+            (
+                json!({"e": null}),
+                json!({"a": 1}),
+                json!({"e": null, "a": 1}),
+            ),
+        ];
+        for (mut target, patch, expected) in cases {
+            merge_patch(&mut target, &patch);
+            assert_eq!(target, expected, "patch {patch}");
+        }
+    }
+
+    #[test]
+    fn diff_round_trips_and_lists_changes() {
+        let before =
+            json!({"name": "ann", "tags": ["a"], "address": {"city": "Oslo", "zip": "0150"}});
+        let after = json!({"name": "ann", "tags": ["a", "b"], "address": {"city": "Bergen", "zip": "0150"}, "a/b": 1});
+
+        let patch = diff(&before, &after);
+        assert_eq!(
+            patch,
+            json!({"tags": ["a", "b"], "address": {"city": "Bergen"}, "a/b": 1})
+        );
+        let mut patched = before.clone();
+        merge_patch(&mut patched, &patch);
+        assert_eq!(patched, after);
+
+        assert_eq!(
+            changed_paths(&before, &after),
+            ["/a~1b", "/address/city", "/tags"]
+        );
+        assert_eq!(diff(&after, &after), json!({}));
+        assert!(changed_paths(&after, &after).is_empty());
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct User {
+        name: String,
+        email: Option<String>,
+        age: u32,
+    }
+
+    #[test]
+    fn patches_structs() {
+        let mut user = User {
+            name: "ann".into(),
+            email: Some("ann@example.com".into()),
+            age: 30,
+        };
+        let patch = MergePatch::<User>::new(json!({"email": null, "age": 31}));
+        assert_eq!(patch.apply_to(&mut user).unwrap(), ["/age", "/email"]);
+        assert_eq!(user.email, None);
+        assert_eq!(user.age, 31);
+
+        let bad = MergePatch::<User>::new(json!({"age": "old"}));
+        assert!(bad.apply(&user).is_err());
+        assert_eq!(
+            bad.apply_to(&mut user)
+                .unwrap_err()
+                .into_response()
+                .status()
+                .as_u16(),
+            422
+        );
+        assert_eq!(user.age, 31);
+    }
+
+    #[test]
+    fn extracts_merge_patch_bodies() {
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+        let mut req = Request::new(Method::Patch, "/users/1");
+        req.headers_mut()
+            .insert("content-type", MERGE_PATCH_CONTENT_TYPE.as_bytes().to_vec());
+        req.set_body(Body::Bytes(br#"{"age": 40}"#.to_vec()));
+        let patch =
+            futures_executor::block_on(MergePatch::<User>::from_request(&ctx, &mut req)).unwrap();
+        assert_eq!(patch.patch(), &json!({"age": 40}));
+
+        let mut req = Request::new(Method::Patch, "/users/1");
+        req.set_body(Body::Bytes(br#"{"age": 40}"#.to_vec()));
+        let err = futures_executor::block_on(MergePatch::<User>::from_request(&ctx, &mut req));
+        assert!(matches!(
+            err,
+            Err(JsonExtractError::UnsupportedMediaType { .. })
+        ));
+    }
+}
@@ -0,0 +1,787 @@
+//! Prometheus metrics.
+//!
+//! A [`MetricsRegistry`] holds counters, gauges and histograms, optionally
+//! split by labels, and renders them in the Prometheus text exposition
+//! format. [`MetricsMiddleware`] fills a registry with the standard HTTP
+//! server metrics, and [`AppBuilder::metrics`](crate::app::AppBuilder::metrics)
+//! serves it for scraping:
+//!
+//! ```ignore
+//! let registry = MetricsRegistry::new();
+//! let signups = registry.counter("signups_total", "Accounts created.");
+//!
+//! let app = App::builder()
+//!     .middleware(MetricsMiddleware::new(&registry))
+//!     .metrics("/metrics", registry.clone())
+//!     .build();
+//!
+//! // in a handler
+//! signups.inc();
+//! ```
+//!
+//! The middleware records, labelled by `method`, matched `route` and
+//! `status`:
+//!
+//! | Metric | Type |
+//! |--------|------|
+//! | `http_requests_total` | counter |
+//! | `http_request_duration_seconds` | histogram |
+//! | `http_requests_in_flight` | gauge (no labels) |
+//! | `http_request_size_bytes` | histogram (`method`, `route`) |
+//! | `http_response_size_bytes` | histogram |
+//!
+//! Requests that match no route are labelled `route="unmatched"`, so probing
+//! random paths cannot create unbounded series.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use parking_lot::{Mutex, RwLock};
+
+use crate::context::RequestContext;
+use crate::middleware::{BoxFuture, ControlFlow, Middleware};
+use crate::request::{Body, Request};
+use crate::response::{Response, ResponseBody, StatusCode};
+
+/// Default buckets for request durations, in seconds.
+pub const DEFAULT_DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Default buckets for body sizes, in bytes.
+pub const DEFAULT_SIZE_BUCKETS: &[f64] = &[
+    100.0,
+    1_000.0,
+    10_000.0,
+    100_000.0,
+    1_000_000.0,
+    10_000_000.0,
+];
+
+/// Content type of the text exposition format.
+pub const TEXT_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+// ============================================================================
+// Metrics
+// ============================================================================
+
+/// A `f64` updated atomically through its bit pattern.
+#[derive(Debug, Default)]
+struct AtomicF64(AtomicU64);
+
+impl AtomicF64 {
+    fn get(&self) -> f64 {
+        f64::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    fn set(&self, value: f64) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    fn add(&self, delta: f64) {
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                Some((f64::from_bits(bits) + delta).to_bits())
+            });
+    }
+}
+
+/// A monotonically increasing count.
+///
+/// Clones share the same value.
+#[derive(Debug, Clone, Default)]
+pub struct Counter(Arc<AtomicU64>);
+
+impl Counter {
+    /// Add one.
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    /// Add `n`.
+    pub fn inc_by(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Current value.
+    #[must_use]
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A value that can go up and down.
+///
+/// Clones share the same value.
+#[derive(Debug, Clone, Default)]
+pub struct Gauge(Arc<AtomicF64>);
+
+impl Gauge {
+    /// Add one.
+    pub fn inc(&self) {
+        self.add(1.0);
+    }
+
+    /// Subtract one.
+    pub fn dec(&self) {
+        self.add(-1.0);
+    }
+
+    /// Add `delta`, which may be negative.
+    pub fn add(&self, delta: f64) {
+        self.0.add(delta);
+    }
+
+    /// Set the value.
+    pub fn set(&self, value: f64) {
+        self.0.set(value);
+    }
+
+    /// Current value.
+    #[must_use]
+    pub fn get(&self) -> f64 {
+        self.0.get()
+    }
+}
+
+/// Observations counted into cumulative buckets.
+///
+/// Clones share the same buckets.
+#[derive(Debug, Clone)]
+pub struct Histogram(Arc<HistogramInner>);
+
+#[derive(Debug)]
+struct HistogramInner {
+    /// Upper bounds, ascending.
+    bounds: Vec<f64>,
+    /// Per-bucket (non-cumulative) counts; the last one is `+Inf`.
+    counts: Vec<AtomicU64>,
+    sum: AtomicF64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    fn new(bounds: &[f64]) -> Self {
+        let mut bounds: Vec<f64> = bounds.iter().copied().filter(|b| b.is_finite()).collect();
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+        let counts = (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect();
+        Self(Arc::new(HistogramInner {
+            bounds,
+            counts,
+            sum: AtomicF64::default(),
+            count: AtomicU64::new(0),
+        }))
+    }
+
+    /// Record one observation.
+    pub fn observe(&self, value: f64) {
+        let inner = &self.0;
+        let bucket = inner.bounds.partition_point(|bound| *bound < value);
+        inner.counts[bucket].fetch_add(1, Ordering::Relaxed);
+        inner.sum.add(value);
+        inner.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of observations.
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.0.count.load(Ordering::Relaxed)
+    }
+
+    /// Sum of all observations.
+    #[must_use]
+    pub fn sum(&self) -> f64 {
+        self.0.sum.get()
+    }
+}
+
+/// A metric split by label values.
+///
+/// Each distinct combination of values is its own series, created on first
+/// use. Clones share the same series.
+#[derive(Clone)]
+pub struct Family<M> {
+    labels: Arc<[String]>,
+    series: Arc<RwLock<BTreeMap<Vec<String>, M>>>,
+    make: Arc<dyn Fn() -> M + Send + Sync>,
+}
+
+impl<M: Clone> Family<M> {
+    fn new(labels: &[&str], make: impl Fn() -> M + Send + Sync + 'static) -> Self {
+        Self {
+            labels: labels.iter().map(|l| (*l).to_string()).collect(),
+            series: Arc::new(RwLock::new(BTreeMap::new())),
+            make: Arc::new(make),
+        }
+    }
+
+    /// The series for `values`, given in label order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values differs from the number of labels.
+    #[must_use]
+    pub fn with(&self, values: &[&str]) -> M {
+        assert_eq!(
+            values.len(),
+            self.labels.len(),
+            "expected {} label values",
+            self.labels.len()
+        );
+        let key: Vec<String> = values.iter().map(|v| (*v).to_string()).collect();
+        if let Some(metric) = self.series.read().get(&key) {
+            return metric.clone();
+        }
+        self.series
+            .write()
+            .entry(key)
+            .or_insert_with(|| (self.make)())
+            .clone()
+    }
+
+    /// Makes `metric` the series for `values`, replacing any existing one.
+    /// Lets a component keep counting into a metric it created before the
+    /// registry was known.
+    pub(crate) fn adopt(&self, values: &[&str], metric: M) {
+        assert_eq!(
+            values.len(),
+            self.labels.len(),
+            "expected {} label values",
+            self.labels.len()
+        );
+        let key: Vec<String> = values.iter().map(|v| (*v).to_string()).collect();
+        self.series.write().insert(key, metric);
+    }
+
+    /// Label names.
+    #[must_use]
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+}
+
+impl<M> std::fmt::Debug for Family<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Family")
+            .field("labels", &self.labels)
+            .field("series", &self.series.read().len())
+            .finish_non_exhaustive()
+    }
+}
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+#[derive(Debug, Clone)]
+enum Collector {
+    Counter(Family<Counter>),
+    Gauge(Family<Gauge>),
+    Histogram(Family<Histogram>),
+}
+
+impl Collector {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Counter(_) => "counter",
+            Self::Gauge(_) => "gauge",
+            Self::Histogram(_) => "histogram",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Registered {
+    name: String,
+    help: String,
+    collector: Collector,
+}
+
+/// A set of named metrics.
+///
+/// Clones share the same metrics. Registering a name again returns the
+/// existing metric, so independent components can ask for the same one.
+#[derive(Debug, Clone, Default)]
+pub struct MetricsRegistry {
+    metrics: Arc<Mutex<Vec<Registered>>>,
+}
+
+impl MetricsRegistry {
+    /// An empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an unlabelled counter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid metric name, or is already registered
+    /// as a different kind of metric or with other labels.
+    #[must_use]
+    pub fn counter(&self, name: &str, help: &str) -> Counter {
+        self.counter_family(name, help, &[]).with(&[])
+    }
+
+    /// Register a counter split by `labels`.
+    ///
+    /// # Panics
+    ///
+    /// See [`counter`](Self::counter).
+    #[must_use]
+    pub fn counter_family(&self, name: &str, help: &str, labels: &[&str]) -> Family<Counter> {
+        let collector = self.register(name, help, labels, || {
+            Collector::Counter(Family::new(labels, Counter::default))
+        });
+        match collector {
+            Collector::Counter(family) => family,
+            other => kind_conflict(name, other.kind()),
+        }
+    }
+
+    /// Register an unlabelled gauge.
+    ///
+    /// # Panics
+    ///
+    /// See [`counter`](Self::counter).
+    #[must_use]
+    pub fn gauge(&self, name: &str, help: &str) -> Gauge {
+        self.gauge_family(name, help, &[]).with(&[])
+    }
+
+    /// Register a gauge split by `labels`.
+    ///
+    /// # Panics
+    ///
+    /// See [`counter`](Self::counter).
+    #[must_use]
+    pub fn gauge_family(&self, name: &str, help: &str, labels: &[&str]) -> Family<Gauge> {
+        let collector = self.register(name, help, labels, || {
+            Collector::Gauge(Family::new(labels, Gauge::default))
+        });
+        match collector {
+            Collector::Gauge(family) => family,
+            other => kind_conflict(name, other.kind()),
+        }
+    }
+
+    /// Register an unlabelled histogram with the given bucket upper bounds.
+    ///
+    /// # Panics
+    ///
+    /// See [`counter`](Self::counter).
+    #[must_use]
+    pub fn histogram(&self, name: &str, help: &str, buckets: &[f64]) -> Histogram {
+        self.histogram_family(name, help, &[], buckets).with(&[])
+    }
+
+    /// Register a histogram split by `labels`.
+    ///
+    /// # Panics
+    ///
+    /// See [`counter`](Self::counter).
+    #[must_use]
+    pub fn histogram_family(
+        &self,
+        name: &str,
+        help: &str,
+        labels: &[&str],
+        buckets: &[f64],
+    ) -> Family<Histogram> {
+        let collector = self.register(name, help, labels, || {
+            let buckets = buckets.to_vec();
+            Collector::Histogram(Family::new(labels, move || Histogram::new(&buckets)))
+        });
+        match collector {
+            Collector::Histogram(family) => family,
+            other => kind_conflict(name, other.kind()),
+        }
+    }
+
+    fn register(
+        &self,
+        name: &str,
+        help: &str,
+        labels: &[&str],
+        make: impl FnOnce() -> Collector,
+    ) -> Collector {
+        assert!(valid_name(name), "invalid metric name {name:?}");
+        for label in labels {
+            assert!(
+                valid_name(label) && !label.contains(':') && *label != "le",
+                "invalid label name {label:?}"
+            );
+        }
+        let mut metrics = self.metrics.lock();
+        if let Some(existing) = metrics.iter().find(|m| m.name == name) {
+            let same_labels = match &existing.collector {
+                Collector::Counter(f) => f.labels() == labels,
+                Collector::Gauge(f) => f.labels() == labels,
+                Collector::Histogram(f) => f.labels() == labels,
+            };
+            assert!(
+                same_labels,
+                "metric {name} is already registered with other labels"
+            );
+            return existing.collector.clone();
+        }
+        let collector = make();
+        metrics.push(Registered {
+            name: name.to_string(),
+            help: help.to_string(),
+            collector: collector.clone(),
+        });
+        collector
+    }
+
+    /// Render every metric in the Prometheus text exposition format.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for metric in self.metrics.lock().iter() {
+            let _ = writeln!(out, "# HELP {} {}", metric.name, escape_help(&metric.help));
+            let _ = writeln!(out, "# TYPE {} {}", metric.name, metric.collector.kind());
+            match &metric.collector {
+                Collector::Counter(family) => {
+                    for (values, counter) in family.series.read().iter() {
+                        let labels = label_set(family.labels(), values, None);
+                        let _ = writeln!(out, "{}{labels} {}", metric.name, counter.get());
+                    }
+                }
+                Collector::Gauge(family) => {
+                    for (values, gauge) in family.series.read().iter() {
+                        let labels = label_set(family.labels(), values, None);
+                        let _ =
+                            writeln!(out, "{}{labels} {}", metric.name, format_value(gauge.get()));
+                    }
+                }
+                Collector::Histogram(family) => {
+                    for (values, histogram) in family.series.read().iter() {
+                        render_histogram(
+                            &mut out,
+                            &metric.name,
+                            family.labels(),
+                            values,
+                            histogram,
+                        );
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// A response with the rendered metrics.
+    #[must_use]
+    pub fn response(&self) -> Response {
+        Response::with_status(StatusCode::OK)
+            .header("content-type", TEXT_CONTENT_TYPE.as_bytes().to_vec())
+            .body(ResponseBody::Bytes(self.render().into_bytes()))
+    }
+}
+
+fn kind_conflict(name: &str, kind: &str) -> ! {
+    panic!("metric {name} is already registered as a {kind}")
+}
+
+fn render_histogram(
+    out: &mut String,
+    name: &str,
+    labels: &[String],
+    values: &[String],
+    histogram: &Histogram,
+) {
+    let inner = &histogram.0;
+    let mut cumulative = 0;
+    for (i, count) in inner.counts.iter().enumerate() {
+        cumulative += count.load(Ordering::Relaxed);
+        let le = inner
+            .bounds
+            .get(i)
+            .map_or_else(|| "+Inf".to_string(), |bound| format_value(*bound));
+        let set = label_set(labels, values, Some(&le));
+        let _ = writeln!(out, "{name}_bucket{set} {cumulative}");
+    }
+    let set = label_set(labels, values, None);
+    let _ = writeln!(out, "{name}_sum{set} {}", format_value(histogram.sum()));
+    let _ = writeln!(out, "{name}_count{set} {}", histogram.count());
+}
+
+/// `{a="1",b="2"}`, or nothing without labels.
+fn label_set(labels: &[String], values: &[String], le: Option<&str>) -> String {
+    let pairs: Vec<String> = labels
+        .iter()
+        .zip(values)
+        .map(|(label, value)| format!("{label}=\"{}\"", escape_label(value)))
+        .chain(le.map(|le| format!("le=\"{le}\"")))
+        .collect();
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        let sign = if value > 0.0 { '+' } else { '-' };
+        format!("{sign}Inf")
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape_help(help: &str) -> String {
+    help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == ':')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
+
+// ============================================================================
+// Middleware
+// ============================================================================
+
+/// Tracks a request from `before` to `after`; leaves the in-flight gauge
+/// when dropped, even if `after` never runs.
+struct InFlight {
+    gauge: Gauge,
+    start: Instant,
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.gauge.dec();
+    }
+}
+
+/// Middleware recording HTTP server metrics into a [`MetricsRegistry`].
+#[derive(Debug, Clone)]
+pub struct MetricsMiddleware {
+    requests: Family<Counter>,
+    duration: Family<Histogram>,
+    in_flight: Gauge,
+    request_size: Family<Histogram>,
+    response_size: Family<Histogram>,
+}
+
+impl MetricsMiddleware {
+    /// Register the HTTP metrics in `registry` with the default buckets.
+    #[must_use]
+    pub fn new(registry: &MetricsRegistry) -> Self {
+        Self::with_buckets(registry, DEFAULT_DURATION_BUCKETS, DEFAULT_SIZE_BUCKETS)
+    }
+
+    /// Register the HTTP metrics with custom duration (seconds) and size
+    /// (bytes) buckets.
+    #[must_use]
+    pub fn with_buckets(
+        registry: &MetricsRegistry,
+        duration_buckets: &[f64],
+        size_buckets: &[f64],
+    ) -> Self {
+        const LABELS: &[&str] = &["method", "route", "status"];
+        Self {
+            requests: registry.counter_family(
+                "http_requests_total",
+                "Total HTTP requests.",
+                LABELS,
+            ),
+            duration: registry.histogram_family(
+                "http_request_duration_seconds",
+                "HTTP request duration in seconds.",
+                LABELS,
+                duration_buckets,
+            ),
+            in_flight: registry.gauge(
+                "http_requests_in_flight",
+                "HTTP requests currently being served.",
+            ),
+            request_size: registry.histogram_family(
+                "http_request_size_bytes",
+                "HTTP request body size in bytes.",
+                &["method", "route"],
+                size_buckets,
+            ),
+            response_size: registry.histogram_family(
+                "http_response_size_bytes",
+                "HTTP response body size in bytes.",
+                LABELS,
+                size_buckets,
+            ),
+        }
+    }
+}
+
+fn request_body_size(req: &Request) -> Option<usize> {
+    match req.body() {
+        Body::Empty => Some(0),
+        Body::Bytes(bytes) => Some(bytes.len()),
+        Body::Stream { content_length, .. } => *content_length,
+    }
+}
+
+fn response_body_size(response: &Response) -> Option<usize> {
+    match response.body_ref() {
+        ResponseBody::Empty => Some(0),
+        ResponseBody::Bytes(bytes) => Some(bytes.len()),
+        ResponseBody::Stream(_) => None,
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn as_f64(n: usize) -> f64 {
+    n as f64
+}
+
+impl Middleware for MetricsMiddleware {
+    fn before<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        self.in_flight.inc();
+        req.insert_extension(InFlight {
+            gauge: self.in_flight.clone(),
+            start: Instant::now(),
+        });
+        Box::pin(async { ControlFlow::Continue })
+    }
+
+    fn after<'a>(
+        &'a self,
+        ctx: &'a RequestContext,
+        req: &'a Request,
+        response: Response,
+    ) -> BoxFuture<'a, Response> {
+        if let Some(in_flight) = req.get_extension::<InFlight>() {
+            let elapsed = in_flight.start.elapsed().as_secs_f64();
+            let method = req.method().as_str();
+            let route = ctx
+                .log_scope()
+                .route()
+                .unwrap_or_else(|| "unmatched".to_string());
+            let status = response.status().as_u16().to_string();
+            let labels = [method, route.as_str(), status.as_str()];
+
+            self.requests.with(&labels).inc();
+            self.duration.with(&labels).observe(elapsed);
+            if let Some(size) = request_body_size(req) {
+                self.request_size
+                    .with(&[method, route.as_str()])
+                    .observe(as_f64(size));
+            }
+            if let Some(size) = response_body_size(&response) {
+                self.response_size.with(&labels).observe(as_f64(size));
+            }
+        }
+        Box::pin(async move { response })
+    }
+
+    fn name(&self) -> &'static str {
+        "Metrics"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Method;
+
+    #[test]
+    fn renders_text_exposition_format() {
+        let registry = MetricsRegistry::new();
+        let jobs = registry.counter_family("jobs_total", "Jobs run.\nBy queue.", &["queue"]);
+        jobs.with(&["mail"]).inc_by(3);
+        jobs.with(&["say \"hi\""]).inc();
+        registry
+            .gauge("temperature", "Current temperature.")
+            .set(-1.5);
+        let latency = registry.histogram("latency_seconds", "Latency.", &[0.1, 1.0]);
+        latency.observe(0.0625);
+        latency.observe(0.5);
+        latency.observe(3.0);
+
+        let text = registry.render();
+        let expected = [
+            "# HELP jobs_total Jobs run.\\nBy queue.",
+            "# TYPE jobs_total counter",
+            "jobs_total{queue=\"mail\"} 3",
+            "jobs_total{queue=\"say \\\"hi\\\"\"} 1",
+            "# TYPE temperature gauge",
+            "temperature -1.5",
+            "# TYPE latency_seconds histogram",
+            "latency_seconds_bucket{le=\"0.1\"} 1",
+            "latency_seconds_bucket{le=\"1\"} 2",
+            "latency_seconds_bucket{le=\"+Inf\"} 3",
+            "latency_seconds_sum 3.5625",
+            "latency_seconds_count 3",
+        ];
+        for line in expected {
+            assert!(
+                text.lines().any(|l| l == line),
+                "missing {line:?} in\n{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn registering_twice_returns_the_same_metric() {
+        let registry = MetricsRegistry::new();
+        registry.counter("hits_total", "Hits.").inc();
+        registry.counter("hits_total", "Hits.").inc();
+        assert_eq!(registry.counter("hits_total", "Hits.").get(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "already registered as a counter")]
+    fn conflicting_kinds_panic() {
+        let registry = MetricsRegistry::new();
+        let _ = registry.counter("hits_total", "Hits.");
+        let _ = registry.gauge("hits_total", "Hits.");
+    }
+
+    #[test]
+    fn middleware_records_request_metrics() {
+        let registry = MetricsRegistry::new();
+        let mw = MetricsMiddleware::new(&registry);
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+        ctx.log_scope().set_route("/items/{id}");
+
+        let mut req = Request::new(Method::Get, "/items/7");
+        assert!(futures_executor::block_on(mw.before(&ctx, &mut req)).is_continue());
+        assert!((mw.in_flight.get() - 1.0).abs() < f64::EPSILON);
+        let response = Response::ok().body(ResponseBody::Bytes(b"hello".to_vec()));
+        let _ = futures_executor::block_on(mw.after(&ctx, &req, response));
+        drop(req);
+        assert!(mw.in_flight.get().abs() < f64::EPSILON);
+
+        let labels = ["GET", "/items/{id}", "200"];
+        assert_eq!(mw.requests.with(&labels).get(), 1);
+        assert_eq!(mw.duration.with(&labels).count(), 1);
+        assert!((mw.response_size.with(&labels).sum() - 5.0).abs() < f64::EPSILON);
+        assert!(registry.render().contains(
+            "http_requests_total{method=\"GET\",route=\"/items/{id}\",status=\"200\"} 1"
+        ));
+    }
+}
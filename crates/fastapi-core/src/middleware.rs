@@ -198,6 +198,15 @@ pub trait Middleware: Send + Sync {
     fn name(&self) -> &'static str {
         std::any::type_name::<Self>()
     }
+
+    /// Reports configuration problems for [`App::check`](crate::App::check).
+    ///
+    /// # Default Implementation
+    ///
+    /// Reports nothing.
+    fn check(&self) -> Vec<crate::check::CheckIssue> {
+        Vec::new()
+    }
 }
 
 /// A handler that processes requests into responses.
@@ -313,6 +322,11 @@ impl MiddlewareStack {
         self.middleware.len()
     }
 
+    /// Iterates over the middleware in execution order.
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn Middleware>> {
+        self.middleware.iter()
+    }
+
     /// Returns `true` if the stack is empty.
     #[must_use]
     pub fn is_empty(&self) -> bool {
@@ -363,7 +377,7 @@ impl MiddlewareStack {
 
         // All before hooks passed, call the handler
         let _ = ctx.checkpoint();
-        let response = handler.call(ctx, req).await;
+        let response = crate::timeout::call_handler(handler, ctx, req).await;
 
         // Run after hooks in reverse order
         self.run_after_hooks(ctx, req, response, ran_before_count)
@@ -607,6 +621,351 @@ impl Middleware for PathPrefixFilter {
     }
 }
 
+/// Deprecation details announced by [`DeprecationHeaders`].
+#[derive(Debug, Clone, Default)]
+pub struct DeprecationNotice {
+    since: Option<std::time::SystemTime>,
+    sunset: Option<std::time::SystemTime>,
+    link: Option<String>,
+}
+
+impl DeprecationNotice {
+    /// Creates a notice with no dates or link.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets when the endpoints became deprecated.
+    #[must_use]
+    pub fn since(mut self, time: std::time::SystemTime) -> Self {
+        self.since = Some(time);
+        self
+    }
+
+    /// Sets when the endpoints will stop responding.
+    #[must_use]
+    pub fn sunset(mut self, time: std::time::SystemTime) -> Self {
+        self.sunset = Some(time);
+        self
+    }
+
+    /// Sets a link to migration documentation.
+    #[must_use]
+    pub fn link(mut self, url: impl Into<String>) -> Self {
+        self.link = Some(url.into());
+        self
+    }
+
+    fn deprecation_value(&self) -> String {
+        match self
+            .since
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        {
+            Some(since) => format!("@{}", since.as_secs()),
+            None => "true".to_string(),
+        }
+    }
+}
+
+/// Middleware that marks responses from deprecated endpoints.
+///
+/// Responses for paths under a registered prefix get a `Deprecation` header
+/// (RFC 9745; `@<unix-seconds>` when a date is set, `true` otherwise), a
+/// `Sunset` header (RFC 8594) when a sunset date is set, and a
+/// `Link: <url>; rel="deprecation"` header when a link is set.
+///
+/// # Example
+///
+/// ```ignore
+/// let mw = DeprecationHeaders::new().deprecate(
+///     "/api/v1",
+///     DeprecationNotice::new()
+///         .sunset(sunset_time)
+///         .link("https://docs.example.com/migrate-v2"),
+/// );
+/// stack.push(mw);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DeprecationHeaders {
+    rules: Vec<(String, DeprecationNotice)>,
+}
+
+impl DeprecationHeaders {
+    /// Creates a middleware with no deprecated paths.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks every path under `prefix` as deprecated.
+    ///
+    /// Prefixes match whole segments: `/v1` covers `/v1` and `/v1/users` but
+    /// not `/v10`. The first matching rule wins.
+    #[must_use]
+    pub fn deprecate(mut self, prefix: impl Into<String>, notice: DeprecationNotice) -> Self {
+        let prefix = prefix.into();
+        let prefix = match prefix.trim_end_matches('/') {
+            "" => "/".to_string(),
+            trimmed => trimmed.to_string(),
+        };
+        self.rules.push((prefix, notice));
+        self
+    }
+
+    fn notice_for(&self, path: &str) -> Option<&DeprecationNotice> {
+        self.rules
+            .iter()
+            .find(|(prefix, _)| {
+                prefix == "/"
+                    || path
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .map(|(_, notice)| notice)
+    }
+}
+
+impl Middleware for DeprecationHeaders {
+    fn after<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a Request,
+        response: Response,
+    ) -> BoxFuture<'a, Response> {
+        let notice = self.notice_for(req.path());
+        Box::pin(async move {
+            let Some(notice) = notice else {
+                return response;
+            };
+            let mut response =
+                response.header("deprecation", notice.deprecation_value().into_bytes());
+            if let Some(sunset) = notice.sunset {
+                response = response.header("sunset", format_http_date(sunset).into_bytes());
+            }
+            if let Some(link) = &notice.link {
+                response = response.header(
+                    "link",
+                    format!("<{link}>; rel=\"deprecation\"").into_bytes(),
+                );
+            }
+            response
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "DeprecationHeaders"
+    }
+
+    fn check(&self) -> Vec<crate::check::CheckIssue> {
+        self.rules
+            .iter()
+            .filter(|(_, notice)| matches!((notice.since, notice.sunset), (Some(since), Some(sunset)) if sunset < since))
+            .map(|(prefix, _)| {
+                crate::check::CheckIssue::warning(
+                    "deprecation",
+                    format!("sunset for `{prefix}` is earlier than its deprecation date"),
+                )
+            })
+            .collect()
+    }
+}
+
+/// A query parameter renamed, handled by [`DeprecatedParams`].
+#[derive(Debug, Clone)]
+pub struct DeprecatedParam {
+    name: String,
+    replacement: String,
+}
+
+impl DeprecatedParam {
+    /// Marks the `name` query parameter as replaced by `replacement`.
+    #[must_use]
+    pub fn new(name: impl Into<String>, replacement: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            replacement: replacement.into(),
+        }
+    }
+
+    /// The old parameter name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The parameter name clients should send instead.
+    #[must_use]
+    pub fn replacement(&self) -> &str {
+        &self.replacement
+    }
+
+    fn warning(&self) -> String {
+        format!(
+            "299 - \"Query parameter '{}' is deprecated; use '{}'\"",
+            self.name, self.replacement
+        )
+    }
+}
+
+/// Indices of the [`DeprecatedParam`]s a request used.
+struct DeprecatedParamsSeen(Vec<usize>);
+
+/// Middleware that keeps renamed query parameters working while clients
+/// migrate.
+///
+/// Old names are rewritten to their replacements before extraction, so
+/// handlers only ever see the new name; when a client sends both, the new
+/// one wins. Each use is logged at WARN level and reported in a
+/// `Warning: 299 - "..."` response header.
+///
+/// `Query<T>` also honours `#[serde(alias = "...")]`, which accepts an old
+/// name without any warning.
+///
+/// # Example
+///
+/// ```ignore
+/// let mw = DeprecatedParams::new()
+///     .param(DeprecatedParam::new("q", "query"))
+///     .param(DeprecatedParam::new("per_page", "limit"));
+/// stack.push(mw);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeprecatedParams {
+    params: Vec<DeprecatedParam>,
+    log_config: LogConfig,
+}
+
+impl Default for DeprecatedParams {
+    fn default() -> Self {
+        Self {
+            params: Vec::new(),
+            log_config: LogConfig::production(),
+        }
+    }
+}
+
+impl DeprecatedParams {
+    /// Creates a middleware with no deprecated parameters.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a renamed parameter.
+    #[must_use]
+    pub fn param(mut self, param: DeprecatedParam) -> Self {
+        self.params.push(param);
+        self
+    }
+
+    /// Sets the logging configuration for the deprecation warnings.
+    #[must_use]
+    pub fn log_config(mut self, config: LogConfig) -> Self {
+        self.log_config = config;
+        self
+    }
+
+    /// Rewrites deprecated names in `query` to their replacements, dropping
+    /// them when the replacement is present too. Values are kept verbatim.
+    fn rewrite(&self, query: &str, present: &crate::extract::QueryParams) -> String {
+        query
+            .split('&')
+            .filter_map(|pair| {
+                let key = crate::extract::QueryParams::parse(pair)
+                    .keys()
+                    .next()
+                    .map(str::to_owned);
+                let Some(param) = key.and_then(|k| self.params.iter().find(|p| p.name == k)) else {
+                    return Some(pair.to_string());
+                };
+                if present.contains(&param.replacement) {
+                    return None;
+                }
+                Some(match pair.split_once('=') {
+                    Some((_, value)) => format!("{}={value}", param.replacement),
+                    None => param.replacement.clone(),
+                })
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+impl Middleware for DeprecatedParams {
+    fn before<'a>(
+        &'a self,
+        ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        if let Some(query) = req.query().map(str::to_owned) {
+            let present = crate::extract::QueryParams::parse(&query);
+            let seen: Vec<usize> = self
+                .params
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| present.contains(&p.name))
+                .map(|(i, _)| i)
+                .collect();
+
+            if !seen.is_empty() {
+                let logger = RequestLogger::new(ctx, self.log_config.clone());
+                for param in seen.iter().map(|&i| &self.params[i]) {
+                    logger.warn_with_fields(
+                        format!("deprecated query parameter `{}` used", param.name),
+                        |entry| {
+                            entry
+                                .field("replacement", &param.replacement)
+                                .field("path", req.path())
+                        },
+                    );
+                }
+                req.set_query(Some(self.rewrite(&query, &present)));
+                req.insert_extension(DeprecatedParamsSeen(seen));
+            }
+        }
+        Box::pin(async { ControlFlow::Continue })
+    }
+
+    fn after<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a Request,
+        response: Response,
+    ) -> BoxFuture<'a, Response> {
+        let warning = req.get_extension::<DeprecatedParamsSeen>().map(|seen| {
+            seen.0
+                .iter()
+                .map(|&i| self.params[i].warning())
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+        Box::pin(async move {
+            match warning {
+                Some(warning) => response.header("warning", warning.into_bytes()),
+                None => response,
+            }
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "DeprecatedParams"
+    }
+
+    fn check(&self) -> Vec<crate::check::CheckIssue> {
+        self.params
+            .iter()
+            .filter(|p| p.name == p.replacement)
+            .map(|p| {
+                crate::check::CheckIssue::warning(
+                    "deprecated-params",
+                    format!("query parameter `{}` is its own replacement", p.name),
+                )
+            })
+            .collect()
+    }
+}
+
 /// Middleware that sets response status code based on a condition.
 ///
 /// This is useful for implementing health checks or conditional responses.
@@ -1039,6 +1398,38 @@ impl Middleware for Cors {
     fn name(&self) -> &'static str {
         "Cors"
     }
+
+    fn check(&self) -> Vec<crate::check::CheckIssue> {
+        use crate::check::CheckIssue;
+
+        let config = &self.config;
+        let mut issues = Vec::new();
+        if config.allow_any_origin && config.allow_credentials {
+            issues.push(CheckIssue::warning(
+                "cors",
+                "allow_any_origin with allow_credentials reflects every origin with credentials",
+            ));
+        }
+        if !config.allow_any_origin && config.origins.is_empty() {
+            issues.push(CheckIssue::warning(
+                "cors",
+                "no allowed origins configured; every cross-origin request is rejected",
+            ));
+        }
+        if config.allow_credentials && config.allowed_headers.iter().any(|h| h == "*") {
+            issues.push(CheckIssue::error(
+                "cors",
+                "allowed header `*` is not honored by browsers when credentials are allowed",
+            ));
+        }
+        if config.allow_credentials && config.expose_headers.iter().any(|h| h == "*") {
+            issues.push(CheckIssue::error(
+                "cors",
+                "exposed header `*` is not honored by browsers when credentials are allowed",
+            ));
+        }
+        issues
+    }
 }
 
 fn wildcard_match(pattern: &str, value: &str) -> bool {
@@ -1315,7 +1706,7 @@ impl Middleware for RequestResponseLogger {
     }
 }
 
-fn default_redacted_headers() -> HashSet<String> {
+pub(crate) fn default_redacted_headers() -> HashSet<String> {
     [
         "authorization",
         "proxy-authorization",
@@ -2819,6 +3210,22 @@ pub trait KeyExtractor: Send + Sync {
     fn extract_key(&self, req: &Request) -> Option<String>;
 }
 
+/// Any `Fn(&Request) -> Option<String>` closure is a key extractor.
+///
+/// ```ignore
+/// let limiter = RateLimitMiddleware::builder()
+///     .key_extractor(|req: &Request| req.get_extension::<ApiKey>().map(|k| k.0.clone()))
+///     .build();
+/// ```
+impl<F> KeyExtractor for F
+where
+    F: Fn(&Request) -> Option<String> + Send + Sync,
+{
+    fn extract_key(&self, req: &Request) -> Option<String> {
+        self(req)
+    }
+}
+
 /// The remote address (peer IP) of the TCP connection.
 ///
 /// This should be set by the HTTP server layer as a request extension to enable
@@ -3041,7 +3448,7 @@ impl KeyExtractor for TrustedProxyIpKeyExtractor {
 }
 
 /// Parse a CIDR string like "192.168.1.0/24" into (ip, prefix_length).
-fn parse_cidr(cidr: &str) -> Option<(std::net::IpAddr, u8)> {
+pub(crate) fn parse_cidr(cidr: &str) -> Option<(std::net::IpAddr, u8)> {
     let (ip_str, prefix_str) = cidr.split_once('/')?;
     let ip: std::net::IpAddr = ip_str.parse().ok()?;
     let prefix: u8 = prefix_str.parse().ok()?;
@@ -3059,7 +3466,7 @@ fn parse_cidr(cidr: &str) -> Option<(std::net::IpAddr, u8)> {
 }
 
 /// Check if an IP address is within a CIDR range.
-fn ip_in_cidr(ip: std::net::IpAddr, cidr_ip: std::net::IpAddr, prefix: u8) -> bool {
+pub(crate) fn ip_in_cidr(ip: std::net::IpAddr, cidr_ip: std::net::IpAddr, prefix: u8) -> bool {
     match (ip, cidr_ip) {
         (std::net::IpAddr::V4(ip), std::net::IpAddr::V4(cidr)) => {
             if prefix == 0 {
@@ -3530,6 +3937,66 @@ impl Default for InMemoryRateLimitStore {
     }
 }
 
+/// Storage backend for rate limit counters.
+///
+/// [`InMemoryRateLimitStore`] keeps counters in the process. Implement this
+/// trait to share limits across instances through an external store such as
+/// Redis, and pass it to [`RateLimitBuilder::build_with_store`].
+pub trait RateLimitStore: Send + Sync {
+    /// Records a request for `key` and reports whether it is allowed.
+    fn check<'a>(
+        &'a self,
+        key: &'a str,
+        algorithm: RateLimitAlgorithm,
+        max_requests: u64,
+        window: Duration,
+    ) -> BoxFuture<'a, RateLimitResult>;
+
+    /// Like [`check`](Self::check), with `now` taken from the request's
+    /// [`Clock`](crate::clock::Clock).
+    ///
+    /// Stores that track time themselves can keep the default, which ignores
+    /// `now`; stores that can honour it make their windows follow virtual
+    /// time in tests.
+    fn check_at<'a>(
+        &'a self,
+        key: &'a str,
+        algorithm: RateLimitAlgorithm,
+        max_requests: u64,
+        window: Duration,
+        now: Instant,
+    ) -> BoxFuture<'a, RateLimitResult> {
+        let _ = now;
+        self.check(key, algorithm, max_requests, window)
+    }
+}
+
+impl RateLimitStore for InMemoryRateLimitStore {
+    fn check<'a>(
+        &'a self,
+        key: &'a str,
+        algorithm: RateLimitAlgorithm,
+        max_requests: u64,
+        window: Duration,
+    ) -> BoxFuture<'a, RateLimitResult> {
+        let result = InMemoryRateLimitStore::check(self, key, algorithm, max_requests, window);
+        Box::pin(std::future::ready(result))
+    }
+
+    fn check_at<'a>(
+        &'a self,
+        key: &'a str,
+        algorithm: RateLimitAlgorithm,
+        max_requests: u64,
+        window: Duration,
+        now: Instant,
+    ) -> BoxFuture<'a, RateLimitResult> {
+        let result =
+            InMemoryRateLimitStore::check_at(self, key, algorithm, max_requests, window, now);
+        Box::pin(std::future::ready(result))
+    }
+}
+
 /// Configuration for the rate limiting middleware.
 ///
 /// Controls request rate limits using token bucket or sliding window algorithms.
@@ -3548,9 +4015,10 @@ impl Default for InMemoryRateLimitStore {
 ///
 /// # Response Headers (when `include_headers` is `true`)
 ///
-/// - `X-RateLimit-Limit`: Maximum requests per window
-/// - `X-RateLimit-Remaining`: Remaining requests in current window
-/// - `X-RateLimit-Reset`: Seconds until window resets
+/// - `RateLimit-Limit` / `X-RateLimit-Limit`: Maximum requests per window
+/// - `RateLimit-Remaining` / `X-RateLimit-Remaining`: Remaining requests in current window
+/// - `RateLimit-Reset` / `X-RateLimit-Reset`: Seconds until window resets
+/// - `RateLimit-Policy`: The quota and window, e.g. `100;w=60`
 /// - `Retry-After`: Seconds to wait (only on 429 responses)
 ///
 /// # Example
@@ -3678,16 +4146,24 @@ impl RateLimitBuilder {
         self
     }
 
-    /// Build the rate limiting middleware.
+    /// Build the rate limiting middleware with an in-memory store.
     #[must_use]
     pub fn build(self) -> RateLimitMiddleware {
+        let store = InMemoryRateLimitStore::with_max_keys(self.config.max_keys);
+        self.build_with_store(store)
+    }
+
+    /// Build the rate limiting middleware backed by `store`.
+    ///
+    /// [`max_keys`](Self::max_keys) only applies to the in-memory store.
+    #[must_use]
+    pub fn build_with_store<S: RateLimitStore>(self, store: S) -> RateLimitMiddleware<S> {
         let key_extractor = self
             .key_extractor
             .unwrap_or_else(|| Box::new(IpKeyExtractor));
-        let store = Arc::new(InMemoryRateLimitStore::with_max_keys(self.config.max_keys));
         RateLimitMiddleware {
             config: self.config,
-            store,
+            store: Arc::new(store),
             key_extractor: Arc::from(key_extractor),
         }
     }
@@ -3727,12 +4203,15 @@ struct RateLimitInfo {
 ///     .middleware(rate_limiter)
 ///     .build();
 /// ```
-pub struct RateLimitMiddleware {
+pub struct RateLimitMiddleware<S = InMemoryRateLimitStore> {
     config: RateLimitConfig,
-    store: Arc<InMemoryRateLimitStore>,
+    store: Arc<S>,
     key_extractor: Arc<dyn KeyExtractor>,
 }
 
+/// Rate limiting layer; an alias for [`RateLimitMiddleware`].
+pub type RateLimitLayer<S = InMemoryRateLimitStore> = RateLimitMiddleware<S>;
+
 impl RateLimitMiddleware {
     /// Create a new rate limiter with default settings (100 requests/minute, token bucket, IP-based).
     #[must_use]
@@ -3745,7 +4224,9 @@ impl RateLimitMiddleware {
     pub fn builder() -> RateLimitBuilder {
         RateLimitBuilder::new()
     }
+}
 
+impl<S> RateLimitMiddleware<S> {
     /// Format a 429 response body as JSON.
     fn too_many_requests_body(&self, result: &RateLimitResult) -> Vec<u8> {
         format!(
@@ -3757,7 +4238,18 @@ impl RateLimitMiddleware {
 
     /// Add rate limit headers to a response.
     fn add_headers(&self, response: Response, result: &RateLimitResult) -> Response {
+        let policy = format!("{};w={}", result.limit, self.config.window.as_secs());
         response
+            .header("RateLimit-Limit", result.limit.to_string().into_bytes())
+            .header(
+                "RateLimit-Remaining",
+                result.remaining.to_string().into_bytes(),
+            )
+            .header(
+                "RateLimit-Reset",
+                result.reset_after_secs.to_string().into_bytes(),
+            )
+            .header("RateLimit-Policy", policy.into_bytes())
             .header("X-RateLimit-Limit", result.limit.to_string().into_bytes())
             .header(
                 "X-RateLimit-Remaining",
@@ -3776,10 +4268,10 @@ impl Default for RateLimitMiddleware {
     }
 }
 
-impl Middleware for RateLimitMiddleware {
+impl<S: RateLimitStore + 'static> Middleware for RateLimitMiddleware<S> {
     fn before<'a>(
         &'a self,
-        _ctx: &'a RequestContext,
+        ctx: &'a RequestContext,
         req: &'a mut Request,
     ) -> BoxFuture<'a, ControlFlow> {
         Box::pin(async move {
@@ -3790,12 +4282,16 @@ impl Middleware for RateLimitMiddleware {
             };
 
             // Check the rate limit
-            let result = self.store.check(
-                &key,
-                self.config.algorithm,
-                self.config.max_requests,
-                self.config.window,
-            );
+            let result = self
+                .store
+                .check_at(
+                    &key,
+                    self.config.algorithm,
+                    self.config.max_requests,
+                    self.config.window,
+                    ctx.clock().now(),
+                )
+                .await;
 
             if result.allowed {
                 // Store the result for the `after` hook to add headers
@@ -3851,6 +4347,131 @@ impl Middleware for RateLimitMiddleware {
 // End Rate Limiting Middleware
 // ---------------------------------------------------------------------------
 
+/// Request extension marking the matched route as safe to retry.
+///
+/// Inserted by the app for routes declared `idempotent`
+/// (`#[post("/jobs", idempotent)]` or [`fastapi_router::Route::idempotent`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetrySafe;
+
+/// Middleware that tells clients when to retry idempotent operations.
+///
+/// `503 Service Unavailable` and `429 Too Many Requests` responses for routes
+/// marked [`RetrySafe`] get a `Retry-After` header unless one is already set
+/// (the rate limiter sets its own from the window). The delay starts at
+/// `base` and doubles with each consecutive unavailable response, up to
+/// `max`; any other response resets it. This way overload, maintenance and
+/// shutdown responses from inner layers steer retries away from a struggling
+/// server instead of inviting a retry storm.
+///
+/// Routes that are not retry-safe are left alone, since clients should not
+/// resend a non-idempotent request on their own.
+///
+/// Push it first so its `after` hook sees responses from every other layer.
+///
+/// # Example
+///
+/// ```ignore
+/// let app = App::builder()
+///     .middleware(RetryAfterMiddleware::new().max(Duration::from_secs(30)))
+///     .middleware(rate_limiter)
+///     .build();
+/// ```
+#[derive(Debug)]
+pub struct RetryAfterMiddleware {
+    base: Duration,
+    max: Duration,
+    streak: std::sync::atomic::AtomicU32,
+}
+
+impl RetryAfterMiddleware {
+    /// Creates the middleware with a 1 second base delay capped at 60 seconds.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            base: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+            streak: std::sync::atomic::AtomicU32::new(0),
+        }
+    }
+
+    /// Sets the delay advertised for the first unavailable response.
+    #[must_use]
+    pub fn base(mut self, delay: Duration) -> Self {
+        self.base = delay;
+        self
+    }
+
+    /// Sets the longest delay ever advertised.
+    #[must_use]
+    pub fn max(mut self, delay: Duration) -> Self {
+        self.max = delay;
+        self
+    }
+
+    /// Records one more unavailable response and returns the delay in whole
+    /// seconds (at least 1).
+    fn next_delay_secs(&self) -> u64 {
+        use std::sync::atomic::Ordering;
+
+        let streak = self.streak.fetch_add(1, Ordering::Relaxed);
+        let factor = 1u32.checked_shl(streak.min(31)).unwrap_or(u32::MAX);
+        let delay = self.base.saturating_mul(factor).min(self.max);
+        let secs = delay.as_secs() + u64::from(delay.subsec_nanos() > 0);
+        secs.max(1)
+    }
+}
+
+impl Default for RetryAfterMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Middleware for RetryAfterMiddleware {
+    fn after<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a Request,
+        response: Response,
+    ) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            let status = response.status().as_u16();
+            if status != 503 && status != 429 {
+                if status < 500 {
+                    self.streak.store(0, std::sync::atomic::Ordering::Relaxed);
+                }
+                return response;
+            }
+            if req.get_extension::<RetrySafe>().is_none()
+                || response
+                    .headers()
+                    .iter()
+                    .any(|(name, _)| name.eq_ignore_ascii_case("retry-after"))
+            {
+                return response;
+            }
+            let secs = self.next_delay_secs();
+            response.header("Retry-After", secs.to_string().into_bytes())
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "RetryAfter"
+    }
+
+    fn check(&self) -> Vec<crate::check::CheckIssue> {
+        if self.base > self.max {
+            vec![crate::check::CheckIssue::warning(
+                "retry-after",
+                "RetryAfterMiddleware base delay is longer than its maximum",
+            )]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
 // ============================================================================
 // Request Inspection Middleware (Development)
 // ============================================================================
@@ -5207,7 +5828,7 @@ impl CacheControlMiddleware {
 }
 
 /// Simple path pattern matching (supports * wildcard).
-fn path_matches_pattern(path: &str, pattern: &str) -> bool {
+pub(crate) fn path_matches_pattern(path: &str, pattern: &str) -> bool {
     if pattern == "*" {
         return true;
     }
@@ -9721,6 +10342,147 @@ mod tests {
         assert_eq!(mw.name(), "AddResponseHeader");
     }
 
+    // =========================================================================
+    // DeprecationHeaders Middleware Tests
+    // =========================================================================
+
+    #[test]
+    fn deprecation_headers_mark_matching_paths() {
+        let since = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_688_169_599);
+        let sunset = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_445_412_480);
+        let mw = DeprecationHeaders::new().deprecate(
+            "/v1/",
+            DeprecationNotice::new()
+                .since(since)
+                .sunset(sunset)
+                .link("https://docs.example.com/v2"),
+        );
+        let ctx = test_context();
+
+        let req = Request::new(crate::request::Method::Get, "/v1/users");
+        let response = futures_executor::block_on(mw.after(&ctx, &req, Response::ok()));
+        assert_eq!(
+            header_value(&response, "deprecation"),
+            Some("@1688169599".to_string())
+        );
+        assert_eq!(
+            header_value(&response, "sunset"),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string())
+        );
+        assert_eq!(
+            header_value(&response, "link"),
+            Some("<https://docs.example.com/v2>; rel=\"deprecation\"".to_string())
+        );
+
+        let req = Request::new(crate::request::Method::Get, "/v10/users");
+        let response = futures_executor::block_on(mw.after(&ctx, &req, Response::ok()));
+        assert_eq!(header_value(&response, "deprecation"), None);
+
+        // The sunset precedes the deprecation date, which `App::check` flags.
+        assert_eq!(mw.check().len(), 1);
+    }
+
+    #[test]
+    fn deprecation_headers_without_dates() {
+        let mw = DeprecationHeaders::new().deprecate("/old", DeprecationNotice::new());
+        let ctx = test_context();
+        let req = Request::new(crate::request::Method::Get, "/old");
+        let response = futures_executor::block_on(mw.after(&ctx, &req, Response::ok()));
+        assert_eq!(
+            header_value(&response, "deprecation"),
+            Some("true".to_string())
+        );
+        assert_eq!(header_value(&response, "sunset"), None);
+        assert!(mw.check().is_empty());
+    }
+
+    #[test]
+    fn deprecated_params_are_renamed_and_reported() {
+        let mw = DeprecatedParams::new()
+            .param(DeprecatedParam::new("q", "query"))
+            .param(DeprecatedParam::new("per_page", "limit"));
+        let ctx = test_context();
+
+        let mut req = Request::new(crate::request::Method::Get, "/search");
+        req.set_query(Some("q=a%20b&page=2&per_page=5&limit=10".to_string()));
+        futures_executor::block_on(mw.before(&ctx, &mut req));
+        assert_eq!(req.query(), Some("query=a%20b&page=2&limit=10"));
+
+        let response = futures_executor::block_on(mw.after(&ctx, &req, Response::ok()));
+        assert_eq!(
+            header_value(&response, "warning"),
+            Some(
+                "299 - \"Query parameter 'q' is deprecated; use 'query'\", \
+                 299 - \"Query parameter 'per_page' is deprecated; use 'limit'\""
+                    .to_string()
+            )
+        );
+
+        let mut req = Request::new(crate::request::Method::Get, "/search");
+        req.set_query(Some("query=a".to_string()));
+        futures_executor::block_on(mw.before(&ctx, &mut req));
+        assert_eq!(req.query(), Some("query=a"));
+        let response = futures_executor::block_on(mw.after(&ctx, &req, Response::ok()));
+        assert_eq!(header_value(&response, "warning"), None);
+    }
+
+    #[test]
+    fn retry_after_backs_off_for_retry_safe_routes() {
+        let mw = RetryAfterMiddleware::new().max(Duration::from_secs(3));
+        let ctx = test_context();
+        let mut req = Request::new(crate::request::Method::Post, "/jobs");
+        req.insert_extension(RetrySafe);
+        let unavailable = || Response::with_status(StatusCode::SERVICE_UNAVAILABLE);
+
+        let delays: Vec<_> = (0..4)
+            .map(|_| {
+                let response = futures_executor::block_on(mw.after(&ctx, &req, unavailable()));
+                header_value(&response, "retry-after")
+            })
+            .collect();
+        assert_eq!(
+            delays,
+            ["1", "2", "3", "3"].map(|s| Some(s.to_string())).to_vec()
+        );
+
+        // A successful response resets the backoff.
+        futures_executor::block_on(mw.after(&ctx, &req, Response::ok()));
+        let response = futures_executor::block_on(mw.after(&ctx, &req, unavailable()));
+        assert_eq!(
+            header_value(&response, "retry-after"),
+            Some("1".to_string())
+        );
+    }
+
+    #[test]
+    fn retry_after_leaves_other_routes_and_existing_headers_alone() {
+        let mw = RetryAfterMiddleware::new();
+        let ctx = test_context();
+
+        let req = Request::new(crate::request::Method::Post, "/orders");
+        let response = futures_executor::block_on(mw.after(
+            &ctx,
+            &req,
+            Response::with_status(StatusCode::SERVICE_UNAVAILABLE),
+        ));
+        assert_eq!(header_value(&response, "retry-after"), None);
+
+        let mut req = Request::new(crate::request::Method::Post, "/jobs");
+        req.insert_extension(RetrySafe);
+        let response = futures_executor::block_on(
+            mw.after(
+                &ctx,
+                &req,
+                Response::with_status(StatusCode::TOO_MANY_REQUESTS)
+                    .header("Retry-After", b"42".to_vec()),
+            ),
+        );
+        assert_eq!(
+            header_value(&response, "retry-after"),
+            Some("42".to_string())
+        );
+    }
+
     // =========================================================================
     // RequireHeader Middleware Tests
     // =========================================================================
@@ -12782,6 +13544,75 @@ mod rate_limit_tests {
         assert_eq!(limit_val, "10");
     }
 
+    #[test]
+    fn rate_limit_sets_standard_headers() {
+        let mw = RateLimitMiddleware::builder()
+            .requests(10)
+            .per(Duration::from_secs(60))
+            .algorithm(RateLimitAlgorithm::FixedWindow)
+            .build();
+
+        let mut req = request_with_ip("10.0.0.1");
+        assert!(run_rate_limit_before(&mw, &mut req).is_continue());
+        let resp = run_rate_limit_after(&mw, &req, Response::with_status(StatusCode::OK));
+
+        let header = |name: &str| {
+            resp.headers()
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| std::str::from_utf8(v).unwrap().to_string())
+        };
+        assert_eq!(header("ratelimit-limit").as_deref(), Some("10"));
+        assert_eq!(header("ratelimit-remaining").as_deref(), Some("9"));
+        assert!(header("ratelimit-reset").is_some());
+        assert_eq!(header("ratelimit-policy").as_deref(), Some("10;w=60"));
+    }
+
+    /// Allows every request and records the keys it saw.
+    #[derive(Default)]
+    struct RecordingStore {
+        keys: Mutex<Vec<String>>,
+    }
+
+    impl RateLimitStore for RecordingStore {
+        fn check<'a>(
+            &'a self,
+            key: &'a str,
+            _algorithm: RateLimitAlgorithm,
+            max_requests: u64,
+            _window: Duration,
+        ) -> BoxFuture<'a, RateLimitResult> {
+            self.keys.lock().push(key.to_string());
+            Box::pin(std::future::ready(RateLimitResult {
+                allowed: true,
+                limit: max_requests,
+                remaining: max_requests,
+                reset_after_secs: 0,
+            }))
+        }
+    }
+
+    #[test]
+    fn rate_limit_uses_closure_keys_and_custom_store() {
+        let mw = RateLimitLayer::builder()
+            .key_extractor(|req: &Request| {
+                req.headers()
+                    .get("x-tenant")
+                    .map(|v| format!("tenant:{}", String::from_utf8_lossy(v)))
+            })
+            .build_with_store(RecordingStore::default());
+
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/");
+        req.headers_mut().insert("x-tenant", b"acme".to_vec());
+        assert!(futures_executor::block_on(mw.before(&ctx, &mut req)).is_continue());
+
+        let mut anonymous = Request::new(Method::Get, "/");
+        assert!(futures_executor::block_on(mw.before(&ctx, &mut anonymous)).is_continue());
+
+        assert_eq!(*mw.store.keys.lock(), vec!["tenant:acme".to_string()]);
+    }
+
     #[test]
     fn rate_limit_429_response_has_retry_after() {
         let mw = RateLimitMiddleware::builder()
@@ -7,8 +7,13 @@ use std::collections::HashMap;
 use std::fs::OpenOptions;
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
+
+use asupersync::stream::Stream;
+
+use crate::blocking::run_blocking;
+use crate::extract::{MultipartExtractError, multipart_parser_error, multipart_stream_error};
+use crate::request::{Body, RequestBodyStream};
+use crate::upload_policy::{SNIFF_LEN, UploadPolicy, UploadPolicyError};
 
 /// Default maximum file size (10MB).
 pub const DEFAULT_MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
@@ -225,6 +230,9 @@ impl Part {
     }
 
     /// Read part bytes regardless of in-memory or spooled backing.
+    ///
+    /// For a spooled part this reads the temporary file on the calling
+    /// thread; in async code prefer [`UploadFile::read`], which does not.
     pub fn bytes(&self) -> std::io::Result<Vec<u8>> {
         if let Some(path) = &self.spooled_path {
             std::fs::read(path)
@@ -241,6 +249,10 @@ enum UploadStorage {
 }
 
 /// An uploaded file with metadata and FastAPI-style async file operations.
+///
+/// For uploads spooled to disk, `read`, `write` and `close` run their file
+/// system calls on a blocking thread pool, so they don't stall the task
+/// serving other requests.
 #[derive(Debug)]
 pub struct UploadFile {
     /// The field name.
@@ -265,6 +277,10 @@ impl UploadFile {
 
     /// Create a new UploadFile from a Part with a custom spool threshold.
     ///
+    /// An in-memory part larger than `spool_threshold` is written to a
+    /// temporary file on the calling thread. Parts produced by the
+    /// [`MultipartForm`] extractor are already spooled off the request task.
+    ///
     /// Returns `None` if the part is not a file.
     #[must_use]
     pub fn from_part_with_spool_threshold(part: Part, spool_threshold: usize) -> Option<Self> {
@@ -333,6 +349,10 @@ impl UploadFile {
     }
 
     /// Read file contents without changing the current cursor.
+    ///
+    /// For a spooled upload this reads the temporary file on the calling
+    /// thread. In async code prefer [`read`](Self::read), which runs on the
+    /// blocking pool.
     pub fn bytes(&self) -> std::io::Result<Vec<u8>> {
         match &self.storage {
             UploadStorage::InMemory(data) => Ok(data.clone()),
@@ -362,17 +382,21 @@ impl UploadFile {
                 Ok(data[start..end].to_vec())
             }
             UploadStorage::SpooledTempFile { path, len } => {
-                let mut file = std::fs::File::open(path)?;
-                file.seek(SeekFrom::Start(self.cursor))?;
-
+                let path = path.clone();
+                let cursor = self.cursor;
                 let max_to_read = match size {
                     Some(n) => u64::try_from(n).unwrap_or(u64::MAX),
-                    None => len.saturating_sub(self.cursor),
+                    None => len.saturating_sub(cursor),
                 };
 
-                let mut reader = file.take(max_to_read);
-                let mut out = Vec::new();
-                reader.read_to_end(&mut out)?;
+                let out = run_blocking(move || {
+                    let mut file = std::fs::File::open(path)?;
+                    file.seek(SeekFrom::Start(cursor))?;
+                    let mut out = Vec::new();
+                    file.take(max_to_read).read_to_end(&mut out)?;
+                    Ok::<_, std::io::Error>(out)
+                })
+                .await?;
                 self.cursor = self
                     .cursor
                     .saturating_add(u64::try_from(out.len()).unwrap_or(u64::MAX));
@@ -406,9 +430,15 @@ impl UploadFile {
                 Ok(bytes.len())
             }
             UploadStorage::SpooledTempFile { path, len } => {
-                let mut file = OpenOptions::new().read(true).write(true).open(path)?;
-                file.seek(SeekFrom::Start(self.cursor))?;
-                file.write_all(bytes)?;
+                let path = path.clone();
+                let cursor = self.cursor;
+                let data = bytes.to_vec();
+                run_blocking(move || {
+                    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+                    file.seek(SeekFrom::Start(cursor))?;
+                    file.write_all(&data)
+                })
+                .await?;
                 self.cursor = self
                     .cursor
                     .saturating_add(u64::try_from(bytes.len()).unwrap_or(u64::MAX));
@@ -435,7 +465,8 @@ impl UploadFile {
         }
 
         if let UploadStorage::SpooledTempFile { path, .. } = &self.storage {
-            match std::fs::remove_file(path) {
+            let path = path.clone();
+            match run_blocking(move || std::fs::remove_file(path)).await {
                 Ok(()) => {}
                 Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
                 Err(err) => return Err(err),
@@ -503,9 +534,25 @@ fn resolve_seek(current: u64, len: u64, position: SeekFrom) -> std::io::Result<u
     })
 }
 
-static UPLOAD_SPOOL_COUNTER: AtomicU64 = AtomicU64::new(1);
+/// Whether large uploads can be spooled to a temporary file. WASI has no
+/// temporary directory, so uploads stay in memory there.
+const SPOOLING_SUPPORTED: bool = cfg!(not(target_family = "wasm"));
 
+#[cfg(target_family = "wasm")]
 fn create_spool_tempfile() -> std::io::Result<(PathBuf, std::fs::File)> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "upload spooling is not available on wasm targets",
+    ))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn create_spool_tempfile() -> std::io::Result<(PathBuf, std::fs::File)> {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    static UPLOAD_SPOOL_COUNTER: AtomicU64 = AtomicU64::new(1);
+
     let temp_dir = std::env::temp_dir();
     let ts_nanos = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -571,7 +618,7 @@ pub fn parse_boundary(content_type: &str) -> Result<String, MultipartError> {
 }
 
 /// Multipart parser (boundary-based).
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MultipartParser {
     boundary: Vec<u8>,
     config: MultipartConfig,
@@ -585,15 +632,50 @@ pub struct MultipartStreamState {
     part_count: usize,
     total_size: usize,
     current_part: Option<StreamingPartState>,
+    streamed_part: Option<StreamedPartState>,
 }
 
-#[derive(Debug, Clone)]
+/// Size accounting for a part whose data is handed out as it arrives
+/// instead of being collected.
+#[derive(Debug, Clone, Copy)]
+struct StreamedPartState {
+    is_file: bool,
+    size: usize,
+}
+
+/// Boundary and headers of a multipart part.
+#[derive(Debug)]
+pub(crate) struct PartHead {
+    name: String,
+    filename: Option<String>,
+    content_type: Option<String>,
+    headers: HashMap<String, String>,
+}
+
+/// Event produced by [`MultipartParser::next_event`].
+#[derive(Debug)]
+pub(crate) enum MultipartEvent {
+    /// A new part begins; its data follows.
+    PartStart(PartHead),
+    /// A chunk of the current part's data.
+    Data(Vec<u8>),
+    /// The current part is complete.
+    PartEnd,
+}
+
+#[derive(Debug)]
 enum PartStreamingStorage {
     InMemory(Vec<u8>),
-    SpooledTempFile { path: PathBuf, len: usize },
+    /// The file stays open until the part is complete, so each chunk is one
+    /// write rather than an open, append and close.
+    SpooledTempFile {
+        path: PathBuf,
+        file: std::fs::File,
+        len: usize,
+    },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 struct StreamingPartState {
     name: String,
     filename: Option<String>,
@@ -648,7 +730,10 @@ impl StreamingPartState {
 
         match &mut self.storage {
             PartStreamingStorage::InMemory(data) => {
-                if self.filename.is_some() && next_size > config.spool_threshold {
+                if SPOOLING_SUPPORTED
+                    && self.filename.is_some()
+                    && next_size > config.spool_threshold
+                {
                     let (path, mut file) =
                         create_spool_tempfile().map_err(|e| MultipartError::Io {
                             detail: format!("failed to create spool tempfile: {e}"),
@@ -661,20 +746,14 @@ impl StreamingPartState {
                     })?;
                     self.storage = PartStreamingStorage::SpooledTempFile {
                         path,
+                        file,
                         len: next_size,
                     };
                 } else {
                     data.extend_from_slice(chunk);
                 }
             }
-            PartStreamingStorage::SpooledTempFile { path, len } => {
-                let mut file =
-                    OpenOptions::new()
-                        .append(true)
-                        .open(path)
-                        .map_err(|e| MultipartError::Io {
-                            detail: format!("failed to open spool tempfile for append: {e}"),
-                        })?;
+            PartStreamingStorage::SpooledTempFile { file, len, .. } => {
                 file.write_all(chunk).map_err(|e| MultipartError::Io {
                     detail: format!("failed to append spool tempfile: {e}"),
                 })?;
@@ -697,7 +776,7 @@ impl StreamingPartState {
                 let len = data.len();
                 (data, None, Some(len))
             }
-            PartStreamingStorage::SpooledTempFile { path, len } => {
+            PartStreamingStorage::SpooledTempFile { path, len, .. } => {
                 (Vec::new(), Some(path), Some(len))
             }
         };
@@ -728,6 +807,14 @@ impl MultipartStreamState {
     pub fn is_done(&self) -> bool {
         self.done
     }
+
+    /// Whether parsing `buffered` more bytes may create or write a spool
+    /// file. Data of a part never exceeds the buffered bytes plus what the
+    /// current part already holds.
+    fn may_spool(&self, buffered: usize, config: &MultipartConfig) -> bool {
+        let current = self.current_part.as_ref().map_or(0, |part| part.size);
+        SPOOLING_SUPPORTED && current.saturating_add(buffered) > config.spool_threshold
+    }
 }
 
 impl MultipartParser {
@@ -782,12 +869,10 @@ impl MultipartParser {
             let (name, filename) = parse_content_disposition(content_disp)?;
             let content_type = headers.get("content-type").cloned();
 
-            let data_end = self.find_boundary_from(body, pos)?;
-            let data = if data_end >= 2 && body[data_end - 2..data_end] == *b"\r\n" {
-                &body[pos..data_end - 2]
-            } else {
-                &body[pos..data_end]
-            };
+            // As in `parse_incremental`, only a CRLF within the part data can
+            // start the delimiter; the one ending the header block cannot.
+            let data_end = pos + self.find_boundary_in_part_data(&body[pos..], 0)?;
+            let data = &body[pos..data_end - 2];
 
             if filename.is_some() && data.len() > self.config.max_file_size {
                 return Err(MultipartError::FileTooLarge {
@@ -828,7 +913,6 @@ impl MultipartParser {
     /// - Set `eof = false` while more chunks may still arrive.
     /// - Set `eof = true` on the final call to enforce that the stream ended on
     ///   a valid multipart boundary.
-    #[allow(clippy::too_many_lines)]
     pub fn parse_incremental(
         &self,
         buffer: &mut Vec<u8>,
@@ -842,100 +926,16 @@ impl MultipartParser {
                 return Ok(parsed);
             }
 
-            if !state.started {
-                match self.find_boundary_from(buffer, 0) {
-                    Ok(boundary_pos) => {
-                        state.started = true;
-                        if boundary_pos > 0 {
-                            buffer.drain(..boundary_pos);
-                        }
-                    }
-                    Err(MultipartError::UnexpectedEof) => {
-                        if eof {
-                            return Err(MultipartError::UnexpectedEof);
-                        }
-                        // Keep only the suffix that could still contain a split boundary.
-                        let keep = self.boundary.len().saturating_add(4);
-                        if buffer.len() > keep {
-                            let drain_to = buffer.len() - keep;
-                            buffer.drain(..drain_to);
-                        }
-                        return Ok(parsed);
-                    }
-                    Err(err) => return Err(err),
-                }
-            }
-
             if state.current_part.is_none() {
-                if !buffer.starts_with(&self.boundary) {
-                    match self.find_boundary_from(buffer, 0) {
-                        Ok(boundary_pos) => {
-                            if boundary_pos > 0 {
-                                buffer.drain(..boundary_pos);
-                            }
-                        }
-                        Err(MultipartError::UnexpectedEof) => {
-                            if eof {
-                                return Err(MultipartError::UnexpectedEof);
-                            }
-                            return Ok(parsed);
-                        }
-                        Err(err) => return Err(err),
-                    }
-                }
-
-                let boundary_end = self.boundary.len();
-                if boundary_end + 2 > buffer.len() {
-                    if eof {
-                        return Err(MultipartError::UnexpectedEof);
-                    }
+                let Some(head) = self.read_part_head(buffer, state, eof)? else {
                     return Ok(parsed);
-                }
-
-                let boundary_suffix = &buffer[boundary_end..boundary_end + 2];
-                if boundary_suffix == b"--" {
-                    state.done = true;
-
-                    // Consume through final boundary marker (+ optional CRLF).
-                    let mut consumed = boundary_end + 2;
-                    if consumed + 2 <= buffer.len() && buffer[consumed..consumed + 2] == *b"\r\n" {
-                        consumed += 2;
-                    }
-                    buffer.drain(..consumed);
-                    return Ok(parsed);
-                }
-
-                if boundary_suffix != b"\r\n" {
-                    return Err(MultipartError::InvalidFormat {
-                        detail: "expected CRLF after boundary",
-                    });
-                }
-
-                let headers_start = boundary_end + 2;
-                let (headers, data_start) = match self.parse_part_headers(buffer, headers_start) {
-                    Ok(v) => v,
-                    Err(MultipartError::UnexpectedEof) => {
-                        if eof {
-                            return Err(MultipartError::UnexpectedEof);
-                        }
-                        return Ok(parsed);
-                    }
-                    Err(err) => return Err(err),
                 };
-
-                let content_disp = headers
-                    .get("content-disposition")
-                    .ok_or(MultipartError::MissingContentDisposition)?;
-                let (name, filename) = parse_content_disposition(content_disp)?;
-                let content_type = headers.get("content-type").cloned();
-
                 state.current_part = Some(StreamingPartState::new(
-                    name,
-                    filename,
-                    content_type,
-                    headers,
+                    head.name,
+                    head.filename,
+                    head.content_type,
+                    head.headers,
                 ));
-                buffer.drain(..data_start);
                 continue;
             }
 
@@ -1000,6 +1000,255 @@ impl MultipartParser {
         }
     }
 
+    /// [`parse_incremental`](Self::parse_incremental) for async callers: a
+    /// call that may spool file data runs on the blocking pool, so writing
+    /// large uploads to disk doesn't stall the task.
+    pub(crate) async fn parse_incremental_async(
+        &self,
+        buffer: &mut Vec<u8>,
+        state: &mut MultipartStreamState,
+        eof: bool,
+    ) -> Result<Vec<Part>, MultipartError> {
+        if !state.may_spool(buffer.len(), &self.config) {
+            return self.parse_incremental(buffer, state, eof);
+        }
+        let parser = self.clone();
+        let mut moved_buffer = std::mem::take(buffer);
+        let mut moved_state = std::mem::take(state);
+        let (moved_buffer, moved_state, result) = run_blocking(move || {
+            let result = parser.parse_incremental(&mut moved_buffer, &mut moved_state, eof);
+            (moved_buffer, moved_state, result)
+        })
+        .await;
+        *buffer = moved_buffer;
+        *state = moved_state;
+        result
+    }
+
+    /// Moves file parts above the spool threshold out of memory into
+    /// temporary files, on the blocking pool. A part that cannot be spooled
+    /// stays in memory.
+    pub(crate) async fn spool_large_files(&self, mut parts: Vec<Part>) -> Vec<Part> {
+        if !SPOOLING_SUPPORTED {
+            return parts;
+        }
+        for part in &mut parts {
+            if !part.is_file()
+                || part.is_spooled()
+                || part.data.len() <= self.config.spool_threshold
+            {
+                continue;
+            }
+            let data = std::mem::take(&mut part.data);
+            let (data, spooled) = run_blocking(move || {
+                let spooled = spool_to_tempfile(&data);
+                (data, spooled)
+            })
+            .await;
+            match spooled {
+                Ok(path) => {
+                    part.spooled_len = Some(data.len());
+                    part.spooled_path = Some(path);
+                }
+                Err(_) => part.data = data,
+            }
+        }
+        parts
+    }
+
+    /// Parse the next event from a streamed multipart buffer, handing part
+    /// data out as it arrives instead of collecting it.
+    ///
+    /// Returns `Ok(None)` when more input is needed, or once the closing
+    /// boundary has been parsed (see [`MultipartStreamState::is_done`]).
+    /// Size and field limits are enforced as with [`parse_incremental`].
+    ///
+    /// [`parse_incremental`]: Self::parse_incremental
+    pub(crate) fn next_event(
+        &self,
+        buffer: &mut Vec<u8>,
+        state: &mut MultipartStreamState,
+        eof: bool,
+    ) -> Result<Option<MultipartEvent>, MultipartError> {
+        if state.done {
+            return Ok(None);
+        }
+
+        if state.streamed_part.is_none() {
+            let Some(head) = self.read_part_head(buffer, state, eof)? else {
+                return Ok(None);
+            };
+            if state.part_count >= self.config.max_fields {
+                return Err(MultipartError::TooManyFields {
+                    count: state.part_count + 1,
+                    max: self.config.max_fields,
+                });
+            }
+            state.part_count += 1;
+            state.streamed_part = Some(StreamedPartState {
+                is_file: head.filename.is_some(),
+                size: 0,
+            });
+            return Ok(Some(MultipartEvent::PartStart(head)));
+        }
+
+        let data_end = match self.find_boundary_in_part_data(buffer, 0) {
+            Ok(pos) => Some(pos),
+            Err(MultipartError::UnexpectedEof) => None,
+            Err(err) => return Err(err),
+        };
+        let chunk_len = match data_end {
+            Some(data_end) if buffer[data_end - 2..data_end] == *b"\r\n" => data_end - 2,
+            Some(data_end) => data_end,
+            None if eof => return Err(MultipartError::UnexpectedEof),
+            // Hold back a suffix that could still contain a split boundary.
+            None => buffer
+                .len()
+                .saturating_sub(self.boundary.len().saturating_add(4)),
+        };
+
+        if chunk_len == 0 {
+            let Some(data_end) = data_end else {
+                return Ok(None);
+            };
+            // Keep the next boundary in-buffer for the next part.
+            buffer.drain(..data_end);
+            state.streamed_part = None;
+            return Ok(Some(MultipartEvent::PartEnd));
+        }
+
+        let Some(part) = state.streamed_part.as_mut() else {
+            return Err(MultipartError::InvalidFormat {
+                detail: "missing current multipart part state",
+            });
+        };
+        let next_size = part.size.saturating_add(chunk_len);
+        if part.is_file && next_size > self.config.max_file_size {
+            return Err(MultipartError::FileTooLarge {
+                size: next_size,
+                max: self.config.max_file_size,
+            });
+        }
+        let next_total = state.total_size.saturating_add(chunk_len);
+        if next_total > self.config.max_total_size {
+            return Err(MultipartError::TotalTooLarge {
+                size: next_total,
+                max: self.config.max_total_size,
+            });
+        }
+        part.size = next_size;
+        state.total_size = next_total;
+        Ok(Some(MultipartEvent::Data(
+            buffer.drain(..chunk_len).collect(),
+        )))
+    }
+
+    /// Parse the boundary and headers of the next part from a streamed buffer,
+    /// draining them.
+    ///
+    /// Returns `Ok(None)` when more input is needed or the closing boundary
+    /// was reached.
+    fn read_part_head(
+        &self,
+        buffer: &mut Vec<u8>,
+        state: &mut MultipartStreamState,
+        eof: bool,
+    ) -> Result<Option<PartHead>, MultipartError> {
+        if !state.started {
+            match self.find_boundary_from(buffer, 0) {
+                Ok(boundary_pos) => {
+                    state.started = true;
+                    if boundary_pos > 0 {
+                        buffer.drain(..boundary_pos);
+                    }
+                }
+                Err(MultipartError::UnexpectedEof) => {
+                    if eof {
+                        return Err(MultipartError::UnexpectedEof);
+                    }
+                    // Keep only the suffix that could still contain a split boundary.
+                    let keep = self.boundary.len().saturating_add(4);
+                    if buffer.len() > keep {
+                        let drain_to = buffer.len() - keep;
+                        buffer.drain(..drain_to);
+                    }
+                    return Ok(None);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        if !buffer.starts_with(&self.boundary) {
+            match self.find_boundary_from(buffer, 0) {
+                Ok(boundary_pos) => {
+                    if boundary_pos > 0 {
+                        buffer.drain(..boundary_pos);
+                    }
+                }
+                Err(MultipartError::UnexpectedEof) => {
+                    if eof {
+                        return Err(MultipartError::UnexpectedEof);
+                    }
+                    return Ok(None);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let boundary_end = self.boundary.len();
+        if boundary_end + 2 > buffer.len() {
+            if eof {
+                return Err(MultipartError::UnexpectedEof);
+            }
+            return Ok(None);
+        }
+
+        let boundary_suffix = &buffer[boundary_end..boundary_end + 2];
+        if boundary_suffix == b"--" {
+            state.done = true;
+
+            // Consume through final boundary marker (+ optional CRLF).
+            let mut consumed = boundary_end + 2;
+            if consumed + 2 <= buffer.len() && buffer[consumed..consumed + 2] == *b"\r\n" {
+                consumed += 2;
+            }
+            buffer.drain(..consumed);
+            return Ok(None);
+        }
+
+        if boundary_suffix != b"\r\n" {
+            return Err(MultipartError::InvalidFormat {
+                detail: "expected CRLF after boundary",
+            });
+        }
+
+        let headers_start = boundary_end + 2;
+        let (headers, data_start) = match self.parse_part_headers(buffer, headers_start) {
+            Ok(v) => v,
+            Err(MultipartError::UnexpectedEof) => {
+                if eof {
+                    return Err(MultipartError::UnexpectedEof);
+                }
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
+        };
+
+        let content_disp = headers
+            .get("content-disposition")
+            .ok_or(MultipartError::MissingContentDisposition)?;
+        let (name, filename) = parse_content_disposition(content_disp)?;
+        let content_type = headers.get("content-type").cloned();
+
+        buffer.drain(..data_start);
+        Ok(Some(PartHead {
+            name,
+            filename,
+            content_type,
+            headers,
+        }))
+    }
+
     fn find_boundary_from(&self, data: &[u8], start: usize) -> Result<usize, MultipartError> {
         let boundary = &self.boundary;
         let boundary_len = boundary.len();
@@ -1212,6 +1461,10 @@ impl MultipartForm {
         &self.parts
     }
 
+    pub(crate) fn parts_mut(&mut self) -> &mut [Part] {
+        &mut self.parts
+    }
+
     /// Consume the form and return all parsed parts.
     #[must_use]
     pub fn into_parts(mut self) -> Vec<Part> {
@@ -1329,6 +1582,278 @@ impl Drop for MultipartForm {
     }
 }
 
+/// Streaming multipart form data.
+///
+/// Where [`MultipartForm`] reads the whole body before the handler runs, a
+/// `MultipartStream` yields parts as the body arrives, and each part's data
+/// chunk by chunk, so large uploads can be forwarded elsewhere without being
+/// held in memory or spooled to disk. Limits come from a [`MultipartConfig`]
+/// request extension, or the defaults. An [`UploadPolicy`] extension is
+/// checked as each file part starts (or, when sniffing, at its first chunk).
+///
+/// # Example
+///
+/// ```ignore
+/// #[post("/upload")]
+/// async fn upload(_cx: &Cx, mut form: MultipartStream) -> Result<StatusCode, MultipartExtractError> {
+///     while let Some(mut part) = form.next_part().await? {
+///         if !part.is_file() {
+///             continue; // unread data is skipped
+///         }
+///         let mut upload = bucket.start_upload(part.filename.as_deref().unwrap_or("upload")).await;
+///         while let Some(chunk) = part.chunk().await? {
+///             upload.write(&chunk).await;
+///         }
+///         upload.finish().await;
+///     }
+///     Ok(StatusCode::CREATED)
+/// }
+/// ```
+pub struct MultipartStream {
+    parser: MultipartParser,
+    state: MultipartStreamState,
+    buffer: Vec<u8>,
+    body: Option<RequestBodyStream>,
+    received: usize,
+    policy: Option<UploadPolicy>,
+}
+
+impl MultipartStream {
+    /// Stream the parts of `body`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MultipartExtractError::PayloadTooLarge`] if the body is known
+    /// to exceed the parser's total size limit.
+    pub(crate) fn new(parser: MultipartParser, body: Body) -> Result<Self, MultipartExtractError> {
+        let limit = parser.config.max_total_size;
+        let (buffer, body) = match body {
+            Body::Empty => (Vec::new(), None),
+            Body::Bytes(bytes) => (bytes, None),
+            Body::Stream {
+                stream,
+                content_length,
+            } => {
+                if let Some(size) = content_length.filter(|&n| n > limit) {
+                    return Err(MultipartExtractError::PayloadTooLarge { size, limit });
+                }
+                let stream = stream.into_inner().unwrap_or_else(|e| e.into_inner());
+                (Vec::new(), Some(stream))
+            }
+        };
+        if buffer.len() > limit {
+            return Err(MultipartExtractError::PayloadTooLarge {
+                size: buffer.len(),
+                limit,
+            });
+        }
+        Ok(Self {
+            parser,
+            state: MultipartStreamState::default(),
+            received: buffer.len(),
+            buffer,
+            body,
+            policy: None,
+        })
+    }
+
+    /// Check file parts against `policy` as they start.
+    #[must_use]
+    pub(crate) fn with_policy(mut self, policy: Option<UploadPolicy>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Wait for the next part, or `None` once the closing boundary is read.
+    ///
+    /// Data of the previous part that was not read is skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body is malformed, exceeds a limit, or cannot
+    /// be read.
+    pub async fn next_part(&mut self) -> Result<Option<StreamingPart<'_>>, MultipartExtractError> {
+        loop {
+            match self.next_event().await? {
+                Some(MultipartEvent::PartStart(mut head)) => {
+                    let mut sniff = false;
+                    if let Some(policy) = self.policy.as_ref().filter(|_| head.filename.is_some()) {
+                        let mut errors = Vec::new();
+                        policy.check_file(
+                            &head.name,
+                            &mut head.filename,
+                            head.content_type.as_deref(),
+                            &mut errors,
+                        );
+                        UploadPolicyError::from_errors(errors)
+                            .map_err(MultipartExtractError::Policy)?;
+                        sniff = policy.sniffs_content();
+                    }
+                    return Ok(Some(StreamingPart {
+                        name: head.name,
+                        filename: head.filename,
+                        content_type: head.content_type,
+                        headers: head.headers,
+                        stream: self,
+                        sniff,
+                        finished: false,
+                    }));
+                }
+                Some(MultipartEvent::Data(_) | MultipartEvent::PartEnd) => {}
+                None => return Ok(None),
+            }
+        }
+    }
+
+    async fn next_event(&mut self) -> Result<Option<MultipartEvent>, MultipartExtractError> {
+        loop {
+            let eof = self.body.is_none();
+            let event = self
+                .parser
+                .next_event(&mut self.buffer, &mut self.state, eof)
+                .map_err(multipart_parser_error)?;
+            if event.is_some() || self.state.is_done() {
+                return Ok(event);
+            }
+            let Some(stream) = self.body.as_mut() else {
+                return Err(MultipartExtractError::BadRequest {
+                    message: MultipartError::UnexpectedEof.to_string(),
+                });
+            };
+            match std::future::poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+                Some(chunk) => {
+                    let chunk = chunk.map_err(multipart_stream_error)?;
+                    self.received = self.received.saturating_add(chunk.len());
+                    let limit = self.parser.config.max_total_size;
+                    if self.received > limit {
+                        return Err(MultipartExtractError::PayloadTooLarge {
+                            size: self.received,
+                            limit,
+                        });
+                    }
+                    self.buffer.extend_from_slice(&chunk);
+                }
+                None => self.body = None,
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for MultipartStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MultipartStream")
+            .field("parser", &self.parser)
+            .field("state", &self.state)
+            .field("buffered", &self.buffer.len())
+            .field("received", &self.received)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A part of a [`MultipartStream`] whose data is read as it arrives.
+pub struct StreamingPart<'a> {
+    /// Field name from Content-Disposition.
+    pub name: String,
+    /// Filename from Content-Disposition (if present).
+    pub filename: Option<String>,
+    /// Content-Type of the part (if present).
+    pub content_type: Option<String>,
+    /// Additional headers.
+    pub headers: HashMap<String, String>,
+    stream: &'a mut MultipartStream,
+    sniff: bool,
+    finished: bool,
+}
+
+impl StreamingPart<'_> {
+    /// Returns true if this part is a file upload.
+    #[must_use]
+    pub fn is_file(&self) -> bool {
+        self.filename.is_some()
+    }
+
+    /// Wait for the next chunk of data, or `None` at the end of the part.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body is malformed, exceeds a limit, or cannot
+    /// be read.
+    pub async fn chunk(&mut self) -> Result<Option<Vec<u8>>, MultipartExtractError> {
+        if self.finished {
+            return Ok(None);
+        }
+        if self.sniff {
+            return self.sniffed_chunk().await;
+        }
+        match self.stream.next_event().await? {
+            Some(MultipartEvent::Data(data)) => Ok(Some(data)),
+            _ => {
+                self.finished = true;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Read the first [`SNIFF_LEN`] bytes (or the whole part, if shorter) and
+    /// check their type against the stream's upload policy.
+    async fn sniffed_chunk(&mut self) -> Result<Option<Vec<u8>>, MultipartExtractError> {
+        self.sniff = false;
+        let mut leading = Vec::new();
+        while leading.len() < SNIFF_LEN {
+            match self.stream.next_event().await? {
+                Some(MultipartEvent::Data(data)) => leading.extend_from_slice(&data),
+                _ => {
+                    self.finished = true;
+                    break;
+                }
+            }
+        }
+        if let Some(policy) = &self.stream.policy {
+            let mut errors = Vec::new();
+            policy.check_sniffed(&self.name, &leading, &mut errors);
+            UploadPolicyError::from_errors(errors).map_err(MultipartExtractError::Policy)?;
+        }
+        Ok(Some(leading).filter(|leading| !leading.is_empty()))
+    }
+
+    /// Read the rest of the part into memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body is malformed, exceeds a limit, or cannot
+    /// be read.
+    pub async fn bytes(mut self) -> Result<Vec<u8>, MultipartExtractError> {
+        let mut out = Vec::new();
+        while let Some(chunk) = self.chunk().await? {
+            out.extend_from_slice(&chunk);
+        }
+        Ok(out)
+    }
+
+    /// Read the rest of the part as UTF-8 text (for form fields).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails or the data is not valid UTF-8.
+    pub async fn text(self) -> Result<String, MultipartExtractError> {
+        let name = self.name.clone();
+        String::from_utf8(self.bytes().await?).map_err(|_| MultipartExtractError::BadRequest {
+            message: format!("multipart field '{name}' is not valid UTF-8"),
+        })
+    }
+}
+
+impl std::fmt::Debug for StreamingPart<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamingPart")
+            .field("name", &self.name)
+            .field("filename", &self.filename)
+            .field("content_type", &self.content_type)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1698,6 +2223,28 @@ mod tests {
         assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
     }
 
+    #[test]
+    fn test_boundary_directly_after_part_headers_is_part_data() {
+        let body = b"--fuzz\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n--fuzz\r\n\
+Content-Disposition: form-data; name=\"b\"\r\n\r\nx\r\n--fuzz--\r\n";
+        let parser = MultipartParser::new("fuzz", MultipartConfig::default());
+        let parts = parser.parse(body).unwrap();
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].name, "a");
+
+        let mut buffer = body.to_vec();
+        let mut state = MultipartStreamState::default();
+        let streamed = parser
+            .parse_incremental(&mut buffer, &mut state, true)
+            .unwrap();
+        assert_eq!(streamed.len(), 1);
+        assert_eq!(streamed[0].data, parts[0].data);
+
+        let empty = b"--fuzz\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n\r\n--fuzz--\r\n";
+        let parts = parser.parse(empty).unwrap();
+        assert!(parts[0].data.is_empty());
+    }
+
     #[test]
     fn test_incremental_parse_with_chunked_input() {
         let boundary = "----boundary";
@@ -1843,4 +2390,87 @@ mod tests {
             "dropping multipart form should clean spooled part file"
         );
     }
+
+    fn chunked_body(body: &[u8], size: usize) -> Body {
+        let chunks: Vec<Result<Vec<u8>, crate::request::RequestBodyStreamError>> =
+            body.chunks(size).map(|chunk| Ok(chunk.to_vec())).collect();
+        Body::streaming(asupersync::stream::iter(chunks))
+    }
+
+    #[test]
+    fn test_multipart_stream_yields_parts_chunk_by_chunk() {
+        let boundary = "----boundary";
+        let payload = vec![b'x'; 64 * 1024];
+        let mut body = Vec::new();
+        body.extend_from_slice(
+            b"------boundary\r\nContent-Disposition: form-data; name=\"skipped\"\r\n\r\nignored\r\n",
+        );
+        body.extend_from_slice(
+            b"------boundary\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nhello\r\n",
+        );
+        body.extend_from_slice(
+            b"------boundary\r\nContent-Disposition: form-data; name=\"file\"; filename=\"big.bin\"\r\n",
+        );
+        body.extend_from_slice(b"Content-Type: application/octet-stream\r\n\r\n");
+        body.extend_from_slice(&payload);
+        body.extend_from_slice(b"\r\n------boundary--\r\n");
+
+        let parser = MultipartParser::new(boundary, MultipartConfig::default());
+        let mut form = MultipartStream::new(parser, chunked_body(&body, 4096)).unwrap();
+
+        futures_executor::block_on(async {
+            let skipped = form.next_part().await.unwrap().unwrap();
+            assert_eq!(skipped.name, "skipped");
+
+            let title = form.next_part().await.unwrap().unwrap();
+            assert!(!title.is_file());
+            assert_eq!(title.text().await.unwrap(), "hello");
+
+            let mut file = form.next_part().await.unwrap().unwrap();
+            assert_eq!(file.filename.as_deref(), Some("big.bin"));
+            assert_eq!(
+                file.content_type.as_deref(),
+                Some("application/octet-stream")
+            );
+            let mut received = 0;
+            let mut chunks = 0;
+            while let Some(chunk) = file.chunk().await.unwrap() {
+                assert!(chunk.iter().all(|&b| b == b'x'));
+                received += chunk.len();
+                chunks += 1;
+            }
+            assert_eq!(received, payload.len());
+            assert!(chunks > 1, "data should arrive incrementally");
+
+            assert!(form.next_part().await.unwrap().is_none());
+        });
+        assert!(form.buffer.len() < 4096 + 64);
+    }
+
+    #[test]
+    fn test_multipart_stream_enforces_limits_and_detects_truncation() {
+        let boundary = "----boundary";
+        let body = b"------boundary\r\nContent-Disposition: form-data; name=\"f\"; filename=\"a.txt\"\r\n\r\n0123456789\r\n------boundary--\r\n";
+
+        let config = MultipartConfig::default().max_file_size(4);
+        let parser = MultipartParser::new(boundary, config);
+        let mut form = MultipartStream::new(parser, chunked_body(body, 3)).unwrap();
+        let err = futures_executor::block_on(async {
+            let part = form.next_part().await.unwrap().unwrap();
+            part.bytes().await.unwrap_err()
+        });
+        assert!(matches!(
+            err,
+            MultipartExtractError::PayloadTooLarge { limit: 4, .. }
+        ));
+
+        let parser = MultipartParser::new(boundary, MultipartConfig::default());
+        let truncated = &body[..body.len() - 25];
+        let mut form = MultipartStream::new(parser, chunked_body(truncated, 3)).unwrap();
+        let err = futures_executor::block_on(async {
+            let part = form.next_part().await.unwrap().unwrap();
+            part.bytes().await.unwrap_err()
+        });
+        assert!(matches!(err, MultipartExtractError::BadRequest { .. }));
+    }
 }
@@ -0,0 +1,300 @@
+//! RFC 9457 problem details (`application/problem+json`).
+//!
+//! [`ProblemDetails`] is a responder carrying the standard `type`, `title`,
+//! `status`, `detail` and `instance` members plus any extension members.
+//! [`HttpError`] and [`ValidationErrors`] convert into it; validation errors
+//! are listed under an `errors` extension member.
+//!
+//! To switch a whole application over, enable
+//! [`AppConfig::problem_details`](crate::app::AppConfig::problem_details):
+//! the built-in `{"detail": ...}` error bodies (including the router's 404
+//! and 405) are then rewritten as problem documents, with `instance` set to
+//! the request path.
+
+use serde_json::{Map, Value};
+
+use crate::error::{DebugInfo, HttpError, ValidationErrors, is_debug_mode_enabled};
+use crate::response::{IntoResponse, Response, ResponseBody, StatusCode};
+
+/// Media type of problem documents.
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// `detail` used for validation failures.
+const VALIDATION_DETAIL: &str = "Request validation failed";
+
+/// An RFC 9457 problem details response.
+///
+/// # Example
+///
+/// ```ignore
+/// let problem = ProblemDetails::new(StatusCode::FORBIDDEN)
+///     .with_type("https://example.com/probs/out-of-credit")
+///     .with_detail("Your current balance is 30, but that costs 50.")
+///     .with_extension("balance", 30);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProblemDetails {
+    /// URI identifying the problem type (`about:blank` by default).
+    pub problem_type: String,
+    /// Short summary of the problem type.
+    pub title: String,
+    /// HTTP status code.
+    pub status: StatusCode,
+    /// Explanation specific to this occurrence.
+    pub detail: Option<String>,
+    /// URI identifying this occurrence, usually the request path.
+    pub instance: Option<String>,
+    /// Extension members, serialized after the standard ones.
+    pub extensions: Map<String, Value>,
+    /// Additional response headers.
+    pub headers: Vec<(String, Vec<u8>)>,
+}
+
+impl ProblemDetails {
+    /// Create a problem for `status`, titled with its reason phrase.
+    #[must_use]
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            problem_type: String::from("about:blank"),
+            title: status.canonical_reason().to_string(),
+            status,
+            detail: None,
+            instance: None,
+            extensions: Map::new(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Set the problem type URI.
+    #[must_use]
+    pub fn with_type(mut self, problem_type: impl Into<String>) -> Self {
+        self.problem_type = problem_type.into();
+        self
+    }
+
+    /// Set the title.
+    #[must_use]
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Set the detail message.
+    #[must_use]
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Set the instance URI.
+    #[must_use]
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Add an extension member.
+    ///
+    /// Names that clash with a standard member are ignored when serializing.
+    #[must_use]
+    pub fn with_extension(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.extensions.insert(name.into(), value.into());
+        self
+    }
+
+    /// Add a response header.
+    #[must_use]
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<Vec<u8>>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Build the JSON document.
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        let mut doc = Map::new();
+        doc.insert("type".into(), Value::from(self.problem_type.as_str()));
+        doc.insert("title".into(), Value::from(self.title.as_str()));
+        doc.insert("status".into(), Value::from(self.status.as_u16()));
+        if let Some(detail) = &self.detail {
+            doc.insert("detail".into(), Value::from(detail.as_str()));
+        }
+        if let Some(instance) = &self.instance {
+            doc.insert("instance".into(), Value::from(instance.as_str()));
+        }
+        for (name, value) in &self.extensions {
+            if !doc.contains_key(name) {
+                doc.insert(name.clone(), value.clone());
+            }
+        }
+        Value::Object(doc)
+    }
+
+    /// Parse a built-in `{"detail": ...}` error body.
+    ///
+    /// A string `detail` is kept as the detail; an array (validation errors)
+    /// becomes the `errors` extension. Other members, such as `debug`, become
+    /// extensions. Returns `None` for bodies of any other shape.
+    #[must_use]
+    pub fn from_error_body(status: StatusCode, body: &[u8]) -> Option<Self> {
+        let Ok(Value::Object(mut fields)) = serde_json::from_slice::<Value>(body) else {
+            return None;
+        };
+        let mut problem = Self::new(status);
+        match fields.remove("detail")? {
+            Value::String(detail) => problem.detail = Some(detail),
+            errors @ Value::Array(_) => {
+                problem.detail = Some(VALIDATION_DETAIL.to_string());
+                problem.extensions.insert("errors".into(), errors);
+            }
+            _ => return None,
+        }
+        problem.extensions.extend(fields);
+        Some(problem)
+    }
+
+    /// Rewrite a JSON error response as a problem document.
+    ///
+    /// Only error statuses with a built-in error body (see
+    /// [`from_error_body`](Self::from_error_body)) are changed; headers other
+    /// than the content type are kept.
+    #[must_use]
+    pub fn rewrite_response(response: Response, instance: &str) -> Response {
+        if response.status().as_u16() < 400 || !has_json_content_type(&response) {
+            return response;
+        }
+        let ResponseBody::Bytes(body) = response.body_ref() else {
+            return response;
+        };
+        let Some(problem) = Self::from_error_body(response.status(), body) else {
+            return response;
+        };
+        let body = problem.with_instance(instance).to_json().to_string();
+        response
+            .remove_header("content-type")
+            .remove_header("content-length")
+            .header("content-type", PROBLEM_JSON.as_bytes().to_vec())
+            .body(ResponseBody::Bytes(body.into_bytes()))
+    }
+
+    /// Add a `debug` extension when debug mode is on, like the built-in bodies.
+    fn with_debug(mut self, debug: Option<&DebugInfo>) -> Self {
+        let debug = debug
+            .filter(|_| is_debug_mode_enabled())
+            .and_then(|debug| serde_json::to_value(debug).ok());
+        if let Some(debug) = debug {
+            self.extensions.insert("debug".into(), debug);
+        }
+        self
+    }
+}
+
+fn has_json_content_type(response: &Response) -> bool {
+    response.headers().iter().any(|(name, value)| {
+        name.eq_ignore_ascii_case("content-type")
+            && std::str::from_utf8(value).is_ok_and(|ct| {
+                let essence = ct.split(';').next().unwrap_or("").trim();
+                essence.eq_ignore_ascii_case("application/json")
+            })
+    })
+}
+
+impl From<HttpError> for ProblemDetails {
+    fn from(err: HttpError) -> Self {
+        let mut problem = Self::new(err.status);
+        problem.detail = err.detail;
+        problem.headers = err.headers;
+        problem.with_debug(err.debug_info.as_ref())
+    }
+}
+
+impl From<ValidationErrors> for ProblemDetails {
+    fn from(err: ValidationErrors) -> Self {
+        let mut problem =
+            Self::new(StatusCode::UNPROCESSABLE_ENTITY).with_detail(VALIDATION_DETAIL);
+        problem.extensions.insert(
+            "errors".into(),
+            serde_json::to_value(&err.errors).unwrap_or(Value::Array(Vec::new())),
+        );
+        problem.with_debug(err.debug_info.as_ref())
+    }
+}
+
+impl IntoResponse for ProblemDetails {
+    fn into_response(self) -> Response {
+        let body = self.to_json().to_string();
+        let mut response = Response::with_status(self.status)
+            .header("content-type", PROBLEM_JSON.as_bytes().to_vec())
+            .body(ResponseBody::Bytes(body.into_bytes()));
+        for (name, value) in self.headers {
+            response = response.header(name, value);
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ValidationError, loc};
+
+    fn body_json(response: &Response) -> Value {
+        match response.body_ref() {
+            ResponseBody::Bytes(bytes) => serde_json::from_slice(bytes).unwrap(),
+            _ => panic!("expected a byte body"),
+        }
+    }
+
+    #[test]
+    fn http_error_becomes_problem_document() {
+        let response = ProblemDetails::from(HttpError::not_found().with_detail("No item 7"))
+            .with_instance("/items/7")
+            .into_response();
+        assert_eq!(response.status().as_u16(), 404);
+        assert!(
+            response
+                .headers()
+                .iter()
+                .any(|(n, v)| n == "content-type" && v == PROBLEM_JSON.as_bytes())
+        );
+        assert_eq!(
+            body_json(&response),
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Not Found",
+                "status": 404,
+                "detail": "No item 7",
+                "instance": "/items/7",
+            })
+        );
+    }
+
+    #[test]
+    fn validation_errors_are_listed_as_an_extension() {
+        let mut errors = ValidationErrors::new();
+        errors.push(ValidationError::missing(loc::query("q")));
+        let doc = ProblemDetails::from(errors).to_json();
+        assert_eq!(doc["status"], 422);
+        assert_eq!(doc["detail"], VALIDATION_DETAIL);
+        assert_eq!(doc["errors"][0]["loc"], serde_json::json!(["query", "q"]));
+    }
+
+    #[test]
+    fn rewrites_builtin_error_bodies_only() {
+        let rewritten =
+            ProblemDetails::rewrite_response(HttpError::bad_request().into_response(), "/x");
+        let doc = body_json(&rewritten);
+        assert_eq!(doc["title"], "Bad Request");
+        assert_eq!(doc["detail"], "Bad Request");
+        assert_eq!(doc["instance"], "/x");
+
+        let custom = Response::with_status(StatusCode::BAD_REQUEST)
+            .header("content-type", b"application/json".to_vec())
+            .body(ResponseBody::Bytes(br#"{"message":"nope"}"#.to_vec()));
+        let untouched = ProblemDetails::rewrite_response(custom, "/x");
+        assert_eq!(
+            body_json(&untouched),
+            serde_json::json!({"message": "nope"})
+        );
+    }
+}
@@ -0,0 +1,521 @@
+//! On-demand profiling endpoint for running deployments.
+//!
+//! [`AppBuilder::profiler`](crate::AppBuilder::profiler) mounts
+//! `GET /_debug/profile`, which runs a [`Profiler`] for a requested number of
+//! seconds and returns the result as collapsed stacks (`frame;frame;leaf
+//! weight` per line), the input format of `flamegraph.pl` and `inferno`.
+//!
+//! | Query parameter | Meaning | Default |
+//! |-----------------|---------|---------|
+//! | `kind`          | `cpu` or `alloc` | `cpu` |
+//! | `seconds`       | capture window | [`ProfileConfig::default_duration`] |
+//!
+//! The endpoint is guarded by the app's
+//! [`DebugConfig`](crate::error::DebugConfig): unless debug mode is enabled and
+//! the request carries the debug token, it answers 404. Only one capture runs
+//! at a time; concurrent requests get 409.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use fastapi_core::profiling::{ProfileConfig, ProfileKind, Profiler};
+//!
+//! let app = App::builder()
+//!     .config(AppConfig::new().debug_config(
+//!         DebugConfig::new().enable().with_debug_header("x-debug-token", token),
+//!     ))
+//!     .profiler(PprofProfiler::new(), ProfileConfig::new())
+//!     .build();
+//!
+//! // curl -H 'x-debug-token: ...' 'http://host/_debug/profile?kind=cpu&seconds=30' \
+//! //     | flamegraph.pl > cpu.svg
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::error::DebugConfig;
+use crate::extract::QueryParams;
+use crate::request::Request;
+use crate::response::{Response, ResponseBody, StatusCode};
+
+/// Default mount path of the profiling endpoint.
+pub const DEFAULT_PROFILE_PATH: &str = "/_debug/profile";
+
+/// What a profile measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProfileKind {
+    /// Sampled CPU time.
+    Cpu,
+    /// Heap allocations.
+    Allocations,
+}
+
+impl ProfileKind {
+    /// Returns the query-string name (`cpu` or `alloc`).
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cpu => "cpu",
+            Self::Allocations => "alloc",
+        }
+    }
+
+    /// Parses a query-string name.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "cpu" => Some(Self::Cpu),
+            "alloc" | "allocations" | "heap" => Some(Self::Allocations),
+            _ => None,
+        }
+    }
+}
+
+/// Error raised by a [`Profiler`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileError {
+    message: String,
+}
+
+impl ProfileError {
+    /// Creates an error with the given message.
+    #[must_use]
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "profiling failed: {}", self.message)
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+/// A captured profile: weighted stacks, root frame first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    kind: ProfileKind,
+    duration: Duration,
+    stacks: Vec<(Vec<String>, u64)>,
+}
+
+impl Profile {
+    /// Creates an empty profile.
+    #[must_use]
+    pub fn new(kind: ProfileKind, duration: Duration) -> Self {
+        Self {
+            kind,
+            duration,
+            stacks: Vec::new(),
+        }
+    }
+
+    /// Adds `weight` to `stack` (root first), merging with an identical stack.
+    ///
+    /// Weight is the sample count for CPU profiles and bytes for allocation profiles.
+    pub fn add_sample<I, S>(&mut self, stack: I, weight: u64)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let stack: Vec<String> = stack.into_iter().map(Into::into).collect();
+        if let Some((_, total)) = self.stacks.iter_mut().find(|(s, _)| *s == stack) {
+            *total += weight;
+        } else {
+            self.stacks.push((stack, weight));
+        }
+    }
+
+    /// Returns the profile kind.
+    #[must_use]
+    pub fn kind(&self) -> ProfileKind {
+        self.kind
+    }
+
+    /// Returns the capture window.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the number of distinct stacks.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.stacks.len()
+    }
+
+    /// Returns true if no samples were recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    /// Renders the profile in collapsed-stack format.
+    ///
+    /// Frames containing `;` or newlines are sanitized so each line stays
+    /// parseable.
+    #[must_use]
+    pub fn to_folded(&self) -> String {
+        let mut out = String::new();
+        for (stack, weight) in &self.stacks {
+            let frames: Vec<String> = stack
+                .iter()
+                .map(|frame| frame.replace([';', '\n', '\r'], "_"))
+                .collect();
+            out.push_str(&frames.join(";"));
+            out.push(' ');
+            out.push_str(&weight.to_string());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// A source of profiles, e.g. a sampling profiler or an allocator hook.
+pub trait Profiler: Send + Sync {
+    /// Returns true if this profiler can capture `kind`.
+    fn supports(&self, kind: ProfileKind) -> bool;
+
+    /// Starts capturing. The endpoint calls [`ProfileSession::finish`] once the
+    /// requested window has elapsed.
+    fn start(&self, kind: ProfileKind) -> Result<Box<dyn ProfileSession>, ProfileError>;
+}
+
+/// An in-progress capture.
+pub trait ProfileSession: Send {
+    /// Stops capturing and returns the profile.
+    fn finish(self: Box<Self>, elapsed: Duration) -> Result<Profile, ProfileError>;
+}
+
+/// Configuration for the profiling endpoint.
+#[derive(Debug, Clone)]
+pub struct ProfileConfig {
+    /// Mount path.
+    pub path: String,
+    /// Capture window when `seconds` is not given.
+    pub default_duration: Duration,
+    /// Longest capture window a request may ask for.
+    pub max_duration: Duration,
+}
+
+impl Default for ProfileConfig {
+    fn default() -> Self {
+        Self {
+            path: DEFAULT_PROFILE_PATH.to_string(),
+            default_duration: Duration::from_secs(10),
+            max_duration: Duration::from_secs(60),
+        }
+    }
+}
+
+impl ProfileConfig {
+    /// Creates a configuration with defaults.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the mount path.
+    #[must_use]
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Sets the default capture window.
+    #[must_use]
+    pub fn default_duration(mut self, duration: Duration) -> Self {
+        self.default_duration = duration;
+        self
+    }
+
+    /// Sets the longest allowed capture window.
+    #[must_use]
+    pub fn max_duration(mut self, duration: Duration) -> Self {
+        self.max_duration = duration;
+        self
+    }
+}
+
+/// The mounted endpoint: profiler, limits, and the single-capture lock.
+pub(crate) struct ProfileEndpoint {
+    profiler: Arc<dyn Profiler>,
+    config: ProfileConfig,
+    debug: DebugConfig,
+    busy: AtomicBool,
+}
+
+/// Releases the capture lock even if the request future is dropped mid-capture.
+struct BusyGuard<'a>(&'a AtomicBool);
+
+impl Drop for BusyGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+fn text_response(status: StatusCode, body: impl Into<String>) -> Response {
+    Response::with_status(status)
+        .header("content-type", b"text/plain; charset=utf-8".to_vec())
+        .body(ResponseBody::Bytes(body.into().into_bytes()))
+}
+
+impl ProfileEndpoint {
+    pub(crate) fn new(
+        profiler: Arc<dyn Profiler>,
+        config: ProfileConfig,
+        debug: DebugConfig,
+    ) -> Self {
+        Self {
+            profiler,
+            config,
+            debug,
+            busy: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn path(&self) -> &str {
+        &self.config.path
+    }
+
+    /// Validates the request, returning the capture parameters or an error response.
+    pub(crate) fn prepare(&self, req: &Request) -> Result<(ProfileKind, Duration), Response> {
+        let headers: Vec<(String, Vec<u8>)> = req
+            .headers()
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_vec()))
+            .collect();
+        if !self.debug.is_authorized(&headers) {
+            return Err(Response::with_status(StatusCode::NOT_FOUND));
+        }
+
+        let params = QueryParams::parse(req.query().unwrap_or(""));
+        let kind = match params.get("kind") {
+            None => ProfileKind::Cpu,
+            Some(value) => ProfileKind::parse(value).ok_or_else(|| {
+                text_response(
+                    StatusCode::BAD_REQUEST,
+                    format!("unknown profile kind `{value}` (expected `cpu` or `alloc`)"),
+                )
+            })?,
+        };
+        if !self.profiler.supports(kind) {
+            return Err(text_response(
+                StatusCode::BAD_REQUEST,
+                format!("profiler does not support `{}` profiles", kind.as_str()),
+            ));
+        }
+
+        let duration = match params.get("seconds") {
+            None => self.config.default_duration,
+            Some(value) => value
+                .parse::<f64>()
+                .ok()
+                .filter(|secs| secs.is_finite() && *secs >= 0.0)
+                .map(Duration::from_secs_f64)
+                .ok_or_else(|| {
+                    text_response(
+                        StatusCode::BAD_REQUEST,
+                        format!("invalid `seconds` value `{value}`"),
+                    )
+                })?,
+        };
+        if duration > self.config.max_duration {
+            return Err(text_response(
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "`seconds` exceeds the limit of {}",
+                    self.config.max_duration.as_secs_f64()
+                ),
+            ));
+        }
+
+        Ok((kind, duration))
+    }
+
+    /// Runs one capture and renders the response.
+    pub(crate) async fn capture(&self, kind: ProfileKind, duration: Duration) -> Response {
+        if self
+            .busy
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return text_response(
+                StatusCode::from_u16(409),
+                "a profile capture is already running",
+            );
+        }
+        let _guard = BusyGuard(&self.busy);
+
+        let session = match self.profiler.start(kind) {
+            Ok(session) => session,
+            Err(err) => return text_response(StatusCode::SERVICE_UNAVAILABLE, err.to_string()),
+        };
+
+        let started = std::time::Instant::now();
+        if !duration.is_zero() {
+            asupersync::time::sleep(asupersync::time::wall_now(), duration).await;
+        }
+
+        match session.finish(started.elapsed()) {
+            Ok(profile) => {
+                let filename = format!("{}-profile.folded", profile.kind().as_str());
+                Response::ok()
+                    .header("content-type", b"text/plain; charset=utf-8".to_vec())
+                    .header(
+                        "content-disposition",
+                        format!("attachment; filename=\"{filename}\"").into_bytes(),
+                    )
+                    .body(ResponseBody::Bytes(profile.to_folded().into_bytes()))
+            }
+            Err(err) => text_response(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::App;
+    use crate::context::RequestContext;
+    use crate::request::Method;
+
+    struct FakeProfiler;
+
+    struct FakeSession(ProfileKind);
+
+    impl Profiler for FakeProfiler {
+        fn supports(&self, kind: ProfileKind) -> bool {
+            kind == ProfileKind::Cpu
+        }
+
+        fn start(&self, kind: ProfileKind) -> Result<Box<dyn ProfileSession>, ProfileError> {
+            Ok(Box::new(FakeSession(kind)))
+        }
+    }
+
+    impl ProfileSession for FakeSession {
+        fn finish(self: Box<Self>, elapsed: Duration) -> Result<Profile, ProfileError> {
+            let mut profile = Profile::new(self.0, elapsed);
+            profile.add_sample(["main", "handle", "parse"], 3);
+            profile.add_sample(["main", "handle"], 1);
+            profile.add_sample(["main", "handle", "parse"], 2);
+            Ok(profile)
+        }
+    }
+
+    fn profiled_app(debug: DebugConfig) -> App {
+        App::builder()
+            .config(crate::app::AppConfig::new().debug_config(debug))
+            .profiler(FakeProfiler, ProfileConfig::new())
+            .build()
+    }
+
+    fn get(app: &App, uri: &str, token: Option<&str>) -> Response {
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+        let (path, query) = uri.split_once('?').unwrap_or((uri, ""));
+        let mut req = Request::new(Method::Get, path);
+        if !query.is_empty() {
+            req.set_query(Some(query.to_string()));
+        }
+        if let Some(token) = token {
+            req.headers_mut()
+                .insert("x-debug-token", token.as_bytes().to_vec());
+        }
+        futures_executor::block_on(app.handle(&ctx, &mut req))
+    }
+
+    fn body_text(response: Response) -> String {
+        match response.into_parts().2 {
+            ResponseBody::Bytes(bytes) => String::from_utf8(bytes).unwrap(),
+            _ => panic!("expected bytes body"),
+        }
+    }
+
+    #[test]
+    fn folded_output_merges_identical_stacks() {
+        let mut profile = Profile::new(ProfileKind::Cpu, Duration::ZERO);
+        profile.add_sample(["a", "b"], 1);
+        profile.add_sample(["a", "b"], 4);
+        profile.add_sample(["a", "semi;colon"], 2);
+        assert_eq!(profile.len(), 2);
+        assert_eq!(profile.to_folded(), "a;b 5\na;semi_colon 2\n");
+    }
+
+    #[test]
+    fn kind_parses_aliases() {
+        assert_eq!(ProfileKind::parse("cpu"), Some(ProfileKind::Cpu));
+        assert_eq!(ProfileKind::parse("alloc"), Some(ProfileKind::Allocations));
+        assert_eq!(ProfileKind::parse("heap"), Some(ProfileKind::Allocations));
+        assert_eq!(ProfileKind::parse("gpu"), None);
+    }
+
+    #[test]
+    fn endpoint_hidden_without_debug_token() {
+        let app = profiled_app(
+            DebugConfig::new()
+                .enable()
+                .with_debug_header("x-debug-token", "secret"),
+        );
+        let response = get(&app, "/_debug/profile?seconds=0", None);
+        assert_eq!(response.status().as_u16(), 404);
+
+        let response = get(&app, "/_debug/profile?seconds=0", Some("wrong"));
+        assert_eq!(response.status().as_u16(), 404);
+    }
+
+    #[test]
+    fn endpoint_returns_folded_profile() {
+        let app = profiled_app(
+            DebugConfig::new()
+                .enable()
+                .with_debug_header("x-debug-token", "secret"),
+        );
+        let response = get(&app, "/_debug/profile?kind=cpu&seconds=0", Some("secret"));
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(body_text(response), "main;handle;parse 5\nmain;handle 1\n");
+    }
+
+    #[test]
+    fn endpoint_rejects_bad_parameters() {
+        let app = profiled_app(DebugConfig::new().enable().allow_unauthenticated());
+        assert_eq!(
+            get(&app, "/_debug/profile?kind=alloc&seconds=0", None)
+                .status()
+                .as_u16(),
+            400
+        );
+        assert_eq!(
+            get(&app, "/_debug/profile?seconds=-1", None)
+                .status()
+                .as_u16(),
+            400
+        );
+        assert_eq!(
+            get(&app, "/_debug/profile?seconds=3600", None)
+                .status()
+                .as_u16(),
+            400
+        );
+    }
+
+    #[test]
+    fn endpoint_rejects_concurrent_capture() {
+        let endpoint = ProfileEndpoint::new(
+            Arc::new(FakeProfiler),
+            ProfileConfig::new(),
+            DebugConfig::new().enable().allow_unauthenticated(),
+        );
+        endpoint.busy.store(true, Ordering::Release);
+        let response =
+            futures_executor::block_on(endpoint.capture(ProfileKind::Cpu, Duration::ZERO));
+        assert_eq!(response.status().as_u16(), 409);
+    }
+}
@@ -72,6 +72,7 @@ pub fn arbitrary_method() -> impl Strategy<Value = Method> {
         Just(Method::Options),
         Just(Method::Head),
         Just(Method::Trace),
+        Just(Method::Connect),
     ]
 }
 
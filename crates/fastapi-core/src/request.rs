@@ -5,13 +5,15 @@ use std::collections::HashMap;
 use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, OnceLock};
 
 // Re-export Method from fastapi-types
-pub use fastapi_types::Method;
+pub use fastapi_types::{ExtensionMethod, InvalidMethodName, Method};
 
 use asupersync::stream::Stream;
 
+use crate::context::RequestContext;
+
 /// Error yielded by streaming request bodies.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RequestBodyStreamError {
@@ -121,9 +123,22 @@ impl Headers {
     /// Insert a header from borrowed name/value slices.
     ///
     /// This is a convenience for parsers that already have `&str`/`&[u8]` and want
-    /// to avoid constructing intermediate owned buffers.
-    pub fn insert_from_slice(&mut self, name: &str, value: &[u8]) {
-        self.inner.insert(name.to_ascii_lowercase(), value.to_vec());
+    /// to avoid constructing intermediate owned buffers. Returns the value it
+    /// replaced, so parsers can tell when a header was repeated.
+    pub fn insert_from_slice(&mut self, name: &str, value: &[u8]) -> Option<Vec<u8>> {
+        self.inner.insert(name.to_ascii_lowercase(), value.to_vec())
+    }
+
+    /// Parse a typed header.
+    ///
+    /// Returns `None` if the header is absent, otherwise the parse result.
+    pub fn typed<H: crate::headers::TypedHeader>(&self) -> Option<Result<H, String>> {
+        let value = self.get(H::NAME)?;
+        Some(
+            std::str::from_utf8(value)
+                .map_err(|_| format!("{} is not valid UTF-8", H::NAME))
+                .and_then(H::decode),
+        )
     }
 
     /// Remove a header value by name (case-insensitive).
@@ -252,13 +267,34 @@ impl Body {
 /// that is executed by the server after the main response completes.
 pub type BackgroundTasksInner = Mutex<Vec<Pin<Box<dyn Future<Output = ()> + Send>>>>;
 
+/// Handle to the work a request runs after its response is written.
+///
+/// Take it as a handler parameter to enqueue tasks:
+///
+/// ```ignore
+/// async fn signup(form: Json<Signup>, tasks: BackgroundTasks) -> StatusCode {
+///     let email = form.email.clone();
+///     tasks.add_fallible(async move { send_welcome_email(&email).await });
+///     StatusCode::CREATED
+/// }
+/// ```
+///
+/// Handles are cheap to clone and all refer to the request's task list. The
+/// server runs the tasks in order on the connection's task, inside the
+/// server's region, once the response has been written; a graceful shutdown
+/// waits for them like it waits for in-flight requests. A task that panics
+/// or fails is logged and does not stop the tasks queued after it.
+#[derive(Clone)]
 pub struct BackgroundTasks {
-    tasks: BackgroundTasksInner,
+    tasks: Arc<BackgroundTasksInner>,
+    ctx: Arc<OnceLock<RequestContext>>,
 }
 
 impl fmt::Debug for BackgroundTasks {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("BackgroundTasks").finish_non_exhaustive()
+        f.debug_struct("BackgroundTasks")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
     }
 }
 
@@ -267,7 +303,8 @@ impl BackgroundTasks {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            tasks: Mutex::new(Vec::new()),
+            tasks: Arc::new(Mutex::new(Vec::new())),
+            ctx: Arc::new(OnceLock::new()),
         }
     }
 
@@ -294,18 +331,92 @@ impl BackgroundTasks {
         guard.push(Box::pin(fut));
     }
 
+    /// Add an async task whose error is logged instead of ignored.
+    pub fn add_fallible<Fut, E>(&self, fut: Fut)
+    where
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: fmt::Display,
+    {
+        let ctx = Arc::clone(&self.ctx);
+        self.add_async(async move {
+            if let Err(err) = fut.await {
+                log_task_failure(ctx.get(), &format!("background task failed: {err}"));
+            }
+        });
+    }
+
+    /// Number of queued tasks.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tasks
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns true if no tasks are queued.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Attach the request context used when logging task failures.
+    pub(crate) fn set_context(&self, ctx: &RequestContext) {
+        let _ = self.ctx.set(ctx.clone());
+    }
+
     /// Execute all background tasks sequentially.
+    ///
+    /// Tasks queued through other handles, including by tasks that are
+    /// already running, are executed too. A panicking task is logged and
+    /// skipped.
     pub async fn execute_all(self) {
-        let tasks = self
-            .tasks
-            .into_inner()
-            .unwrap_or_else(std::sync::PoisonError::into_inner);
-        for t in tasks {
-            t.await;
+        loop {
+            let tasks = std::mem::take(
+                &mut *self
+                    .tasks
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner),
+            );
+            if tasks.is_empty() {
+                return;
+            }
+            for mut task in tasks {
+                let outcome = std::future::poll_fn(|cx| {
+                    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                        task.as_mut().poll(cx)
+                    })) {
+                        Ok(poll) => poll.map(Ok),
+                        Err(payload) => std::task::Poll::Ready(Err(payload)),
+                    }
+                })
+                .await;
+                if let Err(payload) = outcome {
+                    let reason = payload
+                        .downcast_ref::<&str>()
+                        .map(|s| (*s).to_string())
+                        .or_else(|| payload.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "unknown panic".to_string());
+                    log_task_failure(
+                        self.ctx.get(),
+                        &format!("background task panicked: {reason}"),
+                    );
+                }
+            }
         }
     }
 }
 
+fn log_task_failure(ctx: Option<&RequestContext>, message: &str) {
+    match ctx {
+        Some(ctx) => crate::logging::emit(
+            &crate::logging::LogEntry::new(ctx, crate::logging::LogLevel::Error, message)
+                .target(module_path!()),
+        ),
+        None => eprintln!("{message}"),
+    }
+}
+
 impl Default for BackgroundTasks {
     fn default() -> Self {
         Self::new()
@@ -9,6 +9,8 @@ use asupersync::stream::Stream;
 use asupersync::types::PanicPayload;
 use asupersync::types::{CancelKind, CancelReason, Outcome};
 
+use crate::json_case::JsonKeyCase;
+
 /// HTTP status code.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct StatusCode(u16);
@@ -59,6 +61,8 @@ impl StatusCode {
     pub const METHOD_NOT_ALLOWED: Self = Self(405);
     /// 406 Not Acceptable
     pub const NOT_ACCEPTABLE: Self = Self(406);
+    /// 409 Conflict
+    pub const CONFLICT: Self = Self(409);
     /// 412 Precondition Failed
     pub const PRECONDITION_FAILED: Self = Self(412);
     /// 413 Payload Too Large
@@ -123,6 +127,7 @@ impl StatusCode {
             404 => "Not Found",
             405 => "Method Not Allowed",
             406 => "Not Acceptable",
+            409 => "Conflict",
             412 => "Precondition Failed",
             413 => "Payload Too Large",
             415 => "Unsupported Media Type",
@@ -397,6 +402,7 @@ pub struct Response {
     status: StatusCode,
     headers: Vec<(String, Vec<u8>)>,
     body: ResponseBody,
+    error: Option<Box<dyn std::error::Error + Send + Sync>>,
 }
 
 impl Response {
@@ -407,6 +413,7 @@ impl Response {
             status,
             headers: Vec::new(),
             body: ResponseBody::Empty,
+            error: None,
         }
     }
 
@@ -541,6 +548,12 @@ impl Response {
         self
     }
 
+    /// Add a typed header (see [`crate::headers`]).
+    #[must_use]
+    pub fn typed_header<H: crate::headers::TypedHeader>(self, header: &H) -> Self {
+        self.header(H::NAME, header.encode().into_bytes())
+    }
+
     /// Remove all headers matching `name` (case-insensitive).
     ///
     /// This is useful for middleware that needs to suppress or replace headers
@@ -613,6 +626,53 @@ impl Response {
             .body(ResponseBody::Bytes(bytes)))
     }
 
+    /// A 200 response streaming whatever is sent into the channel.
+    ///
+    /// See [`body_channel`](crate::body_channel) for backpressure and
+    /// disconnect handling.
+    #[must_use]
+    pub fn from_channel(body: crate::body_channel::ChannelBody) -> Self {
+        Self::ok().body(ResponseBody::stream(body))
+    }
+
+    /// Re-indent a JSON body for human readers.
+    ///
+    /// Applies only to buffered bodies with a JSON content type
+    /// (`application/json` or `+json`); anything else is returned unchanged.
+    /// Key order is preserved.
+    #[must_use]
+    pub fn pretty_json(self) -> Self {
+        self.map_json_body(pretty_json_bytes)
+    }
+
+    /// Rewrite the object keys of a JSON body to `case`.
+    ///
+    /// Applies to the same bodies as [`pretty_json`](Self::pretty_json); see
+    /// [`JsonKeyCase::convert_json`].
+    #[must_use]
+    pub fn json_key_case(self, case: JsonKeyCase) -> Self {
+        self.map_json_body(|bytes| case.convert_json(bytes))
+    }
+
+    fn map_json_body(mut self, f: impl FnOnce(&[u8]) -> Vec<u8>) -> Self {
+        let is_json = self.headers.iter().any(|(name, value)| {
+            name.eq_ignore_ascii_case("content-type")
+                && std::str::from_utf8(value).is_ok_and(|ct| {
+                    let essence = ct.split(';').next().unwrap_or("").trim();
+                    essence.eq_ignore_ascii_case("application/json") || essence.ends_with("+json")
+                })
+        });
+        if !is_json {
+            return self;
+        }
+        if let ResponseBody::Bytes(bytes) = &self.body {
+            self.body = ResponseBody::Bytes(f(bytes));
+            self.headers
+                .retain(|(name, _)| !name.eq_ignore_ascii_case("content-length"));
+        }
+        self
+    }
+
     /// Get the status code.
     #[must_use]
     pub fn status(&self) -> StatusCode {
@@ -631,6 +691,22 @@ impl Response {
         &self.body
     }
 
+    /// Attach the error this response was produced from.
+    ///
+    /// After the route handler returns, the app passes the error to the
+    /// exception handler registered for its type; the response itself is the
+    /// fallback when none is.
+    #[must_use]
+    pub fn with_error(mut self, error: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        self.error = Some(error);
+        self
+    }
+
+    /// Detach the error set with [`Self::with_error`], if any.
+    pub fn take_error(&mut self) -> Option<Box<dyn std::error::Error + Send + Sync>> {
+        self.error.take()
+    }
+
     /// Decompose this response into its parts.
     #[must_use]
     pub fn into_parts(self) -> (StatusCode, Vec<(String, Vec<u8>)>, ResponseBody) {
@@ -899,7 +975,7 @@ impl Html {
 }
 
 /// Escape HTML special characters to prevent XSS attacks.
-fn escape_html(s: &str) -> String {
+pub(crate) fn escape_html(s: &str) -> String {
     let mut out = String::with_capacity(s.len());
     for c in s.chars() {
         match c {
@@ -1173,19 +1249,19 @@ impl FileResponse {
 
     /// Build the Content-Disposition header value.
     fn content_disposition(&self) -> String {
-        if self.inline {
-            "inline".to_string()
-        } else if let Some(ref name) = self.download_name {
-            // RFC 6266: filename should be quoted and special chars escaped
-            format!("attachment; filename=\"{}\"", name.replace('"', "\\\""))
-        } else {
-            // Use the actual filename from path
-            let filename = self
-                .path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("download");
-            format!("attachment; filename=\"{}\"", filename.replace('"', "\\\""))
+        match (self.inline, &self.download_name) {
+            (true, None) => "inline".to_string(),
+            (true, Some(name)) => content_disposition("inline", name),
+            (false, Some(name)) => content_disposition("attachment", name),
+            (false, None) => {
+                // Use the actual filename from path
+                let filename = self
+                    .path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("download");
+                content_disposition("attachment", filename)
+            }
         }
     }
 
@@ -1223,6 +1299,187 @@ impl IntoResponse for FileResponse {
     }
 }
 
+/// Build a Content-Disposition value carrying `filename` (RFC 6266).
+///
+/// The quoted `filename` parameter is an ASCII fallback for old clients;
+/// when the name is not plain ASCII, `filename*` carries the exact name in
+/// RFC 5987 encoding.
+fn content_disposition(kind: &str, filename: &str) -> String {
+    let fallback: String = filename
+        .chars()
+        .map(|c| match c {
+            '"' | '\\' => '_',
+            c if c.is_ascii() && !c.is_ascii_control() => c,
+            _ => '_',
+        })
+        .collect();
+    if fallback == filename {
+        return format!("{kind}; filename=\"{fallback}\"");
+    }
+    let mut encoded = String::with_capacity(filename.len() * 3);
+    for &byte in filename.as_bytes() {
+        // attr-char from RFC 5987, section 3.2.1
+        if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    format!("{kind}; filename=\"{fallback}\"; filename*=UTF-8''{encoded}")
+}
+
+/// Where the content of an [`Attachment`] comes from.
+#[derive(Debug, Clone)]
+pub enum AttachmentSource {
+    /// Content held in memory.
+    Bytes(Vec<u8>),
+    /// A file on disk, served through [`FileResponse`].
+    Path(std::path::PathBuf),
+}
+
+impl From<Vec<u8>> for AttachmentSource {
+    fn from(data: Vec<u8>) -> Self {
+        Self::Bytes(data)
+    }
+}
+
+impl From<&[u8]> for AttachmentSource {
+    fn from(data: &[u8]) -> Self {
+        Self::Bytes(data.to_vec())
+    }
+}
+
+impl From<std::path::PathBuf> for AttachmentSource {
+    fn from(path: std::path::PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl From<&std::path::Path> for AttachmentSource {
+    fn from(path: &std::path::Path) -> Self {
+        Self::Path(path.to_path_buf())
+    }
+}
+
+/// A download response with a client-facing filename.
+///
+/// Sets `Content-Disposition` with both a quoted ASCII `filename` and, for
+/// names outside plain ASCII, an RFC 5987 `filename*`. The content type is
+/// detected from the filename's extension (then the path's, for files)
+/// unless overridden. File sources are served by [`FileResponse`].
+///
+/// # Examples
+///
+/// ```ignore
+/// use fastapi_core::Attachment;
+/// use std::path::Path;
+///
+/// // In-memory content
+/// let response = Attachment::new(csv.into_bytes(), "Überblick 2024.csv");
+///
+/// // A file on disk, shown in the browser rather than saved
+/// let response = Attachment::new(Path::new("/srv/uploads/8f3a"), "invoice.pdf").inline();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    source: AttachmentSource,
+    filename: String,
+    content_type: Option<String>,
+    inline: bool,
+}
+
+impl Attachment {
+    /// Create an attachment served under `filename`.
+    #[must_use]
+    pub fn new(source: impl Into<AttachmentSource>, filename: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            filename: filename.into(),
+            content_type: None,
+            inline: false,
+        }
+    }
+
+    /// Override the detected content-type.
+    #[must_use]
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Ask the browser to display the content instead of saving it, keeping
+    /// the filename for a later "save as".
+    ///
+    /// Sets Content-Disposition: inline; filename="..."
+    #[must_use]
+    pub fn inline(mut self) -> Self {
+        self.inline = true;
+        self
+    }
+
+    /// Get the client-facing filename.
+    #[must_use]
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// Get the content source.
+    #[must_use]
+    pub fn source(&self) -> &AttachmentSource {
+        &self.source
+    }
+
+    /// The content-type sent with the response.
+    #[must_use]
+    pub fn resolved_content_type(&self) -> String {
+        if let Some(content_type) = &self.content_type {
+            return content_type.clone();
+        }
+        let from_name = extension_mime_type(std::path::Path::new(&self.filename));
+        let from_path = match &self.source {
+            AttachmentSource::Path(path) => extension_mime_type(path),
+            AttachmentSource::Bytes(_) => None,
+        };
+        from_name
+            .or(from_path)
+            .unwrap_or("application/octet-stream")
+            .to_string()
+    }
+}
+
+/// Known MIME type for the extension of `path`, if any.
+fn extension_mime_type(path: &std::path::Path) -> Option<&'static str> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(mime_type_for_extension)
+        .filter(|mime| *mime != "application/octet-stream")
+}
+
+impl IntoResponse for Attachment {
+    fn into_response(self) -> Response {
+        let content_type = self.resolved_content_type();
+        match self.source {
+            AttachmentSource::Bytes(data) => {
+                let kind = if self.inline { "inline" } else { "attachment" };
+                Response::ok()
+                    .header("content-type", content_type.into_bytes())
+                    .header(
+                        "content-disposition",
+                        content_disposition(kind, &self.filename).into_bytes(),
+                    )
+                    .body(ResponseBody::Bytes(data))
+            }
+            AttachmentSource::Path(path) => FileResponse {
+                path,
+                content_type: Some(content_type),
+                download_name: Some(self.filename),
+                inline: self.inline,
+            }
+            .into_response(),
+        }
+    }
+}
+
 /// Get MIME type for a file extension.
 ///
 /// Returns a reasonable MIME type for common file extensions.
@@ -2181,11 +2438,129 @@ impl fmt::Display for LinkHeader {
     }
 }
 
+/// Re-indent compact JSON with two-space indentation.
+///
+/// Works on the token stream, so it never reorders keys or reformats
+/// numbers. Input that is not well-formed is indented on a best-effort basis.
+pub(crate) fn pretty_json_bytes(input: &[u8]) -> Vec<u8> {
+    fn newline(out: &mut Vec<u8>, depth: usize) {
+        out.push(b'\n');
+        out.resize(out.len() + depth * 2, b' ');
+    }
+
+    let mut out = Vec::with_capacity(input.len() + input.len() / 2);
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut i = 0;
+
+    while i < input.len() {
+        let b = input[i];
+        i += 1;
+        if in_string {
+            out.push(b);
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match b {
+            b'"' => {
+                in_string = true;
+                out.push(b);
+            }
+            b'{' | b'[' => {
+                out.push(b);
+                let close = if b == b'{' { b'}' } else { b']' };
+                let next = input[i..].iter().position(|c| !c.is_ascii_whitespace());
+                if next.is_some_and(|n| input[i + n] == close) {
+                    // Keep empty containers on one line.
+                    out.push(close);
+                    i += next.unwrap_or(0) + 1;
+                } else {
+                    depth += 1;
+                    newline(&mut out, depth);
+                }
+            }
+            b'}' | b']' => {
+                depth = depth.saturating_sub(1);
+                newline(&mut out, depth);
+                out.push(b);
+            }
+            b',' => {
+                out.push(b);
+                newline(&mut out, depth);
+            }
+            b':' => out.extend_from_slice(b": "),
+            b if b.is_ascii_whitespace() => {}
+            _ => out.push(b),
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::error::HttpError;
 
+    fn header_str(response: &Response, name: &str) -> Option<String> {
+        response
+            .headers()
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| String::from_utf8_lossy(value).to_string())
+    }
+
+    #[test]
+    fn pretty_json_bytes_matches_serde_pretty() {
+        let value = serde_json::json!({
+            "name": "a \"quoted\" {brace}, [x]: y",
+            "tags": [],
+            "meta": {},
+            "items": [1, 2.5, null, true, {"z": 1, "a": [false]}]
+        });
+        let compact = serde_json::to_vec(&value).unwrap();
+        let expected = serde_json::to_vec_pretty(&value).unwrap();
+        assert_eq!(
+            String::from_utf8(pretty_json_bytes(&compact)).unwrap(),
+            String::from_utf8(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn pretty_json_preserves_key_order_and_skips_non_json() {
+        let resp = Response::ok()
+            .header("content-type", b"application/json".to_vec())
+            .header("content-length", b"15".to_vec())
+            .body(ResponseBody::Bytes(br#"{"b":1,"a":[2]}"#.to_vec()))
+            .pretty_json();
+        match resp.body_ref() {
+            ResponseBody::Bytes(b) => {
+                assert_eq!(b.as_slice(), b"{\n  \"b\": 1,\n  \"a\": [\n    2\n  ]\n}");
+            }
+            _ => panic!("expected bytes body"),
+        }
+        assert!(
+            resp.headers()
+                .iter()
+                .all(|(n, _)| !n.eq_ignore_ascii_case("content-length"))
+        );
+
+        let text = Response::ok()
+            .header("content-type", b"text/plain".to_vec())
+            .body(ResponseBody::Bytes(b"{\"a\":1}".to_vec()))
+            .pretty_json();
+        match text.body_ref() {
+            ResponseBody::Bytes(b) => assert_eq!(b.as_slice(), b"{\"a\":1}"),
+            _ => panic!("expected bytes body"),
+        }
+    }
+
     #[test]
     fn response_remove_header_removes_all_instances_case_insensitive() {
         let resp = Response::ok()
@@ -2469,6 +2844,76 @@ mod tests {
         assert_eq!(response.status().as_u16(), 404);
     }
 
+    #[test]
+    fn content_disposition_encodes_non_ascii_names() {
+        assert_eq!(
+            content_disposition("attachment", "report.csv"),
+            "attachment; filename=\"report.csv\""
+        );
+        assert_eq!(
+            content_disposition("attachment", "Überblick 2024.pdf"),
+            "attachment; filename=\"_berblick 2024.pdf\"; \
+             filename*=UTF-8''%C3%9Cberblick%202024.pdf"
+        );
+        assert_eq!(
+            content_disposition("inline", "a\"b\r\n.txt"),
+            "inline; filename=\"a_b__.txt\"; filename*=UTF-8''a%22b%0D%0A.txt"
+        );
+    }
+
+    #[test]
+    fn attachment_from_bytes_sets_headers() {
+        let response = Attachment::new(b"a,b\n".to_vec(), "données.csv").into_response();
+        assert_eq!(
+            header_str(&response, "content-type").as_deref(),
+            Some("text/csv; charset=utf-8")
+        );
+        assert_eq!(
+            header_str(&response, "content-disposition").as_deref(),
+            Some("attachment; filename=\"donn_es.csv\"; filename*=UTF-8''donn%C3%A9es.csv")
+        );
+        assert_eq!(response.body_ref().len(), 4);
+
+        let inline = Attachment::new(b"%PDF".to_vec(), "invoice.pdf")
+            .inline()
+            .into_response();
+        assert_eq!(
+            header_str(&inline, "content-disposition").as_deref(),
+            Some("inline; filename=\"invoice.pdf\"")
+        );
+
+        let custom = Attachment::new(b"x".to_vec(), "blob").content_type("application/x-custom");
+        assert_eq!(custom.resolved_content_type(), "application/x-custom");
+        assert_eq!(
+            Attachment::new(b"x".to_vec(), "blob").resolved_content_type(),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn attachment_from_path_uses_file_response() {
+        let test_file = std::env::temp_dir().join("test_attachment_source.json");
+        std::fs::write(&test_file, b"{}").unwrap();
+
+        // No extension on the download name: fall back to the path's.
+        let attachment = Attachment::new(test_file.as_path(), "export");
+        assert_eq!(attachment.resolved_content_type(), "application/json");
+        let response = attachment.into_response();
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(
+            header_str(&response, "content-disposition").as_deref(),
+            Some("attachment; filename=\"export\"")
+        );
+        assert_eq!(
+            header_str(&response, "accept-ranges").as_deref(),
+            Some("bytes")
+        );
+
+        let _ = std::fs::remove_file(test_file);
+        let missing = Attachment::new(std::path::Path::new("/nonexistent/a.txt"), "a.txt");
+        assert_eq!(missing.into_response().status().as_u16(), 404);
+    }
+
     // =========================================================================
     // MIME type tests
     // =========================================================================
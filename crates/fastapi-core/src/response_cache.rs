@@ -0,0 +1,713 @@
+//! Response caching honouring `Cache-Control` and `Expires`.
+//!
+//! [`ResponseCacheMiddleware`] stores the responses of `GET` and `HEAD`
+//! requests and answers repeated requests from the cache without running the
+//! handler. What gets cached, and for how long, is decided by the headers the
+//! handler sets:
+//!
+//! - `Cache-Control: s-maxage` or `max-age` (in that order), else `Expires`,
+//!   give the lifetime. Responses without one are only cached when a
+//!   [`default_ttl`](ResponseCacheMiddleware::default_ttl) is configured.
+//! - `no-store`, `no-cache` and `private` responses, responses with
+//!   `Set-Cookie` or `Vary: *`, and streamed bodies are never cached.
+//! - Responses to requests with `Authorization` are only cached when marked
+//!   `public` or given an `s-maxage`.
+//! - Entries are keyed by method, path, query and the request headers named
+//!   in the response's `Vary`.
+//!
+//! A request sent with `Cache-Control: no-store` bypasses the cache;
+//! `no-cache` skips the lookup but still refreshes the entry. Hits carry an
+//! `Age` header computed from when the entry was stored; an upstream `Age`
+//! is not cached.
+//!
+//! ```ignore
+//! let cache = ResponseCacheMiddleware::new().store(MemoryCacheStore::new(10_000));
+//! let stats = cache.stats_handle();
+//!
+//! let app = App::builder().middleware(cache).build();
+//!
+//! #[get("/catalog")]
+//! async fn catalog(cx: &Cx) -> Response {
+//!     Response::ok()
+//!         .typed_header(&CacheControl::new().public().max_age(60))
+//!         .body(..)
+//! }
+//! ```
+//!
+//! Entries live in a [`CacheStore`]; [`MemoryCacheStore`] keeps them in
+//! process with TTL and LRU eviction, and a shared store can be plugged in to
+//! share entries between instances.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+use parking_lot::Mutex;
+
+use crate::context::RequestContext;
+use crate::headers::{CacheControl, TypedHeader, parse_http_date};
+use crate::middleware::{BoxFuture, ControlFlow, Middleware};
+use crate::request::{Method, Request};
+use crate::response::{Response, ResponseBody, StatusCode};
+
+/// A stored response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResponse {
+    /// Response status.
+    pub status: StatusCode,
+    /// Response headers.
+    pub headers: Vec<(String, Vec<u8>)>,
+    /// Response body.
+    pub body: Vec<u8>,
+    /// When the response was stored, for the `Age` header.
+    pub stored_at: SystemTime,
+}
+
+impl CachedResponse {
+    fn to_response(&self, now: SystemTime) -> Response {
+        let age = now.duration_since(self.stored_at).unwrap_or_default();
+        let mut response = Response::with_status(self.status);
+        for (name, value) in &self.headers {
+            response = response.header(name.clone(), value.clone());
+        }
+        response
+            .header("age", age.as_secs().to_string().into_bytes())
+            .body(ResponseBody::Bytes(self.body.clone()))
+    }
+}
+
+// ============================================================================
+// Stores
+// ============================================================================
+
+/// Backend that holds cached responses by key.
+pub trait CacheStore: Send + Sync + 'static {
+    /// Load the entry for `key`, or `None` if it is unknown or expired.
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<CachedResponse>>;
+
+    /// Store `response` under `key`, expiring after `ttl`.
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        response: CachedResponse,
+        ttl: Duration,
+    ) -> BoxFuture<'a, ()>;
+
+    /// Forget the entry for `key`.
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, ()>;
+}
+
+/// In-process [`CacheStore`] with a capacity bound.
+///
+/// Expired entries are dropped when looked up or when room is needed; once
+/// full, the least recently used entry is evicted.
+#[derive(Debug, Clone)]
+pub struct MemoryCacheStore {
+    inner: Arc<Mutex<MemoryInner>>,
+}
+
+#[derive(Debug)]
+struct MemoryInner {
+    capacity: usize,
+    entries: HashMap<String, MemoryEntry>,
+    /// Logical clock for recency.
+    tick: u64,
+    evictions: u64,
+}
+
+#[derive(Debug)]
+struct MemoryEntry {
+    response: CachedResponse,
+    expires: Instant,
+    last_used: u64,
+}
+
+impl MemoryCacheStore {
+    /// Create a store holding at most `capacity` entries.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(MemoryInner {
+                capacity: capacity.max(1),
+                entries: HashMap::new(),
+                tick: 0,
+                evictions: 0,
+            })),
+        }
+    }
+
+    /// Number of entries, including expired ones not yet dropped.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.lock().entries.len()
+    }
+
+    /// Whether the store holds no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().entries.is_empty()
+    }
+
+    /// Entries evicted to make room, not counting expired ones.
+    #[must_use]
+    pub fn evictions(&self) -> u64 {
+        self.inner.lock().evictions
+    }
+}
+
+impl Default for MemoryCacheStore {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+impl MemoryInner {
+    fn make_room(&mut self, now: Instant) {
+        if self.entries.len() < self.capacity {
+            return;
+        }
+        self.entries.retain(|_, entry| entry.expires > now);
+        while self.entries.len() >= self.capacity {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.entries.remove(&oldest);
+            self.evictions += 1;
+        }
+    }
+}
+
+impl CacheStore for MemoryCacheStore {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<CachedResponse>> {
+        Box::pin(async move {
+            let mut inner = self.inner.lock();
+            inner.tick += 1;
+            let tick = inner.tick;
+            match inner.entries.get_mut(key) {
+                Some(entry) if entry.expires > Instant::now() => {
+                    entry.last_used = tick;
+                    Some(entry.response.clone())
+                }
+                Some(_) => {
+                    inner.entries.remove(key);
+                    None
+                }
+                None => None,
+            }
+        })
+    }
+
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        response: CachedResponse,
+        ttl: Duration,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            let now = Instant::now();
+            let mut inner = self.inner.lock();
+            if !inner.entries.contains_key(key) {
+                inner.make_room(now);
+            }
+            inner.tick += 1;
+            let entry = MemoryEntry {
+                response,
+                expires: now + ttl,
+                last_used: inner.tick,
+            };
+            inner.entries.insert(key.to_string(), entry);
+        })
+    }
+
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            self.inner.lock().entries.remove(key);
+        })
+    }
+}
+
+// ============================================================================
+// Middleware
+// ============================================================================
+
+/// Cache hit/miss counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Requests answered from the cache.
+    pub hits: u64,
+    /// Cacheable requests that had to run the handler.
+    pub misses: u64,
+    /// Responses stored.
+    pub stores: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    stores: AtomicU64,
+}
+
+/// Shared view of a [`ResponseCacheMiddleware`]'s counters, usable after the
+/// middleware has been moved into an app.
+#[derive(Debug, Clone)]
+pub struct CacheStatsHandle(Arc<Counters>);
+
+impl CacheStatsHandle {
+    /// Current counters.
+    #[must_use]
+    pub fn get(&self) -> CacheStats {
+        CacheStats {
+            hits: self.0.hits.load(Ordering::Relaxed),
+            misses: self.0.misses.load(Ordering::Relaxed),
+            stores: self.0.stores.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Request extension marking a response served from the cache, so `after`
+/// (which a [`Layer`](crate::middleware::Layer) runs on short-circuits too)
+/// does not store it again.
+#[derive(Debug, Clone, Copy)]
+struct ServedFromCache;
+
+/// Most paths whose `Vary` headers are remembered. Forgetting one only
+/// costs a miss: the lookup key then lacks the header values and the
+/// response is stored again.
+const MAX_VARY_PATHS: usize = 1024;
+
+/// Middleware serving cacheable responses from a [`CacheStore`].
+pub struct ResponseCacheMiddleware {
+    store: Arc<dyn CacheStore>,
+    default_ttl: Option<Duration>,
+    max_body_size: usize,
+    /// Header names each method + path varies on, learned from its
+    /// responses. Only non-empty lists are kept, at most [`MAX_VARY_PATHS`].
+    vary: Mutex<HashMap<String, Vec<String>>>,
+    counters: Arc<Counters>,
+}
+
+impl ResponseCacheMiddleware {
+    /// A cache backed by a [`MemoryCacheStore`] of 1024 entries.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            store: Arc::new(MemoryCacheStore::default()),
+            default_ttl: None,
+            max_body_size: 1024 * 1024,
+            vary: Mutex::new(HashMap::new()),
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    /// Use `store` for the entries.
+    #[must_use]
+    pub fn store(mut self, store: impl CacheStore) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Cache responses without an explicit lifetime for `ttl`.
+    #[must_use]
+    pub fn default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = Some(ttl);
+        self
+    }
+
+    /// Largest body to cache, in bytes (default 1 MiB).
+    #[must_use]
+    pub fn max_body_size(mut self, bytes: usize) -> Self {
+        self.max_body_size = bytes;
+        self
+    }
+
+    /// Current counters.
+    #[must_use]
+    pub fn stats(&self) -> CacheStats {
+        self.stats_handle().get()
+    }
+
+    /// A handle to the counters that stays valid after the middleware is
+    /// added to an app.
+    #[must_use]
+    pub fn stats_handle(&self) -> CacheStatsHandle {
+        CacheStatsHandle(Arc::clone(&self.counters))
+    }
+
+    /// The resource part of the key, or `None` if the request is not
+    /// cacheable.
+    fn resource_key(req: &Request) -> Option<String> {
+        if !matches!(req.method(), Method::Get | Method::Head) {
+            return None;
+        }
+        let mut key = format!("{} {}", req.method(), req.path());
+        if let Some(query) = req.query() {
+            key.push('?');
+            key.push_str(query);
+        }
+        Some(key)
+    }
+
+    /// Key of the `Vary` list for a request: method and path, without the
+    /// query, so distinct query strings share one entry.
+    fn vary_key(req: &Request) -> String {
+        format!("{} {}", req.method(), req.path())
+    }
+
+    /// Remembers the `Vary` list learned for the request's path.
+    fn learn_vary(&self, req: &Request, vary: Vec<String>) {
+        let key = Self::vary_key(req);
+        let mut map = self.vary.lock();
+        if vary.is_empty() {
+            map.remove(&key);
+            return;
+        }
+        if map.len() >= MAX_VARY_PATHS && !map.contains_key(&key) {
+            if let Some(evicted) = map.keys().next().cloned() {
+                map.remove(&evicted);
+            }
+        }
+        map.insert(key, vary);
+    }
+
+    /// The full key: the resource plus the values of the headers it varies on.
+    fn entry_key(resource: &str, vary: &[String], req: &Request) -> String {
+        let mut key = resource.to_string();
+        for name in vary {
+            key.push('\n');
+            key.push_str(name);
+            key.push(':');
+            if let Some(value) = req.headers().get(name) {
+                key.push_str(&String::from_utf8_lossy(value));
+            }
+        }
+        key
+    }
+
+    /// How long `response` may be cached, or `None` if it may not be.
+    fn ttl(&self, req: &Request, response: &Response, now: SystemTime) -> Option<Duration> {
+        if response.status() != StatusCode::OK || header(response, "set-cookie").is_some() {
+            return None;
+        }
+        let cc = match header(response, "cache-control") {
+            Some(value) => CacheControl::decode(value).ok()?,
+            None => CacheControl::new(),
+        };
+        if cc.no_store || cc.no_cache || cc.private {
+            return None;
+        }
+        if req.headers().get("authorization").is_some() && !cc.public && cc.s_maxage.is_none() {
+            return None;
+        }
+        let ttl = match cc.s_maxage.or(cc.max_age) {
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => match header(response, "expires") {
+                Some(expires) => parse_http_date(expires)
+                    .and_then(|at| at.duration_since(now).ok())
+                    .or(Some(Duration::ZERO)),
+                None => self.default_ttl,
+            },
+        };
+        ttl.filter(|ttl| !ttl.is_zero())
+    }
+}
+
+impl Default for ResponseCacheMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn header<'r>(response: &'r Response, name: &str) -> Option<&'r str> {
+    response
+        .headers()
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .and_then(|(_, v)| std::str::from_utf8(v).ok())
+}
+
+fn request_cache_control(req: &Request) -> CacheControl {
+    req.headers()
+        .get("cache-control")
+        .and_then(|v| std::str::from_utf8(v).ok())
+        .and_then(|v| CacheControl::decode(v).ok())
+        .unwrap_or_default()
+}
+
+impl Middleware for ResponseCacheMiddleware {
+    fn before<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        Box::pin(async move {
+            let Some(resource) = Self::resource_key(req) else {
+                return ControlFlow::Continue;
+            };
+            let cc = request_cache_control(req);
+            if cc.no_store {
+                return ControlFlow::Continue;
+            }
+            if !cc.no_cache {
+                let vary = self
+                    .vary
+                    .lock()
+                    .get(&Self::vary_key(req))
+                    .cloned()
+                    .unwrap_or_default();
+                let key = Self::entry_key(&resource, &vary, req);
+                if let Some(cached) = self.store.get(&key).await {
+                    self.counters.hits.fetch_add(1, Ordering::Relaxed);
+                    req.insert_extension(ServedFromCache);
+                    return ControlFlow::Break(cached.to_response(SystemTime::now()));
+                }
+            }
+            self.counters.misses.fetch_add(1, Ordering::Relaxed);
+            ControlFlow::Continue
+        })
+    }
+
+    fn after<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a Request,
+        response: Response,
+    ) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            if req.get_extension::<ServedFromCache>().is_some() {
+                return response;
+            }
+            let Some(resource) = Self::resource_key(req) else {
+                return response;
+            };
+            if request_cache_control(req).no_store {
+                return response;
+            }
+            let body = match response.body_ref() {
+                ResponseBody::Empty => Vec::new(),
+                ResponseBody::Bytes(bytes) if bytes.len() <= self.max_body_size => bytes.clone(),
+                _ => return response,
+            };
+            let vary: Vec<String> = header(&response, "vary")
+                .map(|v| {
+                    v.split(',')
+                        .map(|name| name.trim().to_ascii_lowercase())
+                        .filter(|name| !name.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default();
+            if vary.iter().any(|name| name == "*") {
+                return response;
+            }
+            let now = SystemTime::now();
+            let Some(ttl) = self.ttl(req, &response, now) else {
+                return response;
+            };
+
+            let key = Self::entry_key(&resource, &vary, req);
+            self.learn_vary(req, vary);
+            // `Age` is recomputed from `stored_at` on every hit.
+            let cached = CachedResponse {
+                status: response.status(),
+                headers: response
+                    .headers()
+                    .iter()
+                    .filter(|(name, _)| !name.eq_ignore_ascii_case("age"))
+                    .cloned()
+                    .collect(),
+                body,
+                stored_at: now,
+            };
+            self.store.put(&key, cached, ttl).await;
+            self.counters.stores.fetch_add(1, Ordering::Relaxed);
+            response
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "ResponseCache"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs `mw` around `handler` the way a [`Layer`](crate::middleware::Layer)
+    /// does, including `after` on a short-circuit.
+    fn run(
+        mw: &ResponseCacheMiddleware,
+        req: &mut Request,
+        handler: impl FnOnce() -> Response,
+    ) -> Response {
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+        futures_executor::block_on(async {
+            let response = match mw.before(&ctx, req).await {
+                ControlFlow::Break(response) => response,
+                ControlFlow::Continue => handler(),
+            };
+            mw.after(&ctx, req, response).await
+        })
+    }
+
+    fn body(response: &Response) -> &[u8] {
+        match response.body_ref() {
+            ResponseBody::Bytes(bytes) => bytes,
+            _ => &[],
+        }
+    }
+
+    fn cacheable(body: &str) -> Response {
+        Response::ok()
+            .header("cache-control", b"public, max-age=60".to_vec())
+            .body(ResponseBody::Bytes(body.as_bytes().to_vec()))
+    }
+
+    #[test]
+    fn serves_fresh_responses_from_the_cache() {
+        let mw = ResponseCacheMiddleware::new();
+        let mut req = Request::new(Method::Get, "/catalog");
+        assert_eq!(body(&run(&mw, &mut req, || cacheable("v1"))), b"v1");
+
+        let mut req = Request::new(Method::Get, "/catalog");
+        let hit = run(&mw, &mut req, || panic!("handler should not run"));
+        assert_eq!(body(&hit), b"v1");
+        assert!(hit.headers().iter().any(|(n, _)| n == "age"));
+
+        // Other queries and methods are separate entries.
+        let mut req = Request::new(Method::Get, "/catalog");
+        req.set_query(Some("page=2".into()));
+        assert_eq!(body(&run(&mw, &mut req, || cacheable("p2"))), b"p2");
+        let mut req = Request::new(Method::Post, "/catalog");
+        assert_eq!(body(&run(&mw, &mut req, || cacheable("post"))), b"post");
+
+        assert_eq!(
+            mw.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 2,
+                stores: 2
+            }
+        );
+    }
+
+    #[test]
+    fn honours_cache_control_on_responses_and_requests() {
+        let mw = ResponseCacheMiddleware::new();
+        let uncacheable = [
+            Response::ok().body(ResponseBody::Bytes(b"no lifetime".to_vec())),
+            Response::ok().header("cache-control", b"private, max-age=60".to_vec()),
+            Response::ok().header("cache-control", b"no-store".to_vec()),
+            cacheable("cookie").header("set-cookie", b"a=b".to_vec()),
+            cacheable("vary").header("vary", b"*".to_vec()),
+            Response::ok().header("expires", b"Thu, 01 Jan 1970 00:00:00 GMT".to_vec()),
+        ];
+        for response in uncacheable {
+            let mut req = Request::new(Method::Get, "/item");
+            let _ = run(&mw, &mut req, || response);
+        }
+        assert_eq!(mw.stats().stores, 0);
+
+        let mut req = Request::new(Method::Get, "/item");
+        let _ = run(&mw, &mut req, || cacheable("v1"));
+        let mut req = Request::new(Method::Get, "/item");
+        req.headers_mut()
+            .insert("cache-control", b"no-cache".to_vec());
+        let refreshed = run(&mw, &mut req, || cacheable("v2"));
+        assert_eq!(body(&refreshed), b"v2");
+
+        let mut req = Request::new(Method::Get, "/item");
+        assert_eq!(body(&run(&mw, &mut req, || unreachable!())), b"v2");
+    }
+
+    #[test]
+    fn hits_are_not_stored_again_and_age_is_not_cached() {
+        let mw = ResponseCacheMiddleware::new();
+        let mut req = Request::new(Method::Get, "/aged");
+        let _ = run(&mw, &mut req, || {
+            cacheable("v1").header("age", b"100".to_vec())
+        });
+        let entry = futures_executor::block_on(mw.store.get("GET /aged")).unwrap();
+        assert!(!entry.headers.iter().any(|(n, _)| n == "age"));
+
+        let mut req = Request::new(Method::Get, "/aged");
+        let hit = run(&mw, &mut req, || unreachable!());
+        let ages: Vec<_> = hit.headers().iter().filter(|(n, _)| n == "age").collect();
+        assert_eq!(ages.len(), 1);
+        assert_eq!(mw.stats().stores, 1);
+        let again = futures_executor::block_on(mw.store.get("GET /aged")).unwrap();
+        assert_eq!(again.stored_at, entry.stored_at);
+    }
+
+    #[test]
+    fn vary_headers_select_the_entry() {
+        let mw = ResponseCacheMiddleware::new();
+        let request = |lang: &str| {
+            let mut req = Request::new(Method::Get, "/greeting");
+            req.headers_mut()
+                .insert("accept-language", lang.as_bytes().to_vec());
+            req
+        };
+        let greeting = |text: &str| cacheable(text).header("vary", b"Accept-Language".to_vec());
+
+        let _ = run(&mw, &mut request("en"), || greeting("hello"));
+        let _ = run(&mw, &mut request("fr"), || greeting("bonjour"));
+        assert_eq!(
+            body(&run(&mw, &mut request("en"), || unreachable!())),
+            b"hello"
+        );
+        assert_eq!(
+            body(&run(&mw, &mut request("fr"), || unreachable!())),
+            b"bonjour"
+        );
+    }
+
+    #[test]
+    fn vary_map_is_keyed_by_path_and_bounded() {
+        let mw = ResponseCacheMiddleware::new();
+        for page in 0..10 {
+            let mut req = Request::new(Method::Get, "/search");
+            req.set_query(Some(format!("page={page}")));
+            let _ = run(&mw, &mut req, || cacheable("plain"));
+        }
+        assert!(mw.vary.lock().is_empty());
+
+        for i in 0..MAX_VARY_PATHS + 10 {
+            let mut req = Request::new(Method::Get, format!("/item/{i}"));
+            let _ = run(&mw, &mut req, || {
+                cacheable("v").header("vary", b"Accept".to_vec())
+            });
+        }
+        assert_eq!(mw.vary.lock().len(), MAX_VARY_PATHS);
+    }
+
+    #[test]
+    fn memory_store_evicts_least_recently_used() {
+        let store = MemoryCacheStore::new(2);
+        let entry = CachedResponse {
+            status: StatusCode::OK,
+            headers: Vec::new(),
+            body: Vec::new(),
+            stored_at: SystemTime::now(),
+        };
+        let ttl = Duration::from_secs(60);
+        futures_executor::block_on(async {
+            store.put("a", entry.clone(), ttl).await;
+            store.put("b", entry.clone(), ttl).await;
+            assert!(store.get("a").await.is_some());
+            store.put("c", entry.clone(), ttl).await;
+            assert!(store.get("b").await.is_none());
+            assert!(store.get("a").await.is_some());
+
+            store.put("expired", entry, Duration::ZERO).await;
+            assert!(store.get("expired").await.is_none());
+        });
+        assert_eq!(store.evictions(), 2);
+    }
+}
@@ -387,7 +387,7 @@ impl<T> RouteTable<T> {
                 allowed_methods.push(Method::Head);
             }
             // Sort methods for consistent output
-            allowed_methods.sort_by_key(|m| method_order(*m));
+            allowed_methods.sort_by_key(|m| (method_order(*m), m.as_str()));
             return RouteLookup::MethodNotAllowed {
                 allowed: allowed_methods,
             };
@@ -524,7 +524,8 @@ impl<T> Default for RouteTable<T> {
 /// Get the sort order for an HTTP method.
 ///
 /// Used to produce consistent ordering in Allow headers:
-/// GET, HEAD, POST, PUT, DELETE, PATCH, OPTIONS, TRACE
+/// GET, HEAD, POST, PUT, DELETE, PATCH, OPTIONS, TRACE, CONNECT, then
+/// extension methods (which callers order by name).
 #[must_use]
 pub fn method_order(method: Method) -> u8 {
     match method {
@@ -536,6 +537,8 @@ pub fn method_order(method: Method) -> u8 {
         Method::Patch => 5,
         Method::Options => 6,
         Method::Trace => 7,
+        Method::Connect => 8,
+        Method::Other(_) => 9,
     }
 }
 
@@ -0,0 +1,716 @@
+//! Scheduled and periodic jobs.
+//!
+//! A [`Scheduler`] runs named jobs on a [`Schedule`]: either a fixed delay
+//! between runs or a 5-field cron expression evaluated in UTC. Jobs are
+//! registered on the builder and run inside the server's runtime:
+//!
+//! ```ignore
+//! use fastapi_core::scheduler::{Schedule, Scheduler, status_handler};
+//! use std::time::Duration;
+//!
+//! let scheduler = Scheduler::new();
+//! scheduler.add("nightly-report", Schedule::cron("30 2 * * *")?, || async {
+//!     send_report().await
+//! });
+//!
+//! let app = App::builder()
+//!     .scheduler(scheduler.clone())
+//!     .schedule("purge-sessions", Schedule::every(Duration::from_secs(300)), || async {
+//!         purge_expired_sessions().await
+//!     })
+//!     .get("/health/jobs", status_handler(scheduler))
+//!     .build();
+//! ```
+//!
+//! The jobs start from a startup hook and stop from a shutdown hook: once
+//! shutdown begins no new run starts, and the hook waits for runs already in
+//! progress. A job returning `Err` (or panicking) is logged and scheduled
+//! again as usual. [`Scheduler::statuses`] reports each job's last and next
+//! run for health endpoints.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::{Pin, pin};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::task::Poll;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use asupersync::runtime::Runtime;
+use parking_lot::Mutex;
+
+use crate::context::RequestContext;
+use crate::request::Request;
+use crate::response::{Response, ResponseBody, StatusCode};
+use crate::shutdown::{ShutdownController, ShutdownReceiver};
+
+/// Upper bound on the minutes a cron search walks before giving up.
+const CRON_SEARCH_LIMIT: usize = 100_000;
+
+/// When a job runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    /// Run repeatedly, waiting this long after each run finishes.
+    Interval(Duration),
+    /// Run at the minutes matched by a cron expression.
+    Cron(CronSchedule),
+}
+
+impl Schedule {
+    /// A fixed delay between runs. The first run is one interval after start.
+    #[must_use]
+    pub fn every(interval: Duration) -> Self {
+        Self::Interval(interval)
+    }
+
+    /// Parse a cron expression; see [`CronSchedule::parse`].
+    pub fn cron(expr: &str) -> Result<Self, CronParseError> {
+        CronSchedule::parse(expr).map(Self::Cron)
+    }
+
+    /// The first run time strictly after `now`, or `None` if there is none.
+    #[must_use]
+    pub fn next_after(&self, now: SystemTime) -> Option<SystemTime> {
+        match self {
+            Self::Interval(interval) => now.checked_add(*interval),
+            Self::Cron(cron) => cron.next_after(now),
+        }
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Interval(interval) => write!(f, "every {}s", interval.as_secs_f64()),
+            Self::Cron(cron) => f.write_str(&cron.source),
+        }
+    }
+}
+
+/// A parsed cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    source: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Whether the day-of-month / day-of-week fields were `*`.
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    /// Parse `minute hour day-of-month month day-of-week`.
+    ///
+    /// Each field accepts `*`, numbers, ranges (`1-5`), lists (`1,15`) and
+    /// steps (`*/10`, `0-30/5`). Day-of-week runs from 0 (Sunday) to 7
+    /// (Sunday again). When both day fields are restricted a day matching
+    /// either runs, as in classic cron. The shorthands `@hourly`, `@daily`,
+    /// `@weekly`, `@monthly` and `@yearly` are also accepted. Times are UTC.
+    pub fn parse(expr: &str) -> Result<Self, CronParseError> {
+        let expanded = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(CronParseError::new(
+                expr,
+                format!("expected 5 fields, found {}", fields.len()),
+            ));
+        }
+        let field = |index: usize, min: u32, max: u32| {
+            parse_field(fields[index], min, max)
+                .map_err(|message| CronParseError::new(expr, message))
+        };
+        let mut days_of_week = field(4, 0, 7)?;
+        // 7 is another name for Sunday.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            source: expr.trim().to_string(),
+            minutes: field(0, 0, 59)?,
+            hours: field(1, 0, 23)?,
+            days_of_month: field(2, 1, 31)?,
+            months: field(3, 1, 12)?,
+            days_of_week,
+            any_day_of_month: fields[2] == "*",
+            any_day_of_week: fields[4] == "*",
+        })
+    }
+
+    /// The first matching minute strictly after `now`.
+    #[must_use]
+    pub fn next_after(&self, now: SystemTime) -> Option<SystemTime> {
+        let secs = now.duration_since(UNIX_EPOCH).ok()?.as_secs();
+        let mut minute = i64::try_from(secs / 60).ok()? + 1;
+
+        for _ in 0..CRON_SEARCH_LIMIT {
+            let days = minute.div_euclid(24 * 60);
+            let (_, month, day) = civil_from_days(days);
+            let hour = minute.rem_euclid(24 * 60) / 60;
+            let minute_of_hour = minute.rem_euclid(60);
+
+            if !has_bit(self.months, month) {
+                minute =
+                    (days + i64::from(days_in_month_from(days)) - i64::from(day) + 1) * 24 * 60;
+                continue;
+            }
+            if !self.matches_day(days, day) {
+                minute = (days + 1) * 24 * 60;
+                continue;
+            }
+            if !has_bit(self.hours, hour) {
+                minute = (minute / 60 + 1) * 60;
+                continue;
+            }
+            if !has_bit(self.minutes, minute_of_hour) {
+                minute += 1;
+                continue;
+            }
+            let secs = u64::try_from(minute).ok()? * 60;
+            return UNIX_EPOCH.checked_add(Duration::from_secs(secs));
+        }
+        None
+    }
+
+    fn matches_day(&self, days: i64, day: u32) -> bool {
+        // 1970-01-01 was a Thursday; Sunday is 0.
+        let weekday = (days + 4).rem_euclid(7);
+        let dom = has_bit(self.days_of_month, day);
+        let dow = has_bit(self.days_of_week, weekday);
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (false, true) => dom,
+            (true, false) => dow,
+            (false, false) => dom || dow,
+        }
+    }
+}
+
+/// Error returned for an invalid cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronParseError {
+    /// The expression that failed to parse.
+    pub expr: String,
+    /// What was wrong with it.
+    pub message: String,
+}
+
+impl CronParseError {
+    fn new(expr: &str, message: impl Into<String>) -> Self {
+        Self {
+            expr: expr.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for CronParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid cron expression `{}`: {}",
+            self.expr, self.message
+        )
+    }
+}
+
+impl std::error::Error for CronParseError {}
+
+fn has_bit(set: u64, value: impl TryInto<u32>) -> bool {
+    value
+        .try_into()
+        .ok()
+        .is_some_and(|value: u32| value < 64 && set & (1 << value) != 0)
+}
+
+/// Parse one cron field into a bit set of the allowed values.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let number = |text: &str| {
+        text.parse::<u32>()
+            .ok()
+            .filter(|value| (min..=max).contains(value))
+            .ok_or_else(|| format!("`{text}` is not a number in {min}-{max}"))
+    };
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("`{step}` is not a valid step"))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (number(start)?, number(end)?)
+        } else {
+            let value = number(range)?;
+            // `5/15` means "from 5 to the end, every 15".
+            (value, if step > 1 { max } else { value })
+        };
+        if start > end {
+            return Err(format!("range `{range}` is backwards"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+/// `(year, month, day)` for a count of days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = u32::try_from(doy - (153 * mp + 2) / 5 + 1).unwrap_or(1);
+    let month = u32::try_from(if mp < 10 { mp + 3 } else { mp - 9 }).unwrap_or(1);
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Length of the month containing `days` (days since 1970-01-01).
+fn days_in_month_from(days: i64) -> u32 {
+    let (year, month, _) = civil_from_days(days);
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+/// Snapshot of a job's state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobStatus {
+    /// Job name.
+    pub name: String,
+    /// The job's schedule, as displayed by [`Schedule`]'s `Display`.
+    pub schedule: String,
+    /// Whether a run is in progress.
+    pub running: bool,
+    /// When the last run started.
+    pub last_run: Option<SystemTime>,
+    /// How long the last run took.
+    pub last_duration: Option<Duration>,
+    /// Error from the last run, if it failed.
+    pub last_error: Option<String>,
+    /// When the next run is due; `None` before start and after shutdown.
+    pub next_run: Option<SystemTime>,
+    /// Completed runs.
+    pub runs: u64,
+    /// Runs that returned an error or panicked.
+    pub failures: u64,
+}
+
+impl JobStatus {
+    fn to_json(&self) -> serde_json::Value {
+        let unix = |time: Option<SystemTime>| {
+            time.and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|since| since.as_secs())
+        };
+        serde_json::json!({
+            "name": self.name,
+            "schedule": self.schedule,
+            "running": self.running,
+            "last_run": unix(self.last_run),
+            "last_duration_ms": self.last_duration.map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX)),
+            "last_error": self.last_error,
+            "next_run": unix(self.next_run),
+            "runs": self.runs,
+            "failures": self.failures,
+        })
+    }
+}
+
+type JobFn =
+    Box<dyn Fn() -> Pin<Box<dyn Future<Output = Result<(), String>> + Send>> + Send + Sync>;
+
+struct Job {
+    schedule: Schedule,
+    run: JobFn,
+    status: Mutex<JobStatus>,
+}
+
+#[derive(Default)]
+struct SchedulerInner {
+    jobs: Mutex<Vec<Arc<Job>>>,
+    shutdown: ShutdownController,
+    started: AtomicBool,
+    active_runs: AtomicUsize,
+}
+
+/// Runs registered jobs on their schedules.
+///
+/// Cloning is cheap and clones share the same jobs, so a clone can be handed
+/// to a status endpoint while the app owns the original.
+#[derive(Clone, Default)]
+pub struct Scheduler {
+    inner: Arc<SchedulerInner>,
+}
+
+impl Scheduler {
+    /// Create an empty scheduler.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a job.
+    ///
+    /// Jobs added after [`start`](Self::start) begin immediately.
+    pub fn add<F, Fut, E>(&self, name: impl Into<String>, schedule: Schedule, job: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: fmt::Display + 'static,
+    {
+        let job = Arc::new(Job {
+            status: Mutex::new(JobStatus {
+                name: name.into(),
+                schedule: schedule.to_string(),
+                running: false,
+                last_run: None,
+                last_duration: None,
+                last_error: None,
+                next_run: None,
+                runs: 0,
+                failures: 0,
+            }),
+            schedule,
+            run: Box::new(move || {
+                let fut = job();
+                Box::pin(async move { fut.await.map_err(|err| err.to_string()) })
+            }),
+        });
+        self.inner.jobs.lock().push(Arc::clone(&job));
+        if self.inner.started.load(Ordering::Acquire) {
+            // Nothing to report here; `start` already found a runtime.
+            let _ = spawn_job(&self.inner, job);
+        }
+    }
+
+    /// Move this scheduler's jobs onto `other`, keeping their order.
+    pub(crate) fn move_jobs_to(&self, other: &Self) {
+        if Arc::ptr_eq(&self.inner, &other.inner) {
+            return;
+        }
+        let jobs = std::mem::take(&mut *self.inner.jobs.lock());
+        other.inner.jobs.lock().extend(jobs);
+    }
+
+    /// Number of registered jobs.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.jobs.lock().len()
+    }
+
+    /// Whether no jobs are registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.jobs.lock().is_empty()
+    }
+
+    /// Spawn every job onto the current runtime.
+    ///
+    /// Calling this again is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Fails when called outside an asupersync runtime.
+    pub fn start(&self) -> Result<(), SchedulerError> {
+        if self.inner.started.swap(true, Ordering::AcqRel) {
+            return Ok(());
+        }
+        let jobs: Vec<Arc<Job>> = self.inner.jobs.lock().clone();
+        for job in jobs {
+            if let Err(err) = spawn_job(&self.inner, job) {
+                self.inner.started.store(false, Ordering::Release);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop scheduling runs and wait for the runs in progress to finish.
+    pub async fn shutdown(&self) {
+        self.inner.shutdown.shutdown();
+        while self.inner.active_runs.load(Ordering::Acquire) > 0 {
+            asupersync::time::sleep(asupersync::time::wall_now(), Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Whether [`shutdown`](Self::shutdown) has been called.
+    #[must_use]
+    pub fn is_shut_down(&self) -> bool {
+        self.inner.shutdown.is_shutting_down()
+    }
+
+    /// Status of every job, in registration order.
+    #[must_use]
+    pub fn statuses(&self) -> Vec<JobStatus> {
+        self.inner
+            .jobs
+            .lock()
+            .iter()
+            .map(|job| job.status.lock().clone())
+            .collect()
+    }
+
+    /// Status of the job called `name`.
+    #[must_use]
+    pub fn status(&self, name: &str) -> Option<JobStatus> {
+        self.inner.jobs.lock().iter().find_map(|job| {
+            let status = job.status.lock();
+            (status.name == name).then(|| status.clone())
+        })
+    }
+
+    /// Serialize all job statuses as `{"jobs": [...]}`.
+    ///
+    /// Times are Unix timestamps in seconds.
+    #[must_use]
+    pub fn to_json(&self) -> Vec<u8> {
+        let jobs: Vec<serde_json::Value> = self.statuses().iter().map(JobStatus::to_json).collect();
+        serde_json::to_vec(&serde_json::json!({ "jobs": jobs })).unwrap_or_default()
+    }
+}
+
+impl fmt::Debug for Scheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scheduler")
+            .field("jobs", &self.statuses())
+            .finish()
+    }
+}
+
+/// Error returned when the scheduler cannot start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchedulerError {
+    /// What went wrong.
+    pub message: String,
+}
+
+impl fmt::Display for SchedulerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for SchedulerError {}
+
+fn spawn_job(inner: &Arc<SchedulerInner>, job: Arc<Job>) -> Result<(), SchedulerError> {
+    let runtime = Runtime::current_handle().ok_or_else(|| SchedulerError {
+        message: "the scheduler must be started inside an asupersync runtime".to_string(),
+    })?;
+    let inner = Arc::clone(inner);
+    let name = job.status.lock().name.clone();
+    runtime
+        .try_spawn(job_loop(inner, job))
+        .map(|_| ())
+        .map_err(|_| SchedulerError {
+            message: format!("failed to spawn scheduled job `{name}`"),
+        })
+}
+
+async fn job_loop(inner: Arc<SchedulerInner>, job: Arc<Job>) {
+    let shutdown = inner.shutdown.subscribe();
+    loop {
+        let now = SystemTime::now();
+        let Some(next) = job.schedule.next_after(now) else {
+            break;
+        };
+        job.status.lock().next_run = Some(next);
+        let delay = next.duration_since(now).unwrap_or_default();
+        if !sleep_unless_shutdown(&shutdown, delay).await {
+            break;
+        }
+        run_once(&inner, &job).await;
+    }
+    job.status.lock().next_run = None;
+}
+
+/// Sleep for `delay`; returns `false` if shutdown began first.
+async fn sleep_unless_shutdown(shutdown: &ShutdownReceiver, delay: Duration) -> bool {
+    let mut sleep = pin!(asupersync::time::sleep(asupersync::time::wall_now(), delay));
+    let mut stop = pin!(shutdown.wait());
+    std::future::poll_fn(|cx| {
+        if stop.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(false);
+        }
+        sleep.as_mut().poll(cx).map(|_| true)
+    })
+    .await
+}
+
+async fn run_once(inner: &SchedulerInner, job: &Job) {
+    inner.active_runs.fetch_add(1, Ordering::AcqRel);
+    let started_at = SystemTime::now();
+    let started = Instant::now();
+    {
+        let mut status = job.status.lock();
+        status.running = true;
+        status.last_run = Some(started_at);
+        status.next_run = None;
+    }
+
+    let mut run = (job.run)();
+    let outcome = std::future::poll_fn(|cx| {
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| run.as_mut().poll(cx))) {
+            Ok(poll) => poll,
+            Err(payload) => {
+                let reason = payload
+                    .downcast_ref::<&str>()
+                    .map(ToString::to_string)
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                Poll::Ready(Err(format!("panicked: {reason}")))
+            }
+        }
+    })
+    .await;
+
+    let mut status = job.status.lock();
+    status.running = false;
+    status.last_duration = Some(started.elapsed());
+    status.runs += 1;
+    match outcome {
+        Ok(()) => status.last_error = None,
+        Err(err) => {
+            eprintln!("scheduled job `{}` failed: {err}", status.name);
+            status.failures += 1;
+            status.last_error = Some(err);
+        }
+    }
+    drop(status);
+    inner.active_runs.fetch_sub(1, Ordering::AcqRel);
+}
+
+/// Create a handler that reports the scheduler's job statuses as JSON.
+///
+/// Always answers 200; a failing job shows up in its `last_error`.
+pub fn status_handler(
+    scheduler: Scheduler,
+) -> impl Fn(&RequestContext, &mut Request) -> std::future::Ready<Response> + Send + Sync + 'static
+{
+    move |_ctx: &RequestContext, _req: &mut Request| {
+        std::future::ready(
+            Response::with_status(StatusCode::OK)
+                .header("Content-Type", b"application/json".to_vec())
+                .header("Cache-Control", b"no-cache, no-store".to_vec())
+                .body(ResponseBody::Bytes(scheduler.to_json())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-01-15 10:07:30 UTC, a Monday.
+    fn monday_morning() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_705_313_250)
+    }
+
+    fn next(expr: &str, now: SystemTime) -> u64 {
+        Schedule::cron(expr)
+            .unwrap()
+            .next_after(now)
+            .unwrap()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    #[test]
+    fn cron_next_run() {
+        let now = monday_morning();
+        // Next minute.
+        assert_eq!(next("* * * * *", now), 1_705_313_280);
+        // 10:15 the same day.
+        assert_eq!(next("*/15 * * * *", now), 1_705_313_700);
+        // 02:30 the next day.
+        assert_eq!(next("30 2 * * *", now), 1_705_372_200);
+        // Saturday 2024-01-20 00:00.
+        assert_eq!(next("0 0 * * 6", now), 1_705_708_800);
+        // 2024-02-29 00:00 (leap day).
+        assert_eq!(next("0 0 29 2 *", now), 1_709_164_800);
+        // Sunday via 7, and @weekly agrees: 2024-01-21 00:00.
+        assert_eq!(next("0 0 * * 7", now), 1_705_795_200);
+        assert_eq!(next("@weekly", now), 1_705_795_200);
+        // Day-of-month OR day-of-week: the 1st or a Wednesday, whichever is first.
+        assert_eq!(next("0 0 1 * 3", now), 1_705_449_600);
+        // Never matches.
+        assert!(
+            Schedule::cron("0 0 31 2 *")
+                .unwrap()
+                .next_after(now)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn cron_parse_errors() {
+        assert!(Schedule::cron("* * * *").is_err());
+        assert!(Schedule::cron("60 * * * *").is_err());
+        assert!(Schedule::cron("*/0 * * * *").is_err());
+        assert!(Schedule::cron("5-1 * * * *").is_err());
+        let err = Schedule::cron("a * * * *").unwrap_err();
+        assert!(err.to_string().contains("`a`"));
+    }
+
+    #[test]
+    fn interval_and_statuses() {
+        let now = monday_morning();
+        let every = Schedule::every(Duration::from_secs(90));
+        assert_eq!(every.next_after(now), Some(now + Duration::from_secs(90)));
+
+        let scheduler = Scheduler::new();
+        scheduler.add("purge", every, || async { Ok::<(), String>(()) });
+        assert_eq!(scheduler.len(), 1);
+        let status = scheduler.status("purge").unwrap();
+        assert_eq!(status.schedule, "every 90s");
+        assert_eq!(status.runs, 0);
+        assert!(status.next_run.is_none());
+
+        let job = Arc::clone(&scheduler.inner.jobs.lock()[0]);
+        scheduler.add(
+            "broken",
+            Schedule::every(Duration::from_secs(1)),
+            || async { Err::<(), _>("database unavailable") },
+        );
+        let broken = Arc::clone(&scheduler.inner.jobs.lock()[1]);
+        futures_executor::block_on(run_once(&scheduler.inner, &job));
+        futures_executor::block_on(run_once(&scheduler.inner, &broken));
+
+        let statuses = scheduler.statuses();
+        assert_eq!(statuses[0].runs, 1);
+        assert!(statuses[0].last_run.is_some());
+        assert_eq!(statuses[1].failures, 1);
+        assert_eq!(
+            statuses[1].last_error.as_deref(),
+            Some("database unavailable")
+        );
+
+        let json: serde_json::Value = serde_json::from_slice(&scheduler.to_json()).unwrap();
+        assert_eq!(json["jobs"][1]["last_error"], "database unavailable");
+        assert_eq!(json["jobs"][0]["next_run"], serde_json::Value::Null);
+    }
+}
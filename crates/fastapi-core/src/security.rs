@@ -0,0 +1,369 @@
+//! OAuth2 scopes for security dependencies.
+//!
+//! [`Security<T, S>`] resolves the dependency `T` like [`Depends`], while
+//! requiring the scopes listed by the marker type `S`. Scopes accumulate
+//! through nested `Security` dependencies, and any dependency can read the
+//! full set required at its point in the tree with the [`SecurityScopes`]
+//! extractor, typically to compare it with the scopes granted to a token:
+//!
+//! ```ignore
+//! struct ReadItems;
+//! impl Scopes for ReadItems {
+//!     const SCOPES: &'static [&'static str] = &["items:read"];
+//! }
+//!
+//! #[derive(Clone)]
+//! struct CurrentUser { name: String }
+//!
+//! impl FromDependency for CurrentUser {
+//!     type Error = HttpError;
+//!
+//!     async fn from_dependency(ctx: &RequestContext, req: &mut Request) -> Result<Self, HttpError> {
+//!         let token = OAuth2PasswordBearer::from_request(ctx, req).await.map_err(..)?;
+//!         let claims = decode(&token)?;
+//!         let required = SecurityScopes::from_request(ctx, req).await.unwrap();
+//!         required.require(claims.scope.split_whitespace())?; // 403 on missing scopes
+//!         Ok(CurrentUser { name: claims.sub })
+//!     }
+//! }
+//!
+//! #[get("/items")]
+//! async fn items(cx: &Cx, user: Security<CurrentUser, ReadItems>) -> String { .. }
+//! ```
+//!
+//! When `T` also implements [`SecurityExtractor`], the operation's OpenAPI
+//! security requirement lists `T`'s scheme with the accumulated scopes.
+
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use crate::context::RequestContext;
+use crate::dependency::{DependencyScope, Depends, DependsConfig, FromDependency};
+use crate::dependency_graph::{DeclaresRequirements, DependencyRequirement};
+use crate::extract::{FromRequest, SecurityExtractor};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response, ResponseBody, StatusCode};
+
+/// A list of OAuth2 scopes, named by a marker type.
+pub trait Scopes {
+    /// The scopes.
+    const SCOPES: &'static [&'static str];
+}
+
+/// No scopes: `Security<T>` behaves like an uncached `Depends<T>`.
+impl Scopes for () {
+    const SCOPES: &'static [&'static str] = &[];
+}
+
+/// Resolves `Security` dependencies freshly, since the same dependency may
+/// be required with different scopes within one request.
+#[derive(Debug, Clone, Copy)]
+struct SecurityConfig;
+
+impl DependsConfig for SecurityConfig {
+    const USE_CACHE: bool = false;
+    const SCOPE: Option<DependencyScope> = None;
+}
+
+/// A dependency that requires the OAuth2 scopes in `S`.
+#[derive(Debug, Clone)]
+pub struct Security<T, S = ()>(pub T, PhantomData<S>);
+
+impl<T, S> Security<T, S> {
+    /// Create a new `Security` wrapper.
+    #[must_use]
+    pub fn new(value: T) -> Self {
+        Self(value, PhantomData)
+    }
+
+    /// Unwrap the inner value.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, S> Deref for Security<T, S> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, S> DerefMut for Security<T, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Request extension with the scopes required along the current
+/// `Security` chain.
+#[derive(Debug, Default)]
+struct RequiredScopes(Vec<String>);
+
+impl<T, S> FromRequest for Security<T, S>
+where
+    T: FromDependency,
+    S: Scopes + Send + Sync + 'static,
+{
+    type Error = T::Error;
+
+    async fn from_request(ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
+        let mut required = req.take_extension::<RequiredScopes>().unwrap_or_default();
+        let outer_len = required.0.len();
+        for scope in S::SCOPES {
+            if !required.0.iter().any(|s| s == scope) {
+                required.0.push((*scope).to_string());
+            }
+        }
+        req.insert_extension(required);
+
+        let result = Depends::<T, SecurityConfig>::from_request(ctx, req).await;
+
+        // Scopes added here only apply to this dependency and what it uses.
+        if let Some(required) = req.get_extension_mut::<RequiredScopes>() {
+            required.0.truncate(outer_len);
+        }
+        result.map(|dep| Self::new(dep.into_inner()))
+    }
+}
+
+impl<T, S> SecurityExtractor for Security<T, S>
+where
+    T: SecurityExtractor,
+    S: Scopes,
+{
+    const SCHEME_NAME: &'static str = T::SCHEME_NAME;
+
+    fn security_scheme() -> fastapi_openapi::SecurityScheme {
+        T::security_scheme()
+    }
+
+    fn required_scopes() -> Vec<String> {
+        let mut scopes = T::required_scopes();
+        for scope in S::SCOPES {
+            if !scopes.iter().any(|s| s == scope) {
+                scopes.push((*scope).to_string());
+            }
+        }
+        scopes
+    }
+}
+
+impl<T: FromDependency, S> DeclaresRequirements for Security<T, S> {
+    fn requirements() -> Vec<DependencyRequirement> {
+        vec![DependencyRequirement::dependency::<T, SecurityConfig>()]
+    }
+}
+
+/// The scopes required at this point of the dependency tree.
+///
+/// Empty outside any [`Security`] dependency.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecurityScopes {
+    /// Required scopes, outermost first.
+    pub scopes: Vec<String>,
+}
+
+impl SecurityScopes {
+    /// The scopes separated by spaces, as in a `scope` claim or a
+    /// `WWW-Authenticate` challenge.
+    #[must_use]
+    pub fn scope_str(&self) -> String {
+        self.scopes.join(" ")
+    }
+
+    /// Required scopes absent from `granted`.
+    #[must_use]
+    pub fn missing<'a>(&self, granted: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let granted: Vec<&str> = granted.into_iter().collect();
+        self.scopes
+            .iter()
+            .filter(|scope| !granted.iter().any(|g| g == scope))
+            .cloned()
+            .collect()
+    }
+
+    /// Check that `granted` covers every required scope.
+    pub fn require<'a>(
+        &self,
+        granted: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(), InsufficientScopes> {
+        let missing = self.missing(granted);
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(InsufficientScopes {
+                required: self.scopes.clone(),
+                missing,
+            })
+        }
+    }
+}
+
+impl FromRequest for SecurityScopes {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(_ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
+        let scopes = req
+            .get_extension::<RequiredScopes>()
+            .map(|required| required.0.clone())
+            .unwrap_or_default();
+        Ok(Self { scopes })
+    }
+}
+
+/// Error when a credential lacks required scopes.
+///
+/// Renders as 403 with the missing scopes in the body and a
+/// `WWW-Authenticate: Bearer scope="..."` challenge listing all required ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsufficientScopes {
+    /// Every scope required.
+    pub required: Vec<String>,
+    /// The required scopes that were not granted.
+    pub missing: Vec<String>,
+}
+
+impl std::fmt::Display for InsufficientScopes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Missing scopes: {}", self.missing.join(" "))
+    }
+}
+
+impl std::error::Error for InsufficientScopes {}
+
+impl IntoResponse for InsufficientScopes {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "detail": "Not enough permissions",
+            "missing_scopes": self.missing,
+        });
+        let challenge = format!("Bearer scope=\"{}\"", self.required.join(" "));
+        Response::with_status(StatusCode::FORBIDDEN)
+            .header("www-authenticate", challenge.into_bytes())
+            .header("content-type", b"application/json".to_vec())
+            .body(ResponseBody::Bytes(body.to_string().into_bytes()))
+    }
+}
+
+impl From<InsufficientScopes> for crate::HttpError {
+    fn from(err: InsufficientScopes) -> Self {
+        let challenge = format!("Bearer scope=\"{}\"", err.required.join(" "));
+        Self::new(StatusCode::FORBIDDEN)
+            .with_detail(format!(
+                "Not enough permissions: missing {}",
+                err.missing.join(", ")
+            ))
+            .with_header("www-authenticate", challenge.into_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HttpError;
+    use crate::request::Method;
+
+    struct Read;
+    impl Scopes for Read {
+        const SCOPES: &'static [&'static str] = &["items:read"];
+    }
+
+    struct Write;
+    impl Scopes for Write {
+        const SCOPES: &'static [&'static str] = &["items:write"];
+    }
+
+    /// Checks the required scopes against an `x-scopes` header.
+    #[derive(Clone, Debug)]
+    struct Token {
+        required: Vec<String>,
+    }
+
+    impl FromDependency for Token {
+        type Error = HttpError;
+
+        async fn from_dependency(
+            ctx: &RequestContext,
+            req: &mut Request,
+        ) -> Result<Self, Self::Error> {
+            let granted = req
+                .headers()
+                .get("x-scopes")
+                .and_then(|v| std::str::from_utf8(v).ok())
+                .unwrap_or("")
+                .to_string();
+            let required = SecurityScopes::from_request(ctx, req).await.unwrap();
+            required.require(granted.split_whitespace())?;
+            Ok(Self {
+                required: required.scopes,
+            })
+        }
+    }
+
+    /// Needs a token with write access on top of its own requirements.
+    #[derive(Clone, Debug)]
+    struct Editor {
+        token: Token,
+    }
+
+    impl FromDependency for Editor {
+        type Error = HttpError;
+
+        async fn from_dependency(
+            ctx: &RequestContext,
+            req: &mut Request,
+        ) -> Result<Self, Self::Error> {
+            let token = Security::<Token, Write>::from_request(ctx, req).await?;
+            Ok(Self {
+                token: token.into_inner(),
+            })
+        }
+    }
+
+    fn resolve<T: FromRequest>(granted: &str) -> Result<T, T::Error> {
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+        let mut req = Request::new(Method::Get, "/items");
+        req.headers_mut()
+            .insert("x-scopes", granted.as_bytes().to_vec());
+        futures_executor::block_on(T::from_request(&ctx, &mut req))
+    }
+
+    #[test]
+    fn nested_security_dependencies_accumulate_scopes() {
+        let editor = resolve::<Security<Editor, Read>>("items:read items:write").unwrap();
+        assert_eq!(editor.token.required, vec!["items:read", "items:write"]);
+
+        let token = resolve::<Security<Token, Read>>("items:read").unwrap();
+        assert_eq!(token.required, vec!["items:read"]);
+    }
+
+    #[test]
+    fn missing_scopes_are_forbidden() {
+        let err = resolve::<Security<Editor, Read>>("items:read").unwrap_err();
+        let response = err.into_response();
+        assert_eq!(response.status().as_u16(), 403);
+        assert!(response.headers().iter().any(
+            |(n, v)| n == "www-authenticate" && v == b"Bearer scope=\"items:read items:write\""
+        ));
+
+        let err = SecurityScopes {
+            scopes: vec!["a".into(), "b".into()],
+        }
+        .require(["a"])
+        .unwrap_err();
+        assert_eq!(err.missing, vec!["b"]);
+    }
+
+    #[test]
+    fn openapi_requirement_includes_wrapper_scopes() {
+        let scopes = <Security<crate::OAuth2PasswordBearer, Read>>::required_scopes();
+        assert_eq!(scopes, vec!["items:read"]);
+        assert_eq!(
+            <Security<crate::OAuth2PasswordBearer, Read>>::SCHEME_NAME,
+            "OAuth2PasswordBearer"
+        );
+    }
+}
@@ -0,0 +1,896 @@
+//! Cookie-backed sessions with login/logout helpers.
+//!
+//! [`SessionMiddleware`] loads a [`Session`] from a [`SessionStore`] using an
+//! opaque id cookie, exposes it as an extractor, and saves it after the
+//! handler runs. On top of that it provides the usual auth plumbing:
+//!
+//! - [`Session::login`] records the user id and issues a fresh session id, so
+//!   an id planted before login is worthless afterwards.
+//! - [`Session::logout`] clears the session and expires the cookie.
+//! - [`Session::remember`] turns the cookie into a persistent "remember me"
+//!   cookie that outlives the browser session.
+//! - [`CurrentUser<U>`] loads the logged-in user through a [`UserLoader`]
+//!   and answers 401 when nobody is logged in.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use fastapi_core::session::{CurrentUser, Session, SessionMiddleware, UserLoader};
+//!
+//! struct Users;
+//!
+//! impl UserLoader for Users {
+//!     type User = User;
+//!
+//!     fn load_user<'a>(
+//!         &'a self,
+//!         _ctx: &'a RequestContext,
+//!         user_id: &'a str,
+//!     ) -> BoxFuture<'a, Option<User>> {
+//!         Box::pin(async move { db::find_user(user_id).await })
+//!     }
+//! }
+//!
+//! #[post("/login")]
+//! async fn login(session: Session, form: Form<Credentials>) -> Response {
+//!     let user = check_credentials(&form)?;
+//!     session.login(user.id.to_string());
+//!     session.remember(form.remember_me);
+//!     Response::ok()
+//! }
+//!
+//! #[get("/me")]
+//! async fn me(CurrentUser(user): CurrentUser<User>) -> Json<User> {
+//!     Json(user)
+//! }
+//!
+//! let app = App::builder()
+//!     .middleware(SessionMiddleware::new().user_loader(Users))
+//!     .build();
+//! ```
+
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::context::RequestContext;
+use crate::error::HttpError;
+use crate::extract::{FromRequest, parse_cookies};
+use crate::middleware::{BoxFuture, ControlFlow, Middleware};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response, SameSite, SetCookie};
+
+/// Session contents as persisted by a [`SessionStore`].
+pub type SessionData = HashMap<String, serde_json::Value>;
+
+/// Session key holding the logged-in user id.
+pub const USER_ID_KEY: &str = "_user_id";
+/// Session key marking a "remember me" session.
+pub const REMEMBER_KEY: &str = "_remember";
+
+// ============================================================================
+// Stores
+// ============================================================================
+
+/// Backend that persists session data by session id.
+pub trait SessionStore: Send + Sync + 'static {
+    /// Load the data for `id`, or `None` if it is unknown or expired.
+    fn load<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Option<SessionData>>;
+
+    /// Store `data` under `id`, expiring after `ttl`.
+    fn save<'a>(&'a self, id: &'a str, data: SessionData, ttl: Duration) -> BoxFuture<'a, ()>;
+
+    /// Forget the session `id`.
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, ()>;
+}
+
+/// Store size below which [`MemorySessionStore`] does not sweep.
+const MIN_SWEEP_LEN: usize = 64;
+
+/// In-process [`SessionStore`].
+///
+/// Sessions are lost on restart and not shared between processes; use a
+/// shared store when running several instances.
+///
+/// Expired sessions are dropped when loaded, and swept from the whole store
+/// on `save` whenever it has doubled in size since the last sweep, so
+/// abandoned sessions do not accumulate.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySessionStore {
+    sessions: Arc<Mutex<Sessions>>,
+}
+
+#[derive(Debug)]
+struct Sessions {
+    entries: HashMap<String, (SessionData, Instant)>,
+    /// Size at which the next `save` sweeps expired entries.
+    sweep_at: usize,
+}
+
+impl Default for Sessions {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            sweep_at: MIN_SWEEP_LEN,
+        }
+    }
+}
+
+impl MemorySessionStore {
+    /// Create an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored sessions, including expired ones not yet evicted.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.sessions.lock().entries.len()
+    }
+
+    /// Whether the store holds no sessions.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sessions.lock().entries.is_empty()
+    }
+}
+
+impl SessionStore for MemorySessionStore {
+    fn load<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Option<SessionData>> {
+        Box::pin(async move {
+            let mut sessions = self.sessions.lock();
+            let sessions = &mut sessions.entries;
+            match sessions.get(id) {
+                Some((data, expires)) if *expires > Instant::now() => Some(data.clone()),
+                Some(_) => {
+                    sessions.remove(id);
+                    None
+                }
+                None => None,
+            }
+        })
+    }
+
+    fn save<'a>(&'a self, id: &'a str, data: SessionData, ttl: Duration) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            let now = Instant::now();
+            let mut sessions = self.sessions.lock();
+            sessions.entries.insert(id.to_string(), (data, now + ttl));
+            if sessions.entries.len() >= sessions.sweep_at {
+                sessions.entries.retain(|_, (_, expires)| *expires > now);
+                sessions.sweep_at = (sessions.entries.len() * 2).max(MIN_SWEEP_LEN);
+            }
+        })
+    }
+
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            self.sessions.lock().entries.remove(id);
+        })
+    }
+}
+
+// ============================================================================
+// Session handle
+// ============================================================================
+
+#[derive(Debug, Default)]
+struct SessionState {
+    /// Id the session was loaded under; `None` for a new session.
+    id: Option<String>,
+    data: SessionData,
+    modified: bool,
+    /// Issue a new id on save (login/logout).
+    renew: bool,
+}
+
+/// What the middleware must do with a session after the handler.
+enum Commit {
+    Keep,
+    Save {
+        old: Option<String>,
+        data: SessionData,
+        renew: bool,
+    },
+    Destroy(String),
+}
+
+/// The current request's session.
+///
+/// Inserted by [`SessionMiddleware`] and available as an extractor. Clones
+/// share the same state, so changes made by the handler are saved when the
+/// response goes out.
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    state: Arc<Mutex<SessionState>>,
+}
+
+impl Session {
+    fn loaded(id: Option<String>, data: SessionData) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SessionState {
+                id,
+                data,
+                ..SessionState::default()
+            })),
+        }
+    }
+
+    /// Read and deserialize a value.
+    #[must_use]
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let state = self.state.lock();
+        let value = state.data.get(key)?;
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    /// Store a value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` cannot be serialized to JSON.
+    pub fn insert<T: Serialize>(&self, key: &str, value: T) -> Result<(), serde_json::Error> {
+        let value = serde_json::to_value(value)?;
+        let mut state = self.state.lock();
+        state.data.insert(key.to_string(), value);
+        state.modified = true;
+        Ok(())
+    }
+
+    /// Remove a value, returning it if present.
+    pub fn remove(&self, key: &str) -> Option<serde_json::Value> {
+        let mut state = self.state.lock();
+        let removed = state.data.remove(key);
+        state.modified |= removed.is_some();
+        removed
+    }
+
+    /// Remove every value. An emptied session is deleted from the store.
+    pub fn clear(&self) {
+        let mut state = self.state.lock();
+        state.data.clear();
+        state.modified = true;
+    }
+
+    /// Mark `user_id` as logged in.
+    ///
+    /// The session is saved under a new id, and the previous id is
+    /// discarded, which prevents session fixation.
+    pub fn login(&self, user_id: impl Into<String>) {
+        let mut state = self.state.lock();
+        let user_id = serde_json::Value::String(user_id.into());
+        state.data.insert(USER_ID_KEY.to_string(), user_id);
+        state.modified = true;
+        state.renew = true;
+    }
+
+    /// Log out: clear the session, delete it from the store and expire the
+    /// cookie.
+    pub fn logout(&self) {
+        let mut state = self.state.lock();
+        state.data.clear();
+        state.modified = true;
+        state.renew = true;
+    }
+
+    /// Keep the session across browser restarts.
+    ///
+    /// A remembered session gets a persistent cookie and lives for
+    /// [`SessionConfig::remember_for`] instead of [`SessionConfig::ttl`].
+    pub fn remember(&self, remember: bool) {
+        let mut state = self.state.lock();
+        if remember {
+            state.data.insert(REMEMBER_KEY.to_string(), true.into());
+        } else {
+            state.data.remove(REMEMBER_KEY);
+        }
+        state.modified = true;
+    }
+
+    /// The logged-in user id, if any.
+    #[must_use]
+    pub fn user_id(&self) -> Option<String> {
+        self.get(USER_ID_KEY)
+    }
+
+    /// Whether a user is logged in.
+    #[must_use]
+    pub fn is_authenticated(&self) -> bool {
+        self.state.lock().data.contains_key(USER_ID_KEY)
+    }
+
+    /// Whether this is a "remember me" session.
+    #[must_use]
+    pub fn is_remembered(&self) -> bool {
+        self.get(REMEMBER_KEY).unwrap_or(false)
+    }
+
+    fn commit(&self) -> Commit {
+        let state = self.state.lock();
+        if !state.modified {
+            return Commit::Keep;
+        }
+        if state.data.is_empty() {
+            return match &state.id {
+                Some(id) => Commit::Destroy(id.clone()),
+                None => Commit::Keep,
+            };
+        }
+        Commit::Save {
+            old: state.id.clone(),
+            data: state.data.clone(),
+            renew: state.renew,
+        }
+    }
+}
+
+/// Error returned when the [`Session`] extractor runs without
+/// [`SessionMiddleware`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingSessionError;
+
+impl std::fmt::Display for MissingSessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Session middleware is not installed")
+    }
+}
+
+impl std::error::Error for MissingSessionError {}
+
+impl IntoResponse for MissingSessionError {
+    fn into_response(self) -> Response {
+        // A missing session is a server configuration error (500)
+        HttpError::internal()
+            .with_detail(self.to_string())
+            .into_response()
+    }
+}
+
+impl FromRequest for Session {
+    type Error = MissingSessionError;
+
+    async fn from_request(_ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
+        req.get_extension::<Session>()
+            .cloned()
+            .ok_or(MissingSessionError)
+    }
+}
+
+// ============================================================================
+// Current user
+// ============================================================================
+
+/// Loads the user behind a session's user id.
+pub trait UserLoader: Send + Sync + 'static {
+    /// The user type handed to handlers.
+    type User: Send + 'static;
+
+    /// Load the user for `user_id`, or `None` if it no longer exists.
+    fn load_user<'a>(
+        &'a self,
+        ctx: &'a RequestContext,
+        user_id: &'a str,
+    ) -> BoxFuture<'a, Option<Self::User>>;
+}
+
+/// The logged-in user, loaded through the [`UserLoader`] registered with
+/// [`SessionMiddleware::user_loader`].
+///
+/// Answers 401 when the session has no user or the loader no longer finds
+/// it. Use `Option<CurrentUser<U>>` for routes that also serve anonymous
+/// visitors.
+#[derive(Debug, Clone)]
+pub struct CurrentUser<U>(pub U);
+
+impl<U> CurrentUser<U> {
+    /// Unwrap the inner user.
+    pub fn into_inner(self) -> U {
+        self.0
+    }
+}
+
+impl<U> Deref for CurrentUser<U> {
+    type Target = U;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Error returned when [`CurrentUser`] extraction fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CurrentUserError {
+    /// No user is logged in, or the logged-in user no longer exists.
+    NotAuthenticated,
+    /// [`SessionMiddleware`] is not installed.
+    MissingSession,
+    /// No [`UserLoader`] for the requested user type was registered.
+    MissingLoader {
+        /// The name of the requested user type.
+        type_name: &'static str,
+    },
+}
+
+impl std::fmt::Display for CurrentUserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotAuthenticated => f.write_str("Not authenticated"),
+            Self::MissingSession => write!(f, "{MissingSessionError}"),
+            Self::MissingLoader { type_name } => {
+                write!(f, "No user loader registered for {type_name}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CurrentUserError {}
+
+impl IntoResponse for CurrentUserError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::NotAuthenticated => HttpError::unauthorized().with_detail(self.to_string()),
+            Self::MissingSession | Self::MissingLoader { .. } => {
+                HttpError::internal().with_detail(self.to_string())
+            }
+        }
+        .into_response()
+    }
+}
+
+type SharedLoader<U> = Arc<dyn UserLoader<User = U>>;
+
+impl<U: Send + 'static> FromRequest for CurrentUser<U> {
+    type Error = CurrentUserError;
+
+    async fn from_request(ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
+        let session = req
+            .get_extension::<Session>()
+            .ok_or(CurrentUserError::MissingSession)?;
+        let user_id = session
+            .user_id()
+            .ok_or(CurrentUserError::NotAuthenticated)?;
+        let loader = req.get_extension::<SharedLoader<U>>().cloned().ok_or(
+            CurrentUserError::MissingLoader {
+                type_name: std::any::type_name::<U>(),
+            },
+        )?;
+        loader
+            .load_user(ctx, &user_id)
+            .await
+            .map(CurrentUser)
+            .ok_or(CurrentUserError::NotAuthenticated)
+    }
+}
+
+// ============================================================================
+// Middleware
+// ============================================================================
+
+/// Configuration for [`SessionMiddleware`].
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    /// Cookie holding the session id (default: "session").
+    pub cookie_name: String,
+    /// Cookie path (default: "/").
+    pub path: String,
+    /// Cookie SameSite policy (default: Lax).
+    pub same_site: SameSite,
+    /// Whether in production mode (sets the Secure cookie flag).
+    pub production: bool,
+    /// Lifetime of a session after its last change (default: 24 hours).
+    pub ttl: Duration,
+    /// Lifetime of a "remember me" session and its cookie (default: 30 days).
+    pub remember_for: Duration,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            cookie_name: "session".to_string(),
+            path: "/".to_string(),
+            same_site: SameSite::Lax,
+            production: true,
+            ttl: Duration::from_secs(24 * 60 * 60),
+            remember_for: Duration::from_secs(30 * 24 * 60 * 60),
+        }
+    }
+}
+
+impl SessionConfig {
+    /// Creates a new configuration with defaults.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the session cookie name.
+    #[must_use]
+    pub fn cookie_name(mut self, name: impl Into<String>) -> Self {
+        self.cookie_name = name.into();
+        self
+    }
+
+    /// Sets the session cookie path.
+    #[must_use]
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Sets the session cookie SameSite policy.
+    #[must_use]
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = same_site;
+        self
+    }
+
+    /// Sets production mode (affects Secure cookie flag).
+    #[must_use]
+    pub fn production(mut self, production: bool) -> Self {
+        self.production = production;
+        self
+    }
+
+    /// Sets the session lifetime.
+    #[must_use]
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets the "remember me" lifetime.
+    #[must_use]
+    pub fn remember_for(mut self, remember_for: Duration) -> Self {
+        self.remember_for = remember_for;
+        self
+    }
+}
+
+type InstallLoader = Arc<dyn Fn(&mut Request) + Send + Sync>;
+
+/// Session middleware.
+///
+/// Loads the session named by the session cookie before the handler and
+/// saves it afterwards when it changed. The cookie only carries a random
+/// id; the data stays in the [`SessionStore`]. Unknown or expired ids start
+/// a new, empty session.
+///
+/// # Example
+///
+/// ```ignore
+/// use fastapi_core::session::{SessionConfig, SessionMiddleware};
+///
+/// let sessions = SessionMiddleware::new()
+///     .config(SessionConfig::new().cookie_name("sid").production(false))
+///     .user_loader(Users);
+/// let app = App::builder().middleware(sessions).build();
+/// ```
+#[derive(Clone)]
+pub struct SessionMiddleware {
+    config: SessionConfig,
+    store: Arc<dyn SessionStore>,
+    install_loader: Option<InstallLoader>,
+}
+
+impl Default for SessionMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionMiddleware {
+    /// Creates session middleware backed by a [`MemorySessionStore`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            config: SessionConfig::default(),
+            store: Arc::new(MemorySessionStore::new()),
+            install_loader: None,
+        }
+    }
+
+    /// Sets the configuration.
+    #[must_use]
+    pub fn config(mut self, config: SessionConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets the session store.
+    #[must_use]
+    pub fn store(mut self, store: impl SessionStore) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Registers the loader behind [`CurrentUser<L::User>`](CurrentUser).
+    #[must_use]
+    pub fn user_loader<L: UserLoader>(mut self, loader: L) -> Self {
+        let loader: SharedLoader<L::User> = Arc::new(loader);
+        self.install_loader = Some(Arc::new(move |req: &mut Request| {
+            req.insert_extension(Arc::clone(&loader));
+        }));
+        self
+    }
+
+    fn session_cookie(&self, req: &Request) -> Option<String> {
+        let header = std::str::from_utf8(req.headers().get("cookie")?).ok()?;
+        parse_cookies(header)
+            .find(|(name, _)| *name == self.config.cookie_name)
+            .map(|(_, value)| value.to_string())
+    }
+
+    fn cookie(&self, id: &str) -> SetCookie {
+        SetCookie::new(&self.config.cookie_name, id)
+            .path(&self.config.path)
+            .http_only(true)
+            .secure(self.config.production)
+            .same_site(self.config.same_site)
+    }
+}
+
+impl Middleware for SessionMiddleware {
+    fn before<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        Box::pin(async move {
+            let loaded = match self.session_cookie(req) {
+                Some(id) => self.store.load(&id).await.map(|data| (id, data)),
+                None => None,
+            };
+            let session = match loaded {
+                Some((id, data)) => Session::loaded(Some(id), data),
+                None => Session::default(),
+            };
+            req.insert_extension(session);
+            if let Some(install) = &self.install_loader {
+                install(req);
+            }
+            ControlFlow::Continue
+        })
+    }
+
+    fn after<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a Request,
+        response: Response,
+    ) -> BoxFuture<'a, Response> {
+        let commit = req
+            .get_extension::<Session>()
+            .map_or(Commit::Keep, Session::commit);
+
+        Box::pin(async move {
+            match commit {
+                Commit::Keep => response,
+                Commit::Destroy(id) => {
+                    self.store.remove(&id).await;
+                    response.set_cookie(self.cookie("").max_age(0))
+                }
+                Commit::Save { old, data, renew } => {
+                    let id = match old {
+                        Some(old) if !renew => old,
+                        old => {
+                            if let Some(old) = old {
+                                self.store.remove(&old).await;
+                            }
+                            generate_session_id()
+                        }
+                    };
+                    let remember = data.get(REMEMBER_KEY) == Some(&serde_json::Value::Bool(true));
+                    let ttl = if remember {
+                        self.config.remember_for
+                    } else {
+                        self.config.ttl
+                    };
+                    self.store.save(&id, data, ttl).await;
+
+                    let mut cookie = self.cookie(&id);
+                    if remember {
+                        let max_age = i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX);
+                        cookie = cookie.max_age(max_age);
+                    }
+                    response.set_cookie(cookie)
+                }
+            }
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "SessionMiddleware"
+    }
+}
+
+/// Generate an unguessable session id.
+///
+/// # Panics
+///
+/// Panics if the OS CSPRNG is unavailable; predictable session ids would
+/// let attackers hijack sessions.
+fn generate_session_id() -> String {
+    use std::fmt::Write;
+
+    let mut bytes = [0u8; 32];
+    if let Err(err) = getrandom::fill(&mut bytes) {
+        panic!(
+            "FATAL: OS cryptographically secure random source is unavailable ({err}). \
+             Session ids require a CSPRNG."
+        );
+    }
+    let mut id = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(id, "{b:02x}");
+    }
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Method;
+
+    struct Users;
+
+    impl UserLoader for Users {
+        type User = String;
+
+        fn load_user<'a>(
+            &'a self,
+            _ctx: &'a RequestContext,
+            user_id: &'a str,
+        ) -> BoxFuture<'a, Option<String>> {
+            Box::pin(async move { (user_id == "42").then(|| "alice".to_string()) })
+        }
+    }
+
+    fn test_context() -> RequestContext {
+        RequestContext::new(asupersync::Cx::for_testing(), 1)
+    }
+
+    fn middleware(store: &MemorySessionStore) -> SessionMiddleware {
+        SessionMiddleware::new()
+            .config(SessionConfig::new().production(false))
+            .store(store.clone())
+            .user_loader(Users)
+    }
+
+    /// Run a request through the middleware, letting `handler` act on the
+    /// session, and return the request and the `Set-Cookie` header.
+    fn round_trip(
+        middleware: &SessionMiddleware,
+        cookie: Option<&str>,
+        handler: impl FnOnce(&Session),
+    ) -> (Request, Option<String>) {
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/");
+        if let Some(cookie) = cookie {
+            req.headers_mut()
+                .insert("cookie", format!("session={cookie}").into_bytes());
+        }
+        futures_executor::block_on(middleware.before(&ctx, &mut req));
+        handler(req.get_extension::<Session>().unwrap());
+        let response = futures_executor::block_on(middleware.after(&ctx, &req, Response::ok()));
+        let set_cookie = response
+            .headers()
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("set-cookie"))
+            .map(|(_, value)| String::from_utf8(value.clone()).unwrap());
+        (req, set_cookie)
+    }
+
+    fn cookie_id(set_cookie: &str) -> &str {
+        let pair = set_cookie.split(';').next().unwrap();
+        pair.strip_prefix("session=").unwrap()
+    }
+
+    #[test]
+    fn memory_store_sweeps_abandoned_sessions() {
+        let store = MemorySessionStore::new();
+        futures_executor::block_on(async {
+            for i in 0..MIN_SWEEP_LEN - 1 {
+                store
+                    .save(&format!("gone-{i}"), SessionData::new(), Duration::ZERO)
+                    .await;
+            }
+            assert_eq!(store.len(), MIN_SWEEP_LEN - 1);
+            store
+                .save("live", SessionData::new(), Duration::from_secs(60))
+                .await;
+        });
+        assert_eq!(store.len(), 1);
+        assert!(futures_executor::block_on(store.load("live")).is_some());
+    }
+
+    #[test]
+    fn login_persists_user_and_rotates_id() {
+        let store = MemorySessionStore::new();
+        let mw = middleware(&store);
+
+        let (_, set_cookie) = round_trip(&mw, None, |s| s.insert("cart", 3).unwrap());
+        let set_cookie = set_cookie.unwrap();
+        assert!(set_cookie.contains("HttpOnly"));
+        assert!(!set_cookie.contains("Max-Age"));
+        let anonymous_id = cookie_id(&set_cookie).to_string();
+
+        let (_, set_cookie) = round_trip(&mw, Some(&anonymous_id), |s| s.login("42"));
+        let set_cookie = set_cookie.unwrap();
+        let user_id = cookie_id(&set_cookie).to_string();
+        assert_ne!(user_id, anonymous_id);
+        assert_eq!(store.len(), 1);
+
+        let (req, set_cookie) = round_trip(&mw, Some(&user_id), |_| {});
+        assert!(set_cookie.is_none());
+        let session = req.get_extension::<Session>().unwrap();
+        assert_eq!(session.user_id().as_deref(), Some("42"));
+        assert_eq!(session.get::<i32>("cart"), Some(3));
+
+        let (req, _) = round_trip(&mw, Some(&anonymous_id), |_| {});
+        assert!(!req.get_extension::<Session>().unwrap().is_authenticated());
+    }
+
+    #[test]
+    fn remember_me_sets_persistent_cookie() {
+        let store = MemorySessionStore::new();
+        let mw = middleware(&store);
+
+        let (_, set_cookie) = round_trip(&mw, None, |s| {
+            s.login("42");
+            s.remember(true);
+        });
+        let set_cookie = set_cookie.unwrap();
+        assert!(set_cookie.contains("Max-Age=2592000"), "{set_cookie}");
+
+        let (req, _) = round_trip(&mw, Some(cookie_id(&set_cookie)), |_| {});
+        assert!(req.get_extension::<Session>().unwrap().is_remembered());
+    }
+
+    #[test]
+    fn logout_destroys_session_and_expires_cookie() {
+        let store = MemorySessionStore::new();
+        let mw = middleware(&store);
+
+        let (_, set_cookie) = round_trip(&mw, None, |s| s.login("42"));
+        let id = cookie_id(&set_cookie.unwrap()).to_string();
+
+        let (_, set_cookie) = round_trip(&mw, Some(&id), Session::logout);
+        let set_cookie = set_cookie.unwrap();
+        assert!(set_cookie.starts_with("session=;"), "{set_cookie}");
+        assert!(set_cookie.contains("Max-Age=0"));
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn current_user_loads_through_loader() {
+        let store = MemorySessionStore::new();
+        let mw = middleware(&store);
+        let ctx = test_context();
+
+        let (mut req, _) = round_trip(&mw, None, |s| s.login("42"));
+        let user = futures_executor::block_on(CurrentUser::<String>::from_request(&ctx, &mut req));
+        assert_eq!(user.unwrap().into_inner(), "alice");
+
+        let (mut req, _) = round_trip(&mw, None, |s| s.login("7"));
+        let err = futures_executor::block_on(CurrentUser::<String>::from_request(&ctx, &mut req))
+            .unwrap_err();
+        assert_eq!(err, CurrentUserError::NotAuthenticated);
+        assert_eq!(err.into_response().status().as_u16(), 401);
+
+        let (mut req, _) = round_trip(&mw, None, |_| {});
+        let err = futures_executor::block_on(CurrentUser::<u64>::from_request(&ctx, &mut req))
+            .unwrap_err();
+        assert_eq!(err, CurrentUserError::NotAuthenticated);
+
+        let (mut req, _) = round_trip(&mw, None, |s| s.login("42"));
+        let err = futures_executor::block_on(CurrentUser::<u64>::from_request(&ctx, &mut req))
+            .unwrap_err();
+        assert!(matches!(err, CurrentUserError::MissingLoader { .. }));
+    }
+}
@@ -43,12 +43,15 @@
 //! ```
 
 use asupersync::{Budget, CancelReason};
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
 use std::task::{Context, Poll, Waker};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+use crate::request::Method;
 
 // ============================================================================
 // Shutdown Phase State Machine
@@ -130,6 +133,10 @@ struct ShutdownState {
     hooks: parking_lot::Mutex<Vec<ShutdownHook>>,
     /// In-flight request count.
     in_flight: std::sync::atomic::AtomicUsize,
+    /// Next in-flight guard id.
+    next_request_id: AtomicU64,
+    /// Snapshots of described in-flight requests, keyed by guard id.
+    requests: parking_lot::Mutex<HashMap<u64, InFlightRequest>>,
 }
 
 impl ShutdownState {
@@ -140,6 +147,8 @@ impl ShutdownState {
             wakers: parking_lot::Mutex::new(Vec::new()),
             hooks: parking_lot::Mutex::new(Vec::new()),
             in_flight: std::sync::atomic::AtomicUsize::new(0),
+            next_request_id: AtomicU64::new(1),
+            requests: parking_lot::Mutex::new(HashMap::new()),
         }
     }
 
@@ -317,20 +326,49 @@ impl ShutdownController {
 
     /// Track a new in-flight request.
     ///
-    /// Returns a guard that decrements the count when dropped.
+    /// Returns a guard that decrements the count when dropped. Call
+    /// [`InFlightGuard::describe`] once the request line is known so the
+    /// request shows up in [`in_flight()`](Self::in_flight).
     #[must_use]
     pub fn track_request(&self) -> InFlightGuard {
         self.state.increment_in_flight();
         InFlightGuard {
             state: Arc::clone(&self.state),
+            id: self.state.next_request_id.fetch_add(1, Ordering::Relaxed),
         }
     }
 
+    /// Track a new in-flight request whose method and path are already known.
+    #[must_use]
+    pub fn track_request_with(
+        &self,
+        method: Method,
+        path: impl Into<String>,
+        client: Option<String>,
+    ) -> InFlightGuard {
+        let guard = self.track_request();
+        guard.describe(method, path, client);
+        guard
+    }
+
     /// Get the current in-flight request count.
     #[must_use]
     pub fn in_flight_count(&self) -> usize {
         self.state.in_flight_count()
     }
+
+    /// Snapshot the described in-flight requests, oldest first.
+    ///
+    /// During a slow drain this shows exactly which requests the grace
+    /// period is waiting on. Requests tracked without a description are
+    /// counted by [`in_flight_count()`](Self::in_flight_count) but not listed.
+    #[must_use]
+    pub fn in_flight(&self) -> Vec<InFlightRequest> {
+        let mut requests: Vec<InFlightRequest> =
+            self.state.requests.lock().values().cloned().collect();
+        requests.sort_by_key(|r| (r.started, r.id));
+        requests
+    }
 }
 
 impl Default for ShutdownController {
@@ -405,15 +443,65 @@ impl Future for ShutdownWaitFuture<'_> {
 // In-Flight Guard
 // ============================================================================
 
+/// Snapshot of a request that is still being handled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InFlightRequest {
+    /// Tracking id, unique per controller.
+    pub id: u64,
+    /// HTTP method.
+    pub method: Method,
+    /// Request path.
+    pub path: String,
+    /// Client address, if known.
+    pub client: Option<String>,
+    /// When the request was described.
+    pub started: Instant,
+}
+
+impl InFlightRequest {
+    /// Time spent on this request so far.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
 /// RAII guard for tracking in-flight requests.
 ///
-/// Decrements the in-flight count when dropped.
+/// Decrements the in-flight count and forgets the request snapshot when
+/// dropped.
 pub struct InFlightGuard {
     state: Arc<ShutdownState>,
+    id: u64,
+}
+
+impl InFlightGuard {
+    /// Record the method, path and client of the tracked request.
+    ///
+    /// Calling this again replaces the previous description but keeps the
+    /// original start time, so keep-alive connections can re-describe the
+    /// guard for each request.
+    pub fn describe(&self, method: Method, path: impl Into<String>, client: Option<String>) {
+        let mut requests = self.state.requests.lock();
+        let started = requests
+            .get(&self.id)
+            .map_or_else(Instant::now, |r| r.started);
+        requests.insert(
+            self.id,
+            InFlightRequest {
+                id: self.id,
+                method,
+                path: path.into(),
+                client,
+                started,
+            },
+        );
+    }
 }
 
 impl Drop for InFlightGuard {
     fn drop(&mut self) {
+        self.state.requests.lock().remove(&self.id);
         self.state.decrement_in_flight();
     }
 }
@@ -753,6 +841,49 @@ mod tests {
         assert_eq!(controller.in_flight_count(), 0);
     }
 
+    #[test]
+    fn in_flight_snapshots() {
+        let controller = ShutdownController::new();
+
+        let undescribed = controller.track_request();
+        let first =
+            controller.track_request_with(Method::Get, "/slow", Some("10.0.0.1:5000".into()));
+        let second = controller.track_request();
+        second.describe(Method::Post, "/upload", None);
+
+        assert_eq!(controller.in_flight_count(), 3);
+        let snapshot = controller.in_flight();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].method, Method::Get);
+        assert_eq!(snapshot[0].path, "/slow");
+        assert_eq!(snapshot[0].client.as_deref(), Some("10.0.0.1:5000"));
+        assert_eq!(snapshot[1].path, "/upload");
+        assert!(snapshot[0].started <= snapshot[1].started);
+
+        drop(first);
+        let snapshot = controller.in_flight();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].path, "/upload");
+
+        drop(second);
+        drop(undescribed);
+        assert!(controller.in_flight().is_empty());
+        assert_eq!(controller.in_flight_count(), 0);
+    }
+
+    #[test]
+    fn describe_keeps_start_time() {
+        let controller = ShutdownController::new();
+        let guard = controller.track_request();
+        guard.describe(Method::Get, "/a", None);
+        let started = controller.in_flight()[0].started;
+        guard.describe(Method::Get, "/b", None);
+        let snapshot = controller.in_flight();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].path, "/b");
+        assert_eq!(snapshot[0].started, started);
+    }
+
     #[test]
     fn shutdown_hooks_lifo() {
         let controller = ShutdownController::new();
@@ -0,0 +1,297 @@
+//! Slow request detection.
+//!
+//! [`SlowRequestMiddleware`] reports requests that take longer than a
+//! threshold. Each report carries the matched route and, for handlers
+//! declared with the route attribute macros, how the time split between
+//! extracting arguments, running the handler and converting its return value
+//! into a response:
+//!
+//! ```ignore
+//! let registry = MetricsRegistry::new();
+//! let app = App::builder()
+//!     .middleware(SlowRequestMiddleware::new(Duration::from_millis(500)).metrics(&registry))
+//!     .build();
+//! ```
+//!
+//! Reports are logged at WARN level, and counted in
+//! `http_slow_requests_total{method,route}` when a [`MetricsRegistry`] is
+//! given. A [`reporter`](SlowRequestMiddleware::reporter) replaces the log
+//! line; with the `output` feature, `fastapi::slow_requests::dev_reporter`
+//! prints a highlighted console warning instead.
+//!
+//! Add the middleware first so the timing covers the rest of the stack.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::context::RequestContext;
+use crate::logging::{LogConfig, RequestLogger};
+use crate::metrics::{Counter, Family, MetricsRegistry};
+use crate::middleware::{BoxFuture, ControlFlow, Middleware};
+use crate::request::{Method, Request};
+use crate::response::Response;
+
+/// Where a macro-declared handler spent its time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandlerPhases {
+    /// Running the argument extractors.
+    pub extraction: Duration,
+    /// Running the handler body.
+    pub handler: Duration,
+    /// Converting the return value into a response.
+    pub serialization: Duration,
+}
+
+/// Marks a request whose handler phases should be recorded.
+#[derive(Debug, Clone, Copy)]
+struct TrackPhases {
+    start: Instant,
+}
+
+/// Records the phases of a macro-generated handler on requests that a
+/// [`SlowRequestMiddleware`] is watching.
+#[doc(hidden)]
+pub fn record_handler_phases(
+    req: &mut Request,
+    started: Instant,
+    extracted: Instant,
+    handled: Instant,
+) {
+    if req.get_extension::<TrackPhases>().is_some() {
+        req.insert_extension(HandlerPhases {
+            extraction: extracted.duration_since(started),
+            handler: handled.duration_since(extracted),
+            serialization: handled.elapsed(),
+        });
+    }
+}
+
+/// A request that exceeded the threshold.
+#[derive(Debug, Clone)]
+pub struct SlowRequest {
+    /// Request method.
+    pub method: Method,
+    /// Request path.
+    pub path: String,
+    /// Matched route pattern, if any.
+    pub route: Option<String>,
+    /// Response status code.
+    pub status: u16,
+    /// Time from the middleware seeing the request to the response.
+    pub elapsed: Duration,
+    /// The threshold that was exceeded.
+    pub threshold: Duration,
+    /// Breakdown of the handler's time, for macro-declared handlers.
+    pub phases: Option<HandlerPhases>,
+}
+
+type Reporter = Arc<dyn Fn(&SlowRequest) + Send + Sync>;
+
+/// Middleware reporting requests slower than a threshold.
+#[derive(Clone)]
+pub struct SlowRequestMiddleware {
+    threshold: Duration,
+    counter: Option<Family<Counter>>,
+    reporter: Option<Reporter>,
+    log_config: LogConfig,
+}
+
+impl SlowRequestMiddleware {
+    /// Report requests taking longer than `threshold`.
+    #[must_use]
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            counter: None,
+            reporter: None,
+            log_config: LogConfig::default(),
+        }
+    }
+
+    /// Count slow requests in `registry` as `http_slow_requests_total`.
+    #[must_use]
+    pub fn metrics(mut self, registry: &MetricsRegistry) -> Self {
+        self.counter = Some(registry.counter_family(
+            "http_slow_requests_total",
+            "HTTP requests slower than the slow-request threshold.",
+            &["method", "route"],
+        ));
+        self
+    }
+
+    /// Hand each slow request to `reporter` instead of the request logger.
+    #[must_use]
+    pub fn reporter(mut self, reporter: impl Fn(&SlowRequest) + Send + Sync + 'static) -> Self {
+        self.reporter = Some(Arc::new(reporter));
+        self
+    }
+
+    /// Logging configuration used when no reporter is set.
+    #[must_use]
+    pub fn log_config(mut self, config: LogConfig) -> Self {
+        self.log_config = config;
+        self
+    }
+
+    /// The threshold.
+    #[must_use]
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    fn report(&self, ctx: &RequestContext, slow: &SlowRequest) {
+        if let Some(counter) = &self.counter {
+            let route = slow.route.as_deref().unwrap_or("unmatched");
+            counter.with(&[slow.method.as_str(), route]).inc();
+        }
+        if let Some(reporter) = &self.reporter {
+            reporter(slow);
+            return;
+        }
+        RequestLogger::new(ctx, self.log_config.clone()).warn_with_fields(
+            "slow request",
+            |entry| {
+                let mut entry = entry
+                    .field("method", slow.method.as_str())
+                    .field("route", slow.route.as_deref().unwrap_or("unmatched"))
+                    .field("path", &slow.path)
+                    .field("status", slow.status)
+                    .field("elapsed_ms", millis(slow.elapsed))
+                    .field("threshold_ms", millis(slow.threshold));
+                if let Some(phases) = slow.phases {
+                    entry = entry
+                        .field("extraction_ms", millis(phases.extraction))
+                        .field("handler_ms", millis(phases.handler))
+                        .field("serialization_ms", millis(phases.serialization));
+                }
+                entry
+            },
+        );
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.0)
+}
+
+impl fmt::Debug for SlowRequestMiddleware {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlowRequestMiddleware")
+            .field("threshold", &self.threshold)
+            .field("metrics", &self.counter.is_some())
+            .field("reporter", &self.reporter.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Middleware for SlowRequestMiddleware {
+    fn before<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        req.insert_extension(TrackPhases {
+            start: Instant::now(),
+        });
+        Box::pin(async { ControlFlow::Continue })
+    }
+
+    fn after<'a>(
+        &'a self,
+        ctx: &'a RequestContext,
+        req: &'a Request,
+        response: Response,
+    ) -> BoxFuture<'a, Response> {
+        if let Some(track) = req.get_extension::<TrackPhases>() {
+            let elapsed = track.start.elapsed();
+            if elapsed > self.threshold {
+                self.report(
+                    ctx,
+                    &SlowRequest {
+                        method: req.method(),
+                        path: req.path().to_string(),
+                        route: ctx.log_scope().route(),
+                        status: response.status().as_u16(),
+                        elapsed,
+                        threshold: self.threshold,
+                        phases: req.get_extension::<HandlerPhases>().copied(),
+                    },
+                );
+            }
+        }
+        Box::pin(async move { response })
+    }
+
+    fn name(&self) -> &'static str {
+        "SlowRequest"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::StatusCode;
+    use parking_lot::Mutex;
+
+    fn run(mw: &SlowRequestMiddleware, handler_time: Duration) -> Vec<SlowRequest> {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let mw = mw
+            .clone()
+            .reporter(move |slow: &SlowRequest| sink.lock().push(slow.clone()));
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+        ctx.log_scope().set_route("/reports/{id}");
+        let mut req = Request::new(Method::Get, "/reports/7");
+        futures_executor::block_on(async {
+            assert!(mw.before(&ctx, &mut req).await.is_continue());
+            let started = Instant::now();
+            std::thread::sleep(handler_time);
+            record_handler_phases(&mut req, started, started, Instant::now());
+            mw.after(&ctx, &req, Response::with_status(StatusCode::OK))
+                .await
+        });
+        reports.lock().clone()
+    }
+
+    #[test]
+    fn fast_requests_are_not_reported() {
+        let mw = SlowRequestMiddleware::new(Duration::from_secs(60));
+        assert!(run(&mw, Duration::ZERO).is_empty());
+    }
+
+    #[test]
+    fn slow_requests_are_reported_with_phases() {
+        let mw = SlowRequestMiddleware::new(Duration::from_millis(5));
+        let reports = run(&mw, Duration::from_millis(20));
+        assert_eq!(reports.len(), 1);
+        let slow = &reports[0];
+        assert_eq!(slow.route.as_deref(), Some("/reports/{id}"));
+        assert_eq!(slow.path, "/reports/7");
+        assert_eq!(slow.status, 200);
+        assert!(slow.elapsed >= Duration::from_millis(20));
+        let phases = slow.phases.expect("phases recorded");
+        assert_eq!(phases.extraction, Duration::ZERO);
+        assert!(phases.handler >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn phases_are_only_recorded_when_tracked() {
+        let mut req = Request::new(Method::Get, "/");
+        let now = Instant::now();
+        record_handler_phases(&mut req, now, now, now);
+        assert!(req.get_extension::<HandlerPhases>().is_none());
+    }
+
+    #[test]
+    fn slow_requests_are_counted() {
+        let registry = MetricsRegistry::new();
+        let mw = SlowRequestMiddleware::new(Duration::ZERO).metrics(&registry);
+        run(&mw, Duration::from_millis(1));
+        assert!(
+            registry
+                .render()
+                .contains("http_slow_requests_total{method=\"GET\",route=\"/reports/{id}\"} 1")
+        );
+    }
+}
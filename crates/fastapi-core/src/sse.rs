@@ -46,22 +46,28 @@
 //! - `retry`: Optional reconnection time in milliseconds
 //! - `data`: The actual payload (required, can be multiple lines)
 //!
-//! # Keep-Alive
+//! # Keep-Alive and Reconnection
 //!
-//! Use [`SseEvent::comment()`] to send keep-alive comments that prevent
-//! connection timeouts without sending actual events.
+//! [`SseResponse`] sends a comment line whenever no event has been sent for
+//! [`SseConfig::keep_alive_secs`], so proxies don't close idle connections.
+//! [`SseConfig::retry`] tells browsers how long to wait before reconnecting,
+//! and [`SseResponse::resume`] hands the `Last-Event-ID` a reconnecting
+//! client sends to the code building the stream, so it can replay what the
+//! client missed.
 //!
 //! # Cancellation
 //!
 //! SSE streams integrate with asupersync's cancellation. When the client
 //! disconnects, the stream will be cancelled at the next checkpoint.
 
+use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::time::Duration;
 
 use asupersync::stream::Stream;
 
+use crate::request::Request;
 use crate::response::{Response, ResponseBody, StatusCode};
 
 /// A Server-Sent Event.
@@ -268,6 +274,20 @@ impl SseEvent {
     }
 }
 
+impl SseEvent {
+    /// A field-only event carrying just a `retry:` line.
+    fn retry_hint(retry: Duration) -> Self {
+        Self {
+            data: None,
+            event_type: None,
+            id: None,
+            retry: None,
+            comment: None,
+        }
+        .retry(retry)
+    }
+}
+
 impl From<&str> for SseEvent {
     fn from(data: &str) -> Self {
         Self::new(data)
@@ -285,12 +305,67 @@ impl From<String> for SseEvent {
 /// This stream produces `Vec<u8>` chunks suitable for sending over HTTP.
 pub struct SseStream<S> {
     inner: S,
+    prelude: Option<Vec<u8>>,
+    keep_alive: Option<KeepAlive>,
+}
+
+struct KeepAlive {
+    interval: Duration,
+    comment: Vec<u8>,
+    timer: Pin<Box<dyn Future<Output = ()> + Send>>,
+}
+
+impl KeepAlive {
+    fn reset(&mut self) {
+        self.timer = idle_timer(self.interval);
+    }
+}
+
+fn idle_timer(interval: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+    Box::pin(asupersync::time::sleep(
+        asupersync::time::wall_now(),
+        interval,
+    ))
 }
 
 impl<S> SseStream<S> {
     /// Create a new SSE stream wrapper.
     pub fn new(stream: S) -> Self {
-        Self { inner: stream }
+        Self {
+            inner: stream,
+            prelude: None,
+            keep_alive: None,
+        }
+    }
+
+    /// Create an SSE stream wrapper that applies `config`.
+    ///
+    /// The stream starts with the configured `retry:` hint, if any, and
+    /// sends a keep-alive comment whenever no event was sent for the
+    /// configured interval.
+    pub fn with_config(stream: S, config: &SseConfig) -> Self {
+        let mut sse = Self::new(stream);
+        if let Some(retry) = config.retry {
+            sse.prelude = Some(SseEvent::retry_hint(retry).to_bytes());
+        }
+        if config.keep_alive_secs > 0 {
+            sse = sse.keep_alive(
+                Duration::from_secs(config.keep_alive_secs),
+                &config.keep_alive_comment,
+            );
+        }
+        sse
+    }
+
+    /// Send `: <comment>` whenever `interval` passes without an event.
+    #[must_use]
+    pub fn keep_alive(mut self, interval: Duration, comment: &str) -> Self {
+        self.keep_alive = Some(KeepAlive {
+            interval,
+            comment: SseEvent::comment(comment).to_bytes(),
+            timer: idle_timer(interval),
+        });
+        self
     }
 }
 
@@ -301,10 +376,31 @@ where
     type Item = Vec<u8>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        match Pin::new(&mut self.inner).poll_next(cx) {
-            Poll::Ready(Some(event)) => Poll::Ready(Some(event.to_bytes())),
+        let this = &mut *self;
+        if let Some(prelude) = this.prelude.take() {
+            return Poll::Ready(Some(prelude));
+        }
+
+        match Pin::new(&mut this.inner).poll_next(cx) {
+            Poll::Ready(Some(event)) => {
+                if let Some(keep_alive) = &mut this.keep_alive {
+                    keep_alive.reset();
+                }
+                Poll::Ready(Some(event.to_bytes()))
+            }
             Poll::Ready(None) => Poll::Ready(None),
-            Poll::Pending => Poll::Pending,
+            Poll::Pending => {
+                let Some(keep_alive) = &mut this.keep_alive else {
+                    return Poll::Pending;
+                };
+                match keep_alive.timer.as_mut().poll(cx) {
+                    Poll::Ready(()) => {
+                        keep_alive.reset();
+                        Poll::Ready(Some(keep_alive.comment.clone()))
+                    }
+                    Poll::Pending => Poll::Pending,
+                }
+            }
         }
     }
 }
@@ -316,6 +412,9 @@ pub struct SseConfig {
     pub keep_alive_secs: u64,
     /// Comment to send for keep-alive.
     pub keep_alive_comment: String,
+    /// Reconnection delay sent to the client as a `retry:` line when the
+    /// stream starts (`None` leaves the browser default).
+    pub retry: Option<Duration>,
 }
 
 impl Default for SseConfig {
@@ -323,6 +422,7 @@ impl Default for SseConfig {
         Self {
             keep_alive_secs: 30,
             keep_alive_comment: "keep-alive".to_string(),
+            retry: None,
         }
     }
 }
@@ -354,6 +454,24 @@ impl SseConfig {
         self.keep_alive_comment = comment.into();
         self
     }
+
+    /// Set how long clients wait before reconnecting after the connection drops.
+    #[must_use]
+    pub fn retry(mut self, delay: Duration) -> Self {
+        self.retry = Some(delay);
+        self
+    }
+}
+
+/// Returns the `Last-Event-ID` header a reconnecting client sent, if any.
+///
+/// Browsers send the ID of the last event they received when they reconnect
+/// an `EventSource`. Empty or non-UTF-8 values are treated as absent.
+#[must_use]
+pub fn last_event_id(req: &Request) -> Option<String> {
+    let value = std::str::from_utf8(req.headers().get("last-event-id")?).ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
 }
 
 /// Builder for creating SSE responses.
@@ -376,7 +494,7 @@ impl SseConfig {
 /// ```
 pub struct SseResponse<S> {
     stream: S,
-    _config: SseConfig,
+    config: SseConfig,
 }
 
 impl<S> SseResponse<S>
@@ -387,16 +505,41 @@ where
     pub fn new(stream: S) -> Self {
         Self {
             stream,
-            _config: SseConfig::default(),
+            config: SseConfig::default(),
         }
     }
 
     /// Create an SSE response with custom configuration.
     pub fn with_config(stream: S, config: SseConfig) -> Self {
-        Self {
-            stream,
-            _config: config,
-        }
+        Self { stream, config }
+    }
+
+    /// Create an SSE response that resumes where a reconnecting client left off.
+    ///
+    /// `resume` receives the request's `Last-Event-ID` (see [`last_event_id`])
+    /// and builds the stream, typically replaying the events after that ID
+    /// before following live ones. Give events an [`SseEvent::id`] so the
+    /// browser has something to send back.
+    ///
+    /// ```ignore
+    /// let response = SseResponse::resume(req, |last_id| {
+    ///     let after = last_id.and_then(|id| id.parse().ok()).unwrap_or(0);
+    ///     feed.events_after(after)
+    /// })
+    /// .into_response();
+    /// ```
+    pub fn resume<F>(req: &Request, resume: F) -> Self
+    where
+        F: FnOnce(Option<String>) -> S,
+    {
+        Self::new(resume(last_event_id(req)))
+    }
+
+    /// Set the configuration.
+    #[must_use]
+    pub fn config(mut self, config: SseConfig) -> Self {
+        self.config = config;
+        self
     }
 
     /// Convert to an HTTP Response.
@@ -405,9 +548,12 @@ where
     /// - `Content-Type: text/event-stream`
     /// - `Cache-Control: no-cache`
     /// - `Connection: keep-alive`
+    ///
+    /// The body starts with the configured `retry:` hint and sends a
+    /// keep-alive comment after each idle interval.
     #[must_use]
     pub fn into_response(self) -> Response {
-        let sse_stream = SseStream::new(self.stream);
+        let sse_stream = SseStream::with_config(self.stream, &self.config);
 
         Response::with_status(StatusCode::OK)
             .header("content-type", b"text/event-stream".to_vec())
@@ -565,6 +711,85 @@ mod tests {
         assert!(output.contains("data: \n"));
     }
 
+    /// An event source with nothing to say.
+    struct Idle;
+
+    impl Stream for Idle {
+        type Item = SseEvent;
+
+        fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<SseEvent>> {
+            Poll::Pending
+        }
+    }
+
+    fn next_chunk<S: Stream<Item = Vec<u8>> + Unpin>(stream: &mut S) -> Poll<Option<String>> {
+        let waker = std::task::Waker::noop();
+        let mut cx = Context::from_waker(waker);
+        Pin::new(stream)
+            .poll_next(&mut cx)
+            .map(|chunk| chunk.map(|bytes| String::from_utf8(bytes).unwrap()))
+    }
+
+    #[test]
+    fn stream_starts_with_retry_hint() {
+        let config = SseConfig::new()
+            .disable_keep_alive()
+            .retry(Duration::from_secs(3));
+        let events = asupersync::stream::iter(vec![SseEvent::message("hi")]);
+        let mut sse = SseStream::with_config(events, &config);
+
+        assert_eq!(
+            next_chunk(&mut sse),
+            Poll::Ready(Some("retry: 3000\n\n".into()))
+        );
+        assert_eq!(
+            next_chunk(&mut sse),
+            Poll::Ready(Some("data: hi\n\n".into()))
+        );
+        assert_eq!(next_chunk(&mut sse), Poll::Ready(None));
+    }
+
+    #[test]
+    fn idle_stream_sends_keep_alive_comments() {
+        let mut sse = SseStream::new(Idle).keep_alive(Duration::ZERO, "ping");
+        assert_eq!(next_chunk(&mut sse), Poll::Ready(Some(": ping\n\n".into())));
+        assert_eq!(next_chunk(&mut sse), Poll::Ready(Some(": ping\n\n".into())));
+
+        let mut quiet = SseStream::new(Idle);
+        assert_eq!(next_chunk(&mut quiet), Poll::Pending);
+    }
+
+    #[test]
+    fn resume_receives_last_event_id() {
+        let mut req = Request::new(crate::request::Method::Get, "/events");
+        assert_eq!(last_event_id(&req), None);
+        req.headers_mut().insert("Last-Event-ID", b" 41 ".to_vec());
+
+        let mut seen = None;
+        let _ = SseResponse::resume(&req, |last_id| {
+            seen = last_id;
+            asupersync::stream::iter(Vec::<SseEvent>::new())
+        });
+        assert_eq!(seen.as_deref(), Some("41"));
+
+        req.headers_mut().insert("last-event-id", Vec::new());
+        assert_eq!(last_event_id(&req), None);
+    }
+
+    #[test]
+    fn response_uses_config() {
+        let resp = SseResponse::new(asupersync::stream::iter(vec![SseEvent::message("x")]))
+            .config(SseConfig::new().retry(Duration::from_millis(500)))
+            .into_response();
+        let ResponseBody::Stream(mut body) = resp.into_parts().2 else {
+            panic!("expected a streaming body");
+        };
+        assert_eq!(
+            next_chunk(&mut body),
+            Poll::Ready(Some("retry: 500\n\n".into()))
+        );
+    }
+
     #[test]
     fn retry_from_duration() {
         let event = SseEvent::new("data").retry(Duration::from_secs(10));
@@ -9,6 +9,7 @@
 //! - Index file support (index.html by default)
 //! - Content-Type detection from file extension
 //! - ETag generation for caching
+//! - Precompressed `.br` / `.gz` siblings, negotiated via `Accept-Encoding`
 //! - Last-Modified headers
 //! - Optional directory listing
 //! - Symlink handling (configurable)
@@ -51,6 +52,8 @@
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
+use crate::embed::{accepts_encoding, gzip_variant, is_compressible, variant_etag};
+use crate::request::Request;
 use crate::response::{Response, ResponseBody, StatusCode, mime_type_for_extension};
 
 /// Configuration for static file serving.
@@ -77,6 +80,9 @@ pub struct StaticFilesConfig {
     pub not_found_page: Option<String>,
     /// Additional headers to add to all responses.
     pub extra_headers: Vec<(String, String)>,
+    /// Whether [`StaticFiles::serve_request`] may send `.br` / `.gz`
+    /// siblings of a file, or compress it on the fly.
+    pub precompressed: bool,
 }
 
 impl Default for StaticFilesConfig {
@@ -92,6 +98,7 @@ impl Default for StaticFilesConfig {
             directory_listing: false,
             not_found_page: None,
             extra_headers: Vec::new(),
+            precompressed: true,
         }
     }
 }
@@ -168,6 +175,13 @@ impl StaticFilesConfig {
         self.extra_headers.push((name.into(), value.into()));
         self
     }
+
+    /// Enable or disable serving compressed variants of files.
+    #[must_use]
+    pub fn precompressed(mut self, enable: bool) -> Self {
+        self.precompressed = enable;
+        self
+    }
 }
 
 /// Static file server.
@@ -241,6 +255,22 @@ impl StaticFiles {
     ///
     /// A response containing the file contents, or an error response (404, 403, etc.)
     pub fn serve(&self, request_path: &str) -> Response {
+        self.serve_path(request_path, None)
+    }
+
+    /// Serve `req`, negotiating the content encoding.
+    ///
+    /// Like [`Self::serve`], but when the client's `Accept-Encoding` allows
+    /// it, sends the smallest of the file's `.br` / `.gz` siblings (e.g.
+    /// `app.js.br` for `app.js`). Without an acceptable sibling, compressible
+    /// files are gzipped on the fly when the `compression` feature is
+    /// enabled. Encoded responses get `Content-Encoding`, an ETag specific to
+    /// the encoding, and `Vary: Accept-Encoding`.
+    pub fn serve_request(&self, req: &Request) -> Response {
+        self.serve_path(req.path(), Some(req))
+    }
+
+    fn serve_path(&self, request_path: &str, req: Option<&Request>) -> Response {
         // Strip prefix from path
         let path_without_prefix = self.strip_prefix(request_path);
 
@@ -282,11 +312,11 @@ impl StaticFiles {
 
         // Check if it's a directory
         if canonical_path.is_dir() {
-            return self.serve_directory(&canonical_path, request_path);
+            return self.serve_directory(&canonical_path, request_path, req);
         }
 
         // Serve the file
-        self.serve_file(&canonical_path)
+        self.serve_file(&canonical_path, req)
     }
 
     /// Strip the URL prefix from the request path.
@@ -330,7 +360,12 @@ impl StaticFiles {
     }
 
     /// Serve a directory (index file or listing).
-    fn serve_directory(&self, dir_path: &Path, request_path: &str) -> Response {
+    fn serve_directory(
+        &self,
+        dir_path: &Path,
+        request_path: &str,
+        req: Option<&Request>,
+    ) -> Response {
         // Try index files
         for index_file in &self.config.index_files {
             // Security: validate index file path to prevent traversal
@@ -344,7 +379,7 @@ impl StaticFiles {
                     continue;
                 }
                 if canonical.is_file() {
-                    return self.serve_file(&canonical);
+                    return self.serve_file(&canonical, req);
                 }
             }
         }
@@ -359,7 +394,7 @@ impl StaticFiles {
     }
 
     /// Serve a single file.
-    fn serve_file(&self, file_path: &Path) -> Response {
+    fn serve_file(&self, file_path: &Path, req: Option<&Request>) -> Response {
         // Check for hidden files in any path component (not just the leaf)
         if !self.config.show_hidden && has_hidden_component(file_path) {
             return self.not_found_response();
@@ -384,12 +419,33 @@ impl StaticFiles {
             .header("content-type", content_type.as_bytes().to_vec())
             .header("accept-ranges", b"bytes".to_vec());
 
+        let encoded = match req {
+            Some(req) if self.config.precompressed => {
+                self.negotiate_encoding(file_path, &contents, content_type, req)
+            }
+            _ => EncodedBody::default(),
+        };
+
         // Add ETag if enabled
         if self.config.enable_etag {
-            let etag = generate_etag(&contents);
+            let mut etag = generate_etag(&contents);
+            if let Some(coding) = encoded.coding {
+                etag = variant_etag(&etag, coding);
+            }
             response = response.header("etag", etag.into_bytes());
         }
 
+        if encoded.vary {
+            response = response.header("vary", b"accept-encoding".to_vec());
+        }
+        let contents = match (encoded.coding, encoded.bytes) {
+            (Some(coding), Some(bytes)) => {
+                response = response.header("content-encoding", coding.as_bytes().to_vec());
+                bytes
+            }
+            _ => contents,
+        };
+
         // Add Last-Modified if enabled
         if self.config.enable_last_modified {
             if let Some(ref meta) = metadata {
@@ -408,6 +464,59 @@ impl StaticFiles {
         response.body(ResponseBody::Bytes(contents))
     }
 
+    /// Pick the smallest encoding of `contents` that `req` accepts.
+    ///
+    /// Precompressed siblings are preferred; gzip is computed on the fly
+    /// only when no acceptable sibling exists.
+    fn negotiate_encoding(
+        &self,
+        file_path: &Path,
+        contents: &[u8],
+        content_type: &str,
+        req: &Request,
+    ) -> EncodedBody {
+        let mut vary = cfg!(feature = "compression") && is_compressible(content_type);
+        let mut best: Option<(&'static str, Vec<u8>)> = None;
+
+        for (coding, ext) in [("br", "br"), ("gzip", "gz")] {
+            let Some(bytes) = self.read_sibling(file_path, ext) else {
+                continue;
+            };
+            vary = true;
+            let smaller = bytes.len() < best.as_ref().map_or(contents.len(), |(_, b)| b.len());
+            if smaller && accepts_encoding(req, coding) {
+                best = Some((coding, bytes));
+            }
+        }
+        if best.is_none() && accepts_encoding(req, "gzip") {
+            best = gzip_variant(contents, content_type).map(|bytes| ("gzip", bytes));
+        }
+
+        EncodedBody {
+            coding: best.as_ref().map(|(coding, _)| *coding),
+            bytes: best.map(|(_, bytes)| bytes),
+            vary,
+        }
+    }
+
+    /// Read the `.{ext}` sibling of a file, honouring the symlink policy.
+    fn read_sibling(&self, file_path: &Path, ext: &str) -> Option<Vec<u8>> {
+        let mut sibling = file_path.as_os_str().to_owned();
+        sibling.push(".");
+        sibling.push(ext);
+        let sibling = PathBuf::from(sibling);
+
+        let metadata = if self.config.follow_symlinks {
+            std::fs::metadata(&sibling).ok()?
+        } else {
+            std::fs::symlink_metadata(&sibling).ok()?
+        };
+        if !metadata.is_file() {
+            return None;
+        }
+        std::fs::read(&sibling).ok()
+    }
+
     /// Generate a directory listing HTML page.
     fn generate_directory_listing(&self, dir_path: &Path, request_path: &str) -> Response {
         let mut entries = Vec::new();
@@ -491,6 +600,17 @@ impl StaticFiles {
     }
 }
 
+/// Result of content-encoding negotiation for one file.
+#[derive(Debug, Default)]
+struct EncodedBody {
+    /// Chosen coding (`br`, `gzip`), or `None` for the file as stored.
+    coding: Option<&'static str>,
+    /// Encoded bytes when `coding` is set.
+    bytes: Option<Vec<u8>>,
+    /// Whether the response depends on `Accept-Encoding`.
+    vary: bool,
+}
+
 /// Check if any component of a path starts with `.` (hidden file/directory).
 fn has_hidden_component(path: &Path) -> bool {
     path.components().any(|c| {
@@ -897,6 +1017,51 @@ mod tests {
         assert!(handler.config.directory_listing);
     }
 
+    #[test]
+    fn precompressed_sibling_is_negotiated() {
+        use crate::request::Method;
+
+        let dir = std::env::temp_dir().join(format!("fastapi_static_br_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("app.js"),
+            b"console.log('a reasonably long script');",
+        )
+        .unwrap();
+        std::fs::write(dir.join("app.js.br"), b"br!").unwrap();
+        std::fs::write(dir.join("app.js.gz"), b"gzipped").unwrap();
+
+        let files = StaticFiles::new(&dir).prefix("/static");
+        let header = |response: &Response, name: &str| {
+            response
+                .headers()
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| String::from_utf8_lossy(v).into_owned())
+        };
+
+        let mut req = Request::new(Method::Get, "/static/app.js");
+        req.headers_mut()
+            .insert("accept-encoding", b"gzip, br".to_vec());
+        let br = files.serve_request(&req);
+        assert_eq!(header(&br, "content-encoding").as_deref(), Some("br"));
+        assert_eq!(br.body_ref().len(), 3);
+        assert!(header(&br, "etag").unwrap().ends_with("-br\""));
+
+        let mut req = Request::new(Method::Get, "/static/app.js");
+        req.headers_mut()
+            .insert("accept-encoding", b"gzip".to_vec());
+        let gz = files.serve_request(&req);
+        assert_eq!(header(&gz, "content-encoding").as_deref(), Some("gzip"));
+        assert_eq!(header(&gz, "vary").as_deref(), Some("accept-encoding"));
+
+        let plain = files.serve("/static/app.js");
+        assert!(header(&plain, "content-encoding").is_none());
+        assert!(!header(&plain, "etag").unwrap().contains('-'));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn leap_year_detection() {
         assert!(!is_leap_year(1900)); // Divisible by 100 but not 400
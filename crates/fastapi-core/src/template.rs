@@ -0,0 +1,701 @@
+//! Server-side HTML templates.
+//!
+//! [`Templates`] is a small built-in engine with Jinja-like syntax. Output is
+//! HTML-escaped unless marked `safe`:
+//!
+//! | Syntax | Meaning |
+//! |--------|---------|
+//! | `{{ user.name }}` | Value at a dotted path, escaped |
+//! | `{{ banner \| safe }}` | Value inserted as-is |
+//! | `{% if x %}…{% else %}…{% endif %}` | Conditional; `{% if not x %}` negates |
+//! | `{% for item in items %}…{% endfor %}` | Loop over an array, with `loop.index`, `loop.index0`, `loop.first`, `loop.last` |
+//! | `{% include "nav.html" %}` | Another registered template, same context |
+//! | `{# … #}` | Comment |
+//!
+//! `false`, `null`, `0`, `""`, `[]`, `{}` and missing values are false in
+//! conditions. Printing a missing value is an error rather than an empty
+//! string, so typos surface in tests.
+//!
+//! Context structs implement [`Template`] to name their template and render
+//! to an [`Html`] response:
+//!
+//! ```ignore
+//! #[derive(Serialize)]
+//! struct IndexPage { user: String, items: Vec<String> }
+//!
+//! impl Template for IndexPage {
+//!     const NAME: &'static str = "index.html";
+//! }
+//!
+//! #[get("/")]
+//! async fn index(State(templates): State<Templates>) -> Result<Html, TemplateError> {
+//!     IndexPage { user: "ann".into(), items: vec![] }.render(&templates)
+//! }
+//!
+//! let templates = Templates::from_dir("templates")?;
+//! let app = App::builder().state(templates).route_entry(index_route()).build();
+//! ```
+//!
+//! To use another engine, implement [`TemplateEngine`] for it; [`Template`]
+//! renders through any engine.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::HttpError;
+use crate::response::{Html, IntoResponse, Response, StatusCode, escape_html};
+
+/// Maximum nesting of `{% include %}`.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Error parsing or rendering a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    /// Name of the template.
+    pub template: String,
+    /// 1-based line of the offending tag, or 0 if unknown.
+    pub line: usize,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl TemplateError {
+    /// An error in `template` without a line number.
+    #[must_use]
+    pub fn new(template: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            line: 0,
+            message: message.into(),
+        }
+    }
+
+    fn at(template: &str, line: usize, message: impl Into<String>) -> Self {
+        Self {
+            template: template.to_string(),
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "template `{}`: {}", self.template, self.message)
+        } else {
+            write!(
+                f,
+                "template `{}` line {}: {}",
+                self.template, self.line, self.message
+            )
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl IntoResponse for TemplateError {
+    fn into_response(self) -> Response {
+        HttpError::new(StatusCode::INTERNAL_SERVER_ERROR)
+            .with_detail("Template rendering failed")
+            .into_response()
+    }
+}
+
+/// A template engine that renders named templates with a JSON context.
+///
+/// Implement this to plug in an external engine:
+///
+/// ```ignore
+/// struct Jinja(minijinja::Environment<'static>);
+///
+/// impl TemplateEngine for Jinja {
+///     fn render(&self, name: &str, context: &serde_json::Value) -> Result<String, TemplateError> {
+///         let template = self.0.get_template(name).map_err(|e| TemplateError::new(name, e.to_string()))?;
+///         template.render(context).map_err(|e| TemplateError::new(name, e.to_string()))
+///     }
+/// }
+/// ```
+pub trait TemplateEngine: Send + Sync {
+    /// Render the template `name` with `context`.
+    fn render(&self, name: &str, context: &Value) -> Result<String, TemplateError>;
+}
+
+/// A context struct rendered by a named template.
+pub trait Template: Serialize {
+    /// Name the template is registered under.
+    const NAME: &'static str;
+
+    /// Render `self` with `engine`.
+    fn render<E: TemplateEngine + ?Sized>(&self, engine: &E) -> Result<Html, TemplateError> {
+        render_html(engine, Self::NAME, self)
+    }
+}
+
+fn render_html<E, C>(engine: &E, name: &str, context: &C) -> Result<Html, TemplateError>
+where
+    E: TemplateEngine + ?Sized,
+    C: Serialize + ?Sized,
+{
+    let context = serde_json::to_value(context)
+        .map_err(|err| TemplateError::new(name, format!("context is not serializable: {err}")))?;
+    engine.render(name, &context).map(Html::new)
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Var {
+        path: Vec<String>,
+        safe: bool,
+        line: usize,
+    },
+    If {
+        path: Vec<String>,
+        negate: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    For {
+        var: String,
+        path: Vec<String>,
+        body: Vec<Node>,
+        line: usize,
+    },
+    Include {
+        name: String,
+        line: usize,
+    },
+}
+
+enum Token<'s> {
+    Text(&'s str),
+    Expr(&'s str, usize),
+    Tag(&'s str, usize),
+}
+
+fn tokenize<'s>(name: &str, source: &'s str) -> Result<Vec<Token<'s>>, TemplateError> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    let mut offset = 0;
+    loop {
+        let bytes = rest.as_bytes();
+        let open = (0..bytes.len().saturating_sub(1))
+            .find(|&i| bytes[i] == b'{' && matches!(bytes[i + 1], b'{' | b'%' | b'#'));
+        let Some(open) = open else {
+            if !rest.is_empty() {
+                tokens.push(Token::Text(rest));
+            }
+            return Ok(tokens);
+        };
+        if open > 0 {
+            tokens.push(Token::Text(&rest[..open]));
+        }
+        let line = source[..offset + open].matches('\n').count() + 1;
+        let kind = &rest[open..open + 2];
+        let close = match kind {
+            "{{" => "}}",
+            "{%" => "%}",
+            _ => "#}",
+        };
+        let Some(len) = rest[open + 2..].find(close) else {
+            return Err(TemplateError::at(name, line, format!("unclosed `{kind}`")));
+        };
+        let inner = rest[open + 2..open + 2 + len].trim();
+        match kind {
+            "{{" => tokens.push(Token::Expr(inner, line)),
+            "{%" => tokens.push(Token::Tag(inner, line)),
+            _ => {}
+        }
+        let consumed = open + 2 + len + 2;
+        offset += consumed;
+        rest = &rest[consumed..];
+    }
+}
+
+fn parse_path(name: &str, line: usize, text: &str) -> Result<Vec<String>, TemplateError> {
+    let path: Vec<String> = text.split('.').map(str::to_string).collect();
+    let valid = path.iter().all(|segment| {
+        !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    });
+    if valid {
+        Ok(path)
+    } else {
+        Err(TemplateError::at(
+            name,
+            line,
+            format!("invalid variable `{text}`"),
+        ))
+    }
+}
+
+/// Parse nodes until one of the `closers` tags, returning the closer found.
+fn parse_nodes<'s>(
+    name: &str,
+    tokens: &mut impl Iterator<Item = Token<'s>>,
+    closers: &[&str],
+    opened: Option<(&str, usize)>,
+) -> Result<(Vec<Node>, Option<&'s str>), TemplateError> {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Text(text) => nodes.push(Node::Text(text.to_string())),
+            Token::Expr(expr, line) => {
+                let mut parts = expr.split('|').map(str::trim);
+                let path = parse_path(name, line, parts.next().unwrap_or_default())?;
+                let mut safe = false;
+                for filter in parts {
+                    if filter != "safe" {
+                        return Err(TemplateError::at(
+                            name,
+                            line,
+                            format!("unknown filter `{filter}`"),
+                        ));
+                    }
+                    safe = true;
+                }
+                nodes.push(Node::Var { path, safe, line });
+            }
+            Token::Tag(tag, line) => {
+                let (keyword, args) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+                let args = args.trim();
+                if closers.contains(&keyword) {
+                    return Ok((nodes, Some(keyword)));
+                }
+                match keyword {
+                    "if" => {
+                        let (negate, cond) = match args.strip_prefix("not ") {
+                            Some(cond) => (true, cond.trim()),
+                            None => (false, args),
+                        };
+                        let path = parse_path(name, line, cond)?;
+                        let (then, closer) =
+                            parse_nodes(name, tokens, &["else", "endif"], Some(("if", line)))?;
+                        let otherwise = if closer == Some("else") {
+                            parse_nodes(name, tokens, &["endif"], Some(("if", line)))?.0
+                        } else {
+                            Vec::new()
+                        };
+                        nodes.push(Node::If {
+                            path,
+                            negate,
+                            then,
+                            otherwise,
+                        });
+                    }
+                    "for" => {
+                        let parts: Vec<&str> = args.split_whitespace().collect();
+                        let [var, "in", path] = parts.as_slice() else {
+                            return Err(TemplateError::at(
+                                name,
+                                line,
+                                "expected `{% for item in items %}`",
+                            ));
+                        };
+                        let var = parse_path(name, line, var)?.join(".");
+                        let path = parse_path(name, line, path)?;
+                        let (body, _) =
+                            parse_nodes(name, tokens, &["endfor"], Some(("for", line)))?;
+                        nodes.push(Node::For {
+                            var,
+                            path,
+                            body,
+                            line,
+                        });
+                    }
+                    "include" => {
+                        let target = args
+                            .strip_prefix('"')
+                            .and_then(|a| a.strip_suffix('"'))
+                            .or_else(|| args.strip_prefix('\'').and_then(|a| a.strip_suffix('\'')))
+                            .ok_or_else(|| {
+                                TemplateError::at(name, line, "expected a quoted template name")
+                            })?;
+                        nodes.push(Node::Include {
+                            name: target.to_string(),
+                            line,
+                        });
+                    }
+                    _ => {
+                        return Err(TemplateError::at(
+                            name,
+                            line,
+                            format!("unexpected `{{% {keyword} %}}`"),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    match opened {
+        Some((tag, line)) => Err(TemplateError::at(
+            name,
+            line,
+            format!("`{{% {tag} %}}` is never closed"),
+        )),
+        None => Ok((nodes, None)),
+    }
+}
+
+fn parse(name: &str, source: &str) -> Result<Vec<Node>, TemplateError> {
+    let mut tokens = tokenize(name, source)?.into_iter();
+    Ok(parse_nodes(name, &mut tokens, &[], None)?.0)
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+struct Renderer<'a> {
+    templates: &'a HashMap<String, Arc<Vec<Node>>>,
+    root: &'a Value,
+    scopes: Vec<(String, Value)>,
+    depth: usize,
+}
+
+impl Renderer<'_> {
+    fn lookup(&self, path: &[String]) -> Option<&Value> {
+        let (first, rest) = path.split_first()?;
+        let mut value = self
+            .scopes
+            .iter()
+            .rev()
+            .find(|(name, _)| name == first)
+            .map(|(_, value)| value)
+            .or_else(|| self.root.get(first))?;
+        for segment in rest {
+            value = match value {
+                Value::Object(map) => map.get(segment)?,
+                Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    fn render(
+        &mut self,
+        name: &str,
+        nodes: &[Node],
+        out: &mut String,
+    ) -> Result<(), TemplateError> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Var { path, safe, line } => {
+                    let Some(value) = self.lookup(path) else {
+                        return Err(TemplateError::at(
+                            name,
+                            *line,
+                            format!("undefined variable `{}`", path.join(".")),
+                        ));
+                    };
+                    let text = match value {
+                        Value::Null => String::new(),
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    if *safe {
+                        out.push_str(&text);
+                    } else {
+                        out.push_str(&escape_html(&text));
+                    }
+                }
+                Node::If {
+                    path,
+                    negate,
+                    then,
+                    otherwise,
+                } => {
+                    let branch = if truthy(self.lookup(path)) != *negate {
+                        then
+                    } else {
+                        otherwise
+                    };
+                    self.render(name, branch, out)?;
+                }
+                Node::For {
+                    var,
+                    path,
+                    body,
+                    line,
+                } => {
+                    let items = match self.lookup(path) {
+                        Some(Value::Array(items)) => items.clone(),
+                        _ => {
+                            return Err(TemplateError::at(
+                                name,
+                                *line,
+                                format!("`{}` is not an array", path.join(".")),
+                            ));
+                        }
+                    };
+                    let count = items.len();
+                    for (index, item) in items.into_iter().enumerate() {
+                        let info = serde_json::json!({
+                            "index": index + 1,
+                            "index0": index,
+                            "first": index == 0,
+                            "last": index + 1 == count,
+                        });
+                        self.scopes.push(("loop".to_string(), info));
+                        self.scopes.push((var.clone(), item));
+                        let result = self.render(name, body, out);
+                        self.scopes.truncate(self.scopes.len() - 2);
+                        result?;
+                    }
+                }
+                Node::Include { name: target, line } => {
+                    let Some(included) = self.templates.get(target) else {
+                        return Err(TemplateError::at(
+                            name,
+                            *line,
+                            format!("unknown template `{target}`"),
+                        ));
+                    };
+                    if self.depth >= MAX_INCLUDE_DEPTH {
+                        return Err(TemplateError::at(name, *line, "includes nested too deeply"));
+                    }
+                    let included = Arc::clone(included);
+                    self.depth += 1;
+                    let result = self.render(target, &included, out);
+                    self.depth -= 1;
+                    result?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => false,
+        Some(Value::Bool(b)) => *b,
+        Some(Value::Number(n)) => n.as_f64().is_some_and(|f| f.abs() > 0.0),
+        Some(Value::String(s)) => !s.is_empty(),
+        Some(Value::Array(items)) => !items.is_empty(),
+        Some(Value::Object(map)) => !map.is_empty(),
+    }
+}
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+/// A set of named templates for the built-in engine.
+///
+/// Templates are parsed when added, so syntax errors surface at startup.
+/// Clones share the parsed templates; register it as app state and extract
+/// it with `State<Templates>`.
+#[derive(Clone, Default)]
+pub struct Templates {
+    templates: Arc<HashMap<String, Arc<Vec<Node>>>>,
+}
+
+impl Templates {
+    /// An empty set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse `source` and register it as `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first syntax error in `source`.
+    pub fn add(mut self, name: impl Into<String>, source: &str) -> Result<Self, TemplateError> {
+        let name = name.into();
+        let nodes = parse(&name, source)?;
+        Arc::make_mut(&mut self.templates).insert(name, Arc::new(nodes));
+        Ok(self)
+    }
+
+    /// Register every file under `dir`, named by its path relative to `dir`
+    /// with `/` separators (`"index.html"`, `"partials/nav.html"`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read or fails to parse.
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self, TemplateError> {
+        fn walk(dir: &Path, prefix: &str, templates: &mut Templates) -> Result<(), TemplateError> {
+            let entries = std::fs::read_dir(dir)
+                .map_err(|err| TemplateError::new(dir.display().to_string(), err.to_string()))?;
+            for entry in entries {
+                let entry = entry.map_err(|err| {
+                    TemplateError::new(dir.display().to_string(), err.to_string())
+                })?;
+                let path = entry.path();
+                let name = format!("{prefix}{}", entry.file_name().to_string_lossy());
+                if path.is_dir() {
+                    walk(&path, &format!("{name}/"), templates)?;
+                } else {
+                    let source = std::fs::read_to_string(&path)
+                        .map_err(|err| TemplateError::new(&name, err.to_string()))?;
+                    *templates = std::mem::take(templates).add(name, &source)?;
+                }
+            }
+            Ok(())
+        }
+
+        let mut templates = Self::new();
+        walk(dir.as_ref(), "", &mut templates)?;
+        Ok(templates)
+    }
+
+    /// Whether a template named `name` is registered.
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.templates.contains_key(name)
+    }
+
+    /// Render the template `name` with `context` as an HTML response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the template is unknown or fails to render.
+    pub fn html<C: Serialize + ?Sized>(
+        &self,
+        name: &str,
+        context: &C,
+    ) -> Result<Html, TemplateError> {
+        render_html(self, name, context)
+    }
+}
+
+impl TemplateEngine for Templates {
+    fn render(&self, name: &str, context: &Value) -> Result<String, TemplateError> {
+        let Some(nodes) = self.templates.get(name) else {
+            return Err(TemplateError::new(name, "unknown template"));
+        };
+        let mut out = String::new();
+        Renderer {
+            templates: &self.templates,
+            root: context,
+            scopes: Vec::new(),
+            depth: 0,
+        }
+        .render(name, nodes, &mut out)?;
+        Ok(out)
+    }
+}
+
+impl fmt::Debug for Templates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&String> = self.templates.keys().collect();
+        names.sort();
+        f.debug_struct("Templates")
+            .field("templates", &names)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(templates: &Templates, name: &str, context: &Value) -> String {
+        TemplateEngine::render(templates, name, context).unwrap()
+    }
+
+    #[test]
+    fn escapes_values_unless_safe() {
+        let templates = Templates::new()
+            .add(
+                "t",
+                "<p>{{ user.name }}</p>{{ banner | safe }}{{ count }}{# note #}",
+            )
+            .unwrap();
+        let context = serde_json::json!({
+            "user": {"name": "<Ann & \"Bob\">"},
+            "banner": "<b>hi</b>",
+            "count": 3,
+        });
+        assert_eq!(
+            render(&templates, "t", &context),
+            "<p>&lt;Ann &amp; &quot;Bob&quot;&gt;</p><b>hi</b>3"
+        );
+    }
+
+    #[test]
+    fn renders_conditionals_loops_and_includes() {
+        let templates = Templates::new()
+            .add("item", "<li>{{ loop.index }}. {{ item }}</li>")
+            .unwrap()
+            .add(
+                "list",
+                "{% if items %}<ul>{% for item in items %}{% include \"item\" %}\
+                 {% if not loop.last %},{% endif %}{% endfor %}</ul>{% else %}empty{% endif %}",
+            )
+            .unwrap();
+        assert_eq!(
+            render(
+                &templates,
+                "list",
+                &serde_json::json!({"items": ["a", "<b>"]})
+            ),
+            "<ul><li>1. a</li>,<li>2. &lt;b&gt;</li></ul>"
+        );
+        assert_eq!(
+            render(&templates, "list", &serde_json::json!({"items": []})),
+            "empty"
+        );
+    }
+
+    #[test]
+    fn reports_errors_with_lines() {
+        let err = Templates::new()
+            .add("t", "line one\n{% if x %}\nnever closed")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "template `t` line 2: `{% if %}` is never closed"
+        );
+
+        let err = Templates::new().add("t", "{{ x | upper }}").unwrap_err();
+        assert_eq!(err.message, "unknown filter `upper`");
+
+        let templates = Templates::new().add("t", "\n{{ missing.name }}").unwrap();
+        let err = TemplateEngine::render(&templates, "t", &serde_json::json!({})).unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(err.message, "undefined variable `missing.name`");
+    }
+
+    #[test]
+    fn template_trait_renders_html() {
+        #[derive(Serialize)]
+        struct Greeting<'a> {
+            name: &'a str,
+        }
+
+        impl Template for Greeting<'_> {
+            const NAME: &'static str = "greeting.html";
+        }
+
+        let templates = Templates::new()
+            .add("greeting.html", "Hello, {{ name }}!")
+            .unwrap();
+        let html = Greeting { name: "<world>" }.render(&templates).unwrap();
+        assert_eq!(html.content(), "Hello, &lt;world&gt;!");
+
+        let response = Greeting { name: "x" }
+            .render(&Templates::new())
+            .unwrap_err()
+            .into_response();
+        assert_eq!(response.status().as_u16(), 500);
+    }
+}
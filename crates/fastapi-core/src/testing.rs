@@ -11,6 +11,7 @@
 //! - **Request builder**: Fluent API for headers, body, cookies
 //! - **Response assertions**: Convenient assertion helpers
 //! - **Cookie jar**: Automatic session management across requests
+//! - **WebSockets**: [`TestClient::websocket`] talks to WebSocket routes in-process
 //! - **Lab integration**: Deterministic testing with asupersync
 //!
 //! # Example
@@ -44,16 +45,22 @@
 
 use parking_lot::Mutex;
 use std::collections::HashMap;
-use std::future::Future;
+use std::future::{Future, poll_fn};
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 
 use asupersync::Cx;
+use asupersync::stream::Stream;
 
+use crate::app::App;
+use crate::clock::{Clock, VirtualClock};
 use crate::context::RequestContext;
-use crate::dependency::{DependencyOverrides, FromDependency};
+use crate::dependency::{DependencyOverrides, FromDependency, OverrideGuard};
 use crate::middleware::Handler;
 use crate::request::{Body, Method, Request};
 use crate::response::{Response, ResponseBody, StatusCode};
+use crate::websocket::{Frame, OpCode, WebSocket, WebSocketError};
 
 /// A simple cookie jar for maintaining cookies across requests.
 ///
@@ -388,7 +395,7 @@ fn parse_set_cookie_attrs<'a>(parts: impl Iterator<Item = &'a str>) -> SetCookie
                     };
                 }
                 "expires" => {
-                    if let Some(t) = parse_http_date(v) {
+                    if let Some(t) = crate::headers::parse_http_date(v) {
                         attrs.expires_at = Some(t);
                     }
                 }
@@ -554,66 +561,6 @@ fn path_matches(cookie_path: &str, request_path: &str) -> bool {
         .is_none_or(|&b| b == b'/')
 }
 
-fn parse_http_date(input: &str) -> Option<std::time::SystemTime> {
-    // Parse IMF-fixdate: "Wed, 21 Oct 2015 07:28:00 GMT"
-    // We intentionally keep this minimal; invalid dates are ignored per RFC6265.
-    let s = input.trim().trim_matches('"').trim();
-    let (_dow, rest) = s.split_once(',')?;
-    let rest = rest.trim();
-    let mut it = rest.split_whitespace();
-    let day = it.next()?.parse::<u32>().ok()?;
-    let month = match it.next()? {
-        "Jan" => 1,
-        "Feb" => 2,
-        "Mar" => 3,
-        "Apr" => 4,
-        "May" => 5,
-        "Jun" => 6,
-        "Jul" => 7,
-        "Aug" => 8,
-        "Sep" => 9,
-        "Oct" => 10,
-        "Nov" => 11,
-        "Dec" => 12,
-        _ => return None,
-    };
-    let year = it.next()?.parse::<i32>().ok()?;
-    let time = it.next()?;
-    let tz = it.next()?;
-    if tz != "GMT" {
-        return None;
-    }
-    let (hh, mm, ss) = {
-        let mut t = time.split(':');
-        let hh = t.next()?.parse::<u32>().ok()?;
-        let mm = t.next()?.parse::<u32>().ok()?;
-        let ss = t.next()?.parse::<u32>().ok()?;
-        (hh, mm, ss)
-    };
-
-    // Convert to unix timestamp using a small civil->days function.
-    fn days_from_civil(y: i32, m: u32, d: u32) -> i64 {
-        // Howard Hinnant's algorithm.
-        let y = i64::from(y) - i64::from(m <= 2);
-        let era = (if y >= 0 { y } else { y - 399 }) / 400;
-        let yoe = y - era * 400;
-        let m = i64::from(m);
-        let doy = (153 * (m + if m > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(d) - 1;
-        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
-        era * 146097 + doe - 719468
-    }
-
-    let days = days_from_civil(year, month, day);
-    let secs = days
-        .checked_mul(86_400)?
-        .checked_add(i64::from(hh) * 3600 + i64::from(mm) * 60 + i64::from(ss))?;
-    if secs < 0 {
-        return None;
-    }
-    let secs_u64 = u64::try_from(secs).ok()?;
-    Some(std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs_u64))
-}
-
 /// Test client for in-process HTTP testing.
 ///
 /// `TestClient` wraps a handler and provides an HTTP-like interface
@@ -653,6 +600,9 @@ pub struct TestClient<H> {
     dependency_overrides: Arc<DependencyOverrides>,
     seed: Option<u64>,
     request_id_counter: Arc<std::sync::atomic::AtomicU64>,
+    contract: Option<Arc<ContractChecker>>,
+    clock: Clock,
+    request_timeout: Option<Duration>,
 }
 
 impl<H: Handler + 'static> TestClient<H> {
@@ -664,15 +614,16 @@ impl<H: Handler + 'static> TestClient<H> {
     /// let client = TestClient::new(my_handler);
     /// ```
     pub fn new(handler: H) -> Self {
-        let dependency_overrides = handler
-            .dependency_overrides()
-            .unwrap_or_else(|| Arc::new(DependencyOverrides::new()));
+        let dependency_overrides = client_overrides(&handler);
         Self {
             handler: Arc::new(handler),
             cookies: Arc::new(Mutex::new(CookieJar::new())),
             dependency_overrides,
             seed: None,
             request_id_counter: Arc::new(std::sync::atomic::AtomicU64::new(1)),
+            contract: None,
+            clock: Clock::system(),
+            request_timeout: None,
         }
     }
 
@@ -687,15 +638,16 @@ impl<H: Handler + 'static> TestClient<H> {
     /// let client = TestClient::with_seed(my_handler, 42);
     /// ```
     pub fn with_seed(handler: H, seed: u64) -> Self {
-        let dependency_overrides = handler
-            .dependency_overrides()
-            .unwrap_or_else(|| Arc::new(DependencyOverrides::new()));
+        let dependency_overrides = client_overrides(&handler);
         Self {
             handler: Arc::new(handler),
             cookies: Arc::new(Mutex::new(CookieJar::new())),
             dependency_overrides,
             seed: Some(seed),
             request_id_counter: Arc::new(std::sync::atomic::AtomicU64::new(1)),
+            contract: None,
+            clock: Clock::system(),
+            request_timeout: None,
         }
     }
 
@@ -705,6 +657,53 @@ impl<H: Handler + 'static> TestClient<H> {
         self.seed
     }
 
+    /// Checks every response against `contract`, panicking when one
+    /// departs from the documented API. See [`ContractChecker`].
+    #[must_use]
+    pub fn with_contract(mut self, contract: ContractChecker) -> Self {
+        self.contract = Some(Arc::new(contract));
+        self
+    }
+
+    /// Runs requests against `clock` instead of the system clock.
+    ///
+    /// Request deadlines, [`TimeoutLayer`](crate::TimeoutLayer) and the
+    /// in-memory rate limiter then follow virtual time. A request that is
+    /// only waiting on a virtual timer jumps straight to it, so a handler
+    /// that never answers times out at once; between requests, time moves
+    /// only through [`VirtualClock::advance`].
+    ///
+    /// ```ignore
+    /// let time = VirtualClock::new();
+    /// let client = TestClient::new(app).with_virtual_time(time.clone());
+    /// client.get("/slow").send().assert_status(504);
+    /// ```
+    #[must_use]
+    pub fn with_virtual_time(mut self, clock: VirtualClock) -> Self {
+        self.clock = clock.into();
+        self
+    }
+
+    /// Gives every request a server deadline `timeout` after it starts,
+    /// as the server's request timeout would.
+    #[must_use]
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the virtual clock requests run against, if any.
+    #[must_use]
+    pub fn virtual_time(&self) -> Option<&VirtualClock> {
+        self.clock.virtual_time()
+    }
+
+    /// Returns the handler this client sends requests to.
+    #[must_use]
+    pub fn handler(&self) -> &Arc<H> {
+        &self.handler
+    }
+
     /// Returns a reference to the cookie jar.
     ///
     /// Note: The jar is protected by a mutex, so concurrent access
@@ -784,7 +783,43 @@ impl<H: Handler + 'static> TestClient<H> {
         self.dependency_overrides.insert_value(value);
     }
 
-    /// Clear all registered dependency overrides.
+    /// Override `T` until the returned guard is dropped, then restore the
+    /// client's previous override of `T`.
+    pub fn scoped_override<T, F, Fut>(&self, f: F) -> OverrideGuard<'_>
+    where
+        T: FromDependency,
+        F: Fn(&RequestContext, &mut Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, T::Error>> + Send + 'static,
+    {
+        self.dependency_overrides.scoped::<T, F, Fut>(f)
+    }
+
+    /// Override `T` with a fixed value until the returned guard is dropped.
+    ///
+    /// ```ignore
+    /// let _mail = client.scoped_override_value(Mailer::recording());
+    /// client.post("/signup").json(&form).send().assert_status(201);
+    /// ```
+    pub fn scoped_override_value<T>(&self, value: T) -> OverrideGuard<'_>
+    where
+        T: FromDependency,
+    {
+        self.dependency_overrides.scoped_value(value)
+    }
+
+    /// Run `body` with `T` overridden by `value`, then restore the previous
+    /// override, even if `body` panics.
+    pub fn with_override<T, R>(&self, value: T, body: impl FnOnce() -> R) -> R
+    where
+        T: FromDependency,
+    {
+        let _guard = self.scoped_override_value(value);
+        body()
+    }
+
+    /// Clear the dependency overrides registered through this client.
+    ///
+    /// Overrides registered on the app itself stay in effect.
     pub fn clear_dependency_overrides(&self) {
         self.dependency_overrides.clear();
     }
@@ -799,6 +834,34 @@ impl<H: Handler + 'static> TestClient<H> {
     ///
     /// This is called internally by `RequestBuilder::send()`.
     fn execute(&self, mut request: Request) -> TestResponse {
+        let (ctx, request_id) = self.prepare(&mut request);
+
+        // The TestClient API is synchronous; run the async handler to completion.
+        let call = self.handler.call(&ctx, &mut request);
+        let response = match self.clock.virtual_time() {
+            Some(clock) => clock.block_on(call),
+            None => futures_executor::block_on(call),
+        };
+
+        // Extract cookies from response
+        {
+            let mut jar = self.cookies();
+            for (name, value) in response.headers() {
+                if name.eq_ignore_ascii_case("set-cookie") {
+                    jar.parse_set_cookie(&request, value);
+                }
+            }
+        }
+
+        let response = TestResponse::new(response, request_id);
+        if let Some(contract) = &self.contract {
+            contract.assert_conforms(request.method().as_str(), request.path(), &response);
+        }
+        response
+    }
+
+    /// Fills in the host and cookies, and creates the request's context.
+    fn prepare(&self, request: &mut Request) -> (RequestContext, u64) {
         // Many features (cookies, redirects, absolute URL building) require a host.
         // In tests, default to a stable host if one wasn't provided.
         if !request.headers().contains("host") {
@@ -808,7 +871,7 @@ impl<H: Handler + 'static> TestClient<H> {
         // Add cookies from jar to request
         {
             let jar = self.cookies();
-            if let Some(cookie_header) = jar.cookie_header_for_request(&request) {
+            if let Some(cookie_header) = jar.cookie_header_for_request(request) {
                 request
                     .headers_mut()
                     .insert("cookie", cookie_header.into_bytes());
@@ -818,23 +881,88 @@ impl<H: Handler + 'static> TestClient<H> {
         // Create test context with Cx::for_testing()
         let cx = Cx::for_testing();
         let request_id = self.next_request_id();
-        let ctx =
-            RequestContext::with_overrides(cx, request_id, Arc::clone(&self.dependency_overrides));
+        let mut ctx =
+            RequestContext::with_overrides(cx, request_id, Arc::clone(&self.dependency_overrides))
+                .with_clock(self.clock.clone());
+        if let Some(timeout) = self.request_timeout {
+            let left = u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX);
+            let deadline = asupersync::Time::from_nanos(ctx.now().as_nanos().saturating_add(left));
+            ctx = ctx.with_deadline(deadline);
+        }
+        (ctx, request_id)
+    }
+}
 
-        // The TestClient API is synchronous; run the async handler to completion.
-        let response = futures_executor::block_on(self.handler.call(&ctx, &mut request));
+impl TestClient<App> {
+    /// Checks every response against the app's own OpenAPI document.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the app serves no OpenAPI document; see
+    /// [`ContractChecker::from_app`].
+    #[must_use]
+    pub fn check_contract(self) -> Self {
+        let contract = ContractChecker::from_app(&self.handler);
+        self.with_contract(contract)
+    }
 
-        // Extract cookies from response
-        {
-            let mut jar = self.cookies();
-            for (name, value) in response.headers() {
-                if name.eq_ignore_ascii_case("set-cookie") {
-                    jar.parse_set_cookie(&request, value);
-                }
+    /// Opens a WebSocket connection to a route registered with
+    /// [`AppBuilder::websocket`](crate::app::AppBuilder::websocket).
+    ///
+    /// The handler runs in-process and makes progress whenever the returned
+    /// socket sends or waits for a frame. Use
+    /// `client.get(path).header(..).websocket()` to send extra handshake
+    /// headers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no WebSocket route matches `path`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut ws = client.websocket("/ws/echo");
+    /// ws.send_text("hello");
+    /// assert_eq!(ws.receive_text(), "hello");
+    /// ws.close(1000, None);
+    /// ws.assert_close_code(1000);
+    /// ```
+    pub fn websocket(&self, path: &str) -> TestWebSocket {
+        self.get(path).websocket()
+    }
+
+    fn connect_websocket(&self, mut request: Request) -> TestWebSocket {
+        for (name, value) in [
+            ("upgrade", "websocket"),
+            ("connection", "Upgrade"),
+            ("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ=="),
+            ("sec-websocket-version", "13"),
+        ] {
+            if !request.headers().contains(name) {
+                request
+                    .headers_mut()
+                    .insert(name, value.as_bytes().to_vec());
             }
         }
+        assert!(
+            self.handler.has_websocket_route(request.path()),
+            "no websocket route matches {}",
+            request.path()
+        );
 
-        TestResponse::new(response, request_id)
+        let (ctx, _) = self.prepare(&mut request);
+        let (server, client) = crate::websocket::memory_pair();
+        let app = Arc::clone(&self.handler);
+        let handler: HandlerFuture = Box::pin(async move {
+            app.handle_websocket(&ctx, &mut request, WebSocket::in_memory(server))
+                .await
+        });
+        TestWebSocket {
+            conn: client,
+            rx: Vec::new(),
+            handler: Some(handler),
+            outcome: None,
+        }
     }
 }
 
@@ -846,10 +974,22 @@ impl<H> Clone for TestClient<H> {
             dependency_overrides: Arc::clone(&self.dependency_overrides),
             seed: self.seed,
             request_id_counter: Arc::clone(&self.request_id_counter),
+            contract: self.contract.clone(),
+            clock: self.clock.clone(),
+            request_timeout: self.request_timeout,
         }
     }
 }
 
+/// A client's own override registry, layered over the handler's (the app's)
+/// so client overrides stay out of other clients of the same app.
+fn client_overrides<H: Handler>(handler: &H) -> Arc<DependencyOverrides> {
+    match handler.dependency_overrides() {
+        Some(app) => Arc::new(DependencyOverrides::layered(app)),
+        None => Arc::new(DependencyOverrides::new()),
+    }
+}
+
 /// Builder for constructing test requests with a fluent API.
 ///
 /// Use the methods on [`TestClient`] to create a request builder,
@@ -1001,6 +1141,23 @@ impl<'a, H: Handler + 'static> RequestBuilder<'a, H> {
     }
 }
 
+impl RequestBuilder<'_, App> {
+    /// Opens a WebSocket connection with this request as the handshake.
+    ///
+    /// See [`TestClient::websocket`].
+    #[must_use]
+    pub fn websocket(self) -> TestWebSocket {
+        let mut request = Request::new(Method::Get, self.path);
+        request.set_query(self.query);
+
+        for (name, value) in self.headers {
+            request.headers_mut().insert(name, value);
+        }
+
+        self.client.connect_websocket(request)
+    }
+}
+
 /// Response from a test request with assertion helpers.
 ///
 /// `TestResponse` wraps a [`Response`] and provides convenient methods
@@ -1114,7 +1271,7 @@ impl TestResponse {
             ResponseBody::Empty => &[],
             ResponseBody::Bytes(b) => b,
             ResponseBody::Stream(_) => {
-                panic!("streaming response body not supported in TestResponse")
+                panic!("streaming response body: read it with chunks() or sse_events()")
             }
         }
     }
@@ -1165,6 +1322,55 @@ impl TestResponse {
         self.inner
     }
 
+    /// Reads the body chunk by chunk as the handler produces it.
+    ///
+    /// Each chunk records when it arrived, so tests can assert on pacing. A
+    /// buffered body is a single chunk.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut chunks = client.get("/export").send().chunks();
+    /// assert_eq!(chunks.next().unwrap().text(), "id,name\n");
+    /// for chunk in chunks {
+    ///     chunk.assert_delay_at_most(Duration::from_millis(100));
+    /// }
+    /// ```
+    #[must_use]
+    pub fn chunks(self) -> TestBodyChunks {
+        let (_, _, body) = self.inner.into_parts();
+        let now = std::time::Instant::now();
+        TestBodyChunks {
+            body: Some(body),
+            started: now,
+            previous: now,
+        }
+    }
+
+    /// Reads the body as a stream of Server-Sent Events.
+    ///
+    /// Events are parsed as a browser would: `data` lines are joined with
+    /// newlines, comment lines (such as keep-alives) are skipped, and a
+    /// trailing event without its blank line is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut events = client.get("/feed").send().sse_events();
+    /// let first = events.next().unwrap();
+    /// assert_eq!(first.event.as_deref(), Some("update"));
+    /// assert_eq!(first.data, "42");
+    /// ```
+    #[must_use]
+    pub fn sse_events(self) -> TestSseEvents {
+        TestSseEvents {
+            chunks: self.chunks(),
+            buf: Vec::new(),
+            pending: TestSseEvent::default(),
+            has_fields: false,
+        }
+    }
+
     // =========================================================================
     // Assertion Helpers
     // =========================================================================
@@ -1437,115 +1643,556 @@ impl TestResponse {
 }
 
 // =============================================================================
-// Partial JSON Matching
+// Streaming Responses
 // =============================================================================
 
-/// Checks if `actual` contains all fields from `expected`.
-///
-/// Returns `Ok(())` if matching succeeds, or `Err(path)` where `path` is
-/// the JSON path to the first mismatch.
-///
-/// # Matching Rules
-///
-/// - **Objects**: All keys in `expected` must exist in `actual` with matching values.
-///   Extra keys in `actual` are ignored.
-/// - **Arrays**: Must match exactly (same length, same elements in order).
-/// - **Primitives**: Must be equal.
-///
-/// # Example
-///
-/// ```
-/// use fastapi_core::testing::json_contains;
-/// use serde_json::json;
-///
-/// // Partial match succeeds - actual has extra "email" field
-/// let actual = json!({"id": 1, "name": "Alice", "email": "alice@example.com"});
-/// let expected = json!({"name": "Alice"});
-/// assert!(json_contains(&actual, &expected).is_ok());
+/// A chunk of a streamed response body, read by [`TestResponse::chunks`].
+#[derive(Debug, Clone)]
+pub struct TestChunk {
+    /// The chunk's bytes.
+    pub data: Vec<u8>,
+    /// Time from the start of reading to this chunk.
+    pub since_start: Duration,
+    /// Time from the previous chunk (or the start of reading) to this chunk.
+    pub delay: Duration,
+}
+
+impl TestChunk {
+    /// Returns the chunk as a UTF-8 string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the chunk is not valid UTF-8.
+    #[must_use]
+    pub fn text(&self) -> &str {
+        std::str::from_utf8(&self.data).expect("response chunk is not valid UTF-8")
+    }
+
+    /// Asserts that the chunk arrived at least `min` after the previous one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it arrived sooner.
+    pub fn assert_delay_at_least(&self, min: Duration) -> &Self {
+        assert!(
+            self.delay >= min,
+            "Expected chunk to arrive at least {min:?} after the previous one, got {:?}",
+            self.delay
+        );
+        self
+    }
+
+    /// Asserts that the chunk arrived at most `max` after the previous one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it arrived later.
+    pub fn assert_delay_at_most(&self, max: Duration) -> &Self {
+        assert!(
+            self.delay <= max,
+            "Expected chunk to arrive at most {max:?} after the previous one, got {:?}",
+            self.delay
+        );
+        self
+    }
+}
+
+/// Iterator over the chunks of a response body; see [`TestResponse::chunks`].
 ///
-/// // Mismatch fails
-/// let expected = json!({"name": "Bob"});
-/// assert!(json_contains(&actual, &expected).is_err());
-/// ```
-pub fn json_contains(
-    actual: &serde_json::Value,
-    expected: &serde_json::Value,
-) -> Result<(), String> {
-    json_contains_at_path(actual, expected, "$")
+/// Each call to `next` runs the body's stream until it yields a chunk, so
+/// the handler's producer runs in step with the test.
+pub struct TestBodyChunks {
+    body: Option<ResponseBody>,
+    started: std::time::Instant,
+    previous: std::time::Instant,
 }
 
-fn json_contains_at_path(
-    actual: &serde_json::Value,
-    expected: &serde_json::Value,
-    path: &str,
-) -> Result<(), String> {
-    use serde_json::Value;
+impl TestBodyChunks {
+    /// Reads the rest of the body into one buffer.
+    #[must_use]
+    pub fn remaining_bytes(self) -> Vec<u8> {
+        self.flat_map(|chunk| chunk.data).collect()
+    }
 
-    match (actual, expected) {
-        // For objects, check that all expected keys exist with matching values
-        (Value::Object(actual_obj), Value::Object(expected_obj)) => {
-            for (key, expected_val) in expected_obj {
-                let child_path = format!("{path}.{key}");
-                match actual_obj.get(key) {
-                    Some(actual_val) => {
-                        json_contains_at_path(actual_val, expected_val, &child_path)?;
-                    }
-                    None => {
-                        return Err(child_path);
-                    }
-                }
-            }
-            Ok(())
-        }
-        // For arrays, require exact match (partial array matching is ambiguous)
-        (Value::Array(actual_arr), Value::Array(expected_arr)) => {
-            if actual_arr.len() != expected_arr.len() {
-                return Err(format!("{path}[length]"));
-            }
-            for (i, (actual_elem, expected_elem)) in
-                actual_arr.iter().zip(expected_arr.iter()).enumerate()
-            {
-                let child_path = format!("{path}[{i}]");
-                json_contains_at_path(actual_elem, expected_elem, &child_path)?;
-            }
-            Ok(())
-        }
-        // For primitives, require exact match
-        _ => {
-            if actual == expected {
-                Ok(())
-            } else {
-                Err(path.to_string())
+    fn next_data(&mut self) -> Option<Vec<u8>> {
+        match self.body.take()? {
+            ResponseBody::Empty => None,
+            ResponseBody::Bytes(bytes) => (!bytes.is_empty()).then_some(bytes),
+            ResponseBody::Stream(mut stream) => {
+                let chunk =
+                    futures_executor::block_on(poll_fn(|cx| stream.as_mut().poll_next(cx)))?;
+                self.body = Some(ResponseBody::Stream(stream));
+                Some(chunk)
             }
         }
     }
 }
 
-// =============================================================================
-// Helper Traits for Assertion Macros
-// =============================================================================
+impl Iterator for TestBodyChunks {
+    type Item = TestChunk;
 
-/// Helper trait to convert various types to u16 for status code comparison.
-///
-/// This enables the `assert_status!` macro to accept both `u16` literals
-/// and `StatusCode` values.
-pub trait IntoStatusU16 {
-    fn into_status_u16(self) -> u16;
+    fn next(&mut self) -> Option<TestChunk> {
+        let data = self.next_data()?;
+        let now = std::time::Instant::now();
+        let chunk = TestChunk {
+            data,
+            since_start: now - self.started,
+            delay: now - self.previous,
+        };
+        self.previous = now;
+        Some(chunk)
+    }
 }
 
-impl IntoStatusU16 for u16 {
-    fn into_status_u16(self) -> u16 {
-        self
+impl std::fmt::Debug for TestBodyChunks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestBodyChunks")
+            .field("finished", &self.body.is_none())
+            .finish_non_exhaustive()
     }
 }
 
-impl IntoStatusU16 for StatusCode {
-    fn into_status_u16(self) -> u16 {
-        self.as_u16()
-    }
+/// A Server-Sent Event read by [`TestResponse::sse_events`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestSseEvent {
+    /// The `event:` field, if sent.
+    pub event: Option<String>,
+    /// The `data:` lines, joined with `\n`.
+    pub data: String,
+    /// The `id:` field, if sent.
+    pub id: Option<String>,
+    /// The `retry:` field in milliseconds, if sent.
+    pub retry: Option<u64>,
+    /// Time from the start of reading to the chunk that completed the event.
+    pub since_start: Duration,
 }
 
-// Also implement for i32 since integer literals without suffix default to i32
+impl TestSseEvent {
+    /// Parses the event's data as JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a valid `T`.
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_str(&self.data)
+    }
+}
+
+/// Iterator over the events of an SSE response; see [`TestResponse::sse_events`].
+#[derive(Debug)]
+pub struct TestSseEvents {
+    chunks: TestBodyChunks,
+    buf: Vec<u8>,
+    pending: TestSseEvent,
+    has_fields: bool,
+}
+
+impl TestSseEvents {
+    /// Applies one line of the stream, returning an event on a blank line.
+    fn apply_line(&mut self, line: &str) -> Option<TestSseEvent> {
+        if line.is_empty() {
+            if !std::mem::take(&mut self.has_fields) {
+                return None;
+            }
+            let mut event = std::mem::take(&mut self.pending);
+            if event.data.ends_with('\n') {
+                event.data.pop();
+            }
+            return Some(event);
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "data" => {
+                self.pending.data.push_str(value);
+                self.pending.data.push('\n');
+            }
+            "event" => self.pending.event = Some(value.to_string()),
+            "id" if !value.contains('\0') => self.pending.id = Some(value.to_string()),
+            "retry" => match value.parse() {
+                Ok(ms) => self.pending.retry = Some(ms),
+                Err(_) => return None,
+            },
+            _ => return None,
+        }
+        self.has_fields = true;
+        None
+    }
+}
+
+impl Iterator for TestSseEvents {
+    type Item = TestSseEvent;
+
+    fn next(&mut self) -> Option<TestSseEvent> {
+        loop {
+            while let Some(end) = self.buf.iter().position(|&b| b == b'\n') {
+                let raw: Vec<u8> = self.buf.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&raw[..end]);
+                let line = line.strip_suffix('\r').unwrap_or(&line).to_string();
+                if let Some(event) = self.apply_line(&line) {
+                    return Some(event);
+                }
+            }
+            let chunk = self.chunks.next()?;
+            self.pending.since_start = chunk.since_start;
+            self.buf.extend_from_slice(&chunk.data);
+        }
+    }
+}
+
+// =============================================================================
+// WebSocket Testing
+// =============================================================================
+
+/// A WebSocket handler running on the [`TestWebSocket`]'s thread.
+type HandlerFuture = Pin<Box<dyn Future<Output = Result<(), WebSocketError>>>>;
+
+/// Masking key for frames sent by [`TestWebSocket`] (the one from RFC 6455).
+const TEST_WS_MASK: [u8; 4] = [0x37, 0xfa, 0x21, 0x3d];
+
+/// The client side of an in-process WebSocket connection.
+///
+/// Created by [`TestClient::websocket`]. Frames go straight to the app's
+/// WebSocket handler without a network; the handler runs whenever this side
+/// sends or waits for a frame.
+///
+/// `send_frame` and `receive_frame` report errors; the other methods panic
+/// with a descriptive message instead, like [`TestResponse`]'s assertions.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut ws = client.websocket("/ws/chat");
+/// ws.send_json(&json!({"join": "lobby"}));
+/// let welcome: Welcome = ws.receive_json();
+/// ws.close(1000, Some("bye"));
+/// ws.assert_close_code(1000);
+/// ws.finish().unwrap();
+/// ```
+pub struct TestWebSocket {
+    conn: crate::websocket::MemoryStream,
+    rx: Vec<u8>,
+    handler: Option<HandlerFuture>,
+    outcome: Option<Result<(), WebSocketError>>,
+}
+
+impl TestWebSocket {
+    /// Sends a frame to the handler.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handler already closed the connection.
+    pub fn send_frame(&mut self, frame: &Frame) -> Result<(), WebSocketError> {
+        let wire = crate::websocket::encode_frame(frame, Some(TEST_WS_MASK))?;
+        self.conn.write(&wire)?;
+        // Let the handler react before the test continues.
+        futures_executor::block_on(poll_fn(|cx| {
+            self.poll_handler(cx);
+            Poll::Ready(())
+        }));
+        Ok(())
+    }
+
+    /// Sends a text message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handler already closed the connection.
+    pub fn send_text(&mut self, text: &str) {
+        self.send(OpCode::Text, text.as_bytes().to_vec());
+    }
+
+    /// Sends a binary message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handler already closed the connection.
+    pub fn send_bytes(&mut self, data: &[u8]) {
+        self.send(OpCode::Binary, data.to_vec());
+    }
+
+    /// Sends `value` as a JSON text message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if serialization fails or the handler already closed the connection.
+    pub fn send_json<T: serde::Serialize>(&mut self, value: &T) {
+        let text = serde_json::to_string(value).expect("JSON serialization failed");
+        self.send_text(&text);
+    }
+
+    /// Starts the closing handshake with `code` and an optional reason.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handler already closed the connection.
+    pub fn close(&mut self, code: u16, reason: Option<&str>) {
+        let mut payload = code.to_be_bytes().to_vec();
+        payload.extend_from_slice(reason.unwrap_or_default().as_bytes());
+        self.send(OpCode::Close, payload);
+    }
+
+    fn send(&mut self, opcode: OpCode, payload: Vec<u8>) {
+        let frame = Frame {
+            fin: true,
+            opcode,
+            payload,
+        };
+        if let Err(err) = self.send_frame(&frame) {
+            panic!("failed to send websocket {opcode:?} frame: {err}");
+        }
+    }
+
+    /// Waits for the next frame from the handler.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handler closed the connection without sending
+    /// another frame, or sent a malformed one.
+    pub fn receive_frame(&mut self) -> Result<Frame, WebSocketError> {
+        futures_executor::block_on(poll_fn(|cx| -> Poll<Result<Frame, WebSocketError>> {
+            loop {
+                if let Some((frame, used)) = crate::websocket::parse_server_frame(&self.rx)? {
+                    self.rx.drain(..used);
+                    return Poll::Ready(Ok(frame));
+                }
+                self.poll_handler(cx);
+                let mut chunk = [0u8; 8192];
+                match self.conn.poll_read(cx, &mut chunk) {
+                    Poll::Ready(0) => {
+                        return Poll::Ready(Err(WebSocketError::Protocol("unexpected EOF")));
+                    }
+                    Poll::Ready(n) => self.rx.extend_from_slice(&chunk[..n]),
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+        }))
+    }
+
+    /// Waits for a text message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the next frame is not a complete text message.
+    pub fn receive_text(&mut self) -> String {
+        let frame = self.expect_frame(OpCode::Text);
+        String::from_utf8(frame.payload).expect("websocket text frame is not valid UTF-8")
+    }
+
+    /// Waits for a binary message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the next frame is not a complete binary message.
+    pub fn receive_bytes(&mut self) -> Vec<u8> {
+        self.expect_frame(OpCode::Binary).payload
+    }
+
+    /// Waits for a text message and parses it as JSON.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the next frame is not a text message holding a valid `T`.
+    pub fn receive_json<T: serde::de::DeserializeOwned>(&mut self) -> T {
+        let text = self.receive_text();
+        serde_json::from_str(&text).unwrap_or_else(|err| {
+            panic!("websocket message is not the expected JSON: {err}: {text}")
+        })
+    }
+
+    /// Waits for a close frame and returns its code and reason.
+    ///
+    /// The code is `None` if the close frame had no payload.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the next frame is not a close frame.
+    pub fn receive_close(&mut self) -> (Option<u16>, String) {
+        let payload = self.expect_frame(OpCode::Close).payload;
+        match payload.as_slice() {
+            [hi, lo, reason @ ..] => (
+                Some(u16::from_be_bytes([*hi, *lo])),
+                String::from_utf8_lossy(reason).into_owned(),
+            ),
+            _ => (None, String::new()),
+        }
+    }
+
+    /// Asserts that the next frame closes the connection with `expected`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the next frame is not a close frame with that code.
+    pub fn assert_close_code(&mut self, expected: u16) -> &mut Self {
+        let (code, reason) = self.receive_close();
+        assert_eq!(
+            code,
+            Some(expected),
+            "Expected websocket close code {expected}, got {code:?} (reason {reason:?})"
+        );
+        self
+    }
+
+    /// Runs the handler to completion and returns its result.
+    ///
+    /// Close the connection first if the handler is still reading frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the handler returned.
+    pub fn finish(mut self) -> Result<(), WebSocketError> {
+        futures_executor::block_on(poll_fn(|cx| {
+            self.poll_handler(cx);
+            match self.outcome.take() {
+                Some(outcome) => Poll::Ready(outcome),
+                None => Poll::Pending,
+            }
+        }))
+    }
+
+    fn expect_frame(&mut self, opcode: OpCode) -> Frame {
+        match self.receive_frame() {
+            Ok(frame) if frame.opcode == opcode && frame.fin => frame,
+            Ok(frame) => panic!(
+                "Expected a websocket {opcode:?} frame, got {:?} (fin: {})",
+                frame.opcode, frame.fin
+            ),
+            Err(err) => panic!("Expected a websocket {opcode:?} frame, got error: {err}"),
+        }
+    }
+
+    fn poll_handler(&mut self, cx: &mut Context<'_>) {
+        if let Some(handler) = &mut self.handler {
+            if let Poll::Ready(outcome) = handler.as_mut().poll(cx) {
+                self.handler = None;
+                self.outcome = Some(outcome);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for TestWebSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestWebSocket")
+            .field("handler_finished", &self.handler.is_none())
+            .finish_non_exhaustive()
+    }
+}
+
+// =============================================================================
+// Partial JSON Matching
+// =============================================================================
+
+/// Checks if `actual` contains all fields from `expected`.
+///
+/// Returns `Ok(())` if matching succeeds, or `Err(path)` where `path` is
+/// the JSON path to the first mismatch.
+///
+/// # Matching Rules
+///
+/// - **Objects**: All keys in `expected` must exist in `actual` with matching values.
+///   Extra keys in `actual` are ignored.
+/// - **Arrays**: Must match exactly (same length, same elements in order).
+/// - **Primitives**: Must be equal.
+///
+/// # Example
+///
+/// ```
+/// use fastapi_core::testing::json_contains;
+/// use serde_json::json;
+///
+/// // Partial match succeeds - actual has extra "email" field
+/// let actual = json!({"id": 1, "name": "Alice", "email": "alice@example.com"});
+/// let expected = json!({"name": "Alice"});
+/// assert!(json_contains(&actual, &expected).is_ok());
+///
+/// // Mismatch fails
+/// let expected = json!({"name": "Bob"});
+/// assert!(json_contains(&actual, &expected).is_err());
+/// ```
+pub fn json_contains(
+    actual: &serde_json::Value,
+    expected: &serde_json::Value,
+) -> Result<(), String> {
+    json_contains_at_path(actual, expected, "$")
+}
+
+fn json_contains_at_path(
+    actual: &serde_json::Value,
+    expected: &serde_json::Value,
+    path: &str,
+) -> Result<(), String> {
+    use serde_json::Value;
+
+    match (actual, expected) {
+        // For objects, check that all expected keys exist with matching values
+        (Value::Object(actual_obj), Value::Object(expected_obj)) => {
+            for (key, expected_val) in expected_obj {
+                let child_path = format!("{path}.{key}");
+                match actual_obj.get(key) {
+                    Some(actual_val) => {
+                        json_contains_at_path(actual_val, expected_val, &child_path)?;
+                    }
+                    None => {
+                        return Err(child_path);
+                    }
+                }
+            }
+            Ok(())
+        }
+        // For arrays, require exact match (partial array matching is ambiguous)
+        (Value::Array(actual_arr), Value::Array(expected_arr)) => {
+            if actual_arr.len() != expected_arr.len() {
+                return Err(format!("{path}[length]"));
+            }
+            for (i, (actual_elem, expected_elem)) in
+                actual_arr.iter().zip(expected_arr.iter()).enumerate()
+            {
+                let child_path = format!("{path}[{i}]");
+                json_contains_at_path(actual_elem, expected_elem, &child_path)?;
+            }
+            Ok(())
+        }
+        // For primitives, require exact match
+        _ => {
+            if actual == expected {
+                Ok(())
+            } else {
+                Err(path.to_string())
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Helper Traits for Assertion Macros
+// =============================================================================
+
+/// Helper trait to convert various types to u16 for status code comparison.
+///
+/// This enables the `assert_status!` macro to accept both `u16` literals
+/// and `StatusCode` values.
+pub trait IntoStatusU16 {
+    fn into_status_u16(self) -> u16;
+}
+
+impl IntoStatusU16 for u16 {
+    fn into_status_u16(self) -> u16 {
+        self
+    }
+}
+
+impl IntoStatusU16 for StatusCode {
+    fn into_status_u16(self) -> u16 {
+        self.as_u16()
+    }
+}
+
+// Also implement for i32 since integer literals without suffix default to i32
 impl IntoStatusU16 for i32 {
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     fn into_status_u16(self) -> u16 {
@@ -2326,6 +2973,24 @@ mod tests {
         }
     }
 
+    /// `OverrideDepHandler` with app-level overrides, like an `App`.
+    struct AppOverridesHandler(Arc<DependencyOverrides>);
+
+    impl Handler for AppOverridesHandler {
+        fn call<'a>(
+            &'a self,
+            ctx: &'a RequestContext,
+            req: &'a mut Request,
+        ) -> BoxFuture<'a, Response> {
+            let handler: &'static OverrideDepHandler = &OverrideDepHandler;
+            handler.call(ctx, req)
+        }
+
+        fn dependency_overrides(&self) -> Option<Arc<DependencyOverrides>> {
+            Some(Arc::clone(&self.0))
+        }
+    }
+
     fn override_dep_route(ctx: &RequestContext, req: &mut Request) -> std::future::Ready<Response> {
         let dep = futures_executor::block_on(Depends::<OverrideDep>::from_request(ctx, req))
             .expect("dependency extraction failed");
@@ -2386,8 +3051,219 @@ mod tests {
     }
 
     #[test]
-    fn test_client_all_methods() {
-        let client = TestClient::new(EchoHandler);
+    fn test_client_overrides_are_isolated_and_scoped() {
+        let app_overrides = Arc::new(DependencyOverrides::new());
+        app_overrides.insert_value(OverrideDep { value: 5 });
+        let a = TestClient::new(AppOverridesHandler(Arc::clone(&app_overrides)));
+        let b = TestClient::new(AppOverridesHandler(Arc::clone(&app_overrides)));
+
+        a.override_dependency_value(OverrideDep { value: 7 });
+        assert_eq!(a.get("/").send().text(), "7");
+        assert_eq!(b.get("/").send().text(), "5");
+
+        {
+            let _guard = a.scoped_override_value(OverrideDep { value: 8 });
+            assert_eq!(a.get("/").send().text(), "8");
+        }
+        assert_eq!(a.get("/").send().text(), "7");
+
+        let text = a.with_override(OverrideDep { value: 9 }, || {
+            a.get("/").send().text().to_string()
+        });
+        assert_eq!(text, "9");
+        assert_eq!(a.get("/").send().text(), "7");
+
+        a.clear_dependency_overrides();
+        assert_eq!(a.get("/").send().text(), "5");
+        assert_eq!(app_overrides.len(), 1);
+    }
+
+    #[test]
+    fn virtual_time_drives_timeouts_and_rate_limits() {
+        use crate::middleware::{PathKeyExtractor, RateLimitAlgorithm, RateLimitBuilder};
+
+        let app = App::builder()
+            .middleware(
+                RateLimitBuilder::new()
+                    .requests(2)
+                    .per_minute(1)
+                    .algorithm(RateLimitAlgorithm::FixedWindow)
+                    .key_extractor(PathKeyExtractor)
+                    .build(),
+            )
+            .middleware(crate::TimeoutLayer::new(Duration::from_secs(30)))
+            .get("/ok", |_ctx: &RequestContext, _req: &mut Request| {
+                std::future::ready(Response::ok())
+            })
+            .get("/hang", |_ctx: &RequestContext, _req: &mut Request| {
+                std::future::pending::<Response>()
+            })
+            .build();
+        let time = VirtualClock::new();
+        let client = TestClient::new(app).with_virtual_time(time.clone());
+
+        // The timeout fires on virtual time, without waiting 30 real seconds.
+        client.get("/hang").send().assert_status_code(504);
+        assert_eq!(time.elapsed(), Duration::from_secs(30));
+
+        client.get("/ok").send().assert_status_code(200);
+        client.get("/ok").send().assert_status_code(200);
+        client.get("/ok").send().assert_status_code(429);
+        time.advance(Duration::from_secs(60));
+        client.get("/ok").send().assert_status_code(200);
+    }
+
+    #[test]
+    fn request_timeout_sets_a_virtual_deadline() {
+        let time = VirtualClock::new();
+        let client = TestClient::new(|ctx: &RequestContext, _req: &mut Request| {
+            let text = format!("{:?}", ctx.deadline().map(asupersync::Time::as_nanos));
+            std::future::ready(Response::ok().body(ResponseBody::Bytes(text.into_bytes())))
+        })
+        .with_virtual_time(time.clone())
+        .with_request_timeout(Duration::from_secs(5));
+
+        time.advance(Duration::from_secs(1));
+        assert_eq!(client.get("/").send().text(), "Some(6000000000)");
+    }
+
+    /// Yields `parts` one per poll, sleeping `gap` before each.
+    struct PacedStream {
+        parts: std::collections::VecDeque<&'static str>,
+        gap: Duration,
+    }
+
+    impl Stream for PacedStream {
+        type Item = Vec<u8>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Vec<u8>>> {
+            std::thread::sleep(self.gap);
+            Poll::Ready(self.parts.pop_front().map(|p| p.as_bytes().to_vec()))
+        }
+    }
+
+    fn paced(parts: &[&'static str], gap: Duration) -> Response {
+        Response::ok().body(ResponseBody::stream(PacedStream {
+            parts: parts.iter().copied().collect(),
+            gap,
+        }))
+    }
+
+    #[test]
+    fn test_response_chunks_are_read_incrementally() {
+        let client = TestClient::new(|_ctx: &RequestContext, _req: &mut Request| {
+            std::future::ready(paced(&["a", "b", "c"], Duration::from_millis(15)))
+        });
+        let mut chunks = client.get("/").send().chunks();
+
+        let first = chunks.next().unwrap();
+        assert_eq!(first.text(), "a");
+        first.assert_delay_at_least(Duration::from_millis(15));
+        let second = chunks.next().unwrap();
+        second.assert_delay_at_least(Duration::from_millis(15));
+        assert!(second.since_start >= first.since_start + second.delay);
+        assert_eq!(chunks.remaining_bytes(), b"c");
+
+        let client = TestClient::new(|_ctx: &RequestContext, _req: &mut Request| {
+            std::future::ready(Response::ok().body(ResponseBody::Bytes(b"whole".to_vec())))
+        });
+        let chunks: Vec<_> = client.get("/").send().chunks().collect();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].text(), "whole");
+    }
+
+    #[test]
+    fn test_response_sse_events_are_parsed() {
+        let client = TestClient::new(|_ctx: &RequestContext, _req: &mut Request| {
+            // Events split across chunks, a comment, CRLF lines and a retry hint.
+            std::future::ready(paced(
+                &[
+                    "retry: 2000\n\n: keep-alive\n\nevent: update\nid: 7\nda",
+                    "ta: {\"n\": 1}\r\ndata: more\r\n\r\n",
+                    "data: tail-without-blank-line\n",
+                ],
+                Duration::ZERO,
+            ))
+        });
+        let events: Vec<_> = client.get("/").send().sse_events().collect();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].retry, Some(2000));
+        assert_eq!(events[1].event.as_deref(), Some("update"));
+        assert_eq!(events[1].id.as_deref(), Some("7"));
+        assert_eq!(events[1].data, "{\"n\": 1}\nmore");
+        assert!(events[1].json::<serde_json::Value>().is_err());
+    }
+
+    fn websocket_app() -> App {
+        App::builder()
+            .websocket(
+                "/ws/echo",
+                |_ctx: &RequestContext, _req: &mut Request, mut ws: WebSocket| async move {
+                    while let Some(text) = ws.read_text_or_close().await? {
+                        ws.send_text(&text).await?;
+                    }
+                    Ok(())
+                },
+            )
+            .websocket(
+                "/ws/hello",
+                |_ctx: &RequestContext, req: &mut Request, mut ws: WebSocket| {
+                    let user = req
+                        .headers()
+                        .get("x-user")
+                        .map(|v| String::from_utf8_lossy(v).into_owned());
+                    async move {
+                        match user {
+                            Some(user) => ws.send_text(&format!("hello {user}")).await,
+                            None => ws.close(4003, Some("forbidden")).await,
+                        }
+                    }
+                },
+            )
+            .build()
+    }
+
+    #[test]
+    fn test_websocket_round_trip_and_close() {
+        let client = TestClient::new(websocket_app());
+        let mut ws = client.websocket("/ws/echo");
+
+        ws.send_text("ping");
+        assert_eq!(ws.receive_text(), "ping");
+        ws.send_json(&serde_json::json!({"n": 1}));
+        assert_eq!(
+            ws.receive_json::<serde_json::Value>(),
+            serde_json::json!({"n": 1})
+        );
+
+        ws.close(1000, Some("done"));
+        assert_eq!(ws.receive_close(), (Some(1000), "done".to_string()));
+        assert!(ws.finish().is_ok());
+    }
+
+    #[test]
+    fn test_websocket_server_close_code_and_handshake_headers() {
+        let client = TestClient::new(websocket_app());
+
+        let mut ws = client.websocket("/ws/hello");
+        ws.assert_close_code(4003);
+        assert!(ws.receive_frame().is_err(), "handler is gone");
+
+        let mut ws = client.get("/ws/hello").header("x-user", "ada").websocket();
+        assert_eq!(ws.receive_text(), "hello ada");
+        assert!(ws.finish().is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "no websocket route matches /nope")]
+    fn test_websocket_unknown_route_panics() {
+        let _ = TestClient::new(websocket_app()).websocket("/nope");
+    }
+
+    #[test]
+    fn test_client_all_methods() {
+        let client = TestClient::new(EchoHandler);
 
         assert!(client.get("/").send().text().contains("Get"));
         assert!(client.post("/").send().text().contains("Post"));
@@ -4411,9 +5287,9 @@ impl TestServer {
             }
 
             match listener.accept() {
-                Ok((stream, _peer)) => {
+                Ok((stream, peer)) => {
                     // Track in-flight requests
-                    let _guard = controller.track_request();
+                    let guard = controller.track_request();
 
                     // macOS accepts inherit the listener's non-blocking flag, so we must
                     // force the new stream back to blocking mode before doing the
@@ -4428,7 +5304,15 @@ impl TestServer {
                         continue;
                     }
 
-                    Self::handle_connection(stream, &app, &log_entries, &config, &request_counter);
+                    Self::handle_connection(
+                        stream,
+                        &app,
+                        &log_entries,
+                        &config,
+                        &request_counter,
+                        &guard,
+                        peer,
+                    );
                 }
                 Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
                     thread::sleep(Duration::from_millis(5));
@@ -4447,6 +5331,8 @@ impl TestServer {
         log_entries: &Arc<Mutex<Vec<TestServerLogEntry>>>,
         config: &TestServerConfig,
         request_counter: &std::sync::atomic::AtomicU64,
+        guard: &crate::shutdown::InFlightGuard,
+        peer: std::net::SocketAddr,
     ) {
         let _ = stream.set_read_timeout(Some(config.read_timeout));
 
@@ -4482,6 +5368,8 @@ impl TestServer {
             _ => Method::Get,
         };
 
+        guard.describe(method, parsed.path.clone(), Some(peer.to_string()));
+
         let mut request = Request::new(method, &parsed.path);
 
         // Set query string if present
@@ -6294,6 +7182,329 @@ macro_rules! assert_response_snapshot {
     }};
 }
 
+/// Where and how [`assert_snapshot!`](crate::assert_snapshot) stores response
+/// snapshots.
+///
+/// Snapshots are plain-text `.snap` files holding the status, the selected
+/// headers and the body. JSON bodies are pretty-printed with sorted keys, so
+/// key order and whitespace changes do not fail the assertion.
+///
+/// Review workflow:
+///
+/// - A missing snapshot is recorded and the assertion passes, unless the `CI`
+///   environment variable is set.
+/// - On a mismatch, the new output is written next to the snapshot as
+///   `<name>.snap.new` and the assertion fails with a line diff. Accept it by
+///   renaming it over the `.snap` file, or rerun with `SNAPSHOT_UPDATE=1`.
+///
+/// ```ignore
+/// let snapshots = SnapshotConfig::new("tests/snapshots")
+///     .header("location")
+///     .mask("id")
+///     .mask("created_at");
+/// assert_snapshot!(client.post("/users").json(&user).send(), "create_user", &snapshots);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SnapshotConfig {
+    dir: std::path::PathBuf,
+    headers: Vec<String>,
+    masks: Vec<String>,
+    update: bool,
+    record_new: bool,
+}
+
+impl SnapshotConfig {
+    /// Store snapshots in `dir`, recording only the `content-type` header.
+    ///
+    /// `SNAPSHOT_UPDATE` and `CI` are read from the environment here.
+    pub fn new(dir: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            headers: vec!["content-type".to_string()],
+            masks: Vec::new(),
+            update: std::env::var_os("SNAPSHOT_UPDATE").is_some_and(|v| !v.is_empty() && v != "0"),
+            record_new: std::env::var_os("CI").is_none(),
+        }
+    }
+
+    /// Also record header `name` (case-insensitive).
+    #[must_use]
+    pub fn header(mut self, name: &str) -> Self {
+        self.headers.push(name.to_ascii_lowercase());
+        self
+    }
+
+    /// Record exactly these headers; `&[]` records none.
+    #[must_use]
+    pub fn headers(mut self, names: &[&str]) -> Self {
+        self.headers = names.iter().map(|n| n.to_ascii_lowercase()).collect();
+        self
+    }
+
+    /// Replace the JSON value at the dot-separated `path` (e.g. `items.0.id`)
+    /// with `<MASKED>`, for IDs and timestamps that change between runs.
+    #[must_use]
+    pub fn mask(mut self, path: &str) -> Self {
+        self.masks.push(path.to_string());
+        self
+    }
+
+    /// Overwrite mismatching snapshots instead of failing.
+    #[must_use]
+    pub fn update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Whether a missing snapshot is recorded (`true`) or fails the assertion.
+    #[must_use]
+    pub fn record_new(mut self, record: bool) -> Self {
+        self.record_new = record;
+        self
+    }
+
+    /// The file holding snapshot `name`.
+    #[must_use]
+    pub fn path(&self, name: &str) -> std::path::PathBuf {
+        let file: String = name
+            .replace("::", "__")
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{file}.snap"))
+    }
+
+    /// Render `resp` the way it is stored.
+    #[must_use]
+    pub fn render(&self, resp: &TestResponse) -> String {
+        let mut out = format!("status: {}\n", resp.status().as_u16());
+
+        let mut headers: Vec<(String, &str)> = resp
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                let name = name.to_ascii_lowercase();
+                if !self.headers.contains(&name) {
+                    return None;
+                }
+                Some((
+                    name,
+                    std::str::from_utf8(value).unwrap_or("<non-UTF8 value>"),
+                ))
+            })
+            .collect();
+        // Stable, so repeated headers keep their order.
+        headers.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, value) in headers {
+            out.push_str(&format!("{name}: {value}\n"));
+        }
+        out.push('\n');
+
+        let bytes = resp.bytes();
+        let body = if bytes.is_empty() {
+            String::new()
+        } else if let Ok(mut json) = serde_json::from_slice::<serde_json::Value>(bytes) {
+            for path in &self.masks {
+                mask_json_path(&mut json, path, "<MASKED>");
+            }
+            serde_json::to_string_pretty(&sort_json_keys(json)).unwrap_or_default()
+        } else if let Ok(text) = std::str::from_utf8(bytes) {
+            text.to_string()
+        } else {
+            format!("<{} bytes of binary data>", bytes.len())
+        };
+        out.push_str(&body);
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Compare `resp` with snapshot `name`, recording or updating it as
+    /// described on [`SnapshotConfig`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the response does not match the snapshot, if the snapshot
+    /// is missing and new snapshots are not recorded, or on I/O errors.
+    #[track_caller]
+    pub fn assert_matches(&self, resp: &TestResponse, name: &str) {
+        let path = self.path(name);
+        let pending = path.with_extension("snap.new");
+        let actual = self.render(resp);
+
+        let expected = match std::fs::read_to_string(&path) {
+            Ok(expected) => expected.replace("\r\n", "\n"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                if self.record_new || self.update {
+                    write_snapshot(&path, &actual);
+                    return;
+                }
+                write_snapshot(&pending, &actual);
+                panic!(
+                    "missing snapshot {}\n\
+                     review {} and rename it to accept, or rerun with SNAPSHOT_UPDATE=1",
+                    path.display(),
+                    pending.display()
+                );
+            }
+            Err(e) => panic!("failed to read snapshot {}: {e}", path.display()),
+        };
+
+        if expected == actual {
+            let _ = std::fs::remove_file(&pending);
+            return;
+        }
+        if self.update {
+            write_snapshot(&path, &actual);
+            let _ = std::fs::remove_file(&pending);
+            return;
+        }
+        write_snapshot(&pending, &actual);
+        panic!(
+            "snapshot mismatch for {}\n\
+             {}\
+             request id: {}\n\
+             review {} and rename it to accept, or rerun with SNAPSHOT_UPDATE=1",
+            path.display(),
+            diff_lines(&expected, &actual),
+            resp.request_id(),
+            pending.display()
+        );
+    }
+}
+
+/// The snapshot name for an unnamed [`assert_snapshot!`](crate::assert_snapshot).
+///
+/// libtest names each test's thread after the test, e.g. `tests::list_users`;
+/// outside a test thread, `module_path` is used. Further unnamed snapshots in
+/// the same test get `-2`, `-3`, ... appended.
+#[doc(hidden)]
+pub fn auto_snapshot_name(module_path: &str) -> String {
+    thread_local! {
+        static SEEN: std::cell::RefCell<HashMap<String, usize>> =
+            std::cell::RefCell::new(HashMap::new());
+    }
+
+    let base = match std::thread::current().name() {
+        Some(name) if name != "main" => name.to_string(),
+        _ => module_path.to_string(),
+    };
+    let n = SEEN.with(|seen| {
+        let mut seen = seen.borrow_mut();
+        let n = seen.entry(base.clone()).or_insert(0);
+        *n += 1;
+        *n
+    });
+    if n == 1 { base } else { format!("{base}-{n}") }
+}
+
+fn write_snapshot(path: &std::path::Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .unwrap_or_else(|e| panic!("failed to create {}: {e}", parent.display()));
+    }
+    std::fs::write(path, contents)
+        .unwrap_or_else(|e| panic!("failed to write snapshot {}: {e}", path.display()));
+}
+
+/// Rebuild objects with their keys in sorted order.
+fn sort_json_keys(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, sort_json_keys(v)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(sort_json_keys).collect())
+        }
+        other => other,
+    }
+}
+
+/// A line diff from `expected` to `actual`, marking lines with `-` and `+`.
+fn diff_lines(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // lcs[i][j]: length of the longest common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push_str(&format!("  {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            out.push_str(&format!("+ {}\n", new[j]));
+            j += 1;
+        } else {
+            out.push_str(&format!("- {}\n", old[i]));
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Asserts that a test response matches a stored snapshot.
+///
+/// Without a name, the snapshot is named after the test. Snapshots live in
+/// `tests/snapshots/` of the crate under test unless a [`SnapshotConfig`]
+/// is passed; see it for the file format and the review workflow.
+///
+/// # Examples
+///
+/// ```ignore
+/// use fastapi_core::assert_snapshot;
+///
+/// assert_snapshot!(client.get("/users").send());
+/// assert_snapshot!(client.get("/users/1").send(), "user_detail");
+///
+/// let snapshots = SnapshotConfig::new("tests/snapshots").mask("id");
+/// assert_snapshot!(client.post("/users").json(&user).send(), "create_user", &snapshots);
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($response:expr) => {
+        $crate::assert_snapshot!(
+            $response,
+            $crate::testing::auto_snapshot_name(module_path!())
+        )
+    };
+    ($response:expr, $name:expr) => {{
+        let config = $crate::testing::SnapshotConfig::new(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots"),
+        );
+        config.assert_matches(&$response, &$name);
+    }};
+    ($response:expr, $name:expr, $config:expr) => {{
+        $crate::testing::SnapshotConfig::assert_matches($config, &$response, &$name);
+    }};
+}
+
 #[cfg(test)]
 mod snapshot_tests {
     use super::*;
@@ -6482,6 +7693,524 @@ mod snapshot_tests {
         // But matches_ignoring_headers uses JSON structural comparison
         assert!(s1.matches_ignoring_headers(&s2, &[]));
     }
+
+    #[test]
+    fn snapshot_render_normalizes_json_and_selects_headers() {
+        let resp = mock_test_response(
+            201,
+            r#"{"name":"Alice","id":42,"tags":[{"z":1,"a":2}]}"#,
+            &[
+                ("Content-Type", "application/json"),
+                ("Location", "/users/42"),
+                ("X-Request-Id", "abc"),
+            ],
+        );
+        let config = SnapshotConfig::new("unused").header("location").mask("id");
+
+        assert_eq!(
+            config.render(&resp),
+            "status: 201\n\
+             content-type: application/json\n\
+             location: /users/42\n\
+             \n\
+             {\n  \"id\": \"<MASKED>\",\n  \"name\": \"Alice\",\n  \"tags\": [\n    {\n      \"a\": 2,\n      \"z\": 1\n    }\n  ]\n}\n"
+        );
+    }
+
+    #[test]
+    fn snapshot_review_workflow() {
+        let dir =
+            std::env::temp_dir().join(format!("fastapi_snapshot_workflow_{}", std::process::id()));
+        let config = SnapshotConfig::new(&dir).record_new(true).update(false);
+        let path = config.path("users::list");
+        let pending = path.with_extension("snap.new");
+        assert!(path.ends_with("users__list.snap"));
+
+        // First run records, second run matches.
+        let resp = mock_test_response(200, r#"{"users":[]}"#, &[]);
+        config.assert_matches(&resp, "users::list");
+        config.assert_matches(&resp, "users::list");
+
+        // A mismatch fails and leaves the new output for review.
+        let changed = mock_test_response(200, r#"{"users":["bob"]}"#, &[]);
+        let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            config.assert_matches(&changed, "users::list");
+        }))
+        .expect_err("mismatch must fail");
+        let msg = err.downcast_ref::<String>().expect("panic message");
+        assert!(msg.contains("+   \"users\": [\n"), "{msg}");
+        assert!(msg.contains("-   \"users\": []\n"), "{msg}");
+        assert_eq!(
+            std::fs::read_to_string(&pending).unwrap(),
+            config.render(&changed)
+        );
+
+        // Updating accepts the new output and clears the pending file.
+        config
+            .clone()
+            .update(true)
+            .assert_matches(&changed, "users::list");
+        assert!(!pending.exists());
+        config.assert_matches(&changed, "users::list");
+
+        // Missing snapshots fail when recording is off (as on CI).
+        let strict = config.clone().record_new(false);
+        let missing = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            strict.assert_matches(&resp, "never_recorded");
+        }));
+        assert!(missing.is_err());
+        assert!(
+            strict
+                .path("never_recorded")
+                .with_extension("snap.new")
+                .exists()
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unnamed_snapshots_are_numbered_per_test() {
+        let first = auto_snapshot_name("my_crate::tests");
+        let second = auto_snapshot_name("my_crate::tests");
+        assert!(
+            first.ends_with("unnamed_snapshots_are_numbered_per_test"),
+            "{first}"
+        );
+        assert_eq!(second, format!("{first}-2"));
+    }
+}
+
+// ============================================================================
+// OpenAPI Contract Testing
+// ============================================================================
+
+/// A way a response departs from its documented OpenAPI operation.
+#[derive(Debug, Clone)]
+pub enum ContractViolation {
+    /// The status code is not among the operation's responses (and there is
+    /// no matching `2XX`-style range or `default` response).
+    UndocumentedStatus {
+        /// The response status.
+        status: u16,
+    },
+    /// The documented response lists content types, and the response's
+    /// `Content-Type` is not one of them.
+    UndocumentedContentType {
+        /// The response status.
+        status: u16,
+        /// The response's media type, if it sent one.
+        content_type: Option<String>,
+    },
+    /// A header documented as `required` is missing.
+    MissingHeader {
+        /// The header name as documented.
+        name: String,
+    },
+    /// The documented content is JSON, but the body does not parse.
+    InvalidJson {
+        /// The parse error.
+        error: String,
+    },
+    /// The body does not match the documented schema.
+    Schema(Box<crate::error::ValidationErrors>),
+}
+
+impl std::fmt::Display for ContractViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UndocumentedStatus { status } => write!(f, "status {status} is not documented"),
+            Self::UndocumentedContentType {
+                status,
+                content_type,
+            } => write!(
+                f,
+                "content type {} is not documented for status {status}",
+                content_type.as_deref().unwrap_or("<none>")
+            ),
+            Self::MissingHeader { name } => write!(f, "required header {name} is missing"),
+            Self::InvalidJson { error } => write!(f, "body is not valid JSON: {error}"),
+            Self::Schema(errors) => {
+                write!(f, "body does not match the documented schema:")?;
+                for error in errors.iter() {
+                    let loc: Vec<String> = error
+                        .loc
+                        .iter()
+                        .map(|item| match item {
+                            crate::error::LocItem::Field(name) => name.clone(),
+                            crate::error::LocItem::Index(idx) => idx.to_string(),
+                        })
+                        .collect();
+                    write!(f, "\n  {}: {}", loc.join("."), error.msg)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Checks responses against an OpenAPI document.
+///
+/// Each response is matched to the documented operation for its request's
+/// method and path. The status must be documented, headers marked `required`
+/// must be present, and when the documented response lists content types,
+/// the `Content-Type` must be one of them and JSON bodies must match the
+/// schema (with `$ref`s resolved against `components.schemas`).
+///
+/// Requests that match no documented operation (unknown routes, routes left
+/// out of the schema) are not checked.
+///
+/// ```ignore
+/// // Every response from this client is checked; drift fails the test.
+/// let client = TestClient::new(app).check_contract();
+/// client.get("/users/1").send();
+///
+/// // Or check single responses.
+/// let contract = ContractChecker::from_app(&app);
+/// contract.assert_conforms("GET", "/users/1", &response);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContractChecker {
+    spec: fastapi_openapi::OpenApi,
+}
+
+impl ContractChecker {
+    /// Check responses against `spec`.
+    #[must_use]
+    pub fn new(spec: fastapi_openapi::OpenApi) -> Self {
+        Self { spec }
+    }
+
+    /// Check responses against the document `app` serves.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the app has no OpenAPI document (see
+    /// [`AppBuilder::openapi`](crate::app::AppBuilder::openapi)) or the
+    /// document cannot be parsed.
+    #[must_use]
+    pub fn from_app(app: &App) -> Self {
+        let spec = app
+            .openapi_spec()
+            .expect("contract checks need an OpenAPI document; configure one with .openapi(...)");
+        let spec = serde_json::from_str(&spec)
+            .unwrap_or_else(|e| panic!("failed to parse the app's OpenAPI document: {e}"));
+        Self::new(spec)
+    }
+
+    /// The documented operation for `method` and `path`, preferring the
+    /// template with the most literal segments (`/users/me` over
+    /// `/users/{id}`).
+    #[must_use]
+    pub fn operation(&self, method: &str, path: &str) -> Option<&fastapi_openapi::Operation> {
+        let path = path.split('?').next().unwrap_or(path);
+        self.spec
+            .paths
+            .iter()
+            .filter_map(|(template, item)| {
+                let literals = match_path_template(template, path)?;
+                let (_, operation) = item
+                    .method_operations()
+                    .find(|(m, _)| m.eq_ignore_ascii_case(method))?;
+                Some((literals, operation))
+            })
+            .max_by_key(|(literals, _)| *literals)
+            .map(|(_, operation)| operation)
+    }
+
+    /// Check `resp` to a `method` request for `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns every violation found.
+    pub fn check(
+        &self,
+        method: &str,
+        path: &str,
+        resp: &TestResponse,
+    ) -> Result<(), Vec<ContractViolation>> {
+        let Some(operation) = self.operation(method, path) else {
+            return Ok(());
+        };
+        let status = resp.status().as_u16();
+        let Some(documented) = documented_response(operation, status) else {
+            return Err(vec![ContractViolation::UndocumentedStatus { status }]);
+        };
+
+        let mut violations = Vec::new();
+        for (name, header) in &documented.headers {
+            if header.required && resp.header(name).is_none() {
+                violations.push(ContractViolation::MissingHeader { name: name.clone() });
+            }
+        }
+
+        let body = match resp.inner.body_ref() {
+            ResponseBody::Empty => &[][..],
+            ResponseBody::Bytes(b) => b.as_slice(),
+            // Streamed bodies cannot be read without consuming the response.
+            ResponseBody::Stream(_) => return finish_check(violations),
+        };
+        let content_type = resp.content_type().map(|ct| {
+            ct.split(';')
+                .next()
+                .unwrap_or(ct)
+                .trim()
+                .to_ascii_lowercase()
+        });
+        if documented.content.is_empty() || (body.is_empty() && content_type.is_none()) {
+            return finish_check(violations);
+        }
+
+        let media = content_type.as_deref().and_then(|ct| {
+            documented
+                .content
+                .iter()
+                .find(|(key, _)| media_type_matches(key, ct))
+                .map(|(_, media)| media)
+        });
+        let Some(media) = media else {
+            violations.push(ContractViolation::UndocumentedContentType {
+                status,
+                content_type,
+            });
+            return finish_check(violations);
+        };
+
+        let is_json = content_type
+            .as_deref()
+            .is_some_and(|ct| ct == "application/json" || ct.ends_with("+json"));
+        if let (true, Some(schema)) = (is_json, &media.schema) {
+            match serde_json::from_slice::<serde_json::Value>(body) {
+                Ok(value) => {
+                    let no_components = HashMap::new();
+                    let components = self
+                        .spec
+                        .components
+                        .as_ref()
+                        .map_or(&no_components, |c| &c.schemas);
+                    if let Err(errors) = crate::validation::validate_json_schema_with_components(
+                        &value,
+                        schema,
+                        components,
+                        crate::error::loc::body(),
+                    ) {
+                        violations.push(ContractViolation::Schema(errors));
+                    }
+                }
+                Err(e) => violations.push(ContractViolation::InvalidJson {
+                    error: e.to_string(),
+                }),
+            }
+        }
+        finish_check(violations)
+    }
+
+    /// Like [`check`](Self::check), but panics on violations.
+    ///
+    /// # Panics
+    ///
+    /// Panics with every violation if `resp` does not conform.
+    #[track_caller]
+    pub fn assert_conforms(&self, method: &str, path: &str, resp: &TestResponse) {
+        if let Err(violations) = self.check(method, path, resp) {
+            let list: Vec<String> = violations.iter().map(|v| format!("- {v}")).collect();
+            panic!(
+                "response to {method} {path} breaks the OpenAPI contract\n\
+                 {}\n\
+                 request id: {}\n\
+                 response body: {}",
+                list.join("\n"),
+                resp.request_id(),
+                resp.text_opt().unwrap_or("<non-UTF8 body>")
+            );
+        }
+    }
+}
+
+fn finish_check(violations: Vec<ContractViolation>) -> Result<(), Vec<ContractViolation>> {
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+/// The literal segment count if `path` matches `template`, where `{name}`
+/// matches one segment and `{name:path}` the rest of the path.
+fn match_path_template(template: &str, path: &str) -> Option<usize> {
+    let template: Vec<&str> = template.trim_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let mut literals = 0;
+    for (i, segment) in template.iter().enumerate() {
+        if segment.starts_with('{') && segment.ends_with('}') {
+            if segment.ends_with(":path}") {
+                return (path.len() > i).then_some(literals);
+            }
+            if path.get(i).is_none_or(|s| s.is_empty()) {
+                return None;
+            }
+        } else if path.get(i) == Some(segment) {
+            literals += 1;
+        } else {
+            return None;
+        }
+    }
+    (template.len() == path.len()).then_some(literals)
+}
+
+/// The response documented for `status`: exact code, then range (`2XX`),
+/// then `default`.
+fn documented_response(
+    operation: &fastapi_openapi::Operation,
+    status: u16,
+) -> Option<&fastapi_openapi::Response> {
+    let range = format!("{}XX", status / 100);
+    operation
+        .responses
+        .get(&status.to_string())
+        .or_else(|| {
+            operation
+                .responses
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(&range))
+                .map(|(_, response)| response)
+        })
+        .or_else(|| operation.responses.get("default"))
+}
+
+/// Whether the documented media type `pattern` (possibly `type/*` or `*/*`)
+/// covers `media`.
+fn media_type_matches(pattern: &str, media: &str) -> bool {
+    let pattern = pattern.split(';').next().unwrap_or(pattern).trim();
+    if pattern == "*/*" || pattern.eq_ignore_ascii_case(media) {
+        return true;
+    }
+    pattern.strip_suffix("/*").is_some_and(|kind| {
+        media
+            .split('/')
+            .next()
+            .is_some_and(|m| m.eq_ignore_ascii_case(kind))
+    })
+}
+
+#[cfg(test)]
+mod contract_tests {
+    use super::*;
+    use fastapi_openapi::{MediaType, ObjectSchema, OpenApiBuilder, Operation, Schema};
+
+    fn user_schema() -> Schema {
+        let mut user = ObjectSchema::default();
+        user.properties
+            .insert("id".to_string(), Schema::integer(None));
+        user.properties.insert("name".to_string(), Schema::string());
+        user.required = vec!["id".to_string(), "name".to_string()];
+        Schema::Object(user)
+    }
+
+    fn checker() -> ContractChecker {
+        let mut ok = fastapi_openapi::Response {
+            description: "The user".to_string(),
+            headers: HashMap::new(),
+            content: HashMap::new(),
+        };
+        ok.content.insert(
+            "application/json".to_string(),
+            MediaType::new(Schema::reference("User")),
+        );
+        ok.headers.insert(
+            "ETag".to_string(),
+            fastapi_openapi::Header {
+                required: true,
+                ..fastapi_openapi::Header::new(Schema::string())
+            },
+        );
+        let mut operation = Operation::default();
+        operation.responses.insert("200".to_string(), ok);
+        operation.responses.insert(
+            "4XX".to_string(),
+            fastapi_openapi::Response {
+                description: "Client error".to_string(),
+                headers: HashMap::new(),
+                content: HashMap::new(),
+            },
+        );
+
+        let mut spec = OpenApiBuilder::new("Users", "1.0")
+            .operation("GET", "/users/{id}", operation)
+            .build();
+        spec.components
+            .get_or_insert_with(Default::default)
+            .schemas
+            .insert("User".to_string(), user_schema());
+        ContractChecker::new(spec)
+    }
+
+    fn response(status: u16, body: &str, headers: &[(&str, &str)]) -> TestResponse {
+        let mut resp = Response::with_status(StatusCode::from_u16(status))
+            .header("content-type", b"application/json".to_vec());
+        for (name, value) in headers {
+            resp = resp.header(*name, value.as_bytes().to_vec());
+        }
+        TestResponse::new(resp.body(ResponseBody::Bytes(body.as_bytes().to_vec())), 0)
+    }
+
+    #[test]
+    fn conforming_responses_pass() {
+        let contract = checker();
+        let ok = response(200, r#"{"id":1,"name":"Ada"}"#, &[("etag", "\"v1\"")]);
+        assert!(contract.check("GET", "/users/1", &ok).is_ok());
+        // Covered by the 4XX range, which documents no content.
+        assert!(
+            contract
+                .check("GET", "/users/2", &response(404, "{}", &[]))
+                .is_ok()
+        );
+        // Not a documented operation.
+        assert!(
+            contract
+                .check("POST", "/users/1", &response(500, "", &[]))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn drift_is_reported() {
+        let contract = checker();
+
+        let errors = contract
+            .check("GET", "/users/1", &response(500, "{}", &[]))
+            .unwrap_err();
+        assert!(matches!(
+            errors[..],
+            [ContractViolation::UndocumentedStatus { status: 500 }]
+        ));
+
+        let errors = contract
+            .check("GET", "/users/1", &response(200, r#"{"id":"one"}"#, &[]))
+            .unwrap_err();
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages[0], "required header ETag is missing");
+        assert!(messages[1].contains("body.id: "), "{}", messages[1]);
+        assert!(messages[1].contains("body.name: "), "{}", messages[1]);
+    }
+
+    #[test]
+    fn client_checks_every_response() {
+        let app = App::builder()
+            .get("/ping", |_ctx: &RequestContext, _req: &mut Request| async {
+                Response::with_status(StatusCode::from_u16(201))
+            })
+            .openapi(crate::app::OpenApiConfig::new())
+            .build();
+        let client = TestClient::new(app).check_contract();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            client.get("/ping").send();
+        }));
+        let err = result.expect_err("undocumented 201 must fail");
+        let msg = err.downcast_ref::<String>().expect("panic message");
+        assert!(msg.contains("status 201 is not documented"), "{msg}");
+    }
 }
 
 #[cfg(test)]
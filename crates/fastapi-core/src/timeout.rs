@@ -0,0 +1,207 @@
+//! Per-request handler timeouts.
+//!
+//! [`TimeoutLayer`] gives the handler a share of the request budget: the
+//! handler runs for at most the configured duration, or until the server's
+//! own request deadline if that comes first. At expiry the handler future is
+//! dropped, cancelling everything it was awaiting, and the client gets a 504
+//! with a JSON body:
+//!
+//! ```json
+//! {"detail": "Request timed out", "timeout_ms": 2000}
+//! ```
+//!
+//! Middleware `after` hooks still run on the 504. Layers nest: an inner
+//! layer can only shorten the time an outer one allows.
+//!
+//! ```ignore
+//! let app = App::builder()
+//!     .middleware(TimeoutLayer::new(Duration::from_secs(2)))
+//!     .build();
+//! ```
+//!
+//! Handlers that stop at a [`RequestContext::checkpoint`] can return the
+//! [`CancelledError`] directly; it renders as the same kind of 504.
+
+use std::future::Future;
+use std::pin::pin;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+
+use crate::context::{CancelledError, RequestContext};
+use crate::middleware::{BoxFuture, ControlFlow, Handler, Middleware};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response, ResponseBody, StatusCode};
+
+/// Middleware that bounds how long the handler may run.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeoutLayer {
+    timeout: Duration,
+}
+
+impl TimeoutLayer {
+    /// Give handlers at most `timeout`.
+    #[must_use]
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+
+    /// The configured timeout.
+    #[must_use]
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+/// Request extension with the handler's deadline, read by
+/// [`MiddlewareStack::execute`](crate::middleware::MiddlewareStack::execute).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HandlerDeadline {
+    at: Instant,
+    budget: Duration,
+}
+
+impl Middleware for TimeoutLayer {
+    fn before<'a>(
+        &'a self,
+        ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        let mut budget = self.timeout;
+        if let Some(deadline) = ctx.deadline() {
+            let left = deadline.as_nanos().saturating_sub(ctx.now().as_nanos());
+            budget = budget.min(Duration::from_nanos(left));
+        }
+        let now = ctx.clock().now();
+        let mut deadline = HandlerDeadline {
+            at: now + budget,
+            budget,
+        };
+        if let Some(outer) = req
+            .get_extension::<HandlerDeadline>()
+            .filter(|outer| outer.at < deadline.at)
+        {
+            deadline = *outer;
+        }
+        req.insert_extension(deadline);
+        Box::pin(async { ControlFlow::Continue })
+    }
+
+    fn name(&self) -> &'static str {
+        "Timeout"
+    }
+}
+
+/// Calls `handler`, racing it against the request's [`HandlerDeadline`].
+pub(crate) async fn call_handler<H: Handler>(
+    handler: &H,
+    ctx: &RequestContext,
+    req: &mut Request,
+) -> Response {
+    let Some(deadline) = req.get_extension::<HandlerDeadline>().copied() else {
+        return handler.call(ctx, req).await;
+    };
+    let left = deadline.at.saturating_duration_since(ctx.clock().now());
+    if left.is_zero() {
+        return timed_out(deadline.budget);
+    }
+
+    let mut call = handler.call(ctx, req);
+    let mut expiry = pin!(ctx.clock().sleep(left));
+    let finished = std::future::poll_fn(|cx| {
+        if let Poll::Ready(response) = call.as_mut().poll(cx) {
+            return Poll::Ready(Some(response));
+        }
+        expiry.as_mut().poll(cx).map(|_| None)
+    })
+    .await;
+    // Dropping the handler future cancels whatever it was awaiting.
+    drop(call);
+    finished.unwrap_or_else(|| timed_out(deadline.budget))
+}
+
+fn timed_out(budget: Duration) -> Response {
+    let timeout_ms = u64::try_from(budget.as_millis()).unwrap_or(u64::MAX);
+    let body = serde_json::json!({
+        "detail": "Request timed out",
+        "timeout_ms": timeout_ms,
+    });
+    Response::with_status(StatusCode::GATEWAY_TIMEOUT)
+        .header("content-type", b"application/json".to_vec())
+        .body(ResponseBody::Bytes(body.to_string().into_bytes()))
+}
+
+impl IntoResponse for CancelledError {
+    fn into_response(self) -> Response {
+        crate::HttpError::new(StatusCode::GATEWAY_TIMEOUT)
+            .with_detail("Request cancelled")
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::MiddlewareStack;
+    use crate::request::Method;
+
+    fn test_context() -> RequestContext {
+        RequestContext::new(asupersync::Cx::for_testing(), 1)
+    }
+
+    fn never_responds(_ctx: &RequestContext, _req: &mut Request) -> std::future::Pending<Response> {
+        std::future::pending()
+    }
+
+    fn responds(_ctx: &RequestContext, _req: &mut Request) -> std::future::Ready<Response> {
+        std::future::ready(Response::ok())
+    }
+
+    #[test]
+    fn exhausted_budget_answers_504_without_running_the_handler() {
+        let mut stack = MiddlewareStack::new();
+        stack.push(TimeoutLayer::new(Duration::ZERO));
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/slow");
+
+        let response = futures_executor::block_on(stack.execute(&never_responds, &ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 504);
+        let ResponseBody::Bytes(body) = response.body_ref() else {
+            panic!("expected a JSON body");
+        };
+        let body: serde_json::Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(body["timeout_ms"], 0);
+    }
+
+    #[test]
+    fn inner_layers_only_shorten_the_deadline() {
+        let mut stack = MiddlewareStack::new();
+        stack.push(TimeoutLayer::new(Duration::from_secs(1)));
+        stack.push(TimeoutLayer::new(Duration::from_secs(60)));
+        let ctx = test_context();
+        let mut req = Request::new(Method::Get, "/");
+
+        let response = futures_executor::block_on(stack.execute(&responds, &ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 200);
+        let deadline = req.get_extension::<HandlerDeadline>().unwrap();
+        assert_eq!(deadline.budget, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn pending_handler_times_out_on_virtual_time() {
+        let time = crate::clock::VirtualClock::new();
+        let mut stack = MiddlewareStack::new();
+        stack.push(TimeoutLayer::new(Duration::from_secs(30)));
+        let ctx = test_context().with_clock(time.clone().into());
+        let mut req = Request::new(Method::Get, "/slow");
+
+        let response = time.block_on(stack.execute(&never_responds, &ctx, &mut req));
+        assert_eq!(response.status().as_u16(), 504);
+        assert_eq!(time.elapsed(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn cancelled_error_renders_as_504() {
+        let response = CancelledError.into_response();
+        assert_eq!(response.status().as_u16(), 504);
+    }
+}
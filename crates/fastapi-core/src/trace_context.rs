@@ -0,0 +1,498 @@
+//! W3C Trace Context propagation.
+//!
+//! [`TracingMiddleware`] continues the trace described by an incoming
+//! `traceparent` header, or starts a new one, and opens a server [`Span`]
+//! for the request. The resulting [`TraceContext`] is:
+//!
+//! - added to the request's [`LogScope`](crate::logging::LogScope), so every
+//!   log entry for the request carries `trace_id` and `span_id`;
+//! - available to handlers as an extractor, to propagate the trace to
+//!   outgoing calls with [`TraceContext::traceparent`];
+//! - echoed in the response's `traceparent` header.
+//!
+//! When the request ends, sampled spans are handed to a [`SpanExporter`] as a
+//! [`SpanRecord`]. Implement the trait to forward spans to an OpenTelemetry
+//! collector or any other backend.
+//!
+//! ```ignore
+//! let app = App::builder()
+//!     .middleware(TracingMiddleware::new().exporter(|span: &SpanRecord| {
+//!         collector.send(span.clone());
+//!     }))
+//!     .build();
+//!
+//! #[get("/orders/{id}")]
+//! async fn order(cx: &Cx, trace: TraceContext, id: Path<u64>) -> Response {
+//!     let upstream = client
+//!         .get(format!("http://inventory/items/{id}"))
+//!         .header("traceparent", trace.traceparent())
+//!         .send()
+//!         .await?;
+//!     ...
+//! }
+//! ```
+
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use parking_lot::Mutex;
+
+use crate::context::RequestContext;
+use crate::extract::FromRequest;
+use crate::logging::Span;
+use crate::middleware::{BoxFuture, ControlFlow, Middleware};
+use crate::request::Request;
+use crate::response::Response;
+
+/// Longest `tracestate` value propagated, per the W3C recommendation.
+const MAX_TRACESTATE_LEN: usize = 512;
+
+/// The trace a request belongs to and the server span serving it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_id: Option<[u8; 8]>,
+    flags: u8,
+    tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Start a new trace.
+    #[must_use]
+    pub fn root(sampled: bool) -> Self {
+        Self {
+            trace_id: random_id(),
+            span_id: random_id(),
+            parent_id: None,
+            flags: u8::from(sampled),
+            tracestate: None,
+        }
+    }
+
+    /// Continue the trace described by `traceparent` and `tracestate`
+    /// headers with a new span.
+    ///
+    /// Returns `None` if `traceparent` is malformed.
+    #[must_use]
+    pub fn from_headers(traceparent: &str, tracestate: Option<&str>) -> Option<Self> {
+        let (trace_id, parent_id, flags) = parse_traceparent(traceparent)?;
+        let tracestate = tracestate
+            .map(str::trim)
+            .filter(|state| !state.is_empty() && state.len() <= MAX_TRACESTATE_LEN)
+            .map(str::to_string);
+        Some(Self {
+            trace_id,
+            span_id: random_id(),
+            parent_id: Some(parent_id),
+            flags,
+            tracestate,
+        })
+    }
+
+    /// A context for a child span, such as an outgoing call.
+    #[must_use]
+    pub fn child(&self) -> Self {
+        Self {
+            span_id: random_id(),
+            parent_id: Some(self.span_id),
+            ..self.clone()
+        }
+    }
+
+    /// Trace id as 32 lowercase hex digits.
+    #[must_use]
+    pub fn trace_id(&self) -> String {
+        hex(&self.trace_id)
+    }
+
+    /// Span id as 16 lowercase hex digits.
+    #[must_use]
+    pub fn span_id(&self) -> String {
+        hex(&self.span_id)
+    }
+
+    /// Id of the remote parent span, if the trace came from upstream.
+    #[must_use]
+    pub fn parent_id(&self) -> Option<String> {
+        self.parent_id.as_ref().map(|id| hex(id))
+    }
+
+    /// Whether the trace is sampled.
+    #[must_use]
+    pub fn sampled(&self) -> bool {
+        self.flags & 1 == 1
+    }
+
+    /// Vendor-specific `tracestate`, passed through unchanged.
+    #[must_use]
+    pub fn tracestate(&self) -> Option<&str> {
+        self.tracestate.as_deref()
+    }
+
+    /// `traceparent` header value naming this span as the parent.
+    #[must_use]
+    pub fn traceparent(&self) -> String {
+        format!(
+            "00-{}-{}-{:02x}",
+            self.trace_id(),
+            self.span_id(),
+            self.flags
+        )
+    }
+}
+
+/// The request's trace context, or a fresh unsampled root when
+/// [`TracingMiddleware`] is not installed.
+impl FromRequest for TraceContext {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(_ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
+        Ok(req
+            .get_extension::<Self>()
+            .cloned()
+            .unwrap_or_else(|| Self::root(false)))
+    }
+}
+
+/// Parse `version-traceid-parentid-flags`.
+fn parse_traceparent(value: &str) -> Option<([u8; 16], [u8; 8], u8)> {
+    let mut parts = value.trim().split('-');
+    let version = parts.next()?;
+    let trace_id = parts.next()?;
+    let parent_id = parts.next()?;
+    let flags = parts.next()?;
+    // Version 00 has exactly four fields; later versions may append more.
+    if version.len() != 2 || version == "ff" || (version == "00" && parts.next().is_some()) {
+        return None;
+    }
+    decode_hex::<1>(version)?;
+    let trace_id = decode_hex::<16>(trace_id).filter(|id| *id != [0; 16])?;
+    let parent_id = decode_hex::<8>(parent_id).filter(|id| *id != [0; 8])?;
+    let [flags] = decode_hex::<1>(flags)?;
+    Some((trace_id, parent_id, flags))
+}
+
+/// Decode exactly `N` bytes of lowercase hex.
+fn decode_hex<const N: usize>(s: &str) -> Option<[u8; N]> {
+    let digit = |b: u8| match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        _ => None,
+    };
+    let bytes = s.as_bytes();
+    if bytes.len() != N * 2 {
+        return None;
+    }
+    let mut out = [0u8; N];
+    for (i, pair) in bytes.chunks_exact(2).enumerate() {
+        out[i] = (digit(pair[0])? << 4) | digit(pair[1])?;
+    }
+    Some(out)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(out, "{b:02x}");
+    }
+    out
+}
+
+/// Random non-zero id, falling back to a counter if the OS RNG fails.
+fn random_id<const N: usize>() -> [u8; N] {
+    static FALLBACK: AtomicU64 = AtomicU64::new(1);
+
+    let mut bytes = [0u8; N];
+    if getrandom::fill(&mut bytes).is_err() || bytes == [0; N] {
+        let counter = FALLBACK.fetch_add(1, Ordering::Relaxed).to_be_bytes();
+        let tail = N.min(8);
+        bytes[N - tail..].copy_from_slice(&counter[8 - tail..]);
+    }
+    bytes
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// A finished server span.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanRecord {
+    /// Trace id (32 hex digits).
+    pub trace_id: String,
+    /// Span id (16 hex digits).
+    pub span_id: String,
+    /// Remote parent span id, if any.
+    pub parent_span_id: Option<String>,
+    /// Span name: the method and matched route, e.g. `GET /users/{id}`.
+    pub name: String,
+    /// When the span started.
+    pub start: SystemTime,
+    /// How long the request took.
+    pub duration: Duration,
+    /// Response status code.
+    pub status: u16,
+    /// `tracestate` received with the request.
+    pub tracestate: Option<String>,
+    /// Attributes, using OpenTelemetry HTTP semantic convention names.
+    pub attributes: Vec<(String, String)>,
+}
+
+/// Receives finished, sampled spans.
+///
+/// Called inline at the end of each request, so exporters should queue
+/// spans and ship them in batches.
+pub trait SpanExporter: Send + Sync + 'static {
+    /// Export one span.
+    fn export(&self, span: &SpanRecord);
+}
+
+impl<F> SpanExporter for F
+where
+    F: Fn(&SpanRecord) + Send + Sync + 'static,
+{
+    fn export(&self, span: &SpanRecord) {
+        self(span);
+    }
+}
+
+// ============================================================================
+// Middleware
+// ============================================================================
+
+/// The server span, taken by the `after` hook.
+struct ServerSpan(Mutex<Option<(Span, SystemTime)>>);
+
+/// Middleware propagating W3C Trace Context and recording server spans.
+pub struct TracingMiddleware {
+    exporter: Option<Arc<dyn SpanExporter>>,
+    sample_new_traces: bool,
+    response_header: bool,
+}
+
+impl TracingMiddleware {
+    /// Propagate trace context and sample new traces, without exporting.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            exporter: None,
+            sample_new_traces: true,
+            response_header: true,
+        }
+    }
+
+    /// Send sampled spans to `exporter`.
+    #[must_use]
+    pub fn exporter(mut self, exporter: impl SpanExporter) -> Self {
+        self.exporter = Some(Arc::new(exporter));
+        self
+    }
+
+    /// Whether traces started here are sampled (default true). Incoming
+    /// traces keep their upstream sampling decision.
+    #[must_use]
+    pub fn sample_new_traces(mut self, sampled: bool) -> Self {
+        self.sample_new_traces = sampled;
+        self
+    }
+
+    /// Whether to add `traceparent` to responses (default true).
+    #[must_use]
+    pub fn response_header(mut self, enabled: bool) -> Self {
+        self.response_header = enabled;
+        self
+    }
+}
+
+impl Default for TracingMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn header_str<'r>(req: &'r Request, name: &str) -> Option<&'r str> {
+    req.headers()
+        .get(name)
+        .and_then(|v| std::str::from_utf8(v).ok())
+}
+
+impl Middleware for TracingMiddleware {
+    fn before<'a>(
+        &'a self,
+        ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        let context = header_str(req, "traceparent")
+            .and_then(|parent| TraceContext::from_headers(parent, header_str(req, "tracestate")))
+            .unwrap_or_else(|| TraceContext::root(self.sample_new_traces));
+
+        let scope = ctx.log_scope();
+        scope.insert("trace_id", context.trace_id());
+        scope.insert("span_id", context.span_id());
+        let span = Span::new(ctx, format!("{} {}", req.method(), req.path()));
+
+        req.insert_extension(context);
+        req.insert_extension(ServerSpan(Mutex::new(Some((span, SystemTime::now())))));
+        Box::pin(async { ControlFlow::Continue })
+    }
+
+    fn after<'a>(
+        &'a self,
+        ctx: &'a RequestContext,
+        req: &'a Request,
+        response: Response,
+    ) -> BoxFuture<'a, Response> {
+        let Some(context) = req.get_extension::<TraceContext>() else {
+            return Box::pin(async move { response });
+        };
+        let span = req
+            .get_extension::<ServerSpan>()
+            .and_then(|span| span.0.lock().take());
+
+        if let (Some(exporter), Some((mut span, start))) = (&self.exporter, span) {
+            let duration = span.end();
+            if context.sampled() {
+                let route = ctx.log_scope().route();
+                let name = format!(
+                    "{} {}",
+                    req.method(),
+                    route.as_deref().unwrap_or_else(|| req.path())
+                );
+                let status = response.status().as_u16();
+                let mut attributes = vec![
+                    ("http.request.method".to_string(), req.method().to_string()),
+                    ("url.path".to_string(), req.path().to_string()),
+                    ("http.response.status_code".to_string(), status.to_string()),
+                ];
+                if let Some(route) = route {
+                    attributes.push(("http.route".to_string(), route));
+                }
+                exporter.export(&SpanRecord {
+                    trace_id: context.trace_id(),
+                    span_id: context.span_id(),
+                    parent_span_id: context.parent_id(),
+                    name,
+                    start,
+                    duration,
+                    status,
+                    tracestate: context.tracestate.clone(),
+                    attributes,
+                });
+            }
+        }
+
+        let response = if self.response_header {
+            response.header("traceparent", context.traceparent().into_bytes())
+        } else {
+            response
+        };
+        Box::pin(async move { response })
+    }
+
+    fn name(&self) -> &'static str {
+        "Tracing"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Method;
+    use crate::response::StatusCode;
+
+    const PARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn parses_and_validates_traceparent() {
+        let ctx = TraceContext::from_headers(PARENT, Some(" vendor=abc ")).unwrap();
+        assert_eq!(ctx.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(ctx.parent_id().as_deref(), Some("00f067aa0ba902b7"));
+        assert_ne!(ctx.span_id(), "00f067aa0ba902b7");
+        assert!(ctx.sampled());
+        assert_eq!(ctx.tracestate(), Some("vendor=abc"));
+        assert_eq!(
+            ctx.traceparent(),
+            format!("00-4bf92f3577b34da6a3ce929d0e0e4736-{}-01", ctx.span_id())
+        );
+
+        let invalid = [
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+        ];
+        for value in invalid {
+            assert!(TraceContext::from_headers(value, None).is_none(), "{value}");
+        }
+        // Future versions may carry extra fields.
+        assert!(TraceContext::from_headers(&format!("01{}-extra", &PARENT[2..]), None).is_some());
+    }
+
+    #[test]
+    fn middleware_continues_trace_and_exports_span() {
+        let exported = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&exported);
+        let mw = TracingMiddleware::new()
+            .exporter(move |span: &SpanRecord| sink.lock().push(span.clone()));
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+
+        let mut req = Request::new(Method::Get, "/orders/7");
+        req.headers_mut()
+            .insert("traceparent", PARENT.as_bytes().to_vec());
+        assert!(futures_executor::block_on(mw.before(&ctx, &mut req)).is_continue());
+        ctx.log_scope().set_route("/orders/{id}");
+        let trace = futures_executor::block_on(TraceContext::from_request(&ctx, &mut req)).unwrap();
+        assert!(
+            ctx.log_scope()
+                .fields()
+                .contains(&("trace_id".to_string(), trace.trace_id()))
+        );
+
+        let response =
+            futures_executor::block_on(mw.after(&ctx, &req, Response::with_status(StatusCode::OK)));
+        assert!(
+            response
+                .headers()
+                .iter()
+                .any(|(n, v)| n == "traceparent" && *v == trace.traceparent().into_bytes())
+        );
+
+        let spans = exported.lock();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].name, "GET /orders/{id}");
+        assert_eq!(spans[0].trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(spans[0].parent_span_id.as_deref(), Some("00f067aa0ba902b7"));
+        assert_eq!(spans[0].status, 200);
+    }
+
+    #[test]
+    fn unsampled_traces_are_not_exported() {
+        let exported = Arc::new(AtomicU64::new(0));
+        let count = Arc::clone(&exported);
+        let mw =
+            TracingMiddleware::new()
+                .sample_new_traces(false)
+                .exporter(move |_: &SpanRecord| {
+                    count.fetch_add(1, Ordering::Relaxed);
+                });
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+
+        let mut req = Request::new(Method::Get, "/");
+        let _ = futures_executor::block_on(mw.before(&ctx, &mut req));
+        let response = futures_executor::block_on(mw.after(&ctx, &req, Response::ok()));
+        assert_eq!(exported.load(Ordering::Relaxed), 0);
+        let traceparent = response
+            .headers()
+            .iter()
+            .find(|(n, _)| n == "traceparent")
+            .map(|(_, v)| String::from_utf8(v.clone()).unwrap())
+            .unwrap();
+        assert!(traceparent.ends_with("-00"));
+        assert_eq!(traceparent.len(), 55);
+    }
+}
@@ -0,0 +1,665 @@
+//! Declarative rules for file uploads.
+//!
+//! An [`UploadPolicy`] states what a route accepts: content types (checked
+//! against the declared `Content-Type` or, optionally, the file's leading
+//! bytes), per-file and total sizes, file extensions and how filenames are
+//! cleaned up. [`UploadPolicyMiddleware`] attaches policies to routes, and
+//! the [`MultipartForm`](crate::MultipartForm) and
+//! [`MultipartStream`](crate::MultipartStream) extractors enforce them:
+//!
+//! ```ignore
+//! let uploads = UploadPolicyMiddleware::new().route(
+//!     "/avatars/*",
+//!     UploadPolicy::new()
+//!         .allow_content_type("image/png")
+//!         .allow_content_type("image/jpeg")
+//!         .sniff_content(true)
+//!         .max_file_size(2 * 1024 * 1024),
+//! );
+//! let app = App::builder().middleware(uploads).build();
+//! ```
+//!
+//! Oversized uploads are answered with 413, disallowed types with 415 and
+//! rejected filenames with 422. The 415 and 422 bodies list one item per
+//! offending file, in the same shape as validation errors:
+//!
+//! ```json
+//! {"detail": [{"type": "upload_content_type", "loc": ["body", "avatar"], "msg": "File type 'image/gif' is not allowed", "input": "image/gif"}]}
+//! ```
+
+use serde_json::json;
+
+use crate::context::RequestContext;
+use crate::error::{ValidationError, ValidationErrors, error_types, loc};
+use crate::middleware::{BoxFuture, ControlFlow, Middleware, path_matches_pattern};
+use crate::multipart::{MultipartConfig, MultipartForm, Part};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response, ResponseBody, StatusCode};
+
+/// Default cap on the length of a sanitized filename, in bytes.
+pub const DEFAULT_MAX_FILENAME_LEN: usize = 255;
+
+/// Number of leading bytes inspected by [`sniff_content_type`].
+pub const SNIFF_LEN: usize = 16;
+
+/// How uploaded filenames are treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilenameRule {
+    /// Keep filenames as sent (path separators are always rejected).
+    Keep,
+    /// Replace filenames with their [`sanitize_filename`] form.
+    #[default]
+    Sanitize,
+    /// Reject files whose name is not already in sanitized form.
+    Reject,
+}
+
+/// Rules for the files of a multipart upload.
+#[derive(Debug, Clone)]
+pub struct UploadPolicy {
+    allowed_types: Vec<String>,
+    sniff: bool,
+    max_file_size: Option<usize>,
+    max_total_size: Option<usize>,
+    allowed_extensions: Vec<String>,
+    filenames: FilenameRule,
+    max_filename_len: usize,
+}
+
+impl Default for UploadPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_types: Vec::new(),
+            sniff: false,
+            max_file_size: None,
+            max_total_size: None,
+            allowed_extensions: Vec::new(),
+            filenames: FilenameRule::default(),
+            max_filename_len: DEFAULT_MAX_FILENAME_LEN,
+        }
+    }
+}
+
+impl UploadPolicy {
+    /// Create a policy accepting any content type, with the multipart size
+    /// defaults and sanitized filenames.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept files of `content_type`, e.g. `"image/png"` or `"image/*"`.
+    ///
+    /// Once any type is allowed, files of other types are rejected.
+    #[must_use]
+    pub fn allow_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.allowed_types
+            .push(content_type.into().trim().to_ascii_lowercase());
+        self
+    }
+
+    /// Check the type detected from each file's leading bytes instead of the
+    /// declared `Content-Type`. Files whose type cannot be detected are then
+    /// rejected, so this suits binary formats (see [`sniff_content_type`]).
+    #[must_use]
+    pub fn sniff_content(mut self, enabled: bool) -> Self {
+        self.sniff = enabled;
+        self
+    }
+
+    /// Maximum size of each file, in bytes.
+    #[must_use]
+    pub fn max_file_size(mut self, size: usize) -> Self {
+        self.max_file_size = Some(size);
+        self
+    }
+
+    /// Maximum size of all parts together, in bytes.
+    #[must_use]
+    pub fn max_total_size(mut self, size: usize) -> Self {
+        self.max_total_size = Some(size);
+        self
+    }
+
+    /// Accept files with extension `ext` (without the dot, case-insensitive).
+    ///
+    /// Once any extension is allowed, files with other or no extensions are
+    /// rejected.
+    #[must_use]
+    pub fn allow_extension(mut self, ext: impl Into<String>) -> Self {
+        self.allowed_extensions
+            .push(ext.into().trim_start_matches('.').to_ascii_lowercase());
+        self
+    }
+
+    /// Set how filenames are treated.
+    #[must_use]
+    pub fn filenames(mut self, rule: FilenameRule) -> Self {
+        self.filenames = rule;
+        self
+    }
+
+    /// Cap sanitized filenames at `len` bytes, keeping the extension.
+    #[must_use]
+    pub fn max_filename_len(mut self, len: usize) -> Self {
+        self.max_filename_len = len.max(1);
+        self
+    }
+
+    /// Whether uploaded files are sniffed.
+    #[must_use]
+    pub fn sniffs_content(&self) -> bool {
+        self.sniff && !self.allowed_types.is_empty()
+    }
+
+    /// `config` with this policy's size limits applied.
+    #[must_use]
+    pub fn apply_to_config(&self, mut config: MultipartConfig) -> MultipartConfig {
+        if let Some(size) = self.max_file_size {
+            config = config.max_file_size(size);
+        }
+        if let Some(size) = self.max_total_size {
+            config = config.max_total_size(size);
+        }
+        config
+    }
+
+    /// Check every file of `form`, sanitizing filenames if configured.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`UploadPolicyError`] listing each rejected file.
+    pub fn check_form(&self, form: &mut MultipartForm) -> Result<(), UploadPolicyError> {
+        let mut errors = Vec::new();
+        for part in form.parts_mut().iter_mut().filter(|part| part.is_file()) {
+            self.check_file(
+                &part.name,
+                &mut part.filename,
+                part.content_type.as_deref(),
+                &mut errors,
+            );
+            if self.sniffs_content() {
+                let leading = leading_bytes(part);
+                self.check_sniffed(&part.name, &leading, &mut errors);
+            }
+        }
+        UploadPolicyError::from_errors(errors)
+    }
+
+    /// Check a file's declared content type and filename, sanitizing the
+    /// filename if configured. Sniffing is left to [`check_sniffed`].
+    ///
+    /// [`check_sniffed`]: Self::check_sniffed
+    pub(crate) fn check_file(
+        &self,
+        field: &str,
+        filename: &mut Option<String>,
+        content_type: Option<&str>,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        if !self.sniffs_content() {
+            let declared = content_type.unwrap_or("application/octet-stream");
+            if !self.type_allowed(declared) {
+                errors.push(self.type_error(field, declared));
+            }
+        }
+
+        let Some(name) = filename.as_mut() else {
+            return;
+        };
+        let sanitized = sanitize_filename(name, self.max_filename_len);
+        match self.filenames {
+            FilenameRule::Keep => {}
+            FilenameRule::Sanitize => *name = sanitized,
+            FilenameRule::Reject if sanitized != *name => {
+                errors.push(
+                    ValidationError::new(error_types::UPLOAD_FILENAME, loc::body_field(field))
+                        .with_msg("Filename contains disallowed characters")
+                        .with_input(json!(name)),
+                );
+                return;
+            }
+            FilenameRule::Reject => {}
+        }
+
+        if !self.allowed_extensions.is_empty() {
+            let ext = name
+                .rsplit_once('.')
+                .map(|(_, ext)| ext.to_ascii_lowercase())
+                .unwrap_or_default();
+            if !self.allowed_extensions.contains(&ext) {
+                errors.push(
+                    ValidationError::new(error_types::UPLOAD_FILENAME, loc::body_field(field))
+                        .with_msg(format!("File extension '.{ext}' is not allowed"))
+                        .with_input(json!(name))
+                        .with_ctx_value("allowed", json!(self.allowed_extensions)),
+                );
+            }
+        }
+    }
+
+    /// Check the type detected from a file's leading bytes.
+    pub(crate) fn check_sniffed(
+        &self,
+        field: &str,
+        leading: &[u8],
+        errors: &mut Vec<ValidationError>,
+    ) {
+        match sniff_content_type(leading) {
+            Some(detected) if self.type_allowed(detected) => {}
+            Some(detected) => errors.push(self.type_error(field, detected)),
+            None => errors.push(
+                ValidationError::new(error_types::UPLOAD_CONTENT_TYPE, loc::body_field(field))
+                    .with_msg("File type could not be determined")
+                    .with_ctx_value("allowed", json!(self.allowed_types)),
+            ),
+        }
+    }
+
+    fn type_allowed(&self, content_type: &str) -> bool {
+        if self.allowed_types.is_empty() {
+            return true;
+        }
+        let essence = content_type
+            .split(';')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+        let major = essence.split('/').next().unwrap_or("");
+        self.allowed_types.iter().any(|allowed| {
+            allowed == "*/*"
+                || *allowed == essence
+                || allowed
+                    .strip_suffix("/*")
+                    .is_some_and(|allowed_major| allowed_major == major)
+        })
+    }
+
+    fn type_error(&self, field: &str, content_type: &str) -> ValidationError {
+        ValidationError::new(error_types::UPLOAD_CONTENT_TYPE, loc::body_field(field))
+            .with_msg(format!("File type '{content_type}' is not allowed"))
+            .with_input(json!(content_type))
+            .with_ctx_value("allowed", json!(self.allowed_types))
+    }
+}
+
+fn leading_bytes(part: &Part) -> Vec<u8> {
+    match part.spooled_path() {
+        Some(path) => {
+            use std::io::Read;
+
+            let mut leading = Vec::with_capacity(SNIFF_LEN);
+            if let Ok(file) = std::fs::File::open(path) {
+                let limit = u64::try_from(SNIFF_LEN).unwrap_or(u64::MAX);
+                let _ = file.take(limit).read_to_end(&mut leading);
+            }
+            leading
+        }
+        None => part.data[..part.data.len().min(SNIFF_LEN)].to_vec(),
+    }
+}
+
+/// Clean up an uploaded filename for use on disk.
+///
+/// Characters other than letters, digits, `.`, `-`, `_` and space become
+/// `_`; leading and trailing dots and spaces are removed; names longer than
+/// `max_len` bytes are shortened, keeping the extension. An empty result
+/// becomes `"upload"`.
+#[must_use]
+pub fn sanitize_filename(name: &str, max_len: usize) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | ' ') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let mut out = replaced.trim_matches(|c| c == '.' || c == ' ').to_string();
+
+    if out.len() > max_len {
+        let ext = out
+            .rsplit_once('.')
+            .map(|(_, ext)| format!(".{ext}"))
+            .filter(|ext| ext.len() < max_len / 2);
+        let keep = max_len - ext.as_ref().map_or(0, String::len);
+        let mut cut = keep;
+        while !out.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        out.truncate(cut);
+        if let Some(ext) = ext {
+            out.push_str(&ext);
+        }
+    }
+
+    if out.is_empty() {
+        "upload".to_string()
+    } else {
+        out
+    }
+}
+
+/// Detect a file's type from its leading bytes (at least [`SNIFF_LEN`] are
+/// needed for every format to be recognized).
+///
+/// Recognizes common image, audio, video, font, document and archive
+/// formats. Text formats have no signature and return `None`.
+#[must_use]
+pub fn sniff_content_type(data: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"BM", "image/bmp"),
+        (b"\x00\x00\x01\x00", "image/x-icon"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+        (b"Rar!\x1a\x07", "application/vnd.rar"),
+        (b"\x00asm", "application/wasm"),
+        (b"OggS", "audio/ogg"),
+        (b"fLaC", "audio/flac"),
+        (b"ID3", "audio/mpeg"),
+        (b"\x1aE\xdf\xa3", "video/webm"),
+        (b"wOFF", "font/woff"),
+        (b"wOF2", "font/woff2"),
+    ];
+
+    if data.len() >= 12 && data.starts_with(b"RIFF") {
+        return match &data[8..12] {
+            b"WEBP" => Some("image/webp"),
+            b"WAVE" => Some("audio/wav"),
+            b"AVI " => Some("video/x-msvideo"),
+            _ => None,
+        };
+    }
+    if data.len() >= 12 && &data[4..8] == b"ftyp" {
+        return match &data[8..12] {
+            b"avif" | b"avis" => Some("image/avif"),
+            b"qt  " => Some("video/quicktime"),
+            b"M4A " => Some("audio/mp4"),
+            _ => Some("video/mp4"),
+        };
+    }
+    if let Some((_, content_type)) = SIGNATURES
+        .iter()
+        .find(|(signature, _)| data.starts_with(signature))
+    {
+        return Some(content_type);
+    }
+    None
+}
+
+/// Files rejected by an [`UploadPolicy`].
+///
+/// Responds with 415 when a content type was rejected and 422 otherwise,
+/// with one error item per problem.
+#[derive(Debug, Clone)]
+pub struct UploadPolicyError {
+    /// 415 or 422.
+    pub status: StatusCode,
+    /// The individual problems, located at `["body", <field>]`.
+    pub errors: ValidationErrors,
+}
+
+impl UploadPolicyError {
+    pub(crate) fn from_errors(errors: Vec<ValidationError>) -> Result<(), Self> {
+        if errors.is_empty() {
+            return Ok(());
+        }
+        let status = if errors
+            .iter()
+            .any(|error| error.error_type == error_types::UPLOAD_CONTENT_TYPE)
+        {
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        } else {
+            StatusCode::UNPROCESSABLE_ENTITY
+        };
+        Err(Self {
+            status,
+            errors: ValidationErrors::from_errors(errors),
+        })
+    }
+}
+
+impl std::fmt::Display for UploadPolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let messages: Vec<&str> = self.errors.iter().map(|e| e.msg.as_str()).collect();
+        write!(f, "upload rejected: {}", messages.join("; "))
+    }
+}
+
+impl std::error::Error for UploadPolicyError {}
+
+impl IntoResponse for UploadPolicyError {
+    fn into_response(self) -> Response {
+        Response::with_status(self.status)
+            .header("content-type", b"application/json".to_vec())
+            .body(ResponseBody::Bytes(self.errors.to_json_bytes()))
+    }
+}
+
+/// Middleware attaching [`UploadPolicy`]s to routes.
+///
+/// The policy for a request's path is stored as a request extension, where
+/// the multipart extractors find it. Requests to other paths are unaffected.
+///
+/// ```ignore
+/// let uploads = UploadPolicyMiddleware::new()
+///     .route("/avatars/*", UploadPolicy::new().allow_content_type("image/*"))
+///     .route("*", UploadPolicy::new().max_file_size(10 * 1024 * 1024));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UploadPolicyMiddleware {
+    routes: Vec<(String, UploadPolicy)>,
+}
+
+impl UploadPolicyMiddleware {
+    /// Create a middleware without policies.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply `policy` to paths matching `pattern` (`*` wildcards).
+    ///
+    /// The first matching pattern wins.
+    #[must_use]
+    pub fn route(mut self, pattern: impl Into<String>, policy: UploadPolicy) -> Self {
+        self.routes.push((pattern.into(), policy));
+        self
+    }
+
+    fn policy_for(&self, path: &str) -> Option<&UploadPolicy> {
+        self.routes
+            .iter()
+            .find(|(pattern, _)| path_matches_pattern(path, pattern))
+            .map(|(_, policy)| policy)
+    }
+}
+
+impl Middleware for UploadPolicyMiddleware {
+    fn before<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        req: &'a mut Request,
+    ) -> BoxFuture<'a, ControlFlow> {
+        if let Some(policy) = self.policy_for(req.path()) {
+            req.insert_extension(policy.clone());
+        }
+        Box::pin(async { ControlFlow::Continue })
+    }
+
+    fn name(&self) -> &'static str {
+        "UploadPolicy"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::{FromRequest, MultipartExtractError};
+    use crate::multipart::MultipartStream;
+    use crate::request::{Body, Method};
+
+    fn upload_request(path: &str, files: &[(&str, &str, &str, &[u8])]) -> Request {
+        let mut body = Vec::new();
+        for (field, filename, content_type, data) in files {
+            body.extend_from_slice(
+                format!(
+                    "--b\r\nContent-Disposition: form-data; name=\"{field}\"; filename=\"{filename}\"\r\nContent-Type: {content_type}\r\n\r\n"
+                )
+                .as_bytes(),
+            );
+            body.extend_from_slice(data);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(b"--b--\r\n");
+        let mut req = Request::new(Method::Post, path);
+        req.headers_mut()
+            .insert("content-type", b"multipart/form-data; boundary=b".to_vec());
+        req.set_body(Body::Bytes(body));
+        req
+    }
+
+    #[test]
+    fn sanitizes_filenames() {
+        assert_eq!(sanitize_filename("report 2024.pdf", 255), "report 2024.pdf");
+        assert_eq!(sanitize_filename("..hidden", 255), "hidden");
+        assert_eq!(sanitize_filename("a<b>:c?.txt", 255), "a_b__c_.txt");
+        assert_eq!(sanitize_filename("résumé.doc", 255), "résumé.doc");
+        assert_eq!(sanitize_filename(" . ", 255), "upload");
+        assert_eq!(sanitize_filename("abcdefghij.png", 10), "abcdef.png");
+    }
+
+    #[test]
+    fn sniffs_common_formats() {
+        assert_eq!(
+            sniff_content_type(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Some("image/png")
+        );
+        assert_eq!(
+            sniff_content_type(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(sniff_content_type(b"\0\0\0\x1cftypisom"), Some("video/mp4"));
+        assert_eq!(sniff_content_type(b"%PDF-1.7"), Some("application/pdf"));
+        assert_eq!(sniff_content_type(b"name,email\n"), None);
+    }
+
+    #[test]
+    fn rejects_disallowed_types_with_415() {
+        let policy = UploadPolicy::new().allow_content_type("image/*");
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+
+        let mut req = upload_request(
+            "/avatars",
+            &[
+                ("ok", "a.png", "image/png", b"x"),
+                ("bad", "a.gif", "text/html", b"x"),
+            ],
+        );
+        req.insert_extension(policy.clone());
+        let err =
+            futures_executor::block_on(MultipartForm::from_request(&ctx, &mut req)).unwrap_err();
+        let err = match err {
+            MultipartExtractError::Policy(err) => err,
+            other => panic!("expected a policy error, got {other:?}"),
+        };
+        assert_eq!(err.status.as_u16(), 415);
+        assert_eq!(err.errors.len(), 1);
+        assert_eq!(
+            err.errors.iter().next().unwrap().loc,
+            loc::body_field("bad")
+        );
+        assert_eq!(err.into_response().status().as_u16(), 415);
+
+        // With sniffing, the bytes decide.
+        let mut req = upload_request("/avatars", &[("f", "a.png", "image/png", b"<html>")]);
+        req.insert_extension(policy.sniff_content(true));
+        let err =
+            futures_executor::block_on(MultipartForm::from_request(&ctx, &mut req)).unwrap_err();
+        assert_eq!(err.into_response().status().as_u16(), 415);
+    }
+
+    #[test]
+    fn applies_filename_rules_and_sizes() {
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+
+        let mut req = upload_request("/docs", &[("f", "my report?.pdf", "application/pdf", b"x")]);
+        req.insert_extension(UploadPolicy::new().allow_extension("pdf"));
+        let form = futures_executor::block_on(MultipartForm::from_request(&ctx, &mut req)).unwrap();
+        assert_eq!(form.parts()[0].filename.as_deref(), Some("my report_.pdf"));
+
+        let mut req = upload_request("/docs", &[("f", "my report?.pdf", "application/pdf", b"x")]);
+        req.insert_extension(UploadPolicy::new().filenames(FilenameRule::Reject));
+        let err =
+            futures_executor::block_on(MultipartForm::from_request(&ctx, &mut req)).unwrap_err();
+        assert_eq!(err.into_response().status().as_u16(), 422);
+
+        let mut req = upload_request("/docs", &[("f", "a.exe", "application/pdf", b"x")]);
+        req.insert_extension(UploadPolicy::new().allow_extension("pdf"));
+        let err =
+            futures_executor::block_on(MultipartForm::from_request(&ctx, &mut req)).unwrap_err();
+        assert_eq!(err.into_response().status().as_u16(), 422);
+
+        let mut req = upload_request("/docs", &[("f", "a.pdf", "application/pdf", b"12345")]);
+        req.insert_extension(UploadPolicy::new().max_file_size(4));
+        let err =
+            futures_executor::block_on(MultipartForm::from_request(&ctx, &mut req)).unwrap_err();
+        assert_eq!(err.into_response().status().as_u16(), 413);
+    }
+
+    #[test]
+    fn stream_checks_parts_as_they_start() {
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+        let policy = UploadPolicy::new()
+            .allow_content_type("image/png")
+            .sniff_content(true);
+
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR rest";
+        let mut req = upload_request("/avatars", &[("f", "a b.png", "image/png", png)]);
+        req.insert_extension(policy.clone());
+        let mut form =
+            futures_executor::block_on(MultipartStream::from_request(&ctx, &mut req)).unwrap();
+        let data = futures_executor::block_on(async {
+            let part = form.next_part().await.unwrap().unwrap();
+            assert_eq!(part.filename.as_deref(), Some("a b.png"));
+            part.bytes().await.unwrap()
+        });
+        assert_eq!(data, png);
+
+        let mut req = upload_request("/avatars", &[("f", "a.png", "image/png", b"GIF89a...")]);
+        req.insert_extension(policy);
+        let mut form =
+            futures_executor::block_on(MultipartStream::from_request(&ctx, &mut req)).unwrap();
+        let err = futures_executor::block_on(async {
+            let part = form.next_part().await.unwrap().unwrap();
+            part.bytes().await.unwrap_err()
+        });
+        assert_eq!(err.into_response().status().as_u16(), 415);
+    }
+
+    #[test]
+    fn middleware_attaches_route_policy() {
+        let middleware = UploadPolicyMiddleware::new().route(
+            "/avatars/*",
+            UploadPolicy::new().allow_content_type("image/png"),
+        );
+        let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+
+        let mut req = Request::new(Method::Post, "/avatars/7");
+        futures_executor::block_on(middleware.before(&ctx, &mut req));
+        assert!(req.get_extension::<UploadPolicy>().is_some());
+
+        let mut req = Request::new(Method::Post, "/docs");
+        futures_executor::block_on(middleware.before(&ctx, &mut req));
+        assert!(req.get_extension::<UploadPolicy>().is_none());
+    }
+}
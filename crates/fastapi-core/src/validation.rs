@@ -3,7 +3,11 @@
 //! These functions provide runtime validation for common constraints like
 //! email format, URL format, and regex pattern matching.
 
-use crate::error::ValidationErrors;
+use fastapi_openapi::{ObjectSchema, PrimitiveSchema, Schema, SchemaType};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::error::{LocItem, ValidationError, ValidationErrors, error_types};
 
 /// Trait for types that can be validated.
 ///
@@ -46,6 +50,50 @@ pub trait Validate {
     fn validate(&self) -> Result<(), Box<ValidationErrors>>;
 }
 
+/// Return type of a struct-level `#[validate(schema = "...")]` function.
+///
+/// A message (`Result<(), String>`) is reported against the struct itself.
+/// `ValidationErrors` whose locations are relative to the struct, such as
+/// `[LocItem::field("password_confirm")]`, are reported against those fields.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Validate)]
+/// #[validate(schema = "passwords_match")]
+/// struct Signup {
+///     password: String,
+///     password_confirm: String,
+/// }
+///
+/// fn passwords_match(signup: &Signup) -> Result<(), ValidationErrors> {
+///     if signup.password == signup.password_confirm {
+///         return Ok(());
+///     }
+///     Err(ValidationErrors::single(ValidationError::value_error(
+///         vec![LocItem::field("password_confirm")],
+///         "Passwords do not match",
+///     )))
+/// }
+/// ```
+pub trait SchemaCheck {
+    /// Convert into errors located relative to the validated struct.
+    fn into_errors(self) -> Option<ValidationErrors>;
+}
+
+impl SchemaCheck for Result<(), String> {
+    fn into_errors(self) -> Option<ValidationErrors> {
+        self.err()
+            .map(|msg| ValidationErrors::single(ValidationError::value_error(Vec::new(), msg)))
+    }
+}
+
+impl SchemaCheck for Result<(), ValidationErrors> {
+    fn into_errors(self) -> Option<ValidationErrors> {
+        self.err()
+    }
+}
+
 /// Check if a string is a valid email address.
 ///
 /// Uses a simple but practical regex that matches most real-world emails
@@ -182,8 +230,8 @@ pub fn is_valid_url(value: &str) -> bool {
 /// Check if a string matches a regex pattern.
 ///
 /// This function compiles the regex on each call, which is fine for
-/// validation but may not be ideal for hot paths. Consider caching
-/// the compiled regex if validating many values.
+/// validation but may not be ideal for hot paths. Use [`Pattern`] to
+/// compile once and reuse it across values.
 ///
 /// # Examples
 ///
@@ -197,37 +245,93 @@ pub fn is_valid_url(value: &str) -> bool {
 /// ```
 #[must_use]
 pub fn matches_pattern(value: &str, pattern: &str) -> bool {
-    // Pattern matching without pulling in a full regex engine.
-    //
-    // This intentionally supports a small, practical subset used by our derive
-    // tests and common validation cases:
-    // - anchors: ^ and $
-    // - literals and '.' wildcard
-    // - escapes: \\d (digit)
-    // - character classes: [a-z0-9-] with ranges and literals
-    // - quantifiers: +, *, ?, and {n}
-    //
-    // Anything outside this subset returns false (no match).
+    Pattern::new(pattern).is_match(value)
+}
 
-    // Handle common simple patterns.
-    if pattern.is_empty() {
-        return true;
-    }
+/// A compiled regex pattern for repeated matching.
+///
+/// Pattern matching avoids pulling in a full regex engine. It intentionally
+/// supports a small, practical subset used by common validation cases:
+/// - anchors: ^ and $
+/// - literals and '.' wildcard
+/// - escapes: \\d (digit)
+/// - character classes: [a-z0-9-] with ranges and literals
+/// - quantifiers: +, *, ?, and {n}
+///
+/// Anything outside this subset never matches. `#[derive(Validate)]` keeps
+/// one `Pattern` per field in a static, so each pattern is compiled once.
+///
+/// # Examples
+///
+/// ```
+/// use fastapi_core::validation::Pattern;
+///
+/// let sku = Pattern::new(r"^[A-Z]{3}-\d+$");
+/// assert!(sku.is_valid());
+/// assert!(sku.is_match("ABC-123"));
+/// assert!(!sku.is_match("abc-123"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    source: String,
+    matcher: Matcher,
+}
 
-    // Fast path: exact match patterns.
-    if pattern.starts_with('^') && pattern.ends_with('$') {
-        let inner = &pattern[1..pattern.len() - 1];
-        // If no special chars, it's an exact match
-        if !inner.contains(['[', ']', '*', '+', '?', '\\', '(', ')', '|', '.']) {
-            return value == inner;
+#[derive(Debug, Clone)]
+enum Matcher {
+    Any,
+    Exact(String),
+    Regex(SimpleRegex),
+    Invalid,
+}
+
+impl Pattern {
+    /// Compile a pattern.
+    #[must_use]
+    pub fn new(pattern: &str) -> Self {
+        let matcher = if pattern.is_empty() {
+            Matcher::Any
+        } else if let Some(inner) = pattern
+            .strip_prefix('^')
+            .and_then(|rest| rest.strip_suffix('$'))
+            .filter(|inner| !inner.contains(['[', ']', '*', '+', '?', '\\', '(', ')', '|', '.']))
+        {
+            // Fast path: exact match patterns.
+            Matcher::Exact(inner.to_string())
+        } else {
+            match SimpleRegex::compile(pattern) {
+                Ok(regex) => Matcher::Regex(regex),
+                Err(()) => Matcher::Invalid,
+            }
+        };
+        Self {
+            source: pattern.to_string(),
+            matcher,
         }
     }
 
-    let Ok(compiled) = SimpleRegex::compile(pattern) else {
-        return false;
-    };
+    /// The pattern source as written.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the pattern is within the supported subset.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        !matches!(self.matcher, Matcher::Invalid)
+    }
 
-    compiled.is_match(value)
+    /// Check whether `value` matches the pattern.
+    #[must_use]
+    pub fn is_match(&self, value: &str) -> bool {
+        match &self.matcher {
+            Matcher::Any => true,
+            Matcher::Exact(expected) => value == expected,
+            Matcher::Regex(regex) => regex.is_match(value),
+            Matcher::Invalid => false,
+        }
+    }
 }
 
 /// Check if a string is a "reasonably formatted" phone number.
@@ -322,6 +426,339 @@ pub fn is_valid_phone(value: &str) -> bool {
     digits >= 10
 }
 
+/// Check a JSON value against a derived [`Schema`], before deserialization.
+///
+/// Reports every violation, each with a `loc` path below `loc` (e.g.
+/// `["body", "items", 0, "name"]`): missing required properties, mistyped
+/// values, values outside an `enum`, violated length/range/pattern
+/// constraints, and `email`/`uri` formats. Objects that list properties are
+/// closed: keys they don't declare are rejected as `extra_forbidden` unless
+/// the schema has `additionalProperties`. `$ref`s are not resolved and
+/// accept any value; see [`validate_json_schema_with_components`].
+///
+/// # Errors
+///
+/// Returns the collected errors if `value` does not conform to `schema`.
+pub fn validate_json_schema(
+    value: &Value,
+    schema: &Schema,
+    loc: Vec<LocItem>,
+) -> Result<(), Box<ValidationErrors>> {
+    validate_json_schema_with_components(value, schema, &HashMap::new(), loc)
+}
+
+/// Like [`validate_json_schema`], but resolves `#/components/schemas/...`
+/// `$ref`s against `components`, e.g. the schemas of an OpenAPI document.
+/// References to unknown components still accept any value.
+///
+/// # Errors
+///
+/// Returns the collected errors if `value` does not conform to `schema`.
+pub fn validate_json_schema_with_components(
+    value: &Value,
+    schema: &Schema,
+    components: &HashMap<String, Schema>,
+    loc: Vec<LocItem>,
+) -> Result<(), Box<ValidationErrors>> {
+    let mut errors = ValidationErrors::new();
+    let mut loc = loc;
+    check_schema(value, schema, components, &mut loc, &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Box::new(errors))
+    }
+}
+
+fn check_schema(
+    value: &Value,
+    schema: &Schema,
+    components: &HashMap<String, Schema>,
+    loc: &mut Vec<LocItem>,
+    errors: &mut ValidationErrors,
+) {
+    match schema {
+        Schema::Boolean(true) => {}
+        Schema::Ref(r) => {
+            if let Some(target) = r
+                .reference
+                .strip_prefix("#/components/schemas/")
+                .and_then(|name| components.get(name))
+            {
+                check_schema(value, target, components, loc, errors);
+            }
+        }
+        Schema::Boolean(false) => {
+            errors.push(
+                ValidationError::new(error_types::EXTRA_FORBIDDEN, loc.clone())
+                    .with_msg("Extra inputs are not permitted")
+                    .with_input(value.clone()),
+            );
+        }
+        Schema::Enum(e) => {
+            if !value
+                .as_str()
+                .is_some_and(|s| e.enum_values.iter().any(|v| v == s))
+            {
+                let expected = e
+                    .enum_values
+                    .iter()
+                    .map(|v| format!("'{v}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                errors.push(
+                    ValidationError::new(error_types::ENUM, loc.clone())
+                        .with_msg(format!("Input should be one of {expected}"))
+                        .with_input(value.clone())
+                        .with_ctx_value("expected", serde_json::json!(expected)),
+                );
+            }
+        }
+        Schema::OneOf(o) => {
+            // Accept the first matching alternative; otherwise report the
+            // closest one (fewest errors), which for tagged enums is the
+            // variant whose tag matched.
+            let mut closest: Option<ValidationErrors> = None;
+            for alternative in &o.one_of {
+                let mut attempt = ValidationErrors::new();
+                check_schema(value, alternative, components, loc, &mut attempt);
+                if attempt.is_empty() {
+                    return;
+                }
+                if closest.as_ref().is_none_or(|c| attempt.len() < c.len()) {
+                    closest = Some(attempt);
+                }
+            }
+            if let Some(closest) = closest {
+                errors.extend(closest.errors);
+            }
+        }
+        Schema::AllOf(a) => match merged_object(&a.all_of, components) {
+            Some(merged) => check_object(value, &merged, components, loc, errors),
+            None => {
+                for part in &a.all_of {
+                    check_schema(value, part, components, loc, errors);
+                }
+            }
+        },
+        Schema::Array(a) => {
+            let Some(items) = value.as_array() else {
+                errors.push(
+                    ValidationError::new(error_types::LIST_TYPE, loc.clone())
+                        .with_msg("Input should be a valid list")
+                        .with_input(value.clone()),
+                );
+                return;
+            };
+            if let Some(min) = a.min_items.filter(|&min| items.len() < min) {
+                errors.push(
+                    ValidationError::new(error_types::TOO_SHORT, loc.clone())
+                        .with_msg(format!("List should have at least {min} items"))
+                        .with_ctx_value("min_length", serde_json::json!(min)),
+                );
+            }
+            if let Some(max) = a.max_items.filter(|&max| items.len() > max) {
+                errors.push(
+                    ValidationError::new(error_types::TOO_LONG, loc.clone())
+                        .with_msg(format!("List should have at most {max} items"))
+                        .with_ctx_value("max_length", serde_json::json!(max)),
+                );
+            }
+            for (idx, item) in items.iter().enumerate() {
+                let item_schema = a.prefix_items.get(idx).unwrap_or(&a.items);
+                loc.push(LocItem::index(idx));
+                check_schema(item, item_schema, components, loc, errors);
+                loc.pop();
+            }
+        }
+        Schema::Primitive(p) => check_primitive(value, p, loc, errors),
+        Schema::Object(o) => check_object(value, o, components, loc, errors),
+    }
+}
+
+fn check_primitive(
+    value: &Value,
+    p: &PrimitiveSchema,
+    loc: &mut Vec<LocItem>,
+    errors: &mut ValidationErrors,
+) {
+    if value.is_null() && p.nullable {
+        return;
+    }
+    let type_ok = match p.schema_type {
+        SchemaType::String => value.is_string(),
+        SchemaType::Integer => value.is_i64() || value.is_u64(),
+        SchemaType::Number => value.is_number(),
+        SchemaType::Boolean => value.is_boolean(),
+        SchemaType::Null => value.is_null(),
+    };
+    if !type_ok {
+        let expected = match p.schema_type {
+            SchemaType::String => "string",
+            SchemaType::Integer => "integer",
+            SchemaType::Number => "number",
+            SchemaType::Boolean => "boolean",
+            SchemaType::Null => "null",
+        };
+        errors.push(ValidationError::type_error(loc.clone(), expected).with_input(value.clone()));
+        return;
+    }
+
+    let c = &p.constraints;
+    if let Some(text) = value.as_str() {
+        let len = text.chars().count();
+        if let Some(min) = c.min_length.filter(|&min| len < min) {
+            errors.push(
+                ValidationError::string_too_short(loc.clone(), min).with_input(value.clone()),
+            );
+        }
+        if let Some(max) = c.max_length.filter(|&max| len > max) {
+            errors
+                .push(ValidationError::string_too_long(loc.clone(), max).with_input(value.clone()));
+        }
+        if let Some(pattern) = c
+            .pattern
+            .as_deref()
+            .filter(|pattern| !matches_pattern(text, pattern))
+        {
+            errors.push(
+                ValidationError::pattern_mismatch(loc.clone(), pattern).with_input(value.clone()),
+            );
+        }
+        match p.format.as_deref() {
+            Some("email") if !is_valid_email(text) => {
+                errors.push(ValidationError::invalid_email(loc.clone()).with_input(value.clone()));
+            }
+            Some("uri" | "url") if !is_valid_url(text) => {
+                errors.push(ValidationError::invalid_url(loc.clone()).with_input(value.clone()));
+            }
+            _ => {}
+        }
+    }
+    if let Some(number) = value.as_f64() {
+        if let Some(min) = c.minimum.filter(|&min| number < min) {
+            errors.push(
+                ValidationError::greater_than_equal(loc.clone(), min).with_input(value.clone()),
+            );
+        }
+        if let Some(max) = c.maximum.filter(|&max| number > max) {
+            errors
+                .push(ValidationError::less_than_equal(loc.clone(), max).with_input(value.clone()));
+        }
+        if let Some(min) = c.exclusive_minimum.filter(|&min| number <= min) {
+            errors.push(
+                ValidationError::value_error(
+                    loc.clone(),
+                    format!("Input should be greater than {min}"),
+                )
+                .with_input(value.clone()),
+            );
+        }
+        if let Some(max) = c.exclusive_maximum.filter(|&max| number >= max) {
+            errors.push(
+                ValidationError::value_error(
+                    loc.clone(),
+                    format!("Input should be less than {max}"),
+                )
+                .with_input(value.clone()),
+            );
+        }
+        if let Some(step) = c
+            .multiple_of
+            .filter(|&step| step > 0.0 && !is_multiple(number, step))
+        {
+            errors.push(
+                ValidationError::value_error(
+                    loc.clone(),
+                    format!("Input should be a multiple of {step}"),
+                )
+                .with_input(value.clone()),
+            );
+        }
+    }
+}
+
+/// Whether `number` is an integer multiple of `step`, within float rounding.
+fn is_multiple(number: f64, step: f64) -> bool {
+    let quotient = number / step;
+    (quotient - quotient.round()).abs() < 1e-9
+}
+
+/// Merge `allOf` parts that are all objects into one, so properties of one
+/// part don't count as unknown keys for another.
+fn merged_object(parts: &[Schema], components: &HashMap<String, Schema>) -> Option<ObjectSchema> {
+    let mut merged = ObjectSchema::default();
+    for part in parts {
+        let part = match part {
+            Schema::Ref(r) => components.get(r.reference.strip_prefix("#/components/schemas/")?)?,
+            other => other,
+        };
+        let Schema::Object(o) = part else {
+            return None;
+        };
+        merged
+            .properties
+            .extend(o.properties.iter().map(|(k, v)| (k.clone(), v.clone())));
+        merged.required.extend(o.required.iter().cloned());
+        if o.additional_properties.is_some() {
+            merged
+                .additional_properties
+                .clone_from(&o.additional_properties);
+        }
+    }
+    Some(merged)
+}
+
+fn check_object(
+    value: &Value,
+    o: &ObjectSchema,
+    components: &HashMap<String, Schema>,
+    loc: &mut Vec<LocItem>,
+    errors: &mut ValidationErrors,
+) {
+    let Some(map) = value.as_object() else {
+        errors.push(
+            ValidationError::new(error_types::DICT_TYPE, loc.clone())
+                .with_msg("Input should be a valid dictionary or object")
+                .with_input(value.clone()),
+        );
+        return;
+    };
+
+    for name in &o.required {
+        if !map.contains_key(name) {
+            loc.push(LocItem::field(name));
+            errors.push(ValidationError::missing(loc.clone()));
+            loc.pop();
+        }
+    }
+
+    for (key, field_value) in map {
+        loc.push(LocItem::field(key));
+        match o.properties.get(key) {
+            // An explicit null is how clients clear an optional field.
+            Some(_) if field_value.is_null() && !o.required.contains(key) => {}
+            Some(property) => check_schema(field_value, property, components, loc, errors),
+            None => match &o.additional_properties {
+                Some(additional) => {
+                    check_schema(field_value, additional, components, loc, errors);
+                }
+                None if !o.properties.is_empty() => {
+                    check_schema(
+                        field_value,
+                        &Schema::Boolean(false),
+                        components,
+                        loc,
+                        errors,
+                    );
+                }
+                None => {}
+            },
+        }
+        loc.pop();
+    }
+}
+
 #[derive(Debug, Clone)]
 struct SimpleRegex {
     anchored_start: bool,
@@ -497,8 +934,6 @@ impl SimpleRegex {
     }
 
     fn is_match_at(&self, s: &[char], start: usize) -> bool {
-        use std::collections::HashMap;
-
         fn dp(
             tokens: &[Token],
             s: &[char],
@@ -667,4 +1102,149 @@ mod tests {
         assert!(!matches_pattern("hello", "^world$"));
         assert!(matches_pattern("abc", "abc"));
     }
+
+    #[test]
+    fn test_compiled_pattern() {
+        let sku = Pattern::new(r"^[A-Z]{3}-\d+$");
+        assert!(sku.is_valid());
+        assert_eq!(sku.as_str(), r"^[A-Z]{3}-\d+$");
+        assert!(sku.is_match("ABC-1"));
+        assert!(sku.is_match("XYZ-2048"));
+        assert!(!sku.is_match("AB-1"));
+        assert!(!sku.is_match("ABC-"));
+
+        assert!(Pattern::new("").is_match("anything"));
+        assert!(Pattern::new("^exact$").is_match("exact"));
+
+        let unsupported = Pattern::new("(a|b)");
+        assert!(!unsupported.is_valid());
+        assert!(!unsupported.is_match("a"));
+    }
+
+    fn schema_errors(value: &Value, schema: &Schema) -> Vec<(&'static str, Vec<LocItem>)> {
+        match validate_json_schema(value, schema, vec![]) {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors
+                .iter()
+                .map(|e| (e.error_type, e.loc.clone()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_schema_constraints_and_nullable() {
+        use fastapi_openapi::SchemaConstraints;
+
+        let schema = Schema::integer(None).with_constraints(SchemaConstraints {
+            minimum: Some(1.0),
+            exclusive_maximum: Some(10.0),
+            multiple_of: Some(0.5),
+            ..SchemaConstraints::default()
+        });
+        assert!(schema_errors(&serde_json::json!(5), &schema).is_empty());
+        assert_eq!(
+            schema_errors(&serde_json::json!(0), &schema),
+            [(error_types::GREATER_THAN_EQUAL, Vec::new())]
+        );
+        assert_eq!(
+            schema_errors(&serde_json::json!(10), &schema),
+            [(error_types::VALUE_ERROR, Vec::new())]
+        );
+        assert_eq!(
+            schema_errors(&serde_json::json!(2.5), &schema),
+            [(error_types::INT_TYPE, Vec::new())]
+        );
+        assert_eq!(
+            schema_errors(&Value::Null, &schema),
+            [(error_types::INT_TYPE, Vec::new())]
+        );
+        assert!(schema_errors(&Value::Null, &schema.nullable()).is_empty());
+    }
+
+    #[test]
+    fn test_schema_additional_properties() {
+        let mut open = fastapi_openapi::ObjectSchema::default();
+        open.additional_properties = Some(Box::new(Schema::integer(None)));
+        let schema = Schema::Object(open);
+        assert!(schema_errors(&serde_json::json!({"a": 1, "b": 2}), &schema).is_empty());
+        assert_eq!(
+            schema_errors(&serde_json::json!({"a": "x"}), &schema),
+            [(error_types::INT_TYPE, vec![LocItem::field("a")])]
+        );
+
+        // Free-form objects accept anything.
+        let free = Schema::Object(fastapi_openapi::ObjectSchema::default());
+        assert!(schema_errors(&serde_json::json!({"anything": [1]}), &free).is_empty());
+    }
+
+    #[test]
+    fn test_schema_one_of_reports_closest_alternative() {
+        let circle = Schema::object(
+            [("radius".to_string(), Schema::number(None))].into(),
+            vec!["radius".to_string()],
+        );
+        let square = Schema::object(
+            [
+                ("side".to_string(), Schema::number(None)),
+                ("label".to_string(), Schema::string()),
+            ]
+            .into(),
+            vec!["side".to_string(), "label".to_string()],
+        );
+        let schema = Schema::OneOf(fastapi_openapi::OneOfSchema {
+            one_of: vec![circle, square],
+            discriminator: None,
+        });
+
+        assert!(schema_errors(&serde_json::json!({"radius": 2}), &schema).is_empty());
+        assert_eq!(
+            schema_errors(&serde_json::json!({"side": 2, "label": 3}), &schema),
+            [(error_types::STRING_TYPE, vec![LocItem::field("label")])]
+        );
+    }
+
+    #[test]
+    fn test_schema_tuples_and_all_of() {
+        let pair = Schema::tuple(vec![Schema::integer(None), Schema::string()]);
+        assert!(schema_errors(&serde_json::json!([1, "a"]), &pair).is_empty());
+        assert_eq!(
+            schema_errors(&serde_json::json!(["a", "b"]), &pair),
+            [(error_types::INT_TYPE, vec![LocItem::index(0)])]
+        );
+
+        let tagged = Schema::all_of(vec![
+            Schema::object(
+                [("id".to_string(), Schema::integer(None))].into(),
+                vec!["id".to_string()],
+            ),
+            Schema::object(
+                [("kind".to_string(), Schema::string())].into(),
+                vec!["kind".to_string()],
+            ),
+        ]);
+        assert!(schema_errors(&serde_json::json!({"id": 1, "kind": "x"}), &tagged).is_empty());
+        assert_eq!(
+            schema_errors(&serde_json::json!({"id": 1}), &tagged),
+            [(error_types::MISSING, vec![LocItem::field("kind")])]
+        );
+    }
+
+    #[test]
+    fn test_schema_refs_resolve_against_components() {
+        let mut item = fastapi_openapi::ObjectSchema::default();
+        item.properties
+            .insert("qty".to_string(), Schema::integer(None));
+        let components = HashMap::from([("Item".to_string(), Schema::Object(item))]);
+        let schema = Schema::array(Schema::reference("Item"));
+        let value = serde_json::json!([{"qty": 1}, {"qty": "two"}]);
+
+        // Without components, the reference accepts anything.
+        assert!(schema_errors(&value, &schema).is_empty());
+        let errors =
+            validate_json_schema_with_components(&value, &schema, &components, vec![]).unwrap_err();
+        assert_eq!(
+            errors.iter().map(|e| e.loc.clone()).collect::<Vec<_>>(),
+            [vec![LocItem::index(1), LocItem::field("qty")]]
+        );
+    }
 }
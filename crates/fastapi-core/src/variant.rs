@@ -0,0 +1,693 @@
+//! Declarative request matching for A/B variants.
+//!
+//! A [`VariantRouter`] serves several alternative handlers behind a single
+//! method + path. Each request is assigned to exactly one variant, either by
+//! weighted bucketing or by an explicit header/cookie, and the assignment is:
+//!
+//! - stored on the request as a [`VariantAssignment`] extension (also usable as
+//!   an extractor),
+//! - echoed on the response in a configurable header (default `x-variant`),
+//! - counted in per-variant [`VariantMetrics`], and in the app's
+//!   [`MetricsRegistry`](crate::metrics::MetricsRegistry) as
+//!   `variant_assignments_total{experiment, variant}` when one is served with
+//!   [`AppBuilder::metrics`](crate::AppBuilder::metrics).
+//!
+//! # Example
+//!
+//! ```ignore
+//! use fastapi_core::{App, Method, VariantRouter, VariantStrategy};
+//!
+//! let checkout = VariantRouter::new("checkout-redesign")
+//!     .variant("control", 90, old_checkout)
+//!     .variant("redesign", 10, new_checkout)
+//!     .strategy(VariantStrategy::Cookie("ab_checkout".into()));
+//!
+//! let metrics = checkout.metrics();
+//!
+//! let app = App::builder()
+//!     .variants("/checkout", Method::Post, checkout)
+//!     .build();
+//!
+//! // Later: metrics.count("redesign")
+//! ```
+//!
+//! Bucketing is deterministic: the same bucket key (request id, header value
+//! or cookie value) always lands on the same variant for a given experiment.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use crate::app::{BoxHandler, RouteEntry};
+use crate::context::RequestContext;
+use crate::error::HttpError;
+use crate::extract::FromRequest;
+use crate::metrics::{Counter, MetricsRegistry};
+use crate::request::{Method, Request};
+use crate::response::{IntoResponse, Response, SetCookie};
+
+/// Default response header used to report the assigned variant.
+pub const DEFAULT_VARIANT_HEADER: &str = "x-variant";
+
+// ============================================================================
+// Strategy and assignment
+// ============================================================================
+
+/// How a request is assigned to a variant.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum VariantStrategy {
+    /// Split traffic by weight, bucketing on the request id.
+    #[default]
+    Weighted,
+    /// Bucket on the value of a request header.
+    ///
+    /// A header value equal to a variant name selects that variant directly;
+    /// any other value is hashed into the weighted buckets. Requests without
+    /// the header fall back to [`VariantStrategy::Weighted`].
+    Header(String),
+    /// Bucket on the value of a request cookie.
+    ///
+    /// Matching works like [`VariantStrategy::Header`]. When the cookie is
+    /// absent, the assigned variant is written back with `Set-Cookie` so the
+    /// client stays in the same variant on subsequent requests.
+    Cookie(String),
+}
+
+/// How the variant for a request was chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignmentSource {
+    /// Chosen by hashing a bucket key into the weighted buckets.
+    Bucketed,
+    /// Named explicitly by the client via header or cookie.
+    Forced,
+}
+
+/// The variant a request was assigned to.
+///
+/// Inserted into request extensions before the variant handler runs, so
+/// handlers can read it with `req.get_extension::<VariantAssignment>()` or
+/// take it as an extractor argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantAssignment {
+    /// The experiment name given to [`VariantRouter::new`].
+    pub experiment: String,
+    /// The name of the assigned variant.
+    pub variant: String,
+    /// How the variant was chosen.
+    pub source: AssignmentSource,
+}
+
+/// Error returned when a [`VariantAssignment`] is extracted outside a variant route.
+#[derive(Debug, Clone, Copy)]
+pub struct VariantExtractError;
+
+impl std::fmt::Display for VariantExtractError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Variant assignment not found; route is not served by a VariantRouter"
+        )
+    }
+}
+
+impl std::error::Error for VariantExtractError {}
+
+impl IntoResponse for VariantExtractError {
+    fn into_response(self) -> Response {
+        // Missing assignment is a routing configuration error (500)
+        HttpError::internal()
+            .with_detail(self.to_string())
+            .into_response()
+    }
+}
+
+impl FromRequest for VariantAssignment {
+    type Error = VariantExtractError;
+
+    async fn from_request(_ctx: &RequestContext, req: &mut Request) -> Result<Self, Self::Error> {
+        req.get_extension::<VariantAssignment>()
+            .cloned()
+            .ok_or(VariantExtractError)
+    }
+}
+
+// ============================================================================
+// Metrics
+// ============================================================================
+
+/// Name of the assignment counter exported to the app's metrics registry.
+pub const VARIANT_ASSIGNMENTS_METRIC: &str = "variant_assignments_total";
+
+/// Shared per-variant assignment counters.
+///
+/// Obtain a handle with [`VariantRouter::metrics`] at any point; variants
+/// added afterwards show up in it too, and the handle stays valid for the
+/// lifetime of the application.
+#[derive(Debug, Clone)]
+pub struct VariantMetrics {
+    experiment: Arc<str>,
+    counters: Arc<RwLock<Vec<(String, Counter)>>>,
+}
+
+impl VariantMetrics {
+    fn new(experiment: Arc<str>) -> Self {
+        Self {
+            experiment,
+            counters: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    /// Registers a variant and returns the counter its assignments go to.
+    fn add(&self, variant: &str) -> Counter {
+        let counter = Counter::default();
+        self.counters
+            .write()
+            .push((variant.to_string(), counter.clone()));
+        counter
+    }
+
+    /// Makes the counters the `variant_assignments_total` series of
+    /// `registry`, keeping the counts recorded so far.
+    pub(crate) fn export(&self, registry: &MetricsRegistry) {
+        let family = registry.counter_family(
+            VARIANT_ASSIGNMENTS_METRIC,
+            "Requests assigned to each experiment variant.",
+            &["experiment", "variant"],
+        );
+        for (name, counter) in self.counters.read().iter() {
+            family.adopt(&[&*self.experiment, name.as_str()], counter.clone());
+        }
+    }
+
+    /// Returns the experiment name.
+    #[must_use]
+    pub fn experiment(&self) -> &str {
+        &self.experiment
+    }
+
+    /// Returns the number of requests assigned to `variant`, if it exists.
+    #[must_use]
+    pub fn count(&self, variant: &str) -> Option<u64> {
+        self.counters
+            .read()
+            .iter()
+            .find(|(name, _)| name == variant)
+            .map(|(_, counter)| counter.get())
+    }
+
+    /// Returns the total number of assigned requests across all variants.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.counters.read().iter().map(|(_, c)| c.get()).sum()
+    }
+
+    /// Returns `(variant, count)` pairs in registration order.
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<(String, u64)> {
+        self.counters
+            .read()
+            .iter()
+            .map(|(name, counter)| (name.clone(), counter.get()))
+            .collect()
+    }
+}
+
+// ============================================================================
+// Router
+// ============================================================================
+
+struct Variant {
+    name: String,
+    weight: u32,
+    handler: Arc<BoxHandler>,
+    assignments: Counter,
+}
+
+/// Routes traffic for one method + path to alternative handlers.
+///
+/// Build with [`VariantRouter::new`] and [`VariantRouter::variant`], then
+/// register it with [`crate::AppBuilder::variants`] or convert it with
+/// [`VariantRouter::into_route_entry`].
+pub struct VariantRouter {
+    experiment: Arc<str>,
+    variants: Vec<Variant>,
+    strategy: VariantStrategy,
+    response_header: Option<String>,
+    cookie_max_age: Option<i64>,
+    metrics: VariantMetrics,
+}
+
+impl std::fmt::Debug for VariantRouter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let variants: Vec<(&str, u32)> = self
+            .variants
+            .iter()
+            .map(|v| (v.name.as_str(), v.weight))
+            .collect();
+        f.debug_struct("VariantRouter")
+            .field("experiment", &self.experiment)
+            .field("variants", &variants)
+            .field("strategy", &self.strategy)
+            .field("response_header", &self.response_header)
+            .finish_non_exhaustive()
+    }
+}
+
+impl VariantRouter {
+    /// Creates an empty router for the named experiment.
+    #[must_use]
+    pub fn new(experiment: impl Into<String>) -> Self {
+        let experiment: Arc<str> = Arc::from(experiment.into());
+        Self {
+            metrics: VariantMetrics::new(Arc::clone(&experiment)),
+            experiment,
+            variants: Vec::new(),
+            strategy: VariantStrategy::default(),
+            response_header: Some(DEFAULT_VARIANT_HEADER.to_string()),
+            cookie_max_age: None,
+        }
+    }
+
+    /// Adds a variant with the given relative weight.
+    ///
+    /// Weights are relative to the sum of all weights; a weight of `0` keeps
+    /// the variant reachable only when forced by header or cookie.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a variant with the same name was already added.
+    #[must_use]
+    pub fn variant<H, Fut>(mut self, name: impl Into<String>, weight: u32, handler: H) -> Self
+    where
+        H: Fn(&RequestContext, &mut Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Response> + Send + 'static,
+    {
+        let name = name.into();
+        assert!(
+            self.variants.iter().all(|v| v.name != name),
+            "duplicate variant name: {name}"
+        );
+        let handler: BoxHandler = Box::new(move |ctx, req| Box::pin(handler(ctx, req)));
+        let assignments = self.metrics.add(&name);
+        self.variants.push(Variant {
+            name,
+            weight,
+            handler: Arc::new(handler),
+            assignments,
+        });
+        self
+    }
+
+    /// Sets the assignment strategy.
+    #[must_use]
+    pub fn strategy(mut self, strategy: VariantStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the response header used to report the assigned variant.
+    #[must_use]
+    pub fn response_header(mut self, name: impl Into<String>) -> Self {
+        self.response_header = Some(name.into());
+        self
+    }
+
+    /// Disables the variant response header.
+    #[must_use]
+    pub fn without_response_header(mut self) -> Self {
+        self.response_header = None;
+        self
+    }
+
+    /// Sets `Max-Age` (in seconds) on the sticky cookie written by
+    /// [`VariantStrategy::Cookie`]. Defaults to a session cookie.
+    #[must_use]
+    pub fn cookie_max_age(mut self, seconds: i64) -> Self {
+        self.cookie_max_age = Some(seconds);
+        self
+    }
+
+    /// Returns the experiment name.
+    #[must_use]
+    pub fn experiment(&self) -> &str {
+        &self.experiment
+    }
+
+    /// Returns a handle to this router's assignment counters.
+    #[must_use]
+    pub fn metrics(&self) -> VariantMetrics {
+        self.metrics.clone()
+    }
+
+    /// Converts the router into a [`RouteEntry`] for `method` and `path`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no variants were added.
+    #[must_use]
+    pub fn into_route_entry(self, method: Method, path: impl Into<String>) -> RouteEntry {
+        assert!(
+            !self.variants.is_empty(),
+            "VariantRouter `{}` has no variants",
+            self.experiment
+        );
+        let router = Arc::new(self);
+        RouteEntry::new(
+            method,
+            path,
+            move |ctx: &RequestContext, req: &mut Request| router.dispatch(ctx, req),
+        )
+    }
+
+    fn dispatch(
+        &self,
+        ctx: &RequestContext,
+        req: &mut Request,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send>> {
+        let (index, source, cookie_present) = self.assign(ctx, req);
+        let variant = &self.variants[index];
+        variant.assignments.inc();
+
+        req.insert_extension(VariantAssignment {
+            experiment: self.experiment.to_string(),
+            variant: variant.name.clone(),
+            source,
+        });
+
+        let set_cookie = match &self.strategy {
+            VariantStrategy::Cookie(cookie) if !cookie_present => {
+                let mut c = SetCookie::new(cookie.clone(), variant.name.clone());
+                if let Some(max_age) = self.cookie_max_age {
+                    c = c.max_age(max_age);
+                }
+                Some(c)
+            }
+            _ => None,
+        };
+        let header = self.response_header.clone();
+        let name = variant.name.clone();
+        let fut = (variant.handler)(ctx, req);
+
+        Box::pin(async move {
+            let mut response = fut.await;
+            if let Some(header) = header {
+                response = response.header(header, name.into_bytes());
+            }
+            if let Some(cookie) = set_cookie {
+                response = response.set_cookie(cookie);
+            }
+            response
+        })
+    }
+
+    /// Picks a variant index. Also reports whether the strategy cookie was present.
+    fn assign(&self, ctx: &RequestContext, req: &Request) -> (usize, AssignmentSource, bool) {
+        let key = match &self.strategy {
+            VariantStrategy::Weighted => None,
+            VariantStrategy::Header(name) => req
+                .headers()
+                .get(name)
+                .and_then(|v| std::str::from_utf8(v).ok())
+                .map(|v| v.trim().to_string()),
+            VariantStrategy::Cookie(name) => req
+                .headers()
+                .get("cookie")
+                .and_then(|v| std::str::from_utf8(v).ok())
+                .and_then(|v| find_cookie(v, name)),
+        };
+        let cookie_present = key.is_some();
+
+        if let Some(key) = key.as_deref() {
+            if let Some(index) = self.variants.iter().position(|v| v.name == key) {
+                return (index, AssignmentSource::Forced, cookie_present);
+            }
+            let hash = fnv1a(&self.experiment, key.as_bytes());
+            return (
+                self.bucket(hash),
+                AssignmentSource::Bucketed,
+                cookie_present,
+            );
+        }
+
+        let hash = fnv1a(&self.experiment, &ctx.request_id().to_le_bytes());
+        (
+            self.bucket(hash),
+            AssignmentSource::Bucketed,
+            cookie_present,
+        )
+    }
+
+    /// Maps a hash onto the cumulative weight ranges.
+    fn bucket(&self, hash: u64) -> usize {
+        let total: u64 = self.variants.iter().map(|v| u64::from(v.weight)).sum();
+        if total == 0 {
+            return 0;
+        }
+        let mut point = hash % total;
+        for (i, variant) in self.variants.iter().enumerate() {
+            let weight = u64::from(variant.weight);
+            if point < weight {
+                return i;
+            }
+            point -= weight;
+        }
+        self.variants.len() - 1
+    }
+}
+
+/// Finds a cookie value by name in a `Cookie` header.
+fn find_cookie(header: &str, name: &str) -> Option<String> {
+    header.split(';').find_map(|pair| {
+        let (k, v) = pair.trim().split_once('=')?;
+        (k.trim() == name).then(|| v.trim().to_string())
+    })
+}
+
+/// FNV-1a over the experiment name and bucket key.
+///
+/// Salting with the experiment name keeps assignments independent across
+/// experiments that share the same bucket key.
+fn fnv1a(experiment: &str, key: &[u8]) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for byte in experiment.as_bytes().iter().chain([0u8].iter()).chain(key) {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::App;
+    use crate::response::ResponseBody;
+
+    fn test_context(id: u64) -> RequestContext {
+        RequestContext::new(asupersync::Cx::for_testing(), id)
+    }
+
+    fn control(_ctx: &RequestContext, _req: &mut Request) -> std::future::Ready<Response> {
+        std::future::ready(Response::ok().body(ResponseBody::Bytes(b"control".to_vec())))
+    }
+
+    fn treatment(_ctx: &RequestContext, _req: &mut Request) -> std::future::Ready<Response> {
+        std::future::ready(Response::ok().body(ResponseBody::Bytes(b"treatment".to_vec())))
+    }
+
+    fn body_text(response: &Response) -> String {
+        match response.body_ref() {
+            ResponseBody::Bytes(b) => String::from_utf8_lossy(b).into_owned(),
+            _ => String::new(),
+        }
+    }
+
+    fn header_value(response: &Response, name: &str) -> Option<String> {
+        response
+            .headers()
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| String::from_utf8_lossy(v).into_owned())
+    }
+
+    fn run(entry: &RouteEntry, id: u64, req: &mut Request) -> Response {
+        let ctx = test_context(id);
+        futures_executor::block_on(entry.call(&ctx, req))
+    }
+
+    #[test]
+    fn weighted_split_is_roughly_proportional() {
+        let router = VariantRouter::new("exp")
+            .variant("control", 50, control)
+            .variant("treatment", 50, treatment);
+        let metrics = router.metrics();
+        let entry = router.into_route_entry(Method::Get, "/");
+
+        for id in 0..1000 {
+            let mut req = Request::new(Method::Get, "/");
+            let response = run(&entry, id, &mut req);
+            assert_eq!(
+                header_value(&response, "x-variant"),
+                Some(body_text(&response))
+            );
+        }
+
+        assert_eq!(metrics.total(), 1000);
+        assert!(metrics.count("control").unwrap() > 350);
+        assert!(metrics.count("treatment").unwrap() > 350);
+    }
+
+    #[test]
+    fn zero_weight_variant_is_never_bucketed() {
+        let entry = VariantRouter::new("exp")
+            .variant("control", 1, control)
+            .variant("treatment", 0, treatment)
+            .into_route_entry(Method::Get, "/");
+
+        for id in 0..200 {
+            let mut req = Request::new(Method::Get, "/");
+            assert_eq!(body_text(&run(&entry, id, &mut req)), "control");
+        }
+    }
+
+    #[test]
+    fn header_naming_a_variant_forces_it() {
+        let entry = VariantRouter::new("exp")
+            .variant("control", 1, control)
+            .variant("treatment", 0, treatment)
+            .strategy(VariantStrategy::Header("x-ab".into()))
+            .into_route_entry(Method::Get, "/");
+
+        let mut req = Request::new(Method::Get, "/");
+        req.headers_mut().insert("x-ab", b"treatment".to_vec());
+        assert_eq!(body_text(&run(&entry, 1, &mut req)), "treatment");
+    }
+
+    #[test]
+    fn header_bucket_is_stable_per_value() {
+        let entry = VariantRouter::new("exp")
+            .variant("control", 1, control)
+            .variant("treatment", 1, treatment)
+            .strategy(VariantStrategy::Header("x-user".into()))
+            .into_route_entry(Method::Get, "/");
+
+        let mut first = Request::new(Method::Get, "/");
+        first.headers_mut().insert("x-user", b"user-42".to_vec());
+        let expected = body_text(&run(&entry, 1, &mut first));
+
+        for id in 2..20 {
+            let mut req = Request::new(Method::Get, "/");
+            req.headers_mut().insert("x-user", b"user-42".to_vec());
+            assert_eq!(body_text(&run(&entry, id, &mut req)), expected);
+        }
+    }
+
+    #[test]
+    fn cookie_strategy_sets_sticky_cookie_only_when_absent() {
+        let entry = VariantRouter::new("exp")
+            .variant("control", 1, control)
+            .variant("treatment", 1, treatment)
+            .strategy(VariantStrategy::Cookie("ab".into()))
+            .into_route_entry(Method::Get, "/");
+
+        let mut req = Request::new(Method::Get, "/");
+        let response = run(&entry, 7, &mut req);
+        let cookie = header_value(&response, "set-cookie").expect("sticky cookie");
+        assert!(cookie.starts_with(&format!("ab={}", body_text(&response))));
+
+        let mut req = Request::new(Method::Get, "/");
+        req.headers_mut()
+            .insert("cookie", b"session=x; ab=treatment".to_vec());
+        let response = run(&entry, 8, &mut req);
+        assert_eq!(body_text(&response), "treatment");
+        assert!(header_value(&response, "set-cookie").is_none());
+    }
+
+    #[test]
+    fn assignment_is_exposed_to_handler() {
+        let entry = VariantRouter::new("exp")
+            .variant("only", 1, |_ctx: &RequestContext, req: &mut Request| {
+                let assignment = req
+                    .get_extension::<VariantAssignment>()
+                    .cloned()
+                    .expect("assignment");
+                let text = format!("{}:{}", assignment.experiment, assignment.variant);
+                std::future::ready(Response::ok().body(ResponseBody::Bytes(text.into_bytes())))
+            })
+            .without_response_header()
+            .into_route_entry(Method::Get, "/");
+
+        let mut req = Request::new(Method::Get, "/");
+        let response = run(&entry, 1, &mut req);
+        assert_eq!(body_text(&response), "exp:only");
+        assert!(header_value(&response, "x-variant").is_none());
+    }
+
+    #[test]
+    fn extractor_fails_outside_variant_route() {
+        let ctx = test_context(1);
+        let mut req = Request::new(Method::Get, "/");
+        let result = futures_executor::block_on(VariantAssignment::from_request(&ctx, &mut req));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn app_builder_registers_variant_route() {
+        let app = App::builder()
+            .variants(
+                "/checkout",
+                Method::Get,
+                VariantRouter::new("checkout")
+                    .variant("control", 0, control)
+                    .variant("treatment", 1, treatment)
+                    .response_header("x-experiment-variant"),
+            )
+            .build();
+
+        let ctx = test_context(1);
+        let mut req = Request::new(Method::Get, "/checkout");
+        let response = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(body_text(&response), "treatment");
+        assert_eq!(
+            header_value(&response, "x-experiment-variant").as_deref(),
+            Some("treatment")
+        );
+    }
+
+    #[test]
+    fn metrics_handle_is_order_independent_and_exported() {
+        let router = VariantRouter::new("checkout");
+        let metrics = router.metrics();
+        let router = router
+            .variant("control", 0, control)
+            .variant("treatment", 1, treatment);
+        let registry = MetricsRegistry::new();
+        let app = App::builder()
+            .variants("/checkout", Method::Get, router)
+            .metrics("/metrics", registry.clone())
+            .build();
+
+        let ctx = test_context(1);
+        let mut req = Request::new(Method::Get, "/checkout");
+        let _ = futures_executor::block_on(app.handle(&ctx, &mut req));
+        assert_eq!(
+            metrics.snapshot(),
+            vec![("control".to_string(), 0), ("treatment".to_string(), 1)]
+        );
+        assert!(registry.render().contains(
+            "variant_assignments_total{experiment=\"checkout\",variant=\"treatment\"} 1"
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate variant name")]
+    fn duplicate_variant_names_panic() {
+        let _ = VariantRouter::new("exp")
+            .variant("a", 1, control)
+            .variant("a", 1, treatment);
+    }
+}
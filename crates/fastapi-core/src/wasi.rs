@@ -0,0 +1,354 @@
+//! Running an [`App`] as a WASI HTTP handler.
+//!
+//! Serverless WebAssembly platforms that follow WAGI (Spin, wasmtime's WAGI
+//! mode, and others) start one instance of a `wasm32-wasip1` module per
+//! request and speak CGI (RFC 3875) to it:
+//!
+//! - the request line and headers arrive as environment variables
+//!   (`REQUEST_METHOD`, `PATH_INFO`, `QUERY_STRING`, `HTTP_*`, ...);
+//! - the request body is read from stdin;
+//! - the response is written to stdout as CGI header lines (`Status:` first),
+//!   a blank line, then the body.
+//!
+//! The request goes through the same router, extractors and middleware as on
+//! the native server. Streaming bodies are written chunk by chunk as they are
+//! produced.
+//!
+//! # Example
+//!
+//! ```ignore
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let app = App::builder()
+//!         .get("/hello", |_, _| async { Response::ok().body(ResponseBody::Bytes(b"hi".to_vec())) })
+//!         .build();
+//!
+//!     let rt = asupersync::runtime::RuntimeBuilder::current_thread().build()?;
+//!     rt.block_on(fastapi_core::wasi::serve(&app))?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`App`]: crate::app::App
+
+use std::future::poll_fn;
+use std::io::{self, Read, Write};
+
+use asupersync::Cx;
+use asupersync::stream::Stream;
+
+use crate::app::App;
+use crate::context::RequestContext;
+use crate::middleware::RemoteAddr;
+use crate::request::{Body, ConnectionInfo, HttpVersion, Method, Request};
+use crate::response::{Response, ResponseBody};
+
+/// Error raised while bridging a CGI request to an [`App`].
+#[derive(Debug)]
+pub enum WasiError {
+    /// `REQUEST_METHOD` was not set.
+    MissingMethod,
+    /// `REQUEST_METHOD` is not an HTTP method this crate supports.
+    InvalidMethod(String),
+    /// Reading the request body or writing the response failed.
+    Io(io::Error),
+    /// [`serve`] was called outside an asupersync runtime.
+    NoRuntime,
+}
+
+impl std::fmt::Display for WasiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingMethod => write!(f, "REQUEST_METHOD is not set"),
+            Self::InvalidMethod(method) => write!(f, "unsupported request method: {method}"),
+            Self::Io(err) => write!(f, "CGI I/O failed: {err}"),
+            Self::NoRuntime => write!(f, "wasi::serve must run inside an asupersync runtime"),
+        }
+    }
+}
+
+impl std::error::Error for WasiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for WasiError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Serve the current request: read it from the process environment and
+/// stdin, run it through `app`, and write the response to stdout.
+///
+/// # Errors
+///
+/// Fails if the environment does not describe a request, if stdin or stdout
+/// fail, or if no asupersync runtime is running.
+pub async fn serve(app: &App) -> Result<(), WasiError> {
+    let cx = Cx::current().ok_or(WasiError::NoRuntime)?;
+    let ctx = RequestContext::with_overrides_and_body_limit(
+        cx,
+        1,
+        app.dependency_overrides(),
+        app.config().max_body_size,
+    );
+    let stdout = io::stdout();
+    handle(app, &ctx, std::env::vars(), io::stdin(), &mut stdout.lock()).await
+}
+
+/// Run one CGI request through `app` with explicit environment and streams.
+///
+/// # Errors
+///
+/// Fails if `vars` does not describe a request or if reading `body` or
+/// writing to `out` fails. Handler errors are responses, not errors.
+pub async fn handle<I, R, W>(
+    app: &App,
+    ctx: &RequestContext,
+    vars: I,
+    mut body: R,
+    out: &mut W,
+) -> Result<(), WasiError>
+where
+    I: IntoIterator<Item = (String, String)>,
+    R: Read,
+    W: Write,
+{
+    let mut bytes = Vec::new();
+    body.read_to_end(&mut bytes)?;
+    let mut req = request_from_env(vars, bytes)?;
+    let response = app.handle(ctx, &mut req).await;
+    write_response(response, out).await?;
+    Ok(())
+}
+
+/// Build a [`Request`] from CGI meta-variables and the request body.
+///
+/// The path is `SCRIPT_NAME` followed by `PATH_INFO`, so the router sees the
+/// full path even when the platform mounts the module under a prefix.
+///
+/// # Errors
+///
+/// Fails if `REQUEST_METHOD` is missing or unsupported.
+pub fn request_from_env<I>(vars: I, body: Vec<u8>) -> Result<Request, WasiError>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let mut method = None;
+    let mut script_name = String::new();
+    let mut path_info = String::new();
+    let mut query = None;
+    let mut version = HttpVersion::Http11;
+    let mut remote_addr = None;
+    let mut is_tls = false;
+    let mut headers = Vec::new();
+
+    for (name, value) in vars {
+        match name.as_str() {
+            "REQUEST_METHOD" => method = Some(value),
+            "SCRIPT_NAME" => script_name = value,
+            "PATH_INFO" => path_info = value,
+            "QUERY_STRING" if !value.is_empty() => query = Some(value),
+            "SERVER_PROTOCOL" => version = HttpVersion::parse(&value).unwrap_or(version),
+            "REMOTE_ADDR" => remote_addr = value.parse().ok(),
+            "HTTPS" => is_tls = value.eq_ignore_ascii_case("on") || value == "1",
+            "CONTENT_TYPE" if !value.is_empty() => headers.push(("content-type".into(), value)),
+            "CONTENT_LENGTH" if !value.is_empty() => {
+                headers.push(("content-length".into(), value));
+            }
+            _ => {
+                if let Some(header) = name.strip_prefix("HTTP_") {
+                    headers.push((header.to_ascii_lowercase().replace('_', "-"), value));
+                }
+            }
+        }
+    }
+
+    let method = method.ok_or(WasiError::MissingMethod)?;
+    let method = Method::from_bytes(method.as_bytes()).ok_or(WasiError::InvalidMethod(method))?;
+
+    let mut path = script_name.trim_end_matches('/').to_string();
+    path.push_str(&path_info);
+    if !path.starts_with('/') {
+        path.insert(0, '/');
+    }
+
+    let mut req = Request::with_version(method, path, version);
+    req.set_query(query);
+    for (name, value) in headers {
+        req.headers_mut().insert(name, value);
+    }
+    if let Some(addr) = remote_addr {
+        req.insert_extension(RemoteAddr(addr));
+    }
+    req.insert_extension(ConnectionInfo { is_tls });
+    if !body.is_empty() {
+        req.set_body(Body::Bytes(body));
+    }
+    Ok(req)
+}
+
+/// Write `response` as a CGI response: a `Status` line, the headers, a blank
+/// line, then the body.
+///
+/// # Errors
+///
+/// Fails if writing to `out` fails.
+pub async fn write_response<W: Write>(response: Response, out: &mut W) -> io::Result<()> {
+    let (status, headers, body) = response.into_parts();
+    writeln!(
+        out,
+        "Status: {} {}",
+        status.as_u16(),
+        status.canonical_reason()
+    )?;
+    for (name, value) in &headers {
+        out.write_all(name.as_bytes())?;
+        out.write_all(b": ")?;
+        out.write_all(value)?;
+        out.write_all(b"\n")?;
+    }
+    out.write_all(b"\n")?;
+
+    match body {
+        ResponseBody::Empty => {}
+        ResponseBody::Bytes(bytes) => out.write_all(&bytes)?,
+        ResponseBody::Stream(mut stream) => {
+            while let Some(chunk) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+                out.write_all(&chunk)?;
+                out.flush()?;
+            }
+        }
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::StatusCode;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    fn test_context() -> RequestContext {
+        RequestContext::new(asupersync::Cx::for_testing(), 1)
+    }
+
+    #[test]
+    fn request_is_built_from_cgi_variables() {
+        let req = request_from_env(
+            vars(&[
+                ("REQUEST_METHOD", "POST"),
+                ("SCRIPT_NAME", "/api"),
+                ("PATH_INFO", "/items/7"),
+                ("QUERY_STRING", "verbose=1"),
+                ("SERVER_PROTOCOL", "HTTP/1.0"),
+                ("CONTENT_TYPE", "application/json"),
+                ("CONTENT_LENGTH", "2"),
+                ("HTTP_X_REQUEST_ID", "abc"),
+                ("REMOTE_ADDR", "10.0.0.9"),
+                ("HTTPS", "on"),
+                ("PATH", "/usr/bin"),
+            ]),
+            b"{}".to_vec(),
+        )
+        .unwrap();
+
+        assert_eq!(req.method(), Method::Post);
+        assert_eq!(req.path(), "/api/items/7");
+        assert_eq!(req.query(), Some("verbose=1"));
+        assert_eq!(req.version(), HttpVersion::Http10);
+        assert_eq!(
+            req.headers().get("content-type"),
+            Some(&b"application/json"[..])
+        );
+        assert_eq!(req.headers().get("content-length"), Some(&b"2"[..]));
+        assert_eq!(req.headers().get("x-request-id"), Some(&b"abc"[..]));
+        assert_eq!(
+            req.get_extension::<RemoteAddr>().map(|a| a.0.to_string()),
+            Some("10.0.0.9".to_string())
+        );
+        assert!(req.get_extension::<ConnectionInfo>().unwrap().is_tls);
+        assert!(matches!(req.body(), Body::Bytes(b) if b == b"{}"));
+    }
+
+    #[test]
+    fn empty_path_is_root() {
+        let req = request_from_env(vars(&[("REQUEST_METHOD", "GET")]), Vec::new()).unwrap();
+        assert_eq!(req.path(), "/");
+        assert_eq!(req.query(), None);
+        assert!(matches!(req.body(), Body::Empty));
+    }
+
+    #[test]
+    fn missing_or_unknown_method_is_rejected() {
+        let err = request_from_env(vars(&[("PATH_INFO", "/")]), Vec::new()).unwrap_err();
+        assert!(matches!(err, WasiError::MissingMethod));
+
+        let err = request_from_env(vars(&[("REQUEST_METHOD", "BREW")]), Vec::new()).unwrap_err();
+        assert!(matches!(err, WasiError::InvalidMethod(m) if m == "BREW"));
+    }
+
+    #[test]
+    fn response_is_written_in_cgi_format() {
+        let response = Response::with_status(StatusCode::CREATED)
+            .header("content-type", b"text/plain".to_vec())
+            .body(ResponseBody::Bytes(b"made".to_vec()));
+        let mut out = Vec::new();
+        futures_executor::block_on(write_response(response, &mut out)).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Status: 201 Created\ncontent-type: text/plain\n\nmade"
+        );
+    }
+
+    #[test]
+    fn streaming_bodies_are_written_in_order() {
+        let chunks = vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()];
+        let response = Response::ok().body(ResponseBody::stream(asupersync::stream::iter(chunks)));
+        let mut out = Vec::new();
+        futures_executor::block_on(write_response(response, &mut out)).unwrap();
+
+        assert!(String::from_utf8(out).unwrap().ends_with("\n\nabc"));
+    }
+
+    #[test]
+    fn requests_run_through_the_app() {
+        let app = App::builder()
+            .post("/echo", |_: &RequestContext, req: &mut Request| {
+                let body = match req.take_body() {
+                    Body::Bytes(bytes) => bytes,
+                    _ => Vec::new(),
+                };
+                std::future::ready(Response::ok().body(ResponseBody::Bytes(body)))
+            })
+            .build();
+        let ctx = test_context();
+        let env = vars(&[("REQUEST_METHOD", "POST"), ("PATH_INFO", "/echo")]);
+        let mut out = Vec::new();
+
+        futures_executor::block_on(handle(&app, &ctx, env, &b"ping"[..], &mut out)).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("Status: 200 OK\n"));
+        assert!(out.ends_with("\n\nping"));
+
+        let env = vars(&[("REQUEST_METHOD", "GET"), ("PATH_INFO", "/missing")]);
+        let mut out = Vec::new();
+        futures_executor::block_on(handle(&app, &ctx, env, io::empty(), &mut out)).unwrap();
+        assert!(
+            String::from_utf8(out)
+                .unwrap()
+                .starts_with("Status: 404 Not Found\n")
+        );
+    }
+}
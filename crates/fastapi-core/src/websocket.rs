@@ -168,111 +168,62 @@ impl From<std::str::Utf8Error> for WebSocketError {
 /// - Client -> server frames must be masked (enforced).
 #[derive(Debug)]
 pub struct WebSocket {
-    stream: TcpStream,
+    stream: Transport,
     rx: Vec<u8>,
 }
 
+/// The connection a [`WebSocket`] runs over.
+#[derive(Debug)]
+enum Transport {
+    Tcp(TcpStream),
+    /// In-process connection to the [`TestClient`](crate::TestClient).
+    #[cfg(feature = "testing")]
+    Memory(MemoryStream),
+}
+
 impl WebSocket {
     /// Create a websocket from a TCP stream and an optional prefix of already-buffered bytes.
     #[must_use]
     pub fn new(stream: TcpStream, buffered: Vec<u8>) -> Self {
         Self {
-            stream,
+            stream: Transport::Tcp(stream),
             rx: buffered,
         }
     }
 
-    /// Read the next frame.
-    pub async fn read_frame(&mut self) -> Result<Frame, WebSocketError> {
-        let header = self.read_exact_buf(2).await?;
-        let b0 = header[0];
-        let b1 = header[1];
-
-        let fin = (b0 & 0x80) != 0;
-        let rsv = (b0 >> 4) & 0x07;
-        if rsv != 0 {
-            return Err(WebSocketError::Protocol(
-                "reserved bits must be 0 (no extensions negotiated)",
-            ));
-        }
-        let opcode =
-            OpCode::from_u8(b0 & 0x0f).ok_or(WebSocketError::Protocol("invalid opcode"))?;
-        let masked = (b1 & 0x80) != 0;
-        let mut len7 = u64::from(b1 & 0x7f);
-
-        if opcode.is_control() && !fin {
-            return Err(WebSocketError::Protocol(
-                "control frames must not be fragmented",
-            ));
+    /// Create a websocket over one end of an in-memory connection.
+    #[cfg(feature = "testing")]
+    pub(crate) fn in_memory(stream: MemoryStream) -> Self {
+        Self {
+            stream: Transport::Memory(stream),
+            rx: Vec::new(),
         }
+    }
 
-        if len7 == 126 {
-            let b = self.read_exact_buf(2).await?;
-            len7 = u64::from(u16::from_be_bytes([b[0], b[1]]));
-        } else if len7 == 127 {
-            let b = self.read_exact_buf(8).await?;
-            len7 = u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]);
-            // Most implementations reject lengths with the high bit set (non-minimal encoding).
-            if (len7 >> 63) != 0 {
-                return Err(WebSocketError::Protocol("invalid 64-bit length"));
+    /// Read the next frame.
+    ///
+    /// Cancel-safe: bytes are only consumed once a whole frame has arrived,
+    /// so the future can be dropped (e.g. when racing it against another
+    /// event) without losing data.
+    pub async fn read_frame(&mut self) -> Result<Frame, WebSocketError> {
+        loop {
+            if let Some((frame, used)) = parse_frame(&self.rx)? {
+                self.rx.drain(..used);
+                return Ok(frame);
             }
+            let mut tmp = vec![0u8; 8192];
+            let read = read_once(&mut self.stream, &mut tmp).await?;
+            if read == 0 {
+                return Err(WebSocketError::Protocol("unexpected EOF"));
+            }
+            self.rx.extend_from_slice(&tmp[..read]);
         }
-
-        if !masked {
-            return Err(WebSocketError::Protocol(
-                "client->server frames must be masked",
-            ));
-        }
-        let payload_len = usize::try_from(len7).map_err(|_| WebSocketError::MessageTooLarge {
-            size: usize::MAX,
-            limit: MAX_TEXT_MESSAGE_BYTES,
-        })?;
-
-        if opcode.is_control() && payload_len > 125 {
-            return Err(WebSocketError::Protocol("control frame too large"));
-        }
-        if payload_len > MAX_TEXT_MESSAGE_BYTES {
-            return Err(WebSocketError::MessageTooLarge {
-                size: payload_len,
-                limit: MAX_TEXT_MESSAGE_BYTES,
-            });
-        }
-
-        let mask = self.read_exact_buf(4).await?;
-
-        let mut payload = self.read_exact_buf(payload_len).await?;
-        for (i, b) in payload.iter_mut().enumerate() {
-            *b ^= mask[i & 3];
-        }
-
-        Ok(Frame {
-            fin,
-            opcode,
-            payload,
-        })
     }
 
     /// Write a frame to the peer (server-side, unmasked).
     pub async fn write_frame(&mut self, frame: &Frame) -> Result<(), WebSocketError> {
         validate_outgoing_frame(frame)?;
-
-        let mut out = Vec::with_capacity(2 + frame.payload.len() + 8);
-        let b0 = (if frame.fin { 0x80 } else { 0 }) | (frame.opcode as u8);
-        out.push(b0);
-
-        let len = u64::try_from(frame.payload.len())
-            .map_err(|_| WebSocketError::Protocol("len too large"))?;
-        if len <= 125 {
-            out.push(len as u8);
-        } else if let Ok(len16) = u16::try_from(len) {
-            out.push(126);
-            out.extend_from_slice(&len16.to_be_bytes());
-        } else {
-            out.push(127);
-            out.extend_from_slice(&len.to_be_bytes());
-        }
-
-        out.extend_from_slice(&frame.payload);
+        let out = encode_frame(frame, None)?;
         write_all(&mut self.stream, &out).await?;
         flush(&mut self.stream).await?;
         Ok(())
@@ -456,23 +407,162 @@ impl WebSocket {
         };
         self.write_frame(&frame).await
     }
+}
 
-    async fn read_exact_buf(&mut self, n: usize) -> Result<Vec<u8>, WebSocketError> {
-        while self.rx.len() < n {
-            let mut tmp = vec![0u8; 8192];
-            let read = read_once(&mut self.stream, &mut tmp).await?;
-            if read == 0 {
-                return Err(WebSocketError::Protocol("unexpected EOF"));
-            }
-            self.rx.extend_from_slice(&tmp[..read]);
+/// Encode a frame for the wire, masking the payload with `mask` if given
+/// (client -> server frames must be masked, server -> client ones must not).
+pub(crate) fn encode_frame(
+    frame: &Frame,
+    mask: Option<[u8; 4]>,
+) -> Result<Vec<u8>, WebSocketError> {
+    let mut out = Vec::with_capacity(2 + frame.payload.len() + 12);
+    let b0 = (if frame.fin { 0x80 } else { 0 }) | (frame.opcode as u8);
+    out.push(b0);
+
+    let mask_bit = if mask.is_some() { 0x80 } else { 0 };
+    let len = u64::try_from(frame.payload.len())
+        .map_err(|_| WebSocketError::Protocol("len too large"))?;
+    if len <= 125 {
+        out.push(mask_bit | len as u8);
+    } else if let Ok(len16) = u16::try_from(len) {
+        out.push(mask_bit | 126);
+        out.extend_from_slice(&len16.to_be_bytes());
+    } else {
+        out.push(mask_bit | 127);
+        out.extend_from_slice(&len.to_be_bytes());
+    }
+
+    match mask {
+        Some(mask) => {
+            out.extend_from_slice(&mask);
+            out.extend(
+                frame
+                    .payload
+                    .iter()
+                    .enumerate()
+                    .map(|(i, b)| b ^ mask[i & 3]),
+            );
+        }
+        None => out.extend_from_slice(&frame.payload),
+    }
+    Ok(out)
+}
+
+/// Parse one client frame from the start of `buf`, returning it with the
+/// number of bytes it used, or `None` if more bytes are needed. Header errors
+/// are reported as soon as the offending bytes are available.
+fn parse_frame(buf: &[u8]) -> Result<Option<(Frame, usize)>, WebSocketError> {
+    parse_frame_from(buf, true)
+}
+
+/// Like [`parse_frame`], for frames sent by the server (which are unmasked).
+#[cfg(feature = "testing")]
+pub(crate) fn parse_server_frame(buf: &[u8]) -> Result<Option<(Frame, usize)>, WebSocketError> {
+    parse_frame_from(buf, false)
+}
+
+fn parse_frame_from(
+    buf: &[u8],
+    from_client: bool,
+) -> Result<Option<(Frame, usize)>, WebSocketError> {
+    let [b0, b1, ..] = *buf else {
+        return Ok(None);
+    };
+
+    let fin = (b0 & 0x80) != 0;
+    let rsv = (b0 >> 4) & 0x07;
+    if rsv != 0 {
+        return Err(WebSocketError::Protocol(
+            "reserved bits must be 0 (no extensions negotiated)",
+        ));
+    }
+    let opcode = OpCode::from_u8(b0 & 0x0f).ok_or(WebSocketError::Protocol("invalid opcode"))?;
+    let masked = (b1 & 0x80) != 0;
+    let mut len7 = u64::from(b1 & 0x7f);
+
+    if opcode.is_control() && !fin {
+        return Err(WebSocketError::Protocol(
+            "control frames must not be fragmented",
+        ));
+    }
+
+    let mut pos = 2;
+    if len7 == 126 {
+        let Some(b) = buf.get(pos..pos + 2) else {
+            return Ok(None);
+        };
+        len7 = u64::from(u16::from_be_bytes([b[0], b[1]]));
+        pos += 2;
+    } else if len7 == 127 {
+        let Some(b) = buf.get(pos..pos + 8) else {
+            return Ok(None);
+        };
+        len7 = u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]);
+        // Most implementations reject lengths with the high bit set (non-minimal encoding).
+        if (len7 >> 63) != 0 {
+            return Err(WebSocketError::Protocol("invalid 64-bit length"));
         }
+        pos += 8;
+    }
+
+    if from_client && !masked {
+        return Err(WebSocketError::Protocol(
+            "client->server frames must be masked",
+        ));
+    }
+    if !from_client && masked {
+        return Err(WebSocketError::Protocol(
+            "server->client frames must not be masked",
+        ));
+    }
+    let payload_len = usize::try_from(len7).map_err(|_| WebSocketError::MessageTooLarge {
+        size: usize::MAX,
+        limit: MAX_TEXT_MESSAGE_BYTES,
+    })?;
+
+    if opcode.is_control() && payload_len > 125 {
+        return Err(WebSocketError::Protocol("control frame too large"));
+    }
+    if payload_len > MAX_TEXT_MESSAGE_BYTES {
+        return Err(WebSocketError::MessageTooLarge {
+            size: payload_len,
+            limit: MAX_TEXT_MESSAGE_BYTES,
+        });
+    }
 
-        let out = self.rx.drain(..n).collect();
-        Ok(out)
+    let mut mask = [0u8; 4];
+    if masked {
+        let Some(key) = buf.get(pos..pos + 4) else {
+            return Ok(None);
+        };
+        mask.copy_from_slice(key);
+        pos += 4;
     }
+    let Some(payload) = buf.get(pos..pos + payload_len) else {
+        return Ok(None);
+    };
+    let payload = payload
+        .iter()
+        .enumerate()
+        .map(|(i, b)| b ^ mask[i & 3])
+        .collect();
+
+    Ok(Some((
+        Frame {
+            fin,
+            opcode,
+            payload,
+        },
+        pos + payload_len,
+    )))
 }
 
-async fn read_once(stream: &mut TcpStream, buffer: &mut [u8]) -> io::Result<usize> {
+async fn read_once(stream: &mut Transport, buffer: &mut [u8]) -> io::Result<usize> {
+    let stream = match stream {
+        Transport::Tcp(stream) => stream,
+        #[cfg(feature = "testing")]
+        Transport::Memory(stream) => return Ok(poll_fn(|cx| stream.poll_read(cx, buffer)).await),
+    };
     poll_fn(|cx| {
         let mut read_buf = ReadBuf::new(buffer);
         match Pin::new(&mut *stream).poll_read(cx, &mut read_buf) {
@@ -484,7 +574,12 @@ async fn read_once(stream: &mut TcpStream, buffer: &mut [u8]) -> io::Result<usiz
     .await
 }
 
-async fn write_all(stream: &mut TcpStream, mut buf: &[u8]) -> io::Result<()> {
+async fn write_all(stream: &mut Transport, mut buf: &[u8]) -> io::Result<()> {
+    let stream = match stream {
+        Transport::Tcp(stream) => stream,
+        #[cfg(feature = "testing")]
+        Transport::Memory(stream) => return stream.write(buf),
+    };
     while !buf.is_empty() {
         let n = poll_fn(|cx| Pin::new(&mut *stream).poll_write(cx, buf)).await?;
         if n == 0 {
@@ -495,8 +590,107 @@ async fn write_all(stream: &mut TcpStream, mut buf: &[u8]) -> io::Result<()> {
     Ok(())
 }
 
-async fn flush(stream: &mut TcpStream) -> io::Result<()> {
-    poll_fn(|cx| Pin::new(&mut *stream).poll_flush(cx)).await
+async fn flush(stream: &mut Transport) -> io::Result<()> {
+    match stream {
+        Transport::Tcp(stream) => poll_fn(|cx| Pin::new(&mut *stream).poll_flush(cx)).await,
+        #[cfg(feature = "testing")]
+        Transport::Memory(_) => Ok(()),
+    }
+}
+
+// =============================================================================
+// In-memory connection (for the test client)
+// =============================================================================
+
+#[cfg(feature = "testing")]
+pub(crate) use memory::{MemoryStream, memory_pair};
+
+#[cfg(feature = "testing")]
+mod memory {
+    use parking_lot::Mutex;
+    use std::io;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Waker};
+
+    /// Bytes travelling in one direction of an in-memory connection.
+    #[derive(Debug, Default)]
+    struct Pipe {
+        buf: Vec<u8>,
+        closed: bool,
+        reader: Option<Waker>,
+    }
+
+    /// One end of an in-memory byte connection.
+    ///
+    /// Writes never block. Dropping either end closes both directions; the other
+    /// end still reads what was already written, then sees EOF.
+    #[derive(Debug)]
+    pub(crate) struct MemoryStream {
+        incoming: Arc<Mutex<Pipe>>,
+        outgoing: Arc<Mutex<Pipe>>,
+    }
+
+    /// Create both ends of an in-memory connection.
+    pub(crate) fn memory_pair() -> (MemoryStream, MemoryStream) {
+        let a = Arc::new(Mutex::new(Pipe::default()));
+        let b = Arc::new(Mutex::new(Pipe::default()));
+        (
+            MemoryStream {
+                incoming: Arc::clone(&a),
+                outgoing: Arc::clone(&b),
+            },
+            MemoryStream {
+                incoming: b,
+                outgoing: a,
+            },
+        )
+    }
+
+    impl MemoryStream {
+        /// Read what the peer has written; `0` means the connection is closed.
+        pub(crate) fn poll_read(&self, cx: &mut Context<'_>, out: &mut [u8]) -> Poll<usize> {
+            let mut pipe = self.incoming.lock();
+            if pipe.buf.is_empty() {
+                if pipe.closed {
+                    return Poll::Ready(0);
+                }
+                pipe.reader = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+            let n = out.len().min(pipe.buf.len());
+            out[..n].copy_from_slice(&pipe.buf[..n]);
+            pipe.buf.drain(..n);
+            Poll::Ready(n)
+        }
+
+        /// Send bytes to the peer.
+        pub(crate) fn write(&self, data: &[u8]) -> io::Result<()> {
+            let mut pipe = self.outgoing.lock();
+            if pipe.closed {
+                return Err(io::Error::new(
+                    io::ErrorKind::BrokenPipe,
+                    "in-memory connection closed",
+                ));
+            }
+            pipe.buf.extend_from_slice(data);
+            if let Some(waker) = pipe.reader.take() {
+                waker.wake();
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for MemoryStream {
+        fn drop(&mut self) {
+            for pipe in [&self.incoming, &self.outgoing] {
+                let mut pipe = pipe.lock();
+                pipe.closed = true;
+                if let Some(waker) = pipe.reader.take() {
+                    waker.wake();
+                }
+            }
+        }
+    }
 }
 
 // =============================================================================
@@ -764,6 +958,26 @@ mod tests {
         assert_eq!(dec, data);
     }
 
+    #[test]
+    fn parse_frame_waits_for_whole_frame() {
+        // Masked "Hi" text frame.
+        let mask = [1u8, 2, 3, 4];
+        let mut wire = vec![0x81, 0x82];
+        wire.extend_from_slice(&mask);
+        wire.extend([b'H' ^ mask[0], b'i' ^ mask[1]]);
+        for end in 0..wire.len() {
+            assert!(parse_frame(&wire[..end]).unwrap().is_none(), "prefix {end}");
+        }
+        wire.push(0x89); // start of the next frame stays buffered
+        let (frame, used) = parse_frame(&wire).unwrap().unwrap();
+        assert_eq!(used, 8);
+        assert_eq!(frame.opcode, OpCode::Text);
+        assert_eq!(frame.payload, b"Hi");
+
+        // Header errors don't wait for the payload.
+        assert!(parse_frame(&[0x81, 0x05]).is_err());
+    }
+
     #[test]
     fn close_payload_validation() {
         assert!(is_valid_close_payload(&[]));
@@ -774,6 +988,29 @@ mod tests {
         assert!(!is_valid_close_payload(&[0x03, 0xE8, 0xFF])); // invalid utf-8 reason
     }
 
+    #[cfg(feature = "testing")]
+    #[test]
+    fn encoded_frames_parse_on_the_other_side() {
+        let frame = Frame {
+            fin: true,
+            opcode: OpCode::Binary,
+            payload: vec![7; 300],
+        };
+        let masked = encode_frame(&frame, Some([1, 2, 3, 4])).unwrap();
+        assert_eq!(
+            parse_frame(&masked).unwrap(),
+            Some((frame.clone(), masked.len()))
+        );
+        assert!(parse_server_frame(&masked).is_err());
+
+        let plain = encode_frame(&frame, None).unwrap();
+        assert_eq!(
+            parse_server_frame(&plain).unwrap(),
+            Some((frame, plain.len()))
+        );
+        assert!(parse_frame(&plain).is_err());
+    }
+
     #[test]
     fn build_close_payload_rejects_invalid_code() {
         let err = build_close_payload(1006, None).expect_err("1006 must be rejected");
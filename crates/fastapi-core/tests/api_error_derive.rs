@@ -0,0 +1,75 @@
+//! Integration tests for the `#[derive(ApiError)]` macro.
+
+#![allow(dead_code)]
+
+use fastapi_core::{IntoResponse, Response, ResponseBody};
+use fastapi_macros::ApiError;
+use fastapi_openapi::{ErrorResponses, Schema, SchemaRegistry, http_error_schema};
+
+#[derive(ApiError)]
+enum ItemError {
+    #[api_error(status = 404, message = "Item {id} not found")]
+    NotFound { id: i64 },
+    #[api_error(
+        status = 409,
+        message = "Item {0} already exists",
+        description = "Item name is taken"
+    )]
+    Conflict(String),
+    #[api_error(status = 422, message = "Name is too long")]
+    NameTooLong,
+    #[api_error(status = 503, description = "Storage is unavailable")]
+    Unavailable,
+}
+
+fn detail(response: &Response) -> String {
+    let ResponseBody::Bytes(body) = response.body_ref() else {
+        panic!("expected a bytes body");
+    };
+    let json: serde_json::Value = serde_json::from_slice(body).unwrap();
+    json["detail"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn variants_render_status_and_detail() {
+    let response = ItemError::NotFound { id: 7 }.into_response();
+    assert_eq!(response.status().as_u16(), 404);
+    assert_eq!(detail(&response), "Item 7 not found");
+
+    let response = ItemError::Conflict("widget".to_string()).into_response();
+    assert_eq!(response.status().as_u16(), 409);
+    assert_eq!(detail(&response), "Item widget already exists");
+
+    let response = ItemError::Unavailable.into_response();
+    assert_eq!(response.status().as_u16(), 503);
+    assert_eq!(detail(&response), "Service Unavailable");
+}
+
+#[test]
+fn error_responses_list_every_variant() {
+    assert_eq!(
+        ItemError::error_responses(),
+        vec![
+            (404, "Not Found"),
+            (409, "Item name is taken"),
+            (422, "Name is too long"),
+            (503, "Storage is unavailable"),
+        ]
+    );
+}
+
+#[test]
+fn http_error_schema_is_a_shared_component() {
+    let mut registry = SchemaRegistry::new();
+    let first = http_error_schema(&mut registry.registry());
+    let second = http_error_schema(&mut registry.registry());
+    let Schema::Ref(reference) = &first else {
+        panic!("expected a $ref");
+    };
+    assert_eq!(reference.reference, "#/components/schemas/HTTPError");
+    assert_eq!(
+        serde_json::to_value(&first).unwrap(),
+        serde_json::to_value(&second).unwrap()
+    );
+    assert_eq!(registry.into_schemas().len(), 1);
+}
@@ -0,0 +1,57 @@
+//! Integration tests for the `BackgroundTasks` extractor.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use fastapi_core::{App, BackgroundTasks, FromRequest, Method, Request, RequestContext};
+
+fn extract(req: &mut Request) -> BackgroundTasks {
+    let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+    futures_executor::block_on(BackgroundTasks::from_request(&ctx, req)).unwrap()
+}
+
+#[test]
+fn extracted_handles_share_the_request_tasks() {
+    let ran = Arc::new(AtomicUsize::new(0));
+    let mut req = Request::new(Method::Post, "/signup");
+
+    let first = extract(&mut req);
+    let counter = Arc::clone(&ran);
+    first.add(move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    let second = extract(&mut req);
+    let counter = Arc::clone(&ran);
+    second.add_async(async move {
+        counter.fetch_add(10, Ordering::SeqCst);
+    });
+    assert_eq!(first.len(), 2);
+    assert_eq!(ran.load(Ordering::SeqCst), 0);
+
+    let tasks = App::take_background_tasks(&mut req).expect("tasks are stored on the request");
+    futures_executor::block_on(tasks.execute_all());
+    assert_eq!(ran.load(Ordering::SeqCst), 11);
+    assert!(second.is_empty());
+}
+
+#[test]
+fn failing_and_panicking_tasks_do_not_stop_the_rest() {
+    let ran = Arc::new(AtomicUsize::new(0));
+    let mut req = Request::new(Method::Get, "/");
+    let tasks = extract(&mut req);
+
+    tasks.add_fallible(async { Err::<(), _>("smtp unavailable") });
+    tasks.add(|| panic!("boom"));
+    let counter = Arc::clone(&ran);
+    let nested = tasks.clone();
+    tasks.add(move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        let counter = Arc::clone(&counter);
+        nested.add(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+    });
+
+    futures_executor::block_on(tasks.execute_all());
+    assert_eq!(ran.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,202 @@
+//! Integration tests for the `#[dependency]` macro.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use fastapi_core::{
+    App, DeclaresRequirements, Depends, FromDependency, FromRequest, HttpError, IntoResponse,
+    Method, NoCache, Path, PathParams, Request, RequestContext, Response, RouteEntry, State,
+};
+use fastapi_macros::{ApiError, dependency};
+use fastapi_openapi::ErrorResponses;
+
+static CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone)]
+struct Config {
+    greeting: &'static str,
+}
+
+#[dependency]
+fn settings() -> Config {
+    Config { greeting: "hello" }
+}
+
+#[dependency]
+fn connection() -> usize {
+    CONNECTIONS.fetch_add(1, Ordering::SeqCst)
+}
+
+#[dependency]
+async fn item_owner(id: Path<i64>) -> Result<String, HttpError> {
+    match id.0 {
+        1 => Ok("alice".to_string()),
+        _ => Err(HttpError::not_found()),
+    }
+}
+
+#[dependency(Greeting)]
+async fn greet(
+    ctx: &RequestContext,
+    owner: Depends<ItemOwner>,
+    settings: Depends<Settings>,
+) -> String {
+    format!("{} {} ({})", settings.greeting, **owner, ctx.request_id())
+}
+
+#[derive(ApiError)]
+enum TenantError {
+    #[api_error(status = 400, message = "Missing tenant header")]
+    Missing,
+    #[api_error(status = 404, message = "Unknown tenant")]
+    Unknown,
+}
+
+#[dependency]
+fn tenant(req: &mut Request) -> Result<String, TenantError> {
+    match req.headers().get("x-tenant") {
+        None => Err(TenantError::Missing),
+        Some(b"acme") => Ok("acme".to_string()),
+        Some(_) => Err(TenantError::Unknown),
+    }
+}
+
+#[dependency]
+async fn connection_pair(
+    first: Depends<Connection>,
+    second: Depends<Connection>,
+) -> (usize, usize) {
+    (*first.into_inner(), *second.into_inner())
+}
+
+#[derive(Clone)]
+struct Database;
+
+#[dependency]
+fn database_name(_db: State<Database>) -> &'static str {
+    "main"
+}
+
+#[dependency]
+fn ping(_pong: Depends<Pong>) -> u8 {
+    0
+}
+
+#[dependency]
+fn pong(_ping: Depends<Ping>) -> u8 {
+    1
+}
+
+fn test_context() -> RequestContext {
+    RequestContext::new(asupersync::Cx::for_testing(), 7)
+}
+
+fn request(id: &str) -> Request {
+    let mut req = Request::new(Method::Get, format!("/items/{id}"));
+    req.insert_extension(PathParams::from_pairs(vec![(
+        "id".to_string(),
+        id.to_string(),
+    )]));
+    req
+}
+
+fn resolve<T: FromRequest>(ctx: &RequestContext, req: &mut Request) -> Result<T, T::Error> {
+    futures_executor::block_on(T::from_request(ctx, req))
+}
+
+#[test]
+fn resolves_parameters_and_nested_dependencies() {
+    let ctx = test_context();
+    let mut req = request("1");
+    let greeting = resolve::<Depends<Greeting>>(&ctx, &mut req).unwrap();
+    assert_eq!(greeting.into_inner().into_inner(), "hello alice (7)");
+
+    let settings = resolve::<Depends<Settings>>(&ctx, &mut req).unwrap();
+    assert_eq!(settings.greeting, "hello");
+}
+
+#[test]
+fn nested_dependencies_share_the_request_cache() {
+    let ctx = test_context();
+    let mut req = request("1");
+    let (first, second) = resolve::<Depends<ConnectionPair>>(&ctx, &mut req)
+        .unwrap()
+        .into_inner()
+        .into_inner();
+    assert_eq!(first, second);
+
+    let uncached = resolve::<Depends<Connection, NoCache>>(&ctx, &mut req).unwrap();
+    assert_ne!(*uncached.into_inner(), first);
+}
+
+#[test]
+fn failures_become_responses() {
+    let ctx = test_context();
+    let mut req = request("2");
+    let err = resolve::<Depends<ItemOwner>>(&ctx, &mut req).err().unwrap();
+    assert_eq!(err.into_response().status().as_u16(), 404);
+
+    let ctx = test_context();
+    let mut req = request("abc");
+    let err = resolve::<Depends<Greeting>>(&ctx, &mut req).err().unwrap();
+    assert_eq!(err.into_response().status().as_u16(), 422);
+}
+
+#[test]
+fn typed_errors_keep_their_response_and_docs() {
+    let ctx = test_context();
+    let mut req = request("1");
+    let err = resolve::<Depends<Tenant>>(&ctx, &mut req).err().unwrap();
+    assert_eq!(err.into_response().status().as_u16(), 400);
+
+    req.headers_mut().insert("x-tenant", b"other".to_vec());
+    let err = resolve::<Depends<Tenant>>(&ctx, &mut req).err().unwrap();
+    assert_eq!(err.into_response().status().as_u16(), 404);
+
+    req.headers_mut().insert("x-tenant", b"acme".to_vec());
+    let tenant = resolve::<Depends<Tenant, NoCache>>(&ctx, &mut req).unwrap();
+    assert_eq!(tenant.as_str(), "acme");
+
+    // Route macros document a `Depends<T>` parameter through its rejection type.
+    assert_eq!(
+        <<Depends<Tenant> as FromRequest>::Error as ErrorResponses>::error_responses(),
+        vec![(400, "Missing tenant header"), (404, "Unknown tenant")]
+    );
+}
+
+fn app_depending_on<D: DeclaresRequirements>() -> fastapi_core::AppBuilder {
+    let route = RouteEntry::new(
+        Method::Get,
+        "/",
+        |_ctx: &RequestContext, _req: &mut Request| std::future::ready(Response::ok()),
+    )
+    .requires(D::requirements());
+    App::builder().route_entry(route)
+}
+
+#[test]
+fn dependency_functions_declare_their_requirements() {
+    assert_eq!(<Greeting as FromDependency>::requirements().len(), 2);
+    assert!(<Settings as FromDependency>::requirements().is_empty());
+
+    let app = app_depending_on::<Depends<DatabaseName>>()
+        .state(Database)
+        .build();
+    let report = app.check();
+    assert!(report.is_ok());
+    let route = &report.dependency_trees[0];
+    assert_eq!(route.name, "GET /");
+    assert_eq!(route.children[0].name, "DatabaseName");
+    assert_eq!(route.children[0].children[0].name, "State<Database>");
+}
+
+#[test]
+#[should_panic(expected = "dependency `DatabaseName` requires state `Database`")]
+fn build_rejects_missing_dependency_state() {
+    let _ = app_depending_on::<Depends<DatabaseName>>().build();
+}
+
+#[test]
+#[should_panic(expected = "dependency cycle: Ping -> Pong -> Ping")]
+fn build_rejects_dependency_cycles() {
+    let _ = app_depending_on::<Depends<Ping>>().build();
+}
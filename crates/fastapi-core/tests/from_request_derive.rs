@@ -0,0 +1,90 @@
+//! Integration tests for the `#[derive(FromRequest)]` macro.
+
+#![allow(dead_code)]
+
+use fastapi_core::{
+    Body, CompositeExtractError, FromRequest, Json, Method, Path, PathParams, Query, Request,
+    RequestContext,
+};
+use fastapi_macros::FromRequest;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Paging {
+    limit: u32,
+}
+
+#[derive(Deserialize)]
+struct Patch {
+    name: String,
+}
+
+#[derive(FromRequest)]
+struct UpdateItem {
+    id: Path<i64>,
+    paging: Query<Paging>,
+    body: Json<Patch>,
+}
+
+#[derive(FromRequest)]
+struct ItemId(Path<i64>);
+
+fn test_context() -> RequestContext {
+    RequestContext::new(asupersync::Cx::for_testing(), 1)
+}
+
+fn request(id: &str, query: &str, body: &str) -> Request {
+    let mut req = Request::new(Method::Put, format!("/items/{id}"));
+    req.insert_extension(PathParams::from_pairs(vec![(
+        "id".to_string(),
+        id.to_string(),
+    )]));
+    req.set_query(Some(query.to_string()));
+    req.headers_mut()
+        .insert("content-type", b"application/json".to_vec());
+    req.set_body(Body::Bytes(body.as_bytes().to_vec()));
+    req
+}
+
+fn extract<T: FromRequest>(req: &mut Request) -> Result<T, T::Error> {
+    let ctx = test_context();
+    futures_executor::block_on(T::from_request(&ctx, req))
+}
+
+#[test]
+fn all_fields_are_extracted() {
+    let mut req = request("7", "limit=5", r#"{"name":"widget"}"#);
+    let input = extract::<UpdateItem>(&mut req).ok().unwrap();
+    assert_eq!(input.id.0, 7);
+    assert_eq!(input.paging.limit, 5);
+    assert_eq!(input.body.name, "widget");
+
+    let ItemId(id) = extract::<ItemId>(&mut request("9", "", "")).ok().unwrap();
+    assert_eq!(id.0, 9);
+}
+
+#[test]
+fn validation_errors_from_every_field_are_collected() {
+    let mut req = request("abc", "", "{not json");
+    let Err(CompositeExtractError::Validation(errors)) = extract::<UpdateItem>(&mut req) else {
+        panic!("expected validation errors");
+    };
+    let locs: Vec<String> = errors
+        .iter()
+        .map(|e| serde_json::to_string(&e.loc).unwrap())
+        .collect();
+    assert_eq!(errors.len(), 3, "{locs:?}");
+    assert_eq!(locs[0], r#"["path","id"]"#);
+    assert_eq!(locs[2], r#"["body"]"#);
+}
+
+#[test]
+fn other_rejections_are_returned_as_is() {
+    let mut req = request("1", "limit=5", r#"{"name":"widget"}"#);
+    req.headers_mut()
+        .insert("content-type", b"text/plain".to_vec());
+    let Err(CompositeExtractError::Rejected(response)) = extract::<UpdateItem>(&mut req) else {
+        panic!("expected the JSON extractor's rejection");
+    };
+    assert_eq!(response.status().as_u16(), 415);
+}
@@ -0,0 +1,334 @@
+//! Integration tests for `#[derive(JsonSchema)]` on generic types.
+//!
+//! The derive lives in `fastapi-macros`, which can't be a dev-dependency of
+//! `fastapi-openapi` without a cycle, so these tests live here.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use fastapi_macros::JsonSchema;
+use fastapi_openapi::{JsonSchema, Schema, SchemaRegistry};
+
+#[derive(JsonSchema)]
+struct Item {
+    id: i64,
+    name: String,
+}
+
+#[derive(JsonSchema)]
+struct User {
+    email: String,
+}
+
+#[derive(JsonSchema)]
+struct Page<T> {
+    items: Vec<T>,
+    total: u64,
+    next: Option<String>,
+}
+
+#[derive(JsonSchema)]
+enum ApiResponse<T> {
+    Ok { data: T },
+    Err { message: String },
+}
+
+#[derive(JsonSchema)]
+struct Pair<A, B> {
+    left: A,
+    right: B,
+    tags: HashMap<String, (i32, bool)>,
+}
+
+#[test]
+fn generic_struct_names_each_instantiation() {
+    assert_eq!(Page::<Item>::schema_name(), Some("Page_Item"));
+    assert_eq!(Page::<User>::schema_name(), Some("Page_User"));
+    assert_eq!(Pair::<Item, u32>::schema_name(), Some("Pair_Item_u32"));
+    assert_eq!(
+        Page::<Vec<Item>>::schema_name(),
+        Some("Page_Vec_Item"),
+        "unnamed containers fall back to their compact type name"
+    );
+}
+
+#[test]
+fn generic_struct_schema_uses_type_argument() {
+    let json = serde_json::to_value(Page::<Item>::schema()).unwrap();
+    assert_eq!(json["title"], "Page_Item");
+    assert_eq!(json["properties"]["items"]["type"], "array");
+    assert_eq!(json["properties"]["items"]["items"]["title"], "Item");
+    assert_eq!(json["properties"]["total"]["format"], "uint64");
+
+    let required = json["required"].as_array().unwrap();
+    assert!(required.iter().any(|r| r == "items"));
+    assert!(!required.iter().any(|r| r == "next"));
+}
+
+#[test]
+fn generic_enum_names_each_instantiation() {
+    assert_eq!(ApiResponse::<User>::schema_name(), Some("ApiResponse_User"));
+
+    let json = serde_json::to_value(ApiResponse::<User>::schema()).unwrap();
+    let variants = json["oneOf"].as_array().unwrap();
+    assert_eq!(variants.len(), 2);
+    assert_eq!(
+        variants[0]["properties"]["Ok"]["properties"]["data"]["title"],
+        "User"
+    );
+}
+
+#[test]
+fn registry_keeps_instantiations_distinct() {
+    let mut registry = SchemaRegistry::new();
+    let item_ref = registry.register_type::<Page<Item>>();
+    let user_ref = registry.register_type::<Page<User>>();
+    let inline = registry.register_type::<Vec<Item>>();
+
+    let Schema::Ref(item_ref) = item_ref else {
+        panic!("named type should register as a reference");
+    };
+    let Schema::Ref(user_ref) = user_ref else {
+        panic!("named type should register as a reference");
+    };
+    assert_eq!(item_ref.reference, "#/components/schemas/Page_Item");
+    assert_eq!(user_ref.reference, "#/components/schemas/Page_User");
+    assert!(matches!(inline, Schema::Array(_)));
+
+    let schemas = registry.into_schemas();
+    assert!(schemas.contains_key("Page_Item"));
+    assert!(schemas.contains_key("Page_User"));
+}
+
+#[test]
+fn container_fields_use_builtin_impls() {
+    let json = serde_json::to_value(Pair::<Item, Box<u32>>::schema()).unwrap();
+    assert_eq!(json["properties"]["right"]["format"], "uint32");
+
+    let tags = &json["properties"]["tags"]["additionalProperties"];
+    assert_eq!(tags["type"], "array");
+    assert_eq!(tags["minItems"], 2);
+    assert_eq!(tags["maxItems"], 2);
+}
+
+#[derive(JsonSchema)]
+#[schema(example = serde_json::json!({"name": "alice", "age": 30}))]
+struct Person {
+    #[schema(example = "alice")]
+    name: String,
+    #[schema(example = 30)]
+    age: u32,
+    #[schema(example = "alice@example.com")]
+    email: Option<String>,
+    #[schema(example = serde_json::json!(["admin", "staff"]))]
+    roles: Vec<String>,
+}
+
+#[test]
+fn schema_examples_are_emitted() {
+    let json = serde_json::to_value(Person::schema()).unwrap();
+    assert_eq!(json["example"]["name"], "alice");
+    assert_eq!(json["properties"]["name"]["example"], "alice");
+    assert_eq!(json["properties"]["age"]["example"], 30);
+    assert_eq!(json["properties"]["email"]["example"], "alice@example.com");
+    assert_eq!(json["properties"]["email"]["nullable"], true);
+    assert_eq!(
+        json["properties"]["roles"]["example"],
+        serde_json::json!(["admin", "staff"])
+    );
+    assert!(json["properties"]["name"].get("examples").is_none());
+}
+
+#[derive(JsonSchema)]
+#[schema(deprecated)]
+struct LegacyUser {
+    id: i64,
+    #[schema(deprecated)]
+    username: String,
+    #[deprecated = "use `id`"]
+    legacy_id: Option<i64>,
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_markers_are_emitted() {
+    let json = serde_json::to_value(LegacyUser::schema()).unwrap();
+    assert_eq!(json["deprecated"], true);
+    assert_eq!(json["properties"]["username"]["deprecated"], true);
+    assert_eq!(json["properties"]["legacy_id"]["deprecated"], true);
+    assert!(json["properties"]["id"].get("deprecated").is_none());
+}
+
+fn default_page_size() -> u32 {
+    20
+}
+
+#[derive(JsonSchema)]
+struct SearchQuery {
+    #[schema(example = "rust")]
+    q: String,
+    #[serde(default)]
+    page: u32,
+    #[serde(default = "default_page_size")]
+    #[schema(example = 50)]
+    per_page: u32,
+    #[serde(default)]
+    sort: Option<String>,
+}
+
+#[derive(Default, JsonSchema)]
+#[serde(default)]
+struct Filters {
+    archived: bool,
+    label: String,
+}
+
+#[test]
+fn serde_defaults_are_emitted_and_not_required() {
+    let json = serde_json::to_value(SearchQuery::schema()).unwrap();
+    assert_eq!(json["required"], serde_json::json!(["q"]));
+    assert_eq!(json["properties"]["page"]["default"], 0);
+    assert_eq!(json["properties"]["per_page"]["default"], 20);
+    assert!(json["properties"]["sort"].get("default").is_none());
+    assert!(json["properties"]["q"].get("default").is_none());
+
+    let json = serde_json::to_value(Filters::schema()).unwrap();
+    assert!(json.get("required").is_none());
+    assert_eq!(json["properties"]["archived"]["default"], false);
+    assert_eq!(json["properties"]["label"]["default"], "");
+}
+
+#[test]
+fn query_struct_fields_become_parameters() {
+    let params = fastapi_openapi::params_from_schema(&SearchQuery::schema());
+    let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["page", "per_page", "q", "sort"]);
+
+    let per_page = &params[1];
+    assert!(!per_page.required);
+    assert_eq!(per_page.default, Some(serde_json::json!(20)));
+    assert_eq!(per_page.example, Some(serde_json::json!(50)));
+
+    let q = &params[2];
+    assert!(q.required);
+    assert_eq!(q.example, Some(serde_json::json!("rust")));
+    assert!(q.default.is_none());
+}
+
+#[derive(JsonSchema)]
+struct SignupForm {
+    #[validate(length(min = 3, max = 20), regex = "^[a-z0-9_]+$")]
+    username: String,
+    #[validate(email)]
+    email: String,
+    #[validate(range(ge = 13, lt = 130))]
+    age: u8,
+    #[validate(starts_with = "https://")]
+    homepage: Option<String>,
+    #[validate(length(min = 1, max = 5))]
+    tags: Vec<String>,
+    #[validate(url)]
+    #[schema(format = "iri")]
+    avatar: String,
+}
+
+#[test]
+fn validate_constraints_surface_in_schema() {
+    let json = serde_json::to_value(SignupForm::schema()).unwrap();
+    let props = &json["properties"];
+
+    assert_eq!(props["username"]["minLength"], 3);
+    assert_eq!(props["username"]["maxLength"], 20);
+    assert_eq!(props["username"]["pattern"], "^[a-z0-9_]+$");
+    assert_eq!(props["email"]["format"], "email");
+    assert_eq!(props["age"]["minimum"], 13.0);
+    assert_eq!(props["age"]["exclusiveMaximum"], 130.0);
+    assert!(props["age"].get("maximum").is_none());
+    assert_eq!(props["homepage"]["pattern"], "^https://");
+    assert_eq!(props["tags"]["minItems"], 1);
+    assert_eq!(props["tags"]["maxItems"], 5);
+    assert_eq!(props["avatar"]["format"], "iri");
+}
+
+#[derive(JsonSchema)]
+struct Order {
+    buyer: User,
+    seller: User,
+    lines: Vec<Item>,
+    gift_for: Option<User>,
+}
+
+#[test]
+fn nested_types_register_once_and_are_referenced() {
+    let mut registry = SchemaRegistry::new();
+    let order_ref = registry.register_type::<Order>();
+    let Schema::Ref(order_ref) = order_ref else {
+        panic!("named type should register as a reference");
+    };
+    assert_eq!(order_ref.reference, "#/components/schemas/Order");
+
+    let schemas = registry.into_schemas();
+    assert_eq!(schemas.len(), 3);
+    assert!(schemas.contains_key("User"));
+    assert!(schemas.contains_key("Item"));
+
+    let order = serde_json::to_value(&schemas["Order"]).unwrap();
+    let props = &order["properties"];
+    assert_eq!(props["buyer"]["$ref"], "#/components/schemas/User");
+    assert_eq!(props["seller"]["$ref"], "#/components/schemas/User");
+    assert_eq!(props["gift_for"]["$ref"], "#/components/schemas/User");
+    assert_eq!(props["lines"]["items"]["$ref"], "#/components/schemas/Item");
+
+    // Without a registry the schema stays fully inline.
+    let inline = serde_json::to_value(Order::schema()).unwrap();
+    assert_eq!(inline["properties"]["buyer"]["title"], "User");
+}
+
+/// Documented elsewhere, so its schema is only a reference.
+struct External;
+
+impl JsonSchema for External {
+    fn schema() -> Schema {
+        Schema::reference("External")
+    }
+}
+
+#[derive(JsonSchema)]
+#[serde(tag = "kind")]
+enum Event {
+    Created {
+        #[serde(rename = "createdAt")]
+        created_at: String,
+    },
+    Imported(External),
+}
+
+#[derive(JsonSchema)]
+enum Step {
+    Move(i32, i32),
+}
+
+#[test]
+fn enum_variants_follow_serde_shape() {
+    let json = serde_json::to_value(Event::schema()).unwrap();
+    let variants = json["oneOf"].as_array().unwrap();
+
+    let created = &variants[0];
+    assert!(created["properties"].get("createdAt").is_some());
+    assert!(created["properties"].get("created_at").is_none());
+    assert_eq!(
+        created["required"],
+        serde_json::json!(["kind", "createdAt"])
+    );
+
+    let imported = &variants[1]["allOf"];
+    assert_eq!(imported[0]["$ref"], "#/components/schemas/External");
+    assert_eq!(imported[1]["required"], serde_json::json!(["kind"]));
+
+    let json = serde_json::to_value(Step::schema()).unwrap();
+    let moved = &json["oneOf"][0]["properties"]["Move"];
+    assert_eq!(moved["prefixItems"][0]["type"], "integer");
+    assert_eq!(moved["items"], false);
+    assert_eq!(moved["minItems"], 2);
+}
@@ -0,0 +1,77 @@
+//! Integration tests for the `#[derive(TypedPath)]` macro.
+
+use fastapi_core::{
+    FromRequest, Method, PathExtractError, PathParams, Request, RequestContext, TypedPath,
+};
+use fastapi_macros::TypedPath;
+
+#[derive(TypedPath, Debug)]
+#[typed_path("/orgs/{org}/items/{id:int}")]
+struct ItemPath {
+    org: String,
+    id: i64,
+}
+
+#[derive(TypedPath)]
+#[typed_path("/files/{path:path}")]
+struct FilePath {
+    path: String,
+}
+
+#[derive(TypedPath)]
+#[typed_path("/health")]
+struct HealthPath;
+
+fn request(pairs: &[(&str, &str)]) -> Request {
+    let mut req = Request::new(Method::Get, "/");
+    req.insert_extension(PathParams::from_pairs(
+        pairs
+            .iter()
+            .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+            .collect(),
+    ));
+    req
+}
+
+fn extract<T: FromRequest>(req: &mut Request) -> Result<T, T::Error> {
+    let ctx = RequestContext::new(asupersync::Cx::for_testing(), 1);
+    futures_executor::block_on(T::from_request(&ctx, req))
+}
+
+#[test]
+fn url_fills_and_encodes_parameters() {
+    assert_eq!(ItemPath::PATH, "/orgs/{org}/items/{id:int}");
+    let path = ItemPath {
+        org: "acme corp".to_string(),
+        id: 42,
+    };
+    assert_eq!(path.url(), "/orgs/acme%20corp/items/42");
+
+    let file = FilePath {
+        path: "docs/read me.md".to_string(),
+    };
+    assert_eq!(file.url(), "/files/docs/read%20me.md");
+    assert_eq!(HealthPath.url(), "/health");
+}
+
+#[test]
+fn extracts_fields_from_path_params() {
+    let mut req = request(&[("org", "acme"), ("id", "7")]);
+    let path = extract::<ItemPath>(&mut req).unwrap();
+    assert_eq!(path.org, "acme");
+    assert_eq!(path.id, 7);
+}
+
+#[test]
+fn reports_missing_and_invalid_params() {
+    let mut req = request(&[("org", "acme"), ("id", "seven")]);
+    let err = extract::<ItemPath>(&mut req).unwrap_err();
+    assert!(
+        matches!(err, PathExtractError::InvalidValue { ref name, .. } if name == "id"),
+        "{err}"
+    );
+
+    let mut req = request(&[("id", "7")]);
+    let err = extract::<ItemPath>(&mut req).unwrap_err();
+    assert!(matches!(err, PathExtractError::MissingParam { ref name } if name == "org"));
+}
@@ -395,6 +395,41 @@ fn test_regex_phone_invalid() {
     assert!(invalid.validate().is_err());
 }
 
+#[derive(Validate)]
+struct PatternAliasTest {
+    #[validate(pattern = "^[A-Z]{3}-\\d+$")]
+    sku: String,
+    #[validate(pattern = "^[a-z]+$")]
+    tag: Option<String>,
+}
+
+#[test]
+fn test_pattern_alias_reports_pattern() {
+    let invalid = PatternAliasTest {
+        sku: "abc-1".to_string(),
+        tag: Some("Sale".to_string()),
+    };
+    let errors = invalid.validate().unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors.errors[0].msg,
+        r"String should match pattern '^[A-Z]{3}-\d+$'"
+    );
+    assert_eq!(
+        errors.errors[1].msg,
+        "String should match pattern '^[a-z]+$'"
+    );
+
+    // Each field keeps its own compiled pattern across calls.
+    for _ in 0..2 {
+        let valid = PatternAliasTest {
+            sku: "ABC-42".to_string(),
+            tag: None,
+        };
+        assert!(valid.validate().is_ok());
+    }
+}
+
 // ============================================================================
 // MULTIPLE_OF VALIDATION TESTS
 // ============================================================================
@@ -1526,3 +1561,154 @@ fn test_unit_struct_validation_always_ok() {
     let v = UnitStructTest;
     assert!(v.validate().is_ok());
 }
+
+// ============================================================================
+// Custom validators by name and struct-level schema checks
+// ============================================================================
+
+fn check_password_strength(value: &str) -> Result<(), String> {
+    if value.chars().any(|c| c.is_ascii_digit()) {
+        Ok(())
+    } else {
+        Err("Password must contain a digit".to_string())
+    }
+}
+
+fn passwords_match(signup: &SignupTest) -> Result<(), fastapi_core::ValidationErrors> {
+    if signup.password == signup.password_confirm {
+        return Ok(());
+    }
+    Err(fastapi_core::ValidationErrors::single(
+        fastapi_core::ValidationError::value_error(
+            vec![LocItem::field("password_confirm")],
+            "Passwords do not match",
+        ),
+    ))
+}
+
+fn not_reserved(signup: &SignupTest) -> Result<(), String> {
+    if signup.password == "password1" {
+        Err("Password is too common".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Validate)]
+#[validate(schema = "passwords_match")]
+#[validate(schema = not_reserved)]
+struct SignupTest {
+    #[validate(custom = "check_password_strength")]
+    password: String,
+    password_confirm: String,
+}
+
+#[test]
+fn test_custom_validator_by_name() {
+    let signup = SignupTest {
+        password: "secret".to_string(),
+        password_confirm: "secret".to_string(),
+    };
+    let err = signup.validate().unwrap_err();
+    assert_eq!(err.len(), 1);
+    assert_eq!(
+        err.errors[0].loc,
+        vec![LocItem::field("body"), LocItem::field("password")]
+    );
+}
+
+#[test]
+fn test_schema_checks_report_with_loc() {
+    let signup = SignupTest {
+        password: "secret1".to_string(),
+        password_confirm: "secret2".to_string(),
+    };
+    let err = signup.validate().unwrap_err();
+    assert_eq!(err.len(), 1);
+    assert_eq!(
+        err.errors[0].loc,
+        vec![LocItem::field("body"), LocItem::field("password_confirm")]
+    );
+
+    let signup = SignupTest {
+        password: "password1".to_string(),
+        password_confirm: "password1".to_string(),
+    };
+    let err = signup.validate().unwrap_err();
+    assert_eq!(err.len(), 1);
+    assert_eq!(err.errors[0].loc, vec![LocItem::field("body")]);
+    assert_eq!(err.errors[0].msg, "Password is too common");
+}
+
+// ============================================================================
+// Nested collections
+// ============================================================================
+
+#[derive(Validate)]
+struct OrderLine {
+    #[validate(range(ge = 1))]
+    quantity: i32,
+}
+
+#[derive(Validate)]
+struct Order {
+    #[validate(nested)]
+    lines: Vec<OrderLine>,
+    #[validate(nested)]
+    gift: Option<OrderLine>,
+    #[validate(nested)]
+    by_sku: std::collections::BTreeMap<String, OrderLine>,
+    #[validate(nested)]
+    backorders: Option<Vec<OrderLine>>,
+}
+
+#[test]
+fn test_nested_collections_locate_each_element() {
+    let order = Order {
+        lines: vec![OrderLine { quantity: 1 }, OrderLine { quantity: 0 }],
+        gift: Some(OrderLine { quantity: 0 }),
+        by_sku: std::iter::once(("A-1".to_string(), OrderLine { quantity: -1 })).collect(),
+        backorders: Some(vec![OrderLine { quantity: 0 }]),
+    };
+    let err = order.validate().unwrap_err();
+    let locs: Vec<_> = err.errors.iter().map(|e| e.loc.clone()).collect();
+    assert_eq!(
+        locs,
+        vec![
+            vec![
+                LocItem::field("body"),
+                LocItem::field("lines"),
+                LocItem::index(1),
+                LocItem::field("quantity"),
+            ],
+            vec![
+                LocItem::field("body"),
+                LocItem::field("gift"),
+                LocItem::field("quantity"),
+            ],
+            vec![
+                LocItem::field("body"),
+                LocItem::field("by_sku"),
+                LocItem::field("A-1"),
+                LocItem::field("quantity"),
+            ],
+            vec![
+                LocItem::field("body"),
+                LocItem::field("backorders"),
+                LocItem::index(0),
+                LocItem::field("quantity"),
+            ],
+        ]
+    );
+}
+
+#[test]
+fn test_nested_collections_valid_and_empty() {
+    let order = Order {
+        lines: vec![OrderLine { quantity: 2 }],
+        gift: None,
+        by_sku: std::collections::BTreeMap::new(),
+        backorders: None,
+    };
+    assert!(order.validate().is_ok());
+}
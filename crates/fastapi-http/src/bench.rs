@@ -0,0 +1,671 @@
+//! Load generation for measuring throughput and latency.
+//!
+//! [`Bench`] drives an app with a fixed number of concurrent workers for a
+//! set duration (or request count), cycling through a weighted request mix,
+//! and returns a [`BenchReport`] with requests per second and latency
+//! percentiles.
+//!
+//! Against an [`App`] the requests run in-process: each one is serialized to
+//! HTTP/1.1 bytes, parsed, routed, handled and written back to bytes, so
+//! parser and router regressions show up without network noise. Against a
+//! URL the requests go over the network with the built-in [`Client`], one
+//! keep-alive connection per worker.
+//!
+//! ```ignore
+//! use fastapi_http::bench::{Bench, BenchRequest};
+//!
+//! let report = Bench::app(Arc::new(app))
+//!     .concurrency(8)
+//!     .duration(Duration::from_secs(10))
+//!     .request(BenchRequest::get("/items").weight(9))
+//!     .request(BenchRequest::post("/items").body(r#"{"name":"x"}"#))
+//!     .run()?;
+//! println!("{:.0} req/s, p99 {:?}", report.rps(), report.latency.p99);
+//! ```
+//!
+//! Each worker runs on its own thread with a single-threaded runtime, so
+//! `concurrency` is also the number of OS threads.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::poll_fn;
+use std::pin::pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use asupersync::Cx;
+use asupersync::runtime::{Runtime, RuntimeBuilder, reactor::create_reactor};
+use asupersync::stream::Stream;
+use fastapi_core::{App, Method, RequestContext, StartupOutcome};
+
+use crate::client::Client;
+use crate::parser::Parser;
+use crate::response::{ResponseWrite, ResponseWriter};
+
+/// One kind of request in the mix.
+#[derive(Debug, Clone)]
+pub struct BenchRequest {
+    name: String,
+    method: Method,
+    path: String,
+    headers: Vec<(String, Vec<u8>)>,
+    body: Vec<u8>,
+    weight: u32,
+}
+
+impl BenchRequest {
+    /// A request with `method` for `path` (including any query string).
+    #[must_use]
+    pub fn new(method: Method, path: impl Into<String>) -> Self {
+        let path = path.into();
+        Self {
+            name: format!("{} {path}", method.as_str()),
+            method,
+            path,
+            headers: Vec::new(),
+            body: Vec::new(),
+            weight: 1,
+        }
+    }
+
+    /// A `GET` request for `path`.
+    #[must_use]
+    pub fn get(path: impl Into<String>) -> Self {
+        Self::new(Method::Get, path)
+    }
+
+    /// A `POST` request for `path`.
+    #[must_use]
+    pub fn post(path: impl Into<String>) -> Self {
+        Self::new(Method::Post, path)
+    }
+
+    /// Name shown in the report (defaults to `"METHOD path"`).
+    #[must_use]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Add a request header.
+    #[must_use]
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<Vec<u8>>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set the request body.
+    #[must_use]
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Relative share of this request in the mix (default 1).
+    #[must_use]
+    pub fn weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// The request as HTTP/1.1 bytes, as a client would send it.
+    fn to_http1(&self) -> Vec<u8> {
+        let mut out = format!(
+            "{} {} HTTP/1.1\r\nhost: bench\r\n",
+            self.method.as_str(),
+            self.path
+        )
+        .into_bytes();
+        for (name, value) in &self.headers {
+            out.extend_from_slice(name.as_bytes());
+            out.extend_from_slice(b": ");
+            out.extend_from_slice(value);
+            out.extend_from_slice(b"\r\n");
+        }
+        if !self.body.is_empty() {
+            out.extend_from_slice(format!("content-length: {}\r\n", self.body.len()).as_bytes());
+        }
+        out.extend_from_slice(b"\r\n");
+        out.extend_from_slice(&self.body);
+        out
+    }
+}
+
+/// What a [`Bench`] sends requests to.
+#[derive(Clone)]
+pub enum BenchTarget {
+    /// The app, called in-process.
+    App(Arc<App>),
+    /// A running server, e.g. `http://127.0.0.1:8000`.
+    Url(String),
+}
+
+impl fmt::Debug for BenchTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::App(_) => f.write_str("App"),
+            Self::Url(url) => f.debug_tuple("Url").field(url).finish(),
+        }
+    }
+}
+
+impl fmt::Display for BenchTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::App(_) => f.write_str("in-process"),
+            Self::Url(url) => f.write_str(url),
+        }
+    }
+}
+
+/// Errors that stop a [`Bench`] run before it starts.
+#[derive(Debug)]
+pub enum BenchError {
+    /// No requests were added to the mix, or all have weight 0.
+    NoRequests,
+    /// A request in the mix does not parse as HTTP/1.1.
+    InvalidRequest {
+        /// The request's name.
+        name: String,
+        /// The parser's error.
+        error: String,
+    },
+    /// A startup hook of the app aborted.
+    Startup(String),
+    /// A worker runtime could not be built.
+    Runtime(String),
+}
+
+impl fmt::Display for BenchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoRequests => f.write_str("no requests to send"),
+            Self::InvalidRequest { name, error } => write!(f, "invalid request {name}: {error}"),
+            Self::Startup(e) => write!(f, "startup hook aborted: {e}"),
+            Self::Runtime(e) => write!(f, "runtime failed to start: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for BenchError {}
+
+/// A load generation run.
+#[derive(Debug, Clone)]
+pub struct Bench {
+    target: BenchTarget,
+    concurrency: usize,
+    duration: Duration,
+    warmup: Duration,
+    max_requests: Option<u64>,
+    requests: Vec<BenchRequest>,
+}
+
+impl Bench {
+    /// Benchmark `target` with one worker for ten seconds.
+    #[must_use]
+    pub fn new(target: BenchTarget) -> Self {
+        Self {
+            target,
+            concurrency: 1,
+            duration: Duration::from_secs(10),
+            warmup: Duration::ZERO,
+            max_requests: None,
+            requests: Vec::new(),
+        }
+    }
+
+    /// Benchmark `app` in-process.
+    #[must_use]
+    pub fn app(app: Arc<App>) -> Self {
+        Self::new(BenchTarget::App(app))
+    }
+
+    /// Benchmark the server at `base_url` (`http://` only).
+    #[must_use]
+    pub fn url(base_url: impl Into<String>) -> Self {
+        let base_url: String = base_url.into();
+        Self::new(BenchTarget::Url(base_url.trim_end_matches('/').to_string()))
+    }
+
+    /// Number of concurrent workers (at least 1).
+    #[must_use]
+    pub fn concurrency(mut self, workers: usize) -> Self {
+        self.concurrency = workers.max(1);
+        self
+    }
+
+    /// How long to measure for, after the warmup.
+    #[must_use]
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Send requests for `warmup` before measuring; they are not reported.
+    #[must_use]
+    pub fn warmup(mut self, warmup: Duration) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Stop after `count` measured requests, even if the duration has not
+    /// passed.
+    #[must_use]
+    pub fn max_requests(mut self, count: u64) -> Self {
+        self.max_requests = Some(count);
+        self
+    }
+
+    /// Add a request to the mix.
+    #[must_use]
+    pub fn request(mut self, request: BenchRequest) -> Self {
+        self.requests.push(request);
+        self
+    }
+
+    /// Run the benchmark on the calling thread plus one thread per worker.
+    ///
+    /// Requests that fail to connect or get no response count as errors;
+    /// responses of any status count as completed.
+    ///
+    /// # Errors
+    ///
+    /// Fails before sending anything if the mix is empty or invalid, the
+    /// app's startup hooks abort, or a runtime cannot be built.
+    pub fn run(&self) -> Result<BenchReport, BenchError> {
+        let schedule: Vec<usize> = self
+            .requests
+            .iter()
+            .enumerate()
+            .flat_map(|(i, r)| std::iter::repeat_n(i, r.weight as usize))
+            .collect();
+        if schedule.is_empty() {
+            return Err(BenchError::NoRequests);
+        }
+        let raw: Vec<Vec<u8>> = self.requests.iter().map(BenchRequest::to_http1).collect();
+        for (request, bytes) in self.requests.iter().zip(&raw) {
+            if let Err(e) = Parser::new().parse(bytes) {
+                return Err(BenchError::InvalidRequest {
+                    name: request.name.clone(),
+                    error: e.to_string(),
+                });
+            }
+        }
+        if let BenchTarget::App(app) = &self.target {
+            if let StartupOutcome::Aborted(e) = bench_runtime()?.block_on(app.run_startup_hooks()) {
+                return Err(BenchError::Startup(e.to_string()));
+            }
+        }
+
+        let measure_from = Instant::now() + self.warmup;
+        let plan = Arc::new(Plan {
+            target: self.target.clone(),
+            requests: self.requests.clone(),
+            raw,
+            schedule,
+            measure_from,
+            until: measure_from + self.duration,
+            max_requests: self.max_requests,
+            sent: AtomicU64::new(0),
+            measured: AtomicU64::new(0),
+        });
+        let workers: Vec<_> = (0..self.concurrency)
+            .map(|_| {
+                let plan = Arc::clone(&plan);
+                std::thread::spawn(move || plan.run_worker())
+            })
+            .collect();
+        let mut samples = Vec::new();
+        for worker in workers {
+            match worker.join() {
+                Ok(result) => samples.extend(result?),
+                Err(panic) => std::panic::resume_unwind(panic),
+            }
+        }
+
+        let elapsed = samples
+            .iter()
+            .map(|s| s.finished)
+            .max()
+            .map_or(Duration::ZERO, |end| {
+                end.saturating_duration_since(plan.measure_from)
+            });
+        Ok(BenchReport::from_samples(
+            &self.target,
+            self.concurrency,
+            elapsed,
+            &self.requests,
+            samples,
+        ))
+    }
+}
+
+/// Shared state of a run.
+struct Plan {
+    target: BenchTarget,
+    requests: Vec<BenchRequest>,
+    raw: Vec<Vec<u8>>,
+    schedule: Vec<usize>,
+    measure_from: Instant,
+    until: Instant,
+    max_requests: Option<u64>,
+    /// Requests started so far, across workers; picks the next request
+    /// from `schedule` so the mix holds however the workers interleave.
+    sent: AtomicU64,
+    /// Measured requests started so far, across workers.
+    measured: AtomicU64,
+}
+
+struct Sample {
+    request: usize,
+    latency: Duration,
+    status: Option<u16>,
+    finished: Instant,
+}
+
+impl Plan {
+    fn run_worker(&self) -> Result<Vec<Sample>, BenchError> {
+        let runtime = bench_runtime()?;
+        let client = Client::new();
+        let parser = Parser::new();
+        let mut samples = Vec::new();
+        runtime.block_on(async {
+            let Some(cx) = Cx::current() else {
+                return Err(BenchError::Runtime("runtime has no ambient Cx".to_string()));
+            };
+            loop {
+                let start = Instant::now();
+                if start >= self.until {
+                    break;
+                }
+                let measured = start >= self.measure_from;
+                if measured
+                    && self
+                        .max_requests
+                        .is_some_and(|max| self.measured.fetch_add(1, Ordering::Relaxed) >= max)
+                {
+                    break;
+                }
+                let request_id = self.sent.fetch_add(1, Ordering::Relaxed);
+                let index = self.schedule[(request_id % self.schedule.len() as u64) as usize];
+
+                let status = match &self.target {
+                    BenchTarget::App(app) => {
+                        call_app(app, &cx, &parser, &self.raw[index], request_id).await
+                    }
+                    BenchTarget::Url(base) => call_url(&client, base, &self.requests[index]).await,
+                };
+                let finished = Instant::now();
+                if measured {
+                    samples.push(Sample {
+                        request: index,
+                        latency: finished - start,
+                        status,
+                        finished,
+                    });
+                }
+            }
+            Ok(())
+        })?;
+        client.close_idle();
+        Ok(samples)
+    }
+}
+
+/// Parse, handle and serialize one request; `None` if it does not parse.
+async fn call_app(app: &App, cx: &Cx, parser: &Parser, raw: &[u8], request_id: u64) -> Option<u16> {
+    let mut request = parser.parse(raw).ok()?;
+    let ctx = RequestContext::with_overrides_and_body_limit(
+        cx.clone(),
+        request_id,
+        app.dependency_overrides(),
+        app.config().max_body_size,
+    );
+    let response = app.handle(&ctx, &mut request).await;
+    let status = response.status().as_u16();
+    match ResponseWriter::new().write(response) {
+        ResponseWrite::Full(bytes) => {
+            std::hint::black_box(bytes);
+        }
+        ResponseWrite::Stream(encoder) => {
+            let mut encoder = pin!(encoder);
+            while let Some(chunk) = poll_fn(|task| encoder.as_mut().poll_next(task)).await {
+                std::hint::black_box(chunk);
+            }
+        }
+    }
+    Some(status)
+}
+
+/// Send one request over the network; `None` on a transport error.
+async fn call_url(client: &Client, base: &str, request: &BenchRequest) -> Option<u16> {
+    let mut builder = client.request(request.method, format!("{base}{}", request.path));
+    for (name, value) in &request.headers {
+        builder = builder.header(name.clone(), value.clone());
+    }
+    if !request.body.is_empty() {
+        builder = builder.body(request.body.clone());
+    }
+    let mut response = builder.send().await.ok()?;
+    response.bytes().await.ok()?;
+    Some(response.status().as_u16())
+}
+
+fn bench_runtime() -> Result<Runtime, BenchError> {
+    let reactor = create_reactor().map_err(|e| BenchError::Runtime(format!("{e:?}")))?;
+    RuntimeBuilder::current_thread()
+        .with_reactor(reactor)
+        .build()
+        .map_err(|e| BenchError::Runtime(format!("{e:?}")))
+}
+
+/// Latency distribution of a set of requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LatencyStats {
+    /// Fastest request.
+    pub min: Duration,
+    /// Mean latency.
+    pub mean: Duration,
+    /// Median.
+    pub p50: Duration,
+    /// 90th percentile.
+    pub p90: Duration,
+    /// 99th percentile.
+    pub p99: Duration,
+    /// Slowest request.
+    pub max: Duration,
+}
+
+impl LatencyStats {
+    /// Stats over `latencies`, which are sorted in place. All zero when
+    /// empty.
+    #[must_use]
+    pub fn from_latencies(latencies: &mut [Duration]) -> Self {
+        latencies.sort_unstable();
+        let (Some(&min), Some(&max)) = (latencies.first(), latencies.last()) else {
+            return Self::default();
+        };
+        let total: Duration = latencies.iter().sum();
+        let count = u32::try_from(latencies.len()).unwrap_or(u32::MAX);
+        Self {
+            min,
+            mean: total / count,
+            p50: percentile(latencies, 50),
+            p90: percentile(latencies, 90),
+            p99: percentile(latencies, 99),
+            max,
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted, non-empty `latencies`.
+fn percentile(latencies: &[Duration], pct: usize) -> Duration {
+    let rank = (latencies.len() * pct).div_ceil(100).max(1);
+    latencies[rank - 1]
+}
+
+/// Results for one request of the mix.
+#[derive(Debug, Clone)]
+pub struct EndpointReport {
+    /// The request's name.
+    pub name: String,
+    /// Completed requests.
+    pub requests: u64,
+    /// Requests that got no response.
+    pub errors: u64,
+    /// Latency of completed requests.
+    pub latency: LatencyStats,
+}
+
+/// Results of a [`Bench`] run.
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    /// Where the requests went, e.g. `in-process` or the base URL.
+    pub target: String,
+    /// Number of workers.
+    pub concurrency: usize,
+    /// Measured time, excluding the warmup.
+    pub elapsed: Duration,
+    /// Completed requests (any status).
+    pub requests: u64,
+    /// Requests that got no response.
+    pub errors: u64,
+    /// Completed requests by status code.
+    pub statuses: BTreeMap<u16, u64>,
+    /// Latency of all completed requests.
+    pub latency: LatencyStats,
+    /// Per-request results, in the order the requests were added.
+    pub endpoints: Vec<EndpointReport>,
+}
+
+impl BenchReport {
+    fn from_samples(
+        target: &BenchTarget,
+        concurrency: usize,
+        elapsed: Duration,
+        mix: &[BenchRequest],
+        samples: Vec<Sample>,
+    ) -> Self {
+        let mut all = Vec::with_capacity(samples.len());
+        let mut per_request: Vec<Vec<Duration>> = vec![Vec::new(); mix.len()];
+        let mut errors = vec![0_u64; mix.len()];
+        let mut statuses = BTreeMap::new();
+        for sample in samples {
+            match sample.status {
+                Some(status) => {
+                    *statuses.entry(status).or_insert(0) += 1;
+                    all.push(sample.latency);
+                    per_request[sample.request].push(sample.latency);
+                }
+                None => errors[sample.request] += 1,
+            }
+        }
+        let endpoints = mix
+            .iter()
+            .zip(per_request.iter_mut().zip(&errors))
+            .map(|(request, (latencies, &failed))| EndpointReport {
+                name: request.name.clone(),
+                requests: latencies.len() as u64,
+                errors: failed,
+                latency: LatencyStats::from_latencies(latencies),
+            })
+            .collect();
+        Self {
+            target: target.to_string(),
+            concurrency,
+            elapsed,
+            requests: all.len() as u64,
+            errors: errors.iter().sum(),
+            statuses,
+            latency: LatencyStats::from_latencies(&mut all),
+            endpoints,
+        }
+    }
+
+    /// Completed requests per second.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn rps(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            return 0.0;
+        }
+        self.requests as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// Completed requests with a 4xx or 5xx status.
+    #[must_use]
+    pub fn failed_statuses(&self) -> u64 {
+        self.statuses.range(400..).map(|(_, count)| count).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastapi_core::{Request, Response};
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn latency_stats_use_nearest_rank_percentiles() {
+        let mut latencies = ms(&(1..=100).rev().collect::<Vec<_>>());
+        let stats = LatencyStats::from_latencies(&mut latencies);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.p50, Duration::from_millis(50));
+        assert_eq!(stats.p90, Duration::from_millis(90));
+        assert_eq!(stats.p99, Duration::from_millis(99));
+        assert_eq!(stats.max, Duration::from_millis(100));
+        assert_eq!(stats.mean, Duration::from_micros(50_500));
+
+        assert_eq!(
+            LatencyStats::from_latencies(&mut []),
+            LatencyStats::default()
+        );
+    }
+
+    #[test]
+    fn request_serializes_to_parseable_http1() {
+        let request = BenchRequest::post("/items?draft=1")
+            .header("content-type", "application/json")
+            .body(r#"{"a":1}"#);
+        let parsed = Parser::new().parse(&request.to_http1()).unwrap();
+        assert_eq!(parsed.method(), Method::Post);
+        assert_eq!(parsed.path(), "/items");
+        assert_eq!(parsed.query(), Some("draft=1"));
+        assert_eq!(request.name, "POST /items?draft=1");
+    }
+
+    #[test]
+    fn in_process_run_respects_mix_and_request_cap() {
+        let app = App::builder()
+            .get("/a", |_ctx: &RequestContext, _req: &mut Request| {
+                std::future::ready(Response::ok())
+            })
+            .build();
+        let report = Bench::app(Arc::new(app))
+            .concurrency(2)
+            .max_requests(40)
+            .request(BenchRequest::get("/a").weight(3))
+            .request(BenchRequest::get("/missing"))
+            .run()
+            .unwrap();
+
+        assert_eq!(report.requests, 40);
+        assert_eq!(report.errors, 0);
+        assert_eq!(report.statuses[&200], 30);
+        assert_eq!(report.statuses[&404], 10);
+        assert_eq!(report.failed_statuses(), 10);
+        assert_eq!(report.endpoints[0].requests, 30);
+        assert!(report.rps() > 0.0);
+        assert!(report.latency.p50 <= report.latency.p99);
+    }
+
+    #[test]
+    fn empty_mix_is_rejected() {
+        let err = Bench::url("http://127.0.0.1:1").run().unwrap_err();
+        assert!(matches!(err, BenchError::NoRequests));
+    }
+}
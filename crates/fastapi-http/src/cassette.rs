@@ -0,0 +1,449 @@
+//! Record-and-replay of [`Client`] exchanges for tests.
+//!
+//! Handlers that proxy or call third-party APIs are hard to test against
+//! live upstreams. Attach a [`Cassette`] to the client they use: the first
+//! run goes to the network and writes every exchange to a JSON file, later
+//! runs answer from that file without opening a socket.
+//!
+//! ```ignore
+//! use fastapi_http::cassette::Cassette;
+//! use fastapi_http::client::Client;
+//!
+//! let cassette = Cassette::open("tests/cassettes/weather.json")?;
+//! let client = Client::builder().cassette(cassette.clone()).build();
+//! // ... hand `client` to the app's state and run the test ...
+//! assert!(cassette.unplayed().is_empty(), "{:?}", cassette.unplayed());
+//! ```
+//!
+//! Requests match a recording by method, URL and body, each recording
+//! answering once, in order. Request headers are stored for reference with
+//! credentials redacted, but only those named with
+//! [`Cassette::match_header`] take part in matching. Delete the file or set
+//! `CASSETTE_RECORD=1` to record again.
+//!
+//! [`Client`]: crate::client::Client
+
+use crate::client::ClientError;
+use fastapi_core::Method;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Request headers whose values are not written to cassette files.
+pub const DEFAULT_REDACTED_HEADERS: &[&str] = &["authorization", "cookie", "proxy-authorization"];
+
+const REDACTED: &str = "<REDACTED>";
+
+/// Recorded client exchanges, played back in place of the network.
+///
+/// Cloning is cheap; clones share the recordings, so a test can keep one
+/// clone to inspect after handing another to the client.
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    path: PathBuf,
+    recording: bool,
+    match_body: bool,
+    match_headers: Vec<String>,
+    redact_headers: Vec<String>,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    interactions: Vec<Interaction>,
+    played: Vec<bool>,
+}
+
+/// On-disk layout of a cassette file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+/// A request as stored in a cassette.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct RecordedRequest {
+    method: String,
+    url: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    headers: Vec<(String, String)>,
+    #[serde(flatten)]
+    body: RecordedBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    #[serde(flatten)]
+    body: RecordedBody,
+}
+
+/// A body kept readable in the file: text as is, anything else as hex.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct RecordedBody {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_hex: Option<String>,
+}
+
+impl RecordedBody {
+    fn new(bytes: &[u8]) -> Self {
+        if bytes.is_empty() {
+            return Self::default();
+        }
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self {
+                body: Some(text.to_string()),
+                body_hex: None,
+            },
+            Err(_) => Self {
+                body: None,
+                body_hex: Some(bytes.iter().fold(String::new(), |mut hex, b| {
+                    let _ = write!(hex, "{b:02x}");
+                    hex
+                })),
+            },
+        }
+    }
+
+    fn to_bytes(&self) -> Option<Vec<u8>> {
+        if let Some(text) = &self.body {
+            return Some(text.clone().into_bytes());
+        }
+        let Some(hex) = &self.body_hex else {
+            return Some(Vec::new());
+        };
+        if hex.len() % 2 != 0 {
+            return None;
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+            .collect()
+    }
+}
+
+impl Cassette {
+    /// Record: send every request to the network and write the exchanges
+    /// to `path`, replacing any earlier recording.
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            recording: true,
+            match_body: true,
+            match_headers: Vec::new(),
+            redact_headers: DEFAULT_REDACTED_HEADERS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            state: Arc::default(),
+        }
+    }
+
+    /// Replay: answer requests from the recording at `path`, never touching
+    /// the network. Unmatched requests fail with [`ClientError::Cassette`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or isn't a cassette.
+    pub fn replay(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let mut cassette = Self::record(path);
+        let data = std::fs::read_to_string(&cassette.path)?;
+        let file: CassetteFile = serde_json::from_str(&data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        cassette.recording = false;
+        cassette.state = Arc::new(Mutex::new(State {
+            played: vec![false; file.interactions.len()],
+            interactions: file.interactions,
+        }));
+        Ok(cassette)
+    }
+
+    /// Replay `path` if it exists, otherwise record it. A non-empty
+    /// `CASSETTE_RECORD` (other than `0`) forces recording.
+    ///
+    /// # Errors
+    ///
+    /// See [`replay`](Self::replay).
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let rerecord =
+            std::env::var_os("CASSETTE_RECORD").is_some_and(|v| !v.is_empty() && v != "0");
+        if rerecord || !path.exists() {
+            Ok(Self::record(path))
+        } else {
+            Self::replay(path)
+        }
+    }
+
+    /// Whether request bodies take part in matching (the default).
+    #[must_use]
+    pub fn match_body(mut self, enabled: bool) -> Self {
+        self.match_body = enabled;
+        self
+    }
+
+    /// Also match on the value of request header `name`.
+    #[must_use]
+    pub fn match_header(mut self, name: &str) -> Self {
+        self.match_headers.push(name.to_ascii_lowercase());
+        self
+    }
+
+    /// Don't write the value of request header `name` to the file, in
+    /// addition to [`DEFAULT_REDACTED_HEADERS`].
+    #[must_use]
+    pub fn redact_header(mut self, name: &str) -> Self {
+        self.redact_headers.push(name.to_ascii_lowercase());
+        self
+    }
+
+    /// The cassette file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether requests go to the network and get recorded.
+    #[must_use]
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Number of recorded exchanges.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.state
+            .lock()
+            .map_or(0, |state| state.interactions.len())
+    }
+
+    /// Whether nothing has been recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Recordings not replayed yet, as `"METHOD url"`. Always empty while
+    /// recording.
+    #[must_use]
+    pub fn unplayed(&self) -> Vec<String> {
+        let Ok(state) = self.state.lock() else {
+            return Vec::new();
+        };
+        state
+            .interactions
+            .iter()
+            .zip(&state.played)
+            .filter(|(_, played)| !**played)
+            .map(|(i, _)| format!("{} {}", i.request.method, i.request.url))
+            .collect()
+    }
+
+    /// Describe an outgoing request the way it is stored.
+    pub(crate) fn request(
+        &self,
+        method: Method,
+        url: &str,
+        headers: &[(String, Vec<u8>)],
+        body: &[u8],
+    ) -> RecordedRequest {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if self.redact_headers.contains(name) {
+                    REDACTED.to_string()
+                } else {
+                    String::from_utf8_lossy(value).into_owned()
+                };
+                (name.clone(), value)
+            })
+            .collect();
+        RecordedRequest {
+            method: method.as_str().to_string(),
+            url: url.to_string(),
+            headers,
+            body: RecordedBody::new(body),
+        }
+    }
+
+    /// Take the first unplayed recording matching `request`, returning its
+    /// status, headers and body.
+    pub(crate) fn play(
+        &self,
+        request: &RecordedRequest,
+    ) -> Result<(u16, Vec<(String, Vec<u8>)>, Vec<u8>), ClientError> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| ClientError::Cassette("cassette lock poisoned".into()))?;
+        let State {
+            interactions,
+            played,
+        } = &mut *state;
+        let index = interactions
+            .iter()
+            .zip(played.iter())
+            .position(|(recorded, played)| !played && self.matches(&recorded.request, request))
+            .ok_or_else(|| {
+                ClientError::Cassette(format!(
+                    "no recording in {} for {} {}",
+                    self.path.display(),
+                    request.method,
+                    request.url
+                ))
+            })?;
+        played[index] = true;
+
+        let response = &interactions[index].response;
+        let body = response.body.to_bytes().ok_or_else(|| {
+            ClientError::Cassette(format!("invalid body_hex in {}", self.path.display()))
+        })?;
+        let headers = response
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone().into_bytes()))
+            .collect();
+        Ok((response.status, headers, body))
+    }
+
+    /// Append an exchange and rewrite the file.
+    pub(crate) fn record_exchange(
+        &self,
+        request: RecordedRequest,
+        status: u16,
+        headers: &[(String, Vec<u8>)],
+        body: &[u8],
+    ) -> Result<(), ClientError> {
+        let response = RecordedResponse {
+            status,
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.clone(), String::from_utf8_lossy(value).into_owned()))
+                .collect(),
+            body: RecordedBody::new(body),
+        };
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| ClientError::Cassette("cassette lock poisoned".into()))?;
+        state.interactions.push(Interaction { request, response });
+        state.played.push(true);
+
+        let file = CassetteFile {
+            interactions: state.interactions.clone(),
+        };
+        self.save(&file).map_err(|e| {
+            ClientError::Cassette(format!("failed to write {}: {e}", self.path.display()))
+        })
+    }
+
+    fn save(&self, file: &CassetteFile) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(file).map_err(io::Error::other)?;
+        std::fs::write(&self.path, json)
+    }
+
+    fn matches(&self, recorded: &RecordedRequest, request: &RecordedRequest) -> bool {
+        let header = |req: &RecordedRequest, name: &str| {
+            req.headers
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v.clone())
+        };
+        recorded.method == request.method
+            && recorded.url == request.url
+            && (!self.match_body || recorded.body == request.body)
+            && self
+                .match_headers
+                .iter()
+                .all(|name| header(recorded, name) == header(request, name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cassette(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "fastapi_cassette_{}_{name}.json",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn recordings_round_trip_through_the_file() {
+        let path = temp_cassette("round_trip");
+        let recorder = Cassette::record(&path);
+        let headers = vec![
+            ("authorization".to_string(), b"Bearer secret".to_vec()),
+            ("accept".to_string(), b"application/json".to_vec()),
+        ];
+        let request = recorder.request(Method::Post, "http://api/items", &headers, b"{}");
+        let response_headers = vec![("content-type".to_string(), b"image/png".to_vec())];
+        recorder
+            .record_exchange(request, 201, &response_headers, &[0x89, b'P', 0xff])
+            .unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains(REDACTED) && !saved.contains("secret"));
+        assert!(saved.contains("\"body_hex\": \"8950ff\""));
+
+        let player = Cassette::replay(&path).unwrap();
+        assert_eq!(player.unplayed(), vec!["POST http://api/items"]);
+        let request = player.request(Method::Post, "http://api/items", &[], b"{}");
+        let (status, headers, body) = player.play(&request).unwrap();
+        assert_eq!(status, 201);
+        assert_eq!(headers, response_headers);
+        assert_eq!(body, vec![0x89, b'P', 0xff]);
+        assert!(player.unplayed().is_empty());
+        assert!(matches!(
+            player.play(&request),
+            Err(ClientError::Cassette(_))
+        ));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn matching_uses_body_and_selected_headers() {
+        let path = temp_cassette("matching");
+        let recorder = Cassette::record(&path);
+        for (tenant, body) in [("a", "1"), ("b", "2")] {
+            let headers = vec![("x-tenant".to_string(), tenant.as_bytes().to_vec())];
+            let request = recorder.request(Method::Get, "http://api/", &headers, body.as_bytes());
+            recorder
+                .record_exchange(request, 200, &[], body.as_bytes())
+                .unwrap();
+        }
+
+        let player = Cassette::replay(&path).unwrap();
+        let request = player.request(Method::Get, "http://api/", &[], b"2");
+        assert_eq!(player.play(&request).unwrap().2, b"2");
+
+        let player = Cassette::replay(&path)
+            .unwrap()
+            .match_body(false)
+            .match_header("X-Tenant");
+        let headers = vec![("x-tenant".to_string(), b"b".to_vec())];
+        let request = player.request(Method::Get, "http://api/", &headers, b"");
+        assert_eq!(player.play(&request).unwrap().2, b"2");
+        assert!(player.play(&request).is_err());
+
+        let _ = std::fs::remove_file(path);
+    }
+}
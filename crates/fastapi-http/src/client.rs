@@ -0,0 +1,2009 @@
+//! HTTP client over asupersync sockets.
+//!
+//! [`Client`] speaks HTTP/1.1, or HTTP/2 with prior knowledge (h2c), to
+//! `http://` URLs. Requests are built with [`RequestBuilder`], idle
+//! connections are kept per host for reuse, and response bodies are read
+//! chunk by chunk as they arrive:
+//!
+//! ```ignore
+//! use fastapi_http::client::Client;
+//! use std::time::Duration;
+//!
+//! let client = Client::builder()
+//!     .connect_timeout(Duration::from_secs(2))
+//!     .timeout(Duration::from_secs(10))
+//!     .build();
+//!
+//! let mut resp = client
+//!     .post("http://127.0.0.1:8000/items")
+//!     .header("content-type", "application/json")
+//!     .body(r#"{"name":"widget"}"#)
+//!     .send()
+//!     .await?;
+//! assert_eq!(resp.status().as_u16(), 201);
+//! while let Some(chunk) = resp.chunk().await? {
+//!     // ...
+//! }
+//! ```
+//!
+//! A reverse-proxy handler forwards the incoming request and streams the
+//! upstream response back with [`Client::forward`].
+//!
+//! For tests, [`ClientBuilder::cassette`] records exchanges to a file and
+//! replays them on later runs; see [`crate::cassette`].
+//!
+//! Limitations: there is no TLS, so `https://` URLs are rejected, and an
+//! HTTP/2 connection carries one request at a time (it is pooled and reused,
+//! but streams are not multiplexed).
+
+use crate::cassette::Cassette;
+use crate::connection::is_standard_hop_by_hop_header;
+use crate::http2::{
+    Frame, FrameType, FramedH2, HpackDecoder, Http2Error, PREFACE,
+    hpack_encode_literal_without_indexing,
+};
+use crate::server::{current_time, read_into_buffer, write_all};
+use asupersync::io::AsyncWrite;
+use asupersync::net::TcpStream;
+use asupersync::stream::Stream;
+use asupersync::time::timeout;
+use fastapi_core::{Body, Method, Request, RequestBodyStream, Response, ResponseBody, StatusCode};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::future::{Future, poll_fn};
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Default time allowed to establish a TCP connection.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time allowed from sending a request until its response head arrives.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default time allowed for each read of a response body.
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default number of idle connections kept per host.
+pub const DEFAULT_MAX_IDLE_PER_HOST: usize = 8;
+
+/// Default time an idle connection stays in the pool.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Default limit on the size of an HTTP/1.1 response head.
+pub const DEFAULT_MAX_RESPONSE_HEAD_SIZE: usize = 64 * 1024;
+
+const READ_CHUNK_SIZE: usize = 16 * 1024;
+const H2_MAX_FRAME_SIZE: u32 = 16_384;
+const H2_DEFAULT_WINDOW: i64 = 65_535;
+const FLAG_ACK: u8 = 0x1;
+const FLAG_END_STREAM: u8 = 0x1;
+const FLAG_END_HEADERS: u8 = 0x4;
+const FLAG_PADDED: u8 = 0x8;
+const FLAG_PRIORITY: u8 = 0x20;
+
+/// Wire protocol used for new connections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClientProtocol {
+    /// HTTP/1.1 with keep-alive.
+    #[default]
+    Http1,
+    /// Cleartext HTTP/2 without an upgrade round trip (h2c prior knowledge).
+    Http2PriorKnowledge,
+}
+
+/// Errors returned by [`Client`] requests.
+#[derive(Debug)]
+pub enum ClientError {
+    /// The URL is malformed or uses an unsupported scheme.
+    InvalidUrl(String),
+    /// A request header name or value is not valid.
+    InvalidHeader(String),
+    /// The TCP connection could not be established.
+    Connect(io::Error),
+    /// The connection was closed before a response arrived.
+    Closed,
+    /// A timeout expired: `"connect"`, `"response"` or `"read"`.
+    Timeout(&'static str),
+    /// I/O error on an established connection.
+    Io(io::Error),
+    /// The server sent a malformed HTTP/1.1 response.
+    Protocol(String),
+    /// HTTP/2 framing or header compression error.
+    Http2(Http2Error),
+    /// The streamed request body failed.
+    Body(String),
+    /// No cassette recording matches the request, or the cassette file
+    /// couldn't be written.
+    Cassette(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUrl(url) => write!(f, "invalid URL: {url}"),
+            Self::InvalidHeader(name) => write!(f, "invalid request header: {name}"),
+            Self::Connect(e) => write!(f, "connect failed: {e}"),
+            Self::Closed => write!(f, "connection closed before a response was received"),
+            Self::Timeout(phase) => write!(f, "{phase} timeout expired"),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Protocol(m) => write!(f, "invalid response: {m}"),
+            Self::Http2(e) => write!(f, "{e}"),
+            Self::Body(m) => write!(f, "request body error: {m}"),
+            Self::Cassette(m) => write!(f, "cassette: {m}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Connect(e) | Self::Io(e) => Some(e),
+            Self::Http2(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(e: io::Error) -> Self {
+        if is_disconnect(&e) {
+            Self::Closed
+        } else {
+            Self::Io(e)
+        }
+    }
+}
+
+impl From<Http2Error> for ClientError {
+    fn from(e: Http2Error) -> Self {
+        match e {
+            Http2Error::Io(e) if e.kind() == io::ErrorKind::UnexpectedEof || is_disconnect(&e) => {
+                Self::Closed
+            }
+            other => Self::Http2(other),
+        }
+    }
+}
+
+fn is_disconnect(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::BrokenPipe
+    )
+}
+
+#[derive(Debug, Clone)]
+struct ClientConfig {
+    connect_timeout: Duration,
+    timeout: Duration,
+    read_timeout: Duration,
+    max_idle_per_host: usize,
+    idle_timeout: Duration,
+    protocol: ClientProtocol,
+    max_response_head_size: usize,
+    user_agent: Option<String>,
+    cassette: Option<Cassette>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            max_idle_per_host: DEFAULT_MAX_IDLE_PER_HOST,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            protocol: ClientProtocol::Http1,
+            max_response_head_size: DEFAULT_MAX_RESPONSE_HEAD_SIZE,
+            user_agent: Some(concat!("fastapi-rust/", env!("CARGO_PKG_VERSION")).to_string()),
+            cassette: None,
+        }
+    }
+}
+
+/// Builder for a [`Client`].
+#[derive(Debug, Clone, Default)]
+pub struct ClientBuilder {
+    config: ClientConfig,
+}
+
+impl ClientBuilder {
+    /// Time allowed to establish a TCP connection.
+    #[must_use]
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.config.connect_timeout = timeout;
+        self
+    }
+
+    /// Time allowed from sending a request until its response head arrives,
+    /// including connecting. Overridable per request.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = timeout;
+        self
+    }
+
+    /// Time allowed for each read of a response body.
+    #[must_use]
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.config.read_timeout = timeout;
+        self
+    }
+
+    /// Idle connections kept per host; `0` disables pooling.
+    #[must_use]
+    pub fn max_idle_per_host(mut self, max: usize) -> Self {
+        self.config.max_idle_per_host = max;
+        self
+    }
+
+    /// How long an idle connection stays in the pool.
+    #[must_use]
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.config.idle_timeout = timeout;
+        self
+    }
+
+    /// Speak HTTP/2 (h2c prior knowledge) instead of HTTP/1.1.
+    #[must_use]
+    pub fn http2_prior_knowledge(mut self, enabled: bool) -> Self {
+        self.config.protocol = if enabled {
+            ClientProtocol::Http2PriorKnowledge
+        } else {
+            ClientProtocol::Http1
+        };
+        self
+    }
+
+    /// Limit on the size of an HTTP/1.1 response head.
+    #[must_use]
+    pub fn max_response_head_size(mut self, size: usize) -> Self {
+        self.config.max_response_head_size = size;
+        self
+    }
+
+    /// `User-Agent` sent when a request doesn't set one.
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.config.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Record exchanges to `cassette`, or replay them from it without
+    /// touching the network. Streamed request bodies are read into memory
+    /// first, and each response body is read in full before it is returned.
+    #[must_use]
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.config.cassette = Some(cassette);
+        self
+    }
+
+    /// Build the client.
+    #[must_use]
+    pub fn build(self) -> Client {
+        Client {
+            inner: Arc::new(ClientInner {
+                config: self.config,
+                pool: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+}
+
+/// An HTTP client with a connection pool.
+///
+/// Cloning is cheap; clones share the pool.
+#[derive(Clone)]
+pub struct Client {
+    inner: Arc<ClientInner>,
+}
+
+struct ClientInner {
+    config: ClientConfig,
+    pool: Mutex<HashMap<String, Vec<IdleConnection>>>,
+}
+
+struct IdleConnection {
+    conn: Connection,
+    since: Instant,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("config", &self.inner.config)
+            .field("idle_connections", &self.idle_connections())
+            .finish()
+    }
+}
+
+impl Client {
+    /// A client with default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        ClientBuilder::default().build()
+    }
+
+    /// Start configuring a client.
+    #[must_use]
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Start a request with the given method.
+    pub fn request(&self, method: Method, url: impl Into<String>) -> RequestBuilder {
+        RequestBuilder {
+            client: self.clone(),
+            method,
+            url: url.into(),
+            headers: Vec::new(),
+            body: RequestBody::Empty,
+            timeout: None,
+        }
+    }
+
+    /// Start a `GET` request.
+    pub fn get(&self, url: impl Into<String>) -> RequestBuilder {
+        self.request(Method::Get, url)
+    }
+
+    /// Start a `POST` request.
+    pub fn post(&self, url: impl Into<String>) -> RequestBuilder {
+        self.request(Method::Post, url)
+    }
+
+    /// Start a `PUT` request.
+    pub fn put(&self, url: impl Into<String>) -> RequestBuilder {
+        self.request(Method::Put, url)
+    }
+
+    /// Start a `PATCH` request.
+    pub fn patch(&self, url: impl Into<String>) -> RequestBuilder {
+        self.request(Method::Patch, url)
+    }
+
+    /// Start a `DELETE` request.
+    pub fn delete(&self, url: impl Into<String>) -> RequestBuilder {
+        self.request(Method::Delete, url)
+    }
+
+    /// Start a `HEAD` request.
+    pub fn head(&self, url: impl Into<String>) -> RequestBuilder {
+        self.request(Method::Head, url)
+    }
+
+    /// Number of idle connections currently pooled, across all hosts.
+    pub fn idle_connections(&self) -> usize {
+        self.inner
+            .pool
+            .lock()
+            .map_or(0, |pool| pool.values().map(Vec::len).sum())
+    }
+
+    /// Close all idle pooled connections.
+    pub fn close_idle(&self) {
+        if let Ok(mut pool) = self.inner.pool.lock() {
+            pool.clear();
+        }
+    }
+
+    /// Forward `req` to `upstream` (e.g. `"http://10.0.0.5:8080"`) and
+    /// stream the answer back, for reverse-proxy handlers.
+    ///
+    /// The request path and query are appended to `upstream`. The method,
+    /// headers and body (streamed if it is) are passed along, except `Host`
+    /// and hop-by-hop headers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError`] if the upstream request fails before its
+    /// response head arrives. Later body errors end the response stream early.
+    pub async fn forward(
+        &self,
+        req: &mut Request,
+        upstream: &str,
+    ) -> Result<Response, ClientError> {
+        let mut url = upstream.trim_end_matches('/').to_string();
+        url.push_str(req.path());
+        if let Some(query) = req.query() {
+            url.push('?');
+            url.push_str(query);
+        }
+
+        let mut builder = self.request(req.method(), url);
+        for (name, value) in req.headers().iter() {
+            if name.eq_ignore_ascii_case("host") || is_standard_hop_by_hop_header(name) {
+                continue;
+            }
+            builder = builder.header(name, value);
+        }
+        builder.body = match req.take_body() {
+            Body::Empty => RequestBody::Empty,
+            Body::Bytes(bytes) => RequestBody::Bytes(bytes),
+            body => match body.into_stream() {
+                Some((stream, length)) => RequestBody::Stream {
+                    chunks: Box::pin(RequestChunks(stream)),
+                    length,
+                },
+                None => RequestBody::Empty,
+            },
+        };
+        Ok(builder.send().await?.into_response())
+    }
+
+    async fn execute(
+        &self,
+        outgoing: Outgoing,
+        body: RequestBody,
+    ) -> Result<ClientResponse, ClientError> {
+        let config = &self.inner.config;
+        let key = outgoing.target.authority.clone();
+
+        let mut body = Some(body);
+        if let Some(mut conn) = self.checkout(&key) {
+            // A pooled connection may have been closed by the server while
+            // idle; replay the request on a fresh one if nothing came back.
+            let current = body.take().unwrap_or(RequestBody::Empty);
+            let replay = current.try_clone();
+            match conn.exchange(&outgoing, current, config).await {
+                Ok(head) => return Ok(self.response(key, conn, head)),
+                Err(ClientError::Closed) if replay.is_some() => body = replay,
+                Err(err) => return Err(err),
+            }
+        }
+
+        let mut conn = self.connect(&outgoing.target).await?;
+        let head = conn
+            .exchange(&outgoing, body.unwrap_or(RequestBody::Empty), config)
+            .await?;
+        Ok(self.response(key, conn, head))
+    }
+
+    async fn connect(&self, target: &Target) -> Result<Connection, ClientError> {
+        let config = &self.inner.config;
+        let connect = Box::pin(TcpStream::connect(target.authority.as_str()));
+        let stream = match timeout(current_time(), config.connect_timeout, connect).await {
+            Ok(Ok(stream)) => stream,
+            Ok(Err(e)) => return Err(ClientError::Connect(e)),
+            Err(_elapsed) => return Err(ClientError::Timeout("connect")),
+        };
+        match config.protocol {
+            ClientProtocol::Http1 => Ok(Connection::Http1(Http1Connection {
+                stream,
+                rx: Vec::new(),
+            })),
+            ClientProtocol::Http2PriorKnowledge => {
+                Ok(Connection::Http2(Http2Connection::handshake(stream).await?))
+            }
+        }
+    }
+
+    fn response(&self, key: String, conn: Connection, head: ResponseHead) -> ClientResponse {
+        let mut response = ClientResponse {
+            status: StatusCode::from_u16(head.status),
+            headers: head.headers,
+            body: head.body,
+            reusable: head.keep_alive,
+            conn: Some(conn),
+            client: self.clone(),
+            key,
+        };
+        if matches!(response.body, BodyState::Done) {
+            response.finish();
+        }
+        response
+    }
+
+    /// A response answered from a cassette, with its body in memory.
+    fn recorded(
+        &self,
+        status: u16,
+        headers: Vec<(String, Vec<u8>)>,
+        body: Vec<u8>,
+    ) -> ClientResponse {
+        ClientResponse {
+            status: StatusCode::from_u16(status),
+            headers,
+            body: BodyState::Recorded(body),
+            reusable: false,
+            conn: None,
+            client: self.clone(),
+            key: String::new(),
+        }
+    }
+
+    fn checkout(&self, key: &str) -> Option<Connection> {
+        let idle_timeout = self.inner.config.idle_timeout;
+        let mut pool = self.inner.pool.lock().ok()?;
+        let idle = pool.get_mut(key)?;
+        while let Some(entry) = idle.pop() {
+            if entry.since.elapsed() < idle_timeout {
+                return Some(entry.conn);
+            }
+        }
+        None
+    }
+
+    fn release(&self, key: String, conn: Connection) {
+        let config = &self.inner.config;
+        if config.max_idle_per_host == 0 {
+            return;
+        }
+        if let Ok(mut pool) = self.inner.pool.lock() {
+            let idle = pool.entry(key).or_default();
+            idle.retain(|entry| entry.since.elapsed() < config.idle_timeout);
+            if idle.len() < config.max_idle_per_host {
+                idle.push(IdleConnection {
+                    conn,
+                    since: Instant::now(),
+                });
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Requests
+// =============================================================================
+
+type ChunkStream = Pin<Box<dyn Stream<Item = io::Result<Vec<u8>>> + Send>>;
+
+enum RequestBody {
+    Empty,
+    Bytes(Vec<u8>),
+    Stream {
+        chunks: ChunkStream,
+        length: Option<usize>,
+    },
+}
+
+impl RequestBody {
+    /// A copy for replaying the request; streams can't be replayed.
+    fn try_clone(&self) -> Option<Self> {
+        match self {
+            Self::Empty => Some(Self::Empty),
+            Self::Bytes(bytes) => Some(Self::Bytes(bytes.clone())),
+            Self::Stream { .. } => None,
+        }
+    }
+}
+
+/// Adapts a plain chunk stream to the internal fallible stream.
+struct InfallibleChunks(Pin<Box<dyn Stream<Item = Vec<u8>> + Send>>);
+
+impl Stream for InfallibleChunks {
+    type Item = io::Result<Vec<u8>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.as_mut().poll_next(cx).map(|chunk| chunk.map(Ok))
+    }
+}
+
+/// Adapts an incoming request body to the internal fallible stream.
+struct RequestChunks(RequestBodyStream);
+
+impl Stream for RequestChunks {
+    type Item = io::Result<Vec<u8>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0
+            .as_mut()
+            .poll_next(cx)
+            .map(|chunk| chunk.map(|r| r.map_err(|e| io::Error::other(e.to_string()))))
+    }
+}
+
+async fn next_chunk(chunks: &mut ChunkStream) -> Result<Option<Vec<u8>>, ClientError> {
+    poll_fn(|cx| chunks.as_mut().poll_next(cx))
+        .await
+        .transpose()
+        .map_err(|e| ClientError::Body(e.to_string()))
+}
+
+/// A request being built; send it with [`RequestBuilder::send`].
+pub struct RequestBuilder {
+    client: Client,
+    method: Method,
+    url: String,
+    headers: Vec<(String, Vec<u8>)>,
+    body: RequestBody,
+    timeout: Option<Duration>,
+}
+
+impl fmt::Debug for RequestBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestBuilder")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("headers", &self.headers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl RequestBuilder {
+    /// Add a header. `Host` replaces the one derived from the URL;
+    /// `Content-Length`, `Transfer-Encoding` and `Connection` are managed by
+    /// the client and ignored.
+    #[must_use]
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<Vec<u8>>) -> Self {
+        self.headers
+            .push((name.into().to_ascii_lowercase(), value.into()));
+        self
+    }
+
+    /// Send `body` with a `Content-Length`.
+    #[must_use]
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = RequestBody::Bytes(body.into());
+        self
+    }
+
+    /// Stream the body from `stream`. Without a known `content_length` it is
+    /// sent chunked (HTTP/1.1) or as DATA frames until the stream ends.
+    #[must_use]
+    pub fn body_stream<S>(mut self, stream: S, content_length: Option<usize>) -> Self
+    where
+        S: Stream<Item = Vec<u8>> + Send + 'static,
+    {
+        self.body = RequestBody::Stream {
+            chunks: Box::pin(InfallibleChunks(Box::pin(stream))),
+            length: content_length,
+        };
+        self
+    }
+
+    /// Override the client's request timeout for this request.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Send the request and wait for the response head.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError`] for an invalid URL or header, a connection
+    /// failure, a timeout, or a malformed response, and
+    /// [`ClientError::Cassette`] when replaying a request that wasn't recorded.
+    pub async fn send(self) -> Result<ClientResponse, ClientError> {
+        match self.client.inner.config.cassette.clone() {
+            Some(cassette) => self.send_with_cassette(&cassette).await,
+            None => self.dispatch().await,
+        }
+    }
+
+    /// Replay the exchange from `cassette`, or send it and record it.
+    async fn send_with_cassette(
+        mut self,
+        cassette: &Cassette,
+    ) -> Result<ClientResponse, ClientError> {
+        let body = match std::mem::replace(&mut self.body, RequestBody::Empty) {
+            RequestBody::Empty => Vec::new(),
+            RequestBody::Bytes(bytes) => bytes,
+            RequestBody::Stream { mut chunks, .. } => {
+                let mut bytes = Vec::new();
+                while let Some(chunk) = next_chunk(&mut chunks).await? {
+                    bytes.extend_from_slice(&chunk);
+                }
+                bytes
+            }
+        };
+        let request = cassette.request(self.method, &self.url, &self.headers, &body);
+        let client = self.client.clone();
+        if !cassette.is_recording() {
+            let (status, headers, body) = cassette.play(&request)?;
+            return Ok(client.recorded(status, headers, body));
+        }
+
+        if !body.is_empty() {
+            self.body = RequestBody::Bytes(body);
+        }
+        let mut response = self.dispatch().await?;
+        let body = response.bytes().await?;
+        let status = response.status.as_u16();
+        cassette.record_exchange(request, status, &response.headers, &body)?;
+        Ok(client.recorded(status, std::mem::take(&mut response.headers), body))
+    }
+
+    async fn dispatch(self) -> Result<ClientResponse, ClientError> {
+        let Self {
+            client,
+            method,
+            url,
+            headers,
+            body,
+            timeout: limit,
+        } = self;
+        let outgoing = Outgoing::new(method, Target::parse(&url)?, headers, &body, &client)?;
+        let limit = limit.unwrap_or(client.inner.config.timeout);
+        let exchange = Box::pin(client.execute(outgoing, body));
+        match timeout(current_time(), limit, exchange).await {
+            Ok(result) => result,
+            Err(_elapsed) => Err(ClientError::Timeout("response")),
+        }
+    }
+}
+
+/// Where a request goes, split out of its URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Target {
+    /// `host:port` to connect to; also the pool key.
+    authority: String,
+    /// `Host` / `:authority` value.
+    host: String,
+    /// Path and query for the request line.
+    path: String,
+}
+
+impl Target {
+    fn parse(url: &str) -> Result<Self, ClientError> {
+        let invalid = || ClientError::InvalidUrl(url.to_string());
+        let (scheme, rest) = url.split_once("://").ok_or_else(invalid)?;
+        if scheme.eq_ignore_ascii_case("https") {
+            return Err(ClientError::InvalidUrl(format!(
+                "{url} (https is not supported)"
+            )));
+        }
+        if !scheme.eq_ignore_ascii_case("http") {
+            return Err(invalid());
+        }
+
+        let rest = rest.split('#').next().unwrap_or_default();
+        let split = rest.find(['/', '?']).unwrap_or(rest.len());
+        let (host, path) = rest.split_at(split);
+        if host.is_empty() || host.contains('@') || host.bytes().any(|b| b <= b' ') {
+            return Err(invalid());
+        }
+        if path.bytes().any(|b| b <= b' ' || b == 0x7f) {
+            return Err(invalid());
+        }
+
+        let authority = match host.rfind(':') {
+            Some(colon) if !host[colon..].contains(']') => {
+                host[colon + 1..].parse::<u16>().map_err(|_| invalid())?;
+                host.to_string()
+            }
+            _ => format!("{host}:80"),
+        };
+        let path = if path.is_empty() {
+            "/".to_string()
+        } else if path.starts_with('?') {
+            format!("/{path}")
+        } else {
+            path.to_string()
+        };
+        Ok(Self {
+            authority,
+            host: host.to_string(),
+            path,
+        })
+    }
+}
+
+/// A validated request, ready to be written on either protocol.
+#[derive(Debug)]
+struct Outgoing {
+    method: Method,
+    target: Target,
+    headers: Vec<(String, Vec<u8>)>,
+    content_length: Option<usize>,
+    /// Streamed body of unknown length: sent chunked on HTTP/1.1.
+    chunked: bool,
+}
+
+impl Outgoing {
+    fn new(
+        method: Method,
+        mut target: Target,
+        headers: Vec<(String, Vec<u8>)>,
+        body: &RequestBody,
+        client: &Client,
+    ) -> Result<Self, ClientError> {
+        let mut kept = Vec::with_capacity(headers.len() + 1);
+        for (name, value) in headers {
+            if name.is_empty() || !name.bytes().all(is_token_byte) {
+                return Err(ClientError::InvalidHeader(name));
+            }
+            if value.iter().any(|&b| b == b'\r' || b == b'\n' || b == 0) {
+                return Err(ClientError::InvalidHeader(name));
+            }
+            match name.as_str() {
+                "host" => {
+                    target.host = String::from_utf8(value)
+                        .map_err(|_| ClientError::InvalidHeader(name.clone()))?;
+                }
+                "content-length" | "transfer-encoding" | "connection" => {}
+                _ => kept.push((name, value)),
+            }
+        }
+        if let Some(user_agent) = &client.inner.config.user_agent {
+            if !kept.iter().any(|(name, _)| name == "user-agent") {
+                kept.push(("user-agent".to_string(), user_agent.clone().into_bytes()));
+            }
+        }
+
+        let content_length = match body {
+            RequestBody::Empty => {
+                matches!(method, Method::Post | Method::Put | Method::Patch).then_some(0)
+            }
+            RequestBody::Bytes(bytes) => Some(bytes.len()),
+            RequestBody::Stream { length, .. } => *length,
+        };
+        Ok(Self {
+            method,
+            target,
+            headers: kept,
+            content_length,
+            chunked: content_length.is_none() && matches!(body, RequestBody::Stream { .. }),
+        })
+    }
+
+    fn http1_head(&self) -> Vec<u8> {
+        let mut head = format!(
+            "{} {} HTTP/1.1\r\nhost: {}\r\n",
+            self.method.as_str(),
+            self.target.path,
+            self.target.host
+        )
+        .into_bytes();
+        for (name, value) in &self.headers {
+            head.extend_from_slice(name.as_bytes());
+            head.extend_from_slice(b": ");
+            head.extend_from_slice(value);
+            head.extend_from_slice(b"\r\n");
+        }
+        match self.content_length {
+            Some(len) => head.extend_from_slice(format!("content-length: {len}\r\n").as_bytes()),
+            None if self.chunked => head.extend_from_slice(b"transfer-encoding: chunked\r\n"),
+            None => {}
+        }
+        head.extend_from_slice(b"\r\n");
+        head
+    }
+
+    fn h2_header_block(&self) -> Vec<u8> {
+        let mut block = Vec::new();
+        hpack_encode_literal_without_indexing(
+            &mut block,
+            b":method",
+            self.method.as_str().as_bytes(),
+        );
+        hpack_encode_literal_without_indexing(&mut block, b":scheme", b"http");
+        hpack_encode_literal_without_indexing(
+            &mut block,
+            b":authority",
+            self.target.host.as_bytes(),
+        );
+        hpack_encode_literal_without_indexing(&mut block, b":path", self.target.path.as_bytes());
+        for (name, value) in &self.headers {
+            if is_standard_hop_by_hop_header(name) || name == "keep-alive" {
+                continue;
+            }
+            hpack_encode_literal_without_indexing(&mut block, name.as_bytes(), value);
+        }
+        if let Some(len) = self.content_length {
+            hpack_encode_literal_without_indexing(
+                &mut block,
+                b"content-length",
+                len.to_string().as_bytes(),
+            );
+        }
+        block
+    }
+}
+
+fn is_token_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+// =============================================================================
+// Connections
+// =============================================================================
+
+enum Connection {
+    Http1(Http1Connection),
+    Http2(Http2Connection),
+}
+
+struct ResponseHead {
+    status: u16,
+    headers: Vec<(String, Vec<u8>)>,
+    body: BodyState,
+    keep_alive: bool,
+}
+
+enum BodyState {
+    Length(u64),
+    Chunked(ChunkedState),
+    UntilClose,
+    Http2 {
+        stream_id: u32,
+    },
+    /// Replayed or recorded through a cassette; no connection behind it.
+    Recorded(Vec<u8>),
+    Done,
+}
+
+impl Connection {
+    async fn exchange(
+        &mut self,
+        outgoing: &Outgoing,
+        body: RequestBody,
+        config: &ClientConfig,
+    ) -> Result<ResponseHead, ClientError> {
+        match self {
+            Self::Http1(conn) => conn.exchange(outgoing, body, config).await,
+            Self::Http2(conn) => conn.exchange(outgoing, body).await,
+        }
+    }
+
+    /// Next chunk of the response body, or `None` once it ends.
+    async fn read_body(
+        &mut self,
+        state: &mut BodyState,
+        read_timeout: Duration,
+    ) -> Result<Option<Vec<u8>>, ClientError> {
+        match self {
+            Self::Http1(conn) => conn.read_body(state, read_timeout).await,
+            Self::Http2(conn) => {
+                let BodyState::Http2 { stream_id } = *state else {
+                    return Ok(None);
+                };
+                let read = Box::pin(conn.read_data(stream_id));
+                match timeout(current_time(), read_timeout, read).await {
+                    Ok(result) => result,
+                    Err(_elapsed) => Err(ClientError::Timeout("read")),
+                }
+            }
+        }
+    }
+
+    fn is_reusable(&self) -> bool {
+        match self {
+            Self::Http1(conn) => conn.rx.is_empty(),
+            Self::Http2(conn) => !conn.goaway,
+        }
+    }
+}
+
+struct Http1Connection {
+    stream: TcpStream,
+    rx: Vec<u8>,
+}
+
+impl Http1Connection {
+    async fn exchange(
+        &mut self,
+        outgoing: &Outgoing,
+        body: RequestBody,
+        config: &ClientConfig,
+    ) -> Result<ResponseHead, ClientError> {
+        self.rx.clear();
+        let mut head = outgoing.http1_head();
+        match body {
+            RequestBody::Empty => self.write(&head).await?,
+            RequestBody::Bytes(bytes) => {
+                head.extend_from_slice(&bytes);
+                self.write(&head).await?;
+            }
+            RequestBody::Stream { mut chunks, length } => {
+                self.write(&head).await?;
+                let mut sent = 0usize;
+                while let Some(chunk) = next_chunk(&mut chunks).await? {
+                    if chunk.is_empty() {
+                        continue;
+                    }
+                    sent += chunk.len();
+                    if length.is_some() {
+                        self.write(&chunk).await?;
+                    } else {
+                        let mut framed = format!("{:x}\r\n", chunk.len()).into_bytes();
+                        framed.extend_from_slice(&chunk);
+                        framed.extend_from_slice(b"\r\n");
+                        self.write(&framed).await?;
+                    }
+                }
+                match length {
+                    Some(len) if len != sent => {
+                        return Err(ClientError::Body(format!(
+                            "stream produced {sent} bytes, expected {len}"
+                        )));
+                    }
+                    Some(_) => {}
+                    None => self.write(b"0\r\n\r\n").await?,
+                }
+            }
+        }
+        self.flush().await?;
+
+        loop {
+            let parsed = parse_response_head(&self.rx, config.max_response_head_size)?;
+            let Some((head, consumed)) = parsed else {
+                let received = self.rx.len();
+                let read = Box::pin(self.read_more());
+                let n = match timeout(current_time(), config.read_timeout, read).await {
+                    Ok(result) => result?,
+                    Err(_elapsed) => return Err(ClientError::Timeout("read")),
+                };
+                if n == 0 {
+                    return Err(if received == 0 {
+                        ClientError::Closed
+                    } else {
+                        ClientError::Protocol("connection closed inside response head".into())
+                    });
+                }
+                continue;
+            };
+            self.rx.drain(..consumed);
+            // Interim responses (e.g. 100 Continue) precede the real one.
+            if (100..200).contains(&head.status) && head.status != 101 {
+                continue;
+            }
+            let body = body_framing(outgoing.method, &head)?;
+            let keep_alive =
+                head.keep_alive && head.status != 101 && !matches!(body, BodyState::UntilClose);
+            return Ok(ResponseHead {
+                status: head.status,
+                headers: head.headers,
+                body,
+                keep_alive,
+            });
+        }
+    }
+
+    async fn read_body(
+        &mut self,
+        state: &mut BodyState,
+        read_timeout: Duration,
+    ) -> Result<Option<Vec<u8>>, ClientError> {
+        loop {
+            match state {
+                BodyState::Length(0)
+                | BodyState::Done
+                | BodyState::Http2 { .. }
+                | BodyState::Recorded(_) => {
+                    *state = BodyState::Done;
+                    return Ok(None);
+                }
+                BodyState::Length(remaining) => {
+                    if !self.rx.is_empty() {
+                        let take = usize::try_from(*remaining)
+                            .unwrap_or(usize::MAX)
+                            .min(self.rx.len());
+                        *remaining -= take as u64;
+                        return Ok(Some(self.rx.drain(..take).collect()));
+                    }
+                }
+                BodyState::Chunked(chunked) => match decode_chunk(&mut self.rx, chunked)? {
+                    Decoded::Data(data) => return Ok(Some(data)),
+                    Decoded::End => {
+                        *state = BodyState::Done;
+                        return Ok(None);
+                    }
+                    Decoded::NeedMore => {}
+                },
+                BodyState::UntilClose => {
+                    if !self.rx.is_empty() {
+                        return Ok(Some(std::mem::take(&mut self.rx)));
+                    }
+                }
+            }
+
+            let read = Box::pin(self.read_more());
+            let n = match timeout(current_time(), read_timeout, read).await {
+                Ok(result) => result?,
+                Err(_elapsed) => return Err(ClientError::Timeout("read")),
+            };
+            if n == 0 {
+                if matches!(state, BodyState::UntilClose) {
+                    *state = BodyState::Done;
+                    return Ok(None);
+                }
+                return Err(ClientError::Protocol(
+                    "connection closed inside response body".into(),
+                ));
+            }
+        }
+    }
+
+    async fn read_more(&mut self) -> io::Result<usize> {
+        let mut buffer = vec![0u8; READ_CHUNK_SIZE];
+        let n = read_into_buffer(&mut self.stream, &mut buffer).await?;
+        self.rx.extend_from_slice(&buffer[..n]);
+        Ok(n)
+    }
+
+    async fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        write_all(&mut self.stream, bytes).await
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        poll_fn(|cx| Pin::new(&mut self.stream).poll_flush(cx)).await
+    }
+}
+
+/// A parsed HTTP/1.x status line and headers.
+#[derive(Debug)]
+struct Http1Head {
+    status: u16,
+    headers: Vec<(String, Vec<u8>)>,
+    keep_alive: bool,
+}
+
+/// Parse a response head from the start of `buf`, returning it with the
+/// number of bytes it occupied, or `None` if more bytes are needed.
+fn parse_response_head(
+    buf: &[u8],
+    max_size: usize,
+) -> Result<Option<(Http1Head, usize)>, ClientError> {
+    let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else {
+        if buf.len() > max_size {
+            return Err(ClientError::Protocol("response head too large".into()));
+        }
+        return Ok(None);
+    };
+    if end > max_size {
+        return Err(ClientError::Protocol("response head too large".into()));
+    }
+
+    let mut lines = buf[..end]
+        .split(|&b| b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line));
+    let status_line = lines.next().unwrap_or_default();
+    let status_line = std::str::from_utf8(status_line)
+        .map_err(|_| ClientError::Protocol("status line is not UTF-8".into()))?;
+    let mut parts = status_line.splitn(3, ' ');
+    let version = parts.next().unwrap_or_default();
+    let http11 = match version {
+        "HTTP/1.1" => true,
+        "HTTP/1.0" => false,
+        _ => {
+            return Err(ClientError::Protocol(format!(
+                "unsupported version in status line: {status_line}"
+            )));
+        }
+    };
+    let status = parts
+        .next()
+        .and_then(|code| code.parse::<u16>().ok())
+        .filter(|code| (100..1000).contains(code))
+        .ok_or_else(|| ClientError::Protocol(format!("bad status line: {status_line}")))?;
+
+    let mut headers = Vec::new();
+    let mut keep_alive = http11;
+    for line in lines {
+        let colon = line
+            .iter()
+            .position(|&b| b == b':')
+            .ok_or_else(|| ClientError::Protocol("header line without ':'".into()))?;
+        let name = std::str::from_utf8(&line[..colon])
+            .ok()
+            .filter(|name| !name.is_empty() && name.bytes().all(is_token_byte))
+            .ok_or_else(|| ClientError::Protocol("invalid header name".into()))?
+            .to_ascii_lowercase();
+        let value = line[colon + 1..].trim_ascii().to_vec();
+        if name == "connection" {
+            for token in value.split(|&b| b == b',') {
+                let token = token.trim_ascii();
+                if token.eq_ignore_ascii_case(b"close") {
+                    keep_alive = false;
+                } else if token.eq_ignore_ascii_case(b"keep-alive") {
+                    keep_alive = true;
+                }
+            }
+        }
+        headers.push((name, value));
+    }
+    Ok(Some((
+        Http1Head {
+            status,
+            headers,
+            keep_alive,
+        },
+        end + 4,
+    )))
+}
+
+/// How the body following `head` is delimited (RFC 9112 section 6.3).
+fn body_framing(method: Method, head: &Http1Head) -> Result<BodyState, ClientError> {
+    if method == Method::Head || matches!(head.status, 100..=199 | 204 | 304) {
+        return Ok(BodyState::Done);
+    }
+    let header = |name: &str| {
+        head.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_slice())
+    };
+    if let Some(coding) = header("transfer-encoding") {
+        let last = coding.rsplit(|&b| b == b',').next().unwrap_or_default();
+        return Ok(if last.trim_ascii().eq_ignore_ascii_case(b"chunked") {
+            BodyState::Chunked(ChunkedState::Size)
+        } else {
+            BodyState::UntilClose
+        });
+    }
+    match header("content-length") {
+        Some(value) => std::str::from_utf8(value)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(|len| {
+                if len == 0 {
+                    BodyState::Done
+                } else {
+                    BodyState::Length(len)
+                }
+            })
+            .ok_or_else(|| ClientError::Protocol("invalid content-length".into())),
+        None => Ok(BodyState::UntilClose),
+    }
+}
+
+/// Position inside a chunked body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChunkedState {
+    Size,
+    Data(u64),
+    DataEnd,
+    Trailers,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Decoded {
+    Data(Vec<u8>),
+    NeedMore,
+    End,
+}
+
+/// Decode as much of a chunked body from `buf` as possible, consuming what
+/// was decoded.
+fn decode_chunk(buf: &mut Vec<u8>, state: &mut ChunkedState) -> Result<Decoded, ClientError> {
+    const MAX_LINE: usize = 4096;
+    let line_end = |buf: &[u8]| buf.windows(2).position(|w| w == b"\r\n");
+
+    loop {
+        match *state {
+            ChunkedState::Size => {
+                let Some(end) = line_end(buf) else {
+                    if buf.len() > MAX_LINE {
+                        return Err(ClientError::Protocol("chunk size line too long".into()));
+                    }
+                    return Ok(Decoded::NeedMore);
+                };
+                let line = &buf[..end];
+                let digits = line.split(|&b| b == b';').next().unwrap_or_default();
+                let size = std::str::from_utf8(digits)
+                    .ok()
+                    .and_then(|d| u64::from_str_radix(d.trim(), 16).ok())
+                    .ok_or_else(|| ClientError::Protocol("invalid chunk size".into()))?;
+                buf.drain(..end + 2);
+                *state = if size == 0 {
+                    ChunkedState::Trailers
+                } else {
+                    ChunkedState::Data(size)
+                };
+            }
+            ChunkedState::Data(remaining) => {
+                if buf.is_empty() {
+                    return Ok(Decoded::NeedMore);
+                }
+                let take = usize::try_from(remaining)
+                    .unwrap_or(usize::MAX)
+                    .min(buf.len());
+                let data: Vec<u8> = buf.drain(..take).collect();
+                let left = remaining - take as u64;
+                *state = if left == 0 {
+                    ChunkedState::DataEnd
+                } else {
+                    ChunkedState::Data(left)
+                };
+                return Ok(Decoded::Data(data));
+            }
+            ChunkedState::DataEnd => {
+                if buf.len() < 2 {
+                    return Ok(Decoded::NeedMore);
+                }
+                if &buf[..2] != b"\r\n" {
+                    return Err(ClientError::Protocol("missing CRLF after chunk".into()));
+                }
+                buf.drain(..2);
+                *state = ChunkedState::Size;
+            }
+            ChunkedState::Trailers => {
+                let Some(end) = line_end(buf) else {
+                    if buf.len() > MAX_LINE {
+                        return Err(ClientError::Protocol("trailer line too long".into()));
+                    }
+                    return Ok(Decoded::NeedMore);
+                };
+                buf.drain(..end + 2);
+                if end == 0 {
+                    return Ok(Decoded::End);
+                }
+            }
+        }
+    }
+}
+
+struct Http2Connection {
+    framed: FramedH2,
+    decoder: HpackDecoder,
+    next_stream_id: u32,
+    conn_window: i64,
+    stream_window: i64,
+    peer_initial_window: i64,
+    peer_max_frame_size: usize,
+    current_stream: u32,
+    stream_ended: bool,
+    pending: VecDeque<Frame>,
+    goaway: bool,
+}
+
+impl Http2Connection {
+    async fn handshake(mut stream: TcpStream) -> Result<Self, ClientError> {
+        write_all(&mut stream, PREFACE).await?;
+        let mut framed = FramedH2::new(stream, Vec::new());
+        // SETTINGS_ENABLE_PUSH = 0
+        framed
+            .write_frame(FrameType::Settings, 0, 0, &[0, 2, 0, 0, 0, 0])
+            .await?;
+        Ok(Self {
+            framed,
+            decoder: HpackDecoder::new(),
+            next_stream_id: 1,
+            conn_window: H2_DEFAULT_WINDOW,
+            stream_window: H2_DEFAULT_WINDOW,
+            peer_initial_window: H2_DEFAULT_WINDOW,
+            peer_max_frame_size: H2_MAX_FRAME_SIZE as usize,
+            current_stream: 0,
+            stream_ended: true,
+            pending: VecDeque::new(),
+            goaway: false,
+        })
+    }
+
+    async fn exchange(
+        &mut self,
+        outgoing: &Outgoing,
+        body: RequestBody,
+    ) -> Result<ResponseHead, ClientError> {
+        let stream_id = self.next_stream_id;
+        self.next_stream_id += 2;
+        if self.next_stream_id > 0x7FFF_FFFF {
+            self.goaway = true;
+        }
+        self.current_stream = stream_id;
+        self.stream_ended = false;
+        self.stream_window = self.peer_initial_window;
+        self.pending.clear();
+
+        let block = outgoing.h2_header_block();
+        let end_stream = matches!(body, RequestBody::Empty)
+            || matches!(&body, RequestBody::Bytes(b) if b.is_empty());
+        let mut fragments = block.chunks(self.peer_max_frame_size.max(1)).peekable();
+        let mut frame_type = FrameType::Headers;
+        loop {
+            let fragment = fragments.next().unwrap_or_default();
+            let mut flags = 0;
+            if fragments.peek().is_none() {
+                flags |= FLAG_END_HEADERS;
+            }
+            if frame_type == FrameType::Headers && end_stream {
+                flags |= FLAG_END_STREAM;
+            }
+            self.framed
+                .write_frame(frame_type, flags, stream_id, fragment)
+                .await?;
+            if flags & FLAG_END_HEADERS != 0 {
+                break;
+            }
+            frame_type = FrameType::Continuation;
+        }
+
+        match body {
+            RequestBody::Empty => {}
+            RequestBody::Bytes(bytes) => {
+                if !bytes.is_empty() {
+                    self.send_data(stream_id, &bytes, true).await?;
+                }
+            }
+            RequestBody::Stream { mut chunks, .. } => {
+                while let Some(chunk) = next_chunk(&mut chunks).await? {
+                    self.send_data(stream_id, &chunk, false).await?;
+                }
+                self.send_data(stream_id, &[], true).await?;
+            }
+        }
+
+        loop {
+            let frame = self.next_frame(stream_id).await?;
+            match frame.header.frame_type() {
+                FrameType::Headers => {
+                    let ended = frame.header.flags & FLAG_END_STREAM != 0;
+                    let mut block = frame_payload(&frame)?.to_vec();
+                    let mut flags = frame.header.flags;
+                    while flags & FLAG_END_HEADERS == 0 {
+                        let cont = self.framed.read_frame(H2_MAX_FRAME_SIZE).await?;
+                        if cont.header.frame_type() != FrameType::Continuation
+                            || cont.header.stream_id != stream_id
+                        {
+                            return Err(Http2Error::Protocol("expected CONTINUATION").into());
+                        }
+                        block.extend_from_slice(&cont.payload);
+                        flags = cont.header.flags;
+                    }
+                    let fields = self.decoder.decode(&block)?;
+
+                    let mut status = None;
+                    let mut headers = Vec::with_capacity(fields.len());
+                    for (name, value) in fields {
+                        if name == b":status" {
+                            status = std::str::from_utf8(&value)
+                                .ok()
+                                .and_then(|s| s.parse::<u16>().ok());
+                        } else if !name.starts_with(b":") {
+                            headers.push((String::from_utf8_lossy(&name).into_owned(), value));
+                        }
+                    }
+                    let status = status.ok_or(Http2Error::Protocol("response without :status"))?;
+                    if (100..200).contains(&status) {
+                        continue;
+                    }
+                    self.stream_ended = ended;
+                    let body = if ended || outgoing.method == Method::Head {
+                        BodyState::Done
+                    } else {
+                        BodyState::Http2 { stream_id }
+                    };
+                    return Ok(ResponseHead {
+                        status,
+                        headers,
+                        body,
+                        keep_alive: true,
+                    });
+                }
+                FrameType::Data => {
+                    return Err(Http2Error::Protocol("DATA before response HEADERS").into());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Next body chunk of `stream_id`; empty chunks are skipped.
+    async fn read_data(&mut self, stream_id: u32) -> Result<Option<Vec<u8>>, ClientError> {
+        while !self.stream_ended {
+            let frame = self.next_frame(stream_id).await?;
+            let ended = frame.header.flags & FLAG_END_STREAM != 0;
+            match frame.header.frame_type() {
+                FrameType::Data => {
+                    self.stream_ended = ended;
+                    let len = frame.header.length;
+                    if len > 0 {
+                        self.framed
+                            .write_frame(FrameType::WindowUpdate, 0, 0, &len.to_be_bytes())
+                            .await?;
+                        if !ended {
+                            self.framed
+                                .write_frame(
+                                    FrameType::WindowUpdate,
+                                    0,
+                                    stream_id,
+                                    &len.to_be_bytes(),
+                                )
+                                .await?;
+                        }
+                    }
+                    let data = frame_payload(&frame)?;
+                    if !data.is_empty() {
+                        return Ok(Some(data.to_vec()));
+                    }
+                }
+                // Trailers are ignored.
+                FrameType::Headers => self.stream_ended = ended,
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+
+    async fn send_data(
+        &mut self,
+        stream_id: u32,
+        mut data: &[u8],
+        end: bool,
+    ) -> Result<(), ClientError> {
+        loop {
+            let window = self.conn_window.min(self.stream_window);
+            if !data.is_empty() && window <= 0 {
+                let frame = self.framed.read_frame(H2_MAX_FRAME_SIZE).await?;
+                if !self.control(&frame, stream_id).await? {
+                    if frame.header.stream_id == stream_id
+                        && frame.header.frame_type() == FrameType::RstStream
+                    {
+                        return Err(Http2Error::Protocol("stream reset by server").into());
+                    }
+                    self.pending.push_back(frame);
+                }
+                continue;
+            }
+            let n = data
+                .len()
+                .min(self.peer_max_frame_size)
+                .min(usize::try_from(window).unwrap_or(0));
+            let last = n == data.len();
+            let flags = if last && end { FLAG_END_STREAM } else { 0 };
+            if n > 0 || flags != 0 {
+                self.framed
+                    .write_frame(FrameType::Data, flags, stream_id, &data[..n])
+                    .await?;
+            }
+            let sent = i64::try_from(n).unwrap_or(i64::MAX);
+            self.conn_window -= sent;
+            self.stream_window -= sent;
+            data = &data[n..];
+            if last {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Next frame for `stream_id`, answering connection-level frames and
+    /// dropping frames of other streams.
+    async fn next_frame(&mut self, stream_id: u32) -> Result<Frame, ClientError> {
+        loop {
+            let frame = match self.pending.pop_front() {
+                Some(frame) => frame,
+                None => self.framed.read_frame(H2_MAX_FRAME_SIZE).await?,
+            };
+            if self.control(&frame, stream_id).await? || frame.header.stream_id != stream_id {
+                continue;
+            }
+            if frame.header.frame_type() == FrameType::RstStream {
+                self.goaway = true;
+                return Err(Http2Error::Protocol("stream reset by server").into());
+            }
+            return Ok(frame);
+        }
+    }
+
+    /// Handle SETTINGS, PING, WINDOW_UPDATE and GOAWAY; returns whether
+    /// `frame` was one of them.
+    async fn control(&mut self, frame: &Frame, stream_id: u32) -> Result<bool, ClientError> {
+        match frame.header.frame_type() {
+            FrameType::Settings => {
+                if frame.header.flags & FLAG_ACK == 0 {
+                    for setting in frame.payload.chunks_exact(6) {
+                        let id = u16::from_be_bytes([setting[0], setting[1]]);
+                        let value =
+                            u32::from_be_bytes([setting[2], setting[3], setting[4], setting[5]]);
+                        match id {
+                            0x4 => {
+                                let value = i64::from(value);
+                                self.stream_window += value - self.peer_initial_window;
+                                self.peer_initial_window = value;
+                            }
+                            0x5 => {
+                                self.peer_max_frame_size = usize::try_from(value)
+                                    .unwrap_or(H2_MAX_FRAME_SIZE as usize)
+                                    .max(H2_MAX_FRAME_SIZE as usize);
+                            }
+                            _ => {}
+                        }
+                    }
+                    self.framed
+                        .write_frame(FrameType::Settings, FLAG_ACK, 0, &[])
+                        .await?;
+                }
+                Ok(true)
+            }
+            FrameType::Ping => {
+                if frame.header.flags & FLAG_ACK == 0 {
+                    self.framed
+                        .write_frame(FrameType::Ping, FLAG_ACK, 0, &frame.payload)
+                        .await?;
+                }
+                Ok(true)
+            }
+            FrameType::WindowUpdate => {
+                let increment = frame.payload.get(..4).map_or(0, |b| {
+                    u32::from_be_bytes([b[0], b[1], b[2], b[3]]) & 0x7FFF_FFFF
+                });
+                if frame.header.stream_id == 0 {
+                    self.conn_window += i64::from(increment);
+                } else if frame.header.stream_id == stream_id {
+                    self.stream_window += i64::from(increment);
+                }
+                Ok(true)
+            }
+            FrameType::Goaway => {
+                self.goaway = true;
+                let last_stream = frame.payload.get(..4).map_or(0, |b| {
+                    u32::from_be_bytes([b[0], b[1], b[2], b[3]]) & 0x7FFF_FFFF
+                });
+                if stream_id > last_stream && !self.stream_ended {
+                    return Err(ClientError::Closed);
+                }
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Payload of a HEADERS or DATA frame without padding and priority fields.
+fn frame_payload(frame: &Frame) -> Result<&[u8], ClientError> {
+    let mut payload = frame.payload.as_slice();
+    let mut pad = 0;
+    if frame.header.flags & FLAG_PADDED != 0 {
+        let (&len, rest) = payload
+            .split_first()
+            .ok_or(Http2Error::Protocol("padded frame without pad length"))?;
+        pad = usize::from(len);
+        payload = rest;
+    }
+    if frame.header.frame_type() == FrameType::Headers && frame.header.flags & FLAG_PRIORITY != 0 {
+        payload = payload
+            .get(5..)
+            .ok_or(Http2Error::Protocol("HEADERS priority fields truncated"))?;
+    }
+    if pad > payload.len() {
+        return Err(Http2Error::Protocol("padding exceeds frame payload").into());
+    }
+    Ok(&payload[..payload.len() - pad])
+}
+
+// =============================================================================
+// Responses
+// =============================================================================
+
+/// A response whose head has arrived; the body is read on demand.
+///
+/// The connection returns to the pool once the body has been read to the
+/// end. Dropping the response earlier closes the connection.
+pub struct ClientResponse {
+    status: StatusCode,
+    headers: Vec<(String, Vec<u8>)>,
+    body: BodyState,
+    reusable: bool,
+    conn: Option<Connection>,
+    client: Client,
+    key: String,
+}
+
+impl fmt::Debug for ClientResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ClientResponse {
+    /// Response status.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Response headers, with lowercase names, in the order received.
+    pub fn headers(&self) -> &[(String, Vec<u8>)] {
+        &self.headers
+    }
+
+    /// First value of a header (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&[u8]> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_slice())
+    }
+
+    /// The `Content-Length` header, if present and valid.
+    pub fn content_length(&self) -> Option<u64> {
+        std::str::from_utf8(self.header("content-length")?)
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    /// Read the next body chunk as it arrives; `None` at the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError`] on I/O errors, a read timeout, or a malformed
+    /// body.
+    pub async fn chunk(&mut self) -> Result<Option<Vec<u8>>, ClientError> {
+        if let BodyState::Recorded(body) = &mut self.body {
+            let body = std::mem::take(body);
+            self.body = BodyState::Done;
+            return Ok((!body.is_empty()).then_some(body));
+        }
+        let Some(conn) = self.conn.as_mut() else {
+            return Ok(None);
+        };
+        let read_timeout = self.client.inner.config.read_timeout;
+        match conn.read_body(&mut self.body, read_timeout).await {
+            Ok(Some(chunk)) => Ok(Some(chunk)),
+            Ok(None) => {
+                self.finish();
+                Ok(None)
+            }
+            Err(err) => {
+                self.conn = None;
+                Err(err)
+            }
+        }
+    }
+
+    /// Read the rest of the body into memory.
+    ///
+    /// # Errors
+    ///
+    /// See [`chunk`](Self::chunk).
+    pub async fn bytes(&mut self) -> Result<Vec<u8>, ClientError> {
+        let mut out = Vec::new();
+        while let Some(chunk) = self.chunk().await? {
+            out.extend_from_slice(&chunk);
+        }
+        Ok(out)
+    }
+
+    /// Read the rest of the body as text, replacing invalid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// See [`chunk`](Self::chunk).
+    pub async fn text(&mut self) -> Result<String, ClientError> {
+        let bytes = self.bytes().await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Convert into a server [`Response`] that streams this body, e.g. to
+    /// return an upstream answer from a handler. Hop-by-hop headers and
+    /// `Content-Length` are dropped. A body error ends the stream early.
+    pub fn into_response(self) -> Response {
+        let mut response = Response::with_status(self.status);
+        for (name, value) in &self.headers {
+            if is_standard_hop_by_hop_header(name) || name == "content-length" {
+                continue;
+            }
+            response = response.header(name.clone(), value.clone());
+        }
+        if matches!(self.body, BodyState::Done) {
+            return response;
+        }
+        response.body(ResponseBody::stream(ResponseChunks {
+            response: Some(self),
+            pending: None,
+        }))
+    }
+
+    fn finish(&mut self) {
+        self.body = BodyState::Done;
+        if let Some(conn) = self.conn.take() {
+            if self.reusable && conn.is_reusable() {
+                self.client.release(std::mem::take(&mut self.key), conn);
+            }
+        }
+    }
+}
+
+type ChunkFuture = Pin<Box<dyn Future<Output = (ClientResponse, Option<Vec<u8>>)> + Send>>;
+
+/// Streams a [`ClientResponse`] body as a [`ResponseBody`].
+struct ResponseChunks {
+    response: Option<ClientResponse>,
+    pending: Option<ChunkFuture>,
+}
+
+impl Stream for ResponseChunks {
+    type Item = Vec<u8>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.pending.is_none() {
+            let Some(mut response) = self.response.take() else {
+                return Poll::Ready(None);
+            };
+            self.pending = Some(Box::pin(async move {
+                let chunk = response.chunk().await.ok().flatten();
+                (response, chunk)
+            }));
+        }
+        let Some(pending) = self.pending.as_mut() else {
+            return Poll::Ready(None);
+        };
+        match pending.as_mut().poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready((response, chunk)) => {
+                self.pending = None;
+                if chunk.is_some() {
+                    self.response = Some(response);
+                }
+                Poll::Ready(chunk)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_targets() {
+        let target = Target::parse("http://example.com/a/b?x=1#frag").unwrap();
+        assert_eq!(target.authority, "example.com:80");
+        assert_eq!(target.host, "example.com");
+        assert_eq!(target.path, "/a/b?x=1");
+
+        let target = Target::parse("HTTP://127.0.0.1:8080?q").unwrap();
+        assert_eq!(target.authority, "127.0.0.1:8080");
+        assert_eq!(target.host, "127.0.0.1:8080");
+        assert_eq!(target.path, "/?q");
+
+        let target = Target::parse("http://[::1]").unwrap();
+        assert_eq!(target.authority, "[::1]:80");
+        assert_eq!(target.path, "/");
+
+        for bad in [
+            "https://example.com/",
+            "ftp://example.com/",
+            "example.com/",
+            "http:///path",
+            "http://user@host/",
+            "http://host:99999/",
+            "http://host/a b",
+        ] {
+            assert!(
+                matches!(Target::parse(bad), Err(ClientError::InvalidUrl(_))),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn writes_http1_request_heads() {
+        let client = Client::builder().user_agent("test/1").build();
+        let body = RequestBody::Bytes(b"hello".to_vec());
+        let outgoing = Outgoing::new(
+            Method::Post,
+            Target::parse("http://example.com:8080/items?x=1").unwrap(),
+            vec![
+                ("x-trace".into(), b"abc".to_vec()),
+                ("content-length".into(), b"999".to_vec()),
+            ],
+            &body,
+            &client,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(outgoing.http1_head()).unwrap(),
+            "POST /items?x=1 HTTP/1.1\r\nhost: example.com:8080\r\nx-trace: abc\r\n\
+             user-agent: test/1\r\ncontent-length: 5\r\n\r\n"
+        );
+
+        let bad = Outgoing::new(
+            Method::Get,
+            Target::parse("http://example.com/").unwrap(),
+            vec![("x-bad".into(), b"a\r\nb".to_vec())],
+            &RequestBody::Empty,
+            &client,
+        );
+        assert!(matches!(bad, Err(ClientError::InvalidHeader(name)) if name == "x-bad"));
+    }
+
+    #[test]
+    fn parses_response_heads_and_framing() {
+        let raw = b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nTransfer-Encoding: chunked\r\n\r\n5\r\n";
+        let (head, consumed) = parse_response_head(raw, 1024).unwrap().unwrap();
+        assert_eq!(head.status, 200);
+        assert!(head.keep_alive);
+        assert_eq!(
+            head.headers[0],
+            ("content-type".into(), b"text/plain".to_vec())
+        );
+        assert_eq!(&raw[consumed..], b"5\r\n");
+        assert!(matches!(
+            body_framing(Method::Get, &head).unwrap(),
+            BodyState::Chunked(ChunkedState::Size)
+        ));
+        assert!(matches!(
+            body_framing(Method::Head, &head).unwrap(),
+            BodyState::Done
+        ));
+
+        let (head, _) =
+            parse_response_head(b"HTTP/1.0 404 Not Found\r\ncontent-length: 3\r\n\r\n", 1024)
+                .unwrap()
+                .unwrap();
+        assert!(!head.keep_alive);
+        assert!(matches!(
+            body_framing(Method::Get, &head).unwrap(),
+            BodyState::Length(3)
+        ));
+
+        assert!(
+            parse_response_head(b"HTTP/1.1 200 OK\r\n", 1024)
+                .unwrap()
+                .is_none()
+        );
+        assert!(parse_response_head(b"HTTP/1.1 200 OK\r\nx: y\r\n", 8).is_err());
+        assert!(parse_response_head(b"SPDY/3 200 OK\r\n\r\n", 1024).is_err());
+    }
+
+    #[test]
+    fn decodes_chunked_bodies_incrementally() {
+        let wire = b"4;ext=1\r\nWiki\r\n5\r\npedia\r\n0\r\nx-checksum: 1\r\n\r\n";
+        let mut state = ChunkedState::Size;
+        let mut buf = Vec::new();
+        let mut body = Vec::new();
+        let mut ended = false;
+        // Feed one byte at a time to exercise every partial state.
+        for &byte in wire {
+            buf.push(byte);
+            loop {
+                match decode_chunk(&mut buf, &mut state).unwrap() {
+                    Decoded::Data(data) => body.extend_from_slice(&data),
+                    Decoded::NeedMore => break,
+                    Decoded::End => {
+                        ended = true;
+                        break;
+                    }
+                }
+            }
+        }
+        assert!(ended);
+        assert!(buf.is_empty());
+        assert_eq!(body, b"Wikipedia");
+
+        let mut buf = b"zz\r\n".to_vec();
+        assert!(decode_chunk(&mut buf, &mut ChunkedState::Size).is_err());
+    }
+
+    #[test]
+    fn encodes_http2_request_headers() {
+        let client = Client::builder().user_agent("test/1").build();
+        let outgoing = Outgoing::new(
+            Method::Get,
+            Target::parse("http://example.com/search?q=1").unwrap(),
+            vec![
+                ("accept".into(), b"text/html".to_vec()),
+                ("upgrade".into(), b"h2c".to_vec()),
+            ],
+            &RequestBody::Empty,
+            &client,
+        )
+        .unwrap();
+        let fields = HpackDecoder::new()
+            .decode(&outgoing.h2_header_block())
+            .unwrap();
+        let fields: Vec<(&[u8], &[u8])> = fields
+            .iter()
+            .map(|(n, v)| (n.as_slice(), v.as_slice()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                (&b":method"[..], &b"GET"[..]),
+                (b":scheme", b"http"),
+                (b":authority", b"example.com"),
+                (b":path", b"/search?q=1"),
+                (b"accept", b"text/html"),
+                (b"user-agent", b"test/1"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! Fuzzing entry points for the protocol parsers.
+//!
+//! Each function takes arbitrary bytes, runs one parser over them and panics
+//! if an invariant breaks: parsed values must be well-formed, incremental
+//! parsing must agree with one-shot parsing however the input is split, and
+//! decoded data must survive a re-encode. Errors on malformed input are
+//! fine; panics are bugs. The parsers forbid `unsafe`, so memory safety is
+//! not in question, but logic bugs are.
+//!
+//! With `cargo fuzz`, a target is one line:
+//!
+//! ```ignore
+//! #![no_main]
+//! libfuzzer_sys::fuzz_target!(|data: &[u8]| fastapi_http::fuzz::request_head(data));
+//! ```
+//!
+//! Seed inputs live in `fuzz/corpus/<function>/` next to this crate's
+//! manifest; the unit tests run every function over its corpus.
+
+use fastapi_core::Body;
+use fastapi_core::multipart::{MultipartConfig, MultipartParser, MultipartStreamState};
+
+use crate::body::{BodyConfig, ChunkedReader, parse_body_with_consumed};
+use crate::http2::{HpackDecoder, hpack_encode_literal_without_indexing};
+use crate::parser::{
+    BodyLength, ParseLimits, ParseStatus, Parser, StatefulParser, has_invalid_header_value_bytes,
+    is_valid_header_name,
+};
+
+/// Boundary used by [`multipart`]; inputs delimit parts with `--fuzz`.
+pub const MULTIPART_BOUNDARY: &str = "fuzz";
+
+/// Inputs longer than this are not also fed one byte at a time.
+const BYTEWISE_LIMIT: usize = 512;
+
+/// Parse `data` as HTTP/1.1 request(s).
+///
+/// Checks that parsed header names are tokens and values hold no control
+/// bytes, and that [`StatefulParser`] yields the same requests whether
+/// `data` arrives at once, split in two, or byte by byte.
+pub fn request_head(data: &[u8]) {
+    if let Ok(request) = Parser::new().parse(data) {
+        for (name, value) in request.headers().iter() {
+            assert!(
+                is_valid_header_name(name.as_bytes()),
+                "invalid header name {name:?}"
+            );
+            assert!(
+                !has_invalid_header_value_bytes(value),
+                "control bytes in header {name}"
+            );
+        }
+    }
+
+    // Past the size limit, a whole buffer fails before any request in it is
+    // parsed, while a split one may yield the first requests.
+    if data.len() > ParseLimits::default().max_request_size {
+        return;
+    }
+    let whole = feed_all(&[data]);
+    let (head, tail) = data.split_at(split_point(data));
+    assert_eq!(whole, feed_all(&[head, tail]), "split at {}", head.len());
+    if data.len() <= BYTEWISE_LIMIT {
+        let bytes: Vec<&[u8]> = data.chunks(1).collect();
+        assert_eq!(whole, feed_all(&bytes), "fed byte by byte");
+    }
+}
+
+/// Decode `data` as a chunked request body.
+///
+/// Checks the decoded size accounting, that the consumed prefix alone
+/// decodes to the same body, that [`parse_body_with_consumed`] agrees, and
+/// that re-chunking the body decodes back to it.
+pub fn chunked_body(data: &[u8]) {
+    let config = BodyConfig::default();
+    let mut reader = ChunkedReader::new(data, &config);
+    let Ok(body) = reader.decode_all() else {
+        return;
+    };
+    let consumed = reader.bytes_consumed();
+    assert!(reader.is_complete());
+    assert_eq!(body.len(), reader.total_size());
+    assert!(consumed <= data.len(), "consumed past the input");
+    assert!(body.len() <= consumed, "body larger than its encoding");
+
+    let prefix = ChunkedReader::new(&data[..consumed], &config).decode_all();
+    assert_eq!(prefix.ok().as_ref(), Some(&body), "consumed prefix differs");
+    let (parsed, parsed_consumed) = parse_body_with_consumed(data, BodyLength::Chunked, &config)
+        .expect("parse_body disagrees with ChunkedReader");
+    assert_eq!((parsed, parsed_consumed), (Some(body.clone()), consumed));
+
+    let chunk_size = usize::from(data[0] % 16) + 1;
+    let mut encoded = Vec::new();
+    for chunk in body.chunks(chunk_size) {
+        encoded.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+        encoded.extend_from_slice(chunk);
+        encoded.extend_from_slice(b"\r\n");
+    }
+    encoded.extend_from_slice(b"0\r\n\r\n");
+    let mut reader = ChunkedReader::new(&encoded, &config);
+    assert_eq!(reader.decode_all().ok(), Some(body), "re-chunked body");
+    assert_eq!(reader.bytes_consumed(), encoded.len());
+}
+
+/// Parse `data` as a `multipart/form-data` body with boundary
+/// [`MULTIPART_BOUNDARY`].
+///
+/// Checks the configured limits hold and that, when both accept the input,
+/// incremental parsing of `data` split in two yields the same parts as
+/// one-shot parsing.
+pub fn multipart(data: &[u8]) {
+    let config = MultipartConfig::default().spool_threshold(usize::MAX);
+    let parser = MultipartParser::new(MULTIPART_BOUNDARY, config.clone());
+    let Ok(parts) = parser.parse(data) else {
+        return;
+    };
+    assert!(parts.len() <= config.get_max_fields());
+    let total: usize = parts.iter().map(|part| part.data.len()).sum();
+    assert!(total <= config.get_max_total_size());
+    for part in parts.iter().filter(|part| part.is_file()) {
+        assert!(part.data.len() <= config.get_max_file_size());
+    }
+
+    let (head, tail) = data.split_at(split_point(data));
+    let mut buffer = Vec::new();
+    let mut state = MultipartStreamState::default();
+    let mut streamed = Vec::new();
+    for (chunk, eof) in [(head, false), (tail, true)] {
+        buffer.extend_from_slice(chunk);
+        match parser.parse_incremental(&mut buffer, &mut state, eof) {
+            Ok(more) => streamed.extend(more),
+            Err(_) => return,
+        }
+    }
+    let summary = |parts: &[fastapi_core::multipart::Part]| {
+        parts
+            .iter()
+            .map(|p| {
+                (
+                    p.name.clone(),
+                    p.filename.clone(),
+                    p.content_type.clone(),
+                    p.data.clone(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        summary(&parts),
+        summary(&streamed),
+        "split at {}",
+        head.len()
+    );
+}
+
+/// Decode `data` as an HPACK header block, twice on the same decoder.
+///
+/// Checks the dynamic table accounting after each block and that the
+/// decoded headers survive a re-encode as literals.
+pub fn hpack(data: &[u8]) {
+    let mut decoder = HpackDecoder::new();
+    for _ in 0..2 {
+        let decoded = decoder.decode(data);
+        let (size, entries, max) = decoder.dynamic_table_usage();
+        assert_eq!(size, entries, "dynamic table size out of sync");
+        assert!(size <= max, "dynamic table over its maximum");
+        let Ok(headers) = decoded else {
+            return;
+        };
+
+        let mut block = Vec::new();
+        for (name, value) in &headers {
+            hpack_encode_literal_without_indexing(&mut block, name, value);
+        }
+        let reencoded = HpackDecoder::new().decode(&block);
+        assert_eq!(reencoded.as_ref(), Ok(&headers), "re-encoded block");
+    }
+}
+
+/// A split position derived from the input, so the fuzzer explores splits.
+fn split_point(data: &[u8]) -> usize {
+    data.first()
+        .map_or(0, |&b| usize::from(b) * data.len() / 255)
+}
+
+/// What [`StatefulParser`] produced for a request.
+#[derive(Debug, PartialEq)]
+struct Parsed {
+    method: String,
+    path: String,
+    query: Option<String>,
+    headers: Vec<(String, Vec<u8>)>,
+    /// `None` for a streamed body.
+    body: Option<Vec<u8>>,
+}
+
+/// Feed `chunks` to a fresh parser, draining complete requests after each.
+/// Returns the requests and whether parsing ended in an error.
+fn feed_all(chunks: &[&[u8]]) -> (Vec<Parsed>, bool) {
+    let mut parser = StatefulParser::new();
+    let mut requests = Vec::new();
+    for chunk in chunks {
+        let mut status = parser.feed(chunk);
+        while let Ok(ParseStatus::Complete { request, .. }) = status {
+            requests.push(Parsed {
+                method: request.method().as_str().to_string(),
+                path: request.path().to_string(),
+                query: request.query().map(str::to_string),
+                headers: request
+                    .headers()
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_vec()))
+                    .collect(),
+                body: match request.body() {
+                    Body::Empty => Some(Vec::new()),
+                    Body::Bytes(bytes) => Some(bytes.clone()),
+                    _ => None,
+                },
+            });
+            status = parser.feed(&[]);
+        }
+        if status.is_err() {
+            return (requests, true);
+        }
+    }
+    (requests, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Run `target` over every file in `fuzz/corpus/<name>/`.
+    fn run_corpus(name: &str, target: fn(&[u8])) {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("fuzz/corpus")
+            .join(name);
+        let mut count = 0;
+        for entry in std::fs::read_dir(&dir).expect("corpus directory") {
+            let path = entry.unwrap().path();
+            let data = std::fs::read(&path).unwrap();
+            let result = std::panic::catch_unwind(|| target(&data));
+            assert!(result.is_ok(), "{} failed on {}", name, path.display());
+            count += 1;
+        }
+        assert!(count > 0, "empty corpus for {name}");
+    }
+
+    #[test]
+    fn request_head_corpus() {
+        run_corpus("request_head", request_head);
+    }
+
+    #[test]
+    fn chunked_body_corpus() {
+        run_corpus("chunked_body", chunked_body);
+    }
+
+    #[test]
+    fn multipart_corpus() {
+        run_corpus("multipart", multipart);
+    }
+
+    #[test]
+    fn hpack_corpus() {
+        run_corpus("hpack", hpack);
+    }
+
+    #[test]
+    fn targets_accept_empty_and_garbage_input() {
+        for data in [&b""[..], b"\0", b"\xff\xff\xff\xff", b"\r\n\r\n"] {
+            request_head(data);
+            chunked_body(data);
+            multipart(data);
+            hpack(data);
+        }
+    }
+}
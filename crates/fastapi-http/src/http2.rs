@@ -423,6 +423,13 @@ impl HpackDecoder {
         self.evict_to_max();
     }
 
+    /// Dynamic table accounting as `(recorded size, sum of entry sizes,
+    /// max size)`, for invariant checks.
+    pub(crate) fn dynamic_table_usage(&self) -> (usize, usize, usize) {
+        let entries = self.dynamic.iter().map(|field| field.size).sum();
+        (self.dynamic_size, entries, self.dynamic_max_size)
+    }
+
     fn evict_to_max(&mut self) {
         while self.dynamic_size > self.dynamic_max_size {
             let Some(back) = self.dynamic.pop_back() else {
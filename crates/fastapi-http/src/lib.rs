@@ -12,6 +12,7 @@
 //! - Request body handling (Content-Length and chunked encoding)
 //! - Query string parsing with percent-decoding
 //! - Streaming response support
+//! - HTTP/1.1 and h2c client with connection pooling
 //!
 //! # Role In The System
 //!
@@ -64,9 +65,13 @@
 #![allow(clippy::used_underscore_binding)]
 #![allow(clippy::duplicated_attributes)]
 
+pub mod bench;
 pub mod body;
+pub mod cassette;
+pub mod client;
 pub mod connection;
 pub mod expect;
+pub mod fuzz;
 pub mod http2;
 pub mod multipart;
 mod parser;
@@ -75,6 +80,8 @@ pub mod range;
 mod response;
 mod server;
 pub mod streaming;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod websocket;
 
 pub use body::{
@@ -83,6 +90,8 @@ pub use body::{
     create_chunked_stream, create_content_length_stream, parse_body, parse_body_with_consumed,
     validate_content_length,
 };
+pub use cassette::Cassette;
+pub use client::{Client, ClientBuilder, ClientError, ClientProtocol, ClientResponse};
 pub use connection::{
     ConnectionInfo, STANDARD_HOP_BY_HOP_HEADERS, is_standard_hop_by_hop_header,
     parse_connection_header, should_keep_alive, strip_hop_by_hop_headers,
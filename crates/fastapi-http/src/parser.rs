@@ -25,7 +25,7 @@
 //! ```
 
 use crate::body::{BodyConfig, BodyError, parse_body_with_consumed};
-use fastapi_core::{Body, HttpVersion, Method, Request};
+use fastapi_core::{Body, HttpVersion, Method, RepeatedHeaders, Request};
 use std::borrow::Cow;
 
 /// HTTP parsing error.
@@ -295,11 +295,11 @@ fn is_token_char(b: u8) -> bool {
     )
 }
 
-fn is_valid_header_name(bytes: &[u8]) -> bool {
+pub(crate) fn is_valid_header_name(bytes: &[u8]) -> bool {
     !bytes.is_empty() && bytes.iter().all(|&b| is_token_char(b))
 }
 
-fn has_invalid_header_value_bytes(value: &[u8]) -> bool {
+pub(crate) fn has_invalid_header_value_bytes(value: &[u8]) -> bool {
     value
         .iter()
         .any(|&b| b == 0 || b == 0x7f || (b < 0x20 && b != b'\t' && b != b' '))
@@ -680,6 +680,32 @@ impl<'a> HeadersParser<'a> {
     }
 }
 
+/// Copies parsed headers into `request`.
+///
+/// Names that occur more than once are recorded as a [`RepeatedHeaders`]
+/// extension (the last value wins in the header map), so a
+/// `HeaderPolicyMiddleware` can reject duplicate singleton headers.
+fn copy_headers(request: &mut Request, headers: &HeadersParser<'_>) -> Result<(), ParseError> {
+    let mut repeated: Vec<String> = Vec::new();
+    for header in headers.iter() {
+        let header = header?;
+        // Optimized insert to avoid double allocation
+        let replaced = request
+            .headers_mut()
+            .insert_from_slice(header.name(), header.value());
+        if replaced.is_some() {
+            let name = header.name().to_ascii_lowercase();
+            if !repeated.contains(&name) {
+                repeated.push(name);
+            }
+        }
+    }
+    if !repeated.is_empty() {
+        request.insert_extension(RepeatedHeaders(repeated));
+    }
+    Ok(())
+}
+
 // ============================================================================
 // High-Level Parser (with owned Request for convenience)
 // ============================================================================
@@ -754,13 +780,7 @@ impl Parser {
         let mut request = Request::with_version(method, path, http_version);
         request.set_query(query);
 
-        // Set headers (using optimized insert to avoid double allocation)
-        for header in headers.iter() {
-            let header = header?;
-            request
-                .headers_mut()
-                .insert_from_slice(header.name(), header.value());
-        }
+        copy_headers(&mut request, &headers)?;
 
         // Set body
         if !body_bytes.is_empty() {
@@ -957,13 +977,7 @@ impl StatefulParser {
                     let mut request = Request::with_version(method, path, http_version);
                     request.set_query(query);
 
-                    // Use optimized insert to avoid double allocation
-                    for header in headers.iter() {
-                        let header = header?;
-                        request
-                            .headers_mut()
-                            .insert_from_slice(header.name(), header.value());
-                    }
+                    copy_headers(&mut request, &headers)?;
 
                     let body_length = headers.body_length();
                     if matches!(body_length, BodyLength::None) {
@@ -1251,6 +1265,7 @@ mod tests {
             ("OPTIONS", Method::Options),
             ("HEAD", Method::Head),
             ("TRACE", Method::Trace),
+            ("CONNECT", Method::Connect),
         ];
 
         for (method_str, expected_method) in methods {
@@ -2039,13 +2054,23 @@ mod tests {
     #[test]
     fn edge_connect_method() {
         let buffer = b"CONNECT example.com:443 HTTP/1.1\r\n";
-        let result = RequestLine::parse(buffer);
-        // CONNECT might not be supported
-        match result {
-            Ok(line) => assert_eq!(line.path(), "example.com:443"),
-            Err(ParseError::InvalidMethod) => {} // Also acceptable
-            Err(_) => panic!("unexpected error"),
-        }
+        let line = RequestLine::parse(buffer).unwrap();
+        assert_eq!(line.method(), Method::Connect);
+        assert_eq!(line.path(), "example.com:443");
+    }
+
+    #[test]
+    fn edge_registered_extension_method() {
+        let buffer = b"MKCALENDAR /cal HTTP/1.1\r\n";
+        assert!(matches!(
+            RequestLine::parse(buffer),
+            Err(ParseError::InvalidMethod)
+        ));
+
+        let method = Method::custom("MKCALENDAR").unwrap();
+        let line = RequestLine::parse(buffer).unwrap();
+        assert_eq!(line.method(), method);
+        assert_eq!(line.method().as_str(), "MKCALENDAR");
     }
 
     // ========================================================================
@@ -2142,6 +2167,22 @@ mod tests {
         assert!(request.query().is_none());
     }
 
+    #[test]
+    fn parser_records_repeated_headers() {
+        let parser = Parser::new();
+        let buffer = b"GET / HTTP/1.1\r\nHost: a.example\r\nAccept: */*\r\nHost: b.example\r\n\r\n";
+        let request = parser.parse(buffer).unwrap();
+
+        assert_eq!(request.headers().get("host"), Some(&b"b.example"[..]));
+        assert_eq!(
+            request.get_extension::<RepeatedHeaders>(),
+            Some(&RepeatedHeaders(vec!["host".to_string()]))
+        );
+
+        let request = parser.parse(b"GET / HTTP/1.1\r\nHost: a\r\n\r\n").unwrap();
+        assert!(request.get_extension::<RepeatedHeaders>().is_none());
+    }
+
     #[test]
     fn parser_post_with_json_body() {
         let parser = Parser::new();
@@ -78,7 +78,7 @@ static START_TIME: OnceLock<Instant> = OnceLock::new();
 ///
 /// This uses wall clock time relative to a lazily-initialized start point,
 /// which is compatible with asupersync's standalone timer mechanism.
-fn current_time() -> Time {
+pub(crate) fn current_time() -> Time {
     let start = START_TIME.get_or_init(Instant::now);
     let now = Instant::now();
     if now < *start {
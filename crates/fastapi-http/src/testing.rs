@@ -0,0 +1,236 @@
+//! Integration testing against a live server.
+//!
+//! [`TestClient`] calls the app in-process. To exercise the real protocol
+//! stack (connection reuse, HTTP/2 framing and flow control, graceful
+//! shutdown), [`TestClientBindExt::bind`] serves the same app with a
+//! [`TcpServer`] on an ephemeral port and returns a [`LiveServer`] with
+//! HTTP/1.1 and HTTP/2 [`Client`]s pointed at it:
+//!
+//! ```ignore
+//! use fastapi_http::testing::TestClientBindExt;
+//!
+//! let client = TestClient::new(app);
+//! let live = client.bind();
+//!
+//! live.block_on(async {
+//!     let mut resp = live.get("/items").send().await?;
+//!     assert_eq!(resp.status().as_u16(), 200);
+//!
+//!     let mut resp = live.http2_client().get(live.url("/items")).send().await?;
+//!     assert_eq!(resp.text().await?, "[]");
+//!     Ok::<_, ClientError>(())
+//! })?;
+//!
+//! live.shutdown(); // runs the app's shutdown hooks
+//! ```
+//!
+//! The server has no TLS, so HTTPS cannot be tested this way.
+
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::{Arc, mpsc};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use asupersync::runtime::{Runtime, RuntimeBuilder, reactor::create_reactor};
+use fastapi_core::{App, StartupOutcome, TestClient};
+
+use crate::client::{Client, RequestBuilder};
+use crate::server::{ServerConfig, TcpServer};
+
+/// How long [`LiveServer::start`] waits for the server to start listening.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Serves a [`TestClient`]'s app on a real socket.
+pub trait TestClientBindExt {
+    /// Serve the app on an ephemeral `127.0.0.1` port.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the server cannot start; see [`LiveServer::start`].
+    fn bind(&self) -> LiveServer {
+        self.bind_with_config(ServerConfig::new("127.0.0.1:0"))
+    }
+
+    /// Serve the app with `config`, listening on `config.bind_addr`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the server cannot start; see [`LiveServer::start`].
+    fn bind_with_config(&self, config: ServerConfig) -> LiveServer;
+}
+
+impl TestClientBindExt for TestClient<App> {
+    fn bind_with_config(&self, config: ServerConfig) -> LiveServer {
+        LiveServer::start(Arc::clone(self.handler()), config)
+    }
+}
+
+/// An app served on a background thread, with clients to call it.
+///
+/// The server runs on its own asupersync runtime. Run client requests with
+/// [`block_on`](Self::block_on), which reuses one runtime so pooled
+/// connections stay usable between calls. Dropping the server shuts it
+/// down, like [`shutdown`](Self::shutdown).
+pub struct LiveServer {
+    addr: SocketAddr,
+    server: Arc<TcpServer>,
+    thread: Option<JoinHandle<()>>,
+    runtime: Runtime,
+    http1: Client,
+    http2: Client,
+}
+
+impl LiveServer {
+    /// Run the app's startup hooks, then serve it with `config`.
+    ///
+    /// Connections are handled concurrently, so keep-alive connections do not
+    /// block each other.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a startup hook aborts, the address cannot be bound, or the
+    /// server does not start listening within ten seconds.
+    #[must_use]
+    pub fn start(app: Arc<App>, config: ServerConfig) -> Self {
+        let bind_addr = config.bind_addr.clone();
+        let server = Arc::new(TcpServer::new(config));
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<SocketAddr, String>>();
+
+        let thread = {
+            let server = Arc::clone(&server);
+            std::thread::spawn(move || {
+                let runtime = match live_runtime() {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(format!("runtime failed to start: {e}")));
+                        return;
+                    }
+                };
+                runtime.block_on(async move {
+                    if let StartupOutcome::Aborted(e) = app.run_startup_hooks().await {
+                        let _ = ready_tx.send(Err(format!("startup hook aborted: {e}")));
+                        return;
+                    }
+                    let listener = match asupersync::net::TcpListener::bind(bind_addr).await {
+                        Ok(listener) => listener,
+                        Err(e) => {
+                            let _ = ready_tx.send(Err(format!("bind failed: {e}")));
+                            return;
+                        }
+                    };
+                    let Some(cx) = asupersync::Cx::current() else {
+                        let _ = ready_tx.send(Err("runtime has no ambient Cx".to_string()));
+                        return;
+                    };
+                    let _ = ready_tx.send(listener.local_addr().map_err(|e| e.to_string()));
+
+                    let _ = server
+                        .serve_on_app_concurrent(&cx, listener, Arc::clone(&app))
+                        .await;
+                    app.run_shutdown_hooks().await;
+                });
+            })
+        };
+
+        let addr = match ready_rx.recv_timeout(STARTUP_TIMEOUT) {
+            Ok(Ok(addr)) => addr,
+            Ok(Err(e)) => panic!("live server failed to start: {e}"),
+            Err(_) => panic!("live server did not start within {STARTUP_TIMEOUT:?}"),
+        };
+
+        Self {
+            addr,
+            server,
+            thread: Some(thread),
+            runtime: live_runtime().expect("client runtime must build"),
+            http1: Client::new(),
+            http2: Client::builder().http2_prior_knowledge(true).build(),
+        }
+    }
+
+    /// The address the server listens on.
+    #[must_use]
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The absolute URL of `path` on this server.
+    #[must_use]
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    /// The HTTP/1.1 client.
+    #[must_use]
+    pub fn client(&self) -> &Client {
+        &self.http1
+    }
+
+    /// A client speaking HTTP/2 with prior knowledge (h2c).
+    #[must_use]
+    pub fn http2_client(&self) -> &Client {
+        &self.http2
+    }
+
+    /// The running server, e.g. to read its metrics.
+    #[must_use]
+    pub fn server(&self) -> &TcpServer {
+        &self.server
+    }
+
+    /// Start an HTTP/1.1 `GET` request for `path`.
+    pub fn get(&self, path: &str) -> RequestBuilder {
+        self.http1.get(self.url(path))
+    }
+
+    /// Start an HTTP/1.1 `POST` request for `path`.
+    pub fn post(&self, path: &str) -> RequestBuilder {
+        self.http1.post(self.url(path))
+    }
+
+    /// Run `future` (typically client requests) to completion.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Stop accepting connections, wait for in-flight requests (up to the
+    /// configured drain timeout), and run the app's shutdown hooks.
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+        // Idle keep-alive connections would otherwise hold up the drain.
+        self.http1.close_idle();
+        self.http2.close_idle();
+        self.server.shutdown();
+        let _ = thread.join();
+    }
+}
+
+impl Drop for LiveServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl std::fmt::Debug for LiveServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LiveServer")
+            .field("addr", &self.addr)
+            .field("running", &self.thread.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+fn live_runtime() -> Result<Runtime, String> {
+    let reactor = create_reactor().map_err(|e| format!("{e:?}"))?;
+    RuntimeBuilder::current_thread()
+        .with_reactor(reactor)
+        .build()
+        .map_err(|e| format!("{e:?}"))
+}
@@ -0,0 +1,180 @@
+use asupersync::runtime::{RuntimeBuilder, reactor::create_reactor};
+use fastapi_core::{Request, RequestContext, Response, ResponseBody};
+use fastapi_http::{Cassette, Client, ClientError, ServerConfig, TcpServer};
+use std::net::SocketAddr;
+use std::sync::{Arc, mpsc};
+use std::time::Duration;
+
+fn test_runtime() -> asupersync::runtime::Runtime {
+    let reactor = create_reactor().expect("client test reactor must build");
+    RuntimeBuilder::current_thread()
+        .with_reactor(reactor)
+        .build()
+        .expect("client test runtime must build")
+}
+
+/// Serves `<METHOD> <path> <body>` back to the caller.
+fn spawn_echo_server() -> (Arc<TcpServer>, SocketAddr, std::thread::JoinHandle<()>) {
+    let server = Arc::new(TcpServer::new(ServerConfig::new("127.0.0.1:0")));
+    let (addr_tx, addr_rx) = mpsc::channel::<SocketAddr>();
+
+    let server_thread = {
+        let server = Arc::clone(&server);
+        std::thread::spawn(move || {
+            let rt = test_runtime();
+            rt.block_on(async move {
+                let cx = asupersync::Cx::current().expect("runtime must install an ambient Cx");
+                let listener = asupersync::net::TcpListener::bind("127.0.0.1:0")
+                    .await
+                    .expect("bind must succeed");
+                addr_tx
+                    .send(listener.local_addr().expect("local_addr must work"))
+                    .expect("addr send must succeed");
+
+                let _ = server
+                    .serve_on(&cx, listener, |_ctx: RequestContext, req: &mut Request| {
+                        let mut echo =
+                            format!("{} {} ", req.method().as_str(), req.path()).into_bytes();
+                        echo.extend(req.take_body().into_bytes());
+                        async move { Response::ok().body(ResponseBody::Bytes(echo)) }
+                    })
+                    .await;
+            });
+        })
+    };
+
+    let addr = addr_rx
+        .recv_timeout(Duration::from_secs(5))
+        .expect("server must report addr");
+    (server, addr, server_thread)
+}
+
+#[test]
+fn http1_requests_reuse_pooled_connections() {
+    let (server, addr, server_thread) = spawn_echo_server();
+    let client = Client::builder().timeout(Duration::from_secs(5)).build();
+
+    test_runtime().block_on(async {
+        let mut resp = client
+            .get(format!("http://{addr}/first"))
+            .send()
+            .await
+            .expect("first request");
+        assert_eq!(resp.status().as_u16(), 200);
+        assert_eq!(resp.text().await.expect("first body"), "GET /first ");
+        assert_eq!(client.idle_connections(), 1);
+
+        let mut resp = client
+            .post(format!("http://{addr}/second"))
+            .body("payload")
+            .send()
+            .await
+            .expect("second request");
+        assert_eq!(
+            resp.text().await.expect("second body"),
+            "POST /second payload"
+        );
+        assert_eq!(client.idle_connections(), 1);
+    });
+
+    server.shutdown();
+    drop(std::net::TcpStream::connect(addr));
+    server_thread.join().expect("server thread join");
+}
+
+#[test]
+fn http2_prior_knowledge_requests() {
+    let (server, addr, server_thread) = spawn_echo_server();
+    let client = Client::builder()
+        .http2_prior_knowledge(true)
+        .timeout(Duration::from_secs(5))
+        .build();
+
+    test_runtime().block_on(async {
+        for path in ["/a", "/b"] {
+            let mut resp = client
+                .get(format!("http://{addr}{path}"))
+                .send()
+                .await
+                .expect("h2 request");
+            assert_eq!(resp.status().as_u16(), 200);
+            assert_eq!(resp.text().await.expect("h2 body"), format!("GET {path} "));
+        }
+        assert_eq!(client.idle_connections(), 1);
+    });
+
+    server.shutdown();
+    drop(std::net::TcpStream::connect(addr));
+    server_thread.join().expect("server thread join");
+}
+
+#[test]
+fn connect_errors_are_reported() {
+    // Bind and drop a listener to get a port that refuses connections.
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|l| l.local_addr())
+        .expect("bind");
+    let client = Client::new();
+    let err = test_runtime()
+        .block_on(client.get(format!("http://{addr}/")).send())
+        .expect_err("connection must be refused");
+    assert!(
+        matches!(err, fastapi_http::ClientError::Connect(_)),
+        "{err}"
+    );
+}
+
+#[test]
+fn cassette_records_then_replays_without_the_network() {
+    let path = std::env::temp_dir().join(format!(
+        "fastapi_client_cassette_{}.json",
+        std::process::id()
+    ));
+    let (server, addr, server_thread) = spawn_echo_server();
+    let url = format!("http://{addr}/echo");
+
+    let recorder = Cassette::record(&path);
+    let client = Client::builder()
+        .cassette(recorder.clone())
+        .timeout(Duration::from_secs(5))
+        .build();
+    test_runtime().block_on(async {
+        let mut resp = client
+            .post(&url)
+            .body("recorded")
+            .send()
+            .await
+            .expect("recorded request");
+        assert_eq!(resp.text().await.expect("body"), "POST /echo recorded");
+    });
+    assert_eq!(recorder.len(), 1);
+
+    server.shutdown();
+    drop(std::net::TcpStream::connect(addr));
+    server_thread.join().expect("server thread join");
+
+    let player = Cassette::open(&path).expect("cassette file");
+    assert!(!player.is_recording());
+    let client = Client::builder().cassette(player.clone()).build();
+    test_runtime().block_on(async {
+        let mut resp = client
+            .post(&url)
+            .body("recorded")
+            .send()
+            .await
+            .expect("replayed request");
+        assert_eq!(resp.status().as_u16(), 200);
+        assert_eq!(resp.text().await.expect("body"), "POST /echo recorded");
+
+        let err = client
+            .post(&url)
+            .body("other")
+            .send()
+            .await
+            .expect_err("unrecorded request");
+        assert!(matches!(err, ClientError::Cassette(_)), "{err}");
+    });
+    assert!(player.unplayed().is_empty());
+
+    let _ = std::fs::remove_file(path);
+}
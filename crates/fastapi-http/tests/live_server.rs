@@ -0,0 +1,61 @@
+#![cfg(feature = "testing")]
+
+use fastapi_core::{App, Request, RequestContext, Response, ResponseBody, TestClient};
+use fastapi_http::testing::TestClientBindExt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+fn hello_app(shut_down: Arc<AtomicBool>) -> App {
+    App::builder()
+        .get("/hello", |_ctx: &RequestContext, req: &mut Request| {
+            let body = format!("hello from {}", req.path());
+            async move { Response::ok().body(ResponseBody::Bytes(body.into_bytes())) }
+        })
+        .on_shutdown(move || shut_down.store(true, Ordering::SeqCst))
+        .build()
+}
+
+#[test]
+fn bound_client_speaks_http1_and_http2() {
+    let shut_down = Arc::new(AtomicBool::new(false));
+    let client = TestClient::new(hello_app(Arc::clone(&shut_down)));
+    let live = client.bind();
+
+    live.block_on(async {
+        for _ in 0..2 {
+            let mut resp = live.get("/hello").send().await.expect("http1 request");
+            assert_eq!(resp.status().as_u16(), 200);
+            assert_eq!(resp.text().await.expect("http1 body"), "hello from /hello");
+        }
+        // Both requests went over one keep-alive connection.
+        assert_eq!(live.client().idle_connections(), 1);
+
+        let mut resp = live
+            .http2_client()
+            .get(live.url("/hello"))
+            .send()
+            .await
+            .expect("h2 request");
+        assert_eq!(resp.status().as_u16(), 200);
+        assert_eq!(resp.text().await.expect("h2 body"), "hello from /hello");
+    });
+
+    live.shutdown();
+    assert!(shut_down.load(Ordering::SeqCst), "shutdown hooks must run");
+}
+
+#[test]
+fn unknown_routes_are_404_over_the_wire() {
+    let client = TestClient::new(hello_app(Arc::new(AtomicBool::new(false))));
+    let live = client.bind();
+
+    let status = live.block_on(async {
+        live.get("/missing")
+            .send()
+            .await
+            .expect("request")
+            .status()
+            .as_u16()
+    });
+    assert_eq!(status, 404);
+}
@@ -0,0 +1,219 @@
+//! `#[derive(ApiError)]` for enums whose variants are error responses.
+//!
+//! Each variant names its status code and, optionally, a detail message:
+//!
+//! ```ignore
+//! #[derive(ApiError)]
+//! enum ItemError {
+//!     #[api_error(status = 404, message = "Item {id} not found")]
+//!     NotFound { id: i64 },
+//!     #[api_error(status = 409, message = "Item {0} already exists")]
+//!     Conflict(String),
+//!     #[api_error(status = 503, description = "Storage is unavailable")]
+//!     Unavailable,
+//! }
+//! ```
+//!
+//! The generated `IntoResponse` impl renders the same `{"detail": ...}` body
+//! as `HttpError`; the generated `ErrorResponses` impl lets route macros
+//! document those statuses in OpenAPI.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, LitInt, LitStr, Variant, parse_macro_input};
+
+/// Parsed `#[api_error(...)]` attribute of one variant.
+struct VariantAttrs {
+    status: u16,
+    message: Option<LitStr>,
+    description: Option<LitStr>,
+}
+
+fn parse_variant_attrs(variant: &Variant) -> syn::Result<VariantAttrs> {
+    let mut status = None;
+    let mut message = None;
+    let mut description = None;
+
+    for attr in &variant.attrs {
+        if !attr.path().is_ident("api_error") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("status") {
+                let lit: LitInt = meta.value()?.parse()?;
+                let code: u16 = lit.base10_parse()?;
+                if !(400..=599).contains(&code) {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        "status must be an error status code (400-599)",
+                    ));
+                }
+                status = Some(code);
+            } else if meta.path.is_ident("message") {
+                message = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("description") {
+                description = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("expected `status`, `message` or `description`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let Some(status) = status else {
+        return Err(syn::Error::new_spanned(
+            &variant.ident,
+            "missing `#[api_error(status = ...)]` on variant",
+        ));
+    };
+    Ok(VariantAttrs {
+        status,
+        message,
+        description,
+    })
+}
+
+/// Whether a format string has any `{...}` placeholders (`{{` is an escape).
+fn has_placeholders(message: &str) -> bool {
+    message.replace("{{", "").contains('{')
+}
+
+/// Rewrite positional placeholders (`{0}`, `{1:?}`) to the `_0`, `_1`
+/// bindings of a tuple variant so they are captured inline.
+fn rewrite_positional(message: &str) -> String {
+    let mut out = String::with_capacity(message.len());
+    let mut chars = message.chars().peekable();
+    while let Some(c) = chars.next() {
+        out.push(c);
+        if c != '{' {
+            continue;
+        }
+        if chars.peek() == Some(&'{') {
+            out.push(chars.next().unwrap_or('{'));
+        } else if chars.peek().is_some_and(char::is_ascii_digit) {
+            out.push('_');
+        }
+    }
+    out
+}
+
+pub fn derive_api_error_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let Data::Enum(data_enum) = &input.data else {
+        return syn::Error::new_spanned(ident, "ApiError can only be derived for enums")
+            .to_compile_error()
+            .into();
+    };
+
+    let mut arms = Vec::new();
+    let mut responses = Vec::new();
+    for variant in &data_enum.variants {
+        let attrs = match parse_variant_attrs(variant) {
+            Ok(attrs) => attrs,
+            Err(err) => return err.to_compile_error().into(),
+        };
+        let status = attrs.status;
+        let variant_ident = &variant.ident;
+
+        let pattern = match &variant.fields {
+            Fields::Named(named) => {
+                let idents = named.named.iter().map(|f| &f.ident);
+                quote! { Self::#variant_ident { #(#idents),* } }
+            }
+            Fields::Unnamed(unnamed) => {
+                let names = (0..unnamed.unnamed.len()).map(|i| format_ident!("_{}", i));
+                quote! { Self::#variant_ident(#(#names),*) }
+            }
+            Fields::Unit => quote! { Self::#variant_ident },
+        };
+
+        let detail = match &attrs.message {
+            None => quote! { None },
+            Some(message) if !has_placeholders(&message.value()) => {
+                quote! { Some(String::from(#message)) }
+            }
+            Some(message) => {
+                let format = match &variant.fields {
+                    Fields::Unnamed(_) => {
+                        LitStr::new(&rewrite_positional(&message.value()), message.span())
+                    }
+                    _ => message.clone(),
+                };
+                quote! { Some(format!(#format)) }
+            }
+        };
+        arms.push(quote! {
+            #[allow(unused_variables)]
+            #pattern => (#status, #detail),
+        });
+
+        let description = attrs
+            .description
+            .or_else(|| attrs.message.filter(|m| !has_placeholders(&m.value())))
+            .map_or_else(
+                || {
+                    quote! {
+                        fastapi_core::StatusCode::from_u16(#status).canonical_reason()
+                    }
+                },
+                |lit| quote! { #lit },
+            );
+        responses.push(quote! { (#status, #description) });
+    }
+
+    let into_response_body = if arms.is_empty() {
+        quote! { match self {} }
+    } else {
+        quote! {
+            let (__status, __detail): (u16, Option<String>) = match self {
+                #(#arms)*
+            };
+            let __error = fastapi_core::HttpError::new(
+                fastapi_core::StatusCode::from_u16(__status),
+            );
+            fastapi_core::IntoResponse::into_response(match __detail {
+                Some(__detail) => __error.with_detail(__detail),
+                None => __error,
+            })
+        }
+    };
+
+    let expanded = quote! {
+        impl #impl_generics fastapi_core::IntoResponse for #ident #ty_generics #where_clause {
+            fn into_response(self) -> fastapi_core::Response {
+                #into_response_body
+            }
+        }
+
+        impl #impl_generics fastapi_openapi::ErrorResponses for #ident #ty_generics #where_clause {
+            fn error_responses() -> Vec<(u16, &'static str)> {
+                vec![#(#responses),*]
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_placeholders() {
+        assert!(has_placeholders("Item {id} not found"));
+        assert!(has_placeholders("{0}"));
+        assert!(!has_placeholders("Item not found"));
+        assert!(!has_placeholders("literal {{braces}}"));
+    }
+
+    #[test]
+    fn test_rewrite_positional() {
+        assert_eq!(rewrite_positional("Item {0} exists"), "Item {_0} exists");
+        assert_eq!(rewrite_positional("{0:?} and {1}"), "{_0:?} and {_1}");
+        assert_eq!(rewrite_positional("{{0}} {name}"), "{{0}} {name}");
+    }
+}
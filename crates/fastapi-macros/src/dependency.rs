@@ -0,0 +1,237 @@
+//! `#[dependency]` for functions that resolve a `Depends` value.
+//!
+//! ```ignore
+//! #[dependency]
+//! async fn current_account(token: BearerToken, db: Depends<Db>) -> Result<Account, HttpError> {
+//!     db.find_account(token.token()).await.ok_or_else(HttpError::unauthorized)
+//! }
+//!
+//! #[get("/me")]
+//! async fn me(account: Depends<CurrentAccount>) -> Json<Account> {
+//!     Json(account.into_inner().into_inner())
+//! }
+//! ```
+//!
+//! The function is kept as written. Next to it the macro emits a newtype
+//! named after the function in PascalCase (or the name given as
+//! `#[dependency(Name)]`) and implements `FromDependency` for it: every
+//! parameter is extracted with `FromRequest`, so nested `Depends` go through
+//! the request cache and cycle/scope checks, and the function's result is
+//! wrapped. `requirements()` lists the nested `Depends` and `State`
+//! parameters so the app can validate the graph when it is built.
+//! Failures become a `DependencyError` carrying the failing step's
+//! response; for `Result<T, E>` functions it is `DependencyError<E>`, so the
+//! responses of an `E` deriving `ApiError` are documented on every route
+//! that depends on it.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    FnArg, GenericArgument, Ident, ItemFn, PathArguments, ReturnType, Type, parse_macro_input,
+};
+
+/// Convert `snake_case` to `PascalCase`.
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect()
+}
+
+/// The success and error types of `Result<T, E>`, or `None` for other
+/// return types. The error type is `None` for aliases like `io::Result<T>`.
+fn result_types(ty: &Type) -> Option<(&Type, Option<&Type>)> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let mut types = args.args.iter().filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    });
+    Some((types.next()?, types.next()))
+}
+
+/// Name of a `&Cx`, `&RequestContext` or `&mut Request` parameter type.
+fn context_type_name(ty: &Type) -> Option<String> {
+    let Type::Reference(reference) = ty else {
+        return None;
+    };
+    let Type::Path(type_path) = &*reference.elem else {
+        return None;
+    };
+    let name = type_path.path.segments.last()?.ident.to_string();
+    matches!(name.as_str(), "Cx" | "RequestContext" | "Request").then_some(name)
+}
+
+pub fn dependency_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let name = if attr.is_empty() {
+        None
+    } else {
+        Some(parse_macro_input!(attr as Ident))
+    };
+    let input_fn = parse_macro_input!(item as ItemFn);
+    match expand(name, &input_fn) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(name: Option<Ident>, input_fn: &ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let sig = &input_fn.sig;
+    let fn_name = &sig.ident;
+    let vis = &input_fn.vis;
+
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "#[dependency] functions cannot be generic",
+        ));
+    }
+    let ReturnType::Type(_, output) = &sig.output else {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "#[dependency] functions must return the dependency value",
+        ));
+    };
+    let (value_ty, fallible, error_ty) = match result_types(output) {
+        Some((ok, err)) => (ok, true, err),
+        None => (&**output, false, None),
+    };
+    let error = if let Some(err) = error_ty {
+        quote! { fastapi_core::DependencyError<#err> }
+    } else {
+        quote! { fastapi_core::DependencyError }
+    };
+    let name =
+        name.unwrap_or_else(|| Ident::new(&pascal_case(&fn_name.to_string()), fn_name.span()));
+
+    let mut extracts = Vec::new();
+    let mut requirements = Vec::new();
+    let mut call_args = Vec::new();
+    for (i, arg) in sig.inputs.iter().enumerate() {
+        let FnArg::Typed(pat_type) = arg else {
+            return Err(syn::Error::new_spanned(
+                arg,
+                "#[dependency] functions cannot take `self`",
+            ));
+        };
+        let ty = &*pat_type.ty;
+        match context_type_name(ty).as_deref() {
+            Some("Cx") => call_args.push(quote! { ctx.cx() }),
+            Some("RequestContext") => call_args.push(quote! { ctx }),
+            Some(_) => call_args.push(quote! { req }),
+            None => {
+                let ident = Ident::new(&format!("__fastapi_arg_{i}"), Span::call_site());
+                extracts.push(quote! {
+                    let #ident = <#ty as fastapi_core::FromRequest>::from_request(ctx, req)
+                        .await
+                        .map_err(<#error>::new)?;
+                });
+                requirements.push(quote! {
+                    __requirements
+                        .extend((&&fastapi_core::DependencyProbe::<#ty>::default()).requirements());
+                });
+                call_args.push(quote! { #ident });
+            }
+        }
+    }
+
+    let mut call = quote! { #fn_name(#(#call_args),*) };
+    if sig.asyncness.is_some() {
+        call = quote! { #call.await };
+    }
+    if fallible {
+        call = quote! { #call.map_err(<#error>::new)? };
+    }
+
+    let doc = format!(" Dependency resolved by [`{fn_name}`].");
+    Ok(quote! {
+        #input_fn
+
+        #[doc = #doc]
+        #[derive(Clone)]
+        #vis struct #name(pub #value_ty);
+
+        impl #name {
+            /// Unwrap the resolved value.
+            #[must_use]
+            #vis fn into_inner(self) -> #value_ty {
+                self.0
+            }
+        }
+
+        impl ::std::ops::Deref for #name {
+            type Target = #value_ty;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl fastapi_core::FromDependency for #name {
+            type Error = #error;
+
+            async fn from_dependency(
+                ctx: &fastapi_core::RequestContext,
+                req: &mut fastapi_core::Request,
+            ) -> Result<Self, Self::Error> {
+                #(#extracts)*
+                Ok(Self(#call))
+            }
+
+            #[allow(unused_mut, clippy::needless_borrow)]
+            fn requirements() -> Vec<fastapi_core::DependencyRequirement> {
+                use fastapi_core::{DependencyProbeFallback as _, DependencyProbeMatch as _};
+
+                let mut __requirements = Vec::new();
+                #(#requirements)*
+                __requirements
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pascal_case() {
+        assert_eq!(pascal_case("current_user"), "CurrentUser");
+        assert_eq!(pascal_case("db"), "Db");
+        assert_eq!(pascal_case("__private_pool"), "PrivatePool");
+    }
+
+    #[test]
+    fn test_result_types() {
+        let ty: Type = syn::parse_quote! { Result<User, HttpError> };
+        let (ok, err) = result_types(&ty).unwrap();
+        assert_eq!(quote!(#ok).to_string(), "User");
+        assert_eq!(
+            err.map(|err| quote!(#err).to_string()).as_deref(),
+            Some("HttpError")
+        );
+
+        let ty: Type = syn::parse_quote! { std::result::Result<Vec<u8>, E> };
+        assert!(result_types(&ty).is_some());
+
+        let ty: Type = syn::parse_quote! { io::Result<Config> };
+        assert!(matches!(result_types(&ty), Some((_, None))));
+
+        let ty: Type = syn::parse_quote! { Settings };
+        assert!(result_types(&ty).is_none());
+    }
+}
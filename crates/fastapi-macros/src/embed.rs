@@ -0,0 +1,105 @@
+//! `embed_dir!` implementation.
+//!
+//! Walks a directory (relative to the invoking crate's `CARGO_MANIFEST_DIR`)
+//! at compile time and expands to a `fastapi_core::embed::EmbeddedDir` whose
+//! files are pulled in with `include_bytes!`, so edits to existing files
+//! trigger a rebuild. Hidden entries (names starting with `.`) are skipped.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use std::path::{Path, PathBuf};
+use syn::{LitStr, parse_macro_input};
+
+pub fn embed_dir_impl(input: TokenStream) -> TokenStream {
+    let dir = parse_macro_input!(input as LitStr);
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
+    let root = Path::new(&manifest_dir).join(dir.value());
+
+    let files = match collect_files(&root) {
+        Ok(files) => files,
+        Err(err) => {
+            let message = format!("embed_dir!: cannot read `{}`: {err}", root.display());
+            return syn::Error::new(dir.span(), message)
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let entries = files.iter().map(|(relative, absolute)| {
+        let absolute = absolute.to_string_lossy();
+        quote! {
+            fastapi_core::embed::EmbeddedFile::new(#relative, include_bytes!(#absolute))
+        }
+    });
+
+    quote! {
+        {
+            static __EMBEDDED_FILES: &[fastapi_core::embed::EmbeddedFile] = &[#(#entries),*];
+            fastapi_core::embed::EmbeddedDir::new(__EMBEDDED_FILES)
+        }
+    }
+    .into()
+}
+
+/// Every non-hidden file under `root`, as (`/`-separated relative path,
+/// absolute path), sorted by relative path.
+fn collect_files(root: &Path) -> std::io::Result<Vec<(String, PathBuf)>> {
+    let root = root.canonicalize()?;
+    let mut files = Vec::new();
+    let mut pending = vec![root.clone()];
+
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() || path.is_file() {
+                let relative = path
+                    .strip_prefix(&root)
+                    .unwrap_or(&path)
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push((relative, path));
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_nested_files_sorted_and_skips_hidden() {
+        let root = std::env::temp_dir().join(format!("embed_dir_test_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("css")).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::write(root.join("index.html"), "<h1>hi</h1>").unwrap();
+        std::fs::write(root.join("css/app.css"), "body {}").unwrap();
+        std::fs::write(root.join(".env"), "SECRET=1").unwrap();
+        std::fs::write(root.join(".git/HEAD"), "ref").unwrap();
+
+        let files = collect_files(&root).unwrap();
+        let names: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["css/app.css", "index.html"]);
+        assert!(files.iter().all(|(_, path)| path.is_absolute()));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn missing_directory_is_an_error() {
+        assert!(collect_files(Path::new("/definitely/not/here")).is_err());
+    }
+}
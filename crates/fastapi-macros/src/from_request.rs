@@ -0,0 +1,102 @@
+//! `#[derive(FromRequest)]` for structs whose fields are extractors.
+//!
+//! Every field is extracted in declaration order. Validation failures
+//! (missing or mistyped path/query/header/cookie values, malformed JSON,
+//! `Valid<T>` rule violations) are collected and returned together as one
+//! 422 response; any other failure (415, 413, missing state, ...) is returned
+//! as soon as it happens.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, parse_macro_input};
+
+pub fn derive_from_request_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let Data::Struct(data_struct) = &input.data else {
+        return syn::Error::new_spanned(ident, "FromRequest can only be derived for structs")
+            .to_compile_error()
+            .into();
+    };
+
+    let fields: Vec<_> = data_struct.fields.iter().collect();
+    let vars: Vec<_> = (0..fields.len())
+        .map(|i| format_ident!("__field_{}", i))
+        .collect();
+
+    let extracts = fields.iter().zip(&vars).map(|(field, var)| {
+        let ty = &field.ty;
+        quote! {
+            let #var = match <#ty as fastapi_core::FromRequest>::from_request(ctx, req).await {
+                Ok(value) => Some(value),
+                Err(error) => {
+                    let probe = fastapi_core::ValidationProbe::<
+                        <#ty as fastapi_core::FromRequest>::Error,
+                    >::default();
+                    match (&&probe).split(error) {
+                        Ok(errors) => {
+                            __errors.merge(errors);
+                            None
+                        }
+                        Err(error) => {
+                            return Err(fastapi_core::CompositeExtractError::Rejected(
+                                fastapi_core::IntoResponse::into_response(error),
+                            ));
+                        }
+                    }
+                }
+            };
+        }
+    });
+
+    let construct = match &data_struct.fields {
+        Fields::Named(named) => {
+            let idents = named.named.iter().map(|f| &f.ident);
+            quote! { Self { #(#idents: #vars),* } }
+        }
+        Fields::Unnamed(_) => quote! { Self(#(#vars),*) },
+        Fields::Unit => quote! { Self },
+    };
+
+    if fields.is_empty() {
+        return quote! {
+            impl #impl_generics fastapi_core::FromRequest for #ident #ty_generics #where_clause {
+                type Error = fastapi_core::CompositeExtractError;
+
+                async fn from_request(
+                    _ctx: &fastapi_core::RequestContext,
+                    _req: &mut fastapi_core::Request,
+                ) -> Result<Self, Self::Error> {
+                    Ok(#construct)
+                }
+            }
+        }
+        .into();
+    }
+
+    let expanded = quote! {
+        impl #impl_generics fastapi_core::FromRequest for #ident #ty_generics #where_clause {
+            type Error = fastapi_core::CompositeExtractError;
+
+            async fn from_request(
+                ctx: &fastapi_core::RequestContext,
+                req: &mut fastapi_core::Request,
+            ) -> Result<Self, Self::Error> {
+                #[allow(unused_imports)]
+                use fastapi_core::{ValidationProbeFallback as _, ValidationProbeMatch as _};
+
+                let mut __errors = fastapi_core::ValidationErrors::new();
+                #(#extracts)*
+
+                let (#(Some(#vars),)*) = (#(#vars,)*) else {
+                    return Err(fastapi_core::CompositeExtractError::Validation(__errors));
+                };
+                Ok(#construct)
+            }
+        }
+    };
+
+    expanded.into()
+}
@@ -2,9 +2,15 @@
 //!
 //! This crate provides the following macros:
 //!
-//! - Route macros: `#[get]`, `#[post]`, `#[put]`, `#[delete]`, `#[patch]`, `#[head]`, `#[options]`
+//! - Route macros: `#[get]`, `#[post]`, `#[put]`, `#[delete]`, `#[patch]`, `#[head]`, `#[options]`,
+//!   `#[trace]`, and `#[route]` for handlers serving several methods
 //! - `#[derive(Validate)]` for compile-time validation
 //! - `#[derive(JsonSchema)]` for OpenAPI schema generation
+//! - `#[derive(FromRequest)]` for composite extractors
+//! - `#[derive(ApiError)]` for error enums
+//! - `#[derive(TypedPath)]` for compile-checked path templates
+//! - `#[dependency]` for functions injected with `Depends`
+//! - `embed_dir!` for compiling static assets into the binary
 //!
 //! # Role In The System
 //!
@@ -27,10 +33,15 @@
 
 use proc_macro::TokenStream;
 
+mod api_error;
+mod dependency;
+mod embed;
+mod from_request;
 mod openapi;
 mod param;
 mod response_model;
 mod route;
+mod typed_path;
 mod validate;
 
 /// Mark a function as a GET handler.
@@ -43,6 +54,31 @@ mod validate;
 ///     // ...
 /// }
 /// ```
+///
+/// # OpenAPI Metadata
+///
+/// All route macros accept operation metadata after the path:
+///
+/// ```ignore
+/// #[get(
+///     "/items/{id}",
+///     summary = "Get an item",
+///     description = "Looks an item up by id.",
+///     tags("items"),
+///     operation_id = "getItem",
+///     responses(404 = NotFound, 410 = (Gone, "Item was deleted")),
+///     response_headers(200 = ("ETag", "Item version")),
+/// )]
+/// async fn get_item(id: Path<i64>) -> Result<Json<Item>, HttpError> {
+///     // ...
+/// }
+/// ```
+///
+/// Every type in `responses(...)` must implement `JsonSchema`; it is
+/// registered as a component and documented under its status code.
+///
+/// The `deprecated` and `idempotent` flags take no value. `idempotent` marks
+/// the operation as safe to retry (see `RetryAfterMiddleware`).
 #[proc_macro_attribute]
 pub fn get(attr: TokenStream, item: TokenStream) -> TokenStream {
     route::route_impl("Get", attr, item)
@@ -109,6 +145,78 @@ pub fn options(attr: TokenStream, item: TokenStream) -> TokenStream {
     route::route_impl("Options", attr, item)
 }
 
+/// Mark a function as a TRACE handler.
+///
+/// TRACE requests ask the server to echo the received request back, for
+/// diagnosing what intermediaries changed along the way.
+///
+/// # Example
+///
+/// ```ignore
+/// #[trace("/debug/echo")]
+/// async fn trace_echo(req: &Request) -> Response {
+///     // ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn trace(attr: TokenStream, item: TokenStream) -> TokenStream {
+    route::route_impl("Trace", attr, item)
+}
+
+/// Mark a function as a handler for several HTTP methods.
+///
+/// `methods(...)` is required and lists the methods, case-insensitively.
+/// Every other route attribute works as on `#[get]`; the handler is documented
+/// as one operation per method, with `operation_id` suffixed by the method.
+///
+/// Instead of `{name}_route()`, the macro generates `{name}_routes()`, which
+/// returns one `RouteEntry` per method.
+///
+/// # Example
+///
+/// ```ignore
+/// #[route("/items/{id}", methods("GET", "HEAD"))]
+/// async fn get_item(id: Path<i64>) -> Json<Item> {
+///     // ...
+/// }
+///
+/// let mut app = App::builder();
+/// for entry in get_item_routes() {
+///     app = app.route_entry(entry);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn route(attr: TokenStream, item: TokenStream) -> TokenStream {
+    route::route_methods_impl(attr, item)
+}
+
+/// Turn a function into a dependency usable with `Depends`.
+///
+/// Parameters are extractors (including other `Depends`), `&Cx`,
+/// `&RequestContext` or `&mut Request`. The function may be async and may
+/// return `Result<T, E>` with `E: IntoResponse`. The macro keeps the function
+/// and adds a newtype around `T`, named after the function in PascalCase or
+/// as given in `#[dependency(Name)]`, that implements `FromDependency`.
+/// Caching and scope follow the `Depends<_, C>` config at the use site.
+///
+/// # Example
+///
+/// ```ignore
+/// #[dependency]
+/// async fn current_account(token: BearerToken, db: Depends<Db>) -> Result<Account, HttpError> {
+///     db.find_account(token.token()).await.ok_or_else(HttpError::unauthorized)
+/// }
+///
+/// #[get("/me")]
+/// async fn me(account: Depends<CurrentAccount>) -> Json<Account> {
+///     Json(account.into_inner().into_inner())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn dependency(attr: TokenStream, item: TokenStream) -> TokenStream {
+    dependency::dependency_impl(attr, item)
+}
+
 /// Derive validation for a struct.
 ///
 /// # Validation Attributes
@@ -142,16 +250,97 @@ pub fn derive_validate(input: TokenStream) -> TokenStream {
 /// #[derive(JsonSchema)]
 /// struct Item {
 ///     id: i64,
+///     #[schema(example = "Widget")]
 ///     name: String,
 ///     #[schema(nullable)]
 ///     description: Option<String>,
 /// }
 /// ```
-#[proc_macro_derive(JsonSchema, attributes(schema))]
+#[proc_macro_derive(JsonSchema, attributes(schema, serde, validate))]
 pub fn derive_json_schema(input: TokenStream) -> TokenStream {
     openapi::derive_json_schema_impl(input)
 }
 
+/// Derive an extractor for a struct whose fields are all extractors.
+///
+/// Fields are extracted in order. Validation failures from every field are
+/// returned together as one 422 response; any other failure (wrong content
+/// type, missing state, ...) is returned as soon as it happens.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(FromRequest)]
+/// struct UpdateItem {
+///     id: Path<i64>,
+///     params: Query<UpdateParams>,
+///     body: Json<ItemPatch>,
+///     db: State<Db>,
+/// }
+///
+/// #[put("/items/{id}")]
+/// async fn update_item(input: UpdateItem) -> StatusCode { /* ... */ }
+/// ```
+#[proc_macro_derive(FromRequest)]
+pub fn derive_from_request(input: TokenStream) -> TokenStream {
+    from_request::derive_from_request_impl(input)
+}
+
+/// Derive `IntoResponse` and OpenAPI error documentation for an error enum.
+///
+/// Every variant needs `#[api_error(status = ...)]`. An optional `message`
+/// becomes the `{"detail": ...}` body and may refer to named fields
+/// (`{id}`) or tuple fields (`{0}`); without one the status reason phrase is
+/// used. `description` overrides the OpenAPI response description.
+///
+/// Handlers returning `Result<_, E>` for such an `E` get its statuses
+/// documented automatically, unless `responses(...)` already covers them.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(ApiError)]
+/// enum ItemError {
+///     #[api_error(status = 404, message = "Item {id} not found")]
+///     NotFound { id: i64 },
+///     #[api_error(status = 409, description = "An item with this name exists")]
+///     Conflict,
+/// }
+///
+/// #[get("/items/{id}")]
+/// async fn get_item(id: Path<i64>) -> Result<Json<Item>, ItemError> { /* ... */ }
+/// ```
+#[proc_macro_derive(ApiError, attributes(api_error))]
+pub fn derive_api_error(input: TokenStream) -> TokenStream {
+    api_error::derive_api_error_impl(input)
+}
+
+/// Derive `TypedPath` and `FromRequest` for a struct bound to a route template.
+///
+/// The `#[typed_path("...")]` template's parameters and the struct's fields
+/// must match by name; field types must implement `FromStr` and `Display`.
+/// Pass the type instead of a path string to a route macro to serve the
+/// template, and call `url()` to build links to it.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(TypedPath)]
+/// #[typed_path("/items/{id}")]
+/// struct ItemPath {
+///     id: i64,
+/// }
+///
+/// #[get(ItemPath)]
+/// async fn get_item(path: ItemPath) -> Json<Item> { /* ... */ }
+///
+/// let link = ItemPath { id: 42 }.url(); // "/items/42"
+/// ```
+#[proc_macro_derive(TypedPath, attributes(typed_path))]
+pub fn derive_typed_path(input: TokenStream) -> TokenStream {
+    typed_path::derive_typed_path_impl(input)
+}
+
 /// Derive response model alias metadata for FastAPI-compatible `by_alias` handling.
 ///
 /// This emits an implementation of `fastapi_core::ResponseModelAliases` using
@@ -160,3 +349,22 @@ pub fn derive_json_schema(input: TokenStream) -> TokenStream {
 pub fn derive_response_model_aliases(input: TokenStream) -> TokenStream {
     response_model::derive_response_model_aliases_impl(input)
 }
+
+/// Embed a directory's files into the binary at compile time.
+///
+/// The path is relative to the crate's `Cargo.toml`. Expands to a
+/// `fastapi_core::embed::EmbeddedDir`; serve it with
+/// `AppBuilder::serve_embedded`. Hidden files are skipped, and adding files
+/// to the directory needs a rebuild to be picked up.
+///
+/// # Example
+///
+/// ```ignore
+/// let app = App::builder()
+///     .serve_embedded("/static", embed_dir!("static"))
+///     .build();
+/// ```
+#[proc_macro]
+pub fn embed_dir(input: TokenStream) -> TokenStream {
+    embed::embed_dir_impl(input)
+}
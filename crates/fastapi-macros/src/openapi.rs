@@ -7,7 +7,15 @@
 //!
 //! - Primitive types: String, &str, i8-i64, u8-u64, f32, f64, bool
 //! - Collections: `Vec<T>`, `Option<T>`, `HashMap<K, V>`
-//! - Custom structs (with nested schema generation)
+//! - Custom structs (with nested schema generation), including generic structs
+//!   such as `Page<T>`, which get one component name per instantiation
+//! - Enums: unit-only enums as a string `enum`, data enums as `oneOf`
+//!
+//! Enums follow their serde representation: `#[serde(tag = "...")]`,
+//! `#[serde(tag = "...", content = "...")]`, `#[serde(untagged)]`,
+//! `#[serde(rename_all = "...")]`, variant-level `rename`/`skip` and `rename`
+//! on variant fields are honored. Tuple variants become `prefixItems` arrays.
+//! Tagged enums also emit an OpenAPI `discriminator` on the tag property.
 //!
 //! # Attributes
 //!
@@ -16,13 +24,27 @@
 //! - `#[schema(format = "...")]` - Override format (e.g., "email", "date-time")
 //! - `#[schema(nullable)]` - Mark field as nullable
 //! - `#[schema(skip)]` - Skip field in schema generation
+//! - `#[schema(deprecated)]` - Mark the field or type as deprecated (Rust's own
+//!   `#[deprecated]` attribute is honored too)
+//! - `#[schema(example = ...)]` - Example value; any expression convertible
+//!   into `serde_json::Value` (literals, or `serde_json::json!({...})`)
+//! - `#[validate(...)]` - Constraints shared with `#[derive(Validate)]` are
+//!   mirrored into the schema: `length` as `minLength`/`maxLength` (or
+//!   `minItems`/`maxItems`), `range` as `minimum`/`maximum`, `regex` as
+//!   `pattern`, `multiple_of` as `multipleOf`, `email`/`url` as `format`
+//!
+//! Fields with `#[serde(default)]` or `#[serde(default = "path")]` (or in a
+//! struct marked `#[serde(default)]`) are not required, and their default
+//! value is documented as the schema `default` when it is `Serialize`.
 
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
+use syn::meta::ParseNestedMeta;
 use syn::{
-    Attribute, Data, DeriveInput, Expr, ExprLit, Fields, GenericArgument, Lit, Meta, MetaNameValue,
-    PathArguments, Type, parse_macro_input,
+    Attribute, Data, DataEnum, DeriveInput, Expr, ExprLit, Fields, GenericArgument, Generics,
+    Ident, Lit, LitStr, Meta, MetaNameValue, PathArguments, Token, Type, Variant,
+    parse_macro_input, parse_quote,
 };
 
 /// Schema attributes parsed from `#[schema(...)]`.
@@ -33,6 +55,8 @@ struct SchemaAttrs {
     format: Option<String>,
     nullable: bool,
     skip: bool,
+    deprecated: bool,
+    example: Option<Expr>,
 }
 
 impl SchemaAttrs {
@@ -40,6 +64,10 @@ impl SchemaAttrs {
         let mut result = Self::default();
 
         for attr in attrs {
+            if attr.path().is_ident("deprecated") {
+                result.deprecated = true;
+                continue;
+            }
             if !attr.path().is_ident("schema") {
                 continue;
             }
@@ -67,6 +95,10 @@ impl SchemaAttrs {
                     result.nullable = true;
                 } else if meta.path.is_ident("skip") {
                     result.skip = true;
+                } else if meta.path.is_ident("deprecated") {
+                    result.deprecated = true;
+                } else if meta.path.is_ident("example") {
+                    result.example = Some(meta.value()?.parse::<Expr>()?);
                 }
                 Ok(())
             });
@@ -112,9 +144,13 @@ fn extract_doc_comment(attrs: &[Attribute]) -> Option<String> {
 /// Information about a struct field.
 struct FieldInfo {
     name: String,
+    ident: Ident,
     ty: Type,
     attrs: SchemaAttrs,
     is_optional: bool,
+    default: Option<SerdeDefault>,
+    /// Method calls carrying `#[validate(...)]` constraints into the schema.
+    constraints: TokenStream2,
 }
 
 /// Analyze a type to determine if it's Option<T> and extract T if so.
@@ -158,6 +194,11 @@ fn generate_type_schema(ty: &Type, attrs: &SchemaAttrs) -> TokenStream2 {
                 schema_type: fastapi_openapi::SchemaType::String,
                 format: Some(#format.to_string()),
                 nullable: #nullable,
+                example: None,
+                examples: Vec::new(),
+                default: None,
+                constraints: fastapi_openapi::SchemaConstraints::default(),
+                deprecated: false,
             })
         };
     }
@@ -243,6 +284,10 @@ fn generate_type_schema(ty: &Type, attrs: &SchemaAttrs) -> TokenStream2 {
                                     properties: std::collections::HashMap::new(),
                                     required: Vec::new(),
                                     additional_properties: Some(Box::new(#value_schema)),
+                                    example: None,
+                                    examples: Vec::new(),
+                                    default: None,
+                                    deprecated: false,
                                 })
                             };
                         }
@@ -257,7 +302,7 @@ fn generate_type_schema(ty: &Type, attrs: &SchemaAttrs) -> TokenStream2 {
                 _ => {
                     // For custom types, try to call their JsonSchema implementation
                     quote! {
-                        <#ty as fastapi_openapi::JsonSchema>::schema()
+                        fastapi_openapi::nested_schema::<#ty>(&mut __registry)
                     }
                 }
             };
@@ -266,7 +311,479 @@ fn generate_type_schema(ty: &Type, attrs: &SchemaAttrs) -> TokenStream2 {
 
     // Fallback: try to use the type's JsonSchema implementation
     quote! {
-        <#ty as fastapi_openapi::JsonSchema>::schema()
+        fastapi_openapi::nested_schema::<#ty>(&mut __registry)
+    }
+}
+
+// ============================================================================
+// Enum support
+// ============================================================================
+
+/// Serde attributes that affect the JSON shape of an enum.
+#[derive(Default)]
+struct SerdeAttrs {
+    tag: Option<String>,
+    content: Option<String>,
+    untagged: bool,
+    rename: Option<String>,
+    rename_all: Option<String>,
+    skip: bool,
+    default: Option<SerdeDefault>,
+}
+
+/// Source of a `#[serde(default)]` value.
+enum SerdeDefault {
+    /// `#[serde(default)]`: `Default::default()`.
+    Trait,
+    /// `#[serde(default = "path")]`: calls `path()`.
+    Path(syn::ExprPath),
+}
+
+impl SerdeAttrs {
+    fn from_attributes(attrs: &[Attribute]) -> Self {
+        let mut result = Self::default();
+
+        for attr in attrs {
+            if !attr.path().is_ident("serde") {
+                continue;
+            }
+
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("tag") {
+                    result.tag = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("content") {
+                    result.content = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("untagged") {
+                    result.untagged = true;
+                } else if meta.path.is_ident("rename") {
+                    result.rename = parse_serialize_name(&meta)?;
+                } else if meta.path.is_ident("rename_all") {
+                    result.rename_all = parse_serialize_name(&meta)?;
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                    result.skip = true;
+                } else if meta.path.is_ident("default") {
+                    result.default = Some(if meta.input.peek(Token![=]) {
+                        SerdeDefault::Path(meta.value()?.parse::<LitStr>()?.parse()?)
+                    } else {
+                        SerdeDefault::Trait
+                    });
+                } else {
+                    skip_meta_value(&meta)?;
+                }
+                Ok(())
+            });
+        }
+
+        result
+    }
+}
+
+/// Parse `name = "..."` or `name(serialize = "...")`, returning the serialized name.
+fn parse_serialize_name(meta: &ParseNestedMeta<'_>) -> syn::Result<Option<String>> {
+    if meta.input.peek(Token![=]) {
+        return Ok(Some(meta.value()?.parse::<LitStr>()?.value()));
+    }
+
+    let mut serialize = None;
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident("serialize") {
+            serialize = Some(inner.value()?.parse::<LitStr>()?.value());
+        } else {
+            skip_meta_value(&inner)?;
+        }
+        Ok(())
+    })?;
+    Ok(serialize)
+}
+
+/// Consume the value of a serde attribute we don't interpret.
+fn skip_meta_value(meta: &ParseNestedMeta<'_>) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|inner| skip_meta_value(&inner))?;
+    }
+    Ok(())
+}
+
+/// Apply a serde `rename_all` rule to a PascalCase variant name.
+fn rename_variant(rule: &str, variant: &str) -> String {
+    let snake = || {
+        let mut out = String::with_capacity(variant.len() + 4);
+        for (i, ch) in variant.char_indices() {
+            if i > 0 && ch.is_ascii_uppercase() {
+                out.push('_');
+            }
+            out.push(ch.to_ascii_lowercase());
+        }
+        out
+    };
+
+    match rule {
+        "lowercase" => variant.to_ascii_lowercase(),
+        "UPPERCASE" => variant.to_ascii_uppercase(),
+        "camelCase" => {
+            let mut chars = variant.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_lowercase().to_string() + chars.as_str()
+            })
+        }
+        "snake_case" => snake(),
+        "SCREAMING_SNAKE_CASE" => snake().to_ascii_uppercase(),
+        "kebab-case" => snake().replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake().replace('_', "-").to_ascii_uppercase(),
+        _ => variant.to_string(),
+    }
+}
+
+/// How serde lays out an enum on the wire.
+enum EnumRepr {
+    /// `{"Variant": payload}` (serde default).
+    External,
+    /// `{"<tag>": "Variant", ...fields}`.
+    Internal { tag: String },
+    /// `{"<tag>": "Variant", "<content>": payload}`.
+    Adjacent { tag: String, content: String },
+    /// `payload` with no tag.
+    Untagged,
+}
+
+impl EnumRepr {
+    fn from_serde(attrs: &SerdeAttrs) -> Self {
+        match (&attrs.tag, &attrs.content) {
+            _ if attrs.untagged => Self::Untagged,
+            (Some(tag), Some(content)) => Self::Adjacent {
+                tag: tag.clone(),
+                content: content.clone(),
+            },
+            (Some(tag), None) => Self::Internal { tag: tag.clone() },
+            (None, _) => Self::External,
+        }
+    }
+
+    fn tag(&self) -> Option<&str> {
+        match self {
+            Self::Internal { tag } | Self::Adjacent { tag, .. } => Some(tag.as_str()),
+            Self::External | Self::Untagged => None,
+        }
+    }
+}
+
+/// Generate an `ObjectSchema` literal from property/schema pairs.
+fn object_schema_tokens(
+    title: &TokenStream2,
+    description: &TokenStream2,
+    properties: &[(String, TokenStream2)],
+    required: &[String],
+) -> TokenStream2 {
+    let keys = properties.iter().map(|(k, _)| k);
+    let schemas = properties.iter().map(|(_, s)| s);
+    quote! {
+        fastapi_openapi::Schema::Object(fastapi_openapi::ObjectSchema {
+            title: #title,
+            description: #description,
+            properties: {
+                let mut props = std::collections::HashMap::new();
+                #(props.insert(#keys.to_string(), #schemas);)*
+                props
+            },
+            required: vec![#(#required.to_string()),*],
+            additional_properties: None,
+            example: None,
+            examples: Vec::new(),
+            default: None,
+            deprecated: false,
+        })
+    }
+}
+
+/// Schema for the data carried by a variant, or `None` for unit variants.
+///
+/// Named fields are returned as separate properties so internally tagged
+/// enums can merge the tag into the same object.
+enum VariantPayload {
+    Unit,
+    Fields {
+        properties: Vec<(String, TokenStream2)>,
+        required: Vec<String>,
+    },
+    Value(TokenStream2),
+}
+
+fn variant_payload(fields: &Fields) -> VariantPayload {
+    match fields {
+        Fields::Unit => VariantPayload::Unit,
+        Fields::Named(named) => {
+            let mut properties = Vec::new();
+            let mut required = Vec::new();
+            for f in &named.named {
+                let attrs = SchemaAttrs::from_attributes(&f.attrs);
+                if attrs.skip || SerdeAttrs::from_attributes(&f.attrs).skip {
+                    continue;
+                }
+                let Some(ident) = f.ident.as_ref() else {
+                    continue;
+                };
+                let field_name = SerdeAttrs::from_attributes(&f.attrs)
+                    .rename
+                    .unwrap_or_else(|| ident.to_string());
+                if unwrap_option_type(&f.ty).is_none() {
+                    required.push(field_name.clone());
+                }
+                properties.push((field_name, generate_type_schema(&f.ty, &attrs)));
+            }
+            VariantPayload::Fields {
+                properties,
+                required,
+            }
+        }
+        Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+            let ty = &unnamed.unnamed[0].ty;
+            VariantPayload::Value(generate_type_schema(ty, &SchemaAttrs::default()))
+        }
+        Fields::Unnamed(unnamed) => {
+            // Tuple variants serialize as fixed-length arrays
+            let field_schemas = unnamed
+                .unnamed
+                .iter()
+                .map(|f| generate_type_schema(&f.ty, &SchemaAttrs::default()));
+            VariantPayload::Value(quote! {
+                fastapi_openapi::Schema::tuple(vec![#(#field_schemas),*])
+            })
+        }
+    }
+}
+
+/// Generate the schema of one variant under the given representation.
+#[allow(clippy::too_many_lines)]
+fn variant_schema(variant: &Variant, serial_name: &str, repr: &EnumRepr) -> TokenStream2 {
+    let variant_attrs = SchemaAttrs::from_attributes(&variant.attrs);
+    let title = variant_attrs.title.as_ref().map_or_else(
+        || quote! { Some(#serial_name.to_string()) },
+        |t| quote! { Some(#t.to_string()) },
+    );
+    let description = variant_attrs
+        .description
+        .as_ref()
+        .map_or_else(|| quote! { None }, |d| quote! { Some(#d.to_string()) });
+    let tag_value = quote! {
+        fastapi_openapi::Schema::string_enum(vec![#serial_name.to_string()])
+    };
+    let payload = variant_payload(&variant.fields);
+
+    let payload_value = |payload: VariantPayload| match payload {
+        VariantPayload::Fields {
+            properties,
+            required,
+        } => object_schema_tokens(&quote! { None }, &quote! { None }, &properties, &required),
+        VariantPayload::Value(schema) => schema,
+        VariantPayload::Unit => quote! { fastapi_openapi::Schema::Boolean(true) },
+    };
+
+    match repr {
+        EnumRepr::External => match payload {
+            VariantPayload::Unit => tag_value,
+            payload => object_schema_tokens(
+                &title,
+                &description,
+                &[(serial_name.to_string(), payload_value(payload))],
+                &[serial_name.to_string()],
+            ),
+        },
+        EnumRepr::Internal { tag } => match payload {
+            VariantPayload::Unit => object_schema_tokens(
+                &title,
+                &description,
+                &[(tag.clone(), tag_value)],
+                std::slice::from_ref(tag),
+            ),
+            VariantPayload::Fields {
+                mut properties,
+                mut required,
+            } => {
+                properties.insert(0, (tag.clone(), tag_value));
+                required.insert(0, tag.clone());
+                object_schema_tokens(&title, &description, &properties, &required)
+            }
+            VariantPayload::Value(schema) => {
+                // Newtype variant: serde flattens the inner object and adds the tag
+                let tag_object = object_schema_tokens(
+                    &quote! { None },
+                    &quote! { None },
+                    &[(tag.clone(), tag_value.clone())],
+                    std::slice::from_ref(tag),
+                );
+                quote! {
+                    {
+                        // The inner object is extended in place, so it must
+                        // be inlined rather than referenced.
+                        #[allow(unused_mut, unused_variables)]
+                        let mut __registry: Option<&mut fastapi_openapi::SchemaRegistryMut<'_>> = None;
+                        match #schema {
+                            fastapi_openapi::Schema::Object(mut obj) => {
+                                obj.title = #title;
+                                if let Some(description) = #description {
+                                    obj.description = Some(description);
+                                }
+                                obj.properties.insert(#tag.to_string(), #tag_value);
+                                obj.required.insert(0, #tag.to_string());
+                                fastapi_openapi::Schema::Object(obj)
+                            }
+                            // e.g. a `$ref`, which can't gain properties in place
+                            other => fastapi_openapi::Schema::all_of(vec![other, #tag_object]),
+                        }
+                    }
+                }
+            }
+        },
+        EnumRepr::Adjacent { tag, content } => match payload {
+            VariantPayload::Unit => object_schema_tokens(
+                &title,
+                &description,
+                &[(tag.clone(), tag_value)],
+                std::slice::from_ref(tag),
+            ),
+            payload => object_schema_tokens(
+                &title,
+                &description,
+                &[
+                    (tag.clone(), tag_value),
+                    (content.clone(), payload_value(payload)),
+                ],
+                &[tag.clone(), content.clone()],
+            ),
+        },
+        EnumRepr::Untagged => match payload {
+            VariantPayload::Unit => quote! {
+                fastapi_openapi::Schema::Primitive(fastapi_openapi::PrimitiveSchema {
+                    schema_type: fastapi_openapi::SchemaType::Null,
+                    format: None,
+                    nullable: false,
+                    example: None,
+                    examples: Vec::new(),
+                    default: None,
+                    constraints: fastapi_openapi::SchemaConstraints::default(),
+                    deprecated: false,
+                })
+            },
+            VariantPayload::Fields {
+                properties,
+                required,
+            } => object_schema_tokens(&title, &description, &properties, &required),
+            VariantPayload::Value(schema) => schema,
+        },
+    }
+}
+
+/// Generate the `JsonSchema` impl for an enum, following its serde representation.
+///
+/// Unit-only, externally tagged enums become a string `enum`. Everything else
+/// becomes `oneOf`, with an OpenAPI `discriminator` when serde's `tag` is set.
+fn generate_enum_impl(input: &DeriveInput, data: &DataEnum) -> TokenStream2 {
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let generics = schema_generics(&input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let schema_name = schema_name_body(&name_str, &input.generics);
+    let container = SerdeAttrs::from_attributes(&input.attrs);
+    let repr = EnumRepr::from_serde(&container);
+
+    let variants: Vec<(&Variant, String)> = data
+        .variants
+        .iter()
+        .filter(|v| {
+            !SchemaAttrs::from_attributes(&v.attrs).skip
+                && !SerdeAttrs::from_attributes(&v.attrs).skip
+        })
+        .map(|v| {
+            let ident = v.ident.to_string();
+            let serial_name = SerdeAttrs::from_attributes(&v.attrs)
+                .rename
+                .or_else(|| {
+                    container
+                        .rename_all
+                        .as_deref()
+                        .map(|rule| rename_variant(rule, &ident))
+                })
+                .unwrap_or(ident);
+            (v, serial_name)
+        })
+        .collect();
+
+    let is_simple_enum = variants.iter().all(|(v, _)| v.fields.is_empty());
+    let schema_body = if is_simple_enum && matches!(repr, EnumRepr::External) {
+        let serial_names = variants.iter().map(|(_, n)| n);
+        quote! {
+            fastapi_openapi::Schema::string_enum(vec![#(#serial_names.to_string()),*])
+        }
+    } else {
+        let variant_schemas: Vec<TokenStream2> = variants
+            .iter()
+            .map(|(v, serial_name)| variant_schema(v, serial_name, &repr))
+            .collect();
+        if let Some(tag) = repr.tag() {
+            quote! {
+                fastapi_openapi::Schema::one_of_with_discriminator(vec![#(#variant_schemas),*], #tag)
+            }
+        } else {
+            quote! {
+                fastapi_openapi::Schema::one_of(vec![#(#variant_schemas),*])
+            }
+        }
+    };
+
+    quote! {
+        impl #impl_generics fastapi_openapi::JsonSchema for #name #ty_generics #where_clause {
+            fn schema() -> fastapi_openapi::Schema {
+                #[allow(unused_mut, unused_variables)]
+                let mut __registry: Option<&mut fastapi_openapi::SchemaRegistryMut<'_>> = None;
+                #schema_body
+            }
+
+            fn schema_with(registry: &mut fastapi_openapi::SchemaRegistryMut<'_>) -> fastapi_openapi::Schema {
+                #[allow(unused_mut, unused_variables)]
+                let mut __registry = Some(registry);
+                #schema_body
+            }
+
+            fn schema_name() -> Option<&'static str> {
+                #schema_name
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Generics
+// ============================================================================
+
+/// Clone `generics`, adding a `JsonSchema` bound to every type parameter.
+fn schema_generics(generics: &Generics) -> Generics {
+    let mut generics = generics.clone();
+    let type_params: Vec<Ident> = generics.type_params().map(|p| p.ident.clone()).collect();
+    let where_clause = generics.make_where_clause();
+    for ident in type_params {
+        where_clause
+            .predicates
+            .push(parse_quote!(#ident: fastapi_openapi::JsonSchema));
+    }
+    generics
+}
+
+/// Body of `schema_name()`.
+///
+/// Generic types get one component name per instantiation (`Page_Item`,
+/// `Page_User`) built from the labels of their type arguments.
+fn schema_name_body(name_str: &str, generics: &Generics) -> TokenStream2 {
+    let type_params: Vec<&Ident> = generics.type_params().map(|p| &p.ident).collect();
+    if type_params.is_empty() {
+        return quote! { Some(#name_str) };
+    }
+    quote! {
+        Some(fastapi_openapi::generic_schema_name(
+            #name_str,
+            &[#(&*fastapi_openapi::schema_label::<#type_params>()),*],
+        ))
     }
 }
 
@@ -275,11 +792,22 @@ pub fn derive_json_schema_impl(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
     let name_str = name.to_string();
+    let generics = schema_generics(&input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let schema_name = schema_name_body(&name_str, &input.generics);
 
     // Parse struct-level attributes
     let struct_attrs = SchemaAttrs::from_attributes(&input.attrs);
+    let struct_default = SerdeAttrs::from_attributes(&input.attrs).default;
     let title = struct_attrs.title.as_ref().map_or_else(
-        || quote! { Some(#name_str.to_string()) },
+        || {
+            if input.generics.type_params().next().is_some() {
+                // Title the instantiation, e.g. `Page_Item`
+                quote! { <Self as fastapi_openapi::JsonSchema>::schema_name().map(str::to_string) }
+            } else {
+                quote! { Some(#name_str.to_string()) }
+            }
+        },
         |t| quote! { Some(#t.to_string()) },
     );
     let description = struct_attrs
@@ -298,13 +826,18 @@ pub fn derive_json_schema_impl(input: TokenStream) -> TokenStream {
                     if attrs.skip {
                         return None;
                     }
-                    let field_name = f.ident.as_ref()?.to_string();
+                    let ident = f.ident.clone()?;
                     let is_optional = unwrap_option_type(&f.ty).is_some();
+                    let constraints =
+                        crate::validate::schema_constraint_calls(&f.attrs, attrs.format.is_some());
                     Some(FieldInfo {
-                        name: field_name,
+                        name: ident.to_string(),
+                        ident,
                         ty: f.ty.clone(),
                         attrs,
                         is_optional,
+                        default: SerdeAttrs::from_attributes(&f.attrs).default,
+                        constraints,
                     })
                 })
                 .collect::<Vec<_>>(),
@@ -318,185 +851,7 @@ pub fn derive_json_schema_impl(input: TokenStream) -> TokenStream {
             Fields::Unit => Vec::new(),
         },
         Data::Enum(data) => {
-            // Check if all variants are unit variants (simple string enum)
-            let is_simple_enum = data.variants.iter().all(|v| v.fields.is_empty());
-
-            if is_simple_enum {
-                // Simple string enum: use "enum" keyword in schema
-                let variant_names: Vec<String> =
-                    data.variants.iter().map(|v| v.ident.to_string()).collect();
-
-                let expanded = quote! {
-                    impl fastapi_openapi::JsonSchema for #name {
-                        fn schema() -> fastapi_openapi::Schema {
-                            fastapi_openapi::Schema::string_enum(vec![#(#variant_names.to_string()),*])
-                        }
-
-                        fn schema_name() -> Option<&'static str> {
-                            Some(#name_str)
-                        }
-                    }
-                };
-                return TokenStream::from(expanded);
-            }
-
-            // Complex enum with data: use oneOf with discriminator
-            let variant_schemas: Vec<TokenStream2> = data
-                .variants
-                .iter()
-                .map(|v| {
-                    let variant_name = v.ident.to_string();
-                    let variant_attrs = SchemaAttrs::from_attributes(&v.attrs);
-                    let variant_description = variant_attrs
-                        .description
-                        .as_ref()
-                        .map_or_else(|| quote! { None }, |d| quote! { Some(#d.to_string()) });
-
-                    match &v.fields {
-                        Fields::Unit => {
-                            // Unit variant: just a const value
-                            quote! {
-                                fastapi_openapi::Schema::Object(fastapi_openapi::ObjectSchema {
-                                    title: Some(#variant_name.to_string()),
-                                    description: #variant_description,
-                                    properties: {
-                                        let mut props = std::collections::HashMap::new();
-                                        props.insert("type".to_string(),
-                                            fastapi_openapi::Schema::Primitive(fastapi_openapi::PrimitiveSchema {
-                                                schema_type: fastapi_openapi::SchemaType::String,
-                                                format: None,
-                                                nullable: false,
-                                            }));
-                                        props
-                                    },
-                                    required: vec!["type".to_string()],
-                                    additional_properties: None,
-                                })
-                            }
-                        }
-                        Fields::Named(fields) => {
-                            // Struct variant: object with properties
-                            let field_insertions: Vec<TokenStream2> = fields.named.iter()
-                                .filter_map(|f| {
-                                    let field_name = f.ident.as_ref()?.to_string();
-                                    let field_attrs = SchemaAttrs::from_attributes(&f.attrs);
-                                    if field_attrs.skip {
-                                        return None;
-                                    }
-                                    let schema_code = generate_type_schema(&f.ty, &field_attrs);
-                                    Some(quote! {
-                                        props.insert(#field_name.to_string(), #schema_code);
-                                    })
-                                })
-                                .collect();
-
-                            let required: Vec<String> = fields.named.iter()
-                                .filter_map(|f| {
-                                    let field_name = f.ident.as_ref()?.to_string();
-                                    if unwrap_option_type(&f.ty).is_some() {
-                                        None
-                                    } else {
-                                        Some(field_name)
-                                    }
-                                })
-                                .collect();
-
-                            quote! {
-                                fastapi_openapi::Schema::Object(fastapi_openapi::ObjectSchema {
-                                    title: Some(#variant_name.to_string()),
-                                    description: #variant_description,
-                                    properties: {
-                                        let mut props = std::collections::HashMap::new();
-                                        props.insert("type".to_string(),
-                                            fastapi_openapi::Schema::Primitive(fastapi_openapi::PrimitiveSchema {
-                                                schema_type: fastapi_openapi::SchemaType::String,
-                                                format: None,
-                                                nullable: false,
-                                            }));
-                                        #(#field_insertions)*
-                                        props
-                                    },
-                                    required: {
-                                        let mut req = vec!["type".to_string()];
-                                        req.extend(vec![#(#required.to_string()),*]);
-                                        req
-                                    },
-                                    additional_properties: None,
-                                })
-                            }
-                        }
-                        Fields::Unnamed(fields) => {
-                            // Tuple variant: if single field, use that type; else use array
-                            if fields.unnamed.len() == 1 {
-                                let ty = &fields.unnamed.first().unwrap().ty;
-                                let inner_schema = generate_type_schema(ty, &SchemaAttrs::default());
-                                quote! {
-                                    fastapi_openapi::Schema::Object(fastapi_openapi::ObjectSchema {
-                                        title: Some(#variant_name.to_string()),
-                                        description: #variant_description,
-                                        properties: {
-                                            let mut props = std::collections::HashMap::new();
-                                            props.insert("type".to_string(),
-                                                fastapi_openapi::Schema::Primitive(fastapi_openapi::PrimitiveSchema {
-                                                    schema_type: fastapi_openapi::SchemaType::String,
-                                                    format: None,
-                                                    nullable: false,
-                                                }));
-                                            props.insert("data".to_string(), #inner_schema);
-                                            props
-                                        },
-                                        required: vec!["type".to_string(), "data".to_string()],
-                                        additional_properties: None,
-                                    })
-                                }
-                            } else {
-                                // Multiple fields - use prefixItems (JSON Schema tuple validation)
-                                let field_count = fields.unnamed.len();
-                                let field_schemas: Vec<TokenStream2> = fields.unnamed.iter()
-                                    .map(|f| generate_type_schema(&f.ty, &SchemaAttrs::default()))
-                                    .collect();
-                                quote! {
-                                    fastapi_openapi::Schema::Object(fastapi_openapi::ObjectSchema {
-                                        title: Some(#variant_name.to_string()),
-                                        description: #variant_description,
-                                        properties: {
-                                            let mut props = std::collections::HashMap::new();
-                                            props.insert("type".to_string(),
-                                                fastapi_openapi::Schema::Primitive(fastapi_openapi::PrimitiveSchema {
-                                                    schema_type: fastapi_openapi::SchemaType::String,
-                                                    format: None,
-                                                    nullable: false,
-                                                }));
-                                            props.insert("data".to_string(),
-                                                fastapi_openapi::Schema::Array(fastapi_openapi::ArraySchema {
-                                                    items: Box::new(fastapi_openapi::Schema::one_of(vec![#(#field_schemas),*])),
-                                                    min_items: Some(#field_count),
-                                                    max_items: Some(#field_count),
-                                                }));
-                                            props
-                                        },
-                                        required: vec!["type".to_string(), "data".to_string()],
-                                        additional_properties: None,
-                                    })
-                                }
-                            }
-                        }
-                    }
-                })
-                .collect();
-
-            let expanded = quote! {
-                impl fastapi_openapi::JsonSchema for #name {
-                    fn schema() -> fastapi_openapi::Schema {
-                        fastapi_openapi::Schema::one_of(vec![#(#variant_schemas),*])
-                    }
-
-                    fn schema_name() -> Option<&'static str> {
-                        Some(#name_str)
-                    }
-                }
-            };
-            return TokenStream::from(expanded);
+            return TokenStream::from(generate_enum_impl(&input, data));
         }
         Data::Union(_) => {
             return quote! {
@@ -511,42 +866,202 @@ pub fn derive_json_schema_impl(input: TokenStream) -> TokenStream {
         .iter()
         .map(|field| {
             let field_name = &field.name;
-            let schema_code = generate_type_schema(&field.ty, &field.attrs);
-            quote! {
-                properties.insert(#field_name.to_string(), #schema_code);
+            let constraints = &field.constraints;
+            let mut schema_code = generate_type_schema(&field.ty, &field.attrs);
+            schema_code = quote! { #schema_code #constraints };
+            if let Some(example) = &field.attrs.example {
+                schema_code = quote! { #schema_code.with_example(#example) };
+            }
+            if field.attrs.deprecated {
+                schema_code = quote! { #schema_code.deprecated() };
+            }
+            let ty = &field.ty;
+            let ident = &field.ident;
+            let default_value = match &field.default {
+                Some(SerdeDefault::Trait) => Some(quote! {
+                    <#ty as ::core::default::Default>::default()
+                }),
+                Some(SerdeDefault::Path(path)) => Some(quote! { #path() }),
+                None if struct_default.is_some() => Some(quote! { __struct_default.#ident }),
+                None => None,
+            };
+            if let Some(value) = default_value {
+                quote! {
+                    {
+                        let mut __schema = #schema_code;
+                        let __default: &#ty = &#value;
+                        if let Some(__value) =
+                            (&&fastapi_openapi::DefaultValueProbe(__default)).default_value()
+                        {
+                            __schema = __schema.with_default(__value);
+                        }
+                        properties.insert(#field_name.to_string(), __schema);
+                    }
+                }
+            } else {
+                quote! {
+                    properties.insert(#field_name.to_string(), #schema_code);
+                }
             }
         })
         .collect();
 
-    // Generate required field names (non-optional fields)
+    // Required fields: neither optional nor filled in by serde when missing
     let required_fields: Vec<&str> = fields
         .iter()
-        .filter(|f| !f.is_optional)
+        .filter(|f| !f.is_optional && f.default.is_none() && struct_default.is_none())
         .map(|f| f.name.as_str())
         .collect();
+    let struct_default_init = match &struct_default {
+        Some(SerdeDefault::Trait) => {
+            quote! { let __struct_default = <Self as ::core::default::Default>::default(); }
+        }
+        Some(SerdeDefault::Path(path)) => quote! { let __struct_default: Self = #path(); },
+        None => quote! {},
+    };
+
+    let struct_deprecated = struct_attrs.deprecated;
+    let example = struct_attrs.example.as_ref().map_or_else(
+        || quote! { None },
+        |e| quote! { Some(::core::convert::Into::into(#e)) },
+    );
+
+    let struct_body = quote! {
+        use fastapi_openapi::{DefaultValueProbeFallback as _, DefaultValueProbeMatch as _};
+
+        let mut properties = std::collections::HashMap::new();
+        #struct_default_init
+        #(#property_insertions)*
+
+        let required = vec![#(#required_fields.to_string()),*];
+
+        fastapi_openapi::Schema::Object(fastapi_openapi::ObjectSchema {
+            title: #title,
+            description: #description,
+            properties,
+            required,
+            additional_properties: None,
+            example: #example,
+            examples: Vec::new(),
+            default: None,
+            deprecated: #struct_deprecated,
+        })
+    };
 
     let expanded = quote! {
-        impl fastapi_openapi::JsonSchema for #name {
+        impl #impl_generics fastapi_openapi::JsonSchema for #name #ty_generics #where_clause {
+            #[allow(unused_imports, clippy::needless_borrow)]
             fn schema() -> fastapi_openapi::Schema {
-                let mut properties = std::collections::HashMap::new();
-                #(#property_insertions)*
-
-                let required = vec![#(#required_fields.to_string()),*];
+                #[allow(unused_mut, unused_variables)]
+                let mut __registry: Option<&mut fastapi_openapi::SchemaRegistryMut<'_>> = None;
+                #struct_body
+            }
 
-                fastapi_openapi::Schema::Object(fastapi_openapi::ObjectSchema {
-                    title: #title,
-                    description: #description,
-                    properties,
-                    required,
-                    additional_properties: None,
-                })
+            #[allow(unused_imports, clippy::needless_borrow)]
+            fn schema_with(registry: &mut fastapi_openapi::SchemaRegistryMut<'_>) -> fastapi_openapi::Schema {
+                #[allow(unused_mut, unused_variables)]
+                let mut __registry = Some(registry);
+                #struct_body
             }
 
             fn schema_name() -> Option<&'static str> {
-                Some(#name_str)
+                #schema_name
             }
         }
     };
 
     TokenStream::from(expanded)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serde_attrs(tokens: TokenStream2) -> SerdeAttrs {
+        let input: DeriveInput = syn::parse2(tokens).unwrap();
+        SerdeAttrs::from_attributes(&input.attrs)
+    }
+
+    #[test]
+    fn test_rename_variant_rules() {
+        assert_eq!(rename_variant("snake_case", "CreditCard"), "credit_card");
+        assert_eq!(rename_variant("kebab-case", "CreditCard"), "credit-card");
+        assert_eq!(rename_variant("camelCase", "CreditCard"), "creditCard");
+        assert_eq!(rename_variant("lowercase", "CreditCard"), "creditcard");
+        assert_eq!(
+            rename_variant("SCREAMING_SNAKE_CASE", "CreditCard"),
+            "CREDIT_CARD"
+        );
+        assert_eq!(rename_variant("PascalCase", "CreditCard"), "CreditCard");
+    }
+
+    #[test]
+    fn test_serde_attrs_internal_tag() {
+        let attrs = serde_attrs(quote! {
+            #[serde(tag = "kind", rename_all = "snake_case")]
+            enum Shape {}
+        });
+        assert_eq!(attrs.tag.as_deref(), Some("kind"));
+        assert_eq!(attrs.rename_all.as_deref(), Some("snake_case"));
+        assert!(matches!(
+            EnumRepr::from_serde(&attrs),
+            EnumRepr::Internal { .. }
+        ));
+    }
+
+    #[test]
+    fn test_serde_attrs_adjacent_and_untagged() {
+        let adjacent = serde_attrs(quote! {
+            #[serde(tag = "t", content = "c")]
+            enum Msg {}
+        });
+        assert_eq!(EnumRepr::from_serde(&adjacent).tag(), Some("t"));
+
+        let untagged = serde_attrs(quote! {
+            #[serde(untagged)]
+            enum Value {}
+        });
+        assert!(EnumRepr::from_serde(&untagged).tag().is_none());
+        assert!(matches!(
+            EnumRepr::from_serde(&untagged),
+            EnumRepr::Untagged
+        ));
+    }
+
+    #[test]
+    fn test_serde_attrs_default() {
+        let attrs = serde_attrs(quote! {
+            #[serde(default)]
+            struct Query {}
+        });
+        assert!(matches!(attrs.default, Some(SerdeDefault::Trait)));
+
+        let attrs = serde_attrs(quote! {
+            #[serde(default = "defaults::page_size")]
+            struct Query {}
+        });
+        let Some(SerdeDefault::Path(path)) = attrs.default else {
+            panic!("expected a default function path");
+        };
+        assert_eq!(quote!(#path).to_string(), "defaults :: page_size");
+
+        assert!(serde_attrs(quote! { struct Query {} }).default.is_none());
+    }
+
+    #[test]
+    fn test_serde_attrs_skip_unknown_values() {
+        let attrs = serde_attrs(quote! {
+            #[serde(deny_unknown_fields, bound(serialize = "T: Clone"), tag = "type")]
+            #[serde(rename(serialize = "Renamed", deserialize = "Other"))]
+            enum E {}
+        });
+        assert_eq!(attrs.tag.as_deref(), Some("type"));
+        assert_eq!(attrs.rename.as_deref(), Some("Renamed"));
+    }
+
+    #[test]
+    fn test_default_repr_is_external() {
+        let attrs = serde_attrs(quote! { enum E {} });
+        assert!(matches!(EnumRepr::from_serde(&attrs), EnumRepr::External));
+    }
+}
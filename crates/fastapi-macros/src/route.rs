@@ -28,10 +28,98 @@ struct ResponseDecl {
     description: Option<String>,
 }
 
+impl Parse for ResponseDecl {
+    /// Parse one `status = Type` or `status = (Type, "description")` entry
+    /// of `responses(...)`.
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let status_lit: syn::LitInt = input.parse()?;
+        let status: u16 = status_lit.base10_parse().map_err(|_| {
+            syn::Error::new(status_lit.span(), "expected HTTP status code (e.g., 404)")
+        })?;
+        input.parse::<Token![=]>()?;
+
+        let (type_path, description) = if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            let type_path: Type = content.parse()?;
+            content.parse::<Token![,]>()?;
+            let desc: LitStr = content.parse()?;
+            (type_path, Some(desc.value()))
+        } else {
+            (input.parse()?, None)
+        };
+
+        Ok(ResponseDecl {
+            status,
+            type_name: extract_type_name(&type_path),
+            type_path,
+            description,
+        })
+    }
+}
+
+/// A documented response header.
+struct ResponseHeaderDecl {
+    /// Status code of the response that carries the header.
+    status: u16,
+    /// Header name.
+    name: String,
+    /// Description of the header value (empty if none).
+    description: String,
+}
+
+impl Parse for ResponseHeaderDecl {
+    /// Parse one `status = "Name"` or `status = ("Name", "description")`
+    /// entry of `response_headers(...)`.
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let status_lit: syn::LitInt = input.parse()?;
+        let status: u16 = status_lit.base10_parse().map_err(|_| {
+            syn::Error::new(status_lit.span(), "expected HTTP status code (e.g., 200)")
+        })?;
+        input.parse::<Token![=]>()?;
+
+        let (name, description) = if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            let name: LitStr = content.parse()?;
+            content.parse::<Token![,]>()?;
+            let desc: LitStr = content.parse()?;
+            (name, desc.value())
+        } else {
+            (input.parse()?, String::new())
+        };
+
+        Ok(ResponseHeaderDecl {
+            status,
+            name: name.value(),
+            description,
+        })
+    }
+}
+
+/// Description used for a declared response that has none.
+fn default_response_description(status: u16) -> &'static str {
+    match status {
+        200..=299 => "Successful response",
+        400 => "Bad request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not found",
+        409 => "Conflict",
+        422 => "Validation error",
+        429 => "Too many requests",
+        _ if (400..500).contains(&status) => "Client error",
+        500..=599 => "Server error",
+        _ => "Response",
+    }
+}
+
 /// Parsed route attributes from `#[get("/path", summary = "...", ...)]`.
 struct RouteAttrs {
-    /// The route path (required).
+    /// The route path (required). Empty when `typed_path` is given.
     path: LitStr,
+    /// A `TypedPath` type given in place of the path string.
+    typed_path: Option<syn::Path>,
     /// OpenAPI summary (short description).
     summary: Option<String>,
     /// OpenAPI description (detailed explanation).
@@ -42,23 +130,38 @@ struct RouteAttrs {
     tags: Vec<String>,
     /// Whether the route is deprecated.
     deprecated: bool,
+    /// Whether the operation is safe to retry.
+    idempotent: bool,
     /// Declared response types for compile-time checking and OpenAPI.
     responses: Vec<ResponseDecl>,
+    /// Documented response headers.
+    response_headers: Vec<ResponseHeaderDecl>,
+    /// HTTP methods served by a `#[route]` handler.
+    methods: Vec<LitStr>,
 }
 
 impl Parse for RouteAttrs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        // First argument must be the path string
-        let path: LitStr = input.parse()?;
+        // First argument must be the path string or a `TypedPath` type
+        let (path, typed_path) = if input.peek(LitStr) {
+            (input.parse()?, None)
+        } else {
+            let typed: syn::Path = input.parse()?;
+            (LitStr::new("", Span::call_site()), Some(typed))
+        };
 
         let mut attrs = RouteAttrs {
             path,
+            typed_path,
             summary: None,
             description: None,
             operation_id: None,
             tags: Vec::new(),
             deprecated: false,
+            idempotent: false,
             responses: Vec::new(),
+            response_headers: Vec::new(),
+            methods: Vec::new(),
         };
 
         // Parse optional comma-separated key=value pairs
@@ -71,86 +174,118 @@ impl Parse for RouteAttrs {
             }
 
             let ident: syn::Ident = input.parse()?;
-            let ident_str = ident.to_string();
+            attrs.parse_attribute(&ident, input)?;
+        }
 
-            match ident_str.as_str() {
-                "deprecated" => {
-                    // `deprecated` is a flag, no value needed
-                    attrs.deprecated = true;
-                }
-                "summary" | "description" | "operation_id" => {
-                    input.parse::<Token![=]>()?;
-                    let value: LitStr = input.parse()?;
-                    match ident_str.as_str() {
-                        "summary" => attrs.summary = Some(value.value()),
-                        "description" => attrs.description = Some(value.value()),
-                        "operation_id" => attrs.operation_id = Some(value.value()),
-                        _ => unreachable!(),
-                    }
-                }
-                "tags" => {
-                    input.parse::<Token![=]>()?;
-                    // Parse as either a single string or an array of strings
-                    if input.peek(syn::token::Bracket) {
-                        let content;
-                        syn::bracketed!(content in input);
-                        let tags: Punctuated<LitStr, Token![,]> =
-                            Punctuated::parse_terminated(&content)?;
-                        attrs.tags = tags.into_iter().map(|s| s.value()).collect();
-                    } else {
-                        let tag: LitStr = input.parse()?;
-                        attrs.tags.push(tag.value());
-                    }
-                }
-                "response" => {
-                    // Parse response(status, Type) or response(status, Type, "description")
-                    let content;
-                    syn::parenthesized!(content in input);
-
-                    // Parse status code
-                    let status_lit: syn::LitInt = content.parse()?;
-                    let status: u16 = status_lit.base10_parse().map_err(|_| {
-                        syn::Error::new(status_lit.span(), "expected HTTP status code (e.g., 200)")
-                    })?;
-
-                    content.parse::<Token![,]>()?;
-
-                    // Parse the response type
-                    let type_path: Type = content.parse()?;
-                    let type_name = extract_type_name(&type_path);
-
-                    // Parse optional description
-                    let description = if content.peek(Token![,]) {
-                        content.parse::<Token![,]>()?;
-                        let desc: LitStr = content.parse()?;
-                        Some(desc.value())
-                    } else {
-                        None
-                    };
-
-                    attrs.responses.push(ResponseDecl {
-                        status,
-                        type_name,
-                        type_path,
-                        description,
-                    });
-                }
-                _ => {
-                    return Err(syn::Error::new(
-                        ident.span(),
-                        format!(
-                            "unknown route attribute `{ident_str}`.\n\
-                             Valid attributes: summary, description, operation_id, tags, deprecated, response"
-                        ),
-                    ));
-                }
+        Ok(attrs)
+    }
+}
+
+impl RouteAttrs {
+    /// Parse the value (if any) of the attribute named `ident`.
+    fn parse_attribute(&mut self, ident: &syn::Ident, input: ParseStream) -> syn::Result<()> {
+        let ident_str = ident.to_string();
+        match ident_str.as_str() {
+            // Flags, no value needed
+            "deprecated" => self.deprecated = true,
+            "idempotent" => self.idempotent = true,
+            "summary" => self.summary = Some(parse_string_value(input)?),
+            "description" => self.description = Some(parse_string_value(input)?),
+            "operation_id" => self.operation_id = Some(parse_string_value(input)?),
+            "tags" => self.parse_tags(input)?,
+            "response" => self.responses.push(parse_response(input)?),
+            // responses(404 = NotFound, 409 = (Conflict, "Already exists"))
+            "responses" => self.responses.extend(parse_list::<ResponseDecl>(input)?),
+            // response_headers(200 = "X-Total-Count", 429 = ("Retry-After", "Seconds"))
+            "response_headers" => self
+                .response_headers
+                .extend(parse_list::<ResponseHeaderDecl>(input)?),
+            // methods("GET", "POST")
+            "methods" => self.methods.extend(parse_list::<LitStr>(input)?),
+            _ => {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "unknown route attribute `{ident_str}`.\n\
+                         Valid attributes: summary, description, operation_id, tags, deprecated, idempotent, response, responses, response_headers, methods"
+                    ),
+                ));
             }
         }
+        Ok(())
+    }
 
-        Ok(attrs)
+    /// Parse `tags("a", "b")`, `tags = ["a", "b"]` or `tags = "a"`.
+    fn parse_tags(&mut self, input: ParseStream) -> syn::Result<()> {
+        if input.peek(syn::token::Paren) {
+            let tags = parse_list::<LitStr>(input)?;
+            self.tags.extend(tags.into_iter().map(|s| s.value()));
+            return Ok(());
+        }
+
+        input.parse::<Token![=]>()?;
+        // Parse as either a single string or an array of strings
+        if input.peek(syn::token::Bracket) {
+            let content;
+            syn::bracketed!(content in input);
+            let tags: Punctuated<LitStr, Token![,]> = Punctuated::parse_terminated(&content)?;
+            self.tags = tags.into_iter().map(|s| s.value()).collect();
+        } else {
+            let tag: LitStr = input.parse()?;
+            self.tags.push(tag.value());
+        }
+        Ok(())
     }
 }
 
+/// Parse the `= "value"` of a string-valued route attribute.
+fn parse_string_value(input: ParseStream) -> syn::Result<String> {
+    input.parse::<Token![=]>()?;
+    let value: LitStr = input.parse()?;
+    Ok(value.value())
+}
+
+/// Parse a parenthesized, comma-separated list such as `("GET", "POST")`.
+fn parse_list<T: Parse>(input: ParseStream) -> syn::Result<Punctuated<T, Token![,]>> {
+    let content;
+    syn::parenthesized!(content in input);
+    Punctuated::parse_terminated(&content)
+}
+
+/// Parse `response(status, Type)` or `response(status, Type, "description")`.
+fn parse_response(input: ParseStream) -> syn::Result<ResponseDecl> {
+    let content;
+    syn::parenthesized!(content in input);
+
+    // Parse status code
+    let status_lit: syn::LitInt = content.parse()?;
+    let status: u16 = status_lit
+        .base10_parse()
+        .map_err(|_| syn::Error::new(status_lit.span(), "expected HTTP status code (e.g., 200)"))?;
+
+    content.parse::<Token![,]>()?;
+
+    // Parse the response type
+    let type_path: Type = content.parse()?;
+    let type_name = extract_type_name(&type_path);
+
+    // Parse optional description
+    let description = if content.peek(Token![,]) {
+        content.parse::<Token![,]>()?;
+        let desc: LitStr = content.parse()?;
+        Some(desc.value())
+    } else {
+        None
+    };
+
+    Ok(ResponseDecl {
+        status,
+        type_name,
+        type_path,
+        description,
+    })
+}
+
 /// Extract path parameter names from a route pattern.
 ///
 /// Examples:
@@ -274,6 +409,29 @@ fn get_extractable_types(
         .collect()
 }
 
+/// Return `T` if `ty` is a `State<T>` extractor.
+fn extract_state_type(ty: &Type) -> Option<&Type> {
+    extract_wrapped_type(ty, "State")
+}
+
+/// Return `T` if `ty` is `<wrapper><T>`, e.g. `Query<T>`.
+fn extract_wrapped_type<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
 /// Body extractor information for OpenAPI request body generation.
 struct BodyExtractorInfo {
     /// The inner type name (e.g., "CreateUser" from Json<CreateUser>).
@@ -282,6 +440,8 @@ struct BodyExtractorInfo {
     content_type: &'static str,
     /// Whether the body is required (not Option<Json<T>>).
     required: bool,
+    /// The inner type itself.
+    ty: Type,
 }
 
 /// Check if a type is a body extractor (Json<T>) and extract its info.
@@ -309,11 +469,11 @@ fn extract_body_info(ty: &Type) -> Option<BodyExtractorInfo> {
     extract_json_info(ty)
 }
 
-/// Extract type info from a Json<T> type.
+/// Extract type info from a `Json<T>` or `StrictJson<T>` type.
 fn extract_json_info(ty: &Type) -> Option<BodyExtractorInfo> {
     if let Type::Path(type_path) = ty {
         if let Some(segment) = type_path.path.segments.last() {
-            if segment.ident == "Json" {
+            if segment.ident == "Json" || segment.ident == "StrictJson" {
                 if let PathArguments::AngleBracketed(args) = &segment.arguments {
                     if let Some(GenericArgument::Type(inner_ty)) = args.args.first() {
                         let type_name = extract_type_name(inner_ty);
@@ -321,6 +481,7 @@ fn extract_json_info(ty: &Type) -> Option<BodyExtractorInfo> {
                             type_name,
                             content_type: "application/json",
                             required: true,
+                            ty: inner_ty.clone(),
                         });
                     }
                 }
@@ -346,6 +507,99 @@ fn extract_type_name(ty: &Type) -> String {
     }
 }
 
+/// Render a type as the name recorded in route metadata.
+///
+/// Unlike [`extract_type_name`], generic arguments are kept (`Vec<Item>`,
+/// `Page<User>`) so the OpenAPI builder can expand containers and reference
+/// the matching generic component.
+fn schema_type_name(ty: &Type) -> String {
+    match ty {
+        Type::Path(type_path) => {
+            let Some(segment) = type_path.path.segments.last() else {
+                return quote::quote!(#ty).to_string();
+            };
+            let mut name = segment.ident.to_string();
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                let args: Vec<String> = args
+                    .args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        GenericArgument::Type(ty) => Some(schema_type_name(ty)),
+                        _ => None,
+                    })
+                    .collect();
+                if !args.is_empty() {
+                    name.push('<');
+                    name.push_str(&args.join(", "));
+                    name.push('>');
+                }
+            }
+            name
+        }
+        Type::Reference(reference) => schema_type_name(&reference.elem),
+        _ => quote::quote!(#ty).to_string(),
+    }
+}
+
+/// Infer the success response body from the handler's return type.
+///
+/// Recognizes `Json<T>` and `Result<Json<T>, E>` and returns the schema name
+/// for `T`. Other return types (including `impl IntoResponse`) carry no
+/// schema information.
+fn infer_response_schema(output: &ReturnType) -> Option<String> {
+    infer_response_type(output).map(schema_type_name)
+}
+
+/// The `T` of a `Json<T>` or `Result<Json<T>, E>` return type.
+fn infer_response_type(output: &ReturnType) -> Option<&Type> {
+    let ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+    json_payload_type(ty)
+}
+
+fn json_payload_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let Some(GenericArgument::Type(inner_ty)) = args.args.first() else {
+        return None;
+    };
+
+    if segment.ident == "Json" {
+        Some(inner_ty)
+    } else if segment.ident == "Result" {
+        json_payload_type(inner_ty)
+    } else {
+        None
+    }
+}
+
+/// The `E` of a `Result<T, E>` return type.
+fn result_error_type(output: &ReturnType) -> Option<&Type> {
+    let ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+    let Type::Path(type_path) = &**ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.iter().nth(1) {
+        Some(GenericArgument::Type(error_ty)) => Some(error_ty),
+        _ => None,
+    }
+}
+
 /// Find the first body extractor in function arguments and return its info.
 fn find_body_extractor(
     inputs: &syn::punctuated::Punctuated<FnArg, syn::token::Comma>,
@@ -381,11 +635,73 @@ fn get_return_type(output: &ReturnType) -> Option<proc_macro2::TokenStream> {
     }
 }
 
-#[allow(clippy::too_many_lines)]
+/// The `fastapi_core::Method` variant for an HTTP method name.
+fn method_variant(name: &str) -> Option<&'static str> {
+    match name.to_ascii_uppercase().as_str() {
+        "GET" => Some("Get"),
+        "POST" => Some("Post"),
+        "PUT" => Some("Put"),
+        "DELETE" => Some("Delete"),
+        "PATCH" => Some("Patch"),
+        "HEAD" => Some("Head"),
+        "OPTIONS" => Some("Options"),
+        "TRACE" => Some("Trace"),
+        "CONNECT" => Some("Connect"),
+        _ => None,
+    }
+}
+
 pub fn route_impl(method: &str, attr: TokenStream, item: TokenStream) -> TokenStream {
     let attrs = parse_macro_input!(attr as RouteAttrs);
     let input_fn = parse_macro_input!(item as ItemFn);
 
+    if let Some(first) = attrs.methods.first() {
+        return syn::Error::new(
+            first.span(),
+            "`methods(...)` is only accepted by `#[route]`",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let method_ident = syn::Ident::new(method, Span::call_site());
+    expand_route(&[method_ident], &attrs, &input_fn)
+}
+
+/// `#[route("/path", methods("GET", "POST"))]`: one handler for several methods.
+pub fn route_methods_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attrs = parse_macro_input!(attr as RouteAttrs);
+    let input_fn = parse_macro_input!(item as ItemFn);
+
+    if attrs.methods.is_empty() {
+        return syn::Error::new(
+            attrs.path.span(),
+            "`#[route]` needs the methods it serves, e.g. `methods(\"GET\", \"POST\")`",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let mut methods: Vec<syn::Ident> = Vec::new();
+    for lit in &attrs.methods {
+        let Some(variant) = method_variant(&lit.value()) else {
+            let msg = format!(
+                "unknown HTTP method `{}`.\n\
+                 Valid methods: GET, POST, PUT, DELETE, PATCH, HEAD, OPTIONS, TRACE, CONNECT",
+                lit.value()
+            );
+            return syn::Error::new(lit.span(), msg).to_compile_error().into();
+        };
+        if methods.iter().any(|m| m == variant) {
+            return syn::Error::new(lit.span(), "duplicate method")
+                .to_compile_error()
+                .into();
+        }
+        methods.push(syn::Ident::new(variant, lit.span()));
+    }
+    expand_route(&methods, &attrs, &input_fn)
+}
+
+#[allow(clippy::too_many_lines)]
+fn expand_route(methods: &[syn::Ident], attrs: &RouteAttrs, input_fn: &ItemFn) -> TokenStream {
     let fn_name = &input_fn.sig.ident;
     let fn_vis = &input_fn.vis;
     let fn_block = &input_fn.block;
@@ -397,8 +713,8 @@ pub fn route_impl(method: &str, attr: TokenStream, item: TokenStream) -> TokenSt
     let route_fn_name = syn::Ident::new(&format!("__route_{fn_name}"), fn_name.span());
     let route_entry_fn_name = syn::Ident::new(&format!("{fn_name}_route"), fn_name.span());
     let reg_name = syn::Ident::new(&format!("__FASTAPI_ROUTE_REG_{fn_name}"), fn_name.span());
+    let security_fn_name = syn::Ident::new(&format!("__security_{fn_name}"), fn_name.span());
 
-    let method_ident = syn::Ident::new(method, proc_macro2::Span::call_site());
     let path = &attrs.path;
     let path_str = path.value();
 
@@ -437,10 +753,47 @@ pub fn route_impl(method: &str, attr: TokenStream, item: TokenStream) -> TokenSt
     // COMPILE-TIME PATH PARAMETER VALIDATION
     // =========================================================================
 
-    let path_params = extract_path_params(&path_str);
-    let path_param_count = path_params.len();
     let path_extractor_count = count_path_extractors(fn_inputs);
 
+    // Validation 0c: A typed path route must take its `TypedPath` type, which
+    // replaces `Path<_>` extractors. Its derive already matched the template's
+    // parameters against the struct's fields.
+    if let Some(typed) = &attrs.typed_path {
+        let typed_name = typed.segments.last().map(|segment| &segment.ident);
+        let takes_typed_path = fn_inputs.iter().any(|arg| {
+            let Some(Type::Path(type_path)) = extract_param_type(arg) else {
+                return false;
+            };
+            type_path.path.segments.last().map(|segment| &segment.ident) == typed_name
+        });
+        let type_str = quote!(#typed).to_string().replace(' ', "");
+        if !takes_typed_path {
+            return syn::Error::new_spanned(
+                typed,
+                format!("handler '{fn_name}' is routed by `{type_str}` but has no `{type_str}` parameter"),
+            )
+            .to_compile_error()
+            .into();
+        }
+        if path_extractor_count > 0 {
+            return syn::Error::new_spanned(
+                typed,
+                format!(
+                    "handler '{fn_name}' is routed by `{type_str}`; read path parameters from it instead of Path<_>"
+                ),
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
+    let path_params = if attrs.typed_path.is_some() {
+        Vec::new()
+    } else {
+        extract_path_params(&path_str)
+    };
+    let path_param_count = path_params.len();
+
     // Validation 1: Route has parameters but no Path extractor
     if path_param_count > 0 && path_extractor_count == 0 {
         let param_list = path_params.join(", ");
@@ -449,7 +802,8 @@ pub fn route_impl(method: &str, attr: TokenStream, item: TokenStream) -> TokenSt
              Add a Path extractor, e.g.:\n\
              - Path<i64> for single parameter\n\
              - Path<({})> for multiple parameters\n\
-             - Path<MyParams> for named struct",
+             - Path<MyParams> for named struct\n\
+             - a #[derive(TypedPath)] struct passed as the route path",
             path_str,
             path_param_count,
             param_list,
@@ -505,6 +859,75 @@ pub fn route_impl(method: &str, attr: TokenStream, item: TokenStream) -> TokenSt
     // Collect types that need FromRequest validation
     let extractable_types = get_extractable_types(fn_inputs);
 
+    // Resolve security schemes from extractor parameters. The double reference selects
+    // `SecurityProbeMatch` for `SecurityExtractor` types and the fallback otherwise.
+    let security_probes: Vec<proc_macro2::TokenStream> = extractable_types
+        .iter()
+        .map(|ty| {
+            quote! {
+                __defs.extend(
+                    (&&fastapi_core::SecurityProbe::<#ty>::default()).security_definition()
+                );
+            }
+        })
+        .collect();
+
+    // Declare shared state the handler extracts so `App::check` can verify it.
+    let state_requirements: Vec<proc_macro2::TokenStream> = extractable_types
+        .iter()
+        .filter_map(|ty| extract_state_type(ty))
+        .map(|ty| quote! { __entry = __entry.requires_state::<#ty>(); })
+        .collect();
+
+    // Record `Depends<T>` parameters so `App::build` can validate their trees. The double
+    // reference selects `DependencyProbeMatch` for `Depends`/`State` and the fallback otherwise.
+    let dependency_requirements: Vec<proc_macro2::TokenStream> = extractable_types
+        .iter()
+        .map(|ty| {
+            quote! {
+                __entry = __entry.requires(
+                    (&&fastapi_core::DependencyProbe::<#ty>::default()).requirements()
+                );
+            }
+        })
+        .collect();
+
+    // Document the fields of `Query<T>` / `Path<T>` structs as parameters. The double
+    // reference selects `ParamsProbeMatch` for `JsonSchema` types and the fallback otherwise.
+    let param_docs: Vec<proc_macro2::TokenStream> = extractable_types
+        .iter()
+        .filter_map(|ty| {
+            if let Some(inner) = extract_wrapped_type(ty, "Query") {
+                Some(quote! {
+                    __route = __route.query_params(
+                        (&&fastapi_openapi::ParamsProbe::<#inner>::default()).params()
+                    );
+                })
+            } else {
+                extract_wrapped_type(ty, "Path").map(|inner| {
+                    quote! {
+                        __route = __route.path_param_docs(
+                            (&&fastapi_openapi::ParamsProbe::<#inner>::default()).params()
+                        );
+                    }
+                })
+            }
+        })
+        .collect();
+
+    // Document query parameters read by extractors such as `Pagination`. The double
+    // reference selects `QueryParamsProbeMatch` for `QueryParamDocs` types and the fallback otherwise.
+    let query_param_probes: Vec<proc_macro2::TokenStream> = extractable_types
+        .iter()
+        .map(|ty| {
+            quote! {
+                __route = __route.query_params(
+                    (&&fastapi_core::QueryParamsProbe::<#ty>::default()).query_param_docs()
+                );
+            }
+        })
+        .collect();
+
     // Generate compile-time assertions for FromRequest
     // These assertions will fail to compile if a type doesn't implement FromRequest
     let from_request_checks: Vec<proc_macro2::TokenStream> = extractable_types
@@ -565,8 +988,15 @@ pub fn route_impl(method: &str, attr: TokenStream, item: TokenStream) -> TokenSt
         quote! { .description(#d) }
     });
 
+    // A multi-method handler documents one operation per method, so a custom
+    // operation id gets the method as a suffix to keep ids unique.
+    let multi_method = methods.len() > 1;
     let operation_id_call = attrs.operation_id.as_ref().map(|id| {
-        quote! { .operation_id(#id) }
+        if multi_method {
+            quote! { .operation_id(format!("{}_{}", #id, __method.as_str().to_ascii_lowercase())) }
+        } else {
+            quote! { .operation_id(#id) }
+        }
     });
 
     let tags = &attrs.tags;
@@ -582,14 +1012,106 @@ pub fn route_impl(method: &str, attr: TokenStream, item: TokenStream) -> TokenSt
         None
     };
 
+    let idempotent_call = if attrs.idempotent {
+        Some(quote! { .idempotent() })
+    } else {
+        None
+    };
+
     // Generate request body builder call if a body extractor is present
-    let request_body_call = find_body_extractor(fn_inputs).map(|info| {
+    let body_info = find_body_extractor(fn_inputs);
+    let request_body_call = body_info.as_ref().map(|info| {
         let schema = &info.type_name;
         let content_type = info.content_type;
         let required = info.required;
         quote! { .request_body(#schema, #content_type, #required) }
     });
 
+    // Register body types as OpenAPI components, so nested types get `$ref`s.
+    let mut body_schema_stmts: Vec<proc_macro2::TokenStream> = Vec::new();
+    if let Some(info) = &body_info {
+        let ty = &info.ty;
+        body_schema_stmts.push(quote! {
+            __schemas.request_body =
+                (&&fastapi_openapi::SchemaProbe::<#ty>::default()).schema_ref(__registry);
+        });
+    }
+    let inferred_response = if attrs.responses.iter().any(|r| r.status == 200) {
+        None
+    } else {
+        infer_response_type(fn_output).map(|ty| (200u16, ty))
+    };
+    for (status, ty) in attrs
+        .responses
+        .iter()
+        .map(|r| (r.status, &r.type_path))
+        .chain(inferred_response)
+    {
+        body_schema_stmts.push(quote! {
+            if let Some(__schema) =
+                (&&fastapi_openapi::SchemaProbe::<#ty>::default()).schema_ref(__registry)
+            {
+                __schemas.responses.push((#status, __schema));
+            }
+        });
+    }
+    // Document the statuses of a `#[derive(ApiError)]` error type that
+    // `responses(...)` doesn't already cover: the handler's own error type,
+    // then the rejection of each extractor, so a `Depends<T>` whose
+    // dependency fails with such a type lists its responses too.
+    let error_types: Vec<proc_macro2::TokenStream> = result_error_type(fn_output)
+        .map(|ty| quote! { #ty })
+        .into_iter()
+        .chain(
+            extractable_types
+                .iter()
+                .map(|ty| quote! { <#ty as fastapi_core::FromRequest>::Error }),
+        )
+        .collect();
+    let error_response_docs: Vec<proc_macro2::TokenStream> = error_types
+        .iter()
+        .map(|ty| {
+            quote! {
+                for (__status, __description) in
+                    (&&fastapi_openapi::ErrorResponsesProbe::<#ty>::default()).error_responses()
+                {
+                    if !__route.responses.iter().any(|r| r.status == __status) {
+                        __route = __route.response(__status, "HTTPError", __description);
+                    }
+                }
+            }
+        })
+        .collect();
+    for ty in &error_types {
+        body_schema_stmts.push(quote! {
+            for (__status, _) in
+                (&&fastapi_openapi::ErrorResponsesProbe::<#ty>::default()).error_responses()
+            {
+                if !__schemas.responses.iter().any(|(status, _)| *status == __status) {
+                    __schemas
+                        .responses
+                        .push((__status, fastapi_openapi::http_error_schema(__registry)));
+                }
+            }
+        });
+    }
+    let openapi_schemas_call = if body_schema_stmts.is_empty() {
+        None
+    } else {
+        Some(quote! {
+            __entry = __entry.openapi_schemas(|__registry| {
+                use fastapi_openapi::{
+                    ErrorResponsesProbeFallback as _, ErrorResponsesProbeMatch as _,
+                    SchemaProbeFallback as _, SchemaProbeMatch as _,
+                };
+
+                let mut __schemas = fastapi_openapi::RouteSchemas::default();
+                #(#body_schema_stmts)*
+                __schemas
+            });
+        })
+    };
+
     // Generate compile-time assertions for declared response types
     // Each declared response type must implement JsonSchema
     let response_schema_checks: Vec<proc_macro2::TokenStream> = attrs
@@ -660,17 +1182,34 @@ pub fn route_impl(method: &str, attr: TokenStream, item: TokenStream) -> TokenSt
         };
 
     // Generate response metadata builder calls
-    let response_calls: Vec<proc_macro2::TokenStream> = attrs
+    let mut response_calls: Vec<proc_macro2::TokenStream> = attrs
         .responses
         .iter()
         .map(|resp| {
             let status = resp.status;
             let type_name = &resp.type_name;
-            let description = resp.description.as_deref().unwrap_or("Successful response");
+            let description = resp
+                .description
+                .as_deref()
+                .unwrap_or_else(|| default_response_description(status));
             quote! { .response(#status, #type_name, #description) }
         })
         .collect();
 
+    // Document the return type as the 200 response unless one was declared.
+    if !attrs.responses.iter().any(|r| r.status == 200) {
+        if let Some(type_name) = infer_response_schema(fn_output) {
+            response_calls.push(quote! { .response(200u16, #type_name, "Successful response") });
+        }
+    }
+
+    response_calls.extend(attrs.response_headers.iter().map(|h| {
+        let status = h.status;
+        let name = &h.name;
+        let description = &h.description;
+        quote! { .response_header(#status, #name, #description) }
+    }));
+
     // Generate extraction + invocation wrapper for runtime routing.
     let mut arg_extracts: Vec<proc_macro2::TokenStream> = Vec::new();
     let mut call_args: Vec<proc_macro2::TokenStream> = Vec::new();
@@ -722,6 +1261,84 @@ pub fn route_impl(method: &str, attr: TokenStream, item: TokenStream) -> TokenSt
         quote! { #fn_name(#(#call_args),*) }
     };
 
+    // A single-method handler gets `__route_x()` and `x_route()`. A multi-method
+    // one gets `__route_x(method)`, a `__route_x_<method>()` per method for
+    // registration, and `x_routes()` returning one entry per method.
+    let (route_fn_params, route_method, entry_fn_head, entry_route_binding) = if multi_method {
+        let entry_with_name = syn::Ident::new(&format!("__entry_{fn_name}"), fn_name.span());
+        (
+            quote! { __method: fastapi_core::Method },
+            quote! { __method },
+            quote! {
+                #[doc(hidden)]
+                #[allow(non_snake_case, unused_imports, clippy::needless_borrow)]
+                fn #entry_with_name(__route: fastapi_router::Route) -> fastapi_core::RouteEntry
+            },
+            None,
+        )
+    } else {
+        let method_ident = &methods[0];
+        (
+            quote! {},
+            quote! { fastapi_core::Method::#method_ident },
+            quote! {
+                /// Build a runtime [`fastapi_core::RouteEntry`] for this handler.
+                ///
+                /// This wraps the handler with extractor evaluation (`FromRequest`) and converts
+                /// both extractor errors and handler return values into a [`fastapi_core::Response`].
+                #[allow(non_snake_case, unused_imports, clippy::needless_borrow)]
+                pub fn #route_entry_fn_name() -> fastapi_core::RouteEntry
+            },
+            Some(quote! { let __route = #route_fn_name(); }),
+        )
+    };
+
+    let (reg_names, registered_route_fns, multi_method_items) = if multi_method {
+        let entry_with_name = syn::Ident::new(&format!("__entry_{fn_name}"), fn_name.span());
+        let entries_fn_name = syn::Ident::new(&format!("{fn_name}_routes"), fn_name.span());
+        let suffixes: Vec<String> = methods
+            .iter()
+            .map(|m| m.to_string().to_ascii_lowercase())
+            .collect();
+        let per_method_fns: Vec<syn::Ident> = suffixes
+            .iter()
+            .map(|m| syn::Ident::new(&format!("__route_{fn_name}_{m}"), fn_name.span()))
+            .collect();
+        let reg_names: Vec<syn::Ident> = suffixes
+            .iter()
+            .map(|m| {
+                syn::Ident::new(
+                    &format!("__FASTAPI_ROUTE_REG_{fn_name}_{m}"),
+                    fn_name.span(),
+                )
+            })
+            .collect();
+        let items = quote! {
+            #(
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub fn #per_method_fns() -> fastapi_router::Route {
+                    #route_fn_name(fastapi_core::Method::#methods)
+                }
+            )*
+
+            /// Build one runtime [`fastapi_core::RouteEntry`] per method served by this handler.
+            #[allow(non_snake_case)]
+            pub fn #entries_fn_name() -> Vec<fastapi_core::RouteEntry> {
+                vec![#(#entry_with_name(#per_method_fns())),*]
+            }
+        };
+        (reg_names, per_method_fns, Some(items))
+    } else {
+        (vec![reg_name], vec![route_fn_name.clone()], None)
+    };
+
+    let route_path = if let Some(typed) = &attrs.typed_path {
+        quote! { <#typed as fastapi_core::TypedPath>::PATH }
+    } else {
+        quote! { #path_str }
+    };
+
     // Generate the expanded code
     let expanded = quote! {
         // Original function preserved for direct calling
@@ -740,53 +1357,98 @@ pub fn route_impl(method: &str, attr: TokenStream, item: TokenStream) -> TokenSt
         // Compile-time assertion: return type matches declared 200 response
         #(#response_type_checks)*
 
+        /// Security schemes required by this handler's extractors.
         #[doc(hidden)]
-        #[allow(non_snake_case)]
-        pub fn #route_fn_name() -> fastapi_router::Route {
-            fastapi_router::Route::new(
-                fastapi_core::Method::#method_ident,
-                #path_str,
+        #[allow(non_snake_case, unused_imports, unused_mut, clippy::needless_borrow)]
+        pub fn #security_fn_name() -> Vec<fastapi_core::SecurityDefinition> {
+            use fastapi_core::{SecurityProbeFallback as _, SecurityProbeMatch as _};
+
+            let mut __defs = Vec::new();
+            #(#security_probes)*
+            __defs
+        }
+
+        #[doc(hidden)]
+        #[allow(non_snake_case, unused_imports, clippy::needless_borrow)]
+        pub fn #route_fn_name(#route_fn_params) -> fastapi_router::Route {
+            use fastapi_openapi::{
+                ErrorResponsesProbeFallback as _, ErrorResponsesProbeMatch as _,
+                ParamsProbeFallback as _, ParamsProbeMatch as _,
+            };
+
+            let mut __route = fastapi_router::Route::new(
+                #route_method,
+                #route_path,
             )
             #summary_call
             #description_call
             #operation_id_call
             #tags_call
             #deprecated_call
+            #idempotent_call
             #request_body_call
-            #(#response_calls)*
+            #(#response_calls)*;
+
+            #(#param_docs)*
+            {
+                use fastapi_core::{QueryParamsProbeFallback as _, QueryParamsProbeMatch as _};
+                #(#query_param_probes)*
+            }
+            #(#error_response_docs)*
+
+            for __def in #security_fn_name() {
+                __route = __route.security(__def.name, __def.scopes);
+            }
+            __route
         }
 
-        /// Build a runtime [`fastapi_core::RouteEntry`] for this handler.
-        ///
-        /// This wraps the handler with extractor evaluation (`FromRequest`) and converts
-        /// both extractor errors and handler return values into a [`fastapi_core::Response`].
-        #[allow(non_snake_case)]
-        pub fn #route_entry_fn_name() -> fastapi_core::RouteEntry {
+        #entry_fn_head {
             fn __into_response<T: fastapi_core::IntoResponse>(v: T) -> fastapi_core::Response {
                 v.into_response()
             }
 
-            let __route = #route_fn_name();
-            fastapi_core::RouteEntry::from_route(__route, |ctx, req| {
+            #entry_route_binding
+            let mut __entry = fastapi_core::RouteEntry::from_route(__route, |ctx, req| {
                 Box::pin(async move {
+                    let __started = std::time::Instant::now();
                     #(#arg_extracts)*
+                    let __extracted = std::time::Instant::now();
                     let out = #call_handler;
-                    __into_response(out)
+                    let __handled = std::time::Instant::now();
+                    let __response = __into_response(out);
+                    fastapi_core::slow_request::record_handler_phases(
+                        req, __started, __extracted, __handled,
+                    );
+                    __response
                 }) as fastapi_core::BoxFuture<'_, fastapi_core::Response>
-            })
+            });
+            for __def in #security_fn_name() {
+                __entry = __entry.security_scheme(__def.name, __def.scheme);
+            }
+            #openapi_schemas_call
+            #(#state_requirements)*
+            {
+                use fastapi_core::{DependencyProbeFallback as _, DependencyProbeMatch as _};
+                #(#dependency_requirements)*
+            }
+            __entry
         }
 
+        #multi_method_items
+
         // Static registration for route discovery
-        #[doc(hidden)]
-        #[allow(unsafe_code)]
-        #[allow(non_upper_case_globals)]
-        #[used]
-        #[cfg_attr(
-            any(target_os = "linux", target_os = "android", target_os = "freebsd"),
-            unsafe(link_section = "fastapi_routes")
-        )]
-        static #reg_name: fastapi_router::RouteRegistration =
-            fastapi_router::RouteRegistration::new(#route_fn_name);
+        #(
+            #[doc(hidden)]
+            #[allow(unsafe_code)]
+            #[allow(non_upper_case_globals)]
+            #[used]
+            #[cfg_attr(
+                any(target_os = "linux", target_os = "android", target_os = "freebsd"),
+                unsafe(link_section = "fastapi_routes")
+            )]
+            static #reg_names: fastapi_router::RouteRegistration =
+                fastapi_router::RouteRegistration::new(#registered_route_fns);
+        )*
     };
 
     TokenStream::from(expanded)
@@ -953,6 +1615,14 @@ mod tests {
         assert!(attrs.deprecated);
     }
 
+    #[test]
+    fn test_route_attrs_idempotent() {
+        let attrs: RouteAttrs = syn::parse_quote! { "/jobs", idempotent, summary = "Submit" };
+        assert!(attrs.idempotent);
+        assert!(!attrs.deprecated);
+        assert_eq!(attrs.summary.as_deref(), Some("Submit"));
+    }
+
     #[test]
     fn test_route_attrs_all_options() {
         let attrs: RouteAttrs = syn::parse_quote! {
@@ -981,6 +1651,84 @@ mod tests {
         assert_eq!(attrs.summary.as_deref(), Some("Test"));
     }
 
+    #[test]
+    fn test_route_attrs_parenthesized_tags() {
+        let attrs: RouteAttrs = syn::parse_quote! { "/items", tags("items", "crud") };
+        assert_eq!(attrs.tags, vec!["items", "crud"]);
+    }
+
+    #[test]
+    fn test_route_attrs_responses_list() {
+        let attrs: RouteAttrs = syn::parse_quote! {
+            "/items/{id}",
+            responses(404 = NotFound, 409 = (Conflict, "Already exists"),)
+        };
+        assert_eq!(attrs.responses.len(), 2);
+        assert_eq!(attrs.responses[0].status, 404);
+        assert_eq!(attrs.responses[0].type_name, "NotFound");
+        assert!(attrs.responses[0].description.is_none());
+        assert_eq!(attrs.responses[1].status, 409);
+        assert_eq!(attrs.responses[1].type_name, "Conflict");
+        assert_eq!(
+            attrs.responses[1].description.as_deref(),
+            Some("Already exists")
+        );
+    }
+
+    #[test]
+    fn test_route_attrs_responses_rejects_bad_status() {
+        let result: syn::Result<RouteAttrs> =
+            syn::parse_str(r#""/items", responses(abc = NotFound)"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_route_attrs_response_headers() {
+        let attrs: RouteAttrs = syn::parse_quote! {
+            "/items",
+            response_headers(200 = "X-Total-Count", 429 = ("Retry-After", "Seconds to wait"))
+        };
+        assert_eq!(attrs.response_headers.len(), 2);
+        assert_eq!(attrs.response_headers[0].status, 200);
+        assert_eq!(attrs.response_headers[0].name, "X-Total-Count");
+        assert!(attrs.response_headers[0].description.is_empty());
+        assert_eq!(attrs.response_headers[1].status, 429);
+        assert_eq!(attrs.response_headers[1].description, "Seconds to wait");
+    }
+
+    #[test]
+    fn test_route_attrs_typed_path() {
+        let attrs: RouteAttrs = syn::parse_quote! { paths::ItemPath, summary = "Get item" };
+        let typed = attrs.typed_path.expect("typed path");
+        assert_eq!(typed.segments.last().unwrap().ident, "ItemPath");
+        assert!(attrs.path.value().is_empty());
+        assert_eq!(attrs.summary.as_deref(), Some("Get item"));
+    }
+
+    #[test]
+    fn test_route_attrs_methods() {
+        let attrs: RouteAttrs = syn::parse_quote! { "/items", methods("GET", "head") };
+        let methods: Vec<String> = attrs.methods.iter().map(LitStr::value).collect();
+        assert_eq!(methods, ["GET", "head"]);
+    }
+
+    #[test]
+    fn test_method_variant() {
+        assert_eq!(method_variant("GET"), Some("Get"));
+        assert_eq!(method_variant("options"), Some("Options"));
+        assert_eq!(method_variant("Trace"), Some("Trace"));
+        assert_eq!(method_variant("CONNECT"), Some("Connect"));
+        assert_eq!(method_variant("PROPFIND"), None);
+    }
+
+    #[test]
+    fn test_default_response_description() {
+        assert_eq!(default_response_description(201), "Successful response");
+        assert_eq!(default_response_description(404), "Not found");
+        assert_eq!(default_response_description(418), "Client error");
+        assert_eq!(default_response_description(503), "Server error");
+    }
+
     #[test]
     fn test_extract_body_info_json() {
         let ty: Type = syn::parse_quote! { Json<CreateUser> };
@@ -1027,6 +1775,82 @@ mod tests {
         assert_eq!(extract_type_name(&ty), "CreateUserRequest");
     }
 
+    #[test]
+    fn test_schema_type_name_keeps_generics() {
+        let ty: Type = syn::parse_quote! { Item };
+        assert_eq!(schema_type_name(&ty), "Item");
+
+        let ty: Type = syn::parse_quote! { Vec<models::Item> };
+        assert_eq!(schema_type_name(&ty), "Vec<Item>");
+
+        let ty: Type = syn::parse_quote! { HashMap<String, Page<User>> };
+        assert_eq!(schema_type_name(&ty), "HashMap<String, Page<User>>");
+    }
+
+    #[test]
+    fn test_infer_response_schema_json() {
+        let ret: ReturnType = syn::parse_quote! { -> Json<Item> };
+        assert_eq!(infer_response_schema(&ret).as_deref(), Some("Item"));
+
+        let ret: ReturnType = syn::parse_quote! { -> Json<Vec<Item>> };
+        assert_eq!(infer_response_schema(&ret).as_deref(), Some("Vec<Item>"));
+    }
+
+    #[test]
+    fn test_infer_response_schema_result() {
+        let ret: ReturnType = syn::parse_quote! { -> Result<Json<Item>, HttpError> };
+        assert_eq!(infer_response_schema(&ret).as_deref(), Some("Item"));
+    }
+
+    #[test]
+    fn test_infer_response_schema_opaque() {
+        let ret: ReturnType = syn::parse_quote! { -> impl IntoResponse };
+        assert!(infer_response_schema(&ret).is_none());
+
+        let ret: ReturnType = syn::parse_quote! { -> Response };
+        assert!(infer_response_schema(&ret).is_none());
+
+        let ret: ReturnType = syn::parse_quote! { -> Result<String, HttpError> };
+        assert!(infer_response_schema(&ret).is_none());
+
+        let ret: ReturnType = ReturnType::Default;
+        assert!(infer_response_schema(&ret).is_none());
+    }
+
+    #[test]
+    fn test_result_error_type() {
+        let ret: ReturnType = syn::parse_quote! { -> Result<Json<Item>, ItemError> };
+        let error_ty = result_error_type(&ret).unwrap();
+        assert_eq!(quote!(#error_ty).to_string(), "ItemError");
+
+        let ret: ReturnType = syn::parse_quote! { -> Json<Item> };
+        assert!(result_error_type(&ret).is_none());
+    }
+
+    #[test]
+    fn test_extract_wrapped_type() {
+        let ty: Type = syn::parse_quote! { Query<SearchParams> };
+        let inner = extract_wrapped_type(&ty, "Query").unwrap();
+        assert_eq!(quote!(#inner).to_string(), "SearchParams");
+        assert!(extract_wrapped_type(&ty, "Path").is_none());
+
+        let ty: Type = syn::parse_quote! { fastapi::Path<(i64, String)> };
+        assert!(extract_wrapped_type(&ty, "Path").is_some());
+    }
+
+    #[test]
+    fn test_extract_state_type() {
+        let ty: Type = syn::parse_quote! { State<DbPool> };
+        let inner = extract_state_type(&ty).unwrap();
+        assert_eq!(quote!(#inner).to_string(), "DbPool");
+
+        let ty: Type = syn::parse_quote! { fastapi_core::State<Arc<Config>> };
+        assert!(extract_state_type(&ty).is_some());
+
+        let ty: Type = syn::parse_quote! { Path<i64> };
+        assert!(extract_state_type(&ty).is_none());
+    }
+
     #[test]
     fn test_extract_type_name_vec() {
         let ty: Type = syn::parse_quote! { Vec<Item> };
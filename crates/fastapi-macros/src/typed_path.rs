@@ -0,0 +1,260 @@
+//! `#[derive(TypedPath)]` for structs bound to a route template.
+//!
+//! ```ignore
+//! #[derive(TypedPath)]
+//! #[typed_path("/orgs/{org}/items/{id:int}")]
+//! struct ItemPath {
+//!     org: String,
+//!     id: i64,
+//! }
+//! ```
+//!
+//! Every template parameter must have a field of the same name and every
+//! field must appear in the template. Fields are parsed with `FromStr` on
+//! extraction and written with `Display` by `url()`, so both bounds are
+//! checked at compile time.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, parse_macro_input};
+
+/// One piece of a route template.
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Param {
+        name: String,
+        converter: Option<String>,
+    },
+}
+
+/// Split a template such as `/items/{id:int}` into literals and parameters.
+fn parse_template(template: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            segments.push(Segment::Literal(rest[..start].to_string()));
+        }
+        let Some(len) = rest[start..].find('}') else {
+            return Err(format!("unclosed `{{` in path template `{template}`"));
+        };
+        let inner = &rest[start + 1..start + len];
+        let (name, converter) = match inner.split_once(':') {
+            Some((name, converter)) => (name, Some(converter.to_string())),
+            None => (inner, None),
+        };
+        if name.is_empty() {
+            return Err(format!(
+                "empty parameter name in path template `{template}`"
+            ));
+        }
+        segments.push(Segment::Param {
+            name: name.to_string(),
+            converter,
+        });
+        rest = &rest[start + len + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest.to_string()));
+    }
+    Ok(segments)
+}
+
+fn template_attr(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut template = None;
+    for attr in &input.attrs {
+        if attr.path().is_ident("typed_path") {
+            template = Some(attr.parse_args::<LitStr>()?);
+        }
+    }
+    template.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "missing `#[typed_path(\"/...\")]` attribute with the route template",
+        )
+    })
+}
+
+pub fn derive_typed_path_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// The struct's `(name, type)` pairs; unit structs have none.
+fn named_fields<'a>(
+    ident: &syn::Ident,
+    fields: &'a Fields,
+) -> syn::Result<Vec<(&'a syn::Ident, &'a syn::Type)>> {
+    match fields {
+        Fields::Named(named) => Ok(named
+            .named
+            .iter()
+            .filter_map(|f| f.ident.as_ref().map(|name| (name, &f.ty)))
+            .collect()),
+        Fields::Unit => Ok(Vec::new()),
+        Fields::Unnamed(_) => Err(syn::Error::new_spanned(
+            ident,
+            "TypedPath fields must be named after the template parameters",
+        )),
+    }
+}
+
+/// Check that template parameters and fields match one to one.
+fn check_params(
+    segments: &[Segment],
+    fields: &[(&syn::Ident, &syn::Type)],
+    template: &LitStr,
+    ident: &syn::Ident,
+) -> syn::Result<()> {
+    let path = template.value();
+    let mut params: Vec<&str> = Vec::new();
+    for segment in segments {
+        if let Segment::Param { name, .. } = segment {
+            if params.contains(&name.as_str()) {
+                return Err(syn::Error::new(
+                    template.span(),
+                    format!("parameter `{name}` appears twice in `{path}`"),
+                ));
+            }
+            if !fields.iter().any(|(field, _)| *field == name) {
+                return Err(syn::Error::new(
+                    template.span(),
+                    format!("template parameter `{name}` has no matching field on `{ident}`"),
+                ));
+            }
+            params.push(name);
+        }
+    }
+    for (field, _) in fields {
+        if !params.iter().any(|param| *field == param) {
+            return Err(syn::Error::new_spanned(
+                field,
+                format!("field `{field}` is not a parameter of `{path}`"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let Data::Struct(data_struct) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "TypedPath can only be derived for structs",
+        ));
+    };
+    let fields = named_fields(ident, &data_struct.fields)?;
+
+    let template = template_attr(input)?;
+    let path = template.value();
+    if !path.starts_with('/') {
+        return Err(syn::Error::new(
+            template.span(),
+            "path template must start with `/`",
+        ));
+    }
+    let segments = parse_template(&path).map_err(|msg| syn::Error::new(template.span(), msg))?;
+
+    check_params(&segments, &fields, &template, ident)?;
+
+    let parse_fields = fields.iter().map(|(field, ty)| {
+        let name = field.to_string();
+        quote! {
+            #field: fastapi_core::parse_path_param::<#ty>(params, #name)?
+        }
+    });
+    let construct = if matches!(data_struct.fields, Fields::Unit) {
+        quote! { Self }
+    } else {
+        quote! { Self { #(#parse_fields),* } }
+    };
+
+    let url_parts = segments.iter().map(|segment| match segment {
+        Segment::Literal(text) => quote! { __url.push_str(#text); },
+        Segment::Param { name, converter } => {
+            let field = syn::Ident::new(name, template.span());
+            let keep_slash = converter.as_deref() == Some("path");
+            quote! {
+                __url.push_str(&fastapi_core::encode_path_param(
+                    &self.#field.to_string(),
+                    #keep_slash,
+                ));
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics fastapi_core::TypedPath for #ident #ty_generics #where_clause {
+            const PATH: &'static str = #template;
+
+            #[allow(unused_variables)]
+            fn from_params(
+                params: &fastapi_core::PathParams,
+            ) -> Result<Self, fastapi_core::PathExtractError> {
+                Ok(#construct)
+            }
+
+            fn url(&self) -> String {
+                let mut __url = String::new();
+                #(#url_parts)*
+                __url
+            }
+        }
+
+        impl #impl_generics fastapi_core::FromRequest for #ident #ty_generics #where_clause {
+            type Error = fastapi_core::PathExtractError;
+
+            async fn from_request(
+                _ctx: &fastapi_core::RequestContext,
+                req: &mut fastapi_core::Request,
+            ) -> Result<Self, Self::Error> {
+                let params = req
+                    .get_extension::<fastapi_core::PathParams>()
+                    .ok_or(fastapi_core::PathExtractError::MissingPathParams)?;
+                <Self as fastapi_core::TypedPath>::from_params(params)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_template() {
+        let segments = parse_template("/orgs/{org}/files/{rest:path}").unwrap();
+        assert_eq!(
+            segments,
+            [
+                Segment::Literal("/orgs/".into()),
+                Segment::Param {
+                    name: "org".into(),
+                    converter: None
+                },
+                Segment::Literal("/files/".into()),
+                Segment::Param {
+                    name: "rest".into(),
+                    converter: Some("path".into())
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_template_rejects_malformed() {
+        assert!(parse_template("/items/{id").is_err());
+        assert!(parse_template("/items/{}").is_err());
+        assert_eq!(
+            parse_template("/health").unwrap(),
+            [Segment::Literal("/health".into())]
+        );
+    }
+}
@@ -8,14 +8,18 @@
 //! - `#[validate(range(min = N, max = M))]` - Numeric range constraints
 //! - `#[validate(email)]` - Email format validation
 //! - `#[validate(url)]` - URL format validation
-//! - `#[validate(regex = "pattern")]` - Regex pattern matching
+//! - `#[validate(regex = "pattern")]` or `pattern = "..."` - Regex pattern matching
 //! - `#[validate(phone)]` - Phone number validation
 //! - `#[validate(contains = "substr")]` - Substring containment
 //! - `#[validate(starts_with = "prefix")]` - Prefix matching
 //! - `#[validate(ends_with = "suffix")]` - Suffix matching
 //! - `#[validate(multiple_of = N)]` - Numeric divisibility
-//! - `#[validate(nested)]` - Nested struct validation (calls `Validate` on the field)
-//! - `#[validate(custom = path::to::fn_name)]` - Custom validation function
+//! - `#[validate(nested)]` - Nested struct validation (calls `Validate` on the field, or on
+//!   each element of an `Option`, `Vec` or map)
+//! - `#[validate(custom = path::to::fn_name)]` or `custom = "fn_name"` - Custom validation function
+//!
+//! On the struct itself, `#[validate(schema = "fn_name")]` runs a cross-field
+//! check after the field checks; see `fastapi_core::validation::SchemaCheck`.
 
 use proc_macro::TokenStream;
 use proc_macro2::{Span, TokenStream as TokenStream2};
@@ -66,6 +70,11 @@ pub fn derive_validate_impl(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
 
+    let schema_checks = match parse_schema_attrs(&input.attrs) {
+        Ok(paths) => paths,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
     let validations = match &input.data {
         Data::Struct(data) => match generate_struct_validations(&data.fields) {
             Ok(v) => v,
@@ -97,6 +106,14 @@ pub fn derive_validate_impl(input: TokenStream) -> TokenStream {
 
                 #validations
 
+                #(
+                    if let Some(err) =
+                        fastapi_core::validation::SchemaCheck::into_errors(#schema_checks(self))
+                    {
+                        errors.merge(err.with_loc_prefix(vec![LocItem::field("body")]));
+                    }
+                )*
+
                 if errors.is_empty() {
                     Ok(())
                 } else {
@@ -239,8 +256,7 @@ fn parse_validation_attrs(attrs: &[Attribute]) -> Result<FieldValidation, syn::E
                 let value: Expr = meta.value()?.parse()?;
                 validation.multiple_of = Some(value);
             } else if meta.path.is_ident("custom") {
-                let value: syn::Path = meta.value()?.parse()?;
-                validation.custom = Some(value);
+                validation.custom = Some(parse_fn_path(&meta)?);
             } else {
                 return Err(meta.error("unsupported validate() attribute"));
             }
@@ -251,6 +267,37 @@ fn parse_validation_attrs(attrs: &[Attribute]) -> Result<FieldValidation, syn::E
     Ok(validation)
 }
 
+/// Parse a validator function given as a path (`custom = check`) or a
+/// string (`custom = "check"`).
+fn parse_fn_path(meta: &syn::meta::ParseNestedMeta<'_>) -> Result<syn::Path, syn::Error> {
+    let value = meta.value()?;
+    if value.peek(syn::LitStr) {
+        let lit: syn::LitStr = value.parse()?;
+        lit.parse()
+    } else {
+        value.parse()
+    }
+}
+
+/// Parse struct-level `#[validate(schema = "fn_name")]` attributes.
+fn parse_schema_attrs(attrs: &[Attribute]) -> Result<Vec<syn::Path>, syn::Error> {
+    let mut schema_checks = Vec::new();
+    for attr in attrs {
+        if !attr.path().is_ident("validate") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("schema") {
+                schema_checks.push(parse_fn_path(&meta)?);
+                Ok(())
+            } else {
+                Err(meta.error("only `schema = \"fn_name\"` is supported on the struct"))
+            }
+        })?;
+    }
+    Ok(schema_checks)
+}
+
 #[allow(clippy::too_many_lines)]
 fn generate_field_validation(
     member: &Member,
@@ -462,12 +509,19 @@ fn generate_field_validation(
         checks.push(check);
     }
 
-    // Regex pattern validation
+    // Regex pattern validation. The compiled pattern lives in a static
+    // scoped to this field's check, so it is built on first use only.
     if let Some(ref pattern) = validation.regex {
+        let compiled = quote! {
+            static __PATTERN: ::std::sync::OnceLock<fastapi_core::validation::Pattern> =
+                ::std::sync::OnceLock::new();
+            let __pattern = __PATTERN.get_or_init(|| fastapi_core::validation::Pattern::new(#pattern));
+        };
         let check = if is_optional {
             quote! {
                 if let Some(ref val) = self.#member {
-                    if !fastapi_core::validation::matches_pattern(val, #pattern) {
+                    #compiled
+                    if !__pattern.is_match(val) {
                         errors.push(ValidationError::pattern_mismatch(#loc, #pattern)
                             .with_input(serde_json::json!(val)));
                     }
@@ -475,9 +529,12 @@ fn generate_field_validation(
             }
         } else {
             quote! {
-                if !fastapi_core::validation::matches_pattern(&self.#member, #pattern) {
-                    errors.push(ValidationError::pattern_mismatch(#loc, #pattern)
-                        .with_input(serde_json::json!(&self.#member)));
+                {
+                    #compiled
+                    if !__pattern.is_match(&self.#member) {
+                        errors.push(ValidationError::pattern_mismatch(#loc, #pattern)
+                            .with_input(serde_json::json!(&self.#member)));
+                    }
                 }
             }
         };
@@ -572,36 +629,8 @@ fn generate_field_validation(
 
     // nested
     if validation.nested {
-        let check = if is_optional {
-            quote! {
-                if let Some(ref val) = self.#member {
-                    if let Err(err) = fastapi_core::validation::Validate::validate(val) {
-                        let mut err = *err;
-                        for e in &mut err.errors {
-                            if e.loc.first().and_then(LocItem::as_str) == Some("body") {
-                                e.loc.remove(0);
-                            }
-                        }
-                        let prefix = #prefix;
-                        errors.merge(err.with_loc_prefix(prefix));
-                    }
-                }
-            }
-        } else {
-            quote! {
-                if let Err(err) = fastapi_core::validation::Validate::validate(&self.#member) {
-                    let mut err = *err;
-                    for e in &mut err.errors {
-                        if e.loc.first().and_then(LocItem::as_str) == Some("body") {
-                            e.loc.remove(0);
-                        }
-                    }
-                    let prefix = #prefix;
-                    errors.merge(err.with_loc_prefix(prefix));
-                }
-            }
-        };
-        checks.push(check);
+        let value = quote! { &self.#member };
+        checks.push(generate_nested_validation(&value, field_type, prefix, 0));
     }
 
     // Custom validation function
@@ -620,7 +649,188 @@ fn generate_field_validation(
     }
 }
 
+/// Method calls that carry a field's `#[validate(...)]` constraints into its
+/// JSON Schema, for `#[derive(JsonSchema)]`.
+///
+/// Lengths become `minLength`/`maxLength` (`minItems`/`maxItems` on arrays),
+/// ranges become `minimum`/`maximum` and their exclusive forms, and `regex`
+/// (or a lone `starts_with`/`ends_with`/`contains`) becomes `pattern`.
+/// `email` and `url` set the format unless `format_overridden` (an explicit
+/// `#[schema(format = ...)]` wins). Malformed attributes yield nothing here;
+/// `#[derive(Validate)]` reports them.
+pub(crate) fn schema_constraint_calls(
+    attrs: &[Attribute],
+    format_overridden: bool,
+) -> TokenStream2 {
+    let Ok(validation) = parse_validation_attrs(attrs) else {
+        return quote! {};
+    };
+
+    let mut fields = Vec::new();
+    if let Some(min) = validation.length_min {
+        fields.push(quote! { min_length: Some(#min) });
+    }
+    if let Some(max) = validation.length_max {
+        fields.push(quote! { max_length: Some(#max) });
+    }
+    let derived_pattern = match (
+        &validation.starts_with,
+        &validation.ends_with,
+        &validation.contains,
+    ) {
+        (Some(prefix), None, None) => Some(format!("^{}", escape_regex(prefix))),
+        (None, Some(suffix), None) => Some(format!("{}$", escape_regex(suffix))),
+        (None, None, Some(needle)) => Some(escape_regex(needle)),
+        _ => None,
+    };
+    if let Some(pattern) = validation.regex.clone().or(derived_pattern) {
+        fields.push(quote! { pattern: Some(#pattern.to_string()) });
+    }
+    for (value, field) in [
+        (validation.range_ge, quote! { minimum }),
+        (validation.range_le, quote! { maximum }),
+        (validation.range_gt, quote! { exclusive_minimum }),
+        (validation.range_lt, quote! { exclusive_maximum }),
+        (
+            validation
+                .multiple_of
+                .as_ref()
+                .and_then(|expr| parse_number_expr_to_f64(expr).ok()),
+            quote! { multiple_of },
+        ),
+    ] {
+        if let Some(value) = value {
+            fields.push(quote! { #field: Some(#value) });
+        }
+    }
+
+    let mut calls = TokenStream2::new();
+    if !fields.is_empty() {
+        calls.extend(quote! {
+            .with_constraints(fastapi_openapi::SchemaConstraints {
+                #(#fields,)*
+                ..fastapi_openapi::SchemaConstraints::default()
+            })
+        });
+    }
+    let format = if validation.email {
+        Some("email")
+    } else if validation.url {
+        Some("uri")
+    } else {
+        None
+    };
+    if let Some(format) = format.filter(|_| !format_overridden) {
+        calls.extend(quote! { .with_format(#format) });
+    }
+    calls
+}
+
+/// Escape regex metacharacters so `text` matches literally.
+fn escape_regex(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if "\\.^$|?*+()[]{}".contains(ch) {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
 /// Check if a type is Option<T> and extract the inner type.
+/// Validate a `#[validate(nested)]` value, recursing through `Option`, `Vec`
+/// and map containers down to the element type.
+///
+/// `value` evaluates to a reference to a value of type `ty`; `prefix`
+/// evaluates to its `loc`. Elements are located by index (`Vec`) or by key
+/// (`HashMap`/`BTreeMap`, whose keys must implement `Display`).
+fn generate_nested_validation(
+    value: &TokenStream2,
+    ty: &Type,
+    prefix: &TokenStream2,
+    depth: usize,
+) -> TokenStream2 {
+    let element = quote::format_ident!("__nested_{}", depth);
+    let loc = quote::format_ident!("__nested_loc_{}", depth);
+    let next_prefix = quote! { #loc.clone() };
+
+    match container_kind(ty) {
+        Some((Container::Option, inner)) => {
+            let inner = generate_nested_validation(&quote! { #element }, inner, prefix, depth + 1);
+            quote! {
+                if let Some(#element) = #value {
+                    #inner
+                }
+            }
+        }
+        Some((Container::Seq, inner)) => {
+            let index = quote::format_ident!("__nested_index_{}", depth);
+            let inner =
+                generate_nested_validation(&quote! { #element }, inner, &next_prefix, depth + 1);
+            quote! {
+                for (#index, #element) in IntoIterator::into_iter(#value).enumerate() {
+                    let mut #loc = #prefix;
+                    #loc.push(LocItem::index(#index));
+                    #inner
+                }
+            }
+        }
+        Some((Container::Map, inner)) => {
+            let key = quote::format_ident!("__nested_key_{}", depth);
+            let inner =
+                generate_nested_validation(&quote! { #element }, inner, &next_prefix, depth + 1);
+            quote! {
+                for (#key, #element) in IntoIterator::into_iter(#value) {
+                    let mut #loc = #prefix;
+                    #loc.push(LocItem::field(#key.to_string()));
+                    #inner
+                }
+            }
+        }
+        None => quote! {
+            if let Err(err) = fastapi_core::validation::Validate::validate(#value) {
+                let mut err = *err;
+                for e in &mut err.errors {
+                    if e.loc.first().and_then(LocItem::as_str) == Some("body") {
+                        e.loc.remove(0);
+                    }
+                }
+                errors.merge(err.with_loc_prefix(#prefix));
+            }
+        },
+    }
+}
+
+/// Containers `#[validate(nested)]` looks through.
+enum Container {
+    Option,
+    Seq,
+    Map,
+}
+
+/// Classify `Option<T>`, `Vec<T>`/`VecDeque<T>` and `HashMap<K, T>`/`BTreeMap<K, T>`,
+/// returning the element type `T`.
+fn container_kind(ty: &Type) -> Option<(Container, &Type)> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let mut types = args.args.iter().filter_map(|arg| match arg {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    });
+    match segment.ident.to_string().as_str() {
+        "Option" => Some((Container::Option, types.next()?)),
+        "Vec" | "VecDeque" => Some((Container::Seq, types.next()?)),
+        "HashMap" | "BTreeMap" => Some((Container::Map, types.nth(1)?)),
+        _ => None,
+    }
+}
+
 fn extract_option_inner(ty: &Type) -> (bool, Option<&Type>) {
     if let Type::Path(type_path) = ty {
         if let Some(segment) = type_path.path.segments.last() {
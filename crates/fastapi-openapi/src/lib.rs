@@ -34,15 +34,23 @@
 #![allow(clippy::field_reassign_with_default)]
 #![allow(clippy::trivially_copy_pass_by_ref)]
 
+mod overrides;
 mod schema;
 mod spec;
+mod v30;
 
+pub use overrides::OverrideConflict;
 pub use schema::{
-    ArraySchema, EnumSchema, JsonSchema, ObjectSchema, OneOfSchema, PrimitiveSchema, RefSchema,
-    Schema, SchemaType,
+    AllOfSchema, ArraySchema, ArrayType, DefaultValueProbe, DefaultValueProbeFallback, DefaultValueProbeMatch,
+    Discriminator, EnumSchema, JsonSchema, ObjectSchema, OneOfSchema, PrimitiveSchema, RefSchema,
+    Schema, SchemaConstraints, SchemaType, generic_schema_name, nested_schema, schema_label,
 };
 pub use spec::{
-    Components, Example, HasParamMeta, Info, MediaType, OpenApi, OpenApiBuilder, Operation,
-    ParamMeta, Parameter, ParameterLocation, PathItem, RequestBody, Response, SchemaRegistry,
-    SchemaRegistryMut, Server, Tag,
+    ApiKeyLocation, Components, ErrorResponses, ErrorResponsesProbe, ErrorResponsesProbeFallback,
+    ErrorResponsesProbeMatch, Example, ExternalDocs, HasParamMeta, Header, Info, MediaType,
+    OAuthFlow, OAuthFlows, OpenApi, OpenApiBuilder, Operation, ParamMeta, Parameter,
+    ParameterLocation, ParamsProbe, ParamsProbeFallback, ParamsProbeMatch, PathItem, RequestBody,
+    Response, RouteSchemas, SchemaProbe, SchemaProbeFallback, SchemaProbeMatch, SchemaRegistry,
+    SchemaRegistryMut, SecurityRequirement, SecurityScheme, Server, Tag, http_error_schema,
+    params_from_schema,
 };
@@ -0,0 +1,184 @@
+//! Declarative overrides merged over a generated document.
+//!
+//! Documentation writers can keep descriptions, examples and `x-` extensions
+//! in a partial OpenAPI JSON document instead of Rust attributes.
+//! [`OpenApi::merge_overrides`] deep-merges it over the generated spec:
+//! objects merge key by key, and any other value replaces the generated one.
+//!
+//! Replacing a generated value with a different one, or documenting an
+//! operation the code does not serve, is reported as an [`OverrideConflict`]
+//! so drift between the code and the overrides file stays visible.
+
+use std::fmt::{self, Write as _};
+
+use serde_json::{Map, Value};
+
+use crate::spec::OpenApi;
+
+/// Path item keys that hold operations.
+const OPERATION_KEYS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// A questionable entry in an overrides document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverrideConflict {
+    /// JSON Pointer (RFC 6901) to the overridden location.
+    pub pointer: String,
+    /// The generated value, or `None` when the override targets a path or
+    /// operation missing from the generated spec.
+    pub generated: Option<Value>,
+    /// The value taken from the overrides document.
+    pub replacement: Value,
+}
+
+impl fmt::Display for OverrideConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.generated {
+            Some(generated) => write!(
+                f,
+                "{}: generated value {} replaced by {}",
+                self.pointer, generated, self.replacement
+            ),
+            None => write!(f, "{}: not present in the generated spec", self.pointer),
+        }
+    }
+}
+
+impl OpenApi {
+    /// Deep-merge a partial OpenAPI document over this one.
+    ///
+    /// Returns the merged document as JSON together with every conflict
+    /// found. Overrides always win; conflicts are informational.
+    #[must_use]
+    pub fn merge_overrides(&self, overrides: &Value) -> (Value, Vec<OverrideConflict>) {
+        let mut doc = serde_json::to_value(self).unwrap_or(Value::Null);
+        let mut conflicts = Vec::new();
+        merge_value(&mut doc, overrides, &mut Vec::new(), &mut conflicts);
+        (doc, conflicts)
+    }
+}
+
+fn merge_value(
+    target: &mut Value,
+    overlay: &Value,
+    path: &mut Vec<String>,
+    conflicts: &mut Vec<OverrideConflict>,
+) {
+    match (target, overlay) {
+        (Value::Object(fields), Value::Object(overrides)) => {
+            merge_object(fields, overrides, path, conflicts);
+        }
+        (target, overlay) => {
+            if target != overlay {
+                conflicts.push(OverrideConflict {
+                    pointer: pointer(path),
+                    generated: Some(target.clone()),
+                    replacement: overlay.clone(),
+                });
+                *target = overlay.clone();
+            }
+        }
+    }
+}
+
+fn merge_object(
+    fields: &mut Map<String, Value>,
+    overrides: &Map<String, Value>,
+    path: &mut Vec<String>,
+    conflicts: &mut Vec<OverrideConflict>,
+) {
+    for (key, value) in overrides {
+        path.push(key.clone());
+        match fields.get_mut(key) {
+            Some(existing) => merge_value(existing, value, path, conflicts),
+            None => {
+                if targets_operation(path) {
+                    conflicts.push(OverrideConflict {
+                        pointer: pointer(path),
+                        generated: None,
+                        replacement: value.clone(),
+                    });
+                }
+                fields.insert(key.clone(), value.clone());
+            }
+        }
+        path.pop();
+    }
+}
+
+/// Whether `path` names a path item or an operation under `/paths`.
+fn targets_operation(path: &[String]) -> bool {
+    match path {
+        [root, _] => root == "paths",
+        [root, _, method] => root == "paths" && OPERATION_KEYS.contains(&method.as_str()),
+        _ => false,
+    }
+}
+
+/// Build an RFC 6901 JSON Pointer from path segments.
+fn pointer(path: &[String]) -> String {
+    let mut pointer = String::new();
+    for segment in path {
+        let _ = write!(
+            pointer,
+            "/{}",
+            segment.replace('~', "~0").replace('/', "~1")
+        );
+    }
+    pointer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::{OpenApiBuilder, Operation};
+    use serde_json::json;
+
+    fn spec() -> OpenApi {
+        let operation = Operation {
+            summary: Some("List items".into()),
+            ..Operation::default()
+        };
+        OpenApiBuilder::new("Items", "1.0.0")
+            .operation("GET", "/items", operation)
+            .build()
+    }
+
+    #[test]
+    fn merge_adds_and_replaces() {
+        let overrides = json!({
+            "info": {"description": "Inventory API"},
+            "paths": {"/items": {"get": {
+                "summary": "List all items",
+                "description": "Paginated.",
+                "x-internal": true
+            }}}
+        });
+        let (doc, conflicts) = spec().merge_overrides(&overrides);
+
+        let get = &doc["paths"]["/items"]["get"];
+        assert_eq!(doc["info"]["title"], "Items");
+        assert_eq!(doc["info"]["description"], "Inventory API");
+        assert_eq!(get["summary"], "List all items");
+        assert_eq!(get["description"], "Paginated.");
+        assert_eq!(get["x-internal"], true);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].pointer, "/paths/~1items/get/summary");
+        assert_eq!(conflicts[0].generated, Some(json!("List items")));
+    }
+
+    #[test]
+    fn merge_flags_unknown_operations() {
+        let overrides = json!({"paths": {
+            "/items": {"post": {"summary": "Create"}},
+            "/users": {"get": {"summary": "Users"}}
+        }});
+        let (_, conflicts) = spec().merge_overrides(&overrides);
+
+        let pointers: Vec<&str> = conflicts.iter().map(|c| c.pointer.as_str()).collect();
+        assert_eq!(pointers, ["/paths/~1items/post", "/paths/~1users"]);
+        assert!(conflicts.iter().all(|c| c.generated.is_none()));
+    }
+}
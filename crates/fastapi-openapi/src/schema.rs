@@ -1,9 +1,16 @@
 //! JSON Schema types for OpenAPI 3.1.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+
+use crate::spec::SchemaRegistryMut;
 
 /// JSON Schema representation.
+///
+/// Variants are ordered most specific first so that deserialization picks
+/// the right one: [`ObjectSchema`] has no required keys and accepts anything.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Schema {
@@ -11,16 +18,18 @@ pub enum Schema {
     Boolean(bool),
     /// Reference to another schema.
     Ref(RefSchema),
-    /// Object schema.
-    Object(ObjectSchema),
-    /// Array schema.
-    Array(ArraySchema),
-    /// Primitive type schema.
-    Primitive(PrimitiveSchema),
     /// Enum schema (string values).
     Enum(EnumSchema),
     /// OneOf schema (union type).
     OneOf(OneOfSchema),
+    /// AllOf schema (intersection type).
+    AllOf(AllOfSchema),
+    /// Array schema.
+    Array(ArraySchema),
+    /// Primitive type schema.
+    Primitive(PrimitiveSchema),
+    /// Object schema.
+    Object(ObjectSchema),
 }
 
 impl Schema {
@@ -54,9 +63,15 @@ impl Schema {
     /// Create an array schema.
     pub fn array(items: Schema) -> Self {
         Schema::Array(ArraySchema {
+            schema_type: ArrayType::Array,
+            prefix_items: Vec::new(),
             items: Box::new(items),
             min_items: None,
             max_items: None,
+            example: None,
+            examples: Vec::new(),
+            default: None,
+            deprecated: false,
         })
     }
 
@@ -68,6 +83,10 @@ impl Schema {
             properties,
             required,
             additional_properties: None,
+            example: None,
+            examples: Vec::new(),
+            default: None,
+            deprecated: false,
         })
     }
 
@@ -98,6 +117,98 @@ impl Schema {
         self
     }
 
+    /// Set the example value on this schema (if object, array or primitive).
+    #[must_use]
+    pub fn with_example(mut self, example: impl Into<serde_json::Value>) -> Self {
+        match self {
+            Schema::Object(ref mut o) => o.example = Some(example.into()),
+            Schema::Array(ref mut a) => a.example = Some(example.into()),
+            Schema::Primitive(ref mut p) => p.example = Some(example.into()),
+            _ => {}
+        }
+        self
+    }
+
+    /// Set the default value on this schema (if object, array or primitive).
+    #[must_use]
+    pub fn with_default(mut self, default: impl Into<serde_json::Value>) -> Self {
+        match self {
+            Schema::Object(ref mut o) => o.default = Some(default.into()),
+            Schema::Array(ref mut a) => a.default = Some(default.into()),
+            Schema::Primitive(ref mut p) => p.default = Some(default.into()),
+            _ => {}
+        }
+        self
+    }
+
+    /// Apply validation constraints.
+    ///
+    /// Primitives take them as-is (set fields override existing ones); arrays
+    /// take the length bounds as `min_items` / `max_items`. Other schemas are
+    /// left unchanged.
+    #[must_use]
+    pub fn with_constraints(mut self, constraints: SchemaConstraints) -> Self {
+        match self {
+            Schema::Primitive(ref mut p) => {
+                let c = &mut p.constraints;
+                c.min_length = constraints.min_length.or(c.min_length);
+                c.max_length = constraints.max_length.or(c.max_length);
+                if constraints.pattern.is_some() {
+                    c.pattern = constraints.pattern;
+                }
+                c.minimum = constraints.minimum.or(c.minimum);
+                c.maximum = constraints.maximum.or(c.maximum);
+                c.exclusive_minimum = constraints.exclusive_minimum.or(c.exclusive_minimum);
+                c.exclusive_maximum = constraints.exclusive_maximum.or(c.exclusive_maximum);
+                c.multiple_of = constraints.multiple_of.or(c.multiple_of);
+            }
+            Schema::Array(ref mut a) => {
+                a.min_items = constraints.min_length.or(a.min_items);
+                a.max_items = constraints.max_length.or(a.max_items);
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Set the format hint (if primitive), e.g. `email` or `uri`.
+    #[must_use]
+    pub fn with_format(mut self, format: impl Into<String>) -> Self {
+        if let Schema::Primitive(ref mut p) = self {
+            p.format = Some(format.into());
+        }
+        self
+    }
+
+    /// Mark this schema as deprecated (if object, array or primitive).
+    #[must_use]
+    pub fn deprecated(mut self) -> Self {
+        match self {
+            Schema::Object(ref mut o) => o.deprecated = true,
+            Schema::Array(ref mut a) => a.deprecated = true,
+            Schema::Primitive(ref mut p) => p.deprecated = true,
+            _ => {}
+        }
+        self
+    }
+
+    /// Set the example values on this schema (if object, array or primitive).
+    #[must_use]
+    pub fn with_examples<I, V>(mut self, examples: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<serde_json::Value>,
+    {
+        let examples = examples.into_iter().map(Into::into);
+        match self {
+            Schema::Object(ref mut o) => o.examples = examples.collect(),
+            Schema::Array(ref mut a) => a.examples = examples.collect(),
+            Schema::Primitive(ref mut p) => p.examples = examples.collect(),
+            _ => {}
+        }
+        self
+    }
+
     /// Create a string enum schema with allowed values.
     pub fn string_enum(values: Vec<String>) -> Self {
         Schema::Enum(EnumSchema {
@@ -108,7 +219,42 @@ impl Schema {
 
     /// Create a oneOf schema (union type).
     pub fn one_of(schemas: Vec<Schema>) -> Self {
-        Schema::OneOf(OneOfSchema { one_of: schemas })
+        Schema::OneOf(OneOfSchema {
+            one_of: schemas,
+            discriminator: None,
+        })
+    }
+
+    /// Create a oneOf schema whose alternatives are told apart by `property_name`.
+    pub fn one_of_with_discriminator(
+        schemas: Vec<Schema>,
+        property_name: impl Into<String>,
+    ) -> Self {
+        Schema::OneOf(OneOfSchema {
+            one_of: schemas,
+            discriminator: Some(Discriminator::new(property_name)),
+        })
+    }
+
+    /// Create an allOf schema (every subschema must match).
+    pub fn all_of(schemas: Vec<Schema>) -> Self {
+        Schema::AllOf(AllOfSchema { all_of: schemas })
+    }
+
+    /// Create a fixed-length array schema whose items match `elements` by position.
+    pub fn tuple(elements: Vec<Schema>) -> Self {
+        let len = elements.len();
+        Schema::Array(ArraySchema {
+            schema_type: ArrayType::Array,
+            prefix_items: elements,
+            items: Box::new(Schema::Boolean(false)),
+            min_items: Some(len),
+            max_items: Some(len),
+            example: None,
+            examples: Vec::new(),
+            default: None,
+            deprecated: false,
+        })
     }
 }
 
@@ -136,21 +282,55 @@ pub struct ObjectSchema {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub required: Vec<String>,
     /// Additional properties schema.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        rename = "additionalProperties",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub additional_properties: Option<Box<Schema>>,
+    /// Example value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    /// Example values (JSON Schema `examples`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<serde_json::Value>,
+    /// Value assumed when the field or parameter is omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    /// Whether the schema is deprecated.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deprecated: bool,
 }
 
 /// Array schema.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArraySchema {
+    /// Always `"array"`; defaults when absent on input.
+    #[serde(rename = "type", default)]
+    pub schema_type: ArrayType,
+    /// Positional item schemas (tuples); `items` covers the rest.
+    #[serde(default, rename = "prefixItems", skip_serializing_if = "Vec::is_empty")]
+    pub prefix_items: Vec<Schema>,
     /// Item schema.
     pub items: Box<Schema>,
     /// Minimum items.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default, rename = "minItems", skip_serializing_if = "Option::is_none")]
     pub min_items: Option<usize>,
     /// Maximum items.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default, rename = "maxItems", skip_serializing_if = "Option::is_none")]
     pub max_items: Option<usize>,
+    /// Example value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    /// Example values (JSON Schema `examples`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<serde_json::Value>,
+    /// Value assumed when the field or parameter is omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    /// Whether the schema is deprecated.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deprecated: bool,
 }
 
 /// Enum schema with allowed values.
@@ -170,6 +350,98 @@ pub struct OneOfSchema {
     /// List of possible schemas.
     #[serde(rename = "oneOf")]
     pub one_of: Vec<Schema>,
+    /// Discriminator used to select among `one_of` alternatives.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<Discriminator>,
+}
+
+/// AllOf schema (intersection type).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllOfSchema {
+    /// Schemas that must all match.
+    #[serde(rename = "allOf")]
+    pub all_of: Vec<Schema>,
+}
+
+/// OpenAPI discriminator object for polymorphic `oneOf` schemas.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Discriminator {
+    /// Name of the property holding the variant tag.
+    #[serde(rename = "propertyName")]
+    pub property_name: String,
+    /// Optional mapping from tag values to schema references.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub mapping: HashMap<String, String>,
+}
+
+impl Discriminator {
+    /// Create a discriminator on the given property with no explicit mapping.
+    pub fn new(property_name: impl Into<String>) -> Self {
+        Self {
+            property_name: property_name.into(),
+            mapping: HashMap::new(),
+        }
+    }
+
+    /// Map a tag value to a schema in `#/components/schemas/`.
+    #[must_use]
+    pub fn mapping(mut self, value: impl Into<String>, schema_name: &str) -> Self {
+        self.mapping
+            .insert(value.into(), format!("#/components/schemas/{schema_name}"));
+        self
+    }
+}
+
+/// JSON Schema validation keywords for strings and numbers.
+///
+/// Mirrors the constraints checked by `#[derive(Validate)]`, so generated
+/// docs describe the same rules the server enforces.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SchemaConstraints {
+    /// Minimum string length.
+    #[serde(default, rename = "minLength", skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    /// Maximum string length.
+    #[serde(default, rename = "maxLength", skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    /// Regular expression the string must match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Inclusive lower bound.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<f64>,
+    /// Inclusive upper bound.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<f64>,
+    /// Exclusive lower bound.
+    #[serde(
+        default,
+        rename = "exclusiveMinimum",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub exclusive_minimum: Option<f64>,
+    /// Exclusive upper bound.
+    #[serde(
+        default,
+        rename = "exclusiveMaximum",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub exclusive_maximum: Option<f64>,
+    /// The value must be a multiple of this number.
+    #[serde(
+        default,
+        rename = "multipleOf",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub multiple_of: Option<f64>,
+}
+
+impl SchemaConstraints {
+    /// Whether no constraint is set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 /// Primitive type schema.
@@ -184,6 +456,21 @@ pub struct PrimitiveSchema {
     /// Nullable flag (OpenAPI 3.1).
     #[serde(default, skip_serializing_if = "is_false")]
     pub nullable: bool,
+    /// Example value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    /// Example values (JSON Schema `examples`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<serde_json::Value>,
+    /// Value assumed when the field or parameter is omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    /// Validation constraints (`minLength`, `maximum`, `pattern`, ...).
+    #[serde(flatten)]
+    pub constraints: SchemaConstraints,
+    /// Whether the schema is deprecated.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deprecated: bool,
 }
 
 impl PrimitiveSchema {
@@ -193,6 +480,11 @@ impl PrimitiveSchema {
             schema_type: SchemaType::String,
             format: None,
             nullable: false,
+            example: None,
+            examples: Vec::new(),
+            default: None,
+            constraints: SchemaConstraints::default(),
+            deprecated: false,
         }
     }
 
@@ -202,6 +494,11 @@ impl PrimitiveSchema {
             schema_type: SchemaType::Integer,
             format: format.map(String::from),
             nullable: false,
+            example: None,
+            examples: Vec::new(),
+            default: None,
+            constraints: SchemaConstraints::default(),
+            deprecated: false,
         }
     }
 
@@ -211,6 +508,11 @@ impl PrimitiveSchema {
             schema_type: SchemaType::Number,
             format: format.map(String::from),
             nullable: false,
+            example: None,
+            examples: Vec::new(),
+            default: None,
+            constraints: SchemaConstraints::default(),
+            deprecated: false,
         }
     }
 
@@ -220,6 +522,11 @@ impl PrimitiveSchema {
             schema_type: SchemaType::Boolean,
             format: None,
             nullable: false,
+            example: None,
+            examples: Vec::new(),
+            default: None,
+            constraints: SchemaConstraints::default(),
+            deprecated: false,
         }
     }
 }
@@ -229,6 +536,15 @@ fn is_false(b: &bool) -> bool {
     !*b
 }
 
+/// The `type` of an [`ArraySchema`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArrayType {
+    /// Array type.
+    #[default]
+    Array,
+}
+
 /// JSON Schema primitive types.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -255,6 +571,40 @@ pub trait JsonSchema {
     fn schema_name() -> Option<&'static str> {
         None
     }
+
+    /// Generate the schema, registering nested named types in `registry`
+    /// and referring to them by `$ref` instead of inlining them.
+    ///
+    /// Defaults to [`JsonSchema::schema`], which suits types with no nested
+    /// named types.
+    fn schema_with(registry: &mut SchemaRegistryMut<'_>) -> Schema {
+        let _ = registry;
+        Self::schema()
+    }
+
+    /// A `$ref` to this type's component, registering it (once) on first use.
+    ///
+    /// Types without a [`JsonSchema::schema_name`] are returned inline.
+    fn schema_ref(registry: &mut SchemaRegistryMut<'_>) -> Schema {
+        match Self::schema_name() {
+            Some(name) => registry.register_with::<Self>(name, Self::schema_with),
+            None => Self::schema_with(registry),
+        }
+    }
+}
+
+/// Schema for a nested field type, used by `#[derive(JsonSchema)]`.
+///
+/// With a registry, named types become `$ref`s to registered components;
+/// without one they are inlined.
+#[doc(hidden)]
+pub fn nested_schema<T: JsonSchema + ?Sized>(
+    registry: &mut Option<&mut SchemaRegistryMut<'_>>,
+) -> Schema {
+    match registry {
+        Some(registry) => T::schema_ref(registry),
+        None => T::schema(),
+    }
 }
 
 // Implement for primitive types
@@ -264,6 +614,11 @@ impl JsonSchema for String {
             schema_type: SchemaType::String,
             format: None,
             nullable: false,
+            example: None,
+            examples: Vec::new(),
+            default: None,
+            constraints: SchemaConstraints::default(),
+            deprecated: false,
         })
     }
 }
@@ -274,6 +629,11 @@ impl JsonSchema for i64 {
             schema_type: SchemaType::Integer,
             format: Some("int64".to_string()),
             nullable: false,
+            example: None,
+            examples: Vec::new(),
+            default: None,
+            constraints: SchemaConstraints::default(),
+            deprecated: false,
         })
     }
 }
@@ -284,6 +644,11 @@ impl JsonSchema for i32 {
             schema_type: SchemaType::Integer,
             format: Some("int32".to_string()),
             nullable: false,
+            example: None,
+            examples: Vec::new(),
+            default: None,
+            constraints: SchemaConstraints::default(),
+            deprecated: false,
         })
     }
 }
@@ -294,6 +659,11 @@ impl JsonSchema for f64 {
             schema_type: SchemaType::Number,
             format: Some("double".to_string()),
             nullable: false,
+            example: None,
+            examples: Vec::new(),
+            default: None,
+            constraints: SchemaConstraints::default(),
+            deprecated: false,
         })
     }
 }
@@ -304,28 +674,396 @@ impl JsonSchema for bool {
             schema_type: SchemaType::Boolean,
             format: None,
             nullable: false,
+            example: None,
+            examples: Vec::new(),
+            default: None,
+            constraints: SchemaConstraints::default(),
+            deprecated: false,
         })
     }
 }
 
 impl<T: JsonSchema> JsonSchema for Option<T> {
     fn schema() -> Schema {
-        match T::schema() {
-            Schema::Primitive(mut p) => {
-                p.nullable = true;
-                Schema::Primitive(p)
-            }
-            other => other,
-        }
+        T::schema().nullable()
+    }
+
+    fn schema_with(registry: &mut SchemaRegistryMut<'_>) -> Schema {
+        T::schema_ref(registry).nullable()
     }
 }
 
 impl<T: JsonSchema> JsonSchema for Vec<T> {
     fn schema() -> Schema {
-        Schema::Array(ArraySchema {
-            items: Box::new(T::schema()),
-            min_items: None,
-            max_items: None,
-        })
+        Schema::array(T::schema())
+    }
+
+    fn schema_with(registry: &mut SchemaRegistryMut<'_>) -> Schema {
+        Schema::array(T::schema_ref(registry))
+    }
+}
+
+macro_rules! impl_primitive_schema {
+    ($($ty:ty => $ctor:ident($format:literal)),* $(,)?) => {
+        $(
+            impl JsonSchema for $ty {
+                fn schema() -> Schema {
+                    Schema::$ctor(Some($format))
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive_schema! {
+    i8 => integer("int8"),
+    i16 => integer("int16"),
+    isize => integer("int64"),
+    u8 => integer("uint8"),
+    u16 => integer("uint16"),
+    u32 => integer("uint32"),
+    u64 => integer("uint64"),
+    usize => integer("uint64"),
+    f32 => number("float"),
+}
+
+impl JsonSchema for str {
+    fn schema() -> Schema {
+        Schema::string()
+    }
+}
+
+impl<T: JsonSchema + ?Sized> JsonSchema for Box<T> {
+    fn schema() -> Schema {
+        T::schema()
+    }
+
+    fn schema_name() -> Option<&'static str> {
+        T::schema_name()
+    }
+
+    fn schema_with(registry: &mut SchemaRegistryMut<'_>) -> Schema {
+        T::schema_with(registry)
+    }
+
+    // `Box<T>` shares `T`'s component rather than registering a twin.
+    fn schema_ref(registry: &mut SchemaRegistryMut<'_>) -> Schema {
+        T::schema_ref(registry)
+    }
+}
+
+impl<K, V: JsonSchema, S> JsonSchema for HashMap<K, V, S> {
+    fn schema() -> Schema {
+        map_schema(V::schema())
+    }
+
+    fn schema_with(registry: &mut SchemaRegistryMut<'_>) -> Schema {
+        map_schema(V::schema_ref(registry))
+    }
+}
+
+impl<K, V: JsonSchema> JsonSchema for BTreeMap<K, V> {
+    fn schema() -> Schema {
+        map_schema(V::schema())
+    }
+
+    fn schema_with(registry: &mut SchemaRegistryMut<'_>) -> Schema {
+        map_schema(V::schema_ref(registry))
+    }
+}
+
+/// Maps serialize as JSON objects keyed by string.
+pub(crate) fn map_schema(values: Schema) -> Schema {
+    Schema::Object(ObjectSchema {
+        additional_properties: Some(Box::new(values)),
+        ..ObjectSchema::default()
+    })
+}
+
+// Tuples serialize as fixed-length arrays. Without `prefixItems`, the element
+// schemas are expressed as a `oneOf` with the length pinned by min/max items.
+macro_rules! impl_tuple_schema {
+    ($($name:ident),+) => {
+        impl<$($name: JsonSchema),+> JsonSchema for ($($name,)+) {
+            fn schema() -> Schema {
+                Schema::tuple(vec![$($name::schema()),+])
+            }
+
+            fn schema_with(registry: &mut SchemaRegistryMut<'_>) -> Schema {
+                Schema::tuple(vec![$($name::schema_ref(registry)),+])
+            }
+        }
+    };
+}
+
+
+impl_tuple_schema!(A);
+impl_tuple_schema!(A, B);
+impl_tuple_schema!(A, B, C);
+impl_tuple_schema!(A, B, C, D);
+impl_tuple_schema!(A, B, C, D, E);
+impl_tuple_schema!(A, B, C, D, E, F);
+
+// ============================================================================
+// Generic component names
+// ============================================================================
+
+/// Label for a type argument in a generic component name.
+///
+/// Uses the type's [`JsonSchema::schema_name`] when it has one, otherwise a
+/// compact form of its Rust type name (`Vec<app::User>` becomes `Vec_User`).
+pub fn schema_label<T: JsonSchema + ?Sized>() -> Cow<'static, str> {
+    T::schema_name().map_or_else(
+        || Cow::Owned(compact_type_name(std::any::type_name::<T>())),
+        Cow::Borrowed,
+    )
+}
+
+/// Component name for a generic instantiation, e.g. `Page_Item` for `Page<Item>`.
+///
+/// Names are interned so they can be returned from [`JsonSchema::schema_name`];
+/// each distinct instantiation is allocated once for the life of the process.
+pub fn generic_schema_name(base: &str, args: &[&str]) -> &'static str {
+    static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+    let mut name = String::from(base);
+    for arg in args {
+        name.push('_');
+        name.push_str(arg);
+    }
+
+    let mut names = NAMES
+        .get_or_init(|| Mutex::new(HashSet::new()))
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some(existing) = names.get(name.as_str()).copied() {
+        return existing;
+    }
+    let interned: &'static str = Box::leak(name.into_boxed_str());
+    names.insert(interned);
+    interned
+}
+
+// ============================================================================
+// Field defaults
+// ============================================================================
+
+/// Derive support: renders a `#[serde(default)]` field value for the schema.
+///
+/// `(&&DefaultValueProbe(&value)).default_value()` picks
+/// [`DefaultValueProbeMatch`] when the value is `Serialize` and falls back to
+/// [`DefaultValueProbeFallback`] (no default documented) otherwise.
+#[doc(hidden)]
+pub struct DefaultValueProbe<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait DefaultValueProbeMatch {
+    fn default_value(&self) -> Option<serde_json::Value>;
+}
+
+impl<T: Serialize> DefaultValueProbeMatch for &DefaultValueProbe<'_, T> {
+    fn default_value(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self.0)
+            .ok()
+            .filter(|value| !value.is_null())
+    }
+}
+
+#[doc(hidden)]
+pub trait DefaultValueProbeFallback {
+    fn default_value(&self) -> Option<serde_json::Value>;
+}
+
+impl<T> DefaultValueProbeFallback for DefaultValueProbe<'_, T> {
+    fn default_value(&self) -> Option<serde_json::Value> {
+        None
+    }
+}
+
+/// Strip module paths and punctuation from a Rust type name.
+pub(crate) fn compact_type_name(type_name: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for segment in type_name.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')) {
+        if let Some(last) = segment.rsplit("::").next() {
+            if !last.is_empty() {
+                parts.push(last);
+            }
+        }
+    }
+    parts.join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Item;
+
+    impl JsonSchema for Item {
+        fn schema() -> Schema {
+            Schema::object(HashMap::new(), Vec::new())
+        }
+
+        fn schema_name() -> Option<&'static str> {
+            Some("Item")
+        }
+    }
+
+    #[test]
+    fn compact_type_name_strips_paths() {
+        assert_eq!(compact_type_name("alloc::vec::Vec<app::User>"), "Vec_User");
+        assert_eq!(
+            compact_type_name("(i32, alloc::string::String)"),
+            "i32_String"
+        );
+        assert_eq!(compact_type_name("u64"), "u64");
+    }
+
+    #[test]
+    fn schema_label_prefers_schema_name() {
+        assert_eq!(schema_label::<Item>(), "Item");
+        assert_eq!(schema_label::<Box<Item>>(), "Item");
+        assert_eq!(schema_label::<Vec<Item>>(), "Vec_Item");
+        assert_eq!(schema_label::<i64>(), "i64");
+    }
+
+    #[test]
+    fn generic_schema_name_is_interned() {
+        let a = generic_schema_name("Page", &["Item"]);
+        let b = generic_schema_name("Page", &["Item"]);
+        assert_eq!(a, "Page_Item");
+        assert!(std::ptr::eq(a, b));
+        assert_eq!(
+            generic_schema_name("Pair", &["i64", "String"]),
+            "Pair_i64_String"
+        );
+    }
+
+    #[test]
+    fn schema_round_trips_through_json() {
+        let original = Schema::integer(Some("int32")).with_default(10);
+        let value = serde_json::to_value(&original).unwrap();
+        let Schema::Primitive(p) = serde_json::from_value::<Schema>(value).unwrap() else {
+            panic!("integer schema should deserialize as a primitive");
+        };
+        assert!(matches!(p.schema_type, SchemaType::Integer));
+        assert_eq!(p.default, Some(serde_json::json!(10)));
+
+        let value = serde_json::to_value(Vec::<String>::schema()).unwrap();
+        assert!(matches!(
+            serde_json::from_value::<Schema>(value).unwrap(),
+            Schema::Array(_)
+        ));
+
+        let value = serde_json::to_value(Schema::string_enum(vec!["a".into()])).unwrap();
+        assert!(matches!(
+            serde_json::from_value::<Schema>(value).unwrap(),
+            Schema::Enum(_)
+        ));
+    }
+
+    #[test]
+    #[allow(clippy::needless_borrow)]
+    fn default_probe_serializes_when_possible() {
+        struct Opaque;
+
+        assert_eq!(
+            (&&DefaultValueProbe(&10_u32)).default_value(),
+            Some(serde_json::json!(10))
+        );
+        assert_eq!((&&DefaultValueProbe(&None::<u32>)).default_value(), None);
+        assert_eq!((&&DefaultValueProbe(&Opaque)).default_value(), None);
+    }
+
+    #[test]
+    fn constraints_serialize_with_json_schema_names() {
+        let schema = Schema::string().with_constraints(SchemaConstraints {
+            min_length: Some(3),
+            max_length: Some(20),
+            pattern: Some("^[a-z]+$".to_string()),
+            ..SchemaConstraints::default()
+        });
+        let json = serde_json::to_value(&schema).unwrap();
+        assert_eq!(json["minLength"], 3);
+        assert_eq!(json["maxLength"], 20);
+        assert_eq!(json["pattern"], "^[a-z]+$");
+        assert!(json.get("minimum").is_none());
+
+        let schema = Schema::integer(None).with_constraints(SchemaConstraints {
+            minimum: Some(1.0),
+            exclusive_maximum: Some(100.0),
+            multiple_of: Some(5.0),
+            ..SchemaConstraints::default()
+        });
+        let json = serde_json::to_value(&schema).unwrap();
+        assert_eq!(json["minimum"], 1.0);
+        assert_eq!(json["exclusiveMaximum"], 100.0);
+        assert_eq!(json["multipleOf"], 5.0);
+
+        let Schema::Primitive(p) = serde_json::from_value::<Schema>(json).unwrap() else {
+            panic!("constrained integer should stay a primitive");
+        };
+        assert_eq!(p.constraints.minimum, Some(1.0));
+    }
+
+    #[test]
+    fn length_constraints_apply_to_arrays() {
+        let schema = Vec::<String>::schema().with_constraints(SchemaConstraints {
+            min_length: Some(1),
+            max_length: Some(5),
+            ..SchemaConstraints::default()
+        });
+        let Schema::Array(array) = schema else {
+            panic!("expected array");
+        };
+        assert_eq!(array.min_items, Some(1));
+        assert_eq!(array.max_items, Some(5));
+    }
+
+    #[test]
+    fn map_schema_uses_additional_properties() {
+        let json = serde_json::to_value(HashMap::<String, i32>::schema()).unwrap();
+        assert_eq!(json["additionalProperties"]["type"], "integer");
+        assert!(json.get("additional_properties").is_none());
+
+        let json = serde_json::to_value(BTreeMap::<String, bool>::schema()).unwrap();
+        assert_eq!(json["additionalProperties"]["type"], "boolean");
+
+        let Schema::Object(object) = serde_json::from_value(json).unwrap() else {
+            panic!("expected object schema");
+        };
+        assert!(object.additional_properties.is_some());
+    }
+
+    #[test]
+    fn tuple_schema_pins_length() {
+        let Schema::Array(array) = <(i32, String, bool)>::schema() else {
+            panic!("tuple should be an array schema");
+        };
+        assert_eq!(array.min_items, Some(3));
+        assert_eq!(array.max_items, Some(3));
+        assert_eq!(array.prefix_items.len(), 3);
+        assert!(matches!(*array.items, Schema::Boolean(false)));
+
+        let json = serde_json::to_value(<(i32, String)>::schema()).unwrap();
+        assert_eq!(json["minItems"], 2);
+        assert_eq!(json["maxItems"], 2);
+        assert_eq!(json["prefixItems"][1]["type"], "string");
+        assert_eq!(json["items"], false);
+        let Schema::Array(array) = serde_json::from_value(json).unwrap() else {
+            panic!("expected array schema");
+        };
+        assert_eq!(array.min_items, Some(2));
+        assert_eq!(array.prefix_items.len(), 2);
+    }
+
+    #[test]
+    fn box_and_option_delegate() {
+        let json = serde_json::to_value(Box::<u32>::schema()).unwrap();
+        assert_eq!(json["format"], "uint32");
+
+        let json = serde_json::to_value(Option::<Box<f32>>::schema()).unwrap();
+        assert_eq!(json["nullable"], true);
+        assert_eq!(json["format"], "float");
     }
 }
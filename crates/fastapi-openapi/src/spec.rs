@@ -1,8 +1,8 @@
 //! OpenAPI 3.1 specification types.
 
-use crate::schema::Schema;
+use crate::schema::{JsonSchema, Schema};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// OpenAPI 3.1 document.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +23,12 @@ pub struct OpenApi {
     /// API tags.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<Tag>,
+    /// Outbound webhooks, keyed by event name (OpenAPI 3.1).
+    ///
+    /// Each entry describes the request this API sends to subscribers when
+    /// the event fires.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub webhooks: HashMap<String, PathItem>,
 }
 
 /// API information.
@@ -104,6 +110,67 @@ pub struct PathItem {
     /// HEAD operation.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub head: Option<Operation>,
+    /// TRACE operation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<Operation>,
+    /// Operations for other methods (`CONNECT`, extension methods such as
+    /// `PROPFIND`), keyed by method name as in OpenAPI 3.2.
+    #[serde(
+        rename = "additionalOperations",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub additional_operations: BTreeMap<String, Operation>,
+}
+
+impl PathItem {
+    /// Set the operation for `method` (matched case-insensitively for the
+    /// standard methods; other names are kept as given).
+    pub fn set_operation(&mut self, method: &str, operation: Operation) {
+        let slot = match method.to_ascii_uppercase().as_str() {
+            "GET" => &mut self.get,
+            "POST" => &mut self.post,
+            "PUT" => &mut self.put,
+            "DELETE" => &mut self.delete,
+            "PATCH" => &mut self.patch,
+            "OPTIONS" => &mut self.options,
+            "HEAD" => &mut self.head,
+            "TRACE" => &mut self.trace,
+            _ => {
+                self.additional_operations
+                    .insert(method.to_string(), operation);
+                return;
+            }
+        };
+        *slot = Some(operation);
+    }
+
+    /// All operations of this path item.
+    pub fn operations(&self) -> impl Iterator<Item = &Operation> {
+        self.method_operations().map(|(_, operation)| operation)
+    }
+
+    /// All operations of this path item with their method names (uppercase
+    /// for the standard methods).
+    pub fn method_operations(&self) -> impl Iterator<Item = (&str, &Operation)> {
+        [
+            ("GET", &self.get),
+            ("POST", &self.post),
+            ("PUT", &self.put),
+            ("DELETE", &self.delete),
+            ("PATCH", &self.patch),
+            ("OPTIONS", &self.options),
+            ("HEAD", &self.head),
+            ("TRACE", &self.trace),
+        ]
+        .into_iter()
+        .filter_map(|(method, operation)| operation.as_ref().map(|op| (method, op)))
+        .chain(
+            self.additional_operations
+                .iter()
+                .map(|(method, operation)| (method.as_str(), operation)),
+        )
+    }
 }
 
 /// API operation.
@@ -140,6 +207,12 @@ pub struct Operation {
     /// Deprecated flag.
     #[serde(default, skip_serializing_if = "is_false")]
     pub deprecated: bool,
+    /// Whether the operation is safe to retry (`x-idempotent` extension).
+    #[serde(rename = "x-idempotent", default, skip_serializing_if = "is_false")]
+    pub idempotent: bool,
+    /// Security requirements (alternatives; any one must be satisfied).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub security: Vec<SecurityRequirement>,
 }
 
 fn is_false(b: &bool) -> bool {
@@ -153,12 +226,80 @@ fn default_responses() -> HashMap<String, Response> {
         "200".to_string(),
         Response {
             description: "Successful response".to_string(),
+            headers: HashMap::new(),
             content: HashMap::new(),
         },
     );
     responses
 }
 
+/// Build the schema for a type name recorded in route metadata.
+///
+/// Route metadata only carries type names (e.g. `Item`, `Vec<Item>`,
+/// `Page<User>`). Well-known containers and primitives are expanded inline;
+/// everything else becomes a `$ref` to the component with the same name the
+/// `JsonSchema` derive would register (`Page<User>` -> `Page_User`).
+fn schema_for_type_name(type_name: &str) -> Schema {
+    let type_name = type_name.trim();
+    let Some((base, args)) = split_generic_type_name(type_name) else {
+        return match type_name.rsplit("::").next().unwrap_or(type_name) {
+            "String" | "str" | "&str" => Schema::string(),
+            "bool" => Schema::boolean(),
+            "i8" => Schema::integer(Some("int8")),
+            "i16" => Schema::integer(Some("int16")),
+            "i32" => Schema::integer(Some("int32")),
+            "i64" | "isize" => Schema::integer(Some("int64")),
+            "u8" => Schema::integer(Some("uint8")),
+            "u16" => Schema::integer(Some("uint16")),
+            "u32" => Schema::integer(Some("uint32")),
+            "u64" | "usize" => Schema::integer(Some("uint64")),
+            "f32" => Schema::number(Some("float")),
+            "f64" => Schema::number(Some("double")),
+            name => Schema::reference(name),
+        };
+    };
+
+    match (base, args.as_slice()) {
+        ("Vec" | "VecDeque" | "HashSet" | "BTreeSet", [item]) => {
+            Schema::array(schema_for_type_name(item))
+        }
+        ("Option", [inner]) => schema_for_type_name(inner).nullable(),
+        ("Box" | "Arc" | "Rc", [inner]) => schema_for_type_name(inner),
+        ("HashMap" | "BTreeMap", [_, value]) => {
+            crate::schema::map_schema(schema_for_type_name(value))
+        }
+        _ => Schema::reference(&crate::schema::compact_type_name(type_name)),
+    }
+}
+
+/// Split `Base<A, B<C>>` into `("Base", ["A", "B<C>"])`.
+fn split_generic_type_name(type_name: &str) -> Option<(&str, Vec<&str>)> {
+    let open = type_name.find('<')?;
+    let inner = type_name[open + 1..].strip_suffix('>')?;
+    let base = type_name[..open].trim();
+    let base = base.rsplit("::").next().unwrap_or(base);
+
+    let mut args = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                args.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = inner[start..].trim();
+    if !last.is_empty() {
+        args.push(last);
+    }
+    Some((base, args))
+}
+
 /// Operation parameter.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Parameter {
@@ -371,6 +512,220 @@ impl ParamMeta {
     }
 }
 
+/// Macro support: documents the fields of a `Query<T>` / `Path<T>` struct.
+///
+/// `(&&ParamsProbe::<T>::default()).params()` picks [`ParamsProbeMatch`] when
+/// `T` implements [`JsonSchema`] and falls back to [`ParamsProbeFallback`]
+/// (no parameters) otherwise.
+#[doc(hidden)]
+pub struct ParamsProbe<T>(std::marker::PhantomData<T>);
+
+impl<T> Default for ParamsProbe<T> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait ParamsProbeMatch {
+    fn params(&self) -> Vec<fastapi_router::ParamInfo>;
+}
+
+impl<T: JsonSchema> ParamsProbeMatch for &ParamsProbe<T> {
+    fn params(&self) -> Vec<fastapi_router::ParamInfo> {
+        params_from_schema(&T::schema())
+    }
+}
+
+#[doc(hidden)]
+pub trait ParamsProbeFallback {
+    fn params(&self) -> Vec<fastapi_router::ParamInfo>;
+}
+
+impl<T> ParamsProbeFallback for ParamsProbe<T> {
+    fn params(&self) -> Vec<fastapi_router::ParamInfo> {
+        Vec::new()
+    }
+}
+
+/// Macro support: registers a request or response body type as a component.
+///
+/// `(&&SchemaProbe::<T>::default()).schema_ref(registry)` picks
+/// [`SchemaProbeMatch`] when `T` implements [`JsonSchema`] and falls back to
+/// [`SchemaProbeFallback`] (`None`, keeping the type-name `$ref`) otherwise.
+#[doc(hidden)]
+pub struct SchemaProbe<T: ?Sized>(std::marker::PhantomData<T>);
+
+impl<T: ?Sized> Default for SchemaProbe<T> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait SchemaProbeMatch {
+    fn schema_ref(&self, registry: &mut SchemaRegistryMut<'_>) -> Option<Schema>;
+}
+
+impl<T: JsonSchema + ?Sized> SchemaProbeMatch for &SchemaProbe<T> {
+    fn schema_ref(&self, registry: &mut SchemaRegistryMut<'_>) -> Option<Schema> {
+        Some(T::schema_ref(registry))
+    }
+}
+
+#[doc(hidden)]
+pub trait SchemaProbeFallback {
+    fn schema_ref(&self, registry: &mut SchemaRegistryMut<'_>) -> Option<Schema>;
+}
+
+impl<T: ?Sized> SchemaProbeFallback for SchemaProbe<T> {
+    fn schema_ref(&self, _registry: &mut SchemaRegistryMut<'_>) -> Option<Schema> {
+        None
+    }
+}
+
+/// Error types that know which responses they produce.
+///
+/// Implemented by `#[derive(ApiError)]`. Route macros document every
+/// `(status, description)` pair of a handler's `Result<_, E>` error type
+/// that isn't already declared in `responses(...)`, with an
+/// [`http_error_schema`] body.
+pub trait ErrorResponses {
+    /// Status codes and descriptions, in declaration order.
+    fn error_responses() -> Vec<(u16, &'static str)>;
+}
+
+/// Register the `HTTPError` component (`{"detail": string}`, the body of
+/// `HttpError` responses) and return a `$ref` to it.
+pub fn http_error_schema(registry: &mut SchemaRegistryMut<'_>) -> Schema {
+    let mut properties = HashMap::new();
+    properties.insert("detail".to_string(), Schema::string());
+    registry.register(
+        "HTTPError",
+        Schema::object(properties, vec!["detail".to_string()]),
+    )
+}
+
+/// Macro support: lists the error responses of a handler's error type.
+///
+/// `(&&ErrorResponsesProbe::<E>::default()).error_responses()` picks
+/// [`ErrorResponsesProbeMatch`] when `E` implements [`ErrorResponses`] and
+/// falls back to [`ErrorResponsesProbeFallback`] (no responses) otherwise.
+#[doc(hidden)]
+pub struct ErrorResponsesProbe<E>(std::marker::PhantomData<E>);
+
+impl<E> Default for ErrorResponsesProbe<E> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait ErrorResponsesProbeMatch {
+    fn error_responses(&self) -> Vec<(u16, &'static str)>;
+}
+
+impl<E: ErrorResponses> ErrorResponsesProbeMatch for &ErrorResponsesProbe<E> {
+    fn error_responses(&self) -> Vec<(u16, &'static str)> {
+        E::error_responses()
+    }
+}
+
+#[doc(hidden)]
+pub trait ErrorResponsesProbeFallback {
+    fn error_responses(&self) -> Vec<(u16, &'static str)>;
+}
+
+impl<E> ErrorResponsesProbeFallback for ErrorResponsesProbe<E> {
+    fn error_responses(&self) -> Vec<(u16, &'static str)> {
+        Vec::new()
+    }
+}
+
+/// Schemas for a route's request body and responses, resolved against the
+/// document's component registry.
+///
+/// Route metadata only records type names. Proc-macro generated routes also
+/// produce these, so body types and the types nested in them are registered
+/// in `components/schemas` once, under collision-free names.
+#[derive(Debug, Clone, Default)]
+pub struct RouteSchemas {
+    /// Request body schema.
+    pub request_body: Option<Schema>,
+    /// Response body schemas by status code.
+    pub responses: Vec<(u16, Schema)>,
+}
+
+/// One parameter per property of an object schema, sorted by name.
+///
+/// Property examples and defaults become the parameter's `example` and
+/// schema `default`; non-object schemas yield no parameters.
+#[must_use]
+pub fn params_from_schema(schema: &Schema) -> Vec<fastapi_router::ParamInfo> {
+    use crate::schema::SchemaType;
+    use fastapi_router::{Converter, ParamInfo};
+
+    let Schema::Object(object) = schema else {
+        return Vec::new();
+    };
+    let mut names: Vec<&String> = object.properties.keys().collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let property = &object.properties[name];
+            let (converter, example, default, deprecated) = match property {
+                Schema::Primitive(p) => {
+                    let converter = match p.schema_type {
+                        SchemaType::Integer => Converter::Int,
+                        SchemaType::Number => Converter::Float,
+                        _ if p.format.as_deref() == Some("uuid") => Converter::Uuid,
+                        _ => Converter::Str,
+                    };
+                    (
+                        converter,
+                        p.example.as_ref(),
+                        p.default.as_ref(),
+                        p.deprecated,
+                    )
+                }
+                Schema::Array(a) => (
+                    Converter::Str,
+                    a.example.as_ref(),
+                    a.default.as_ref(),
+                    a.deprecated,
+                ),
+                Schema::Object(o) => (
+                    Converter::Str,
+                    o.example.as_ref(),
+                    o.default.as_ref(),
+                    o.deprecated,
+                ),
+                _ => (Converter::Str, None, None, false),
+            };
+
+            let mut param = ParamInfo::new(name.clone(), converter);
+            if let Ok(value) = serde_json::to_value(property) {
+                param = param.with_schema(value);
+            }
+            if let Some(example) = example {
+                param = param.with_example(example.clone());
+            }
+            if let Some(default) = default {
+                param = param.with_default(default.clone());
+            }
+            if deprecated {
+                param = param.deprecated();
+            }
+            if object.required.contains(name) {
+                param = param.required();
+            }
+            param
+        })
+        .collect()
+}
+
 /// Trait for types that provide parameter metadata.
 ///
 /// Implement this trait to enable automatic OpenAPI parameter documentation.
@@ -414,6 +769,38 @@ pub struct MediaType {
     /// Schema.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub schema: Option<Schema>,
+    /// Example payload.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    /// Named example payloads.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub examples: HashMap<String, Example>,
+}
+
+impl MediaType {
+    /// Create a media type with the given schema.
+    #[must_use]
+    pub fn new(schema: Schema) -> Self {
+        Self {
+            schema: Some(schema),
+            example: None,
+            examples: HashMap::new(),
+        }
+    }
+
+    /// Set the example payload.
+    #[must_use]
+    pub fn example(mut self, example: impl Into<serde_json::Value>) -> Self {
+        self.example = Some(example.into());
+        self
+    }
+
+    /// Add a named example payload.
+    #[must_use]
+    pub fn named_example(mut self, name: impl Into<String>, example: Example) -> Self {
+        self.examples.insert(name.into(), example);
+        self
+    }
 }
 
 /// Response definition.
@@ -421,43 +808,349 @@ pub struct MediaType {
 pub struct Response {
     /// Description.
     pub description: String,
+    /// Response headers by name.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, Header>,
     /// Content by media type.
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub content: HashMap<String, MediaType>,
 }
 
+/// Response header definition.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Header {
+    /// Description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the header is always sent.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub required: bool,
+    /// Deprecated flag.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deprecated: bool,
+    /// Schema of the header value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Schema>,
+}
+
+impl Header {
+    /// Create a header with the given value schema.
+    #[must_use]
+    pub fn new(schema: Schema) -> Self {
+        Self {
+            schema: Some(schema),
+            ..Self::default()
+        }
+    }
+
+    /// Set the description.
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
 /// Reusable components.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Components {
     /// Schema definitions.
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub schemas: HashMap<String, Schema>,
+    /// Security scheme definitions.
+    #[serde(
+        rename = "securitySchemes",
+        default,
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub security_schemes: HashMap<String, SecurityScheme>,
+}
+
+impl Components {
+    fn is_empty(&self) -> bool {
+        self.schemas.is_empty() && self.security_schemes.is_empty()
+    }
+}
+
+/// Security requirement: scheme name to required scopes.
+pub type SecurityRequirement = HashMap<String, Vec<String>>;
+
+/// Security scheme definition (`components.securitySchemes`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SecurityScheme {
+    /// API key sent in a header, query parameter or cookie.
+    #[serde(rename = "apiKey")]
+    ApiKey {
+        /// Header, query parameter or cookie name.
+        name: String,
+        /// Where the key is sent.
+        #[serde(rename = "in")]
+        location: ApiKeyLocation,
+        /// Description.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+    },
+    /// HTTP authentication (`bearer`, `basic`, ...).
+    #[serde(rename = "http")]
+    Http {
+        /// Authorization scheme name (RFC 7235).
+        scheme: String,
+        /// Hint for the bearer token format (e.g. `JWT`).
+        #[serde(
+            rename = "bearerFormat",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        bearer_format: Option<String>,
+        /// Description.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+    },
+    /// OAuth2 flows.
+    #[serde(rename = "oauth2")]
+    OAuth2 {
+        /// Supported flows, boxed to keep the other schemes small.
+        flows: Box<OAuthFlows>,
+        /// Description.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+    },
+    /// OpenID Connect discovery.
+    #[serde(rename = "openIdConnect")]
+    OpenIdConnect {
+        /// Discovery document URL.
+        #[serde(rename = "openIdConnectUrl")]
+        open_id_connect_url: String,
+        /// Description.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+    },
+}
+
+impl SecurityScheme {
+    /// HTTP bearer authentication.
+    #[must_use]
+    pub fn bearer() -> Self {
+        Self::Http {
+            scheme: "bearer".to_string(),
+            bearer_format: None,
+            description: None,
+        }
+    }
+
+    /// HTTP bearer authentication with a token format hint.
+    #[must_use]
+    pub fn bearer_with_format(format: impl Into<String>) -> Self {
+        Self::Http {
+            scheme: "bearer".to_string(),
+            bearer_format: Some(format.into()),
+            description: None,
+        }
+    }
+
+    /// HTTP basic authentication.
+    #[must_use]
+    pub fn basic() -> Self {
+        Self::Http {
+            scheme: "basic".to_string(),
+            bearer_format: None,
+            description: None,
+        }
+    }
+
+    /// API key authentication.
+    #[must_use]
+    pub fn api_key(name: impl Into<String>, location: ApiKeyLocation) -> Self {
+        Self::ApiKey {
+            name: name.into(),
+            location,
+            description: None,
+        }
+    }
+
+    /// OAuth2 with the given flows.
+    #[must_use]
+    pub fn oauth2(flows: OAuthFlows) -> Self {
+        Self::OAuth2 {
+            flows: Box::new(flows),
+            description: None,
+        }
+    }
+
+    /// OAuth2 resource owner password flow.
+    #[must_use]
+    pub fn oauth2_password(token_url: impl Into<String>) -> Self {
+        Self::oauth2(OAuthFlows {
+            password: Some(OAuthFlow::password(token_url)),
+            ..OAuthFlows::default()
+        })
+    }
+
+    /// OpenID Connect discovery.
+    #[must_use]
+    pub fn open_id_connect(url: impl Into<String>) -> Self {
+        Self::OpenIdConnect {
+            open_id_connect_url: url.into(),
+            description: None,
+        }
+    }
+
+    /// Set the description.
+    #[must_use]
+    pub fn with_description(mut self, text: impl Into<String>) -> Self {
+        let text = Some(text.into());
+        match &mut self {
+            Self::ApiKey { description, .. }
+            | Self::Http { description, .. }
+            | Self::OAuth2 { description, .. }
+            | Self::OpenIdConnect { description, .. } => *description = text,
+        }
+        self
+    }
+}
+
+/// Location of an API key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyLocation {
+    /// HTTP header.
+    Header,
+    /// Query parameter.
+    Query,
+    /// Cookie.
+    Cookie,
+}
+
+/// OAuth2 flows supported by a scheme.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuthFlows {
+    /// Implicit flow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub implicit: Option<OAuthFlow>,
+    /// Resource owner password flow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<OAuthFlow>,
+    /// Client credentials flow.
+    #[serde(
+        rename = "clientCredentials",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub client_credentials: Option<OAuthFlow>,
+    /// Authorization code flow.
+    #[serde(
+        rename = "authorizationCode",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub authorization_code: Option<OAuthFlow>,
+}
+
+/// A single OAuth2 flow.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuthFlow {
+    /// Authorization URL (implicit, authorization code).
+    #[serde(
+        rename = "authorizationUrl",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub authorization_url: Option<String>,
+    /// Token URL (password, client credentials, authorization code).
+    #[serde(rename = "tokenUrl", default, skip_serializing_if = "Option::is_none")]
+    pub token_url: Option<String>,
+    /// Refresh URL.
+    #[serde(
+        rename = "refreshUrl",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub refresh_url: Option<String>,
+    /// Available scopes and their descriptions.
+    #[serde(default)]
+    pub scopes: HashMap<String, String>,
+}
+
+impl OAuthFlow {
+    /// A flow that exchanges credentials at `token_url`.
+    #[must_use]
+    pub fn password(token_url: impl Into<String>) -> Self {
+        Self {
+            token_url: Some(token_url.into()),
+            ..Self::default()
+        }
+    }
+
+    /// A flow that redirects to `authorization_url` and exchanges the code at `token_url`.
+    #[must_use]
+    pub fn authorization_code(
+        authorization_url: impl Into<String>,
+        token_url: impl Into<String>,
+    ) -> Self {
+        Self {
+            authorization_url: Some(authorization_url.into()),
+            token_url: Some(token_url.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Set the refresh URL.
+    #[must_use]
+    pub fn refresh_url(mut self, url: impl Into<String>) -> Self {
+        self.refresh_url = Some(url.into());
+        self
+    }
+
+    /// Add a scope.
+    #[must_use]
+    pub fn scope(mut self, name: impl Into<String>, description: impl Into<String>) -> Self {
+        self.scopes.insert(name.into(), description.into());
+        self
+    }
 }
 
 /// Schema registry for `#/components/schemas`.
 ///
 /// This owns a schema map and provides `register()` helpers that return `$ref`s.
+/// Types are registered once (keyed by their Rust type name) together with
+/// their nested named types; see [`SchemaRegistryMut`] for how colliding
+/// names are resolved.
 #[derive(Debug, Default)]
 pub struct SchemaRegistry {
     schemas: HashMap<String, Schema>,
+    types: HashMap<&'static str, String>,
 }
 
 impl SchemaRegistry {
     /// Create an empty registry.
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            schemas: HashMap::new(),
-        }
+        Self::default()
     }
 
     /// Register `schema` under `name` if it doesn't already exist, and return a `$ref`.
     ///
     /// This does **not** overwrite an existing entry, which enables stable deduplication.
     pub fn register(&mut self, name: impl Into<String>, schema: Schema) -> Schema {
-        let name = name.into();
-        self.schemas.entry(name.clone()).or_insert(schema);
-        Schema::reference(&name)
+        self.registry().register(name, schema)
+    }
+
+    /// Register `T` under its [`JsonSchema::schema_name`] and return a `$ref`.
+    ///
+    /// Generic instantiations get their own component (`Page_Item`, `Page_User`).
+    /// Nested named types are registered as components of their own.
+    /// Types without a schema name are returned inline.
+    pub fn register_type<T: JsonSchema + ?Sized>(&mut self) -> Schema {
+        self.registry().register_type::<T>()
+    }
+
+    /// A mutable view of this registry, as taken by [`JsonSchema::schema_with`].
+    pub fn registry(&mut self) -> SchemaRegistryMut<'_> {
+        SchemaRegistryMut {
+            schemas: &mut self.schemas,
+            types: &mut self.types,
+        }
     }
 
     /// Consume the registry and return the underlying schema map.
@@ -468,8 +1161,15 @@ impl SchemaRegistry {
 }
 
 /// A mutable view into an existing component schema map.
+///
+/// Each Rust type is registered once; later registrations return a `$ref` to
+/// the same component. When two different types want the same component
+/// name (say `billing::Account` and `auth::Account`), the first keeps it and
+/// the second is qualified by its module (`auth_Account`), or numbered
+/// (`Account_2`) if that is taken too.
 pub struct SchemaRegistryMut<'a> {
     schemas: &'a mut HashMap<String, Schema>,
+    types: &'a mut HashMap<&'static str, String>,
 }
 
 impl SchemaRegistryMut<'_> {
@@ -479,9 +1179,70 @@ impl SchemaRegistryMut<'_> {
         self.schemas.entry(name.clone()).or_insert(schema);
         Schema::reference(&name)
     }
+
+    /// Register `T` under its [`JsonSchema::schema_name`] and return a `$ref`.
+    ///
+    /// Nested named types are registered as components of their own.
+    /// Types without a schema name are returned inline.
+    pub fn register_type<T: JsonSchema + ?Sized>(&mut self) -> Schema {
+        T::schema_ref(self)
+    }
+
+    /// Register the type `T` as a component named `name` (or a
+    /// disambiguated variant of it) and return a `$ref`.
+    ///
+    /// `build` produces the schema and runs only on the first registration
+    /// of `T`. The name is reserved beforehand, so recursive types refer to
+    /// themselves by `$ref`.
+    pub fn register_with<T: ?Sized>(
+        &mut self,
+        name: &str,
+        build: impl FnOnce(&mut SchemaRegistryMut<'_>) -> Schema,
+    ) -> Schema {
+        let type_name = std::any::type_name::<T>();
+        if let Some(existing) = self.types.get(type_name) {
+            return Schema::reference(existing);
+        }
+
+        let name = self.unique_name(name, type_name);
+        self.types.insert(type_name, name.clone());
+        self.schemas.insert(name.clone(), Schema::Boolean(true));
+        let schema = build(self);
+        self.schemas.insert(name.clone(), schema);
+        Schema::reference(&name)
+    }
+
+    /// `name` if free, else module-qualified, else numbered.
+    fn unique_name(&self, name: &str, type_name: &str) -> String {
+        if !self.schemas.contains_key(name) {
+            return name.to_string();
+        }
+
+        // `app::auth::Account<T>` -> `auth`.
+        let path = type_name.split('<').next().unwrap_or(type_name);
+        let module = path.rsplit("::").nth(1);
+        if let Some(module) = module {
+            let qualified = format!("{module}_{name}");
+            if !self.schemas.contains_key(&qualified) {
+                return qualified;
+            }
+        }
+
+        let mut n = 2;
+        loop {
+            let candidate = format!("{name}_{n}");
+            if !self.schemas.contains_key(&candidate) {
+                return candidate;
+            }
+            n += 1;
+        }
+    }
 }
 
 /// API tag.
+///
+/// Tags group operations in documentation UIs. The document's top-level
+/// `tags` array controls the group descriptions and their display order.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tag {
     /// Tag name.
@@ -489,6 +1250,92 @@ pub struct Tag {
     /// Tag description.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Link to additional documentation.
+    #[serde(
+        default,
+        rename = "externalDocs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub external_docs: Option<ExternalDocs>,
+    /// Display order. Not serialized: [`OpenApiBuilder::build`] lists ordered
+    /// tags first (ascending), then the rest in declaration order.
+    #[serde(skip)]
+    pub order: Option<i32>,
+}
+
+impl Tag {
+    /// Create a tag with only a name.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            external_docs: None,
+            order: None,
+        }
+    }
+
+    /// Set the description.
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the external documentation link.
+    #[must_use]
+    pub fn external_docs(mut self, docs: ExternalDocs) -> Self {
+        self.external_docs = Some(docs);
+        self
+    }
+
+    /// Set the display order.
+    #[must_use]
+    pub fn order(mut self, order: i32) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Overwrite the fields that `other` sets.
+    fn merge(&mut self, other: Tag) {
+        if other.description.is_some() {
+            self.description = other.description;
+        }
+        if other.external_docs.is_some() {
+            self.external_docs = other.external_docs;
+        }
+        if other.order.is_some() {
+            self.order = other.order;
+        }
+    }
+}
+
+/// External documentation link.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalDocs {
+    /// Documentation URL.
+    pub url: String,
+    /// Link description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl ExternalDocs {
+    /// Create a link to `url`.
+    #[must_use]
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            description: None,
+        }
+    }
+
+    /// Set the link description.
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
 }
 
 // ============================================================================
@@ -781,6 +1628,26 @@ mod serialization_tests {
         assert_eq!(doc.tags[0].name, "users");
     }
 
+    #[test]
+    fn trace_and_extension_methods_are_emitted() {
+        let propfind = fastapi_types::Method::custom("PROPFIND").unwrap();
+        let mut builder = OpenApiBuilder::new("Test API", "1.0.0");
+        builder.add_route(&fastapi_router::Route::new(
+            fastapi_types::Method::Trace,
+            "/files",
+        ));
+        builder.add_route(&fastapi_router::Route::new(propfind, "/files"));
+        let doc = builder.build();
+
+        let item = &doc.paths["/files"];
+        assert!(item.trace.is_some());
+        assert!(item.additional_operations.contains_key("PROPFIND"));
+        assert_eq!(item.operations().count(), 2);
+
+        let json = serde_json::to_value(&doc).unwrap();
+        assert!(json["paths"]["/files"]["additionalOperations"]["PROPFIND"].is_object());
+    }
+
     #[test]
     fn openapi_serializes_to_valid_json() {
         let doc = OpenApiBuilder::new("Test API", "1.0.0").build();
@@ -813,7 +1680,10 @@ pub struct OpenApiBuilder {
     servers: Vec<Server>,
     paths: HashMap<String, PathItem>,
     components: Components,
+    /// Component name assigned to each registered Rust type.
+    schema_types: HashMap<&'static str, String>,
     tags: Vec<Tag>,
+    webhooks: HashMap<String, PathItem>,
 }
 
 impl OpenApiBuilder {
@@ -832,7 +1702,9 @@ impl OpenApiBuilder {
             servers: Vec::new(),
             paths: HashMap::new(),
             components: Components::default(),
+            schema_types: HashMap::new(),
             tags: Vec::new(),
+            webhooks: HashMap::new(),
         }
     }
 
@@ -855,14 +1727,30 @@ impl OpenApiBuilder {
 
     /// Add a tag.
     #[must_use]
-    pub fn tag(mut self, name: impl Into<String>, description: Option<String>) -> Self {
-        self.tags.push(Tag {
-            name: name.into(),
-            description,
-        });
+    pub fn tag(self, name: impl Into<String>, description: Option<String>) -> Self {
+        let mut tag = Tag::new(name);
+        tag.description = description;
+        self.tag_def(tag)
+    }
+
+    /// Add a tag with full metadata.
+    ///
+    /// Declaring the same name twice merges the two definitions; fields set
+    /// by the later one win, and the tag keeps its first position.
+    #[must_use]
+    pub fn tag_def(mut self, tag: Tag) -> Self {
+        self.add_tag(tag);
         self
     }
 
+    /// Add a tag with full metadata (in-place variant of [`Self::tag_def`]).
+    pub fn add_tag(&mut self, tag: Tag) {
+        match self.tags.iter_mut().find(|t| t.name == tag.name) {
+            Some(existing) => existing.merge(tag),
+            None => self.tags.push(tag),
+        }
+    }
+
     /// Add a schema component.
     #[must_use]
     pub fn schema(mut self, name: impl Into<String>, schema: Schema) -> Self {
@@ -870,18 +1758,38 @@ impl OpenApiBuilder {
         self
     }
 
+    /// Add a security scheme component.
+    #[must_use]
+    pub fn security_scheme(mut self, name: impl Into<String>, scheme: SecurityScheme) -> Self {
+        self.components.security_schemes.insert(name.into(), scheme);
+        self
+    }
+
     /// Access the component schema registry for in-place registration.
     pub fn registry(&mut self) -> SchemaRegistryMut<'_> {
         SchemaRegistryMut {
             schemas: &mut self.components.schemas,
+            types: &mut self.schema_types,
         }
     }
 
     /// Add a metadata-rich route (from `fastapi-router`) as an OpenAPI operation.
     ///
     /// This is a convenience bridge used by integration tests and by higher-level crates.
-    #[allow(clippy::too_many_lines)]
     pub fn add_route(&mut self, route: &fastapi_router::Route) {
+        self.add_route_with_schemas(route, &RouteSchemas::default());
+    }
+
+    /// Add a route, using `schemas` for its request and response bodies.
+    ///
+    /// Bodies missing from `schemas` fall back to a `$ref` derived from the
+    /// type name in the route metadata.
+    #[allow(clippy::too_many_lines)]
+    pub fn add_route_with_schemas(
+        &mut self,
+        route: &fastapi_router::Route,
+        schemas: &RouteSchemas,
+    ) {
         use fastapi_router::Converter as RouteConverter;
 
         fn param_schema(conv: RouteConverter) -> Schema {
@@ -893,6 +1801,11 @@ impl OpenApiBuilder {
                     schema_type: crate::schema::SchemaType::String,
                     format: Some("uuid".to_string()),
                     nullable: false,
+                    example: None,
+                    examples: Vec::new(),
+                    default: None,
+                    constraints: crate::schema::SchemaConstraints::default(),
+                    deprecated: false,
                 }),
             }
         }
@@ -907,11 +1820,27 @@ impl OpenApiBuilder {
             description: route.description.clone(),
             tags: route.tags.clone(),
             deprecated: route.deprecated,
+            idempotent: route.idempotent,
+            security: route
+                .security
+                .iter()
+                .map(|req| HashMap::from([(req.scheme.clone(), req.scopes.clone())]))
+                .collect(),
             ..Default::default()
         };
 
-        // Path parameters.
-        for p in &route.path_params {
+        // Path parameters first, then query parameters.
+        let params = route
+            .path_params
+            .iter()
+            .map(|p| (p, ParameterLocation::Path, true))
+            .chain(
+                route
+                    .query_params
+                    .iter()
+                    .map(|p| (p, ParameterLocation::Query, p.required)),
+            );
+        for (p, location, required) in params {
             let mut examples = HashMap::new();
             for (name, value) in &p.examples {
                 examples.insert(
@@ -925,11 +1854,20 @@ impl OpenApiBuilder {
                 );
             }
 
+            let mut schema = p
+                .schema
+                .clone()
+                .and_then(|value| serde_json::from_value::<Schema>(value).ok())
+                .unwrap_or_else(|| param_schema(p.converter));
+            if let Some(default) = &p.default {
+                schema = schema.with_default(default.clone());
+            }
+
             op.parameters.push(Parameter {
                 name: p.name.clone(),
-                location: ParameterLocation::Path,
-                required: true,
-                schema: Some(param_schema(p.converter)),
+                location,
+                required,
+                schema: Some(schema),
                 title: p.title.clone(),
                 description: p.description.clone(),
                 deprecated: p.deprecated,
@@ -945,12 +1883,11 @@ impl OpenApiBuilder {
                 .clone()
                 .unwrap_or_else(|| "application/json".to_string());
             let mut content = HashMap::new();
-            content.insert(
-                content_type,
-                MediaType {
-                    schema: Some(Schema::reference(schema_name)),
-                },
-            );
+            let schema = schemas
+                .request_body
+                .clone()
+                .unwrap_or_else(|| schema_for_type_name(schema_name));
+            content.insert(content_type, MediaType::new(schema));
             op.request_body = Some(RequestBody {
                 required: route.request_body_required,
                 content,
@@ -964,35 +1901,43 @@ impl OpenApiBuilder {
             responses = default_responses();
         } else {
             for r in &route.responses {
+                let schema = schemas
+                    .responses
+                    .iter()
+                    .find(|(status, _)| *status == r.status)
+                    .map_or_else(|| schema_for_type_name(&r.schema_name), |(_, s)| s.clone());
                 let mut content = HashMap::new();
-                content.insert(
-                    "application/json".to_string(),
-                    MediaType {
-                        schema: Some(Schema::reference(&r.schema_name)),
-                    },
-                );
+                content.insert("application/json".to_string(), MediaType::new(schema));
                 responses.insert(
                     r.status.to_string(),
                     Response {
                         description: r.description.clone(),
+                        headers: HashMap::new(),
                         content,
                     },
                 );
             }
         }
+        for h in &route.response_headers {
+            let response = responses
+                .entry(h.status.to_string())
+                .or_insert_with(|| Response {
+                    description: "Response".to_string(),
+                    headers: HashMap::new(),
+                    content: HashMap::new(),
+                });
+            let mut header = Header::new(Schema::string());
+            if !h.description.is_empty() {
+                header = header.description(h.description.clone());
+            }
+            response.headers.insert(h.name.clone(), header);
+        }
         op.responses = responses;
 
-        let path_item = self.paths.entry(route.path.clone()).or_default();
-        match route.method.as_str() {
-            "GET" => path_item.get = Some(op),
-            "POST" => path_item.post = Some(op),
-            "PUT" => path_item.put = Some(op),
-            "DELETE" => path_item.delete = Some(op),
-            "PATCH" => path_item.patch = Some(op),
-            "OPTIONS" => path_item.options = Some(op),
-            "HEAD" => path_item.head = Some(op),
-            _ => {}
-        }
+        self.paths
+            .entry(route.path.clone())
+            .or_default()
+            .set_operation(route.method.as_str(), op);
     }
 
     /// Add multiple routes.
@@ -1019,6 +1964,7 @@ impl OpenApiBuilder {
     ///         responses: HashMap::from([
     ///             ("200".to_string(), Response {
     ///                 description: "Success".to_string(),
+    ///                 headers: HashMap::new(),
     ///                 content: HashMap::new(),
     ///             })
     ///         ]),
@@ -1032,23 +1978,64 @@ impl OpenApiBuilder {
         path: impl Into<String>,
         operation: Operation,
     ) -> Self {
-        let path = path.into();
-        let path_item = self.paths.entry(path).or_default();
-
-        match method.to_uppercase().as_str() {
-            "GET" => path_item.get = Some(operation),
-            "POST" => path_item.post = Some(operation),
-            "PUT" => path_item.put = Some(operation),
-            "DELETE" => path_item.delete = Some(operation),
-            "PATCH" => path_item.patch = Some(operation),
-            "OPTIONS" => path_item.options = Some(operation),
-            "HEAD" => path_item.head = Some(operation),
-            _ => {} // Ignore unknown methods
-        }
+        self.paths
+            .entry(path.into())
+            .or_default()
+            .set_operation(method, operation);
+        self
+    }
 
+    /// Document an outbound webhook.
+    ///
+    /// `name` is the event name (e.g. `order.created`); `operation` describes
+    /// the request sent to subscribers. Methods are handled as in
+    /// [`Self::operation`].
+    #[must_use]
+    pub fn webhook(mut self, name: impl Into<String>, method: &str, operation: Operation) -> Self {
+        self.webhooks
+            .entry(name.into())
+            .or_default()
+            .set_operation(method, operation);
         self
     }
 
+    /// Document a webhook that POSTs a JSON `T` payload to subscribers.
+    ///
+    /// `T` is registered as a component schema and referenced from the
+    /// request body. Subscribers are expected to acknowledge with a 2xx.
+    #[must_use]
+    pub fn webhook_payload<T: JsonSchema + ?Sized>(
+        mut self,
+        name: impl Into<String>,
+        summary: impl Into<String>,
+    ) -> Self {
+        let name = name.into();
+        let schema = self.registry().register_type::<T>();
+        let mut content = HashMap::new();
+        content.insert("application/json".to_string(), MediaType::new(schema));
+        let mut responses = HashMap::new();
+        responses.insert(
+            "200".to_string(),
+            Response {
+                description: "Event received".to_string(),
+                headers: HashMap::new(),
+                content: HashMap::new(),
+            },
+        );
+        let operation = Operation {
+            operation_id: Some(name.clone()),
+            summary: Some(summary.into()),
+            request_body: Some(RequestBody {
+                required: true,
+                content,
+                description: None,
+            }),
+            responses,
+            ..Default::default()
+        };
+        self.webhook(name, "POST", operation)
+    }
+
     /// Add a simple GET endpoint with default 200 response.
     #[must_use]
     pub fn get(self, path: impl Into<String>, operation_id: impl Into<String>) -> Self {
@@ -1145,19 +2132,149 @@ impl OpenApiBuilder {
     }
 
     /// Build the OpenAPI document.
+    ///
+    /// Tags used by operations but never declared are appended to the
+    /// top-level `tags` array in alphabetical order, so every group shows up.
     #[must_use]
-    pub fn build(self) -> OpenApi {
+    pub fn build(mut self) -> OpenApi {
+        let mut undeclared: Vec<String> = self
+            .paths
+            .values()
+            .flat_map(PathItem::operations)
+            .flat_map(|op| op.tags.iter())
+            .filter(|name| !self.tags.iter().any(|t| &t.name == *name))
+            .cloned()
+            .collect();
+        undeclared.sort();
+        undeclared.dedup();
+        self.tags.extend(undeclared.into_iter().map(Tag::new));
+        self.tags
+            .sort_by_key(|tag| (tag.order.is_none(), tag.order.unwrap_or(0)));
+
         OpenApi {
             openapi: "3.1.0".to_string(),
             info: self.info,
             servers: self.servers,
             paths: self.paths,
-            components: if self.components.schemas.is_empty() {
+            components: if self.components.is_empty() {
                 None
             } else {
                 Some(self.components)
             },
             tags: self.tags,
+            webhooks: self.webhooks,
         }
     }
 }
+
+#[cfg(test)]
+mod registry_tests {
+    use super::*;
+
+    mod billing {
+        use crate::{JsonSchema, Schema};
+
+        pub struct Account;
+
+        impl JsonSchema for Account {
+            fn schema() -> Schema {
+                Schema::string()
+            }
+
+            fn schema_name() -> Option<&'static str> {
+                Some("Account")
+            }
+        }
+    }
+
+    mod auth {
+        use crate::{JsonSchema, Schema};
+
+        pub struct Account;
+
+        impl JsonSchema for Account {
+            fn schema() -> Schema {
+                Schema::integer(None)
+            }
+
+            fn schema_name() -> Option<&'static str> {
+                Some("Account")
+            }
+        }
+    }
+
+    /// A linked list node that refers to itself.
+    struct Node;
+
+    impl JsonSchema for Node {
+        fn schema() -> Schema {
+            Schema::object(HashMap::new(), Vec::new())
+        }
+
+        fn schema_with(registry: &mut SchemaRegistryMut<'_>) -> Schema {
+            let mut properties = HashMap::new();
+            properties.insert("next".to_string(), Option::<Node>::schema_ref(registry));
+            Schema::object(properties, Vec::new())
+        }
+
+        fn schema_name() -> Option<&'static str> {
+            Some("Node")
+        }
+    }
+
+    fn reference(schema: &Schema) -> &str {
+        match schema {
+            Schema::Ref(r) => &r.reference,
+            other => panic!("expected a $ref, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn same_type_registers_once() {
+        let mut registry = SchemaRegistry::new();
+        let first = registry.register_type::<billing::Account>();
+        let second = registry.register_type::<billing::Account>();
+
+        assert_eq!(reference(&first), "#/components/schemas/Account");
+        assert_eq!(reference(&second), "#/components/schemas/Account");
+        assert_eq!(registry.into_schemas().len(), 1);
+    }
+
+    #[test]
+    fn colliding_names_are_module_qualified() {
+        let mut registry = SchemaRegistry::new();
+        let billing = registry.register_type::<billing::Account>();
+        let auth = registry.register_type::<auth::Account>();
+
+        assert_eq!(reference(&billing), "#/components/schemas/Account");
+        assert_eq!(reference(&auth), "#/components/schemas/auth_Account");
+
+        let schemas = registry.into_schemas();
+        assert!(matches!(schemas["Account"], Schema::Primitive(_)));
+        assert!(matches!(schemas["auth_Account"], Schema::Primitive(_)));
+    }
+
+    #[test]
+    fn collisions_fall_back_to_numbering() {
+        let mut registry = SchemaRegistry::new();
+        registry.register("Account", Schema::boolean());
+        registry.register("auth_Account", Schema::boolean());
+
+        let auth = registry.register_type::<auth::Account>();
+        assert_eq!(reference(&auth), "#/components/schemas/Account_2");
+    }
+
+    #[test]
+    fn recursive_types_refer_to_themselves() {
+        let mut registry = SchemaRegistry::new();
+        let node = registry.register_type::<Node>();
+        assert_eq!(reference(&node), "#/components/schemas/Node");
+
+        let schemas = registry.into_schemas();
+        let json = serde_json::to_value(&schemas["Node"]).unwrap();
+        assert_eq!(
+            json["properties"]["next"]["$ref"],
+            "#/components/schemas/Node"
+        );
+    }
+}
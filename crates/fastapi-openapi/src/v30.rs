@@ -0,0 +1,405 @@
+//! OpenAPI 3.0.x export.
+//!
+//! Many API gateways and client generators still reject 3.1 documents.
+//! [`OpenApi::to_v30`] rewrites the 3.1-only constructs this crate can emit
+//! into their closest 3.0 equivalents:
+//!
+//! | 3.1                                  | 3.0                                        |
+//! |--------------------------------------|--------------------------------------------|
+//! | `"type": ["string", "null"]`         | `"type": "string", "nullable": true`       |
+//! | `"oneOf": [{"$ref": R}, {"type": "null"}]` | `"allOf": [{"$ref": R}], "nullable": true` |
+//! | `"exclusiveMinimum": 5`              | `"minimum": 5, "exclusiveMinimum": true`   |
+//! | `"prefixItems": [A, B]`             | `"items": {"oneOf": [A, B]}`               |
+//! | `"const": 1`                         | `"enum": [1]`                              |
+//! | schema `"examples": [a, b]`          | `"example": a`                             |
+//! | top-level `webhooks`                 | `x-webhooks` extension                     |
+
+use serde_json::{Map, Value};
+
+use crate::spec::OpenApi;
+
+/// Version string written to downgraded documents.
+const V30_VERSION: &str = "3.0.3";
+
+/// Keywords whose value is a single subschema.
+const SUBSCHEMA_KEYS: &[&str] = &["items", "not", "additionalProperties"];
+
+/// Keywords whose value is a list of subschemas.
+const SUBSCHEMA_LIST_KEYS: &[&str] = &["oneOf", "anyOf", "allOf"];
+
+/// Keywords holding literal values that must never be rewritten.
+const LITERAL_KEYS: &[&str] = &["example", "examples", "default", "enum", "const"];
+
+impl OpenApi {
+    /// Export this document as OpenAPI 3.0.3 JSON.
+    ///
+    /// Type arrays with `null` become `nullable`, numeric `exclusiveMinimum`
+    /// / `exclusiveMaximum` become boolean flags next to `minimum` /
+    /// `maximum`, tuple `prefixItems` become `items: oneOf`, `const` becomes
+    /// a one-value `enum`, schema `examples` become a single `example`, and
+    /// `webhooks` move to the `x-webhooks` extension understood by ReDoc and
+    /// most codegen tools.
+    #[must_use]
+    pub fn to_v30(&self) -> Value {
+        let mut doc = serde_json::to_value(self).unwrap_or(Value::Null);
+        let Value::Object(root) = &mut doc else {
+            return doc;
+        };
+
+        root.insert("openapi".into(), Value::String(V30_VERSION.into()));
+        if let Some(webhooks) = root.remove("webhooks") {
+            root.insert("x-webhooks".into(), webhooks);
+        }
+
+        downgrade_document(&mut doc);
+        doc
+    }
+}
+
+/// Walk non-schema parts of the document and downgrade every schema found
+/// under a `schema` key or in `components.schemas`.
+fn downgrade_document(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if key == "schema" {
+                    downgrade_schema(child);
+                } else if key == "schemas" {
+                    if let Value::Object(schemas) = child {
+                        schemas.values_mut().for_each(downgrade_schema);
+                    }
+                } else if !LITERAL_KEYS.contains(&key.as_str()) {
+                    downgrade_document(child);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(downgrade_document),
+        _ => {}
+    }
+}
+
+/// Rewrite one JSON Schema (and its subschemas) into the 3.0 dialect.
+fn downgrade_schema(value: &mut Value) {
+    let Value::Object(schema) = value else {
+        return;
+    };
+
+    downgrade_prefix_items(schema);
+    if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+        properties.values_mut().for_each(downgrade_schema);
+    }
+    for key in SUBSCHEMA_KEYS {
+        if let Some(sub) = schema.get_mut(*key) {
+            downgrade_schema(sub);
+        }
+    }
+    for key in SUBSCHEMA_LIST_KEYS {
+        if let Some(Value::Array(subs)) = schema.get_mut(*key) {
+            subs.iter_mut().for_each(downgrade_schema);
+        }
+    }
+
+    downgrade_type(schema);
+    downgrade_null_variant(schema);
+    downgrade_exclusive_bound(schema, "exclusiveMinimum", "minimum", |new, old| new >= old);
+    downgrade_exclusive_bound(schema, "exclusiveMaximum", "maximum", |new, old| new <= old);
+
+    if let Some(value) = schema.remove("const") {
+        schema.insert("enum".into(), Value::Array(vec![value]));
+    }
+    if matches!(schema.get("examples"), Some(Value::Array(_))) {
+        if let Some(Value::Array(examples)) = schema.remove("examples") {
+            if let Some(first) = examples.into_iter().next() {
+                schema.entry("example").or_insert(first);
+            }
+        }
+    }
+}
+
+/// `"prefixItems": [A, B]` → `"items": {"oneOf": [A, B]}`; 3.0 has no
+/// positional item schemas, so tuples keep only their length bounds.
+fn downgrade_prefix_items(schema: &mut Map<String, Value>) {
+    let Some(Value::Array(prefix)) = schema.remove("prefixItems") else {
+        return;
+    };
+    let items = Map::from_iter([("oneOf".to_string(), Value::Array(prefix))]);
+    schema.insert("items".into(), Value::Object(items));
+}
+
+/// `"type": ["string", "null"]` → `"type": "string", "nullable": true`.
+///
+/// Several non-null types become an `anyOf` of single-type schemas.
+fn downgrade_type(schema: &mut Map<String, Value>) {
+    let types = match schema.get("type") {
+        Some(Value::Array(types)) => types.clone(),
+        Some(Value::String(t)) if t == "null" => {
+            schema.remove("type");
+            schema.insert("nullable".into(), Value::Bool(true));
+            schema.insert("enum".into(), Value::Array(vec![Value::Null]));
+            return;
+        }
+        _ => return,
+    };
+
+    let total = types.len();
+    let mut non_null: Vec<Value> = types.into_iter().filter(|t| *t != "null").collect();
+    let nullable = non_null.len() < total;
+    schema.remove("type");
+    match non_null.len() {
+        0 => {
+            schema.insert("enum".into(), Value::Array(vec![Value::Null]));
+        }
+        1 => {
+            schema.insert("type".into(), non_null.remove(0));
+        }
+        _ => {
+            let variants = non_null
+                .into_iter()
+                .map(|t| Value::Object(Map::from_iter([("type".to_string(), t)])))
+                .collect();
+            schema.insert("anyOf".into(), Value::Array(variants));
+        }
+    }
+    if nullable {
+        schema.insert("nullable".into(), Value::Bool(true));
+    }
+}
+
+/// Drop `{"type": "null"}` alternatives from `oneOf` / `anyOf` and mark the
+/// schema `nullable` instead.
+///
+/// A single remaining `$ref` is wrapped in `allOf`, since 3.0 ignores
+/// siblings of `$ref`.
+fn downgrade_null_variant(schema: &mut Map<String, Value>) {
+    for key in ["oneOf", "anyOf"] {
+        let Some(Value::Array(variants)) = schema.get_mut(key) else {
+            continue;
+        };
+        let before = variants.len();
+        variants.retain(|v| !is_null_schema(v));
+        if variants.len() == before {
+            continue;
+        }
+        schema.insert("nullable".into(), Value::Bool(true));
+
+        let Some(Value::Array(variants)) = schema.get(key) else {
+            continue;
+        };
+        if variants.len() == 1 {
+            let Some(Value::Array(mut variants)) = schema.remove(key) else {
+                continue;
+            };
+            let only = variants.remove(0);
+            if only.get("$ref").is_some() {
+                schema.insert("allOf".into(), Value::Array(vec![only]));
+            } else if let Value::Object(fields) = only {
+                for (k, v) in fields {
+                    schema.entry(k).or_insert(v);
+                }
+            }
+        }
+    }
+}
+
+fn is_null_schema(value: &Value) -> bool {
+    // After `downgrade_type`, `{"type": "null"}` has become this shape.
+    value.get("type").is_some_and(|t| *t == "null")
+        || (value.get("nullable") == Some(&Value::Bool(true))
+            && value.get("enum") == Some(&Value::Array(vec![Value::Null])))
+}
+
+/// Numeric exclusive bound → inclusive bound plus a boolean flag.
+///
+/// If an inclusive bound is also present, the stricter of the two is kept.
+fn downgrade_exclusive_bound(
+    schema: &mut Map<String, Value>,
+    exclusive_key: &str,
+    inclusive_key: &str,
+    stricter: fn(f64, f64) -> bool,
+) {
+    let Some(bound) = schema.get(exclusive_key).and_then(Value::as_f64) else {
+        return;
+    };
+    let exclusive = schema.remove(exclusive_key).unwrap_or(Value::Null);
+    let existing = schema.get(inclusive_key).and_then(Value::as_f64);
+    if existing.is_none_or(|old| stricter(bound, old)) {
+        schema.insert(inclusive_key.into(), exclusive);
+        schema.insert(exclusive_key.into(), Value::Bool(true));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{JsonSchema, Schema, SchemaConstraints};
+    use crate::spec::{MediaType, OpenApiBuilder, Operation, RequestBody};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn downgraded(schema: Value) -> Value {
+        let mut schema = schema;
+        downgrade_schema(&mut schema);
+        schema
+    }
+
+    #[test]
+    fn version_is_rewritten() {
+        let doc = OpenApiBuilder::new("API", "1.0.0").build().to_v30();
+        assert_eq!(doc["openapi"], "3.0.3");
+    }
+
+    #[test]
+    fn nullable_type_arrays_become_nullable_flag() {
+        assert_eq!(
+            downgraded(json!({"type": ["string", "null"]})),
+            json!({"type": "string", "nullable": true})
+        );
+        assert_eq!(
+            downgraded(json!({"type": ["integer", "string"]})),
+            json!({"anyOf": [{"type": "integer"}, {"type": "string"}]})
+        );
+    }
+
+    #[test]
+    fn null_alternatives_are_dropped_from_one_of() {
+        assert_eq!(
+            downgraded(json!({"oneOf": [{"$ref": "#/components/schemas/User"}, {"type": "null"}]})),
+            json!({"allOf": [{"$ref": "#/components/schemas/User"}], "nullable": true})
+        );
+        assert_eq!(
+            downgraded(json!({"anyOf": [{"type": "integer"}, {"type": "null"}]})),
+            json!({"type": "integer", "nullable": true})
+        );
+    }
+
+    #[test]
+    fn exclusive_bounds_become_boolean_flags() {
+        assert_eq!(
+            downgraded(
+                json!({"type": "number", "exclusiveMinimum": 0.0, "exclusiveMaximum": 10.0})
+            ),
+            json!({
+                "type": "number",
+                "minimum": 0.0,
+                "exclusiveMinimum": true,
+                "maximum": 10.0,
+                "exclusiveMaximum": true,
+            })
+        );
+        // An inclusive bound that is already stricter wins.
+        assert_eq!(
+            downgraded(json!({"type": "number", "minimum": 5.0, "exclusiveMinimum": 1.0})),
+            json!({"type": "number", "minimum": 5.0})
+        );
+    }
+
+    #[test]
+    fn prefix_items_become_one_of_items() {
+        assert_eq!(
+            downgraded(json!({
+                "type": "array",
+                "prefixItems": [{"type": "integer"}, {"type": ["string", "null"]}],
+                "items": false,
+                "minItems": 2,
+            })),
+            json!({
+                "type": "array",
+                "items": {"oneOf": [{"type": "integer"}, {"type": "string", "nullable": true}]},
+                "minItems": 2,
+            })
+        );
+    }
+
+    #[test]
+    fn const_and_examples_are_rewritten() {
+        assert_eq!(
+            downgraded(json!({"const": "v1", "examples": ["a", "b"]})),
+            json!({"enum": ["v1"], "example": "a"})
+        );
+    }
+
+    #[test]
+    fn literals_and_property_names_are_left_alone() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "const": {"type": ["boolean", "null"]},
+            },
+            "example": {"type": ["not", "a", "schema"]},
+        });
+        assert_eq!(
+            downgraded(schema),
+            json!({
+                "type": "object",
+                "properties": {"const": {"type": "boolean", "nullable": true}},
+                "example": {"type": ["not", "a", "schema"]},
+            })
+        );
+    }
+
+    #[test]
+    fn document_schemas_and_webhooks_are_downgraded() {
+        let constrained = Schema::number(None).with_constraints(SchemaConstraints {
+            exclusive_minimum: Some(0.0),
+            ..SchemaConstraints::default()
+        });
+        let mut properties = HashMap::new();
+        properties.insert("price".to_string(), constrained.clone());
+        let mut content = HashMap::new();
+        content.insert("application/json".to_string(), MediaType::new(constrained));
+
+        let mut operation = Operation::default();
+        operation.request_body = Some(RequestBody {
+            required: true,
+            content,
+            description: None,
+        });
+        let doc = OpenApiBuilder::new("API", "1.0.0")
+            .schema("Item", Schema::object(properties, Vec::new()))
+            .webhook("itemCreated", "POST", operation)
+            .build()
+            .to_v30();
+
+        assert!(doc.get("webhooks").is_none());
+        let price = &doc["components"]["schemas"]["Item"]["properties"]["price"];
+        assert_eq!(price["exclusiveMinimum"], true);
+        assert_eq!(price["minimum"], 0.0);
+        let hook = &doc["x-webhooks"]["itemCreated"]["post"]["requestBody"]["content"]["application/json"]
+            ["schema"];
+        assert_eq!(hook["exclusiveMinimum"], true);
+    }
+
+    /// A positive price: exclusive bound and examples are 3.1 constructs.
+    struct Price;
+
+    impl JsonSchema for Price {
+        fn schema() -> Schema {
+            Schema::number(None)
+                .with_constraints(SchemaConstraints {
+                    exclusive_minimum: Some(0.0),
+                    ..SchemaConstraints::default()
+                })
+                .with_examples([9.99, 19.99])
+        }
+    }
+
+    #[test]
+    fn map_value_schemas_are_downgraded() {
+        let mut properties = HashMap::new();
+        properties.insert(
+            "prices".to_string(),
+            HashMap::<String, Option<Price>>::schema(),
+        );
+        let doc = OpenApiBuilder::new("API", "1.0.0")
+            .schema("Catalog", Schema::object(properties, Vec::new()))
+            .build()
+            .to_v30();
+
+        let value = &doc["components"]["schemas"]["Catalog"]["properties"]["prices"]["additionalProperties"];
+        assert_eq!(value["nullable"], true);
+        assert_eq!(value["minimum"], 0.0);
+        assert_eq!(value["exclusiveMinimum"], true);
+        assert_eq!(value["example"], 9.99);
+        assert!(value.get("examples").is_none());
+    }
+}
@@ -4,7 +4,7 @@
 //! Keeping macro-derive tests here creates a dependency cycle. These tests validate
 //! the underlying schema types and serialization behavior instead.
 
-use fastapi_openapi::{EnumSchema, OneOfSchema, Schema, SchemaType};
+use fastapi_openapi::{Discriminator, EnumSchema, OneOfSchema, Schema, SchemaType};
 
 #[test]
 fn enum_schema_serializes_as_string_enum() {
@@ -25,6 +25,7 @@ fn enum_schema_serializes_as_string_enum() {
 fn oneof_schema_serializes() {
     let schema = Schema::OneOf(OneOfSchema {
         one_of: vec![Schema::reference("Int"), Schema::reference("Text")],
+        discriminator: None,
     });
 
     let json = serde_json::to_string(&schema).unwrap();
@@ -32,3 +33,58 @@ fn oneof_schema_serializes() {
     assert!(json.contains(r"#/components/schemas/Int"), "{json}");
     assert!(json.contains(r"#/components/schemas/Text"), "{json}");
 }
+
+#[test]
+fn oneof_without_discriminator_omits_field() {
+    let schema = Schema::one_of(vec![Schema::string(), Schema::boolean()]);
+
+    let json = serde_json::to_string(&schema).unwrap();
+    assert!(!json.contains("discriminator"), "{json}");
+}
+
+#[test]
+fn oneof_discriminator_serializes_property_name_and_mapping() {
+    let schema = Schema::OneOf(OneOfSchema {
+        one_of: vec![Schema::reference("Cat"), Schema::reference("Dog")],
+        discriminator: Some(
+            Discriminator::new("kind")
+                .mapping("cat", "Cat")
+                .mapping("dog", "Dog"),
+        ),
+    });
+
+    let json = serde_json::to_value(&schema).unwrap();
+    assert_eq!(json["discriminator"]["propertyName"], "kind");
+    assert_eq!(
+        json["discriminator"]["mapping"]["cat"],
+        "#/components/schemas/Cat"
+    );
+    assert_eq!(
+        json["discriminator"]["mapping"]["dog"],
+        "#/components/schemas/Dog"
+    );
+}
+
+#[test]
+fn tagged_oneof_helper_sets_discriminator() {
+    let schema = Schema::one_of_with_discriminator(
+        vec![Schema::reference("Cat"), Schema::reference("Dog")],
+        "type",
+    );
+
+    let json = serde_json::to_value(&schema).unwrap();
+    assert_eq!(json["discriminator"]["propertyName"], "type");
+    assert!(json["discriminator"].get("mapping").is_none());
+}
+
+#[test]
+fn allof_schema_round_trips() {
+    let schema = Schema::all_of(vec![Schema::reference("Cat"), Schema::string()]);
+
+    let json = serde_json::to_value(&schema).unwrap();
+    assert_eq!(json["allOf"][0]["$ref"], "#/components/schemas/Cat");
+    let Schema::AllOf(parsed) = serde_json::from_value(json).unwrap() else {
+        panic!("expected allOf schema");
+    };
+    assert_eq!(parsed.all_of.len(), 2);
+}
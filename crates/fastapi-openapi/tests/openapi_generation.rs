@@ -5,9 +5,12 @@
 //! - Schema deduplication
 //! - Request body and response handling
 //! - Security scheme integration
+//! - Webhooks
 //! - Validation against OpenAPI 3.1 spec
 
-use fastapi_openapi::{OpenApiBuilder, ParameterLocation, Schema, SchemaRegistry};
+use fastapi_openapi::{
+    ExternalDocs, OpenApiBuilder, ParameterLocation, RouteSchemas, Schema, SchemaRegistry, Tag,
+};
 use fastapi_router::Route;
 use fastapi_types::Method;
 
@@ -155,6 +158,75 @@ mod path_generation {
     }
 }
 
+// ============================================================================
+// QUERY PARAMETER TESTS
+// ============================================================================
+
+mod query_parameters {
+    use super::*;
+    use fastapi_openapi::{JsonSchema, params_from_schema};
+    use fastapi_router::{Converter, ParamInfo};
+    use std::collections::HashMap;
+
+    struct ListParams;
+
+    impl JsonSchema for ListParams {
+        fn schema() -> Schema {
+            let mut properties = HashMap::new();
+            properties.insert("q".to_string(), Schema::string().with_example("rust"));
+            properties.insert(
+                "limit".to_string(),
+                Schema::integer(Some("int32")).with_default(20),
+            );
+            Schema::object(properties, vec!["q".to_string()])
+        }
+    }
+
+    #[test]
+    fn query_params_carry_default_and_example() {
+        let route = Route::new(Method::Get, "/items")
+            .query_params(params_from_schema(&ListParams::schema()));
+
+        let mut builder = OpenApiBuilder::new("Test API", "1.0.0");
+        builder.add_route(&route);
+        let doc = builder.build();
+        let json = serde_json::to_value(&doc.paths["/items"]).unwrap();
+        let params = json["get"]["parameters"].as_array().unwrap();
+
+        assert_eq!(params[0]["name"], "limit");
+        assert_eq!(params[0]["in"], "query");
+        assert_eq!(params[0]["required"], false);
+        assert_eq!(params[0]["schema"]["type"], "integer");
+        assert_eq!(params[0]["schema"]["default"], 20);
+
+        assert_eq!(params[1]["name"], "q");
+        assert_eq!(params[1]["required"], true);
+        assert_eq!(params[1]["example"], "rust");
+        assert_eq!(params[1]["schema"]["type"], "string");
+    }
+
+    #[test]
+    fn path_param_docs_add_example_and_default() {
+        let route = Route::new(Method::Get, "/items/{id:int}").path_param_docs([ParamInfo::new(
+            "id",
+            Converter::Str,
+        )
+        .with_example(serde_json::json!(7))
+        .with_default(serde_json::json!(1))]);
+
+        let mut builder = OpenApiBuilder::new("Test API", "1.0.0");
+        builder.add_route(&route);
+        let doc = builder.build();
+        let json = serde_json::to_value(&doc.paths["/items/{id:int}"]).unwrap();
+        let param = &json["get"]["parameters"][0];
+
+        assert_eq!(param["in"], "path");
+        assert_eq!(param["example"], 7);
+        assert_eq!(param["schema"]["type"], "integer");
+        assert_eq!(param["schema"]["default"], 1);
+    }
+}
+
 // ============================================================================
 // SCHEMA DEDUPLICATION TESTS
 // ============================================================================
@@ -258,6 +330,28 @@ mod request_body {
         assert!(json.contains("CreateUserRequest"));
     }
 
+    #[test]
+    fn resolved_schemas_replace_type_name_refs() {
+        let route = Route::new(Method::Post, "/users")
+            .operation_id("create_user")
+            .request_body("CreateUserRequest", "application/json", true);
+        let schemas = RouteSchemas {
+            request_body: Some(Schema::reference("admin_CreateUserRequest")),
+            responses: Vec::new(),
+        };
+
+        let mut builder = OpenApiBuilder::new("Test API", "1.0.0");
+        builder.add_route_with_schemas(&route, &schemas);
+        let doc = builder.build();
+
+        let op = doc.paths["/users"].post.as_ref().unwrap();
+        let json = serde_json::to_value(op.request_body.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            json["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/admin_CreateUserRequest"
+        );
+    }
+
     #[test]
     fn route_with_form_request_body() {
         let route = Route::new(Method::Post, "/upload")
@@ -322,6 +416,148 @@ mod responses {
         assert!(op.responses.contains_key("200"));
         assert_eq!(op.responses["200"].description, "Successful response");
     }
+
+    fn response_schema(route: &Route, status: &str) -> serde_json::Value {
+        let mut builder = OpenApiBuilder::new("Test API", "1.0.0");
+        builder.add_route(route);
+        let doc = builder.build();
+        let op = doc.paths[&route.path].get.as_ref().unwrap();
+        let media = &op.responses[status].content["application/json"];
+        serde_json::to_value(media.schema.as_ref().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn named_response_references_component() {
+        let route = Route::new(Method::Get, "/items/{id}").response(200, "Item", "Item found");
+        let schema = response_schema(&route, "200");
+        assert_eq!(schema["$ref"], "#/components/schemas/Item");
+    }
+
+    #[test]
+    fn container_response_expands_inline() {
+        let route = Route::new(Method::Get, "/items").response(200, "Vec<Item>", "All items");
+        let schema = response_schema(&route, "200");
+        assert_eq!(schema["type"], "array");
+        assert_eq!(schema["items"]["$ref"], "#/components/schemas/Item");
+
+        let route = Route::new(Method::Get, "/name").response(200, "String", "A name");
+        let schema = response_schema(&route, "200");
+        assert_eq!(schema["type"], "string");
+    }
+
+    #[test]
+    fn generic_response_references_instantiation() {
+        let route =
+            Route::new(Method::Get, "/users").response(200, "Page<User>", "A page of users");
+        let schema = response_schema(&route, "200");
+        assert_eq!(schema["$ref"], "#/components/schemas/Page_User");
+    }
+
+    #[test]
+    fn response_headers_are_documented() {
+        let route = Route::new(Method::Get, "/items")
+            .response(200, "Vec<Item>", "All items")
+            .response(404, "NotFound", "Not found")
+            .response_header(200, "X-Total-Count", "Number of items")
+            .response_header(429, "Retry-After", "");
+
+        let mut builder = OpenApiBuilder::new("Test API", "1.0.0");
+        builder.add_route(&route);
+        let json = serde_json::to_value(builder.build()).unwrap();
+        let responses = &json["paths"]["/items"]["get"]["responses"];
+
+        let total = &responses["200"]["headers"]["X-Total-Count"];
+        assert_eq!(total["description"], "Number of items");
+        assert_eq!(total["schema"]["type"], "string");
+        assert!(responses["404"].get("headers").is_none());
+        // A header on an undeclared status still documents that response.
+        assert!(
+            responses["429"]["headers"]["Retry-After"]
+                .get("description")
+                .is_none()
+        );
+        assert!(responses["429"].get("content").is_none());
+    }
+}
+
+// ============================================================================
+// SECURITY TESTS
+// ============================================================================
+
+mod security {
+    use super::*;
+    use fastapi_openapi::{ApiKeyLocation, OAuthFlow, OAuthFlows, SecurityScheme};
+
+    #[test]
+    fn security_schemes_serialize_by_type() {
+        let doc = OpenApiBuilder::new("Test API", "1.0.0")
+            .security_scheme("bearer", SecurityScheme::bearer_with_format("JWT"))
+            .security_scheme("basic", SecurityScheme::basic())
+            .security_scheme(
+                "key",
+                SecurityScheme::api_key("api_key", ApiKeyLocation::Query)
+                    .with_description("Partner key"),
+            )
+            .security_scheme(
+                "oauth",
+                SecurityScheme::oauth2(OAuthFlows {
+                    authorization_code: Some(
+                        OAuthFlow::authorization_code("/authorize", "/token")
+                            .scope("read", "Read access"),
+                    ),
+                    ..OAuthFlows::default()
+                }),
+            )
+            .security_scheme(
+                "oidc",
+                SecurityScheme::open_id_connect("https://id.example.com/.well-known"),
+            )
+            .build();
+
+        let json = serde_json::to_value(&doc).unwrap();
+        let schemes = &json["components"]["securitySchemes"];
+        assert_eq!(schemes["bearer"]["type"], "http");
+        assert_eq!(schemes["bearer"]["scheme"], "bearer");
+        assert_eq!(schemes["bearer"]["bearerFormat"], "JWT");
+        assert_eq!(schemes["basic"]["scheme"], "basic");
+        assert_eq!(schemes["key"]["type"], "apiKey");
+        assert_eq!(schemes["key"]["in"], "query");
+        assert_eq!(schemes["key"]["description"], "Partner key");
+        let code = &schemes["oauth"]["flows"]["authorizationCode"];
+        assert_eq!(code["authorizationUrl"], "/authorize");
+        assert_eq!(code["tokenUrl"], "/token");
+        assert_eq!(code["scopes"]["read"], "Read access");
+        assert_eq!(schemes["oidc"]["type"], "openIdConnect");
+        assert_eq!(
+            schemes["oidc"]["openIdConnectUrl"],
+            "https://id.example.com/.well-known"
+        );
+        assert!(json["components"].get("schemas").is_none());
+    }
+
+    #[test]
+    fn route_security_becomes_operation_requirements() {
+        let route = Route::new(Method::Post, "/items")
+            .security("oauth", vec!["write:items"])
+            .security_scheme("key");
+
+        let mut builder = OpenApiBuilder::new("Test API", "1.0.0");
+        builder.add_route(&route);
+        let doc = builder.build();
+
+        let op = doc.paths["/items"].post.as_ref().unwrap();
+        assert_eq!(op.security.len(), 2);
+        assert_eq!(op.security[0]["oauth"], vec!["write:items".to_string()]);
+        assert!(op.security[1]["key"].is_empty());
+    }
+
+    #[test]
+    fn security_scheme_round_trips() {
+        let scheme = SecurityScheme::oauth2_password("/token");
+        let json = serde_json::to_string(&scheme).unwrap();
+        let back: SecurityScheme = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, scheme);
+    }
 }
 
 // ============================================================================
@@ -361,6 +597,24 @@ mod metadata {
         assert!(op.deprecated);
     }
 
+    #[test]
+    fn idempotent_flag_becomes_extension() {
+        let route = Route::new(Method::Post, "/jobs")
+            .operation_id("submit_job")
+            .idempotent();
+
+        let mut builder = OpenApiBuilder::new("Test API", "1.0.0");
+        builder.add_route(&route);
+        let json = serde_json::to_value(builder.build()).unwrap();
+
+        assert_eq!(json["paths"]["/jobs"]["post"]["x-idempotent"], true);
+
+        let mut builder = OpenApiBuilder::new("Test API", "1.0.0");
+        builder.add_route(&Route::new(Method::Post, "/jobs"));
+        let json = serde_json::to_value(builder.build()).unwrap();
+        assert!(json["paths"]["/jobs"]["post"].get("x-idempotent").is_none());
+    }
+
     #[test]
     fn empty_operation_id_becomes_none() {
         let route = Route::new(Method::Get, "/test").operation_id("");
@@ -391,6 +645,31 @@ mod metadata {
         assert_eq!(doc.servers[0].url, "https://api.example.com");
         assert_eq!(doc.tags.len(), 2);
     }
+
+    #[test]
+    fn tag_definitions_merge_and_sort() {
+        let mut builder = OpenApiBuilder::new("Test API", "1.0.0")
+            .tag("users", None)
+            .tag_def(Tag::new("admin").order(1))
+            .tag_def(Tag::new("health").order(-1))
+            .tag_def(
+                Tag::new("users")
+                    .description("User management")
+                    .external_docs(ExternalDocs::new("https://example.com").description("Guide")),
+            );
+        builder.add_route(&Route::new(Method::Get, "/z").tag("zeta").tag("users"));
+        builder.add_route(&Route::new(Method::Get, "/b").tag("beta"));
+        let doc = builder.build();
+
+        let names: Vec<_> = doc.tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["health", "admin", "users", "beta", "zeta"]);
+
+        let users = &doc.tags[2];
+        assert_eq!(users.description.as_deref(), Some("User management"));
+        let json = serde_json::to_value(users).unwrap();
+        assert_eq!(json["externalDocs"]["url"], "https://example.com");
+        assert_eq!(json["externalDocs"]["description"], "Guide");
+    }
 }
 
 // ============================================================================
@@ -587,4 +866,125 @@ mod schema_example_tests {
         assert_eq!(json["value"]["name"], "Alice");
         assert_eq!(json["value"]["age"], 30);
     }
+
+    #[test]
+    fn schema_example_serializes_on_primitives_and_objects() {
+        use fastapi_openapi::Schema;
+
+        let schema = Schema::integer(Some("int32")).with_example(42);
+        let json = serde_json::to_value(&schema).unwrap();
+        assert_eq!(json["example"], 42);
+
+        let schema = Schema::string().with_examples(["a", "b"]);
+        let json = serde_json::to_value(&schema).unwrap();
+        assert_eq!(json["examples"], serde_json::json!(["a", "b"]));
+        assert!(json.get("example").is_none());
+
+        let schema = Schema::reference("User").with_example(1);
+        let json = serde_json::to_value(&schema).unwrap();
+        assert!(json.get("example").is_none());
+    }
+
+    #[test]
+    fn media_type_examples_serialize() {
+        use fastapi_openapi::{MediaType, Schema};
+
+        let media = MediaType::new(Schema::reference("User"))
+            .example(serde_json::json!({"name": "Alice"}))
+            .named_example(
+                "minimal",
+                Example {
+                    summary: Some("Only required fields".to_string()),
+                    description: None,
+                    value: Some(serde_json::json!({"name": "Bob"})),
+                    external_value: None,
+                },
+            );
+
+        let json = serde_json::to_value(&media).unwrap();
+        assert_eq!(json["schema"]["$ref"], "#/components/schemas/User");
+        assert_eq!(json["example"]["name"], "Alice");
+        assert_eq!(json["examples"]["minimal"]["value"]["name"], "Bob");
+
+        let parsed: MediaType = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.examples.len(), 1);
+    }
+}
+
+// ============================================================================
+// WEBHOOK TESTS
+// ============================================================================
+
+mod webhooks {
+    use fastapi_openapi::{JsonSchema, OpenApi, OpenApiBuilder, Operation, Schema};
+
+    struct OrderCreated;
+
+    impl JsonSchema for OrderCreated {
+        fn schema() -> Schema {
+            Schema::object(
+                [("order_id".to_string(), Schema::integer(Some("int64")))]
+                    .into_iter()
+                    .collect(),
+                vec!["order_id".to_string()],
+            )
+        }
+
+        fn schema_name() -> Option<&'static str> {
+            Some("OrderCreated")
+        }
+    }
+
+    #[test]
+    fn webhook_payload_registers_schema_and_post_operation() {
+        let spec = OpenApiBuilder::new("Shop", "1.0.0")
+            .webhook_payload::<OrderCreated>("order.created", "Sent when an order is placed")
+            .build();
+
+        let json = serde_json::to_value(&spec).unwrap();
+        let post = &json["webhooks"]["order.created"]["post"];
+        assert_eq!(post["operationId"], "order.created");
+        assert_eq!(post["summary"], "Sent when an order is placed");
+        assert_eq!(post["requestBody"]["required"], true);
+        assert_eq!(
+            post["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/OrderCreated"
+        );
+        assert!(json["components"]["schemas"]["OrderCreated"].is_object());
+        assert!(json.get("paths").is_none());
+    }
+
+    #[test]
+    fn webhook_with_custom_operation() {
+        let spec = OpenApiBuilder::new("Shop", "1.0.0")
+            .webhook(
+                "order.deleted",
+                "delete",
+                Operation {
+                    summary: Some("Order removed".to_string()),
+                    ..Default::default()
+                },
+            )
+            .build();
+
+        let item = &spec.webhooks["order.deleted"];
+        assert!(item.post.is_none());
+        assert_eq!(
+            item.delete.as_ref().unwrap().summary.as_deref(),
+            Some("Order removed")
+        );
+    }
+
+    #[test]
+    fn webhooks_omitted_when_empty_and_round_trip() {
+        let spec = OpenApiBuilder::new("Shop", "1.0.0").build();
+        let json = serde_json::to_value(&spec).unwrap();
+        assert!(json.get("webhooks").is_none());
+
+        let spec = OpenApiBuilder::new("Shop", "1.0.0")
+            .webhook_payload::<OrderCreated>("order.created", "Order placed")
+            .build();
+        let parsed: OpenApi = serde_json::from_str(&serde_json::to_string(&spec).unwrap()).unwrap();
+        assert!(parsed.webhooks["order.created"].post.is_some());
+    }
 }
@@ -0,0 +1,261 @@
+//! Load generation report component.
+//!
+//! Renders the result of a benchmark run: throughput, error counts, the
+//! status code breakdown and latency percentiles, overall and per request
+//! kind, with agent-friendly plain output.
+
+use std::time::Duration;
+
+use crate::mode::OutputMode;
+use crate::themes::FastApiTheme;
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Latency percentiles for a group of requests.
+#[derive(Debug, Clone, Default)]
+pub struct BenchLatency {
+    /// Label (a request name, or `all`).
+    pub label: String,
+    /// Completed requests.
+    pub requests: u64,
+    /// Requests that got no response.
+    pub errors: u64,
+    /// Median latency.
+    pub p50: Duration,
+    /// 90th percentile latency.
+    pub p90: Duration,
+    /// 99th percentile latency.
+    pub p99: Duration,
+    /// Slowest request.
+    pub max: Duration,
+}
+
+impl BenchLatency {
+    /// Create an empty latency row.
+    #[must_use]
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Benchmark summary.
+#[derive(Debug, Clone, Default)]
+pub struct BenchSummary {
+    /// Where the requests went (`in-process` or a URL).
+    pub target: String,
+    /// Number of concurrent workers.
+    pub concurrency: usize,
+    /// Measured duration.
+    pub elapsed: Duration,
+    /// Completed requests per second.
+    pub rps: f64,
+    /// Completed requests by status code, ascending.
+    pub statuses: Vec<(u16, u64)>,
+    /// Latency over all requests.
+    pub overall: BenchLatency,
+    /// Latency per request kind.
+    pub endpoints: Vec<BenchLatency>,
+}
+
+impl BenchSummary {
+    /// Create an empty summary for `target`.
+    #[must_use]
+    pub fn new(target: &str, concurrency: usize) -> Self {
+        Self {
+            target: target.to_string(),
+            concurrency,
+            overall: BenchLatency::new("all"),
+            ..Self::default()
+        }
+    }
+
+    /// Add a per-request latency row.
+    #[must_use]
+    pub fn endpoint(mut self, latency: BenchLatency) -> Self {
+        self.endpoints.push(latency);
+        self
+    }
+
+    /// Return the number of completed requests with a 4xx or 5xx status.
+    #[must_use]
+    pub fn failed_statuses(&self) -> u64 {
+        self.statuses
+            .iter()
+            .filter(|(status, _)| *status >= 400)
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+/// Benchmark report display.
+#[derive(Debug, Clone)]
+pub struct BenchReportDisplay {
+    mode: OutputMode,
+    theme: FastApiTheme,
+    title: Option<String>,
+}
+
+impl BenchReportDisplay {
+    /// Create a new benchmark report display.
+    #[must_use]
+    pub fn new(mode: OutputMode) -> Self {
+        Self {
+            mode,
+            theme: FastApiTheme::default(),
+            title: Some("Benchmark".to_string()),
+        }
+    }
+
+    /// Set a custom theme.
+    #[must_use]
+    pub fn theme(mut self, theme: FastApiTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Set a custom title (None to disable).
+    #[must_use]
+    pub fn title(mut self, title: Option<String>) -> Self {
+        self.title = title;
+        self
+    }
+
+    /// Render the summary.
+    #[must_use]
+    pub fn render(&self, summary: &BenchSummary) -> String {
+        let mut lines = Vec::new();
+
+        if let Some(title) = &self.title {
+            lines.push(title.clone());
+            lines.push("-".repeat(title.len()));
+        }
+
+        lines.push(format!(
+            "Target: {} ({} worker(s), {:.2}s)",
+            summary.target,
+            summary.concurrency,
+            summary.elapsed.as_secs_f64()
+        ));
+        lines.push(self.render_throughput(summary));
+        if !summary.statuses.is_empty() {
+            let statuses: Vec<String> = summary
+                .statuses
+                .iter()
+                .map(|(status, count)| format!("{status} x {count}"))
+                .collect();
+            lines.push(format!("Status: {}", statuses.join(", ")));
+        }
+
+        let width = summary
+            .endpoints
+            .iter()
+            .map(|row| row.label.len())
+            .chain(std::iter::once(summary.overall.label.len()))
+            .max()
+            .unwrap_or(0);
+        lines.push(format!(
+            "  {:<width$} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            "request", "count", "p50", "p90", "p99", "max"
+        ));
+        for row in summary
+            .endpoints
+            .iter()
+            .chain(std::iter::once(&summary.overall))
+        {
+            lines.push(format!(
+                "  {:<width$} {:>10} {:>10} {:>10} {:>10} {:>10}",
+                row.label,
+                row.requests,
+                format_latency(row.p50),
+                format_latency(row.p90),
+                format_latency(row.p99),
+                format_latency(row.max)
+            ));
+        }
+
+        lines.join("\n")
+    }
+
+    fn render_throughput(&self, summary: &BenchSummary) -> String {
+        let errors = summary.overall.errors;
+        let failed = summary.failed_statuses();
+        let line = format!(
+            "Requests: {} ({:.1} req/s), {errors} error(s), {failed} 4xx/5xx",
+            summary.overall.requests, summary.rps
+        );
+        if !self.mode.uses_ansi() {
+            return line;
+        }
+        let color = if errors > 0 {
+            self.theme.error
+        } else if failed > 0 {
+            self.theme.warning
+        } else {
+            self.theme.success
+        };
+        format!("{}{line}{}", color.to_ansi_fg(), ANSI_RESET)
+    }
+}
+
+/// Format a latency with a unit suited to its size, ASCII only.
+fn format_latency(latency: Duration) -> String {
+    let micros = latency.as_secs_f64() * 1_000_000.0;
+    if micros < 1_000.0 {
+        format!("{micros:.0}us")
+    } else if micros < 1_000_000.0 {
+        format!("{:.2}ms", micros / 1_000.0)
+    } else {
+        format!("{:.2}s", micros / 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_contains, assert_has_ansi, assert_no_ansi};
+
+    fn sample_summary() -> BenchSummary {
+        let mut summary = BenchSummary::new("in-process", 4);
+        summary.elapsed = Duration::from_secs(10);
+        summary.rps = 1234.5;
+        summary.statuses = vec![(200, 12_000), (404, 345)];
+        summary.overall.requests = 12_345;
+        summary.overall.p50 = Duration::from_micros(450);
+        summary.overall.p99 = Duration::from_millis(3);
+        summary.overall.max = Duration::from_secs(2);
+        summary.endpoint(BenchLatency {
+            requests: 12_000,
+            p50: Duration::from_micros(400),
+            ..BenchLatency::new("GET /items")
+        })
+    }
+
+    #[test]
+    fn test_plain_render() {
+        let output = BenchReportDisplay::new(OutputMode::Plain).render(&sample_summary());
+        assert_no_ansi(&output);
+        assert_contains(&output, "Benchmark");
+        assert_contains(&output, "Target: in-process (4 worker(s), 10.00s)");
+        assert_contains(
+            &output,
+            "Requests: 12345 (1234.5 req/s), 0 error(s), 345 4xx/5xx",
+        );
+        assert_contains(&output, "Status: 200 x 12000, 404 x 345");
+        assert_contains(&output, "GET /items");
+        assert_contains(&output, "450us");
+        assert_contains(&output, "3.00ms");
+        assert_contains(&output, "2.00s");
+    }
+
+    #[test]
+    fn test_rich_render_has_ansi() {
+        let output = BenchReportDisplay::new(OutputMode::Rich)
+            .title(None)
+            .render(&sample_summary());
+        assert_has_ansi(&output);
+        assert!(output.starts_with("Target:"));
+    }
+}
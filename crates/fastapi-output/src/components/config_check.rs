@@ -0,0 +1,287 @@
+//! Startup configuration check report component.
+//!
+//! Renders the result of an application `--check` run: a short inventory of
+//! what was built followed by errors and warnings, with agent-friendly plain
+//! output.
+
+use crate::mode::OutputMode;
+use crate::themes::FastApiTheme;
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Severity of a configuration finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindingSeverity {
+    /// Suspicious but workable configuration.
+    Warning,
+    /// Configuration that will fail at runtime.
+    Error,
+}
+
+impl FindingSeverity {
+    /// Return a human-readable label for the severity.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Warning => "WARN",
+            Self::Error => "ERROR",
+        }
+    }
+
+    fn color(self, theme: &FastApiTheme) -> crate::themes::Color {
+        match self {
+            Self::Warning => theme.warning,
+            Self::Error => theme.error,
+        }
+    }
+}
+
+/// A single configuration finding.
+#[derive(Debug, Clone)]
+pub struct CheckFinding {
+    /// Severity.
+    pub severity: FindingSeverity,
+    /// Area the finding belongs to (e.g. `state`, `cors`).
+    pub category: String,
+    /// Description.
+    pub message: String,
+}
+
+impl CheckFinding {
+    /// Create a new finding.
+    #[must_use]
+    pub fn new(severity: FindingSeverity, category: &str, message: &str) -> Self {
+        Self {
+            severity,
+            category: category.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Configuration check summary.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigCheckReport {
+    /// Application name.
+    pub app_name: String,
+    /// Application version.
+    pub version: String,
+    /// Number of HTTP routes.
+    pub route_count: usize,
+    /// Number of websocket routes.
+    pub websocket_route_count: usize,
+    /// Middleware names in execution order.
+    pub middleware: Vec<String>,
+    /// Number of shared state values.
+    pub state_count: usize,
+    /// Size of the generated OpenAPI document, if enabled.
+    pub openapi_bytes: Option<usize>,
+    /// Findings.
+    pub findings: Vec<CheckFinding>,
+}
+
+impl ConfigCheckReport {
+    /// Create an empty report for the named application.
+    #[must_use]
+    pub fn new(app_name: &str, version: &str) -> Self {
+        Self {
+            app_name: app_name.to_string(),
+            version: version.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Add a finding.
+    #[must_use]
+    pub fn finding(mut self, finding: CheckFinding) -> Self {
+        self.findings.push(finding);
+        self
+    }
+
+    /// Return the number of findings with the given severity.
+    #[must_use]
+    pub fn count(&self, severity: FindingSeverity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .count()
+    }
+
+    /// Return true if there are no error findings.
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.count(FindingSeverity::Error) == 0
+    }
+}
+
+/// Configuration check display.
+#[derive(Debug, Clone)]
+pub struct ConfigCheckDisplay {
+    mode: OutputMode,
+    theme: FastApiTheme,
+    title: Option<String>,
+}
+
+impl ConfigCheckDisplay {
+    /// Create a new configuration check display.
+    #[must_use]
+    pub fn new(mode: OutputMode) -> Self {
+        Self {
+            mode,
+            theme: FastApiTheme::default(),
+            title: Some("Configuration Check".to_string()),
+        }
+    }
+
+    /// Set a custom theme.
+    #[must_use]
+    pub fn theme(mut self, theme: FastApiTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Set a custom title (None to disable).
+    #[must_use]
+    pub fn title(mut self, title: Option<String>) -> Self {
+        self.title = title;
+        self
+    }
+
+    /// Render the report.
+    #[must_use]
+    pub fn render(&self, report: &ConfigCheckReport) -> String {
+        let mut lines = Vec::new();
+
+        if let Some(title) = &self.title {
+            lines.push(title.clone());
+            lines.push("-".repeat(title.len()));
+        }
+
+        lines.push(format!("App: {} {}", report.app_name, report.version));
+        if report.websocket_route_count > 0 {
+            lines.push(format!(
+                "Routes: {} (+{} websocket)",
+                report.route_count, report.websocket_route_count
+            ));
+        } else {
+            lines.push(format!("Routes: {}", report.route_count));
+        }
+        if report.middleware.is_empty() {
+            lines.push("Middleware: none".to_string());
+        } else {
+            lines.push(format!("Middleware: {}", report.middleware.join(" -> ")));
+        }
+        lines.push(format!("State values: {}", report.state_count));
+        match report.openapi_bytes {
+            Some(bytes) => lines.push(format!("OpenAPI: {bytes} bytes")),
+            None => lines.push("OpenAPI: disabled".to_string()),
+        }
+
+        for finding in &report.findings {
+            lines.push(self.render_finding(finding));
+        }
+
+        lines.push(self.render_summary(report));
+        lines.join("\n")
+    }
+
+    fn render_finding(&self, finding: &CheckFinding) -> String {
+        if self.mode.uses_ansi() {
+            format!(
+                "{}{:<5}{} [{}] {}",
+                finding.severity.color(&self.theme).to_ansi_fg(),
+                finding.severity.label(),
+                ANSI_RESET,
+                finding.category,
+                finding.message
+            )
+        } else {
+            format!(
+                "{:<5} [{}] {}",
+                finding.severity.label(),
+                finding.category,
+                finding.message
+            )
+        }
+    }
+
+    fn render_summary(&self, report: &ConfigCheckReport) -> String {
+        let errors = report.count(FindingSeverity::Error);
+        let warnings = report.count(FindingSeverity::Warning);
+        let (label, color) = if report.passed() {
+            ("Check passed", self.theme.success)
+        } else {
+            ("Check failed", self.theme.error)
+        };
+        let counts = format!("{errors} error(s), {warnings} warning(s)");
+        if self.mode.uses_ansi() {
+            format!("{}{label}{}: {counts}", color.to_ansi_fg(), ANSI_RESET)
+        } else {
+            format!("{label}: {counts}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_contains, assert_has_ansi, assert_no_ansi};
+
+    fn sample_report() -> ConfigCheckReport {
+        let mut report = ConfigCheckReport::new("api", "1.2.0");
+        report.route_count = 3;
+        report.middleware = vec!["RequestId".to_string(), "Cors".to_string()];
+        report.openapi_bytes = Some(2048);
+        report
+            .finding(CheckFinding::new(
+                FindingSeverity::Error,
+                "state",
+                "GET /users requires state `Db`",
+            ))
+            .finding(CheckFinding::new(
+                FindingSeverity::Warning,
+                "cors",
+                "allow_any_origin with credentials",
+            ))
+    }
+
+    #[test]
+    fn test_report_counts() {
+        let report = sample_report();
+        assert_eq!(report.count(FindingSeverity::Error), 1);
+        assert_eq!(report.count(FindingSeverity::Warning), 1);
+        assert!(!report.passed());
+        assert!(ConfigCheckReport::new("api", "1").passed());
+    }
+
+    #[test]
+    fn test_plain_render() {
+        let output = ConfigCheckDisplay::new(OutputMode::Plain).render(&sample_report());
+        assert_no_ansi(&output);
+        assert_contains(&output, "Configuration Check");
+        assert_contains(&output, "App: api 1.2.0");
+        assert_contains(&output, "Middleware: RequestId -> Cors");
+        assert_contains(&output, "OpenAPI: 2048 bytes");
+        assert_contains(&output, "ERROR [state] GET /users requires state `Db`");
+        assert_contains(&output, "WARN  [cors]");
+        assert_contains(&output, "Check failed: 1 error(s), 1 warning(s)");
+    }
+
+    #[test]
+    fn test_plain_render_passing() {
+        let output = ConfigCheckDisplay::new(OutputMode::Plain)
+            .title(None)
+            .render(&ConfigCheckReport::new("api", "1.0.0"));
+        assert!(output.starts_with("App: api 1.0.0"));
+        assert_contains(&output, "Middleware: none");
+        assert_contains(&output, "OpenAPI: disabled");
+        assert_contains(&output, "Check passed: 0 error(s), 0 warning(s)");
+    }
+
+    #[test]
+    fn test_rich_render_has_ansi() {
+        let output = ConfigCheckDisplay::new(OutputMode::Rich).render(&sample_report());
+        assert_has_ansi(&output);
+        assert_contains(&output, "[state]");
+    }
+}
@@ -179,6 +179,49 @@ impl LogEntry {
     }
 }
 
+/// A request that exceeded the slow-request threshold.
+#[derive(Debug, Clone)]
+pub struct SlowRequestEntry {
+    /// The request, with its total timing.
+    pub entry: LogEntry,
+    /// The threshold that was exceeded.
+    pub threshold: Duration,
+    /// Named phases of the request, in order.
+    pub phases: Vec<(String, Duration)>,
+}
+
+impl SlowRequestEntry {
+    /// Create a slow-request entry.
+    #[must_use]
+    pub fn new(entry: LogEntry, threshold: Duration) -> Self {
+        Self {
+            entry,
+            threshold,
+            phases: Vec::new(),
+        }
+    }
+
+    /// Add a phase to the breakdown.
+    #[must_use]
+    pub fn phase(mut self, name: impl Into<String>, duration: Duration) -> Self {
+        self.phases.push((name.into(), duration));
+        self
+    }
+
+    fn breakdown(&self) -> String {
+        let mut parts = vec![format!(
+            "threshold {}",
+            ResponseTiming::new(self.threshold).format()
+        )];
+        parts.extend(
+            self.phases.iter().map(|(name, duration)| {
+                format!("{name} {}", ResponseTiming::new(*duration).format())
+            }),
+        );
+        parts.join(", ")
+    }
+}
+
 /// Request/response logger.
 #[derive(Debug, Clone)]
 pub struct RequestLogger {
@@ -354,6 +397,28 @@ impl RequestLogger {
         parts.join(" ")
     }
 
+    /// Format a slow-request warning: the request line, prefixed with a
+    /// warning marker and followed by the threshold and phase breakdown.
+    #[must_use]
+    pub fn format_slow(&self, slow: &SlowRequestEntry) -> String {
+        let line = self.format(&slow.entry);
+        let breakdown = slow.breakdown();
+        match self.mode {
+            OutputMode::Plain => format!("SLOW {line} ({breakdown})"),
+            OutputMode::Minimal => {
+                let warning = self.theme.warning.to_ansi_fg();
+                format!("{warning}SLOW{ANSI_RESET} {line} ({breakdown})")
+            }
+            OutputMode::Rich => {
+                let warning = self.theme.warning.to_ansi_fg();
+                let muted = self.theme.muted.to_ansi_fg();
+                format!(
+                    "{warning}{ANSI_BOLD}⚠ SLOW{ANSI_RESET} {line} {muted}({breakdown}){ANSI_RESET}"
+                )
+            }
+        }
+    }
+
     fn status_color(&self, status: u16) -> crate::themes::Color {
         match status {
             100..=199 => self.theme.status_1xx,
@@ -482,4 +547,35 @@ mod tests {
 
         assert!(output.contains("(abc-123)"));
     }
+
+    #[test]
+    fn test_slow_request_plain_format() {
+        let logger = RequestLogger::new(OutputMode::Plain);
+        let slow = SlowRequestEntry::new(
+            LogEntry::new(HttpMethod::Get, "/reports/7", 200)
+                .timing(ResponseTiming::new(Duration::from_millis(1200))),
+            Duration::from_millis(500),
+        )
+        .phase("extraction", Duration::from_micros(300))
+        .phase("handler", Duration::from_millis(1195));
+
+        assert_eq!(
+            logger.format_slow(&slow),
+            "SLOW GET     /reports/7 200 1.20s (threshold 500.00ms, extraction 300µs, handler 1.19s)"
+        );
+    }
+
+    #[test]
+    fn test_slow_request_rich_is_highlighted() {
+        let logger = RequestLogger::new(OutputMode::Rich);
+        let slow = SlowRequestEntry::new(
+            LogEntry::new(HttpMethod::Post, "/upload", 201),
+            Duration::from_secs(1),
+        );
+
+        let output = logger.format_slow(&slow);
+
+        assert!(output.contains("⚠ SLOW"));
+        assert!(output.contains(&logger.theme.warning.to_ansi_fg()));
+    }
 }
@@ -2,6 +2,8 @@
 //!
 //! This module contains the primary visual components:
 //! - [`banner`] - Startup banner with ASCII art and server info
+//! - [`bench_report`] - Load generation results with latency percentiles
+//! - [`config_check`] - Startup configuration check report
 //! - [`logging`] - Request/response logging with colors and timing
 //! - [`errors`] - Error formatters for validation and HTTP errors
 //! - [`routes`] - Route table display with method coloring
@@ -15,6 +17,8 @@
 //! - [`help_display`] - Help and usage display (Phase 5)
 
 pub mod banner;
+pub mod bench_report;
+pub mod config_check;
 pub mod dependency_tree;
 pub mod errors;
 pub mod help_display;
@@ -29,11 +33,13 @@ pub mod test_results;
 
 // Re-export main types
 pub use banner::{Banner, BannerConfig, ServerInfo};
+pub use bench_report::{BenchLatency, BenchReportDisplay, BenchSummary};
+pub use config_check::{CheckFinding, ConfigCheckDisplay, ConfigCheckReport, FindingSeverity};
 pub use dependency_tree::{DependencyNode, DependencyTreeDisplay};
 pub use errors::{ErrorFormatter, FormattedError, ValidationContext};
 pub use help_display::{ArgGroup, ArgInfo, CommandInfo, HelpDisplay, HelpInfo};
 pub use http_inspector::{RequestInfo, RequestInspector, ResponseInfo, ResponseInspector};
-pub use logging::{LogEntry, RequestLogger, ResponseTiming};
+pub use logging::{LogEntry, RequestLogger, ResponseTiming, SlowRequestEntry};
 pub use middleware_stack::{MiddlewareInfo, MiddlewareStackDisplay};
 pub use openapi_display::{
     EndpointInfo, OpenApiDisplay, OpenApiDisplayConfig, OpenApiSummary, PropertyInfo, SchemaType,
@@ -42,7 +48,7 @@ pub use routes::{RouteDisplay, RouteTableConfig};
 pub use routing_debug::{
     CandidateRoute, ExtractedParams, MatchResult, RoutingDebug, RoutingDebugInfo,
 };
-pub use shutdown_progress::{ShutdownPhase, ShutdownProgress};
+pub use shutdown_progress::{InFlightRequestInfo, ShutdownPhase, ShutdownProgress};
 pub use test_results::{
     TestCaseResult, TestModuleResult, TestReport, TestReportDisplay, TestStatus,
 };
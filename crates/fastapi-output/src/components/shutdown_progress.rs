@@ -1,7 +1,11 @@
 //! Graceful shutdown progress indicator component.
 //!
 //! Displays progress for connection draining, background task completion,
-//! and cleanup stages with agent-friendly fallback output.
+//! and cleanup stages with agent-friendly fallback output. When request
+//! snapshots are supplied, the requests a slow drain is waiting on are listed
+//! longest-running first.
+
+use std::time::Duration;
 
 use crate::mode::OutputMode;
 use crate::themes::FastApiTheme;
@@ -39,6 +43,39 @@ impl ShutdownPhase {
     }
 }
 
+/// A request that is still being handled during shutdown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InFlightRequestInfo {
+    /// HTTP method.
+    pub method: String,
+    /// Request path.
+    pub path: String,
+    /// Time spent on the request so far.
+    pub elapsed: Duration,
+    /// Client address, if known.
+    pub client: Option<String>,
+}
+
+impl InFlightRequestInfo {
+    /// Create a new in-flight request entry.
+    #[must_use]
+    pub fn new(method: impl Into<String>, path: impl Into<String>, elapsed: Duration) -> Self {
+        Self {
+            method: method.into(),
+            path: path.into(),
+            elapsed,
+            client: None,
+        }
+    }
+
+    /// Set the client address.
+    #[must_use]
+    pub fn client(mut self, client: impl Into<String>) -> Self {
+        self.client = Some(client.into());
+        self
+    }
+}
+
 /// Shutdown progress snapshot.
 #[derive(Debug, Clone)]
 pub struct ShutdownProgress {
@@ -50,6 +87,8 @@ pub struct ShutdownProgress {
     pub drained_connections: usize,
     /// In-flight requests remaining.
     pub in_flight_requests: usize,
+    /// Details of the in-flight requests, if known.
+    pub requests: Vec<InFlightRequestInfo>,
     /// Background tasks still running.
     pub background_tasks: usize,
     /// Cleanup steps completed.
@@ -69,6 +108,7 @@ impl ShutdownProgress {
             total_connections: 0,
             drained_connections: 0,
             in_flight_requests: 0,
+            requests: Vec::new(),
             background_tasks: 0,
             cleanup_done: 0,
             cleanup_total: 0,
@@ -91,6 +131,17 @@ impl ShutdownProgress {
         self
     }
 
+    /// Add details for an in-flight request.
+    ///
+    /// The in-flight count is raised to at least the number of listed
+    /// requests.
+    #[must_use]
+    pub fn in_flight_request(mut self, request: InFlightRequestInfo) -> Self {
+        self.requests.push(request);
+        self.in_flight_requests = self.in_flight_requests.max(self.requests.len());
+        self
+    }
+
     /// Set background task count.
     #[must_use]
     pub fn background_tasks(mut self, tasks: usize) -> Self {
@@ -120,6 +171,7 @@ pub struct ShutdownProgressDisplay {
     mode: OutputMode,
     theme: FastApiTheme,
     progress_width: usize,
+    max_requests: usize,
     title: Option<String>,
 }
 
@@ -131,6 +183,7 @@ impl ShutdownProgressDisplay {
             mode,
             theme: FastApiTheme::default(),
             progress_width: 24,
+            max_requests: 10,
             title: Some("Shutdown Progress".to_string()),
         }
     }
@@ -149,6 +202,13 @@ impl ShutdownProgressDisplay {
         self
     }
 
+    /// Set how many in-flight requests to list before summarizing the rest.
+    #[must_use]
+    pub fn max_requests(mut self, max: usize) -> Self {
+        self.max_requests = max;
+        self
+    }
+
     /// Set a custom title (None to disable).
     #[must_use]
     pub fn title(mut self, title: Option<String>) -> Self {
@@ -179,6 +239,7 @@ impl ShutdownProgressDisplay {
                 "In-flight requests: {}",
                 progress.in_flight_requests
             ));
+            self.render_requests(progress, &mut lines);
         }
 
         if progress.background_tasks > 0 {
@@ -233,6 +294,43 @@ impl ShutdownProgressDisplay {
         )
     }
 
+    fn render_requests(&self, progress: &ShutdownProgress, lines: &mut Vec<String>) {
+        let mut requests: Vec<&InFlightRequestInfo> = progress.requests.iter().collect();
+        requests.sort_by(|a, b| b.elapsed.cmp(&a.elapsed));
+
+        let method_width = requests.iter().map(|r| r.method.len()).max().unwrap_or(0);
+
+        for request in requests.iter().take(self.max_requests) {
+            let elapsed = format_elapsed(request.elapsed);
+            let client = request
+                .client
+                .as_ref()
+                .map(|c| format!(" from {c}"))
+                .unwrap_or_default();
+            if self.mode.uses_ansi() {
+                lines.push(format!(
+                    "  {}{:<method_width$}{} {} {}({elapsed}){}{client}",
+                    self.theme.accent.to_ansi_fg(),
+                    request.method,
+                    ANSI_RESET,
+                    request.path,
+                    self.theme.muted.to_ansi_fg(),
+                    ANSI_RESET,
+                ));
+            } else {
+                lines.push(format!(
+                    "  {:<method_width$} {} ({elapsed}){client}",
+                    request.method, request.path
+                ));
+            }
+        }
+
+        let hidden = requests.len().saturating_sub(self.max_requests);
+        if hidden > 0 {
+            lines.push(format!("  ... and {hidden} more"));
+        }
+    }
+
     fn render_complete(&self) -> String {
         if self.mode.uses_ansi() {
             format!(
@@ -246,6 +344,14 @@ impl ShutdownProgressDisplay {
     }
 }
 
+fn format_elapsed(elapsed: Duration) -> String {
+    if elapsed.as_secs() >= 1 {
+        format!("{:.1}s", elapsed.as_secs_f64())
+    } else {
+        format!("{}ms", elapsed.as_millis())
+    }
+}
+
 fn shutdown_bar(
     drained: usize,
     total: usize,
@@ -575,6 +681,79 @@ mod tests {
         assert_contains(&output, "Note: Note 3");
     }
 
+    // =================================================================
+    // In-Flight Request Tests
+    // =================================================================
+
+    #[test]
+    fn test_in_flight_request_builder_raises_count() {
+        let progress = ShutdownProgress::new(ShutdownPhase::GracePeriod)
+            .in_flight_request(InFlightRequestInfo::new("GET", "/a", Duration::ZERO))
+            .in_flight_request(InFlightRequestInfo::new("GET", "/b", Duration::ZERO));
+        assert_eq!(progress.in_flight_requests, 2);
+        assert_eq!(progress.requests.len(), 2);
+
+        let progress = ShutdownProgress::new(ShutdownPhase::GracePeriod)
+            .in_flight(5)
+            .in_flight_request(InFlightRequestInfo::new("GET", "/a", Duration::ZERO));
+        assert_eq!(progress.in_flight_requests, 5);
+    }
+
+    #[test]
+    fn test_in_flight_requests_listed_longest_first() {
+        let progress = ShutdownProgress::new(ShutdownPhase::GracePeriod)
+            .in_flight_request(InFlightRequestInfo::new(
+                "GET",
+                "/fast",
+                Duration::from_millis(250),
+            ))
+            .in_flight_request(
+                InFlightRequestInfo::new("POST", "/upload", Duration::from_millis(12_300))
+                    .client("10.0.0.7:51234"),
+            );
+        let output = ShutdownProgressDisplay::new(OutputMode::Plain).render(&progress);
+
+        assert_no_ansi(&output);
+        assert_contains(&output, "In-flight requests: 2");
+        assert_contains(&output, "  POST /upload (12.3s) from 10.0.0.7:51234");
+        assert_contains(&output, "  GET  /fast (250ms)");
+        let slow = output.find("/upload").unwrap();
+        let fast = output.find("/fast").unwrap();
+        assert!(slow < fast);
+    }
+
+    #[test]
+    fn test_in_flight_requests_truncated() {
+        let mut progress = ShutdownProgress::new(ShutdownPhase::GracePeriod);
+        for i in 0..5_u64 {
+            progress = progress.in_flight_request(InFlightRequestInfo::new(
+                "GET",
+                format!("/r{i}"),
+                Duration::from_secs(i),
+            ));
+        }
+        let output = ShutdownProgressDisplay::new(OutputMode::Plain)
+            .max_requests(2)
+            .render(&progress);
+
+        assert_contains(&output, "/r4");
+        assert_contains(&output, "/r3");
+        assert!(!output.contains("/r2"));
+        assert_contains(&output, "... and 3 more");
+    }
+
+    #[test]
+    fn test_in_flight_requests_rich() {
+        let progress = ShutdownProgress::new(ShutdownPhase::GracePeriod).in_flight_request(
+            InFlightRequestInfo::new("GET", "/slow", Duration::from_secs(3)),
+        );
+        let output = ShutdownProgressDisplay::new(OutputMode::Rich).render(&progress);
+
+        assert_has_ansi(&output);
+        assert_contains(&output, "/slow");
+        assert_contains(&output, "(3.0s)");
+    }
+
     // =================================================================
     // Progress Bar Tests
     // =================================================================
@@ -173,6 +173,10 @@ pub use themes::{FastApiTheme, ThemePreset};
 
 // Re-export component types
 pub use components::banner::{Banner, BannerConfig, ServerInfo};
+pub use components::bench_report::{BenchLatency, BenchReportDisplay, BenchSummary};
+pub use components::config_check::{
+    CheckFinding, ConfigCheckDisplay, ConfigCheckReport, FindingSeverity,
+};
 pub use components::dependency_tree::{DependencyNode, DependencyTreeDisplay};
 pub use components::errors::{
     ErrorFormatter, FormattedError, HttpErrorInfo, LocItem, ValidationContext,
@@ -182,7 +186,9 @@ pub use components::help_display::{ArgGroup, ArgInfo, CommandInfo, HelpDisplay,
 pub use components::http_inspector::{
     RequestInfo, RequestInspector, ResponseInfo, ResponseInspector,
 };
-pub use components::logging::{HttpMethod, LogEntry, RequestLogger, ResponseTiming};
+pub use components::logging::{
+    HttpMethod, LogEntry, RequestLogger, ResponseTiming, SlowRequestEntry,
+};
 pub use components::middleware_stack::{MiddlewareInfo, MiddlewareStackDisplay};
 pub use components::openapi_display::{
     EndpointInfo, OpenApiDisplay, OpenApiDisplayConfig, OpenApiSummary, PropertyInfo, SchemaType,
@@ -191,7 +197,9 @@ pub use components::routes::{RouteDisplay, RouteEntry, RouteTableConfig};
 pub use components::routing_debug::{
     CandidateRoute, ExtractedParams, MatchResult, RoutingDebug, RoutingDebugInfo,
 };
-pub use components::shutdown_progress::{ShutdownPhase, ShutdownProgress, ShutdownProgressDisplay};
+pub use components::shutdown_progress::{
+    InFlightRequestInfo, ShutdownPhase, ShutdownProgress, ShutdownProgressDisplay,
+};
 pub use components::test_results::{
     TestCaseResult, TestModuleResult, TestReport, TestReportDisplay, TestStatus,
 };
@@ -200,6 +208,10 @@ pub use components::test_results::{
 pub mod prelude {
     // Components
     pub use crate::components::banner::{Banner, BannerConfig, ServerInfo};
+    pub use crate::components::bench_report::{BenchLatency, BenchReportDisplay, BenchSummary};
+    pub use crate::components::config_check::{
+        CheckFinding, ConfigCheckDisplay, ConfigCheckReport, FindingSeverity,
+    };
     pub use crate::components::dependency_tree::{DependencyNode, DependencyTreeDisplay};
     pub use crate::components::errors::{
         ErrorFormatter, FormattedError, HttpErrorInfo, LocItem, ValidationContext,
@@ -222,7 +234,7 @@ pub mod prelude {
         CandidateRoute, ExtractedParams, MatchResult, RoutingDebug, RoutingDebugInfo,
     };
     pub use crate::components::shutdown_progress::{
-        ShutdownPhase, ShutdownProgress, ShutdownProgressDisplay,
+        InFlightRequestInfo, ShutdownPhase, ShutdownProgress, ShutdownProgressDisplay,
     };
     pub use crate::components::test_results::{
         TestCaseResult, TestModuleResult, TestReport, TestReportDisplay, TestStatus,
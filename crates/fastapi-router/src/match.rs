@@ -108,7 +108,7 @@ impl AllowedMethods {
         if methods.contains(&Method::Get) && !methods.contains(&Method::Head) {
             methods.push(Method::Head);
         }
-        methods.sort_by_key(method_order);
+        methods.sort_by_key(|method| (method_order(method), method.as_str()));
         methods.dedup();
         Self { methods }
     }
@@ -149,5 +149,7 @@ fn method_order(method: &Method) -> u8 {
         Method::Patch => 5,
         Method::Options => 6,
         Method::Trace => 7,
+        Method::Connect => 8,
+        Method::Other(_) => 9,
     }
 }
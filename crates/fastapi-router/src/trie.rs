@@ -262,7 +262,7 @@ fn is_uuid(s: &str) -> bool {
     })
 }
 
-/// Path parameter information with optional OpenAPI metadata.
+/// Path or query parameter information with optional OpenAPI metadata.
 #[derive(Debug, Clone, Default)]
 pub struct ParamInfo {
     /// Parameter name.
@@ -279,6 +279,12 @@ pub struct ParamInfo {
     pub example: Option<serde_json::Value>,
     /// Named examples for OpenAPI documentation.
     pub examples: Vec<(String, serde_json::Value)>,
+    /// Value used when the parameter is omitted, for OpenAPI documentation.
+    pub default: Option<serde_json::Value>,
+    /// Whether a query parameter must be present (path parameters always are).
+    pub required: bool,
+    /// JSON Schema of the value, overriding the one derived from `converter`.
+    pub schema: Option<serde_json::Value>,
 }
 
 impl ParamInfo {
@@ -293,6 +299,9 @@ impl ParamInfo {
             deprecated: false,
             example: None,
             examples: Vec::new(),
+            default: None,
+            required: false,
+            schema: None,
         }
     }
 
@@ -330,6 +339,48 @@ impl ParamInfo {
         self.examples.push((name.into(), value));
         self
     }
+
+    /// Set the value used when the parameter is omitted.
+    #[must_use]
+    pub fn with_default(mut self, default: serde_json::Value) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Mark a query parameter as required.
+    #[must_use]
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Set the JSON Schema of the value.
+    #[must_use]
+    pub fn with_schema(mut self, schema: serde_json::Value) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Copy documentation (everything but name and converter) from `other`.
+    fn merge_docs(&mut self, other: &ParamInfo) {
+        if other.title.is_some() {
+            self.title.clone_from(&other.title);
+        }
+        if other.description.is_some() {
+            self.description.clone_from(&other.description);
+        }
+        self.deprecated |= other.deprecated;
+        if other.example.is_some() {
+            self.example.clone_from(&other.example);
+        }
+        self.examples.extend(other.examples.iter().cloned());
+        if other.default.is_some() {
+            self.default.clone_from(&other.default);
+        }
+        if other.schema.is_some() {
+            self.schema.clone_from(&other.schema);
+        }
+    }
 }
 
 /// Extract path parameters from a route path pattern.
@@ -452,6 +503,29 @@ impl RouteResponse {
     }
 }
 
+/// Response header declaration for OpenAPI documentation.
+#[derive(Debug, Clone)]
+pub struct RouteResponseHeader {
+    /// Status code of the response that carries the header.
+    pub status: u16,
+    /// Header name (e.g., "X-Total-Count").
+    pub name: String,
+    /// Description of the header value.
+    pub description: String,
+}
+
+impl RouteResponseHeader {
+    /// Create a new response header declaration.
+    #[must_use]
+    pub fn new(status: u16, name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            status,
+            name: name.into(),
+            description: description.into(),
+        }
+    }
+}
+
 /// Security requirement for a route.
 ///
 /// Specifies a security scheme and optional scopes required to access a route.
@@ -516,8 +590,15 @@ pub struct Route {
     pub tags: Vec<String>,
     /// Whether this route is deprecated.
     pub deprecated: bool,
+    /// Whether clients may safely retry this operation.
+    ///
+    /// Idempotent routes get an `x-idempotent` OpenAPI extension and a
+    /// `Retry-After` header on overload responses.
+    pub idempotent: bool,
     /// Path parameters extracted from the route pattern for OpenAPI documentation.
     pub path_params: Vec<ParamInfo>,
+    /// Query parameters for OpenAPI documentation.
+    pub query_params: Vec<ParamInfo>,
     /// Request body schema type name for OpenAPI documentation (e.g., "CreateUser").
     pub request_body_schema: Option<String>,
     /// Request body content type for OpenAPI documentation (e.g., "application/json").
@@ -533,6 +614,8 @@ pub struct Route {
     ///
     /// Each response specifies a status code, schema type, and description.
     pub responses: Vec<RouteResponse>,
+    /// Declared response headers for OpenAPI documentation.
+    pub response_headers: Vec<RouteResponseHeader>,
 }
 
 impl fmt::Debug for Route {
@@ -553,9 +636,15 @@ impl fmt::Debug for Route {
         if self.deprecated {
             s.field("deprecated", &self.deprecated);
         }
+        if self.idempotent {
+            s.field("idempotent", &self.idempotent);
+        }
         if !self.path_params.is_empty() {
             s.field("path_params", &self.path_params);
         }
+        if !self.query_params.is_empty() {
+            s.field("query_params", &self.query_params);
+        }
         if let Some(ref schema) = self.request_body_schema {
             s.field("request_body_schema", schema);
         }
@@ -571,6 +660,9 @@ impl fmt::Debug for Route {
         if !self.responses.is_empty() {
             s.field("responses", &self.responses);
         }
+        if !self.response_headers.is_empty() {
+            s.field("response_headers", &self.response_headers);
+        }
         s.finish()
     }
 }
@@ -697,12 +789,15 @@ impl Route {
             description: None,
             tags: Vec::new(),
             deprecated: false,
+            idempotent: false,
             path_params,
+            query_params: Vec::new(),
             request_body_schema: None,
             request_body_content_type: None,
             request_body_required: false,
             security: Vec::new(),
             responses: Vec::new(),
+            response_headers: Vec::new(),
         }
     }
 
@@ -758,6 +853,36 @@ impl Route {
         self
     }
 
+    /// Mark this route as safe to retry.
+    #[must_use]
+    pub fn idempotent(mut self) -> Self {
+        self.idempotent = true;
+        self
+    }
+
+    /// Document query parameters, replacing any with the same name.
+    #[must_use]
+    pub fn query_params(mut self, params: impl IntoIterator<Item = ParamInfo>) -> Self {
+        for param in params {
+            self.query_params.retain(|p| p.name != param.name);
+            self.query_params.push(param);
+        }
+        self
+    }
+
+    /// Merge documentation into the path parameters of the same name.
+    ///
+    /// Entries that don't name a parameter of the route pattern are ignored.
+    #[must_use]
+    pub fn path_param_docs(mut self, params: impl IntoIterator<Item = ParamInfo>) -> Self {
+        for doc in params {
+            if let Some(param) = self.path_params.iter_mut().find(|p| p.name == doc.name) {
+                param.merge_docs(&doc);
+            }
+        }
+        self
+    }
+
     /// Set the request body schema for OpenAPI documentation.
     ///
     /// The schema name will be used to generate a `$ref` to the schema
@@ -858,6 +983,22 @@ impl Route {
         self
     }
 
+    /// Document a header sent with the `status` response.
+    ///
+    /// A status without a declared response is documented with a generic
+    /// description and no body.
+    #[must_use]
+    pub fn response_header(
+        mut self,
+        status: u16,
+        name: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.response_headers
+            .push(RouteResponseHeader::new(status, name, description));
+        self
+    }
+
     /// Check if this route has response declarations.
     #[must_use]
     pub fn has_responses(&self) -> bool {
@@ -916,6 +1057,7 @@ impl Node {
 pub struct Router {
     root: Node,
     routes: Vec<Route>,
+    tags: Vec<String>,
 }
 
 impl Router {
@@ -925,9 +1067,25 @@ impl Router {
         Self {
             root: Node::new(""),
             routes: Vec::new(),
+            tags: Vec::new(),
         }
     }
 
+    /// Add a tag applied to every route of this router when it is mounted.
+    ///
+    /// Router tags come before each route's own tags; duplicates are dropped.
+    #[must_use]
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Tags applied to this router's routes when it is mounted.
+    #[must_use]
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
     /// Add a route, returning an error if it conflicts with existing routes
     /// or the path pattern is invalid.
     ///
@@ -1053,8 +1211,13 @@ impl Router {
     /// Returns an error if any mounted route conflicts with existing routes.
     pub fn mount(mut self, prefix: &str, child: Router) -> Result<Self, RouteAddError> {
         let prefix = prefix.trim_end_matches('/');
+        let Router {
+            routes,
+            tags: router_tags,
+            ..
+        } = child;
 
-        for route in child.routes {
+        for route in routes {
             let child_path = if route.path == "/" {
                 String::new()
             } else if route.path.starts_with('/') {
@@ -1076,21 +1239,35 @@ impl Router {
             };
 
             // Recompute path_params from full_path since the mounted path may differ
-            let path_params = extract_path_params(&full_path);
+            let mut path_params = extract_path_params(&full_path);
+            for param in &mut path_params {
+                if let Some(doc) = route.path_params.iter().find(|p| p.name == param.name) {
+                    param.merge_docs(doc);
+                }
+            }
+            let mut tags = router_tags.clone();
+            for tag in route.tags {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
             let mounted = Route {
                 path: full_path,
                 method: route.method,
                 operation_id: route.operation_id,
                 summary: route.summary,
                 description: route.description,
-                tags: route.tags,
+                tags,
                 deprecated: route.deprecated,
+                idempotent: route.idempotent,
                 path_params,
+                query_params: route.query_params,
                 request_body_schema: route.request_body_schema,
                 request_body_content_type: route.request_body_content_type,
                 request_body_required: route.request_body_required,
                 security: route.security,
                 responses: route.responses,
+                response_headers: route.response_headers,
             };
 
             self.add(mounted)?;
@@ -3181,6 +3358,59 @@ mod tests {
         assert_eq!(m.route.path, "/api/v1/items");
     }
 
+    #[test]
+    fn mount_applies_router_tags() {
+        let mut users = Router::new().tag("users").tag("v1");
+        users
+            .add(route(Method::Get, "/").tag("admin").tag("users"))
+            .unwrap();
+
+        let outer = Router::new().mount("/users", users).unwrap();
+        let m = outer.match_path("/users", Method::Get).unwrap();
+        assert_eq!(m.route.tags, vec!["users", "v1", "admin"]);
+    }
+
+    #[test]
+    fn mount_keeps_parameter_docs() {
+        let mut items = Router::new();
+        items
+            .add(
+                route(Method::Get, "/{id:int}")
+                    .path_param_docs([
+                        ParamInfo::new("id", Converter::Str).with_example(serde_json::json!(7))
+                    ])
+                    .query_params([
+                        ParamInfo::new("limit", Converter::Int).with_default(serde_json::json!(10))
+                    ]),
+            )
+            .unwrap();
+
+        let outer = Router::new().mount("/items", items).unwrap();
+        let m = outer.match_path("/items/3", Method::Get).unwrap();
+        assert_eq!(m.route.path_params[0].example, Some(serde_json::json!(7)));
+        assert!(matches!(m.route.path_params[0].converter, Converter::Int));
+        assert_eq!(m.route.query_params[0].default, Some(serde_json::json!(10)));
+    }
+
+    #[test]
+    fn path_param_docs_ignore_unknown_names() {
+        let route = Route::new(Method::Get, "/users/{id}").path_param_docs([
+            ParamInfo::new("id", Converter::Str).with_description("User ID"),
+            ParamInfo::new("missing", Converter::Str),
+        ]);
+        assert_eq!(route.path_params.len(), 1);
+        assert_eq!(route.path_params[0].description.as_deref(), Some("User ID"));
+    }
+
+    #[test]
+    fn query_params_replace_same_name() {
+        let route = Route::new(Method::Get, "/search")
+            .query_params([ParamInfo::new("q", Converter::Str)])
+            .query_params([ParamInfo::new("q", Converter::Str).required()]);
+        assert_eq!(route.query_params.len(), 1);
+        assert!(route.query_params[0].required);
+    }
+
     #[test]
     fn mount_conflict_detection() {
         let mut child1 = Router::new();
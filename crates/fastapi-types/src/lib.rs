@@ -6,8 +6,14 @@
 #![forbid(unsafe_code)]
 
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{OnceLock, RwLock};
 
 /// HTTP method.
+///
+/// Besides the standard methods, extension methods such as WebDAV's
+/// `PROPFIND` are represented as [`Method::Other`] once registered with
+/// [`Method::custom`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Method {
     /// GET method.
@@ -26,6 +32,10 @@ pub enum Method {
     Head,
     /// TRACE method.
     Trace,
+    /// CONNECT method.
+    Connect,
+    /// A registered extension method.
+    Other(ExtensionMethod),
 }
 
 impl Method {
@@ -41,10 +51,29 @@ impl Method {
             b"OPTIONS" => Some(Self::Options),
             b"HEAD" => Some(Self::Head),
             b"TRACE" => Some(Self::Trace),
-            _ => None,
+            b"CONNECT" => Some(Self::Connect),
+            _ => ExtensionMethod::lookup(bytes).map(Self::Other),
         }
     }
 
+    /// The method named `name`, registering it as an extension method if it
+    /// is not a standard one.
+    ///
+    /// Extension names are case-sensitive and limited to uppercase ASCII
+    /// letters, digits, `-` and `_` (at most 32 bytes). Only registered
+    /// methods are accepted from the network, so register every extension
+    /// method the app serves before starting the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidMethodName`] if `name` is not a valid extension name.
+    pub fn custom(name: &str) -> Result<Self, InvalidMethodName> {
+        if let Some(method) = Self::from_bytes(name.as_bytes()) {
+            return Ok(method);
+        }
+        ExtensionMethod::register(name).map(Self::Other)
+    }
+
     /// Return the canonical uppercase method name.
     #[must_use]
     pub const fn as_str(self) -> &'static str {
@@ -57,6 +86,8 @@ impl Method {
             Self::Options => "OPTIONS",
             Self::Head => "HEAD",
             Self::Trace => "TRACE",
+            Self::Connect => "CONNECT",
+            Self::Other(method) => method.as_str(),
         }
     }
 }
@@ -66,3 +97,88 @@ impl fmt::Display for Method {
         f.write_str(self.as_str())
     }
 }
+
+/// Name of a registered extension method, such as `PROPFIND`.
+///
+/// Names are interned for the life of the process, which keeps [`Method`]
+/// `Copy`. Only [`Method::custom`] adds names, so request input can never
+/// grow the registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExtensionMethod(&'static str);
+
+/// Longest accepted extension method name.
+const MAX_EXTENSION_LEN: usize = 32;
+
+/// Set once the first extension method is registered, so parsing an unknown
+/// method off the wire takes no lock in apps that register none.
+static ANY_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+fn extension_registry() -> &'static RwLock<Vec<&'static str>> {
+    static REGISTRY: OnceLock<RwLock<Vec<&'static str>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+impl ExtensionMethod {
+    /// The method name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        self.0
+    }
+
+    fn lookup(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() > MAX_EXTENSION_LEN || !ANY_REGISTERED.load(Ordering::Acquire) {
+            return None;
+        }
+        let registry = extension_registry()
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        registry
+            .iter()
+            .find(|name| name.as_bytes() == bytes)
+            .copied()
+            .map(Self)
+    }
+
+    fn register(name: &str) -> Result<Self, InvalidMethodName> {
+        let valid = !name.is_empty()
+            && name.len() <= MAX_EXTENSION_LEN
+            && name
+                .bytes()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'-' || b == b'_');
+        if !valid {
+            return Err(InvalidMethodName(name.to_string()));
+        }
+        let mut registry = extension_registry()
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(existing) = registry.iter().copied().find(|existing| *existing == name) {
+            return Ok(Self(existing));
+        }
+        let interned: &'static str = Box::leak(name.to_string().into_boxed_str());
+        registry.push(interned);
+        ANY_REGISTERED.store(true, Ordering::Release);
+        Ok(Self(interned))
+    }
+}
+
+impl fmt::Display for ExtensionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// Error returned by [`Method::custom`] for an invalid extension method name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMethodName(pub String);
+
+impl fmt::Display for InvalidMethodName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid HTTP method name `{}`: expected 1-{MAX_EXTENSION_LEN} uppercase letters, digits, `-` or `_`",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for InvalidMethodName {}
@@ -196,6 +196,8 @@ fn build_config() -> AppConfig {
         root_path_in_servers: false,
         trailing_slash_mode: fastapi_core::routing::TrailingSlashMode::Strict,
         debug_config: fastapi_core::error::DebugConfig::default(),
+        pretty_json: cfg!(debug_assertions),
+        json_key_case: None,
     }
 }
 
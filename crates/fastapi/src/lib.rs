@@ -115,6 +115,13 @@
 //!     let items = db.list(page.offset(), page.limit()).await;
 //!     Json(Page::new(items, total_count, page.page(), page.per_page()))
 //! }
+//!
+//! // Cursor-based: `?cursor=...&limit=...`, with a `Link` header
+//! #[get("/events")]
+//! async fn list_events(cx: &Cx, page: CursorPagination) -> CursorPage<Event> {
+//!     let (events, next) = db.events_after(page.cursor(), page.limit()).await;
+//!     CursorPage::new(events, page.limit(), next).with_links("/events")
+//! }
 //! ```
 //!
 //! ## Bearer Token Authentication
@@ -243,13 +250,32 @@ pub use fastapi_macros as macros;
 pub use fastapi_openapi as openapi;
 pub use fastapi_router as router;
 
+/// Typed HTTP header names and values.
+pub use fastapi_core::headers;
+
+/// Static assets compiled into the binary with [`embed_dir!`].
+pub use fastapi_core::embed;
+
+/// Adapters for mounting handlers written for other frameworks.
+pub use fastapi_core::interop;
+
+/// Serving an app as a WASI (WAGI/CGI) module.
+pub use fastapi_core::wasi;
+
+/// Cookie sessions with login/logout helpers and the current-user extractor.
+pub use fastapi_core::session;
+
+/// Interval and cron jobs that run alongside the server.
+pub use fastapi_core::scheduler;
+
 // Re-export commonly used types
 pub use fastapi_core::{
     App, AppBuilder, AppConfig, Cors, CorsConfig, Cx, DefaultConfig, DefaultDependencyConfig,
     DependencyOverrides, DependencyScope, Depends, DependsConfig, FromDependency, FromRequest,
-    HttpError, IntoResponse, Method, NoCache, Request, RequestId, RequestIdConfig,
-    RequestIdMiddleware, Response, ResponseBody, StateContainer, StatusCode, ValidationError,
-    ValidationErrors,
+    HandlerError, HttpError, IntoResponse, JsonKeyCase, Method, NoCache, ProblemDetails, Request,
+    RequestId, RequestIdConfig, RequestIdMiddleware, RequestOutcome, Response, ResponseBody,
+    Scopes, Security, SecurityScopes, Singleton, SingletonRetry, StateContainer, StatusCode,
+    ValidationError, ValidationErrors,
 };
 
 // Re-export extractors
@@ -257,11 +283,15 @@ pub use fastapi_core::{
     // Common header types
     Accept,
     AddResponseHeader,
+    // Auth extractors
+    ApiKeyCookie,
+    ApiKeyHeader,
+    ApiKeyQuery,
+    ApiKeyVerifier,
     AppState,
     Authorization,
     // Background tasks
     BackgroundTasks,
-    // Auth extractors
     BasicAuth,
     BasicAuthError,
     BearerToken,
@@ -269,6 +299,10 @@ pub use fastapi_core::{
     ContentType,
     // Cookies
     Cookie,
+    // Sessions
+    CurrentUser,
+    CursorPage,
+    CursorPagination,
     DEFAULT_PAGE,
     DEFAULT_PER_PAGE,
     // Headers
@@ -280,6 +314,8 @@ pub use fastapi_core::{
     Json,
     JsonConfig,
     JsonExtractError,
+    JwtBearer,
+    JwtValidator,
     MAX_PER_PAGE,
     NamedHeader,
     OAuth2BearerError,
@@ -299,8 +335,12 @@ pub use fastapi_core::{
     QueryParams,
     RequestContext,
     SameSite,
+    Session,
+    SessionMiddleware,
     // State
     State,
+    StrictJson,
+    TypedPath,
     UserAgent,
     XRequestId,
 };
@@ -308,7 +348,10 @@ pub use fastapi_core::{
 // Re-export testing utilities
 #[cfg(feature = "testing")]
 pub use fastapi_core::{CookieJar, RequestBuilder, TestClient, TestResponse};
-pub use fastapi_macros::{JsonSchema, Validate, delete, get, head, options, patch, post, put};
+pub use fastapi_macros::{
+    ApiError, FromRequest, JsonSchema, TypedPath, Validate, delete, dependency, embed_dir, get,
+    head, options, patch, post, put, route, trace,
+};
 pub use fastapi_openapi::{OpenApi, OpenApiBuilder, SchemaRegistry};
 pub use fastapi_router::{
     // Route matching
@@ -338,6 +381,8 @@ pub use fastapi_http::{
 /// Prelude module for convenient imports.
 pub mod prelude {
     pub use crate::{
+        // Macros
+        ApiError,
         // Core types
         App,
         AppBuilder,
@@ -348,6 +393,9 @@ pub mod prelude {
         Cookie,
         Cors,
         CorsConfig,
+        CurrentUser,
+        CursorPage,
+        CursorPagination,
         // asupersync context
         Cx,
         DefaultConfig,
@@ -358,6 +406,7 @@ pub mod prelude {
         DependsConfig,
         FromDependency,
         FromRequest,
+        HandlerError,
         Header,
         HttpError,
         IntoResponse,
@@ -383,39 +432,251 @@ pub mod prelude {
         Response,
         Route,
         Router,
+        Security,
+        SecurityScopes,
         // Server
         Server,
         ServerConfig,
+        Session,
+        Singleton,
         State,
         StatusCode,
+        TypedPath,
         Validate,
         ValidationError,
         ValidationErrors,
         delete,
+        dependency,
         get,
         head,
         options,
         patch,
         post,
         put,
+        route,
         serve,
+        trace,
     };
     pub use serde::{Deserialize, Serialize};
 }
 
+/// Startup configuration checks for `--check` CI gates.
+///
+/// ```ignore
+/// let app = build_app();
+/// if std::env::args().any(|a| a == "--check") {
+///     let passed = fastapi_rust::check::print_report(&app.check());
+///     std::process::exit(i32::from(!passed));
+/// }
+/// ```
+#[cfg(any(feature = "output", feature = "output-plain"))]
+pub mod check {
+    pub use fastapi_core::check::{CheckIssue, CheckReport, CheckSeverity};
+    use fastapi_core::{DependencyScope, DependencyTree};
+    use fastapi_output::{
+        CheckFinding, ConfigCheckDisplay, ConfigCheckReport, DependencyNode, DependencyTreeDisplay,
+        FindingSeverity, OutputMode,
+    };
+
+    /// Converts a core check report into the `fastapi-output` display model.
+    #[must_use]
+    pub fn to_display_report(report: &CheckReport) -> ConfigCheckReport {
+        let mut out = ConfigCheckReport::new(&report.app_name, &report.version);
+        out.route_count = report.routes.len();
+        out.websocket_route_count = report.websocket_routes;
+        out.middleware.clone_from(&report.middleware);
+        out.state_count = report.state_count;
+        out.openapi_bytes = report.openapi_bytes;
+        for issue in &report.issues {
+            let severity = match issue.severity {
+                CheckSeverity::Error => FindingSeverity::Error,
+                CheckSeverity::Warning => FindingSeverity::Warning,
+            };
+            out = out.finding(CheckFinding::new(severity, &issue.category, &issue.message));
+        }
+        out
+    }
+
+    /// Renders `report` for the detected output mode.
+    #[must_use]
+    pub fn render_report(report: &CheckReport) -> String {
+        ConfigCheckDisplay::new(OutputMode::auto()).render(&to_display_report(report))
+    }
+
+    /// Builds the display for the report's per-route dependency trees.
+    #[must_use]
+    pub fn dependency_display(report: &CheckReport, mode: OutputMode) -> DependencyTreeDisplay {
+        let roots = report.dependency_trees.iter().map(to_node).collect();
+        report
+            .dependency_cycles
+            .iter()
+            .fold(DependencyTreeDisplay::new(mode, roots), |display, cycle| {
+                display.with_cycle_path(cycle.clone())
+            })
+    }
+
+    fn to_node(tree: &DependencyTree) -> DependencyNode {
+        let mut node =
+            DependencyNode::new(&tree.name).children(tree.children.iter().map(to_node).collect());
+        if tree.cached {
+            node = node.cached();
+        }
+        if let Some(scope) = tree.scope {
+            node = node.scope(match scope {
+                DependencyScope::Request => "request",
+                DependencyScope::Function => "function",
+                DependencyScope::App => "app",
+            });
+        }
+        if let Some(note) = &tree.note {
+            node = node.note(note);
+        }
+        if tree.cycle {
+            node = node.cycle();
+        }
+        node
+    }
+
+    /// Prints `report` to stdout and returns whether the check passed.
+    pub fn print_report(report: &CheckReport) -> bool {
+        println!("{}", render_report(report));
+        if !report.dependency_trees.is_empty() {
+            println!(
+                "{}",
+                dependency_display(report, OutputMode::auto()).render()
+            );
+        }
+        report.is_ok()
+    }
+}
+
+/// Console reporting for slow requests.
+///
+/// ```ignore
+/// use fastapi_rust::slow_requests::dev_reporter;
+///
+/// let app = App::builder()
+///     .middleware(
+///         SlowRequestMiddleware::new(Duration::from_millis(200)).reporter(dev_reporter()),
+///     )
+///     .build();
+/// ```
+#[cfg(any(feature = "output", feature = "output-plain"))]
+pub mod slow_requests {
+    pub use fastapi_core::slow_request::{HandlerPhases, SlowRequest, SlowRequestMiddleware};
+    use fastapi_output::{
+        HttpMethod, LogEntry, OutputMode, RequestLogger, ResponseTiming, SlowRequestEntry,
+    };
+
+    /// Converts a slow request into the `fastapi-output` display model.
+    #[must_use]
+    pub fn to_display_entry(slow: &SlowRequest) -> SlowRequestEntry {
+        let method = slow.method.as_str().parse().unwrap_or(HttpMethod::Get);
+        let path = slow.route.as_deref().unwrap_or(&slow.path);
+        let entry =
+            LogEntry::new(method, path, slow.status).timing(ResponseTiming::new(slow.elapsed));
+        let display = SlowRequestEntry::new(entry, slow.threshold);
+        match slow.phases {
+            Some(phases) => display
+                .phase("extraction", phases.extraction)
+                .phase("handler", phases.handler)
+                .phase("serialization", phases.serialization),
+            None => display,
+        }
+    }
+
+    /// A reporter printing a highlighted warning line to stderr, for the
+    /// detected output mode.
+    #[must_use]
+    pub fn dev_reporter() -> impl Fn(&SlowRequest) + Send + Sync + 'static {
+        let logger = RequestLogger::new(OutputMode::auto());
+        move |slow| eprintln!("{}", logger.format_slow(&to_display_entry(slow)))
+    }
+}
+
+/// Load generation with a console report.
+///
+/// ```ignore
+/// use fastapi_rust::bench::{Bench, BenchRequest};
+///
+/// let report = Bench::app(Arc::new(build_app()))
+///     .concurrency(4)
+///     .duration(Duration::from_secs(5))
+///     .request(BenchRequest::get("/items"))
+///     .run()?;
+/// fastapi_rust::bench::print_report(&report);
+/// ```
+#[cfg(any(feature = "output", feature = "output-plain"))]
+pub mod bench {
+    pub use fastapi_http::bench::{
+        Bench, BenchError, BenchReport, BenchRequest, BenchTarget, EndpointReport, LatencyStats,
+    };
+    use fastapi_output::{BenchLatency, BenchReportDisplay, BenchSummary, OutputMode};
+
+    /// Converts a benchmark report into the `fastapi-output` display model.
+    #[must_use]
+    pub fn to_display_summary(report: &BenchReport) -> BenchSummary {
+        let mut summary = BenchSummary::new(&report.target, report.concurrency);
+        summary.elapsed = report.elapsed;
+        summary.rps = report.rps();
+        summary.statuses = report.statuses.iter().map(|(s, n)| (*s, *n)).collect();
+        summary.overall = to_row("all", report.requests, report.errors, &report.latency);
+        report.endpoints.iter().fold(summary, |summary, endpoint| {
+            summary.endpoint(to_row(
+                &endpoint.name,
+                endpoint.requests,
+                endpoint.errors,
+                &endpoint.latency,
+            ))
+        })
+    }
+
+    fn to_row(label: &str, requests: u64, errors: u64, latency: &LatencyStats) -> BenchLatency {
+        BenchLatency {
+            requests,
+            errors,
+            p50: latency.p50,
+            p90: latency.p90,
+            p99: latency.p99,
+            max: latency.max,
+            ..BenchLatency::new(label)
+        }
+    }
+
+    /// Renders `report` for the detected output mode.
+    #[must_use]
+    pub fn render_report(report: &BenchReport) -> String {
+        BenchReportDisplay::new(OutputMode::auto()).render(&to_display_summary(report))
+    }
+
+    /// Prints `report` to stdout.
+    pub fn print_report(report: &BenchReport) {
+        println!("{}", render_report(report));
+    }
+}
+
+#[cfg(any(feature = "output", feature = "output-plain"))]
+pub mod try_it;
+
 /// Testing utilities module.
 #[cfg(feature = "testing")]
 pub mod testing {
-    pub use fastapi_core::testing::{CookieJar, RequestBuilder, TestClient, TestResponse};
+    pub use fastapi_core::clock::VirtualClock;
+    pub use fastapi_core::testing::{
+        ContractChecker, ContractViolation, CookieJar, RequestBuilder, ResponseSnapshot,
+        SnapshotConfig, TestClient, TestResponse,
+    };
+    pub use fastapi_http::cassette::Cassette;
+    pub use fastapi_http::testing::{LiveServer, TestClientBindExt};
 }
 
 /// Extractors module for type-safe request data extraction.
 pub mod extractors {
     pub use fastapi_core::{
         Accept, AppState, Authorization, BackgroundTasks, BasicAuth, BearerToken, ContentType,
-        Cookie, Header, HeaderValues, Host, Json, JsonConfig, NamedHeader, OAuth2PasswordBearer,
-        Page, Pagination, PaginationConfig, Path, PathParams, Query, QueryParams, State, UserAgent,
-        XRequestId,
+        Cookie, CursorPage, CursorPagination, Header, HeaderValues, Host, Json, JsonConfig,
+        NamedHeader, OAuth2PasswordBearer, Page, Pagination, PaginationConfig, Path, PathParams,
+        Query, QueryParams, State, UserAgent, XRequestId,
     };
 }
 
@@ -461,8 +722,9 @@ pub mod server {
 pub trait OpenApiExt {
     /// Generate an OpenAPI specification from the application.
     ///
-    /// This creates an OpenAPI 3.1 document based on the application's
-    /// configuration and registered routes.
+    /// Returns the document served by the app when OpenAPI is enabled
+    /// (see `AppBuilder::openapi_route`); otherwise builds an empty document
+    /// from the app's name and version.
     ///
     /// # Example
     ///
@@ -487,7 +749,10 @@ pub trait OpenApiExt {
 
 impl OpenApiExt for App {
     fn openapi(&self) -> OpenApi {
-        self.openapi_with(|b| b)
+        // Prefer the document the app already serves: it covers every route.
+        self.openapi_spec()
+            .and_then(|spec| serde_json::from_str(&spec).ok())
+            .unwrap_or_else(|| self.openapi_with(|b| b))
     }
 
     fn openapi_with<F>(&self, configure: F) -> OpenApi
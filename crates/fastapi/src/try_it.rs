@@ -0,0 +1,497 @@
+//! Terminal "Try it out" for OpenAPI operations.
+//!
+//! [`run`] looks up an operation by `operationId`, prompts for its
+//! parameters and body, sends the request to a running server or to the
+//! in-process app, and prints the exchange with the HTTP inspectors from
+//! `fastapi-output`:
+//!
+//! ```ignore
+//! let app = build_app();
+//! let mut args = std::env::args().skip(1);
+//! if args.next().as_deref() == Some("try") {
+//!     let spec = app.openapi(); // via fastapi::OpenApiExt
+//!     let operation_id = args.next().expect("usage: try <operation_id> [base_url]");
+//!     let target = match args.next() {
+//!         Some(url) => try_it::Target::BaseUrl(url),
+//!         None => try_it::Target::App(Arc::new(app)),
+//!     };
+//!     try_it::run(&spec, &operation_id, &target)?;
+//! }
+//! ```
+//!
+//! Inputs are checked against the parameter schemas (type, enum values,
+//! length and range constraints) and re-prompted until they pass; `pattern`
+//! constraints are left to the server. Base URLs must use plain `http://`.
+
+use std::io::{self, BufRead, Read, Write};
+use std::net::TcpStream;
+#[cfg(feature = "testing")]
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use fastapi_core::routing::url_encode;
+use fastapi_openapi::{OpenApi, Parameter, ParameterLocation, Schema, SchemaType};
+use fastapi_output::{OutputMode, RequestInfo, RequestInspector, ResponseInfo, ResponseInspector};
+
+/// An operation found in an OpenAPI document.
+#[derive(Debug, Clone)]
+pub struct TryOperation {
+    /// The `operationId`.
+    pub operation_id: String,
+    /// HTTP method name.
+    pub method: String,
+    /// Path template, e.g. `/items/{id}`.
+    pub path: String,
+    /// Operation summary.
+    pub summary: Option<String>,
+    /// Path, query, header and cookie parameters.
+    pub parameters: Vec<Parameter>,
+    /// Whether the operation takes a request body, and whether it is required.
+    pub body: Option<bool>,
+}
+
+impl TryOperation {
+    /// Finds the operation with `operation_id` in `spec`.
+    #[must_use]
+    pub fn find(spec: &OpenApi, operation_id: &str) -> Option<Self> {
+        spec.paths.iter().find_map(|(path, item)| {
+            item.method_operations()
+                .find(|(_, op)| op.operation_id.as_deref() == Some(operation_id))
+                .map(|(method, op)| Self {
+                    operation_id: operation_id.to_string(),
+                    method: method.to_string(),
+                    path: path.clone(),
+                    summary: op.summary.clone(),
+                    parameters: op.parameters.clone(),
+                    body: op.request_body.as_ref().map(|body| body.required),
+                })
+        })
+    }
+
+    /// Prompts on `output` for each parameter and the body, reading answers
+    /// from `input`.
+    ///
+    /// Empty answers skip optional values. Invalid answers are reported and
+    /// asked again.
+    ///
+    /// # Errors
+    ///
+    /// Fails on I/O errors, or with `UnexpectedEof` if `input` ends before
+    /// every required value was given.
+    pub fn prompt(
+        &self,
+        input: &mut dyn BufRead,
+        output: &mut dyn Write,
+    ) -> io::Result<TryRequest> {
+        let mut request = TryRequest {
+            method: self.method.clone(),
+            path: self.path.clone(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: None,
+        };
+        writeln!(output, "{} {}", self.method, self.path)?;
+        if let Some(summary) = &self.summary {
+            writeln!(output, "{summary}")?;
+        }
+
+        let mut cookies = Vec::new();
+        for param in &self.parameters {
+            let label = format!("{} ({})", param.name, location_name(param.location));
+            let Some(value) = ask(input, output, &label, param.required, &|value| {
+                validate_input(param.schema.as_ref(), value)
+            })?
+            else {
+                continue;
+            };
+            match param.location {
+                ParameterLocation::Path => {
+                    request.path = request
+                        .path
+                        .replace(&format!("{{{}}}", param.name), &url_encode(&value));
+                }
+                ParameterLocation::Query => request.query.push((param.name.clone(), value)),
+                ParameterLocation::Header => request.headers.push((param.name.clone(), value)),
+                ParameterLocation::Cookie => cookies.push(format!("{}={value}", param.name)),
+            }
+        }
+        if !cookies.is_empty() {
+            request
+                .headers
+                .push(("cookie".to_string(), cookies.join("; ")));
+        }
+
+        if let Some(required) = self.body {
+            request.body = ask(input, output, "body (JSON)", required, &|value| {
+                serde_json::from_str::<serde_json::Value>(value)
+                    .map(|_| ())
+                    .map_err(|e| format!("invalid JSON: {e}"))
+            })?;
+            if request.body.is_some() {
+                request
+                    .headers
+                    .push(("content-type".to_string(), "application/json".to_string()));
+            }
+        }
+        Ok(request)
+    }
+}
+
+/// Asks for one value until it validates. `None` means an optional value
+/// was skipped.
+fn ask(
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+    label: &str,
+    required: bool,
+    validate: &dyn Fn(&str) -> Result<(), String>,
+) -> io::Result<Option<String>> {
+    loop {
+        let marker = if required { "*" } else { "" };
+        write!(output, "{label}{marker}: ")?;
+        output.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            if required {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("no value for required `{label}`"),
+                ));
+            }
+            return Ok(None);
+        }
+        let value = line.trim_end_matches(['\r', '\n']);
+        if value.is_empty() {
+            if required {
+                writeln!(output, "  a value is required")?;
+                continue;
+            }
+            return Ok(None);
+        }
+        match validate(value) {
+            Ok(()) => return Ok(Some(value.to_string())),
+            Err(message) => writeln!(output, "  {message}")?,
+        }
+    }
+}
+
+fn location_name(location: ParameterLocation) -> &'static str {
+    match location {
+        ParameterLocation::Path => "path",
+        ParameterLocation::Query => "query",
+        ParameterLocation::Header => "header",
+        ParameterLocation::Cookie => "cookie",
+    }
+}
+
+/// Checks a prompted value against a parameter schema.
+///
+/// # Errors
+///
+/// Returns a message describing why `value` does not match `schema`.
+pub fn validate_input(schema: Option<&Schema>, value: &str) -> Result<(), String> {
+    match schema {
+        Some(Schema::Enum(schema)) => {
+            if schema.enum_values.iter().any(|allowed| allowed == value) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "expected one of: {}",
+                    schema.enum_values.join(", ")
+                ))
+            }
+        }
+        Some(Schema::Primitive(schema)) => {
+            let c = &schema.constraints;
+            let number = match schema.schema_type {
+                SchemaType::Integer => {
+                    value
+                        .parse::<i64>()
+                        .map_err(|_| "expected an integer".to_string())?;
+                    value.parse::<f64>().ok()
+                }
+                SchemaType::Number => Some(
+                    value
+                        .parse::<f64>()
+                        .map_err(|_| "expected a number".to_string())?,
+                ),
+                SchemaType::Boolean if value != "true" && value != "false" => {
+                    return Err("expected true or false".to_string());
+                }
+                _ => None,
+            };
+            if let Some(n) = number {
+                if c.minimum.is_some_and(|min| n < min)
+                    || c.exclusive_minimum.is_some_and(|min| n <= min)
+                {
+                    return Err("value is below the minimum".to_string());
+                }
+                if c.maximum.is_some_and(|max| n > max)
+                    || c.exclusive_maximum.is_some_and(|max| n >= max)
+                {
+                    return Err("value is above the maximum".to_string());
+                }
+            }
+            let len = value.chars().count();
+            if let Some(min) = c.min_length.filter(|min| len < *min) {
+                return Err(format!("expected at least {min} characters"));
+            }
+            if let Some(max) = c.max_length.filter(|max| len > *max) {
+                return Err(format!("expected at most {max} characters"));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A request assembled from prompted values.
+#[derive(Debug, Clone)]
+pub struct TryRequest {
+    /// HTTP method name.
+    pub method: String,
+    /// Path with parameters filled in.
+    pub path: String,
+    /// Query parameters, unencoded.
+    pub query: Vec<(String, String)>,
+    /// Request headers.
+    pub headers: Vec<(String, String)>,
+    /// JSON body.
+    pub body: Option<String>,
+}
+
+impl TryRequest {
+    /// The encoded query string, if any.
+    #[must_use]
+    pub fn query_string(&self) -> Option<String> {
+        if self.query.is_empty() {
+            return None;
+        }
+        let pairs: Vec<String> = self
+            .query
+            .iter()
+            .map(|(k, v)| format!("{}={}", url_encode(k), url_encode(v)))
+            .collect();
+        Some(pairs.join("&"))
+    }
+
+    /// Path and query, as sent on the request line.
+    #[must_use]
+    pub fn target(&self) -> String {
+        match self.query_string() {
+            Some(query) => format!("{}?{query}", self.path),
+            None => self.path.clone(),
+        }
+    }
+}
+
+/// Where [`execute`] sends requests.
+pub enum Target {
+    /// A running server, e.g. `http://127.0.0.1:8000`.
+    BaseUrl(String),
+    /// The app itself, called in-process.
+    #[cfg(feature = "testing")]
+    App(Arc<fastapi_core::App>),
+}
+
+/// A response received for a [`TryRequest`].
+#[derive(Debug, Clone)]
+pub struct TryResponse {
+    /// Status code.
+    pub status: u16,
+    /// Response headers.
+    pub headers: Vec<(String, String)>,
+    /// Response body.
+    pub body: Vec<u8>,
+    /// Time from sending the request to receiving the full response.
+    pub elapsed: Duration,
+}
+
+/// Sends `request` to `target`.
+///
+/// # Errors
+///
+/// Fails if the base URL is not `http://`, the server cannot be reached, or
+/// its response cannot be parsed.
+pub fn execute(request: &TryRequest, target: &Target) -> io::Result<TryResponse> {
+    let started = Instant::now();
+    match target {
+        Target::BaseUrl(url) => execute_http(request, url, started),
+        #[cfg(feature = "testing")]
+        Target::App(app) => {
+            let method = fastapi_core::Method::from_bytes(request.method.as_bytes())
+                .ok_or_else(|| invalid_data(format!("unknown method {}", request.method)))?;
+            let client = fastapi_core::TestClient::new(Arc::clone(app));
+            let mut builder = client.request(method, &request.target());
+            for (name, value) in &request.headers {
+                builder = builder.header(name.clone(), value.as_bytes().to_vec());
+            }
+            if let Some(body) = &request.body {
+                builder = builder.body(body.as_bytes().to_vec());
+            }
+            let response = builder.send();
+            Ok(TryResponse {
+                status: response.status_code(),
+                headers: response
+                    .headers()
+                    .iter()
+                    .map(|(k, v)| (k.clone(), String::from_utf8_lossy(v).into_owned()))
+                    .collect(),
+                body: response.bytes().to_vec(),
+                elapsed: started.elapsed(),
+            })
+        }
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn execute_http(request: &TryRequest, url: &str, started: Instant) -> io::Result<TryResponse> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "only http:// base URLs are supported",
+        )
+    })?;
+    let (authority, base_path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{authority}:80")
+    };
+
+    let mut head = format!(
+        "{} {}{} HTTP/1.1\r\nHost: {authority}\r\nConnection: close\r\n",
+        request.method,
+        base_path.trim_end_matches('/'),
+        request.target()
+    );
+    for (name, value) in &request.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    let body = request.body.as_deref().unwrap_or_default();
+    if request.body.is_some() {
+        head.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    head.push_str("\r\n");
+
+    let mut stream = TcpStream::connect(address)?;
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(body.as_bytes())?;
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw)?;
+    let elapsed = started.elapsed();
+
+    let split = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| invalid_data("incomplete response head".to_string()))?;
+    let head = String::from_utf8_lossy(&raw[..split]);
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|line| line.split(' ').nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| invalid_data("malformed status line".to_string()))?;
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    let mut body = raw[split + 4..].to_vec();
+    if headers
+        .iter()
+        .any(|(name, value)| name == "transfer-encoding" && value.eq_ignore_ascii_case("chunked"))
+    {
+        body = dechunk(&body).ok_or_else(|| invalid_data("malformed chunked body".to_string()))?;
+    }
+    Ok(TryResponse {
+        status,
+        headers,
+        body,
+        elapsed,
+    })
+}
+
+fn dechunk(mut data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    loop {
+        let line_end = data.windows(2).position(|w| w == b"\r\n")?;
+        let size_field = std::str::from_utf8(&data[..line_end]).ok()?;
+        let size_hex = size_field.split(';').next()?.trim();
+        let size = usize::from_str_radix(size_hex, 16).ok()?;
+        data = &data[line_end + 2..];
+        if size == 0 {
+            return Some(out);
+        }
+        out.extend_from_slice(data.get(..size)?);
+        data = data.get(size + 2..)?;
+    }
+}
+
+/// Renders the exchange with the request and response inspectors.
+#[must_use]
+pub fn render_exchange(request: &TryRequest, response: &TryResponse, mode: OutputMode) -> String {
+    const PREVIEW: usize = 4096;
+
+    let mut request_info =
+        RequestInfo::new(&request.method, &request.path).headers(request.headers.iter().cloned());
+    if let Some(query) = request.query_string() {
+        request_info = request_info.query(query);
+    }
+    if let Some(body) = &request.body {
+        request_info = request_info
+            .content_type("application/json")
+            .body_preview(preview(body.as_bytes(), PREVIEW), body.len());
+    }
+
+    let mut response_info = ResponseInfo::new(response.status)
+        .headers(response.headers.iter().cloned())
+        .response_time(response.elapsed)
+        .body_preview(preview(&response.body, PREVIEW), response.body.len());
+    if let Some((_, content_type)) = response
+        .headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+    {
+        response_info = response_info.content_type(content_type);
+    }
+
+    format!(
+        "{}\n{}",
+        RequestInspector::new(mode).inspect(&request_info),
+        ResponseInspector::new(mode).inspect(&response_info)
+    )
+}
+
+fn preview(body: &[u8], limit: usize) -> String {
+    let text = String::from_utf8_lossy(&body[..body.len().min(limit)]);
+    serde_json::from_str::<serde_json::Value>(&text)
+        .ok()
+        .and_then(|json| serde_json::to_string_pretty(&json).ok())
+        .unwrap_or_else(|| text.into_owned())
+}
+
+/// Prompts on the terminal for `operation_id`, sends it to `target` and
+/// prints the exchange.
+///
+/// # Errors
+///
+/// Fails if the operation is not in `spec`, on terminal I/O errors, or if the
+/// request cannot be sent.
+pub fn run(spec: &OpenApi, operation_id: &str, target: &Target) -> io::Result<TryResponse> {
+    let operation = TryOperation::find(spec, operation_id).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no operation with operationId `{operation_id}`"),
+        )
+    })?;
+    let request = operation.prompt(&mut io::stdin().lock(), &mut io::stdout())?;
+    let response = execute(&request, target)?;
+    println!(
+        "{}",
+        render_exchange(&request, &response, OutputMode::auto())
+    );
+    Ok(response)
+}